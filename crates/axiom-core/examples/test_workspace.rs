@@ -40,7 +40,7 @@ fn main() -> axiom_core::Result<()> {
             // Activate it
             println!("Activating workspace...");
             let service = manager.activate_workspace(ws.id)?;
-            println!("  Service created for: {}\n", service.cwd().display());
+            println!("  Service created for: {}\n", service.lock().cwd().display());
 
             // List workspaces again
             println!("Workspaces after creation:");
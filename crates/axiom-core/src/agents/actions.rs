@@ -0,0 +1,190 @@
+//! Quick actions on an agent
+//!
+//! What an agents list offers on an entry: view its output, cancel it,
+//! re-run it, export it, copy its last message, open the files it touched.
+//! Each action maps to a [`Command`] so the web UI offers the same menu as
+//! the TUI, and the helpers here read what the actions need out of an
+//! agent's output.
+
+use crate::commands::Command;
+use crate::run_log::RunLogFormat;
+use crate::types::{AgentId, OutputContext};
+use serde::{Deserialize, Serialize};
+
+/// An action on one agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AgentAction {
+    /// Show the agent's output
+    ViewOutput,
+    /// Stop the agent's running work
+    Cancel,
+    /// Start the agent's task again, as a new agent
+    Rerun,
+    /// Write the agent's run out as Markdown
+    Export,
+    /// Copy the agent's last message to the clipboard
+    CopyLastMessage,
+    /// Open the files the agent wrote
+    OpenTouchedFiles,
+}
+
+impl AgentAction {
+    /// Every action, in menu order
+    pub const ALL: [AgentAction; 6] = [
+        AgentAction::ViewOutput,
+        AgentAction::Cancel,
+        AgentAction::Rerun,
+        AgentAction::Export,
+        AgentAction::CopyLastMessage,
+        AgentAction::OpenTouchedFiles,
+    ];
+
+    /// Menu label
+    pub fn label(self) -> &'static str {
+        match self {
+            AgentAction::ViewOutput => "View output",
+            AgentAction::Cancel => "Cancel",
+            AgentAction::Rerun => "Re-run",
+            AgentAction::Export => "Export",
+            AgentAction::CopyLastMessage => "Copy last message",
+            AgentAction::OpenTouchedFiles => "Open touched files",
+        }
+    }
+
+    /// Key choosing the action in the menu
+    pub fn key(self) -> char {
+        match self {
+            AgentAction::ViewOutput => 'v',
+            AgentAction::Cancel => 'x',
+            AgentAction::Rerun => 'r',
+            AgentAction::Export => 'e',
+            AgentAction::CopyLastMessage => 'y',
+            AgentAction::OpenTouchedFiles => 'o',
+        }
+    }
+
+    /// The action chosen by `key`
+    pub fn from_key(key: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.key() == key)
+    }
+
+    /// Whether the action applies to an agent that is (or isn't) running:
+    /// only running agents can be cancelled, only finished ones re-run
+    pub fn available(self, running: bool) -> bool {
+        match self {
+            AgentAction::Cancel => running,
+            AgentAction::Rerun => !running,
+            _ => true,
+        }
+    }
+
+    /// The command carrying out the action on `agent_id`
+    pub fn command(self, agent_id: AgentId) -> Command {
+        match self {
+            AgentAction::ViewOutput => Command::SelectContext {
+                context: OutputContext::Agent { agent_id },
+            },
+            AgentAction::Cancel => Command::CancelAgent { agent_id },
+            AgentAction::Rerun => Command::RerunAgent { agent_id },
+            AgentAction::Export => Command::ExportRunLog {
+                agent_id,
+                format: RunLogFormat::Markdown,
+            },
+            AgentAction::CopyLastMessage => Command::GetLastMessage { agent_id },
+            AgentAction::OpenTouchedFiles => Command::GetTouchedFiles { agent_id },
+        }
+    }
+}
+
+/// Start of a conductor's reply in its output
+const REPLY_START: &str = ">>>axiom\n";
+
+/// Start of a prompt to the conductor in its output
+const PROMPT_START: &str = ">>>user\n";
+
+/// End of a prompt or reply
+const BLOCK_END: &str = "<<<";
+
+/// The agent's last message: the conductor's latest reply, or for other
+/// agents their whole output
+pub fn last_message(output: &str) -> &str {
+    block_after(output, REPLY_START).unwrap_or(output).trim()
+}
+
+/// The latest prompt in a conductor's output
+pub fn last_prompt(output: &str) -> Option<&str> {
+    block_after(output, PROMPT_START).map(str::trim)
+}
+
+/// The text of the last block opened by `start`, up to its end marker
+fn block_after<'a>(output: &'a str, start: &str) -> Option<&'a str> {
+    let block = &output[output.rfind(start)? + start.len()..];
+    Some(match block.find(BLOCK_END) {
+        Some(end) => &block[..end],
+        None => block,
+    })
+}
+
+/// Files an agent wrote, as its output names them ("Writing to: ..." or a
+/// diff's `+++ b/...` header), in the order first named
+pub fn touched_files(output: &str) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for line in output.lines() {
+        let path = line
+            .strip_prefix("Writing to: ")
+            .or_else(|| line.strip_prefix("+++ b/"))
+            .map(str::trim);
+        if let Some(path) = path.filter(|path| !path.is_empty()) {
+            if !files.iter().any(|file| file == path) {
+                files.push(path.to_string());
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_pick_actions() {
+        for action in AgentAction::ALL {
+            assert_eq!(AgentAction::from_key(action.key()), Some(action));
+        }
+        assert_eq!(AgentAction::from_key('q'), None);
+        assert!(AgentAction::Cancel.available(true));
+        assert!(!AgentAction::Cancel.available(false));
+        assert!(!AgentAction::Rerun.available(true));
+        assert!(matches!(
+            AgentAction::Rerun.command(AgentId(3)),
+            Command::RerunAgent {
+                agent_id: AgentId(3)
+            }
+        ));
+    }
+
+    #[test]
+    fn test_conductor_messages() {
+        let output = ">>>user\nfirst\n<<<\n>>>axiom\nOne\n<<<\n\n\
+                      >>>user\nsecond\n<<<\n>>>axiom\nTwo\nlines\n<<<\n\n";
+        assert_eq!(last_message(output), "Two\nlines");
+        assert_eq!(last_prompt(output), Some("second"));
+
+        // Still streaming
+        assert_eq!(last_message(">>>axiom\nHalf a rep"), "Half a rep");
+        assert_eq!(last_message("$ ls\nsrc\n"), "$ ls\nsrc");
+        assert_eq!(last_prompt("$ ls\n"), None);
+    }
+
+    #[test]
+    fn test_touched_files() {
+        let output = "Writing to: /repo/src/main.rs\n\
+                      ```diff\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n```\n\
+                      Writing to: /repo/src/main.rs\n";
+        assert_eq!(
+            touched_files(output),
+            vec!["/repo/src/main.rs", "src/lib.rs"]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Cancelling running agent work
+//!
+//! Each agent carries a [`CancelToken`] that its worker checks (LLM streams)
+//! or that watches the agent's process group ([`kill_on_cancel`]). Processes
+//! are asked to stop with SIGTERM and killed with SIGKILL if they are still
+//! running after [`KILL_GRACE`].
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long a process gets to exit after SIGTERM before it is killed
+pub const KILL_GRACE: Duration = Duration::from_secs(3);
+
+/// How often cancellation and process exit are checked
+pub const CANCEL_POLL: Duration = Duration::from_millis(50);
+
+/// Shared flag telling an agent's worker to stop
+///
+/// Clones share the flag, so the registry can cancel work running on
+/// another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Stops watching a process when dropped, i.e. once it has been waited on
+pub struct ProcessGuard {
+    done: Arc<AtomicBool>,
+}
+
+impl Drop for ProcessGuard {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Terminate the process group led by `pid` once `token` is cancelled
+///
+/// The process must lead its own group (see
+/// `std::os::unix::process::CommandExt::process_group`) so that whatever
+/// it started goes with it.
+pub fn kill_on_cancel(pid: u32, token: CancelToken) -> ProcessGuard {
+    let done = Arc::new(AtomicBool::new(false));
+    let exited = done.clone();
+    std::thread::spawn(move || {
+        while !exited.load(Ordering::SeqCst) {
+            if token.is_cancelled() {
+                terminate(pid, || exited.load(Ordering::SeqCst));
+                return;
+            }
+            std::thread::sleep(CANCEL_POLL);
+        }
+    });
+    ProcessGuard { done }
+}
+
+/// Cancel `token` once `timeout` has passed, unless the guard is dropped first
+pub fn cancel_after(token: CancelToken, timeout: Duration) -> ProcessGuard {
+    let done = Arc::new(AtomicBool::new(false));
+    let exited = done.clone();
+    std::thread::spawn(move || {
+        let deadline = Instant::now() + timeout;
+        while !exited.load(Ordering::SeqCst) {
+            if Instant::now() >= deadline {
+                token.cancel();
+                return;
+            }
+            std::thread::sleep(CANCEL_POLL);
+        }
+    });
+    ProcessGuard { done }
+}
+
+/// SIGTERM the process group led by `pid`, then SIGKILL it if `exited`
+/// hasn't reported it gone within [`KILL_GRACE`]; blocks until then
+pub fn terminate(pid: u32, mut exited: impl FnMut() -> bool) {
+    if exited() {
+        return;
+    }
+    signal(pid, false);
+    let deadline = Instant::now() + KILL_GRACE;
+    while Instant::now() < deadline {
+        if exited() {
+            return;
+        }
+        std::thread::sleep(CANCEL_POLL);
+    }
+    signal(pid, true);
+}
+
+#[cfg(unix)]
+fn signal(pid: u32, kill: bool) {
+    let sig = if kill { libc::SIGKILL } else { libc::SIGTERM };
+    // SAFETY: kill(2) has no memory effects; a negative pid targets the group
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), sig);
+    }
+}
+
+#[cfg(not(unix))]
+fn signal(pid: u32, kill: bool) {
+    let mut taskkill = std::process::Command::new("taskkill");
+    taskkill.args(["/PID", &pid.to_string(), "/T"]);
+    if kill {
+        taskkill.arg("/F");
+    }
+    let _ = taskkill.output();
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    #[test]
+    fn test_cancel_stops_process_group() {
+        let mut child = Command::new("sh")
+            .args(["-c", "sleep 30; echo done"])
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let token = CancelToken::new();
+        let guard = kill_on_cancel(child.id(), token.clone());
+
+        let started = Instant::now();
+        token.cancel();
+        let status = child.wait().unwrap();
+        drop(guard);
+        assert!(!status.success());
+        assert!(started.elapsed() < KILL_GRACE);
+    }
+}
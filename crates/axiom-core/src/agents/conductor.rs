@@ -3,11 +3,18 @@
 //! The conductor receives user prompts and decides what agents to spawn.
 //! It uses the active LLM provider to analyze requests and plan agent execution.
 
+use super::{CancelToken, ContextManager, CANCEL_POLL};
+use crate::config::ContextConfig;
+use crate::error_codes::ErrorCode;
 use crate::events::Event;
-use crate::llm::{ChatMessage, MessageContent, ProviderRegistry, Role};
+use crate::index::{context_prompt, SemanticIndex};
+use crate::llm::{ChatMessage, LlmProvider, ProviderRegistry, Role, ToolCall, ToolSpec};
+use crate::orchestration::AgentRole;
+use crate::prompts::{PromptLibrary, CONDUCTOR};
 use crate::types::{AgentId, AgentSpawnRequest, AgentStatus, AgentType};
+use crate::usage::estimate_tokens;
 use crossbeam_channel::Sender;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::sync::Arc;
 
 /// The conductor service
@@ -20,14 +27,27 @@ pub struct Conductor {
     /// Event sender for emitting events
     event_tx: Sender<Event>,
 
-    /// Conversation history for context
-    history: Vec<ChatMessage>,
-
-    /// Maximum history length to retain
-    max_history: usize,
+    /// Conversation history, trimmed to the provider's context window
+    /// before each request
+    context: Arc<Mutex<ContextManager>>,
 
     /// Persistent conductor agent ID (reused across inputs)
     agent_id: Option<AgentId>,
+
+    /// Stops the LLM request in flight; replaced after each cancel
+    cancel_token: CancelToken,
+
+    /// Index whose closest snippets are added to each prompt
+    index: Option<Arc<SemanticIndex>>,
+
+    /// Context added to the next prompt, such as the output of a CLI agent
+    pending_context: Vec<String>,
+
+    /// Workspace templates replacing the built-in system prompt
+    prompts: PromptLibrary,
+
+    /// The latest prompt, for sending it again
+    last_input: Option<String>,
 }
 
 impl Conductor {
@@ -36,12 +56,40 @@ impl Conductor {
         Self {
             llm_registry,
             event_tx,
-            history: Vec::new(),
-            max_history: 20,
+            context: Arc::new(Mutex::new(ContextManager::default())),
             agent_id: None,
+            cancel_token: CancelToken::new(),
+            index: None,
+            pending_context: Vec::new(),
+            prompts: PromptLibrary::default(),
+            last_input: None,
         }
     }
 
+    /// Use `config` for the history's token budget and summarization
+    pub fn with_context_config(self, config: ContextConfig) -> Self {
+        *self.context.lock() = ContextManager::new(config);
+        self
+    }
+
+    /// Add the snippets of `index` closest to each prompt to the system prompt
+    pub fn with_index(mut self, index: Arc<SemanticIndex>) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Build the system prompt from `prompts`
+    pub fn with_prompts(mut self, prompts: PromptLibrary) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Build the system prompt from `prompts` from now on, e.g. after the
+    /// `[prompts]` section changed
+    pub fn set_prompts(&mut self, prompts: PromptLibrary) {
+        self.prompts = prompts;
+    }
+
     /// Set the persistent conductor agent ID
     pub fn set_agent_id(&mut self, id: AgentId) {
         self.agent_id = Some(id);
@@ -57,16 +105,14 @@ impl Conductor {
     /// Analyzes the request and spawns appropriate agents.
     /// Reuses the existing conductor agent if available.
     pub fn process(&mut self, input: String) {
-        // Add user message to history
-        self.history.push(ChatMessage {
-            role: Role::User,
-            content: MessageContent::Text(input.clone()),
-        });
-
-        // Trim history if needed
-        while self.history.len() > self.max_history {
-            self.history.remove(0);
-        }
+        // Add user message to history, led by any context added since the
+        // last one
+        let mut message = std::mem::take(&mut self.pending_context);
+        message.push(input.clone());
+        self.context
+            .lock()
+            .push(ChatMessage::user(message.join("\n\n")));
+        self.last_input = Some(input.clone());
 
         // Check if we have a persistent conductor agent
         if let Some(id) = self.agent_id {
@@ -106,61 +152,179 @@ impl Conductor {
 
         let event_tx = self.event_tx.clone();
         let llm_registry = self.llm_registry.clone();
-        let history = self.history.clone();
+        let context = self.context.clone();
         let task = task.to_string();
+        let cancel_token = self.cancel_token.clone();
+        let index = self.index.clone();
+        let prompts = self.prompts.clone();
 
         // Run in background thread to not block UI
         std::thread::spawn(move || {
-            execute_conductor(agent_id, &task, history, llm_registry, event_tx);
+            let system_prompt = system_prompt(&prompts, index, &task);
+            execute_conductor(
+                agent_id,
+                system_prompt,
+                context,
+                llm_registry,
+                event_tx,
+                cancel_token,
+            );
         });
     }
 
-    /// Get the conversation history
-    pub fn history(&self) -> &[ChatMessage] {
-        &self.history
+    /// Send the latest prompt again, if it got no reply, e.g. after the
+    /// provider rejected its key; returns whether it was sent
+    pub fn retry(&mut self) -> bool {
+        let unanswered = self
+            .context
+            .lock()
+            .messages()
+            .last()
+            .is_some_and(|message| message.role == Role::User);
+        match (self.agent_id, self.last_input.clone()) {
+            (Some(id), Some(input)) if unanswered => {
+                let _ = self.event_tx.send(Event::AgentWake(id));
+                self.execute(id, &input);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Stop the LLM response being streamed, if any
+    ///
+    /// The conductor agent goes back to idle and takes the next prompt as usual.
+    pub fn cancel(&mut self) {
+        self.cancel_token.cancel();
+        self.cancel_token = CancelToken::new();
+    }
+
+    /// Get the conversation history, led by the summary of older turns if
+    /// any were summarized
+    pub fn history(&self) -> Vec<ChatMessage> {
+        self.context.lock().messages()
     }
 
     /// Clear conversation history
     pub fn clear_history(&mut self) {
-        self.history.clear();
+        self.context.lock().clear();
+    }
+
+    /// Continue a saved conversation, reusing its conductor agent
+    pub fn restore(&mut self, history: Vec<ChatMessage>, agent_id: Option<AgentId>) {
+        self.context.lock().restore(history);
+        self.agent_id = agent_id;
     }
 
     /// Add assistant response to history
     pub fn add_response(&mut self, response: String) {
-        self.history.push(ChatMessage {
-            role: Role::Assistant,
-            content: MessageContent::Text(response),
-        });
+        self.context.lock().push(ChatMessage::assistant(response));
+    }
+
+    /// Give the conductor `context` along with the next prompt
+    ///
+    /// It goes into the history ahead of the prompt, but isn't shown as
+    /// part of it.
+    pub fn add_context(&mut self, context: String) {
+        self.pending_context.push(context);
+    }
+}
+
+/// The provider whose rejected key left a Conductor prompt unanswered
+///
+/// Front ends record the rejection when they see
+/// [`Event::ProviderAuthFailed`] and report each new key; the prompt is sent
+/// again, once, when that provider gets one.
+#[derive(Debug, Clone, Default)]
+pub struct AuthRetry {
+    provider_id: Option<String>,
+}
+
+impl AuthRetry {
+    /// `provider_id` rejected its key
+    pub fn rejected(&mut self, provider_id: &str) {
+        self.provider_id = Some(provider_id.to_string());
+    }
+
+    /// The provider waiting for a new key, if any
+    pub fn pending(&self) -> Option<&str> {
+        self.provider_id.as_deref()
+    }
+
+    /// Stop waiting, e.g. when the user dismisses the key prompt
+    pub fn cancel(&mut self) {
+        self.provider_id = None;
+    }
 
-        while self.history.len() > self.max_history {
-            self.history.remove(0);
+    /// `provider_id` got a new key; returns whether the prompt should be sent
+    /// again
+    pub fn key_updated(&mut self, provider_id: &str) -> bool {
+        let due = self.pending() == Some(provider_id);
+        if due {
+            self.cancel();
         }
+        due
     }
 }
 
+/// Fit the history into `provider`'s context window, leaving room for
+/// `overhead` tokens of system prompt and tools
+///
+/// The oldest turns are summarized when `[llm.context] summarize` is set and
+/// the provider manages it, and dropped otherwise.
+fn fit_context(context: &Mutex<ContextManager>, provider: &dyn LlmProvider, overhead: usize) {
+    let (count, request) = {
+        let context = context.lock();
+        let budget = context
+            .budget(provider.id(), provider.capabilities().max_context)
+            .saturating_sub(overhead);
+        let count = context.overflow(budget);
+        if count == 0 {
+            return;
+        }
+        let request = context
+            .config()
+            .summarize
+            .then(|| context.summary_request(count));
+        (count, request)
+    };
+
+    // Summarize without holding the lock; the request can take a while
+    let summary = request
+        .and_then(|messages| provider.chat_with_tools(messages, &[]).ok())
+        .map(|reply| reply.text)
+        .filter(|text| !text.trim().is_empty());
+
+    let mut context = context.lock();
+    match summary {
+        Some(summary) => context.fold(count, &summary),
+        None => context.truncate(count),
+    }
+}
+
+/// The conductor's system prompt for `task`: the workspace's template or
+/// the built-in one, with the code closest to the request if there's an
+/// index; a failed search only costs the snippets
+fn system_prompt(prompts: &PromptLibrary, index: Option<Arc<SemanticIndex>>, task: &str) -> String {
+    let mut system_prompt = prompts.render(CONDUCTOR, &build_system_prompt(), task);
+    if let Some(hits) = index.and_then(|index| index.search(task).ok()) {
+        system_prompt.push_str(&context_prompt(&hits));
+    }
+    system_prompt
+}
+
 /// Execute the conductor agent
 fn execute_conductor(
     agent_id: AgentId,
-    _task: &str,
-    history: Vec<ChatMessage>,
+    system_prompt: String,
+    context: Arc<Mutex<ContextManager>>,
     llm_registry: Arc<RwLock<ProviderRegistry>>,
     event_tx: Sender<Event>,
+    cancel_token: CancelToken,
 ) {
-    // Build the system prompt for the conductor
-    let system_prompt = build_system_prompt();
-
-    // Build messages for the LLM
-    let mut messages = vec![ChatMessage {
-        role: Role::System,
-        content: MessageContent::Text(system_prompt),
-    }];
-
-    // Add conversation history
-    messages.extend(history);
-
-    // Get the active provider
+    // Get the orchestrator's provider
     let registry = llm_registry.read();
-    let provider = match registry.active() {
+    let provider = match registry.for_role(AgentRole::Orchestrator) {
         Some(p) => p.clone(),
         None => {
             let _ = event_tx.send(Event::AgentOutput {
@@ -177,11 +341,27 @@ fn execute_conductor(
     };
     drop(registry);
 
-    // Create a channel for LLM responses
-    let (llm_tx, llm_rx) = crossbeam_channel::unbounded();
-
-    // Send to LLM
-    provider.send_message(messages, llm_tx);
+    let tools = conductor_tools();
+
+    // Make room for the prompt and tools, then add the conversation history
+    let overhead = estimate_tokens(&system_prompt)
+        + tools
+            .iter()
+            .map(|tool| {
+                estimate_tokens(&tool.description) + estimate_tokens(&tool.parameters.to_string())
+            })
+            .sum::<u64>();
+    fit_context(&context, provider.as_ref(), overhead as usize);
+    let mut messages = vec![ChatMessage::system(system_prompt)];
+    messages.extend(context.lock().messages());
+    let provider_id = provider.id().to_string();
+
+    // The call blocks until the whole reply is in; wait for it off-thread so
+    // a cancel doesn't have to
+    let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+    std::thread::spawn(move || {
+        let _ = reply_tx.send(provider.chat_with_tools(messages, &tools));
+    });
 
     // Start Axiom response box (chat interface style)
     let _ = event_tx.send(Event::AgentOutput {
@@ -189,27 +369,44 @@ fn execute_conductor(
         chunk: ">>>axiom\n".to_string(),
     });
 
-    // Stream responses to agent output
-    let mut full_response = String::new();
     loop {
-        match llm_rx.recv() {
-            Ok(Event::LlmChunk(chunk)) => {
-                full_response.push_str(&chunk);
-                let _ = event_tx.send(Event::AgentOutput {
-                    id: agent_id,
-                    chunk,
-                });
-            }
-            Ok(Event::LlmDone) => {
+        if cancel_token.is_cancelled() {
+            // The reply is dropped when it arrives
+            let _ = event_tx.send(Event::AgentOutput {
+                id: agent_id,
+                chunk: "\n[cancelled]\n<<<\n\n".to_string(),
+            });
+            let _ = event_tx.send(Event::AgentUpdate {
+                id: agent_id,
+                status: AgentStatus::Idle,
+            });
+            break;
+        }
+        match reply_rx.recv_timeout(CANCEL_POLL) {
+            Ok(Ok(reply)) => {
+                // Show the answer and the agents it starts
+                let mut response = reply.text.trim().to_string();
+                for call in &reply.calls {
+                    if !response.is_empty() {
+                        response.push('\n');
+                    }
+                    response.push_str(&format!("→ {}", call_summary(call)));
+                }
                 // Close Axiom response box with separator for next Q&A pair
                 let _ = event_tx.send(Event::AgentOutput {
                     id: agent_id,
-                    chunk: "\n<<<\n\n".to_string(),
+                    chunk: format!("{}\n<<<\n\n", response),
                 });
                 // Send response to add to conductor history (for LLM context)
-                let _ = event_tx.send(Event::ConductorResponse(full_response.clone()));
-                // Parse response for agent spawn commands (pass conductor_id as parent)
-                parse_and_spawn_agents(&full_response, &event_tx, agent_id);
+                let _ = event_tx.send(Event::ConductorResponse(response));
+                // Spawn the requested agents (conductor_id as parent)
+                for request in reply
+                    .calls
+                    .iter()
+                    .filter_map(|c| spawn_request(c, agent_id))
+                {
+                    let _ = event_tx.send(Event::AgentSpawn(request));
+                }
                 // Set to Idle so conductor can be reused for next input
                 let _ = event_tx.send(Event::AgentUpdate {
                     id: agent_id,
@@ -217,12 +414,19 @@ fn execute_conductor(
                 });
                 break;
             }
-            Ok(Event::LlmError(e)) => {
+            Ok(Err(e)) => {
                 // Close box and show error
                 let _ = event_tx.send(Event::AgentOutput {
                     id: agent_id,
                     chunk: format!("\nError: {}\n<<<\n", e),
                 });
+                // A rejected key gets its own event, so UIs can ask for another
+                if e.code() == ErrorCode::ProviderAuthFailed {
+                    let _ = event_tx.send(Event::ProviderAuthFailed {
+                        provider_id,
+                        message: e.to_string(),
+                    });
+                }
                 // Set to Idle so conductor can be reused
                 let _ = event_tx.send(Event::AgentUpdate {
                     id: agent_id,
@@ -230,7 +434,8 @@ fn execute_conductor(
                 });
                 break;
             }
-            Err(_) => {
+            Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
                 // Close box on error
                 let _ = event_tx.send(Event::AgentOutput {
                     id: agent_id,
@@ -243,7 +448,6 @@ fn execute_conductor(
                 });
                 break;
             }
-            _ => {}
         }
     }
 }
@@ -252,127 +456,132 @@ fn execute_conductor(
 fn build_system_prompt() -> String {
     r#"You are an AI assistant integrated into Axiom, a terminal-based IDE. You help users with code, shell commands, and file operations.
 
-When you need to perform actions, you can spawn specialized agents:
-
-1. **Shell Agent**: Execute shell commands
-   Format: `@shell <command>`
-   Example: `@shell ls -la`
+When you need to perform actions, call the tools for Axiom's specialized agents:
 
-2. **Coder Agent**: Modify or create code files
-   Format: `@coder <description>` followed by code blocks
-   Example:
-   @coder Update the main function
-   ```rust:src/main.rs
-   fn main() {
-       println!("Hello!");
-   }
-   ```
+1. **shell**: Execute a shell command, e.g. `ls -la`
+2. **coder**: Modify or create a code file; pass the file's full new content with its path
+3. **search**: Search files or content, e.g. `TODO`
+4. **fileops**: Read, write, or manage files, e.g. `read src/main.rs`.
+   Read only what you need: `read src/main.rs:40-60`, `read src/main.rs#run`,
+   or `outline src/main.rs` to list functions and types.
+5. **service**: Keep a long-running process alive (dev servers, watchers), e.g.
+   `--port 3000 npm run dev`. Use this instead of shell for anything that doesn't exit on its own.
 
-3. **Search Agent**: Search files or content
-   Format: `@search <query>`
-   Example: `@search TODO`
-
-4. **FileOps Agent**: Read, write, or manage files
-   Format: `@fileops <operation> <path>`
-   Example: `@fileops read src/main.rs`
-
-You can spawn multiple agents in a single response. Always explain what you're doing before spawning agents.
+You can call several tools in a single response. Always explain what you're doing alongside the calls.
 
 If the user's request doesn't require any agent actions, just respond conversationally."#.to_string()
 }
 
-/// Parse LLM response and spawn any requested agents
-fn parse_and_spawn_agents(response: &str, event_tx: &Sender<Event>, parent_id: AgentId) {
-    let mut lines = response.lines().peekable();
+/// The agents the conductor can start, as tools
+fn conductor_tools() -> Vec<ToolSpec> {
+    vec![
+        ToolSpec::with_string_args(
+            "shell",
+            "Run a shell command that exits on its own",
+            &[("command", "Command line to run")],
+            &["command"],
+        ),
+        ToolSpec::with_string_args(
+            "coder",
+            "Create or change a code file",
+            &[
+                ("description", "What the change does"),
+                ("path", "File to write, relative to the workspace"),
+                ("content", "Full new content of the file"),
+            ],
+            &["description"],
+        ),
+        ToolSpec::with_string_args(
+            "search",
+            "Search file names and contents",
+            &[("query", "Text to search for")],
+            &["query"],
+        ),
+        ToolSpec::with_string_args(
+            "fileops",
+            "Read, outline, write or manage files",
+            &[(
+                "operation",
+                "Operation and path, e.g. `read src/main.rs:40-60` or `outline src/main.rs`",
+            )],
+            &["operation"],
+        ),
+        ToolSpec::with_string_args(
+            "service",
+            "Keep a long-running process alive (dev servers, watchers)",
+            &[(
+                "spec",
+                "`[--port N | --health URL] [--no-restart] <command>`",
+            )],
+            &["spec"],
+        ),
+    ]
+}
 
-    while let Some(line) = lines.next() {
-        let trimmed = line.trim();
+/// One-line description of a tool call for the conductor's output
+fn call_summary(call: &ToolCall) -> String {
+    let detail = ["command", "description", "query", "operation", "spec"]
+        .iter()
+        .find_map(|key| call.arg(key))
+        .unwrap_or_default();
+    format!("{}: {}", call.name, detail)
+}
 
-        // Check for shell agent
-        if let Some(cmd) = trimmed.strip_prefix("@shell ") {
-            let request = AgentSpawnRequest {
-                agent_type: AgentType::Shell,
-                name: "Shell".to_string(),
-                description: truncate(cmd, 50),
-                parameters: Some(cmd.to_string()),
-                parent_id: Some(parent_id),
-            };
-            let _ = event_tx.send(Event::AgentSpawn(request));
+/// The agent a conductor tool call asks for, if the call is well-formed
+fn spawn_request(call: &ToolCall, parent_id: AgentId) -> Option<AgentSpawnRequest> {
+    let (agent_type, name, description, parameters) = match call.name.as_str() {
+        "shell" => {
+            let cmd = call.arg("command")?;
+            (
+                AgentType::Shell,
+                "Shell",
+                truncate(cmd, 50),
+                cmd.to_string(),
+            )
         }
-
-        // Check for search agent
-        if let Some(query) = trimmed.strip_prefix("@search ") {
-            let request = AgentSpawnRequest {
-                agent_type: AgentType::Search,
-                name: "Search".to_string(),
-                description: format!("Searching: {}", truncate(query, 40)),
-                parameters: Some(query.to_string()),
-                parent_id: Some(parent_id),
-            };
-            let _ = event_tx.send(Event::AgentSpawn(request));
+        "search" => {
+            let query = call.arg("query")?;
+            let description = format!("Searching: {}", truncate(query, 40));
+            (AgentType::Search, "Search", description, query.to_string())
         }
-
-        // Check for fileops agent
-        if let Some(op) = trimmed.strip_prefix("@fileops ") {
-            let request = AgentSpawnRequest {
-                agent_type: AgentType::FileOps,
-                name: "FileOps".to_string(),
-                description: truncate(op, 50),
-                parameters: Some(op.to_string()),
-                parent_id: Some(parent_id),
-            };
-            let _ = event_tx.send(Event::AgentSpawn(request));
+        "fileops" => {
+            let op = call.arg("operation")?;
+            (
+                AgentType::FileOps,
+                "FileOps",
+                truncate(op, 50),
+                op.to_string(),
+            )
         }
-
-        // Check for coder agent (may span multiple lines with code blocks)
-        if let Some(desc) = trimmed.strip_prefix("@coder ") {
-            let mut code_content = String::new();
-            let mut in_code_block = false;
-            let mut current_path = String::new();
-
-            // Collect following code blocks
-            while let Some(&next_line) = lines.peek() {
-                if next_line.trim().starts_with("```") && !in_code_block {
-                    in_code_block = true;
-                    // Extract path from code block header
-                    let header = next_line.trim().trim_start_matches('`');
-                    if let Some(path_part) = header.split(':').nth(1) {
-                        current_path = path_part.to_string();
-                    } else if header.contains('/') {
-                        current_path = header.to_string();
-                    }
-                    lines.next();
-                } else if next_line.trim() == "```" && in_code_block {
-                    in_code_block = false;
-                    lines.next();
-                    break;
-                } else if in_code_block {
-                    code_content.push_str(next_line);
-                    code_content.push('\n');
-                    lines.next();
-                } else if next_line.trim().starts_with('@') {
-                    break;
-                } else {
-                    lines.next();
+        "service" => {
+            let spec = call.arg("spec")?;
+            (
+                AgentType::Service,
+                "Service",
+                truncate(spec, 50),
+                spec.to_string(),
+            )
+        }
+        "coder" => {
+            let desc = call.arg("description")?;
+            // The coder takes `path|content` when given the new file
+            let params = match (call.arg("path"), call.arg("content")) {
+                (Some(path), Some(content)) if !path.is_empty() => {
+                    format!("{}|{}", path, content)
                 }
-            }
-
-            let params = if !current_path.is_empty() {
-                format!("{}|{}", current_path, code_content)
-            } else {
-                desc.to_string()
-            };
-
-            let request = AgentSpawnRequest {
-                agent_type: AgentType::Coder,
-                name: "Coder".to_string(),
-                description: truncate(desc, 50),
-                parameters: Some(params),
-                parent_id: Some(parent_id),
+                _ => desc.to_string(),
             };
-            let _ = event_tx.send(Event::AgentSpawn(request));
+            (AgentType::Coder, "Coder", truncate(desc, 50), params)
         }
-    }
+        _ => return None,
+    };
+    Some(AgentSpawnRequest {
+        agent_type,
+        name: name.to_string(),
+        description,
+        parameters: Some(parameters),
+        parent_id: Some(parent_id),
+    })
 }
 
 /// Truncate a string to the specified length
@@ -387,6 +596,7 @@ fn truncate(s: &str, max_len: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_truncate() {
@@ -395,35 +605,64 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_shell_command() {
-        let (tx, rx) = crossbeam_channel::unbounded();
-        let response = "@shell ls -la";
-        let parent_id = AgentId::new(1);
-        parse_and_spawn_agents(response, &tx, parent_id);
+    fn test_auth_retry_fires_once_for_its_provider() {
+        let mut retry = AuthRetry::default();
+        assert!(!retry.key_updated("claude"));
+
+        retry.rejected("claude");
+        assert!(!retry.key_updated("openai"));
+        assert!(retry.key_updated("claude"));
+        assert!(!retry.key_updated("claude"));
+
+        retry.rejected("claude");
+        retry.cancel();
+        assert!(!retry.key_updated("claude"));
+    }
 
-        if let Ok(Event::AgentSpawn(req)) = rx.try_recv() {
-            assert_eq!(req.agent_type, AgentType::Shell);
-            assert_eq!(req.parameters, Some("ls -la".to_string()));
-            assert_eq!(req.parent_id, Some(parent_id));
-        } else {
-            panic!("Expected AgentSpawn event");
+    fn call(name: &str, arguments: serde_json::Value) -> ToolCall {
+        ToolCall {
+            id: "call_0".to_string(),
+            name: name.to_string(),
+            arguments,
         }
     }
 
     #[test]
-    fn test_parse_multiple_agents() {
-        let (tx, rx) = crossbeam_channel::unbounded();
-        let response = r#"Let me help you with that.
-
-@shell git status
-@search TODO comments"#;
+    fn test_shell_call() {
         let parent_id = AgentId::new(1);
-        parse_and_spawn_agents(response, &tx, parent_id);
+        let req = spawn_request(&call("shell", json!({ "command": "ls -la" })), parent_id).unwrap();
+        assert_eq!(req.agent_type, AgentType::Shell);
+        assert_eq!(req.parameters, Some("ls -la".to_string()));
+        assert_eq!(req.parent_id, Some(parent_id));
+    }
 
-        let mut count = 0;
-        while rx.try_recv().is_ok() {
-            count += 1;
-        }
-        assert_eq!(count, 2);
+    #[test]
+    fn test_service_call() {
+        let spec = json!({ "spec": "--port 3000 npm run dev" });
+        let req = spawn_request(&call("service", spec), AgentId::new(1)).unwrap();
+        assert_eq!(req.agent_type, AgentType::Service);
+        assert_eq!(req.parameters, Some("--port 3000 npm run dev".to_string()));
+    }
+
+    #[test]
+    fn test_coder_and_bad_calls() {
+        let args = json!({
+            "description": "Update main",
+            "path": "src/main.rs",
+            "content": "fn main() {}\n"
+        });
+        let req = spawn_request(&call("coder", args), AgentId::new(1)).unwrap();
+        assert_eq!(req.agent_type, AgentType::Coder);
+        assert_eq!(
+            req.parameters,
+            Some("src/main.rs|fn main() {}\n".to_string())
+        );
+
+        // Missing arguments and unknown tools start nothing
+        assert!(spawn_request(&call("shell", json!({})), AgentId::new(1)).is_none());
+        assert!(spawn_request(&call("deploy", json!({})), AgentId::new(1)).is_none());
+
+        let names: Vec<String> = conductor_tools().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, ["shell", "coder", "search", "fileops", "service"]);
     }
 }
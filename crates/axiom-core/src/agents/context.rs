@@ -0,0 +1,268 @@
+//! Conversation history for the Conductor, kept within the model's window
+//!
+//! Every message is stored with its estimated token count. Before a request
+//! the Conductor asks how many of the oldest turns must go for the rest to
+//! fit the provider's context window, then either drops them or, with
+//! `[llm.context] summarize = true`, folds them into a running summary that
+//! is sent ahead of the remaining turns.
+
+use crate::config::ContextConfig;
+use crate::llm::{ChatMessage, Role};
+use crate::usage::estimate_tokens;
+use std::collections::VecDeque;
+
+/// Tokens set aside for the summary when older turns are summarized
+const SUMMARY_TOKENS: usize = 512;
+
+/// Marks the system message carrying the summary in saved histories
+const SUMMARY_PREFIX: &str = "Summary of the earlier conversation:\n";
+
+/// A message and its estimated size
+#[derive(Debug, Clone)]
+struct Entry {
+    message: ChatMessage,
+    tokens: usize,
+}
+
+impl Entry {
+    fn new(message: ChatMessage) -> Self {
+        let tokens = estimate_tokens(&message.text()) as usize;
+        Self { message, tokens }
+    }
+}
+
+/// Token-aware conversation history
+#[derive(Debug, Clone, Default)]
+pub struct ContextManager {
+    /// Turns still sent in full, oldest first
+    entries: VecDeque<Entry>,
+
+    /// Summary of turns folded away, sent as a system message
+    summary: Option<Entry>,
+
+    config: ContextConfig,
+}
+
+impl ContextManager {
+    pub fn new(config: ContextConfig) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            summary: None,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &ContextConfig {
+        &self.config
+    }
+
+    /// Switch to `config`, e.g. after `[llm.context]` changed, keeping the
+    /// history
+    pub fn set_config(&mut self, config: ContextConfig) {
+        self.config = config;
+    }
+
+    /// Append a message
+    pub fn push(&mut self, message: ChatMessage) {
+        self.entries.push_back(Entry::new(message));
+    }
+
+    /// The history to send: the summary, if any, then the turns
+    pub fn messages(&self) -> Vec<ChatMessage> {
+        self.summary
+            .iter()
+            .chain(&self.entries)
+            .map(|entry| entry.message.clone())
+            .collect()
+    }
+
+    /// Estimated tokens of everything [`messages`](Self::messages) returns
+    pub fn tokens(&self) -> usize {
+        self.summary
+            .iter()
+            .chain(&self.entries)
+            .map(|entry| entry.tokens)
+            .sum()
+    }
+
+    /// Number of turns held in full
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.summary.is_none()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.summary = None;
+    }
+
+    /// Replace the history, e.g. from a saved session
+    ///
+    /// A leading summary written by [`messages`](Self::messages) becomes the
+    /// summary again.
+    pub fn restore(&mut self, history: Vec<ChatMessage>) {
+        self.clear();
+        let mut history = history.into_iter().peekable();
+        if let Some(first) =
+            history.next_if(|m| m.role == Role::System && m.text().starts_with(SUMMARY_PREFIX))
+        {
+            self.summary = Some(Entry::new(first));
+        }
+        self.entries.extend(history.map(Entry::new));
+    }
+
+    /// Tokens the history may take with a provider whose window is
+    /// `max_context`, after the configured reserve for the reply
+    ///
+    /// `[llm.context.max_tokens]` overrides the provider's own figure.
+    pub fn budget(&self, provider_id: &str, max_context: usize) -> usize {
+        let window = self
+            .config
+            .max_tokens
+            .get(provider_id)
+            .copied()
+            .unwrap_or(max_context);
+        window.saturating_sub(self.config.reserve_tokens)
+    }
+
+    /// How many of the oldest turns must go for the history to fit `budget`
+    ///
+    /// The newest turn always stays. When summarizing, room is left for the
+    /// summary that replaces the turns.
+    pub fn overflow(&self, budget: usize) -> usize {
+        if self.tokens() <= budget {
+            return 0;
+        }
+        let budget = if self.config.summarize {
+            budget.saturating_sub(SUMMARY_TOKENS)
+        } else {
+            budget.saturating_sub(self.summary.as_ref().map_or(0, |s| s.tokens))
+        };
+        let mut kept = 0;
+        let mut fit = 0;
+        for entry in self.entries.iter().rev() {
+            if fit > 0 && kept + entry.tokens > budget {
+                break;
+            }
+            kept += entry.tokens;
+            fit += 1;
+        }
+        self.entries.len() - fit
+    }
+
+    /// Drop the `count` oldest turns
+    pub fn truncate(&mut self, count: usize) {
+        self.entries.drain(..count.min(self.entries.len()));
+    }
+
+    /// Messages asking the model to summarize the `count` oldest turns,
+    /// together with the summary so far
+    pub fn summary_request(&self, count: usize) -> Vec<ChatMessage> {
+        let mut transcript = String::new();
+        if let Some(summary) = &self.summary {
+            transcript.push_str(&summary.message.text());
+            transcript.push_str("\n\n");
+        }
+        for entry in self.entries.iter().take(count) {
+            let speaker = match entry.message.role {
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+                _ => "System",
+            };
+            transcript.push_str(&format!("{}: {}\n", speaker, entry.message.text()));
+        }
+        vec![
+            ChatMessage::system(
+                "Summarize this conversation between a user and a coding assistant in \
+                 under 250 words. Keep decisions, file names, commands and open \
+                 questions; drop pleasantries. Reply with the summary only.",
+            ),
+            ChatMessage::user(transcript),
+        ]
+    }
+
+    /// Replace the `count` oldest turns with `summary`, which covers them
+    /// and any earlier summary
+    pub fn fold(&mut self, count: usize, summary: &str) {
+        self.truncate(count);
+        self.summary = Some(Entry::new(ChatMessage::system(format!(
+            "{}{}",
+            SUMMARY_PREFIX,
+            summary.trim()
+        ))));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A message of about `tokens` tokens
+    fn turn(tokens: usize) -> ChatMessage {
+        ChatMessage::user("abcd".repeat(tokens))
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let mut context = ContextManager::new(ContextConfig::default());
+        for _ in 0..5 {
+            context.push(turn(100));
+        }
+        assert_eq!(context.tokens(), 500);
+        assert_eq!(context.overflow(500), 0);
+        assert_eq!(context.overflow(350), 2);
+        // The newest turn is kept even if it alone is too big
+        assert_eq!(context.overflow(10), 4);
+
+        context.truncate(2);
+        assert_eq!(context.len(), 3);
+        assert_eq!(context.tokens(), 300);
+    }
+
+    #[test]
+    fn test_budget_uses_config_override() {
+        let mut config = ContextConfig::default();
+        config.max_tokens.insert("ollama".to_string(), 32_768);
+        let context = ContextManager::new(config);
+        assert_eq!(context.budget("ollama", 8192), 32_768 - 4096);
+        assert_eq!(context.budget("claude", 200_000), 200_000 - 4096);
+        assert_eq!(context.budget("tiny", 1000), 0);
+    }
+
+    #[test]
+    fn test_fold_into_summary_and_restore() {
+        let config = ContextConfig {
+            summarize: true,
+            ..Default::default()
+        };
+        let mut context = ContextManager::new(config);
+        context.push(ChatMessage::user("rename foo to bar in src/lib.rs"));
+        for _ in 0..4 {
+            context.push(turn(300));
+        }
+
+        // Room is left for the summary
+        let count = context.overflow(1200);
+        assert_eq!(count, 3);
+        let request = context.summary_request(count);
+        assert!(request[1].text().contains("User: rename foo to bar"));
+
+        context.fold(count, "The user renamed foo to bar.");
+        assert_eq!(context.len(), 2);
+        let history = context.messages();
+        assert_eq!(history[0].role, Role::System);
+        assert!(history[0].text().ends_with("The user renamed foo to bar."));
+
+        // A saved history keeps its summary
+        let mut restored = ContextManager::new(ContextConfig::default());
+        restored.restore(history);
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.tokens(), context.tokens());
+        assert!(restored.summary_request(0)[1]
+            .text()
+            .contains("renamed foo to bar"));
+    }
+}
@@ -2,13 +2,17 @@
 //!
 //! Handles the actual execution of different agent types (Shell, Coder, Search, FileOps).
 
-use super::AgentRegistry;
+use super::inputs::resolve_bindings;
+use super::{cancel_after, kill_on_cancel, AgentRegistry, CancelToken};
+use crate::context::{self, ContextBudget, ContextTarget};
 use crate::events::Event;
+use crate::guard::{guard_content, GuardConfig};
 use crate::types::{AgentId, AgentSpawnRequest, AgentStatus, AgentType};
+use crate::workspace::{ExecPolicy, PolicyViolation};
 use crossbeam_channel::Sender;
 use parking_lot::RwLock;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::Arc;
 
@@ -45,13 +49,17 @@ impl Executor {
         let event_tx = self.event_tx.clone();
         let agent_registry = self.agent_registry.clone();
         let cwd = self.cwd.clone();
-        let request = request.clone();
+        let mut request = request.clone();
 
         // Mark agent as running
-        {
+        let cancel_token = {
             let mut registry = agent_registry.write();
             registry.start(agent_id);
-        }
+            registry
+                .get(agent_id)
+                .map(|agent| agent.cancel_token.clone())
+                .unwrap_or_default()
+        };
 
         // Update status
         let _ = event_tx.send(Event::AgentUpdate {
@@ -61,11 +69,35 @@ impl Executor {
 
         // Execute based on type
         std::thread::spawn(move || {
-            let result = match request.agent_type {
-                AgentType::Shell => execute_shell(agent_id, &request, &cwd, &event_tx),
+            // Take in the output of the agents the parameters refer to
+            let resolved = match request.parameters.as_deref() {
+                Some(params) => {
+                    resolve_bindings(agent_id, params, &agent_registry, &cancel_token, |other| {
+                        let _ = event_tx.send(Event::AgentOutput {
+                            id: agent_id,
+                            chunk: format!("Waiting for agent {}...\n", other.value()),
+                        });
+                    })
+                    .map(|params| request.parameters = Some(params))
+                }
+                None => Ok(()),
+            };
+
+            let result = resolved.and_then(|()| match request.agent_type {
+                AgentType::Shell => {
+                    execute_shell(agent_id, &request, &cwd, &event_tx, cancel_token)
+                }
                 AgentType::Coder => execute_coder(agent_id, &request, &cwd, &event_tx),
                 AgentType::Search => execute_search(agent_id, &request, &cwd, &event_tx),
                 AgentType::FileOps => execute_fileops(agent_id, &request, &cwd, &event_tx),
+                AgentType::Service => super::service_agent::run_service(
+                    agent_id,
+                    request.parameters.as_deref().unwrap_or(""),
+                    &cwd,
+                    &agent_registry,
+                    &event_tx,
+                    cancel_token,
+                ),
                 AgentType::Conductor => {
                     // Conductor is handled by the Conductor service
                     Ok(())
@@ -81,10 +113,17 @@ impl Executor {
                     // CLI agents are handled by PtyAgentManager, not this executor
                     Ok(())
                 }
-            };
+            });
 
             // Update agent status based on result
             let mut registry = agent_registry.write();
+            let cancelled = registry
+                .get(agent_id)
+                .map(|a| a.status == AgentStatus::Cancelled)
+                .unwrap_or(true);
+            if cancelled {
+                return;
+            }
             match result {
                 Ok(()) => registry.complete(agent_id),
                 Err(e) => registry.error(agent_id, e),
@@ -101,6 +140,7 @@ fn execute_shell(
     request: &AgentSpawnRequest,
     cwd: &PathBuf,
     event_tx: &Sender<Event>,
+    cancel_token: CancelToken,
 ) -> Result<(), String> {
     let cmd = request.parameters.as_deref().unwrap_or("");
     if cmd.is_empty() {
@@ -112,53 +152,66 @@ fn execute_shell(
         chunk: format!("$ {}\n", cmd),
     });
 
-    // Execute the command
-    let output = Command::new("sh")
-        .arg("-c")
-        .arg(cmd)
-        .current_dir(cwd)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn();
+    // The workspace's policy may refuse the command outright
+    let policy = ExecPolicy::load(cwd);
+    let mut command = policy
+        .command(cwd, cmd)
+        .map_err(|violation| format!("Command blocked: {}", violation))?;
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
 
-    match output {
-        Ok(mut child) => {
-            // Stream stdout
-            if let Some(stdout) = child.stdout.take() {
-                let reader = BufReader::new(stdout);
-                for line in reader.lines() {
-                    if let Ok(line) = line {
-                        let _ = event_tx.send(Event::AgentOutput {
-                            id: agent_id,
-                            chunk: format!("{}\n", line),
-                        });
-                    }
-                }
-            }
+    // Execute the command in its own process group, so cancelling stops
+    // everything it started
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
 
-            // Collect stderr
-            if let Some(stderr) = child.stderr.take() {
-                let reader = BufReader::new(stderr);
-                for line in reader.lines() {
-                    if let Ok(line) = line {
-                        let _ = event_tx.send(Event::AgentOutput {
-                            id: agent_id,
-                            chunk: format!("{}\n", line),
-                        });
-                    }
+    match command.spawn() {
+        Ok(mut child) => {
+            let _guard = kill_on_cancel(child.id(), cancel_token);
+
+            // Stopped by the policy when it runs too long or prints too much
+            let stop = CancelToken::new();
+            let _stop_guard = kill_on_cancel(child.id(), stop.clone());
+            let _timer = policy.timeout().map(|t| cancel_after(stop.clone(), t));
+            let limit = policy.max_output().unwrap_or(usize::MAX);
+            let mut printed = 0usize;
+
+            // Stream stdout, then collect stderr
+            let stdout = child.stdout.take().map(|out| BufReader::new(out).lines());
+            let stderr = child.stderr.take().map(|err| BufReader::new(err).lines());
+            for line in stdout
+                .into_iter()
+                .flatten()
+                .chain(stderr.into_iter().flatten())
+            {
+                let Ok(line) = line else { continue };
+                printed = printed.saturating_add(line.len() + 1);
+                if printed > limit {
+                    stop.cancel();
+                    continue;
                 }
+                let _ = event_tx.send(Event::AgentOutput {
+                    id: agent_id,
+                    chunk: format!("{}\n", line),
+                });
             }
 
             // Wait for completion
-            match child.wait() {
-                Ok(status) => {
-                    if status.success() {
-                        Ok(())
-                    } else {
-                        Err(format!("Command exited with status: {}", status))
+            let status = child
+                .wait()
+                .map_err(|e| format!("Failed to wait for command: {}", e))?;
+            if stop.is_cancelled() {
+                let violation = if printed > limit {
+                    PolicyViolation::OutputLimit { bytes: limit }
+                } else {
+                    PolicyViolation::Timeout {
+                        secs: policy.timeout_secs,
                     }
-                }
-                Err(e) => Err(format!("Failed to wait for command: {}", e)),
+                };
+                Err(format!("Command blocked: {}", violation))
+            } else if status.success() {
+                Ok(())
+            } else {
+                Err(format!("Command exited with status: {}", status))
             }
         }
         Err(e) => Err(format!("Failed to execute command: {}", e)),
@@ -206,6 +259,7 @@ fn execute_coder(
 
         // Emit file modification event for output panel to update
         let _ = event_tx.send(Event::FileModification {
+            id: agent_id,
             path: file_path.to_string_lossy().to_string(),
             content: content.to_string(),
         });
@@ -251,7 +305,14 @@ fn execute_search(
 
     match output {
         Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
+            // Matching lines can hold secrets too, so they're guarded like a file
+            let stdout = guarded(
+                agent_id,
+                &String::from_utf8_lossy(&output.stdout),
+                "search results",
+                cwd,
+                event_tx,
+            );
             let stderr = String::from_utf8_lossy(&output.stderr);
 
             if !stdout.is_empty() {
@@ -295,6 +356,25 @@ fn execute_search(
     }
 }
 
+/// `text` from `source` after the guard of the workspace at `cwd`, with
+/// what it found noted in the agent's output
+fn guarded(
+    agent_id: AgentId,
+    text: &str,
+    source: &str,
+    cwd: &Path,
+    event_tx: &Sender<Event>,
+) -> String {
+    let guarded = guard_content(text, &GuardConfig::load(cwd));
+    if let Some(summary) = guarded.summary(source) {
+        let _ = event_tx.send(Event::AgentOutput {
+            id: agent_id,
+            chunk: format!("{}\n", summary),
+        });
+    }
+    guarded.text
+}
+
 /// Execute a file operations agent
 fn execute_fileops(
     agent_id: AgentId,
@@ -314,10 +394,12 @@ fn execute_fileops(
 
     match operation {
         "read" => {
-            let file_path = if path.starts_with('/') {
-                PathBuf::from(path)
+            // Accepts `path`, `path:10-20` or `path#symbol`
+            let spec = context::parse_file_spec(path);
+            let file_path = if spec.path.starts_with('/') {
+                PathBuf::from(&spec.path)
             } else {
-                cwd.join(path)
+                cwd.join(&spec.path)
             };
 
             let _ = event_tx.send(Event::AgentOutput {
@@ -325,30 +407,88 @@ fn execute_fileops(
                 chunk: format!("Reading: {}\n\n", file_path.display()),
             });
 
-            match std::fs::read_to_string(&file_path) {
-                Ok(content) => {
-                    // Limit output to first 100 lines
-                    let lines: Vec<&str> = content.lines().take(100).collect();
-                    let total_lines = content.lines().count();
+            let content = std::fs::read_to_string(&file_path)
+                .map_err(|e| format!("Failed to read file: {}", e))?;
+            let content = guarded(agent_id, &content, &spec.path, cwd, event_tx);
+            let budget = ContextBudget::default();
 
-                    for (i, line) in lines.iter().enumerate() {
-                        let _ = event_tx.send(Event::AgentOutput {
-                            id: agent_id,
-                            chunk: format!("{:4} | {}\n", i + 1, line),
-                        });
-                    }
+            let snippet = match &spec.target {
+                ContextTarget::File => None,
+                ContextTarget::Lines(start, end) => Some(
+                    context::snippet_for_range(&content, *start, *end, budget.context_lines)
+                        .ok_or_else(|| format!("Line {} is past the end of the file", start))?,
+                ),
+                ContextTarget::Symbol(name) => Some(
+                    context::snippet_for_symbol(&spec.path, &content, name, budget.context_lines)
+                        .ok_or_else(|| format!("Symbol not found: {}", name))?,
+                ),
+            };
 
-                    if total_lines > 100 {
-                        let _ = event_tx.send(Event::AgentOutput {
-                            id: agent_id,
-                            chunk: format!("\n... {} more lines\n", total_lines - 100),
-                        });
-                    }
+            if let Some(snippet) = snippet {
+                let last = snippet.end;
+                let (snippet, _) = context::fit_snippet(snippet, budget.max_bytes);
+                for (i, line) in snippet.text.lines().enumerate() {
+                    let _ = event_tx.send(Event::AgentOutput {
+                        id: agent_id,
+                        chunk: format!("{:4} | {}\n", snippet.start + i, line),
+                    });
+                }
+                if last > snippet.end {
+                    let _ = event_tx.send(Event::AgentOutput {
+                        id: agent_id,
+                        chunk: format!("\n... {} more lines\n", last - snippet.end),
+                    });
+                }
+                return Ok(());
+            }
 
-                    Ok(())
+            // Limit output to first 100 lines
+            let lines: Vec<&str> = content.lines().take(100).collect();
+            let total_lines = content.lines().count();
+
+            for (i, line) in lines.iter().enumerate() {
+                let _ = event_tx.send(Event::AgentOutput {
+                    id: agent_id,
+                    chunk: format!("{:4} | {}\n", i + 1, line),
+                });
+            }
+
+            if total_lines > 100 {
+                let _ = event_tx.send(Event::AgentOutput {
+                    id: agent_id,
+                    chunk: format!("\n... {} more lines\n", total_lines - 100),
+                });
+
+                // Point at the rest of the file instead of dumping it
+                let items = context::outline(&spec.path, &content);
+                if !items.is_empty() {
+                    let _ = event_tx.send(Event::AgentOutput {
+                        id: agent_id,
+                        chunk: format!("\nOutline:\n{}\n", context::format_outline(&items)),
+                    });
                 }
-                Err(e) => Err(format!("Failed to read file: {}", e)),
             }
+
+            Ok(())
+        }
+        "outline" => {
+            let file_path = if path.starts_with('/') {
+                PathBuf::from(path)
+            } else {
+                cwd.join(path)
+            };
+
+            let content = std::fs::read_to_string(&file_path)
+                .map_err(|e| format!("Failed to read file: {}", e))?;
+            let items = context::outline(path, &content);
+
+            let chunk = if items.is_empty() {
+                format!("No outline available for {}\n", file_path.display())
+            } else {
+                format!("Outline: {}\n\n{}\n", file_path.display(), context::format_outline(&items))
+            };
+            let _ = event_tx.send(Event::AgentOutput { id: agent_id, chunk });
+            Ok(())
         }
         "list" | "ls" => {
             let dir_path = if path.is_empty() {
@@ -459,7 +599,7 @@ mod tests {
             parent_id: None,
         };
 
-        let result = execute_shell(AgentId::new(1), &request, &cwd, &tx);
+        let result = execute_shell(AgentId::new(1), &request, &cwd, &tx, CancelToken::new());
         assert!(result.is_ok());
 
         // Check output contains "hello"
@@ -474,6 +614,45 @@ mod tests {
         assert!(found_hello);
     }
 
+    #[test]
+    fn test_shell_policy_blocks_and_stops_commands() {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let dir = tempfile::TempDir::new().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let run = |command: &str| {
+            let request = AgentSpawnRequest {
+                agent_type: AgentType::Shell,
+                name: "Test".to_string(),
+                description: "Test".to_string(),
+                parameters: Some(command.to_string()),
+                parent_id: None,
+            };
+            execute_shell(AgentId::new(1), &request, &cwd, &tx, CancelToken::new())
+        };
+
+        let err = run("sudo rm -rf build").unwrap_err();
+        assert_eq!(
+            err,
+            "Command blocked: 'sudo' is denied by the command policy"
+        );
+
+        std::fs::create_dir(cwd.join(".axiom")).unwrap();
+        std::fs::write(
+            cwd.join(".axiom/config.toml"),
+            "[exec_policy]\ntimeout_secs = 1\nmax_output = 100\n",
+        )
+        .unwrap();
+        assert_eq!(
+            run("sleep 10").unwrap_err(),
+            "Command blocked: command stopped after 1s"
+        );
+        assert_eq!(
+            run("yes").unwrap_err(),
+            "Command blocked: command stopped after printing more than 100 bytes"
+        );
+        assert!(run("echo fine").is_ok());
+    }
+
     #[test]
     fn test_search_execution() {
         let (tx, _rx) = crossbeam_channel::unbounded();
@@ -490,4 +669,33 @@ mod tests {
         let result = execute_search(AgentId::new(1), &request, &cwd, &tx);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_fileops_read_is_guarded() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let dir = tempfile::TempDir::new().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let key = "sk-proj-abcdefghijklmnopqrstuvwxyz012345";
+        std::fs::write(cwd.join(".env"), format!("OPENAI_API_KEY={}\n", key)).unwrap();
+
+        let request = AgentSpawnRequest {
+            agent_type: AgentType::FileOps,
+            name: "Read".to_string(),
+            description: "Test".to_string(),
+            parameters: Some("read .env".to_string()),
+            parent_id: None,
+        };
+        execute_fileops(AgentId::new(1), &request, &cwd, &tx).unwrap();
+
+        let output: String = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                Event::AgentOutput { chunk, .. } => Some(chunk),
+                _ => None,
+            })
+            .collect();
+        assert!(!output.contains(key));
+        assert!(output.contains("[REDACTED:"));
+        assert!(output.contains("Guard: redacted 1 secret in .env"));
+    }
 }
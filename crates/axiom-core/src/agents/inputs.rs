@@ -0,0 +1,246 @@
+//! Agent parameters that take another agent's result
+//!
+//! A spawn request's parameters may refer to an earlier agent with
+//! `{{agent:<id>.output}}` or `{{agent:<id>.status}}`. The executor fills
+//! these in just before the agent runs. With `| wait`, as in
+//! `{{agent:12.output | wait}}`, it first waits for agent 12 to finish, so a
+//! simple chain (write a plan, then carry it out) needs no pipeline.
+
+use super::{AgentRegistry, CancelToken, CANCEL_POLL};
+use crate::types::{AgentId, AgentStatus};
+use parking_lot::RwLock;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// What a binding takes from the agent it names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputField {
+    /// Everything the agent has written so far
+    Output,
+
+    /// Its status, e.g. `Completed`
+    Status,
+}
+
+/// A `{{agent:…}}` reference in an agent's parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputBinding {
+    /// The agent referred to
+    pub agent_id: AgentId,
+
+    /// What is taken from it
+    pub field: InputField,
+
+    /// Whether to wait until the agent has finished
+    pub wait: bool,
+}
+
+fn binding_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\{\{\s*agent:(\d+)\.(\w+)\s*(?:\|\s*(\w+)\s*)?\}\}").unwrap())
+}
+
+/// The bindings in `text`, in order
+pub fn parse_bindings(text: &str) -> Result<Vec<InputBinding>, String> {
+    binding_regex()
+        .captures_iter(text)
+        .map(|caps| {
+            let agent_id = AgentId::new(
+                caps[1]
+                    .parse()
+                    .map_err(|_| format!("Agent ID out of range in `{}`", &caps[0]))?,
+            );
+            let field = match &caps[2] {
+                "output" => InputField::Output,
+                "status" => InputField::Status,
+                other => {
+                    return Err(format!(
+                        "Unknown field `{}` in `{}` (use output or status)",
+                        other, &caps[0]
+                    ))
+                }
+            };
+            let wait = match caps.get(3).map(|m| m.as_str()) {
+                None => false,
+                Some("wait") => true,
+                Some(other) => return Err(format!("Unknown option `{}` in `{}`", other, &caps[0])),
+            };
+            Ok(InputBinding {
+                agent_id,
+                field,
+                wait,
+            })
+        })
+        .collect()
+}
+
+/// Fill in the bindings in `text` for agent `agent_id`
+///
+/// Waits for the agents marked `| wait`, giving up if `cancel_token` is
+/// cancelled or one of them fails. `on_wait` is told which agent is being
+/// waited for, before the wait starts.
+pub fn resolve_bindings(
+    agent_id: AgentId,
+    text: &str,
+    registry: &RwLock<AgentRegistry>,
+    cancel_token: &CancelToken,
+    mut on_wait: impl FnMut(AgentId),
+) -> Result<String, String> {
+    let bindings = parse_bindings(text)?;
+    if bindings.is_empty() {
+        return Ok(text.to_string());
+    }
+
+    for binding in bindings.iter().filter(|b| b.wait) {
+        if binding.agent_id == agent_id {
+            return Err("An agent can't wait for itself".to_string());
+        }
+        let mut announced = false;
+        loop {
+            if cancel_token.is_cancelled() {
+                return Err("Cancelled".to_string());
+            }
+            let status = registry
+                .read()
+                .get(binding.agent_id)
+                .map(|agent| agent.status.clone())
+                .ok_or_else(|| format!("No agent {}", binding.agent_id.value()))?;
+            match status {
+                AgentStatus::Completed => break,
+                AgentStatus::Error(_) | AgentStatus::Cancelled => {
+                    return Err(format!(
+                        "Agent {} didn't complete ({})",
+                        binding.agent_id.value(),
+                        status
+                    ))
+                }
+                _ => {}
+            }
+            if !announced {
+                on_wait(binding.agent_id);
+                announced = true;
+            }
+            std::thread::sleep(CANCEL_POLL);
+        }
+    }
+
+    let registry = registry.read();
+    let mut missing = None;
+    let resolved = binding_regex().replace_all(text, |caps: &regex::Captures| {
+        // Parsed above, so only the lookup can fail
+        let id = AgentId::new(caps[1].parse().unwrap_or_default());
+        match registry.get(id) {
+            Some(agent) if &caps[2] == "status" => agent.status.to_string(),
+            Some(agent) => agent.output.trim_end().to_string(),
+            None => {
+                missing.get_or_insert(id);
+                String::new()
+            }
+        }
+    });
+    match missing {
+        Some(id) => Err(format!("No agent {}", id.value())),
+        None => Ok(resolved.into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AgentSpawnRequest, AgentType};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn spawn(registry: &RwLock<AgentRegistry>) -> AgentId {
+        registry.write().spawn(AgentSpawnRequest {
+            agent_type: AgentType::Shell,
+            name: "Plan".to_string(),
+            description: "Plan".to_string(),
+            parameters: None,
+            parent_id: None,
+        })
+    }
+
+    #[test]
+    fn test_parse_bindings() {
+        let bindings =
+            parse_bindings("do {{agent:12.output | wait}} after {{ agent:3.status }}").unwrap();
+        assert_eq!(
+            bindings,
+            [
+                InputBinding {
+                    agent_id: AgentId::new(12),
+                    field: InputField::Output,
+                    wait: true,
+                },
+                InputBinding {
+                    agent_id: AgentId::new(3),
+                    field: InputField::Status,
+                    wait: false,
+                },
+            ]
+        );
+        assert!(parse_bindings("{{agent:1.lines}}").is_err());
+        assert!(parse_bindings("{{agent:1.output | later}}").is_err());
+        assert!(parse_bindings("{{agent:x.output}}").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_resolve_waits_for_completion() {
+        let registry = Arc::new(RwLock::new(AgentRegistry::new()));
+        let plan = spawn(&registry);
+        let step = spawn(&registry);
+        registry.write().append_output(plan, "1. add a flag\n");
+
+        // Without wait the output so far is used
+        let text = format!("so far: {{{{agent:{}.output}}}}", plan.value());
+        let resolved =
+            resolve_bindings(step, &text, &registry, &CancelToken::new(), |_| {}).unwrap();
+        assert_eq!(resolved, "so far: 1. add a flag");
+
+        let writer = {
+            let registry = registry.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(100));
+                let mut registry = registry.write();
+                registry.append_output(plan, "2. test it\n");
+                registry.complete(plan);
+            })
+        };
+        let text = format!("Implement: {{{{agent:{}.output | wait}}}}", plan.value());
+        let mut waited = Vec::new();
+        let resolved = resolve_bindings(step, &text, &registry, &CancelToken::new(), |id| {
+            waited.push(id)
+        })
+        .unwrap();
+        writer.join().unwrap();
+        assert_eq!(resolved, "Implement: 1. add a flag\n2. test it");
+        assert_eq!(waited, [plan]);
+    }
+
+    #[test]
+    fn test_resolve_failures() {
+        let registry = RwLock::new(AgentRegistry::new());
+        let plan = spawn(&registry);
+        let step = spawn(&registry);
+        let cancel = CancelToken::new();
+
+        let missing = resolve_bindings(step, "{{agent:99.output}}", &registry, &cancel, |_| {});
+        assert_eq!(missing.unwrap_err(), "No agent 99");
+
+        let own = format!("{{{{agent:{}.output|wait}}}}", step.value());
+        assert!(resolve_bindings(step, &own, &registry, &cancel, |_| {}).is_err());
+
+        registry.write().error(plan, "boom".to_string());
+        let text = format!("{{{{agent:{}.output | wait}}}}", plan.value());
+        let failed = resolve_bindings(step, &text, &registry, &cancel, |_| {}).unwrap_err();
+        assert!(failed.contains("didn't complete"));
+
+        // A cancelled agent stops waiting
+        let other = spawn(&registry);
+        let text = format!("{{{{agent:{}.output | wait}}}}", other.value());
+        cancel.cancel();
+        let cancelled = resolve_bindings(step, &text, &registry, &cancel, |_| {});
+        assert_eq!(cancelled.unwrap_err(), "Cancelled");
+    }
+}
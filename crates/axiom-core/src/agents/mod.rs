@@ -3,15 +3,37 @@
 //! The agent system tracks all agents spawned by the conductor,
 //! manages their lifecycle, and stores their output.
 
+mod actions;
+mod cancel;
 mod conductor;
+mod context;
 mod executor;
+mod inputs;
+mod output_parser;
 mod pty_agent;
 mod pty_manager;
-
-pub use conductor::Conductor;
+mod readiness;
+mod service_agent;
+mod terminal;
+mod transcript;
+
+pub use actions::{last_message, last_prompt, touched_files, AgentAction};
+pub use cancel::{
+    cancel_after, kill_on_cancel, terminate, CancelToken, ProcessGuard, CANCEL_POLL, KILL_GRACE,
+};
+pub use conductor::{AuthRetry, Conductor};
+pub use context::ContextManager;
 pub use executor::Executor;
+pub use inputs::{parse_bindings, InputBinding, InputField};
+pub use output_parser::{output_line, strip_ansi, OutputParsers};
 pub use pty_agent::PtyAgent;
 pub use pty_manager::PtyAgentManager;
+pub use readiness::PromptInjector;
+pub use service_agent::{
+    supervise, HealthCheck, RestartPolicy, ServiceHost, ServiceSpec, ServiceState,
+};
+pub use terminal::{TerminalEvent, TerminalOutput, TerminalSession};
+pub use transcript::{transcript_context, Transcript, DEFAULT_SCROLLBACK_LINES};
 
 use crate::types::{AgentId, AgentSpawnRequest, AgentStatus, AgentType};
 use std::collections::HashMap;
@@ -33,6 +55,9 @@ pub struct Agent {
     /// Description of what this agent is doing
     pub description: String,
 
+    /// What the agent was spawned with, kept for re-running it
+    pub parameters: Option<String>,
+
     /// Current execution status
     pub status: AgentStatus,
 
@@ -56,6 +81,14 @@ pub struct Agent {
 
     /// Parent agent that spawned this agent (for aggregated output)
     pub parent_id: Option<AgentId>,
+
+    /// Process health and restarts (service agents only)
+    pub service: Option<ServiceState>,
+    /// When the agent last started or produced output
+    pub last_activity: Instant,
+
+    /// Stops the agent's running work when cancelled
+    pub cancel_token: CancelToken,
 }
 
 impl Agent {
@@ -72,25 +105,31 @@ impl Agent {
             agent_type,
             name,
             description,
+            parameters: None,
             status: AgentStatus::Pending,
             output: String::new(),
             created_at: Instant::now(),
             completed_at: None,
+            last_activity: Instant::now(),
             token_count: 0,
             line_count: 0,
             progress: None,
             parent_id,
+            service: None,
+            cancel_token: CancelToken::new(),
         }
     }
 
     /// Start the agent (set status to Running)
     pub fn start(&mut self) {
         self.status = AgentStatus::Running;
+        self.last_activity = Instant::now();
     }
 
     /// Append output to the agent
     pub fn append_output(&mut self, chunk: &str) {
         self.output.push_str(chunk);
+        self.last_activity = Instant::now();
         // Estimate tokens (~4 chars per token on average)
         self.token_count = self.output.len() / 4;
         // Count lines
@@ -118,6 +157,7 @@ impl Agent {
     pub fn cancel(&mut self) {
         self.status = AgentStatus::Cancelled;
         self.completed_at = Some(Instant::now());
+        self.cancel_token.cancel();
     }
 
     /// Get the duration since creation
@@ -161,13 +201,14 @@ impl AgentRegistry {
     /// Spawn a new agent and return its ID
     pub fn spawn(&mut self, request: AgentSpawnRequest) -> AgentId {
         let id = AgentId::new(self.next_id.fetch_add(1, Ordering::SeqCst));
-        let agent = Agent::new(
+        let mut agent = Agent::new(
             id,
             request.agent_type,
             request.name,
             request.description,
             request.parent_id,
         );
+        agent.parameters = request.parameters;
 
         self.order.insert(0, id); // Add to front (most recent)
         self.agents.insert(id, agent);
@@ -235,14 +276,45 @@ impl AgentRegistry {
         self.agents.is_empty()
     }
 
+    /// Running agents with no output for at least `after`, quietest first
+    pub fn stalled(&self, after: std::time::Duration) -> Vec<&Agent> {
+        let mut stalled: Vec<&Agent> = self
+            .agents
+            .values()
+            .filter(|a| a.status.is_running() && a.last_activity.elapsed() >= after)
+            .collect();
+        stalled.sort_by_key(|a| a.last_activity);
+        stalled
+    }
+
+    /// Count an agent as active now, e.g. when the user chooses to keep waiting
+    pub fn touch(&mut self, id: AgentId) {
+        if let Some(agent) = self.agents.get_mut(&id) {
+            agent.last_activity = Instant::now();
+        }
+    }
+
     /// Get the number of running agents
+    ///
+    /// Long-running services are not counted; they never "finish".
     pub fn running_count(&self) -> usize {
         self.agents
             .values()
-            .filter(|a| a.status.is_running())
+            .filter(|a| a.status.is_running() && !a.agent_type.is_long_running())
             .count()
     }
 
+    /// Check whether all of a parent's children have finished
+    ///
+    /// Service agents are ignored so a running dev server doesn't hold a
+    /// Conductor run open.
+    pub fn children_done(&self, parent_id: AgentId) -> bool {
+        self.agents
+            .values()
+            .filter(|a| a.parent_id == Some(parent_id) && !a.agent_type.is_long_running())
+            .all(|a| a.status.is_terminal())
+    }
+
     /// Start an agent
     pub fn start(&mut self, id: AgentId) {
         if let Some(agent) = self.agents.get_mut(&id) {
@@ -271,6 +343,33 @@ impl AgentRegistry {
         }
     }
 
+    /// Cancel a pending or running agent, stopping its work
+    ///
+    /// Returns whether there was anything to cancel.
+    pub fn cancel(&mut self, id: AgentId) -> bool {
+        match self.agents.get_mut(&id) {
+            Some(agent) if is_cancellable(&agent.status) => {
+                agent.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Cancel every pending or running agent; returns their IDs
+    pub fn cancel_all(&mut self) -> Vec<AgentId> {
+        let ids: Vec<AgentId> = self
+            .order
+            .iter()
+            .copied()
+            .filter(|id| self.agents.get(id).is_some_and(|a| is_cancellable(&a.status)))
+            .collect();
+        for id in &ids {
+            self.cancel(*id);
+        }
+        ids
+    }
+
     /// Remove completed/cancelled agents older than the specified age
     pub fn cleanup_old(&mut self, max_age: std::time::Duration) {
         let now = Instant::now();
@@ -295,6 +394,21 @@ impl AgentRegistry {
         }
     }
 
+    /// Replace every agent with `agents`, e.g. from a saved session
+    ///
+    /// Ids are kept, and new agents are numbered after the highest of them.
+    pub fn restore(&mut self, agents: Vec<Agent>) {
+        self.agents.clear();
+        self.order.clear();
+        let next = agents.iter().map(|a| a.id.0 + 1).max().unwrap_or(1);
+        self.next_id.store(next, Ordering::SeqCst);
+        for agent in agents {
+            self.order.insert(0, agent.id);
+            self.agents.insert(agent.id, agent);
+        }
+        self.selected = self.order.first().copied();
+    }
+
     /// Remove all children of a parent agent (for new interaction cleanup)
     pub fn remove_children(&mut self, parent_id: AgentId) {
         let to_remove: Vec<AgentId> = self
@@ -314,6 +428,11 @@ impl AgentRegistry {
     }
 }
 
+/// Idle agents (the Conductor between prompts) have no work to stop
+fn is_cancellable(status: &AgentStatus) -> bool {
+    matches!(status, AgentStatus::Pending | AgentStatus::Running)
+}
+
 impl Default for AgentRegistry {
     fn default() -> Self {
         Self::new()
@@ -367,6 +486,34 @@ mod tests {
         assert!(registry.get(id).unwrap().status.is_terminal());
     }
 
+    #[test]
+    fn test_cancel_fires_token() {
+        let mut registry = AgentRegistry::new();
+        let spawn = |registry: &mut AgentRegistry, agent_type| {
+            registry.spawn(AgentSpawnRequest {
+                agent_type,
+                name: "Agent".to_string(),
+                description: "".to_string(),
+                parameters: None,
+                parent_id: None,
+            })
+        };
+        let conductor = spawn(&mut registry, AgentType::Conductor);
+        let running = spawn(&mut registry, AgentType::Shell);
+        let done = spawn(&mut registry, AgentType::Shell);
+        registry.get_mut(conductor).unwrap().status = AgentStatus::Idle;
+        registry.start(running);
+        registry.complete(done);
+        let token = registry.get(running).unwrap().cancel_token.clone();
+
+        assert_eq!(registry.cancel_all(), [running]);
+        assert!(token.is_cancelled());
+        assert_eq!(registry.get(running).unwrap().status, AgentStatus::Cancelled);
+        assert_eq!(registry.get(done).unwrap().status, AgentStatus::Completed);
+        assert_eq!(registry.get(conductor).unwrap().status, AgentStatus::Idle);
+        assert!(!registry.cancel(running));
+    }
+
     #[test]
     fn test_selection() {
         let mut registry = AgentRegistry::new();
@@ -395,4 +542,39 @@ mod tests {
         registry.select(id2);
         assert_eq!(registry.selected_id(), Some(id2));
     }
+
+    #[test]
+    fn test_services_excluded_from_completion() {
+        let mut registry = AgentRegistry::new();
+        let parent = registry.spawn(AgentSpawnRequest {
+            agent_type: AgentType::Conductor,
+            name: "Conductor".to_string(),
+            description: "".to_string(),
+            parameters: None,
+            parent_id: None,
+        });
+        let shell = registry.spawn(AgentSpawnRequest {
+            agent_type: AgentType::Shell,
+            name: "Shell".to_string(),
+            description: "".to_string(),
+            parameters: None,
+            parent_id: Some(parent),
+        });
+        let service = registry.spawn(AgentSpawnRequest {
+            agent_type: AgentType::Service,
+            name: "Service".to_string(),
+            description: "".to_string(),
+            parameters: None,
+            parent_id: Some(parent),
+        });
+        registry.start(shell);
+        registry.start(service);
+        assert_eq!(registry.running_count(), 1);
+        assert!(!registry.children_done(parent));
+
+        registry.complete(shell);
+        assert_eq!(registry.running_count(), 0);
+        assert!(registry.children_done(parent));
+        assert!(registry.get(service).unwrap().status.is_running());
+    }
 }
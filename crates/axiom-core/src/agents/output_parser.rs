@@ -0,0 +1,390 @@
+//! Structured events from CLI agent output
+//!
+//! CLI agents print recognizable lines as they work: aider says
+//! `Applied edit to src/main.py` and reports what each message cost. The
+//! parsers in a [`CliAgentConfig`](crate::config::CliAgentConfig) turn such
+//! lines into [`AgentEvent`]s, either with a regex or with a script that
+//! reads the output and prints events as JSON.
+//!
+//! Output is split into lines with escape sequences removed before parsing.
+
+use crate::config::{OutputParserConfig, ParserEvent};
+use crate::error::{AxiomError, Result};
+use crate::types::AgentEvent;
+use crossbeam_channel::Receiver;
+use regex::{Captures, Regex};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::thread::JoinHandle;
+
+/// Longest partial line kept while waiting for its newline
+const MAX_PENDING: usize = 64 * 1024;
+
+/// A regex parser, compiled
+struct LineRule {
+    regex: Regex,
+    event: ParserEvent,
+}
+
+/// A parser script, fed output lines on stdin
+struct ScriptParser {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    events: Receiver<AgentEvent>,
+    /// Forwards the script's events until its stdout closes
+    reader: Option<JoinHandle<()>>,
+}
+
+/// The parsers of one running CLI agent
+pub struct OutputParsers {
+    rules: Vec<LineRule>,
+    scripts: Vec<ScriptParser>,
+    /// Output after the last newline
+    pending: Vec<u8>,
+}
+
+impl OutputParsers {
+    /// Compile `configs`, starting parser scripts in `cwd`
+    ///
+    /// Fails on an invalid regex or a script that can't be started.
+    pub fn new(configs: &[OutputParserConfig], cwd: &Path) -> Result<Self> {
+        let mut parsers = Self {
+            rules: Vec::new(),
+            scripts: Vec::new(),
+            pending: Vec::new(),
+        };
+        for config in configs {
+            if let Some(pattern) = &config.regex {
+                let regex = Regex::new(pattern).map_err(|e| {
+                    AxiomError::config(format!("Invalid output parser regex '{}': {}", pattern, e))
+                })?;
+                parsers.rules.push(LineRule {
+                    regex,
+                    event: config.event.unwrap_or(ParserEvent::Progress),
+                });
+            }
+            if let Some(script) = &config.script {
+                parsers.scripts.push(ScriptParser::start(script, cwd)?);
+            }
+        }
+        Ok(parsers)
+    }
+
+    /// Whether there is nothing to parse with
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.scripts.is_empty()
+    }
+
+    /// Feed raw PTY output; returns the events found so far
+    ///
+    /// Events from scripts arrive as they print them, so some may come with
+    /// a later call.
+    pub fn feed(&mut self, data: &[u8]) -> Vec<AgentEvent> {
+        self.pending.extend_from_slice(data);
+        let mut events = Vec::new();
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let raw: Vec<u8> = self.pending.drain(..=end).collect();
+            let line = output_line(&String::from_utf8_lossy(&raw));
+            self.parse_line(&line, &mut events);
+        }
+        if self.pending.len() > MAX_PENDING {
+            self.pending.clear();
+        }
+        self.collect_script_events(&mut events);
+        events
+    }
+
+    /// Parse what's left of the output and stop the scripts
+    ///
+    /// Returns the remaining events, waiting for each script to exit.
+    pub fn finish(mut self) -> Vec<AgentEvent> {
+        let mut events = Vec::new();
+        if !self.pending.is_empty() {
+            let line = output_line(&String::from_utf8_lossy(&self.pending));
+            self.parse_line(&line, &mut events);
+        }
+        for script in &mut self.scripts {
+            // Closing stdin ends the script's input
+            script.stdin = None;
+            let _ = script.child.wait();
+            // The last events may still be on their way from stdout
+            if let Some(reader) = script.reader.take() {
+                let _ = reader.join();
+            }
+        }
+        self.collect_script_events(&mut events);
+        events
+    }
+
+    fn parse_line(&mut self, line: &str, events: &mut Vec<AgentEvent>) {
+        if line.trim().is_empty() {
+            return;
+        }
+        events.extend(
+            self.rules
+                .iter()
+                .filter_map(|rule| rule.regex.captures(line).and_then(|c| rule.event(line, &c))),
+        );
+        for script in &mut self.scripts {
+            let sent = script
+                .stdin
+                .as_mut()
+                .is_some_and(|stdin| writeln!(stdin, "{}", line).and_then(|_| stdin.flush()).is_ok());
+            if !sent {
+                script.stdin = None;
+            }
+        }
+    }
+
+    fn collect_script_events(&self, events: &mut Vec<AgentEvent>) {
+        for script in &self.scripts {
+            events.extend(script.events.try_iter());
+        }
+    }
+}
+
+impl Drop for OutputParsers {
+    fn drop(&mut self) {
+        for script in &mut self.scripts {
+            let _ = script.child.kill();
+            let _ = script.child.wait();
+        }
+    }
+}
+
+impl LineRule {
+    /// The event for a matching `line`, from the regex's named groups
+    ///
+    /// File and command events need a `path` or `command` group; progress
+    /// takes `message` (the whole line without one) and `percent`; cost
+    /// takes `cost`, `input_tokens` and `output_tokens`.
+    fn event(&self, line: &str, captures: &Captures) -> Option<AgentEvent> {
+        let group = |name: &str| captures.name(name).map(|m| m.as_str().trim());
+        match self.event {
+            ParserEvent::FileChanged => Some(AgentEvent::FileChanged {
+                path: group("path").filter(|p| !p.is_empty())?.into(),
+            }),
+            ParserEvent::CommandRun => Some(AgentEvent::CommandRun {
+                command: group("command").filter(|c| !c.is_empty())?.to_string(),
+            }),
+            ParserEvent::Progress => Some(AgentEvent::Progress {
+                message: group("message").unwrap_or(line.trim()).to_string(),
+                percent: group("percent").and_then(|p| p.parse().ok()),
+            }),
+            ParserEvent::Cost => {
+                let usd = group("cost").and_then(|c| c.replace(',', "").parse().ok());
+                let input_tokens = group("input_tokens").and_then(parse_count).unwrap_or(0);
+                let output_tokens = group("output_tokens").and_then(parse_count).unwrap_or(0);
+                if usd.is_none() && input_tokens == 0 && output_tokens == 0 {
+                    return None;
+                }
+                Some(AgentEvent::Cost {
+                    usd,
+                    input_tokens,
+                    output_tokens,
+                })
+            }
+        }
+    }
+}
+
+impl ScriptParser {
+    /// Run `script` with `sh -c`, reading JSON events from its stdout
+    fn start(script: &str, cwd: &Path) -> Result<Self> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .current_dir(cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| {
+                AxiomError::config(format!("Failed to start output parser '{}': {}", script, e))
+            })?;
+        let stdin = child.stdin.take();
+        let stdout = child.stdout.take();
+
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let reader = stdout.map(|stdout| {
+            std::thread::spawn(move || {
+                for line in BufReader::new(stdout).lines() {
+                    let Ok(line) = line else { break };
+                    // Anything that isn't an event is ignored
+                    if let Ok(event) = serde_json::from_str::<AgentEvent>(&line) {
+                        if tx.send(event).is_err() {
+                            break;
+                        }
+                    }
+                }
+            })
+        });
+        Ok(Self {
+            child,
+            stdin,
+            events: rx,
+            reader,
+        })
+    }
+}
+
+/// A token count such as `1234`, `1,234` or `2.3k`
+fn parse_count(text: &str) -> Option<u64> {
+    let text = text.replace(',', "").to_lowercase();
+    let (number, scale) = match text.strip_suffix('k') {
+        Some(number) => (number, 1_000.0),
+        None => match text.strip_suffix('m') {
+            Some(number) => (number, 1_000_000.0),
+            None => (text.as_str(), 1.0),
+        },
+    };
+    let value: f64 = number.trim().parse().ok()?;
+    Some((value * scale).round() as u64)
+}
+
+/// The text a terminal would show for one line of raw output
+///
+/// Drops escape sequences and control characters; a carriage return
+/// starts the line over, as progress bars redraw themselves.
+pub fn output_line(raw: &str) -> String {
+    let text = strip_ansi(raw.trim_end_matches(['\r', '\n']));
+    match text.rsplit_once('\r') {
+        Some((_, last)) => last.to_string(),
+        None => text,
+    }
+}
+
+/// Remove ANSI escape sequences and control characters other than tab and
+/// carriage return
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters up to a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: up to BEL or ST (ESC \)
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // Two-byte sequences such as ESC ( B
+                Some('(') | Some(')') => {
+                    chars.next();
+                }
+                _ => {}
+            },
+            '\t' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(regex: &str, event: ParserEvent) -> OutputParserConfig {
+        OutputParserConfig {
+            regex: Some(regex.to_string()),
+            event: Some(event),
+            script: None,
+        }
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[1;32mok\x1b[0m done"), "ok done");
+        assert_eq!(strip_ansi("\x1b]0;title\x07text"), "text");
+        assert_eq!(output_line("50%\r\x1b[K100%\r\n"), "100%");
+    }
+
+    #[test]
+    fn test_regex_parsers() {
+        let dir = tempfile::tempdir().unwrap();
+        let configs = crate::config::default_parsers("aider");
+        let mut parsers = OutputParsers::new(&configs, dir.path()).unwrap();
+
+        let mut events = parsers.feed(b"\x1b[32mApplied edit to src/app");
+        assert!(events.is_empty());
+        events.extend(parsers.feed(b".py\r\nTokens: 2.3k sent, 150 received. Cost: $0.01 message, $0.05 session.\n"));
+        assert_eq!(
+            events,
+            vec![
+                AgentEvent::FileChanged {
+                    path: "src/app.py".into()
+                },
+                AgentEvent::Cost {
+                    usd: Some(0.01),
+                    input_tokens: 2300,
+                    output_tokens: 150,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_progress_and_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let configs = [
+            rule(r"^\$ (?P<command>.+)$", ParserEvent::CommandRun),
+            rule(r"^Step .* (?P<percent>\d+)%$", ParserEvent::Progress),
+        ];
+        let mut parsers = OutputParsers::new(&configs, dir.path()).unwrap();
+        let events = parsers.feed(b"$ cargo test\nStep 2 of 4 50%\n");
+        assert_eq!(
+            events,
+            vec![
+                AgentEvent::CommandRun {
+                    command: "cargo test".to_string()
+                },
+                AgentEvent::Progress {
+                    message: "Step 2 of 4 50%".to_string(),
+                    percent: Some(50.0),
+                },
+            ]
+        );
+
+        assert!(OutputParsers::new(&[rule("(", ParserEvent::Progress)], dir.path()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_script_parser() {
+        let dir = tempfile::tempdir().unwrap();
+        let configs = [OutputParserConfig {
+            regex: None,
+            event: None,
+            script: Some(
+                r#"while read -r line; do printf '{"kind":"command_run","command":"%s"}\n' "$line"; done"#
+                    .to_string(),
+            ),
+        }];
+        let mut parsers = OutputParsers::new(&configs, dir.path()).unwrap();
+        let mut events = parsers.feed(b"make\n");
+        events.extend(parsers.finish());
+        assert_eq!(
+            events,
+            vec![AgentEvent::CommandRun {
+                command: "make".to_string()
+            }]
+        );
+    }
+}
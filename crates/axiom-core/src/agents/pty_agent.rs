@@ -1,17 +1,22 @@
 //! PTY session wrapper for CLI agents
 //!
 //! Manages a single PTY session running an external CLI coding agent
-//! (Claude Code, Gemini CLI, etc.) with terminal emulation.
+//! (Claude Code, Gemini CLI, etc.), or an interactive shell, with terminal
+//! emulation.
 //!
 //! This module is UI-agnostic and returns `TerminalScreen` for rendering.
 
+use super::readiness::PromptInjector;
+use super::terminate;
 use crate::config::CliAgentConfig;
 use crate::error::{AxiomError, Result};
 use crate::events::Event;
+use crate::recordings::Recorder;
 use crate::types::{AgentId, TerminalCell, TerminalColor, TerminalLine, TerminalScreen};
 use crossbeam_channel::Sender;
 use parking_lot::RwLock;
-use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use std::ffi::OsString;
 use std::io::{Read, Write};
 use std::path::Path;
 use std::sync::Arc;
@@ -35,6 +40,12 @@ pub struct PtyAgent {
 
     /// Whether the PTY process has exited
     pub exited: bool,
+
+    /// The CLI process, kept so it can be stopped
+    child: Box<dyn Child + Send + Sync>,
+
+    /// Recording of the session, if the agent is configured to record
+    recorder: Option<Arc<parking_lot::Mutex<Recorder>>>,
 }
 
 impl PtyAgent {
@@ -56,6 +67,112 @@ impl PtyAgent {
         cols: u16,
         rows: u16,
         event_tx: Sender<Event>,
+    ) -> Result<Self> {
+        // Build the command, under bash if activation hooks run first
+        let (program, args) = config.launch(prompt);
+        let mut cmd = CommandBuilder::new(program);
+        for arg in args {
+            cmd.arg(arg);
+        }
+
+        // Start in the workspace, or the configured subdirectory of it
+        let dir = config.start_dir(cwd).map_err(AxiomError::config)?;
+        if let Some(dir) = &dir {
+            if !dir.is_dir() {
+                return Err(AxiomError::pty(format!(
+                    "Working directory not found: {}",
+                    dir.display()
+                )));
+            }
+            cmd.cwd(dir);
+        }
+
+        // Set any custom environment variables
+        for (key, value) in &config.env {
+            cmd.env(key, value);
+        }
+
+        // Extra PATH entries go in front of the configured or inherited PATH
+        let inherited = match config.env.get("PATH") {
+            Some(path) => Some(OsString::from(path)),
+            None => std::env::var_os("PATH"),
+        };
+        let path_base = dir.as_deref().unwrap_or(cwd);
+        if let Some(path) = config.search_path(path_base, inherited.as_deref()) {
+            cmd.env("PATH", path);
+        }
+
+        // A prompt left off the command line is typed in once it's ready
+        let injector = match &config.ready {
+            Some(ready) if !prompt.is_empty() => Some(PromptInjector::new(ready, prompt)?),
+            _ => None,
+        };
+        // Record the session from the first byte of output
+        let recorder = match &config.record {
+            Some(record) => Some(
+                Recorder::start(cwd, &config.name, id, record, cols, rows)
+                    .map_err(|e| AxiomError::pty(format!("Failed to start recording: {}", e)))?,
+            ),
+            None => None,
+        };
+        let agent = Self::spawn(id, cmd, cols, rows, event_tx.clone(), recorder)?;
+        if let Some(injector) = injector {
+            agent.inject_prompt(injector, event_tx);
+        }
+        Ok(agent)
+    }
+
+    /// Type a prompt in on a background thread once the agent is ready
+    ///
+    /// Sends [`Event::CliAgentNotReady`] if it never is.
+    fn inject_prompt(&self, injector: PromptInjector, event_tx: Sender<Event>) {
+        let id = self.id;
+        // Weak, so a removed agent's screen and input go away
+        let parser = Arc::downgrade(&self.parser);
+        let writer = Arc::downgrade(&self.writer);
+        std::thread::spawn(move || {
+            let screen = || parser.upgrade().map(|p| p.read().screen().contents());
+            let write = |data: &[u8]| {
+                writer.upgrade().is_some_and(|writer| {
+                    let mut writer = writer.lock();
+                    writer.write_all(data).and_then(|_| writer.flush()).is_ok()
+                })
+            };
+            if let Err(e) = injector.run(screen, write) {
+                let _ = event_tx.send(Event::CliAgentNotReady {
+                    id,
+                    error: e.to_string(),
+                });
+            }
+        });
+    }
+
+    /// Start `program`, an interactive shell (see [`default_shell`]), in `cwd`
+    ///
+    /// Output arrives as [`Event::CliAgentOutput`] and the end as
+    /// [`Event::CliAgentExit`], tagged with `id`, as for CLI agents.
+    pub fn shell(
+        id: AgentId,
+        program: &str,
+        cwd: &Path,
+        cols: u16,
+        rows: u16,
+        event_tx: Sender<Event>,
+    ) -> Result<Self> {
+        let mut cmd = CommandBuilder::new(program);
+        cmd.cwd(cwd);
+        cmd.env("TERM", "xterm-256color");
+        Self::spawn(id, cmd, cols, rows, event_tx, None)
+    }
+
+    /// Run `cmd` in a new PTY and start reading its output
+    fn spawn(
+        id: AgentId,
+        cmd: CommandBuilder,
+        cols: u16,
+        rows: u16,
+        event_tx: Sender<Event>,
+        recorder: Option<Recorder>,
     ) -> Result<Self> {
         let size = PtySize {
             rows,
@@ -70,31 +187,9 @@ impl PtyAgent {
             .openpty(size)
             .map_err(|e| AxiomError::pty(format!("Failed to create PTY: {}", e)))?;
 
-        // Build the command
-        let mut cmd = CommandBuilder::new(&config.command);
-
-        // Add default arguments
-        for arg in &config.default_args {
-            cmd.arg(arg);
-        }
-
-        // Add the user's prompt as the final argument
-        if !prompt.is_empty() {
-            cmd.arg(prompt);
-        }
-
-        // Set working directory if configured
-        if config.use_cwd {
-            cmd.cwd(cwd);
-        }
-
-        // Set any custom environment variables
-        for (key, value) in &config.env {
-            cmd.env(key, value);
-        }
-
-        // Spawn the CLI agent process
-        pair.slave
+        // Spawn the process
+        let child = pair
+            .slave
             .spawn_command(cmd)
             .map_err(|e| AxiomError::pty(format!("Failed to spawn command: {}", e)))?;
 
@@ -116,13 +211,15 @@ impl PtyAgent {
         // Create vt100 parser
         let parser = Arc::new(RwLock::new(vt100::Parser::new(rows, cols, 1000)));
 
-        // Clone parser for reader thread
+        // Clone parser and recorder for reader thread
         let parser_clone = parser.clone();
+        let recorder = recorder.map(|recorder| Arc::new(parking_lot::Mutex::new(recorder)));
+        let recorder_clone = recorder.clone();
 
         // Spawn reader thread
         let agent_id = id;
         std::thread::spawn(move || {
-            Self::read_loop(agent_id, reader, parser_clone, event_tx);
+            Self::read_loop(agent_id, reader, parser_clone, recorder_clone, event_tx);
         });
 
         Ok(Self {
@@ -132,9 +229,20 @@ impl PtyAgent {
             parser,
             size,
             exited: false,
+            child,
+            recorder,
         })
     }
 
+    /// Stop the CLI process: SIGTERM, then SIGKILL if it lingers
+    ///
+    /// Blocks for up to [`super::KILL_GRACE`].
+    pub fn terminate(mut self) {
+        if let Some(pid) = self.child.process_id() {
+            terminate(pid, || matches!(self.child.try_wait(), Ok(Some(_))));
+        }
+    }
+
     /// Resize the PTY
     pub fn resize(&mut self, cols: u16, rows: u16) -> Result<()> {
         // Skip if size unchanged
@@ -161,6 +269,9 @@ impl PtyAgent {
 
         // Resize the parser
         self.parser.write().set_size(rows, cols);
+        if let Some(recorder) = &self.recorder {
+            recorder.lock().resize(cols, rows);
+        }
 
         Ok(())
     }
@@ -204,6 +315,7 @@ impl PtyAgent {
                         italic: cell.italic(),
                         underline: cell.underline(),
                         inverse: cell.inverse(),
+                        continuation: cell.is_wide_continuation(),
                     });
                 } else {
                     cells.push(TerminalCell::default());
@@ -235,6 +347,10 @@ impl PtyAgent {
         for row in 0..rows {
             for col in 0..cols {
                 if let Some(cell) = screen.cell(row, col) {
+                    // The wide character before it already covers this cell
+                    if cell.is_wide_continuation() {
+                        continue;
+                    }
                     let text = cell.contents();
                     if text.is_empty() {
                         output.push(' ');
@@ -261,6 +377,7 @@ impl PtyAgent {
         agent_id: AgentId,
         mut reader: Box<dyn Read + Send>,
         parser: Arc<RwLock<vt100::Parser>>,
+        recorder: Option<Arc<parking_lot::Mutex<Recorder>>>,
         tx: Sender<Event>,
     ) {
         let mut buf = [0u8; 4096];
@@ -280,6 +397,9 @@ impl PtyAgent {
 
                     // Feed data to vt100 parser
                     parser.write().process(data);
+                    if let Some(recorder) = &recorder {
+                        recorder.lock().output(data);
+                    }
 
                     // Send output event
                     if tx
@@ -307,6 +427,14 @@ impl PtyAgent {
     }
 }
 
+/// The user's shell from `$SHELL`, or `/bin/sh` when it isn't set
+pub(super) fn default_shell() -> String {
+    std::env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| "/bin/sh".to_string())
+}
+
 /// Convert vt100 color to our UI-agnostic TerminalColor
 fn vt100_color_to_terminal(color: vt100::Color) -> TerminalColor {
     match color {
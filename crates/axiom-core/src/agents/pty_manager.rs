@@ -3,20 +3,98 @@
 //! Manages multiple PTY sessions for external CLI coding agents.
 //! Handles spawning, input routing, resize events, and cleanup.
 
+use super::output_parser::OutputParsers;
 use super::pty_agent::PtyAgent;
+use super::transcript::{Transcript, DEFAULT_SCROLLBACK_LINES};
 use crate::config::CliAgentConfig;
 use crate::error::Result;
 use crate::events::Event;
-use crate::types::{AgentId, TerminalScreen};
+use crate::types::{AgentEvent, AgentId, ScreenDiff, TerminalScreen};
 use crossbeam_channel::Sender;
 use std::collections::HashMap;
 use std::path::Path;
 
+/// Diffs between keyframes, so a client that missed one without noticing
+/// still converges
+const KEYFRAME_INTERVAL: u64 = 100;
+
+/// The screen as last sent to clients, for working out what changed
+#[derive(Default)]
+struct ScreenTracker {
+    last: Option<TerminalScreen>,
+    seq: u64,
+    since_keyframe: u64,
+}
+
+impl ScreenTracker {
+    /// Diff `screen` against the last one sent, or None if nothing changed
+    fn update(&mut self, screen: TerminalScreen) -> Option<ScreenDiff> {
+        let resized = self
+            .last
+            .as_ref()
+            .is_none_or(|last| (last.cols, last.rows) != (screen.cols, screen.rows));
+        if resized || self.since_keyframe + 1 >= KEYFRAME_INTERVAL {
+            return Some(self.keyframe(screen));
+        }
+        let last = self.last.as_ref()?;
+        let lines: Vec<_> = screen
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(row, line)| last.lines.get(*row) != Some(*line))
+            .map(|(row, line)| (row as u16, line.clone()))
+            .collect();
+        if lines.is_empty() && last.cursor == screen.cursor {
+            return None;
+        }
+        self.seq += 1;
+        self.since_keyframe += 1;
+        let diff = ScreenDiff {
+            seq: self.seq,
+            keyframe: false,
+            cols: screen.cols,
+            rows: screen.rows,
+            cursor: screen.cursor,
+            lines,
+        };
+        self.last = Some(screen);
+        Some(diff)
+    }
+
+    /// Every row of `screen`, resetting the keyframe interval
+    fn keyframe(&mut self, screen: TerminalScreen) -> ScreenDiff {
+        self.seq += 1;
+        self.since_keyframe = 0;
+        let diff = ScreenDiff {
+            seq: self.seq,
+            keyframe: true,
+            cols: screen.cols,
+            rows: screen.rows,
+            cursor: screen.cursor,
+            lines: (0..).zip(screen.lines.iter().cloned()).collect(),
+        };
+        self.last = Some(screen);
+        diff
+    }
+}
+
 /// Manages multiple PTY agent sessions
 pub struct PtyAgentManager {
     /// Active PTY agents
     agents: HashMap<AgentId, PtyAgent>,
 
+    /// Last screen sent per agent, for differential updates
+    screens: HashMap<AgentId, ScreenTracker>,
+
+    /// Output parsers of agents that have any configured
+    parsers: HashMap<AgentId, OutputParsers>,
+
+    /// Plain-text output per agent, kept until the agent is removed
+    transcripts: HashMap<AgentId, Transcript>,
+
+    /// Lines kept in each transcript
+    scrollback: usize,
+
     /// Event sender
     event_tx: Sender<Event>,
 
@@ -30,6 +108,10 @@ impl PtyAgentManager {
     pub fn new(event_tx: Sender<Event>) -> Self {
         Self {
             agents: HashMap::new(),
+            screens: HashMap::new(),
+            parsers: HashMap::new(),
+            transcripts: HashMap::new(),
+            scrollback: DEFAULT_SCROLLBACK_LINES,
             event_tx,
             default_cols: 80,
             default_rows: 24,
@@ -42,6 +124,11 @@ impl PtyAgentManager {
         self.default_rows = rows.max(5);
     }
 
+    /// Set the lines kept in the transcripts of new agents
+    pub fn set_scrollback(&mut self, lines: usize) {
+        self.scrollback = lines;
+    }
+
     /// Get the default PTY dimensions
     pub fn default_size(&self) -> (u16, u16) {
         (self.default_cols, self.default_rows)
@@ -61,6 +148,8 @@ impl PtyAgentManager {
         prompt: &str,
         cwd: &Path,
     ) -> Result<()> {
+        let dir = config.start_dir(cwd).ok().flatten();
+        let parsers = OutputParsers::new(&config.parsers, dir.as_deref().unwrap_or(cwd))?;
         let agent = PtyAgent::new(
             id,
             config,
@@ -72,9 +161,44 @@ impl PtyAgentManager {
         )?;
 
         self.agents.insert(id, agent);
+        self.transcripts
+            .insert(id, Transcript::new(self.scrollback));
+        if !parsers.is_empty() {
+            self.parsers.insert(id, parsers);
+        }
         Ok(())
     }
 
+    /// Run an agent's output through its parsers; returns the events found
+    pub fn parse_output(&mut self, id: AgentId, data: &[u8]) -> Vec<AgentEvent> {
+        self.parsers
+            .get_mut(&id)
+            .map(|parsers| parsers.feed(data))
+            .unwrap_or_default()
+    }
+
+    /// Add output to an agent's transcript
+    pub fn capture(&mut self, id: AgentId, data: &[u8]) {
+        if let Some(transcript) = self.transcripts.get_mut(&id) {
+            transcript.feed(data);
+        }
+    }
+
+    /// Everything an agent printed, including what scrolled off its screen
+    pub fn transcript(&self, id: AgentId) -> Option<&Transcript> {
+        self.transcripts.get(&id)
+    }
+
+    /// Events from the end of an exited agent's output
+    ///
+    /// Stops its parser scripts, waiting for them to finish reading.
+    pub fn finish_output(&mut self, id: AgentId) -> Vec<AgentEvent> {
+        self.parsers
+            .remove(&id)
+            .map(OutputParsers::finish)
+            .unwrap_or_default()
+    }
+
     /// Write input data to a CLI agent
     pub fn write(&mut self, id: AgentId, data: &[u8]) -> Result<()> {
         if let Some(agent) = self.agents.get(&id) {
@@ -107,6 +231,22 @@ impl PtyAgentManager {
         self.agents.get(&id).map(|agent| agent.get_screen())
     }
 
+    /// The rows of an agent's screen that changed since the last diff
+    ///
+    /// None if nothing changed or the agent is unknown. Every
+    /// [`KEYFRAME_INTERVAL`]th diff, and the first after a resize, is a
+    /// keyframe.
+    pub fn screen_diff(&mut self, id: AgentId) -> Option<ScreenDiff> {
+        let screen = self.agents.get(&id)?.get_screen();
+        self.screens.entry(id).or_default().update(screen)
+    }
+
+    /// A keyframe of an agent's screen, for a client that fell behind
+    pub fn screen_keyframe(&mut self, id: AgentId) -> Option<ScreenDiff> {
+        let screen = self.agents.get(&id)?.get_screen();
+        Some(self.screens.entry(id).or_default().keyframe(screen))
+    }
+
     /// Get the raw text output for a CLI agent
     pub fn get_output_text(&self, id: AgentId) -> Option<String> {
         self.agents.get(&id).map(|agent| agent.get_output_text())
@@ -124,14 +264,21 @@ impl PtyAgentManager {
         }
     }
 
-    /// Remove a CLI agent session
+    /// Remove a CLI agent session, stopping its process in the background
     pub fn remove(&mut self, id: AgentId) {
-        self.agents.remove(&id);
+        self.screens.remove(&id);
+        self.parsers.remove(&id);
+        self.transcripts.remove(&id);
+        if let Some(agent) = self.agents.remove(&id) {
+            std::thread::spawn(move || agent.terminate());
+        }
     }
 
-    /// Remove all exited agents
+    /// Remove all exited agents, keeping their transcripts
     pub fn cleanup_exited(&mut self) {
         self.agents.retain(|_, agent| !agent.exited);
+        self.screens.retain(|id, _| self.agents.contains_key(id));
+        self.parsers.retain(|id, _| self.agents.contains_key(id));
     }
 
     /// Get all active CLI agent IDs
@@ -162,6 +309,7 @@ impl PtyAgentManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{TerminalCell, TerminalLine};
 
     #[test]
     fn test_manager_creation() {
@@ -177,4 +325,65 @@ mod tests {
         let id = AgentId::new(1);
         assert!(!manager.contains(id));
     }
+
+    fn screen(rows: &[&str]) -> TerminalScreen {
+        let lines = rows
+            .iter()
+            .map(|row| TerminalLine {
+                cells: row
+                    .chars()
+                    .map(|char| TerminalCell {
+                        char,
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+            .collect();
+        TerminalScreen {
+            lines,
+            cursor: Some((0, 0)),
+            cols: 3,
+            rows: rows.len() as u16,
+        }
+    }
+
+    #[test]
+    fn test_screen_diffs() {
+        let mut tracker = ScreenTracker::default();
+        let first = tracker.update(screen(&["abc", "def"])).unwrap();
+        assert!(first.keyframe);
+        assert_eq!((first.seq, first.lines.len()), (1, 2));
+
+        assert!(tracker.update(screen(&["abc", "def"])).is_none());
+
+        let diff = tracker.update(screen(&["abc", "dXf"])).unwrap();
+        assert!(!diff.keyframe);
+        assert_eq!(diff.seq, 2);
+        assert_eq!(diff.lines.len(), 1);
+        assert_eq!(diff.lines[0].0, 1);
+
+        let mut client = screen(&["abc", "def"]);
+        assert!(diff.apply(&mut client));
+        assert_eq!(client, screen(&["abc", "dXf"]));
+
+        // A resize sends every row again
+        let resized = tracker.update(screen(&["abc", "dXf", "ghi"])).unwrap();
+        assert!(resized.keyframe);
+        assert_eq!(resized.seq, 3);
+        assert!(!diff.apply(&mut screen(&["abc"])));
+        assert!(resized.apply(&mut client));
+        assert_eq!(client.lines.len(), 3);
+    }
+
+    #[test]
+    fn test_periodic_keyframes() {
+        let mut tracker = ScreenTracker::default();
+        tracker.update(screen(&["a"]));
+        let keyframes = (0..KEYFRAME_INTERVAL * 2)
+            .map(|n| tracker.update(screen(&[if n % 2 == 0 { "b" } else { "a" }])))
+            .filter(|diff| diff.as_ref().unwrap().keyframe)
+            .count();
+        assert_eq!(keyframes, 2);
+        assert_eq!(tracker.seq, KEYFRAME_INTERVAL * 2 + 1);
+    }
 }
@@ -0,0 +1,195 @@
+//! Typing a prompt into a CLI agent once its REPL is ready
+//!
+//! An agent with a [`ReadinessConfig`] starts without the prompt on its
+//! command line. A [`PromptInjector`] watches its screen, answers the
+//! configured steps, waits for the ready pattern and then types the prompt
+//! in, giving up after the timeout.
+
+use crate::config::ReadinessConfig;
+use crate::error::{AxiomError, Result};
+use regex::Regex;
+use std::time::{Duration, Instant};
+
+/// How often the screen is checked while waiting
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Types a prompt into an agent when it is ready for it
+pub struct PromptInjector {
+    steps: Vec<(Regex, String)>,
+    pattern: Option<Regex>,
+    delay: Duration,
+    timeout: Duration,
+    type_delay: Duration,
+    submit: String,
+    prompt: String,
+}
+
+impl PromptInjector {
+    /// Compile `config`'s regexes for typing `prompt`
+    pub fn new(config: &ReadinessConfig, prompt: &str) -> Result<Self> {
+        let compile = |pattern: &str| {
+            Regex::new(pattern).map_err(|e| {
+                AxiomError::config(format!("Invalid readiness regex '{}': {}", pattern, e))
+            })
+        };
+        let steps = config
+            .steps
+            .iter()
+            .map(|step| Ok((compile(&step.expect)?, step.send.clone())))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            steps,
+            pattern: config.pattern.as_deref().map(compile).transpose()?,
+            delay: Duration::from_millis(config.delay_ms),
+            timeout: Duration::from_secs(config.timeout_secs),
+            type_delay: Duration::from_millis(config.type_delay_ms),
+            submit: config.submit.clone(),
+            prompt: prompt.to_string(),
+        })
+    }
+
+    /// Wait for the agent to be ready, then type the prompt and submit it
+    ///
+    /// `screen` returns the text on the agent's screen, or None once the
+    /// agent is gone; `write` sends input, returning false if it couldn't.
+    /// Blocks until the prompt is sent, so run it on its own thread.
+    ///
+    /// Fails if the agent isn't ready within the timeout, or goes away.
+    pub fn run(
+        &self,
+        mut screen: impl FnMut() -> Option<String>,
+        mut write: impl FnMut(&[u8]) -> bool,
+    ) -> Result<()> {
+        let deadline = Instant::now() + self.timeout;
+        for (expect, send) in &self.steps {
+            self.wait_for(expect, deadline, &mut screen)?;
+            self.type_text(send, &mut write)?;
+        }
+        if let Some(pattern) = &self.pattern {
+            self.wait_for(pattern, deadline, &mut screen)?;
+        }
+        std::thread::sleep(self.delay);
+        if screen().is_none() {
+            return Err(AxiomError::pty("Agent exited before it was ready"));
+        }
+
+        self.type_text(&self.prompt, &mut write)?;
+        if !write(self.submit.as_bytes()) {
+            return Err(AxiomError::pty("Failed to send the prompt"));
+        }
+        Ok(())
+    }
+
+    /// Poll the screen until `regex` matches it
+    fn wait_for(
+        &self,
+        regex: &Regex,
+        deadline: Instant,
+        screen: &mut impl FnMut() -> Option<String>,
+    ) -> Result<()> {
+        loop {
+            let Some(text) = screen() else {
+                return Err(AxiomError::pty("Agent exited before it was ready"));
+            };
+            if regex.is_match(&text) {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(AxiomError::pty(format!(
+                    "Agent not ready after {}s (waiting for '{}')",
+                    self.timeout.as_secs(),
+                    regex.as_str()
+                )));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Send `text`, a character at a time if there is a typing delay
+    fn type_text(&self, text: &str, write: &mut impl FnMut(&[u8]) -> bool) -> Result<()> {
+        let sent = if self.type_delay.is_zero() {
+            write(text.as_bytes())
+        } else {
+            let mut buf = [0; 4];
+            text.chars().all(|c| {
+                std::thread::sleep(self.type_delay);
+                write(c.encode_utf8(&mut buf).as_bytes())
+            })
+        };
+        if !sent {
+            return Err(AxiomError::pty("Failed to send the prompt"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ExpectStep;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_steps_then_prompt() {
+        let config = ReadinessConfig {
+            pattern: Some(r"(?m)^> $".to_string()),
+            steps: vec![ExpectStep {
+                expect: "Trust this folder?".to_string(),
+                send: "y".to_string(),
+            }],
+            type_delay_ms: 1,
+            ..Default::default()
+        };
+        let injector = PromptInjector::new(&config, "fix it").unwrap();
+
+        // The screen asks to be trusted, then shows the REPL once answered
+        let sent = RefCell::new(String::new());
+        let polls = RefCell::new(0);
+        let screen = || {
+            *polls.borrow_mut() += 1;
+            Some(match sent.borrow().as_str() {
+                "" if *polls.borrow() < 3 => "Loading...".to_string(),
+                "" => "Trust this folder? (y/n)".to_string(),
+                _ => "Welcome\n> ".to_string(),
+            })
+        };
+        let write = |data: &[u8]| {
+            sent.borrow_mut()
+                .push_str(std::str::from_utf8(data).unwrap());
+            true
+        };
+        injector.run(screen, write).unwrap();
+        assert_eq!(sent.into_inner(), "yfix it\r");
+    }
+
+    #[test]
+    fn test_not_ready() {
+        let config = ReadinessConfig {
+            pattern: Some("ready>".to_string()),
+            timeout_secs: 0,
+            ..Default::default()
+        };
+        let injector = PromptInjector::new(&config, "fix it").unwrap();
+        let mut sent = Vec::new();
+        let err = injector
+            .run(
+                || Some("Loading...".to_string()),
+                |data| {
+                    sent.extend_from_slice(data);
+                    true
+                },
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("waiting for 'ready>'"));
+        assert!(sent.is_empty());
+
+        // An agent that exits stops the wait
+        assert!(injector.run(|| None, |_| true).is_err());
+
+        let config = ReadinessConfig {
+            pattern: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(PromptInjector::new(&config, "").is_err());
+    }
+}
@@ -0,0 +1,515 @@
+//! Long-running service agents (dev servers, watchers)
+//!
+//! Unlike shell agents, a service keeps its process alive, is health-checked
+//! against a port or URL, and is restarted when it crashes.
+
+use super::{terminate, AgentRegistry, CancelToken};
+use crate::events::Event;
+use crate::types::{AgentId, ServiceHealth, ServiceInfo};
+use crate::workspace::ExecPolicy;
+use crossbeam_channel::Sender;
+use parking_lot::RwLock;
+use std::io::{BufRead, BufReader};
+use std::net::{SocketAddr, TcpStream};
+use std::path::Path;
+use std::process::{Child, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often the process and health are polled
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often health checks run
+const HEALTH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How a service's health is checked
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthCheck {
+    /// TCP connect to localhost:port
+    Port(u16),
+    /// HTTP GET returning a non-error status
+    Url(String),
+}
+
+impl HealthCheck {
+    /// Run the check once
+    pub fn probe(&self) -> bool {
+        match self {
+            HealthCheck::Port(port) => {
+                let addr = SocketAddr::from(([127, 0, 0, 1], *port));
+                TcpStream::connect_timeout(&addr, Duration::from_secs(1)).is_ok()
+            }
+            HealthCheck::Url(url) => ureq::get(url)
+                .timeout(Duration::from_secs(2))
+                .call()
+                .is_ok(),
+        }
+    }
+
+    /// Short description for display
+    pub fn describe(&self) -> String {
+        match self {
+            HealthCheck::Port(port) => format!("port {}", port),
+            HealthCheck::Url(url) => url.clone(),
+        }
+    }
+}
+
+/// What to do when the service process exits
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestartPolicy {
+    /// Restart on exit
+    pub enabled: bool,
+    /// Give up after this many restarts
+    pub max_restarts: u32,
+    /// Delay before restarting
+    pub backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_restarts: 5,
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Parsed service agent parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceSpec {
+    pub command: String,
+    pub health: Option<HealthCheck>,
+    pub restart: RestartPolicy,
+}
+
+impl ServiceSpec {
+    /// Parse `[--port N | --health URL] [--no-restart] [--max-restarts N] <command>`
+    pub fn parse(params: &str) -> Result<Self, String> {
+        let mut words = params.split_whitespace().peekable();
+        let mut health = None;
+        let mut restart = RestartPolicy::default();
+
+        while let Some(&word) = words.peek() {
+            if !word.starts_with("--") {
+                break;
+            }
+            words.next();
+            match word {
+                "--port" => {
+                    let port = words
+                        .next()
+                        .and_then(|p| p.parse().ok())
+                        .ok_or("--port needs a port number")?;
+                    health = Some(HealthCheck::Port(port));
+                }
+                "--health" => {
+                    let url = words.next().ok_or("--health needs a URL")?;
+                    health = Some(HealthCheck::Url(url.to_string()));
+                }
+                "--no-restart" => restart.enabled = false,
+                "--max-restarts" => {
+                    restart.max_restarts = words
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .ok_or("--max-restarts needs a number")?;
+                }
+                "--" => break,
+                other => return Err(format!("Unknown service option: {}", other)),
+            }
+        }
+
+        let command = words.collect::<Vec<_>>().join(" ");
+        if command.is_empty() {
+            return Err("No command provided".to_string());
+        }
+
+        Ok(Self {
+            command,
+            health,
+            restart,
+        })
+    }
+}
+
+/// Live state of a service agent, stored on the `Agent`
+#[derive(Debug, Clone)]
+pub struct ServiceState {
+    pub health: ServiceHealth,
+    pub restarts: u32,
+    /// When the current process was (re)started
+    pub started_at: Instant,
+    pub check: Option<String>,
+}
+
+impl ServiceState {
+    pub fn new(check: Option<String>) -> Self {
+        Self {
+            health: ServiceHealth::Unknown,
+            restarts: 0,
+            started_at: Instant::now(),
+            check,
+        }
+    }
+
+    /// Snapshot for UI display
+    pub fn info(&self) -> ServiceInfo {
+        ServiceInfo {
+            health: self.health,
+            restarts: self.restarts,
+            uptime_secs: self.started_at.elapsed().as_secs_f64(),
+            check: self.check.clone(),
+        }
+    }
+}
+
+/// Where a supervised service keeps its state and sends its output
+///
+/// The core executor and the TUI each keep agents in their own registry;
+/// both run services through [`supervise`] with a host over theirs.
+pub trait ServiceHost: Clone + Send + 'static {
+    /// Whether the service was stopped (cancelled, or its agent removed)
+    fn stopped(&self) -> bool;
+
+    /// Apply `change` to the service's state, if its agent is still there;
+    /// returns whether there was a state to change
+    fn update(&self, change: impl FnOnce(&mut ServiceState)) -> bool;
+
+    /// Send a chunk of the service's output; false once nobody listens
+    fn output(&self, chunk: String) -> bool;
+}
+
+/// Start the service command with its output streamed to `host`
+///
+/// The workspace's policy may refuse the command, but its timeout doesn't
+/// apply: a service is meant to keep running.
+fn spawn_process(command: &str, cwd: &Path, host: &impl ServiceHost) -> Result<Child, String> {
+    let mut process = ExecPolicy::load(cwd)
+        .command(cwd, command)
+        .map_err(|violation| format!("Command blocked: {}", violation))?;
+    process
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Its own process group, so stopping it stops everything it started
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut process, 0);
+    let mut child = process
+        .spawn()
+        .map_err(|e| format!("Failed to start service: {}", e))?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .map(|s| Box::new(s) as Box<dyn std::io::Read + Send>);
+    let stderr = child
+        .stderr
+        .take()
+        .map(|s| Box::new(s) as Box<dyn std::io::Read + Send>);
+    for stream in [stdout, stderr].into_iter().flatten() {
+        let host = host.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(|l| l.ok()) {
+                if !host.output(format!("{}\n", line)) {
+                    break;
+                }
+            }
+        });
+    }
+
+    Ok(child)
+}
+
+/// Wait `duration`, or until the service is stopped; returns whether it was
+fn stopped_within(duration: Duration, host: &impl ServiceHost) -> bool {
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline {
+        if host.stopped() {
+            return true;
+        }
+        std::thread::sleep(POLL_INTERVAL.min(deadline - Instant::now()));
+    }
+    host.stopped()
+}
+
+/// Keep the service of `spec` running in `cwd` until it is stopped or
+/// gives up restarting
+///
+/// `host` must already hold a [`ServiceState`] for it.
+pub fn supervise(spec: &ServiceSpec, cwd: &Path, host: &impl ServiceHost) -> Result<(), String> {
+    let mut restarts = 0u32;
+    loop {
+        host.output(format!("$ {}\n", spec.command));
+        let mut child = spawn_process(&spec.command, cwd, host)?;
+        host.update(|state| {
+            state.started_at = Instant::now();
+            state.health = ServiceHealth::Unknown;
+            state.restarts = restarts;
+        });
+
+        let mut last_check: Option<Instant> = None;
+        let exit_code = loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            // Stopped services take their process with them
+            if host.stopped() {
+                terminate(child.id(), || matches!(child.try_wait(), Ok(Some(_))));
+                let _ = child.wait();
+                return Ok(());
+            }
+
+            if let Ok(Some(status)) = child.try_wait() {
+                break status.code().unwrap_or(-1);
+            }
+
+            if let Some(check) = &spec.health {
+                if last_check.is_none_or(|t| t.elapsed() >= HEALTH_INTERVAL) {
+                    last_check = Some(Instant::now());
+                    update_health(check, host);
+                }
+            }
+        };
+
+        host.update(|state| state.health = ServiceHealth::Unhealthy);
+
+        if !spec.restart.enabled || restarts >= spec.restart.max_restarts {
+            return Err(format!("Service exited with code {}", exit_code));
+        }
+
+        restarts += 1;
+        host.output(format!(
+            "\n[service exited with code {}, restarting ({}/{})]\n",
+            exit_code, restarts, spec.restart.max_restarts
+        ));
+        // A service stopped while waiting to restart isn't started again
+        if stopped_within(spec.restart.backoff, host) {
+            return Ok(());
+        }
+    }
+}
+
+/// Probe health and report transitions in the service's output
+fn update_health(check: &HealthCheck, host: &impl ServiceHost) {
+    let health = if check.probe() {
+        ServiceHealth::Healthy
+    } else {
+        ServiceHealth::Unhealthy
+    };
+
+    let mut changed = false;
+    host.update(|state| {
+        changed = state.health != health;
+        state.health = health;
+    });
+
+    if changed {
+        host.output(format!("[health: {} on {}]\n", health, check.describe()));
+    }
+}
+
+/// A service agent of the core registry
+#[derive(Clone)]
+struct AgentHost {
+    agent_id: AgentId,
+    registry: Arc<RwLock<AgentRegistry>>,
+    event_tx: Sender<Event>,
+    cancel_token: CancelToken,
+}
+
+impl ServiceHost for AgentHost {
+    fn stopped(&self) -> bool {
+        self.cancel_token.is_cancelled() || self.registry.read().get(self.agent_id).is_none()
+    }
+
+    fn update(&self, change: impl FnOnce(&mut ServiceState)) -> bool {
+        let mut registry = self.registry.write();
+        let state = registry
+            .get_mut(self.agent_id)
+            .and_then(|a| a.service.as_mut());
+        state.map(change).is_some()
+    }
+
+    fn output(&self, chunk: String) -> bool {
+        self.event_tx
+            .send(Event::AgentOutput {
+                id: self.agent_id,
+                chunk,
+            })
+            .is_ok()
+    }
+}
+
+/// Run a service agent until it is cancelled or gives up restarting
+pub(crate) fn run_service(
+    agent_id: AgentId,
+    params: &str,
+    cwd: &Path,
+    registry: &Arc<RwLock<AgentRegistry>>,
+    event_tx: &Sender<Event>,
+    cancel_token: CancelToken,
+) -> Result<(), String> {
+    let spec = ServiceSpec::parse(params)?;
+    let check = spec.health.as_ref().map(HealthCheck::describe);
+
+    if let Some(agent) = registry.write().get_mut(agent_id) {
+        agent.service = Some(ServiceState::new(check));
+    }
+
+    let host = AgentHost {
+        agent_id,
+        registry: registry.clone(),
+        event_tx: event_tx.clone(),
+        cancel_token,
+    };
+    supervise(&spec, cwd, &host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AgentSpawnRequest, AgentType};
+
+    #[test]
+    fn test_parse_spec() {
+        let spec = ServiceSpec::parse("--port 3000 npm run dev").unwrap();
+        assert_eq!(spec.command, "npm run dev");
+        assert_eq!(spec.health, Some(HealthCheck::Port(3000)));
+        assert!(spec.restart.enabled);
+
+        let spec = ServiceSpec::parse(
+            "--health http://localhost:8000/ --no-restart python -m http.server",
+        )
+        .unwrap();
+        assert_eq!(
+            spec.health,
+            Some(HealthCheck::Url("http://localhost:8000/".into()))
+        );
+        assert!(!spec.restart.enabled);
+
+        assert!(ServiceSpec::parse("--port").is_err());
+        assert!(ServiceSpec::parse("--bogus x").is_err());
+        assert!(ServiceSpec::parse("--port 80").is_err());
+    }
+
+    #[test]
+    fn test_port_health_check() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(HealthCheck::Port(port).probe());
+        drop(listener);
+        assert!(!HealthCheck::Port(port).probe());
+    }
+
+    #[test]
+    fn test_service_restarts_then_gives_up() {
+        let registry = Arc::new(RwLock::new(AgentRegistry::new()));
+        let id = registry.write().spawn(AgentSpawnRequest {
+            agent_type: AgentType::Service,
+            name: "Service".into(),
+            description: "flaky".into(),
+            parameters: None,
+            parent_id: None,
+        });
+        registry.write().start(id);
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let cwd = std::env::temp_dir();
+
+        let token = registry.read().get(id).unwrap().cancel_token.clone();
+        let result = run_service(id, "--max-restarts 1 exit 3", &cwd, &registry, &tx, token);
+        assert_eq!(result, Err("Service exited with code 3".to_string()));
+
+        let output: String = rx
+            .try_iter()
+            .filter_map(|e| match e {
+                Event::AgentOutput { chunk, .. } => Some(chunk),
+                _ => None,
+            })
+            .collect();
+        assert!(output.contains("restarting (1/1)"));
+        assert_eq!(
+            registry
+                .read()
+                .get(id)
+                .unwrap()
+                .service
+                .as_ref()
+                .unwrap()
+                .restarts,
+            1
+        );
+    }
+
+    #[test]
+    fn test_cancel_stops_service() {
+        let registry = Arc::new(RwLock::new(AgentRegistry::new()));
+        let id = registry.write().spawn(AgentSpawnRequest {
+            agent_type: AgentType::Service,
+            name: "Service".into(),
+            description: "sleep".into(),
+            parameters: None,
+            parent_id: None,
+        });
+        registry.write().start(id);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+
+        let reg = registry.clone();
+        let token = registry.read().get(id).unwrap().cancel_token.clone();
+        let handle = std::thread::spawn(move || {
+            run_service(id, "sleep 30", &std::env::temp_dir(), &reg, &tx, token)
+        });
+        std::thread::sleep(Duration::from_millis(300));
+        registry.write().get_mut(id).unwrap().cancel();
+
+        assert_eq!(handle.join().unwrap(), Ok(()));
+    }
+
+    /// A host that counts starts and is stopped by the test
+    #[derive(Clone, Default)]
+    struct TestHost {
+        starts: Arc<std::sync::atomic::AtomicU32>,
+        token: CancelToken,
+    }
+
+    impl ServiceHost for TestHost {
+        fn stopped(&self) -> bool {
+            self.token.is_cancelled()
+        }
+
+        fn update(&self, change: impl FnOnce(&mut ServiceState)) -> bool {
+            let mut state = ServiceState::new(None);
+            change(&mut state);
+            true
+        }
+
+        fn output(&self, chunk: String) -> bool {
+            if chunk.starts_with("$ ") {
+                self.starts
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+            true
+        }
+    }
+
+    #[test]
+    fn test_stop_during_backoff_does_not_restart() {
+        let host = TestHost::default();
+        let spec = ServiceSpec {
+            restart: RestartPolicy {
+                backoff: Duration::from_secs(5),
+                ..RestartPolicy::default()
+            },
+            ..ServiceSpec::parse("exit 1").unwrap()
+        };
+
+        let running = host.clone();
+        let handle = std::thread::spawn(move || supervise(&spec, &std::env::temp_dir(), &running));
+        std::thread::sleep(Duration::from_millis(800));
+        let stopped_at = Instant::now();
+        host.token.cancel();
+
+        assert_eq!(handle.join().unwrap(), Ok(()));
+        assert!(stopped_at.elapsed() < Duration::from_secs(1));
+        assert_eq!(host.starts.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,117 @@
+//! Interactive shell sessions for remote terminals
+//!
+//! Wraps a [`PtyAgent`] running the user's shell for clients, such as the
+//! web UI's xterm.js, that render the raw output themselves. Input and
+//! resizes go through [`TerminalSession`]; output is read, blocking, from
+//! the [`TerminalOutput`] it starts with.
+
+use super::pty_agent::{default_shell, PtyAgent};
+use crate::error::Result;
+use crate::events::Event;
+use crate::types::AgentId;
+use crossbeam_channel::Receiver;
+use std::path::Path;
+
+/// Something the shell's PTY produced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TerminalEvent {
+    /// Raw output bytes, escape sequences included
+    Output(Vec<u8>),
+    /// The shell exited (or its PTY failed) with this code
+    Exit(i32),
+}
+
+/// Blocking iterator over a session's output, ending after [`TerminalEvent::Exit`]
+pub struct TerminalOutput {
+    events: Receiver<Event>,
+    exited: bool,
+}
+
+impl Iterator for TerminalOutput {
+    type Item = TerminalEvent;
+
+    fn next(&mut self) -> Option<TerminalEvent> {
+        while !self.exited {
+            match self.events.recv().ok()? {
+                Event::CliAgentOutput { data, .. } => return Some(TerminalEvent::Output(data)),
+                Event::CliAgentExit { exit_code, .. } => {
+                    self.exited = true;
+                    return Some(TerminalEvent::Exit(exit_code));
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// A shell running in a PTY
+pub struct TerminalSession {
+    pty: PtyAgent,
+}
+
+impl TerminalSession {
+    /// Start the user's shell in `cwd` with a `cols` x `rows` screen
+    pub fn start(cwd: &Path, cols: u16, rows: u16) -> Result<(Self, TerminalOutput)> {
+        Self::start_with(&default_shell(), cwd, cols, rows)
+    }
+
+    /// Start `shell` in `cwd` with a `cols` x `rows` screen
+    fn start_with(shell: &str, cwd: &Path, cols: u16, rows: u16) -> Result<(Self, TerminalOutput)> {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let pty = PtyAgent::shell(AgentId::new(0), shell, cwd, cols, rows, tx)?;
+        let output = TerminalOutput {
+            events: rx,
+            exited: false,
+        };
+        Ok((Self { pty }, output))
+    }
+
+    /// Send keystrokes or pasted text to the shell
+    pub fn write(&self, data: &[u8]) -> Result<()> {
+        self.pty.write(data)
+    }
+
+    /// Resize the PTY (and the screen parsed from its output)
+    pub fn resize(&mut self, cols: u16, rows: u16) -> Result<()> {
+        self.pty.resize(cols, rows)
+    }
+
+    /// Visible screen text, as parsed from the output so far
+    pub fn screen_text(&self) -> String {
+        self.pty.get_output_text()
+    }
+
+    /// Stop the shell: SIGTERM, then SIGKILL if it lingers
+    ///
+    /// Blocks for up to [`super::KILL_GRACE`].
+    pub fn terminate(self) {
+        self.pty.terminate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut session, output) =
+            TerminalSession::start_with("/bin/sh", dir.path(), 80, 24).unwrap();
+        session.resize(100, 30).unwrap();
+        session.write(b"echo marker-$((6 * 7)); exit 3\n").unwrap();
+
+        let mut data = Vec::new();
+        let mut exit = None;
+        for event in output {
+            match event {
+                TerminalEvent::Output(bytes) => data.extend(bytes),
+                TerminalEvent::Exit(code) => exit = Some(code),
+            }
+        }
+        assert!(exit.is_some());
+        assert!(String::from_utf8_lossy(&data).contains("marker-42"));
+        assert!(session.screen_text().contains("marker-42"));
+    }
+}
@@ -0,0 +1,176 @@
+//! Plain-text transcripts of CLI agent output
+//!
+//! The vt100 screen only holds what is visible, so output that scrolls off
+//! is gone. A transcript keeps every line the agent printed, escape
+//! sequences stripped, up to a limit of lines; the oldest go first.
+
+use super::output_parser::output_line;
+use std::collections::VecDeque;
+
+/// Lines kept per agent unless `[cli_agents] scrollback_lines` says otherwise
+pub const DEFAULT_SCROLLBACK_LINES: usize = 5000;
+
+/// Characters of a transcript handed to the conductor
+pub const CONTEXT_CHARS: usize = 4000;
+
+/// Bytes of an unfinished line kept before it's cut off
+const MAX_PENDING: usize = 64 * 1024;
+
+/// The plain-text output of a CLI agent
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    lines: VecDeque<String>,
+    /// Output after the last newline
+    pending: Vec<u8>,
+    limit: usize,
+    /// Lines dropped to stay within the limit
+    dropped: usize,
+}
+
+impl Default for Transcript {
+    fn default() -> Self {
+        Self::new(DEFAULT_SCROLLBACK_LINES)
+    }
+}
+
+impl Transcript {
+    /// An empty transcript keeping at most `limit` lines
+    pub fn new(limit: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            pending: Vec::new(),
+            limit: limit.max(1),
+            dropped: 0,
+        }
+    }
+
+    /// Add raw PTY output
+    pub fn feed(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let raw: Vec<u8> = self.pending.drain(..=end).collect();
+            self.push(output_line(&String::from_utf8_lossy(&raw)));
+        }
+        if self.pending.len() > MAX_PENDING {
+            let raw = std::mem::take(&mut self.pending);
+            self.push(output_line(&String::from_utf8_lossy(&raw)));
+        }
+    }
+
+    fn push(&mut self, line: String) {
+        let line = line.trim_end().to_string();
+        // Redrawn screens leave runs of blank lines; one is enough
+        if line.is_empty() && self.lines.back().is_none_or(|last| last.is_empty()) {
+            return;
+        }
+        self.lines.push_back(line);
+        while self.lines.len() > self.limit {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+    }
+
+    /// Lines kept, including the unfinished last one
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.lines.iter().cloned().collect();
+        let last = output_line(&String::from_utf8_lossy(&self.pending));
+        if !last.trim().is_empty() {
+            lines.push(last.trim_end().to_string());
+        }
+        lines
+    }
+
+    /// Lines dropped to stay within the limit
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// The whole transcript as text
+    pub fn text(&self) -> String {
+        self.lines().join("\n").trim().to_string()
+    }
+
+    /// The last whole lines of the transcript, at most `chars` characters
+    pub fn tail(&self, chars: usize) -> String {
+        let mut tail = Vec::new();
+        let mut len = 0;
+        for line in self.lines().into_iter().rev() {
+            len += line.chars().count() + 1;
+            if len > chars && !tail.is_empty() {
+                break;
+            }
+            tail.push(line);
+        }
+        tail.reverse();
+        let text = tail.join("\n");
+        let skip = text.chars().count().saturating_sub(chars);
+        text.chars()
+            .skip(skip)
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    /// Whether nothing was printed but whitespace
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty() && String::from_utf8_lossy(&self.pending).trim().is_empty()
+    }
+}
+
+/// What the conductor is told about a CLI agent that finished, or None when
+/// it printed nothing
+pub fn transcript_context(agent: &str, exit_code: i32, transcript: &Transcript) -> Option<String> {
+    if transcript.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} exited with code {}. The end of its output:\n```\n{}\n```",
+        agent,
+        exit_code,
+        transcript.tail(CONTEXT_CHARS)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_strips_escapes() {
+        let mut transcript = Transcript::new(100);
+        transcript.feed(b"\x1b[1;32mCompiling\x1b[0m axiom\r\n");
+        transcript.feed(b"Progress 10%\rProgress 100%\n\n\n\nDo");
+        transcript.feed(b"ne \xe2\x9c");
+        transcript.feed(b"\x93");
+        assert_eq!(
+            transcript.lines(),
+            ["Compiling axiom", "Progress 100%", "", "Done ✓"]
+        );
+        assert_eq!(
+            transcript.text(),
+            "Compiling axiom\nProgress 100%\n\nDone ✓"
+        );
+    }
+
+    #[test]
+    fn test_transcript_limit() {
+        let mut transcript = Transcript::new(3);
+        for n in 1..=5 {
+            transcript.feed(format!("line {}\n", n).as_bytes());
+        }
+        assert_eq!(transcript.lines(), ["line 3", "line 4", "line 5"]);
+        assert_eq!(transcript.dropped(), 2);
+        assert_eq!(transcript.tail(14), "line 4\nline 5");
+        assert_eq!(transcript.tail(3), "e 5");
+    }
+
+    #[test]
+    fn test_transcript_context() {
+        let mut transcript = Transcript::default();
+        assert_eq!(transcript_context("Claude Code", 0, &transcript), None);
+        transcript.feed(b"Wrote src/lib.rs\n");
+        let context = transcript_context("Claude Code", 0, &transcript).unwrap();
+        assert!(context.starts_with("Claude Code exited with code 0."));
+        assert!(context.contains("```\nWrote src/lib.rs\n```"));
+    }
+}
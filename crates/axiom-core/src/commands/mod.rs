@@ -5,6 +5,8 @@
 
 pub mod slash;
 
+use crate::notifications::HistoryFilter;
+use crate::run_log::RunLogFormat;
 use crate::types::AgentId;
 use crate::workspace::WorkspaceId;
 use serde::{Deserialize, Serialize};
@@ -67,6 +69,15 @@ pub enum Command {
         rows: u16,
     },
 
+    /// Ask for a keyframe of a PTY screen
+    ///
+    /// For a client that missed a `PtyScreenDiff`; the next diff carries
+    /// every row.
+    ResyncPtyScreen {
+        /// The runtime agent ID
+        agent_id: AgentId,
+    },
+
     /// Read a file
     ///
     /// Loads a file and sends its content via notification.
@@ -105,6 +116,50 @@ pub enum Command {
         agent_id: AgentId,
     },
 
+    /// Cancel every pending or running agent
+    CancelAll,
+
+    /// Start a finished agent's task again, as a new agent
+    ///
+    /// The conductor is asked its latest prompt again.
+    RerunAgent {
+        /// The agent to re-run
+        agent_id: AgentId,
+    },
+
+    /// Get an agent's last message, e.g. to copy it
+    ///
+    /// Answered with an `AgentLastMessage` notification.
+    GetLastMessage {
+        /// The agent whose message to get
+        agent_id: AgentId,
+    },
+
+    /// Get the files an agent wrote, e.g. to open them
+    ///
+    /// Answered with an `AgentTouchedFiles` notification.
+    GetTouchedFiles {
+        /// The agent whose files to get
+        agent_id: AgentId,
+    },
+
+    /// Apply agent operations held for approval
+    ///
+    /// Each is applied in turn; a `PendingOperations` with what is left
+    /// follows.
+    ApproveOperations {
+        /// IDs from `PendingOperations` (empty = all of them)
+        #[serde(default)]
+        ids: Vec<String>,
+    },
+
+    /// Discard agent operations held for approval
+    RejectOperations {
+        /// IDs from `PendingOperations` (empty = all of them)
+        #[serde(default)]
+        ids: Vec<String>,
+    },
+
     /// List available LLM providers and their models
     ListProviders,
 
@@ -116,6 +171,15 @@ pub enum Command {
     /// Returns a snapshot of all agents, providers, etc.
     GetSnapshot,
 
+    /// Get recent notifications, e.g. to catch up after a reconnect
+    ///
+    /// Answered with a `History` notification.
+    QueryHistory {
+        /// Which notifications to return (default: all kept)
+        #[serde(default)]
+        filter: HistoryFilter,
+    },
+
     /// Select an output context (what to display)
     SelectContext {
         /// The context to display
@@ -190,6 +254,124 @@ pub enum Command {
         #[serde(default)]
         include_hidden: bool,
     },
+
+    /// Search the workspace's files
+    ///
+    /// Hits stream back as `SearchResult` notifications, then a
+    /// `SearchFinished`. Starting a search cancels the previous one.
+    SearchWorkspace {
+        /// Text to find, or a regex when `regex` is set
+        query: String,
+
+        /// Treat `query` as a regular expression
+        #[serde(default)]
+        regex: bool,
+
+        /// Globs limiting the files searched (e.g. `*.rs`, `!tests/**`)
+        #[serde(default)]
+        globs: Vec<String>,
+    },
+
+    /// Search the workspace by meaning rather than by text
+    ///
+    /// Brings the semantic index up to date, then sends the closest chunks
+    /// as `SemanticSearchResults`.
+    SemanticSearch {
+        /// What to look for, in plain words
+        query: String,
+    },
+
+    /// Ask the file's language server about the symbol at a position
+    ///
+    /// The answer comes back as `Hover`.
+    Hover {
+        /// Path to the file
+        path: PathBuf,
+
+        /// Line, from 1
+        line: u32,
+
+        /// Character column, from 1
+        column: u32,
+    },
+
+    /// Find where the symbol at a position is defined
+    ///
+    /// The answer comes back as `Definition`.
+    GoToDefinition {
+        /// Path to the file
+        path: PathBuf,
+
+        /// Line, from 1
+        line: u32,
+
+        /// Character column, from 1
+        column: u32,
+    },
+
+    /// Preview replacing text across the workspace
+    ///
+    /// Nothing is written: a `ReplacePreview` comes back with a diff per
+    /// file, to be confirmed with `ApplyReplace`.
+    ReplaceInWorkspace {
+        /// Text to find, or a regex when `regex` is set
+        query: String,
+
+        /// Replacement; in regex mode `$1`/`${name}` insert capture groups
+        replacement: String,
+
+        /// Treat `query` as a regular expression
+        #[serde(default)]
+        regex: bool,
+
+        /// Globs limiting the files changed (e.g. `*.rs`, `!tests/**`)
+        #[serde(default)]
+        globs: Vec<String>,
+    },
+
+    /// Write the changes of the latest `ReplacePreview`
+    ApplyReplace {
+        /// ID of the preview being confirmed
+        replace_id: u64,
+
+        /// Files to change, relative to the workspace root (empty = all)
+        #[serde(default)]
+        paths: Vec<PathBuf>,
+    },
+
+    /// Pause or resume the workspace file watcher
+    SetWatcherEnabled {
+        /// Whether changed files are reported
+        enabled: bool,
+    },
+
+    // ========== Session Commands ==========
+
+    /// Save the conversation and agent output to `.axiom/sessions/`
+    SaveSession {
+        /// Session name (defaults to `latest`)
+        #[serde(default)]
+        name: Option<String>,
+    },
+
+    /// Replace the conversation and agents with a saved session
+    RestoreSession {
+        /// Session name (defaults to the most recently saved)
+        #[serde(default)]
+        name: Option<String>,
+    },
+
+    // ========== Run Log Commands ==========
+
+    /// Export the latest logged run of an agent from `.axiom/logs/`
+    ExportRunLog {
+        /// The agent whose run to export
+        agent_id: AgentId,
+
+        /// JSON or a Markdown transcript (defaults to JSON)
+        #[serde(default)]
+        format: RunLogFormat,
+    },
 }
 
 impl Command {
@@ -227,6 +409,11 @@ impl Command {
         }
     }
 
+    /// Create a ResyncPtyScreen command
+    pub fn resync_pty_screen(agent_id: AgentId) -> Self {
+        Command::ResyncPtyScreen { agent_id }
+    }
+
     /// Create a ReadFile command
     pub fn read_file(path: impl Into<PathBuf>) -> Self {
         Command::ReadFile { path: path.into() }
@@ -253,6 +440,21 @@ impl Command {
         Command::CancelAgent { agent_id }
     }
 
+    /// Create a RerunAgent command
+    pub fn rerun_agent(agent_id: AgentId) -> Self {
+        Command::RerunAgent { agent_id }
+    }
+
+    /// Create an ApproveOperations command
+    pub fn approve_operations(ids: Vec<String>) -> Self {
+        Command::ApproveOperations { ids }
+    }
+
+    /// Create a RejectOperations command
+    pub fn reject_operations(ids: Vec<String>) -> Self {
+        Command::RejectOperations { ids }
+    }
+
     /// Create a CreateWorkspace command
     pub fn create_workspace(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
         Command::CreateWorkspace {
@@ -279,6 +481,20 @@ impl Command {
         }
     }
 
+    /// Create a SearchWorkspace command for plain text
+    pub fn search_workspace(query: impl Into<String>) -> Self {
+        Command::SearchWorkspace {
+            query: query.into(),
+            regex: false,
+            globs: Vec::new(),
+        }
+    }
+
+    /// Create a QueryHistory command
+    pub fn query_history(filter: HistoryFilter) -> Self {
+        Command::QueryHistory { filter }
+    }
+
     /// Create a SlashCommand command
     pub fn slash_command(command: slash::SlashCommand) -> Self {
         Command::SlashCommand { command }
@@ -312,6 +528,86 @@ mod tests {
         assert!(json.contains("explain this code"));
     }
 
+    #[test]
+    fn test_cancel_commands() {
+        let json = serde_json::to_string(&Command::cancel_agent(AgentId(4))).unwrap();
+        assert_eq!(json, r#"{"type":"CancelAgent","agent_id":4}"#);
+        let parsed: Command = serde_json::from_str(r#"{"type":"CancelAll"}"#).unwrap();
+        assert!(matches!(parsed, Command::CancelAll));
+    }
+
+    #[test]
+    fn test_session_commands() {
+        let parsed: Command = serde_json::from_str(r#"{"type":"RestoreSession"}"#).unwrap();
+        assert!(matches!(parsed, Command::RestoreSession { name: None }));
+
+        let cmd = Command::SaveSession {
+            name: Some("work".to_string()),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"type":"SaveSession","name":"work"}"#);
+    }
+
+    #[test]
+    fn test_export_run_log_command() {
+        let parsed: Command =
+            serde_json::from_str(r#"{"type":"ExportRunLog","agent_id":3}"#).unwrap();
+        assert!(matches!(
+            parsed,
+            Command::ExportRunLog {
+                agent_id: AgentId(3),
+                format: RunLogFormat::Json
+            }
+        ));
+
+        let cmd = Command::ExportRunLog {
+            agent_id: AgentId(3),
+            format: RunLogFormat::Markdown,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"ExportRunLog","agent_id":3,"format":"markdown"}"#
+        );
+    }
+
+    #[test]
+    fn test_search_command() {
+        let parsed: Command =
+            serde_json::from_str(r#"{"type":"SearchWorkspace","query":"TODO"}"#).unwrap();
+        match parsed {
+            Command::SearchWorkspace {
+                query,
+                regex,
+                globs,
+            } => {
+                assert_eq!(query, "TODO");
+                assert!(!regex);
+                assert!(globs.is_empty());
+            }
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_replace_commands() {
+        let parsed: Command = serde_json::from_str(
+            r#"{"type":"ReplaceInWorkspace","query":"foo","replacement":"bar"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            parsed,
+            Command::ReplaceInWorkspace { regex: false, ref globs, .. } if globs.is_empty()
+        ));
+
+        let parsed: Command =
+            serde_json::from_str(r#"{"type":"ApplyReplace","replace_id":3}"#).unwrap();
+        assert!(matches!(
+            parsed,
+            Command::ApplyReplace { replace_id: 3, ref paths } if paths.is_empty()
+        ));
+    }
+
     #[test]
     fn test_pty_input_command() {
         let cmd = Command::send_pty_input(AgentId::new(42), vec![0x1b, 0x5b, 0x41]); // ESC [ A
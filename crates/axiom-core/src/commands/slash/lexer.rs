@@ -0,0 +1,223 @@
+//! Splitting a slash command into words
+//!
+//! After the leading `/`, a command line is:
+//!
+//! ```text
+//! line = space* (word (space+ word)*)? space*
+//! word = part+
+//! part = bare | "'" literal* "'" | '"' (escape | literal)* '"'
+//! ```
+//!
+//! - Whitespace separates words; quotes keep it in, as in
+//!   `/replace "old name" "new name"`.
+//! - A quote only opens a part at the start of a word, after a closing
+//!   quote or after a leading `@`, so `don't` stays as typed.
+//! - Double quotes take `\"` and `\\` as escapes; single quotes take
+//!   everything as it is.
+//! - Outside quotes a backslash escapes whitespace, a quote or a backslash,
+//!   and is kept before anything else, so Windows paths need no doubling.
+//! - A word starting with an unquoted `@` is a mention, e.g. `@src/main.rs`
+//!   or `@"docs/My Notes.md"`.
+
+use super::parser::{ParseError, SlashError};
+use std::iter::Peekable;
+use std::ops::Range;
+
+/// A word of a command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// The word with its quotes and escapes taken out
+    pub text: String,
+    /// Byte range of the word in the input, quotes included
+    pub span: Range<usize>,
+    /// Whether the word starts with an unquoted `@`
+    mention: bool,
+}
+
+impl Token {
+    /// What a mention word refers to, e.g. `src/main.rs` for `@src/main.rs`
+    pub fn mention(&self) -> Option<&str> {
+        self.text
+            .strip_prefix('@')
+            .filter(|target| self.mention && !target.is_empty())
+    }
+}
+
+/// The words of `input` from byte `start` on, with spans into `input`
+///
+/// Fails on a quote that is never closed, pointing from it to the end.
+pub fn tokenize(input: &str, start: usize) -> Result<Vec<Token>, SlashError> {
+    let mut chars = input[start..]
+        .char_indices()
+        .map(|(i, c)| (start + i, c))
+        .peekable();
+    let mut tokens = Vec::new();
+    while let Some(&(begin, first)) = chars.peek() {
+        if first.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut text = String::new();
+        let mut end = begin;
+        let mut can_quote = true;
+        while let Some(&(i, c)) = chars.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            chars.next();
+            end = i + c.len_utf8();
+            match c {
+                '"' | '\'' if can_quote => {
+                    end = quoted(&mut chars, c, &mut text).ok_or(SlashError {
+                        error: ParseError::UnclosedQuote,
+                        span: i..input.len(),
+                        suggestion: None,
+                    })?;
+                    continue;
+                }
+                '\\' => match chars.peek() {
+                    Some(&(j, next))
+                        if next.is_whitespace() || matches!(next, '"' | '\'' | '\\') =>
+                    {
+                        chars.next();
+                        text.push(next);
+                        end = j + next.len_utf8();
+                    }
+                    _ => text.push('\\'),
+                },
+                c => text.push(c),
+            }
+            can_quote = c == '@' && i == begin;
+        }
+        tokens.push(Token {
+            text,
+            span: begin..end,
+            mention: first == '@',
+        });
+    }
+    Ok(tokens)
+}
+
+/// Read a quoted part into `text`, up to its closing `quote`; the byte
+/// after the closing quote, or `None` if there is none
+fn quoted(
+    chars: &mut Peekable<impl Iterator<Item = (usize, char)>>,
+    quote: char,
+    text: &mut String,
+) -> Option<usize> {
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c == quote => return Some(i + 1),
+            '\\' if quote == '"' => match chars.peek() {
+                Some(&(_, next @ ('"' | '\\'))) => {
+                    chars.next();
+                    text.push(next);
+                }
+                _ => text.push('\\'),
+            },
+            c => text.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn words(input: &str) -> Vec<String> {
+        tokenize(input, 0)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.text)
+            .collect()
+    }
+
+    /// `word` quoted so [`tokenize`] gives it back as one word
+    fn quote(word: &str) -> String {
+        let mut quoted = String::from("\"");
+        for c in word.chars() {
+            if matches!(c, '"' | '\\') {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    #[test]
+    fn test_quotes_and_escapes() {
+        assert_eq!(words("  a  b "), ["a", "b"]);
+        assert_eq!(words(r#""old name" 'new name'"#), ["old name", "new name"]);
+        assert_eq!(words(r#"don't "a b"c 'x'"y""#), ["don't", "a bc", "xy"]);
+        assert_eq!(words(r#""say \"hi\"" 'it\s'"#), [r#"say "hi""#, r"it\s"]);
+        assert_eq!(words(r"a\ b C:\Users\me \\"), ["a b", r"C:\Users\me", r"\"]);
+        assert_eq!(words(""), Vec::<String>::new());
+
+        let tokens = tokenize(r#"/x "a b" c"#, 1).unwrap();
+        assert_eq!(tokens[0].span, 1..2);
+        assert_eq!(tokens[1].span, 3..8);
+        assert_eq!(tokens[2].span, 9..10);
+
+        let error = tokenize("say 'hi there", 0).unwrap_err();
+        assert_eq!(error.error, ParseError::UnclosedQuote);
+        assert_eq!(error.span, 4..13);
+    }
+
+    #[test]
+    fn test_mentions() {
+        let tokens = tokenize(r#"@src/main.rs @"docs/My Notes.md" "@not" a@b @"#, 0).unwrap();
+        let mentions: Vec<Option<&str>> = tokens.iter().map(Token::mention).collect();
+        assert_eq!(
+            mentions,
+            [
+                Some("src/main.rs"),
+                Some("docs/My Notes.md"),
+                None,
+                None,
+                None
+            ]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_tokenize_never_panics(input in "\\PC*", start in 0usize..4) {
+            let start = input.char_indices().nth(start).map_or(input.len(), |(i, _)| i);
+            match tokenize(&input, start) {
+                Ok(tokens) => {
+                    for token in tokens {
+                        prop_assert!(token.span.start >= start && token.span.end <= input.len());
+                        prop_assert!(input.is_char_boundary(token.span.start));
+                        prop_assert!(input.is_char_boundary(token.span.end));
+                        prop_assert!(!token.text.is_empty() || token.span.len() >= 2);
+                    }
+                }
+                Err(error) => {
+                    prop_assert_eq!(error.span.end, input.len());
+                    prop_assert!(input[error.span.clone()].starts_with(['"', '\'']));
+                }
+            }
+        }
+
+        #[test]
+        fn prop_quoted_words_round_trip(args in prop::collection::vec("[ a-z\"'\\\\@/.]{0,8}", 0..5)) {
+            let line: Vec<String> = args.iter().map(|arg| quote(arg)).collect();
+            prop_assert_eq!(words(&line.join(" ")), args);
+        }
+
+        #[test]
+        fn prop_spans_cover_the_words(input in "[ a-z\"'\\\\@]{0,24}") {
+            if let Ok(tokens) = tokenize(&input, 0) {
+                for token in &tokens {
+                    // Re-reading a word's span gives the same word
+                    let again = tokenize(&input[token.span.clone()], 0).unwrap();
+                    prop_assert_eq!(again.len(), 1);
+                    prop_assert_eq!(&again[0].text, &token.text);
+                }
+            }
+        }
+    }
+}
@@ -17,12 +17,16 @@
 //! }
 //! ```
 
+mod lexer;
 mod parser;
 mod types;
 
-pub use parser::{ParseError, SlashCommandParser};
+pub use lexer::{tokenize, Token};
+pub use parser::{ParseError, SlashCommandParser, SlashError};
 pub use types::*;
 
+use crate::orchestration::AgentRole;
+use crate::workspace::WatchAction;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -65,20 +69,176 @@ pub enum SlashCommand {
     ///
     /// - `/init` - initialize in current directory
     /// - `/init <path>` - initialize at specified path
+    /// - `/init <template> [path]` - create a project from a template
     Init {
+        /// Template to create the project from
+        template: Option<String>,
         /// Optional path to initialize
         path: Option<PathBuf>,
     },
 
+    /// List the project templates `/init` can create from
+    ///
+    /// `/templates`
+    Templates,
+
+    /// List the active key bindings
+    ///
+    /// `/keys`
+    Keys,
+
+    /// Show or set the level of the TUI's log file
+    ///
+    /// - `/loglevel` - show the level and where the log is
+    /// - `/loglevel debug` - log debug events and above from now on
+    LogLevel {
+        /// off, error, warn, info, debug or trace
+        level: Option<String>,
+    },
+
     /// Workspace management commands
     Workspace(WorkspaceSubcommand),
 
+    /// Show the active workspace's activity feed
+    ///
+    /// Aliases: `/act`
+    Activity,
+
     /// Model selection commands
     Model(ModelSubcommand),
 
     /// Theme commands
     Theme(ThemeSubcommand),
 
+    /// Workspace statistics
+    Stats(StatsSubcommand),
+
+    /// LLM usage and cost
+    Usage(UsageSubcommand),
+
+    /// Agent writes waiting for approval
+    Approvals(ApprovalSubcommand),
+
+    /// Show or change whether the workspace is trusted
+    ///
+    /// `/trust [on|off]`
+    Trust {
+        /// New trust setting (shows the current one if omitted)
+        trusted: Option<bool>,
+    },
+
+    /// Data routing policy and overrides
+    Routing(RoutingSubcommand),
+
+    /// Check the activity log's signature chain for tampering or gaps
+    ///
+    /// `/verify-audit [export]`
+    VerifyAudit {
+        /// Also write the log with its chain head to the workspace root
+        export: bool,
+    },
+
+    /// Rules that run commands or agents on file changes
+    Watch(WatchSubcommand),
+
+    /// Reports compiled from the activity log
+    Report(ReportSubcommand),
+
+    /// Generate tests for a file or symbol and iterate until they pass
+    ///
+    /// `/gen-tests <path|symbol> [--attempts <n>]`
+    GenTests {
+        /// File path (relative to the workspace) or function/type name
+        target: String,
+        /// Most generate-and-run rounds
+        attempts: usize,
+    },
+
+    /// Draft doc comments for undocumented public items, held for review
+    ///
+    /// `/gen-docs <path> [--check]`
+    GenDocs {
+        /// File or module directory (relative to the workspace)
+        path: PathBuf,
+        /// Build the docs (`cargo doc`) with the changes before offering them
+        check: bool,
+    },
+
+    /// Rename a symbol across files with a reviewable preview
+    Refactor(RefactorSubcommand),
+
+    /// List the build's compiler errors, or have the Developer fix one
+    ///
+    /// `/fix [<n>] [--attempts <n>]`
+    Fix {
+        /// Problem to fix (1-based); `None` lists them
+        problem: Option<usize>,
+        /// Most fix-and-rebuild rounds
+        attempts: usize,
+    },
+
+    /// Save or restore the conversation and agent output
+    Session(SessionSubcommand),
+
+    /// List asciicast recordings of CLI agent sessions, newest first
+    ///
+    /// `/recordings`
+    Recordings,
+
+    /// Write the last Conductor run as a markdown document and open it
+    ///
+    /// - `/run-doc` - save under `.axiom/runs/`
+    /// - `/run-doc <path>` - save to the given file
+    RunDoc {
+        /// Where to write the document
+        path: Option<PathBuf>,
+    },
+
+    /// Open the review queue: pending approvals, merge conflicts and
+    /// stalled agents, most urgent first
+    ///
+    /// `/review` (alias `/queue`)
+    Review,
+
+    /// Capture agents' LLM requests and replies, or show an agent's
+    Inspect(InspectSubcommand),
+
+    /// Have the Conductor plan a request as steps and run them in turn
+    Plan(PlanSubcommand),
+
+    /// List or switch the workspace's feature flags
+    Features(FeaturesSubcommand),
+
+    /// Search the workspace's files
+    ///
+    /// `/find [--regex] [--glob <glob>]... [query]` (alias `/grep`); without
+    /// a query the TUI opens an empty search panel
+    Find {
+        /// Text to find (words are joined with spaces)
+        query: Option<String>,
+        /// Treat the query as a regular expression
+        regex: bool,
+        /// Globs limiting the files searched
+        globs: Vec<String>,
+    },
+
+    /// Replace text across the workspace's files
+    ///
+    /// `/replace [--regex] [--glob <glob>]... [--yes] <find> <replacement>`;
+    /// shows a diff per file to confirm unless `--yes` is given
+    Replace {
+        /// Text to find (a single word)
+        query: String,
+        /// Replacement (the remaining words, joined with spaces)
+        replacement: String,
+        /// Treat the query as a regular expression
+        regex: bool,
+        /// Globs limiting the files changed
+        globs: Vec<String>,
+        /// Write the changes without showing the preview first
+        apply: bool,
+    },
+
     /// Custom/extension command
     ///
     /// For commands not built-in, allows extensions to handle them
@@ -117,6 +277,44 @@ pub enum WorkspaceSubcommand {
         /// Workspace root path
         path: PathBuf,
     },
+
+    /// Merge workspaces registered more than once for the same directory
+    ///
+    /// `/workspace dedupe`
+    Dedupe,
+
+    /// Hide a workspace from the list, keeping its data
+    ///
+    /// `/workspace archive <name|id>` (also `delete`)
+    Archive {
+        /// Workspace name or ID
+        id: String,
+    },
+
+    /// Bring an archived workspace back
+    ///
+    /// `/workspace restore <name|id>`
+    Restore {
+        /// Workspace name or ID
+        id: String,
+    },
+
+    /// List archived workspaces
+    ///
+    /// `/workspace archived`
+    Archived,
+
+    /// Permanently delete an archived workspace's Axiom data
+    ///
+    /// - `/workspace purge <name|id>` - show what would be deleted
+    /// - `/workspace purge <name|id> --yes` - delete it
+    Purge {
+        /// Workspace name or ID
+        id: String,
+        /// Delete rather than only list what would go
+        #[serde(default)]
+        confirm: bool,
+    },
 }
 
 /// Model management subcommands
@@ -136,6 +334,18 @@ pub enum ModelSubcommand {
         model: String,
     },
 
+    /// Map an agent role to a provider and model
+    ///
+    /// `/model set <role> <provider>/<model>`
+    Assign {
+        /// Role to map
+        role: AgentRole,
+        /// Provider ID
+        provider: String,
+        /// Model the role uses on that provider
+        model: String,
+    },
+
     /// Show currently active model
     ///
     /// `/model current`
@@ -160,6 +370,244 @@ pub enum ThemeSubcommand {
     },
 }
 
+/// Statistics subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "subcommand")]
+pub enum StatsSubcommand {
+    /// Agent vs. user time in the active workspace
+    ///
+    /// `/stats time [days]` or `/stats`
+    Time {
+        /// Only count the last N days
+        days: Option<u32>,
+    },
+}
+
+/// Usage subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "subcommand")]
+pub enum UsageSubcommand {
+    /// Tokens and estimated cost of the active workspace
+    ///
+    /// `/usage`
+    Summary,
+
+    /// Write a cost report into the workspace
+    ///
+    /// `/usage export [from] [to] [--json]`
+    Export {
+        /// First day included (YYYY-MM-DD)
+        from: Option<String>,
+        /// Last day included (YYYY-MM-DD)
+        to: Option<String>,
+        /// Write JSON instead of CSV
+        json: bool,
+    },
+}
+
+/// Routing subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "subcommand")]
+pub enum RoutingSubcommand {
+    /// Show the workspace's policy and which providers it allows
+    ///
+    /// `/routing`
+    Show,
+
+    /// Allow a blocked provider for this session
+    ///
+    /// `/routing allow <provider> <reason>`
+    Allow {
+        provider: String,
+        /// Why, recorded in the activity log
+        reason: String,
+    },
+
+    /// Withdraw an override
+    ///
+    /// `/routing revoke <provider>`
+    Revoke { provider: String },
+}
+
+/// Watch subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "subcommand")]
+pub enum WatchSubcommand {
+    /// List the workspace's watch rules
+    ///
+    /// `/watch list`
+    List,
+
+    /// Add a rule
+    ///
+    /// `/watch add <glob> run <command>` or `/watch add <glob> agent <prompt>`
+    Add { pattern: String, action: WatchAction },
+
+    /// Remove a rule by its number in `/watch list`
+    ///
+    /// `/watch rm <n>`
+    Remove { number: usize },
+}
+
+/// Session subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "subcommand")]
+pub enum SessionSubcommand {
+    /// List saved sessions, newest first
+    ///
+    /// `/session list`
+    List,
+
+    /// Save the conversation and agents
+    ///
+    /// `/session save [name]`
+    Save { name: Option<String> },
+
+    /// Restore a saved session (the newest if unnamed)
+    ///
+    /// `/session restore [name]`
+    Restore { name: Option<String> },
+}
+
+/// Inspect subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "subcommand")]
+pub enum InspectSubcommand {
+    /// Start capturing each LLM request and its reply, redacted
+    ///
+    /// `/inspect on`
+    On,
+
+    /// Stop capturing; what was captured is kept
+    ///
+    /// `/inspect off`
+    Off,
+
+    /// Show an agent's captured exchanges (the agent in the output panel
+    /// if none is named), or copy them to the clipboard
+    ///
+    /// `/inspect [agent] [--copy]`
+    Show {
+        /// Agent ID or name
+        agent: Option<String>,
+        /// Copy instead of opening them
+        copy: bool,
+    },
+}
+
+/// Plan subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "subcommand")]
+pub enum PlanSubcommand {
+    /// Show the latest plan and its progress
+    ///
+    /// `/plan`
+    Show,
+
+    /// Plan a request, then run its steps one after the other
+    ///
+    /// `/plan <request>`
+    Start { request: String },
+
+    /// Stop the running plan; its remaining steps are skipped
+    ///
+    /// `/plan cancel`
+    Cancel,
+}
+
+/// Feature flag subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "subcommand")]
+pub enum FeaturesSubcommand {
+    /// Show every flag and whether it is on
+    ///
+    /// `/features`
+    List,
+
+    /// Switch a flag on or off for the workspace
+    ///
+    /// `/features <name> on|off`
+    Set {
+        /// Flag name as written under `[features]`
+        name: String,
+        enabled: bool,
+    },
+}
+
+/// Report subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "subcommand")]
+pub enum ReportSubcommand {
+    /// Changelog of what agents changed
+    ///
+    /// `/report agents [--since 7d]`
+    Agents {
+        /// Look-back period, e.g. "7d", "12h" or "2w"
+        since: String,
+    },
+}
+
+/// Approval subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "subcommand")]
+pub enum ApprovalSubcommand {
+    /// List held writes
+    ///
+    /// `/approvals`
+    List,
+
+    /// Apply a held write
+    ///
+    /// `/approve [id]`
+    Approve {
+        /// Approval ID (oldest if omitted)
+        id: Option<String>,
+    },
+
+    /// Discard a held write
+    ///
+    /// `/reject [id]`
+    Reject {
+        /// Approval ID (oldest if omitted)
+        id: Option<String>,
+    },
+}
+
+/// Refactor subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "subcommand")]
+pub enum RefactorSubcommand {
+    /// Show the pending rename
+    ///
+    /// `/refactor`
+    Show,
+
+    /// Preview renaming a symbol everywhere it is referenced
+    ///
+    /// `/refactor rename <symbol> <new_name>`
+    Rename { symbol: String, new_name: String },
+
+    /// Include or leave out one file of the pending rename
+    ///
+    /// `/refactor toggle <path>`
+    Toggle { path: String },
+
+    /// Apply the pending rename to every included file at once
+    ///
+    /// `/refactor apply`
+    Apply,
+
+    /// Revert the last applied refactor
+    ///
+    /// `/refactor undo`
+    Undo,
+
+    /// Drop the pending rename
+    ///
+    /// `/refactor cancel`
+    Cancel,
+}
+
 impl SlashCommand {
     /// Get the command name (for display purposes)
     pub fn name(&self) -> &'static str {
@@ -170,9 +618,34 @@ impl SlashCommand {
             SlashCommand::Exit => "exit",
             SlashCommand::Version => "version",
             SlashCommand::Init { .. } => "init",
+            SlashCommand::Templates => "templates",
+            SlashCommand::Keys => "keys",
+            SlashCommand::LogLevel { .. } => "loglevel",
             SlashCommand::Workspace(_) => "workspace",
+            SlashCommand::Activity => "activity",
             SlashCommand::Model(_) => "model",
             SlashCommand::Theme(_) => "theme",
+            SlashCommand::Stats(_) => "stats",
+            SlashCommand::Usage(_) => "usage",
+            SlashCommand::Approvals(_) => "approvals",
+            SlashCommand::Trust { .. } => "trust",
+            SlashCommand::Routing(_) => "routing",
+            SlashCommand::Watch(_) => "watch",
+            SlashCommand::Report(_) => "report",
+            SlashCommand::GenTests { .. } => "gen-tests",
+            SlashCommand::GenDocs { .. } => "gen-docs",
+            SlashCommand::Refactor(_) => "refactor",
+            SlashCommand::Fix { .. } => "fix",
+            SlashCommand::RunDoc { .. } => "run-doc",
+            SlashCommand::Session(_) => "session",
+            SlashCommand::Recordings => "recordings",
+            SlashCommand::Review => "review",
+            SlashCommand::Inspect(_) => "inspect",
+            SlashCommand::Plan(_) => "plan",
+            SlashCommand::Features(_) => "features",
+            SlashCommand::Find { .. } => "find",
+            SlashCommand::Replace { .. } => "replace",
+            SlashCommand::VerifyAudit { .. } => "verify-audit",
             SlashCommand::Custom { .. } => "custom",
         }
     }
@@ -1,13 +1,25 @@
 //! Slash command parser
 //!
 //! Parses user input strings starting with "/" into structured commands.
-
-use super::{ModelSubcommand, SlashCommand, ThemeSubcommand, WorkspaceSubcommand};
+//! The line is split into words by the [lexer](super::lexer), which handles
+//! quoting, and errors point at the word they are about, with a suggestion
+//! when a command looks mistyped.
+
+use super::lexer::{tokenize, Token};
+use super::{
+    ApprovalSubcommand, FeaturesSubcommand, InspectSubcommand, ModelSubcommand, PlanSubcommand,
+    RefactorSubcommand, ReportSubcommand, RoutingSubcommand, SessionSubcommand, SlashCommand,
+    StatsSubcommand, ThemeSubcommand, UsageSubcommand, WatchSubcommand, WorkspaceSubcommand,
+};
+use crate::orchestration::{AgentRole, DEFAULT_ATTEMPTS};
+use crate::workspace::{parse_since, Feature, WatchAction};
+use std::fmt;
+use std::ops::Range;
 use std::path::PathBuf;
 use thiserror::Error;
 
 /// Errors that can occur during slash command parsing
-#[derive(Debug, Clone, Error)]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum ParseError {
     /// Empty command (just "/")
     #[error("Empty command")]
@@ -24,8 +36,35 @@ pub enum ParseError {
     /// Invalid argument format
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
+
+    /// A quote that is never closed
+    #[error("Unclosed quote")]
+    UnclosedQuote,
+}
+
+/// A parse error and the part of the input it is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlashError {
+    pub error: ParseError,
+    /// Byte range of the input at fault; empty where a missing argument
+    /// should go
+    pub span: Range<usize>,
+    /// What was probably meant, e.g. "/workspace" for "/wrkspace"
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for SlashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(ref suggestion) = self.suggestion {
+            write!(f, " (did you mean {}?)", suggestion)?;
+        }
+        Ok(())
+    }
 }
 
+impl std::error::Error for SlashError {}
+
 /// Parser for slash commands
 pub struct SlashCommandParser;
 
@@ -36,17 +75,73 @@ impl SlashCommandParser {
     /// Returns `Some(Ok(...))` if parsing succeeds
     /// Returns `Some(Err(...))` if parsing fails
     pub fn parse(input: &str) -> Option<Result<SlashCommand, ParseError>> {
-        let trimmed = input.trim();
-        if !trimmed.starts_with('/') {
+        Self::parse_spanned(input).map(|result| result.map_err(|e| e.error))
+    }
+
+    /// Like [`Self::parse`], with errors pointing into `input`
+    pub fn parse_spanned(input: &str) -> Option<Result<SlashCommand, SlashError>> {
+        let slash = input.len() - input.trim_start().len();
+        if !input[slash..].starts_with('/') {
             return None;
         }
-        Some(Self::parse_command(&trimmed[1..]))
+        let tokens = match tokenize(input, slash + 1) {
+            Ok(tokens) => tokens,
+            Err(e) => return Some(Err(e)),
+        };
+        let words: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        Some(
+            Self::parse_command(&words).map_err(|error| Self::locate(error, input, slash, &tokens)),
+        )
+    }
+
+    /// Point `error` at the word it is about, and suggest what was meant
+    fn locate(error: ParseError, input: &str, slash: usize, tokens: &[Token]) -> SlashError {
+        let end = input.trim_end().len();
+        let (span, suggestion) = match &error {
+            ParseError::Empty => (slash..slash + 1, None),
+            // "workspace sw" names the subcommand, "wrkspace" the command
+            ParseError::UnknownCommand(name) => {
+                let token = &tokens[usize::from(name.contains(' ')).min(tokens.len() - 1)];
+                let suggestion = (!name.contains(' '))
+                    .then(|| Self::suggest_command(&token.text))
+                    .flatten()
+                    .map(|command| format!("/{}", command));
+                (token.span.clone(), suggestion)
+            }
+            ParseError::MissingArgument(_) | ParseError::UnclosedQuote => (end..end, None),
+            // The last argument the message quotes, e.g. "onn" in
+            // "trust: onn (expected on or off)"
+            ParseError::InvalidArgument(message) => {
+                let named = tokens[1..]
+                    .iter()
+                    .rev()
+                    .find(|token| !token.text.is_empty() && message.contains(&token.text));
+                match named {
+                    Some(token) => {
+                        let suggestion = suggest(&token.text, expected(message)).map(String::from);
+                        (token.span.clone(), suggestion)
+                    }
+                    None => (tokens[0].span.end..end, None),
+                }
+            }
+        };
+        SlashError {
+            error,
+            span,
+            suggestion,
+        }
     }
 
-    /// Parse the command portion (after the "/" prefix)
-    fn parse_command(input: &str) -> Result<SlashCommand, ParseError> {
-        let parts: Vec<&str> = input.split_whitespace().collect();
-        let (cmd, args) = parts.split_first().ok_or(ParseError::Empty)?;
+    /// The known command closest to `name`, if one is close enough to be a
+    /// typo of it
+    pub fn suggest_command(name: &str) -> Option<String> {
+        let help = Self::get_all_commands_help();
+        suggest(name, help.iter().map(|command| command.name.as_str())).map(String::from)
+    }
+
+    /// Parse the words after the "/" prefix
+    fn parse_command(words: &[&str]) -> Result<SlashCommand, ParseError> {
+        let (cmd, args) = words.split_first().ok_or(ParseError::Empty)?;
 
         match cmd.to_lowercase().as_str() {
             // Help commands
@@ -67,19 +162,101 @@ impl SlashCommandParser {
             "version" | "v" => Ok(SlashCommand::Version),
 
             // Initialize workspace
-            "init" => Ok(SlashCommand::Init {
-                path: args.first().map(PathBuf::from),
-            }),
+            "init" => Ok(Self::parse_init(args)),
+            "templates" => Ok(SlashCommand::Templates),
+
+            // Key bindings
+            "keys" => Ok(SlashCommand::Keys),
+
+            // Log level
+            "loglevel" => Self::parse_log_level(args),
 
             // Workspace commands
             "workspace" | "ws" => Self::parse_workspace(args),
 
+            // Activity feed
+            "activity" | "act" => Ok(SlashCommand::Activity),
+
             // Model commands
             "model" | "m" => Self::parse_model(args),
 
             // Theme commands
             "theme" => Self::parse_theme(args),
 
+            // Statistics
+            "stats" => Self::parse_stats(args),
+
+            // Usage and cost
+            "usage" => Self::parse_usage(args),
+
+            // Write approvals
+            "approvals" => Ok(SlashCommand::Approvals(ApprovalSubcommand::List)),
+            "approve" => Ok(SlashCommand::Approvals(ApprovalSubcommand::Approve {
+                id: args.first().map(|s| s.to_string()),
+            })),
+            "reject" => Ok(SlashCommand::Approvals(ApprovalSubcommand::Reject {
+                id: args.first().map(|s| s.to_string()),
+            })),
+
+            // Workspace trust
+            "trust" => Self::parse_trust(args),
+
+            // Data routing
+            "routing" => Self::parse_routing(args),
+
+            // Audit log
+            "verify-audit" | "audit" => Self::parse_verify_audit(args),
+
+            // Watch mode
+            "watch" => Self::parse_watch(args),
+
+            // Reports
+            "report" => Self::parse_report(args),
+
+            // Test generation
+            "gen-tests" => Self::parse_gen_tests(args),
+
+            // Documentation generation
+            "gen-docs" => Self::parse_gen_docs(args),
+
+            // Cross-file refactoring
+            "refactor" => Self::parse_refactor(args),
+
+            // Compiler errors
+            "fix" => Self::parse_fix(args),
+
+            // Saved sessions
+            "session" => Self::parse_session(args),
+
+            // Agent session recordings
+            "recordings" | "recs" => Ok(SlashCommand::Recordings),
+
+            // Run documents
+            "run-doc" => Ok(SlashCommand::RunDoc {
+                path: args.first().map(PathBuf::from),
+            }),
+
+            // Review queue
+            "review" | "queue" => Ok(SlashCommand::Review),
+
+            // LLM request inspector
+            "inspect" => Self::parse_inspect(args),
+
+            // Multi-step plans
+            "plan" => Ok(Self::parse_plan(args)),
+
+            // Feature flags
+            "features" => Self::parse_features(args),
+
+            // Workspace search
+            "find" | "grep" => Self::parse_find(args),
+            "replace" => Self::parse_replace(args),
+
+            // A typo of a known command
+            other if Self::suggest_command(other).is_some() => {
+                Err(ParseError::UnknownCommand(other.to_string()))
+            }
+
             // Unknown command - treat as custom
             other => Ok(SlashCommand::Custom {
                 name: other.to_string(),
@@ -88,6 +265,45 @@ impl SlashCommandParser {
         }
     }
 
+    /// Parse /init arguments: a lone argument that looks like a path is
+    /// the directory to initialize, anything else names a template
+    fn parse_init(args: &[&str]) -> SlashCommand {
+        let is_path = |arg: &str| {
+            arg.contains('/') || arg.contains('\\') || arg.starts_with('.') || arg.starts_with('~')
+        };
+        match args {
+            [] => SlashCommand::Init {
+                template: None,
+                path: None,
+            },
+            [arg] if is_path(arg) => SlashCommand::Init {
+                template: None,
+                path: Some(PathBuf::from(arg)),
+            },
+            [template, rest @ ..] => SlashCommand::Init {
+                template: Some(template.to_string()),
+                path: rest.first().map(PathBuf::from),
+            },
+        }
+    }
+
+    /// Parse /loglevel's optional level
+    fn parse_log_level(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        const LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+        let Some(level) = args.first() else {
+            return Ok(SlashCommand::LogLevel { level: None });
+        };
+        let level = level.to_lowercase();
+        if !LEVELS.contains(&level.as_str()) {
+            return Err(ParseError::InvalidArgument(format!(
+                "loglevel: {} (expected one of {})",
+                level,
+                LEVELS.join(", ")
+            )));
+        }
+        Ok(SlashCommand::LogLevel { level: Some(level) })
+    }
+
     /// Parse workspace subcommand
     fn parse_workspace(args: &[&str]) -> Result<SlashCommand, ParseError> {
         let subcommand = args.first().map(|s| s.to_lowercase());
@@ -114,6 +330,25 @@ impl SlashCommandParser {
                     path,
                 }))
             }
+            Some("dedupe") | Some("merge") => {
+                Ok(SlashCommand::Workspace(WorkspaceSubcommand::Dedupe))
+            }
+            Some("archived") => Ok(SlashCommand::Workspace(WorkspaceSubcommand::Archived)),
+            Some(sub @ ("archive" | "delete" | "rm" | "restore" | "unarchive" | "purge")) => {
+                let id = args
+                    .get(1)
+                    .ok_or_else(|| ParseError::MissingArgument("workspace name or ID".to_string()))?
+                    .to_string();
+                let sub = match sub {
+                    "restore" | "unarchive" => WorkspaceSubcommand::Restore { id },
+                    "purge" => WorkspaceSubcommand::Purge {
+                        id,
+                        confirm: args[2..].iter().any(|a| matches!(*a, "--yes" | "-y")),
+                    },
+                    _ => WorkspaceSubcommand::Archive { id },
+                };
+                Ok(SlashCommand::Workspace(sub))
+            }
             Some(other) => Err(ParseError::UnknownCommand(format!("workspace {}", other))),
         }
     }
@@ -131,7 +366,25 @@ impl SlashCommandParser {
                     .get(1)
                     .ok_or_else(|| ParseError::MissingArgument("model name".to_string()))?
                     .to_string();
-                Ok(SlashCommand::Model(ModelSubcommand::Set { model }))
+                let Ok(role) = model.parse::<AgentRole>() else {
+                    return Ok(SlashCommand::Model(ModelSubcommand::Set { model }));
+                };
+                let target = args.get(2).ok_or_else(|| {
+                    ParseError::MissingArgument("<provider>/<model> for the role".to_string())
+                })?;
+                match target.split_once('/') {
+                    Some((provider, model)) if !provider.is_empty() && !model.is_empty() => {
+                        Ok(SlashCommand::Model(ModelSubcommand::Assign {
+                            role,
+                            provider: provider.to_lowercase(),
+                            model: model.to_string(),
+                        }))
+                    }
+                    _ => Err(ParseError::InvalidArgument(format!(
+                        "expected <provider>/<model>, got '{}'",
+                        target
+                    ))),
+                }
             }
             Some("current") | Some("show") => {
                 Ok(SlashCommand::Model(ModelSubcommand::Current))
@@ -163,6 +416,437 @@ impl SlashCommandParser {
         }
     }
 
+    /// Parse stats subcommand
+    fn parse_stats(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let subcommand = args.first().map(|s| s.to_lowercase());
+
+        match subcommand.as_deref() {
+            Some("time") | None => {
+                let days = args
+                    .get(1)
+                    .map(|s| {
+                        s.parse::<u32>()
+                            .map_err(|_| ParseError::InvalidArgument(format!("days: {}", s)))
+                    })
+                    .transpose()?;
+                Ok(SlashCommand::Stats(StatsSubcommand::Time { days }))
+            }
+            Some(other) => Err(ParseError::UnknownCommand(format!("stats {}", other))),
+        }
+    }
+
+    /// Parse usage subcommand
+    fn parse_usage(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let subcommand = args.first().map(|s| s.to_lowercase());
+
+        match subcommand.as_deref() {
+            None | Some("summary") => Ok(SlashCommand::Usage(UsageSubcommand::Summary)),
+            Some("export") => {
+                let json = args[1..].contains(&"--json");
+                let mut dates = args[1..].iter().filter(|a| !a.starts_with("--"));
+                Ok(SlashCommand::Usage(UsageSubcommand::Export {
+                    from: dates.next().map(|s| s.to_string()),
+                    to: dates.next().map(|s| s.to_string()),
+                    json,
+                }))
+            }
+            Some(other) => Err(ParseError::UnknownCommand(format!("usage {}", other))),
+        }
+    }
+
+    /// Parse trust argument
+    fn parse_trust(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let trusted = match args.first().map(|s| s.to_lowercase()).as_deref() {
+            None => None,
+            Some("on") | Some("yes") | Some("true") => Some(true),
+            Some("off") | Some("no") | Some("false") => Some(false),
+            Some(other) => {
+                return Err(ParseError::InvalidArgument(format!(
+                    "trust: {} (expected on or off)",
+                    other
+                )))
+            }
+        };
+        Ok(SlashCommand::Trust { trusted })
+    }
+
+    /// Parse features arguments
+    fn parse_features(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let Some(name) = args.first() else {
+            return Ok(SlashCommand::Features(FeaturesSubcommand::List));
+        };
+        let feature = Feature::parse(name).ok_or_else(|| {
+            let known: Vec<&str> = Feature::ALL.iter().map(|f| f.name()).collect();
+            ParseError::InvalidArgument(format!(
+                "features: {} (expected one of {})",
+                name,
+                known.join(", ")
+            ))
+        })?;
+        let enabled = match args.get(1).map(|s| s.to_lowercase()).as_deref() {
+            Some("on") | Some("yes") | Some("true") => true,
+            Some("off") | Some("no") | Some("false") => false,
+            Some(other) => {
+                return Err(ParseError::InvalidArgument(format!(
+                    "features {}: {} (expected on or off)",
+                    feature.name(),
+                    other
+                )))
+            }
+            None => return Err(ParseError::MissingArgument("on or off".to_string())),
+        };
+        Ok(SlashCommand::Features(FeaturesSubcommand::Set {
+            name: feature.name().to_string(),
+            enabled,
+        }))
+    }
+
+    /// Parse routing subcommand
+    fn parse_routing(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let provider = || {
+            args.get(1)
+                .map(|s| s.to_lowercase())
+                .ok_or_else(|| ParseError::MissingArgument("provider".to_string()))
+        };
+        let subcommand = match args.first().map(|s| s.to_lowercase()).as_deref() {
+            None | Some("show") => RoutingSubcommand::Show,
+            Some("allow") => {
+                let provider = provider()?;
+                if args.len() < 3 {
+                    return Err(ParseError::MissingArgument("reason".to_string()));
+                }
+                RoutingSubcommand::Allow {
+                    provider,
+                    reason: args[2..].join(" "),
+                }
+            }
+            Some("revoke") => RoutingSubcommand::Revoke {
+                provider: provider()?,
+            },
+            Some(other) => {
+                return Err(ParseError::InvalidArgument(format!(
+                    "routing: {} (expected allow or revoke)",
+                    other
+                )))
+            }
+        };
+        Ok(SlashCommand::Routing(subcommand))
+    }
+
+    /// Parse verify-audit arguments
+    fn parse_verify_audit(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        match args.first().map(|s| s.to_lowercase()).as_deref() {
+            None => Ok(SlashCommand::VerifyAudit { export: false }),
+            Some("export") => Ok(SlashCommand::VerifyAudit { export: true }),
+            Some(other) => Err(ParseError::InvalidArgument(format!(
+                "verify-audit: {} (expected export)",
+                other
+            ))),
+        }
+    }
+
+    /// Parse watch subcommand
+    fn parse_watch(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let subcommand = match args.first().map(|s| s.to_lowercase()).as_deref() {
+            None | Some("list") => WatchSubcommand::List,
+            Some("add") => {
+                let pattern = args
+                    .get(1)
+                    .ok_or_else(|| ParseError::MissingArgument("glob".to_string()))?;
+                let kind = args.get(2).map(|s| s.to_lowercase());
+                let text = args.get(3..).unwrap_or_default().join(" ");
+                if kind.is_some() && text.is_empty() {
+                    return Err(ParseError::MissingArgument("command or prompt".to_string()));
+                }
+                let action = match kind.as_deref() {
+                    Some("run") => WatchAction::Run { command: text },
+                    Some("agent") => WatchAction::Agent { prompt: text },
+                    Some(other) => {
+                        return Err(ParseError::InvalidArgument(format!(
+                            "watch: {} (expected run or agent)",
+                            other
+                        )))
+                    }
+                    None => return Err(ParseError::MissingArgument("run or agent".to_string())),
+                };
+                WatchSubcommand::Add {
+                    pattern: pattern.to_string(),
+                    action,
+                }
+            }
+            Some("rm" | "remove") => {
+                let number = args
+                    .get(1)
+                    .ok_or_else(|| ParseError::MissingArgument("rule number".to_string()))?;
+                WatchSubcommand::Remove {
+                    number: number.parse().map_err(|_| {
+                        ParseError::InvalidArgument(format!("watch rm: {} is not a number", number))
+                    })?,
+                }
+            }
+            Some(other) => {
+                return Err(ParseError::InvalidArgument(format!(
+                    "watch: {} (expected list, add or rm)",
+                    other
+                )))
+            }
+        };
+        Ok(SlashCommand::Watch(subcommand))
+    }
+
+    /// Parse session subcommand
+    fn parse_session(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let name = args.get(1).map(|s| s.to_string());
+        let subcommand = match args.first().map(|s| s.to_lowercase()).as_deref() {
+            None | Some("list" | "ls") => SessionSubcommand::List,
+            Some("save") => SessionSubcommand::Save { name },
+            Some("restore" | "load") => SessionSubcommand::Restore { name },
+            Some(other) => {
+                return Err(ParseError::InvalidArgument(format!(
+                    "session: {} (expected list, save or restore)",
+                    other
+                )))
+            }
+        };
+        Ok(SlashCommand::Session(subcommand))
+    }
+
+    /// Parse inspect subcommand: `on`, `off`, or an agent and `--copy`
+    fn parse_inspect(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let copy = args.iter().any(|a| matches!(*a, "--copy" | "-c"));
+        let words: Vec<&str> = args
+            .iter()
+            .copied()
+            .filter(|a| !matches!(*a, "--copy" | "-c"))
+            .collect();
+        let subcommand = match (words.as_slice(), copy) {
+            (["on"], false) => InspectSubcommand::On,
+            (["off"], false) => InspectSubcommand::Off,
+            ([], _) => InspectSubcommand::Show { agent: None, copy },
+            ([agent], _) if !matches!(*agent, "on" | "off") => InspectSubcommand::Show {
+                agent: Some(agent.to_string()),
+                copy,
+            },
+            _ => {
+                return Err(ParseError::InvalidArgument(
+                    "inspect: expected on, off, or an agent and --copy".to_string(),
+                ))
+            }
+        };
+        Ok(SlashCommand::Inspect(subcommand))
+    }
+
+    /// Parse plan subcommand; anything but `cancel` is a request to plan
+    fn parse_plan(args: &[&str]) -> SlashCommand {
+        SlashCommand::Plan(match args {
+            [] => PlanSubcommand::Show,
+            ["cancel"] => PlanSubcommand::Cancel,
+            words => PlanSubcommand::Start {
+                request: words.join(" "),
+            },
+        })
+    }
+
+    /// Parse report subcommand
+    fn parse_report(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        match args.first().map(|s| s.to_lowercase()).as_deref() {
+            None | Some("agents") => {}
+            Some(other) => {
+                return Err(ParseError::InvalidArgument(format!(
+                    "report: {} (expected agents)",
+                    other
+                )))
+            }
+        }
+        let since = match args.get(1..).unwrap_or_default() {
+            [] => "7d",
+            ["--since"] => return Err(ParseError::MissingArgument("period".to_string())),
+            ["--since", since] | [since] => since,
+            [_, extra, ..] => {
+                return Err(ParseError::InvalidArgument(format!(
+                    "report: unexpected {}",
+                    extra
+                )))
+            }
+        };
+        if parse_since(since).is_none() {
+            return Err(ParseError::InvalidArgument(format!(
+                "report: {} (expected a period like 7d, 12h or 2w)",
+                since
+            )));
+        }
+        Ok(SlashCommand::Report(ReportSubcommand::Agents {
+            since: since.to_lowercase(),
+        }))
+    }
+
+    /// Parse gen-tests arguments: a target and an optional `--attempts <n>`
+    fn parse_gen_tests(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let mut target = None;
+        let mut attempts = DEFAULT_ATTEMPTS;
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            if arg == "--attempts" {
+                let n = args
+                    .next()
+                    .ok_or_else(|| ParseError::MissingArgument("attempts".to_string()))?;
+                attempts = n.parse().ok().filter(|&n| n > 0).ok_or_else(|| {
+                    ParseError::InvalidArgument(format!(
+                        "gen-tests: {} (expected a positive number of attempts)",
+                        n
+                    ))
+                })?;
+            } else if target.is_none() {
+                target = Some(arg.to_string());
+            } else {
+                return Err(ParseError::InvalidArgument(format!(
+                    "gen-tests: unexpected {}",
+                    arg
+                )));
+            }
+        }
+        let target =
+            target.ok_or_else(|| ParseError::MissingArgument("path or symbol".to_string()))?;
+        Ok(SlashCommand::GenTests { target, attempts })
+    }
+
+    /// Parse find arguments: `--regex`, any number of `--glob <glob>`, and
+    /// the query
+    fn parse_find(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let (words, regex, globs) = Self::search_flags(args, &mut None)?;
+        Ok(SlashCommand::Find {
+            query: (!words.is_empty()).then(|| words.join(" ")),
+            regex,
+            globs,
+        })
+    }
+
+    /// Parse replace arguments: search flags, `--yes`, the query and the
+    /// replacement
+    fn parse_replace(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let mut apply = Some(false);
+        let (words, regex, globs) = Self::search_flags(args, &mut apply)?;
+        match words.split_first() {
+            Some((query, replacement)) if !replacement.is_empty() => Ok(SlashCommand::Replace {
+                query: query.to_string(),
+                replacement: replacement.join(" "),
+                regex,
+                globs,
+                apply: apply.unwrap_or(false),
+            }),
+            Some(_) => Err(ParseError::MissingArgument("replacement".to_string())),
+            None => Err(ParseError::MissingArgument("text to find".to_string())),
+        }
+    }
+
+    /// Split `--regex` and `--glob <glob>` (and `--yes` when `yes` is
+    /// `Some`) from the words of a search
+    fn search_flags<'a>(
+        args: &[&'a str],
+        yes: &mut Option<bool>,
+    ) -> Result<(Vec<&'a str>, bool, Vec<String>), ParseError> {
+        let mut words = Vec::new();
+        let mut regex = false;
+        let mut globs = Vec::new();
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            match arg {
+                "--regex" | "-r" => regex = true,
+                "--glob" | "-g" => {
+                    let glob = args
+                        .next()
+                        .ok_or_else(|| ParseError::MissingArgument("glob".to_string()))?;
+                    globs.push(glob.to_string());
+                }
+                "--yes" | "-y" if yes.is_some() => *yes = Some(true),
+                word => words.push(word),
+            }
+        }
+        Ok((words, regex, globs))
+    }
+
+    /// Parse gen-docs arguments: a path and an optional `--check`
+    fn parse_gen_docs(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let check = args.contains(&"--check");
+        let paths: Vec<&&str> = args.iter().filter(|&&a| a != "--check").collect();
+        match paths.as_slice() {
+            [] => Err(ParseError::MissingArgument("path".to_string())),
+            [path] => Ok(SlashCommand::GenDocs {
+                path: PathBuf::from(path),
+                check,
+            }),
+            [_, extra, ..] => Err(ParseError::InvalidArgument(format!(
+                "gen-docs: unexpected {}",
+                extra
+            ))),
+        }
+    }
+
+    /// Parse fix arguments: an optional problem number and `--attempts <n>`
+    fn parse_fix(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let positive = |n: &str, what: &str| {
+            n.parse().ok().filter(|&n: &usize| n > 0).ok_or_else(|| {
+                ParseError::InvalidArgument(format!("fix: {} (expected a positive {})", n, what))
+            })
+        };
+        let mut problem = None;
+        let mut attempts = DEFAULT_ATTEMPTS;
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            if arg == "--attempts" {
+                let n = args
+                    .next()
+                    .ok_or_else(|| ParseError::MissingArgument("attempts".to_string()))?;
+                attempts = positive(n, "number of attempts")?;
+            } else if problem.is_none() {
+                problem = Some(positive(arg, "problem number")?);
+            } else {
+                return Err(ParseError::InvalidArgument(format!(
+                    "fix: unexpected {}",
+                    arg
+                )));
+            }
+        }
+        Ok(SlashCommand::Fix { problem, attempts })
+    }
+
+    /// Parse refactor subcommand
+    fn parse_refactor(args: &[&str]) -> Result<SlashCommand, ParseError> {
+        let subcommand = match args.first().map(|s| s.to_lowercase()).as_deref() {
+            None | Some("show") => RefactorSubcommand::Show,
+            Some("rename") => match args[1..] {
+                [symbol, new_name] => RefactorSubcommand::Rename {
+                    symbol: symbol.to_string(),
+                    new_name: new_name.to_string(),
+                },
+                [] => return Err(ParseError::MissingArgument("symbol".to_string())),
+                [_] => return Err(ParseError::MissingArgument("new name".to_string())),
+                [_, _, extra, ..] => {
+                    return Err(ParseError::InvalidArgument(format!(
+                        "refactor rename: unexpected {}",
+                        extra
+                    )))
+                }
+            },
+            Some("toggle") => RefactorSubcommand::Toggle {
+                path: args
+                    .get(1)
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| ParseError::MissingArgument("path".to_string()))?,
+            },
+            Some("apply") => RefactorSubcommand::Apply,
+            Some("undo") => RefactorSubcommand::Undo,
+            Some("cancel") => RefactorSubcommand::Cancel,
+            Some(other) => {
+                return Err(ParseError::InvalidArgument(format!(
+                    "refactor: {} (expected rename, toggle, apply, undo or cancel)",
+                    other
+                )))
+            }
+        };
+        Ok(SlashCommand::Refactor(subcommand))
+    }
+
     /// Get help for all commands
     pub fn get_all_commands_help() -> Vec<super::types::CommandHelp> {
         use super::types::CommandHelp;
@@ -207,8 +891,33 @@ impl SlashCommandParser {
                 name: "init".to_string(),
                 aliases: vec![],
                 description: "Initialize a new workspace".to_string(),
-                usage: "/init [path]".to_string(),
-                examples: vec!["/init".to_string(), "/init ./myproject".to_string()],
+                usage: "/init [template] [path]".to_string(),
+                examples: vec![
+                    "/init".to_string(),
+                    "/init ./myproject".to_string(),
+                    "/init rust-bin ./myproject".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "templates".to_string(),
+                aliases: vec![],
+                description: "List project templates for /init".to_string(),
+                usage: "/templates".to_string(),
+                examples: vec!["/templates".to_string()],
+            },
+            CommandHelp {
+                name: "keys".to_string(),
+                aliases: vec![],
+                description: "List the active key bindings".to_string(),
+                usage: "/keys".to_string(),
+                examples: vec!["/keys".to_string()],
+            },
+            CommandHelp {
+                name: "loglevel".to_string(),
+                aliases: vec![],
+                description: "Show or set the TUI log level".to_string(),
+                usage: "/loglevel [off|error|warn|info|debug|trace]".to_string(),
+                examples: vec!["/loglevel".to_string(), "/loglevel debug".to_string()],
             },
             CommandHelp {
                 name: "workspace".to_string(),
@@ -219,8 +928,20 @@ impl SlashCommandParser {
                     "/workspace list".to_string(),
                     "/workspace switch myproject".to_string(),
                     "/workspace create myproject /path/to/project".to_string(),
+                    "/workspace dedupe".to_string(),
+                    "/workspace archive myproject".to_string(),
+                    "/workspace archived".to_string(),
+                    "/workspace restore myproject".to_string(),
+                    "/workspace purge myproject --yes".to_string(),
                 ],
             },
+            CommandHelp {
+                name: "activity".to_string(),
+                aliases: vec!["act".to_string()],
+                description: "Show the workspace activity feed".to_string(),
+                usage: "/activity".to_string(),
+                examples: vec!["/activity".to_string()],
+            },
             CommandHelp {
                 name: "model".to_string(),
                 aliases: vec!["m".to_string()],
@@ -229,6 +950,7 @@ impl SlashCommandParser {
                 examples: vec![
                     "/model list".to_string(),
                     "/model set claude-sonnet".to_string(),
+                    "/model set reviewer ollama/qwen2.5-coder".to_string(),
                     "/model current".to_string(),
                 ],
             },
@@ -243,6 +965,216 @@ impl SlashCommandParser {
                     "/theme light".to_string(),
                 ],
             },
+            CommandHelp {
+                name: "stats".to_string(),
+                aliases: vec![],
+                description: "Agent vs. user time statistics".to_string(),
+                usage: "/stats time [days]".to_string(),
+                examples: vec!["/stats time".to_string(), "/stats time 7".to_string()],
+            },
+            CommandHelp {
+                name: "usage".to_string(),
+                aliases: vec![],
+                description: "LLM token usage and cost report".to_string(),
+                usage: "/usage [export [from] [to] [--json]]".to_string(),
+                examples: vec![
+                    "/usage".to_string(),
+                    "/usage export".to_string(),
+                    "/usage export 2026-01-01 2026-01-31".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "approvals".to_string(),
+                aliases: vec!["approve".to_string(), "reject".to_string()],
+                description: "Review agent writes to sensitive files".to_string(),
+                usage: "/approvals | /approve [id] | /reject [id]".to_string(),
+                examples: vec![
+                    "/approvals".to_string(),
+                    "/approve".to_string(),
+                    "/reject 3f9a1c2e".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "trust".to_string(),
+                aliases: vec![],
+                description: "Trust the workspace to run commands and CLI agents".to_string(),
+                usage: "/trust [on|off]".to_string(),
+                examples: vec!["/trust".to_string(), "/trust on".to_string()],
+            },
+            CommandHelp {
+                name: "routing".to_string(),
+                aliases: vec![],
+                description: "Show which providers may receive this workspace's data".to_string(),
+                usage: "/routing [allow <provider> <reason> | revoke <provider>]".to_string(),
+                examples: vec![
+                    "/routing".to_string(),
+                    "/routing allow claude synthetic test data only".to_string(),
+                    "/routing revoke claude".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "verify-audit".to_string(),
+                aliases: vec!["audit".to_string()],
+                description: "Check the signed activity log for tampering or gaps".to_string(),
+                usage: "/verify-audit [export]".to_string(),
+                examples: vec![
+                    "/verify-audit".to_string(),
+                    "/verify-audit export".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "watch".to_string(),
+                aliases: vec![],
+                description: "Run a command or agent when matching files change".to_string(),
+                usage: "/watch [list | add <glob> run|agent <command or prompt> | rm <n>]"
+                    .to_string(),
+                examples: vec![
+                    "/watch add src/**/*.rs run cargo test".to_string(),
+                    "/watch add *.py agent Lint {files} and fix warnings".to_string(),
+                    "/watch rm 1".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "report".to_string(),
+                aliases: vec![],
+                description: "Changelog of what agents changed in this workspace".to_string(),
+                usage: "/report agents [--since <period>]".to_string(),
+                examples: vec![
+                    "/report agents".to_string(),
+                    "/report agents --since 24h".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "gen-tests".to_string(),
+                aliases: vec![],
+                description: "Write tests for a file or function and fix them until they pass"
+                    .to_string(),
+                usage: "/gen-tests <path|symbol> [--attempts <n>]".to_string(),
+                examples: vec![
+                    "/gen-tests src/pager.rs".to_string(),
+                    "/gen-tests parse_since --attempts 5".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "gen-docs".to_string(),
+                aliases: vec![],
+                description: "Draft doc comments for undocumented public items, for review"
+                    .to_string(),
+                usage: "/gen-docs <path> [--check]".to_string(),
+                examples: vec![
+                    "/gen-docs src/pager.rs".to_string(),
+                    "/gen-docs crates/core/src/llm --check".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "refactor".to_string(),
+                aliases: vec![],
+                description: "Rename a symbol across files: preview, pick files, apply, undo"
+                    .to_string(),
+                usage: "/refactor [rename <symbol> <new_name>|toggle <path>|apply|undo|cancel]"
+                    .to_string(),
+                examples: vec![
+                    "/refactor rename parse_config load_config".to_string(),
+                    "/refactor toggle tests/config.rs".to_string(),
+                    "/refactor apply".to_string(),
+                    "/refactor undo".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "fix".to_string(),
+                aliases: vec![],
+                description: "List compiler errors, or have the Developer fix one and rebuild"
+                    .to_string(),
+                usage: "/fix [<n>] [--attempts <n>]".to_string(),
+                examples: vec!["/fix".to_string(), "/fix 2 --attempts 5".to_string()],
+            },
+            CommandHelp {
+                name: "session".to_string(),
+                aliases: vec![],
+                description: "Save or restore the conversation and agent output".to_string(),
+                usage: "/session [list | save [name] | restore [name]]".to_string(),
+                examples: vec![
+                    "/session save refactor".to_string(),
+                    "/session restore".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "recordings".to_string(),
+                aliases: vec!["recs".to_string()],
+                description: "List recordings of CLI agent sessions".to_string(),
+                usage: "/recordings".to_string(),
+                examples: vec!["/recordings".to_string()],
+            },
+            CommandHelp {
+                name: "run-doc".to_string(),
+                aliases: vec![],
+                description: "Save the last Conductor run as a markdown document and open it"
+                    .to_string(),
+                usage: "/run-doc [path]".to_string(),
+                examples: vec!["/run-doc".to_string(), "/run-doc docs/run.md".to_string()],
+            },
+            CommandHelp {
+                name: "review".to_string(),
+                aliases: vec!["queue".to_string()],
+                description: "Review pending approvals, merge conflicts and stalled agents"
+                    .to_string(),
+                usage: "/review".to_string(),
+                examples: vec!["/review".to_string()],
+            },
+            CommandHelp {
+                name: "inspect".to_string(),
+                aliases: vec![],
+                description: "Capture LLM requests and replies (redacted) and show an agent's"
+                    .to_string(),
+                usage: "/inspect [on | off | <agent> [--copy]]".to_string(),
+                examples: vec![
+                    "/inspect on".to_string(),
+                    "/inspect Conductor".to_string(),
+                    "/inspect 3 --copy".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "plan".to_string(),
+                aliases: vec![],
+                description: "Plan a request as steps and run them in turn, or show the plan"
+                    .to_string(),
+                usage: "/plan [<request> | cancel]".to_string(),
+                examples: vec![
+                    "/plan add a --json flag to the CLI".to_string(),
+                    "/plan".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "features".to_string(),
+                aliases: vec![],
+                description: "List or switch the workspace's experimental features".to_string(),
+                usage: "/features [<name> on|off]".to_string(),
+                examples: vec!["/features".to_string(), "/features watch off".to_string()],
+            },
+            CommandHelp {
+                name: "find".to_string(),
+                aliases: vec!["grep".to_string()],
+                description: "Search the workspace's files (skips ignored and binary files)"
+                    .to_string(),
+                usage: "/find [--regex] [--glob <glob>]... [query]".to_string(),
+                examples: vec![
+                    "/find TODO".to_string(),
+                    "/find --regex fn \\w+_test --glob *.rs".to_string(),
+                ],
+            },
+            CommandHelp {
+                name: "replace".to_string(),
+                aliases: vec![],
+                description: "Replace text across the workspace, previewing each file's diff"
+                    .to_string(),
+                usage: "/replace [--regex] [--glob <glob>]... [--yes] <find> <replacement>"
+                    .to_string(),
+                examples: vec![
+                    "/replace old_name new_name --glob *.rs".to_string(),
+                    "/replace --regex foo_(\\w+) bar_$1".to_string(),
+                    "/replace \"old name\" \"new name\"".to_string(),
+                ],
+            },
         ]
     }
 
@@ -254,6 +1186,60 @@ impl SlashCommandParser {
     }
 }
 
+/// The one of `candidates` `word` is most likely a typo of: within one
+/// edit for short words and two for longer ones, or the only candidate it
+/// starts
+fn suggest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let word = word.to_lowercase();
+    let len = word.chars().count();
+    let max = if len <= 4 { 1 } else { 2 };
+    let candidates: Vec<&str> = candidates.into_iter().filter(|c| *c != word).collect();
+    let closest = candidates
+        .iter()
+        .map(|candidate| (edit_distance(&word, candidate), *candidate))
+        .filter(|&(distance, _)| distance <= max && distance < len)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate);
+    closest.or_else(|| {
+        let mut started = candidates
+            .iter()
+            .filter(|c| len >= 3 && c.starts_with(&word));
+        match (started.next(), started.next()) {
+            (Some(only), None) => Some(*only),
+            _ => None,
+        }
+    })
+}
+
+/// Single-char insertions, deletions and substitutions turning `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Single words of an "(expected on or off)" list in an error message
+fn expected(message: &str) -> Vec<&str> {
+    let Some((_, list)) = message.split_once("(expected ") else {
+        return Vec::new();
+    };
+    let list = list.split(')').next().unwrap_or_default();
+    let list = list.strip_prefix("one of ").unwrap_or(list);
+    list.split(", ")
+        .flat_map(|item| item.split(" or "))
+        .filter(|item| !item.is_empty() && !item.contains(' '))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -373,19 +1359,76 @@ mod tests {
     #[test]
     fn test_init_no_path() {
         let result = SlashCommandParser::parse("/init").unwrap().unwrap();
-        assert!(matches!(result, SlashCommand::Init { path: None }));
+        assert!(matches!(
+            result,
+            SlashCommand::Init {
+                template: None,
+                path: None
+            }
+        ));
     }
 
     #[test]
     fn test_init_with_path() {
         let result = SlashCommandParser::parse("/init ./myproject").unwrap().unwrap();
-        if let SlashCommand::Init { path: Some(p) } = result {
+        if let SlashCommand::Init {
+            template: None,
+            path: Some(p),
+        } = result
+        {
             assert_eq!(p, PathBuf::from("./myproject"));
         } else {
             panic!("Expected Init with path");
         }
     }
 
+    #[test]
+    fn test_init_with_template() {
+        let result = SlashCommandParser::parse("/init rust-bin").unwrap().unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Init {
+                template: Some("rust-bin".to_string()),
+                path: None,
+            }
+        );
+        let result = SlashCommandParser::parse("/init next-app web").unwrap().unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Init {
+                template: Some("next-app".to_string()),
+                path: Some(PathBuf::from("web")),
+            }
+        );
+        let result = SlashCommandParser::parse("/templates").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Templates);
+    }
+
+    #[test]
+    fn test_parse_log_level() {
+        let result = SlashCommandParser::parse("/loglevel").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::LogLevel { level: None });
+        assert_eq!(
+            SlashCommandParser::parse("/loglevel Debug").unwrap(),
+            Ok(SlashCommand::LogLevel {
+                level: Some("debug".to_string())
+            })
+        );
+        let error = SlashCommandParser::parse_spanned("/loglevel debgu")
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(error.error, ParseError::InvalidArgument(_)));
+        assert_eq!(error.span, 10..15);
+        assert_eq!(error.suggestion.as_deref(), Some("debug"));
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let result = SlashCommandParser::parse("/keys").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Keys);
+        assert_eq!(result.name(), "keys");
+    }
+
     // ==================== Workspace Commands ====================
 
     #[test]
@@ -451,6 +1494,48 @@ mod tests {
         assert!(matches!(result, Err(ParseError::MissingArgument(_))));
     }
 
+    #[test]
+    fn test_workspace_dedupe() {
+        for input in ["/workspace dedupe", "/ws merge"] {
+            let result = SlashCommandParser::parse(input).unwrap().unwrap();
+            assert_eq!(result, SlashCommand::Workspace(WorkspaceSubcommand::Dedupe));
+        }
+    }
+
+    #[test]
+    fn test_workspace_archive_restore_purge() {
+        let parse = |input| SlashCommandParser::parse(input).unwrap().unwrap();
+        let archive = SlashCommand::Workspace(WorkspaceSubcommand::Archive { id: "api".into() });
+        assert_eq!(parse("/workspace archive api"), archive);
+        assert_eq!(parse("/ws delete api"), archive);
+        assert_eq!(
+            parse("/ws restore api"),
+            SlashCommand::Workspace(WorkspaceSubcommand::Restore { id: "api".into() })
+        );
+        assert_eq!(
+            parse("/ws archived"),
+            SlashCommand::Workspace(WorkspaceSubcommand::Archived)
+        );
+        assert_eq!(
+            parse("/ws purge api"),
+            SlashCommand::Workspace(WorkspaceSubcommand::Purge {
+                id: "api".into(),
+                confirm: false
+            })
+        );
+        assert_eq!(
+            parse("/ws purge api --yes"),
+            SlashCommand::Workspace(WorkspaceSubcommand::Purge {
+                id: "api".into(),
+                confirm: true
+            })
+        );
+        assert!(matches!(
+            SlashCommandParser::parse("/ws purge").unwrap(),
+            Err(ParseError::MissingArgument(_))
+        ));
+    }
+
     #[test]
     fn test_ws_alias() {
         let result = SlashCommandParser::parse("/ws list").unwrap().unwrap();
@@ -460,6 +1545,16 @@ mod tests {
         ));
     }
 
+    // ==================== Activity Command ====================
+
+    #[test]
+    fn test_activity_command() {
+        for input in ["/activity", "/act"] {
+            let result = SlashCommandParser::parse(input).unwrap().unwrap();
+            assert!(matches!(result, SlashCommand::Activity));
+        }
+    }
+
     // ==================== Model Commands ====================
 
     #[test]
@@ -489,6 +1584,36 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_model_set_role() {
+        let result = SlashCommandParser::parse("/model set shell-summarizer ollama/gemma3:4b")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Model(ModelSubcommand::Assign {
+                role: AgentRole::ShellSummarizer,
+                provider: "ollama".to_string(),
+                model: "gemma3:4b".to_string(),
+            })
+        );
+
+        // Only the first slash separates the provider from the model
+        let result = SlashCommandParser::parse("/model set reviewer openrouter/meta/llama-3")
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            result,
+            SlashCommand::Model(ModelSubcommand::Assign { provider, model, .. })
+                if provider == "openrouter" && model == "meta/llama-3"
+        ));
+
+        let result = SlashCommandParser::parse("/model set reviewer gpt-4o").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+        let result = SlashCommandParser::parse("/model set developer").unwrap();
+        assert!(matches!(result, Err(ParseError::MissingArgument(_))));
+    }
+
     #[test]
     fn test_model_set_missing() {
         let result = SlashCommandParser::parse("/model set").unwrap();
@@ -560,6 +1685,491 @@ mod tests {
         ));
     }
 
+    // ==================== Stats Commands ====================
+
+    #[test]
+    fn test_stats_time() {
+        let result = SlashCommandParser::parse("/stats").unwrap().unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Stats(StatsSubcommand::Time { days: None })
+        );
+
+        let result = SlashCommandParser::parse("/stats time 7").unwrap().unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Stats(StatsSubcommand::Time { days: Some(7) })
+        );
+
+        let result = SlashCommandParser::parse("/stats time week").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+    }
+
+    // ==================== Usage Commands ====================
+
+    #[test]
+    fn test_usage_export() {
+        let result = SlashCommandParser::parse("/usage").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Usage(UsageSubcommand::Summary));
+
+        let result = SlashCommandParser::parse("/usage export 2026-01-01 --json")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Usage(UsageSubcommand::Export {
+                from: Some("2026-01-01".to_string()),
+                to: None,
+                json: true,
+            })
+        );
+    }
+
+    // ==================== Approval Commands ====================
+
+    #[test]
+    fn test_approval_commands() {
+        let result = SlashCommandParser::parse("/approvals").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Approvals(ApprovalSubcommand::List));
+
+        let result = SlashCommandParser::parse("/approve 3f9a1c2e").unwrap().unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Approvals(ApprovalSubcommand::Approve {
+                id: Some("3f9a1c2e".to_string())
+            })
+        );
+
+        let result = SlashCommandParser::parse("/reject").unwrap().unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Approvals(ApprovalSubcommand::Reject { id: None })
+        );
+    }
+
+    // ==================== Trust Commands ====================
+
+    #[test]
+    fn test_trust_command() {
+        let result = SlashCommandParser::parse("/trust").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Trust { trusted: None });
+
+        let result = SlashCommandParser::parse("/trust off").unwrap().unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Trust {
+                trusted: Some(false)
+            }
+        );
+
+        let result = SlashCommandParser::parse("/trust maybe").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+    }
+
+    // ==================== Routing Commands ====================
+
+    #[test]
+    fn test_routing_command() {
+        let result = SlashCommandParser::parse("/routing").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Routing(RoutingSubcommand::Show));
+
+        let result = SlashCommandParser::parse("/routing allow Claude public docs only")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Routing(RoutingSubcommand::Allow {
+                provider: "claude".to_string(),
+                reason: "public docs only".to_string(),
+            })
+        );
+
+        let result = SlashCommandParser::parse("/routing allow claude").unwrap();
+        assert!(matches!(result, Err(ParseError::MissingArgument(_))));
+
+        let result = SlashCommandParser::parse("/routing revoke").unwrap();
+        assert!(matches!(result, Err(ParseError::MissingArgument(_))));
+    }
+
+    // ==================== Audit Commands ====================
+
+    #[test]
+    fn test_verify_audit_command() {
+        let result = SlashCommandParser::parse("/verify-audit").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::VerifyAudit { export: false });
+
+        let result = SlashCommandParser::parse("/audit export").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::VerifyAudit { export: true });
+
+        let result = SlashCommandParser::parse("/verify-audit now").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+    }
+
+    // ==================== Watch Commands ====================
+
+    #[test]
+    fn test_watch_command() {
+        let result = SlashCommandParser::parse("/watch").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Watch(WatchSubcommand::List));
+
+        let result = SlashCommandParser::parse("/watch add src/**/*.rs run cargo test --lib")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Watch(WatchSubcommand::Add {
+                pattern: "src/**/*.rs".to_string(),
+                action: WatchAction::Run {
+                    command: "cargo test --lib".to_string(),
+                },
+            })
+        );
+
+        let result = SlashCommandParser::parse("/watch add *.py agent Lint {files}")
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            result,
+            SlashCommand::Watch(WatchSubcommand::Add {
+                action: WatchAction::Agent { .. },
+                ..
+            })
+        ));
+
+        let result = SlashCommandParser::parse("/watch rm 2").unwrap().unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Watch(WatchSubcommand::Remove { number: 2 })
+        );
+
+        let result = SlashCommandParser::parse("/watch add *.rs run").unwrap();
+        assert!(matches!(result, Err(ParseError::MissingArgument(_))));
+        let result = SlashCommandParser::parse("/watch add *.rs lint x").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+        let result = SlashCommandParser::parse("/watch rm first").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+    }
+
+    // ==================== Report Commands ====================
+
+    #[test]
+    fn test_report_command() {
+        let result = SlashCommandParser::parse("/report agents").unwrap().unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Report(ReportSubcommand::Agents {
+                since: "7d".to_string()
+            })
+        );
+
+        let result = SlashCommandParser::parse("/report agents --since 2W")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Report(ReportSubcommand::Agents {
+                since: "2w".to_string()
+            })
+        );
+
+        let result = SlashCommandParser::parse("/report agents --since").unwrap();
+        assert!(matches!(result, Err(ParseError::MissingArgument(_))));
+        let result = SlashCommandParser::parse("/report agents --since soon").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+        let result = SlashCommandParser::parse("/report users").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+    }
+
+    // ==================== Test Generation Commands ====================
+
+    #[test]
+    fn test_gen_tests_command() {
+        let result = SlashCommandParser::parse("/gen-tests src/Pager.rs")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::GenTests {
+                target: "src/Pager.rs".to_string(),
+                attempts: DEFAULT_ATTEMPTS
+            }
+        );
+
+        let result = SlashCommandParser::parse("/gen-tests --attempts 5 parse_since")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::GenTests {
+                target: "parse_since".to_string(),
+                attempts: 5
+            }
+        );
+
+        let result = SlashCommandParser::parse("/gen-tests").unwrap();
+        assert!(matches!(result, Err(ParseError::MissingArgument(_))));
+        let result = SlashCommandParser::parse("/gen-tests a.rs --attempts 0").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+        let result = SlashCommandParser::parse("/gen-tests a.rs b.rs").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_gen_docs_command() {
+        let result = SlashCommandParser::parse("/gen-docs src/llm --check")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::GenDocs {
+                path: PathBuf::from("src/llm"),
+                check: true
+            }
+        );
+
+        let result = SlashCommandParser::parse("/gen-docs").unwrap();
+        assert!(matches!(result, Err(ParseError::MissingArgument(_))));
+        let result = SlashCommandParser::parse("/gen-docs a.rs b.rs").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_fix_command() {
+        let result = SlashCommandParser::parse("/fix").unwrap().unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Fix {
+                problem: None,
+                attempts: DEFAULT_ATTEMPTS
+            }
+        );
+
+        let result = SlashCommandParser::parse("/fix 2 --attempts 5")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Fix {
+                problem: Some(2),
+                attempts: 5
+            }
+        );
+
+        let result = SlashCommandParser::parse("/fix 0").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+        let result = SlashCommandParser::parse("/fix 1 2").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_session_command() {
+        let result = SlashCommandParser::parse("/session").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Session(SessionSubcommand::List));
+
+        let result = SlashCommandParser::parse("/session save work")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Session(SessionSubcommand::Save {
+                name: Some("work".to_string())
+            })
+        );
+
+        let result = SlashCommandParser::parse("/session restore")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Session(SessionSubcommand::Restore { name: None })
+        );
+
+        let result = SlashCommandParser::parse("/session drop").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_inspect_command() {
+        let parse = |line: &str| SlashCommandParser::parse(line).unwrap();
+        assert_eq!(
+            parse("/inspect on").unwrap(),
+            SlashCommand::Inspect(InspectSubcommand::On)
+        );
+        assert_eq!(
+            parse("/inspect").unwrap(),
+            SlashCommand::Inspect(InspectSubcommand::Show {
+                agent: None,
+                copy: false
+            })
+        );
+        assert_eq!(
+            parse("/inspect 3 --copy").unwrap(),
+            SlashCommand::Inspect(InspectSubcommand::Show {
+                agent: Some("3".to_string()),
+                copy: true
+            })
+        );
+        assert!(matches!(
+            parse("/inspect on --copy"),
+            Err(ParseError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            parse("/inspect Shell Search"),
+            Err(ParseError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_doc_command() {
+        let result = SlashCommandParser::parse("/run-doc").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::RunDoc { path: None });
+
+        let result = SlashCommandParser::parse("/run-doc docs/run.md")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::RunDoc {
+                path: Some(PathBuf::from("docs/run.md"))
+            }
+        );
+    }
+
+    #[test]
+    fn test_recordings_command() {
+        let result = SlashCommandParser::parse("/recordings").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Recordings);
+        let result = SlashCommandParser::parse("/recs").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Recordings);
+    }
+
+    #[test]
+    fn test_review_command() {
+        let result = SlashCommandParser::parse("/review").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Review);
+        let result = SlashCommandParser::parse("/queue").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Review);
+    }
+
+    #[test]
+    fn test_plan_command() {
+        let result = SlashCommandParser::parse("/plan").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Plan(PlanSubcommand::Show));
+        let result = SlashCommandParser::parse("/plan cancel").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Plan(PlanSubcommand::Cancel));
+
+        let result = SlashCommandParser::parse("/plan add a  --json flag")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Plan(PlanSubcommand::Start {
+                request: "add a --json flag".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_find_command() {
+        let result = SlashCommandParser::parse("/find").unwrap().unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Find {
+                query: None,
+                regex: false,
+                globs: vec![],
+            }
+        );
+        let result = SlashCommandParser::parse("/grep -r fn \\w+ --glob *.rs -g !tests/**")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Find {
+                query: Some("fn \\w+".to_string()),
+                regex: true,
+                globs: vec!["*.rs".to_string(), "!tests/**".to_string()],
+            }
+        );
+        assert!(SlashCommandParser::parse("/find --glob").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_replace_command() {
+        let result = SlashCommandParser::parse("/replace -g *.rs old_name new name --yes")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Replace {
+                query: "old_name".to_string(),
+                replacement: "new name".to_string(),
+                regex: false,
+                globs: vec!["*.rs".to_string()],
+                apply: true,
+            }
+        );
+        assert!(SlashCommandParser::parse("/replace old_name").unwrap().is_err());
+        assert!(SlashCommandParser::parse("/replace").unwrap().is_err());
+        // --yes only means something to /replace
+        let result = SlashCommandParser::parse("/find --yes").unwrap().unwrap();
+        assert!(matches!(result, SlashCommand::Find { query: Some(q), .. } if q == "--yes"));
+    }
+
+    #[test]
+    fn test_features_command() {
+        let result = SlashCommandParser::parse("/features").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Features(FeaturesSubcommand::List));
+        let result = SlashCommandParser::parse("/features File-Watcher off")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Features(FeaturesSubcommand::Set {
+                name: "file_watcher".to_string(),
+                enabled: false,
+            })
+        );
+        assert!(SlashCommandParser::parse("/features teleport on")
+            .unwrap()
+            .is_err());
+        assert!(SlashCommandParser::parse("/features watch maybe")
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn test_refactor_command() {
+        let result = SlashCommandParser::parse("/refactor rename parse_config load_config")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Refactor(RefactorSubcommand::Rename {
+                symbol: "parse_config".to_string(),
+                new_name: "load_config".to_string()
+            })
+        );
+        let result = SlashCommandParser::parse("/refactor toggle src/a.rs")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            SlashCommand::Refactor(RefactorSubcommand::Toggle {
+                path: "src/a.rs".to_string()
+            })
+        );
+        let result = SlashCommandParser::parse("/refactor").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Refactor(RefactorSubcommand::Show));
+        let result = SlashCommandParser::parse("/refactor undo").unwrap().unwrap();
+        assert_eq!(result, SlashCommand::Refactor(RefactorSubcommand::Undo));
+
+        let result = SlashCommandParser::parse("/refactor rename only").unwrap();
+        assert!(matches!(result, Err(ParseError::MissingArgument(_))));
+        let result = SlashCommandParser::parse("/refactor rewrite").unwrap();
+        assert!(matches!(result, Err(ParseError::InvalidArgument(_))));
+    }
+
     // ==================== Custom Commands ====================
 
     #[test]
@@ -609,4 +2219,85 @@ mod tests {
         assert!(help.is_some());
         assert_eq!(help.unwrap().name, "exit");
     }
+
+    // ==================== Quoting, Errors and Suggestions ====================
+
+    fn error(input: &str) -> SlashError {
+        SlashCommandParser::parse_spanned(input)
+            .unwrap()
+            .unwrap_err()
+    }
+
+    #[test]
+    fn test_quoted_arguments() {
+        let result = SlashCommandParser::parse(r#"/replace "old name" 'new "name"'"#)
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            result,
+            SlashCommand::Replace { query, replacement, .. }
+                if query == "old name" && replacement == r#"new "name""#
+        ));
+        let result = SlashCommandParser::parse(r"/workspace create docs C:\My\ Docs")
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            result,
+            SlashCommand::Workspace(WorkspaceSubcommand::Create { path, .. })
+                if path.as_path() == std::path::Path::new(r"C:\My Docs")
+        ));
+    }
+
+    #[test]
+    fn test_error_spans_and_suggestions() {
+        let e = error("  /wrkspace list");
+        assert_eq!(e.error, ParseError::UnknownCommand("wrkspace".to_string()));
+        assert_eq!(e.span, 3..11);
+        assert_eq!(e.suggestion.as_deref(), Some("/workspace"));
+        assert_eq!(
+            e.to_string(),
+            "Unknown command: wrkspace (did you mean /workspace?)"
+        );
+        assert_eq!(error("/sett").suggestion.as_deref(), Some("/settings"));
+
+        let e = error("/trust onn");
+        assert_eq!(e.span, 7..10);
+        assert_eq!(e.suggestion.as_deref(), Some("on"));
+        assert_eq!(error("/workspace sw1tch x").span, 11..17);
+
+        // Where the missing argument should go
+        assert_eq!(error("/workspace create docs ").span, 22..22);
+        assert_eq!(error(r#"/find "fn main"#).error, ParseError::UnclosedQuote);
+        assert_eq!(error(r#"/find "fn main"#).span, 6..14);
+        assert_eq!(error("/").span, 0..1);
+
+        // Every command and alias still parses as itself
+        for help in SlashCommandParser::get_all_commands_help() {
+            for name in std::iter::once(&help.name).chain(&help.aliases) {
+                let result = SlashCommandParser::parse(&format!("/{}", name)).unwrap();
+                let unknown = matches!(result, Err(ParseError::UnknownCommand(_)));
+                assert!(!unknown, "/{}", name);
+            }
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_parse_never_panics(input in r#"[ /a-z0-9\-\\"'@.]{0,32}"#) {
+            if let Some(Err(e)) = SlashCommandParser::parse_spanned(&input) {
+                proptest::prop_assert!(e.span.start <= e.span.end && e.span.end <= input.len());
+                proptest::prop_assert!(!e.to_string().is_empty());
+            }
+        }
+
+        #[test]
+        fn prop_commands_ignore_extra_spaces(
+            words in proptest::collection::vec("[a-z0-9]{1,6}", 1..5),
+            pad in "[ \t]{1,3}",
+        ) {
+            let plain = SlashCommandParser::parse(&format!("/{}", words.join(" ")));
+            let padded = SlashCommandParser::parse(&format!("{pad}/{}{pad}", words.join(&pad)));
+            proptest::prop_assert_eq!(plain, padded);
+        }
+    }
 }
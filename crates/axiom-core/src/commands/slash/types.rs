@@ -79,6 +79,47 @@ pub enum UiAction {
     /// Open the workspace selector
     OpenWorkspaceSelector,
 
+    /// Open the activity feed of the active workspace
+    OpenActivityFeed,
+
+    /// Open the review queue of the active workspace
+    OpenReviewQueue,
+
+    /// Open the search panel, running `query` if given
+    OpenSearch {
+        /// Text to find
+        query: Option<String>,
+        /// Treat the query as a regular expression
+        regex: bool,
+        /// Globs limiting the files searched
+        globs: Vec<String>,
+    },
+
+    /// Preview a workspace-wide replace for confirmation
+    OpenReplace {
+        /// Text to find
+        query: String,
+        /// Replacement
+        replacement: String,
+        /// Treat the query as a regular expression
+        regex: bool,
+        /// Globs limiting the files changed
+        globs: Vec<String>,
+        /// Apply without waiting for confirmation (`--yes`)
+        apply: bool,
+    },
+
+    /// Open the directory picker (e.g. `/init` without a path)
+    OpenDirectoryPicker,
+
+    /// The directory given already has a workspace: offer to open it
+    OfferExistingWorkspace {
+        /// ID of the existing workspace
+        id: String,
+        /// Its name
+        name: String,
+    },
+
     /// Clear the output panel
     ClearOutput,
 
@@ -3,8 +3,11 @@
 //! Defines configuration for external CLI coding agents like Claude Code,
 //! Gemini CLI, GitHub Copilot, etc. that can be invoked from Axiom.
 
+use super::default_true;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Path, PathBuf};
 
 /// Configuration for a single CLI agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,10 +27,41 @@ pub struct CliAgentConfig {
     #[serde(default = "default_true")]
     pub use_cwd: bool,
 
+    /// Subdirectory of the workspace to start in (e.g. `frontend`)
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
+
     /// Additional environment variables to set
     #[serde(default)]
     pub env: HashMap<String, String>,
 
+    /// Directories to put in front of `PATH`; relative ones are in the
+    /// agent's working directory (e.g. `node_modules/.bin`)
+    #[serde(default)]
+    pub path: Vec<PathBuf>,
+
+    /// Shell lines run by bash before the agent starts, such as
+    /// `source .venv/bin/activate` or `source ~/.nvm/nvm.sh && nvm use`
+    ///
+    /// The agent only starts if every line succeeds, and inherits the
+    /// environment they leave.
+    #[serde(default)]
+    pub activate: Vec<String>,
+
+    /// Parsers turning the agent's output into structured events
+    #[serde(default)]
+    pub parsers: Vec<OutputParserConfig>,
+
+    /// When set, the prompt is typed into the agent's REPL once it is ready
+    /// instead of passed on the command line
+    #[serde(default)]
+    pub ready: Option<ReadinessConfig>,
+
+    /// When set, sessions are recorded as asciicast files under
+    /// `.axiom/recordings/`
+    #[serde(default)]
+    pub record: Option<RecordingConfig>,
+
     /// Whether this agent is enabled
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -37,8 +71,146 @@ pub struct CliAgentConfig {
     pub icon: String,
 }
 
-fn default_true() -> bool {
-    true
+/// A parser for CLI agent output: a regex matched against each line, or a
+/// script reading the lines on stdin and printing JSON events on stdout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputParserConfig {
+    /// Regex matched against each line, escape sequences removed; named
+    /// groups fill in the event (`path`, `command`, `message`, `percent`,
+    /// `cost`, `input_tokens`, `output_tokens`)
+    #[serde(default)]
+    pub regex: Option<String>,
+
+    /// What a regex match means (progress when unset)
+    #[serde(default)]
+    pub event: Option<ParserEvent>,
+
+    /// Command run by `sh -c` for the agent's lifetime, printing one event
+    /// per line such as `{"kind": "file_changed", "path": "src/lib.rs"}`
+    #[serde(default)]
+    pub script: Option<String>,
+}
+
+/// Kind of event a regex parser reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParserEvent {
+    FileChanged,
+    CommandRun,
+    Progress,
+    Cost,
+}
+
+/// How to tell a CLI agent's REPL is ready for its prompt
+///
+/// Steps are answered in order, then the agent is ready once `pattern`
+/// matches and `delay_ms` has passed. With neither, only the delay is
+/// waited out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadinessConfig {
+    /// Regex matched against the text on the agent's screen
+    #[serde(default)]
+    pub pattern: Option<String>,
+
+    /// Milliseconds to wait before typing, after `pattern` matches if set
+    #[serde(default)]
+    pub delay_ms: u64,
+
+    /// Screens to answer on the way, such as a trust-this-folder question
+    #[serde(default)]
+    pub steps: Vec<ExpectStep>,
+
+    /// Seconds to wait for the agent before giving up on the prompt
+    #[serde(default = "default_ready_timeout")]
+    pub timeout_secs: u64,
+
+    /// Milliseconds between typed characters; 0 sends the prompt at once
+    #[serde(default)]
+    pub type_delay_ms: u64,
+
+    /// Sent after the prompt to submit it
+    #[serde(default = "default_submit")]
+    pub submit: String,
+}
+
+/// Input to send once a regex matches the agent's screen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpectStep {
+    /// Regex matched against the text on the agent's screen
+    pub expect: String,
+
+    /// Typed when it matches (e.g. `"1\r"`)
+    pub send: String,
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        Self {
+            pattern: None,
+            delay_ms: 0,
+            steps: Vec::new(),
+            timeout_secs: default_ready_timeout(),
+            type_delay_ms: 0,
+            submit: default_submit(),
+        }
+    }
+}
+
+/// Limits on an agent's asciicast recordings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordingConfig {
+    /// Bytes a recording may grow to; output after that isn't recorded
+    #[serde(default = "default_max_recording_bytes")]
+    pub max_bytes: u64,
+
+    /// Recordings of this agent kept; the oldest are deleted as new ones
+    /// start
+    #[serde(default = "default_keep_recordings")]
+    pub keep: usize,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: default_max_recording_bytes(),
+            keep: default_keep_recordings(),
+        }
+    }
+}
+
+fn default_max_recording_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_keep_recordings() -> usize {
+    20
+}
+
+fn default_ready_timeout() -> u64 {
+    30
+}
+
+fn default_submit() -> String {
+    "\r".to_string()
+}
+
+/// Built-in output parsers for a known CLI agent
+pub fn default_parsers(agent_id: &str) -> Vec<OutputParserConfig> {
+    let rule = |regex: &str, event| OutputParserConfig {
+        regex: Some(regex.to_string()),
+        event: Some(event),
+        script: None,
+    };
+    match agent_id {
+        "aider" => vec![
+            rule(r"^Applied edit to (?P<path>.+)$", ParserEvent::FileChanged),
+            rule(
+                r"^Tokens: (?P<input_tokens>[\d.,]+[km]?) sent.*?, (?P<output_tokens>[\d.,]+[km]?) received\. Cost: \$(?P<cost>[\d.,]+) message",
+                ParserEvent::Cost,
+            ),
+        ],
+        _ => Vec::new(),
+    }
 }
 
 fn default_icon() -> String {
@@ -52,25 +224,105 @@ impl Default for CliAgentConfig {
             command: String::new(),
             default_args: Vec::new(),
             use_cwd: true,
+            working_dir: None,
             env: HashMap::new(),
+            path: Vec::new(),
+            activate: Vec::new(),
+            parsers: Vec::new(),
+            ready: None,
+            record: None,
             enabled: true,
             icon: default_icon(),
         }
     }
 }
 
+impl CliAgentConfig {
+    /// Where the agent starts for a `workspace`: the workspace itself, or
+    /// `working_dir` inside it; None when `use_cwd` is off
+    ///
+    /// Fails if `working_dir` is absolute or climbs out of the workspace.
+    pub fn start_dir(&self, workspace: &Path) -> Result<Option<PathBuf>, String> {
+        if !self.use_cwd {
+            return Ok(None);
+        }
+        let Some(sub) = &self.working_dir else {
+            return Ok(Some(workspace.to_path_buf()));
+        };
+        if !sub.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+            return Err(format!(
+                "working_dir must be a subdirectory of the workspace: {}",
+                sub.display()
+            ));
+        }
+        Ok(Some(workspace.join(sub)))
+    }
+
+    /// `PATH` with the `path` entries in front of `inherited`, or None when
+    /// there are no entries
+    pub fn search_path(&self, dir: &Path, inherited: Option<&OsStr>) -> Option<OsString> {
+        if self.path.is_empty() {
+            return None;
+        }
+        let entries = self.path.iter().map(|entry| dir.join(entry));
+        let inherited = inherited.map(std::env::split_paths).into_iter().flatten();
+        std::env::join_paths(entries.chain(inherited)).ok()
+    }
+
+    /// Program and arguments to run for `prompt`
+    ///
+    /// The prompt is left out when it is typed in once the agent is ready.
+    /// With `activate` lines the agent runs under `bash -c`, which runs them
+    /// and then `exec`s the agent.
+    pub fn launch(&self, prompt: &str) -> (String, Vec<String>) {
+        let mut args = self.default_args.clone();
+        if !prompt.is_empty() && self.ready.is_none() {
+            args.push(prompt.to_string());
+        }
+        if self.activate.is_empty() {
+            return (self.command.clone(), args);
+        }
+
+        let script = format!("{} && exec \"$@\"", self.activate.join(" && "));
+        let mut bash_args = vec![
+            "-c".to_string(),
+            script,
+            // $0 for the script
+            "axiom-activate".to_string(),
+            self.command.clone(),
+        ];
+        bash_args.extend(args);
+        ("bash".to_string(), bash_args)
+    }
+}
+
 /// Collection of CLI agent configurations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CliAgentsConfig {
     /// Map of agent ID to configuration
     #[serde(default = "default_cli_agents")]
     pub agents: HashMap<String, CliAgentConfig>,
+
+    /// Lines of each agent's output kept as its transcript, past what
+    /// fits on its screen
+    #[serde(default = "default_scrollback_lines")]
+    pub scrollback_lines: usize,
+
+    /// Agent `#` starts when none is named
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+fn default_scrollback_lines() -> usize {
+    crate::agents::DEFAULT_SCROLLBACK_LINES
 }
 
 impl Default for CliAgentsConfig {
     fn default() -> Self {
         Self {
             agents: default_cli_agents(),
+            scrollback_lines: default_scrollback_lines(),
+            default: None,
         }
     }
 }
@@ -90,6 +342,15 @@ impl CliAgentsConfig {
     pub fn is_available(&self, id: &str) -> bool {
         self.agents.get(id).map(|c| c.enabled).unwrap_or(false)
     }
+
+    /// `id`, or the default agent when it's empty
+    pub fn agent_or_default<'a>(&'a self, id: &'a str) -> Option<&'a str> {
+        if id.is_empty() {
+            self.default.as_deref()
+        } else {
+            Some(id)
+        }
+    }
 }
 
 /// Create default CLI agent configurations
@@ -104,7 +365,13 @@ pub fn default_cli_agents() -> HashMap<String, CliAgentConfig> {
             command: "claude".to_string(),
             default_args: vec![],
             use_cwd: true,
+            working_dir: None,
             env: HashMap::new(),
+            path: Vec::new(),
+            activate: Vec::new(),
+            parsers: Vec::new(),
+            ready: None,
+            record: None,
             enabled: true,
             icon: "🤖".to_string(),
         },
@@ -118,7 +385,13 @@ pub fn default_cli_agents() -> HashMap<String, CliAgentConfig> {
             command: "gemini".to_string(),
             default_args: vec![],
             use_cwd: true,
+            working_dir: None,
             env: HashMap::new(),
+            path: Vec::new(),
+            activate: Vec::new(),
+            parsers: Vec::new(),
+            ready: None,
+            record: None,
             enabled: true,
             icon: "💎".to_string(),
         },
@@ -132,7 +405,13 @@ pub fn default_cli_agents() -> HashMap<String, CliAgentConfig> {
             command: "gh".to_string(),
             default_args: vec!["copilot".to_string(), "suggest".to_string()],
             use_cwd: true,
+            working_dir: None,
             env: HashMap::new(),
+            path: Vec::new(),
+            activate: Vec::new(),
+            parsers: Vec::new(),
+            ready: None,
+            record: None,
             enabled: true,
             icon: "🐙".to_string(),
         },
@@ -146,7 +425,13 @@ pub fn default_cli_agents() -> HashMap<String, CliAgentConfig> {
             command: "opencode".to_string(),
             default_args: vec![],
             use_cwd: true,
+            working_dir: None,
             env: HashMap::new(),
+            path: Vec::new(),
+            activate: Vec::new(),
+            parsers: Vec::new(),
+            ready: None,
+            record: None,
             enabled: true,
             icon: "🔓".to_string(),
         },
@@ -160,7 +445,13 @@ pub fn default_cli_agents() -> HashMap<String, CliAgentConfig> {
             command: "aider".to_string(),
             default_args: vec![],
             use_cwd: true,
+            working_dir: None,
             env: HashMap::new(),
+            path: Vec::new(),
+            activate: Vec::new(),
+            parsers: default_parsers("aider"),
+            ready: None,
+            record: None,
             enabled: true,
             icon: "🔧".to_string(),
         },
@@ -185,6 +476,71 @@ mod tests {
         assert!(claude.enabled);
     }
 
+    #[test]
+    fn test_start_dir() {
+        let workspace = Path::new("/work");
+        let mut config = CliAgentConfig::default();
+        assert_eq!(config.start_dir(workspace).unwrap(), Some(PathBuf::from("/work")));
+
+        config.working_dir = Some(PathBuf::from("web/app"));
+        assert_eq!(
+            config.start_dir(workspace).unwrap(),
+            Some(PathBuf::from("/work/web/app"))
+        );
+
+        config.working_dir = Some(PathBuf::from("../other"));
+        assert!(config.start_dir(workspace).is_err());
+        config.working_dir = Some(PathBuf::from("/etc"));
+        assert!(config.start_dir(workspace).is_err());
+
+        config.use_cwd = false;
+        assert_eq!(config.start_dir(workspace).unwrap(), None);
+    }
+
+    #[test]
+    fn test_search_path() {
+        let mut config = CliAgentConfig::default();
+        assert_eq!(config.search_path(Path::new("/work"), None), None);
+
+        config.path = vec![PathBuf::from("node_modules/.bin"), PathBuf::from("/opt/bin")];
+        let path = config
+            .search_path(Path::new("/work"), Some(OsStr::new("/usr/bin")))
+            .unwrap();
+        let entries: Vec<_> = std::env::split_paths(&path).collect();
+        assert_eq!(
+            entries,
+            vec![
+                PathBuf::from("/work/node_modules/.bin"),
+                PathBuf::from("/opt/bin"),
+                PathBuf::from("/usr/bin"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_launch() {
+        let mut config = CliAgentConfig {
+            command: "aider".to_string(),
+            default_args: vec!["--yes".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            config.launch("fix it"),
+            ("aider".to_string(), vec!["--yes".to_string(), "fix it".to_string()])
+        );
+
+        // A prompt typed in once the agent is ready stays off the command line
+        config.ready = Some(ReadinessConfig::default());
+        assert_eq!(config.launch("fix it").1, ["--yes"]);
+        config.ready = None;
+
+        config.activate = vec!["source .venv/bin/activate".to_string()];
+        let (program, args) = config.launch("");
+        assert_eq!(program, "bash");
+        assert_eq!(args[1], "source .venv/bin/activate && exec \"$@\"");
+        assert_eq!(&args[3..], ["aider", "--yes"]);
+    }
+
     #[test]
     fn test_cli_agents_config() {
         let config = CliAgentsConfig::default();
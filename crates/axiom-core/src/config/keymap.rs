@@ -0,0 +1,626 @@
+//! Key bindings (`[keys]`)
+//!
+//! The actions that work across the UI — quitting, opening the model
+//! selector, moving focus — are bound to chords such as `ctrl+m` or
+//! `shift+tab`. Binding an action replaces its default chords; an empty
+//! list unbinds it. A panel's table rebinds actions only while that panel
+//! has focus:
+//!
+//! ```toml
+//! [keys]
+//! model_selector = "ctrl+o"
+//! search = ["ctrl+f", "alt+f"]
+//!
+//! [keys.panels.file_tree]
+//! quit = []
+//! ```
+//!
+//! A chord is any of `ctrl+`, `alt+` and `shift+` followed by a key: a
+//! character, `space`, `tab`, `enter`, `esc`, `backspace`, `delete`,
+//! `insert`, an arrow, `home`, `end`, `pageup`, `pagedown` or `f1`–`f12`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Panels that can have bindings of their own
+pub const PANELS: &[&str] = &["file_tree", "output", "input", "agents"];
+
+/// Something a key binding does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    ModelSelector,
+    Settings,
+    ToggleTheme,
+    WorkspaceSelector,
+    Search,
+    FocusNext,
+    FocusPrev,
+    NormalMode,
+    InsertMode,
+}
+
+impl Action {
+    /// Every action, in the order `/keys` lists them
+    pub const ALL: [Action; 10] = [
+        Action::Quit,
+        Action::ModelSelector,
+        Action::Settings,
+        Action::ToggleTheme,
+        Action::WorkspaceSelector,
+        Action::Search,
+        Action::FocusNext,
+        Action::FocusPrev,
+        Action::NormalMode,
+        Action::InsertMode,
+    ];
+
+    /// Name used in the config
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::ModelSelector => "model_selector",
+            Action::Settings => "settings",
+            Action::ToggleTheme => "toggle_theme",
+            Action::WorkspaceSelector => "workspace_selector",
+            Action::Search => "search",
+            Action::FocusNext => "focus_next",
+            Action::FocusPrev => "focus_prev",
+            Action::NormalMode => "normal_mode",
+            Action::InsertMode => "insert_mode",
+        }
+    }
+
+    /// What the action does, for `/keys`
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit (in normal mode)",
+            Action::ModelSelector => "Open the model selector",
+            Action::Settings => "Open settings",
+            Action::ToggleTheme => "Switch between dark and light themes",
+            Action::WorkspaceSelector => "Open the workspace selector",
+            Action::Search => "Search the workspace",
+            Action::FocusNext => "Focus the next panel",
+            Action::FocusPrev => "Focus the previous panel",
+            Action::NormalMode => "Return to normal mode",
+            Action::InsertMode => "Enter insert mode",
+        }
+    }
+
+    /// Chords the action has unless the config binds it
+    pub fn default_chords(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::ModelSelector => &["ctrl+m"],
+            Action::Settings => &["ctrl+,"],
+            Action::ToggleTheme => &["ctrl+t"],
+            Action::WorkspaceSelector => &["ctrl+w"],
+            Action::Search => &["ctrl+f"],
+            Action::FocusNext => &["tab"],
+            Action::FocusPrev => &["shift+tab"],
+            Action::NormalMode => &["esc"],
+            Action::InsertMode => &["i"],
+        }
+    }
+
+    /// The action called `name` in the config
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// A key, without modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Esc,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    F(u8),
+}
+
+const NAMED_KEYS: &[(&str, Key)] = &[
+    ("space", Key::Char(' ')),
+    ("enter", Key::Enter),
+    ("esc", Key::Esc),
+    ("tab", Key::Tab),
+    ("backspace", Key::Backspace),
+    ("delete", Key::Delete),
+    ("insert", Key::Insert),
+    ("up", Key::Up),
+    ("down", Key::Down),
+    ("left", Key::Left),
+    ("right", Key::Right),
+    ("home", Key::Home),
+    ("end", Key::End),
+    ("pageup", Key::PageUp),
+    ("pagedown", Key::PageDown),
+];
+
+/// A key with the modifiers held down with it, e.g. `ctrl+m`
+///
+/// Chords are kept in one form so a key event matches the binding however
+/// the terminal reports it: a capital letter is its lowercase letter with
+/// shift, and other characters carry no shift since the character already
+/// says it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub key: Key,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+impl Chord {
+    /// `key` with the given modifiers
+    pub fn new(key: Key, ctrl: bool, alt: bool, shift: bool) -> Self {
+        let (key, shift) = match key {
+            Key::Char(c) if c.is_uppercase() => {
+                (Key::Char(c.to_lowercase().next().unwrap_or(c)), true)
+            }
+            Key::Char(c) if !c.is_alphabetic() => (key, false),
+            _ => (key, shift),
+        };
+        Self {
+            key,
+            ctrl,
+            alt,
+            shift,
+        }
+    }
+
+    /// `key` on its own
+    pub fn key(key: Key) -> Self {
+        Self::new(key, false, false, false)
+    }
+}
+
+impl FromStr for Chord {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        // The key comes last and may itself be `+`
+        let (modifiers, key) = match text.strip_suffix("++") {
+            Some(modifiers) => (Some(modifiers), "+"),
+            None if text == "+" => (None, "+"),
+            None => match text.rsplit_once('+') {
+                Some((modifiers, key)) => (Some(modifiers), key),
+                None => (None, text),
+            },
+        };
+        let (mut ctrl, mut alt, mut shift) = (false, false, false);
+        for modifier in modifiers.into_iter().flat_map(|m| m.split('+')) {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => ctrl = true,
+                "alt" | "meta" | "option" => alt = true,
+                "shift" => shift = true,
+                _ => return Err(format!("unknown modifier '{}' in '{}'", modifier, text)),
+            }
+        }
+
+        let mut chars = key.chars();
+        let key = match (chars.next(), chars.next()) {
+            (Some(c), None) => Key::Char(c),
+            _ => {
+                let lower = key.to_lowercase();
+                let function = lower
+                    .strip_prefix('f')
+                    .and_then(|n| n.parse::<u8>().ok())
+                    .filter(|n| (1..=12).contains(n));
+                match (function, NAMED_KEYS.iter().find(|(name, _)| *name == lower)) {
+                    (Some(n), _) => Key::F(n),
+                    (None, Some(&(_, key))) => key,
+                    (None, None) => return Err(format!("unknown key '{}'", key)),
+                }
+            }
+        };
+        Ok(Chord::new(key, ctrl, alt, shift))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (held, name) in [
+            (self.ctrl, "ctrl+"),
+            (self.alt, "alt+"),
+            (self.shift, "shift+"),
+        ] {
+            if held {
+                f.write_str(name)?;
+            }
+        }
+        match self.key {
+            Key::Char(c) => match NAMED_KEYS.iter().find(|(_, key)| *key == self.key) {
+                Some((name, _)) => f.write_str(name),
+                None => write!(f, "{}", c),
+            },
+            Key::F(n) => write!(f, "f{}", n),
+            key => {
+                let name = NAMED_KEYS
+                    .iter()
+                    .find(|(_, named)| *named == key)
+                    .map_or("?", |(name, _)| name);
+                f.write_str(name)
+            }
+        }
+    }
+}
+
+/// The chords of an action in the config: one, or a list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChordList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl ChordList {
+    /// The chords as written
+    pub fn chords(&self) -> &[String] {
+        match self {
+            ChordList::One(chord) => std::slice::from_ref(chord),
+            ChordList::Many(chords) => chords,
+        }
+    }
+}
+
+/// Key binding section (`[keys]`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeymapConfig {
+    /// Bindings by panel (`[keys.panels.file_tree]`), over the global ones
+    /// while that panel has focus
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub panels: BTreeMap<String, BTreeMap<String, ChordList>>,
+
+    /// Actions bound everywhere, each replacing its default chords
+    #[serde(flatten)]
+    pub bindings: BTreeMap<String, ChordList>,
+}
+
+impl KeymapConfig {
+    /// Check every binding names an action, a panel and chords that exist,
+    /// and that no chord does two things in one place
+    pub fn validate(&self) -> Result<(), String> {
+        Keymap::new(self).map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+/// A binding that doesn't parse or clashes with another
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("[{table}] {action}: {problem}")]
+pub struct KeymapError {
+    /// Table the binding is in, e.g. `keys.panels.input`
+    pub table: String,
+    /// Action as written
+    pub action: String,
+    pub problem: String,
+}
+
+/// The chords of one action, everywhere or in one panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    pub action: Action,
+    pub chords: Vec<Chord>,
+    /// Panel the binding is limited to
+    pub panel: Option<String>,
+}
+
+/// Resolves key chords to actions, with the defaults under the config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&KeymapConfig::default()).expect("default bindings parse")
+    }
+}
+
+impl Keymap {
+    /// The default bindings with `config`'s over them
+    pub fn new(config: &KeymapConfig) -> Result<Self, KeymapError> {
+        let mut bindings = Vec::new();
+        for action in Action::ALL {
+            let chords = match config.bindings.get(action.name()) {
+                Some(list) => parse_chords("keys", action.name(), list.chords())?,
+                None => parse_chords("keys", action.name(), action.default_chords())?,
+            };
+            bindings.push(Binding {
+                action,
+                chords,
+                panel: None,
+            });
+        }
+        if let Some(name) = config
+            .bindings
+            .keys()
+            .find(|name| Action::from_name(name).is_none())
+        {
+            return Err(KeymapError {
+                table: "keys".to_string(),
+                action: name.clone(),
+                problem: "no such action".to_string(),
+            });
+        }
+        check_clashes("keys", &bindings)?;
+
+        for (panel, table) in &config.panels {
+            let table_name = format!("keys.panels.{}", panel);
+            if !PANELS.contains(&panel.as_str()) {
+                return Err(KeymapError {
+                    table: table_name,
+                    action: String::new(),
+                    problem: format!("no such panel; panels are {}", PANELS.join(", ")),
+                });
+            }
+            let mut scoped = Vec::new();
+            for (name, list) in table {
+                let action = Action::from_name(name).ok_or_else(|| KeymapError {
+                    table: table_name.clone(),
+                    action: name.clone(),
+                    problem: "no such action".to_string(),
+                })?;
+                scoped.push(Binding {
+                    action,
+                    chords: parse_chords(&table_name, name, list.chords())?,
+                    panel: Some(panel.clone()),
+                });
+            }
+            // In the panel its own bindings replace the global ones
+            let global = bindings
+                .iter()
+                .filter(|b| b.panel.is_none() && !scoped.iter().any(|s| s.action == b.action))
+                .cloned();
+            let in_panel: Vec<Binding> = scoped.iter().cloned().chain(global).collect();
+            check_clashes(&table_name, &in_panel)?;
+            bindings.extend(scoped);
+        }
+        Ok(Self { bindings })
+    }
+
+    /// The action `chord` does while `panel` has focus
+    pub fn action(&self, panel: &str, chord: &Chord) -> Option<Action> {
+        let in_panel = |b: &&Binding| b.panel.as_deref() == Some(panel);
+        if let Some(binding) = self
+            .bindings
+            .iter()
+            .filter(in_panel)
+            .find(|b| b.chords.contains(chord))
+        {
+            return Some(binding.action);
+        }
+        self.bindings
+            .iter()
+            .filter(|b| b.panel.is_none() && b.chords.contains(chord))
+            .find(|b| {
+                !self
+                    .bindings
+                    .iter()
+                    .filter(in_panel)
+                    .any(|s| s.action == b.action)
+            })
+            .map(|b| b.action)
+    }
+
+    /// Chords that do `action` everywhere, e.g. for hints
+    pub fn chords(&self, action: Action) -> &[Chord] {
+        self.bindings
+            .iter()
+            .find(|b| b.panel.is_none() && b.action == action)
+            .map_or(&[], |b| &b.chords)
+    }
+
+    /// The first chord for `action`, written out, or "unbound"
+    pub fn hint(&self, action: Action) -> String {
+        self.chords(action)
+            .first()
+            .map_or_else(|| "unbound".to_string(), Chord::to_string)
+    }
+
+    /// Every binding, the global ones first
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    /// The bindings in effect, for `/keys`
+    pub fn list(&self) -> String {
+        let mut text = String::from("Key bindings:\n");
+        let mut panel = None;
+        for binding in &self.bindings {
+            if binding.panel != panel {
+                panel = binding.panel.clone();
+                if let Some(name) = &panel {
+                    text.push_str(&format!("\nWhile {} has focus:\n", name));
+                }
+            }
+            let chords = if binding.chords.is_empty() {
+                "unbound".to_string()
+            } else {
+                let chords: Vec<String> = binding.chords.iter().map(Chord::to_string).collect();
+                chords.join(", ")
+            };
+            text.push_str(&format!(
+                "  {:<16} {:<20} {}\n",
+                chords,
+                binding.action.name(),
+                binding.action.description()
+            ));
+        }
+        text.push_str("\nRebind them in the [keys] section of the config.");
+        text
+    }
+}
+
+fn parse_chords<S: AsRef<str>>(
+    table: &str,
+    action: &str,
+    chords: &[S],
+) -> Result<Vec<Chord>, KeymapError> {
+    chords
+        .iter()
+        .map(|chord| {
+            chord.as_ref().parse().map_err(|problem| KeymapError {
+                table: table.to_string(),
+                action: action.to_string(),
+                problem,
+            })
+        })
+        .collect()
+}
+
+/// Fail if a chord is bound to two actions among `bindings`
+fn check_clashes(table: &str, bindings: &[Binding]) -> Result<(), KeymapError> {
+    for (i, binding) in bindings.iter().enumerate() {
+        for chord in &binding.chords {
+            if let Some(other) = bindings[..i].iter().find(|b| b.chords.contains(chord)) {
+                return Err(KeymapError {
+                    table: table.to_string(),
+                    action: binding.action.name().to_string(),
+                    problem: format!("{} is also bound to {}", chord, other.action.name()),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(text: &str) -> Chord {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_chords_parse_and_display() {
+        assert_eq!(
+            chord("ctrl+m"),
+            Chord::new(Key::Char('m'), true, false, false)
+        );
+        assert_eq!(
+            chord("Ctrl+Shift+T"),
+            Chord::new(Key::Char('t'), true, false, true)
+        );
+        assert_eq!(chord("T"), chord("shift+t"));
+        assert_eq!(chord("shift+?"), chord("?"));
+        assert_eq!(chord("ctrl++").key, Key::Char('+'));
+        assert_eq!(chord("+"), Chord::key(Key::Char('+')));
+        assert_eq!(chord("alt+F5"), Chord::new(Key::F(5), false, true, false));
+        assert_eq!(chord("space"), Chord::key(Key::Char(' ')));
+
+        for text in [
+            "ctrl+m",
+            "shift+tab",
+            "ctrl+,",
+            "alt+f12",
+            "esc",
+            "ctrl+shift+t",
+            "space",
+        ] {
+            assert_eq!(chord(text).to_string(), text);
+        }
+        assert!("hyper+x".parse::<Chord>().unwrap_err().contains("hyper"));
+        assert!("ctrl+nope".parse::<Chord>().unwrap_err().contains("nope"));
+        assert!("f13".parse::<Chord>().is_err());
+        assert!("".parse::<Chord>().is_err());
+    }
+
+    #[test]
+    fn test_defaults_and_panel_overrides() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.action("input", &chord("ctrl+m")),
+            Some(Action::ModelSelector)
+        );
+        assert_eq!(
+            keymap.action("output", &chord("shift+tab")),
+            Some(Action::FocusPrev)
+        );
+        assert_eq!(keymap.action("output", &chord("ctrl+shift+t")), None);
+        assert_eq!(keymap.hint(Action::Search), "ctrl+f");
+
+        let config: KeymapConfig = toml::from_str(
+            r#"
+            model_selector = "ctrl+o"
+            search = ["ctrl+f", "alt+f"]
+            settings = []
+
+            [panels.file_tree]
+            quit = []
+            search = "/"
+            "#,
+        )
+        .unwrap();
+        let keymap = Keymap::new(&config).unwrap();
+        assert_eq!(
+            keymap.action("input", &chord("ctrl+o")),
+            Some(Action::ModelSelector)
+        );
+        assert_eq!(keymap.action("input", &chord("ctrl+m")), None);
+        assert_eq!(
+            keymap.action("input", &chord("alt+f")),
+            Some(Action::Search)
+        );
+        assert_eq!(keymap.action("input", &chord("ctrl+,")), None);
+        assert_eq!(keymap.hint(Action::Settings), "unbound");
+
+        // The file tree's own bindings replace the global ones there
+        assert_eq!(keymap.action("output", &chord("q")), Some(Action::Quit));
+        assert_eq!(keymap.action("file_tree", &chord("q")), None);
+        assert_eq!(
+            keymap.action("file_tree", &chord("/")),
+            Some(Action::Search)
+        );
+        assert_eq!(keymap.action("file_tree", &chord("ctrl+f")), None);
+        assert_eq!(
+            keymap.action("file_tree", &chord("ctrl+o")),
+            Some(Action::ModelSelector)
+        );
+
+        let list = keymap.list();
+        assert!(list.contains("ctrl+f, alt+f"));
+        assert!(list.contains("While file_tree has focus:"));
+    }
+
+    #[test]
+    fn test_bad_bindings_are_reported() {
+        let error = |text: &str| {
+            let config: KeymapConfig = toml::from_str(text).unwrap();
+            config.validate().unwrap_err()
+        };
+        assert_eq!(
+            error(r#"search = "ctrl+t""#),
+            "[keys] search: ctrl+t is also bound to toggle_theme"
+        );
+        assert_eq!(
+            error(r#"serach = "ctrl+s""#),
+            "[keys] serach: no such action"
+        );
+        assert!(error(r#"quit = "ctlr+q""#).contains("unknown modifier 'ctlr'"));
+        assert!(error("[panels.editor]\nquit = []").contains("no such panel"));
+        assert_eq!(
+            error("[panels.input]\nsearch = \"tab\""),
+            "[keys.panels.input] focus_next: tab is also bound to search"
+        );
+        // Rebinding in a panel frees the chord there
+        let config: KeymapConfig =
+            toml::from_str("[panels.input]\nsearch = \"tab\"\nfocus_next = []").unwrap();
+        assert!(config.validate().is_ok());
+    }
+}
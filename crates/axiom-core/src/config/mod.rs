@@ -4,11 +4,29 @@
 //! with support for environment variable expansion.
 
 mod cli_agents;
+mod keymap;
 mod loader;
+mod reload;
 mod types;
 mod writer;
 
-pub use cli_agents::{CliAgentConfig, CliAgentsConfig};
-pub use loader::{load_config, sample_config, ConfigError};
-pub use types::{AxiomConfig, LlmConfig, ProviderConfig};
-pub use writer::{config_path, save_config, user_config_path, WriteError};
+pub use cli_agents::{
+    default_parsers, CliAgentConfig, CliAgentsConfig, ExpectStep, OutputParserConfig, ParserEvent,
+    ReadinessConfig, RecordingConfig,
+};
+pub use keymap::{
+    Action, Binding, Chord, ChordList, Key, Keymap, KeymapConfig, KeymapError, PANELS,
+};
+pub use loader::{key_commands_allowed, load_config, sample_config, ConfigError};
+pub use reload::ConfigChanges;
+pub use types::{
+    AgentModelConfig, AxiomConfig, ContextConfig, ContextSource, DelegateConfig, IndexConfig,
+    LlmConfig, LspConfig, LspServerConfig, OrchestrationConfig, PromptsConfig, ProviderConfig,
+    RoleContextConfig, BUILTIN_PROVIDERS,
+};
+pub use writer::{config_path, loaded_config_path, save_config, user_config_path, WriteError};
+
+/// Serde default for switches that are on unless turned off
+pub(crate) fn default_true() -> bool {
+    true
+}
@@ -0,0 +1,244 @@
+//! What changed between two configs
+//!
+//! When a config file is edited while Axiom runs, the new config is compared
+//! with the one in use section by section, so only what changed is applied
+//! and UIs can say what that was.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Differences between the config in use and a reloaded one
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigChanges {
+    /// Providers added, removed, or whose settings, rate limits or routing
+    /// changed
+    #[serde(default)]
+    pub providers: Vec<String>,
+
+    /// The new default provider, if it changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_provider: Option<String>,
+
+    /// CLI agents added, removed or changed
+    #[serde(default)]
+    pub cli_agents: Vec<String>,
+
+    /// Other settings that changed, e.g. `orchestration` or `llm.timeout`
+    #[serde(default)]
+    pub sections: Vec<String>,
+
+    /// Settings that changed but only apply after a restart
+    #[serde(default)]
+    pub restart_needed: Vec<String>,
+}
+
+impl ConfigChanges {
+    /// Compare two configs of the same type
+    pub fn between<T: Serialize>(old: &T, new: &T) -> Self {
+        let (old, new) = (to_map(old), to_map(new));
+        let mut changes = Self::default();
+        for key in keys(&old, &new) {
+            let (before, after) = (old.get(&key), new.get(&key));
+            match key.as_str() {
+                "llm" => changes.llm(before, after),
+                "cli_agents" => {
+                    let (before, after) = (as_map(before), as_map(after));
+                    for key in keys(&before, &after) {
+                        if key == "agents" {
+                            changes.cli_agents = changed_keys(before.get(&key), after.get(&key));
+                        } else if before.get(&key) != after.get(&key) {
+                            changes.sections.push(format!("cli_agents.{}", key));
+                        }
+                    }
+                }
+                _ if before != after => changes.sections.push(key),
+                _ => {}
+            }
+        }
+        changes
+    }
+
+    /// Sort the `[llm]` table's changes into providers and sections
+    fn llm(&mut self, before: Option<&Value>, after: Option<&Value>) {
+        let (before, after) = (as_map(before), as_map(after));
+        for key in keys(&before, &after) {
+            let (old, new) = (before.get(&key), after.get(&key));
+            if old == new {
+                continue;
+            }
+            match key.as_str() {
+                "providers" | "rate_limits" | "routing" => {
+                    self.providers.extend(changed_keys(old, new))
+                }
+                "default_provider" => {
+                    self.default_provider = new.and_then(Value::as_str).map(String::from)
+                }
+                _ => self.sections.push(format!("llm.{}", key)),
+            }
+        }
+        self.providers.sort();
+        self.providers.dedup();
+    }
+
+    /// Move changed sections that start with any of `prefixes` to
+    /// `restart_needed`
+    pub fn needing_restart(mut self, prefixes: &[&str]) -> Self {
+        let (restart, live) = self
+            .sections
+            .into_iter()
+            .partition(|section| prefixes.iter().any(|prefix| section.starts_with(prefix)));
+        self.sections = live;
+        self.restart_needed = restart;
+        self
+    }
+
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+            && self.default_provider.is_none()
+            && self.cli_agents.is_empty()
+            && self.sections.is_empty()
+            && self.restart_needed.is_empty()
+    }
+
+    /// Whether providers, the default provider or other `[llm]` settings
+    /// changed
+    pub fn llm_changed(&self) -> bool {
+        !self.providers.is_empty()
+            || self.default_provider.is_some()
+            || self
+                .sections
+                .iter()
+                .any(|section| section.starts_with("llm."))
+    }
+
+    /// Whether `section` changed
+    pub fn changed(&self, section: &str) -> bool {
+        self.sections.iter().any(|s| s == section)
+    }
+
+    /// One line for a status bar, e.g. "providers claude; CLI agents codex;
+    /// orchestration (restart for index)"
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.providers.is_empty() {
+            parts.push(format!("providers {}", self.providers.join(", ")));
+        }
+        if let Some(ref provider) = self.default_provider {
+            parts.push(format!("default provider {}", provider));
+        }
+        if !self.cli_agents.is_empty() {
+            parts.push(format!("CLI agents {}", self.cli_agents.join(", ")));
+        }
+        if !self.sections.is_empty() {
+            parts.push(self.sections.join(", "));
+        }
+        let mut summary = if parts.is_empty() {
+            "nothing applied".to_string()
+        } else {
+            parts.join("; ")
+        };
+        if !self.restart_needed.is_empty() {
+            summary.push_str(&format!(
+                " (restart for {})",
+                self.restart_needed.join(", ")
+            ));
+        }
+        summary
+    }
+}
+
+fn to_map<T: Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+fn as_map(value: Option<&Value>) -> Map<String, Value> {
+    match value {
+        Some(Value::Object(map)) => map.clone(),
+        _ => Map::new(),
+    }
+}
+
+/// Keys of either table, sorted
+fn keys(a: &Map<String, Value>, b: &Map<String, Value>) -> Vec<String> {
+    let mut keys: Vec<String> = a.keys().chain(b.keys()).cloned().collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// Keys whose values differ between two tables, including added and
+/// removed ones
+fn changed_keys(before: Option<&Value>, after: Option<&Value>) -> Vec<String> {
+    let (before, after) = (as_map(before), as_map(after));
+    keys(&before, &after)
+        .into_iter()
+        .filter(|key| before.get(key) != after.get(key))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AxiomConfig, CliAgentConfig, ProviderConfig};
+
+    #[test]
+    fn test_config_changes() {
+        let old = AxiomConfig::default();
+        assert!(ConfigChanges::between(&old, &old).is_empty());
+
+        let mut new = old.clone();
+        new.llm.default_provider = "claude".to_string();
+        new.llm.providers.get_mut("claude").unwrap().enabled = true;
+        new.llm.timeout = 30;
+        new.cli_agents.agents.insert(
+            "aider".to_string(),
+            CliAgentConfig {
+                name: "Aider".to_string(),
+                command: "aider".to_string(),
+                ..Default::default()
+            },
+        );
+        new.orchestration.auto_approve = !old.orchestration.auto_approve;
+        new.index.top_k = 9;
+
+        let changes = ConfigChanges::between(&old, &new).needing_restart(&["index"]);
+        assert_eq!(changes.providers, vec!["claude"]);
+        assert_eq!(changes.default_provider.as_deref(), Some("claude"));
+        assert_eq!(changes.cli_agents, vec!["aider"]);
+        assert_eq!(changes.sections, vec!["llm.timeout", "orchestration"]);
+        assert_eq!(changes.restart_needed, vec!["index"]);
+        assert!(changes.llm_changed());
+        assert_eq!(
+            changes.summary(),
+            "providers claude; default provider claude; CLI agents aider; \
+             llm.timeout, orchestration (restart for index)"
+        );
+    }
+
+    #[test]
+    fn test_validate_names() {
+        let mut config = AxiomConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.llm.default_provider = "mistral".to_string();
+        assert!(config.validate().unwrap_err().contains("mistral"));
+
+        config.llm.default_provider = "ollama".to_string();
+        config.cli_agents.default = Some("nope".to_string());
+        assert!(config.validate().unwrap_err().contains("nope"));
+
+        config.cli_agents.default = None;
+        config
+            .llm
+            .providers
+            .insert("vllm".to_string(), ProviderConfig::default());
+        assert!(config.validate().unwrap_err().contains("vllm"));
+        config.llm.providers.get_mut("vllm").unwrap().base_url =
+            Some("http://localhost:8000/v1".to_string());
+        assert!(config.validate().is_ok());
+    }
+}
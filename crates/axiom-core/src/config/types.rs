@@ -3,8 +3,10 @@
 //! Defines the structure of `.axiom.toml` configuration.
 
 use super::cli_agents::CliAgentsConfig;
+use super::keymap::KeymapConfig;
+use crate::llm::{ProviderRouting, RateLimits};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Root configuration structure
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -16,6 +18,246 @@ pub struct AxiomConfig {
     /// CLI agent configurations
     #[serde(default)]
     pub cli_agents: CliAgentsConfig,
+
+    /// How agent operations are carried out
+    #[serde(default)]
+    pub orchestration: OrchestrationConfig,
+
+    /// Semantic code search
+    #[serde(default)]
+    pub index: IndexConfig,
+
+    /// Language servers for diagnostics, hover and go-to-definition
+    #[serde(default)]
+    pub lsp: LspConfig,
+
+    /// Key bindings
+    #[serde(default)]
+    pub keys: KeymapConfig,
+
+    /// System prompt template for each agent role
+    #[serde(default)]
+    pub prompts: PromptsConfig,
+}
+
+/// Language server section (`[lsp]`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LspConfig {
+    /// Start language servers for files that are opened or changed
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Servers by name (`[lsp.servers.rust]`); configuring a name replaces
+    /// its default
+    #[serde(default = "default_lsp_servers")]
+    pub servers: HashMap<String, LspServerConfig>,
+
+    /// Seconds an agent waits for diagnostics on the files it changed
+    #[serde(default = "default_lsp_check_timeout")]
+    pub check_timeout_secs: u64,
+}
+
+/// A language server, talking JSON-RPC over stdio
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LspServerConfig {
+    /// Command to run
+    pub command: String,
+
+    /// Arguments to the command
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// File extensions the server handles, without the dot
+    pub extensions: Vec<String>,
+
+    /// LSP language ID of the files, e.g. `rust`; worked out from the
+    /// extension when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_id: Option<String>,
+}
+
+fn default_lsp_check_timeout() -> u64 {
+    10
+}
+
+/// rust-analyzer, pyright and typescript-language-server
+fn default_lsp_servers() -> HashMap<String, LspServerConfig> {
+    let server = |command: &str, args: &[&str], extensions: &[&str]| LspServerConfig {
+        command: command.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        extensions: extensions.iter().map(|ext| ext.to_string()).collect(),
+        language_id: None,
+    };
+    HashMap::from([
+        ("rust".to_string(), server("rust-analyzer", &[], &["rs"])),
+        (
+            "python".to_string(),
+            server("pyright-langserver", &["--stdio"], &["py"]),
+        ),
+        (
+            "typescript".to_string(),
+            server(
+                "typescript-language-server",
+                &["--stdio"],
+                &["ts", "tsx", "js", "jsx"],
+            ),
+        ),
+    ])
+}
+
+impl Default for LspConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            servers: default_lsp_servers(),
+            check_timeout_secs: default_lsp_check_timeout(),
+        }
+    }
+}
+
+/// Prompt template section (`[prompts]`)
+///
+/// Names the template in `.axiom/prompts/` each agent role uses, e.g.
+/// `developer = "careful-developer"`; a role not named uses `<role>.md`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptsConfig {
+    /// Template name by role (`conductor`, `orchestrator`, `developer`)
+    #[serde(flatten)]
+    pub roles: BTreeMap<String, String>,
+}
+
+/// Semantic index section (`[index]`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexConfig {
+    /// Provider whose embedding model indexes the workspace
+    #[serde(default = "default_provider")]
+    pub provider: String,
+
+    /// Embedding model, e.g. `nomic-embed-text` on Ollama or
+    /// `text-embedding-3-small` on OpenAI
+    #[serde(default = "default_embedding_model")]
+    pub model: String,
+
+    /// Lines per indexed chunk of a file
+    #[serde(default = "default_chunk_lines")]
+    pub chunk_lines: usize,
+
+    /// Snippets returned per search
+    #[serde(default = "default_top_k")]
+    pub top_k: usize,
+
+    /// Add the snippets most relevant to each prompt to the Conductor's
+    /// system prompt
+    #[serde(default)]
+    pub auto_context: bool,
+}
+
+fn default_embedding_model() -> String {
+    "nomic-embed-text".to_string()
+}
+
+fn default_chunk_lines() -> usize {
+    40
+}
+
+fn default_top_k() -> usize {
+    5
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        Self {
+            provider: default_provider(),
+            model: default_embedding_model(),
+            chunk_lines: default_chunk_lines(),
+            top_k: default_top_k(),
+            auto_context: false,
+        }
+    }
+}
+
+/// Orchestration section (`[orchestration]`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrchestrationConfig {
+    /// Apply the developer agent's file operations as soon as it answers;
+    /// when off, each one waits for `ApproveOperations` or `RejectOperations`
+    #[serde(default)]
+    pub auto_approve: bool,
+
+    /// Have the reviewer agent check the developer agent's operations, and
+    /// send them back once with its objections
+    #[serde(default)]
+    pub review: bool,
+
+    /// Repository context per agent role (`[orchestration.context.developer]`);
+    /// roles not listed get their defaults
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub context: HashMap<String, RoleContextConfig>,
+
+    /// When a CLI agent the orchestrator delegates to counts as done
+    #[serde(default)]
+    pub delegate: DelegateConfig,
+}
+
+/// Limits on a CLI agent run for the orchestrator
+/// (`[orchestration.delegate]`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegateConfig {
+    /// Seconds without output after which the agent is taken to be done
+    #[serde(default = "default_delegate_idle")]
+    pub idle_secs: u64,
+
+    /// Seconds after which the agent is stopped however busy it is
+    #[serde(default = "default_delegate_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_delegate_idle() -> u64 {
+    30
+}
+
+fn default_delegate_timeout() -> u64 {
+    1800
+}
+
+impl Default for DelegateConfig {
+    fn default() -> Self {
+        Self {
+            idle_secs: default_delegate_idle(),
+            timeout_secs: default_delegate_timeout(),
+        }
+    }
+}
+
+/// Repository context put in an agent role's system prompt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleContextConfig {
+    /// Sources to include, in order; earlier ones get the budget first
+    pub sources: Vec<ContextSource>,
+
+    /// Token budget for all the sources together
+    #[serde(default = "default_context_tokens")]
+    pub max_tokens: usize,
+}
+
+/// Where repository context comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextSource {
+    /// Files in the workspace, a few directories deep
+    FileTree,
+    /// The workspace's `AXIOM.md` instructions
+    AxiomMd,
+    /// The workspace's README
+    Readme,
+    /// Most recently modified files
+    RecentFiles,
+    /// Files open in the editor
+    OpenTabs,
+}
+
+fn default_context_tokens() -> usize {
+    4000
 }
 
 /// LLM configuration section
@@ -36,6 +278,66 @@ pub struct LlmConfig {
     /// Provider configurations
     #[serde(default)]
     pub providers: HashMap<String, ProviderConfig>,
+
+    /// Request budgets per provider (`[llm.rate_limits.claude]`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rate_limits: HashMap<String, RateLimits>,
+
+    /// Data residency marks per provider (`[llm.routing.claude]`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub routing: HashMap<String, ProviderRouting>,
+
+    /// Provider and model per agent role (`[llm.agents.developer]`); roles
+    /// not listed use the default provider
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub agents: HashMap<String, AgentModelConfig>,
+
+    /// How much conversation the Conductor sends (`[llm.context]`)
+    #[serde(default)]
+    pub context: ContextConfig,
+
+    /// Agents run at once when a request is split into subtasks
+    #[serde(default = "default_max_parallel_tasks")]
+    pub max_parallel_tasks: usize,
+}
+
+/// Conversation history limits for the Conductor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextConfig {
+    /// Context window in tokens per provider, overriding the provider's own
+    /// figure (`[llm.context.max_tokens]`, e.g. `ollama = 32768`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub max_tokens: HashMap<String, usize>,
+
+    /// Tokens of the window kept free for the reply
+    #[serde(default = "default_reserve_tokens")]
+    pub reserve_tokens: usize,
+
+    /// Have the model summarize turns that no longer fit instead of
+    /// dropping them
+    #[serde(default)]
+    pub summarize: bool,
+}
+
+fn default_reserve_tokens() -> usize {
+    4096
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            max_tokens: HashMap::new(),
+            reserve_tokens: default_reserve_tokens(),
+            summarize: false,
+        }
+    }
+}
+
+/// Provider and model an agent role runs on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentModelConfig {
+    pub provider: String,
+    pub model: String,
 }
 
 fn default_provider() -> String {
@@ -50,6 +352,10 @@ fn default_max_retries() -> u32 {
     3
 }
 
+fn default_max_parallel_tasks() -> usize {
+    4
+}
+
 impl Default for LlmConfig {
     fn default() -> Self {
         Self {
@@ -57,10 +363,19 @@ impl Default for LlmConfig {
             timeout: default_timeout(),
             max_retries: default_max_retries(),
             providers: default_providers(),
+            rate_limits: HashMap::new(),
+            routing: HashMap::new(),
+            agents: HashMap::new(),
+            context: ContextConfig::default(),
+            max_parallel_tasks: default_max_parallel_tasks(),
         }
     }
 }
 
+/// Providers Axiom knows how to reach; any other ID under
+/// `[llm.providers]` is an OpenAI-compatible endpoint at its `base_url`
+pub const BUILTIN_PROVIDERS: &[&str] = &["claude", "gemini", "ollama", "openai"];
+
 /// Individual provider configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderConfig {
@@ -72,6 +387,16 @@ pub struct ProviderConfig {
     #[serde(default)]
     pub api_key: Option<String>,
 
+    /// Command printing a fresh API key, for tokens that expire (e.g.
+    /// `az account get-access-token --query accessToken -o tsv`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_command: Option<String>,
+
+    /// When the API key expires, in Unix seconds; reminded about a week
+    /// ahead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_expires_at: Option<u64>,
+
     /// Base URL for the API
     #[serde(default)]
     pub base_url: Option<String>,
@@ -83,6 +408,11 @@ pub struct ProviderConfig {
     /// Available models (if empty, will be fetched from API)
     #[serde(default)]
     pub models: Vec<String>,
+
+    /// Headers sent with every request, e.g. OpenRouter's `HTTP-Referer`
+    /// (values support ${ENV_VAR} syntax)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra_headers: HashMap<String, String>,
 }
 
 fn default_enabled() -> bool {
@@ -94,9 +424,12 @@ impl Default for ProviderConfig {
         Self {
             enabled: true,
             api_key: None,
+            api_key_command: None,
+            api_key_expires_at: None,
             base_url: None,
             default_model: None,
             models: Vec::new(),
+            extra_headers: HashMap::new(),
         }
     }
 }
@@ -111,9 +444,12 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
         ProviderConfig {
             enabled: true,
             api_key: None,
+            api_key_command: None,
+            api_key_expires_at: None,
             base_url: Some("http://localhost:11434".to_string()),
             default_model: Some("gemma3:4b".to_string()),
             models: Vec::new(),
+            extra_headers: HashMap::new(),
         },
     );
 
@@ -123,12 +459,15 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
         ProviderConfig {
             enabled: false, // Disabled by default until API key is set
             api_key: None,
+            api_key_command: None,
+            api_key_expires_at: None,
             base_url: Some("https://api.anthropic.com".to_string()),
             default_model: Some("claude-sonnet-4-20250514".to_string()),
             models: vec![
                 "claude-sonnet-4-20250514".to_string(),
                 "claude-opus-4-20250514".to_string(),
             ],
+            extra_headers: HashMap::new(),
         },
     );
 
@@ -138,6 +477,8 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
         ProviderConfig {
             enabled: false, // Disabled by default until API key is set
             api_key: None,
+            api_key_command: None,
+            api_key_expires_at: None,
             base_url: Some("https://generativelanguage.googleapis.com".to_string()),
             default_model: Some("gemini-2.0-flash".to_string()),
             models: vec![
@@ -145,6 +486,7 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
                 "gemini-1.5-pro".to_string(),
                 "gemini-1.5-flash".to_string(),
             ],
+            extra_headers: HashMap::new(),
         },
     );
 
@@ -154,6 +496,8 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
         ProviderConfig {
             enabled: false,
             api_key: None,
+            api_key_command: None,
+            api_key_expires_at: None,
             base_url: Some("https://api.openai.com".to_string()),
             default_model: Some("gpt-4o".to_string()),
             models: vec![
@@ -161,6 +505,7 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
                 "gpt-4o-mini".to_string(),
                 "gpt-4-turbo".to_string(),
             ],
+            extra_headers: HashMap::new(),
         },
     );
 
@@ -186,4 +531,45 @@ impl AxiomConfig {
             .filter(|(_, config)| config.enabled && config.api_key.is_some())
             .collect()
     }
+    /// Check that names refer to things that are configured: the default
+    /// provider, the providers of agent roles and the default CLI agent,
+    /// that custom providers have a base URL, and that the key bindings
+    /// parse
+    pub fn validate(&self) -> Result<(), String> {
+        let providers = &self.llm.providers;
+        let mut custom: Vec<_> = providers
+            .iter()
+            .filter(|(id, _)| !BUILTIN_PROVIDERS.contains(&id.as_str()))
+            .collect();
+        custom.sort_by_key(|(id, _)| id.as_str());
+        if let Some((id, _)) = custom.iter().find(|(_, cfg)| cfg.base_url.is_none()) {
+            return Err(format!(
+                "[llm.providers.{}] needs a base_url for its OpenAI-compatible endpoint",
+                id
+            ));
+        }
+        if !providers.contains_key(&self.llm.default_provider) {
+            return Err(format!(
+                "default_provider '{}' isn't in [llm.providers]",
+                self.llm.default_provider
+            ));
+        }
+        let mut roles: Vec<_> = self.llm.agents.iter().collect();
+        roles.sort_by_key(|(role, _)| role.as_str());
+        if let Some((role, agent)) = roles
+            .into_iter()
+            .find(|(_, agent)| !providers.contains_key(&agent.provider))
+        {
+            return Err(format!(
+                "[llm.agents.{}] uses unknown provider '{}'",
+                role, agent.provider
+            ));
+        }
+        if let Some(ref agent) = self.cli_agents.default {
+            if !self.cli_agents.agents.contains_key(agent) {
+                return Err(format!("cli_agents.default '{}' isn't configured", agent));
+            }
+        }
+        self.keys.validate()
+    }
 }
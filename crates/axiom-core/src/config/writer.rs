@@ -2,7 +2,7 @@
 //!
 //! Saves configuration to `.axiom.toml` files.
 
-use super::types::AxiomConfig;
+use super::types::{AxiomConfig, ContextConfig, BUILTIN_PROVIDERS};
 use std::path::{Path, PathBuf};
 
 /// Configuration writing error
@@ -32,6 +32,17 @@ pub fn user_config_path() -> Option<PathBuf> {
     dirs::config_dir().map(|d| d.join("axiom").join("config.toml"))
 }
 
+/// Get the path of the config file `load_config` reads for a project
+///
+/// The project's `.axiom.toml` if it exists, otherwise the user config.
+pub fn loaded_config_path(project_dir: &Path) -> PathBuf {
+    let project = config_path(project_dir);
+    if project.exists() {
+        return project;
+    }
+    user_config_path().unwrap_or(project)
+}
+
 /// Save configuration to a TOML file
 ///
 /// # Arguments
@@ -83,16 +94,39 @@ fn serialize_config(config: &AxiomConfig) -> Result<String, toml::ser::Error> {
         config.llm.max_retries
     ));
 
-    // Write each provider in a consistent order
-    let provider_order = ["claude", "gemini", "ollama", "openai"];
+    // Write the built-in providers in a consistent order, then custom
+    // endpoints by name
+    let mut custom: Vec<&str> = config
+        .llm
+        .providers
+        .keys()
+        .map(String::as_str)
+        .filter(|id| !BUILTIN_PROVIDERS.contains(id))
+        .collect();
+    custom.sort_unstable();
 
-    for provider_name in provider_order {
+    for provider_name in BUILTIN_PROVIDERS.iter().copied().chain(custom) {
         if let Some(provider) = config.llm.providers.get(provider_name) {
             output.push_str(&format!("\n[llm.providers.{}]\n", provider_name));
             output.push_str(&format!("enabled = {}\n", provider.enabled));
 
+            // A key fetched by a command is never saved, only the command
+            if let Some(ref command) = provider.api_key_command {
+                output.push_str(&format!(
+                    "api_key_command = {}\n",
+                    toml::Value::String(command.clone())
+                ));
+            }
+            if let Some(expires_at) = provider.api_key_expires_at {
+                output.push_str(&format!("api_key_expires_at = {}\n", expires_at));
+            }
+
             // Write API key if present (mask in comments but save actual value)
-            if let Some(ref api_key) = provider.api_key {
+            if let Some(api_key) = provider
+                .api_key
+                .as_ref()
+                .filter(|_| provider.api_key_command.is_none())
+            {
                 if !api_key.is_empty() && !api_key.starts_with("${") {
                     // Direct key value
                     output.push_str(&format!("api_key = \"{}\"\n", api_key));
@@ -118,6 +152,74 @@ fn serialize_config(config: &AxiomConfig) -> Result<String, toml::ser::Error> {
                     provider.models.iter().map(|m| format!("\"{}\"", m)).collect();
                 output.push_str(&format!("models = [{}]\n", models_str.join(", ")));
             }
+
+            // Write extra headers as an inline table
+            if !provider.extra_headers.is_empty() {
+                let mut headers: Vec<_> = provider.extra_headers.iter().collect();
+                headers.sort();
+                let pairs: Vec<String> = headers
+                    .into_iter()
+                    .map(|(name, value)| {
+                        format!(
+                            "{} = {}",
+                            toml::Value::String(name.clone()),
+                            toml::Value::String(value.clone())
+                        )
+                    })
+                    .collect();
+                output.push_str(&format!("extra_headers = {{ {} }}\n", pairs.join(", ")));
+            }
+        }
+    }
+
+    let mut rate_limited: Vec<_> = config.llm.rate_limits.iter().collect();
+    rate_limited.sort_by_key(|(name, _)| name.as_str());
+    for (provider_name, limits) in rate_limited {
+        output.push_str(&format!("\n[llm.rate_limits.{}]\n", provider_name));
+        if let Some(rpm) = limits.requests_per_minute {
+            output.push_str(&format!("requests_per_minute = {}\n", rpm));
+        }
+        if let Some(tpm) = limits.tokens_per_minute {
+            output.push_str(&format!("tokens_per_minute = {}\n", tpm));
+        }
+    }
+
+    let mut routed: Vec<_> = config.llm.routing.iter().collect();
+    routed.sort_by_key(|(name, _)| name.as_str());
+    for (provider_name, routing) in routed {
+        output.push_str(&format!("\n[llm.routing.{}]\n", provider_name));
+        if let Some(ref region) = routing.region {
+            output.push_str(&format!("region = \"{}\"\n", region));
+        }
+        if let Some(classification) = routing.classification {
+            output.push_str(&format!(
+                "classification = \"{}\"\n",
+                classification.label()
+            ));
+        }
+    }
+
+    let mut agents: Vec<_> = config.llm.agents.iter().collect();
+    agents.sort_by_key(|(role, _)| role.as_str());
+    for (role, agent) in agents {
+        output.push_str(&format!("\n[llm.agents.{}]\n", role));
+        output.push_str(&format!("provider = \"{}\"\n", agent.provider));
+        output.push_str(&format!("model = \"{}\"\n", agent.model));
+    }
+
+    if config.llm.context != ContextConfig::default() {
+        let context = &config.llm.context;
+        output.push_str("\n[llm.context]\n");
+        output.push_str(&format!("reserve_tokens = {}\n", context.reserve_tokens));
+        output.push_str(&format!("summarize = {}\n", context.summarize));
+
+        let mut windows: Vec<_> = context.max_tokens.iter().collect();
+        windows.sort_by_key(|(provider_name, _)| provider_name.as_str());
+        if !windows.is_empty() {
+            output.push_str("\n[llm.context.max_tokens]\n");
+        }
+        for (provider_name, tokens) in windows {
+            output.push_str(&format!("{} = {}\n", provider_name, tokens));
         }
     }
 
@@ -127,6 +229,7 @@ fn serialize_config(config: &AxiomConfig) -> Result<String, toml::ser::Error> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{AgentModelConfig, ProviderConfig};
 
     #[test]
     fn test_config_path() {
@@ -135,6 +238,28 @@ mod tests {
         assert_eq!(path, PathBuf::from("/home/user/project/.axiom.toml"));
     }
 
+    #[test]
+    fn test_serialize_key_command_without_key() {
+        let mut config = AxiomConfig::default();
+        let openai = config.llm.providers.get_mut("openai").unwrap();
+        openai.api_key_command = Some("az account get-access-token -o \"json\"".to_string());
+        openai.api_key = Some("fetched-token".to_string());
+        let claude = config.llm.providers.get_mut("claude").unwrap();
+        claude.api_key_expires_at = Some(1_900_000_000);
+
+        let content = serialize_config(&config).unwrap();
+        assert!(!content.contains("fetched-token"));
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(
+            parsed.llm.providers["openai"].api_key_command,
+            config.llm.providers["openai"].api_key_command
+        );
+        assert_eq!(
+            parsed.llm.providers["claude"].api_key_expires_at,
+            Some(1_900_000_000)
+        );
+    }
+
     #[test]
     fn test_serialize_default_config() {
         let config = AxiomConfig::default();
@@ -147,6 +272,75 @@ mod tests {
         assert!(content.contains("[llm.providers.ollama]"));
     }
 
+    #[test]
+    fn test_serialize_agent_models_round_trip() {
+        let mut config = AxiomConfig::default();
+        config.llm.agents.insert(
+            "developer".to_string(),
+            AgentModelConfig {
+                provider: "openai".to_string(),
+                model: "gpt-4o".to_string(),
+            },
+        );
+        let content = serialize_config(&config).unwrap();
+        assert!(content.contains("[llm.agents.developer]"));
+
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.llm.agents, config.llm.agents);
+    }
+
+    #[test]
+    fn test_serialize_context_round_trip() {
+        let mut config = AxiomConfig::default();
+        let content = serialize_config(&config).unwrap();
+        assert!(!content.contains("[llm.context]"));
+
+        config.llm.context.summarize = true;
+        config.llm.context.reserve_tokens = 2048;
+        config
+            .llm
+            .context
+            .max_tokens
+            .insert("ollama".to_string(), 32_768);
+        let content = serialize_config(&config).unwrap();
+        assert!(content.contains("[llm.context.max_tokens]\nollama = 32768\n"));
+
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.llm.context, config.llm.context);
+    }
+
+    #[test]
+    fn test_serialize_custom_provider_round_trip() {
+        let mut config = AxiomConfig::default();
+        let mut openrouter = ProviderConfig {
+            api_key: Some("${OPENROUTER_API_KEY}".to_string()),
+            base_url: Some("https://openrouter.ai/api/v1".to_string()),
+            default_model: Some("anthropic/claude-3.5-sonnet".to_string()),
+            ..Default::default()
+        };
+        openrouter
+            .extra_headers
+            .insert("HTTP-Referer".to_string(), "https://axiom.dev".to_string());
+        openrouter
+            .extra_headers
+            .insert("X-Title".to_string(), "Axiom".to_string());
+        config
+            .llm
+            .providers
+            .insert("openrouter".to_string(), openrouter);
+
+        let content = serialize_config(&config).unwrap();
+        assert!(content.contains(
+            "extra_headers = { \"HTTP-Referer\" = \"https://axiom.dev\", \"X-Title\" = \"Axiom\" }"
+        ));
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        let parsed = &parsed.llm.providers["openrouter"];
+        let original = &config.llm.providers["openrouter"];
+        assert_eq!(parsed.base_url, original.base_url);
+        assert_eq!(parsed.default_model, original.default_model);
+        assert_eq!(parsed.extra_headers, original.extra_headers);
+    }
+
     #[test]
     fn test_save_config_creates_dirs() {
         use std::fs;
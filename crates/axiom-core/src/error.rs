@@ -30,6 +30,10 @@ pub enum AxiomError {
     #[error("PTY error: {0}")]
     Pty(String),
 
+    /// Language server error
+    #[error("LSP error: {0}")]
+    Lsp(String),
+
     /// Channel error (communication failure)
     #[error("Channel error: {0}")]
     Channel(String),
@@ -68,6 +72,11 @@ impl AxiomError {
         AxiomError::Pty(msg.into())
     }
 
+    /// Create a language server error
+    pub fn lsp(msg: impl Into<String>) -> Self {
+        AxiomError::Lsp(msg.into())
+    }
+
     /// Create a channel error
     pub fn channel(msg: impl Into<String>) -> Self {
         AxiomError::Channel(msg.into())
@@ -0,0 +1,473 @@
+//! Catalog of error codes
+//!
+//! Every [`AxiomError`](crate::AxiomError) maps to a stable code such as
+//! `AX2003`, sent alongside the message in `Error` notifications and API
+//! error bodies so scripts can match on it instead of the wording.
+//! `axiom explain AX2003` prints what usually causes an error and how to
+//! fix it.
+//!
+//! Codes are grouped by their first digit: 1 configuration, 2 LLM
+//! providers, 3 agents, 4 language servers, 5 I/O and internal failures,
+//! 6 requests. A code is never reused for a different error.
+
+use crate::error::AxiomError;
+use crate::llm::LlmError;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A cataloged error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum ErrorCode {
+    ConfigInvalid,
+    LlmRequestFailed,
+    ProviderUnreachable,
+    ModelNotFound,
+    ProviderAuthFailed,
+    RateLimited,
+    ProviderTimeout,
+    ProviderUnavailable,
+    AgentFailed,
+    CliAgentFailed,
+    LanguageServerFailed,
+    Io,
+    Serialization,
+    Channel,
+    NotFound,
+    InvalidOperation,
+}
+
+/// What `axiom explain` says about a code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorInfo {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub causes: &'static [&'static str],
+    pub fixes: &'static [&'static str],
+}
+
+impl ErrorCode {
+    /// Every code, in catalog order
+    pub const ALL: [ErrorCode; 16] = [
+        ErrorCode::ConfigInvalid,
+        ErrorCode::LlmRequestFailed,
+        ErrorCode::ProviderUnreachable,
+        ErrorCode::ModelNotFound,
+        ErrorCode::ProviderAuthFailed,
+        ErrorCode::RateLimited,
+        ErrorCode::ProviderTimeout,
+        ErrorCode::ProviderUnavailable,
+        ErrorCode::AgentFailed,
+        ErrorCode::CliAgentFailed,
+        ErrorCode::LanguageServerFailed,
+        ErrorCode::Io,
+        ErrorCode::Serialization,
+        ErrorCode::Channel,
+        ErrorCode::NotFound,
+        ErrorCode::InvalidOperation,
+    ];
+
+    /// The code as shown, e.g. `AX2003`
+    pub fn as_str(self) -> &'static str {
+        self.info().code
+    }
+
+    /// Title, causes and fixes
+    pub fn info(self) -> ErrorInfo {
+        match self {
+            ErrorCode::ConfigInvalid => ErrorInfo {
+                code: "AX1001",
+                title: "Configuration is invalid",
+                causes: &[
+                    ".axiom.toml, ~/.config/axiom/config.toml or .axiom/config.toml doesn't parse as TOML",
+                    "A setting has the wrong type or an unknown value",
+                    "A provider is configured that Axiom doesn't support",
+                ],
+                fixes: &[
+                    "Fix the line named in the message, or delete the setting to use its default",
+                    "Compare with the example configuration in CLAUDE.md",
+                ],
+            },
+            ErrorCode::LlmRequestFailed => ErrorInfo {
+                code: "AX2000",
+                title: "LLM request failed",
+                causes: &[
+                    "The provider rejected the request or returned something Axiom couldn't read",
+                    "The conversation is too long for the model",
+                ],
+                fixes: &[
+                    "Retry; if it keeps failing, check the provider's status page",
+                    "Start a new conversation or switch to a model with a larger context",
+                ],
+            },
+            ErrorCode::ProviderUnreachable => ErrorInfo {
+                code: "AX2001",
+                title: "LLM provider unreachable",
+                causes: &[
+                    "No network connection, or a proxy or firewall blocks the provider",
+                    "A local provider such as Ollama isn't running",
+                    "The provider's base_url is wrong",
+                ],
+                fixes: &[
+                    "Check the connection and the provider's base_url in [llm.providers]",
+                    "Start the local server (e.g. `ollama serve`)",
+                ],
+            },
+            ErrorCode::ModelNotFound => ErrorInfo {
+                code: "AX2002",
+                title: "Model not found",
+                causes: &[
+                    "The configured model name is misspelled or was retired",
+                    "A local model hasn't been downloaded",
+                ],
+                fixes: &[
+                    "Pick a model the provider lists with /model",
+                    "Pull the model first (e.g. `ollama pull <model>`)",
+                ],
+            },
+            ErrorCode::ProviderAuthFailed => ErrorInfo {
+                code: "AX2003",
+                title: "LLM provider authentication failed",
+                causes: &[
+                    "The API key is missing, mistyped or revoked",
+                    "The key's environment variable isn't set in the shell Axiom runs in",
+                    "The key has no access to the configured model",
+                    "A short-lived token (Azure AD, OIDC) expired, or its api_key_command failed",
+                ],
+                fixes: &[
+                    "Set the provider's api_key in [llm.providers] or export its environment variable",
+                    "Create a new key in the provider's console and restart Axiom",
+                    "For tokens that expire, set api_key_command to a command printing a fresh one",
+                ],
+            },
+            ErrorCode::RateLimited => ErrorInfo {
+                code: "AX2004",
+                title: "Rate limited by the LLM provider",
+                causes: &[
+                    "Too many requests or tokens in a short time",
+                    "The account's quota or credit is used up",
+                ],
+                fixes: &[
+                    "Wait and retry, or lower llm.max_parallel_tasks",
+                    "Check the account's limits and billing with the provider",
+                ],
+            },
+            ErrorCode::ProviderTimeout => ErrorInfo {
+                code: "AX2005",
+                title: "LLM request timed out",
+                causes: &[
+                    "The provider is overloaded or the network is slow",
+                    "A local model is too large for the machine",
+                ],
+                fixes: &["Retry, or switch to a smaller or faster model"],
+            },
+            ErrorCode::ProviderUnavailable => ErrorInfo {
+                code: "AX2006",
+                title: "LLM provider unavailable",
+                causes: &[
+                    "The provider isn't configured or is disabled",
+                    "No provider is active",
+                ],
+                fixes: &[
+                    "Enable a provider in [llm.providers] and set llm.default_provider",
+                    "Check the providers' status with /model",
+                ],
+            },
+            ErrorCode::AgentFailed => ErrorInfo {
+                code: "AX3001",
+                title: "Agent failed",
+                causes: &[
+                    "A command the agent ran failed or was blocked by the command policy",
+                    "The agent was asked for something it can't do",
+                ],
+                fixes: &[
+                    "Read the agent's output for the underlying error",
+                    "Adjust [exec_policy] in .axiom/config.toml if a command was blocked",
+                ],
+            },
+            ErrorCode::CliAgentFailed => ErrorInfo {
+                code: "AX3002",
+                title: "CLI agent failed to run",
+                causes: &[
+                    "The agent's command isn't installed or isn't on PATH",
+                    "The terminal for the agent couldn't be created",
+                ],
+                fixes: &[
+                    "Install the CLI and check [cli_agents.<agent>].command",
+                    "Run the command by hand to see why it doesn't start",
+                ],
+            },
+            ErrorCode::LanguageServerFailed => ErrorInfo {
+                code: "AX4001",
+                title: "Language server failed",
+                causes: &[
+                    "The server's command isn't installed",
+                    "The server didn't answer in time or crashed",
+                ],
+                fixes: &[
+                    "Install the server and check [lsp.servers] in the config",
+                    "Run the server's command by hand in the workspace to see why it stops",
+                ],
+            },
+            ErrorCode::Io => ErrorInfo {
+                code: "AX5001",
+                title: "File system error",
+                causes: &[
+                    "A file or directory is missing or not readable",
+                    "The disk is full or the directory is read-only",
+                ],
+                fixes: &["Check the path and its permissions named in the message"],
+            },
+            ErrorCode::Serialization => ErrorInfo {
+                code: "AX5002",
+                title: "Data couldn't be read or written",
+                causes: &[
+                    "A file under .axiom/ is corrupted",
+                    "A client sent JSON that doesn't match the API",
+                ],
+                fixes: &[
+                    "Move the damaged file out of .axiom/ so it's recreated",
+                    "Compare the request with the API types",
+                ],
+            },
+            ErrorCode::Channel => ErrorInfo {
+                code: "AX5003",
+                title: "Internal channel closed",
+                causes: &["A background part of Axiom stopped, usually while shutting down"],
+                fixes: &["Restart Axiom; report it if it happens while running"],
+            },
+            ErrorCode::NotFound => ErrorInfo {
+                code: "AX6001",
+                title: "Not found",
+                causes: &[
+                    "The workspace, agent, file or session named doesn't exist",
+                    "It was deleted, or an ID from a previous run was used",
+                ],
+                fixes: &["List what exists (e.g. `/workspace list`) and use one of those"],
+            },
+            ErrorCode::InvalidOperation => ErrorInfo {
+                code: "AX6002",
+                title: "Operation not allowed",
+                causes: &[
+                    "The request doesn't fit the current state, e.g. agents are still running",
+                    "The workspace isn't trusted for what was asked",
+                    "An argument is malformed",
+                ],
+                fixes: &["Read the message for what's expected and retry once it applies"],
+            },
+        }
+    }
+
+    /// Best guess at the code of an LLM error known only by its message,
+    /// as [`LlmError`]s are passed around as text
+    pub fn for_llm_message(message: &str) -> Self {
+        let message = message.trim_start();
+        if message.starts_with("API error (401)")
+            || message.starts_with("API error (403)")
+            || message.contains("Authentication failed")
+        {
+            ErrorCode::ProviderAuthFailed
+        } else if message.starts_with("API error (429)") || message.starts_with("Rate limited") {
+            ErrorCode::RateLimited
+        } else if message.starts_with("Connection error") {
+            ErrorCode::ProviderUnreachable
+        } else if message.starts_with("Model not found") {
+            ErrorCode::ModelNotFound
+        } else if message.starts_with("Provider unavailable") {
+            ErrorCode::ProviderUnavailable
+        } else if message.starts_with("Request timed out") {
+            ErrorCode::ProviderTimeout
+        } else {
+            ErrorCode::LlmRequestFailed
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = AxiomError;
+
+    /// `AX2003`, `ax2003` or `2003`
+    fn from_str(s: &str) -> Result<Self, AxiomError> {
+        let s = s.trim();
+        let digits = s
+            .get(..2)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("ax"))
+            .map_or(s, |_| &s[2..]);
+        ErrorCode::ALL
+            .into_iter()
+            .find(|code| &code.as_str()[2..] == digits)
+            .ok_or_else(|| AxiomError::not_found(format!("error code '{}'", s)))
+    }
+}
+
+impl From<ErrorCode> for String {
+    fn from(code: ErrorCode) -> Self {
+        code.as_str().to_string()
+    }
+}
+
+impl TryFrom<String> for ErrorCode {
+    type Error = AxiomError;
+
+    fn try_from(s: String) -> Result<Self, AxiomError> {
+        s.parse()
+    }
+}
+
+impl AxiomError {
+    /// The error's catalog code
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AxiomError::Io(_) => ErrorCode::Io,
+            AxiomError::Config(_) => ErrorCode::ConfigInvalid,
+            AxiomError::Llm(message) => ErrorCode::for_llm_message(message),
+            AxiomError::Agent(_) => ErrorCode::AgentFailed,
+            AxiomError::Pty(_) => ErrorCode::CliAgentFailed,
+            AxiomError::Lsp(_) => ErrorCode::LanguageServerFailed,
+            AxiomError::Channel(_) => ErrorCode::Channel,
+            AxiomError::Serialization(_) => ErrorCode::Serialization,
+            AxiomError::NotFound(_) => ErrorCode::NotFound,
+            AxiomError::InvalidOperation(_) => ErrorCode::InvalidOperation,
+        }
+    }
+}
+
+impl LlmError {
+    /// The error's catalog code
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            LlmError::Connection(_) => ErrorCode::ProviderUnreachable,
+            LlmError::Api {
+                status: 401 | 403, ..
+            } => ErrorCode::ProviderAuthFailed,
+            LlmError::Api { status: 429, .. } | LlmError::RateLimited { .. } => {
+                ErrorCode::RateLimited
+            }
+            LlmError::ModelNotFound(_) => ErrorCode::ModelNotFound,
+            LlmError::ProviderUnavailable(_) => ErrorCode::ProviderUnavailable,
+            LlmError::Timeout => ErrorCode::ProviderTimeout,
+            // Errors streamed by a provider arrive as their message
+            LlmError::Internal(message) => ErrorCode::for_llm_message(message),
+            LlmError::Api { .. } | LlmError::InvalidRequest(_) => ErrorCode::LlmRequestFailed,
+        }
+    }
+}
+
+/// What `axiom explain <code>` prints: title, causes and fixes
+pub fn explain(code: ErrorCode) -> String {
+    let info = code.info();
+    let mut text = format!("{}: {}\n\nCommon causes:\n", info.code, info.title);
+    for cause in info.causes {
+        text.push_str(&format!("  - {}\n", cause));
+    }
+    text.push_str("\nHow to fix it:\n");
+    for fix in info.fixes {
+        text.push_str(&format!("  - {}\n", fix));
+    }
+    text
+}
+
+/// Every code with its title, one per line
+pub fn catalog() -> String {
+    let lines: Vec<String> = ErrorCode::ALL
+        .iter()
+        .map(|code| format!("{}  {}", code, code.info().title))
+        .collect();
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_unique_and_parse() {
+        let mut seen = std::collections::HashSet::new();
+        for code in ErrorCode::ALL {
+            let info = code.info();
+            assert!(seen.insert(info.code), "{} is used twice", info.code);
+            assert!(!info.causes.is_empty() && !info.fixes.is_empty());
+            assert_eq!(info.code.parse::<ErrorCode>().unwrap(), code);
+        }
+        assert_eq!(
+            "ax2003".parse::<ErrorCode>().unwrap(),
+            ErrorCode::ProviderAuthFailed
+        );
+        assert_eq!(
+            "2003".parse::<ErrorCode>().unwrap(),
+            ErrorCode::ProviderAuthFailed
+        );
+        assert!("AX9999".parse::<ErrorCode>().is_err());
+        assert!("".parse::<ErrorCode>().is_err());
+    }
+
+    #[test]
+    fn test_errors_map_to_codes() {
+        assert_eq!(AxiomError::config("bad toml").code().as_str(), "AX1001");
+        assert_eq!(
+            AxiomError::not_found("workspace").code(),
+            ErrorCode::NotFound
+        );
+
+        // LLM errors keep their code through their message
+        let errors = [
+            (
+                LlmError::Api {
+                    status: 401,
+                    message: "Authentication failed".to_string(),
+                },
+                ErrorCode::ProviderAuthFailed,
+            ),
+            (
+                LlmError::RateLimited {
+                    retry_after: Some(5),
+                },
+                ErrorCode::RateLimited,
+            ),
+            (
+                LlmError::Connection("refused".to_string()),
+                ErrorCode::ProviderUnreachable,
+            ),
+            (LlmError::Timeout, ErrorCode::ProviderTimeout),
+            (
+                LlmError::Api {
+                    status: 500,
+                    message: "oops".to_string(),
+                },
+                ErrorCode::LlmRequestFailed,
+            ),
+        ];
+        for (error, code) in errors {
+            assert_eq!(error.code(), code);
+            assert_eq!(AxiomError::llm(error.to_string()).code(), code);
+            assert_eq!(LlmError::Internal(error.to_string()).code(), code);
+        }
+    }
+
+    #[test]
+    fn test_serializes_as_code() {
+        let json = serde_json::to_string(&ErrorCode::ProviderAuthFailed).unwrap();
+        assert_eq!(json, r#""AX2003""#);
+        let code: ErrorCode = serde_json::from_str(&json).unwrap();
+        assert_eq!(code, ErrorCode::ProviderAuthFailed);
+        assert!(serde_json::from_str::<ErrorCode>(r#""AX0000""#).is_err());
+    }
+
+    #[test]
+    fn test_explain() {
+        let text = explain(ErrorCode::ProviderAuthFailed);
+        assert!(text.starts_with("AX2003: LLM provider authentication failed\n"));
+        assert!(text.contains("Common causes:\n  - The API key"));
+        assert!(text.contains("How to fix it:\n"));
+        assert!(catalog()
+            .lines()
+            .any(|line| line == "AX1001  Configuration is invalid"));
+        assert_eq!(catalog().lines().count(), ErrorCode::ALL.len());
+    }
+}
@@ -3,6 +3,7 @@
 //! This module defines backend-only events that don't depend on any UI framework.
 //! UI events (Key, Mouse, etc.) are handled in axiom-tui, not here.
 
+use crate::llm::FreshKey;
 use crate::types::{AgentId, AgentSpawnRequest, AgentStatus, OutputContext};
 use crossbeam_channel::{bounded, Receiver, Sender};
 use std::path::PathBuf;
@@ -27,8 +28,24 @@ pub enum Event {
     /// LLM error occurred
     LlmError(String),
 
+    /// A provider rejected its API key (401/403)
+    ProviderAuthFailed {
+        provider_id: String,
+        message: String,
+    },
+
+    /// A provider's `api_key_command` finished
+    ProviderKeyFetched {
+        provider_id: String,
+        result: Result<FreshKey, String>,
+    },
+
     /// File modification request from LLM
-    FileModification { path: String, content: String },
+    FileModification {
+        id: AgentId,
+        path: String,
+        content: String,
+    },
 
     // ===== Agent Events =====
     /// Request conductor to process user input
@@ -79,6 +96,12 @@ pub enum Event {
         exit_code: i32,
     },
 
+    /// CLI agent never became ready for its prompt
+    CliAgentNotReady {
+        id: AgentId,
+        error: String,
+    },
+
     /// Send input to CLI agent
     CliAgentInput {
         id: AgentId,
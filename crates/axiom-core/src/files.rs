@@ -0,0 +1,253 @@
+//! Creating, moving, deleting and saving workspace files
+//!
+//! Shared by the TUI file tree and the server's file routes. Paths may be
+//! relative to the workspace root or absolute, but must stay inside the
+//! root; `..` is resolved before checking, and the root itself can't be
+//! moved or deleted. Each function returns the absolute path it acted on.
+//!
+//! [`write_atomic`] and [`modified_time`] take the path as given, for
+//! editors saving files they already have open.
+
+use crate::error::{AxiomError, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+/// Resolve `.` and `..` without touching the filesystem
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// `path` as an absolute path strictly inside `root`
+///
+/// Symlinks in the part of the path that exists must lead inside `root`
+/// too.
+pub(crate) fn resolve(root: &Path, path: &Path) -> Result<PathBuf> {
+    let outside =
+        || AxiomError::invalid_operation(format!("{} is outside the workspace", path.display()));
+    let root = normalize(root);
+    let resolved = normalize(&root.join(path));
+    if resolved == root || !resolved.starts_with(&root) {
+        return Err(outside());
+    }
+    if let Ok(real_root) = root.canonicalize() {
+        let existing = resolved
+            .ancestors()
+            .find(|p| p.symlink_metadata().is_ok())
+            .unwrap_or(&root);
+        let real = existing.canonicalize().map_err(|_| outside())?;
+        if !real.starts_with(&real_root) || (real == real_root && existing != root) {
+            return Err(outside());
+        }
+    }
+    Ok(resolved)
+}
+
+/// `path` as an absolute directory inside `root`, or `root` itself
+pub(crate) fn resolve_dir(root: &Path, path: &Path) -> Result<PathBuf> {
+    if normalize(&root.join(path)) == normalize(root) {
+        return Ok(root.to_path_buf());
+    }
+    resolve(root, path)
+}
+
+fn ensure_free(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Err(AxiomError::invalid_operation(format!(
+            "{} already exists",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Create an empty file, or a directory if `dir`, with any missing parents
+pub fn create_path(root: &Path, path: &Path, dir: bool) -> Result<PathBuf> {
+    let path = resolve(root, path)?;
+    ensure_free(&path)?;
+    if dir {
+        fs::create_dir_all(&path)?;
+    } else {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::File::create_new(&path)?;
+    }
+    Ok(path)
+}
+
+/// Rename or move `from` to `to`, which must not exist yet
+pub fn move_path(root: &Path, from: &Path, to: &Path) -> Result<PathBuf> {
+    let from = resolve(root, from)?;
+    let to = resolve(root, to)?;
+    if !from.exists() {
+        return Err(AxiomError::not_found(from.display().to_string()));
+    }
+    if to.starts_with(&from) {
+        return Err(AxiomError::invalid_operation(format!(
+            "Can't move {} into itself",
+            from.display()
+        )));
+    }
+    ensure_free(&to)?;
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(&from, &to)?;
+    Ok(to)
+}
+
+/// Delete a file, or a directory and everything in it
+pub fn delete_path(root: &Path, path: &Path) -> Result<PathBuf> {
+    let path = resolve(root, path)?;
+    match fs::symlink_metadata(&path) {
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(&path)?,
+        Ok(_) => fs::remove_file(&path)?,
+        Err(_) => return Err(AxiomError::not_found(path.display().to_string())),
+    }
+    Ok(path)
+}
+
+/// Write `content` to `path` through a temporary file renamed into place
+///
+/// Readers see the old content or the new, never half of each. An existing
+/// file keeps its permissions; missing parent directories are created.
+/// Returns the file's new modification time.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<SystemTime> {
+    let name = path.file_name().ok_or_else(|| {
+        AxiomError::invalid_operation(format!("{} is not a file", path.display()))
+    })?;
+    // Ends in .tmp so file watchers ignore it
+    let temp = path.with_file_name(format!(".{}.axiom-save.tmp", name.to_string_lossy()));
+    let written = (|| -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::File::create(&temp)?;
+        file.write_all(content)?;
+        if let Ok(meta) = fs::metadata(path) {
+            file.set_permissions(meta.permissions())?;
+        }
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    })();
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp);
+        return Err(e.into());
+    }
+    Ok(fs::metadata(path)?.modified()?)
+}
+
+/// When `path` was last modified, or None if it doesn't exist
+pub fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Where `path` is after `from` moved to `to`, if it was `from` or inside it
+pub fn moved_path(path: &Path, from: &Path, to: &Path) -> Option<PathBuf> {
+    let rest = path.strip_prefix(from).ok()?;
+    Some(if rest.as_os_str().is_empty() {
+        to.to_path_buf()
+    } else {
+        to.join(rest)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_move_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        let file = create_path(root, Path::new("src/new.rs"), false).unwrap();
+        assert_eq!(file, root.join("src/new.rs"));
+        assert!(file.is_file());
+        assert!(create_path(root, Path::new("src/new.rs"), false).is_err());
+        assert!(create_path(root, Path::new("docs/api"), true)
+            .unwrap()
+            .is_dir());
+
+        // Absolute paths inside the root work too
+        let moved = move_path(root, &file, Path::new("docs/api/new.rs")).unwrap();
+        assert!(moved.is_file() && !file.exists());
+        assert!(move_path(root, Path::new("docs"), Path::new("docs/api/inner")).is_err());
+
+        delete_path(root, Path::new("docs")).unwrap();
+        assert!(!root.join("docs").exists());
+        assert!(delete_path(root, Path::new("docs")).is_err());
+    }
+
+    #[test]
+    fn test_paths_stay_in_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("ws");
+        fs::create_dir(&root).unwrap();
+
+        let outside = create_path(&root, Path::new("../escape.txt"), false);
+        assert!(outside.unwrap_err().to_string().contains("outside"));
+        assert!(!dir.path().join("escape.txt").exists());
+        assert!(delete_path(&root, Path::new(".")).is_err());
+        assert!(delete_path(&root, Path::new("/etc/hosts")).is_err());
+        assert!(create_path(&root, Path::new("a/../b.txt"), false).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_stay_in_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("ws");
+        fs::create_dir_all(root.join("src")).unwrap();
+        std::os::unix::fs::symlink(dir.path(), root.join("out")).unwrap();
+        std::os::unix::fs::symlink(root.join("src"), root.join("code")).unwrap();
+
+        let escape = create_path(&root, Path::new("out/escape.txt"), false);
+        assert!(escape.unwrap_err().to_string().contains("outside"));
+        assert!(!dir.path().join("escape.txt").exists());
+        assert_eq!(
+            create_path(&root, Path::new("code/lib.rs"), false).unwrap(),
+            root.join("code/lib.rs")
+        );
+        assert!(root.join("src/lib.rs").is_file());
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("src/lib.rs");
+
+        let mtime = write_atomic(&path, b"fn one() {}\n").unwrap();
+        assert_eq!(modified_time(&path), Some(mtime));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fn one() {}\n");
+
+        write_atomic(&path, b"fn two() {}\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fn two() {}\n");
+        // Only the file itself is left behind
+        assert_eq!(fs::read_dir(dir.path().join("src")).unwrap().count(), 1);
+        assert_eq!(modified_time(&dir.path().join("missing.rs")), None);
+    }
+
+    #[test]
+    fn test_moved_path() {
+        let (from, to) = (Path::new("/ws/src"), Path::new("/ws/lib"));
+        assert_eq!(
+            moved_path(Path::new("/ws/src/main.rs"), from, to),
+            Some(PathBuf::from("/ws/lib/main.rs"))
+        );
+        assert_eq!(moved_path(from, from, to), Some(to.to_path_buf()));
+        assert_eq!(moved_path(Path::new("/ws/srcs/a.rs"), from, to), None);
+    }
+}
@@ -0,0 +1,431 @@
+//! Semantic code search
+//!
+//! Workspace files are cut into chunks of lines, embedded with the
+//! `[index]` provider's embedding model and kept in a vector index under
+//! `.axiom/index`. An update only embeds the files that changed since the
+//! last one. A search embeds the query and returns the closest chunks,
+//! which the Conductor can also add to its prompts (`auto_context`).
+//!
+//! Files are walked as for [`search`](crate::search): `.gitignore` files,
+//! the built-in ignores and the workspace's `ignore_patterns` are honoured.
+
+mod store;
+
+pub use store::{normalize, IndexedChunk, IndexedFile, VectorStore};
+
+use crate::config::IndexConfig;
+use crate::error::{AxiomError, Result};
+use crate::llm::{ProviderRegistry, SharedProvider};
+use crate::watcher::IgnoreRules;
+use ignore::WalkBuilder;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+/// Texts sent per embedding request
+const EMBED_BATCH: usize = 32;
+
+/// Files larger than this are left out of the index
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+
+/// Longest chunk text embedded, in characters
+const MAX_CHUNK_CHARS: usize = 4000;
+
+/// A chunk of a file matching a semantic search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SemanticHit {
+    /// File path relative to the workspace root
+    pub path: PathBuf,
+
+    /// First line of the chunk, from 1
+    pub start_line: usize,
+
+    /// Last line of the chunk, inclusive
+    pub end_line: usize,
+
+    /// Cosine similarity to the query, up to 1
+    pub score: f32,
+
+    /// The chunk's lines
+    pub text: String,
+}
+
+/// What an index update did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexStats {
+    /// Files embedded because they are new or changed
+    pub embedded: usize,
+
+    /// Files dropped because they are gone or no longer indexed
+    pub removed: usize,
+
+    /// Files in the index afterwards
+    pub files: usize,
+
+    /// Chunks in the index afterwards
+    pub chunks: usize,
+}
+
+/// A file found while walking the workspace
+struct WalkedFile {
+    path: PathBuf,
+    modified: u64,
+    len: u64,
+}
+
+/// The semantic index of one workspace
+pub struct SemanticIndex {
+    root: PathBuf,
+    config: IndexConfig,
+    ignores: Vec<String>,
+    registry: Arc<RwLock<ProviderRegistry>>,
+    /// Loaded on first use
+    store: Mutex<Option<VectorStore>>,
+}
+
+impl SemanticIndex {
+    /// The index of `root`, embedding with providers from `registry`
+    ///
+    /// `ignores` are the workspace's `ignore_patterns`. Nothing is read until
+    /// the first update or search.
+    pub fn new(
+        root: &Path,
+        config: IndexConfig,
+        ignores: Vec<String>,
+        registry: Arc<RwLock<ProviderRegistry>>,
+    ) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            config,
+            ignores,
+            registry,
+            store: Mutex::new(None),
+        }
+    }
+
+    /// Where the index is saved
+    pub fn dir(&self) -> PathBuf {
+        self.root.join(".axiom").join("index")
+    }
+
+    /// The index settings
+    pub fn config(&self) -> &IndexConfig {
+        &self.config
+    }
+
+    /// Embed new and changed files, drop removed ones and save the index
+    ///
+    /// Blocks on the embedding requests; the first update of a large
+    /// workspace can take a while.
+    pub fn update(&self) -> Result<IndexStats> {
+        let provider = self.provider()?;
+        let mut guard = self.store.lock();
+        let store = guard.get_or_insert_with(|| VectorStore::load(&self.dir(), &self.config.model));
+
+        let walked = self.walk();
+        let present: HashSet<&Path> = walked.iter().map(|file| file.path.as_path()).collect();
+        let removed = store.retain(|path| present.contains(path));
+
+        // Chunk every new or changed file, then embed them all in batches
+        let mut changed = Vec::new();
+        for file in &walked {
+            let unchanged = store.file(&file.path).is_some_and(|indexed| {
+                (indexed.modified, indexed.len) == (file.modified, file.len)
+            });
+            if unchanged {
+                continue;
+            }
+            let text = std::fs::read(self.root.join(&file.path))
+                .ok()
+                .filter(|bytes| !bytes.contains(&0))
+                .and_then(|bytes| String::from_utf8(bytes).ok());
+            let chunks = text
+                .map(|text| chunk_lines(&text, self.config.chunk_lines))
+                .unwrap_or_default();
+            changed.push((file, chunks));
+        }
+        let texts: Vec<String> = changed
+            .iter()
+            .flat_map(|(_, chunks)| chunks.iter().map(|chunk| chunk.2.clone()))
+            .collect();
+        let mut vectors = self.embed(&provider, &texts)?.into_iter();
+
+        let embedded = changed.len();
+        for (file, chunks) in changed {
+            let chunks = chunks
+                .into_iter()
+                .zip(vectors.by_ref())
+                .map(|((start_line, end_line, text), vector)| IndexedChunk {
+                    start_line,
+                    end_line,
+                    text,
+                    vector,
+                })
+                .collect();
+            store.insert(
+                file.path.clone(),
+                IndexedFile {
+                    modified: file.modified,
+                    len: file.len,
+                    chunks,
+                },
+            );
+        }
+        if embedded > 0 || removed > 0 {
+            store.save(&self.dir())?;
+        }
+
+        let (files, chunks) = store.counts();
+        Ok(IndexStats {
+            embedded,
+            removed,
+            files,
+            chunks,
+        })
+    }
+
+    /// The `top_k` chunks closest in meaning to `query`, best first
+    ///
+    /// Updates the index first, so results reflect the files as they are.
+    pub fn search(&self, query: &str) -> Result<Vec<SemanticHit>> {
+        if query.trim().is_empty() {
+            return Err(AxiomError::invalid_operation("Search query is empty"));
+        }
+        self.update()?;
+        let provider = self.provider()?;
+        let query = self
+            .embed(&provider, &[query.to_string()])?
+            .pop()
+            .unwrap_or_default();
+        let store = self.store.lock();
+        Ok(store
+            .as_ref()
+            .map(|store| store.nearest(&query, self.config.top_k))
+            .unwrap_or_default())
+    }
+
+    /// The provider doing the embedding
+    fn provider(&self) -> Result<SharedProvider> {
+        self.registry
+            .read()
+            .get(&self.config.provider)
+            .ok_or_else(|| {
+                AxiomError::not_found(format!(
+                    "Embedding provider '{}' is not configured",
+                    self.config.provider
+                ))
+            })
+    }
+
+    /// Unit-length embeddings of `texts`, in batches
+    fn embed(&self, provider: &SharedProvider, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(EMBED_BATCH) {
+            let embedded = provider
+                .embed(&self.config.model, batch)
+                .map_err(|e| AxiomError::llm(format!("Embedding failed: {}", e)))?;
+            vectors.extend(embedded.into_iter().map(normalize));
+        }
+        Ok(vectors)
+    }
+
+    /// Indexable files under the root, as paths relative to it
+    fn walk(&self) -> Vec<WalkedFile> {
+        let rules = IgnoreRules::load(&self.root, &self.ignores);
+        let filter_root = self.root.clone();
+        WalkBuilder::new(&self.root)
+            .require_git(false)
+            .filter_entry(move |entry| {
+                let Ok(relative) = entry.path().strip_prefix(&filter_root) else {
+                    return true;
+                };
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                !rules.is_ignored(&relative.to_string_lossy().replace('\\', "/"), is_dir)
+            })
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                if meta.len() > MAX_FILE_BYTES {
+                    return None;
+                }
+                let modified = meta
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map_or(0, |since| since.as_secs());
+                Some(WalkedFile {
+                    path: entry.path().strip_prefix(&self.root).ok()?.to_path_buf(),
+                    modified,
+                    len: meta.len(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// `text` cut into runs of `lines` lines, as `(first line, last line, text)`
+///
+/// Lines are numbered from 1. Runs with nothing but whitespace are skipped
+/// and long ones are cut short.
+pub fn chunk_lines(text: &str, lines: usize) -> Vec<(usize, usize, String)> {
+    let all: Vec<&str> = text.lines().collect();
+    all.chunks(lines.max(1))
+        .enumerate()
+        .filter(|(_, run)| run.iter().any(|line| !line.trim().is_empty()))
+        .map(|(n, run)| {
+            let start = n * lines.max(1) + 1;
+            let text: String = run.join("\n").chars().take(MAX_CHUNK_CHARS).collect();
+            (start, start + run.len() - 1, text)
+        })
+        .collect()
+}
+
+/// A system prompt section quoting `hits`, or an empty string without any
+pub fn context_prompt(hits: &[SemanticHit]) -> String {
+    if hits.is_empty() {
+        return String::new();
+    }
+    let mut prompt = String::from("\n\nCode from the workspace that may be relevant:\n");
+    for hit in hits {
+        prompt.push_str(&format!(
+            "\n{}:{}-{}\n```\n{}\n```\n",
+            hit.path.display(),
+            hit.start_line,
+            hit.end_line,
+            hit.text
+        ));
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Event;
+    use crate::llm::{ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus};
+    use crossbeam_channel::Sender;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Embeds a text as how often it mentions each of a few words
+    struct WordCounts {
+        embedded: AtomicUsize,
+    }
+
+    impl LlmProvider for WordCounts {
+        fn id(&self) -> &str {
+            "words"
+        }
+
+        fn name(&self) -> &str {
+            "Words"
+        }
+
+        fn model(&self) -> String {
+            "words-1".to_string()
+        }
+
+        fn set_model(&self, _model: &str) -> std::result::Result<(), LlmError> {
+            Ok(())
+        }
+
+        fn list_models(&self) -> std::result::Result<Vec<String>, LlmError> {
+            Ok(Vec::new())
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities::default()
+        }
+
+        fn status(&self) -> ProviderStatus {
+            ProviderStatus::Ready
+        }
+
+        fn embed(
+            &self,
+            _model: &str,
+            texts: &[String],
+        ) -> std::result::Result<Vec<Vec<f32>>, LlmError> {
+            self.embedded.fetch_add(texts.len(), Ordering::SeqCst);
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    ["parse", "network", "render"]
+                        .iter()
+                        .map(|word| text.matches(word).count() as f32)
+                        .collect()
+                })
+                .collect())
+        }
+
+        fn send_message(&self, _messages: Vec<ChatMessage>, _event_tx: Sender<Event>) {}
+    }
+
+    fn index(root: &Path) -> (SemanticIndex, Arc<WordCounts>) {
+        let provider = Arc::new(WordCounts {
+            embedded: AtomicUsize::new(0),
+        });
+        let mut registry = ProviderRegistry::new();
+        registry.register(provider.clone());
+        let config = IndexConfig {
+            provider: "words".to_string(),
+            chunk_lines: 2,
+            top_k: 1,
+            ..Default::default()
+        };
+        let index = SemanticIndex::new(root, config, Vec::new(), Arc::new(RwLock::new(registry)));
+        (index, provider)
+    }
+
+    #[test]
+    fn test_chunk_lines() {
+        let chunks = chunk_lines("a\nb\n\n\nc", 2);
+        assert_eq!(
+            chunks,
+            vec![(1, 2, "a\nb".to_string()), (5, 5, "c".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_search_and_update() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("parser.rs"),
+            "fn parse() {}\n// parse input\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("net.rs"),
+            "fn fetch() {}\n// network call\n",
+        )
+        .unwrap();
+
+        let (index, provider) = index(dir.path());
+        let hits = index.search("where is the network code").unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, PathBuf::from("net.rs"));
+        assert_eq!((hits[0].start_line, hits[0].end_line), (1, 2));
+        assert!(dir.path().join(".axiom/index/vectors.json").exists());
+
+        // Nothing changed, so only queries are embedded; a new index reads
+        // the saved vectors
+        let before = provider.embedded.load(Ordering::SeqCst);
+        let (reopened, provider) = self::index(dir.path());
+        let stats = reopened.update().unwrap();
+        assert_eq!((stats.embedded, stats.files, stats.chunks), (0, 2, 2));
+        assert_eq!(provider.embedded.load(Ordering::SeqCst), 0);
+        assert_eq!(before, 3);
+
+        std::fs::remove_file(dir.path().join("net.rs")).unwrap();
+        std::fs::write(dir.path().join("view.rs"), "fn render() {}\n").unwrap();
+        let stats = reopened.update().unwrap();
+        assert_eq!((stats.embedded, stats.removed, stats.files), (1, 1, 2));
+        let hits = reopened.search("render").unwrap();
+        assert_eq!(hits[0].path, PathBuf::from("view.rs"));
+        assert!(context_prompt(&hits).contains("view.rs:1-1"));
+    }
+}
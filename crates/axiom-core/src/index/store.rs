@@ -0,0 +1,137 @@
+//! On-disk vector index
+//!
+//! One JSON file per workspace holding every chunk's text and unit-length
+//! embedding, keyed by file so changed files can be replaced on their own.
+
+use super::SemanticHit;
+use crate::error::{AxiomError, Result};
+use crate::files::write_atomic;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// File name of the index inside `.axiom/index`
+const INDEX_FILE: &str = "vectors.json";
+
+/// Every indexed chunk of a workspace, embedded with one model
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VectorStore {
+    /// Embedding model the vectors came from
+    model: String,
+
+    /// Indexed files by path relative to the workspace root
+    files: BTreeMap<PathBuf, IndexedFile>,
+}
+
+/// A file's chunks, with what the file looked like when they were made
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedFile {
+    /// Modification time, in seconds since the epoch
+    pub modified: u64,
+
+    /// Size in bytes
+    pub len: u64,
+
+    pub chunks: Vec<IndexedChunk>,
+}
+
+/// A run of lines and its embedding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedChunk {
+    /// First line, from 1
+    pub start_line: usize,
+
+    /// Last line, inclusive
+    pub end_line: usize,
+
+    pub text: String,
+
+    /// Embedding scaled to unit length
+    pub vector: Vec<f32>,
+}
+
+impl VectorStore {
+    /// An empty index for `model`
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// The index saved in `dir`, or an empty one if there is none or it was
+    /// made with another model
+    pub fn load(dir: &Path, model: &str) -> Self {
+        std::fs::read(dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Self>(&bytes).ok())
+            .filter(|store| store.model == model)
+            .unwrap_or_else(|| Self::new(model))
+    }
+
+    /// Write the index to `dir`
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let json =
+            serde_json::to_vec(self).map_err(|e| AxiomError::Serialization(e.to_string()))?;
+        write_atomic(&dir.join(INDEX_FILE), &json)?;
+        Ok(())
+    }
+
+    /// The indexed state of `path`
+    pub fn file(&self, path: &Path) -> Option<&IndexedFile> {
+        self.files.get(path)
+    }
+
+    /// Replace the chunks of `path`
+    pub fn insert(&mut self, path: PathBuf, file: IndexedFile) {
+        self.files.insert(path, file);
+    }
+
+    /// Drop files `keep` rejects; returns how many went
+    pub fn retain(&mut self, mut keep: impl FnMut(&Path) -> bool) -> usize {
+        let before = self.files.len();
+        self.files.retain(|path, _| keep(path));
+        before - self.files.len()
+    }
+
+    /// Number of indexed files and chunks
+    pub fn counts(&self) -> (usize, usize) {
+        let chunks = self.files.values().map(|file| file.chunks.len()).sum();
+        (self.files.len(), chunks)
+    }
+
+    /// The `top_k` chunks closest to the unit-length `query`, best first
+    pub fn nearest(&self, query: &[f32], top_k: usize) -> Vec<SemanticHit> {
+        let mut scored: Vec<(f32, &Path, &IndexedChunk)> = self
+            .files
+            .iter()
+            .flat_map(|(path, file)| file.chunks.iter().map(move |chunk| (path, chunk)))
+            .map(|(path, chunk)| (dot(query, &chunk.vector), path.as_path(), chunk))
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored
+            .into_iter()
+            .take(top_k)
+            .map(|(score, path, chunk)| SemanticHit {
+                path: path.to_path_buf(),
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                score,
+                text: chunk.text.clone(),
+            })
+            .collect()
+    }
+}
+
+/// `vector` scaled to unit length, so a dot product is the cosine similarity
+pub fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = dot(&vector, &vector).sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
@@ -6,6 +6,9 @@
 //! - PTY management for CLI agents
 //! - Configuration loading
 //! - File watching
+//! - Workspace-wide text search
+//! - Semantic code search over an embeddings index
+//! - Language servers for diagnostics, hover and go-to-definition
 //!
 //! Any UI (TUI, Web, GUI) can consume this crate through the `AxiomService` interface.
 //!
@@ -44,6 +47,7 @@
 // Public API modules
 pub mod commands;
 pub mod error;
+pub mod error_codes;
 pub mod notifications;
 pub mod types;
 
@@ -59,35 +63,61 @@ pub mod llm;
 // Workspace management
 pub mod workspace;
 
+// LLM usage and cost accounting
+pub mod usage;
+
 // Agent orchestration system
 pub mod orchestration;
 
+// File context extraction (outlines, snippets, @file mentions)
+pub mod context;
+
+// Secret redaction and prompt-injection checks on LLM input
+pub mod guard;
+
+// Wall-clock time as stored in Axiom's files
+pub mod clock;
+
 // Re-export commonly used types
 pub use commands::Command;
 pub use commands::slash::{
-    CommandHelp, ModelSubcommand, ParseError as SlashParseError, SlashCommand,
-    SlashCommandData, SlashCommandParser, SlashCommandResult, ThemeSubcommand, UiAction,
-    WorkspaceInfo, WorkspaceSubcommand,
+    ApprovalSubcommand, CommandHelp, FeaturesSubcommand, InspectSubcommand, ModelSubcommand, ParseError as SlashParseError,
+    PlanSubcommand, RefactorSubcommand, ReportSubcommand, RoutingSubcommand, SessionSubcommand, SlashCommand, SlashCommandData, SlashCommandParser,
+    SlashCommandResult, SlashError, StatsSubcommand, ThemeSubcommand, UiAction, UsageSubcommand,
+    WatchSubcommand, WorkspaceInfo, WorkspaceSubcommand,
 };
 pub use error::{AxiomError, Result};
-pub use notifications::{FileEntry, Notification};
+pub use error_codes::ErrorCode;
+pub use notifications::{FileEntry, HistoryEntry, HistoryFilter, Notification};
 pub use types::{
-    AgentId, AgentSpawnRequest, AgentStatus, AgentType, AgentView, CliAgentInfo, OutputContext,
-    ProviderInfo, ProviderStatus, TerminalCell, TerminalColor, TerminalLine, TerminalScreen,
+    AgentEvent, AgentId, AgentSpawnRequest, AgentStatus, AgentType, AgentView, CliAgentInfo, OutputContext,
+    ProviderInfo, ProviderStatus, ScreenDiff, ServiceHealth, ServiceInfo, TerminalCell, TerminalColor, TerminalLine, TerminalScreen,
 };
 
 // Re-export config types
-pub use config::{AxiomConfig, CliAgentConfig, CliAgentsConfig, LlmConfig, ProviderConfig};
+pub use config::{
+    AxiomConfig, CliAgentConfig, CliAgentsConfig, ConfigChanges, ContextConfig, IndexConfig,
+    LlmConfig, LspConfig, OrchestrationConfig, ProviderConfig,
+};
 
 // Re-export workspace types
 pub use workspace::{
-    Workspace, WorkspaceConfig, WorkspaceId, WorkspaceManager, WorkspaceType, WorkspaceView,
+    discover_projects, filter_workspaces, format_duration, fuzzy_score, ActivityEvent,
+    ActivityKind, ActivityPage, AuditReport, BundleFile, DiscoveredProject, ExecPolicy, Feature, FeatureFlags, MergeConflict, MergeOutcome, PendingWrite, PolicyViolation, PurgeItem, PurgePlan, ReviewItem, ReviewKind, TimeReport,
+    merge_summary, normalize_path, purge_summary, MergedWorkspaces, WatchAction, WatchEngine, WatchRule, WatchTrigger, WritePolicy, Workspace, WorkspaceConfig, WorkspaceDefaults, WorkspaceBundle, WorkspaceId, WorkspaceManager, WorkspaceType, WorkspaceView,
 };
 
+// Re-export usage types
+pub use usage::{UsageFilter, UsageRecord, UsageRow};
+
+// Re-export guard types
+pub use guard::{guard_content, GuardConfig, GuardFinding, Guarded};
+
 // Re-export LLM types
 pub use llm::{
     ChatMessage, ClaudeProvider, GeminiProvider, LlmError, LlmProvider, OllamaProvider,
-    OpenAIProvider, ProviderCapabilities, ProviderRegistry, SharedProvider,
+    OpenAICompatibleProvider, OpenAIProvider, ProviderCapabilities, ProviderRegistry,
+    SharedProvider, ToolCall, ToolReply, ToolSpec,
 };
 
 // Re-export orchestration types
@@ -100,14 +130,68 @@ pub use orchestration::{
 pub mod agents;
 
 // Re-export agent types
-pub use agents::{Agent, AgentRegistry, Conductor, Executor, PtyAgent, PtyAgentManager};
+pub use agents::{
+    Agent, AgentAction, AgentRegistry, Conductor, ContextManager, Executor, PtyAgent,
+    PtyAgentManager,
+};
 
 // Main service facade
 pub mod service;
-pub use service::AxiomService;
+pub use service::{AxiomService, SharedService};
+
+// Workspace file watcher
+pub mod watcher;
+pub use watcher::{FileWatcher, IgnoreRules};
+
+// Workspace-wide text search
+pub mod search;
+pub use search::{SearchHandle, SearchHit, SearchQuery, SearchSummary, SearchUpdate};
+
+// Embeddings and vector index for semantic code search
+pub mod index;
+pub use index::{SemanticHit, SemanticIndex};
+
+// Language server clients for diagnostics and navigation
+pub mod lsp;
+pub use lsp::{Diagnostic, DiagnosticSeverity, Location, LspManager};
+
+// Workspace-wide find and replace
+pub mod replace;
+pub use replace::{replace_summary, FileReplace, ReplaceOutcome};
+
+// Creating, moving and deleting workspace files
+pub mod files;
+
+// Compact WebSocket framing for notifications
+pub mod wire;
+
+// Saved conversations and agent output
+pub mod sessions;
+pub use sessions::{SavedAgent, Session, SessionSummary};
+
+// Asciicast recordings of CLI agent sessions
+pub mod recordings;
+pub use recordings::{Recorder, RecordingSummary};
+
+// Structured logs of agent runs
+pub mod run_log;
+pub use run_log::{RunLogFormat, RunRecord, RunStatus};
+
+// Project templates for /init
+pub mod templates;
+pub use templates::{ScaffoldReport, Template};
+
+// System prompt templates for agent roles
+pub mod prompts;
+pub use prompts::PromptLibrary;
+
+// Multi-step plans made by the conductor
+pub mod plan;
+pub use plan::{Plan, PlanStep, PlanStepStatus};
 
-// TODO: File watcher will be added later
-// pub mod watcher;
+// Scripted LLM provider and temporary workspaces for tests
+#[cfg(feature = "testing")]
+pub mod testing;
 
 /// Get the crate version
 pub fn version() -> &'static str {
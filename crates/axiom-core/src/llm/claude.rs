@@ -2,7 +2,12 @@
 //!
 //! Connects to Anthropic's Claude API for chat completions.
 
-use super::{ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus};
+use super::provider::fetch_model_ids;
+use super::tools::{claude_reply, claude_tools};
+use super::{
+    send_with_quota, ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus,
+    ToolReply, ToolSpec,
+};
 use crate::events::Event;
 use crossbeam_channel::Sender;
 use parking_lot::RwLock;
@@ -68,6 +73,17 @@ impl LlmProvider for ClaudeProvider {
         Ok(self.models.clone())
     }
 
+    fn probe(&self) -> Result<Vec<String>, LlmError> {
+        if self.api_key.is_empty() {
+            return Err(LlmError::ProviderUnavailable("No API key configured".to_string()));
+        }
+        let url = format!("{}/v1/models", self.base_url);
+        let request = ureq::get(&url)
+            .set("x-api-key", &self.api_key)
+            .set("anthropic-version", "2023-06-01");
+        fetch_model_ids(request, "data", "id")
+    }
+
     fn capabilities(&self) -> ProviderCapabilities {
         ProviderCapabilities {
             streaming: true,
@@ -99,16 +115,21 @@ impl LlmProvider for ClaudeProvider {
             let _ = event_tx.send(Event::LlmDone);
         });
     }
+
+    fn chat_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: &[ToolSpec],
+    ) -> Result<ToolReply, LlmError> {
+        let mut body = messages_body(&self.model.read(), messages);
+        body["tools"] = claude_tools(tools);
+        let response = post_messages(&self.base_url, &self.api_key, "tools", &body)?;
+        Ok(claude_reply(&response.into_json()?))
+    }
 }
 
-/// Send request to Claude API and stream response
-fn send_claude_request(
-    base_url: &str,
-    api_key: &str,
-    model: &str,
-    messages: Vec<ChatMessage>,
-    event_tx: &Sender<Event>,
-) -> Result<(), LlmError> {
+/// Messages API request body; system messages go in the `system` field
+fn messages_body(model: &str, messages: Vec<ChatMessage>) -> serde_json::Value {
     let mut system_content = String::new();
     let mut conversation: Vec<serde_json::Value> = Vec::new();
 
@@ -127,21 +148,48 @@ fn send_claude_request(
     let mut body = serde_json::json!({
         "model": model,
         "max_tokens": 8192,
-        "stream": true,
         "messages": conversation
     });
 
     if !system_content.is_empty() {
         body["system"] = serde_json::Value::String(system_content.trim().to_string());
     }
+    body
+}
 
+/// POST to the Messages API, queued behind other agents for the provider's budget
+fn post_messages(
+    base_url: &str,
+    api_key: &str,
+    agent: &str,
+    body: &serde_json::Value,
+) -> Result<ureq::Response, LlmError> {
     let url = format!("{}/v1/messages", base_url);
+    let response = send_with_quota(
+        "claude",
+        agent,
+        || {
+            ureq::post(&url)
+                .set("Content-Type", "application/json")
+                .set("x-api-key", api_key)
+                .set("anthropic-version", "2023-06-01")
+        },
+        body,
+    )?;
+    Ok(response)
+}
 
-    let response = ureq::post(&url)
-        .set("Content-Type", "application/json")
-        .set("x-api-key", api_key)
-        .set("anthropic-version", "2023-06-01")
-        .send_json(&body)?;
+/// Send request to Claude API and stream response
+fn send_claude_request(
+    base_url: &str,
+    api_key: &str,
+    model: &str,
+    messages: Vec<ChatMessage>,
+    event_tx: &Sender<Event>,
+) -> Result<(), LlmError> {
+    let mut body = messages_body(model, messages);
+    body["stream"] = serde_json::Value::Bool(true);
+    let response = post_messages(base_url, api_key, "chat", &body)?;
 
     let reader = BufReader::new(response.into_reader());
 
@@ -0,0 +1,295 @@
+//! API keys that expire
+//!
+//! Tokens such as Azure AD or OIDC access tokens only last an hour or so,
+//! and long-lived keys are rotated on a schedule. A provider's
+//! `api_key_command` prints a fresh key, which is fetched when there is none
+//! yet, shortly before the current one expires and after the provider
+//! rejects it. Keys without a command can carry `api_key_expires_at`, and
+//! are reminded about a week before that and once they have expired.
+//!
+//! The command may print the key alone, or JSON such as
+//! `{"access_token": "...", "expires_in": 3599}`; the expiry is then taken
+//! from `expires_in` (seconds from now) or `expires_at` / `expires_on`
+//! (Unix seconds).
+
+use crate::clock::unix_now;
+use crate::workspace::format_duration;
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// How often keys are checked
+pub const KEY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long before it expires a key with a command is fetched again
+pub const REFRESH_AHEAD: Duration = Duration::from_secs(5 * 60);
+
+/// How long before it expires a key without a command is reminded about
+pub const ROTATION_REMINDER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A key printed by a provider's `api_key_command`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreshKey {
+    pub key: String,
+    /// Unix seconds the key stops working, if the command said
+    pub expires_at: Option<u64>,
+}
+
+/// What to do about a provider's key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    /// Run its `api_key_command`
+    Refresh,
+    /// Remind that it expires in `secs_left`
+    Remind { secs_left: u64 },
+    /// Say that it has expired
+    Expired,
+}
+
+/// Which keys are being refreshed or have been warned about, so each
+/// expiry is only reported once
+#[derive(Debug, Default)]
+pub struct KeyWatch {
+    last_check: Option<Instant>,
+    refreshing: HashSet<String>,
+    /// Refreshes asked for with [`KeyWatch::force`]
+    forced: HashSet<String>,
+    /// Failed refreshes already reported
+    failed: HashSet<String>,
+    /// Expiry and whether it had passed, per reminded provider
+    warned: HashMap<String, (u64, bool)>,
+}
+
+impl KeyWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether [`KEY_CHECK_INTERVAL`] has passed since the last check, or a
+    /// refresh was forced
+    pub fn due(&mut self, now: Instant) -> bool {
+        if self.forced.is_empty()
+            && self
+                .last_check
+                .is_some_and(|last| now.duration_since(last) < KEY_CHECK_INTERVAL)
+        {
+            return false;
+        }
+        self.last_check = Some(now);
+        true
+    }
+
+    /// What to do about `provider`'s key at `now` (Unix seconds)
+    pub fn check(
+        &mut self,
+        provider: &str,
+        expires_at: Option<u64>,
+        refreshable: bool,
+        has_key: bool,
+        now: u64,
+    ) -> Option<KeyAction> {
+        let forced = self.forced.remove(provider);
+        if self.refreshing.contains(provider) {
+            return None;
+        }
+        if refreshable {
+            let due = forced
+                || !has_key
+                || expires_at.is_some_and(|at| at <= now + REFRESH_AHEAD.as_secs());
+            if !due {
+                return None;
+            }
+            self.refreshing.insert(provider.to_string());
+            return Some(KeyAction::Refresh);
+        }
+
+        let at = expires_at?;
+        let expired = at <= now;
+        if !expired && at > now + ROTATION_REMINDER.as_secs() {
+            return None;
+        }
+        if self.warned.get(provider) == Some(&(at, expired)) {
+            return None;
+        }
+        self.warned.insert(provider.to_string(), (at, expired));
+        Some(if expired {
+            KeyAction::Expired
+        } else {
+            KeyAction::Remind {
+                secs_left: at - now,
+            }
+        })
+    }
+
+    /// Refresh `provider`'s key at the next check, e.g. after it was
+    /// rejected
+    pub fn force(&mut self, provider: &str) {
+        self.forced.insert(provider.to_string());
+    }
+
+    /// A refresh finished; true if it should be reported, which a failure
+    /// is only the first time in a row
+    pub fn finished(&mut self, provider: &str, ok: bool) -> bool {
+        self.refreshing.remove(provider);
+        if ok {
+            self.failed.remove(provider);
+            self.warned.remove(provider);
+            true
+        } else {
+            self.failed.insert(provider.to_string())
+        }
+    }
+}
+
+/// How long until `expires_at`, e.g. "in 4m 10s" or "3 days ago"
+pub fn describe_expiry(expires_at: u64, now: u64) -> String {
+    let describe = |secs: u64| match secs / 86_400 {
+        0 => format_duration(secs),
+        1 => "1 day".to_string(),
+        days => format!("{} days", days),
+    };
+    if expires_at > now {
+        format!("in {}", describe(expires_at - now))
+    } else {
+        format!("{} ago", describe(now - expires_at))
+    }
+}
+
+/// Run `command` and read the key it prints
+pub fn fetch_key(command: &str) -> Result<FreshKey, String> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(match stderr.trim() {
+            "" => format!("`{}` failed ({})", command, output.status),
+            stderr => format!("`{}` failed: {}", command, stderr),
+        });
+    }
+    parse_key_output(&String::from_utf8_lossy(&output.stdout), unix_now())
+        .ok_or_else(|| format!("`{}` printed no key", command))
+}
+
+/// The key in a command's output, read at `now`
+pub fn parse_key_output(stdout: &str, now: u64) -> Option<FreshKey> {
+    let stdout = stdout.trim();
+    let Ok(serde_json::Value::Object(json)) = serde_json::from_str(stdout) else {
+        let key = stdout.lines().next()?.trim();
+        return (!key.is_empty()).then(|| FreshKey {
+            key: key.to_string(),
+            expires_at: None,
+        });
+    };
+
+    let field = |names: &[&str]| names.iter().find_map(|name| json.get(*name));
+    let number = |value: &serde_json::Value| match value {
+        serde_json::Value::Number(n) => n.as_u64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    };
+    let key = field(&["access_token", "accessToken", "token", "api_key"])?
+        .as_str()
+        .filter(|key| !key.is_empty())?;
+    let expires_at = field(&["expires_in", "expiresIn"])
+        .and_then(number)
+        .map(|secs| now + secs)
+        .or_else(|| field(&["expires_at", "expires_on", "expiresOn"]).and_then(number));
+    Some(FreshKey {
+        key: key.to_string(),
+        expires_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_output() {
+        assert_eq!(
+            parse_key_output("sk-abc\n", 100),
+            Some(FreshKey {
+                key: "sk-abc".to_string(),
+                expires_at: None,
+            })
+        );
+        assert_eq!(
+            parse_key_output(r#"{"access_token": "tok", "expires_in": 3600}"#, 100),
+            Some(FreshKey {
+                key: "tok".to_string(),
+                expires_at: Some(3700),
+            })
+        );
+        // az account get-access-token
+        assert_eq!(
+            parse_key_output(r#"{"accessToken": "tok", "expires_on": "5000"}"#, 100)
+                .unwrap()
+                .expires_at,
+            Some(5000)
+        );
+        assert_eq!(parse_key_output("", 100), None);
+        assert_eq!(parse_key_output(r#"{"expires_in": 60}"#, 100), None);
+    }
+
+    #[test]
+    fn test_key_watch_refreshes_before_expiry() {
+        let mut watch = KeyWatch::new();
+        // No key yet
+        assert_eq!(
+            watch.check("azure", None, true, false, 1000),
+            Some(KeyAction::Refresh)
+        );
+        // Only one refresh at a time
+        assert_eq!(watch.check("azure", None, true, false, 1000), None);
+        assert!(watch.finished("azure", true));
+
+        assert_eq!(watch.check("azure", Some(5000), true, true, 1000), None);
+        assert_eq!(
+            watch.check("azure", Some(5000), true, true, 4800),
+            Some(KeyAction::Refresh)
+        );
+        // A failure is reported once until a refresh works again
+        assert!(watch.finished("azure", false));
+        assert_eq!(
+            watch.check("azure", Some(5000), true, true, 4860),
+            Some(KeyAction::Refresh)
+        );
+        assert!(!watch.finished("azure", false));
+
+        // A rejected key is fetched again even if it hasn't expired
+        watch.force("azure");
+        assert!(watch.due(Instant::now()));
+        assert_eq!(
+            watch.check("azure", Some(9000), true, true, 4900),
+            Some(KeyAction::Refresh)
+        );
+    }
+
+    #[test]
+    fn test_key_watch_reminds_once() {
+        let mut watch = KeyWatch::new();
+        let day = 86_400;
+        assert_eq!(watch.check("claude", Some(30 * day), false, true, 0), None);
+        assert_eq!(
+            watch.check("claude", Some(30 * day), false, true, 25 * day),
+            Some(KeyAction::Remind { secs_left: 5 * day })
+        );
+        assert_eq!(
+            watch.check("claude", Some(30 * day), false, true, 26 * day),
+            None
+        );
+        assert_eq!(
+            watch.check("claude", Some(30 * day), false, true, 31 * day),
+            Some(KeyAction::Expired)
+        );
+        assert_eq!(
+            watch.check("claude", Some(30 * day), false, true, 32 * day),
+            None
+        );
+        assert_eq!(describe_expiry(5 * day, 0), "in 5 days");
+        assert_eq!(describe_expiry(0, 90), "1m 30s ago");
+    }
+}
@@ -2,7 +2,12 @@
 //!
 //! Connects to Google's Gemini API for chat completions.
 
-use super::{ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus};
+use super::provider::{embedding_vectors, fetch_model_ids};
+use super::tools::{gemini_reply, gemini_tools};
+use super::{
+    send_with_quota, ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus,
+    ToolReply, ToolSpec,
+};
 use crate::events::Event;
 use crossbeam_channel::Sender;
 use parking_lot::RwLock;
@@ -68,6 +73,41 @@ impl LlmProvider for GeminiProvider {
         Ok(self.models.clone())
     }
 
+    fn probe(&self) -> Result<Vec<String>, LlmError> {
+        if self.api_key.is_empty() {
+            return Err(LlmError::ProviderUnavailable("No API key configured".to_string()));
+        }
+        let url = format!("{}/v1beta/models?key={}", self.base_url, self.api_key);
+        let models = fetch_model_ids(ureq::get(&url), "models", "name")?;
+        // Names come as `models/gemini-2.0-flash`
+        Ok(models
+            .into_iter()
+            .map(|name| name.trim_start_matches("models/").to_string())
+            .collect())
+    }
+
+    fn embed(&self, model: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        if self.api_key.is_empty() {
+            return Err(LlmError::ProviderUnavailable("No API key configured".to_string()));
+        }
+        let url = format!(
+            "{}/v1beta/models/{}:batchEmbedContents?key={}",
+            self.base_url, model, self.api_key
+        );
+        let requests: Vec<_> = texts
+            .iter()
+            .map(|text| {
+                serde_json::json!({
+                    "model": format!("models/{}", model),
+                    "content": { "parts": [{ "text": text }] }
+                })
+            })
+            .collect();
+        let body = serde_json::json!({ "requests": requests });
+        let json: serde_json::Value = ureq::post(&url).send_json(&body)?.into_json()?;
+        embedding_vectors(&json, "embeddings", Some("values"), texts.len())
+    }
+
     fn capabilities(&self) -> ProviderCapabilities {
         ProviderCapabilities {
             streaming: true,
@@ -99,16 +139,32 @@ impl LlmProvider for GeminiProvider {
             let _ = event_tx.send(Event::LlmDone);
         });
     }
+
+    fn chat_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: &[ToolSpec],
+    ) -> Result<ToolReply, LlmError> {
+        let mut body = contents_body(messages);
+        body["tools"] = gemini_tools(tools);
+        let url = format!(
+            "{}/v1beta/models/{}:generateContent?key={}",
+            self.base_url,
+            self.model.read(),
+            self.api_key
+        );
+        let response = send_with_quota(
+            "gemini",
+            "tools",
+            || ureq::post(&url).set("Content-Type", "application/json"),
+            &body,
+        )?;
+        Ok(gemini_reply(&response.into_json()?))
+    }
 }
 
-/// Send request to Gemini API and stream response
-fn send_gemini_request(
-    base_url: &str,
-    api_key: &str,
-    model: &str,
-    messages: Vec<ChatMessage>,
-    event_tx: &Sender<Event>,
-) -> Result<(), LlmError> {
+/// generateContent request body; system messages become `system_instruction`
+fn contents_body(messages: Vec<ChatMessage>) -> serde_json::Value {
     // Convert messages to Gemini format
     let mut contents: Vec<serde_json::Value> = Vec::new();
     let mut system_instruction: Option<String> = None;
@@ -149,6 +205,18 @@ fn send_gemini_request(
             }]
         });
     }
+    body
+}
+
+/// Send request to Gemini API and stream response
+fn send_gemini_request(
+    base_url: &str,
+    api_key: &str,
+    model: &str,
+    messages: Vec<ChatMessage>,
+    event_tx: &Sender<Event>,
+) -> Result<(), LlmError> {
+    let body = contents_body(messages);
 
     // Use streamGenerateContent endpoint
     let url = format!(
@@ -156,9 +224,16 @@ fn send_gemini_request(
         base_url, model, api_key
     );
 
-    let response = ureq::post(&url)
-        .set("Content-Type", "application/json")
-        .send_json(&body)?;
+    // Chat requests queue behind other agents for the provider's budget
+    let response = send_with_quota(
+        "gemini",
+        "chat",
+        || {
+            ureq::post(&url)
+                .set("Content-Type", "application/json")
+        },
+        &body,
+    )?;
 
     // Read SSE streaming response
     let reader = BufReader::new(response.into_reader());
@@ -1,31 +1,56 @@
 //! LLM integration module
 //!
-//! Provides multi-provider LLM support with streaming chat.
+//! Provides multi-provider LLM support with streaming chat and tool calling.
 //!
 //! Supported providers:
 //! - OpenAI (GPT-4, GPT-3.5)
+//! - Any OpenAI-compatible endpoint (LiteLLM, vLLM, LM Studio, OpenRouter)
 //! - Claude (Anthropic API)
 //! - Gemini (Google AI)
 //! - Ollama (local inference)
 
 mod claude;
+mod credentials;
 mod error;
 mod gemini;
 mod message;
 mod ollama;
 mod openai;
+mod openai_compatible;
 mod provider;
 mod registry;
+mod routing;
+mod scheduler;
+mod tools;
 
+pub use credentials::{
+    describe_expiry, fetch_key, parse_key_output, FreshKey, KeyAction, KeyWatch,
+    KEY_CHECK_INTERVAL, REFRESH_AHEAD, ROTATION_REMINDER,
+};
 pub use error::LlmError;
 pub use message::{
     build_prompt_with_context, format_file_context, ChatMessage, ContentPart, MessageContent, Role,
 };
-pub use provider::{LlmProvider, ProviderCapabilities, ProviderStatus, SharedProvider};
-pub use registry::{ProviderInfo, ProviderRegistry};
+pub use provider::{
+    LlmProvider, ProviderCapabilities, ProviderStatus, SharedProvider, PROBE_TIMEOUT,
+};
+pub use registry::{ProviderInfo, ProviderProbe, ProviderRegistry};
+pub use routing::{
+    blocked_message, check_route, routing_summary, Enforcement, ProviderRouting, RouteCheck,
+    RoutingPolicy, Sensitivity,
+};
+pub use scheduler::{
+    configure_rate_limits, scheduler, send_with_quota, QuotaScheduler, RateLimits,
+    MAX_RATE_LIMIT_RETRIES,
+};
+pub use tools::{
+    claude_reply, claude_tools, fallback_prompt, gemini_reply, gemini_tools, openai_reply,
+    openai_tools, parse_fallback_reply, ToolCall, ToolReply, ToolSpec,
+};
 
 // Provider implementations
 pub use claude::ClaudeProvider;
 pub use gemini::GeminiProvider;
 pub use ollama::OllamaProvider;
 pub use openai::OpenAIProvider;
+pub use openai_compatible::OpenAICompatibleProvider;
@@ -2,6 +2,7 @@
 //!
 //! Connects to local Ollama instance for chat completions.
 
+use super::provider::embedding_vectors;
 use super::{ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus};
 use crate::events::Event;
 use crossbeam_channel::Sender;
@@ -109,6 +110,17 @@ impl LlmProvider for OllamaProvider {
         self.fetch_models()
     }
 
+    fn probe(&self) -> Result<Vec<String>, LlmError> {
+        self.fetch_models()
+    }
+
+    fn embed(&self, model: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        let url = format!("{}/api/embed", self.base_url);
+        let body = serde_json::json!({ "model": model, "input": texts });
+        let json: serde_json::Value = ureq::post(&url).send_json(&body)?.into_json()?;
+        embedding_vectors(&json, "embeddings", None, texts.len())
+    }
+
     fn capabilities(&self) -> ProviderCapabilities {
         ProviderCapabilities {
             streaming: true,
@@ -139,6 +151,8 @@ impl LlmProvider for OllamaProvider {
             let _ = event_tx.send(Event::LlmDone);
         });
     }
+
+    // No native tool calling: `chat_with_tools` uses the trait's JSON prompt
 }
 
 /// Send request to Ollama and stream response
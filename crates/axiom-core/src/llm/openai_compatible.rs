@@ -0,0 +1,391 @@
+//! OpenAI-compatible LLM provider
+//!
+//! Speaks OpenAI's Chat Completions API to any server at a configured base
+//! URL: LiteLLM, vLLM, LM Studio, OpenRouter and the like. Each configured
+//! endpoint registers under its own ID, with its own key, headers and
+//! budget. [`OpenAIProvider`](super::OpenAIProvider) is this pointed at
+//! OpenAI.
+
+use super::provider::{embedding_vectors, fetch_model_ids};
+use super::tools::{openai_reply, openai_tools};
+use super::{
+    send_with_quota, ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus,
+    ToolReply, ToolSpec,
+};
+use crate::config::ProviderConfig;
+use crate::events::Event;
+use crossbeam_channel::Sender;
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader};
+
+/// Where requests go and what they carry
+#[derive(Debug, Clone)]
+struct Endpoint {
+    /// Provider ID, which names its budget
+    id: String,
+
+    /// Base URL, e.g. `http://localhost:8000/v1`
+    base_url: String,
+
+    /// API key, sent as a bearer token when set
+    api_key: String,
+
+    /// Headers sent with every request
+    headers: BTreeMap<String, String>,
+}
+
+impl Endpoint {
+    /// Add the key and extra headers to `request`
+    fn authorize(&self, mut request: ureq::Request) -> ureq::Request {
+        if !self.api_key.is_empty() {
+            request = request.set("Authorization", &format!("Bearer {}", self.api_key));
+        }
+        for (name, value) in &self.headers {
+            request = request.set(name, value);
+        }
+        request
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path)
+    }
+
+    /// POST to Chat Completions, queued behind other agents for the
+    /// provider's budget
+    fn post_completions(
+        &self,
+        agent: &str,
+        body: &serde_json::Value,
+    ) -> Result<ureq::Response, LlmError> {
+        let url = self.url("chat/completions");
+        let response = send_with_quota(
+            &self.id,
+            agent,
+            || self.authorize(ureq::post(&url).set("Content-Type", "application/json")),
+            body,
+        )?;
+        Ok(response)
+    }
+}
+
+/// Provider for any OpenAI-compatible endpoint
+pub struct OpenAICompatibleProvider {
+    endpoint: Endpoint,
+
+    /// Display name
+    name: String,
+
+    /// Whether the endpoint is unusable without an API key (local servers
+    /// usually don't need one)
+    key_required: bool,
+
+    /// Current model
+    model: RwLock<String>,
+
+    /// Available models
+    models: Vec<String>,
+}
+
+impl OpenAICompatibleProvider {
+    /// Create a provider registered as `id` for the endpoint at `base_url`
+    pub fn new(id: &str, base_url: &str, model: &str) -> Self {
+        Self {
+            endpoint: Endpoint {
+                id: id.to_string(),
+                base_url: base_url.trim_end_matches('/').to_string(),
+                api_key: String::new(),
+                headers: BTreeMap::new(),
+            },
+            name: id.to_string(),
+            key_required: false,
+            model: RwLock::new(model.to_string()),
+            models: Vec::new(),
+        }
+    }
+
+    /// Create from the `[llm.providers.<id>]` section, which needs a
+    /// `base_url`
+    ///
+    /// The model is the configured default, else the first listed model;
+    /// with no list the default is the one model offered.
+    pub fn from_config(id: &str, config: &ProviderConfig) -> Option<Self> {
+        let base_url = config.base_url.as_deref()?;
+        let model = config
+            .default_model
+            .as_deref()
+            .or(config.models.first().map(String::as_str))
+            .unwrap_or_default();
+        let models = match &config.models {
+            models if models.is_empty() && !model.is_empty() => vec![model.to_string()],
+            models => models.clone(),
+        };
+        let mut provider = Self::new(id, base_url, model).with_models(models);
+        if let Some(api_key) = &config.api_key {
+            provider = provider.with_api_key(api_key);
+        }
+        for (name, value) in &config.extra_headers {
+            provider = provider.with_header(name, value);
+        }
+        Some(provider)
+    }
+
+    /// Set the display name
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Send `api_key` as a bearer token
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.endpoint.api_key = api_key.to_string();
+        self
+    }
+
+    /// Send `name: value` with every request
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.endpoint
+            .headers
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Offer `models` in the model selector
+    pub fn with_models(mut self, models: Vec<String>) -> Self {
+        self.models = models;
+        self
+    }
+
+    /// Point at a different base URL
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.endpoint.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Report the provider unavailable until an API key is set
+    pub fn requiring_key(mut self) -> Self {
+        self.key_required = true;
+        self
+    }
+
+    /// The base URL requests go to
+    pub fn base_url(&self) -> &str {
+        &self.endpoint.base_url
+    }
+
+    fn check_key(&self) -> Result<(), LlmError> {
+        if self.key_required && self.endpoint.api_key.is_empty() {
+            return Err(LlmError::ProviderUnavailable(
+                "No API key configured".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl LlmProvider for OpenAICompatibleProvider {
+    fn id(&self) -> &str {
+        &self.endpoint.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn model(&self) -> String {
+        self.model.read().clone()
+    }
+
+    fn set_model(&self, model: &str) -> Result<(), LlmError> {
+        *self.model.write() = model.to_string();
+        Ok(())
+    }
+
+    fn list_models(&self) -> Result<Vec<String>, LlmError> {
+        Ok(self.models.clone())
+    }
+
+    fn probe(&self) -> Result<Vec<String>, LlmError> {
+        self.check_key()?;
+        let request = self
+            .endpoint
+            .authorize(ureq::get(&self.endpoint.url("models")));
+        fetch_model_ids(request, "data", "id")
+    }
+
+    fn embed(&self, model: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        self.check_key()?;
+        let body = serde_json::json!({ "model": model, "input": texts });
+        let json: serde_json::Value = self
+            .endpoint
+            .authorize(ureq::post(&self.endpoint.url("embeddings")))
+            .send_json(&body)?
+            .into_json()?;
+        embedding_vectors(&json, "data", Some("embedding"), texts.len())
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            streaming: true,
+            function_calling: true,
+            vision: true,
+            file_context: true,
+            max_context: 128_000, // GPT-4o context window
+            max_output: 16384,
+        }
+    }
+
+    fn status(&self) -> ProviderStatus {
+        match self.check_key() {
+            Ok(()) => ProviderStatus::Ready,
+            Err(_) => ProviderStatus::Unavailable("No API key configured".to_string()),
+        }
+    }
+
+    fn send_message(&self, messages: Vec<ChatMessage>, event_tx: Sender<Event>) {
+        let endpoint = self.endpoint.clone();
+        let model = self.model.read().clone();
+
+        std::thread::spawn(move || {
+            if let Err(e) = send_streaming_request(&endpoint, &model, messages, &event_tx) {
+                let _ = event_tx.send(Event::LlmError(e.to_string()));
+            }
+            let _ = event_tx.send(Event::LlmDone);
+        });
+    }
+
+    fn chat_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: &[ToolSpec],
+    ) -> Result<ToolReply, LlmError> {
+        let mut body = completions_body(&self.model.read(), &messages);
+        body["tools"] = openai_tools(tools);
+        let response = self.endpoint.post_completions("tools", &body)?;
+        Ok(openai_reply(&response.into_json()?))
+    }
+}
+
+/// Chat Completions request body
+fn completions_body(model: &str, messages: &[ChatMessage]) -> serde_json::Value {
+    let conversation: Vec<serde_json::Value> = messages
+        .iter()
+        .map(|msg| {
+            serde_json::json!({
+                "role": msg.role_str(),
+                "content": msg.text()
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "model": model,
+        "messages": conversation,
+        "temperature": 0.7
+    })
+}
+
+/// Send a chat request and stream the response
+fn send_streaming_request(
+    endpoint: &Endpoint,
+    model: &str,
+    messages: Vec<ChatMessage>,
+    event_tx: &Sender<Event>,
+) -> Result<(), LlmError> {
+    let mut body = completions_body(model, &messages);
+    body["stream"] = serde_json::Value::Bool(true);
+    let response = endpoint.post_completions("chat", &body)?;
+
+    let reader = BufReader::new(response.into_reader());
+
+    for line in reader.lines() {
+        let line = line?;
+
+        if line.is_empty() || !line.starts_with("data: ") {
+            continue;
+        }
+
+        let data = &line[6..];
+
+        if data == "[DONE]" {
+            break;
+        }
+
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
+            // Extract content from delta
+            if let Some(choices) = json.get("choices").and_then(|c| c.as_array()) {
+                if let Some(choice) = choices.first() {
+                    if let Some(delta) = choice.get("delta") {
+                        if let Some(content) = delta.get("content").and_then(|c| c.as_str()) {
+                            if !content.is_empty()
+                                && event_tx.send(Event::LlmChunk(content.to_string())).is_err()
+                            {
+                                break;
+                            }
+                        }
+                    }
+
+                    // Check for finish_reason
+                    if let Some(finish_reason) = choice.get("finish_reason") {
+                        if !finish_reason.is_null() {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            // Check for errors
+            if let Some(error) = json.get("error") {
+                let error_msg = error
+                    .get("message")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("Unknown error");
+                return Err(LlmError::Api {
+                    status: 500,
+                    message: error_msg.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_config() {
+        let mut config = ProviderConfig {
+            base_url: Some("http://localhost:1234/v1/".to_string()),
+            api_key: Some("sk-local".to_string()),
+            models: vec!["qwen2.5-coder".to_string(), "llama3.1".to_string()],
+            ..Default::default()
+        };
+        config
+            .extra_headers
+            .insert("X-Title".to_string(), "Axiom".to_string());
+
+        let provider = OpenAICompatibleProvider::from_config("lmstudio", &config).unwrap();
+        assert_eq!(provider.id(), "lmstudio");
+        assert_eq!(provider.base_url(), "http://localhost:1234/v1");
+        assert_eq!(provider.model(), "qwen2.5-coder");
+        assert_eq!(provider.list_models().unwrap(), config.models);
+        assert_eq!(provider.endpoint.headers["X-Title"], "Axiom");
+        assert_eq!(provider.status(), ProviderStatus::Ready);
+
+        config.base_url = None;
+        assert!(OpenAICompatibleProvider::from_config("lmstudio", &config).is_none());
+    }
+
+    #[test]
+    fn test_key_only_required_when_asked() {
+        let local = OpenAICompatibleProvider::new("vllm", "http://localhost:8000/v1", "m");
+        assert!(local.status().is_ready());
+        let hosted = OpenAICompatibleProvider::new("openai", "https://api.openai.com/v1", "m")
+            .requiring_key();
+        assert!(!hosted.status().is_ready());
+        assert!(hosted.with_api_key("sk").status().is_ready());
+    }
+}
@@ -1,8 +1,13 @@
 //! LLM provider trait and capabilities
 
-use super::{ChatMessage, LlmError};
+use super::tools::{collect_reply, fallback_prompt, parse_fallback_reply};
+use super::{ChatMessage, LlmError, ToolReply, ToolSpec};
 use crate::events::Event;
 use crossbeam_channel::Sender;
+use std::time::Duration;
+
+/// How long a health check waits for a provider to answer
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Provider capabilities flags
 #[derive(Debug, Clone, Default)]
@@ -73,6 +78,29 @@ pub trait LlmProvider: Send + Sync {
     /// Get current provider status
     fn status(&self) -> ProviderStatus;
 
+    /// Check the provider answers and fetch the models it serves
+    ///
+    /// Blocks on a network request of up to [`PROBE_TIMEOUT`]. The default
+    /// trusts `status` and returns the configured models.
+    fn probe(&self) -> Result<Vec<String>, LlmError> {
+        match self.status() {
+            ProviderStatus::Unavailable(reason) => Err(LlmError::ProviderUnavailable(reason)),
+            _ => self.list_models(),
+        }
+    }
+
+    /// Embed each of `texts` with the embedding `model`, in order
+    ///
+    /// Blocks on a network request. The default fails, for providers
+    /// without an embeddings API.
+    fn embed(&self, model: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        let _ = (model, texts);
+        Err(LlmError::InvalidRequest(format!(
+            "{} has no embedding models",
+            self.name()
+        )))
+    }
+
     /// Send a message and stream the response
     ///
     /// This method initiates a request to the LLM provider and streams the
@@ -84,11 +112,81 @@ pub trait LlmProvider: Send + Sync {
     /// - `Event::LlmError(String)` - An error occurred
     fn send_message(&self, messages: Vec<ChatMessage>, event_tx: Sender<Event>);
 
+    /// Send a message offering `tools`; returns the reply text and the calls
+    /// the model made
+    ///
+    /// Blocks until the whole reply has arrived. Providers with native tool
+    /// calling override this; the default describes the tools in a system
+    /// prompt and reads the calls from a JSON reply.
+    fn chat_with_tools(
+        &self,
+        mut messages: Vec<ChatMessage>,
+        tools: &[ToolSpec],
+    ) -> Result<ToolReply, LlmError> {
+        messages.insert(0, ChatMessage::system(fallback_prompt(tools)));
+        let (tx, rx) = crossbeam_channel::unbounded();
+        self.send_message(messages, tx);
+        let reply = collect_reply(&rx)?;
+        Ok(parse_fallback_reply(&reply, tools))
+    }
+
     /// Check if the provider is currently available
     fn is_available(&self) -> bool {
         self.status().is_ready()
     }
 }
 
+/// Run a model listing request and collect `field` from each entry of the
+/// `list` array in the reply
+pub(super) fn fetch_model_ids(
+    request: ureq::Request,
+    list: &str,
+    field: &str,
+) -> Result<Vec<String>, LlmError> {
+    let json: serde_json::Value = request.timeout(PROBE_TIMEOUT).call()?.into_json()?;
+    let entries = json
+        .get(list)
+        .and_then(|entries| entries.as_array())
+        .ok_or_else(|| LlmError::Internal(format!("Model list has no `{}` array", list)))?;
+    Ok(entries
+        .iter()
+        .filter_map(|entry| entry.get(field).and_then(|id| id.as_str()))
+        .map(|id| id.to_string())
+        .collect())
+}
+
+/// The vectors in an embeddings reply: `field` of each entry of the `list`
+/// array, or the entries themselves when `field` is None
+///
+/// Fails unless there is one per text sent.
+pub(super) fn embedding_vectors(
+    json: &serde_json::Value,
+    list: &str,
+    field: Option<&str>,
+    expected: usize,
+) -> Result<Vec<Vec<f32>>, LlmError> {
+    let entries = json
+        .get(list)
+        .and_then(|entries| entries.as_array())
+        .ok_or_else(|| LlmError::Internal(format!("Embeddings reply has no `{}` array", list)))?;
+    let vectors: Vec<Vec<f32>> = entries
+        .iter()
+        .filter_map(|entry| match field {
+            Some(field) => entry.get(field),
+            None => Some(entry),
+        })
+        .filter_map(|vector| vector.as_array())
+        .map(|values| values.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect())
+        .collect();
+    if vectors.len() != expected {
+        return Err(LlmError::Internal(format!(
+            "Expected {} embeddings, got {}",
+            expected,
+            vectors.len()
+        )));
+    }
+    Ok(vectors)
+}
+
 /// Wrapper to make Box<dyn LlmProvider> cloneable via Arc
 pub type SharedProvider = std::sync::Arc<dyn LlmProvider>;
@@ -1,9 +1,17 @@
 //! Provider registry for managing multiple LLM providers
 
-use super::{LlmError, ProviderStatus, SharedProvider};
-use crate::config::AxiomConfig;
+use super::routing::{check_route, ProviderRouting, RouteCheck, RoutingPolicy};
+use super::{
+    ClaudeProvider, GeminiProvider, LlmError, LlmProvider, OllamaProvider,
+    OpenAICompatibleProvider, OpenAIProvider, ProviderStatus, SharedProvider,
+};
+use crate::config::{AxiomConfig, BUILTIN_PROVIDERS};
+use crate::notifications::Notification;
+use crate::orchestration::AgentRole;
+use crate::types;
 use parking_lot::RwLock;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Registry for managing multiple LLM providers
 pub struct ProviderRegistry {
@@ -15,6 +23,15 @@ pub struct ProviderRegistry {
 
     /// Fallback chain for automatic failover
     fallback_chain: Vec<String>,
+
+    /// Region and data classification per provider
+    routing: HashMap<String, ProviderRouting>,
+
+    /// Status found by the last probe of each provider
+    probed: RwLock<HashMap<String, ProviderStatus>>,
+
+    /// Providers answering for agent roles mapped in `[llm.agents]`
+    roles: HashMap<String, SharedProvider>,
 }
 
 impl ProviderRegistry {
@@ -24,6 +41,9 @@ impl ProviderRegistry {
             providers: HashMap::new(),
             active_provider: RwLock::new(String::new()),
             fallback_chain: Vec::new(),
+            routing: HashMap::new(),
+            probed: RwLock::new(HashMap::new()),
+            roles: HashMap::new(),
         }
     }
 
@@ -42,10 +62,41 @@ impl ProviderRegistry {
             .filter(|(_, cfg)| cfg.enabled)
             .map(|(name, _)| name.clone())
             .collect();
+        registry.routing = config.llm.routing.clone();
 
         registry
     }
 
+    /// Register the enabled providers in `config` that have what they need
+    /// to connect (an API key for hosted ones), and every enabled custom
+    /// OpenAI-compatible endpoint
+    ///
+    /// Each role mapped in `[llm.agents]` gets an instance of its own so
+    /// that its model doesn't change with the one picked for the provider.
+    pub fn register_configured(&mut self, config: &AxiomConfig) {
+        let custom = config
+            .llm
+            .providers
+            .keys()
+            .map(String::as_str)
+            .filter(|id| !BUILTIN_PROVIDERS.contains(id));
+        for id in ["ollama", "claude", "gemini", "openai"]
+            .into_iter()
+            .chain(custom)
+        {
+            if let Some(provider) = configured_provider(config, id, None) {
+                self.register(provider);
+            }
+        }
+
+        for (role, agent) in &config.llm.agents {
+            let model = Some(agent.model.as_str()).filter(|model| !model.is_empty());
+            if let Some(provider) = configured_provider(config, &agent.provider, model) {
+                self.roles.insert(role.clone(), provider);
+            }
+        }
+    }
+
     /// Register a provider
     pub fn register(&mut self, provider: SharedProvider) {
         let id = provider.id().to_string();
@@ -63,6 +114,15 @@ impl ProviderRegistry {
         self.get(&id)
     }
 
+    /// The provider answering for `role`: the one mapped to it, else the
+    /// active one
+    pub fn for_role(&self, role: AgentRole) -> Option<SharedProvider> {
+        self.roles
+            .get(role.as_str())
+            .cloned()
+            .or_else(|| self.active())
+    }
+
     /// Set the active provider
     pub fn set_active(&self, id: &str) -> Result<(), LlmError> {
         if !self.providers.contains_key(id) {
@@ -101,6 +161,12 @@ impl ProviderRegistry {
         None
     }
 
+    /// Check whether a workspace with `policy` may send content to a provider
+    pub fn check_route(&self, provider_id: &str, policy: &RoutingPolicy) -> RouteCheck {
+        let routing = self.routing.get(provider_id).cloned().unwrap_or_default();
+        check_route(provider_id, &routing, policy)
+    }
+
     /// Get all registered provider IDs
     pub fn provider_ids(&self) -> Vec<String> {
         self.providers.keys().cloned().collect()
@@ -141,6 +207,48 @@ impl ProviderRegistry {
         models
     }
 
+    /// Health-check every provider at once and list the models each serves
+    ///
+    /// Probes run on blocking threads, each bounded by
+    /// [`PROBE_TIMEOUT`](super::provider::PROBE_TIMEOUT). Results are sorted
+    /// by provider ID; `changed` marks those whose status differs from the
+    /// previous probe (or that weren't probed before).
+    pub async fn probe_all(&self) -> Vec<ProviderProbe> {
+        let providers: Vec<SharedProvider> = self.providers.values().cloned().collect();
+        let probes = futures::future::join_all(providers.into_iter().map(|provider| {
+            tokio::task::spawn_blocking(move || {
+                let result = provider.probe();
+                (provider, result)
+            })
+        }))
+        .await;
+
+        let mut probed = self.probed.write();
+        let mut results: Vec<ProviderProbe> = probes
+            .into_iter()
+            .filter_map(|joined| joined.ok())
+            .map(|(provider, result)| {
+                let id = provider.id().to_string();
+                let (status, models) = match result {
+                    Ok(models) => (ProviderStatus::Ready, models),
+                    Err(LlmError::RateLimited { .. }) => (ProviderStatus::RateLimited, Vec::new()),
+                    Err(e) => (ProviderStatus::Unavailable(e.to_string()), Vec::new()),
+                };
+                let changed = probed.insert(id.clone(), status.clone()).as_ref() != Some(&status);
+                ProviderProbe {
+                    id,
+                    name: provider.name().to_string(),
+                    model: provider.model(),
+                    status,
+                    models,
+                    changed,
+                }
+            })
+            .collect();
+        results.sort_by(|a, b| a.id.cmp(&b.id));
+        results
+    }
+
     /// Set model on a specific provider
     pub fn set_model(&self, provider_id: &str, model: &str) -> Result<(), LlmError> {
         let provider = self
@@ -165,6 +273,52 @@ pub struct ProviderInfo {
     pub status: ProviderStatus,
 }
 
+/// What [`ProviderRegistry::probe_all`] found for one provider
+#[derive(Debug, Clone)]
+pub struct ProviderProbe {
+    pub id: String,
+    pub name: String,
+    /// The active model
+    pub model: String,
+    pub status: ProviderStatus,
+    /// Models the provider reported; empty when it couldn't be reached
+    pub models: Vec<String>,
+    /// Status differs from the previous probe
+    pub changed: bool,
+}
+
+impl ProviderProbe {
+    /// The probe as sent to UIs
+    pub fn info(&self) -> types::ProviderInfo {
+        types::ProviderInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            status: types::ProviderStatus::from(&self.status),
+            current_model: Some(self.model.clone()),
+            models: self.models.clone(),
+        }
+    }
+
+    /// A `ProviderStatusChanged` notification for the probe
+    pub fn notification(&self) -> Notification {
+        Notification::ProviderStatusChanged {
+            provider_id: self.id.clone(),
+            status: types::ProviderStatus::from(&self.status),
+            models: self.models.clone(),
+        }
+    }
+}
+
+impl From<&ProviderStatus> for types::ProviderStatus {
+    fn from(status: &ProviderStatus) -> Self {
+        match status {
+            ProviderStatus::Ready | ProviderStatus::Busy => types::ProviderStatus::Available,
+            ProviderStatus::Unavailable(reason) => types::ProviderStatus::Error(reason.clone()),
+            ProviderStatus::RateLimited => types::ProviderStatus::Error("Rate limited".to_string()),
+        }
+    }
+}
+
 impl ProviderInfo {
     /// Get a display string for the provider
     pub fn display(&self) -> String {
@@ -181,3 +335,194 @@ impl ProviderInfo {
         }
     }
 }
+
+/// A provider for `id` as configured, answering with `model` rather than
+/// its default model when one is given; None when the provider is disabled
+/// or can't connect without an API key
+fn configured_provider(
+    config: &AxiomConfig,
+    id: &str,
+    model: Option<&str>,
+) -> Option<SharedProvider> {
+    let cfg = config.llm.providers.get(id).filter(|cfg| cfg.enabled)?;
+    let pick = |default: &str| {
+        model
+            .or(cfg.default_model.as_deref())
+            .unwrap_or(default)
+            .to_string()
+    };
+
+    let provider: SharedProvider = match id {
+        "ollama" => {
+            let model = pick("gemma3:4b");
+            Arc::new(match &cfg.base_url {
+                Some(url) => OllamaProvider::new(url, &model),
+                None => OllamaProvider::with_model(&model),
+            })
+        }
+        "claude" => {
+            let mut provider =
+                ClaudeProvider::new(cfg.api_key.as_ref()?, &pick("claude-sonnet-4-20250514"));
+            if let Some(url) = &cfg.base_url {
+                provider = provider.with_base_url(url);
+            }
+            Arc::new(provider)
+        }
+        "gemini" => {
+            let mut provider =
+                GeminiProvider::new(cfg.api_key.as_ref()?, &pick("gemini-2.0-flash"));
+            if let Some(url) = &cfg.base_url {
+                provider = provider.with_base_url(url);
+            }
+            Arc::new(provider)
+        }
+        "openai" => {
+            let mut provider = OpenAIProvider::new(cfg.api_key.as_ref()?, &pick("gpt-4o"));
+            if let Some(url) = &cfg.base_url {
+                provider = provider.with_base_url(url);
+            }
+            for (name, value) in &cfg.extra_headers {
+                provider = provider.with_header(name, value);
+            }
+            Arc::new(provider)
+        }
+        _ => {
+            let provider = OpenAICompatibleProvider::from_config(id, cfg)?;
+            if let Some(model) = model {
+                provider.set_model(model).ok()?;
+            }
+            Arc::new(provider)
+        }
+    };
+    Some(provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Event;
+    use crate::llm::{ChatMessage, LlmProvider, ProviderCapabilities};
+    use crossbeam_channel::Sender;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Provider whose reachability the test switches
+    struct Stub {
+        up: AtomicBool,
+    }
+
+    impl LlmProvider for Stub {
+        fn id(&self) -> &str {
+            "stub"
+        }
+
+        fn name(&self) -> &str {
+            "Stub"
+        }
+
+        fn model(&self) -> String {
+            "stub-1".to_string()
+        }
+
+        fn set_model(&self, _model: &str) -> Result<(), LlmError> {
+            Ok(())
+        }
+
+        fn list_models(&self) -> Result<Vec<String>, LlmError> {
+            Ok(vec!["stub-1".to_string(), "stub-2".to_string()])
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities::default()
+        }
+
+        fn status(&self) -> ProviderStatus {
+            if self.up.load(Ordering::SeqCst) {
+                ProviderStatus::Ready
+            } else {
+                ProviderStatus::Unavailable("down".to_string())
+            }
+        }
+
+        fn send_message(&self, _messages: Vec<ChatMessage>, _event_tx: Sender<Event>) {}
+    }
+
+    #[test]
+    fn test_probe_all_reports_changes() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let stub = Arc::new(Stub {
+            up: AtomicBool::new(true),
+        });
+        let mut registry = ProviderRegistry::new();
+        registry.register(stub.clone());
+
+        let probes = runtime.block_on(registry.probe_all());
+        assert_eq!(probes.len(), 1);
+        assert!(probes[0].changed);
+        assert_eq!(probes[0].status, ProviderStatus::Ready);
+        assert_eq!(probes[0].models, vec!["stub-1", "stub-2"]);
+
+        let probes = runtime.block_on(registry.probe_all());
+        assert!(!probes[0].changed);
+
+        stub.up.store(false, Ordering::SeqCst);
+        let probes = runtime.block_on(registry.probe_all());
+        assert!(probes[0].changed);
+        assert!(probes[0].models.is_empty());
+        assert_eq!(
+            probes[0].info().status,
+            types::ProviderStatus::Error("Provider unavailable: down".to_string())
+        );
+    }
+
+    #[test]
+    fn test_register_configured_custom_endpoints() {
+        let mut config = AxiomConfig::default();
+        for (id, port) in [("litellm", 4000), ("vllm", 8000)] {
+            let endpoint = crate::config::ProviderConfig {
+                base_url: Some(format!("http://localhost:{}/v1", port)),
+                default_model: Some("qwen2.5-coder".to_string()),
+                ..Default::default()
+            };
+            config.llm.providers.insert(id.to_string(), endpoint);
+        }
+        config.llm.providers.get_mut("vllm").unwrap().enabled = false;
+
+        let mut registry = ProviderRegistry::new();
+        registry.register_configured(&config);
+        let litellm = registry.get("litellm").unwrap();
+        assert_eq!(litellm.model(), "qwen2.5-coder");
+        assert!(litellm.status().is_ready());
+        assert!(registry.get("vllm").is_none());
+    }
+
+    #[test]
+    fn test_roles_use_their_own_model() {
+        let mut config = AxiomConfig::default();
+        let mapping = |model: &str| crate::config::AgentModelConfig {
+            provider: "ollama".to_string(),
+            model: model.to_string(),
+        };
+        config
+            .llm
+            .agents
+            .insert("reviewer".to_string(), mapping("qwen2.5-coder"));
+        config
+            .llm
+            .agents
+            .insert("developer".to_string(), mapping(""));
+
+        let mut registry = ProviderRegistry::new();
+        registry.register_configured(&config);
+        registry.set_active("ollama").unwrap();
+        let reviewer = registry.for_role(AgentRole::Reviewer).unwrap();
+        assert_eq!(reviewer.model(), "qwen2.5-coder");
+
+        // Changing the active provider's model leaves the role's alone
+        registry.active().unwrap().set_model("llama3").unwrap();
+        assert_eq!(reviewer.model(), "qwen2.5-coder");
+        let developer = registry.for_role(AgentRole::Developer).unwrap();
+        assert_eq!(developer.model(), "gemma3:4b");
+        let orchestrator = registry.for_role(AgentRole::Orchestrator).unwrap();
+        assert_eq!(orchestrator.model(), "llama3");
+    }
+}
@@ -0,0 +1,244 @@
+//! Data-residency and sensitivity routing
+//!
+//! Providers can be marked with the region they process data in and the
+//! most sensitive class of data they are cleared for
+//! (`[llm.routing.<provider>]` in `.axiom.toml`). Workspaces declare how
+//! sensitive their content is and which regions it may go to (`[routing]` in
+//! `.axiom/config.toml`). A provider that doesn't satisfy both is refused,
+//! or only warned about with `enforce = "warn"`.
+//!
+//! Unmarked providers count as cleared for internal data in any region, and
+//! workspaces default to internal, so nothing changes until either side is
+//! marked.
+
+use serde::{Deserialize, Serialize};
+
+/// How sensitive data is, least to most
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Sensitivity {
+    Public,
+    #[default]
+    Internal,
+    Confidential,
+    Restricted,
+}
+
+impl Sensitivity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Sensitivity::Public => "public",
+            Sensitivity::Internal => "internal",
+            Sensitivity::Confidential => "confidential",
+            Sensitivity::Restricted => "restricted",
+        }
+    }
+}
+
+/// Where and for what a provider may process data (`[llm.routing.<provider>]`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderRouting {
+    /// Region requests are processed in, e.g. "eu", "us" or "local"
+    #[serde(default)]
+    pub region: Option<String>,
+
+    /// Most sensitive data the provider may receive (internal if unset)
+    #[serde(default)]
+    pub classification: Option<Sensitivity>,
+}
+
+/// What happens when a route breaks the policy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Enforcement {
+    /// Refuse to send
+    #[default]
+    Block,
+    /// Send, but warn and record it
+    Warn,
+}
+
+/// Routing requirements of a workspace (`[routing]`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingPolicy {
+    /// How sensitive the workspace's content is
+    #[serde(default)]
+    pub sensitivity: Sensitivity,
+
+    /// Regions content may be sent to (any if empty)
+    #[serde(default)]
+    pub allowed_regions: Vec<String>,
+
+    #[serde(default)]
+    pub enforce: Enforcement,
+}
+
+impl RoutingPolicy {
+    /// Why `provider` may not receive this workspace's content, if it may not
+    pub fn violation(&self, provider: &str, routing: &ProviderRouting) -> Option<String> {
+        let cleared = routing.classification.unwrap_or_default();
+        if cleared < self.sensitivity {
+            return Some(format!(
+                "{} is cleared for {} data, this workspace is {}",
+                provider,
+                cleared.label(),
+                self.sensitivity.label()
+            ));
+        }
+        if self.allowed_regions.is_empty() {
+            return None;
+        }
+        let allowed = self.allowed_regions.join(", ");
+        match &routing.region {
+            Some(region)
+                if self
+                    .allowed_regions
+                    .iter()
+                    .any(|r| r.eq_ignore_ascii_case(region)) =>
+            {
+                None
+            }
+            Some(region) => Some(format!(
+                "{} processes data in {}, this workspace only allows {}",
+                provider, region, allowed
+            )),
+            None => Some(format!(
+                "{} has no region set, this workspace only allows {}",
+                provider, allowed
+            )),
+        }
+    }
+}
+
+/// Outcome of checking whether content may go to a provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteCheck {
+    Allowed,
+    /// Sent despite a violation (`enforce = "warn"` or a user override)
+    Warn(String),
+    /// Not sent
+    Blocked(String),
+}
+
+/// Check `policy` against a provider's routing marks
+pub fn check_route(
+    provider: &str,
+    routing: &ProviderRouting,
+    policy: &RoutingPolicy,
+) -> RouteCheck {
+    match policy.violation(provider, routing) {
+        None => RouteCheck::Allowed,
+        Some(reason) if policy.enforce == Enforcement::Warn => RouteCheck::Warn(reason),
+        Some(reason) => RouteCheck::Blocked(reason),
+    }
+}
+
+/// Error shown when content isn't sent to `provider`
+pub fn blocked_message(provider: &str, reason: &str) -> String {
+    format!(
+        "Routing blocked: {} (/routing allow {} <reason> to override)",
+        reason, provider
+    )
+}
+
+/// Plain-text policy and per-provider status for `/routing`
+pub fn routing_summary(
+    policy: &RoutingPolicy,
+    routes: &[(String, RouteCheck)],
+    overrides: &[String],
+) -> String {
+    let regions = if policy.allowed_regions.is_empty() {
+        "any region".to_string()
+    } else {
+        policy.allowed_regions.join(", ")
+    };
+    let mut text = format!(
+        "Workspace data: {}, {} ({})\n",
+        policy.sensitivity.label(),
+        regions,
+        match policy.enforce {
+            Enforcement::Block => "blocking",
+            Enforcement::Warn => "warning only",
+        }
+    );
+    for (provider, check) in routes {
+        let status = match check {
+            RouteCheck::Allowed => "allowed".to_string(),
+            RouteCheck::Warn(reason) => format!("warn: {}", reason),
+            RouteCheck::Blocked(reason) if overrides.contains(provider) => {
+                format!("overridden: {}", reason)
+            }
+            RouteCheck::Blocked(reason) => format!("blocked: {}", reason),
+        };
+        text.push_str(&format!("\n  {:<10} {}", provider, status));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(region: &str, classification: Sensitivity) -> ProviderRouting {
+        ProviderRouting {
+            region: Some(region.to_string()),
+            classification: Some(classification),
+        }
+    }
+
+    #[test]
+    fn test_unmarked_defaults_allow() {
+        let policy = RoutingPolicy::default();
+        assert_eq!(
+            check_route("claude", &ProviderRouting::default(), &policy),
+            RouteCheck::Allowed
+        );
+    }
+
+    #[test]
+    fn test_sensitivity_and_region() {
+        let policy = RoutingPolicy {
+            sensitivity: Sensitivity::Confidential,
+            allowed_regions: vec!["eu".to_string(), "local".to_string()],
+            ..Default::default()
+        };
+
+        let local = provider("local", Sensitivity::Restricted);
+        assert_eq!(check_route("ollama", &local, &policy), RouteCheck::Allowed);
+
+        let us = provider("us", Sensitivity::Confidential);
+        assert_eq!(
+            check_route("claude", &us, &policy),
+            RouteCheck::Blocked(
+                "claude processes data in us, this workspace only allows eu, local".to_string()
+            )
+        );
+
+        let internal = provider("EU", Sensitivity::Internal);
+        assert!(matches!(
+            check_route("gemini", &internal, &policy),
+            RouteCheck::Blocked(reason) if reason.contains("cleared for internal")
+        ));
+
+        let warn = RoutingPolicy {
+            enforce: Enforcement::Warn,
+            ..policy
+        };
+        assert!(matches!(
+            check_route("claude", &us, &warn),
+            RouteCheck::Warn(_)
+        ));
+    }
+
+    #[test]
+    fn test_policy_from_toml() {
+        let policy: RoutingPolicy = toml::from_str(
+            "sensitivity = \"restricted\"\nallowed_regions = [\"local\"]\nenforce = \"warn\"",
+        )
+        .unwrap();
+        assert_eq!(policy.sensitivity, Sensitivity::Restricted);
+        assert_eq!(policy.enforce, Enforcement::Warn);
+    }
+}
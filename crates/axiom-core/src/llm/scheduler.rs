@@ -0,0 +1,350 @@
+//! Quota-aware request scheduling for rate-limited providers
+//!
+//! Every outgoing LLM request waits for a slot from its provider's
+//! [`QuotaScheduler`]. Slots respect the configured requests-per-minute and
+//! tokens-per-minute budgets, are spaced evenly instead of bursting, and go
+//! to the waiting agent that was served least recently. A 429 response
+//! pauses the whole provider for the server's `retry-after` before retrying.
+
+use crate::usage::estimate_tokens;
+use parking_lot::{Condvar, Mutex};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Length of the sliding budget window
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Retries after a 429 before the error is returned to the caller
+pub const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Pause after a 429 without a usable `retry-after` header
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Per-provider request budgets (`[llm.rate_limits.<provider>]`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimits {
+    /// Maximum requests started per minute
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+
+    /// Maximum (estimated) prompt tokens sent per minute
+    #[serde(default)]
+    pub tokens_per_minute: Option<u32>,
+}
+
+/// A request waiting for a slot
+struct Waiter {
+    ticket: u64,
+    agent: String,
+    tokens: u64,
+}
+
+/// Outcome of looking for the next slot
+#[derive(Debug, PartialEq)]
+enum Slot {
+    /// The waiter with this ticket may start now
+    Grant(u64),
+    /// Nothing may start for this long
+    Wait(Duration),
+    /// Nobody is waiting
+    Idle,
+}
+
+#[derive(Default)]
+struct State {
+    limits: RateLimits,
+    /// Requests started within the window: (start, tokens)
+    window: VecDeque<(Instant, u64)>,
+    waiting: Vec<Waiter>,
+    /// Sequence number of each agent's last grant
+    last_served: HashMap<String, u64>,
+    grants: u64,
+    next_ticket: u64,
+    last_start: Option<Instant>,
+    paused_until: Option<Instant>,
+}
+
+impl State {
+    fn enqueue(&mut self, agent: &str, tokens: u64) -> u64 {
+        let ticket = self.next_ticket;
+        self.next_ticket += 1;
+        self.waiting.push(Waiter {
+            ticket,
+            agent: agent.to_string(),
+            tokens,
+        });
+        ticket
+    }
+
+    /// Decide who may start at `now`
+    fn next_slot(&mut self, now: Instant) -> Slot {
+        while self
+            .window
+            .front()
+            .is_some_and(|(start, _)| now.duration_since(*start) >= WINDOW)
+        {
+            self.window.pop_front();
+        }
+
+        // Least recently served agent first, then first come first served
+        let Some(next) = self
+            .waiting
+            .iter()
+            .min_by_key(|w| (self.last_served.get(&w.agent).copied(), w.ticket))
+        else {
+            return Slot::Idle;
+        };
+
+        let mut ready_at = now;
+        if let Some(until) = self.paused_until {
+            ready_at = ready_at.max(until);
+        }
+        if let Some(rpm) = self.limits.requests_per_minute.filter(|&n| n > 0) {
+            // Spread requests evenly over the minute instead of bursting
+            if let Some(last) = self.last_start {
+                ready_at = ready_at.max(last + WINDOW / rpm);
+            }
+            if self.window.len() >= rpm as usize {
+                ready_at = ready_at.max(self.window[self.window.len() - rpm as usize].0 + WINDOW);
+            }
+        }
+        if let Some(tpm) = self.limits.tokens_per_minute {
+            // Drop the oldest starts until the request fits; a request
+            // larger than the whole budget goes once the window is empty
+            let mut used: u64 = self.window.iter().map(|(_, t)| t).sum();
+            for (start, tokens) in &self.window {
+                if used + next.tokens <= u64::from(tpm) {
+                    break;
+                }
+                used -= tokens;
+                ready_at = ready_at.max(*start + WINDOW);
+            }
+        }
+
+        if ready_at > now {
+            Slot::Wait(ready_at - now)
+        } else {
+            Slot::Grant(next.ticket)
+        }
+    }
+
+    /// Start the request holding `ticket`
+    fn grant(&mut self, ticket: u64, now: Instant) {
+        let Some(pos) = self.waiting.iter().position(|w| w.ticket == ticket) else {
+            return;
+        };
+        let waiter = self.waiting.remove(pos);
+        self.grants += 1;
+        self.last_served.insert(waiter.agent, self.grants);
+        self.window.push_back((now, waiter.tokens));
+        self.last_start = Some(now);
+    }
+}
+
+/// Request queue and budgets of one provider
+#[derive(Default)]
+pub struct QuotaScheduler {
+    state: Mutex<State>,
+    wake: Condvar,
+}
+
+impl QuotaScheduler {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            state: Mutex::new(State {
+                limits,
+                ..Default::default()
+            }),
+            wake: Condvar::new(),
+        }
+    }
+
+    /// Replace the budgets (waiting requests are re-evaluated)
+    pub fn set_limits(&self, limits: RateLimits) {
+        self.state.lock().limits = limits;
+        self.wake.notify_all();
+    }
+
+    /// Block until `agent` may send a request of about `tokens` prompt tokens
+    pub fn acquire(&self, agent: &str, tokens: u64) {
+        let mut state = self.state.lock();
+        let ticket = state.enqueue(agent, tokens);
+        loop {
+            match state.next_slot(Instant::now()) {
+                Slot::Grant(t) if t == ticket => {
+                    state.grant(ticket, Instant::now());
+                    // The next waiter may be able to go after us
+                    self.wake.notify_all();
+                    return;
+                }
+                Slot::Grant(_) => {
+                    self.wake.notify_all();
+                    self.wake.wait(&mut state);
+                }
+                Slot::Wait(wait) => {
+                    self.wake.wait_for(&mut state, wait);
+                }
+                Slot::Idle => return,
+            }
+        }
+    }
+
+    /// Hold all requests to this provider for `duration` (after a 429)
+    pub fn pause(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut state = self.state.lock();
+        state.paused_until = Some(state.paused_until.map_or(until, |p| p.max(until)));
+        drop(state);
+        self.wake.notify_all();
+    }
+
+    /// Time left on a 429 pause, if any
+    pub fn paused_for(&self) -> Option<Duration> {
+        let until = self.state.lock().paused_until?;
+        until
+            .checked_duration_since(Instant::now())
+            .filter(|d| !d.is_zero())
+    }
+}
+
+fn schedulers() -> &'static Mutex<HashMap<String, Arc<QuotaScheduler>>> {
+    static SCHEDULERS: OnceLock<Mutex<HashMap<String, Arc<QuotaScheduler>>>> = OnceLock::new();
+    SCHEDULERS.get_or_init(Default::default)
+}
+
+/// Scheduler shared by all requests to `provider`
+pub fn scheduler(provider: &str) -> Arc<QuotaScheduler> {
+    schedulers()
+        .lock()
+        .entry(provider.to_string())
+        .or_default()
+        .clone()
+}
+
+/// Apply configured budgets; providers not listed become unlimited
+pub fn configure_rate_limits(limits: &HashMap<String, RateLimits>) {
+    let mut all = schedulers().lock();
+    for (provider, scheduler) in all.iter() {
+        scheduler.set_limits(limits.get(provider).copied().unwrap_or_default());
+    }
+    for (provider, limits) in limits {
+        all.entry(provider.clone())
+            .or_insert_with(|| Arc::new(QuotaScheduler::new(*limits)));
+    }
+}
+
+/// POST `body` through `provider`'s scheduler, retrying on HTTP 429
+///
+/// `request` builds a fresh request for every attempt. The body's length
+/// is what counts against the token budget.
+#[allow(clippy::result_large_err)]
+pub fn send_with_quota(
+    provider: &str,
+    agent: &str,
+    request: impl Fn() -> ureq::Request,
+    body: &serde_json::Value,
+) -> Result<ureq::Response, ureq::Error> {
+    let scheduler = scheduler(provider);
+    let tokens = estimate_tokens(&body.to_string());
+    let mut attempt = 0;
+    loop {
+        scheduler.acquire(agent, tokens);
+        match request().send_json(body) {
+            Err(ureq::Error::Status(429, response)) if attempt < MAX_RATE_LIMIT_RETRIES => {
+                let wait = retry_after(&response).unwrap_or(DEFAULT_BACKOFF * 2u32.pow(attempt));
+                scheduler.pause(wait);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Delay requested by a `retry-after` header given in seconds
+fn retry_after(response: &ureq::Response) -> Option<Duration> {
+    let secs: f64 = response.header("retry-after")?.trim().parse().ok()?;
+    (secs >= 0.0).then(|| Duration::from_secs_f64(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(rpm: Option<u32>, tpm: Option<u32>) -> State {
+        State {
+            limits: RateLimits {
+                requests_per_minute: rpm,
+                tokens_per_minute: tpm,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn run(state: &mut State, now: Instant) -> Slot {
+        let slot = state.next_slot(now);
+        if let Slot::Grant(ticket) = slot {
+            state.grant(ticket, now);
+        }
+        slot
+    }
+
+    #[test]
+    fn test_requests_are_spaced() {
+        let t0 = Instant::now();
+        let mut s = state(Some(30), None);
+        s.enqueue("a", 10);
+        s.enqueue("a", 10);
+
+        assert_eq!(run(&mut s, t0), Slot::Grant(0));
+        assert_eq!(run(&mut s, t0), Slot::Wait(Duration::from_secs(2)));
+        assert_eq!(run(&mut s, t0 + Duration::from_secs(2)), Slot::Grant(1));
+        assert_eq!(run(&mut s, t0 + Duration::from_secs(2)), Slot::Idle);
+    }
+
+    #[test]
+    fn test_token_budget() {
+        let t0 = Instant::now();
+        let mut s = state(None, Some(1000));
+        s.enqueue("a", 800);
+        s.enqueue("a", 300);
+
+        assert_eq!(run(&mut s, t0), Slot::Grant(0));
+        assert_eq!(
+            run(&mut s, t0 + Duration::from_secs(20)),
+            Slot::Wait(Duration::from_secs(40))
+        );
+        assert_eq!(run(&mut s, t0 + WINDOW), Slot::Grant(1));
+    }
+
+    #[test]
+    fn test_fair_between_agents() {
+        let t0 = Instant::now();
+        let mut s = state(None, None);
+        let a1 = s.enqueue("coder", 1);
+        let a2 = s.enqueue("coder", 1);
+        let b1 = s.enqueue("reviewer", 1);
+
+        assert_eq!(run(&mut s, t0), Slot::Grant(a1));
+        // The reviewer hasn't been served yet, so it goes before coder's second
+        assert_eq!(run(&mut s, t0), Slot::Grant(b1));
+        assert_eq!(run(&mut s, t0), Slot::Grant(a2));
+    }
+
+    #[test]
+    fn test_pause_holds_requests() {
+        let scheduler = QuotaScheduler::new(RateLimits::default());
+        scheduler.pause(Duration::from_secs(30));
+        assert!(scheduler.paused_for().is_some());
+
+        let t0 = Instant::now();
+        let mut s = scheduler.state.lock();
+        s.enqueue("a", 1);
+        assert!(matches!(s.next_slot(t0), Slot::Wait(d) if d > Duration::from_secs(29)));
+        assert!(matches!(
+            s.next_slot(t0 + Duration::from_secs(31)),
+            Slot::Grant(_)
+        ));
+    }
+}
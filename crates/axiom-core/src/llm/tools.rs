@@ -0,0 +1,349 @@
+//! Tool (function) calling
+//!
+//! [`LlmProvider::chat_with_tools`](super::LlmProvider::chat_with_tools)
+//! offers the model a set of [`ToolSpec`]s and returns the calls it chose as
+//! [`ToolCall`]s. Claude, OpenAI and Gemini use their native tool APIs (the
+//! request and response translation lives here); other providers get the
+//! tools described in a system prompt and answer with JSON, read back by
+//! [`parse_fallback_reply`].
+
+use super::LlmError;
+use crate::events::Event;
+use crossbeam_channel::Receiver;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// A function the model may call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    /// JSON schema of the arguments object
+    pub parameters: Value,
+}
+
+impl ToolSpec {
+    pub fn new(name: impl Into<String>, description: impl Into<String>, parameters: Value) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            parameters,
+        }
+    }
+
+    /// A tool taking string arguments, given as `(name, description)`
+    ///
+    /// `required` names the arguments the model must always pass.
+    pub fn with_string_args(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        args: &[(&str, &str)],
+        required: &[&str],
+    ) -> Self {
+        let properties: serde_json::Map<String, Value> = args
+            .iter()
+            .map(|(arg, about)| {
+                (
+                    arg.to_string(),
+                    json!({ "type": "string", "description": about }),
+                )
+            })
+            .collect();
+        Self::new(
+            name,
+            description,
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            }),
+        )
+    }
+}
+
+/// A call the model made
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// Provider-assigned ID (`call_<n>` for providers that don't assign one)
+    pub id: String,
+    pub name: String,
+    /// Arguments object, as the model wrote it
+    pub arguments: Value,
+}
+
+impl ToolCall {
+    /// String argument `key`, if the model passed one
+    pub fn arg(&self, key: &str) -> Option<&str> {
+        self.arguments.get(key).and_then(Value::as_str)
+    }
+}
+
+/// Reply to a chat that offered tools
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolReply {
+    /// Text the model wrote alongside (or instead of) its calls
+    pub text: String,
+    pub calls: Vec<ToolCall>,
+}
+
+// ========== Fallback for providers without native tool calling ==========
+
+/// System prompt asking a model without native tool calling to answer in JSON
+pub fn fallback_prompt(tools: &[ToolSpec]) -> String {
+    let mut prompt = String::from(
+        "You can call the following tools. Each takes a JSON object of arguments \
+         matching its schema.\n\n",
+    );
+    for tool in tools {
+        prompt.push_str(&format!(
+            "- {}: {}\n  arguments schema: {}\n",
+            tool.name, tool.description, tool.parameters
+        ));
+    }
+    prompt.push_str(
+        "\nAnswer with a single JSON object and nothing else:\n\
+         {\"text\": \"<what to tell the user>\", \"tool_calls\": [{\"name\": \"<tool>\", \"arguments\": {...}}]}\n\
+         Leave \"tool_calls\" empty when no tool is needed.",
+    );
+    prompt
+}
+
+/// Read a reply written as [`fallback_prompt`] asks
+///
+/// A reply that isn't that JSON object is taken as plain text. Calls to tools
+/// not in `tools` are dropped.
+pub fn parse_fallback_reply(reply: &str, tools: &[ToolSpec]) -> ToolReply {
+    let json = reply
+        .find('{')
+        .zip(reply.rfind('}'))
+        .filter(|(start, end)| start < end)
+        .and_then(|(start, end)| serde_json::from_str::<Value>(&reply[start..=end]).ok())
+        .filter(|json| json.get("tool_calls").is_some() || json.get("text").is_some());
+    let Some(json) = json else {
+        return ToolReply {
+            text: reply.trim().to_string(),
+            calls: Vec::new(),
+        };
+    };
+
+    let calls = json
+        .get("tool_calls")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|call| {
+            let name = call.get("name")?.as_str()?;
+            tools.iter().any(|t| t.name == name).then_some((name, call))
+        })
+        .enumerate()
+        .map(|(i, (name, call))| ToolCall {
+            id: format!("call_{}", i),
+            name: name.to_string(),
+            arguments: call.get("arguments").cloned().unwrap_or_else(|| json!({})),
+        })
+        .collect();
+    ToolReply {
+        text: json
+            .get("text")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        calls,
+    }
+}
+
+/// Wait for a streamed reply on `rx` and return its text
+pub(super) fn collect_reply(rx: &Receiver<Event>) -> Result<String, LlmError> {
+    let mut text = String::new();
+    for event in rx {
+        match event {
+            Event::LlmChunk(chunk) => text.push_str(&chunk),
+            Event::LlmDone => break,
+            Event::LlmError(e) => return Err(LlmError::Internal(e)),
+            _ => {}
+        }
+    }
+    Ok(text)
+}
+
+// ========== Native formats ==========
+
+/// `tools` for the Anthropic Messages API
+pub fn claude_tools(tools: &[ToolSpec]) -> Value {
+    tools
+        .iter()
+        .map(|t| {
+            json!({
+                "name": t.name,
+                "description": t.description,
+                "input_schema": t.parameters,
+            })
+        })
+        .collect()
+}
+
+/// Text and `tool_use` blocks of an Anthropic Messages response
+pub fn claude_reply(response: &Value) -> ToolReply {
+    let mut reply = ToolReply::default();
+    for block in response["content"].as_array().into_iter().flatten() {
+        match block["type"].as_str() {
+            Some("text") => reply.text.push_str(block["text"].as_str().unwrap_or_default()),
+            Some("tool_use") => reply.calls.push(ToolCall {
+                id: block["id"].as_str().unwrap_or_default().to_string(),
+                name: block["name"].as_str().unwrap_or_default().to_string(),
+                arguments: block["input"].clone(),
+            }),
+            _ => {}
+        }
+    }
+    reply
+}
+
+/// `tools` for the OpenAI Chat Completions API
+pub fn openai_tools(tools: &[ToolSpec]) -> Value {
+    tools
+        .iter()
+        .map(|t| {
+            json!({
+                "type": "function",
+                "function": {
+                    "name": t.name,
+                    "description": t.description,
+                    "parameters": t.parameters,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Message content and `tool_calls` of an OpenAI Chat Completions response
+///
+/// OpenAI passes arguments as a JSON string; one that doesn't parse is kept
+/// as a string.
+pub fn openai_reply(response: &Value) -> ToolReply {
+    let message = &response["choices"][0]["message"];
+    let calls = message["tool_calls"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|call| {
+            let arguments = call["function"]["arguments"].as_str().unwrap_or("{}");
+            ToolCall {
+                id: call["id"].as_str().unwrap_or_default().to_string(),
+                name: call["function"]["name"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                arguments: serde_json::from_str(arguments)
+                    .unwrap_or_else(|_| Value::String(arguments.to_string())),
+            }
+        })
+        .collect();
+    ToolReply {
+        text: message["content"].as_str().unwrap_or_default().to_string(),
+        calls,
+    }
+}
+
+/// `tools` for the Gemini generateContent API
+pub fn gemini_tools(tools: &[ToolSpec]) -> Value {
+    let declarations: Vec<Value> = tools
+        .iter()
+        .map(|t| {
+            json!({
+                "name": t.name,
+                "description": t.description,
+                "parameters": t.parameters,
+            })
+        })
+        .collect();
+    json!([{ "functionDeclarations": declarations }])
+}
+
+/// Text and `functionCall` parts of a Gemini generateContent response
+pub fn gemini_reply(response: &Value) -> ToolReply {
+    let mut reply = ToolReply::default();
+    let parts = response["candidates"][0]["content"]["parts"].as_array();
+    for part in parts.into_iter().flatten() {
+        if let Some(text) = part["text"].as_str() {
+            reply.text.push_str(text);
+        }
+        if let Some(call) = part.get("functionCall") {
+            reply.calls.push(ToolCall {
+                id: format!("call_{}", reply.calls.len()),
+                name: call["name"].as_str().unwrap_or_default().to_string(),
+                arguments: call.get("args").cloned().unwrap_or_else(|| json!({})),
+            });
+        }
+    }
+    reply
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell() -> ToolSpec {
+        ToolSpec::with_string_args(
+            "shell",
+            "Run a shell command",
+            &[("command", "Command line")],
+            &["command"],
+        )
+    }
+
+    #[test]
+    fn test_fallback_reply() {
+        let tools = [shell()];
+        let prompt = fallback_prompt(&tools);
+        assert!(prompt.contains("- shell: Run a shell command"));
+        assert!(prompt.contains(r#""required":["command"]"#));
+
+        let reply = parse_fallback_reply(
+            "```json\n{\"text\": \"Listing files\", \"tool_calls\": [\
+             {\"name\": \"shell\", \"arguments\": {\"command\": \"ls\"}},\
+             {\"name\": \"rm_rf\", \"arguments\": {}}]}\n```",
+            &tools,
+        );
+        assert_eq!(reply.text, "Listing files");
+        assert_eq!(reply.calls.len(), 1);
+        assert_eq!(reply.calls[0].arg("command"), Some("ls"));
+
+        let plain = parse_fallback_reply("Hello! Use {braces} freely.", &tools);
+        assert_eq!(plain.text, "Hello! Use {braces} freely.");
+        assert!(plain.calls.is_empty());
+    }
+
+    #[test]
+    fn test_native_replies() {
+        let tools = [shell()];
+        assert_eq!(claude_tools(&tools)[0]["input_schema"]["type"], "object");
+        assert_eq!(openai_tools(&tools)[0]["function"]["name"], "shell");
+        assert_eq!(
+            gemini_tools(&tools)[0]["functionDeclarations"][0]["name"],
+            "shell"
+        );
+
+        let claude = claude_reply(&json!({ "content": [
+            { "type": "text", "text": "Running it" },
+            { "type": "tool_use", "id": "toolu_1", "name": "shell", "input": { "command": "ls" } }
+        ]}));
+        assert_eq!(claude.text, "Running it");
+        assert_eq!(claude.calls[0].id, "toolu_1");
+        assert_eq!(claude.calls[0].arg("command"), Some("ls"));
+
+        let openai = openai_reply(&json!({ "choices": [{ "message": {
+            "content": null,
+            "tool_calls": [{ "id": "call_a", "type": "function",
+                "function": { "name": "shell", "arguments": "{\"command\":\"ls\"}" } }]
+        }}]}));
+        assert_eq!(openai.text, "");
+        assert_eq!(openai.calls[0].arg("command"), Some("ls"));
+
+        let gemini = gemini_reply(&json!({ "candidates": [{ "content": { "parts": [
+            { "functionCall": { "name": "shell", "args": { "command": "ls" } } }
+        ]}}]}));
+        assert_eq!(gemini.calls[0].id, "call_0");
+        assert_eq!(gemini.calls[0].arg("command"), Some("ls"));
+    }
+}
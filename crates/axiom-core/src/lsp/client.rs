@@ -0,0 +1,326 @@
+//! JSON-RPC connection to one language server
+//!
+//! Messages travel over the server's stdin and stdout, each behind a
+//! `Content-Length` header. A reader thread hands responses to the requests
+//! waiting for them, answers the requests servers make of their client, and
+//! passes notifications such as `textDocument/publishDiagnostics` on.
+
+use crate::config::LspServerConfig;
+use crate::error::{AxiomError, Result};
+use crossbeam_channel::{bounded, Sender};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Requests waiting for a response, by ID
+type Pending = Arc<Mutex<HashMap<u64, Sender<std::result::Result<Value, String>>>>>;
+
+/// A running language server
+pub struct LspClient {
+    /// Name of the server in `[lsp.servers]`
+    name: String,
+    child: Mutex<Child>,
+    stdin: Arc<Mutex<ChildStdin>>,
+    next_id: AtomicU64,
+    pending: Pending,
+    /// Version of each open document
+    documents: Mutex<HashMap<PathBuf, i32>>,
+}
+
+impl LspClient {
+    /// Start the server `name` for the workspace at `root` and wait up to
+    /// `timeout` for it to initialize
+    ///
+    /// `on_notification` gets the method and params of every notification
+    /// the server sends.
+    pub fn start(
+        name: &str,
+        config: &LspServerConfig,
+        root: &Path,
+        timeout: Duration,
+        on_notification: impl Fn(&str, Value) + Send + 'static,
+    ) -> Result<Self> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| AxiomError::lsp(format!("{} ({}): {}", name, config.command, e)))?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _ = child.kill();
+            return Err(AxiomError::lsp(format!("{}: no stdio", name)));
+        };
+
+        let stdin = Arc::new(Mutex::new(stdin));
+        let pending = Pending::default();
+        {
+            let stdin = stdin.clone();
+            let pending = pending.clone();
+            std::thread::spawn(move || {
+                read_loop(BufReader::new(stdout), &stdin, &pending, on_notification);
+                // Nothing more will answer the requests still waiting
+                pending.lock().clear();
+            });
+        }
+
+        let client = Self {
+            name: name.to_string(),
+            child: Mutex::new(child),
+            stdin,
+            next_id: AtomicU64::new(1),
+            pending,
+            documents: Mutex::new(HashMap::new()),
+        };
+        let root_uri = path_to_uri(root)?;
+        let folder = root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        client.request(
+            "initialize",
+            json!({
+                "processId": std::process::id(),
+                "rootUri": root_uri,
+                "workspaceFolders": [{ "uri": root_uri, "name": folder }],
+                "capabilities": {
+                    "textDocument": {
+                        "synchronization": { "didSave": true },
+                        "publishDiagnostics": {},
+                        "hover": { "contentFormat": ["plaintext", "markdown"] },
+                        "definition": {}
+                    },
+                    "workspace": { "workspaceFolders": true }
+                }
+            }),
+            timeout,
+        )?;
+        client.notify("initialized", json!({}))?;
+        Ok(client)
+    }
+
+    /// Send a request and wait up to `timeout` for its result
+    pub fn request(&self, method: &str, params: Value, timeout: Duration) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = bounded(1);
+        self.pending.lock().insert(id, tx);
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if let Err(e) = write_message(&mut *self.stdin.lock(), &message) {
+            self.pending.lock().remove(&id);
+            return Err(AxiomError::lsp(format!("{}: {}", self.name, e)));
+        }
+        let result = rx.recv_timeout(timeout);
+        self.pending.lock().remove(&id);
+        match result {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(error)) => Err(AxiomError::lsp(format!("{}: {}", self.name, error))),
+            Err(_) => Err(AxiomError::lsp(format!(
+                "{}: no answer to {} within {}s",
+                self.name,
+                method,
+                timeout.as_secs()
+            ))),
+        }
+    }
+
+    /// Send a notification
+    pub fn notify(&self, method: &str, params: Value) -> Result<()> {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        write_message(&mut *self.stdin.lock(), &message)
+            .map_err(|e| AxiomError::lsp(format!("{}: {}", self.name, e)))
+    }
+
+    /// Give the server the current text of `path`: opened the first time,
+    /// changed and saved after that
+    pub fn sync(&self, path: &Path, language_id: &str, text: &str) -> Result<()> {
+        let uri = path_to_uri(path)?;
+        let version = {
+            let mut documents = self.documents.lock();
+            let version = documents.entry(path.to_path_buf()).or_insert(0);
+            *version += 1;
+            *version
+        };
+        if version == 1 {
+            return self.notify(
+                "textDocument/didOpen",
+                json!({ "textDocument": {
+                    "uri": uri,
+                    "languageId": language_id,
+                    "version": version,
+                    "text": text
+                } }),
+            );
+        }
+        self.notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": uri, "version": version },
+                "contentChanges": [{ "text": text }]
+            }),
+        )?;
+        self.notify(
+            "textDocument/didSave",
+            json!({ "textDocument": { "uri": uri } }),
+        )
+    }
+
+    /// Whether `path` has been opened on the server
+    pub fn is_open(&self, path: &Path) -> bool {
+        self.documents.lock().contains_key(path)
+    }
+
+    /// Ask the server to shut down, then stop it
+    pub fn shutdown(&self, timeout: Duration) {
+        if self.request("shutdown", Value::Null, timeout).is_ok() {
+            let _ = self.notify("exit", Value::Null);
+        }
+        let mut child = self.child.lock();
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+impl Drop for LspClient {
+    fn drop(&mut self) {
+        let mut child = self.child.lock();
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+/// Route the server's messages until its stdout closes
+fn read_loop(
+    mut reader: impl BufRead,
+    stdin: &Mutex<ChildStdin>,
+    pending: &Pending,
+    on_notification: impl Fn(&str, Value),
+) {
+    while let Ok(Some(message)) = read_message(&mut reader) {
+        let method = message.get("method").and_then(Value::as_str);
+        match (method, message.get("id")) {
+            // A response to one of our requests
+            (None, Some(id)) => {
+                let Some(tx) = id.as_u64().and_then(|id| pending.lock().remove(&id)) else {
+                    continue;
+                };
+                let result = match message.get("error") {
+                    Some(error) => Err(error
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .unwrap_or("request failed")
+                        .to_string()),
+                    None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                };
+                let _ = tx.send(result);
+            }
+            // A request from the server; it gets empty answers
+            (Some(method), Some(id)) => {
+                let result = match method {
+                    "workspace/configuration" => {
+                        let items = message["params"]["items"].as_array().map_or(0, Vec::len);
+                        Value::Array(vec![Value::Null; items])
+                    }
+                    _ => Value::Null,
+                };
+                let reply = json!({ "jsonrpc": "2.0", "id": id, "result": result });
+                let _ = write_message(&mut *stdin.lock(), &reply);
+            }
+            (Some(method), None) => {
+                let params = message.get("params").cloned().unwrap_or(Value::Null);
+                on_notification(method, params);
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Write `message` with its `Content-Length` header
+pub(crate) fn write_message(writer: &mut impl Write, message: &Value) -> std::io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+/// Read the next message, or None at the end of the stream
+pub(crate) fn read_message(reader: &mut impl BufRead) -> std::io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let length = length.ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, "missing Content-Length")
+    })?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// The `file://` URI of an absolute path
+pub(crate) fn path_to_uri(path: &Path) -> Result<String> {
+    url::Url::from_file_path(path)
+        .map(String::from)
+        .map_err(|_| AxiomError::lsp(format!("not an absolute path: {}", path.display())))
+}
+
+/// The path of a `file://` URI
+pub(crate) fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    url::Url::parse(uri).ok()?.to_file_path().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_message_framing() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &json!({ "id": 1, "result": "é" })).unwrap();
+        // Other headers are allowed, and header names ignore case
+        buffer.extend_from_slice(
+            b"content-length: 17\r\nContent-Type: application/vscode-jsonrpc\r\n\r\n{\"method\":\"exit\"}",
+        );
+
+        let mut reader = Cursor::new(buffer);
+        let first = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(first["result"], "é");
+        let second = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(second["method"], "exit");
+        assert!(read_message(&mut reader).unwrap().is_none());
+
+        let mut reader = Cursor::new(b"Content-Type: text\r\n\r\n{}".to_vec());
+        assert!(read_message(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_uris() {
+        let path = std::env::temp_dir().join("my project").join("main.rs");
+        let uri = path_to_uri(&path).unwrap();
+        assert!(uri.starts_with("file://"));
+        assert!(uri.ends_with("/my%20project/main.rs"));
+        assert_eq!(uri_to_path(&uri), Some(path));
+        assert!(path_to_uri(Path::new("src/main.rs")).is_err());
+        assert_eq!(uri_to_path("https://example.com/main.rs"), None);
+    }
+}
@@ -0,0 +1,592 @@
+//! Language server integration
+//!
+//! Files are handed to the language server configured for their extension
+//! in `[lsp.servers]` (rust-analyzer, pyright and typescript-language-server
+//! by default). A server is started the first time one of its files is
+//! synced and runs until the manager shuts down; one that fails to start
+//! isn't tried again.
+//!
+//! Diagnostics the servers publish are kept per file and passed to the
+//! callback given to [`LspManager::new`], which the service turns into
+//! `Diagnostics` notifications. Agents use [`LspManager::check`] to see
+//! whether the files they changed still compile.
+
+mod client;
+
+pub use client::LspClient;
+
+use crate::config::{LspConfig, LspServerConfig};
+use crate::error::{AxiomError, Result};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long a server may take to initialize
+const INIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a hover or definition request may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a server may take to shut down before it is killed
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often [`LspManager::check`] looks for new diagnostics
+const CHECK_POLL: Duration = Duration::from_millis(50);
+
+/// Called with a file and its diagnostics whenever a server publishes them
+pub type DiagnosticsCallback = Arc<dyn Fn(PathBuf, Vec<Diagnostic>) + Send + Sync>;
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl DiagnosticSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+            DiagnosticSeverity::Information => "info",
+            DiagnosticSeverity::Hint => "hint",
+        }
+    }
+}
+
+/// A problem a language server found in a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Line the problem starts on, from 1
+    pub line: u32,
+
+    /// Character column it starts at, from 1
+    pub column: u32,
+
+    /// Line it ends on, inclusive
+    pub end_line: u32,
+
+    /// Column it ends before
+    pub end_column: u32,
+
+    pub severity: DiagnosticSeverity,
+
+    pub message: String,
+
+    /// What reported it, e.g. `rustc` or `Pyright`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.line,
+            self.column,
+            self.severity.as_str(),
+            self.message
+        )
+    }
+}
+
+/// A place in a file, as returned by go-to-definition
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub path: PathBuf,
+
+    /// Line, from 1
+    pub line: u32,
+
+    /// Character column, from 1
+    pub column: u32,
+}
+
+/// Diagnostics per file, and how many times each file's were published
+#[derive(Default)]
+struct DiagnosticStore {
+    files: HashMap<PathBuf, Vec<Diagnostic>>,
+    published: HashMap<PathBuf, u64>,
+}
+
+/// The language servers of one workspace
+pub struct LspManager {
+    root: PathBuf,
+    config: LspConfig,
+    clients: Mutex<HashMap<String, Arc<LspClient>>>,
+    /// Servers that couldn't be started
+    failed: Mutex<HashSet<String>>,
+    diagnostics: Arc<Mutex<DiagnosticStore>>,
+    on_diagnostics: DiagnosticsCallback,
+}
+
+impl LspManager {
+    /// Language servers for the workspace at `root`; none is started until
+    /// one of its files is synced
+    pub fn new(
+        root: impl Into<PathBuf>,
+        config: LspConfig,
+        on_diagnostics: impl Fn(PathBuf, Vec<Diagnostic>) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            root: root.into(),
+            config,
+            clients: Mutex::new(HashMap::new()),
+            failed: Mutex::new(HashSet::new()),
+            diagnostics: Arc::new(Mutex::new(DiagnosticStore::default())),
+            on_diagnostics: Arc::new(on_diagnostics),
+        }
+    }
+
+    /// The workspace root
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether a server is configured for `path` and hasn't failed to start
+    pub fn handles(&self, path: &Path) -> bool {
+        self.server_for(path)
+            .is_some_and(|(name, _)| !self.failed.lock().contains(name))
+    }
+
+    /// Whether `path` has been synced to its server
+    pub fn is_open(&self, path: &Path) -> bool {
+        let path = self.absolute(path);
+        let Some((name, _)) = self.server_for(&path) else {
+            return false;
+        };
+        self.clients
+            .lock()
+            .get(name)
+            .is_some_and(|client| client.is_open(&path))
+    }
+
+    /// Send the current contents of `path` to its server, starting the
+    /// server if needed; false if no server handles the file
+    pub fn sync(&self, path: &Path) -> Result<bool> {
+        let path = self.absolute(path);
+        let Some((name, config)) = self.server_for(&path) else {
+            return Ok(false);
+        };
+        let client = self.client(name, config)?;
+        let text = std::fs::read_to_string(&path)?;
+        client.sync(&path, &language_id(&path, config), &text)?;
+        Ok(true)
+    }
+
+    /// The latest diagnostics for `path`
+    pub fn diagnostics(&self, path: &Path) -> Vec<Diagnostic> {
+        let path = self.absolute(path);
+        self.diagnostics
+            .lock()
+            .files
+            .get(&path)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Hover text for the symbol at `line` and `column` (both from 1)
+    pub fn hover(&self, path: &Path, line: u32, column: u32) -> Result<Option<String>> {
+        let Some(result) = self.position_request("textDocument/hover", path, line, column)? else {
+            return Ok(None);
+        };
+        Ok(hover_text(&result))
+    }
+
+    /// Where the symbol at `line` and `column` (both from 1) is defined
+    pub fn definition(&self, path: &Path, line: u32, column: u32) -> Result<Vec<Location>> {
+        let result = self.position_request("textDocument/definition", path, line, column)?;
+        Ok(result.as_ref().map(parse_locations).unwrap_or_default())
+    }
+
+    /// Sync `paths` and wait up to `timeout` for their servers to publish
+    /// fresh diagnostics; files no server handles are left out
+    pub fn check(
+        &self,
+        paths: &[PathBuf],
+        timeout: Duration,
+    ) -> BTreeMap<PathBuf, Vec<Diagnostic>> {
+        let mut waiting = Vec::new();
+        for path in paths {
+            let path = self.absolute(path);
+            let before = self.published(&path);
+            if let Ok(true) = self.sync(&path) {
+                waiting.push((path, before));
+            }
+        }
+
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline
+            && waiting
+                .iter()
+                .any(|(path, before)| self.published(path) == *before)
+        {
+            std::thread::sleep(CHECK_POLL);
+        }
+        waiting
+            .into_iter()
+            .map(|(path, _)| {
+                let diagnostics = self.diagnostics(&path);
+                (path, diagnostics)
+            })
+            .collect()
+    }
+
+    /// Stop every running server
+    pub fn shutdown(&self) {
+        let clients: Vec<_> = self.clients.lock().drain().map(|(_, c)| c).collect();
+        for client in clients {
+            client.shutdown(SHUTDOWN_TIMEOUT);
+        }
+    }
+
+    /// `path` made absolute against the workspace root
+    fn absolute(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        }
+    }
+
+    /// The server configured for the extension of `path`
+    fn server_for(&self, path: &Path) -> Option<(&str, &LspServerConfig)> {
+        if !self.config.enabled {
+            return None;
+        }
+        let extension = path.extension()?.to_str()?;
+        self.config
+            .servers
+            .iter()
+            .find(|(_, server)| server.extensions.iter().any(|ext| ext == extension))
+            .map(|(name, server)| (name.as_str(), server))
+    }
+
+    /// The running server `name`, started if it isn't yet
+    fn client(&self, name: &str, config: &LspServerConfig) -> Result<Arc<LspClient>> {
+        let mut clients = self.clients.lock();
+        if let Some(client) = clients.get(name) {
+            return Ok(client.clone());
+        }
+        if self.failed.lock().contains(name) {
+            return Err(AxiomError::lsp(format!("{} is not running", name)));
+        }
+
+        let store = self.diagnostics.clone();
+        let on_diagnostics = self.on_diagnostics.clone();
+        let on_notification = move |method: &str, params: Value| {
+            if method != "textDocument/publishDiagnostics" {
+                return;
+            }
+            let Some((path, diagnostics)) = parse_diagnostics(&params) else {
+                return;
+            };
+            {
+                let mut store = store.lock();
+                store.files.insert(path.clone(), diagnostics.clone());
+                *store.published.entry(path.clone()).or_default() += 1;
+            }
+            on_diagnostics(path, diagnostics);
+        };
+        match LspClient::start(name, config, &self.root, INIT_TIMEOUT, on_notification) {
+            Ok(client) => {
+                let client = Arc::new(client);
+                clients.insert(name.to_string(), client.clone());
+                Ok(client)
+            }
+            Err(e) => {
+                self.failed.lock().insert(name.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    /// How many times diagnostics for `path` have been published
+    fn published(&self, path: &Path) -> u64 {
+        self.diagnostics
+            .lock()
+            .published
+            .get(path)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Send a request about a position in `path`, syncing the file first
+    /// if its server hasn't seen it; None if no server handles it
+    fn position_request(
+        &self,
+        method: &str,
+        path: &Path,
+        line: u32,
+        column: u32,
+    ) -> Result<Option<Value>> {
+        let path = self.absolute(path);
+        let Some((name, config)) = self.server_for(&path) else {
+            return Ok(None);
+        };
+        let client = self.client(name, config)?;
+        if !client.is_open(&path) {
+            let text = std::fs::read_to_string(&path)?;
+            client.sync(&path, &language_id(&path, config), &text)?;
+        }
+        let params = json!({
+            "textDocument": { "uri": client::path_to_uri(&path)? },
+            "position": {
+                "line": line.saturating_sub(1),
+                "character": column.saturating_sub(1)
+            }
+        });
+        client.request(method, params, REQUEST_TIMEOUT).map(Some)
+    }
+}
+
+impl Drop for LspManager {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Diagnostics as a message for an agent or the chat, with paths relative to
+/// `root`
+pub fn diagnostics_report(root: &Path, files: &BTreeMap<PathBuf, Vec<Diagnostic>>) -> String {
+    let lines: Vec<String> = files
+        .iter()
+        .flat_map(|(path, diagnostics)| {
+            let path = path.strip_prefix(root).unwrap_or(path);
+            diagnostics
+                .iter()
+                .map(move |diagnostic| format!("{}:{}", path.display(), diagnostic))
+        })
+        .collect();
+    if lines.is_empty() {
+        return "No problems found".to_string();
+    }
+    lines.join("\n")
+}
+
+/// The LSP language ID of `path`
+fn language_id(path: &Path, config: &LspServerConfig) -> String {
+    if let Some(id) = &config.language_id {
+        return id.clone();
+    }
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    match extension {
+        "rs" => "rust",
+        "py" => "python",
+        "ts" => "typescript",
+        "tsx" => "typescriptreact",
+        "js" => "javascript",
+        "jsx" => "javascriptreact",
+        other => other,
+    }
+    .to_string()
+}
+
+/// The file and diagnostics of a `textDocument/publishDiagnostics`
+fn parse_diagnostics(params: &Value) -> Option<(PathBuf, Vec<Diagnostic>)> {
+    let path = client::uri_to_path(params.get("uri")?.as_str()?)?;
+    let diagnostics = params
+        .get("diagnostics")?
+        .as_array()?
+        .iter()
+        .filter_map(|diagnostic| {
+            let (line, column) = position(&diagnostic["range"]["start"])?;
+            let (end_line, end_column) = position(&diagnostic["range"]["end"])?;
+            let severity = match diagnostic.get("severity").and_then(Value::as_u64) {
+                Some(2) => DiagnosticSeverity::Warning,
+                Some(3) => DiagnosticSeverity::Information,
+                Some(4) => DiagnosticSeverity::Hint,
+                _ => DiagnosticSeverity::Error,
+            };
+            Some(Diagnostic {
+                line,
+                column,
+                end_line,
+                end_column,
+                severity,
+                message: diagnostic.get("message")?.as_str()?.to_string(),
+                source: diagnostic
+                    .get("source")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            })
+        })
+        .collect();
+    Some((path, diagnostics))
+}
+
+/// A zero-based LSP position as a line and column from 1
+fn position(position: &Value) -> Option<(u32, u32)> {
+    let line = position.get("line")?.as_u64()?;
+    let character = position.get("character")?.as_u64()?;
+    Some((line as u32 + 1, character as u32 + 1))
+}
+
+/// The locations of a definition result: a `Location`, a list of them, or
+/// a list of `LocationLink`s
+fn parse_locations(result: &Value) -> Vec<Location> {
+    let items = match result {
+        Value::Array(items) => items.as_slice(),
+        Value::Null => &[],
+        single => std::slice::from_ref(single),
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            let (uri, range) = match item.get("targetUri") {
+                Some(uri) => (uri, &item["targetSelectionRange"]),
+                None => (item.get("uri")?, &item["range"]),
+            };
+            let (line, column) = position(&range["start"])?;
+            Some(Location {
+                path: client::uri_to_path(uri.as_str()?)?,
+                line,
+                column,
+            })
+        })
+        .collect()
+}
+
+/// The text of a hover result, whichever of the LSP's forms it takes
+fn hover_text(result: &Value) -> Option<String> {
+    fn text(contents: &Value) -> Option<String> {
+        match contents {
+            Value::String(text) => Some(text.clone()),
+            Value::Array(parts) => {
+                let parts: Vec<String> = parts.iter().filter_map(text).collect();
+                (!parts.is_empty()).then(|| parts.join("\n\n"))
+            }
+            // MarkupContent, or a MarkedString with a language
+            Value::Object(object) => object.get("value")?.as_str().map(str::to_string),
+            _ => None,
+        }
+    }
+    text(result.get("contents")?).filter(|text| !text.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uri(path: &Path) -> String {
+        client::path_to_uri(path).unwrap()
+    }
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let path = std::env::temp_dir().join("main.rs");
+        let params = json!({
+            "uri": uri(&path),
+            "diagnostics": [
+                {
+                    "range": {
+                        "start": { "line": 2, "character": 4 },
+                        "end": { "line": 2, "character": 9 }
+                    },
+                    "severity": 1,
+                    "source": "rustc",
+                    "message": "cannot find value `x` in this scope"
+                },
+                {
+                    "range": {
+                        "start": { "line": 0, "character": 0 },
+                        "end": { "line": 0, "character": 3 }
+                    },
+                    "severity": 2,
+                    "message": "unused import"
+                }
+            ]
+        });
+
+        let (parsed, diagnostics) = parse_diagnostics(&params).unwrap();
+        assert_eq!(parsed, path);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (3, 5));
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].source.as_deref(), Some("rustc"));
+        assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Warning);
+
+        let files = BTreeMap::from([(path.clone(), diagnostics)]);
+        let report = diagnostics_report(&std::env::temp_dir(), &files);
+        assert_eq!(
+            report,
+            "main.rs:3:5: error: cannot find value `x` in this scope\n\
+             main.rs:1:1: warning: unused import"
+        );
+        let clean = BTreeMap::from([(path, Vec::new())]);
+        assert_eq!(
+            diagnostics_report(&std::env::temp_dir(), &clean),
+            "No problems found"
+        );
+    }
+
+    #[test]
+    fn test_definition_and_hover_results() {
+        let path = std::env::temp_dir().join("lib.rs");
+        let range = json!({
+            "start": { "line": 9, "character": 3 },
+            "end": { "line": 9, "character": 8 }
+        });
+        let location = json!({ "uri": uri(&path), "range": range });
+        let link = json!({
+            "targetUri": uri(&path),
+            "targetRange": range,
+            "targetSelectionRange": range
+        });
+        let expected = Location {
+            path: path.clone(),
+            line: 10,
+            column: 4,
+        };
+        assert_eq!(parse_locations(&location), std::slice::from_ref(&expected));
+        assert_eq!(parse_locations(&json!([link])), [expected]);
+        assert!(parse_locations(&Value::Null).is_empty());
+
+        let markup = json!({ "contents": { "kind": "markdown", "value": "fn main()" } });
+        assert_eq!(hover_text(&markup).as_deref(), Some("fn main()"));
+        let marked = json!({ "contents": ["i32", { "language": "rust", "value": "let x" }] });
+        assert_eq!(hover_text(&marked).as_deref(), Some("i32\n\nlet x"));
+        assert_eq!(hover_text(&json!({ "contents": "" })), None);
+    }
+
+    #[test]
+    fn test_missing_server_is_not_retried() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "hello\n").unwrap();
+        let mut config = LspConfig::default();
+        config.servers.get_mut("rust").unwrap().command = "axiom-no-such-server".to_string();
+        let manager = LspManager::new(dir.path(), config, |_, _| {});
+
+        assert!(!manager.sync(Path::new("notes.txt")).unwrap());
+        assert!(manager.handles(Path::new("main.rs")));
+        assert!(matches!(
+            manager.sync(Path::new("main.rs")),
+            Err(AxiomError::Lsp(_))
+        ));
+        assert!(!manager.handles(Path::new("main.rs")));
+        let checked = manager.check(&[PathBuf::from("main.rs")], Duration::from_secs(5));
+        assert!(checked.is_empty());
+        assert_eq!(
+            language_id(Path::new("App.tsx"), &config_for("ts")),
+            "typescriptreact"
+        );
+    }
+
+    fn config_for(extension: &str) -> LspServerConfig {
+        LspServerConfig {
+            command: "server".to_string(),
+            args: Vec::new(),
+            extensions: vec![extension.to_string()],
+            language_id: None,
+        }
+    }
+}
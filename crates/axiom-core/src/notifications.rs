@@ -3,14 +3,27 @@
 //! These notifications inform the UI about state changes in the backend.
 //! They are serializable for web UI integration over WebSocket/gRPC.
 
+use crate::config::ConfigChanges;
+use crate::error::AxiomError;
+use crate::error_codes::ErrorCode;
+use crate::index::SemanticHit;
+use crate::lsp::{Diagnostic, Location};
+use crate::plan::Plan;
+use crate::replace::FileReplace;
+use crate::run_log::RunLogFormat;
 use crate::types::{
-    AgentId, AgentStatus, AgentType, AgentView, CliAgentInfo, OutputContext, ProviderInfo,
-    ProviderStatus, TerminalScreen,
+    AgentEvent, AgentId, AgentStatus, AgentType, AgentView, CliAgentInfo, OutputContext, ProviderInfo,
+    ProviderStatus, ScreenDiff, TerminalScreen,
 };
-use crate::workspace::{Workspace, WorkspaceId, WorkspaceView};
+use crate::usage::UsageRecord;
+use crate::workspace::{PendingWrite, Workspace, WorkspaceId, WorkspaceView};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::path::PathBuf;
 
+/// Notifications kept for [`AxiomService::history`](crate::AxiomService::history)
+pub const HISTORY_CAPACITY: usize = 1000;
+
 /// Notifications that the backend sends to any UI
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -39,6 +52,12 @@ pub enum Notification {
         status: AgentStatus,
     },
 
+    /// A running agent was cancelled and its work stopped
+    AgentCancelled {
+        /// The agent ID
+        id: AgentId,
+    },
+
     /// Agent produced output (streaming)
     AgentOutput {
         /// The agent ID
@@ -69,6 +88,18 @@ pub enum Notification {
         screen: TerminalScreen,
     },
 
+    /// Rows of a CLI agent's screen that changed
+    ///
+    /// Sent as the PTY produces output. Send `ResyncPtyScreen` after
+    /// missing one to get a keyframe.
+    PtyScreenDiff {
+        /// The runtime agent ID
+        id: AgentId,
+
+        /// The changes since the previous diff
+        diff: ScreenDiff,
+    },
+
     /// CLI agent PTY exited
     PtyExited {
         /// The runtime agent ID
@@ -78,12 +109,58 @@ pub enum Notification {
         exit_code: i32,
     },
 
+    /// A CLI agent's output parsers recognized an event
+    CliAgentEvent {
+        /// The runtime agent ID
+        id: AgentId,
+
+        /// The agent's name, for activity entries
+        agent: String,
+
+        /// What the agent reported
+        event: AgentEvent,
+    },
+
+    /// An agent's file operation was carried out
+    OperationApplied {
+        /// The agent that made it
+        id: AgentId,
+
+        /// Path to the file, relative to the workspace where possible
+        path: PathBuf,
+
+        /// `write`, `delete`, ... as in the agent's run log
+        kind: String,
+    },
+
+    /// The Conductor's plan was made or moved on a step
+    PlanUpdated {
+        /// The whole plan, replacing any sent before
+        plan: Plan,
+    },
+
+    /// Estimated token usage of an LLM call
+    UsageUpdate {
+        /// The agent that made the call, if any
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<AgentId>,
+
+        /// Provider, model and tokens of the call
+        record: UsageRecord,
+    },
+
     /// File was modified (by an agent or file watcher)
     FileModified {
         /// Path to the modified file
         path: PathBuf,
     },
 
+    /// File changed on disk, as seen by the workspace file watcher
+    FileChanged {
+        /// Path to the changed file
+        path: PathBuf,
+    },
+
     /// File content loaded (in response to ReadFile command)
     FileLoaded {
         /// Path to the file
@@ -93,6 +170,132 @@ pub enum Notification {
         content: String,
     },
 
+    /// A line matching a running workspace search
+    SearchResult {
+        /// ID of the search, as in `SearchFinished`
+        search_id: u64,
+
+        /// File path relative to the workspace root
+        path: PathBuf,
+
+        /// Line number, from 1
+        line: u64,
+
+        /// Character column of the first match, from 1
+        column: usize,
+
+        /// The matching line
+        text: String,
+    },
+
+    /// A workspace search ended
+    ///
+    /// A search that couldn't run (e.g. an invalid regex) sends an `Error`
+    /// first.
+    SearchFinished {
+        /// ID of the search
+        search_id: u64,
+
+        /// The query searched for
+        query: String,
+
+        /// Hits reported
+        matches: usize,
+
+        /// Stopped at the result limit
+        truncated: bool,
+    },
+
+    /// Chunks of the workspace closest in meaning to a `SemanticSearch`
+    SemanticSearchResults {
+        /// The query searched for
+        query: String,
+
+        /// Best match first
+        hits: Vec<SemanticHit>,
+    },
+
+    /// A language server's latest diagnostics for a file
+    ///
+    /// Replaces any sent before for the file; an empty list means the file
+    /// has no problems.
+    Diagnostics {
+        /// Path to the file
+        path: PathBuf,
+
+        /// Problems in the file
+        diagnostics: Vec<Diagnostic>,
+    },
+
+    /// Hover text for a symbol (response to `Hover`)
+    Hover {
+        /// Path to the file
+        path: PathBuf,
+
+        /// Line, from 1
+        line: u32,
+
+        /// Character column, from 1
+        column: u32,
+
+        /// What the language server says about the symbol, or None if
+        /// there is nothing there
+        contents: Option<String>,
+    },
+
+    /// Where a symbol is defined (response to `GoToDefinition`)
+    Definition {
+        /// Path to the file
+        path: PathBuf,
+
+        /// Line, from 1
+        line: u32,
+
+        /// Character column, from 1
+        column: u32,
+
+        /// Definitions found, usually one
+        locations: Vec<Location>,
+    },
+
+    /// What a `ReplaceInWorkspace` would change, awaiting `ApplyReplace`
+    ReplacePreview {
+        /// ID to confirm with `ApplyReplace`
+        replace_id: u64,
+
+        /// The query searched for
+        query: String,
+
+        /// The replacement
+        replacement: String,
+
+        /// Files that would change, sorted by path
+        files: Vec<FileReplace>,
+    },
+
+    /// A confirmed replace was written
+    ReplaceApplied {
+        /// ID of the preview applied
+        replace_id: u64,
+
+        /// Files written, relative to the workspace root
+        applied: Vec<PathBuf>,
+
+        /// Matches replaced
+        replacements: usize,
+
+        /// Files left alone because they changed since the preview
+        skipped: Vec<PathBuf>,
+    },
+
+    /// Agent operations waiting for approval changed
+    ///
+    /// Carries the whole queue so UIs can replace their list.
+    PendingOperations {
+        /// Held operations, oldest first
+        operations: Vec<PendingWrite>,
+    },
+
     /// File read error
     FileError {
         /// Path to the file
@@ -106,6 +309,10 @@ pub enum Notification {
     Error {
         /// Error message
         message: String,
+
+        /// Catalog code, when the error has one (see `axiom explain`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code: Option<ErrorCode>,
     },
 
     /// Informational message
@@ -129,6 +336,18 @@ pub enum Notification {
         status: ProviderStatus,
     },
 
+    /// A health check found a provider's reachability changed
+    ProviderStatusChanged {
+        /// Provider identifier
+        provider_id: String,
+
+        /// Status found by the check
+        status: ProviderStatus,
+
+        /// Models the provider serves; empty when it can't be reached
+        models: Vec<String>,
+    },
+
     /// Available models changed for a provider
     ModelsChanged {
         /// Provider identifier
@@ -147,6 +366,91 @@ pub enum Notification {
         model: String,
     },
 
+    /// A provider rejected its API key; UIs should ask for a new one
+    ProviderAuthFailed {
+        /// Provider identifier
+        provider_id: String,
+
+        /// The provider's error
+        message: String,
+    },
+
+    /// A provider's API key expires soon or has expired, and has no
+    /// `api_key_command` to fetch a new one
+    ProviderKeyExpiring {
+        /// Provider identifier
+        provider_id: String,
+
+        /// When the key expires, in Unix seconds
+        expires_at: u64,
+
+        /// Whether it has expired already
+        expired: bool,
+    },
+
+    /// A provider's `api_key_command` fetched a new key, now in use
+    ProviderKeyRefreshed {
+        /// Provider identifier
+        provider_id: String,
+
+        /// When the new key expires, in Unix seconds, if known
+        expires_at: Option<u64>,
+    },
+
+    /// A config file changed on disk and what changed in it was applied
+    ConfigReloaded {
+        /// The files that changed
+        paths: Vec<PathBuf>,
+
+        /// What changed
+        changes: ConfigChanges,
+    },
+
+    /// Saved settings changed; UIs showing them should reload
+    SettingsChanged {
+        /// Config section that changed, e.g. `llm.providers.openai`
+        section: String,
+    },
+
+    /// A saved session replaced the agents; UIs should reload their lists
+    SessionRestored {
+        /// Session name
+        name: String,
+
+        /// The restored agents
+        agents: Vec<AgentView>,
+    },
+
+    /// A logged run, exported (response to `ExportRunLog`)
+    RunLogExport {
+        /// The agent whose run it is
+        agent_id: AgentId,
+
+        /// Format of `content`
+        format: RunLogFormat,
+
+        /// The exported run
+        content: String,
+    },
+
+    /// An agent's last message (response to `GetLastMessage`)
+    AgentLastMessage {
+        /// The agent whose message it is
+        agent_id: AgentId,
+
+        /// The message; empty when the agent said nothing yet
+        text: String,
+    },
+
+    /// The files an agent wrote (response to `GetTouchedFiles`)
+    AgentTouchedFiles {
+        /// The agent that wrote them
+        agent_id: AgentId,
+
+        /// Paths as the agent named them, first written first
+        paths: Vec<PathBuf>,
+    },
+
     /// State snapshot (in response to GetSnapshot command)
     Snapshot {
         /// All agents
@@ -162,6 +466,12 @@ pub enum Notification {
         context: OutputContext,
     },
 
+    /// Recent notifications (in response to QueryHistory command)
+    History {
+        /// Matching notifications, oldest first
+        entries: Vec<HistoryEntry>,
+    },
+
     /// Providers list (in response to ListProviders command)
     ProvidersList {
         /// Available providers
@@ -262,7 +572,207 @@ pub struct FileEntry {
     pub is_hidden: bool,
 }
 
+/// A notification as recorded in the history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Position in the service's notification stream, counting from 1
+    pub seq: u64,
+
+    /// When it was sent (Unix epoch milliseconds)
+    pub timestamp_ms: u64,
+
+    /// The notification
+    pub notification: Notification,
+}
+
+/// Which recorded notifications a history query returns
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryFilter {
+    /// Only entries after this sequence number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<u64>,
+
+    /// Only entries sent at or after this time (Unix epoch milliseconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since_ms: Option<u64>,
+
+    /// Only these notification types, e.g. `AgentOutput` (empty = all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<String>,
+
+    /// Only notifications about this agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<AgentId>,
+
+    /// At most this many, keeping the most recent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, entry: &HistoryEntry) -> bool {
+        self.after.is_none_or(|after| entry.seq > after)
+            && self
+                .since_ms
+                .is_none_or(|since| entry.timestamp_ms >= since)
+            && (self.kinds.is_empty()
+                || self
+                    .kinds
+                    .iter()
+                    .any(|kind| kind == entry.notification.kind()))
+            && self
+                .agent_id
+                .is_none_or(|id| entry.notification.agent_id() == Some(id))
+    }
+}
+
+/// The most recent notifications, oldest first
+///
+/// Lets a UI that missed some (busy rendering, a dropped WebSocket) catch up
+/// without a full snapshot. `History` replies aren't recorded, and nor are
+/// screen diffs, which are useless without the ones before them.
+#[derive(Debug)]
+pub struct NotificationHistory {
+    entries: VecDeque<HistoryEntry>,
+    capacity: usize,
+    next_seq: u64,
+}
+
+impl NotificationHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity.min(HISTORY_CAPACITY)),
+            capacity,
+            next_seq: 1,
+        }
+    }
+
+    /// Record a notification as sent now
+    pub fn record(&mut self, notification: &Notification) {
+        if matches!(
+            notification,
+            Notification::History { .. } | Notification::PtyScreenDiff { .. }
+        ) || self.capacity == 0
+        {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(HistoryEntry {
+            seq: self.next_seq,
+            timestamp_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            notification: notification.clone(),
+        });
+        self.next_seq += 1;
+    }
+
+    /// Sequence number of the latest notification (0 before the first)
+    pub fn last_seq(&self) -> u64 {
+        self.next_seq - 1
+    }
+
+    /// Recorded notifications matching `filter`, oldest first
+    pub fn query(&self, filter: &HistoryFilter) -> Vec<HistoryEntry> {
+        let mut entries: Vec<HistoryEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| filter.matches(entry))
+            .cloned()
+            .collect();
+        if let Some(limit) = filter.limit {
+            entries.drain(..entries.len().saturating_sub(limit));
+        }
+        entries
+    }
+}
+
 impl Notification {
+    /// The notification's type as it is serialized, e.g. `AgentOutput`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Notification::AgentSpawned { .. } => "AgentSpawned",
+            Notification::AgentStatusChanged { .. } => "AgentStatusChanged",
+            Notification::AgentCancelled { .. } => "AgentCancelled",
+            Notification::AgentOutput { .. } => "AgentOutput",
+            Notification::PtyOutput { .. } => "PtyOutput",
+            Notification::PtyScreen { .. } => "PtyScreen",
+            Notification::PtyScreenDiff { .. } => "PtyScreenDiff",
+            Notification::PtyExited { .. } => "PtyExited",
+            Notification::CliAgentEvent { .. } => "CliAgentEvent",
+            Notification::OperationApplied { .. } => "OperationApplied",
+            Notification::PlanUpdated { .. } => "PlanUpdated",
+            Notification::UsageUpdate { .. } => "UsageUpdate",
+            Notification::FileModified { .. } => "FileModified",
+            Notification::FileChanged { .. } => "FileChanged",
+            Notification::FileLoaded { .. } => "FileLoaded",
+            Notification::SearchResult { .. } => "SearchResult",
+            Notification::SearchFinished { .. } => "SearchFinished",
+            Notification::SemanticSearchResults { .. } => "SemanticSearchResults",
+            Notification::Diagnostics { .. } => "Diagnostics",
+            Notification::Hover { .. } => "Hover",
+            Notification::Definition { .. } => "Definition",
+            Notification::ReplacePreview { .. } => "ReplacePreview",
+            Notification::ReplaceApplied { .. } => "ReplaceApplied",
+            Notification::PendingOperations { .. } => "PendingOperations",
+            Notification::FileError { .. } => "FileError",
+            Notification::Error { .. } => "Error",
+            Notification::Info { .. } => "Info",
+            Notification::Warning { .. } => "Warning",
+            Notification::LlmStatusChanged { .. } => "LlmStatusChanged",
+            Notification::ProviderStatusChanged { .. } => "ProviderStatusChanged",
+            Notification::ModelsChanged { .. } => "ModelsChanged",
+            Notification::ActiveModelChanged { .. } => "ActiveModelChanged",
+            Notification::ProviderAuthFailed { .. } => "ProviderAuthFailed",
+            Notification::ProviderKeyExpiring { .. } => "ProviderKeyExpiring",
+            Notification::ProviderKeyRefreshed { .. } => "ProviderKeyRefreshed",
+            Notification::ConfigReloaded { .. } => "ConfigReloaded",
+            Notification::SettingsChanged { .. } => "SettingsChanged",
+            Notification::SessionRestored { .. } => "SessionRestored",
+            Notification::RunLogExport { .. } => "RunLogExport",
+            Notification::AgentLastMessage { .. } => "AgentLastMessage",
+            Notification::AgentTouchedFiles { .. } => "AgentTouchedFiles",
+            Notification::Snapshot { .. } => "Snapshot",
+            Notification::History { .. } => "History",
+            Notification::ProvidersList { .. } => "ProvidersList",
+            Notification::CliAgentsList { .. } => "CliAgentsList",
+            Notification::ContextChanged { .. } => "ContextChanged",
+            Notification::ShuttingDown => "ShuttingDown",
+            Notification::WorkspaceList { .. } => "WorkspaceList",
+            Notification::WorkspaceCreated { .. } => "WorkspaceCreated",
+            Notification::WorkspaceDeleted { .. } => "WorkspaceDeleted",
+            Notification::WorkspaceActivated { .. } => "WorkspaceActivated",
+            Notification::WorkspaceDeactivated { .. } => "WorkspaceDeactivated",
+            Notification::WorkspaceDetails { .. } => "WorkspaceDetails",
+            Notification::WorkspaceUpdated { .. } => "WorkspaceUpdated",
+            Notification::FileList { .. } => "FileList",
+        }
+    }
+
+    /// The agent the notification is about, if any
+    pub fn agent_id(&self) -> Option<AgentId> {
+        match self {
+            Notification::AgentSpawned { id, .. }
+            | Notification::AgentStatusChanged { id, .. }
+            | Notification::AgentCancelled { id }
+            | Notification::AgentOutput { id, .. }
+            | Notification::PtyOutput { id, .. }
+            | Notification::PtyScreen { id, .. }
+            | Notification::PtyScreenDiff { id, .. }
+            | Notification::PtyExited { id, .. }
+            | Notification::CliAgentEvent { id, .. }
+            | Notification::OperationApplied { id, .. }
+            | Notification::UsageUpdate { id: Some(id), .. }
+            | Notification::RunLogExport { agent_id: id, .. }
+            | Notification::AgentLastMessage { agent_id: id, .. }
+            | Notification::AgentTouchedFiles { agent_id: id, .. } => Some(*id),
+            _ => None,
+        }
+    }
+
     /// Create an AgentSpawned notification
     pub fn agent_spawned(
         id: AgentId,
@@ -306,6 +816,11 @@ impl Notification {
         Notification::FileModified { path: path.into() }
     }
 
+    /// Create a FileChanged notification
+    pub fn file_changed(path: impl Into<PathBuf>) -> Self {
+        Notification::FileChanged { path: path.into() }
+    }
+
     /// Create a FileLoaded notification
     pub fn file_loaded(path: impl Into<PathBuf>, content: impl Into<String>) -> Self {
         Notification::FileLoaded {
@@ -318,6 +833,15 @@ impl Notification {
     pub fn error(message: impl Into<String>) -> Self {
         Notification::Error {
             message: message.into(),
+            code: None,
+        }
+    }
+
+    /// Create an Error notification for `error`, with its code
+    pub fn from_error(error: &AxiomError) -> Self {
+        Notification::Error {
+            message: error.to_string(),
+            code: Some(error.code()),
         }
     }
 
@@ -389,6 +913,76 @@ mod tests {
         assert!(json.contains("PtyOutput"));
     }
 
+    #[test]
+    fn test_kind_matches_serialized_type() {
+        for notif in [
+            Notification::agent_output(AgentId::new(1), "hi"),
+            Notification::ShuttingDown,
+            Notification::History { entries: vec![] },
+            Notification::PlanUpdated {
+                plan: Plan::new("Ship it", ["Build", "Test"]),
+            },
+        ] {
+            let json = serde_json::to_value(&notif).unwrap();
+            assert_eq!(json["type"], notif.kind());
+        }
+    }
+
+    #[test]
+    fn test_structured_agent_events() {
+        let applied = Notification::OperationApplied {
+            id: AgentId::new(2),
+            path: PathBuf::from("src/main.rs"),
+            kind: "write".to_string(),
+        };
+        let json = serde_json::to_value(&applied).unwrap();
+        assert_eq!(json["path"], "src/main.rs");
+        assert_eq!(applied.agent_id(), Some(AgentId::new(2)));
+
+        let usage = Notification::UsageUpdate {
+            id: None,
+            record: UsageRecord::new("ollama", "llama3.2", 120, 40),
+        };
+        let json = serde_json::to_value(&usage).unwrap();
+        assert!(json.get("id").is_none());
+        assert_eq!(json["record"]["output_tokens"], 40);
+        assert_eq!(usage.agent_id(), None);
+    }
+
+    #[test]
+    fn test_history_filter_and_capacity() {
+        let mut history = NotificationHistory::new(3);
+        history.record(&Notification::info("dropped"));
+        history.record(&Notification::agent_output(AgentId::new(1), "a"));
+        history.record(&Notification::agent_output(AgentId::new(2), "b"));
+        history.record(&Notification::History { entries: vec![] });
+        history.record(&Notification::warning("w"));
+        assert_eq!(history.last_seq(), 4);
+
+        let all = history.query(&HistoryFilter::default());
+        let seqs: Vec<u64> = all.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, [2, 3, 4]);
+
+        let filter = HistoryFilter {
+            kinds: vec!["AgentOutput".to_string()],
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(history.query(&filter)[0].seq, 3);
+
+        let filter = HistoryFilter {
+            agent_id: Some(AgentId::new(1)),
+            ..Default::default()
+        };
+        assert_eq!(history.query(&filter).len(), 1);
+
+        let filter = HistoryFilter {
+            after: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(history.query(&filter)[0].notification.kind(), "Warning");
+    }
+
     #[test]
     fn test_error_notification() {
         let notif = Notification::error("Something went wrong");
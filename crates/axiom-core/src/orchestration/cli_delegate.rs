@@ -0,0 +1,250 @@
+//! CLI Agent Delegation
+//!
+//! Runs a configured CLI agent (Claude Code, Gemini CLI, ...) as one step of
+//! the orchestration flow: the orchestrator's task is its prompt, the run is
+//! over when the agent exits or goes quiet, and the files it created,
+//! modified or deleted are reported back into the conversation.
+
+use crate::agents::PtyAgent;
+use crate::config::{CliAgentConfig, DelegateConfig};
+use crate::events::Event;
+use crate::types::AgentId;
+use crate::workspace::format_duration;
+use crate::{AxiomError, Result};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Terminal size the agent runs in
+const COLS: u16 = 120;
+const ROWS: u16 = 40;
+
+/// Characters of the agent's final screen kept in the report
+const OUTPUT_TAIL: usize = 2000;
+
+/// How a delegated run ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "code")]
+pub enum DelegateEnd {
+    /// The agent exited with this code
+    Exited(i32),
+    /// The agent stopped producing output and was stopped
+    Idle,
+    /// The agent was still busy at the timeout and was stopped
+    TimedOut,
+}
+
+/// What a CLI agent did with a delegated task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegateReport {
+    /// Name of the agent
+    pub agent: String,
+    pub end: DelegateEnd,
+    pub duration_secs: u64,
+    /// Files relative to the workspace
+    pub created: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+    pub deleted: Vec<PathBuf>,
+    /// The end of the text on the agent's screen
+    pub output: String,
+}
+
+impl DelegateReport {
+    /// The report as a message for the conversation
+    pub fn summary(&self) -> String {
+        let end = match self.end {
+            DelegateEnd::Exited(0) => "finished".to_string(),
+            DelegateEnd::Exited(code) => format!("exited with code {}", code),
+            DelegateEnd::Idle => "went idle".to_string(),
+            DelegateEnd::TimedOut => "timed out".to_string(),
+        };
+        let mut summary = format!(
+            "{} {} after {}.",
+            self.agent,
+            end,
+            format_duration(self.duration_secs)
+        );
+        let changes = [
+            ("Created", &self.created),
+            ("Modified", &self.modified),
+            ("Deleted", &self.deleted),
+        ];
+        if changes.iter().all(|(_, files)| files.is_empty()) {
+            summary.push_str("\nNo files changed.");
+        }
+        for (label, files) in changes {
+            if files.is_empty() {
+                continue;
+            }
+            summary.push_str(&format!("\n\n{}:", label));
+            for file in files {
+                summary.push_str(&format!("\n- {}", file.display()));
+            }
+        }
+        summary
+    }
+}
+
+/// Run the CLI agent `config` in the workspace at `root` with `task` as its
+/// prompt, and report the files it changed
+///
+/// Blocks until the agent exits, has been quiet for `limits.idle_secs`, or
+/// has run for `limits.timeout_secs`; an agent still running then is
+/// stopped. Fails if the agent can't start or never gets the prompt.
+pub fn run_cli_delegate(
+    config: &CliAgentConfig,
+    task: &str,
+    root: &Path,
+    limits: &DelegateConfig,
+) -> Result<DelegateReport> {
+    let before = snapshot(root);
+    let started = Instant::now();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let agent = PtyAgent::new(AgentId(0), config, task, root, COLS, ROWS, tx)?;
+
+    let idle = Duration::from_secs(limits.idle_secs);
+    let deadline = started + Duration::from_secs(limits.timeout_secs);
+    let mut last_output = Instant::now();
+    let end = loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break DelegateEnd::TimedOut;
+        }
+        let wait = (last_output + idle)
+            .min(deadline)
+            .saturating_duration_since(now);
+        match rx.recv_timeout(wait) {
+            Ok(Event::CliAgentOutput { .. }) => last_output = Instant::now(),
+            Ok(Event::CliAgentExit { exit_code, .. }) => break DelegateEnd::Exited(exit_code),
+            Ok(Event::CliAgentNotReady { error, .. }) => {
+                agent.terminate();
+                return Err(AxiomError::pty(format!("{}: {}", config.name, error)));
+            }
+            Ok(_) => {}
+            Err(_) if last_output.elapsed() >= idle => break DelegateEnd::Idle,
+            Err(_) => {}
+        }
+    };
+
+    let output = tail(&agent.get_output_text(), OUTPUT_TAIL);
+    agent.terminate();
+    let (created, modified, deleted) = diff(&before, &snapshot(root));
+    Ok(DelegateReport {
+        agent: config.name.clone(),
+        end,
+        duration_secs: started.elapsed().as_secs(),
+        created,
+        modified,
+        deleted,
+        output,
+    })
+}
+
+/// Modification time and size of each file under `root`, skipping ignored
+/// and hidden ones
+fn snapshot(root: &Path) -> BTreeMap<PathBuf, (Option<SystemTime>, u64)> {
+    WalkBuilder::new(root)
+        .require_git(false)
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let path = entry.path().strip_prefix(root).ok()?.to_path_buf();
+            Some((path, (metadata.modified().ok(), metadata.len())))
+        })
+        .collect()
+}
+
+/// Files created, modified and deleted between two snapshots
+fn diff(
+    before: &BTreeMap<PathBuf, (Option<SystemTime>, u64)>,
+    after: &BTreeMap<PathBuf, (Option<SystemTime>, u64)>,
+) -> (Vec<PathBuf>, Vec<PathBuf>, Vec<PathBuf>) {
+    let mut created = Vec::new();
+    let mut modified = Vec::new();
+    for (path, state) in after {
+        match before.get(path) {
+            None => created.push(path.clone()),
+            Some(old) if old != state => modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    let deleted = before
+        .keys()
+        .filter(|path| !after.contains_key(*path))
+        .cloned()
+        .collect();
+    (created, modified, deleted)
+}
+
+/// The last `chars` characters of `text`, trimmed
+fn tail(text: &str, chars: usize) -> String {
+    let text = text.trim();
+    let skip = text.chars().count().saturating_sub(chars);
+    text.chars().skip(skip).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn shell_agent(script: &str) -> CliAgentConfig {
+        CliAgentConfig {
+            name: "Script".to_string(),
+            command: "/bin/sh".to_string(),
+            default_args: vec!["-c".to_string(), script.to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reports_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("keep.txt"), "same").unwrap();
+        fs::write(dir.path().join("edit.txt"), "old").unwrap();
+        fs::write(dir.path().join("gone.txt"), "bye").unwrap();
+
+        // The task arrives as the script's $0
+        let config =
+            shell_agent("echo \"$0\" > new.txt; echo longer > edit.txt; rm gone.txt; echo done");
+        let report = run_cli_delegate(
+            &config,
+            "write the file",
+            dir.path(),
+            &DelegateConfig::default(),
+        )
+        .unwrap();
+
+        assert!(matches!(report.end, DelegateEnd::Exited(_)));
+        assert_eq!(report.created, [PathBuf::from("new.txt")]);
+        assert_eq!(report.modified, [PathBuf::from("edit.txt")]);
+        assert_eq!(report.deleted, [PathBuf::from("gone.txt")]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("new.txt")).unwrap(),
+            "write the file\n"
+        );
+        let summary = report.summary();
+        assert!(summary.starts_with("Script "));
+        assert!(summary.contains("Created:\n- new.txt"));
+        assert!(summary.contains("Deleted:\n- gone.txt"));
+    }
+
+    #[test]
+    fn test_idle_agent_is_stopped() {
+        let dir = tempfile::tempdir().unwrap();
+        let limits = DelegateConfig {
+            idle_secs: 1,
+            timeout_secs: 30,
+        };
+        let started = Instant::now();
+        let report = run_cli_delegate(&shell_agent("sleep 30"), "", dir.path(), &limits).unwrap();
+
+        assert_eq!(report.end, DelegateEnd::Idle);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(report.summary().contains("No files changed."));
+    }
+}
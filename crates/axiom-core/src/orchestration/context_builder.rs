@@ -0,0 +1,263 @@
+//! Repository Context Builder
+//!
+//! Gathers what an agent should know about the workspace - its file tree,
+//! `AXIOM.md`, README, recently modified files and the files open in the
+//! editor - into a section of the agent's system prompt. Which sources an
+//! agent role gets, and how many tokens they may take, comes from
+//! `[orchestration.context.<role>]`.
+
+use super::developer::get_file_tree;
+use super::types::AgentRole;
+use crate::config::{ContextSource, RoleContextConfig};
+use crate::files::resolve;
+use crate::usage::estimate_tokens;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Directory depth of the file tree
+const TREE_DEPTH: usize = 3;
+
+/// Directory depth searched for recently modified files
+const RECENT_DEPTH: usize = 6;
+
+/// Files listed as recently modified
+const RECENT_FILES: usize = 10;
+
+/// README file names, in order of preference
+const README_NAMES: &[&str] = &["README.md", "README", "README.txt", "readme.md"];
+
+/// Marks a source cut short by the budget
+const TRUNCATED: &str = "\n[truncated]";
+
+/// Builds the repository context section of agent system prompts
+#[derive(Debug, Clone)]
+pub struct ContextBuilder {
+    root: PathBuf,
+    roles: HashMap<String, RoleContextConfig>,
+    open_tabs: Vec<PathBuf>,
+}
+
+impl ContextBuilder {
+    /// A builder for the workspace at `root`, with the sources configured
+    /// per role name; roles not in `roles` get [`default_context`]
+    pub fn new(root: impl Into<PathBuf>, roles: HashMap<String, RoleContextConfig>) -> Self {
+        Self {
+            root: root.into(),
+            roles,
+            open_tabs: Vec::new(),
+        }
+    }
+
+    /// Files open in the editor, absolute or relative to the workspace;
+    /// ones outside it are left out
+    pub fn with_open_tabs(mut self, tabs: Vec<PathBuf>) -> Self {
+        self.open_tabs = tabs;
+        self
+    }
+
+    /// The sources and budget for `role`
+    pub fn config(&self, role: AgentRole) -> RoleContextConfig {
+        self.roles
+            .get(role.as_str())
+            .cloned()
+            .unwrap_or_else(|| default_context(role))
+    }
+
+    /// The context section for `role`, or an empty string if no source has
+    /// anything to say
+    ///
+    /// Sources are added in the configured order, each cut short once the
+    /// budget runs out; sources after that are left out.
+    pub fn build(&self, role: AgentRole) -> String {
+        let config = self.config(role);
+        let mut remaining = config.max_tokens;
+        let mut sections = Vec::new();
+        for source in config.sources {
+            if remaining == 0 {
+                break;
+            }
+            let Some((title, body)) = self.gather(source) else {
+                continue;
+            };
+            let section = format!("## {}\n\n{}", title, body.trim_end());
+            let Some(section) = truncate(&section, remaining) else {
+                break;
+            };
+            remaining = remaining.saturating_sub(estimate_tokens(&section) as usize);
+            sections.push(section);
+        }
+
+        if sections.is_empty() {
+            return String::new();
+        }
+        format!("# Repository Context\n\n{}", sections.join("\n\n"))
+    }
+
+    /// The title and text of `source`, if the workspace has it
+    fn gather(&self, source: ContextSource) -> Option<(String, String)> {
+        match source {
+            ContextSource::FileTree => {
+                let mut files = get_file_tree(&self.root, TREE_DEPTH);
+                files.sort();
+                (!files.is_empty()).then(|| ("File Tree".to_string(), files.join("\n")))
+            }
+            ContextSource::AxiomMd => {
+                let text = std::fs::read_to_string(self.root.join("AXIOM.md")).ok()?;
+                Some(("Project Instructions (AXIOM.md)".to_string(), text))
+            }
+            ContextSource::Readme => README_NAMES.iter().find_map(|name| {
+                let text = std::fs::read_to_string(self.root.join(name)).ok()?;
+                Some((name.to_string(), text))
+            }),
+            ContextSource::RecentFiles => {
+                let files = recent_files(&self.root, RECENT_FILES);
+                (!files.is_empty())
+                    .then(|| ("Recently Modified Files".to_string(), files.join("\n")))
+            }
+            ContextSource::OpenTabs => {
+                let tabs: Vec<String> = self
+                    .open_tabs
+                    .iter()
+                    .filter_map(|tab| {
+                        let path = resolve(&self.root, tab).ok()?;
+                        let text = std::fs::read_to_string(&path).ok()?;
+                        let name = path.strip_prefix(&self.root).unwrap_or(&path);
+                        Some(format!(
+                            "### {}\n\n```\n{}\n```",
+                            name.display(),
+                            text.trim_end()
+                        ))
+                    })
+                    .collect();
+                (!tabs.is_empty()).then(|| ("Open in the Editor".to_string(), tabs.join("\n\n")))
+            }
+        }
+    }
+}
+
+/// Sources and budget for a role with no `[orchestration.context]` entry
+///
+/// The orchestrator, product owner and shell summarizer only need to know
+/// what the project is; the architect, developer and reviewer also see
+/// where work is happening.
+pub fn default_context(role: AgentRole) -> RoleContextConfig {
+    use ContextSource::*;
+    let (sources, max_tokens) = match role {
+        AgentRole::Orchestrator => (vec![AxiomMd, Readme, FileTree], 2000),
+        AgentRole::Po => (vec![AxiomMd, Readme], 2000),
+        AgentRole::Architect => (vec![AxiomMd, Readme, FileTree, RecentFiles], 4000),
+        AgentRole::Developer => (vec![AxiomMd, FileTree, OpenTabs, RecentFiles], 4000),
+        AgentRole::Reviewer => (vec![AxiomMd, FileTree, RecentFiles], 4000),
+        AgentRole::ShellSummarizer => (vec![AxiomMd], 1000),
+    };
+    RoleContextConfig {
+        sources,
+        max_tokens,
+    }
+}
+
+/// `prompt` with `context` appended, if there is any
+pub fn with_context(prompt: &str, context: &str) -> String {
+    if context.is_empty() {
+        return prompt.to_string();
+    }
+    format!("{}\n\n{}", prompt.trim_end(), context)
+}
+
+/// The `count` most recently modified files under `root`, newest first
+fn recent_files(root: &Path, count: usize) -> Vec<String> {
+    let mut files: Vec<(SystemTime, String)> = get_file_tree(root, RECENT_DEPTH)
+        .into_iter()
+        .filter_map(|file| {
+            let modified = std::fs::metadata(root.join(&file)).ok()?.modified().ok()?;
+            Some((modified, file))
+        })
+        .collect();
+    files.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    files
+        .into_iter()
+        .take(count)
+        .map(|(_, file)| file)
+        .collect()
+}
+
+/// `text` cut at a line break to fit in `tokens`, or None if no line fits
+fn truncate(text: &str, tokens: usize) -> Option<String> {
+    if estimate_tokens(text) as usize <= tokens {
+        return Some(text.to_string());
+    }
+    let keep = (tokens * 4).saturating_sub(TRUNCATED.len());
+    let mut cut: String = text.chars().take(keep).collect();
+    cut.truncate(cut.rfind('\n')?);
+    cut.push_str(TRUNCATED);
+    Some(cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_build_sources_in_order() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("AXIOM.md"), "Use tabs.\n").unwrap();
+        fs::write(dir.path().join("README.md"), "# Demo\n").unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        let outside = tempdir().unwrap();
+        fs::write(outside.path().join("secret.txt"), "hunter2").unwrap();
+
+        let builder = ContextBuilder::new(dir.path(), HashMap::new()).with_open_tabs(vec![
+            dir.path().join("src/main.rs"),
+            PathBuf::from("gone.rs"),
+            outside.path().join("secret.txt"),
+        ]);
+
+        let context = builder.build(AgentRole::Developer);
+        let instructions = context.find("Use tabs.").unwrap();
+        let tree = context.find("## File Tree").unwrap();
+        let tab = context
+            .find("### src/main.rs\n\n```\nfn main() {}\n```")
+            .unwrap();
+        assert!(instructions < tree && tree < tab);
+        assert!(context.contains("## Recently Modified Files"));
+        assert!(!context.contains("# Demo"));
+        assert!(!context.contains("gone.rs"));
+        assert!(!context.contains("hunter2"));
+
+        let context = builder.build(AgentRole::Po);
+        assert!(context.contains("## README.md\n\n# Demo"));
+        assert!(!context.contains("File Tree"));
+    }
+
+    #[test]
+    fn test_role_config_and_budget() {
+        let dir = tempdir().unwrap();
+        let readme: String = (0..200).map(|i| format!("line {}\n", i)).collect();
+        fs::write(dir.path().join("README.md"), &readme).unwrap();
+        fs::write(dir.path().join("AXIOM.md"), "Use tabs.\n").unwrap();
+
+        let roles = HashMap::from([(
+            "orchestrator".to_string(),
+            RoleContextConfig {
+                sources: vec![ContextSource::Readme, ContextSource::AxiomMd],
+                max_tokens: 50,
+            },
+        )]);
+        let context = ContextBuilder::new(dir.path(), roles).build(AgentRole::Orchestrator);
+        assert!(context.contains("line 0\n"));
+        assert!(context.contains(TRUNCATED));
+        assert!(!context.contains("line 199"));
+        // The README used the whole budget
+        assert!(!context.contains("Use tabs."));
+
+        let empty = tempdir().unwrap();
+        let builder = ContextBuilder::new(empty.path(), HashMap::new());
+        assert_eq!(builder.build(AgentRole::Po), "");
+        assert_eq!(with_context("Prompt\n", ""), "Prompt\n");
+        assert_eq!(with_context("Prompt\n", "# Context"), "Prompt\n\n# Context");
+    }
+}
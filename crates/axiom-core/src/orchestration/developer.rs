@@ -2,27 +2,59 @@
 //!
 //! Writes code, fixes bugs, and executes commands.
 
-use super::types::{AgentOperation, ChatMessage, DeveloperResponse};
+use super::context_builder::with_context;
+use super::patch::{check_patch, failure_report, parse_search_replace, TextEdit};
+use super::refactor::plan_rename;
+use super::types::{AgentOperation, AgentRole, ChatMessage, DeveloperResponse};
+use crate::files::write_atomic;
+use crate::prompts::PromptLibrary;
 use crate::Result;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 const DEVELOPER_SYSTEM_PROMPT: &str = r#"
 You are the Developer Agent. Your job is to write code, fix bugs, and run commands.
 
 **Capabilities:**
-1. **File System**: Write/Overwrite files.
-2. **Terminal**: Execute shell commands (e.g., npm install, npm test, ls -la).
+1. **File System**: Create files, patch existing files, rename/move and delete files.
+2. **Refactoring**: Rename a function, type or variable everywhere it is referenced.
+3. **Terminal**: Execute shell commands (e.g., npm install, npm test, ls -la).
 
 **Output Format:**
 You must respond with a strict JSON object (no markdown):
 {
   "reasoning": "Explanation of your plan",
   "operations": [
+    {
+      "type": "patch",
+      "path": "/absolute/path/to/existing.ts",
+      "diff": "@@ -10,3 +10,4 @@\n context line\n-old line\n+new line\n+added line\n context line"
+    },
+    {
+      "type": "patch",
+      "path": "/absolute/path/to/other.ts",
+      "edits": [{ "find": "exact existing text", "replace": "replacement text" }]
+    },
+    {
+      "type": "patch",
+      "path": "/absolute/path/to/third.ts",
+      "blocks": "<<<<<<< SEARCH\nexact existing text\n=======\nreplacement text\n>>>>>>> REPLACE"
+    },
     {
       "type": "write",
-      "path": "/absolute/path/to/file.ts",
+      "path": "/absolute/path/to/new_file.ts",
       "content": "file content here"
     },
+    {
+      "type": "rename",
+      "from": "/absolute/path/to/old_name.ts",
+      "to": "/absolute/path/to/new_name.ts"
+    },
+    {
+      "type": "rename_symbol",
+      "symbol": "oldName",
+      "new_name": "newName"
+    },
     {
       "type": "execute",
       "command": "npm install"
@@ -31,6 +63,17 @@ You must respond with a strict JSON object (no markdown):
   "message": "Summary for the user"
 }
 
+**Editing rules:**
+- Prefer "patch" for existing files. Use a unified diff with 2-3 lines of unchanged context
+  around each change, or "edits" (or "blocks" of SEARCH/REPLACE) where each search text
+  matches exactly one place in the file.
+- A patch is applied only if all of it matches. If one fails you'll be told which hunks or
+  edits didn't match and what the file holds there; send a corrected patch.
+- Only use "write" for new files or when most of a file changes.
+- Use "rename" to move files instead of writing a copy and deleting the original.
+- Use "rename_symbol" to rename an identifier; it updates every file that references it,
+  so don't patch those references yourself.
+
 Prioritize writing files before executing commands if they are dependencies.
 "#;
 
@@ -99,10 +142,63 @@ fn parse_operation(op: &serde_json::Value) -> Option<AgentOperation> {
                 content: content.to_string(),
             })
         }
+        "patch" | "edit" => {
+            let path = op.get("path").and_then(|v| v.as_str())?;
+            let diff = op
+                .get("diff")
+                .or_else(|| op.get("patch"))
+                .and_then(|v| v.as_str())
+                .map(String::from);
+            let mut edits: Vec<TextEdit> = op
+                .get("edits")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(parse_edit).collect())
+                .unwrap_or_default();
+            if let Some(blocks) = op.get("blocks").and_then(|v| v.as_str()) {
+                edits.extend(parse_search_replace(blocks));
+            }
+
+            if diff.is_none() && edits.is_empty() {
+                return None;
+            }
+            Some(AgentOperation::Patch {
+                path: path.into(),
+                diff,
+                edits,
+            })
+        }
+        "rename" | "move" => {
+            let from = op
+                .get("from")
+                .or_else(|| op.get("path"))
+                .and_then(|v| v.as_str())?;
+            let to = op
+                .get("to")
+                .or_else(|| op.get("new_path"))
+                .and_then(|v| v.as_str())?;
+            Some(AgentOperation::Rename {
+                from: from.into(),
+                to: to.into(),
+            })
+        }
         "delete" => {
             let path = op.get("path").and_then(|v| v.as_str())?;
             Some(AgentOperation::Delete { path: path.into() })
         }
+        "rename_symbol" => {
+            let symbol = op
+                .get("symbol")
+                .or_else(|| op.get("from"))
+                .and_then(|v| v.as_str())?;
+            let new_name = op
+                .get("new_name")
+                .or_else(|| op.get("to"))
+                .and_then(|v| v.as_str())?;
+            Some(AgentOperation::RenameSymbol {
+                symbol: symbol.to_string(),
+                new_name: new_name.to_string(),
+            })
+        }
         "execute" => {
             let command = op.get("command").and_then(|v| v.as_str())?;
             Some(AgentOperation::Execute {
@@ -113,6 +209,20 @@ fn parse_operation(op: &serde_json::Value) -> Option<AgentOperation> {
     }
 }
 
+/// Parse a single search/replace edit, accepting common key spellings
+fn parse_edit(edit: &serde_json::Value) -> Option<TextEdit> {
+    let find = ["find", "search", "old"]
+        .iter()
+        .find_map(|k| edit.get(*k).and_then(|v| v.as_str()))?;
+    let replace = ["replace", "new"]
+        .iter()
+        .find_map(|k| edit.get(*k).and_then(|v| v.as_str()))?;
+    Some(TextEdit {
+        find: find.to_string(),
+        replace: replace.to_string(),
+    })
+}
+
 /// Get a simple file tree for context
 pub fn get_file_tree(workspace_path: &Path, max_depth: usize) -> Vec<String> {
     let mut files = Vec::new();
@@ -162,29 +272,96 @@ fn collect_files(
     }
 }
 
-/// Build messages for developer with task and context
+/// Build messages for developer with task and repository context
+///
+/// The system prompt is the workspace's developer template from `prompts`,
+/// else the built-in one; `context` comes from a
+/// [`ContextBuilder`](super::ContextBuilder) and is added to it.
 pub fn build_developer_messages(
+    prompts: &PromptLibrary,
     task: &str,
     workspace_path: &Path,
-    file_list: &[String],
+    context: &str,
 ) -> Vec<ChatMessage> {
-    let file_context = if file_list.is_empty() {
-        "No files found in workspace".to_string()
-    } else {
-        file_list.join("\n")
-    };
-
+    let prompt = prompts.render(AgentRole::Developer.as_str(), DEVELOPER_SYSTEM_PROMPT, task);
     vec![
-        ChatMessage::system(DEVELOPER_SYSTEM_PROMPT),
+        ChatMessage::system(with_context(&prompt, context)),
         ChatMessage::user(format!(
-            "Workspace Base Path: {}\n\nExisting Files:\n{}\n\nTASK: {}",
+            "Workspace Base Path: {}\n\nTASK: {}",
             workspace_path.display(),
-            file_context,
             task
         )),
     ]
 }
 
+/// The task again, with the operations of the previous attempt that failed
+/// to apply
+///
+/// `failures` pairs each operation's path with its error; a failed patch's
+/// error names the hunks that didn't match and what the file holds there.
+pub fn retry_task(task: &str, failures: &[(PathBuf, String)]) -> String {
+    if failures.is_empty() {
+        return task.to_string();
+    }
+    let mut retry = format!(
+        "{}\n\nSome changes from your previous attempt were not applied. \
+         Send them again, matching the files as they are now:",
+        task
+    );
+    for (path, error) in failures {
+        retry.push_str(&format!("\n\n{}: {}", path.display(), error));
+    }
+    retry
+}
+
+/// Apply a file operation, resolving relative paths against `root`
+///
+/// Files are written atomically, and patches only when every hunk applies;
+/// otherwise the error reports each one that didn't (see
+/// [`failure_report`]). `Execute` operations are left to the caller.
+pub fn apply_operation(root: &Path, op: &AgentOperation) -> std::result::Result<(), String> {
+    let resolve = |path: &Path| {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            root.join(path)
+        }
+    };
+    let create_parent = |path: &Path| match path.parent() {
+        Some(parent) => fs::create_dir_all(parent).map_err(|e| e.to_string()),
+        None => Ok(()),
+    };
+
+    match op {
+        AgentOperation::Write { path, content } => write_atomic(&resolve(path), content.as_bytes())
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        AgentOperation::Patch { path, diff, edits } => {
+            let path = resolve(path);
+            let original = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            let patched = check_patch(&original, diff.as_deref(), edits)
+                .map_err(|failures| failure_report(&failures))?;
+            write_atomic(&path, patched.as_bytes())
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+        AgentOperation::Rename { from, to } => {
+            let (from, to) = (resolve(from), resolve(to));
+            if to.exists() {
+                return Err(format!("{} already exists", to.display()));
+            }
+            create_parent(&to)?;
+            fs::rename(&from, &to).map_err(|e| e.to_string())
+        }
+        AgentOperation::Delete { path } => fs::remove_file(resolve(path)).map_err(|e| e.to_string()),
+        AgentOperation::RenameSymbol { symbol, new_name } => plan_rename(root, symbol, new_name)
+            .and_then(|changeset| changeset.apply(root))
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        AgentOperation::Execute { .. } => Err("Commands are not file operations".to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,4 +398,71 @@ mod tests {
             _ => panic!("Expected Execute operation"),
         }
     }
+
+    #[test]
+    fn test_parse_patch_and_rename() {
+        let response = r#"{
+            "reasoning": "Small edits",
+            "operations": [
+                {"type": "patch", "path": "src/a.rs", "diff": "@@ -1 +1 @@\n-a\n+b"},
+                {"type": "patch", "path": "src/b.rs", "edits": [{"search": "x", "replace": "y"}]},
+                {"type": "patch", "path": "src/c.rs"},
+                {"type": "move", "from": "src/old.rs", "to": "src/new.rs"},
+                {"type": "rename_symbol", "symbol": "load", "new_name": "load_all"}
+            ],
+            "message": "Done"
+        }"#;
+
+        let result = parse_developer_response(response).unwrap();
+        assert_eq!(result.operations.len(), 4);
+        match &result.operations[0] {
+            AgentOperation::Patch { diff, edits, .. } => {
+                assert!(diff.as_deref().unwrap().contains("+b"));
+                assert!(edits.is_empty());
+            }
+            _ => panic!("Expected Patch operation"),
+        }
+        match &result.operations[1] {
+            AgentOperation::Patch { edits, .. } => assert_eq!(edits[0].find, "x"),
+            _ => panic!("Expected Patch operation"),
+        }
+        match &result.operations[2] {
+            AgentOperation::Rename { from, to } => {
+                assert_eq!(from.to_str(), Some("src/old.rs"));
+                assert_eq!(to.to_str(), Some("src/new.rs"));
+            }
+            _ => panic!("Expected Rename operation"),
+        }
+        match &result.operations[3] {
+            AgentOperation::RenameSymbol { symbol, new_name } => {
+                assert_eq!(symbol, "load");
+                assert_eq!(new_name, "load_all");
+            }
+            _ => panic!("Expected RenameSymbol operation"),
+        }
+    }
+
+    #[test]
+    fn test_failed_patch_is_reported_for_retry() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        let response = r#"{
+            "operations": [{"type": "patch", "path": "a.txt",
+                "blocks": "<<<<<<< SEARCH\nfour\n=======\n4\n>>>>>>> REPLACE\n<<<<<<< SEARCH\ntwo\n=======\n2\n>>>>>>> REPLACE"}]
+        }"#;
+        let op = &parse_developer_response(response).unwrap().operations[0];
+        let error = apply_operation(dir.path(), op).unwrap_err();
+        assert!(error.starts_with("Patch not applied: 1 part didn't match"));
+        assert!(error.contains("edit 1 text not found"));
+        // Nothing was written
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "one\ntwo\nthree\n"
+        );
+
+        let task = retry_task("Rename four", &[(PathBuf::from("a.txt"), error)]);
+        assert!(task.starts_with("Rename four\n\nSome changes from your previous attempt"));
+        assert!(task.contains("\n\na.txt: Patch not applied"));
+        assert_eq!(retry_task("Rename four", &[]), "Rename four");
+    }
 }
@@ -0,0 +1,699 @@
+//! Documentation generation (`/gen-docs <path>`)
+//!
+//! Finds the public items of a file (or of every source file in a module
+//! directory) that have no doc comment, asks the LLM to draft one for each,
+//! and turns the drafts into a unified diff that only inserts lines, so
+//! existing docs and code are left exactly as they are. The diff is held for
+//! review like any other protected write; with `--check`, Rust changes are
+//! first built with `cargo doc`.
+//!
+//! Items are found by a line scanner rather than a parser: declarations are
+//! recognised by their leading keywords, which is enough for the languages
+//! below and keeps the scanner dependency-free.
+
+use super::developer::get_file_tree;
+use super::types::{AgentOperation, ChatMessage};
+use crate::{AxiomError, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Files documented by one `/gen-docs` run on a directory
+pub const MAX_FILES: usize = 20;
+
+/// Longest source sent in full
+const MAX_SOURCE_CHARS: usize = 20_000;
+
+/// Unchanged lines around each insertion in the diff
+const CONTEXT_LINES: usize = 2;
+
+/// `cargo doc` output kept when verification fails (its tail)
+const MAX_CHECK_CHARS: usize = 3_000;
+
+const DOCS_SYSTEM_PROMPT: &str = r#"
+You are the Documentation Agent. You write doc comments for public APIs.
+
+For each item you are given, write a concise doc comment: what it does or
+represents, and, where it isn't obvious, its parameters, return value, errors
+and panics. Match the tone and length of the documentation already in the
+file. Write plain text without comment markers; it is formatted for you.
+For Go, start the comment with the item's name.
+
+**Output Format:**
+Respond with a strict JSON object (no markdown):
+{
+  "docs": [
+    { "line": 12, "doc": "Parses the configuration file at `path`." }
+  ]
+}
+
+Use the line numbers you were given. Leave out items you can't describe
+accurately.
+"#;
+
+/// Languages whose public items can be documented
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocLanguage {
+    Rust,
+    Python,
+    /// JavaScript and TypeScript
+    JavaScript,
+    Go,
+}
+
+impl DocLanguage {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(DocLanguage::Rust),
+            "py" => Some(DocLanguage::Python),
+            "js" | "jsx" | "ts" | "tsx" | "mjs" => Some(DocLanguage::JavaScript),
+            "go" => Some(DocLanguage::Go),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DocLanguage::Rust => "Rust",
+            DocLanguage::Python => "Python",
+            DocLanguage::JavaScript => "JavaScript/TypeScript",
+            DocLanguage::Go => "Go",
+        }
+    }
+}
+
+/// A public item without documentation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicItem {
+    /// Line of the declaration (0-based)
+    pub line: usize,
+    pub name: String,
+    /// The declaration line, trimmed
+    pub signature: String,
+}
+
+/// Source files to document for `path`: the file itself, or the source
+/// files below a directory (tests excluded)
+pub fn doc_targets(root: &Path, path: &Path) -> Result<Vec<PathBuf>> {
+    let full = root.join(path);
+    if full.is_file() {
+        if DocLanguage::from_path(&full).is_none() {
+            return Err(AxiomError::config(format!(
+                "Can't document {}: unsupported file type",
+                path.display()
+            )));
+        }
+        return Ok(vec![path.to_path_buf()]);
+    }
+    if !full.is_dir() {
+        return Err(AxiomError::not_found(format!(
+            "No such file or directory: {}",
+            path.display()
+        )));
+    }
+
+    let mut files: Vec<PathBuf> = get_file_tree(&full, 6)
+        .into_iter()
+        .map(|f| path.join(f))
+        .filter(|f| DocLanguage::from_path(f).is_some() && !is_test_path(f))
+        .collect();
+    files.sort();
+    files.truncate(MAX_FILES);
+    Ok(files)
+}
+
+fn is_test_path(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    name.starts_with("test_")
+        || name.contains("_test.")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || path
+            .components()
+            .any(|c| matches!(c.as_os_str().to_str(), Some("tests" | "__tests__")))
+}
+
+/// Public items in `source` that have no doc comment yet
+pub fn undocumented_items(language: DocLanguage, source: &str) -> Vec<PublicItem> {
+    let lines: Vec<&str> = source.lines().collect();
+    // Rust test modules aren't public API
+    let end = match language {
+        DocLanguage::Rust => lines
+            .iter()
+            .position(|l| l.trim() == "#[cfg(test)]")
+            .unwrap_or(lines.len()),
+        _ => lines.len(),
+    };
+
+    let mut items = Vec::new();
+    for (i, line) in lines[..end].iter().enumerate() {
+        let Some(name) = public_name(language, line) else {
+            continue;
+        };
+        if !documented(language, &lines, i) {
+            items.push(PublicItem {
+                line: i,
+                name,
+                signature: line.trim().to_string(),
+            });
+        }
+    }
+    items
+}
+
+/// Name of the public item declared on `line`, if it declares one
+fn public_name(language: DocLanguage, line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    let rest = match language {
+        DocLanguage::Rust => {
+            let rest = trimmed.strip_prefix("pub ")?;
+            let rest = strip_modifiers(rest, &["async ", "unsafe ", "extern \"C\" "]);
+            // `pub mod x;` is documented in the module's own file
+            if rest.starts_with("mod ") && rest.trim_end().ends_with(';') {
+                return None;
+            }
+            strip_keyword(
+                rest,
+                &[
+                    "const fn ",
+                    "fn ",
+                    "struct ",
+                    "enum ",
+                    "trait ",
+                    "type ",
+                    "const ",
+                    "static ",
+                    "mod ",
+                    "union ",
+                ],
+            )?
+        }
+        DocLanguage::Python => {
+            let rest = strip_modifiers(trimmed, &["async "]);
+            strip_keyword(rest, &["def ", "class "])?
+        }
+        DocLanguage::JavaScript => {
+            let rest = trimmed.strip_prefix("export ")?;
+            let rest = strip_modifiers(rest, &["default ", "async ", "abstract ", "declare "]);
+            strip_keyword(
+                rest,
+                &[
+                    "function* ",
+                    "function ",
+                    "class ",
+                    "interface ",
+                    "type ",
+                    "enum ",
+                    "const ",
+                    "let ",
+                ],
+            )?
+        }
+        DocLanguage::Go => {
+            let rest = strip_keyword(trimmed, &["func ", "type "])?;
+            // Methods: `func (r *Recv) Name(`
+            match rest.strip_prefix('(') {
+                Some(r) => r.split_once(')')?.1.trim_start(),
+                None => rest,
+            }
+        }
+    };
+
+    let name: String = rest
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+        .collect();
+    let public = match language {
+        DocLanguage::Python => !name.starts_with('_'),
+        DocLanguage::Go => name.starts_with(|c: char| c.is_uppercase()),
+        DocLanguage::Rust | DocLanguage::JavaScript => true,
+    };
+    (!name.is_empty() && public).then_some(name)
+}
+
+fn strip_modifiers<'a>(mut text: &'a str, modifiers: &[&str]) -> &'a str {
+    while let Some(rest) = modifiers.iter().find_map(|m| text.strip_prefix(m)) {
+        text = rest;
+    }
+    text
+}
+
+fn strip_keyword<'a>(text: &'a str, keywords: &[&str]) -> Option<&'a str> {
+    keywords
+        .iter()
+        .find_map(|k| text.strip_prefix(k))
+        .map(str::trim_start)
+}
+
+/// Whether the item declared on line `i` already has documentation
+fn documented(language: DocLanguage, lines: &[&str], i: usize) -> bool {
+    if language == DocLanguage::Python {
+        return lines[signature_end(lines, i) + 1..]
+            .iter()
+            .map(|l| l.trim())
+            .find(|l| !l.is_empty())
+            .is_some_and(|l| l.starts_with("\"\"\"") || l.starts_with("'''"));
+    }
+    let above = insertion_line(language, lines, i);
+    let Some(previous) = above.checked_sub(1).map(|p| lines[p].trim()) else {
+        return false;
+    };
+    match language {
+        DocLanguage::Rust => previous.starts_with("///") || previous.starts_with("#[doc"),
+        DocLanguage::JavaScript => previous.ends_with("*/"),
+        _ => previous.starts_with("//"),
+    }
+}
+
+/// Line a new doc comment for the item on line `i` goes before
+///
+/// Comments go above the item's attributes or decorators; Python docstrings
+/// go after the (possibly multi-line) signature.
+fn insertion_line(language: DocLanguage, lines: &[&str], i: usize) -> usize {
+    let attribute = match language {
+        DocLanguage::Rust => "#[",
+        DocLanguage::JavaScript => "@",
+        DocLanguage::Python => return signature_end(lines, i) + 1,
+        DocLanguage::Go => return i,
+    };
+    let mut at = i;
+    while at > 0 && lines[at - 1].trim_start().starts_with(attribute) {
+        at -= 1;
+    }
+    at
+}
+
+/// Last line of the Python signature starting on line `i`
+fn signature_end(lines: &[&str], i: usize) -> usize {
+    (i..lines.len())
+        .find(|&j| lines[j].trim_end().ends_with(':'))
+        .unwrap_or(i)
+}
+
+/// Messages asking the LLM to document `items`
+pub fn build_docs_messages(
+    path: &Path,
+    language: DocLanguage,
+    source: &str,
+    items: &[PublicItem],
+) -> Vec<ChatMessage> {
+    let listed: Vec<String> = items
+        .iter()
+        .map(|item| format!("{}: {}", item.line + 1, item.signature))
+        .collect();
+    let source = match source.char_indices().nth(MAX_SOURCE_CHARS) {
+        Some((i, _)) => &source[..i],
+        None => source,
+    };
+    vec![
+        ChatMessage::system(DOCS_SYSTEM_PROMPT),
+        ChatMessage::user(format!(
+            "File: {} ({})\n\nUndocumented public items (line: declaration):\n{}\n\nSource:\n```\n{}\n```",
+            path.display(),
+            language.name(),
+            listed.join("\n"),
+            source
+        )),
+    ]
+}
+
+/// Drafted docs by 1-based line number
+pub fn parse_docs_response(content: &str) -> HashMap<usize, String> {
+    let clean = content
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(clean) else {
+        return HashMap::new();
+    };
+    json.get("docs")
+        .and_then(|d| d.as_array())
+        .map(|docs| {
+            docs.iter()
+                .filter_map(|d| {
+                    let line = d.get("line")?.as_u64()? as usize;
+                    let doc = d.get("doc")?.as_str()?.trim();
+                    (!doc.is_empty()).then(|| (line, doc.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Drafted documentation for one file, as a patch for review
+#[derive(Debug, Clone)]
+pub struct DocChangeset {
+    /// File relative to the workspace root
+    pub file: PathBuf,
+    /// Names of the items that got docs
+    pub documented: Vec<String>,
+    /// An insert-only `Patch` operation
+    pub operation: AgentOperation,
+    /// The file with the patch applied
+    pub patched: String,
+}
+
+/// Build the changeset inserting `docs` (by 1-based line) above `items`
+///
+/// Returns `None` when no item got a doc.
+pub fn docs_changeset(
+    file: &Path,
+    language: DocLanguage,
+    source: &str,
+    items: &[PublicItem],
+    docs: &HashMap<usize, String>,
+) -> Option<DocChangeset> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut documented = Vec::new();
+    let mut insertions: Vec<(usize, Vec<String>)> = Vec::new();
+    for item in items {
+        let Some(doc) = docs.get(&(item.line + 1)) else {
+            continue;
+        };
+        let declaration = lines[item.line];
+        let mut indent: String = declaration
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .collect();
+        if language == DocLanguage::Python {
+            indent.push_str("    ");
+        }
+        insertions.push((
+            insertion_line(language, &lines, item.line),
+            doc_comment(language, &indent, doc),
+        ));
+        documented.push(item.name.clone());
+    }
+    if insertions.is_empty() {
+        return None;
+    }
+    insertions.sort_by_key(|(at, _)| *at);
+
+    let mut patched: Vec<&str> = Vec::new();
+    let mut next = 0;
+    for (at, comment) in &insertions {
+        patched.extend(&lines[next..*at]);
+        patched.extend(comment.iter().map(String::as_str));
+        next = *at;
+    }
+    patched.extend(&lines[next..]);
+    let mut patched = patched.join("\n");
+    if source.ends_with('\n') {
+        patched.push('\n');
+    }
+
+    Some(DocChangeset {
+        file: file.to_path_buf(),
+        documented,
+        operation: AgentOperation::Patch {
+            path: file.to_path_buf(),
+            diff: Some(insertion_diff(&lines, &insertions)),
+            edits: Vec::new(),
+        },
+        patched,
+    })
+}
+
+/// `doc` formatted as a comment in `language`
+fn doc_comment(language: DocLanguage, indent: &str, doc: &str) -> Vec<String> {
+    let text: Vec<&str> = doc.lines().map(str::trim_end).collect();
+    let prefixed = |prefix: &str| {
+        text.iter()
+            .map(|l| format!("{}{}{}", indent, prefix, l).trim_end().to_string())
+            .collect()
+    };
+    match language {
+        DocLanguage::Rust => prefixed("/// "),
+        DocLanguage::Go => prefixed("// "),
+        DocLanguage::JavaScript => {
+            let mut lines = vec![format!("{}/**", indent)];
+            lines.extend(prefixed(" * "));
+            lines.push(format!("{} */", indent));
+            lines
+        }
+        DocLanguage::Python => match text.as_slice() {
+            [line] => vec![format!("{}\"\"\"{}\"\"\"", indent, line)],
+            _ => {
+                let mut lines: Vec<String> = prefixed("");
+                lines[0] = format!("{}\"\"\"{}", indent, text[0]);
+                lines.push(format!("{}\"\"\"", indent));
+                lines
+            }
+        },
+    }
+}
+
+/// Unified diff inserting each block before its (0-based) line of `lines`
+fn insertion_diff(lines: &[&str], insertions: &[(usize, Vec<String>)]) -> String {
+    // Group insertions whose context would overlap into one hunk
+    let mut groups: Vec<Vec<&(usize, Vec<String>)>> = Vec::new();
+    for insertion in insertions {
+        match groups.last_mut() {
+            Some(group) if insertion.0 - group[group.len() - 1].0 <= 2 * CONTEXT_LINES => {
+                group.push(insertion)
+            }
+            _ => groups.push(vec![insertion]),
+        }
+    }
+
+    let mut diff = String::new();
+    let mut added_before = 0;
+    for group in groups {
+        let start = group[0].0.saturating_sub(CONTEXT_LINES);
+        let end = (group[group.len() - 1].0 + CONTEXT_LINES).min(lines.len());
+        let added: usize = group.iter().map(|(_, block)| block.len()).sum();
+
+        let mut body = String::new();
+        let mut next = start;
+        for (at, block) in &group {
+            for line in &lines[next..*at] {
+                body.push_str(&format!(" {}\n", line));
+            }
+            for line in block {
+                body.push_str(&format!("+{}\n", line));
+            }
+            next = *at;
+        }
+        for line in &lines[next..end] {
+            body.push_str(&format!(" {}\n", line));
+        }
+
+        let old_len = end - start;
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n{}",
+            start + 1,
+            old_len,
+            start + 1 + added_before,
+            old_len + added,
+            body
+        ));
+        added_before += added;
+    }
+    diff
+}
+
+/// Outcome of `/gen-docs`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocGenReport {
+    /// Changesets held for review: file, approval ID and documented items
+    pub held: Vec<(PathBuf, String, Vec<String>)>,
+    /// Files whose changes broke `cargo doc`, with its errors
+    pub failed_check: Vec<(PathBuf, String)>,
+    /// Files that failed to document, with the error
+    pub errors: Vec<(PathBuf, String)>,
+    /// Files with nothing to document
+    pub unchanged: usize,
+}
+
+impl DocGenReport {
+    pub fn summary(&self) -> String {
+        let items: usize = self.held.iter().map(|(_, _, items)| items.len()).sum();
+        let mut text = format!(
+            "Drafted docs for {} item(s) in {} file(s)",
+            items,
+            self.held.len()
+        );
+        if self.unchanged > 0 {
+            text.push_str(&format!(
+                "; {} file(s) had nothing to document",
+                self.unchanged
+            ));
+        }
+        for (file, id, items) in &self.held {
+            text.push_str(&format!(
+                "\n  [{}] {}: {}",
+                id,
+                file.display(),
+                items.join(", ")
+            ));
+        }
+        for (file, errors) in &self.failed_check {
+            text.push_str(&format!(
+                "\nNot offered, `cargo doc` failed for {}:\n{}",
+                file.display(),
+                errors.trim_end()
+            ));
+        }
+        for (file, error) in &self.errors {
+            text.push_str(&format!("\nFailed on {}: {}", file.display(), error));
+        }
+        if !self.held.is_empty() {
+            text.push_str("\n\n/approvals shows the changes, /approve <id> applies one");
+        }
+        text
+    }
+}
+
+/// Build the docs of the crate containing `changeset.file` with the changes
+/// applied, restoring the file afterwards
+///
+/// Returns the `cargo doc` errors when the build fails. Only Rust files are
+/// checked; anything else passes.
+pub fn verify_docs(root: &Path, changeset: &DocChangeset) -> Result<Option<String>> {
+    if DocLanguage::from_path(&changeset.file) != Some(DocLanguage::Rust) {
+        return Ok(None);
+    }
+    let path = root.join(&changeset.file);
+    let Some(crate_dir) = path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Cargo.toml").is_file())
+    else {
+        return Ok(None);
+    };
+
+    let original = std::fs::read_to_string(&path)?;
+    std::fs::write(&path, &changeset.patched)?;
+    let output = Command::new("cargo")
+        .args(["doc", "--no-deps", "--quiet"])
+        .current_dir(crate_dir)
+        .output();
+    std::fs::write(&path, original)?;
+
+    let output = output?;
+    if output.status.success() {
+        return Ok(None);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let skip = stderr.chars().count().saturating_sub(MAX_CHECK_CHARS);
+    Ok(Some(stderr.chars().skip(skip).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestration::patch::apply_unified_diff;
+
+    const RUST: &str = "\
+use std::fmt;
+
+pub const MAX: usize = 3;
+
+/// Already documented
+pub fn documented() {}
+
+#[derive(Debug)]
+pub struct Pager {
+    pub size: usize,
+}
+
+impl Pager {
+    pub fn new(size: usize) -> Self {
+        Self { size }
+    }
+
+    fn private(&self) {}
+}
+
+pub(crate) fn internal() {}
+
+#[cfg(test)]
+mod tests {
+    pub fn helper() {}
+}
+";
+
+    #[test]
+    fn test_undocumented_items() {
+        let items = undocumented_items(DocLanguage::Rust, RUST);
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["MAX", "Pager", "new"]);
+        assert_eq!(items[2].line, 13);
+        assert_eq!(items[2].signature, "pub fn new(size: usize) -> Self {");
+
+        let python = "def area(w, h):\n    \"\"\"Area.\"\"\"\n    return w * h\n\n\
+                      def perimeter(w,\n              h):\n    return 2 * (w + h)\n\n\
+                      def _hidden():\n    pass\n";
+        let names: Vec<String> = undocumented_items(DocLanguage::Python, python)
+            .into_iter()
+            .map(|i| i.name)
+            .collect();
+        assert_eq!(names, ["perimeter"]);
+
+        let go = "// Open opens it.\nfunc Open() {}\nfunc (s *Server) Start() error {\nfunc helper() {}\ntype Config struct {";
+        let names: Vec<String> = undocumented_items(DocLanguage::Go, go)
+            .into_iter()
+            .map(|i| i.name)
+            .collect();
+        assert_eq!(names, ["Start", "Config"]);
+    }
+
+    #[test]
+    fn test_docs_changeset_inserts_above_attributes() {
+        let items = undocumented_items(DocLanguage::Rust, RUST);
+        let docs = parse_docs_response(
+            r#"```json
+            {"docs": [
+                {"line": 9, "doc": "Splits output into pages."},
+                {"line": 14, "doc": "Creates a pager.\n\nPages hold `size` lines."}
+            ]}
+            ```"#,
+        );
+        let changeset = docs_changeset(
+            Path::new("src/pager.rs"),
+            DocLanguage::Rust,
+            RUST,
+            &items,
+            &docs,
+        )
+        .unwrap();
+        assert_eq!(changeset.documented, ["Pager", "new"]);
+        assert!(changeset
+            .patched
+            .contains("/// Splits output into pages.\n#[derive(Debug)]\npub struct Pager {"));
+        assert!(changeset.patched.contains(
+            "    /// Creates a pager.\n    ///\n    /// Pages hold `size` lines.\n    pub fn new("
+        ));
+
+        // The diff produces the same file and leaves everything else alone
+        let AgentOperation::Patch {
+            diff: Some(diff), ..
+        } = &changeset.operation
+        else {
+            panic!("expected a diff");
+        };
+        assert_eq!(apply_unified_diff(RUST, diff).unwrap(), changeset.patched);
+        assert!(diff.lines().all(|l| !l.starts_with('-')));
+    }
+
+    #[test]
+    fn test_doc_comment_styles() {
+        assert_eq!(
+            doc_comment(DocLanguage::JavaScript, "  ", "Adds two numbers."),
+            ["  /**", "   * Adds two numbers.", "   */"]
+        );
+        assert_eq!(
+            doc_comment(DocLanguage::Python, "    ", "Area of a rectangle."),
+            ["    \"\"\"Area of a rectangle.\"\"\""]
+        );
+        assert_eq!(
+            doc_comment(DocLanguage::Python, "    ", "Area.\n\nIn square units."),
+            ["    \"\"\"Area.", "", "    In square units.", "    \"\"\""]
+        );
+    }
+}
@@ -0,0 +1,412 @@
+//! Error-to-fix loop (`/fix`)
+//!
+//! Builds the workspace and lists the compiler errors (rustc and tsc) as
+//! problems. Fixing one hands the Developer the error, the code around it
+//! and the file's uncommitted changes, then rebuilds to check the error is
+//! gone, feeding the new output back for another attempt if it isn't.
+
+use super::testgen::{tail, truncate};
+use crate::Result;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Lines shown on each side of the error
+const CONTEXT_LINES: usize = 20;
+
+/// Longest error block sent
+const MAX_ERROR_CHARS: usize = 4_000;
+
+/// Longest diff of recent changes sent
+const MAX_DIFF_CHARS: usize = 6_000;
+
+/// Build output fed back after a failed attempt (its tail)
+const MAX_FAILURE_CHARS: usize = 6_000;
+
+/// A compiler error at a source location
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// Source file, as the compiler printed it (relative to the workspace)
+    pub file: PathBuf,
+    /// 1-based line and column
+    pub line: usize,
+    pub column: usize,
+    /// Error code such as `E0308` or `TS2322`
+    pub code: Option<String>,
+    pub message: String,
+    /// The compiler's full report of the error
+    pub detail: String,
+}
+
+impl Problem {
+    /// One line: location, code and message
+    pub fn describe(&self) -> String {
+        let code = self
+            .code
+            .as_ref()
+            .map(|c| format!("[{}]", c))
+            .unwrap_or_default();
+        format!(
+            "{}:{}:{} error{}: {}",
+            self.file.display(),
+            self.line,
+            self.column,
+            code,
+            self.message
+        )
+    }
+
+    /// Whether `other` is the same error, wherever edits have moved it
+    fn same_error(&self, other: &Problem) -> bool {
+        self.file == other.file && self.code == other.code && self.message == other.message
+    }
+}
+
+/// Command that builds the project and reports compiler errors
+pub fn build_command(root: &Path) -> Option<&'static str> {
+    if root.join("Cargo.toml").is_file() {
+        Some("cargo build --all-targets")
+    } else if root.join("tsconfig.json").is_file() {
+        Some("npx tsc --noEmit --pretty false")
+    } else {
+        None
+    }
+}
+
+/// Errors in rustc or tsc output, in the order reported, without duplicates
+pub fn parse_problems(output: &str) -> Vec<Problem> {
+    let mut problems: Vec<Problem> = Vec::new();
+    let mut push = |problem: Problem| {
+        if !problems.iter().any(|p| p == &problem) {
+            problems.push(problem);
+        }
+    };
+
+    let lines: Vec<&str> = output.lines().collect();
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(problem) = parse_tsc_line(line) {
+            push(problem);
+            continue;
+        }
+        let Some((code, message)) = parse_rustc_header(line) else {
+            continue;
+        };
+        // The block runs to the next blank line; its first `-->` is the location
+        let block: Vec<&str> = lines[i..]
+            .iter()
+            .take_while(|l| !l.trim().is_empty())
+            .copied()
+            .collect();
+        let location = block
+            .iter()
+            .find_map(|l| l.trim_start().strip_prefix("--> "))
+            .and_then(parse_location);
+        if let Some((file, line, column)) = location {
+            push(Problem {
+                file,
+                line,
+                column,
+                code,
+                message,
+                detail: block.join("\n"),
+            });
+        }
+    }
+    problems
+}
+
+/// `error[E0308]: message` or `error: message`
+fn parse_rustc_header(line: &str) -> Option<(Option<String>, String)> {
+    let rest = line.strip_prefix("error")?;
+    let (code, rest) = match rest.strip_prefix('[') {
+        Some(rest) => {
+            let (code, rest) = rest.split_once(']')?;
+            (Some(code.to_string()), rest)
+        }
+        None => (None, rest),
+    };
+    let message = rest.strip_prefix(':')?.trim();
+    Some((code, message.to_string()))
+}
+
+/// `path:line:col`
+fn parse_location(text: &str) -> Option<(PathBuf, usize, usize)> {
+    let mut parts = text.trim().rsplitn(3, ':');
+    let column = parts.next()?.parse().ok()?;
+    let line = parts.next()?.parse().ok()?;
+    let file = parts.next()?;
+    Some((PathBuf::from(file), line, column))
+}
+
+/// `file(line,col): error TS2322: message` or, pretty-printed,
+/// `file:line:col - error TS2322: message`
+fn parse_tsc_line(line: &str) -> Option<Problem> {
+    let (location, rest) = match line.split_once(": error ") {
+        Some((location, rest)) if location.ends_with(')') => {
+            let (file, position) = location.strip_suffix(')')?.rsplit_once('(')?;
+            (format!("{}:{}", file, position.replace(',', ":")), rest)
+        }
+        _ => {
+            let (location, rest) = line.split_once(" - error ")?;
+            (location.to_string(), rest)
+        }
+    };
+    let (code, message) = rest.split_once(": ")?;
+    if !code.starts_with("TS") {
+        return None;
+    }
+    let (file, line_no, column) = parse_location(&location)?;
+    Some(Problem {
+        file,
+        line: line_no,
+        column,
+        code: Some(code.to_string()),
+        message: message.trim().to_string(),
+        detail: line.trim().to_string(),
+    })
+}
+
+/// Result of one build
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildRun {
+    pub success: bool,
+    pub problems: Vec<Problem>,
+    /// Combined stdout and stderr
+    pub output: String,
+}
+
+/// Run `command` in `root` and collect its errors
+pub fn run_build(root: &Path, command: &str) -> Result<BuildRun> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(root)
+        .output()?;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(BuildRun {
+        success: output.status.success(),
+        problems: parse_problems(&text),
+        output: text,
+    })
+}
+
+/// Numbered list of problems, as `/fix` shows them
+pub fn problems_list(command: &str, run: &BuildRun) -> String {
+    if run.problems.is_empty() {
+        return if run.success {
+            format!("`{}` succeeded: no problems", command)
+        } else {
+            format!(
+                "`{}` failed without reporting a compiler error:\n{}",
+                command,
+                tail(&run.output, 2_000)
+            )
+        };
+    }
+    let mut text = format!("Problems from `{}`:", command);
+    for (i, problem) in run.problems.iter().enumerate() {
+        text.push_str(&format!("\n  {}. {}", i + 1, problem.describe()));
+    }
+    text.push_str("\n\n/fix <n> sends a problem to the Developer");
+    text
+}
+
+/// Lines around the problem, numbered, with the error line marked
+fn file_region(source: &str, line: usize) -> String {
+    let first = line.saturating_sub(CONTEXT_LINES + 1);
+    source
+        .lines()
+        .enumerate()
+        .skip(first)
+        .take_while(|(i, _)| *i < line + CONTEXT_LINES)
+        .map(|(i, text)| {
+            let marker = if i + 1 == line { ">" } else { " " };
+            format!("{}{:>5} | {}\n", marker, i + 1, text)
+        })
+        .collect()
+}
+
+/// Uncommitted changes to `file`, or to the workspace when `file` has none
+fn recent_changes(root: &Path, file: &Path) -> Option<String> {
+    let git = |args: &[&str]| {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !text.is_empty()).then_some(text)
+    };
+    let file = file.to_string_lossy();
+    git(&["diff", "HEAD", "--", &file]).or_else(|| git(&["diff", "HEAD", "--stat"]))
+}
+
+/// Task handed to the Developer for one attempt
+///
+/// `failure` is the output of the previous attempt's build.
+pub fn fix_task(
+    root: &Path,
+    problem: &Problem,
+    command: &str,
+    failure: Option<&str>,
+) -> Result<String> {
+    let source = std::fs::read_to_string(root.join(&problem.file))?;
+    let mut task = format!(
+        "Fix this error reported by `{}`:\n```\n{}\n```\n\n\
+         Make the smallest change that fixes the error at its cause, which may be in \
+         another file. Don't silence it (no `allow` attributes, `any` casts or \
+         `@ts-ignore`). Don't run any commands: the build is rerun for you.",
+        command,
+        truncate(&problem.detail, MAX_ERROR_CHARS)
+    );
+    task.push_str(&format!(
+        "\n\n{} around line {}:\n```\n{}```",
+        problem.file.display(),
+        problem.line,
+        file_region(&source, problem.line)
+    ));
+    if let Some(diff) = recent_changes(root, &problem.file) {
+        task.push_str(&format!(
+            "\n\nRecent uncommitted changes, which likely caused the error:\n```\n{}\n```",
+            truncate(&diff, MAX_DIFF_CHARS)
+        ));
+    }
+    if let Some(output) = failure {
+        task.push_str(&format!(
+            "\n\nThe previous attempt didn't fix it. Output of `{}`:\n```\n{}\n```",
+            command,
+            tail(output, MAX_FAILURE_CHARS)
+        ));
+    }
+    Ok(task)
+}
+
+/// Whether `run` shows `problem` fixed without adding errors
+pub fn is_fixed(problem: &Problem, before: usize, run: &BuildRun) -> bool {
+    run.success
+        || (!run.problems.iter().any(|p| problem.same_error(p)) && run.problems.len() < before)
+}
+
+/// Outcome of `/fix <n>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixReport {
+    /// The problem, described
+    pub problem: String,
+    pub command: String,
+    pub attempts: usize,
+    /// Whether the problem is gone from the last build
+    pub fixed: bool,
+    /// Whether the last build succeeded
+    pub success: bool,
+    /// Errors left in the last build
+    pub remaining: usize,
+    /// Files the Developer wrote or patched
+    pub files: BTreeSet<PathBuf>,
+    /// Operations that weren't applied, with the reason
+    pub skipped: Vec<String>,
+}
+
+impl FixReport {
+    pub fn summary(&self) -> String {
+        let outcome = match (self.fixed, self.success) {
+            (_, true) => "fixed, build succeeds".to_string(),
+            (true, false) => format!("fixed, {} other error(s) remain", self.remaining),
+            (false, _) => "not fixed".to_string(),
+        };
+        let mut text = format!(
+            "{}\n{} after {} attempt(s) of `{}`",
+            self.problem, outcome, self.attempts, self.command
+        );
+        if !self.files.is_empty() {
+            text.push_str("\nFiles:");
+            for file in &self.files {
+                text.push_str(&format!("\n  {}", file.display()));
+            }
+        }
+        for skipped in &self.skipped {
+            text.push_str(&format!("\nSkipped: {}", skipped));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUSTC: &str = r#"   Compiling demo v0.1.0 (/tmp/demo)
+error[E0308]: mismatched types
+ --> src/main.rs:3:18
+  |
+3 |     let x: u32 = "a";
+  |            ---   ^^^ expected `u32`, found `&str`
+
+warning: unused variable: `y`
+ --> src/lib.rs:1:5
+
+error: cannot find macro `printn` in this scope
+ --> src/lib.rs:7:5
+
+error: could not compile `demo` (bin "demo") due to 2 previous errors
+"#;
+
+    #[test]
+    fn test_parse_rustc_problems() {
+        let problems = parse_problems(RUSTC);
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].file, PathBuf::from("src/main.rs"));
+        assert_eq!((problems[0].line, problems[0].column), (3, 18));
+        assert_eq!(problems[0].code.as_deref(), Some("E0308"));
+        assert_eq!(problems[0].message, "mismatched types");
+        assert!(problems[0].detail.contains("expected `u32`"));
+        assert_eq!(
+            problems[1].describe(),
+            "src/lib.rs:7:5 error: cannot find macro `printn` in this scope"
+        );
+    }
+
+    #[test]
+    fn test_parse_tsc_problems() {
+        let output = "src/app.ts(12,7): error TS2322: Type 'string' is not assignable to type 'number'.\n\
+                      src/util.ts:4:1 - error TS2304: Cannot find name 'foo'.\n\
+                      Found 2 errors.\n";
+        let problems = parse_problems(output);
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].file, PathBuf::from("src/app.ts"));
+        assert_eq!((problems[0].line, problems[0].column), (12, 7));
+        assert_eq!(problems[0].code.as_deref(), Some("TS2322"));
+        assert_eq!(problems[1].message, "Cannot find name 'foo'.");
+    }
+
+    #[test]
+    fn test_fix_task_and_is_fixed() {
+        let dir = tempfile::tempdir().unwrap();
+        let source: String = (1..=50).map(|i| format!("line {}\n", i)).collect();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), source).unwrap();
+
+        let problem = parse_problems(RUSTC).remove(0);
+        let task = fix_task(dir.path(), &problem, "cargo build", Some("still broken")).unwrap();
+        assert!(task.contains("error[E0308]: mismatched types"));
+        assert!(task.contains(">    3 | line 3"));
+        assert!(task.contains("   23 | line 23"));
+        assert!(!task.contains("line 24\n"));
+        assert!(task.contains("still broken"));
+
+        // The same error on another line is still the same error
+        let mut moved = problem.clone();
+        moved.line = 9;
+        let run = |success, problems: Vec<Problem>| BuildRun {
+            success,
+            problems,
+            output: String::new(),
+        };
+        assert!(!is_fixed(&problem, 2, &run(false, vec![moved])));
+        assert!(is_fixed(&problem, 2, &run(true, vec![])));
+        assert!(!is_fixed(&problem, 1, &run(false, vec![parse_problems(RUSTC).remove(1)])));
+    }
+}
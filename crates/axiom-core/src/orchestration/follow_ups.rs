@@ -0,0 +1,197 @@
+//! Follow-up suggestions after a run
+//!
+//! Once a request has been handled, a few likely next prompts are offered
+//! ("run the tests", "also update the docs", "revert this change"). They
+//! come from what the run did rather than from a model, so they cost
+//! nothing and show up immediately.
+
+use super::testgen::test_command;
+use std::path::{Path, PathBuf};
+
+/// Most suggestions offered after a run
+pub const MAX_FOLLOW_UPS: usize = 3;
+
+/// Files named in a suggestion before the rest are counted
+const NAMED_FILES: usize = 3;
+
+/// What a finished run did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOutcome {
+    /// The request that started the run
+    pub task: String,
+    /// Files the run wrote, relative to the workspace
+    pub files: Vec<PathBuf>,
+    /// Whether an agent of the run failed
+    pub failed: bool,
+}
+
+impl RunOutcome {
+    pub fn new(task: impl Into<String>) -> Self {
+        Self {
+            task: task.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Note a file the run wrote
+    pub fn file_written(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        if !self.files.contains(&path) {
+            self.files.push(path);
+        }
+    }
+}
+
+/// A suggested next prompt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FollowUp {
+    /// Short text on the chip
+    pub label: String,
+    /// What picking it puts in the input
+    pub input: String,
+}
+
+impl FollowUp {
+    fn new(label: &str, input: impl Into<String>) -> Self {
+        Self {
+            label: label.to_string(),
+            input: input.into(),
+        }
+    }
+}
+
+/// Up to [`MAX_FOLLOW_UPS`] prompts that likely come next after `run` in
+/// the workspace at `root`
+pub fn suggest_follow_ups(root: &Path, run: &RunOutcome) -> Vec<FollowUp> {
+    let mut suggestions = Vec::new();
+    if run.failed {
+        suggestions.push(FollowUp::new(
+            "explain the error",
+            "Explain why that failed and how to fix it",
+        ));
+        if !run.task.trim().is_empty() {
+            suggestions.push(FollowUp::new("try again", run.task.trim()));
+        }
+    }
+
+    if !run.files.is_empty() {
+        let files = describe_files(&run.files);
+        if let Some(command) = test_command(root) {
+            suggestions.push(FollowUp::new("run the tests", format!("!{}", command)));
+        }
+        let has_docs = root.join("README.md").is_file() || root.join("docs").is_dir();
+        if has_docs && !run.files.iter().any(|path| is_doc(path)) {
+            suggestions.push(FollowUp::new(
+                "also update the docs",
+                format!("Update the documentation to match the changes to {}", files),
+            ));
+        }
+        suggestions.push(FollowUp::new(
+            "revert this change",
+            format!("Revert the changes you just made to {}", files),
+        ));
+    } else if !run.failed {
+        suggestions.push(FollowUp::new("go on", "Go ahead and make those changes"));
+        suggestions.push(FollowUp::new("explain more", "Explain that in more detail"));
+    }
+
+    suggestions.truncate(MAX_FOLLOW_UPS);
+    suggestions
+}
+
+/// Whether `path` is documentation rather than code
+fn is_doc(path: &Path) -> bool {
+    path.starts_with("docs")
+        || path
+            .extension()
+            .is_some_and(|ext| ext == "md" || ext == "mdx" || ext == "rst" || ext == "txt")
+}
+
+/// `a.rs, b.rs and 2 more files`
+fn describe_files(files: &[PathBuf]) -> String {
+    let names: Vec<String> = files
+        .iter()
+        .take(NAMED_FILES)
+        .map(|path| path.display().to_string())
+        .collect();
+    let rest = files.len().saturating_sub(NAMED_FILES);
+    if rest > 0 {
+        return format!("{} and {} more files", names.join(", "), rest);
+    }
+    match names.split_last() {
+        Some((last, first)) if !first.is_empty() => format!("{} and {}", first.join(", "), last),
+        _ => names.concat(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn labels(suggestions: &[FollowUp]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_changes_suggest_tests_docs_and_revert() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[package]").unwrap();
+        fs::write(dir.path().join("README.md"), "# Demo").unwrap();
+
+        let mut run = RunOutcome::new("Add a parser");
+        run.file_written("src/parser.rs");
+        run.file_written("src/lib.rs");
+        run.file_written("src/parser.rs");
+        let suggestions = suggest_follow_ups(dir.path(), &run);
+
+        assert_eq!(
+            labels(&suggestions),
+            [
+                "run the tests",
+                "also update the docs",
+                "revert this change"
+            ]
+        );
+        assert_eq!(suggestions[0].input, "!cargo test");
+        assert_eq!(
+            suggestions[2].input,
+            "Revert the changes you just made to src/parser.rs and src/lib.rs"
+        );
+
+        // Docs already changed, and no test command
+        fs::remove_file(dir.path().join("Cargo.toml")).unwrap();
+        run.file_written("README.md");
+        assert_eq!(
+            labels(&suggest_follow_ups(dir.path(), &run)),
+            ["revert this change"]
+        );
+    }
+
+    #[test]
+    fn test_failed_and_empty_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut run = RunOutcome::new("Fix the build");
+        run.failed = true;
+        let suggestions = suggest_follow_ups(dir.path(), &run);
+        assert_eq!(labels(&suggestions), ["explain the error", "try again"]);
+        assert_eq!(suggestions[1].input, "Fix the build");
+
+        let run = RunOutcome::new("How does the parser work?");
+        assert_eq!(
+            labels(&suggest_follow_ups(dir.path(), &run)),
+            ["go on", "explain more"]
+        );
+    }
+
+    #[test]
+    fn test_describe_files() {
+        let files: Vec<PathBuf> = ["a.rs", "b.rs", "c.rs", "d.rs", "e.rs"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        assert_eq!(describe_files(&files[..1]), "a.rs");
+        assert_eq!(describe_files(&files[..3]), "a.rs, b.rs and c.rs");
+        assert_eq!(describe_files(&files), "a.rs, b.rs, c.rs and 2 more files");
+    }
+}
@@ -5,6 +5,8 @@
 //! - Product Owner: Defines requirements
 //! - Architect: Designs technical solutions
 //! - Developer: Writes and modifies code
+//! - Reviewer: Checks the Developer's changes before they are applied
+//! - Shell Summarizer: Condenses long build and test output
 //!
 //! # Example
 //!
@@ -22,10 +24,22 @@
 //! println!("Next agent: {:?}", decision.next_agent);
 //! ```
 
+mod cli_delegate;
+mod context_builder;
 mod developer;
+mod docgen;
+mod fixer;
+mod follow_ups;
 mod orchestrator;
+mod patch;
+mod queue;
+mod refactor;
+mod reviewer;
 mod service;
+mod summarizer;
+mod testgen;
 mod types;
+mod verify;
 
 // Re-export types
 pub use types::{
@@ -33,9 +47,65 @@ pub use types::{
     DeveloperResponse, LlmSettings, MessageRole, NextAgent, OrchestratorDecision, ProviderConfig,
 };
 
+// Re-export patch helpers
+pub use patch::{
+    apply_edits, apply_llm_change, apply_patch, apply_unified_diff, check_patch, failure_report,
+    parse_search_replace, unified_diff, PatchError, PatchFailure, TextEdit,
+};
+
+// Re-export the task queue
+pub use queue::{
+    summarize_outcomes, task_prompt, SubTask, TaskOutcome, TaskProgress, TaskQueue, TaskState,
+};
+
+// Re-export CLI agent delegation
+pub use cli_delegate::{run_cli_delegate, DelegateEnd, DelegateReport};
+
+// Re-export the repository context builder
+pub use context_builder::{default_context, ContextBuilder};
+
 // Re-export service
 pub use service::{OperationResult, OrchestrationService, ProviderConfigUpdate};
 
+// Re-export documentation generation
+pub use docgen::{
+    doc_targets, undocumented_items, verify_docs, DocChangeset, DocGenReport, DocLanguage,
+    PublicItem,
+};
+
+// Re-export the error-to-fix loop
+pub use fixer::{
+    build_command, fix_task, is_fixed, parse_problems, problems_list, run_build, BuildRun,
+    FixReport, Problem,
+};
+
+// Re-export follow-up suggestions
+pub use follow_ups::{suggest_follow_ups, FollowUp, RunOutcome, MAX_FOLLOW_UPS};
+
+// Re-export symbol rename
+pub use refactor::{
+    is_identifier, plan_rename, FileChange, RefactorSession, RefactorUndo, RenameChangeset,
+};
+
+// Re-export test generation
+pub use testgen::{
+    parse_test_counts, resolve_target, test_command, TestGenReport, TestTarget, DEFAULT_ATTEMPTS,
+};
+
+// Re-export outcome verification
+pub use verify::{
+    repair_task, run_checks, verify_and_repair, CheckResult, Verification, VerifyCheck,
+    VerifyConfig, VerifyReport,
+};
+
+// Re-export the review step
+pub use reviewer::{parse_review, revision_task};
+
 // Re-export helper functions
-pub use developer::{build_developer_messages, get_file_tree, parse_developer_response};
-pub use orchestrator::{build_orchestrator_messages, parse_orchestrator_response};
+pub use developer::{
+    apply_operation, build_developer_messages, get_file_tree, parse_developer_response, retry_task,
+};
+pub use orchestrator::{
+    build_orchestrator_messages, decision_from_reply, parse_orchestrator_response, route_tool,
+    Delegate,
+};
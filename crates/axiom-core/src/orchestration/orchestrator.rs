@@ -2,7 +2,10 @@
 //!
 //! Analyzes user requests and delegates to appropriate agents.
 
-use super::types::{ChatMessage, NextAgent, OrchestratorDecision};
+use super::context_builder::with_context;
+use super::types::{AgentRole, ChatMessage, MessageRole, NextAgent, OrchestratorDecision};
+use crate::llm::{ToolReply, ToolSpec};
+use crate::prompts::PromptLibrary;
 use crate::Result;
 
 const ORCHESTRATOR_SYSTEM_PROMPT: &str = r#"
@@ -14,17 +17,90 @@ The agents at your disposal are:
 2. **Architect (architect)**: Responsible for technical design, file structure, and technology choices. Call this agent after requirements are clear.
 3. **Developer (developer)**: Responsible for writing code, fixing bugs, and running tests. Call this agent when the design is ready or for specific code tasks.
 
-**Output Format:**
-You must respond with a strict JSON object (no markdown formatting) in the following format:
-{
-  "next_agent": "po" | "architect" | "developer" | "user",
-  "reasoning": "Explanation of why you chose this agent",
-  "task": "Specific instructions for the agent (or the final answer to the user if next_agent is 'user')"
-}
+Always hand over by calling the `route` tool once, with:
+- "next_agent": "po", "architect", "developer" or "user"
+- "reasoning": why you chose this agent
+- "task": specific instructions for the agent (or the final answer to the user if next_agent is "user")
+
+If the user says "hello" or asks a general question unrelated to coding/project, route to "user" and provide a friendly answer in "task".
+"#;
 
-If the user says "hello" or asks a general question unrelated to coding/project, set "next_agent": "user" and provide a friendly answer in "task".
+const CLI_DELEGATES_PROMPT: &str = r#"
+You can also hand a self-contained coding task to one of these CLI agents by routing to "cli" with its ID in "cli_agent". It works in the workspace on its own, with "task" as its prompt, and you are told which files it changed when it is done:
 "#;
 
+/// Name of the tool the orchestrator hands over with
+pub const ROUTE_TOOL: &str = "route";
+
+/// A CLI agent the orchestrator may hand a task to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delegate {
+    /// ID in `[cli_agents]`
+    pub id: String,
+    pub name: String,
+}
+
+/// The tool the orchestrator calls to pick the next agent
+///
+/// With `delegates`, it may also pick `"cli"` and name one of them.
+pub fn route_tool(delegates: &[Delegate]) -> ToolSpec {
+    let mut agents = vec!["po", "architect", "developer", "user"];
+    let mut parameters = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "next_agent": {
+                "type": "string",
+                "description": "Who handles the next step"
+            },
+            "reasoning": {
+                "type": "string",
+                "description": "Why this agent"
+            },
+            "task": {
+                "type": "string",
+                "description": "Instructions for the agent, or the answer for the user"
+            }
+        },
+        "required": ["next_agent", "task"]
+    });
+    if !delegates.is_empty() {
+        agents.push("cli");
+        let ids: Vec<&str> = delegates.iter().map(|d| d.id.as_str()).collect();
+        parameters["properties"]["cli_agent"] = serde_json::json!({
+            "type": "string",
+            "enum": ids,
+            "description": "The CLI agent to run, when next_agent is \"cli\""
+        });
+    }
+    parameters["properties"]["next_agent"]["enum"] = serde_json::json!(agents);
+    ToolSpec::new(
+        ROUTE_TOOL,
+        "Hand the request to the agent best suited for the next step",
+        parameters,
+    )
+}
+
+/// The decision in the orchestrator's reply
+///
+/// Replies without a `route` call are read as before: a JSON decision in the
+/// text, or else an answer for the user.
+pub fn decision_from_reply(reply: &ToolReply) -> Result<OrchestratorDecision> {
+    let Some(call) = reply.calls.iter().find(|c| c.name == ROUTE_TOOL) else {
+        return parse_orchestrator_response(&reply.text);
+    };
+    Ok(OrchestratorDecision {
+        next_agent: call
+            .arg("next_agent")
+            .and_then(|a| NextAgent::from_route(a, call.arg("cli_agent")))
+            .unwrap_or(NextAgent::User),
+        reasoning: call
+            .arg("reasoning")
+            .unwrap_or("Direct response")
+            .to_string(),
+        task: call.arg("task").map(|s| s.to_string()),
+    })
+}
+
 /// Parse orchestrator response from LLM output
 pub fn parse_orchestrator_response(content: &str) -> Result<OrchestratorDecision> {
     // Clean up potential markdown code blocks
@@ -44,7 +120,9 @@ pub fn parse_orchestrator_response(content: &str) -> Result<OrchestratorDecision
                 .and_then(|v| v.as_str())
                 .unwrap_or("user");
 
-            let next_agent = next_agent_str.parse().unwrap_or(NextAgent::User);
+            let cli_agent = json.get("cli_agent").and_then(|v| v.as_str());
+            let next_agent =
+                NextAgent::from_route(next_agent_str, cli_agent).unwrap_or(NextAgent::User);
 
             let reasoning = json
                 .get("reasoning")
@@ -75,8 +153,34 @@ pub fn parse_orchestrator_response(content: &str) -> Result<OrchestratorDecision
 }
 
 /// Build messages for orchestrator with conversation history
-pub fn build_orchestrator_messages(conversation: &[ChatMessage]) -> Vec<ChatMessage> {
-    let mut messages = vec![ChatMessage::system(ORCHESTRATOR_SYSTEM_PROMPT)];
+///
+/// The system prompt is the workspace's orchestrator template from
+/// `prompts`, else the built-in one, with the latest user message as its
+/// task. It lists `delegates` as further agents, and ends with `context`
+/// from a [`ContextBuilder`](super::ContextBuilder).
+pub fn build_orchestrator_messages(
+    prompts: &PromptLibrary,
+    conversation: &[ChatMessage],
+    delegates: &[Delegate],
+    context: &str,
+) -> Vec<ChatMessage> {
+    let task = conversation
+        .iter()
+        .rev()
+        .find(|message| message.role == MessageRole::User)
+        .map_or("", |message| message.content.as_str());
+    let mut prompt = prompts.render(
+        AgentRole::Orchestrator.as_str(),
+        ORCHESTRATOR_SYSTEM_PROMPT,
+        task,
+    );
+    if !delegates.is_empty() {
+        prompt.push_str(CLI_DELEGATES_PROMPT);
+        for delegate in delegates {
+            prompt.push_str(&format!("- {} (`{}`)\n", delegate.name, delegate.id));
+        }
+    }
+    let mut messages = vec![ChatMessage::system(with_context(&prompt, context))];
     messages.extend(conversation.iter().cloned());
     messages
 }
@@ -102,6 +206,75 @@ mod tests {
         assert_eq!(decision.next_agent, NextAgent::Po);
     }
 
+    #[test]
+    fn test_decision_from_route_call() {
+        let reply = ToolReply {
+            text: "Sending this to the architect.".to_string(),
+            calls: vec![crate::llm::ToolCall {
+                id: "toolu_1".to_string(),
+                name: ROUTE_TOOL.to_string(),
+                arguments: serde_json::json!({
+                    "next_agent": "architect",
+                    "task": "Design the login flow"
+                }),
+            }],
+        };
+        let decision = decision_from_reply(&reply).unwrap();
+        assert_eq!(decision.next_agent, NextAgent::Architect);
+        assert_eq!(decision.reasoning, "Direct response");
+        assert_eq!(decision.task.as_deref(), Some("Design the login flow"));
+
+        let text_only = ToolReply {
+            text: "Hello there!".to_string(),
+            calls: Vec::new(),
+        };
+        assert_eq!(
+            decision_from_reply(&text_only).unwrap().next_agent,
+            NextAgent::User
+        );
+    }
+
+    #[test]
+    fn test_cli_delegate() {
+        let delegates = [Delegate {
+            id: "claude".to_string(),
+            name: "Claude Code".to_string(),
+        }];
+        let tool = route_tool(&delegates);
+        assert_eq!(
+            tool.parameters["properties"]["next_agent"]["enum"][4],
+            "cli"
+        );
+        assert_eq!(
+            tool.parameters["properties"]["cli_agent"]["enum"][0],
+            "claude"
+        );
+        assert!(route_tool(&[]).parameters["properties"]
+            .get("cli_agent")
+            .is_none());
+
+        let messages = build_orchestrator_messages(&PromptLibrary::default(), &[], &delegates, "");
+        assert!(messages[0].content.contains("- Claude Code (`claude`)"));
+
+        let response = r#"{"next_agent": "cli", "cli_agent": "claude", "task": "Add tests"}"#;
+        let decision = parse_orchestrator_response(response).unwrap();
+        assert_eq!(
+            decision.next_agent,
+            NextAgent::CliDelegate("claude".to_string())
+        );
+        assert_eq!(decision.next_agent.as_str(), "cli");
+        assert_eq!(decision.next_agent.cli_agent(), Some("claude"));
+
+        // "cli" without an agent can't be routed
+        let response = r#"{"next_agent": "cli", "task": "Add tests"}"#;
+        let decision = parse_orchestrator_response(response).unwrap();
+        assert_eq!(decision.next_agent, NextAgent::User);
+        assert_eq!(
+            "cli:gemini".parse::<NextAgent>().unwrap(),
+            NextAgent::CliDelegate("gemini".to_string())
+        );
+    }
+
     #[test]
     fn test_fallback_response() {
         let response = "I don't understand what you want me to do.";
@@ -0,0 +1,906 @@
+//! Patch application for developer operations
+//!
+//! Applies unified diffs and search/replace edit lists produced by the LLM.
+//! Hunks are located by their context lines rather than trusting line
+//! numbers, with a small amount of fuzz to survive sloppy model output.
+//! [`check_patch`] reports every hunk that fails rather than the first, with
+//! what the file holds there, so the model can correct them all at once.
+//! [`unified_diff`] goes the other way, describing a change for review.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+/// Maximum number of context lines that may be dropped from a hunk's edges
+const MAX_FUZZ: usize = 2;
+
+/// Unchanged lines shown around each change by [`unified_diff`]
+const DIFF_CONTEXT: usize = 3;
+
+/// Largest changed region (old lines × new lines) diffed line by line;
+/// bigger ones are shown as a wholesale replacement
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Lines of the file shown either side of where a failed hunk was expected
+const FAILURE_CONTEXT: usize = 2;
+
+/// Search/replace block markers
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER_MARKER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+
+/// A single search/replace edit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextEdit {
+    /// Exact text to find (must occur once)
+    pub find: String,
+    /// Replacement text
+    pub replace: String,
+}
+
+/// Errors while applying a patch
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum PatchError {
+    /// Diff text contained no hunks
+    #[error("patch contains no hunks")]
+    Empty,
+
+    /// Hunk header could not be parsed
+    #[error("malformed hunk header: {0}")]
+    MalformedHeader(String),
+
+    /// Hunk context did not match the file
+    #[error("hunk {hunk} does not match file near line {line}")]
+    HunkMismatch { hunk: usize, line: usize },
+
+    /// Edit text was not found in the file
+    #[error("edit {0} text not found in file")]
+    EditNotFound(usize),
+
+    /// Edit text matched more than once
+    #[error("edit {0} text is ambiguous ({1} matches)")]
+    EditAmbiguous(usize, usize),
+}
+
+/// One line of a hunk body
+#[derive(Debug, Clone, PartialEq)]
+enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+/// Old and new line counts of a hunk
+type LineCounts = (usize, usize);
+
+/// A parsed unified diff hunk
+#[derive(Debug, Clone, Default)]
+struct Hunk {
+    /// 1-based start line in the original file, if given
+    old_start: Option<usize>,
+    lines: Vec<HunkLine>,
+}
+
+impl Hunk {
+    /// Lines expected in the original file
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|l| match l {
+                HunkLine::Context(s) | HunkLine::Remove(s) => Some(s.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    /// Lines that replace them
+    fn new_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|l| match l {
+                HunkLine::Context(s) | HunkLine::Add(s) => Some(s.as_str()),
+                HunkLine::Remove(_) => None,
+            })
+            .collect()
+    }
+
+    /// Copy of the hunk with up to `fuzz` context lines trimmed from each edge
+    fn trimmed(&self, fuzz: usize) -> Hunk {
+        let lead = self
+            .lines
+            .iter()
+            .take(fuzz)
+            .take_while(|l| matches!(l, HunkLine::Context(_)))
+            .count();
+        let trail = self
+            .lines
+            .iter()
+            .rev()
+            .take(fuzz)
+            .take_while(|l| matches!(l, HunkLine::Context(_)))
+            .count();
+        let end = self.lines.len().saturating_sub(trail).max(lead);
+
+        Hunk {
+            old_start: self.old_start.map(|s| s + lead),
+            lines: self.lines[lead..end].to_vec(),
+        }
+    }
+}
+
+/// Parse a unified diff into hunks
+///
+/// A hunk ends once it holds the old and new line counts from its header, or
+/// at the next file's header, so multi-file diffs don't run together.
+fn parse_hunks(diff: &str) -> Result<Vec<Hunk>, PatchError> {
+    let mut hunks = Vec::new();
+    let mut current: Option<Hunk> = None;
+    // Old and new lines left in the current hunk, when its header gave them
+    let mut remaining: Option<LineCounts> = None;
+
+    let mut lines = diff.lines().peekable();
+    while let Some(line) = lines.next() {
+        if line.starts_with("@@") {
+            hunks.extend(current.take());
+            let (old_start, counts) = parse_header(line)?;
+            current = Some(Hunk {
+                old_start,
+                lines: Vec::new(),
+            });
+            remaining = counts;
+            continue;
+        }
+
+        let next = lines.peek().copied().unwrap_or_default();
+        if is_file_header(line, next) {
+            hunks.extend(current.take());
+            continue;
+        }
+
+        let Some(hunk) = current.as_mut() else {
+            // Headers (index, mode lines) and noise between hunks
+            continue;
+        };
+
+        let (old, new) = if let Some(rest) = line.strip_prefix('+') {
+            hunk.lines.push(HunkLine::Add(rest.to_string()));
+            (0, 1)
+        } else if let Some(rest) = line.strip_prefix('-') {
+            hunk.lines.push(HunkLine::Remove(rest.to_string()));
+            (1, 0)
+        } else if let Some(rest) = line.strip_prefix(' ') {
+            hunk.lines.push(HunkLine::Context(rest.to_string()));
+            (1, 1)
+        } else if line.is_empty() {
+            // Models often strip the leading space from blank context lines
+            hunk.lines.push(HunkLine::Context(String::new()));
+            (1, 1)
+        } else {
+            // "\ No newline at end of file" and other noise is ignored
+            (0, 0)
+        };
+
+        if let Some((old_left, new_left)) = remaining.as_mut() {
+            *old_left = old_left.saturating_sub(old);
+            *new_left = new_left.saturating_sub(new);
+            if (*old_left, *new_left) == (0, 0) {
+                hunks.extend(current.take());
+                remaining = None;
+            }
+        }
+    }
+    hunks.extend(current);
+
+    hunks.retain(|h| !h.lines.is_empty());
+    if hunks.is_empty() {
+        return Err(PatchError::Empty);
+    }
+    Ok(hunks)
+}
+
+/// Whether `line` starts the next file of a multi-file diff
+///
+/// A "--- " line only counts when "+++ " follows, since it may also remove
+/// a line that itself starts with "-- ".
+fn is_file_header(line: &str, next: &str) -> bool {
+    line.starts_with("diff ") || (line.starts_with("--- ") && next.starts_with("+++ "))
+}
+
+/// Parse "@@ -12,5 +12,6 @@" into the old start line and the old and new
+/// line counts; a bare "@@" gives neither
+fn parse_header(header: &str) -> Result<(Option<usize>, Option<LineCounts>), PatchError> {
+    let malformed = || PatchError::MalformedHeader(header.to_string());
+    let range = |sign: char| {
+        header
+            .split_whitespace()
+            .find_map(|p| p.strip_prefix(sign))
+            .map(|range| {
+                let (start, count) = range.split_once(',').unwrap_or((range, "1"));
+                Ok::<_, PatchError>((
+                    start.parse::<usize>().map_err(|_| malformed())?,
+                    count.parse::<usize>().map_err(|_| malformed())?,
+                ))
+            })
+            .transpose()
+    };
+
+    let Some((old_start, old_count)) = range('-')? else {
+        return Ok((None, None));
+    };
+    let counts = range('+')?.map(|(_, new_count)| (old_count, new_count));
+    Ok((Some(old_start), counts))
+}
+
+/// Find `needle` in `lines[from..]`, preferring the match closest to `hint`
+fn find_block(
+    lines: &[String],
+    needle: &[&str],
+    from: usize,
+    hint: usize,
+    eq: impl Fn(&str, &str) -> bool,
+) -> Option<usize> {
+    if needle.is_empty() || needle.len() > lines.len() {
+        return None;
+    }
+    let last = lines.len() - needle.len();
+    if from > last {
+        return None;
+    }
+
+    let matches_at = |pos: usize| {
+        needle
+            .iter()
+            .enumerate()
+            .all(|(i, n)| eq(&lines[pos + i], n))
+    };
+
+    (from..=last)
+        .filter(|&pos| matches_at(pos))
+        .min_by_key(|&pos| pos.abs_diff(hint))
+}
+
+/// Apply a unified diff to `original`
+///
+/// Each hunk is located by its context and removed lines. Exact matches are
+/// tried first, then whitespace-insensitive ones, then progressively fewer
+/// context lines (up to `MAX_FUZZ`) at the hunk edges.
+pub fn apply_unified_diff(original: &str, diff: &str) -> Result<String, PatchError> {
+    apply_hunks(original, &parse_hunks(diff)?)
+}
+
+fn apply_hunks(original: &str, hunks: &[Hunk]) -> Result<String, PatchError> {
+    let trailing_newline = original.is_empty() || original.ends_with('\n');
+    let mut lines: Vec<String> = original.lines().map(String::from).collect();
+
+    // Where the next hunk may start and how far hunks have drifted so far
+    let mut cursor = 0usize;
+    let mut offset = 0isize;
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        let expected = hunk
+            .old_start
+            .map(|s| (s.saturating_sub(1) as isize + offset).max(0) as usize)
+            .unwrap_or(cursor);
+
+        let mut applied = None;
+        for fuzz in 0..=MAX_FUZZ {
+            let candidate = if fuzz == 0 {
+                hunk.clone()
+            } else {
+                hunk.trimmed(fuzz)
+            };
+            let old = candidate.old_lines();
+
+            // Pure insertion: nothing to match against. Fuzz that trims away
+            // all context would insert blindly, so it is not allowed.
+            if old.is_empty() {
+                if fuzz == 0 {
+                    applied = Some((expected.clamp(cursor, lines.len()), candidate));
+                }
+                break;
+            }
+
+            let found = find_block(&lines, &old, cursor, expected, |a, b| a == b).or_else(|| {
+                find_block(&lines, &old, cursor, expected, |a, b| a.trim() == b.trim())
+            });
+            if let Some(at) = found {
+                applied = Some((at, candidate));
+                break;
+            }
+        }
+
+        let Some((at, candidate)) = applied else {
+            return Err(PatchError::HunkMismatch {
+                hunk: index + 1,
+                line: expected + 1,
+            });
+        };
+
+        let old_len = candidate.old_lines().len();
+        let new: Vec<String> = candidate
+            .new_lines()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let new_len = new.len();
+        lines.splice(at..at + old_len, new);
+
+        // Later hunks drift by wherever this one landed plus its size change
+        let growth = new_len as isize - old_len as isize;
+        offset = match candidate.old_start {
+            Some(start) => at as isize - start.saturating_sub(1) as isize + growth,
+            None => offset + growth,
+        };
+        cursor = at + new_len;
+    }
+
+    let mut result = lines.join("\n");
+    if trailing_newline && !result.is_empty() {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+/// Apply a list of search/replace edits to `original`
+///
+/// Every `find` must match exactly once. If it does not match verbatim, a
+/// line-by-line comparison ignoring surrounding whitespace is tried.
+pub fn apply_edits(original: &str, edits: &[TextEdit]) -> Result<String, PatchError> {
+    let mut content = original.to_string();
+
+    for (index, edit) in edits.iter().enumerate() {
+        let n = index + 1;
+        let count = content.matches(edit.find.as_str()).count();
+        match count {
+            1 => {
+                content = content.replacen(edit.find.as_str(), &edit.replace, 1);
+                continue;
+            }
+            0 => {}
+            many => return Err(PatchError::EditAmbiguous(n, many)),
+        }
+
+        // Fuzzy fallback: match whole lines ignoring indentation differences
+        let lines: Vec<String> = content.lines().map(String::from).collect();
+        let needle: Vec<&str> = edit.find.lines().collect();
+        let positions: Vec<usize> = (0..lines.len())
+            .filter(|&pos| {
+                !needle.is_empty()
+                    && pos + needle.len() <= lines.len()
+                    && needle
+                        .iter()
+                        .enumerate()
+                        .all(|(i, l)| lines[pos + i].trim() == l.trim())
+            })
+            .collect();
+
+        match positions.as_slice() {
+            [] => return Err(PatchError::EditNotFound(n)),
+            [pos] => {
+                let trailing_newline = content.ends_with('\n');
+                let mut out: Vec<String> = lines[..*pos].to_vec();
+                out.extend(edit.replace.lines().map(String::from));
+                out.extend_from_slice(&lines[pos + needle.len()..]);
+                content = out.join("\n");
+                if trailing_newline {
+                    content.push('\n');
+                }
+            }
+            many => return Err(PatchError::EditAmbiguous(n, many.len())),
+        }
+    }
+
+    Ok(content)
+}
+
+/// Parse search/replace blocks into edits
+///
+/// ```text
+/// <<<<<<< SEARCH
+/// exact existing text
+/// =======
+/// replacement text
+/// >>>>>>> REPLACE
+/// ```
+///
+/// Text outside blocks is ignored, as is a block missing its end marker.
+pub fn parse_search_replace(text: &str) -> Vec<TextEdit> {
+    let mut edits = Vec::new();
+    // The block being read: its find lines, then its replace lines
+    let mut block: Option<(Vec<&str>, Option<Vec<&str>>)> = None;
+
+    for line in text.lines() {
+        let marker = line.trim_end();
+        match block.as_mut() {
+            None if marker == SEARCH_MARKER => block = Some((Vec::new(), None)),
+            None => {}
+            Some((_, replace @ None)) if marker == DIVIDER_MARKER => *replace = Some(Vec::new()),
+            Some((find, Some(replace))) if marker == REPLACE_MARKER => {
+                edits.push(TextEdit {
+                    find: find.join("\n"),
+                    replace: replace.join("\n"),
+                });
+                block = None;
+            }
+            Some((_, Some(replace))) => replace.push(line),
+            Some((find, None)) => find.push(line),
+        }
+    }
+    edits
+}
+
+/// Why one hunk or edit of a patch didn't apply
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchFailure {
+    /// What went wrong
+    pub error: PatchError,
+    /// The text the hunk or edit expected to find
+    pub expected: String,
+    /// What the file holds where a hunk was expected
+    pub found: Option<String>,
+}
+
+impl PatchFailure {
+    fn new(error: PatchError) -> Self {
+        Self {
+            error,
+            expected: String::new(),
+            found: None,
+        }
+    }
+}
+
+impl fmt::Display for PatchFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if !self.expected.is_empty() {
+            write!(f, ". It expected:\n```\n{}\n```", self.expected)?;
+        }
+        if let Some(found) = &self.found {
+            write!(f, "\nThe file there reads:\n```\n{}\n```", found)?;
+        }
+        Ok(())
+    }
+}
+
+/// Apply a patch, or report everything in it that doesn't apply
+///
+/// Where [`apply_patch`] stops at the first problem, this checks each hunk
+/// of `diff` against `original` on its own, then each edit, so a model
+/// retrying can fix them all at once.
+pub fn check_patch(
+    original: &str,
+    diff: Option<&str>,
+    edits: &[TextEdit],
+) -> Result<String, Vec<PatchFailure>> {
+    let error = match apply_patch(original, diff, edits) {
+        Ok(patched) => return Ok(patched),
+        Err(error) => error,
+    };
+
+    let mut failures = Vec::new();
+    let mut content = original.to_string();
+    match diff.map(parse_hunks) {
+        Some(Ok(hunks)) => {
+            for (index, hunk) in hunks.iter().enumerate() {
+                let Err(PatchError::HunkMismatch { line, .. }) =
+                    apply_hunks(original, std::slice::from_ref(hunk))
+                else {
+                    continue;
+                };
+                let expected = hunk.old_lines();
+                let lines: Vec<&str> = original.lines().collect();
+                let start = line.saturating_sub(1 + FAILURE_CONTEXT).min(lines.len());
+                let end = (line - 1 + expected.len() + FAILURE_CONTEXT).min(lines.len());
+                failures.push(PatchFailure {
+                    error: PatchError::HunkMismatch {
+                        hunk: index + 1,
+                        line,
+                    },
+                    expected: expected.join("\n"),
+                    found: Some(lines[start..end].join("\n")),
+                });
+            }
+            if failures.is_empty() {
+                if let Ok(patched) = apply_hunks(original, &hunks) {
+                    content = patched;
+                }
+            }
+        }
+        Some(Err(e)) => failures.push(PatchFailure::new(e)),
+        None => {}
+    }
+
+    for (index, edit) in edits.iter().enumerate() {
+        match apply_edits(&content, std::slice::from_ref(edit)) {
+            Ok(edited) => content = edited,
+            Err(e) => {
+                let error = match e {
+                    PatchError::EditNotFound(_) => PatchError::EditNotFound(index + 1),
+                    PatchError::EditAmbiguous(_, count) => {
+                        PatchError::EditAmbiguous(index + 1, count)
+                    }
+                    other => other,
+                };
+                failures.push(PatchFailure {
+                    error,
+                    expected: edit.find.clone(),
+                    found: None,
+                });
+            }
+        }
+    }
+
+    // Hunks that apply alone but not together
+    if failures.is_empty() {
+        failures.push(PatchFailure::new(error));
+    }
+    Err(failures)
+}
+
+/// One line summing up `failures`, then each in full, for the model to
+/// correct its patch
+pub fn failure_report(failures: &[PatchFailure]) -> String {
+    let details: Vec<String> = failures.iter().map(|f| f.to_string()).collect();
+    let noun = if failures.len() == 1 { "part" } else { "parts" };
+    format!(
+        "Patch not applied: {} {} didn't match\n\n{}",
+        failures.len(),
+        noun,
+        details.join("\n\n")
+    )
+}
+
+/// Apply a change an LLM gave for a file: a unified diff, search/replace
+/// blocks, or otherwise the file's whole new content
+pub fn apply_llm_change(original: &str, text: &str) -> Result<String, Vec<PatchFailure>> {
+    let edits = parse_search_replace(text);
+    if !edits.is_empty() {
+        return check_patch(original, None, &edits);
+    }
+    let first = text.trim_start().lines().next().unwrap_or_default();
+    let is_diff = ["--- ", "diff --git", "@@"]
+        .iter()
+        .any(|prefix| first.starts_with(prefix));
+    if is_diff && text.lines().any(|line| line.starts_with("@@")) {
+        return check_patch(original, Some(text), &[]);
+    }
+    Ok(text.to_string())
+}
+
+/// A line of a computed diff
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Lines of `before` and `after`, aligned on a longest common subsequence
+fn diff_lines<'a>(before: &[&'a str], after: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let prefix = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old = &before[prefix..before.len() - suffix];
+    let new = &after[prefix..after.len() - suffix];
+
+    let mut lines: Vec<DiffLine> = before[..prefix].iter().map(|l| DiffLine::Same(l)).collect();
+    if old.len() * new.len() <= MAX_DIFF_CELLS {
+        // lcs[i][j]: common lines of old[i..] and new[j..]
+        let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i][j] = if old[i] == new[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                lines.push(DiffLine::Same(old[i]));
+                i += 1;
+                j += 1;
+            } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                lines.push(DiffLine::Removed(old[i]));
+                i += 1;
+            } else {
+                lines.push(DiffLine::Added(new[j]));
+                j += 1;
+            }
+        }
+    } else {
+        lines.extend(old.iter().map(|l| DiffLine::Removed(l)));
+        lines.extend(new.iter().map(|l| DiffLine::Added(l)));
+    }
+    lines.extend(
+        before[before.len() - suffix..]
+            .iter()
+            .map(|l| DiffLine::Same(l)),
+    );
+    lines
+}
+
+/// Unified diff from `before` to `after`, labelled with `path`
+///
+/// Empty when nothing changed.
+pub fn unified_diff(path: &str, before: &str, after: &str) -> String {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let lines = diff_lines(&old, &new);
+
+    // Changed lines with their context, merged where they touch
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if matches!(line, DiffLine::Same(_)) {
+            continue;
+        }
+        let start = i.saturating_sub(DIFF_CONTEXT);
+        let end = (i + 1 + DIFF_CONTEXT).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let is_old = |l: &&DiffLine| !matches!(l, DiffLine::Added(_));
+    let is_new = |l: &&DiffLine| !matches!(l, DiffLine::Removed(_));
+    let mut out = format!("--- a/{0}\n+++ b/{0}\n", path);
+    for (start, end) in hunks {
+        let (old_start, new_start) = (
+            lines[..start].iter().filter(is_old).count(),
+            lines[..start].iter().filter(is_new).count(),
+        );
+        let (old_len, new_len) = (
+            lines[start..end].iter().filter(is_old).count(),
+            lines[start..end].iter().filter(is_new).count(),
+        );
+        // An empty side is numbered after the line it follows
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + usize::from(old_len > 0),
+            old_len,
+            new_start + usize::from(new_len > 0),
+            new_len
+        ));
+        for line in &lines[start..end] {
+            let (marker, text) = match line {
+                DiffLine::Same(text) => (' ', text),
+                DiffLine::Removed(text) => ('-', text),
+                DiffLine::Added(text) => ('+', text),
+            };
+            out.push(marker);
+            out.push_str(text);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Apply a patch operation: the unified diff first, then any edits
+pub fn apply_patch(
+    original: &str,
+    diff: Option<&str>,
+    edits: &[TextEdit],
+) -> Result<String, PatchError> {
+    if diff.is_none() && edits.is_empty() {
+        return Err(PatchError::Empty);
+    }
+
+    let content = match diff {
+        Some(diff) => apply_unified_diff(original, diff)?,
+        None => original.to_string(),
+    };
+    apply_edits(&content, edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "fn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}\n";
+
+    #[test]
+    fn test_apply_simple_diff() {
+        let diff = "--- a/main.rs\n+++ b/main.rs\n@@ -1,4 +1,4 @@\n fn main() {\n-    let x = 1;\n+    let x = 2;\n     println!(\"{}\", x);\n }\n";
+        let result = apply_unified_diff(ORIGINAL, diff).unwrap();
+        assert_eq!(result, ORIGINAL.replace("x = 1", "x = 2"));
+    }
+
+    #[test]
+    fn test_apply_diff_with_wrong_line_numbers() {
+        let diff =
+            "@@ -40,3 +40,4 @@\n     let x = 1;\n+    let y = 2;\n     println!(\"{}\", x);\n";
+        let result = apply_unified_diff(ORIGINAL, diff).unwrap();
+        assert!(result.contains("let x = 1;\n    let y = 2;\n    println!"));
+    }
+
+    #[test]
+    fn test_apply_diff_with_fuzz() {
+        // First context line does not exist in the file
+        let diff = "@@\n // stale comment\n     let x = 1;\n-    println!(\"{}\", x);\n+    dbg!(x);\n }\n";
+        let result = apply_unified_diff(ORIGINAL, diff).unwrap();
+        assert!(result.contains("dbg!(x);"));
+        assert!(!result.contains("println!"));
+    }
+
+    #[test]
+    fn test_apply_diff_rejects_mismatch() {
+        let diff = "@@ -1,2 +1,2 @@\n-    let z = 9;\n+    let z = 10;\n";
+        assert_eq!(
+            apply_unified_diff(ORIGINAL, diff),
+            Err(PatchError::HunkMismatch { hunk: 1, line: 1 })
+        );
+        assert_eq!(
+            apply_unified_diff(ORIGINAL, "no hunks"),
+            Err(PatchError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_apply_multiple_hunks() {
+        let original = (1..=10)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>();
+        let diff = "@@ -2,1 +2,1 @@\n-line 2\n+line two\n@@ -9,1 +9,2 @@\n line 9\n+line 9.5\n";
+        let result = apply_unified_diff(&original, diff).unwrap();
+        assert!(result.contains("line two\nline 3"));
+        assert!(result.contains("line 9\nline 9.5\nline 10\n"));
+    }
+
+    #[test]
+    fn test_two_file_diff_keeps_hunks_apart() {
+        let diff = "diff --git a/main.rs b/main.rs\n--- a/main.rs\n+++ b/main.rs\n@@ -2,1 +2,1 @@\n-    let x = 1;\n+    let x = 2;\ndiff --git a/lib.rs b/lib.rs\nindex 83db48f..bf269f4 100644\n--- a/lib.rs\n+++ b/lib.rs\n@@ -1 +1,2 @@\n pub mod a;\n+pub mod b;\n";
+        let hunks = parse_hunks(diff).unwrap();
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(
+            hunks[0].lines,
+            vec![
+                HunkLine::Remove("    let x = 1;".to_string()),
+                HunkLine::Add("    let x = 2;".to_string()),
+            ]
+        );
+        assert_eq!(hunks[1].old_lines(), vec!["pub mod a;"]);
+        assert_eq!(hunks[1].new_lines(), vec!["pub mod a;", "pub mod b;"]);
+
+        // Wrong counts still stop at the next file's header
+        let sloppy = diff.replace("@@ -2,1 +2,1 @@", "@@ -2,5 +2,5 @@");
+        assert_eq!(parse_hunks(&sloppy).unwrap()[0].lines, hunks[0].lines);
+    }
+
+    #[test]
+    fn test_unified_diff_round_trips() {
+        let original = (1..=20)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>();
+        let changed = original
+            .replace("line 2\n", "line two\n")
+            .replace("line 15\n", "")
+            .replace("line 20\n", "line 20\nline 21\n");
+
+        let diff = unified_diff("notes.txt", &original, &changed);
+        assert!(diff.starts_with("--- a/notes.txt\n+++ b/notes.txt\n@@ -1,5 +1,5 @@\n"));
+        assert!(diff.contains("-line 2\n+line two\n"));
+        assert_eq!(apply_unified_diff(&original, &diff).unwrap(), changed);
+
+        assert_eq!(unified_diff("notes.txt", &original, &original), "");
+        let created = unified_diff("new.txt", "", "a\nb\n");
+        assert!(created.contains("@@ -0,0 +1,2 @@\n+a\n+b\n"));
+    }
+
+    #[test]
+    fn test_apply_edits() {
+        let edits = vec![TextEdit {
+            find: "let x = 1;".into(),
+            replace: "let x = 42;".into(),
+        }];
+        let result = apply_edits(ORIGINAL, &edits).unwrap();
+        assert!(result.contains("let x = 42;"));
+    }
+
+    #[test]
+    fn test_apply_edits_ignores_indentation() {
+        let edits = vec![TextEdit {
+            find: "let x = 1;\nprintln!(\"{}\", x);".into(),
+            replace: "    let x = 3;".into(),
+        }];
+        let result = apply_edits(ORIGINAL, &edits).unwrap();
+        assert_eq!(result, "fn main() {\n    let x = 3;\n}\n");
+    }
+
+    #[test]
+    fn test_check_patch_reports_every_hunk() {
+        let original = (1..=10)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>();
+        let diff = "@@ -2,1 +2,1 @@\n-line two\n+line 2!\n@@ -5,1 +5,1 @@\n-line 5\n+line five\n@@ -8,1 +8,1 @@\n-line eight\n+line 8!\n";
+        let failures = check_patch(&original, Some(diff), &[]).unwrap_err();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(
+            failures[0].error,
+            PatchError::HunkMismatch { hunk: 1, line: 2 }
+        );
+        assert_eq!(failures[0].expected, "line two");
+        assert_eq!(
+            failures[0].found.as_deref(),
+            Some("line 1\nline 2\nline 3\nline 4")
+        );
+        assert!(matches!(
+            failures[1].error,
+            PatchError::HunkMismatch { hunk: 3, .. }
+        ));
+        let feedback = failures[1].to_string();
+        assert!(feedback.starts_with("hunk 3 does not match file near line 8. It expected:"));
+        assert!(feedback.contains("The file there reads:\n```\nline 6\n"));
+
+        let edits = vec![
+            TextEdit {
+                find: "line 3".into(),
+                replace: "line three".into(),
+            },
+            TextEdit {
+                find: "line 1".into(),
+                replace: "line one".into(),
+            },
+        ];
+        let failures = check_patch(&original, None, &edits).unwrap_err();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].error, PatchError::EditAmbiguous(2, 2));
+        assert!(check_patch(&original, None, &edits[..1])
+            .unwrap()
+            .contains("line three\n"));
+    }
+
+    #[test]
+    fn test_search_replace_blocks() {
+        let text = "Here's the fix:\n<<<<<<< SEARCH\n    let x = 1;\n=======\n    let x = 2;\n    let y = 3;\n>>>>>>> REPLACE\n<<<<<<< SEARCH\nunfinished\n";
+        let edits = parse_search_replace(text);
+        assert_eq!(
+            edits,
+            vec![TextEdit {
+                find: "    let x = 1;".into(),
+                replace: "    let x = 2;\n    let y = 3;".into(),
+            }]
+        );
+
+        let changed = apply_llm_change(ORIGINAL, text).unwrap();
+        assert!(changed.contains("let x = 2;\n    let y = 3;\n    println!"));
+        let diff =
+            "--- a/main.rs\n+++ b/main.rs\n@@ -2,1 +2,1 @@\n-    let x = 1;\n+    let x = 5;\n";
+        assert!(apply_llm_change(ORIGINAL, diff).unwrap().contains("x = 5"));
+        assert_eq!(
+            apply_llm_change(ORIGINAL, "fn main() {}\n").unwrap(),
+            "fn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_edits_errors() {
+        let missing = vec![TextEdit {
+            find: "nope".into(),
+            replace: String::new(),
+        }];
+        assert_eq!(
+            apply_edits(ORIGINAL, &missing),
+            Err(PatchError::EditNotFound(1))
+        );
+
+        let ambiguous = vec![TextEdit {
+            find: "x".into(),
+            replace: "y".into(),
+        }];
+        assert!(matches!(
+            apply_edits(ORIGINAL, &ambiguous),
+            Err(PatchError::EditAmbiguous(1, _))
+        ));
+    }
+}
@@ -0,0 +1,399 @@
+//! Task queue for running independent subtasks side by side
+//!
+//! A request split into subtasks runs each on its own agent. Tasks whose
+//! dependencies are done start right away, up to a concurrency limit; a task
+//! whose dependency failed is skipped. The outcomes are summarized for the
+//! Conductor to pick up from.
+
+use super::types::DeveloperResponse;
+use crate::{AxiomError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::mpsc;
+
+/// One unit of work in a queue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubTask {
+    /// Name other tasks refer to it by
+    pub id: String,
+
+    /// Instructions for the agent
+    pub task: String,
+
+    /// Tasks that must be done first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+impl SubTask {
+    pub fn new(id: impl Into<String>, task: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            task: task.into(),
+            depends_on: Vec::new(),
+        }
+    }
+
+    /// Run after `ids`
+    pub fn after(mut self, ids: &[&str]) -> Self {
+        self.depends_on = ids.iter().map(|id| id.to_string()).collect();
+        self
+    }
+}
+
+/// Where a task is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", content = "reason", rename_all = "snake_case")]
+pub enum TaskState {
+    /// Waiting for its dependencies or a free slot
+    Queued,
+
+    /// An agent is working on it
+    Running,
+
+    /// Finished
+    Done,
+
+    /// The agent failed
+    Failed(String),
+
+    /// Not run because a dependency didn't finish
+    Skipped(String),
+}
+
+/// A task changing state, as reported while the queue runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskProgress {
+    /// The task
+    pub id: String,
+
+    /// Its new state
+    #[serde(flatten)]
+    pub state: TaskState,
+
+    /// Tasks finished so far, out of `total`
+    pub finished: usize,
+
+    /// Tasks in the queue
+    pub total: usize,
+}
+
+/// How a task ended, with the agent's result if it finished
+#[derive(Debug, Clone)]
+pub struct TaskOutcome<T> {
+    pub id: String,
+    pub state: TaskState,
+    pub output: Option<T>,
+}
+
+/// Subtasks and the order their dependencies allow
+#[derive(Debug, Clone)]
+pub struct TaskQueue {
+    tasks: Vec<SubTask>,
+
+    /// Indices of each task's dependencies
+    deps: Vec<Vec<usize>>,
+}
+
+impl TaskQueue {
+    /// Check the tasks' IDs and dependencies
+    ///
+    /// Fails on a duplicate ID, a dependency on an unknown task, or a cycle.
+    pub fn new(tasks: Vec<SubTask>) -> Result<Self> {
+        let mut index = HashMap::new();
+        for (i, task) in tasks.iter().enumerate() {
+            if index.insert(task.id.as_str(), i).is_some() {
+                return Err(AxiomError::config(format!(
+                    "Duplicate task ID: {}",
+                    task.id
+                )));
+            }
+        }
+        let deps = tasks
+            .iter()
+            .map(|task| {
+                task.depends_on
+                    .iter()
+                    .map(|dep| {
+                        index.get(dep.as_str()).copied().ok_or_else(|| {
+                            AxiomError::config(format!(
+                                "Task {} depends on unknown task {}",
+                                task.id, dep
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<usize>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let queue = Self { tasks, deps };
+        if let Some(task) = queue.find_cycle() {
+            return Err(AxiomError::config(format!(
+                "Task {} depends on itself through its dependencies",
+                queue.tasks[task].id
+            )));
+        }
+        Ok(queue)
+    }
+
+    pub fn tasks(&self) -> &[SubTask] {
+        &self.tasks
+    }
+
+    /// A task on a dependency cycle, if there is one
+    fn find_cycle(&self) -> Option<usize> {
+        // Peel off tasks with no unfinished dependencies; what's left is cyclic
+        let mut done = vec![false; self.tasks.len()];
+        loop {
+            let ready: Vec<usize> = (0..self.tasks.len())
+                .filter(|&i| !done[i] && self.deps[i].iter().all(|&d| done[d]))
+                .collect();
+            if ready.is_empty() {
+                return done.iter().position(|&d| !d);
+            }
+            ready.into_iter().for_each(|i| done[i] = true);
+        }
+    }
+
+    /// Run every task with `work`, at most `max_parallel` at a time
+    ///
+    /// `work` gets the task and the outputs of its dependencies, and is
+    /// called from worker threads. `on_progress` hears of each state change
+    /// on the calling thread. Outcomes are in the order the tasks were given.
+    pub fn run<T, F>(
+        &self,
+        max_parallel: usize,
+        work: F,
+        mut on_progress: impl FnMut(TaskProgress),
+    ) -> Vec<TaskOutcome<T>>
+    where
+        T: Clone + Send,
+        F: Fn(&SubTask, &[(&SubTask, T)]) -> std::result::Result<T, String> + Sync,
+    {
+        let total = self.tasks.len();
+        let mut states = vec![TaskState::Queued; total];
+        let mut outputs: Vec<Option<T>> = vec![None; total];
+        let mut finished = 0;
+        let mut running = 0;
+        let work = &work;
+
+        let mut report = |i: usize, state: &TaskState, finished: usize| {
+            on_progress(TaskProgress {
+                id: self.tasks[i].id.clone(),
+                state: state.clone(),
+                finished,
+                total,
+            });
+        };
+        for i in 0..total {
+            report(i, &TaskState::Queued, 0);
+        }
+
+        std::thread::scope(|scope| {
+            let (done_tx, done_rx) = mpsc::channel();
+            while finished < total {
+                // Skip whatever can no longer run, then start what can;
+                // a skip can lead to more, so go until nothing changes
+                let mut changed = true;
+                while changed {
+                    changed = false;
+                    for i in 0..total {
+                        if states[i] != TaskState::Queued {
+                            continue;
+                        }
+                        let failed = self.deps[i].iter().find(|&&d| {
+                            matches!(states[d], TaskState::Failed(_) | TaskState::Skipped(_))
+                        });
+                        if let Some(&dep) = failed {
+                            states[i] =
+                                TaskState::Skipped(format!("{} didn't finish", self.tasks[dep].id));
+                            finished += 1;
+                            report(i, &states[i], finished);
+                            changed = true;
+                            continue;
+                        }
+                        let ready = self.deps[i].iter().all(|&d| states[d] == TaskState::Done);
+                        if !ready || running >= max_parallel.max(1) {
+                            continue;
+                        }
+                        let inputs: Vec<(&SubTask, T)> = self.deps[i]
+                            .iter()
+                            .filter_map(|&d| Some((&self.tasks[d], outputs[d].clone()?)))
+                            .collect();
+                        states[i] = TaskState::Running;
+                        running += 1;
+                        report(i, &states[i], finished);
+                        let task = &self.tasks[i];
+                        let done_tx = done_tx.clone();
+                        scope.spawn(move || {
+                            let _ = done_tx.send((i, work(task, &inputs)));
+                        });
+                    }
+                }
+                if running == 0 {
+                    // Everything left was skipped
+                    break;
+                }
+
+                let Ok((i, result)) = done_rx.recv() else {
+                    break;
+                };
+                running -= 1;
+                finished += 1;
+                states[i] = match result {
+                    Ok(output) => {
+                        outputs[i] = Some(output);
+                        TaskState::Done
+                    }
+                    Err(error) => TaskState::Failed(error),
+                };
+                report(i, &states[i], finished);
+            }
+        });
+
+        self.tasks
+            .iter()
+            .zip(states)
+            .zip(outputs)
+            .map(|((task, state), output)| TaskOutcome {
+                id: task.id.clone(),
+                state,
+                output,
+            })
+            .collect()
+    }
+}
+
+/// The developer's instructions for `task`, with what the tasks it builds
+/// on did
+pub fn task_prompt(task: &SubTask, inputs: &[(&SubTask, DeveloperResponse)]) -> String {
+    let mut prompt = task.task.clone();
+    if !inputs.is_empty() {
+        prompt.push_str("\n\nOther developers have already finished these parts:");
+        for (dep, response) in inputs {
+            prompt.push_str(&format!(
+                "\n- {} ({}): {}",
+                dep.id, dep.task, response.message
+            ));
+        }
+    }
+    prompt
+}
+
+/// What the developers did, for the Conductor to carry on from
+pub fn summarize_outcomes(outcomes: &[TaskOutcome<DeveloperResponse>]) -> String {
+    let done = outcomes
+        .iter()
+        .filter(|o| o.state == TaskState::Done)
+        .count();
+    let mut summary = format!("{} of {} tasks done.", done, outcomes.len());
+    for outcome in outcomes {
+        let line = match (&outcome.state, &outcome.output) {
+            (TaskState::Done, Some(response)) => format!(
+                "{}: done, {} change(s). {}",
+                outcome.id,
+                response.operations.len(),
+                response.message
+            ),
+            (TaskState::Failed(error), _) => format!("{}: failed: {}", outcome.id, error),
+            (TaskState::Skipped(reason), _) => format!("{}: skipped, {}", outcome.id, reason),
+            (state, _) => format!("{}: {:?}", outcome.id, state),
+        };
+        summary.push_str("\n- ");
+        summary.push_str(line.trim_end());
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_invalid_queues() {
+        let dup = TaskQueue::new(vec![SubTask::new("a", "x"), SubTask::new("a", "y")]);
+        assert!(dup
+            .unwrap_err()
+            .to_string()
+            .contains("Duplicate task ID: a"));
+
+        let unknown = TaskQueue::new(vec![SubTask::new("a", "x").after(&["b"])]);
+        assert!(unknown.unwrap_err().to_string().contains("unknown task b"));
+
+        let cycle = TaskQueue::new(vec![
+            SubTask::new("a", "x").after(&["c"]),
+            SubTask::new("b", "y").after(&["a"]),
+            SubTask::new("c", "z").after(&["b"]),
+            SubTask::new("d", "w"),
+        ]);
+        assert!(cycle.unwrap_err().to_string().contains("depends on itself"));
+    }
+
+    #[test]
+    fn test_runs_in_parallel_within_limit() {
+        let queue = TaskQueue::new(
+            (0..6)
+                .map(|i| SubTask::new(format!("t{}", i), "work"))
+                .collect(),
+        )
+        .unwrap();
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let outcomes = queue.run(
+            3,
+            |task, _| {
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(30));
+                active.fetch_sub(1, Ordering::SeqCst);
+                Ok(task.id.clone())
+            },
+            |_| {},
+        );
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert!(outcomes.iter().all(|o| o.state == TaskState::Done));
+        assert_eq!(outcomes[4].output.as_deref(), Some("t4"));
+    }
+
+    #[test]
+    fn test_dependencies_pass_outputs_and_failures_skip() {
+        let queue = TaskQueue::new(vec![
+            SubTask::new("schema", "add table"),
+            SubTask::new("api", "add endpoint").after(&["schema"]),
+            SubTask::new("broken", "fail"),
+            SubTask::new("docs", "document").after(&["api", "broken"]),
+        ])
+        .unwrap();
+        let mut progress = Vec::new();
+        let outcomes = queue.run(
+            4,
+            |task, inputs: &[(&SubTask, String)]| match task.id.as_str() {
+                "broken" => Err("no provider".to_string()),
+                _ => {
+                    let from: Vec<String> = inputs.iter().map(|(_, out)| out.clone()).collect();
+                    Ok(format!("{}<-[{}]", task.id, from.join(",")))
+                }
+            },
+            |p| progress.push(p),
+        );
+
+        assert_eq!(outcomes[1].output.as_deref(), Some("api<-[schema<-[]]"));
+        assert_eq!(outcomes[2].state, TaskState::Failed("no provider".into()));
+        assert!(matches!(&outcomes[3].state, TaskState::Skipped(r) if r.contains("broken")));
+
+        // Every task is queued, and the last report counts all of them
+        assert_eq!(
+            progress
+                .iter()
+                .filter(|p| p.state == TaskState::Queued)
+                .count(),
+            4
+        );
+        let last = progress.last().unwrap();
+        assert_eq!((last.finished, last.total), (4, 4));
+        let json = serde_json::to_value(last).unwrap();
+        assert!(json["state"].is_string());
+    }
+}
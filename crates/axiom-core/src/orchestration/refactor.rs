@@ -0,0 +1,625 @@
+//! Symbol rename across files
+//!
+//! [`plan_rename`] finds every reference to a symbol by scanning the
+//! workspace's source files for whole identifiers (outside string literals;
+//! comments are included so doc references follow the rename) and turns them
+//! into a [`RenameChangeset`]: one [`FileChange`] per file, each of which can
+//! be left out before applying. Applying is all-or-nothing and returns a
+//! [`RefactorUndo`] that puts every file back.
+//!
+//! [`RefactorSession`] holds a workspace's preview and undo history for the
+//! `/refactor` command. The Developer agent gets the same rename through
+//! [`AgentOperation::RenameSymbol`](super::AgentOperation::RenameSymbol).
+
+use crate::error::{AxiomError, Result};
+use crate::watcher::IgnoreRules;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Extensions of the files searched for references
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "jsx", "mjs", "ts", "tsx", "go", "java", "kt", "c", "h", "cc", "cpp", "hpp",
+    "cs", "rb", "swift", "php", "scala",
+];
+
+/// Larger files are skipped (generated code, bundles)
+const MAX_FILE_BYTES: u64 = 1_000_000;
+
+/// Unchanged lines shown around each change in a preview
+const CONTEXT_LINES: usize = 2;
+
+/// Applied renames a session can undo
+const MAX_UNDO: usize = 20;
+
+/// Whether `name` is a plain identifier (letters, digits, `_`; no leading digit)
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// String delimiters for a file; Rust's `'` starts lifetimes and chars
+fn string_quotes(path: &Path) -> &'static [char] {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("rs") => &['"'],
+        _ => &['"', '\'', '`'],
+    }
+}
+
+/// Byte offsets of `symbol` as a whole identifier in `line`, outside strings
+fn identifier_matches(line: &str, symbol: &str, quotes: &[char]) -> Vec<usize> {
+    let mut matches = Vec::new();
+    let mut in_string: Option<char> = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if let Some(quote) = in_string {
+            if c == '\\' {
+                chars.next();
+            } else if c == quote {
+                in_string = None;
+            }
+            continue;
+        }
+        if quotes.contains(&c) {
+            in_string = Some(c);
+        } else if c.is_alphanumeric() || c == '_' {
+            // Consume the whole token so `foo` never matches inside `foo_bar`
+            let mut end = i + c.len_utf8();
+            while let Some(&(j, next)) = chars.peek() {
+                if !(next.is_alphanumeric() || next == '_') {
+                    break;
+                }
+                end = j + next.len_utf8();
+                chars.next();
+            }
+            if &line[i..end] == symbol && !c.is_ascii_digit() {
+                matches.push(i);
+            }
+        }
+    }
+    matches
+}
+
+/// Changes to one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// Relative to the workspace root
+    pub path: PathBuf,
+    /// Lines (1-based) with at least one reference
+    pub lines: Vec<usize>,
+    /// References renamed in the file
+    pub occurrences: usize,
+    /// Whether the file is part of the apply
+    pub included: bool,
+    original: String,
+    updated: String,
+}
+
+impl FileChange {
+    /// Unified diff of the change, with a few lines of context
+    pub fn diff(&self) -> String {
+        let before: Vec<&str> = self.original.lines().collect();
+        let after: Vec<&str> = self.updated.lines().collect();
+        let mut out = format!(
+            "--- a/{0}\n+++ b/{0}\n",
+            self.path.to_string_lossy().replace('\\', "/")
+        );
+
+        // Renames never add or remove lines, so hunks line up one to one
+        let mut hunks: Vec<(usize, usize)> = Vec::new();
+        for &line in &self.lines {
+            let start = (line - 1).saturating_sub(CONTEXT_LINES);
+            let end = (line + CONTEXT_LINES).min(before.len());
+            match hunks.last_mut() {
+                Some(last) if start <= last.1 => last.1 = end,
+                _ => hunks.push((start, end)),
+            }
+        }
+        for (start, end) in hunks {
+            out.push_str(&format!(
+                "@@ -{0},{1} +{0},{1} @@\n",
+                start + 1,
+                end - start
+            ));
+            for i in start..end {
+                if before[i] == after[i] {
+                    out.push_str(&format!(" {}\n", before[i]));
+                } else {
+                    out.push_str(&format!("-{}\n+{}\n", before[i], after[i]));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// A planned rename: every file that references the symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameChangeset {
+    pub symbol: String,
+    pub new_name: String,
+    pub files: Vec<FileChange>,
+    /// Files that already use the new name
+    pub conflicts: Vec<PathBuf>,
+}
+
+impl RenameChangeset {
+    /// References renamed in the included files
+    pub fn occurrences(&self) -> usize {
+        self.included().map(|f| f.occurrences).sum()
+    }
+
+    pub fn included(&self) -> impl Iterator<Item = &FileChange> {
+        self.files.iter().filter(|f| f.included)
+    }
+
+    /// Include or leave out the file at `path` (or the only one ending in it)
+    ///
+    /// Returns whether the file is now included.
+    pub fn toggle(&mut self, path: &str) -> Result<bool> {
+        let wanted = path.trim_start_matches("./");
+        let matching: Vec<usize> = self
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| {
+                let file = f.path.to_string_lossy().replace('\\', "/");
+                file == wanted || file.ends_with(&format!("/{}", wanted))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        match matching[..] {
+            [i] => {
+                let file = &mut self.files[i];
+                file.included = !file.included;
+                Ok(file.included)
+            }
+            [] => Err(AxiomError::not_found(format!(
+                "{} isn't part of the rename",
+                path
+            ))),
+            _ => Err(AxiomError::config(format!(
+                "{} matches {} files; give more of the path",
+                path,
+                matching.len()
+            ))),
+        }
+    }
+
+    /// File list with include marks, conflicts and the diff of each included file
+    pub fn preview(&self) -> String {
+        let mut out = format!(
+            "Rename `{}` → `{}`: {} reference(s) in {} of {} file(s)\n",
+            self.symbol,
+            self.new_name,
+            self.occurrences(),
+            self.included().count(),
+            self.files.len()
+        );
+        for file in &self.files {
+            out.push_str(&format!(
+                "  [{}] {} ({})\n",
+                if file.included { "x" } else { " " },
+                file.path.display(),
+                file.occurrences
+            ));
+        }
+        if !self.conflicts.is_empty() {
+            let files: Vec<String> = self
+                .conflicts
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect();
+            out.push_str(&format!(
+                "Warning: `{}` is already used in {}\n",
+                self.new_name,
+                files.join(", ")
+            ));
+        }
+        for file in self.included() {
+            out.push('\n');
+            out.push_str(&file.diff());
+        }
+        out
+    }
+
+    /// Write every included file, or none of them
+    ///
+    /// Fails without writing anything if an included file changed since the
+    /// rename was planned.
+    pub fn apply(&self, root: &Path) -> Result<RefactorUndo> {
+        let files: Vec<FileSnapshot> = self
+            .included()
+            .map(|f| FileSnapshot {
+                path: f.path.clone(),
+                before: f.original.clone(),
+                after: f.updated.clone(),
+            })
+            .collect();
+        if files.is_empty() {
+            return Err(AxiomError::invalid_operation(
+                "Every file is left out of the rename",
+            ));
+        }
+        let writes: Vec<(&Path, &str, &str)> = files
+            .iter()
+            .map(|f| (f.path.as_path(), f.before.as_str(), f.after.as_str()))
+            .collect();
+        write_all(root, &writes)?;
+        Ok(RefactorUndo {
+            description: format!("rename `{}` → `{}`", self.symbol, self.new_name),
+            files,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileSnapshot {
+    path: PathBuf,
+    before: String,
+    after: String,
+}
+
+/// What an applied refactor changed, to put it back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefactorUndo {
+    pub description: String,
+    files: Vec<FileSnapshot>,
+}
+
+impl RefactorUndo {
+    /// Files the refactor wrote
+    pub fn paths(&self) -> Vec<&Path> {
+        self.files.iter().map(|f| f.path.as_path()).collect()
+    }
+
+    /// Restore every file, or none if one was edited since the refactor
+    pub fn revert(&self, root: &Path) -> Result<()> {
+        let writes: Vec<(&Path, &str, &str)> = self
+            .files
+            .iter()
+            .map(|f| (f.path.as_path(), f.after.as_str(), f.before.as_str()))
+            .collect();
+        write_all(root, &writes)
+    }
+}
+
+/// Replace each file's `expected` content with `content`, all or nothing
+///
+/// New contents go to temporary files next to their targets first and are
+/// then renamed into place; if a rename fails the files already replaced
+/// get their old content back.
+fn write_all(root: &Path, writes: &[(&Path, &str, &str)]) -> Result<()> {
+    for (path, expected, _) in writes {
+        let current = fs::read_to_string(root.join(path))?;
+        if current != *expected {
+            return Err(AxiomError::invalid_operation(format!(
+                "{} changed since the refactor was planned",
+                path.display()
+            )));
+        }
+    }
+
+    let temp_path = |path: &Path| {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        root.join(path)
+            .with_file_name(format!(".{}.axiom-refactor", name))
+    };
+    let mut temps = Vec::new();
+    for (path, _, content) in writes {
+        let temp = temp_path(path);
+        if let Err(e) = fs::write(&temp, content) {
+            let _ = fs::remove_file(&temp);
+            for temp in temps {
+                let _ = fs::remove_file(temp);
+            }
+            return Err(e.into());
+        }
+        temps.push(temp);
+    }
+
+    for (i, temp) in temps.iter().enumerate() {
+        if let Err(e) = fs::rename(temp, root.join(writes[i].0)) {
+            for (path, expected, _) in &writes[..i] {
+                let _ = fs::write(root.join(path), expected);
+            }
+            for temp in &temps[i..] {
+                let _ = fs::remove_file(temp);
+            }
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+/// Source files under `root`, relative, skipping ignored paths
+fn source_files(root: &Path) -> Vec<PathBuf> {
+    fn walk(root: &Path, dir: &Path, rules: &IgnoreRules, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(relative) = path.strip_prefix(root) else {
+                continue;
+            };
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+            if rules.is_ignored(&relative.to_string_lossy().replace('\\', "/"), is_dir) {
+                continue;
+            }
+            if is_dir {
+                walk(root, &path, rules, files);
+            } else if path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| SOURCE_EXTENSIONS.contains(&e))
+                && entry.metadata().is_ok_and(|m| m.len() <= MAX_FILE_BYTES)
+            {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    walk(root, root, &IgnoreRules::load(root, &[]), &mut files);
+    files.sort();
+    files
+}
+
+/// Plan renaming `symbol` to `new_name` in every source file under `root`
+pub fn plan_rename(root: &Path, symbol: &str, new_name: &str) -> Result<RenameChangeset> {
+    for name in [symbol, new_name] {
+        if !is_identifier(name) {
+            return Err(AxiomError::config(format!(
+                "`{}` isn't an identifier",
+                name
+            )));
+        }
+    }
+    if symbol == new_name {
+        return Err(AxiomError::config(
+            "The new name is the same as the old one",
+        ));
+    }
+
+    let mut files = Vec::new();
+    let mut conflicts = Vec::new();
+    for path in source_files(root) {
+        // Binary or non-UTF-8 files can't hold references we could rewrite
+        let Ok(original) = fs::read_to_string(root.join(&path)) else {
+            continue;
+        };
+        let quotes = string_quotes(&path);
+        let mut updated = String::with_capacity(original.len());
+        let mut lines = Vec::new();
+        let mut occurrences = 0;
+        let mut uses_new_name = false;
+        for (n, line) in original.split_inclusive('\n').enumerate() {
+            uses_new_name |= !identifier_matches(line, new_name, quotes).is_empty();
+            let matches = identifier_matches(line, symbol, quotes);
+            if matches.is_empty() {
+                updated.push_str(line);
+                continue;
+            }
+            let mut last = 0;
+            for start in &matches {
+                updated.push_str(&line[last..*start]);
+                updated.push_str(new_name);
+                last = start + symbol.len();
+            }
+            updated.push_str(&line[last..]);
+            lines.push(n + 1);
+            occurrences += matches.len();
+        }
+        if uses_new_name {
+            conflicts.push(path.clone());
+        }
+        if occurrences > 0 {
+            files.push(FileChange {
+                path,
+                lines,
+                occurrences,
+                included: true,
+                original,
+                updated,
+            });
+        }
+    }
+
+    if files.is_empty() {
+        return Err(AxiomError::not_found(format!(
+            "No references to `{}` found",
+            symbol
+        )));
+    }
+    Ok(RenameChangeset {
+        symbol: symbol.to_string(),
+        new_name: new_name.to_string(),
+        files,
+        conflicts,
+    })
+}
+
+/// A workspace's rename preview and undo history
+#[derive(Debug, Default)]
+pub struct RefactorSession {
+    preview: Option<RenameChangeset>,
+    history: Vec<RefactorUndo>,
+}
+
+impl RefactorSession {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The rename waiting to be applied
+    pub fn preview(&self) -> Option<&RenameChangeset> {
+        self.preview.as_ref()
+    }
+
+    /// The most recent refactor that can still be undone
+    pub fn last_applied(&self) -> Option<&RefactorUndo> {
+        self.history.last()
+    }
+
+    /// Plan a rename, replacing any previous preview
+    pub fn plan(&mut self, root: &Path, symbol: &str, new_name: &str) -> Result<&RenameChangeset> {
+        let changeset = plan_rename(root, symbol, new_name)?;
+        Ok(self.preview.insert(changeset))
+    }
+
+    /// Include or leave out a file of the preview
+    pub fn toggle(&mut self, path: &str) -> Result<&RenameChangeset> {
+        let changeset = self.preview_mut()?;
+        changeset.toggle(path)?;
+        Ok(changeset)
+    }
+
+    /// Apply the preview; returns a summary
+    ///
+    /// A preview that fails to apply is kept, so it can be planned again or
+    /// discarded.
+    pub fn apply(&mut self, root: &Path) -> Result<String> {
+        let changeset = self.preview_mut()?;
+        let undo = changeset.apply(root)?;
+        let summary = format!(
+            "Applied {}: {} reference(s) in {} file(s). /refactor undo reverts it.",
+            undo.description,
+            changeset.occurrences(),
+            undo.files.len()
+        );
+        self.preview = None;
+        self.history.push(undo);
+        if self.history.len() > MAX_UNDO {
+            self.history.remove(0);
+        }
+        Ok(summary)
+    }
+
+    /// Revert the most recent applied refactor; returns a summary
+    pub fn undo(&mut self, root: &Path) -> Result<String> {
+        let undo = self
+            .history
+            .last()
+            .ok_or_else(|| AxiomError::not_found("Nothing to undo"))?;
+        undo.revert(root)?;
+        let summary = format!(
+            "Reverted {} in {} file(s)",
+            undo.description,
+            undo.files.len()
+        );
+        self.history.pop();
+        Ok(summary)
+    }
+
+    /// Drop the preview; false if there wasn't one
+    pub fn discard(&mut self) -> bool {
+        self.preview.take().is_some()
+    }
+
+    fn preview_mut(&mut self) -> Result<&mut RenameChangeset> {
+        self.preview.as_mut().ok_or_else(|| {
+            AxiomError::not_found("No rename to apply (start one with /refactor rename)")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("target")).unwrap();
+        fs::write(
+            dir.path().join("src/lib.rs"),
+            "/// Uses parse_config\npub fn parse_config() {}\nfn parse_config_file() {}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("src/main.rs"),
+            "fn main() {\n    parse_config();\n    println!(\"parse_config\");\n}\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("target/gen.rs"), "parse_config();\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_identifier_matches() {
+        let rs = string_quotes(Path::new("a.rs"));
+        assert_eq!(identifier_matches("foo(foo_bar, foo)", "foo", rs), [0, 13]);
+        assert_eq!(
+            identifier_matches("x = \"foo \\\" foo\"; foo", "foo", rs),
+            [18]
+        );
+        assert_eq!(
+            identifier_matches("fn f<'foo>(x: &'foo str)", "foo", rs),
+            [6, 16]
+        );
+        let py = string_quotes(Path::new("a.py"));
+        assert!(identifier_matches("print('foo')", "foo", py).is_empty());
+        assert!(is_identifier("_private2"));
+        assert!(!is_identifier("2fast"));
+        assert!(!is_identifier("a-b"));
+    }
+
+    #[test]
+    fn test_plan_rename() {
+        let dir = workspace();
+        let mut changeset = plan_rename(dir.path(), "parse_config", "load_config").unwrap();
+        let paths: Vec<&Path> = changeset.files.iter().map(|f| f.path.as_path()).collect();
+        assert_eq!(paths, [Path::new("src/lib.rs"), Path::new("src/main.rs")]);
+        assert_eq!(changeset.files[0].lines, [1, 2]);
+        assert_eq!(changeset.occurrences(), 3);
+        assert!(changeset.conflicts.is_empty());
+
+        let diff = changeset.files[1].diff();
+        assert!(diff.contains(
+            "@@ -1,4 +1,4 @@\n fn main() {\n-    parse_config();\n+    load_config();\n"
+        ));
+        assert!(diff.contains(" println!(\"parse_config\");"));
+
+        assert!(!changeset.toggle("main.rs").unwrap());
+        assert_eq!(changeset.occurrences(), 2);
+        assert!(changeset.preview().contains("[ ] src/main.rs (1)"));
+        assert!(changeset.toggle("nope.rs").is_err());
+
+        assert!(plan_rename(dir.path(), "missing", "other").is_err());
+        assert!(plan_rename(dir.path(), "parse_config", "load-config").is_err());
+        let conflicting = plan_rename(dir.path(), "parse_config", "main").unwrap();
+        assert_eq!(conflicting.conflicts, [PathBuf::from("src/main.rs")]);
+    }
+
+    #[test]
+    fn test_session_apply_and_undo() {
+        let dir = workspace();
+        let root = dir.path();
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        let mut session = RefactorSession::new();
+        assert!(session.apply(root).is_err());
+
+        session.plan(root, "parse_config", "load_config").unwrap();
+        session.toggle("src/main.rs").unwrap();
+        let summary = session.apply(root).unwrap();
+        assert!(summary.contains("2 reference(s) in 1 file(s)"));
+        assert!(session.preview().is_none());
+        let renamed = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(renamed.contains("pub fn load_config() {}\nfn parse_config_file()"));
+        assert!(fs::read_to_string(root.join("src/main.rs"))
+            .unwrap()
+            .contains("    parse_config();"));
+
+        session.undo(root).unwrap();
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), lib);
+        assert!(session.undo(root).is_err());
+
+        // A file edited after planning stops the whole apply
+        session.plan(root, "parse_config", "load_config").unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() { parse_config(); }\n").unwrap();
+        assert!(session.apply(root).is_err());
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), lib);
+        assert!(session.preview().is_some());
+    }
+}
@@ -0,0 +1,86 @@
+//! Reviewer Agent
+//!
+//! With `[orchestration] review = true`, the Reviewer reads the Developer's
+//! operations before they are applied and either approves them or sends
+//! back the problems it found, for one revision.
+
+use super::context_builder::with_context;
+use super::types::{AgentRole, ChatMessage, DeveloperResponse};
+use crate::prompts::PromptLibrary;
+
+const REVIEWER_SYSTEM_PROMPT: &str = r#"
+You are the Reviewer Agent. You check a developer's proposed changes before they are applied.
+
+**Output Format:**
+- If the changes do what the task asks without introducing bugs, reply with a single line:
+  APPROVE
+- Otherwise reply with REVISE on the first line, followed by each problem that must be fixed,
+  one per line.
+
+Only object to real problems: bugs, parts of the task left undone, or changes the task
+didn't ask for. Don't object to style.
+"#;
+
+/// Messages asking the Reviewer about the operations in `response`
+///
+/// The system prompt is the workspace's reviewer template from `prompts`,
+/// else the built-in one, with `context` added to it.
+pub fn build_reviewer_messages(
+    prompts: &PromptLibrary,
+    task: &str,
+    response: &DeveloperResponse,
+    context: &str,
+) -> Vec<ChatMessage> {
+    let prompt = prompts.render(AgentRole::Reviewer.as_str(), REVIEWER_SYSTEM_PROMPT, task);
+    let operations = serde_json::to_string_pretty(&response.operations).unwrap_or_default();
+    vec![
+        ChatMessage::system(with_context(&prompt, context)),
+        ChatMessage::user(format!(
+            "TASK: {}\n\nDEVELOPER'S REASONING: {}\n\nPROPOSED OPERATIONS:\n{}",
+            task, response.reasoning, operations
+        )),
+    ]
+}
+
+/// The Reviewer's objections, or `None` if it approved
+///
+/// A reply that neither approves nor says what to change counts as
+/// approval, so a confused reviewer doesn't hold the work up.
+pub fn parse_review(reply: &str) -> Option<String> {
+    let reply = reply.trim();
+    let (verdict, rest) = reply.split_once('\n').unwrap_or((reply, ""));
+    let verdict = verdict.trim().trim_matches('*').to_uppercase();
+    let objections = rest.trim();
+    (verdict.starts_with("REVISE") && !objections.is_empty()).then(|| objections.to_string())
+}
+
+/// The task again, with the Reviewer's objections to the previous attempt
+pub fn revision_task(task: &str, objections: &str) -> String {
+    format!(
+        "{}\n\nA reviewer rejected your previous attempt, which was not applied. \
+         Send the complete changes again, fixing these problems:\n{}",
+        task, objections
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_review() {
+        assert_eq!(parse_review("APPROVE"), None);
+        assert_eq!(parse_review("**Approve**\nLooks good."), None);
+        assert_eq!(
+            parse_review("REVISE\n- `add` subtracts\n- no test\n"),
+            Some("- `add` subtracts\n- no test".to_string())
+        );
+        // Nothing to act on
+        assert_eq!(parse_review("REVISE"), None);
+        assert_eq!(parse_review("I'm not sure."), None);
+
+        let task = revision_task("Add `add`", "- `add` subtracts");
+        assert!(task.starts_with("Add `add`\n\n"));
+        assert!(task.ends_with("- `add` subtracts"));
+    }
+}
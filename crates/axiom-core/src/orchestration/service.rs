@@ -3,17 +3,38 @@
 //! Main service for coordinating agents and LLM interactions.
 
 use super::{
-    developer::{build_developer_messages, get_file_tree, parse_developer_response},
-    orchestrator::{build_orchestrator_messages, parse_orchestrator_response},
+    context_builder::ContextBuilder,
+    developer::{build_developer_messages, parse_developer_response, retry_task},
+    docgen::{
+        build_docs_messages, doc_targets, docs_changeset, parse_docs_response, undocumented_items,
+        verify_docs, DocChangeset, DocGenReport, DocLanguage,
+    },
+    fixer::{build_command, fix_task, is_fixed, problems_list, run_build, FixReport},
+    orchestrator::{build_orchestrator_messages, decision_from_reply, route_tool, Delegate},
+    patch::{check_patch, failure_report, TextEdit},
+    queue::{task_prompt, SubTask, TaskOutcome, TaskProgress, TaskQueue},
+    refactor::plan_rename,
+    reviewer::{build_reviewer_messages, parse_review, revision_task},
+    summarizer::{build_summarizer_messages, SUMMARIZE_AFTER_CHARS},
+    testgen::{gen_tests_task, resolve_target, run_tests, test_command, TestGenReport},
     types::{
         AgentMapping, AgentOperation, AgentRole, ChatMessage, DeveloperResponse, LlmSettings,
         OrchestratorDecision, ProviderConfig,
     },
 };
-use crate::Result;
+use crate::events::Event;
+use crate::files::write_atomic;
+use crate::llm::{
+    send_with_quota, ClaudeProvider, GeminiProvider, LlmProvider, OllamaProvider,
+    OpenAICompatibleProvider, OpenAIProvider, SharedProvider, ToolReply, ToolSpec,
+};
+use crate::prompts::PromptLibrary;
+use crate::usage::{estimate_tokens, UsageRecord};
+use crate::{AxiomError, Result};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
 
 /// Orchestration service for managing agent interactions
 pub struct OrchestrationService {
@@ -23,6 +44,18 @@ pub struct OrchestrationService {
     workspace_path: PathBuf,
     /// HTTP client for API calls
     client: ureq::Agent,
+    /// Usage of LLM calls not yet collected with `take_usage`
+    usage: parking_lot::Mutex<Vec<UsageRecord>>,
+    /// Repository context added to agent system prompts
+    context: ContextBuilder,
+    /// CLI agents the orchestrator may hand tasks to
+    delegates: Vec<Delegate>,
+    /// Provider answering for every agent in place of the configured ones
+    provider: Option<SharedProvider>,
+    /// Workspace templates replacing the built-in system prompts
+    prompts: PromptLibrary,
+    /// Have the Reviewer check the Developer's operations
+    review: bool,
 }
 
 impl OrchestrationService {
@@ -30,8 +63,14 @@ impl OrchestrationService {
     pub fn new(workspace_path: PathBuf) -> Self {
         Self {
             settings: RwLock::new(LlmSettings::default()),
+            context: ContextBuilder::new(workspace_path.clone(), Default::default()),
+            delegates: Vec::new(),
+            provider: None,
+            prompts: PromptLibrary::new(&workspace_path, &Default::default()),
+            review: false,
             workspace_path,
             client: ureq::Agent::new(),
+            usage: parking_lot::Mutex::new(Vec::new()),
         }
     }
 
@@ -39,11 +78,54 @@ impl OrchestrationService {
     pub fn with_settings(workspace_path: PathBuf, settings: LlmSettings) -> Self {
         Self {
             settings: RwLock::new(settings),
+            context: ContextBuilder::new(workspace_path.clone(), Default::default()),
+            delegates: Vec::new(),
+            provider: None,
+            prompts: PromptLibrary::new(&workspace_path, &Default::default()),
+            review: false,
             workspace_path,
             client: ureq::Agent::new(),
+            usage: parking_lot::Mutex::new(Vec::new()),
         }
     }
 
+    /// Use `context` for the repository context in agent prompts, e.g. to
+    /// apply `[orchestration.context]` or pass the open editor tabs
+    pub fn with_context(mut self, context: ContextBuilder) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Let the orchestrator hand tasks to `delegates`, routing to
+    /// [`NextAgent::CliDelegate`](super::NextAgent::CliDelegate)
+    pub fn with_delegates(mut self, delegates: Vec<Delegate>) -> Self {
+        self.delegates = delegates;
+        self
+    }
+
+    /// Take system prompts from `prompts`, e.g. to apply `[prompts]`; by
+    /// default each role's `.axiom/prompts/<role>.md` replaces its built-in
+    /// prompt
+    pub fn with_prompts(mut self, prompts: PromptLibrary) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Have the Reviewer check each Developer answer before it's returned,
+    /// e.g. to apply `[orchestration] review`
+    pub fn with_review(mut self, review: bool) -> Self {
+        self.review = review;
+        self
+    }
+
+    /// Send every agent's calls to `provider` instead of the providers in
+    /// the settings, e.g. a [`FakeProvider`](crate::testing::FakeProvider)
+    /// in tests
+    pub fn with_provider(mut self, provider: SharedProvider) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
     /// Get current LLM settings
     pub fn settings(&self) -> LlmSettings {
         self.settings.read().clone()
@@ -84,20 +166,383 @@ impl OrchestrationService {
     }
 
     /// Run the orchestrator to decide next action
+    ///
+    /// The model hands over by calling the `route` tool.
     pub fn orchestrate(&self, messages: &[ChatMessage]) -> Result<OrchestratorDecision> {
-        let llm_messages = build_orchestrator_messages(messages);
-        let response = self.call_llm(AgentRole::Orchestrator, &llm_messages)?;
-        parse_orchestrator_response(&response)
+        let context = self.context.build(AgentRole::Orchestrator);
+        let llm_messages =
+            build_orchestrator_messages(&self.prompts, messages, &self.delegates, &context);
+        let tools = [route_tool(&self.delegates)];
+        let reply = self.call_tools(AgentRole::Orchestrator, &llm_messages, &tools)?;
+        decision_from_reply(&reply)
+    }
+
+    /// Run the orchestrator, passing response text to `on_token`
+    ///
+    /// A tool-calling reply arrives whole, so the text the model wrote
+    /// alongside its decision is passed on in one piece.
+    pub fn orchestrate_streaming(
+        &self,
+        messages: &[ChatMessage],
+        mut on_token: impl FnMut(&str),
+    ) -> Result<OrchestratorDecision> {
+        let context = self.context.build(AgentRole::Orchestrator);
+        let llm_messages =
+            build_orchestrator_messages(&self.prompts, messages, &self.delegates, &context);
+        let tools = [route_tool(&self.delegates)];
+        let reply = self.call_tools(AgentRole::Orchestrator, &llm_messages, &tools)?;
+        if !reply.text.is_empty() {
+            on_token(&reply.text);
+        }
+        decision_from_reply(&reply)
     }
 
     /// Run the developer agent
+    ///
+    /// With reviews on, an answer the Reviewer objects to is sent back for
+    /// one revision.
     pub fn run_developer(&self, task: &str) -> Result<DeveloperResponse> {
-        let file_list = get_file_tree(&self.workspace_path, 3);
-        let llm_messages = build_developer_messages(task, &self.workspace_path, &file_list);
+        let response = self.ask_developer(task)?;
+        match self.review(task, &response)? {
+            Some(objections) => self.ask_developer(&revision_task(task, &objections)),
+            None => Ok(response),
+        }
+    }
+
+    /// Run the developer agent, passing response text to `on_token` as it arrives
+    ///
+    /// A revision the Reviewer asks for streams after its objections.
+    pub fn run_developer_streaming(
+        &self,
+        task: &str,
+        mut on_token: impl FnMut(&str),
+    ) -> Result<DeveloperResponse> {
+        let response = self.ask_developer_streaming(task, &mut on_token)?;
+        let Some(objections) = self.review(task, &response)? else {
+            return Ok(response);
+        };
+        on_token(&format!("\n\nReviewer asked for changes:\n{}\n\n", objections));
+        self.ask_developer_streaming(&revision_task(task, &objections), &mut on_token)
+    }
+
+    /// Have the Reviewer check the operations `response` proposes for `task`
+    ///
+    /// Returns its objections, or `None` when it approves, reviews are off or
+    /// there is nothing to change.
+    pub fn review(&self, task: &str, response: &DeveloperResponse) -> Result<Option<String>> {
+        if !self.review || response.operations.is_empty() {
+            return Ok(None);
+        }
+        let context = self.context.build(AgentRole::Reviewer);
+        let llm_messages = build_reviewer_messages(&self.prompts, task, response, &context);
+        let reply = self.call_llm(AgentRole::Reviewer, &llm_messages)?;
+        Ok(parse_review(&reply))
+    }
+
+    fn ask_developer(&self, task: &str) -> Result<DeveloperResponse> {
+        let context = self.context.build(AgentRole::Developer);
+        let llm_messages =
+            build_developer_messages(&self.prompts, task, &self.workspace_path, &context);
         let response = self.call_llm(AgentRole::Developer, &llm_messages)?;
         parse_developer_response(&response)
     }
 
+    fn ask_developer_streaming(
+        &self,
+        task: &str,
+        on_token: &mut impl FnMut(&str),
+    ) -> Result<DeveloperResponse> {
+        let context = self.context.build(AgentRole::Developer);
+        let llm_messages =
+            build_developer_messages(&self.prompts, task, &self.workspace_path, &context);
+        let response = self.call_llm_streaming(AgentRole::Developer, &llm_messages, on_token)?;
+        parse_developer_response(&response)
+    }
+
+    /// `output` of `command` to hand the Developer: as it is when short,
+    /// else condensed by the Shell Summarizer (left for the task to cut
+    /// down if that fails)
+    fn condense_output(&self, command: &str, output: String) -> String {
+        if output.chars().count() <= SUMMARIZE_AFTER_CHARS {
+            return output;
+        }
+        let llm_messages = build_summarizer_messages(&self.prompts, command, &output);
+        match self.call_llm(AgentRole::ShellSummarizer, &llm_messages) {
+            Ok(summary) if !summary.trim().is_empty() => summary,
+            _ => output,
+        }
+    }
+
+    /// Run a developer agent per task, up to `max_parallel` at once
+    ///
+    /// A task starts once the tasks it depends on are done, told what they
+    /// did. `apply` carries out each response's operations before any
+    /// dependent task starts, so that task sees the changes; an error from it
+    /// fails the task.
+    pub fn run_developer_tasks(
+        &self,
+        queue: &TaskQueue,
+        max_parallel: usize,
+        apply: impl Fn(&SubTask, &DeveloperResponse) -> std::result::Result<(), String> + Sync,
+        on_progress: impl FnMut(TaskProgress),
+    ) -> Vec<TaskOutcome<DeveloperResponse>> {
+        queue.run(
+            max_parallel,
+            |task, inputs| {
+                let response = self
+                    .run_developer(&task_prompt(task, inputs))
+                    .map_err(|e| e.to_string())?;
+                apply(task, &response)?;
+                Ok(response)
+            },
+            on_progress,
+        )
+    }
+
+    /// Draft doc comments for the undocumented public items of `file`
+    /// (relative to the workspace)
+    ///
+    /// Returns `None` when there is nothing to document or the model
+    /// documented nothing.
+    pub fn draft_docs(&self, file: &Path) -> Result<Option<DocChangeset>> {
+        let language = DocLanguage::from_path(file).ok_or_else(|| {
+            AxiomError::config(format!(
+                "Can't document {}: unsupported file type",
+                file.display()
+            ))
+        })?;
+        let source = std::fs::read_to_string(self.workspace_path.join(file))?;
+        let items = undocumented_items(language, &source);
+        if items.is_empty() {
+            return Ok(None);
+        }
+        let messages = build_docs_messages(file, language, &source, &items);
+        let response = self.call_llm(AgentRole::Developer, &messages)?;
+        let docs = parse_docs_response(&response);
+        Ok(docs_changeset(file, language, &source, &items, &docs))
+    }
+
+    /// Draft docs for the file or module directory `path` and hand each
+    /// file's changeset to `hold` for review
+    ///
+    /// `hold` queues the changeset and returns its approval ID. With `check`,
+    /// Rust changesets that break `cargo doc` are reported instead of held.
+    pub fn generate_docs(
+        &self,
+        path: &Path,
+        check: bool,
+        mut hold: impl FnMut(&DocChangeset) -> std::result::Result<String, String>,
+        mut progress: impl FnMut(&str),
+    ) -> Result<DocGenReport> {
+        let mut report = DocGenReport::default();
+        for file in doc_targets(&self.workspace_path, path)? {
+            progress(&format!("Documenting {}", file.display()));
+            let changeset = match self.draft_docs(&file) {
+                Ok(Some(changeset)) => changeset,
+                Ok(None) => {
+                    report.unchanged += 1;
+                    continue;
+                }
+                Err(e) => {
+                    report.errors.push((file, e.to_string()));
+                    continue;
+                }
+            };
+            if check {
+                progress(&format!("Checking {} with cargo doc", file.display()));
+                if let Some(errors) = verify_docs(&self.workspace_path, &changeset)? {
+                    report.failed_check.push((file, errors));
+                    continue;
+                }
+            }
+            match hold(&changeset) {
+                Ok(id) => report.held.push((file, id, changeset.documented)),
+                Err(e) => report.errors.push((file, e)),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Generate tests for `target` (a path or symbol) with the developer
+    /// agent, rerunning them and feeding failures back for up to
+    /// `max_attempts` attempts
+    ///
+    /// `apply` applies each file operation, so callers can enforce their
+    /// write policy; commands the developer asks for are not run.
+    /// `progress` receives a line per step.
+    pub fn generate_tests(
+        &self,
+        target: &str,
+        max_attempts: usize,
+        mut apply: impl FnMut(&AgentOperation) -> std::result::Result<(), String>,
+        mut progress: impl FnMut(&str),
+    ) -> Result<TestGenReport> {
+        let root = &self.workspace_path;
+        let target = resolve_target(root, target)?;
+        let command = test_command(root)
+            .ok_or_else(|| AxiomError::config("No test runner found for this workspace"))?;
+
+        let mut report = TestGenReport {
+            target: target.describe(),
+            command: command.to_string(),
+            attempts: 0,
+            passed: 0,
+            failed: 0,
+            success: false,
+            files: Default::default(),
+            skipped: Vec::new(),
+        };
+        let mut failure: Option<String> = None;
+        let mut not_applied = Vec::new();
+        for attempt in 1..=max_attempts.max(1) {
+            progress(&format!(
+                "Attempt {}/{}: writing tests for {}",
+                attempt, max_attempts, report.target
+            ));
+            let task = gen_tests_task(root, &target, command, failure.as_deref())?;
+            let response = self.run_developer(&retry_task(&task, &not_applied))?;
+            not_applied.clear();
+            for op in &response.operations {
+                let Some(path) = op.path() else {
+                    if let AgentOperation::Execute { command } = op {
+                        report
+                            .skipped
+                            .push(format!("`{}` (commands aren't run)", command));
+                    }
+                    continue;
+                };
+                match apply(op) {
+                    Ok(()) => {
+                        progress(&format!("Updated {}", path.display()));
+                        report.files.insert(path.to_path_buf());
+                    }
+                    Err(e) => {
+                        // The first line sums it up; the rest is for the retry
+                        let summary = e.lines().next().unwrap_or_default();
+                        report
+                            .skipped
+                            .push(format!("{}: {}", path.display(), summary));
+                        not_applied.push((path.to_path_buf(), e));
+                    }
+                }
+            }
+
+            progress(&format!("Running `{}`", command));
+            let run = run_tests(root, command)?;
+            report.attempts = attempt;
+            report.passed = run.passed;
+            report.failed = run.failed;
+            report.success = run.success;
+            if run.success {
+                break;
+            }
+            progress(&format!("{} passed, {} failed", run.passed, run.failed));
+            failure = Some(self.condense_output(command, run.output));
+        }
+        Ok(report)
+    }
+
+    /// Build the workspace and list its compiler errors, numbered for
+    /// [`Self::fix_problem`]
+    pub fn list_problems(&self) -> Result<String> {
+        let command = build_command(&self.workspace_path)
+            .ok_or_else(|| AxiomError::config("No build command found for this workspace"))?;
+        let run = run_build(&self.workspace_path, command)?;
+        Ok(problems_list(command, &run))
+    }
+
+    /// Have the developer agent fix compiler error `number` (1-based, as
+    /// listed by [`Self::list_problems`]), rebuilding after each attempt
+    /// until the error is gone, for up to `max_attempts` attempts
+    ///
+    /// `apply` and `progress` work as for [`Self::generate_tests`].
+    pub fn fix_problem(
+        &self,
+        number: usize,
+        max_attempts: usize,
+        mut apply: impl FnMut(&AgentOperation) -> std::result::Result<(), String>,
+        mut progress: impl FnMut(&str),
+    ) -> Result<FixReport> {
+        let root = &self.workspace_path;
+        let command = build_command(root)
+            .ok_or_else(|| AxiomError::config("No build command found for this workspace"))?;
+
+        progress(&format!("Running `{}`", command));
+        let run = run_build(root, command)?;
+        let problem = number
+            .checked_sub(1)
+            .and_then(|i| run.problems.get(i))
+            .cloned()
+            .ok_or_else(|| {
+                AxiomError::not_found(format!(
+                    "No problem {} ({} reported by `{}`)",
+                    number,
+                    run.problems.len(),
+                    command
+                ))
+            })?;
+        let before = run.problems.len();
+
+        let mut report = FixReport {
+            problem: problem.describe(),
+            command: command.to_string(),
+            attempts: 0,
+            fixed: false,
+            success: false,
+            remaining: before,
+            files: Default::default(),
+            skipped: Vec::new(),
+        };
+        let mut failure: Option<String> = None;
+        let mut not_applied = Vec::new();
+        for attempt in 1..=max_attempts.max(1) {
+            progress(&format!(
+                "Attempt {}/{}: fixing {}",
+                attempt, max_attempts, report.problem
+            ));
+            let task = fix_task(root, &problem, command, failure.as_deref())?;
+            let response = self.run_developer(&retry_task(&task, &not_applied))?;
+            not_applied.clear();
+            for op in &response.operations {
+                let Some(path) = op.path() else {
+                    if let AgentOperation::Execute { command } = op {
+                        report
+                            .skipped
+                            .push(format!("`{}` (commands aren't run)", command));
+                    }
+                    continue;
+                };
+                match apply(op) {
+                    Ok(()) => {
+                        progress(&format!("Updated {}", path.display()));
+                        report.files.insert(path.to_path_buf());
+                    }
+                    Err(e) => {
+                        // The first line sums it up; the rest is for the retry
+                        let summary = e.lines().next().unwrap_or_default();
+                        report
+                            .skipped
+                            .push(format!("{}: {}", path.display(), summary));
+                        not_applied.push((path.to_path_buf(), e));
+                    }
+                }
+            }
+
+            progress(&format!("Running `{}`", command));
+            let run = run_build(root, command)?;
+            report.attempts = attempt;
+            report.success = run.success;
+            report.remaining = run.problems.len();
+            report.fixed = is_fixed(&problem, before, &run);
+            if report.fixed {
+                break;
+            }
+            progress(&format!("Still failing: {} error(s)", run.problems.len()));
+            failure = Some(self.condense_output(command, run.output));
+        }
+        Ok(report)
+    }
+
     /// Execute developer operations
     pub async fn execute_operations(&self, operations: &[AgentOperation]) -> Vec<OperationResult> {
         let mut results = Vec::new();
@@ -107,9 +552,18 @@ impl OrchestrationService {
                 AgentOperation::Write { path, content } => {
                     self.execute_write(path, content).await
                 }
+                AgentOperation::Patch { path, diff, edits } => {
+                    self.execute_patch(path, diff.as_deref(), edits).await
+                }
+                AgentOperation::Rename { from, to } => {
+                    self.execute_rename(from, to).await
+                }
                 AgentOperation::Delete { path } => {
                     self.execute_delete(path).await
                 }
+                AgentOperation::RenameSymbol { symbol, new_name } => {
+                    self.execute_rename_symbol(symbol, new_name)
+                }
                 AgentOperation::Execute { command } => {
                     self.execute_command(command).await
                 }
@@ -120,8 +574,119 @@ impl OrchestrationService {
         results
     }
 
-    /// Call LLM for a specific agent
+    /// Take the usage of LLM calls made since the last call
+    pub fn take_usage(&self) -> Vec<UsageRecord> {
+        std::mem::take(&mut *self.usage.lock())
+    }
+
+    /// Call LLM for a specific agent, noting estimated token usage
     fn call_llm(&self, agent: AgentRole, messages: &[ChatMessage]) -> Result<String> {
+        self.record_call(messages, self.send_llm(agent, messages, None)?)
+    }
+
+    /// Like [`Self::call_llm`], but streams the response through `on_token`
+    fn call_llm_streaming(
+        &self,
+        agent: AgentRole,
+        messages: &[ChatMessage],
+        on_token: &mut dyn FnMut(&str),
+    ) -> Result<String> {
+        self.record_call(messages, self.send_llm(agent, messages, Some(on_token))?)
+    }
+
+    /// Note the estimated usage of a call; returns its response
+    fn record_call(
+        &self,
+        messages: &[ChatMessage],
+        (provider, model, response): (String, String, String),
+    ) -> Result<String> {
+        let input: u64 = messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+        self.usage.lock().push(UsageRecord::new(
+            provider,
+            model,
+            input,
+            estimate_tokens(&response),
+        ));
+        Ok(response)
+    }
+
+    /// Send `messages` to the provider mapped to `agent`, offering `tools`
+    fn call_tools(
+        &self,
+        agent: AgentRole,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
+    ) -> Result<ToolReply> {
+        if let Some(llm) = &self.provider {
+            let reply = llm
+                .chat_with_tools(llm_messages(messages), tools)
+                .map_err(|e| crate::AxiomError::Llm(e.to_string()))?;
+            let calls = serde_json::to_string(&reply.calls).unwrap_or_default();
+            self.record_call(
+                messages,
+                (
+                    llm.id().to_string(),
+                    llm.model(),
+                    format!("{}{}", reply.text, calls),
+                ),
+            )?;
+            return Ok(reply);
+        }
+        let (provider, model) = self.provider_for(agent)?;
+        let llm: Box<dyn LlmProvider> = match provider.id.as_str() {
+            "openai" => {
+                let base_url = provider
+                    .base_url
+                    .as_deref()
+                    .unwrap_or("https://api.openai.com/v1");
+                let mut llm =
+                    OpenAIProvider::new(&provider.api_key, &model).with_base_url(base_url);
+                for (name, value) in &provider.extra_headers {
+                    llm = llm.with_header(name, value);
+                }
+                Box::new(llm)
+            }
+            "anthropic" => Box::new(ClaudeProvider::new(&provider.api_key, &model)),
+            "gemini" => Box::new(GeminiProvider::new(&provider.api_key, &model)),
+            "ollama" => {
+                let base_url = provider
+                    .base_url
+                    .as_deref()
+                    .unwrap_or("http://localhost:11434");
+                Box::new(OllamaProvider::new(base_url, &model))
+            }
+            // Anything else is an OpenAI-compatible endpoint
+            _ if provider.base_url.is_some() => {
+                let base_url = provider.base_url.as_deref().unwrap_or_default();
+                let mut llm = OpenAICompatibleProvider::new(&provider.id, base_url, &model)
+                    .with_api_key(&provider.api_key);
+                for (name, value) in &provider.extra_headers {
+                    llm = llm.with_header(name, value);
+                }
+                Box::new(llm)
+            }
+            _ => {
+                return Err(crate::AxiomError::Config(format!(
+                    "Unsupported provider: {}",
+                    provider.id
+                )))
+            }
+        };
+        let reply = llm
+            .chat_with_tools(llm_messages(messages), tools)
+            .map_err(|e| crate::AxiomError::Llm(e.to_string()))?;
+
+        // Calls count as output alongside the text
+        let calls = serde_json::to_string(&reply.calls).unwrap_or_default();
+        self.record_call(
+            messages,
+            (provider.id, model, format!("{}{}", reply.text, calls)),
+        )?;
+        Ok(reply)
+    }
+
+    /// The provider (enabled) and model mapped to `agent`
+    fn provider_for(&self, agent: AgentRole) -> Result<(ProviderConfig, String)> {
         let settings = self.settings.read();
 
         // Find mapping for this agent
@@ -142,25 +707,70 @@ impl OrchestrationService {
                     mapping.provider_id
                 ))
             })?;
+        Ok((provider.clone(), mapping.model_id.clone()))
+    }
+
+    /// Send `messages` to the provider mapped to `agent`
+    ///
+    /// Returns the provider ID and model along with the response text. With
+    /// `on_token` the provider streams its answer and each chunk is passed on.
+    fn send_llm(
+        &self,
+        agent: AgentRole,
+        messages: &[ChatMessage],
+        on_token: Option<&mut dyn FnMut(&str)>,
+    ) -> Result<(String, String, String)> {
+        if let Some(llm) = &self.provider {
+            let response = send_to(llm.as_ref(), messages, on_token)?;
+            return Ok((llm.id().to_string(), llm.model(), response));
+        }
+        let (provider, model) = self.provider_for(agent)?;
+        let provider = &provider;
 
         // Make API call based on provider
-        match provider.id.as_str() {
-            "openai" => self.call_openai(provider, &mapping.model_id, messages),
-            "anthropic" => self.call_anthropic(provider, &mapping.model_id, messages),
-            "gemini" => self.call_gemini(provider, &mapping.model_id, messages),
-            "ollama" => self.call_ollama(provider, &mapping.model_id, messages),
+        let response = match provider.id.as_str() {
+            "openai" => self.call_openai(agent, provider, &model, messages, on_token),
+            "anthropic" => self.call_anthropic(agent, provider, &model, messages, on_token),
+            "gemini" => self.call_gemini(agent, provider, &model, messages, on_token),
+            "ollama" => self.call_ollama(agent, provider, &model, messages, on_token),
+            // Anything else is an OpenAI-compatible endpoint
+            _ if provider.base_url.is_some() => {
+                self.call_openai(agent, provider, &model, messages, on_token)
+            }
             _ => Err(crate::AxiomError::Config(format!(
                 "Unsupported provider: {}",
                 provider.id
             ))),
-        }
+        }?;
+        Ok((provider.id.clone(), model, response))
+    }
+
+    /// POST `body` through the provider's quota scheduler
+    ///
+    /// `request` may be built more than once when the provider answers 429.
+    fn post_json(
+        &self,
+        agent: AgentRole,
+        provider: &ProviderConfig,
+        request: impl Fn() -> ureq::Request,
+        body: &serde_json::Value,
+    ) -> Result<ureq::Response> {
+        // Budgets are configured under the chat provider names
+        let quota_key = match provider.id.as_str() {
+            "anthropic" => "claude",
+            id => id,
+        };
+        send_with_quota(quota_key, &format!("{:?}", agent), request, body)
+            .map_err(|e| crate::AxiomError::Llm(e.to_string()))
     }
 
     fn call_openai(
         &self,
+        agent: AgentRole,
         provider: &ProviderConfig,
         model: &str,
         messages: &[ChatMessage],
+        on_token: Option<&mut dyn FnMut(&str)>,
     ) -> Result<String> {
         let base_url = provider
             .base_url
@@ -184,16 +794,32 @@ impl OrchestrationService {
         let body = serde_json::json!({
             "model": model,
             "messages": api_messages,
-            "temperature": 0.7
+            "temperature": 0.7,
+            "stream": on_token.is_some()
         });
 
-        let response = self
-            .client
-            .post(&format!("{}/chat/completions", base_url))
-            .set("Content-Type", "application/json")
-            .set("Authorization", &format!("Bearer {}", provider.api_key))
-            .send_json(&body)
-            .map_err(|e| crate::AxiomError::Llm(e.to_string()))?;
+        let response = self.post_json(
+            agent,
+            provider,
+            || {
+                let mut request = self
+                    .client
+                    .post(&format!("{}/chat/completions", base_url))
+                    .set("Content-Type", "application/json");
+                // Local endpoints often take no key
+                if !provider.api_key.is_empty() {
+                    request = request.set("Authorization", &format!("Bearer {}", provider.api_key));
+                }
+                for (name, value) in &provider.extra_headers {
+                    request = request.set(name, value);
+                }
+                request
+            },
+            &body,
+        )?;
+        if let Some(on_token) = on_token {
+            return read_stream(response, "/choices/0/delta/content", on_token);
+        }
 
         let json: serde_json::Value = response
             .into_json()
@@ -210,9 +836,11 @@ impl OrchestrationService {
 
     fn call_anthropic(
         &self,
+        agent: AgentRole,
         provider: &ProviderConfig,
         model: &str,
         messages: &[ChatMessage],
+        on_token: Option<&mut dyn FnMut(&str)>,
     ) -> Result<String> {
         let mut system_content = String::new();
         let mut api_messages: Vec<serde_json::Value> = Vec::new();
@@ -241,21 +869,30 @@ impl OrchestrationService {
         let mut body = serde_json::json!({
             "model": model,
             "max_tokens": 8192,
-            "messages": api_messages
+            "messages": api_messages,
+            "stream": on_token.is_some()
         });
 
         if !system_content.is_empty() {
             body["system"] = serde_json::Value::String(system_content.trim().to_string());
         }
 
-        let response = self
-            .client
-            .post("https://api.anthropic.com/v1/messages")
-            .set("Content-Type", "application/json")
-            .set("x-api-key", &provider.api_key)
-            .set("anthropic-version", "2023-06-01")
-            .send_json(&body)
-            .map_err(|e| crate::AxiomError::Llm(e.to_string()))?;
+        let response = self.post_json(
+            agent,
+            provider,
+            || {
+                self.client
+                    .post("https://api.anthropic.com/v1/messages")
+                    .set("Content-Type", "application/json")
+                    .set("x-api-key", &provider.api_key)
+                    .set("anthropic-version", "2023-06-01")
+            },
+            &body,
+        )?;
+        if let Some(on_token) = on_token {
+            // Text arrives in `content_block_delta` events
+            return read_stream(response, "/delta/text", on_token);
+        }
 
         let json: serde_json::Value = response
             .into_json()
@@ -271,9 +908,11 @@ impl OrchestrationService {
 
     fn call_gemini(
         &self,
+        agent: AgentRole,
         provider: &ProviderConfig,
         model: &str,
         messages: &[ChatMessage],
+        on_token: Option<&mut dyn FnMut(&str)>,
     ) -> Result<String> {
         let contents: Vec<serde_json::Value> = messages
             .iter()
@@ -288,17 +927,32 @@ impl OrchestrationService {
             })
             .collect();
 
-        let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
-            model, provider.api_key
-        );
+        let url = if on_token.is_some() {
+            format!(
+                "https://generativelanguage.googleapis.com/v1beta/models/{}:streamGenerateContent?alt=sse&key={}",
+                model, provider.api_key
+            )
+        } else {
+            format!(
+                "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
+                model, provider.api_key
+            )
+        };
 
-        let response = self
-            .client
-            .post(&url)
-            .set("Content-Type", "application/json")
-            .send_json(&serde_json::json!({ "contents": contents }))
-            .map_err(|e| crate::AxiomError::Llm(e.to_string()))?;
+        let body = serde_json::json!({ "contents": contents });
+        let response = self.post_json(
+            agent,
+            provider,
+            || {
+                self.client
+                    .post(&url)
+                    .set("Content-Type", "application/json")
+            },
+            &body,
+        )?;
+        if let Some(on_token) = on_token {
+            return read_stream(response, "/candidates/0/content/parts/0/text", on_token);
+        }
 
         let json: serde_json::Value = response
             .into_json()
@@ -317,9 +971,11 @@ impl OrchestrationService {
 
     fn call_ollama(
         &self,
+        agent: AgentRole,
         provider: &ProviderConfig,
         model: &str,
         messages: &[ChatMessage],
+        on_token: Option<&mut dyn FnMut(&str)>,
     ) -> Result<String> {
         let base_url = provider
             .base_url
@@ -340,16 +996,25 @@ impl OrchestrationService {
             })
             .collect();
 
-        let response = self
-            .client
-            .post(&format!("{}/api/chat", base_url))
-            .set("Content-Type", "application/json")
-            .send_json(&serde_json::json!({
-                "model": model,
-                "messages": api_messages,
-                "stream": false
-            }))
-            .map_err(|e| crate::AxiomError::Llm(e.to_string()))?;
+        let body = serde_json::json!({
+            "model": model,
+            "messages": api_messages,
+            "stream": on_token.is_some()
+        });
+        let response = self.post_json(
+            agent,
+            provider,
+            || {
+                self.client
+                    .post(&format!("{}/api/chat", base_url))
+                    .set("Content-Type", "application/json")
+            },
+            &body,
+        )?;
+        if let Some(on_token) = on_token {
+            // Ollama streams one JSON object per line
+            return read_stream(response, "/message/content", on_token);
+        }
 
         let json: serde_json::Value = response
             .into_json()
@@ -379,7 +1044,7 @@ impl OrchestrationService {
             }
         }
 
-        match tokio::fs::write(&full_path, content).await {
+        match write_atomic(&full_path, content.as_bytes()) {
             Ok(_) => OperationResult {
                 success: true,
                 message: format!("Wrote {}", full_path.display()),
@@ -391,6 +1056,93 @@ impl OrchestrationService {
         }
     }
 
+    async fn execute_patch(
+        &self,
+        path: &PathBuf,
+        diff: Option<&str>,
+        edits: &[TextEdit],
+    ) -> OperationResult {
+        let full_path = if path.is_absolute() {
+            path.clone()
+        } else {
+            self.workspace_path.join(path)
+        };
+
+        let original = match tokio::fs::read_to_string(&full_path).await {
+            Ok(content) => content,
+            Err(e) => {
+                return OperationResult {
+                    success: false,
+                    message: format!("Failed to read {}: {}", full_path.display(), e),
+                }
+            }
+        };
+
+        // Nothing is written unless every hunk/edit applies
+        let patched = match check_patch(&original, diff, edits) {
+            Ok(patched) => patched,
+            Err(failures) => {
+                return OperationResult {
+                    success: false,
+                    message: format!(
+                        "Failed to patch {}: {}",
+                        full_path.display(),
+                        failure_report(&failures)
+                    ),
+                }
+            }
+        };
+
+        match write_atomic(&full_path, patched.as_bytes()) {
+            Ok(_) => OperationResult {
+                success: true,
+                message: format!("Patched {}", full_path.display()),
+            },
+            Err(e) => OperationResult {
+                success: false,
+                message: format!("Failed to write file: {}", e),
+            },
+        }
+    }
+
+    async fn execute_rename(&self, from: &PathBuf, to: &PathBuf) -> OperationResult {
+        let resolve = |p: &PathBuf| {
+            if p.is_absolute() {
+                p.clone()
+            } else {
+                self.workspace_path.join(p)
+            }
+        };
+        let (from, to) = (resolve(from), resolve(to));
+
+        if to.exists() {
+            return OperationResult {
+                success: false,
+                message: format!("Cannot rename: {} already exists", to.display()),
+            };
+        }
+
+        if let Some(parent) = to.parent() {
+            if let Err(e) = tokio::fs::create_dir_all(parent).await {
+                return OperationResult {
+                    success: false,
+                    message: format!("Failed to create directory: {}", e),
+                };
+            }
+        }
+
+        match tokio::fs::rename(&from, &to).await {
+            Ok(_) => OperationResult {
+                success: true,
+                message: format!("Renamed {} -> {}", from.display(), to.display()),
+            },
+            Err(e) => OperationResult {
+                success: false,
+                message: format!("Failed to rename file: {}", e),
+            },
+        }
+    }
+
     async fn execute_delete(&self, path: &PathBuf) -> OperationResult {
         let full_path = if path.is_absolute() {
             path.clone()
@@ -410,6 +1162,24 @@ impl OrchestrationService {
         }
     }
 
+    fn execute_rename_symbol(&self, symbol: &str, new_name: &str) -> OperationResult {
+        let applied = plan_rename(&self.workspace_path, symbol, new_name).and_then(|changeset| {
+            changeset
+                .apply(&self.workspace_path)
+                .map(|_| changeset.occurrences())
+        });
+        match applied {
+            Ok(count) => OperationResult {
+                success: true,
+                message: format!("Renamed {} -> {} ({} references)", symbol, new_name, count),
+            },
+            Err(e) => OperationResult {
+                success: false,
+                message: format!("Failed to rename {}: {}", symbol, e),
+            },
+        }
+    }
+
     async fn execute_command(&self, command: &str) -> OperationResult {
         use tokio::process::Command;
 
@@ -443,6 +1213,95 @@ impl OrchestrationService {
     }
 }
 
+/// Read a streamed response, passing the text at `pointer` in each chunk to
+/// `on_token`; returns the whole text
+/// `messages` as the LLM providers take them
+fn llm_messages(messages: &[ChatMessage]) -> Vec<crate::llm::ChatMessage> {
+    messages
+        .iter()
+        .map(|m| match m.role {
+            super::types::MessageRole::System => crate::llm::ChatMessage::system(&m.content),
+            super::types::MessageRole::User => crate::llm::ChatMessage::user(&m.content),
+            super::types::MessageRole::Assistant => crate::llm::ChatMessage::assistant(&m.content),
+        })
+        .collect()
+}
+
+/// Send `messages` to `llm` and wait for the whole response, passing each
+/// chunk to `on_token` if given
+fn send_to(
+    llm: &dyn LlmProvider,
+    messages: &[ChatMessage],
+    mut on_token: Option<&mut dyn FnMut(&str)>,
+) -> Result<String> {
+    let (tx, rx) = crossbeam_channel::unbounded();
+    llm.send_message(llm_messages(messages), tx);
+    let mut response = String::new();
+    for event in rx {
+        match event {
+            Event::LlmChunk(chunk) => {
+                if let Some(on_token) = on_token.as_mut() {
+                    on_token(&chunk);
+                }
+                response.push_str(&chunk);
+            }
+            Event::LlmDone => break,
+            Event::LlmError(e) => return Err(crate::AxiomError::Llm(e)),
+            _ => {}
+        }
+    }
+    Ok(response)
+}
+
+fn read_stream(
+    response: ureq::Response,
+    pointer: &str,
+    on_token: &mut dyn FnMut(&str),
+) -> Result<String> {
+    stream_text(
+        std::io::BufReader::new(response.into_reader()),
+        pointer,
+        on_token,
+    )
+}
+
+/// Collect text from SSE `data:` lines or newline-delimited JSON
+fn stream_text(
+    reader: impl BufRead,
+    pointer: &str,
+    on_token: &mut dyn FnMut(&str),
+) -> Result<String> {
+    let mut text = String::new();
+    for line in reader.lines() {
+        let line = line?;
+        let data = line
+            .strip_prefix("data:")
+            .map_or(line.as_str(), str::trim_start);
+        if data == "[DONE]" {
+            break;
+        }
+        // Blank lines and `event:` lines carry no text
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(data) else {
+            continue;
+        };
+        if let Some(error) = json.get("error") {
+            let message = error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .or(error.as_str())
+                .unwrap_or("Unknown error");
+            return Err(AxiomError::Llm(message.to_string()));
+        }
+        if let Some(chunk) = json.pointer(pointer).and_then(|t| t.as_str()) {
+            if !chunk.is_empty() {
+                on_token(chunk);
+                text.push_str(chunk);
+            }
+        }
+    }
+    Ok(text)
+}
+
 /// Updates for provider configuration
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProviderConfigUpdate {
@@ -458,3 +1317,30 @@ pub struct OperationResult {
     pub success: bool,
     pub message: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_text() {
+        let sse = "event: content_block_delta\ndata: {\"delta\":{\"text\":\"Hel\"}}\n\n\
+                   data: {\"delta\":{\"text\":\"lo\"}}\n\ndata: [DONE]\ndata: {\"delta\":{\"text\":\"!\"}}\n";
+        let mut chunks = Vec::new();
+        let text = stream_text(sse.as_bytes(), "/delta/text", &mut |t| {
+            chunks.push(t.to_string())
+        })
+        .unwrap();
+        assert_eq!(text, "Hello");
+        assert_eq!(chunks, ["Hel", "lo"]);
+
+        let ndjson =
+            "{\"message\":{\"content\":\"a\"}}\n{\"message\":{\"content\":\"\"},\"done\":true}\n";
+        let text = stream_text(ndjson.as_bytes(), "/message/content", &mut |_| {}).unwrap();
+        assert_eq!(text, "a");
+
+        let error = "{\"error\":\"model not found\"}\n";
+        let err = stream_text(error.as_bytes(), "/message/content", &mut |_| {}).unwrap_err();
+        assert!(err.to_string().contains("model not found"));
+    }
+}
@@ -0,0 +1,45 @@
+//! Shell Summarizer Agent
+//!
+//! Build and test output fed back to the Developer is cut to its tail when
+//! it's long. The Shell Summarizer condenses it instead, keeping the errors
+//! wherever they were printed.
+
+use super::testgen::tail;
+use super::types::{AgentRole, ChatMessage};
+use crate::prompts::PromptLibrary;
+
+/// Output longer than this is condensed
+pub const SUMMARIZE_AFTER_CHARS: usize = 6_000;
+
+/// Most output sent to be condensed (its tail)
+const MAX_OUTPUT_CHARS: usize = 60_000;
+
+const SUMMARIZER_SYSTEM_PROMPT: &str = r#"
+You are the Shell Summarizer Agent. You condense the output of a build or test command for a
+developer who has to fix what failed.
+
+Keep every error and failing test verbatim, with its file, line and message. Drop progress
+lines, passing tests, warnings unrelated to the failures and repeated text. Reply with the
+condensed output only.
+"#;
+
+/// Messages asking the Shell Summarizer to condense `output` of `command`
+pub fn build_summarizer_messages(
+    prompts: &PromptLibrary,
+    command: &str,
+    output: &str,
+) -> Vec<ChatMessage> {
+    let prompt = prompts.render(
+        AgentRole::ShellSummarizer.as_str(),
+        SUMMARIZER_SYSTEM_PROMPT,
+        command,
+    );
+    vec![
+        ChatMessage::system(prompt),
+        ChatMessage::user(format!(
+            "Output of `{}`:\n```\n{}\n```",
+            command,
+            tail(output, MAX_OUTPUT_CHARS)
+        )),
+    ]
+}
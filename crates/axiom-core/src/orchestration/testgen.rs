@@ -0,0 +1,479 @@
+//! Test generation (`/gen-tests <path|symbol>`)
+//!
+//! Resolves the target to a source file, works out which of its functions
+//! no existing test mentions, and asks the Developer to cover them, showing
+//! a nearby test file so the new tests follow the project's conventions.
+//! The project's test runner then runs the suite; failures are fed back to
+//! the Developer for another attempt, up to the attempt limit.
+//!
+//! "Coverage" here is a heuristic: a function counts as tested when its name
+//! appears in a test file or test module. It needs no coverage tooling and
+//! works the same across languages.
+
+use super::developer::get_file_tree;
+use crate::{AxiomError, Result};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Attempts when `--attempts` isn't given
+pub const DEFAULT_ATTEMPTS: usize = 3;
+
+/// How deep to look for the target symbol and test examples
+const SEARCH_DEPTH: usize = 8;
+
+/// Longest source file sent in full
+const MAX_SOURCE_CHARS: usize = 12_000;
+
+/// Longest test example sent
+const MAX_EXAMPLE_CHARS: usize = 4_000;
+
+/// Test output fed back after a failed run (its tail)
+const MAX_FAILURE_CHARS: usize = 6_000;
+
+/// Test files shown as examples of the project's conventions
+const MAX_EXAMPLES: usize = 2;
+
+const SOURCE_EXTENSIONS: &[&str] = &["rs", "py", "ts", "tsx", "js", "jsx", "go"];
+
+/// What to write tests for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestTarget {
+    /// Source file, relative to the workspace root
+    pub file: PathBuf,
+    /// Function or type the user named, if they named one
+    pub symbol: Option<String>,
+}
+
+impl TestTarget {
+    pub fn describe(&self) -> String {
+        match &self.symbol {
+            Some(symbol) => format!("{} ({})", symbol, self.file.display()),
+            None => self.file.display().to_string(),
+        }
+    }
+}
+
+/// Resolve `target` as a file below `root`, or else as a symbol defined in
+/// one of the workspace's source files
+pub fn resolve_target(root: &Path, target: &str) -> Result<TestTarget> {
+    let target = target.trim();
+    let path = root.join(target);
+    if path.is_file() {
+        let file = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        return Ok(TestTarget { file, symbol: None });
+    }
+
+    let symbol = target.rsplit("::").next().unwrap_or(target);
+    source_files(root)
+        .into_iter()
+        .find(|file| {
+            std::fs::read_to_string(root.join(file))
+                .is_ok_and(|source| source.lines().any(|l| defines(l, symbol)))
+        })
+        .map(|file| TestTarget {
+            file,
+            symbol: Some(symbol.to_string()),
+        })
+        .ok_or_else(|| AxiomError::not_found(format!("No file or definition named '{}'", target)))
+}
+
+/// Command that runs the project's tests
+pub fn test_command(root: &Path) -> Option<&'static str> {
+    if root.join("Cargo.toml").is_file() {
+        Some("cargo test")
+    } else if root.join("go.mod").is_file() {
+        Some("go test ./...")
+    } else if root.join("package.json").is_file() {
+        Some("npm test")
+    } else if ["pyproject.toml", "pytest.ini", "setup.py", "setup.cfg"]
+        .iter()
+        .any(|f| root.join(f).is_file())
+    {
+        Some("python -m pytest")
+    } else {
+        None
+    }
+}
+
+/// Existing test files, those closest to `file` first
+pub fn test_examples(root: &Path, file: &Path) -> Vec<PathBuf> {
+    let dir = file.parent().unwrap_or(Path::new(""));
+    let mut tests: Vec<PathBuf> = source_files(root)
+        .into_iter()
+        .filter(|f| is_test_file(f) && f != file)
+        .collect();
+    // Most leading path components shared with the target first
+    tests.sort_by_key(|t| {
+        let shared = t
+            .components()
+            .zip(dir.components())
+            .take_while(|(a, b)| a == b)
+            .count();
+        (usize::MAX - shared, t.clone())
+    });
+    tests.truncate(MAX_EXAMPLES);
+    tests
+}
+
+fn is_test_file(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    let in_tests_dir = path
+        .components()
+        .any(|c| matches!(c.as_os_str().to_str(), Some("tests" | "__tests__")));
+    (name.starts_with("test_") && name.ends_with(".py"))
+        || name.ends_with("_test.py")
+        || name.ends_with("_test.go")
+        || [".test.", ".spec."].iter().any(|m| name.contains(m))
+        || (in_tests_dir && name.ends_with(".rs"))
+}
+
+/// Source files below `root`, sorted
+fn source_files(root: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = get_file_tree(root, SEARCH_DEPTH)
+        .into_iter()
+        .map(PathBuf::from)
+        .filter(|f| {
+            f.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| SOURCE_EXTENSIONS.contains(&e))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Whether `line` defines a function or type named `symbol`
+fn defines(line: &str, symbol: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "fn ",
+        "def ",
+        "function ",
+        "func ",
+        "class ",
+        "struct ",
+        "enum ",
+        "trait ",
+        "interface ",
+        "type ",
+    ];
+    KEYWORDS.iter().any(|kw| {
+        line.match_indices(kw).any(|(i, _)| {
+            let starts_word = line[..i]
+                .chars()
+                .last()
+                .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+            let rest = line[i + kw.len()..].trim_start();
+            starts_word
+                && rest.starts_with(symbol)
+                && !rest[symbol.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+        })
+    })
+}
+
+/// Names of the functions defined in `source`
+fn function_names(source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for line in source.lines() {
+        let line = line.trim_start();
+        for kw in ["fn ", "def ", "function ", "func "] {
+            let Some(i) = line.find(kw) else { continue };
+            // Only declarations: keywords at the start, after modifiers
+            let prefix = &line[..i];
+            if !prefix.split_whitespace().all(|w| {
+                w.starts_with("pub") || ["async", "export", "const", "unsafe"].contains(&w)
+            }) {
+                continue;
+            }
+            let rest = &line[i + kw.len()..];
+            // Go methods: `func (r *Recv) Name(`
+            let rest = match rest.strip_prefix('(') {
+                Some(r) => r.split_once(')').map_or("", |(_, r)| r.trim_start()),
+                None => rest,
+            };
+            let name: String = rest
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect();
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+            break;
+        }
+    }
+    names
+}
+
+/// Functions in `source` no test mentions
+///
+/// `tests` is the text of the related test files; a Rust file's own
+/// `#[cfg(test)]` module counts as a test too.
+pub fn untested_functions(source: &str, tests: &str) -> Vec<String> {
+    let (code, own_tests) = source.split_once("#[cfg(test)]").unwrap_or((source, ""));
+    function_names(code)
+        .into_iter()
+        .filter(|name| name != "main" && name != "new")
+        .filter(|name| !own_tests.contains(name.as_str()) && !tests.contains(name.as_str()))
+        .collect()
+}
+
+/// Task handed to the Developer for one attempt
+///
+/// `failure` is the output of the previous attempt's failed test run.
+pub fn gen_tests_task(
+    root: &Path,
+    target: &TestTarget,
+    command: &str,
+    failure: Option<&str>,
+) -> Result<String> {
+    let source = std::fs::read_to_string(root.join(&target.file))?;
+    let examples: Vec<(PathBuf, String)> = test_examples(root, &target.file)
+        .into_iter()
+        .filter_map(|f| Some((f.clone(), std::fs::read_to_string(root.join(&f)).ok()?)))
+        .collect();
+    let tests_text: String = examples.iter().map(|(_, text)| text.as_str()).collect();
+
+    let mut task = match &target.symbol {
+        Some(symbol) => format!("Write tests for `{}` in {}.", symbol, target.file.display()),
+        None => format!("Write tests for {}.", target.file.display()),
+    };
+    if target.symbol.is_none() {
+        let untested = untested_functions(&source, &tests_text);
+        if !untested.is_empty() {
+            task.push_str(&format!(
+                " Focus on these functions, which no existing test covers: {}.",
+                untested.join(", ")
+            ));
+        }
+    }
+    task.push_str(
+        " Cover normal use, edge cases and error paths. Put the tests where this project \
+         keeps them and follow the conventions of the existing tests. Only add or change \
+         test code; don't change the code under test. Don't run any commands: the tests \
+         are run for you with `",
+    );
+    task.push_str(command);
+    task.push_str("`.");
+    if source.contains("#[cfg(test)]") {
+        task.push_str(" The file already has a `#[cfg(test)]` module; add the tests there.");
+    }
+
+    task.push_str(&format!(
+        "\n\nSource of {}:\n```\n{}\n```",
+        target.file.display(),
+        truncate(&source, MAX_SOURCE_CHARS)
+    ));
+    for (file, text) in &examples {
+        task.push_str(&format!(
+            "\n\nExisting tests in {}:\n```\n{}\n```",
+            file.display(),
+            truncate(text, MAX_EXAMPLE_CHARS)
+        ));
+    }
+    if let Some(output) = failure {
+        task.push_str(&format!(
+            "\n\nThe previous attempt's tests failed. Fix the tests (not the code under test) \
+             so they pass. Output of `{}`:\n```\n{}\n```",
+            command,
+            tail(output, MAX_FAILURE_CHARS)
+        ));
+    }
+    Ok(task)
+}
+
+/// Result of one test run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestRun {
+    pub success: bool,
+    pub passed: usize,
+    pub failed: usize,
+    /// Combined stdout and stderr
+    pub output: String,
+}
+
+/// Run `command` in `root`
+pub fn run_tests(root: &Path, command: &str) -> Result<TestRun> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(root)
+        .output()?;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let (passed, failed) = parse_test_counts(&text);
+    Ok(TestRun {
+        success: output.status.success(),
+        passed,
+        failed,
+        output: text,
+    })
+}
+
+/// Passed and failed test counts from cargo, pytest, jest or `go test -v`
+/// output
+pub fn parse_test_counts(output: &str) -> (usize, usize) {
+    let (mut passed, mut failed) = (0, 0);
+    for line in output.lines().map(str::trim) {
+        if line.starts_with("--- PASS") {
+            passed += 1;
+        } else if line.starts_with("--- FAIL") {
+            failed += 1;
+        } else if line.starts_with("test result:")
+            || line.starts_with("Tests:")
+            || (line.starts_with('=') && line.ends_with('='))
+        {
+            passed += count_of(line, "passed");
+            failed += count_of(line, "failed");
+        }
+    }
+    (passed, failed)
+}
+
+/// Sum of the numbers right before `word`, e.g. "3" in "3 passed"
+fn count_of(line: &str, word: &str) -> usize {
+    let words: Vec<&str> = line
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|w| !w.is_empty())
+        .collect();
+    words
+        .windows(2)
+        .filter(|w| w[1] == word)
+        .filter_map(|w| w[0].parse::<usize>().ok())
+        .sum()
+}
+
+/// Outcome of `/gen-tests`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestGenReport {
+    pub target: String,
+    pub command: String,
+    pub attempts: usize,
+    pub passed: usize,
+    pub failed: usize,
+    /// Whether the last run succeeded
+    pub success: bool,
+    /// Files the Developer wrote or patched
+    pub files: BTreeSet<PathBuf>,
+    /// Operations that weren't applied, with the reason
+    pub skipped: Vec<String>,
+}
+
+impl TestGenReport {
+    /// Share of tests passing in the last run, in percent
+    pub fn pass_rate(&self) -> Option<f64> {
+        let total = self.passed + self.failed;
+        (total > 0).then(|| self.passed as f64 * 100.0 / total as f64)
+    }
+
+    pub fn summary(&self) -> String {
+        let outcome = match (self.pass_rate(), self.success) {
+            (Some(rate), _) => format!(
+                "{} passed, {} failed ({:.0}% pass rate)",
+                self.passed, self.failed, rate
+            ),
+            (None, true) => "tests passed".to_string(),
+            (None, false) => "tests didn't run (build or runner error)".to_string(),
+        };
+        let mut text = format!(
+            "Tests for {}: {} after {} attempt(s) of `{}`",
+            self.target, outcome, self.attempts, self.command
+        );
+        if !self.files.is_empty() {
+            text.push_str("\nFiles:");
+            for file in &self.files {
+                text.push_str(&format!("\n  {}", file.display()));
+            }
+        }
+        for skipped in &self.skipped {
+            text.push_str(&format!("\nSkipped: {}", skipped));
+        }
+        text
+    }
+}
+
+pub(super) fn truncate(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
+}
+
+pub(super) fn tail(text: &str, max: usize) -> &str {
+    let len = text.chars().count();
+    match text.char_indices().nth(len.saturating_sub(max)) {
+        Some((i, _)) => &text[i..],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_test_counts() {
+        let cargo = "running 3 tests\n\
+                     test result: ok. 3 passed; 0 failed; 0 ignored\n\
+                     test result: FAILED. 1 passed; 2 failed; 0 ignored\n";
+        assert_eq!(parse_test_counts(cargo), (4, 2));
+        let pytest = "===== 1 failed, 5 passed in 0.12s =====";
+        assert_eq!(parse_test_counts(pytest), (5, 1));
+        let jest = "Tests:       2 failed, 7 passed, 9 total";
+        assert_eq!(parse_test_counts(jest), (7, 2));
+        let go = "--- PASS: TestAdd (0.00s)\n--- FAIL: TestSub (0.00s)\nFAIL";
+        assert_eq!(parse_test_counts(go), (1, 1));
+        assert_eq!(parse_test_counts("error[E0425]: cannot find value"), (0, 0));
+    }
+
+    #[test]
+    fn test_untested_functions() {
+        let source = "pub fn add(a: i32) -> i32 { a }\n\
+                      pub(crate) async fn fetch_all() {}\n\
+                      fn helper() { let f = |x| x; }\n\
+                      #[cfg(test)]\nmod tests {\n    fn t() { helper(); }\n}\n";
+        assert_eq!(
+            untested_functions(source, "assert_eq!(add(1), 1);"),
+            ["fetch_all"]
+        );
+
+        let go = "func (s *Server) Start() error {\nfunc parse(x string) {}";
+        assert_eq!(function_names(go), ["Start", "parse"]);
+    }
+
+    #[test]
+    fn test_resolve_target_and_examples() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("pkg/tests")).unwrap();
+        std::fs::write(root.join("pyproject.toml"), "").unwrap();
+        std::fs::write(root.join("pkg/pager.py"), "def page_count(n):\n    pass\n").unwrap();
+        std::fs::write(root.join("pkg/tests/test_other.py"), "def test_x(): pass\n").unwrap();
+        std::fs::write(root.join("test_root.py"), "").unwrap();
+
+        let target = resolve_target(root, "page_count").unwrap();
+        assert_eq!(target.file, PathBuf::from("pkg/pager.py"));
+        assert_eq!(target.symbol.as_deref(), Some("page_count"));
+        assert!(resolve_target(root, "page").is_err());
+        assert_eq!(resolve_target(root, "pkg/pager.py").unwrap().symbol, None);
+
+        assert_eq!(
+            test_examples(root, &target.file),
+            [
+                PathBuf::from("pkg/tests/test_other.py"),
+                PathBuf::from("test_root.py")
+            ]
+        );
+        assert_eq!(test_command(root), Some("python -m pytest"));
+
+        let task = gen_tests_task(root, &target, "python -m pytest", Some("1 failed")).unwrap();
+        assert!(task.starts_with("Write tests for `page_count` in pkg/pager.py."));
+        assert!(task.contains("Existing tests in pkg/tests/test_other.py"));
+        assert!(task.ends_with("```\n1 failed\n```"));
+    }
+}
@@ -2,8 +2,10 @@
 //!
 //! Defines the types used by the agent orchestration system.
 
+use super::patch::TextEdit;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// Agent roles in the orchestration system
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -17,15 +19,32 @@ pub enum AgentRole {
     Architect,
     /// Developer - writes and modifies code
     Developer,
+    /// Reviewer - checks changes before they are kept
+    Reviewer,
+    /// Shell summarizer - condenses long command output
+    #[serde(rename = "shell-summarizer")]
+    ShellSummarizer,
 }
 
 impl AgentRole {
+    /// Every role, in the order settings list them
+    pub const ALL: [AgentRole; 6] = [
+        AgentRole::Orchestrator,
+        AgentRole::Po,
+        AgentRole::Architect,
+        AgentRole::Developer,
+        AgentRole::Reviewer,
+        AgentRole::ShellSummarizer,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             AgentRole::Orchestrator => "orchestrator",
             AgentRole::Po => "po",
             AgentRole::Architect => "architect",
             AgentRole::Developer => "developer",
+            AgentRole::Reviewer => "reviewer",
+            AgentRole::ShellSummarizer => "shell-summarizer",
         }
     }
 
@@ -35,6 +54,8 @@ impl AgentRole {
             AgentRole::Po => "Product Owner",
             AgentRole::Architect => "Architect",
             AgentRole::Developer => "Developer",
+            AgentRole::Reviewer => "Reviewer",
+            AgentRole::ShellSummarizer => "Shell Summarizer",
         }
     }
 }
@@ -48,6 +69,8 @@ impl std::str::FromStr for AgentRole {
             "po" | "product_owner" => Ok(AgentRole::Po),
             "architect" => Ok(AgentRole::Architect),
             "developer" => Ok(AgentRole::Developer),
+            "reviewer" => Ok(AgentRole::Reviewer),
+            "shell-summarizer" | "shell_summarizer" => Ok(AgentRole::ShellSummarizer),
             _ => Err(format!("Unknown agent role: {}", s)),
         }
     }
@@ -92,12 +115,50 @@ pub enum NextAgent {
     Architect,
     Developer,
     User,
+    /// A configured CLI agent, by ID, run with the task as its prompt
+    #[serde(rename = "cli")]
+    CliDelegate(String),
+}
+
+impl NextAgent {
+    /// The name the orchestrator routes with (`"cli"` for any CLI agent)
+    pub fn as_str(&self) -> &str {
+        match self {
+            NextAgent::Po => "po",
+            NextAgent::Architect => "architect",
+            NextAgent::Developer => "developer",
+            NextAgent::User => "user",
+            NextAgent::CliDelegate(_) => "cli",
+        }
+    }
+
+    /// The CLI agent delegated to, if any
+    pub fn cli_agent(&self) -> Option<&str> {
+        match self {
+            NextAgent::CliDelegate(agent) => Some(agent),
+            _ => None,
+        }
+    }
+
+    /// `name` as routed, with the CLI agent given alongside `"cli"`
+    pub fn from_route(name: &str, cli_agent: Option<&str>) -> Option<Self> {
+        match (name.to_lowercase().as_str(), cli_agent) {
+            ("cli", Some(agent)) if !agent.is_empty() => {
+                Some(NextAgent::CliDelegate(agent.to_string()))
+            }
+            _ => name.parse().ok(),
+        }
+    }
 }
 
 impl std::str::FromStr for NextAgent {
     type Err = String;
 
+    /// A role name, or `cli:<agent>` for a CLI agent
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(agent) = s.strip_prefix("cli:").filter(|agent| !agent.is_empty()) {
+            return Ok(NextAgent::CliDelegate(agent.to_string()));
+        }
         match s.to_lowercase().as_str() {
             "po" | "product_owner" => Ok(NextAgent::Po),
             "architect" => Ok(NextAgent::Architect),
@@ -117,16 +178,47 @@ pub enum AgentOperation {
         path: PathBuf,
         content: String,
     },
+    /// Apply a unified diff and/or search/replace edits to an existing file
+    Patch {
+        path: PathBuf,
+        #[serde(default)]
+        diff: Option<String>,
+        #[serde(default)]
+        edits: Vec<TextEdit>,
+    },
+    /// Rename or move a file
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+    },
     /// Delete a file
     Delete {
         path: PathBuf,
     },
+    /// Rename a symbol and every reference to it across the workspace
+    RenameSymbol {
+        symbol: String,
+        new_name: String,
+    },
     /// Execute a shell command
     Execute {
         command: String,
     },
 }
 
+impl AgentOperation {
+    /// File the operation leaves changed (the destination of a rename)
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            AgentOperation::Write { path, .. }
+            | AgentOperation::Patch { path, .. }
+            | AgentOperation::Delete { path } => Some(path),
+            AgentOperation::Rename { to, .. } => Some(to),
+            AgentOperation::RenameSymbol { .. } | AgentOperation::Execute { .. } => None,
+        }
+    }
+}
+
 /// Response from the developer agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeveloperResponse {
@@ -201,6 +293,9 @@ pub struct ProviderConfig {
     pub base_url: Option<String>,
     pub default_model: String,
     pub enabled: bool,
+    /// Headers sent with every request to an OpenAI-compatible endpoint
+    #[serde(default)]
+    pub extra_headers: HashMap<String, String>,
 }
 
 impl LlmSettings {
@@ -235,32 +330,25 @@ impl LlmSettings {
                 base_url: p.base_url.clone(),
                 default_model: p.default_model.clone().unwrap_or_default(),
                 enabled: p.enabled,
+                extra_headers: p.extra_headers.clone(),
             })
             .collect();
 
-        // Map all agents to the default provider
-        let agent_mappings = vec![
-            AgentMapping {
-                agent_id: AgentRole::Orchestrator,
-                provider_id: default_provider.clone(),
-                model_id: default_model.clone(),
-            },
-            AgentMapping {
-                agent_id: AgentRole::Po,
-                provider_id: default_provider.clone(),
-                model_id: default_model.clone(),
-            },
-            AgentMapping {
-                agent_id: AgentRole::Architect,
-                provider_id: default_provider.clone(),
-                model_id: default_model.clone(),
-            },
-            AgentMapping {
-                agent_id: AgentRole::Developer,
-                provider_id: default_provider.clone(),
-                model_id: default_model.clone(),
-            },
-        ];
+        // Map agents to the default provider unless configured otherwise
+        let agent_mappings = AgentRole::ALL
+            .into_iter()
+            .map(|role| {
+                let (provider_id, model_id) = match config.llm.agents.get(role.as_str()) {
+                    Some(agent) => (agent.provider.clone(), agent.model.clone()),
+                    None => (default_provider.clone(), default_model.clone()),
+                };
+                AgentMapping {
+                    agent_id: role,
+                    provider_id,
+                    model_id,
+                }
+            })
+            .collect();
 
         Self {
             providers,
@@ -280,6 +368,7 @@ impl Default for LlmSettings {
                     base_url: None,
                     default_model: "gpt-4o".to_string(),
                     enabled: false,
+                    extra_headers: HashMap::new(),
                 },
                 ProviderConfig {
                     id: "anthropic".to_string(),
@@ -288,6 +377,7 @@ impl Default for LlmSettings {
                     base_url: None,
                     default_model: "claude-sonnet-4-20250514".to_string(),
                     enabled: false,
+                    extra_headers: HashMap::new(),
                 },
                 ProviderConfig {
                     id: "gemini".to_string(),
@@ -296,6 +386,7 @@ impl Default for LlmSettings {
                     base_url: None,
                     default_model: "gemini-1.5-pro".to_string(),
                     enabled: false,
+                    extra_headers: HashMap::new(),
                 },
                 ProviderConfig {
                     id: "ollama".to_string(),
@@ -304,30 +395,17 @@ impl Default for LlmSettings {
                     base_url: Some("http://localhost:11434".to_string()),
                     default_model: "llama3".to_string(),
                     enabled: false,
+                    extra_headers: HashMap::new(),
                 },
             ],
-            agent_mappings: vec![
-                AgentMapping {
-                    agent_id: AgentRole::Orchestrator,
-                    provider_id: "openai".to_string(),
-                    model_id: "gpt-4o".to_string(),
-                },
-                AgentMapping {
-                    agent_id: AgentRole::Po,
+            agent_mappings: AgentRole::ALL
+                .into_iter()
+                .map(|role| AgentMapping {
+                    agent_id: role,
                     provider_id: "openai".to_string(),
                     model_id: "gpt-4o".to_string(),
-                },
-                AgentMapping {
-                    agent_id: AgentRole::Architect,
-                    provider_id: "openai".to_string(),
-                    model_id: "gpt-4o".to_string(),
-                },
-                AgentMapping {
-                    agent_id: AgentRole::Developer,
-                    provider_id: "openai".to_string(),
-                    model_id: "gpt-4o".to_string(),
-                },
-            ],
+                })
+                .collect(),
         }
     }
 }
@@ -0,0 +1,410 @@
+//! Outcome verification after a Developer run
+//!
+//! A Developer saying it's done doesn't make the code build. Workspaces list
+//! checks under `[verify]` in `.axiom/config.toml` (a build, a test task, a
+//! linter); once a run's operations are applied they run in order, stopping
+//! at the first failure. A failure goes back to the Developer with the
+//! check's output for a repair, and the checks run again, up to
+//! `max_repairs` times.
+//!
+//! ```toml
+//! [verify]
+//! max_repairs = 2
+//!
+//! [[verify.checks]]
+//! name = "build"
+//! command = "cargo build --all-targets"
+//!
+//! [[verify.checks]]
+//! name = "test"
+//! command = "cargo test"
+//! ```
+
+use super::testgen::tail;
+use crate::agents::{cancel_after, kill_on_cancel, CancelToken};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Output of a failed check kept in its result and sent for repair (its tail)
+const MAX_OUTPUT_CHARS: usize = 6_000;
+
+/// A command that must succeed after the Developer's changes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyCheck {
+    /// Short label, such as `build`, `test` or `lint`
+    pub name: String,
+    /// Run with `sh -c` in the workspace
+    pub command: String,
+}
+
+/// Checks of a workspace (`[verify]`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyConfig {
+    /// Checks in the order they run; none turns verification off
+    #[serde(default)]
+    pub checks: Vec<VerifyCheck>,
+
+    /// Times a failure is handed back to the Developer
+    #[serde(default = "default_max_repairs")]
+    pub max_repairs: usize,
+
+    /// Seconds before a check is stopped; 0 for no limit
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_max_repairs() -> usize {
+    2
+}
+
+fn default_timeout() -> u64 {
+    600
+}
+
+impl Default for VerifyConfig {
+    fn default() -> Self {
+        Self {
+            checks: Vec::new(),
+            max_repairs: default_max_repairs(),
+            timeout_secs: default_timeout(),
+        }
+    }
+}
+
+impl VerifyConfig {
+    /// Whether there is anything to run
+    pub fn is_enabled(&self) -> bool {
+        !self.checks.is_empty()
+    }
+}
+
+/// Result of one check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub command: String,
+    pub success: bool,
+    /// None when the command didn't start or was stopped
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_secs: u64,
+    /// The end of the combined stdout and stderr
+    pub output: String,
+}
+
+impl CheckResult {
+    /// One line: name, outcome and duration
+    pub fn describe(&self) -> String {
+        let outcome = match (self.success, self.timed_out, self.exit_code) {
+            (true, _, _) => "passed".to_string(),
+            (false, true, _) => "timed out".to_string(),
+            (false, false, Some(code)) => format!("failed with exit code {}", code),
+            (false, false, None) => "failed".to_string(),
+        };
+        format!("{}: {} ({}s)", self.name, outcome, self.duration_secs)
+    }
+}
+
+/// Results of one pass over the checks
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyReport {
+    /// Checks that ran; those after a failure don't
+    pub checks: Vec<CheckResult>,
+}
+
+impl VerifyReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.success)
+    }
+
+    /// The check that failed, if any
+    pub fn failure(&self) -> Option<&CheckResult> {
+        self.checks.iter().find(|check| !check.success)
+    }
+}
+
+/// Verification of a run, with the repairs it took
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Verification {
+    /// One report per pass; the last is the outcome
+    pub attempts: Vec<VerifyReport>,
+    /// Failures handed back to the Developer
+    pub repairs: usize,
+    pub passed: bool,
+}
+
+impl Verification {
+    /// The outcome as a message for the conversation
+    pub fn summary(&self) -> String {
+        let outcome = if self.passed { "passed" } else { "failed" };
+        let mut text = match self.repairs {
+            0 => format!("Verification {}", outcome),
+            n => format!("Verification {} after {} repair(s)", outcome, n),
+        };
+        let Some(last) = self.attempts.last() else {
+            return text;
+        };
+        for check in &last.checks {
+            text.push_str(&format!("\n  {}", check.describe()));
+        }
+        if let Some(failure) = last.failure() {
+            text.push_str(&format!(
+                "\n\nOutput of `{}`:\n{}",
+                failure.command,
+                tail(&failure.output, 2_000)
+            ));
+        }
+        text
+    }
+}
+
+/// Run `checks` in order in `root`, stopping at the first failure
+///
+/// A check still running after `timeout` (unless zero) is stopped, along
+/// with everything it started.
+pub fn run_checks(root: &Path, checks: &[VerifyCheck], timeout: Duration) -> VerifyReport {
+    let mut report = VerifyReport::default();
+    for check in checks {
+        let result = run_check(root, check, timeout);
+        let failed = !result.success;
+        report.checks.push(result);
+        if failed {
+            break;
+        }
+    }
+    report
+}
+
+fn run_check(root: &Path, check: &VerifyCheck, timeout: Duration) -> CheckResult {
+    let started = Instant::now();
+    let mut result = CheckResult {
+        name: check.name.clone(),
+        command: check.command.clone(),
+        success: false,
+        exit_code: None,
+        timed_out: false,
+        duration_secs: 0,
+        output: String::new(),
+    };
+
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(&check.command)
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // In its own process group, so a timeout stops the whole build
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+    let child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            result.output = format!("Failed to run: {}", e);
+            return result;
+        }
+    };
+    let stop = CancelToken::new();
+    let _guard = kill_on_cancel(child.id(), stop.clone());
+    let _timer = (!timeout.is_zero()).then(|| cancel_after(stop.clone(), timeout));
+    let output = child.wait_with_output();
+    result.duration_secs = started.elapsed().as_secs();
+
+    match output {
+        Ok(output) => {
+            let text = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            result.timed_out = stop.is_cancelled();
+            result.success = output.status.success() && !result.timed_out;
+            result.exit_code = output.status.code().filter(|_| !result.timed_out);
+            result.output = tail(text.trim(), MAX_OUTPUT_CHARS).to_string();
+        }
+        Err(e) => result.output = format!("Failed to wait for the command: {}", e),
+    }
+    result
+}
+
+/// Task handed to the Developer to repair a failed check
+pub fn repair_task(task: &str, report: &VerifyReport) -> String {
+    let Some(failure) = report.failure() else {
+        return task.to_string();
+    };
+    let mut repair = format!(
+        "{}\n\nYour changes were applied, but the `{}` check failed afterwards. \
+         Fix the cause in the code; don't change, skip or silence the check. \
+         Don't run any commands: the checks are rerun for you.",
+        task, failure.name
+    );
+    if failure.timed_out {
+        repair.push_str("\n\nIt was stopped for running too long.");
+    }
+    repair.push_str(&format!(
+        "\n\nOutput of `{}`:\n```\n{}\n```",
+        failure.command, failure.output
+    ));
+    repair
+}
+
+/// Run the checks of `config` in `root`, and while they fail hand the
+/// failure to `repair` and check again, at most `config.max_repairs` times
+///
+/// `repair` gets the task to send the Developer and returns whether the
+/// Developer changed anything; a repair that changed nothing ends the loop.
+pub fn verify_and_repair(
+    root: &Path,
+    config: &VerifyConfig,
+    task: &str,
+    mut repair: impl FnMut(&str) -> bool,
+) -> Verification {
+    let timeout = Duration::from_secs(config.timeout_secs);
+    let mut attempts = vec![run_checks(root, &config.checks, timeout)];
+    let mut repairs = 0;
+    while let Some(last) = attempts.last().filter(|report| !report.passed()) {
+        if repairs >= config.max_repairs {
+            break;
+        }
+        repairs += 1;
+        if !repair(&repair_task(task, last)) {
+            break;
+        }
+        attempts.push(run_checks(root, &config.checks, timeout));
+    }
+    let passed = attempts.last().is_some_and(VerifyReport::passed);
+    Verification {
+        attempts,
+        repairs,
+        passed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn check(name: &str, command: &str) -> VerifyCheck {
+        VerifyCheck {
+            name: name.to_string(),
+            command: command.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_workspace_config() {
+        let config: crate::workspace::WorkspaceConfig = toml::from_str(
+            "[verify]\nmax_repairs = 1\n\n[[verify.checks]]\nname = \"lint\"\ncommand = \"cargo clippy\"\n",
+        )
+        .unwrap();
+        assert!(config.verify.is_enabled());
+        assert_eq!(config.verify.checks, [check("lint", "cargo clippy")]);
+        assert_eq!(config.verify.max_repairs, 1);
+        assert_eq!(config.verify.timeout_secs, 600);
+
+        let config: crate::workspace::WorkspaceConfig = toml::from_str("").unwrap();
+        assert!(!config.verify.is_enabled());
+    }
+
+    #[test]
+    fn test_checks_stop_at_first_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let checks = [
+            check("build", "echo built"),
+            check("test", "echo 'assertion failed' >&2; exit 3"),
+            check("lint", "touch linted"),
+        ];
+        let report = run_checks(dir.path(), &checks, Duration::from_secs(30));
+
+        assert!(!report.passed());
+        assert_eq!(report.checks.len(), 2);
+        assert!(report.checks[0].success);
+        let failure = report.failure().unwrap();
+        assert_eq!(failure.name, "test");
+        assert_eq!(failure.exit_code, Some(3));
+        assert_eq!(failure.output, "assertion failed");
+        assert!(!dir.path().join("linted").exists());
+
+        let task = repair_task("Add a parser", &report);
+        assert!(task.starts_with("Add a parser\n\n"));
+        assert!(task.contains("the `test` check failed"));
+        assert!(task.contains("assertion failed"));
+    }
+
+    #[test]
+    fn test_slow_check_times_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let started = Instant::now();
+        let report = run_checks(
+            dir.path(),
+            &[check("test", "sleep 30")],
+            Duration::from_secs(1),
+        );
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        let failure = report.failure().unwrap();
+        assert!(failure.timed_out);
+        assert_eq!(failure.exit_code, None);
+        assert!(failure.describe().contains("timed out"));
+    }
+
+    #[test]
+    fn test_repair_until_checks_pass() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VerifyConfig {
+            checks: vec![check("build", "test -f fixed.txt")],
+            ..Default::default()
+        };
+        let mut tasks = Vec::new();
+        let verification = verify_and_repair(dir.path(), &config, "Fix it", |task| {
+            tasks.push(task.to_string());
+            fs::write(dir.path().join("fixed.txt"), "").unwrap();
+            true
+        });
+
+        assert!(verification.passed);
+        assert_eq!(verification.repairs, 1);
+        assert_eq!(verification.attempts.len(), 2);
+        assert_eq!(tasks.len(), 1);
+        assert!(verification
+            .summary()
+            .starts_with("Verification passed after 1 repair(s)\n  build: passed"));
+    }
+
+    #[test]
+    fn test_repairs_are_bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VerifyConfig {
+            checks: vec![check("build", "exit 1")],
+            max_repairs: 2,
+            timeout_secs: 30,
+        };
+        let mut calls = 0;
+        let verification = verify_and_repair(dir.path(), &config, "Fix it", |_| {
+            calls += 1;
+            true
+        });
+        assert!(!verification.passed);
+        assert_eq!((calls, verification.repairs), (2, 2));
+        assert_eq!(verification.attempts.len(), 3);
+        assert!(verification
+            .summary()
+            .contains("build: failed with exit code 1"));
+
+        // A repair that changes nothing isn't checked again
+        let verification = verify_and_repair(dir.path(), &config, "Fix it", |_| false);
+        assert_eq!(verification.repairs, 1);
+        assert_eq!(verification.attempts.len(), 1);
+
+        // Nothing to repair when the checks pass
+        let verification =
+            verify_and_repair(dir.path(), &VerifyConfig::default(), "Fix it", |_| true);
+        assert!(verification.passed);
+        assert_eq!(verification.repairs, 0);
+    }
+}
@@ -0,0 +1,353 @@
+//! Multi-step plans made by the conductor
+//!
+//! In planning mode the conductor first lays a request out as numbered
+//! steps instead of acting on it. The steps are then handed back to it one
+//! at a time, each marked done or failed once the agents it started have
+//! finished. The plan is kept in `.axiom/plan.json`, where the server reads
+//! its progress from.
+
+use crate::clock::unix_now;
+use crate::error::{AxiomError, Result};
+use crate::types::AgentId;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Most steps taken from a plan; the rest are dropped
+pub const MAX_STEPS: usize = 12;
+
+/// Line the conductor starts its plan with
+const PLAN_MARKER: &str = "@plan";
+
+/// Where a plan is in its steps
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanStepStatus {
+    #[default]
+    Pending,
+    Running,
+    Done,
+    Failed,
+    /// Not run, as an earlier step failed or the plan was cancelled
+    Skipped,
+}
+
+impl PlanStepStatus {
+    /// Whether the step won't run (again)
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            PlanStepStatus::Done | PlanStepStatus::Failed | PlanStepStatus::Skipped
+        )
+    }
+}
+
+impl std::fmt::Display for PlanStepStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PlanStepStatus::Pending => "pending",
+            PlanStepStatus::Running => "running",
+            PlanStepStatus::Done => "done",
+            PlanStepStatus::Failed => "failed",
+            PlanStepStatus::Skipped => "skipped",
+        })
+    }
+}
+
+/// One step of a plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanStep {
+    /// What the step does, as the conductor put it
+    pub title: String,
+    #[serde(default)]
+    pub status: PlanStepStatus,
+    /// Agents started for the step
+    #[serde(default)]
+    pub agents: Vec<AgentId>,
+    /// Unix seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+}
+
+impl PlanStep {
+    fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            status: PlanStepStatus::Pending,
+            agents: Vec::new(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+}
+
+/// A request laid out as steps, run one after the other
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Plan {
+    /// The request the plan is for
+    pub goal: String,
+    pub steps: Vec<PlanStep>,
+    /// Unix seconds
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl Plan {
+    /// A plan for `goal` with every step pending
+    pub fn new<S: Into<String>>(
+        goal: impl Into<String>,
+        titles: impl IntoIterator<Item = S>,
+    ) -> Self {
+        let now = unix_now();
+        Self {
+            goal: goal.into(),
+            steps: titles
+                .into_iter()
+                .map(PlanStep::new)
+                .take(MAX_STEPS)
+                .collect(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// The plan in the conductor's `reply`, if it made one
+    ///
+    /// Steps are the list items after the `@plan` line, or anywhere in the
+    /// reply when it has no such line.
+    pub fn parse(goal: impl Into<String>, reply: &str) -> Option<Self> {
+        let lines: Vec<&str> = reply.lines().map(str::trim).collect();
+        let start = lines
+            .iter()
+            .position(|line| line.eq_ignore_ascii_case(PLAN_MARKER))
+            .map_or(0, |marker| marker + 1);
+        let titles: Vec<String> = lines[start..]
+            .iter()
+            .filter_map(|line| list_item(line))
+            .collect();
+        (!titles.is_empty()).then(|| Self::new(goal, titles))
+    }
+
+    /// Index of the step running, if any
+    pub fn current(&self) -> Option<usize> {
+        self.steps
+            .iter()
+            .position(|step| step.status == PlanStepStatus::Running)
+    }
+
+    /// Start the next pending step and return the prompt for it
+    pub fn start_next(&mut self) -> Option<String> {
+        if self.current().is_some() {
+            return None;
+        }
+        let index = self
+            .steps
+            .iter()
+            .position(|step| step.status == PlanStepStatus::Pending)?;
+        let step = &mut self.steps[index];
+        step.status = PlanStepStatus::Running;
+        step.started_at = Some(unix_now());
+        self.updated_at = unix_now();
+        Some(self.step_prompt(index))
+    }
+
+    /// What the conductor is asked for step `index`
+    pub fn step_prompt(&self, index: usize) -> String {
+        format!(
+            "Step {} of {} of the plan for: {}\n\n{}\n\nOnly do this step; the next one follows once it's done.",
+            index + 1,
+            self.steps.len(),
+            self.goal,
+            self.steps[index].title
+        )
+    }
+
+    /// Note an agent started for the running step
+    pub fn add_agent(&mut self, id: AgentId) {
+        if let Some(index) = self.current() {
+            let agents = &mut self.steps[index].agents;
+            if !agents.contains(&id) {
+                agents.push(id);
+                self.updated_at = unix_now();
+            }
+        }
+    }
+
+    /// Whether `id` was started for the running step
+    pub fn is_running_agent(&self, id: AgentId) -> bool {
+        self.current()
+            .is_some_and(|index| self.steps[index].agents.contains(&id))
+    }
+
+    /// End the running step; a failed step skips the rest of the plan
+    pub fn finish_step(&mut self, failed: bool) {
+        let Some(index) = self.current() else {
+            return;
+        };
+        let now = unix_now();
+        let step = &mut self.steps[index];
+        step.finished_at = Some(now);
+        if failed {
+            step.status = PlanStepStatus::Failed;
+            self.skip_pending();
+        } else {
+            step.status = PlanStepStatus::Done;
+        }
+        self.updated_at = now;
+    }
+
+    /// Stop the plan: the running step failed and the rest are skipped
+    pub fn cancel(&mut self) {
+        self.finish_step(true);
+        self.skip_pending();
+        self.updated_at = unix_now();
+    }
+
+    fn skip_pending(&mut self) {
+        for step in &mut self.steps {
+            if step.status == PlanStepStatus::Pending {
+                step.status = PlanStepStatus::Skipped;
+            }
+        }
+    }
+
+    /// Whether every step has finished
+    pub fn is_finished(&self) -> bool {
+        self.steps.iter().all(|step| step.status.is_finished())
+    }
+
+    /// Whether a step failed
+    pub fn failed(&self) -> bool {
+        self.steps
+            .iter()
+            .any(|step| step.status == PlanStepStatus::Failed)
+    }
+
+    /// Steps done, out of all steps
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self
+            .steps
+            .iter()
+            .filter(|step| step.status == PlanStepStatus::Done)
+            .count();
+        (done, self.steps.len())
+    }
+}
+
+/// The prompt that has the conductor plan `request` instead of acting on it
+pub fn planning_prompt(request: &str) -> String {
+    format!(
+        "Plan this request before doing any of it. Reply with a line `{}` followed by the steps as a numbered list, one line each and at most {}, and don't start any agents yet; each step is handed back to you in turn.\n\n{}",
+        PLAN_MARKER, MAX_STEPS, request
+    )
+}
+
+/// The text of a numbered or bulleted list item
+fn list_item(line: &str) -> Option<String> {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let rest = if digits > 0 {
+        line[digits..].strip_prefix(['.', ')'])?
+    } else {
+        line.strip_prefix(['-', '*'])?
+    };
+    let title = rest.trim().trim_matches('*').trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Where the workspace at `root` keeps its plan
+pub fn plan_path(root: &Path) -> PathBuf {
+    root.join(".axiom").join("plan.json")
+}
+
+/// Keep `plan` as the workspace's plan
+pub fn save(root: &Path, plan: &Plan) -> Result<()> {
+    let path = plan_path(root);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let json =
+        serde_json::to_string_pretty(plan).map_err(|e| AxiomError::Serialization(e.to_string()))?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+/// The workspace's latest plan, if it has one
+pub fn load(root: &Path) -> Result<Option<Plan>> {
+    let path = plan_path(root);
+    let json = match std::fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| AxiomError::Serialization(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plan() {
+        let reply = "Here's how I'd go about it:\n\n@plan\n1. Add a `Config` struct\n2) **Load it at startup**\n- Write tests\n\nShall I start?";
+        let plan = Plan::parse("Add config loading", reply).unwrap();
+        let titles: Vec<&str> = plan.steps.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            ["Add a `Config` struct", "Load it at startup", "Write tests"]
+        );
+        assert!(plan
+            .steps
+            .iter()
+            .all(|s| s.status == PlanStepStatus::Pending));
+
+        // Without the marker, any list counts; without a list there's no plan
+        assert_eq!(Plan::parse("x", "1. One\n2. Two").unwrap().steps.len(), 2);
+        assert!(Plan::parse("x", "Sure, 2024 was a good year.").is_none());
+    }
+
+    #[test]
+    fn test_steps_run_in_order() {
+        let mut plan = Plan::new("Ship it", ["Build", "Test", "Release"]);
+        let prompt = plan.start_next().unwrap();
+        assert!(prompt.starts_with("Step 1 of 3 of the plan for: Ship it"));
+        assert!(prompt.contains("Build"));
+        // Only one step runs at a time
+        assert!(plan.start_next().is_none());
+
+        plan.add_agent(AgentId(4));
+        plan.add_agent(AgentId(4));
+        assert!(plan.is_running_agent(AgentId(4)));
+        assert_eq!(plan.steps[0].agents, [AgentId(4)]);
+
+        plan.finish_step(false);
+        assert_eq!(plan.steps[0].status, PlanStepStatus::Done);
+        assert!(plan.start_next().unwrap().contains("Step 2 of 3"));
+        assert_eq!(plan.progress(), (1, 3));
+        assert!(!plan.is_finished());
+
+        // A failed step skips the rest
+        plan.finish_step(true);
+        assert_eq!(plan.steps[1].status, PlanStepStatus::Failed);
+        assert_eq!(plan.steps[2].status, PlanStepStatus::Skipped);
+        assert!(plan.is_finished() && plan.failed());
+        assert!(plan.start_next().is_none());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(load(dir.path()).unwrap(), None);
+
+        let mut plan = Plan::new("Ship it", ["Build", "Test"]);
+        plan.start_next();
+        plan.cancel();
+        save(dir.path(), &plan).unwrap();
+        let loaded = load(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded, plan);
+        assert_eq!(loaded.steps[0].status, PlanStepStatus::Failed);
+        assert_eq!(loaded.steps[1].status, PlanStepStatus::Skipped);
+    }
+}
@@ -0,0 +1,157 @@
+//! System prompt templates for agent roles
+//!
+//! Each role (`conductor`, `orchestrator`, `developer`) has a built-in
+//! system prompt. A Markdown file in the workspace's `.axiom/prompts/`
+//! replaces it: `<role>.md`, or the template `[prompts]` picks for the role:
+//!
+//! ```toml
+//! [prompts]
+//! developer = "careful-developer"   # .axiom/prompts/careful-developer.md
+//! ```
+//!
+//! Templates may use `{{workspace_name}}`, `{{file_tree}}` and `{{task}}`;
+//! other `{{...}}` text is left as written. Files are read each time a
+//! prompt is built, so edits apply to the next request without a restart.
+
+use crate::config::PromptsConfig;
+use crate::orchestration::get_file_tree;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Role name of the Conductor's prompt
+pub const CONDUCTOR: &str = "conductor";
+
+/// Directory levels `{{file_tree}}` lists
+const FILE_TREE_DEPTH: usize = 3;
+
+/// The prompt templates of a workspace
+///
+/// The default library has no workspace and gives the built-in prompts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptLibrary {
+    /// Workspace whose `.axiom/prompts/` holds the templates
+    workspace: Option<PathBuf>,
+
+    /// Template chosen for each role, by name
+    selection: BTreeMap<String, String>,
+}
+
+impl PromptLibrary {
+    /// Templates in `workspace`, chosen per role by `config`
+    pub fn new(workspace: &Path, config: &PromptsConfig) -> Self {
+        Self {
+            workspace: Some(workspace.to_path_buf()),
+            selection: config.roles.clone(),
+        }
+    }
+
+    /// Directory the templates are read from
+    pub fn dir(&self) -> Option<PathBuf> {
+        self.workspace
+            .as_ref()
+            .map(|workspace| workspace.join(".axiom").join("prompts"))
+    }
+
+    /// Template file for `role`, whether or not it exists
+    pub fn path(&self, role: &str) -> Option<PathBuf> {
+        let name = self.selection.get(role).map_or(role, String::as_str);
+        Some(self.dir()?.join(format!("{}.md", name)))
+    }
+
+    /// The workspace's template for `role`, if it has one
+    pub fn template(&self, role: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(role)?).ok()
+    }
+
+    /// System prompt for `role` working on `task`: the workspace's template
+    /// with its variables filled in, else `builtin`
+    pub fn render(&self, role: &str, builtin: &str, task: &str) -> String {
+        let Some(template) = self.template(role) else {
+            return builtin.to_string();
+        };
+        let workspace = self.workspace.as_deref().unwrap_or(Path::new("."));
+        interpolate(&template, |name| match name {
+            "workspace_name" => Some(
+                workspace
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            ),
+            "file_tree" => Some(get_file_tree(workspace, FILE_TREE_DEPTH).join("\n")),
+            "task" => Some(task.to_string()),
+            _ => None,
+        })
+    }
+}
+
+/// `template` with each `{{name}}` replaced by `value(name)`; names without
+/// a value are left as written
+pub fn interpolate(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = &rest[start + 2..start + 2 + len];
+        text.push_str(&rest[..start]);
+        match value(name.trim()) {
+            Some(value) => text.push_str(&value),
+            None => text.push_str(&rest[start..start + len + 4]),
+        }
+        rest = &rest[start + len + 4..];
+    }
+    text.push_str(rest);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        let value = |name: &str| (name == "task").then(|| "fix the build".to_string());
+        assert_eq!(
+            interpolate("Task: {{task}}, {{ task }}; {{unknown}} {{open", value),
+            "Task: fix the build, fix the build; {{unknown}} {{open"
+        );
+    }
+
+    #[test]
+    fn test_workspace_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("shop");
+        let prompts = workspace.join(".axiom").join("prompts");
+        std::fs::create_dir_all(&prompts).unwrap();
+        std::fs::write(workspace.join("main.rs"), "").unwrap();
+
+        // Built-in until the workspace has a template
+        let library = PromptLibrary::new(&workspace, &PromptsConfig::default());
+        assert_eq!(library.render("developer", "Built in", "t"), "Built in");
+        assert_eq!(
+            PromptLibrary::default().render("developer", "Built in", "t"),
+            "Built in"
+        );
+
+        std::fs::write(
+            prompts.join("developer.md"),
+            "Work on {{workspace_name}}: {{task}}\n{{file_tree}}",
+        )
+        .unwrap();
+        assert_eq!(
+            library.render("developer", "Built in", "add a test"),
+            "Work on shop: add a test\nmain.rs"
+        );
+
+        // A template picked in the config, which falls back when missing
+        let mut config = PromptsConfig::default();
+        config
+            .roles
+            .insert("developer".to_string(), "terse".to_string());
+        let library = PromptLibrary::new(&workspace, &config);
+        assert_eq!(library.render("developer", "Built in", "t"), "Built in");
+        std::fs::write(prompts.join("terse.md"), "Be brief.").unwrap();
+        assert_eq!(library.render("developer", "Built in", "t"), "Be brief.");
+    }
+}
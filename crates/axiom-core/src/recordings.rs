@@ -0,0 +1,349 @@
+//! Recorded CLI agent sessions
+//!
+//! A CLI agent with a `record` table in its config has its terminal output
+//! written to `.axiom/recordings/<started>-<agent>-<id>.cast` in asciicast
+//! v2 format, so exactly what it did can be replayed with `asciinema play`
+//! or shared as is.
+//!
+//! A recording stops growing at the configured size, and only the newest
+//! `keep` recordings of each agent are kept.
+
+use crate::clock::unix_now;
+use crate::config::RecordingConfig;
+use crate::error::{AxiomError, Result};
+use crate::types::AgentId;
+use crate::workspace::{format_bytes, format_duration};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Extension of recording files
+const EXTENSION: &str = "cast";
+
+/// Writes an agent's terminal output to an asciicast file
+pub struct Recorder {
+    file: File,
+    path: PathBuf,
+    started: Instant,
+    written: u64,
+    max_bytes: u64,
+    /// Start of a UTF-8 character split across reads
+    pending: Vec<u8>,
+    /// Set once the size limit is reached or a write fails
+    stopped: bool,
+}
+
+impl Recorder {
+    /// Start recording `agent` in a `cols` x `rows` terminal, deleting its
+    /// oldest recordings beyond `config.keep`
+    pub fn start(
+        root: &Path,
+        agent: &str,
+        id: AgentId,
+        config: &RecordingConfig,
+        cols: u16,
+        rows: u16,
+    ) -> Result<Self> {
+        let dir = recordings_dir(root);
+        std::fs::create_dir_all(&dir)?;
+        let slug = slug(agent);
+        rotate(&dir, &slug, config.keep.saturating_sub(1))?;
+
+        let started_at = unix_now();
+        let path = dir.join(format!("{}-{}-{}.{}", started_at, slug, id.0, EXTENSION));
+        let mut file = File::create(&path)?;
+        let header = serde_json::json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": started_at,
+            "title": agent,
+        });
+        let header = format!("{}\n", header);
+        file.write_all(header.as_bytes())?;
+        Ok(Self {
+            file,
+            path,
+            started: Instant::now(),
+            written: header.len() as u64,
+            max_bytes: config.max_bytes,
+            pending: Vec::new(),
+            stopped: false,
+        })
+    }
+
+    /// The recording file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record output from the agent
+    pub fn output(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+        // Hold back a character cut off at the end until the rest arrives
+        let complete = match std::str::from_utf8(&self.pending) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => self.pending.len(),
+        };
+        let text = String::from_utf8_lossy(&self.pending[..complete]).into_owned();
+        self.pending.drain(..complete);
+        if !text.is_empty() {
+            self.event("o", &text);
+        }
+    }
+
+    /// Record the terminal being resized
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.event("r", &format!("{}x{}", cols, rows));
+    }
+
+    /// Append an event line, or a marker and nothing more once the file
+    /// would pass the size limit
+    fn event(&mut self, code: &str, data: &str) {
+        if self.stopped {
+            return;
+        }
+        let time = self.started.elapsed().as_secs_f64();
+        let line = format!("{}\n", serde_json::json!([time, code, data]));
+        if self.written + line.len() as u64 > self.max_bytes {
+            self.stopped = true;
+            let marker = serde_json::json!([time, "m", "Recording stopped at the size limit"]);
+            let _ = writeln!(self.file, "{}", marker);
+            return;
+        }
+        match self.file.write_all(line.as_bytes()) {
+            Ok(()) => self.written += line.len() as u64,
+            Err(_) => self.stopped = true,
+        }
+    }
+}
+
+/// What `/recordings` shows about a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingSummary {
+    /// File name, used to download it
+    pub name: String,
+    /// The agent, as in the file name
+    pub agent: String,
+    /// Unix seconds
+    pub started_at: u64,
+    pub size: u64,
+}
+
+impl std::fmt::Display for RecordingSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let age = unix_now().saturating_sub(self.started_at);
+        write!(
+            f,
+            "{}: {}, {}, started {} ago",
+            self.name,
+            self.agent,
+            format_bytes(self.size),
+            format_duration(age)
+        )
+    }
+}
+
+/// Where a workspace's recordings are kept
+pub fn recordings_dir(root: &Path) -> PathBuf {
+    root.join(".axiom").join("recordings")
+}
+
+/// Recordings in the workspace at `root`, newest first
+pub fn list(root: &Path) -> Result<Vec<RecordingSummary>> {
+    let entries = match std::fs::read_dir(recordings_dir(root)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut recordings: Vec<RecordingSummary> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let (started_at, agent) = parse_name(&name)?;
+            Some(RecordingSummary {
+                agent: agent.to_string(),
+                started_at,
+                size: entry.metadata().ok()?.len(),
+                name,
+            })
+        })
+        .collect();
+    recordings.sort_by(|a, b| {
+        b.started_at
+            .cmp(&a.started_at)
+            .then_with(|| b.name.cmp(&a.name))
+    });
+    Ok(recordings)
+}
+
+/// What `/recordings` shows: one recording per line
+pub fn summary(recordings: &[RecordingSummary]) -> String {
+    if recordings.is_empty() {
+        return "No recordings (add a [cli_agents.<agent>.record] table to record an agent)"
+            .to_string();
+    }
+    let lines: Vec<String> = recordings.iter().map(ToString::to_string).collect();
+    lines.join("\n")
+}
+
+/// File of recording `name`; names are limited so they can't leave the
+/// directory
+pub fn recording_path(root: &Path, name: &str) -> Result<PathBuf> {
+    let valid = !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && parse_name(name).is_some();
+    if !valid {
+        return Err(AxiomError::InvalidOperation(format!(
+            "invalid recording name '{}'",
+            name
+        )));
+    }
+    let path = recordings_dir(root).join(name);
+    if !path.is_file() {
+        return Err(AxiomError::not_found(format!("recording '{}'", name)));
+    }
+    Ok(path)
+}
+
+/// Delete the oldest recordings of agent `slug` in `dir` so at most `keep`
+/// remain
+fn rotate(dir: &Path, slug: &str, keep: usize) -> Result<()> {
+    let mut recordings: Vec<(u64, String)> = std::fs::read_dir(dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let (started_at, agent) = parse_name(&name)?;
+            (agent == slug).then(|| (started_at, name.clone()))
+        })
+        .collect();
+    recordings.sort();
+    let excess = recordings.len().saturating_sub(keep);
+    for (_, name) in recordings.into_iter().take(excess) {
+        std::fs::remove_file(dir.join(name))?;
+    }
+    Ok(())
+}
+
+/// The start time and agent of a `<started>-<agent>-<id>.cast` file name
+fn parse_name(name: &str) -> Option<(u64, &str)> {
+    let stem = name.strip_suffix(EXTENSION)?.strip_suffix('.')?;
+    let (rest, id) = stem.rsplit_once('-')?;
+    let (started_at, agent) = rest.split_once('-')?;
+    id.parse::<u64>().ok()?;
+    (!agent.is_empty()).then_some((started_at.parse().ok()?, agent))
+}
+
+/// `name` in lowercase letters, digits and dashes, for file names
+fn slug(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "agent".to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(path: &Path) -> Vec<serde_json::Value> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_records_asciicast() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = RecordingConfig::default();
+        let mut recorder =
+            Recorder::start(dir.path(), "Claude Code", AgentId(3), &config, 80, 24).unwrap();
+
+        // "é" split across two reads
+        recorder.output(b"caf\xc3");
+        recorder.output(b"\xa9\r\n");
+        recorder.resize(100, 30);
+
+        let lines = lines(recorder.path());
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 80);
+        assert_eq!(lines[0]["title"], "Claude Code");
+        assert_eq!(lines[1][1], "o");
+        assert_eq!(lines[1][2], "caf");
+        assert_eq!(lines[2][2], "é\r\n");
+        assert_eq!(lines[3][1], "r");
+        assert_eq!(lines[3][2], "100x30");
+
+        let recordings = list(dir.path()).unwrap();
+        assert_eq!(recordings.len(), 1);
+        assert_eq!(recordings[0].agent, "claude-code");
+        assert!(recordings[0].name.ends_with("-claude-code-3.cast"));
+        let path = recording_path(dir.path(), &recordings[0].name).unwrap();
+        assert_eq!(path, recorder.path());
+    }
+
+    #[test]
+    fn test_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = RecordingConfig {
+            max_bytes: 200,
+            ..Default::default()
+        };
+        let mut recorder =
+            Recorder::start(dir.path(), "gemini", AgentId(1), &config, 80, 24).unwrap();
+        for _ in 0..20 {
+            recorder.output(b"0123456789");
+        }
+
+        let lines = lines(recorder.path());
+        let last = lines.last().unwrap();
+        assert_eq!(last[1], "m");
+        assert!(lines.len() < 10);
+        assert_eq!(lines.iter().filter(|line| line[1] == "m").count(), 1);
+    }
+
+    #[test]
+    fn test_rotation_and_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let recordings = recordings_dir(dir.path());
+        std::fs::create_dir_all(&recordings).unwrap();
+        for (started, agent) in [(100, "claude"), (200, "claude"), (300, "gemini")] {
+            let name = format!("{}-{}-1.cast", started, agent);
+            std::fs::write(recordings.join(name), "{}\n").unwrap();
+        }
+
+        let config = RecordingConfig {
+            keep: 2,
+            ..Default::default()
+        };
+        Recorder::start(dir.path(), "claude", AgentId(2), &config, 80, 24).unwrap();
+        let names: Vec<String> = list(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|recording| recording.name)
+            .collect();
+        assert_eq!(names.len(), 3);
+        assert!(names[0].ends_with("-claude-2.cast"));
+        assert_eq!(names[1..], ["300-gemini-1.cast", "200-claude-1.cast"]);
+
+        assert!(recording_path(dir.path(), "../secret.cast").is_err());
+        assert!(recording_path(dir.path(), "notes.txt").is_err());
+        assert!(matches!(
+            recording_path(dir.path(), "1-claude-9.cast"),
+            Err(AxiomError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+//! Workspace-wide find and replace
+//!
+//! A replace runs in two steps. [`preview_replace`] finds the files the
+//! query matches (with [`search`](crate::search), so the same ignore rules
+//! apply) and describes each change as a unified diff without touching
+//! anything. [`apply_replace`] then writes the files the user kept, refusing
+//! any that changed on disk since the preview.
+//!
+//! The query uses the search's smart case. In a regex replace, `$1` or
+//! `${name}` in the replacement stand for capture groups; a plain replace
+//! inserts the replacement literally.
+
+use crate::error::{AxiomError, Result};
+use crate::orchestration::unified_diff;
+use crate::search::{search_collect, SearchQuery};
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// A file a replace would change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileReplace {
+    /// File path relative to the workspace root
+    pub path: PathBuf,
+
+    /// Matches replaced in the file
+    pub replacements: usize,
+
+    /// Unified diff of the change
+    pub diff: String,
+
+    /// Fingerprint of the content previewed, to spot later edits
+    pub fingerprint: String,
+}
+
+/// Result of applying a replace
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplaceOutcome {
+    /// Files written, relative to the workspace root
+    pub applied: Vec<PathBuf>,
+
+    /// Matches replaced across those files
+    pub replacements: usize,
+
+    /// Files left alone because they changed since the preview
+    pub skipped: Vec<PathBuf>,
+}
+
+impl ReplaceOutcome {
+    /// One-line description for status bars and slash command output
+    pub fn summary(&self) -> String {
+        let mut text = format!(
+            "Replaced {} match(es) in {} file(s)",
+            self.replacements,
+            self.applied.len()
+        );
+        if !self.skipped.is_empty() {
+            text.push_str(&format!(
+                "; skipped {} changed since the preview: {}",
+                self.skipped.len(),
+                self.skipped
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        text
+    }
+}
+
+/// Compiled query and replacement
+struct Replacer {
+    pattern: Regex,
+    replacement: String,
+    expand: bool,
+}
+
+impl Replacer {
+    fn new(query: &SearchQuery, replacement: &str) -> Result<Self> {
+        if query.query.is_empty() {
+            return Err(AxiomError::invalid_operation("Search query is empty"));
+        }
+        let source = if query.regex {
+            query.query.clone()
+        } else {
+            regex::escape(&query.query)
+        };
+        let pattern = RegexBuilder::new(&source)
+            .case_insensitive(!query.query.chars().any(char::is_uppercase))
+            .multi_line(true)
+            .build()
+            .map_err(|e| {
+                AxiomError::invalid_operation(format!("Search pattern doesn't parse: {}", e))
+            })?;
+        Ok(Self {
+            pattern,
+            replacement: replacement.to_string(),
+            expand: query.regex,
+        })
+    }
+
+    /// `text` with every match replaced, and how many there were
+    fn apply(&self, text: &str) -> (String, usize) {
+        let count = self.pattern.find_iter(text).count();
+        if count == 0 {
+            return (text.to_string(), 0);
+        }
+        let replaced = if self.expand {
+            self.pattern.replace_all(text, self.replacement.as_str())
+        } else {
+            self.pattern.replace_all(text, NoExpand(&self.replacement))
+        };
+        (replaced.into_owned(), count)
+    }
+}
+
+/// Describe what replacing `query` with `replacement` under `root` would do
+///
+/// Files come back sorted by path. `query.limit` is ignored: every matching
+/// file is previewed.
+pub fn preview_replace(
+    root: &Path,
+    query: &SearchQuery,
+    replacement: &str,
+    extra_ignores: &[String],
+) -> Result<Vec<FileReplace>> {
+    let replacer = Replacer::new(query, replacement)?;
+    let search = SearchQuery {
+        limit: usize::MAX,
+        ..query.clone()
+    };
+    let (hits, _) = search_collect(root, &search, extra_ignores)?;
+    let mut paths: Vec<PathBuf> = hits.into_iter().map(|hit| hit.path).collect();
+    paths.dedup();
+
+    let mut files = Vec::new();
+    for path in paths {
+        let Ok(before) = std::fs::read_to_string(root.join(&path)) else {
+            continue; // not UTF-8: searched lossily, but not safe to rewrite
+        };
+        let (after, replacements) = replacer.apply(&before);
+        if replacements == 0 || after == before {
+            continue;
+        }
+        files.push(FileReplace {
+            diff: unified_diff(&path.to_string_lossy(), &before, &after),
+            fingerprint: fingerprint(&before),
+            path,
+            replacements,
+        });
+    }
+    Ok(files)
+}
+
+/// Write the replacements previewed in `files`
+///
+/// Each file is replaced afresh from its current content; a file whose
+/// content no longer matches its preview is skipped.
+pub fn apply_replace(
+    root: &Path,
+    query: &SearchQuery,
+    replacement: &str,
+    files: &[FileReplace],
+) -> Result<ReplaceOutcome> {
+    let replacer = Replacer::new(query, replacement)?;
+    let mut outcome = ReplaceOutcome::default();
+    for file in files {
+        let full = root.join(&file.path);
+        let current = std::fs::read_to_string(&full).ok();
+        let Some(before) = current.filter(|text| fingerprint(text) == file.fingerprint) else {
+            outcome.skipped.push(file.path.clone());
+            continue;
+        };
+        let (after, replacements) = replacer.apply(&before);
+        std::fs::write(&full, after)?;
+        outcome.applied.push(file.path.clone());
+        outcome.replacements += replacements;
+    }
+    Ok(outcome)
+}
+
+/// Plain-text listing of a preview: each file's diff, then the totals
+pub fn replace_summary(files: &[FileReplace]) -> String {
+    if files.is_empty() {
+        return "No matches".to_string();
+    }
+    let mut text = String::new();
+    for file in files {
+        text.push_str(&file.diff);
+        text.push('\n');
+    }
+    text.push_str(&format!(
+        "{} match(es) in {} file(s)",
+        files.iter().map(|f| f.replacements).sum::<usize>(),
+        files.len()
+    ));
+    text
+}
+
+fn fingerprint(text: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "fn old_name() {}\nfn caller() { old_name(); }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("src/main.rs"),
+            "fn main() { Old_Name(); }\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "nothing here\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_preview_then_apply() {
+        let dir = workspace();
+        let query = SearchQuery::new("old_name");
+        let files = preview_replace(dir.path(), &query, "new_name", &[]).unwrap();
+
+        // Smart case: a lowercase query matches any case
+        let paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [PathBuf::from("src/lib.rs"), PathBuf::from("src/main.rs")]
+        );
+        assert_eq!(files[0].replacements, 2);
+        assert!(files[0].diff.contains("\n-fn old_name() {}\n"));
+        assert!(files[0].diff.contains("\n+fn new_name() {}\n"));
+        // Nothing is written by the preview
+        let lib = std::fs::read_to_string(dir.path().join("src/lib.rs")).unwrap();
+        assert!(lib.contains("old_name"));
+
+        // A file edited after the preview is skipped
+        std::fs::write(
+            dir.path().join("src/main.rs"),
+            "fn main() { old_name(); }\n",
+        )
+        .unwrap();
+        let outcome = apply_replace(dir.path(), &query, "new_name", &files).unwrap();
+        assert_eq!(outcome.applied, [PathBuf::from("src/lib.rs")]);
+        assert_eq!(outcome.replacements, 2);
+        assert_eq!(outcome.skipped, [PathBuf::from("src/main.rs")]);
+        let lib = std::fs::read_to_string(dir.path().join("src/lib.rs")).unwrap();
+        assert_eq!(lib, "fn new_name() {}\nfn caller() { new_name(); }\n");
+        assert!(outcome
+            .summary()
+            .contains("skipped 1 changed since the preview"));
+    }
+
+    #[test]
+    fn test_regex_groups_and_literal_replacement() {
+        let dir = workspace();
+        let regex = SearchQuery {
+            regex: true,
+            ..SearchQuery::new(r"fn (\w+)\(\)")
+        };
+        let files = preview_replace(dir.path(), &regex, "pub fn $1()", &[]).unwrap();
+        assert!(files[0].diff.contains("+pub fn old_name() {}"));
+
+        // Outside regex mode `$1` is inserted as is
+        let plain = SearchQuery::new("caller");
+        let files = preview_replace(dir.path(), &plain, "$1", &[]).unwrap();
+        assert!(files[0].diff.contains("+fn $1() { old_name(); }"));
+
+        assert!(preview_replace(dir.path(), &SearchQuery::new(""), "x", &[]).is_err());
+    }
+}
@@ -0,0 +1,434 @@
+//! Structured logs of agent runs
+//!
+//! Every agent invocation is appended to `.axiom/logs/runs.jsonl` once it
+//! ends: its prompt, the provider and model behind it, the tool calls and
+//! file operations it made, how long it took and how it ended. A run can be
+//! exported as JSON or as a Markdown transcript.
+//!
+//! The log is moved to `runs.1.jsonl` once it passes [`MAX_LOG_BYTES`], so
+//! at most two files are kept.
+
+use crate::clock::unix_now;
+use crate::error::{AxiomError, Result};
+use crate::types::AgentId;
+use crate::workspace::{format_duration, utc_date};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Size at which the log is rotated
+pub const MAX_LOG_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Most output kept per run, from its end
+const MAX_OUTPUT_BYTES: usize = 16 * 1024;
+
+/// Name of the current log file
+const LOG_FILE: &str = "runs.jsonl";
+
+/// Name of the log file it is rotated to
+const ROTATED_FILE: &str = "runs.1.jsonl";
+
+/// How a run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl std::fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RunStatus::Completed => "completed",
+            RunStatus::Failed => "failed",
+            RunStatus::Cancelled => "cancelled",
+        })
+    }
+}
+
+/// A tool call made during a run, i.e. an agent it started
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunToolCall {
+    /// The tool, e.g. `Shell` or `Coder`
+    pub tool: String,
+    /// What it was asked to do
+    pub input: String,
+    /// The agent that carried it out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<AgentId>,
+}
+
+/// A file operation made during a run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunOperation {
+    /// `write`, `delete`, ...
+    pub kind: String,
+    /// Relative to the workspace where possible
+    pub path: String,
+    #[serde(default = "default_success")]
+    pub success: bool,
+}
+
+fn default_success() -> bool {
+    true
+}
+
+/// One agent invocation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub agent_id: AgentId,
+    /// Agent name, e.g. `Conductor` or `Claude Code`
+    pub agent: String,
+    /// Agent type label, e.g. `Shell` or the CLI agent's config ID
+    pub agent_type: String,
+    /// The agent that started this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<AgentId>,
+    pub prompt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<RunToolCall>,
+    #[serde(default)]
+    pub operations: Vec<RunOperation>,
+    /// Unix seconds
+    pub started_at: u64,
+    pub duration_ms: u64,
+    pub status: RunStatus,
+    /// Exit code of a CLI agent's process
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The end of what the agent printed during the run
+    #[serde(default)]
+    pub output: String,
+}
+
+impl RunRecord {
+    /// A run of `agent` starting now, completed until finished otherwise
+    pub fn new(
+        agent_id: AgentId,
+        agent: impl Into<String>,
+        agent_type: impl Into<String>,
+        prompt: impl Into<String>,
+    ) -> Self {
+        Self {
+            agent_id,
+            agent: agent.into(),
+            agent_type: agent_type.into(),
+            parent_id: None,
+            prompt: prompt.into(),
+            provider: None,
+            model: None,
+            tool_calls: Vec::new(),
+            operations: Vec::new(),
+            started_at: unix_now(),
+            duration_ms: 0,
+            status: RunStatus::Completed,
+            exit_code: None,
+            error: None,
+            output: String::new(),
+        }
+    }
+
+    /// Set the output, keeping only its last [`MAX_OUTPUT_BYTES`]
+    pub fn set_output(&mut self, output: &str) {
+        let mut start = output.len().saturating_sub(MAX_OUTPUT_BYTES);
+        while !output.is_char_boundary(start) {
+            start += 1;
+        }
+        self.output = output[start..].to_string();
+    }
+}
+
+/// How [`export`] writes a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunLogFormat {
+    #[default]
+    Json,
+    Markdown,
+}
+
+impl FromStr for RunLogFormat {
+    type Err = AxiomError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(RunLogFormat::Json),
+            "markdown" | "md" => Ok(RunLogFormat::Markdown),
+            _ => Err(AxiomError::invalid_operation(format!(
+                "unknown run log format '{}' (json or markdown)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Where a workspace's logs are kept
+pub fn logs_dir(root: &Path) -> PathBuf {
+    root.join(".axiom").join("logs")
+}
+
+/// Append `record` to the run log of the workspace at `root`
+pub fn append(root: &Path, record: &RunRecord) -> Result<()> {
+    let dir = logs_dir(root);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(LOG_FILE);
+    if std::fs::metadata(&path).is_ok_and(|meta| meta.len() >= MAX_LOG_BYTES) {
+        std::fs::rename(&path, dir.join(ROTATED_FILE))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// Up to `limit` logged runs in the workspace at `root`, newest first
+///
+/// Lines that don't parse are skipped.
+pub fn list(root: &Path, limit: usize) -> Result<Vec<RunRecord>> {
+    let dir = logs_dir(root);
+    let mut runs = Vec::new();
+    for name in [LOG_FILE, ROTATED_FILE] {
+        let content = match std::fs::read_to_string(dir.join(name)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let remaining = limit.saturating_sub(runs.len());
+        runs.extend(
+            content
+                .lines()
+                .rev()
+                .filter_map(|line| serde_json::from_str::<RunRecord>(line).ok())
+                .take(remaining),
+        );
+        if runs.len() >= limit {
+            break;
+        }
+    }
+    Ok(runs)
+}
+
+/// The latest logged run of agent `agent_id`
+///
+/// Agent IDs restart with the service, so an older session's run of the
+/// same ID only turns up when there's no newer one.
+pub fn find(root: &Path, agent_id: AgentId) -> Result<RunRecord> {
+    list(root, usize::MAX)?
+        .into_iter()
+        .find(|run| run.agent_id == agent_id)
+        .ok_or_else(|| AxiomError::not_found(format!("run of {}", agent_id)))
+}
+
+/// `record` as pretty JSON or a Markdown transcript
+pub fn export(record: &RunRecord, format: RunLogFormat) -> String {
+    match format {
+        RunLogFormat::Json => serde_json::to_string_pretty(record).unwrap_or_default(),
+        RunLogFormat::Markdown => transcript(record),
+    }
+}
+
+/// A run as Markdown: details, prompt, tool calls, operations and output
+fn transcript(record: &RunRecord) -> String {
+    let mut md = format!("# {} run ({})\n\n", record.agent, record.agent_id);
+    md.push_str(&format!("- Type: {}\n", record.agent_type));
+    if let Some(parent) = record.parent_id {
+        md.push_str(&format!("- Started by: {}\n", parent));
+    }
+    match (&record.provider, &record.model) {
+        (Some(provider), Some(model)) => {
+            md.push_str(&format!("- Model: {} ({})\n", model, provider))
+        }
+        (Some(provider), None) => md.push_str(&format!("- Provider: {}\n", provider)),
+        _ => {}
+    }
+    md.push_str(&format!(
+        "- Started: {} UTC\n",
+        utc_time(record.started_at as i64)
+    ));
+    md.push_str(&format!(
+        "- Duration: {}\n",
+        format_duration(record.duration_ms / 1000)
+    ));
+    md.push_str(&format!("- Status: {}", record.status));
+    if let Some(code) = record.exit_code {
+        md.push_str(&format!(" (exit code {})", code));
+    }
+    md.push('\n');
+    if let Some(error) = &record.error {
+        md.push_str(&format!("- Error: {}\n", error));
+    }
+
+    md.push_str("\n## Prompt\n\n");
+    md.push_str(&fenced(&record.prompt));
+
+    if !record.tool_calls.is_empty() {
+        md.push_str("\n## Tool calls\n\n");
+        for (i, call) in record.tool_calls.iter().enumerate() {
+            md.push_str(&format!("{}. **{}**", i + 1, call.tool));
+            if let Some(id) = call.agent_id {
+                md.push_str(&format!(" ({})", id));
+            }
+            md.push_str(&format!(": `{}`\n", one_line(&call.input)));
+        }
+    }
+
+    if !record.operations.is_empty() {
+        md.push_str("\n## Operations\n\n");
+        for op in &record.operations {
+            let failed = if op.success { "" } else { " (failed)" };
+            md.push_str(&format!("- {} `{}`{}\n", op.kind, op.path, failed));
+        }
+    }
+
+    if !record.output.trim().is_empty() {
+        md.push_str("\n## Output\n\n");
+        md.push_str(&fenced(record.output.trim_end()));
+    }
+    md
+}
+
+/// `text` in a code fence longer than any backtick run inside it
+fn fenced(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{}text\n{}\n{}\n", fence, text, fence)
+}
+
+/// The first line of `text`, cut to 120 characters
+fn one_line(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().replace('`', "'");
+    match line.char_indices().nth(120) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line,
+    }
+}
+
+/// `YYYY-MM-DD HH:MM:SS` of a Unix timestamp
+fn utc_time(timestamp: i64) -> String {
+    let secs = timestamp.rem_euclid(86_400);
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        utc_date(timestamp),
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: u64, prompt: &str) -> RunRecord {
+        let mut record = RunRecord::new(AgentId(id), "Conductor", "Conductor", prompt);
+        record.started_at = 1_700_000_000;
+        record
+    }
+
+    #[test]
+    fn test_append_and_list() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(list(dir.path(), 10).unwrap().is_empty());
+
+        for id in 1..=3 {
+            append(dir.path(), &record(id, &format!("task {}", id))).unwrap();
+        }
+        // A broken line doesn't hide the rest
+        let path = logs_dir(dir.path()).join(LOG_FILE);
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        writeln!(file, "{{not json").unwrap();
+        append(dir.path(), &record(1, "again")).unwrap();
+
+        let runs = list(dir.path(), 10).unwrap();
+        let prompts: Vec<&str> = runs.iter().map(|run| run.prompt.as_str()).collect();
+        assert_eq!(prompts, ["again", "task 3", "task 2", "task 1"]);
+        assert_eq!(list(dir.path(), 2).unwrap().len(), 2);
+
+        assert_eq!(find(dir.path(), AgentId(1)).unwrap().prompt, "again");
+        assert!(matches!(
+            find(dir.path(), AgentId(9)),
+            Err(AxiomError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_rotation_keeps_older_runs_listed() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = logs_dir(dir.path());
+        std::fs::create_dir_all(&logs).unwrap();
+        // A run, then padding past the limit
+        let old = serde_json::to_string(&record(1, "old")).unwrap();
+        let padding = " ".repeat(MAX_LOG_BYTES as usize);
+        std::fs::write(logs.join(LOG_FILE), format!("{}\n{}\n", old, padding)).unwrap();
+        append(dir.path(), &record(2, "new")).unwrap();
+
+        assert!(logs.join(ROTATED_FILE).is_file());
+        let prompts: Vec<String> = list(dir.path(), 10)
+            .unwrap()
+            .into_iter()
+            .map(|run| run.prompt)
+            .collect();
+        assert_eq!(prompts, ["new", "old"]);
+    }
+
+    #[test]
+    fn test_output_keeps_the_end() {
+        let mut run = record(1, "task");
+        let output = format!("{}é{}", "a".repeat(MAX_OUTPUT_BYTES), "tail");
+        run.set_output(&output);
+        assert!(run.output.len() <= MAX_OUTPUT_BYTES);
+        assert!(run.output.ends_with("étail"));
+    }
+
+    #[test]
+    fn test_export() {
+        let mut run = record(4, "Add a `parse` function");
+        run.provider = Some("claude".to_string());
+        run.model = Some("claude-sonnet".to_string());
+        run.duration_ms = 65_000;
+        run.tool_calls.push(RunToolCall {
+            tool: "Coder".to_string(),
+            input: "src/parse.rs|fn parse() {}".to_string(),
+            agent_id: Some(AgentId(5)),
+        });
+        run.operations.push(RunOperation {
+            kind: "write".to_string(),
+            path: "src/parse.rs".to_string(),
+            success: true,
+        });
+        run.set_output("```rust\nfn parse() {}\n```");
+
+        let json: RunRecord = serde_json::from_str(&export(&run, RunLogFormat::Json)).unwrap();
+        assert_eq!(json, run);
+
+        let md = export(&run, RunLogFormat::Markdown);
+        assert!(md.starts_with("# Conductor run (agent-4)\n"));
+        assert!(md.contains("- Model: claude-sonnet (claude)\n"));
+        assert!(md.contains("- Started: 2023-11-14 22:13:20 UTC\n"));
+        assert!(md.contains("- Duration: 1m 05s\n"));
+        assert!(md.contains("- Status: completed\n"));
+        assert!(md.contains("1. **Coder** (agent-5): `src/parse.rs|fn parse() {}`\n"));
+        assert!(md.contains("- write `src/parse.rs`\n"));
+        // The output's own fence doesn't close the transcript's
+        assert!(md.contains("````text\n```rust\n"));
+
+        assert_eq!(
+            "md".parse::<RunLogFormat>().unwrap(),
+            RunLogFormat::Markdown
+        );
+        assert!("pdf".parse::<RunLogFormat>().is_err());
+    }
+}
@@ -0,0 +1,445 @@
+//! Workspace-wide text search
+//!
+//! Walks the workspace in parallel with the `ignore` crate (so nested
+//! `.gitignore`/`.ignore` files are honoured, as in ripgrep) and also skips
+//! what the file watcher skips: the built-in defaults and the workspace's
+//! `ignore_patterns` (see [`IgnoreRules`]). Each file is searched line by
+//! line with `grep-searcher`; binary files are skipped.
+//!
+//! Hits are handed to a callback as they're found, so UIs can show them
+//! while the search runs. [`AxiomService`](crate::AxiomService) streams them
+//! as [`Notification::SearchResult`](crate::Notification::SearchResult);
+//! the TUI reads them from a [`SearchHandle`].
+
+use crate::error::{AxiomError, Result};
+use crate::watcher::IgnoreRules;
+use crossbeam_channel::{unbounded, Receiver};
+use grep_matcher::Matcher;
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
+use grep_searcher::{sinks::Lossy, BinaryDetection, SearcherBuilder};
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::{WalkBuilder, WalkState};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Hits returned when a query doesn't set its own limit
+pub const DEFAULT_LIMIT: usize = 500;
+
+/// Longest line text kept in a hit, in characters
+const MAX_LINE_CHARS: usize = 240;
+
+/// What to search for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchQuery {
+    /// Text to find, or a regex when `regex` is set
+    ///
+    /// Smart case: case-insensitive unless it contains an uppercase letter.
+    pub query: String,
+
+    /// Treat `query` as a regular expression
+    #[serde(default)]
+    pub regex: bool,
+
+    /// Globs limiting the files searched, e.g. `*.rs`; `!glob` excludes
+    #[serde(default)]
+    pub globs: Vec<String>,
+
+    /// Stop after this many hits
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+impl SearchQuery {
+    /// A plain-text query with no globs
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            regex: false,
+            globs: Vec::new(),
+            limit: DEFAULT_LIMIT,
+        }
+    }
+
+    fn matcher(&self) -> Result<RegexMatcher> {
+        if self.query.is_empty() {
+            return Err(AxiomError::invalid_operation("Search query is empty"));
+        }
+        RegexMatcherBuilder::new()
+            .case_smart(true)
+            .fixed_strings(!self.regex)
+            .build(&self.query)
+            .map_err(|e| {
+                AxiomError::invalid_operation(format!("Search pattern doesn't parse: {}", e))
+            })
+    }
+
+    fn overrides(&self, root: &Path) -> Result<Override> {
+        let mut builder = OverrideBuilder::new(root);
+        for glob in &self.globs {
+            builder.add(glob).map_err(|e| {
+                AxiomError::invalid_operation(format!("Search glob doesn't parse: {}", e))
+            })?;
+        }
+        builder
+            .build()
+            .map_err(|e| AxiomError::invalid_operation(format!("Search glob doesn't parse: {}", e)))
+    }
+}
+
+/// A matching line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// File path relative to the workspace root
+    pub path: PathBuf,
+
+    /// Line number, from 1
+    pub line: u64,
+
+    /// Character column of the first match on the line, from 1
+    pub column: usize,
+
+    /// The line, without its line ending (long lines are cut short)
+    pub text: String,
+}
+
+/// How a search ended
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchSummary {
+    /// Hits reported
+    pub matches: usize,
+
+    /// Files with at least one hit
+    pub files: usize,
+
+    /// Stopped at the query's limit
+    pub truncated: bool,
+
+    /// Stopped because it was cancelled
+    pub cancelled: bool,
+}
+
+/// Search the files under `root` for `query`, calling `on_hit` for each hit
+///
+/// `extra_ignores` are the workspace's `ignore_patterns`. Hits arrive in
+/// no particular order, from several threads. Setting `cancel` stops the
+/// search after the files being searched.
+pub fn search_workspace(
+    root: &Path,
+    query: &SearchQuery,
+    extra_ignores: &[String],
+    cancel: &AtomicBool,
+    on_hit: impl Fn(SearchHit) + Sync,
+) -> Result<SearchSummary> {
+    let matcher = query.matcher()?;
+    let overrides = query.overrides(root)?;
+    let rules = IgnoreRules::load(root, extra_ignores);
+    let filter_root = root.to_path_buf();
+
+    let walker = WalkBuilder::new(root)
+        .require_git(false)
+        .overrides(overrides)
+        .filter_entry(move |entry| {
+            let Ok(relative) = entry.path().strip_prefix(&filter_root) else {
+                return true;
+            };
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            !rules.is_ignored(&relative.to_string_lossy().replace('\\', "/"), is_dir)
+        })
+        .build_parallel();
+
+    let matches = AtomicUsize::new(0);
+    let files = AtomicUsize::new(0);
+    let truncated = AtomicBool::new(false);
+    let limit = query.limit.max(1);
+
+    walker.run(|| {
+        let matcher = matcher.clone();
+        let mut searcher = SearcherBuilder::new()
+            .binary_detection(BinaryDetection::quit(b'\x00'))
+            .line_number(true)
+            .build();
+        let (on_hit, matches, files, truncated) = (&on_hit, &matches, &files, &truncated);
+        Box::new(move |entry| {
+            if cancel.load(Ordering::Relaxed) || truncated.load(Ordering::Relaxed) {
+                return WalkState::Quit;
+            }
+            let Ok(entry) = entry else {
+                return WalkState::Continue;
+            };
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                return WalkState::Continue;
+            }
+            let path = entry.path();
+            let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+            let mut found = false;
+            // Unreadable files are skipped like binary ones
+            let _ = searcher.search_path(
+                &matcher,
+                path,
+                Lossy(|line, text| {
+                    if matches.fetch_add(1, Ordering::Relaxed) >= limit {
+                        truncated.store(true, Ordering::Relaxed);
+                        return Ok(false);
+                    }
+                    found = true;
+                    on_hit(hit(&matcher, relative.clone(), line, text));
+                    Ok(!cancel.load(Ordering::Relaxed))
+                }),
+            );
+            if found {
+                files.fetch_add(1, Ordering::Relaxed);
+            }
+            WalkState::Continue
+        })
+    });
+
+    Ok(SearchSummary {
+        matches: matches.into_inner().min(limit),
+        files: files.into_inner(),
+        truncated: truncated.into_inner(),
+        cancelled: cancel.load(Ordering::Relaxed),
+    })
+}
+
+/// Run a search to completion and return its hits sorted by path and line
+pub fn search_collect(
+    root: &Path,
+    query: &SearchQuery,
+    extra_ignores: &[String],
+) -> Result<(Vec<SearchHit>, SearchSummary)> {
+    let hits = parking_lot::Mutex::new(Vec::new());
+    let summary = search_workspace(root, query, extra_ignores, &AtomicBool::new(false), |hit| {
+        hits.lock().push(hit)
+    })?;
+    let mut hits = hits.into_inner();
+    hits.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    Ok((hits, summary))
+}
+
+/// Plain-text listing of a finished search, one `path:line:column: text` per hit
+pub fn search_summary(hits: &[SearchHit], summary: &SearchSummary) -> String {
+    if hits.is_empty() {
+        return "No matches".to_string();
+    }
+    let mut text = String::new();
+    for hit in hits {
+        text.push_str(&format!(
+            "{}:{}:{}: {}\n",
+            hit.path.display(),
+            hit.line,
+            hit.column,
+            hit.text.trim()
+        ));
+    }
+    text.push_str(&format!(
+        "\n{} match(es) in {} file(s){}",
+        summary.matches,
+        summary.files,
+        if summary.truncated {
+            " (limit reached)"
+        } else {
+            ""
+        }
+    ));
+    text
+}
+
+fn hit(matcher: &RegexMatcher, path: PathBuf, line: u64, text: &str) -> SearchHit {
+    let text = text.trim_end_matches(['\n', '\r']);
+    let start = matcher
+        .find(text.as_bytes())
+        .ok()
+        .flatten()
+        .map_or(0, |m| m.start());
+    let column = text.get(..start).map_or(0, |before| before.chars().count()) + 1;
+    let text = match text.char_indices().nth(MAX_LINE_CHARS) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    };
+    SearchHit {
+        path,
+        line,
+        column,
+        text,
+    }
+}
+
+/// Progress of a search running in the background
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchUpdate {
+    Hit(SearchHit),
+    Done(SearchSummary),
+    Failed(String),
+}
+
+/// A search running on its own thread; dropping the handle cancels it
+pub struct SearchHandle {
+    updates: Receiver<SearchUpdate>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl SearchHandle {
+    /// Start searching `root` for `query`
+    pub fn spawn(root: PathBuf, query: SearchQuery, extra_ignores: Vec<String>) -> Self {
+        let (tx, updates) = unbounded();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let flag = cancel.clone();
+        std::thread::spawn(move || {
+            let result = search_workspace(&root, &query, &extra_ignores, &flag, |hit| {
+                let _ = tx.send(SearchUpdate::Hit(hit));
+            });
+            let _ = tx.send(match result {
+                Ok(summary) => SearchUpdate::Done(summary),
+                Err(e) => SearchUpdate::Failed(e.to_string()),
+            });
+        });
+        Self { updates, cancel }
+    }
+
+    /// Updates received so far, without waiting
+    pub fn poll(&self) -> Vec<SearchUpdate> {
+        self.updates.try_iter().collect()
+    }
+
+    /// Stop the search
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for SearchHandle {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::create_dir_all(root.join("generated")).unwrap();
+        std::fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+        std::fs::write(
+            root.join("src/main.rs"),
+            "fn main() {\n    // TODO: parse args\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// todo later\npub fn todo_list() {}\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("notes.md"), "TODO write docs\n").unwrap();
+        std::fs::write(root.join("target/debug/out.rs"), "// TODO built\n").unwrap();
+        std::fs::write(root.join("generated/api.rs"), "// TODO generated\n").unwrap();
+        std::fs::write(root.join("data.bin"), b"TODO\x00\x01binary").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_search_skips_ignored_and_binary_files() {
+        let dir = workspace();
+        let (hits, summary) = search_collect(dir.path(), &SearchQuery::new("todo"), &[]).unwrap();
+        let found: Vec<_> = hits
+            .iter()
+            .map(|h| (h.path.to_string_lossy().replace('\\', "/"), h.line))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("notes.md".to_string(), 1),
+                ("src/lib.rs".to_string(), 1),
+                ("src/lib.rs".to_string(), 2),
+                ("src/main.rs".to_string(), 2),
+            ]
+        );
+        assert_eq!(summary.matches, 4);
+        assert_eq!(summary.files, 3);
+        assert!(!summary.truncated);
+
+        let main = &hits[3];
+        assert_eq!(main.text, "    // TODO: parse args");
+        assert_eq!(main.column, 8);
+        assert!(search_summary(&hits, &summary).starts_with("notes.md:1:1: TODO write docs\n"));
+
+        // An uppercase letter makes the query case-sensitive
+        let (hits, _) = search_collect(dir.path(), &SearchQuery::new("TODO"), &[]).unwrap();
+        assert_eq!(hits.len(), 2);
+
+        // Workspace ignore patterns apply too
+        let (hits, _) =
+            search_collect(dir.path(), &SearchQuery::new("todo"), &["*.md".to_string()]).unwrap();
+        assert_eq!(hits.len(), 3);
+    }
+
+    #[test]
+    fn test_regex_globs_and_limit() {
+        let dir = workspace();
+        let query = SearchQuery {
+            regex: true,
+            globs: vec!["*.rs".to_string()],
+            ..SearchQuery::new(r"fn \w+\(")
+        };
+        let (hits, _) = search_collect(dir.path(), &query, &[]).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].column, 5);
+
+        // Without `regex` the pattern is literal
+        let literal = SearchQuery {
+            regex: false,
+            ..query.clone()
+        };
+        assert!(search_collect(dir.path(), &literal, &[])
+            .unwrap()
+            .0
+            .is_empty());
+
+        let limited = SearchQuery {
+            limit: 1,
+            ..SearchQuery::new("todo")
+        };
+        let (hits, summary) = search_collect(dir.path(), &limited, &[]).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(summary.truncated);
+
+        let bad = SearchQuery {
+            regex: true,
+            ..SearchQuery::new("fn (")
+        };
+        assert!(search_collect(dir.path(), &bad, &[]).is_err());
+        assert!(search_collect(dir.path(), &SearchQuery::new(""), &[]).is_err());
+    }
+
+    #[test]
+    fn test_search_handle_streams_updates() {
+        let dir = workspace();
+        let handle = SearchHandle::spawn(
+            dir.path().to_path_buf(),
+            SearchQuery::new("todo"),
+            Vec::new(),
+        );
+        let mut hits = 0;
+        loop {
+            match handle.updates.recv().unwrap() {
+                SearchUpdate::Hit(_) => hits += 1,
+                SearchUpdate::Done(summary) => {
+                    assert_eq!(summary.matches, hits);
+                    break;
+                }
+                SearchUpdate::Failed(e) => panic!("{}", e),
+            }
+        }
+        assert_eq!(hits, 4);
+    }
+}
@@ -36,22 +36,57 @@
 //! }
 //! ```
 
-use crate::agents::{AgentRegistry, Conductor, Executor, PtyAgentManager};
+use crate::agents::{
+    last_message, last_prompt, touched_files, transcript_context, AgentRegistry, AuthRetry,
+    Conductor, Executor, PtyAgentManager, Transcript,
+};
+use crate::clock::unix_now;
 use crate::commands::Command;
-use crate::config::AxiomConfig;
+use crate::config::{
+    config_path, key_commands_allowed, load_config, user_config_path, AxiomConfig, ConfigChanges,
+};
+use crate::context::{self, ContextBudget};
 use crate::error::{AxiomError, Result};
+use crate::error_codes::ErrorCode;
 use crate::events::Event;
-use crate::llm::ProviderRegistry;
-use crate::notifications::Notification;
+use crate::files;
+use crate::guard::{guard_content, GuardConfig};
+use crate::index::SemanticIndex;
+use crate::llm::{
+    configure_rate_limits, fetch_key, ChatMessage, KeyAction, KeyWatch,
+    ProviderRegistry, SharedProvider,
+};
+use crate::lsp::LspManager;
+use crate::notifications::{
+    HistoryEntry, HistoryFilter, Notification, NotificationHistory, HISTORY_CAPACITY,
+};
+use crate::orchestration::AgentRole;
+use crate::plan;
+use crate::prompts::PromptLibrary;
+use crate::replace::{self, FileReplace};
+use crate::run_log::{self, RunLogFormat, RunOperation, RunRecord, RunStatus, RunToolCall};
+use crate::search::{self, SearchQuery};
+use crate::sessions::{self, SavedAgent, Session};
 use crate::types::{
-    AgentId, AgentSpawnRequest, AgentStatus, AgentType, AgentView, CliAgentInfo, OutputContext,
+    AgentEvent, AgentId, AgentSpawnRequest, AgentStatus, AgentType, AgentView, CliAgentInfo, OutputContext,
     TerminalScreen,
 };
+use crate::usage::{estimate_tokens, UsageRecord};
+use crate::watcher::FileWatcher;
+use crate::workspace::{ApprovalQueue, Feature, ReviewItem, WorkspaceConfig, STALL_AFTER};
 use crossbeam_channel::{bounded, Receiver, Sender};
-use parking_lot::RwLock;
-use std::path::PathBuf;
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often `.axiom/plan.json` is checked for a changed plan
+const PLAN_POLL: Duration = Duration::from_secs(1);
+
+/// A service shared between request handlers and its event pump
+pub type SharedService = Arc<Mutex<AxiomService>>;
 
 /// Main backend service facade
 ///
@@ -64,8 +99,8 @@ pub struct AxiomService {
     /// Internal event bus receiver
     event_rx: Receiver<Event>,
 
-    /// Notification sender (to UI)
-    notification_tx: Sender<Notification>,
+    /// Notification sender (to UI), recording what it sends
+    notification_tx: NotificationSender,
 
     /// Notification receiver (for UI to poll)
     notification_rx: Receiver<Notification>,
@@ -93,6 +128,86 @@ pub struct AxiomService {
 
     /// Current output context
     output_context: Arc<RwLock<OutputContext>>,
+
+    /// Watcher for the working directory (None if it couldn't be started)
+    watcher: Option<FileWatcher>,
+
+    /// ID of the latest workspace search
+    search_id: u64,
+
+    /// Cancels the latest workspace search
+    search_cancel: Option<Arc<AtomicBool>>,
+
+    /// Embeddings of the working directory for semantic search
+    index: Arc<SemanticIndex>,
+
+    /// Language servers for the working directory
+    lsp: Arc<LspManager>,
+
+    /// ID of the latest replace preview
+    replace_id: u64,
+
+    /// The latest replace preview, until it is applied
+    pending_replace: Option<PendingReplace>,
+
+    /// Runs in progress, logged to `.axiom/logs/` as they end
+    runs: HashMap<AgentId, ActiveRun>,
+
+    /// The latest input for the conductor, as typed, for its run's log
+    conductor_prompt: Option<String>,
+
+    /// Provider keys being refreshed or reminded about
+    key_watch: KeyWatch,
+
+    /// The provider whose rejected key left the latest prompt unanswered
+    auth_retry: AuthRetry,
+
+    /// Whether the workspace is trusted to run language servers, which
+    /// reloading the config mustn't turn back on, and its own config's
+    /// `api_key_command`s
+    trusted: bool,
+
+    /// Agent operations held for approval; the workspace manager's queue
+    /// when it runs the service
+    approvals: ApprovalQueue,
+
+    /// When the plan file was last seen modified
+    plan_modified: Option<SystemTime>,
+
+    /// When the plan file was last checked
+    plan_checked: Instant,
+}
+
+/// Sends notifications to the UI and keeps the latest for
+/// [`AxiomService::history`]
+#[derive(Clone)]
+struct NotificationSender {
+    tx: Sender<Notification>,
+    history: Arc<Mutex<NotificationHistory>>,
+}
+
+impl NotificationSender {
+    /// Send and record a notification; false once the receiver is gone
+    fn send(&self, notification: Notification) -> bool {
+        self.history.lock().record(&notification);
+        self.tx.send(notification).is_ok()
+    }
+}
+
+/// An agent run being logged
+struct ActiveRun {
+    record: RunRecord,
+    started: Instant,
+    /// Length of the agent's output when the run started
+    output_from: usize,
+}
+
+/// A replace previewed but not yet applied
+struct PendingReplace {
+    id: u64,
+    query: SearchQuery,
+    replacement: String,
+    files: Vec<FileReplace>,
 }
 
 impl AxiomService {
@@ -107,21 +222,41 @@ impl AxiomService {
 
         // Create notification channel for UI
         let (notification_tx, notification_rx) = bounded(1000);
+        let notification_tx = NotificationSender {
+            tx: notification_tx,
+            history: Arc::new(Mutex::new(NotificationHistory::new(HISTORY_CAPACITY))),
+        };
 
         // Create agent registry
         let agent_registry = Arc::new(RwLock::new(AgentRegistry::new()));
 
         // Create PTY manager
-        let pty_manager = Arc::new(RwLock::new(PtyAgentManager::new(event_tx.clone())));
+        let mut pty_manager = PtyAgentManager::new(event_tx.clone());
+        pty_manager.set_scrollback(config.cli_agents.scrollback_lines);
+        let pty_manager = Arc::new(RwLock::new(pty_manager));
 
         // Create LLM provider registry
         let llm_registry = Arc::new(RwLock::new(ProviderRegistry::from_config(&config)));
 
-        // Create conductor
-        let conductor = Arc::new(RwLock::new(Conductor::new(
+        // Create the semantic index, read on first use
+        let ignores = workspace_config(&cwd)
+            .map(|config| config.ignore_patterns)
+            .unwrap_or_default();
+        let index = Arc::new(SemanticIndex::new(
+            &cwd,
+            config.index.clone(),
+            ignores,
             llm_registry.clone(),
-            event_tx.clone(),
-        )));
+        ));
+
+        // Create conductor
+        let mut conductor = Conductor::new(llm_registry.clone(), event_tx.clone())
+            .with_context_config(config.llm.context.clone())
+            .with_prompts(PromptLibrary::new(&cwd, &config.prompts));
+        if config.index.auto_context {
+            conductor = conductor.with_index(index.clone());
+        }
+        let conductor = Arc::new(RwLock::new(conductor));
 
         // Create executor
         let executor = Arc::new(RwLock::new(Executor::new(
@@ -133,6 +268,10 @@ impl AxiomService {
         // Initialize LLM providers based on config
         Self::init_llm_providers(&config, &llm_registry);
 
+        let watcher = Self::start_watcher(&cwd, &notification_tx);
+        let lsp = Self::start_lsp(&cwd, &config, &notification_tx);
+        let plan_seen = plan_modified(&cwd);
+
         Ok(Self {
             event_tx,
             event_rx,
@@ -146,54 +285,65 @@ impl AxiomService {
             cwd,
             config,
             output_context: Arc::new(RwLock::new(OutputContext::Empty)),
+            watcher,
+            search_id: 0,
+            search_cancel: None,
+            index,
+            lsp,
+            replace_id: 0,
+            pending_replace: None,
+            runs: HashMap::new(),
+            conductor_prompt: None,
+            key_watch: KeyWatch::new(),
+            auth_retry: AuthRetry::default(),
+            trusted: false,
+            approvals: ApprovalQueue::new(),
+            plan_modified: plan_seen,
+            plan_checked: Instant::now(),
         })
     }
 
-    /// Initialize LLM providers from configuration
-    fn init_llm_providers(config: &AxiomConfig, registry: &Arc<RwLock<ProviderRegistry>>) {
-        let mut reg = registry.write();
-
-        // Initialize Ollama if configured
-        if let Some(ollama_config) = config.llm.providers.get("ollama") {
-            if ollama_config.enabled {
-                let model = ollama_config
-                    .default_model
-                    .as_deref()
-                    .unwrap_or("gemma3:4b");
-                let provider = Arc::new(crate::llm::OllamaProvider::with_model(model));
-                reg.register(provider);
-            }
-        }
-
-        // Initialize Claude if configured
-        if let Some(claude_config) = config.llm.providers.get("claude") {
-            if claude_config.enabled {
-                if let Some(api_key) = &claude_config.api_key {
-                    let model = claude_config
-                        .default_model
-                        .as_deref()
-                        .unwrap_or("claude-sonnet-4-20250514");
-                    let provider = Arc::new(crate::llm::ClaudeProvider::new(api_key, model));
-                    reg.register(provider);
-                }
-            }
+    /// Watch `cwd` with the workspace's ignore patterns, unless its
+    /// `file_watcher` feature is off
+    ///
+    /// A directory that can't be watched (e.g. the inotify limit is reached)
+    /// only costs the change notifications, so this warns instead of failing.
+    fn start_watcher(cwd: &Path, notification_tx: &NotificationSender) -> Option<FileWatcher> {
+        let config = workspace_config(cwd).unwrap_or_default();
+        if !config.features.is_enabled(Feature::FileWatcher) {
+            return None;
         }
-
-        // Initialize Gemini if configured
-        if let Some(gemini_config) = config.llm.providers.get("gemini") {
-            if gemini_config.enabled {
-                if let Some(api_key) = &gemini_config.api_key {
-                    let model = gemini_config
-                        .default_model
-                        .as_deref()
-                        .unwrap_or("gemini-2.0-flash");
-                    let provider = Arc::new(crate::llm::GeminiProvider::new(api_key, model));
-                    reg.register(provider);
-                }
+        let ignores = config.ignore_patterns;
+        match FileWatcher::new(cwd, &ignores) {
+            Ok(mut watcher) => {
+                watcher.watch_config(&config_files(cwd));
+                Some(watcher)
+            }
+            Err(e) => {
+                let _ = notification_tx.send(Notification::warning(e.to_string()));
+                None
             }
         }
     }
 
+    /// Language servers for `cwd`, their diagnostics sent as
+    /// [`Notification::Diagnostics`]; none runs until a file is synced
+    fn start_lsp(
+        cwd: &Path,
+        config: &AxiomConfig,
+        notification_tx: &NotificationSender,
+    ) -> Arc<LspManager> {
+        let tx = notification_tx.clone();
+        Arc::new(LspManager::new(cwd, config.lsp.clone(), move |path, diagnostics| {
+            let _ = tx.send(Notification::Diagnostics { path, diagnostics });
+        }))
+    }
+
+    /// Initialize LLM providers from configuration
+    fn init_llm_providers(config: &AxiomConfig, registry: &Arc<RwLock<ProviderRegistry>>) {
+        registry.write().register_configured(config);
+    }
+
     /// Send a command to the backend
     pub fn send(&mut self, command: Command) -> Result<()> {
         match command {
@@ -212,6 +362,14 @@ impl AxiomService {
             Command::ResizePty { agent_id, cols, rows } => {
                 self.resize_pty(agent_id, cols, rows)?;
             }
+            Command::ResyncPtyScreen { agent_id } => {
+                let keyframe = self.pty_manager.write().screen_keyframe(agent_id);
+                if let Some(diff) = keyframe {
+                    let _ = self
+                        .notification_tx
+                        .send(Notification::PtyScreenDiff { id: agent_id, diff });
+                }
+            }
             Command::ReadFile { path } => {
                 self.read_file(path)?;
             }
@@ -224,6 +382,30 @@ impl AxiomService {
             Command::CancelAgent { agent_id } => {
                 self.cancel_agent(agent_id)?;
             }
+            Command::ApproveOperations { ids } => {
+                self.resolve_operations(&ids, true)?;
+            }
+            Command::RejectOperations { ids } => {
+                self.resolve_operations(&ids, false)?;
+            }
+            Command::CancelAll => {
+                self.cancel_all();
+            }
+            Command::RerunAgent { agent_id } => {
+                self.rerun_agent(agent_id)?;
+            }
+            Command::GetLastMessage { agent_id } => {
+                let text = self.last_message(agent_id)?;
+                let _ = self
+                    .notification_tx
+                    .send(Notification::AgentLastMessage { agent_id, text });
+            }
+            Command::GetTouchedFiles { agent_id } => {
+                let paths = self.touched_files(agent_id)?;
+                let _ = self
+                    .notification_tx
+                    .send(Notification::AgentTouchedFiles { agent_id, paths });
+            }
             Command::SelectContext { context } => {
                 self.switch_context(context)?;
             }
@@ -248,6 +430,10 @@ impl AxiomService {
             Command::GetSnapshot => {
                 // Could send a full state snapshot as notification
             }
+            Command::QueryHistory { filter } => {
+                let entries = self.history(&filter);
+                let _ = self.notification_tx.send(Notification::History { entries });
+            }
             Command::Shutdown => {
                 self.shutdown()?;
             }
@@ -293,6 +479,60 @@ impl AxiomService {
             Command::ListFiles { path, include_hidden } => {
                 self.list_files(path, include_hidden)?;
             }
+            Command::SearchWorkspace {
+                query,
+                regex,
+                globs,
+            } => {
+                self.search_workspace(SearchQuery {
+                    regex,
+                    globs,
+                    ..SearchQuery::new(query)
+                });
+            }
+            Command::SemanticSearch { query } => {
+                self.semantic_search(query);
+            }
+            Command::Hover { path, line, column } => {
+                self.hover(path, line, column)?;
+            }
+            Command::GoToDefinition { path, line, column } => {
+                self.go_to_definition(path, line, column)?;
+            }
+            Command::ReplaceInWorkspace {
+                query,
+                replacement,
+                regex,
+                globs,
+            } => {
+                self.preview_replace(
+                    SearchQuery {
+                        regex,
+                        globs,
+                        ..SearchQuery::new(query)
+                    },
+                    replacement,
+                )?;
+            }
+            Command::ApplyReplace { replace_id, paths } => {
+                self.apply_replace(replace_id, &paths)?;
+            }
+            Command::SetWatcherEnabled { enabled } => {
+                self.set_watcher_enabled(enabled);
+            }
+            Command::SaveSession { name } => {
+                let path = self.save_session(name.as_deref())?;
+                let _ = self.notification_tx.send(Notification::info(format!(
+                    "Session saved to {}",
+                    path.display()
+                )));
+            }
+            Command::RestoreSession { name } => {
+                self.restore_session(name.as_deref())?;
+            }
+            Command::ExportRunLog { agent_id, format } => {
+                self.export_run_log(agent_id, format)?;
+            }
             Command::SlashCommand { command } => {
                 // Slash commands are handled by the TUI/UI layer directly
                 // When used via backend API, just log them for now
@@ -304,15 +544,11 @@ impl AxiomService {
         Ok(())
     }
 
-    /// List files in a directory
+    /// List files in a directory of the workspace
     fn list_files(&mut self, path: PathBuf, include_hidden: bool) -> Result<()> {
         use crate::notifications::FileEntry;
 
-        let full_path = if path.is_absolute() {
-            path.clone()
-        } else {
-            self.cwd.join(&path)
-        };
+        let full_path = files::resolve_dir(&self.cwd, &path)?;
 
         let mut entries = Vec::new();
 
@@ -358,6 +594,220 @@ impl AxiomService {
         Ok(())
     }
 
+    /// Search the working directory on a background thread
+    ///
+    /// Hits stream as [`Notification::SearchResult`] tagged with the returned
+    /// ID. Any search still running is cancelled.
+    pub fn search_workspace(&mut self, query: SearchQuery) -> u64 {
+        if let Some(previous) = self.search_cancel.take() {
+            previous.store(true, Ordering::Relaxed);
+        }
+        self.search_id += 1;
+        let search_id = self.search_id;
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.search_cancel = Some(cancel.clone());
+
+        let root = self.cwd.clone();
+        let ignores = workspace_config(&root)
+            .map(|config| config.ignore_patterns)
+            .unwrap_or_default();
+        let guard = self.guard_config();
+        let tx = self.notification_tx.clone();
+        std::thread::spawn(move || {
+            let result = search::search_workspace(&root, &query, &ignores, &cancel, |hit| {
+                let _ = tx.send(Notification::SearchResult {
+                    search_id,
+                    path: hit.path,
+                    line: hit.line,
+                    column: hit.column,
+                    text: guard_content(&hit.text, &guard).text,
+                });
+            });
+            let summary = result.unwrap_or_else(|e| {
+                let _ = tx.send(Notification::from_error(&e));
+                Default::default()
+            });
+            let _ = tx.send(Notification::SearchFinished {
+                search_id,
+                query: query.query,
+                matches: summary.matches,
+                truncated: summary.truncated,
+            });
+        });
+        search_id
+    }
+
+    /// Search the working directory by meaning on a background thread
+    ///
+    /// The index is brought up to date first; the hits arrive as
+    /// [`Notification::SemanticSearchResults`].
+    pub fn semantic_search(&self, query: String) {
+        let index = self.index.clone();
+        let tx = self.notification_tx.clone();
+        std::thread::spawn(move || match index.search(&query) {
+            Ok(hits) => {
+                let _ = tx.send(Notification::SemanticSearchResults { query, hits });
+            }
+            Err(e) => {
+                let _ = tx.send(Notification::from_error(&e));
+            }
+        });
+    }
+
+    /// Ask the language server about the symbol at `line` and `column` on a
+    /// background thread; the answer arrives as [`Notification::Hover`]
+    ///
+    /// Fails if `path` is outside the workspace.
+    pub fn hover(&self, path: PathBuf, line: u32, column: u32) -> Result<()> {
+        let path = files::resolve(&self.cwd, &path)?;
+        let lsp = self.lsp.clone();
+        let tx = self.notification_tx.clone();
+        std::thread::spawn(move || match lsp.hover(&path, line, column) {
+            Ok(contents) => {
+                let _ = tx.send(Notification::Hover {
+                    path,
+                    line,
+                    column,
+                    contents,
+                });
+            }
+            Err(e) => {
+                let _ = tx.send(Notification::from_error(&e));
+            }
+        });
+        Ok(())
+    }
+
+    /// Find where the symbol at `line` and `column` is defined on a
+    /// background thread; the answer arrives as [`Notification::Definition`]
+    ///
+    /// Fails if `path` is outside the workspace.
+    pub fn go_to_definition(&self, path: PathBuf, line: u32, column: u32) -> Result<()> {
+        let path = files::resolve(&self.cwd, &path)?;
+        let lsp = self.lsp.clone();
+        let tx = self.notification_tx.clone();
+        std::thread::spawn(move || match lsp.definition(&path, line, column) {
+            Ok(locations) => {
+                let _ = tx.send(Notification::Definition {
+                    path,
+                    line,
+                    column,
+                    locations,
+                });
+            }
+            Err(e) => {
+                let _ = tx.send(Notification::from_error(&e));
+            }
+        });
+        Ok(())
+    }
+
+    /// Hand `paths` to their language servers on a background thread, as
+    /// starting a server can take a while; with `open_only`, only files a
+    /// server already has are synced
+    fn sync_lsp(&self, paths: Vec<PathBuf>, open_only: bool) {
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .filter(|path| self.lsp.handles(path))
+            .collect();
+        if paths.is_empty() {
+            return;
+        }
+        let lsp = self.lsp.clone();
+        let tx = self.notification_tx.clone();
+        std::thread::spawn(move || {
+            for path in paths {
+                if open_only && !lsp.is_open(&path) {
+                    continue;
+                }
+                if let Err(e) = lsp.sync(&path) {
+                    let _ = tx.send(Notification::warning(e.to_string()));
+                }
+            }
+        });
+    }
+
+    /// Work out a workspace-wide replace and send it as
+    /// [`Notification::ReplacePreview`]; nothing is written until
+    /// [`Self::apply_replace`] confirms it
+    pub fn preview_replace(&mut self, query: SearchQuery, replacement: String) -> Result<u64> {
+        let ignores = workspace_config(&self.cwd)
+            .map(|config| config.ignore_patterns)
+            .unwrap_or_default();
+        let files = replace::preview_replace(&self.cwd, &query, &replacement, &ignores)?;
+
+        self.replace_id += 1;
+        let replace_id = self.replace_id;
+        let _ = self.notification_tx.send(Notification::ReplacePreview {
+            replace_id,
+            query: query.query.clone(),
+            replacement: replacement.clone(),
+            files: files.clone(),
+        });
+        self.pending_replace = Some(PendingReplace {
+            id: replace_id,
+            query,
+            replacement,
+            files,
+        });
+        Ok(replace_id)
+    }
+
+    /// Write the files of preview `replace_id` (all of them when `paths` is
+    /// empty) and send [`Notification::ReplaceApplied`]
+    pub fn apply_replace(&mut self, replace_id: u64, paths: &[PathBuf]) -> Result<()> {
+        let pending = match self.pending_replace.take() {
+            Some(pending) if pending.id == replace_id => pending,
+            other => {
+                self.pending_replace = other;
+                return Err(AxiomError::invalid_operation(format!(
+                    "No replace preview {} to apply (preview again)",
+                    replace_id
+                )));
+            }
+        };
+        let files: Vec<FileReplace> = pending
+            .files
+            .into_iter()
+            .filter(|file| paths.is_empty() || paths.contains(&file.path))
+            .collect();
+        let outcome =
+            replace::apply_replace(&self.cwd, &pending.query, &pending.replacement, &files)?;
+        let _ = self.notification_tx.send(Notification::ReplaceApplied {
+            replace_id,
+            applied: outcome.applied,
+            replacements: outcome.replacements,
+            skipped: outcome.skipped,
+        });
+        Ok(())
+    }
+
+    /// Share `approvals` as the queue of operations held for approval
+    pub fn set_approvals(&mut self, approvals: ApprovalQueue) {
+        self.approvals = approvals;
+    }
+
+    /// Apply (`approve`) or discard the held operations `ids` (all of them
+    /// when empty) and send the queue left as
+    /// [`Notification::PendingOperations`]
+    ///
+    /// An operation that fails to apply doesn't stop the rest.
+    pub fn resolve_operations(&mut self, ids: &[String], approve: bool) -> Result<()> {
+        let (resolved, errors) = self.approvals.resolve_each(&self.cwd, ids, approve)?;
+        let _ = self.notification_tx.send(Notification::PendingOperations {
+            operations: self.approvals.list(),
+        });
+        if !errors.is_empty() {
+            return Err(AxiomError::invalid_operation(errors.join("\n")));
+        }
+        let _ = self.notification_tx.send(Notification::info(format!(
+            "{} {} operation(s)",
+            if approve { "Applied" } else { "Discarded" },
+            resolved.len()
+        )));
+        Ok(())
+    }
+
     /// Process internal events and emit notifications
     ///
     /// Call this periodically (e.g., in your UI event loop) to process
@@ -367,6 +817,9 @@ impl AxiomService {
         while let Ok(event) = self.event_rx.try_recv() {
             self.handle_event(event)?;
         }
+        self.poll_watcher();
+        self.poll_keys();
+        self.poll_plan();
         Ok(())
     }
 
@@ -383,10 +836,300 @@ impl AxiomService {
         while let Ok(event) = self.event_rx.try_recv() {
             self.handle_event(event)?;
         }
+        self.poll_watcher();
+        self.poll_keys();
+        self.poll_plan();
 
         Ok(())
     }
 
+    /// Report files whose changes have settled
+    fn poll_watcher(&mut self) {
+        let Some(watcher) = &mut self.watcher else {
+            return;
+        };
+        let now = Instant::now();
+        let changed = watcher.poll(now);
+        let config_changed = watcher.poll_config(now);
+        for path in &changed {
+            let _ = self
+                .notification_tx
+                .send(Notification::FileChanged { path: path.clone() });
+        }
+        self.sync_lsp(changed, true);
+        if !config_changed.is_empty() {
+            self.reload_config(config_changed);
+        }
+    }
+
+    /// Send the Conductor's plan when `.axiom/plan.json` changed
+    ///
+    /// Plans are run by the terminal UI, which saves each step's progress
+    /// there; the file watcher skips `.axiom/`, so the file is checked
+    /// every [`PLAN_POLL`].
+    fn poll_plan(&mut self) {
+        let now = Instant::now();
+        if now.duration_since(self.plan_checked) < PLAN_POLL {
+            return;
+        }
+        self.plan_checked = now;
+        let modified = plan_modified(&self.cwd);
+        if modified == self.plan_modified {
+            return;
+        }
+        self.plan_modified = modified;
+        if let Ok(Some(plan)) = plan::load(&self.cwd) {
+            let _ = self
+                .notification_tx
+                .send(Notification::PlanUpdated { plan });
+        }
+    }
+
+    /// Reread the config after `paths` changed and apply what changed; a
+    /// config that doesn't load or validate is reported and the current one
+    /// kept
+    fn reload_config(&mut self, paths: Vec<PathBuf>) {
+        match self.load_current_config() {
+            Ok(config) => {
+                let changes = self.apply_config(config);
+                if !changes.is_empty() {
+                    let _ = self
+                        .notification_tx
+                        .send(Notification::ConfigReloaded { paths, changes });
+                }
+            }
+            Err(e) => {
+                let _ = self.notification_tx.send(Notification::Error {
+                    message: format!("Config not reloaded: {}", e),
+                    code: Some(ErrorCode::ConfigInvalid),
+                });
+            }
+        }
+    }
+
+    /// The config on disk now, with the workspace's `.axiom/config.toml`
+    /// over it
+    fn load_current_config(&self) -> std::result::Result<AxiomConfig, String> {
+        let mut config = load_config(&self.cwd).map_err(|e| e.to_string())?;
+        let path = self.cwd.join(".axiom").join("config.toml");
+        if path.exists() {
+            let workspace: WorkspaceConfig = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| toml::from_str(&content).map_err(|e| e.to_string()))
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            workspace.apply_overrides(&mut config, self.trusted);
+        }
+        if !self.trusted {
+            config.lsp.enabled = false;
+        }
+        config.validate()?;
+
+        // Keys fetched by a command aren't in the file
+        for (id, provider) in &mut config.llm.providers {
+            let Some(current) = self.config.llm.providers.get(id) else {
+                continue;
+            };
+            if provider.api_key.is_none()
+                && provider.api_key_command.is_some()
+                && provider.api_key_command == current.api_key_command
+            {
+                provider.api_key = current.api_key.clone();
+                provider.api_key_expires_at = current.api_key_expires_at;
+            }
+        }
+        Ok(config)
+    }
+
+    /// Switch to `config`: providers are registered again, CLI agents,
+    /// orchestration settings and prompt templates are read from it from now
+    /// on and language servers restart; the semantic index and the
+    /// Conductor's context settings wait for a restart
+    pub fn apply_config(&mut self, config: AxiomConfig) -> ConfigChanges {
+        let changes = ConfigChanges::between(&self.config, &config)
+            .needing_restart(&["index", "llm.context"]);
+        let api_key = |config: &AxiomConfig, id: &str| {
+            config
+                .llm
+                .providers
+                .get(id)
+                .and_then(|provider| provider.api_key.clone())
+        };
+        let rekeyed = self
+            .auth_retry
+            .pending()
+            .filter(|id| api_key(&self.config, id) != api_key(&config, id))
+            .map(str::to_string);
+        self.config = config;
+
+        if changes.llm_changed() {
+            configure_rate_limits(&self.config.llm.rate_limits);
+            let mut registry = ProviderRegistry::from_config(&self.config);
+            registry.register_configured(&self.config);
+            *self.llm_registry.write() = registry;
+        }
+        self.pty_manager
+            .write()
+            .set_scrollback(self.config.cli_agents.scrollback_lines);
+        if changes.changed("lsp") {
+            let lsp = std::mem::replace(
+                &mut self.lsp,
+                Self::start_lsp(&self.cwd, &self.config, &self.notification_tx),
+            );
+            std::thread::spawn(move || lsp.shutdown());
+        }
+        if changes.changed("prompts") {
+            self.conductor
+                .write()
+                .set_prompts(PromptLibrary::new(&self.cwd, &self.config.prompts));
+        }
+        if let Some(provider_id) = rekeyed {
+            self.retry_rejected_prompt(&provider_id);
+        }
+        changes
+    }
+
+    /// Send the prompt `provider_id` rejected again, now that it has a new key
+    fn retry_rejected_prompt(&mut self, provider_id: &str) {
+        if self.auth_retry.key_updated(provider_id) {
+            self.conductor.write().retry();
+        }
+    }
+
+    /// Keep language servers off when reloading the config, and the
+    /// config's key commands, CLI agents and commands agents start from
+    /// running, for workspaces that aren't trusted
+    ///
+    /// A new service is restricted until told otherwise.
+    pub fn set_trusted(&mut self, trusted: bool) {
+        self.trusted = trusted;
+    }
+
+    /// Whether the config's `api_key_command`s may run
+    fn runs_key_commands(&self) -> bool {
+        key_commands_allowed(&self.cwd, self.trusted)
+    }
+
+    /// Fetch provider keys that are missing or about to expire, and remind
+    /// about the others as they near their expiry
+    fn poll_keys(&mut self) {
+        if !self.key_watch.due(Instant::now()) {
+            return;
+        }
+        let now = unix_now();
+        let commands_allowed = self.runs_key_commands();
+        let mut providers: Vec<_> = self
+            .config
+            .llm
+            .providers
+            .iter()
+            .filter(|(_, provider)| provider.enabled)
+            .collect();
+        providers.sort_by_key(|(id, _)| id.as_str());
+
+        let mut fetches = Vec::new();
+        for (id, provider) in providers {
+            let action = self.key_watch.check(
+                id,
+                provider.api_key_expires_at,
+                provider.api_key_command.is_some() && commands_allowed,
+                provider.api_key.is_some(),
+                now,
+            );
+            match (action, provider.api_key_expires_at) {
+                (Some(KeyAction::Refresh), _) => {
+                    fetches.extend(provider.api_key_command.clone().map(|c| (id.clone(), c)))
+                }
+                (Some(action), Some(expires_at)) => {
+                    let _ = self
+                        .notification_tx
+                        .send(Notification::ProviderKeyExpiring {
+                            provider_id: id.clone(),
+                            expires_at,
+                            expired: action == KeyAction::Expired,
+                        });
+                }
+                _ => {}
+            }
+        }
+        for (id, command) in fetches {
+            self.spawn_key_fetch(id, command);
+        }
+    }
+
+    /// Run a provider's `api_key_command` on a background thread
+    fn spawn_key_fetch(&self, provider_id: String, command: String) {
+        let event_tx = self.event_tx.clone();
+        std::thread::spawn(move || {
+            let result = fetch_key(&command);
+            let _ = event_tx.send(Event::ProviderKeyFetched {
+                provider_id,
+                result,
+            });
+        });
+    }
+
+    /// Whether changed files are being reported
+    pub fn watcher_enabled(&self) -> bool {
+        self.watcher.as_ref().is_some_and(|w| w.is_enabled())
+    }
+
+    fn set_watcher_enabled(&mut self, enabled: bool) {
+        let message = match &mut self.watcher {
+            Some(watcher) => {
+                watcher.set_enabled(enabled);
+                Notification::info(if enabled {
+                    "File watcher resumed"
+                } else {
+                    "File watcher paused"
+                })
+            }
+            None => Notification::warning("File watcher is not running"),
+        };
+        let _ = self.notification_tx.send(message);
+    }
+
+    /// Save the conversation and every agent as session `name`
+    /// (default [`DEFAULT_SESSION`](sessions::DEFAULT_SESSION))
+    pub fn save_session(&self, name: Option<&str>) -> Result<PathBuf> {
+        let conductor = self.conductor.read();
+        let session = Session::capture(
+            name.unwrap_or(sessions::DEFAULT_SESSION),
+            &conductor.history(),
+            conductor.agent_id(),
+            &self.agent_registry.read(),
+        );
+        sessions::save(&self.cwd, &session)
+    }
+
+    /// Replace the conversation and agents with session `name`, or the
+    /// most recently saved one; returns the restored session's name
+    ///
+    /// Refused while agents are running, since their output would land in
+    /// the restored agents.
+    pub fn restore_session(&mut self, name: Option<&str>) -> Result<String> {
+        if self.agent_registry.read().running_count() > 0 {
+            return Err(AxiomError::InvalidOperation(
+                "agents are still running; cancel them before restoring a session".to_string(),
+            ));
+        }
+        let session = sessions::load(&self.cwd, name)?;
+        let agents = session
+            .agents
+            .into_iter()
+            .map(SavedAgent::into_agent)
+            .collect();
+        self.agent_registry.write().restore(agents);
+        self.runs.clear();
+        self.conductor
+            .write()
+            .restore(session.history, session.conductor_id);
+        let _ = self.notification_tx.send(Notification::SessionRestored {
+            name: session.name.clone(),
+            agents: self.agents(),
+        });
+        Ok(session.name)
+    }
+
     /// Poll for a notification (non-blocking)
     pub fn poll_notification(&self) -> Option<Notification> {
         self.notification_rx.try_recv().ok()
@@ -397,6 +1140,29 @@ impl AxiomService {
         &self.notification_rx
     }
 
+    /// Recently sent notifications matching `filter`, oldest first
+    ///
+    /// The last [`HISTORY_CAPACITY`] are kept, whether or not a UI has
+    /// polled them yet.
+    pub fn history(&self, filter: &HistoryFilter) -> Vec<HistoryEntry> {
+        self.notification_tx.history.lock().query(filter)
+    }
+    /// Running agents that have gone quiet for [`STALL_AFTER`], as review items
+    pub fn stalled_agents(&self) -> Vec<ReviewItem> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        self.agent_registry
+            .read()
+            .stalled(STALL_AFTER)
+            .into_iter()
+            .map(|agent| {
+                ReviewItem::stalled_agent(agent.id, &agent.name, agent.last_activity.elapsed(), now)
+            })
+            .collect()
+    }
+
     /// Get a snapshot of all agents
     pub fn agents(&self) -> Vec<AgentView> {
         let registry = self.agent_registry.read();
@@ -411,6 +1177,7 @@ impl AxiomService {
                 elapsed_secs: agent.elapsed().as_secs_f64(),
                 parent_id: agent.parent_id,
                 token_count: agent.token_count,
+                service: agent.service.as_ref().map(|s| s.info()),
             })
             .collect()
     }
@@ -427,6 +1194,7 @@ impl AxiomService {
             elapsed_secs: agent.elapsed().as_secs_f64(),
             parent_id: agent.parent_id,
             token_count: agent.token_count,
+            service: agent.service.as_ref().map(|s| s.info()),
         })
     }
 
@@ -442,6 +1210,12 @@ impl AxiomService {
         manager.get_screen(id)
     }
 
+    /// Plain-text output of a CLI agent, including what scrolled off its
+    /// screen; None for agents that aren't CLI agents
+    pub fn pty_transcript(&self, id: AgentId) -> Option<Transcript> {
+        self.pty_manager.read().transcript(id).cloned()
+    }
+
     /// Get current output context
     pub fn output_context(&self) -> OutputContext {
         self.output_context.read().clone()
@@ -464,6 +1238,19 @@ impl AxiomService {
         }
     }
 
+    /// Register `provider` alongside the configured ones and make it the
+    /// active one
+    ///
+    /// It is dropped when a config change re-registers the providers.
+    pub fn use_provider(&self, provider: SharedProvider) -> Result<()> {
+        let id = provider.id().to_string();
+        let mut registry = self.llm_registry.write();
+        registry.register(provider);
+        registry
+            .set_active(&id)
+            .map_err(|e| AxiomError::Llm(e.to_string()))
+    }
+
     /// Get available CLI agents from config
     pub fn cli_agents(&self) -> Vec<CliAgentInfo> {
         self.config
@@ -487,9 +1274,26 @@ impl AxiomService {
 
     /// Set current working directory
     pub fn set_cwd(&mut self, cwd: PathBuf) {
+        let enabled = self.watcher.as_ref().is_none_or(|w| w.is_enabled());
+        self.watcher = Self::start_watcher(&cwd, &self.notification_tx);
+        if let Some(watcher) = &mut self.watcher {
+            watcher.set_enabled(enabled);
+        }
+        // The old servers shut down without holding up the switch
+        let lsp = std::mem::replace(
+            &mut self.lsp,
+            Self::start_lsp(&cwd, &self.config, &self.notification_tx),
+        );
+        std::thread::spawn(move || lsp.shutdown());
         self.cwd = cwd;
     }
 
+    /// Language servers for the working directory, e.g. to check files an
+    /// agent changed
+    pub fn lsp(&self) -> &Arc<LspManager> {
+        &self.lsp
+    }
+
     /// Get configuration
     pub fn config(&self) -> &AxiomConfig {
         &self.config
@@ -508,12 +1312,27 @@ impl AxiomService {
             }
         }
 
-        // Regular input - send to conductor
+        // Regular input - attach any @file mentions, then send to conductor
+        self.conductor_prompt = Some(text.clone());
+        let (text, warnings) = context::attach_mentions(
+            &text,
+            &self.cwd,
+            &ContextBudget::default(),
+            &self.guard_config(),
+        );
+        for warning in warnings {
+            let _ = self.notification_tx.send(Notification::warning(warning));
+        }
         let mut conductor = self.conductor.write();
         conductor.process(text);
         Ok(())
     }
 
+    /// Guard settings from the project's `.axiom/config.toml`
+    fn guard_config(&self) -> GuardConfig {
+        GuardConfig::load(&self.cwd)
+    }
+
     fn execute_shell(&mut self, command: String) -> Result<()> {
         let request = AgentSpawnRequest {
             agent_type: AgentType::Shell,
@@ -528,6 +1347,21 @@ impl AxiomService {
     }
 
     fn invoke_cli_agent(&mut self, agent_id: &str, prompt: &str) -> Result<()> {
+        if !self.trusted {
+            return Err(AxiomError::invalid_operation(
+                "CLI agents are disabled in untrusted workspaces",
+            ));
+        }
+
+        // No agent named: the workspace's or global default
+        let agent_id = self
+            .config
+            .cli_agents
+            .agent_or_default(agent_id)
+            .ok_or_else(|| AxiomError::invalid_operation("No CLI agent named and no default set"))?
+            .to_string();
+        let agent_id = agent_id.as_str();
+
         // Get CLI agent config
         let config = self
             .config
@@ -557,10 +1391,11 @@ impl AxiomService {
 
         let id = {
             let mut registry = self.agent_registry.write();
-            let id = registry.spawn(request);
+            let id = registry.spawn(request.clone());
             registry.start(id);
             id
         };
+        self.start_run(id, &request);
 
         // Start PTY session
         {
@@ -597,12 +1432,15 @@ impl AxiomService {
     }
 
     fn read_file(&mut self, path: PathBuf) -> Result<()> {
+        let path = files::resolve(&self.cwd, &path)?;
         let content = std::fs::read_to_string(&path)?;
         let _ = self.notification_tx.send(Notification::FileLoaded {
             path: path.clone(),
             content,
         });
 
+        self.sync_lsp(vec![path.clone()], false);
+
         // Switch context to file
         *self.output_context.write() = OutputContext::File { path };
 
@@ -610,6 +1448,7 @@ impl AxiomService {
     }
 
     fn write_file(&mut self, path: PathBuf, content: String) -> Result<()> {
+        let path = files::resolve(&self.cwd, &path)?;
         std::fs::write(&path, &content)?;
         let _ = self.notification_tx.send(Notification::FileModified { path });
         Ok(())
@@ -623,16 +1462,116 @@ impl AxiomService {
         Ok(())
     }
 
-    fn cancel_agent(&mut self, agent_id: AgentId) -> Result<()> {
-        // Cancel in registry
-        {
+    /// Cancel a pending or running agent and stop its work
+    pub fn cancel_agent(&mut self, agent_id: AgentId) -> Result<()> {
+        let (exists, cancelled) = {
             let mut registry = self.agent_registry.write();
-            if let Some(agent) = registry.get_mut(agent_id) {
-                agent.cancel();
+            (registry.get(agent_id).is_some(), registry.cancel(agent_id))
+        };
+        if !exists {
+            return Err(AxiomError::not_found(agent_id.to_string()));
+        }
+        if cancelled {
+            self.stop_agent_work(agent_id);
+        }
+        Ok(())
+    }
+
+    /// Run the task of an agent that isn't at work again
+    ///
+    /// The Conductor is sent its latest prompt again, a CLI agent its task;
+    /// other agents are spawned with what they were spawned with.
+    pub fn rerun_agent(&mut self, agent_id: AgentId) -> Result<()> {
+        let request = {
+            let registry = self.agent_registry.read();
+            let agent = registry
+                .get(agent_id)
+                .ok_or_else(|| AxiomError::not_found(agent_id.to_string()))?;
+            if matches!(agent.status, AgentStatus::Pending | AgentStatus::Running) {
+                return Err(AxiomError::invalid_operation(format!(
+                    "{} is still running",
+                    agent.name
+                )));
+            }
+            AgentSpawnRequest {
+                agent_type: agent.agent_type.clone(),
+                name: agent.name.clone(),
+                description: last_prompt(&agent.output)
+                    .filter(|_| agent.agent_type == AgentType::Conductor)
+                    .unwrap_or(&agent.description)
+                    .to_string(),
+                parameters: agent.parameters.clone(),
+                parent_id: None,
+            }
+        };
+        match request.agent_type {
+            AgentType::Conductor => self.process_input(request.description),
+            AgentType::CliAgent { config_id } => {
+                let prompt = request.parameters.unwrap_or(request.description);
+                self.invoke_cli_agent(&config_id, &prompt)
+            }
+            _ => {
+                let _ = self.event_tx.send(Event::AgentSpawn(request));
+                Ok(())
+            }
+        }
+    }
+
+    /// The last message of agent `agent_id`: the Conductor's latest reply,
+    /// or another agent's output
+    pub fn last_message(&self, agent_id: AgentId) -> Result<String> {
+        let registry = self.agent_registry.read();
+        let agent = registry
+            .get(agent_id)
+            .ok_or_else(|| AxiomError::not_found(agent_id.to_string()))?;
+        Ok(last_message(&agent.output).to_string())
+    }
+
+    /// Files agent `agent_id` wrote, from its logged run if it has one,
+    /// else as its output names them
+    pub fn touched_files(&self, agent_id: AgentId) -> Result<Vec<PathBuf>> {
+        let paths = match run_log::find(&self.cwd, agent_id) {
+            Ok(record) => {
+                let mut paths: Vec<String> = Vec::new();
+                for operation in record.operations {
+                    if operation.success && !paths.contains(&operation.path) {
+                        paths.push(operation.path);
+                    }
+                }
+                paths
+            }
+            Err(_) => {
+                let registry = self.agent_registry.read();
+                let agent = registry
+                    .get(agent_id)
+                    .ok_or_else(|| AxiomError::not_found(agent_id.to_string()))?;
+                touched_files(&agent.output)
+            }
+        };
+        Ok(paths.into_iter().map(|path| self.cwd.join(path)).collect())
+    }
+
+    /// Cancel every pending or running agent; returns their IDs
+    pub fn cancel_all(&mut self) -> Vec<AgentId> {
+        let ids = self.agent_registry.write().cancel_all();
+        for id in &ids {
+            self.stop_agent_work(*id);
+        }
+        ids
+    }
+
+    /// Stop what a just-cancelled agent was doing and tell the UIs
+    ///
+    /// Shell and service processes watch the agent's cancel token; the
+    /// Conductor's LLM stream and CLI agent PTYs are stopped here.
+    fn stop_agent_work(&mut self, agent_id: AgentId) {
+        {
+            let mut conductor = self.conductor.write();
+            if conductor.agent_id() == Some(agent_id) {
+                conductor.cancel();
             }
         }
 
-        // Remove PTY if it's a CLI agent
         {
             let mut manager = self.pty_manager.write();
             if manager.contains(agent_id) {
@@ -640,12 +1579,14 @@ impl AxiomService {
             }
         }
 
+        self.finish_run(agent_id, &AgentStatus::Cancelled, None);
+        let _ = self
+            .notification_tx
+            .send(Notification::AgentCancelled { id: agent_id });
         let _ = self.notification_tx.send(Notification::AgentStatusChanged {
             id: agent_id,
             status: AgentStatus::Cancelled,
         });
-
-        Ok(())
     }
 
     fn switch_context(&mut self, context: OutputContext) -> Result<()> {
@@ -661,6 +1602,7 @@ impl AxiomService {
                 manager.remove(id);
             }
         }
+        self.lsp.shutdown();
 
         Ok(())
     }
@@ -682,28 +1624,65 @@ impl AxiomService {
                 self.handle_agent_complete(id)?;
             }
             Event::AgentWake(id) => {
-                // Wake agent - just update status
-                let mut registry = self.agent_registry.write();
-                if let Some(agent) = registry.get_mut(id) {
-                    agent.status = AgentStatus::Running;
+                // Wake agent - update status and start logging its new run
+                let request = {
+                    let mut registry = self.agent_registry.write();
+                    registry.get_mut(id).map(|agent| {
+                        agent.status = AgentStatus::Running;
+                        AgentSpawnRequest {
+                            agent_type: agent.agent_type.clone(),
+                            name: agent.name.clone(),
+                            description: agent.description.clone(),
+                            parameters: None,
+                            parent_id: agent.parent_id,
+                        }
+                    })
+                };
+                if let Some(request) = request {
+                    self.start_run(id, &request);
                 }
             }
             Event::CliAgentOutput { id, data } => {
-                // PTY output - emit notification
+                // PTY output - emit notification, then what its parsers found
+                // and whatever it changed on screen
+                let events = {
+                    let mut manager = self.pty_manager.write();
+                    manager.capture(id, &data);
+                    manager.parse_output(id, &data)
+                };
                 let _ = self.notification_tx.send(Notification::PtyOutput { id, data });
+                self.notify_agent_events(id, events);
+                let diff = self.pty_manager.write().screen_diff(id);
+                if let Some(diff) = diff {
+                    let _ = self
+                        .notification_tx
+                        .send(Notification::PtyScreenDiff { id, diff });
+                }
             }
             Event::CliAgentExit { id, exit_code } => {
                 self.handle_cli_agent_exit(id, exit_code)?;
             }
+            Event::CliAgentNotReady { id, error } => {
+                self.handle_cli_agent_not_ready(id, error)?;
+            }
             Event::CliAgentInput { id, data } => {
                 // Forward input to PTY
                 let mut manager = self.pty_manager.write();
                 let _ = manager.write(id, &data);
             }
             Event::ConductorResponse(response) => {
-                // Add response to conductor history
+                // Add response to conductor history, after counting what
+                // the turn cost
                 let mut conductor = self.conductor.write();
+                let usage = self.conductor_usage(&conductor.history(), &response);
+                let id = conductor.agent_id();
                 conductor.add_response(response);
+                drop(conductor);
+                if let Some(record) = usage {
+                    let _ = self
+                        .notification_tx
+                        .send(Notification::UsageUpdate { id, record });
+                }
             }
             Event::LlmChunk(chunk) => {
                 // LLM chunks are handled by conductor internally
@@ -715,12 +1694,86 @@ impl AxiomService {
                 // LLM done - nothing to do here
             }
             Event::LlmError(error) => {
-                let _ = self.notification_tx.send(Notification::Error { message: error });
+                let code = ErrorCode::for_llm_message(&error);
+                let _ = self.notification_tx.send(Notification::Error {
+                    message: error,
+                    code: Some(code),
+                });
             }
-            Event::FileModification { path, content: _ } => {
+            Event::ProviderAuthFailed {
+                provider_id,
+                message,
+            } => {
+                // The prompt is sent again once the provider has a new key
+                self.auth_retry.rejected(&provider_id);
+                // A key with a command is fetched again instead of asked for
+                let refreshable = self.runs_key_commands()
+                    && self
+                        .config
+                        .get_provider(&provider_id)
+                        .is_some_and(|provider| provider.api_key_command.is_some());
+                if refreshable {
+                    self.key_watch.force(&provider_id);
+                } else {
+                    let _ = self.notification_tx.send(Notification::ProviderAuthFailed {
+                        provider_id,
+                        message,
+                    });
+                }
+            }
+            Event::ProviderKeyFetched {
+                provider_id,
+                result,
+            } => {
+                let report = self.key_watch.finished(&provider_id, result.is_ok());
+                match result {
+                    Ok(fresh) => {
+                        if let Some(provider) = self.config.llm.providers.get_mut(&provider_id) {
+                            provider.api_key = Some(fresh.key);
+                            provider.api_key_expires_at = fresh.expires_at;
+                        }
+                        self.llm_registry.write().register_configured(&self.config);
+                        self.retry_rejected_prompt(&provider_id);
+                        let _ = self
+                            .notification_tx
+                            .send(Notification::ProviderKeyRefreshed {
+                                provider_id,
+                                expires_at: fresh.expires_at,
+                            });
+                    }
+                    Err(e) if report => {
+                        let _ = self.notification_tx.send(Notification::Error {
+                            message: format!("Couldn't refresh {}'s API key: {}", provider_id, e),
+                            code: Some(ErrorCode::ProviderAuthFailed),
+                        });
+                    }
+                    Err(_) => {}
+                }
+            }
+            Event::FileModification {
+                id,
+                path,
+                content: _,
+            } => {
+                let relative = Path::new(&path)
+                    .strip_prefix(&self.cwd)
+                    .map(|relative| relative.display().to_string())
+                    .unwrap_or_else(|_| path.clone());
+                if let Some(run) = self.runs.get_mut(&id) {
+                    run.record.operations.push(RunOperation {
+                        kind: "write".to_string(),
+                        path: relative.clone(),
+                        success: true,
+                    });
+                }
                 let _ = self.notification_tx.send(Notification::FileModified {
                     path: PathBuf::from(path),
                 });
+                let _ = self.notification_tx.send(Notification::OperationApplied {
+                    id,
+                    path: PathBuf::from(relative),
+                    kind: "write".to_string(),
+                });
             }
             Event::SwitchContext(context) => {
                 *self.output_context.write() = context;
@@ -729,7 +1782,7 @@ impl AxiomService {
                 self.execute_shell(command)?;
             }
             Event::FileChanged(path) => {
-                let _ = self.notification_tx.send(Notification::FileModified { path });
+                let _ = self.notification_tx.send(Notification::FileChanged { path });
             }
             Event::Tick => {
                 // Periodic tick - could be used for cleanup, etc.
@@ -747,11 +1800,25 @@ impl AxiomService {
         Ok(())
     }
 
+    /// Estimated usage of a conductor turn: the history so far in and
+    /// `response` out, on the orchestrator's provider
+    fn conductor_usage(&self, history: &[ChatMessage], response: &str) -> Option<UsageRecord> {
+        let provider = self.llm_registry.read().for_role(AgentRole::Orchestrator)?;
+        let input = history.iter().map(|m| estimate_tokens(&m.text())).sum();
+        Some(UsageRecord::new(
+            provider.id(),
+            provider.model(),
+            input,
+            estimate_tokens(response),
+        ))
+    }
+
     fn handle_agent_spawn(&mut self, request: AgentSpawnRequest) -> Result<()> {
         let id = {
             let mut registry = self.agent_registry.write();
             registry.spawn(request.clone())
         };
+        self.start_run(id, &request);
 
         // Emit notification
         let _ = self.notification_tx.send(Notification::AgentSpawned {
@@ -761,8 +1828,18 @@ impl AxiomService {
             parent_id: request.parent_id,
         });
 
-        // If this is a conductor agent, set it up
-        if request.agent_type == AgentType::Conductor {
+        // Restricted workspaces don't run commands an agent decided on by
+        // itself
+        if request.agent_type.runs_commands() && request.parent_id.is_some() && !self.trusted {
+            let message = "Workspace is not trusted".to_string();
+            self.agent_registry.write().error(id, message.clone());
+            let status = AgentStatus::Error(message);
+            self.finish_run(id, &status, None);
+            let _ = self
+                .notification_tx
+                .send(Notification::AgentStatusChanged { id, status });
+        } else if request.agent_type == AgentType::Conductor {
+            // If this is a conductor agent, set it up
             let mut conductor = self.conductor.write();
             conductor.set_agent_id(id);
 
@@ -789,6 +1866,10 @@ impl AxiomService {
                 agent.status = status.clone();
             }
         }
+        // The conductor goes idle when it's done with a request
+        if status.is_terminal() || status == AgentStatus::Idle {
+            self.finish_run(id, &status, None);
+        }
 
         let _ = self
             .notification_tx
@@ -811,42 +1892,222 @@ impl AxiomService {
     }
 
     fn handle_agent_complete(&mut self, id: AgentId) -> Result<()> {
-        {
+        // The executor has already recorded success or failure
+        let status = {
             let mut registry = self.agent_registry.write();
-            registry.complete(id);
-        }
+            let Some(agent) = registry.get_mut(id) else {
+                return Ok(());
+            };
+            if !agent.status.is_terminal() {
+                agent.complete();
+            }
+            agent.status.clone()
+        };
+        self.finish_run(id, &status, None);
 
-        let _ = self.notification_tx.send(Notification::AgentStatusChanged {
-            id,
-            status: AgentStatus::Completed,
-        });
+        let _ = self.notification_tx.send(Notification::AgentStatusChanged { id, status });
 
         Ok(())
     }
 
+    /// Send the events a CLI agent's output parsers found
+    fn notify_agent_events(&self, id: AgentId, events: Vec<AgentEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        let agent = self
+            .agent_registry
+            .read()
+            .get(id)
+            .map(|agent| agent.name.clone())
+            .unwrap_or_default();
+        for event in events {
+            let _ = self.notification_tx.send(Notification::CliAgentEvent {
+                id,
+                agent: agent.clone(),
+                event,
+            });
+        }
+    }
+
     fn handle_cli_agent_exit(&mut self, id: AgentId, exit_code: i32) -> Result<()> {
         // Mark as exited in PTY manager
-        {
+        let (events, transcript) = {
             let mut manager = self.pty_manager.write();
             manager.mark_exited(id);
+            (manager.finish_output(id), manager.transcript(id).cloned())
+        };
+        self.notify_agent_events(id, events);
+
+        // The conductor reads what it did with the next prompt
+        let name = self
+            .agent_registry
+            .read()
+            .get(id)
+            .map(|agent| agent.name.clone());
+        let context = name
+            .zip(transcript)
+            .and_then(|(name, transcript)| transcript_context(&name, exit_code, &transcript));
+        if let Some(context) = context {
+            self.conductor.write().add_context(context);
         }
 
         // Update agent status
-        {
+        let status = {
             let mut registry = self.agent_registry.write();
-            if let Some(agent) = registry.get_mut(id) {
+            registry.get_mut(id).map(|agent| {
                 if exit_code == 0 {
                     agent.complete();
                 } else {
                     agent.error(format!("Exited with code {}", exit_code));
                 }
-            }
+                agent.status.clone()
+            })
+        };
+        if let Some(status) = status {
+            self.finish_run(id, &status, Some(exit_code));
         }
 
         let _ = self.notification_tx.send(Notification::PtyExited { id, exit_code });
 
         Ok(())
     }
+
+    /// Report a CLI agent that never became ready for its prompt
+    ///
+    /// It's left running for the user to take over.
+    fn handle_cli_agent_not_ready(&mut self, id: AgentId, error: String) -> Result<()> {
+        let running = self.pty_manager.read().get(id).is_some_and(|agent| !agent.exited);
+        if !running {
+            return Ok(());
+        }
+        self.handle_agent_update(id, AgentStatus::Error(error.clone()))?;
+
+        let name = self
+            .agent_registry
+            .read()
+            .get(id)
+            .map(|agent| agent.name.clone())
+            .unwrap_or_default();
+        let _ = self.notification_tx.send(Notification::Error {
+            message: format!("{} never became ready, prompt not sent: {}", name, error),
+            code: Some(ErrorCode::CliAgentFailed),
+        });
+        Ok(())
+    }
+
+    /// Start logging a run of agent `id`, as a tool call of the run that
+    /// started it
+    fn start_run(&mut self, id: AgentId, request: &AgentSpawnRequest) {
+        let prompt = if request.agent_type == AgentType::Conductor {
+            self.conductor_prompt.take()
+        } else {
+            None
+        };
+        let prompt = prompt
+            .or_else(|| request.parameters.clone())
+            .unwrap_or_else(|| request.description.clone());
+        let mut record = RunRecord::new(id, &request.name, request.agent_type.label(), &prompt);
+        record.parent_id = request.parent_id;
+        if request.agent_type == AgentType::Conductor {
+            let provider = self.llm_registry.read().for_role(AgentRole::Orchestrator);
+            if let Some(provider) = provider {
+                record.provider = Some(provider.id().to_string());
+                record.model = Some(provider.model());
+            }
+        }
+        if let Some(parent) = request
+            .parent_id
+            .and_then(|parent| self.runs.get_mut(&parent))
+        {
+            parent.record.tool_calls.push(RunToolCall {
+                tool: request.agent_type.label().to_string(),
+                input: prompt,
+                agent_id: Some(id),
+            });
+        }
+
+        let output_from = self
+            .agent_registry
+            .read()
+            .get(id)
+            .map_or(0, |agent| agent.output.len());
+        self.runs.insert(
+            id,
+            ActiveRun {
+                record,
+                started: Instant::now(),
+                output_from,
+            },
+        );
+    }
+
+    /// Log the run of agent `id`, if one is in progress, as ending in `status`
+    ///
+    /// The conductor only goes idle, so an idle conductor whose output for
+    /// the run reports an error has failed.
+    fn finish_run(&mut self, id: AgentId, status: &AgentStatus, exit_code: Option<i32>) {
+        let Some(run) = self.runs.remove(&id) else {
+            return;
+        };
+        let mut record = run.record;
+        record.duration_ms = run.started.elapsed().as_millis() as u64;
+        record.exit_code = exit_code;
+        if let Some(agent) = self.agent_registry.read().get(id) {
+            record.set_output(agent.output.get(run.output_from..).unwrap_or_default());
+        }
+        (record.status, record.error) = match status {
+            AgentStatus::Error(error) => (RunStatus::Failed, Some(error.clone())),
+            AgentStatus::Cancelled => (RunStatus::Cancelled, None),
+            AgentStatus::Idle => match record
+                .output
+                .lines()
+                .find_map(|line| line.strip_prefix("Error: "))
+            {
+                Some(error) => (RunStatus::Failed, Some(error.to_string())),
+                None => (RunStatus::Completed, None),
+            },
+            _ => (RunStatus::Completed, None),
+        };
+
+        if let Err(e) = run_log::append(&self.cwd, &record) {
+            let _ = self
+                .notification_tx
+                .send(Notification::warning(format!("Run not logged: {}", e)));
+        }
+    }
+
+    /// Send the latest logged run of agent `agent_id` in `format`
+    fn export_run_log(&self, agent_id: AgentId, format: RunLogFormat) -> Result<()> {
+        let record = run_log::find(&self.cwd, agent_id)?;
+        let _ = self.notification_tx.send(Notification::RunLogExport {
+            agent_id,
+            format,
+            content: run_log::export(&record, format),
+        });
+        Ok(())
+    }
+}
+
+/// Files the config of a service in `cwd` comes from
+fn config_files(cwd: &Path) -> Vec<PathBuf> {
+    let mut files = vec![config_path(cwd), cwd.join(".axiom").join("config.toml")];
+    files.extend(user_config_path());
+    files
+}
+
+/// When the plan file of the workspace at `cwd` was modified, if it exists
+fn plan_modified(cwd: &Path) -> Option<SystemTime> {
+    std::fs::metadata(plan::plan_path(cwd))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// The project's `.axiom/config.toml`, if it exists and parses
+fn workspace_config(cwd: &Path) -> Option<WorkspaceConfig> {
+    std::fs::read_to_string(cwd.join(".axiom").join("config.toml"))
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
 }
 
 #[cfg(test)]
@@ -878,4 +2139,438 @@ mod tests {
         // Default config has claude and gemini enabled
         assert!(!agents.is_empty());
     }
+
+    #[test]
+    fn test_watcher_reports_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut service = AxiomService::new(AxiomConfig::default(), dir.path().into()).unwrap();
+        assert!(service.watcher_enabled());
+
+        let file = dir.path().join("notes.md");
+        std::fs::write(&file, "hello").unwrap();
+        let reported = (0..40).any(|_| {
+            service.process_events_timeout(Duration::from_millis(50)).unwrap();
+            service.notifications().try_iter().any(
+                |n| matches!(n, Notification::FileChanged { path } if path.ends_with("notes.md")),
+            )
+        });
+        assert!(reported);
+
+        service
+            .send(Command::SetWatcherEnabled { enabled: false })
+            .unwrap();
+        assert!(!service.watcher_enabled());
+    }
+
+    #[test]
+    fn test_config_file_edits_are_reloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(".axiom.toml");
+        let write = |timeout: &str| {
+            let content = format!(
+                "[llm]\ndefault_provider = \"ollama\"\ntimeout = {}\n\n[llm.providers.ollama]\nenabled = true\n",
+                timeout
+            );
+            std::fs::write(&file, content).unwrap();
+        };
+        write("30");
+        let config = load_config(dir.path()).unwrap();
+        let mut service = AxiomService::new(config, dir.path().into()).unwrap();
+        service.set_trusted(true);
+
+        let wait_for = |service: &mut AxiomService, kind: &str| {
+            (0..40).find_map(|_| {
+                service
+                    .process_events_timeout(Duration::from_millis(50))
+                    .unwrap();
+                service
+                    .notifications()
+                    .try_iter()
+                    .find(|n| n.kind() == kind)
+            })
+        };
+        write("60");
+        match wait_for(&mut service, "ConfigReloaded") {
+            Some(Notification::ConfigReloaded { changes, .. }) => {
+                assert_eq!(changes.sections, vec!["llm.timeout"]);
+            }
+            other => panic!("expected ConfigReloaded, got {:?}", other),
+        }
+        assert_eq!(service.config().llm.timeout, 60);
+
+        // A config that doesn't parse is reported and the current one kept
+        write("\"soon\"");
+        assert!(matches!(
+            wait_for(&mut service, "Error"),
+            Some(Notification::Error {
+                code: Some(ErrorCode::ConfigInvalid),
+                ..
+            })
+        ));
+        assert_eq!(service.config().llm.timeout, 60);
+    }
+
+    #[test]
+    fn test_rejected_prompt_is_sent_again_with_a_new_key() {
+        use crate::llm::{FreshKey, LlmError};
+        use crate::testing::FakeProvider;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut service = AxiomService::new(AxiomConfig::default(), dir.path().into()).unwrap();
+        let provider = Arc::new(
+            FakeProvider::new()
+                .fail(LlmError::Api {
+                    status: 401,
+                    message: "invalid x-api-key".to_string(),
+                })
+                .reply("Hello again"),
+        );
+        service.use_provider(provider.clone()).unwrap();
+        let wait_for = |service: &mut AxiomService, found: &dyn Fn(&Notification) -> bool| {
+            (0..100).any(|_| {
+                service
+                    .process_events_timeout(Duration::from_millis(50))
+                    .unwrap();
+                service.notifications().try_iter().any(|n| found(&n))
+            })
+        };
+
+        service
+            .send(Command::ProcessInput {
+                text: "Hello".to_string(),
+            })
+            .unwrap();
+        assert!(wait_for(&mut service, &|n| n.kind() == "ProviderAuthFailed"));
+        assert_eq!(service.auth_retry.pending(), Some("fake"));
+
+        // The provider's new key sends the prompt again
+        let fresh = FreshKey {
+            key: "sk-new".to_string(),
+            expires_at: None,
+        };
+        let key_fetched = Event::ProviderKeyFetched {
+            provider_id: "fake".to_string(),
+            result: Ok(fresh),
+        };
+        service.event_tx.send(key_fetched.clone()).unwrap();
+        assert!(wait_for(&mut service, &|n| matches!(
+            n,
+            Notification::AgentOutput { chunk, .. } if chunk.contains("Hello again")
+        )));
+        assert_eq!(provider.requests().len(), 2);
+
+        // The answered prompt isn't sent a third time
+        service.event_tx.send(key_fetched).unwrap();
+        service
+            .process_events_timeout(Duration::from_millis(200))
+            .unwrap();
+        assert_eq!(provider.requests().len(), 2);
+        assert_eq!(service.auth_retry.pending(), None);
+    }
+
+    #[test]
+    fn test_untrusted_project_config_runs_no_key_command() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".axiom.toml"),
+            "[llm.providers.openai]\nenabled = true\napi_key_command = \"echo sk-fresh\"\n",
+        )
+        .unwrap();
+        let fetched = |trusted: bool| {
+            let config = load_config(dir.path()).unwrap();
+            let mut service = AxiomService::new(config, dir.path().into()).unwrap();
+            service.set_trusted(trusted);
+            (0..40).any(|_| {
+                service
+                    .process_events_timeout(Duration::from_millis(50))
+                    .unwrap();
+                service.config().llm.providers["openai"].api_key.is_some()
+            })
+        };
+        assert!(!fetched(false));
+        assert!(fetched(true));
+    }
+
+    #[test]
+    fn test_untrusted_workspace_runs_no_cli_agent_or_agent_command() {
+        let dir = tempfile::tempdir().unwrap();
+        // Restricted until the front end says otherwise
+        let mut service = AxiomService::new(AxiomConfig::default(), dir.path().into()).unwrap();
+
+        let refused = service.send(Command::invoke_cli_agent("claude", "hi"));
+        assert!(refused.unwrap_err().to_string().contains("untrusted"));
+        assert!(service.agents().is_empty());
+
+        // Commands and services the Conductor decided on are recorded but
+        // never run
+        for (agent_type, file) in [
+            (AgentType::Shell, "agent.txt"),
+            (AgentType::Service, "service.txt"),
+        ] {
+            let command = format!("touch {}", file);
+            service
+                .handle_agent_spawn(AgentSpawnRequest {
+                    name: agent_type.label().to_string(),
+                    agent_type,
+                    description: command.clone(),
+                    parameters: Some(command),
+                    parent_id: Some(AgentId(1)),
+                })
+                .unwrap();
+        }
+        let agents = service.agents();
+        assert_eq!(agents.len(), 2);
+        assert!(agents
+            .iter()
+            .all(|agent| matches!(agent.status, AgentStatus::Error(_))));
+
+        // The user's own commands still do
+        service
+            .send(Command::execute_shell("touch user.txt"))
+            .unwrap();
+        let ran = (0..100).any(|_| {
+            service
+                .process_events_timeout(Duration::from_millis(50))
+                .unwrap();
+            dir.path().join("user.txt").exists()
+        });
+        assert!(ran);
+        assert!(!dir.path().join("agent.txt").exists());
+        assert!(!dir.path().join("service.txt").exists());
+    }
+
+    #[test]
+    fn test_history_backfills_polled_notifications() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut service = AxiomService::new(AxiomConfig::default(), dir.path().into()).unwrap();
+        service.send(Command::SaveSession { name: None }).unwrap();
+        // The UI has already drained the channel
+        while service.poll_notification().is_some() {}
+
+        let filter = HistoryFilter {
+            kinds: vec!["Info".to_string()],
+            ..Default::default()
+        };
+        service.send(Command::query_history(filter)).unwrap();
+        match service.poll_notification() {
+            Some(Notification::History { entries }) => {
+                assert_eq!(entries.len(), 1);
+                assert!(matches!(
+                    &entries[0].notification,
+                    Notification::Info { message } if message.starts_with("Session saved")
+                ));
+            }
+            other => panic!("expected History, got {:?}", other),
+        }
+        // The reply itself isn't kept
+        assert!(service
+            .history(&HistoryFilter::default())
+            .iter()
+            .all(|e| e.notification.kind() != "History"));
+    }
+
+    #[test]
+    fn test_search_streams_results() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.md"), "one\nTODO two\n").unwrap();
+        let mut service = AxiomService::new(AxiomConfig::default(), dir.path().into()).unwrap();
+        service.send(Command::search_workspace("todo")).unwrap();
+
+        let mut hits = Vec::new();
+        loop {
+            match service
+                .notifications()
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+            {
+                Notification::SearchResult {
+                    search_id,
+                    path,
+                    line,
+                    ..
+                } => hits.push((search_id, path, line)),
+                Notification::SearchFinished {
+                    search_id, matches, ..
+                } => {
+                    assert_eq!(search_id, 1);
+                    assert_eq!(matches, 1);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(hits, [(1, PathBuf::from("notes.md"), 2)]);
+    }
+
+    #[test]
+    fn test_runs_are_logged_and_exported() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut service = AxiomService::new(AxiomConfig::default(), dir.path().into()).unwrap();
+        service.send(Command::execute_shell("echo logged")).unwrap();
+
+        let logged = (0..100).any(|_| {
+            service
+                .process_events_timeout(Duration::from_millis(50))
+                .unwrap();
+            !run_log::list(dir.path(), 1).unwrap().is_empty()
+        });
+        assert!(logged);
+        let run = &run_log::list(dir.path(), 1).unwrap()[0];
+        assert_eq!(run.agent_type, "Shell");
+        assert_eq!(run.prompt, "echo logged");
+        assert_eq!(run.status, RunStatus::Completed);
+        assert!(run.output.contains("logged"));
+
+        while service.poll_notification().is_some() {}
+        service
+            .send(Command::ExportRunLog {
+                agent_id: run.agent_id,
+                format: RunLogFormat::Markdown,
+            })
+            .unwrap();
+        match service.poll_notification() {
+            Some(Notification::RunLogExport { content, .. }) => {
+                assert!(content.starts_with("# Shell run"));
+            }
+            other => panic!("expected RunLogExport, got {:?}", other),
+        }
+        assert!(service
+            .send(Command::ExportRunLog {
+                agent_id: AgentId(99),
+                format: RunLogFormat::Json,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_agent_actions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut service = AxiomService::new(AxiomConfig::default(), dir.path().into()).unwrap();
+        service.send(Command::execute_shell("echo again")).unwrap();
+        let logged = (0..100).any(|_| {
+            service
+                .process_events_timeout(Duration::from_millis(50))
+                .unwrap();
+            !run_log::list(dir.path(), 1).unwrap().is_empty()
+        });
+        assert!(logged);
+        let agent_id = run_log::list(dir.path(), 1).unwrap()[0].agent_id;
+
+        while service.poll_notification().is_some() {}
+        service.send(Command::GetLastMessage { agent_id }).unwrap();
+        match service.poll_notification() {
+            Some(Notification::AgentLastMessage { text, .. }) => assert!(text.contains("again")),
+            other => panic!("expected AgentLastMessage, got {:?}", other),
+        }
+        assert!(service.touched_files(agent_id).unwrap().is_empty());
+
+        // Re-running spawns a new Shell agent with the same command
+        service.send(Command::rerun_agent(agent_id)).unwrap();
+        service
+            .process_events_timeout(Duration::from_millis(50))
+            .unwrap();
+        let registry = service.agent_registry.read();
+        let rerun = registry
+            .agents()
+            .find(|agent| agent.id != agent_id)
+            .unwrap();
+        assert_eq!(rerun.parameters.as_deref(), Some("echo again"));
+        drop(registry);
+        assert!(service.send(Command::rerun_agent(AgentId(99))).is_err());
+    }
+
+    #[test]
+    fn test_structured_agent_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut service = AxiomService::new(AxiomConfig::default(), dir.path().into()).unwrap();
+        let path = dir.path().join("src").join("lib.rs");
+        service
+            .event_tx
+            .send(Event::FileModification {
+                id: AgentId(4),
+                path: path.display().to_string(),
+                content: String::new(),
+            })
+            .unwrap();
+        service.process_events().unwrap();
+        let applied = std::iter::from_fn(|| service.poll_notification()).find_map(|n| match n {
+            Notification::OperationApplied { id, path, kind } => Some((id, path, kind)),
+            _ => None,
+        });
+        assert_eq!(
+            applied,
+            Some((AgentId(4), PathBuf::from("src/lib.rs"), "write".to_string()))
+        );
+
+        // A plan saved by the terminal UI is sent once it's seen
+        let plan = plan::Plan::new("Ship it", ["Build", "Test"]);
+        plan::save(dir.path(), &plan).unwrap();
+        service.plan_checked -= PLAN_POLL;
+        service.process_events().unwrap();
+        match service.poll_notification() {
+            Some(Notification::PlanUpdated { plan: sent }) => assert_eq!(sent, plan),
+            other => panic!("expected PlanUpdated, got {:?}", other),
+        }
+        // and not again until it changes
+        service.plan_checked -= PLAN_POLL;
+        service.process_events().unwrap();
+        assert!(service.poll_notification().is_none());
+    }
+
+    #[test]
+    fn test_replace_previews_then_applies() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "colour\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "colour colour\n").unwrap();
+        let mut service = AxiomService::new(AxiomConfig::default(), dir.path().into()).unwrap();
+        service
+            .send(Command::ReplaceInWorkspace {
+                query: "colour".into(),
+                replacement: "color".into(),
+                regex: false,
+                globs: Vec::new(),
+            })
+            .unwrap();
+
+        let replace_id = loop {
+            if let Notification::ReplacePreview {
+                replace_id, files, ..
+            } = service
+                .notifications()
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+            {
+                assert_eq!(files.len(), 2);
+                break replace_id;
+            }
+        };
+        assert!(service.apply_replace(replace_id + 1, &[]).is_err());
+
+        service
+            .send(Command::ApplyReplace {
+                replace_id,
+                paths: vec![PathBuf::from("b.txt")],
+            })
+            .unwrap();
+        loop {
+            if let Notification::ReplaceApplied {
+                applied,
+                replacements,
+                ..
+            } = service
+                .notifications()
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+            {
+                assert_eq!(applied, [PathBuf::from("b.txt")]);
+                assert_eq!(replacements, 2);
+                break;
+            }
+        }
+        let read = |name| std::fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("a.txt"), "colour\n");
+        assert_eq!(read("b.txt"), "color color\n");
+        // A preview is applied once
+        assert!(service.apply_replace(replace_id, &[]).is_err());
+    }
 }
@@ -0,0 +1,311 @@
+//! Saved agent sessions
+//!
+//! A session is the Conductor's conversation history plus every agent in the
+//! registry (type, status, parent and output), written per workspace to
+//! `.axiom/sessions/<name>.json` so a conversation can be picked up again
+//! after the TUI or server restarts.
+//!
+//! Agents that were still pending or running when saved can't be resumed;
+//! they come back cancelled (the Conductor comes back idle).
+
+use crate::agents::{Agent, AgentRegistry};
+use crate::clock::unix_now;
+use crate::error::{AxiomError, Result};
+use crate::llm::ChatMessage;
+use crate::types::{AgentId, AgentStatus, AgentType};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Name used when a session is saved without one
+pub const DEFAULT_SESSION: &str = "latest";
+
+/// An agent as stored in a session file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedAgent {
+    pub id: AgentId,
+    pub agent_type: AgentType,
+    pub name: String,
+    pub description: String,
+    pub status: AgentStatus,
+    pub output: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<AgentId>,
+}
+
+impl SavedAgent {
+    pub fn from_agent(agent: &Agent) -> Self {
+        Self {
+            id: agent.id,
+            agent_type: agent.agent_type.clone(),
+            name: agent.name.clone(),
+            description: agent.description.clone(),
+            status: agent.status.clone(),
+            output: agent.output.clone(),
+            parent_id: agent.parent_id,
+        }
+    }
+
+    /// A registry agent again; unfinished work is marked as stopped
+    pub fn into_agent(self) -> Agent {
+        let mut agent = Agent::new(
+            self.id,
+            self.agent_type,
+            self.name,
+            self.description,
+            self.parent_id,
+        );
+        agent.append_output(&self.output);
+        agent.status = match self.status {
+            AgentStatus::Pending | AgentStatus::Running
+                if agent.agent_type == AgentType::Conductor =>
+            {
+                AgentStatus::Idle
+            }
+            AgentStatus::Pending | AgentStatus::Running => AgentStatus::Cancelled,
+            status => status,
+        };
+        if agent.status.is_terminal() {
+            agent.completed_at = Some(Instant::now());
+        }
+        agent
+    }
+}
+
+/// A saved conversation and its agents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub name: String,
+    /// Unix seconds
+    pub saved_at: u64,
+    /// The Conductor's agent, reused for the next prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conductor_id: Option<AgentId>,
+    pub history: Vec<ChatMessage>,
+    /// Oldest first
+    pub agents: Vec<SavedAgent>,
+}
+
+impl Session {
+    /// A session saved now
+    pub fn new(
+        name: &str,
+        history: Vec<ChatMessage>,
+        conductor_id: Option<AgentId>,
+        mut agents: Vec<SavedAgent>,
+    ) -> Self {
+        agents.sort_by_key(|agent| agent.id.0);
+        Self {
+            name: name.to_string(),
+            saved_at: unix_now(),
+            conductor_id,
+            history,
+            agents,
+        }
+    }
+
+    /// Capture `history` and every agent in `registry`
+    pub fn capture(
+        name: &str,
+        history: &[ChatMessage],
+        conductor_id: Option<AgentId>,
+        registry: &AgentRegistry,
+    ) -> Self {
+        let agents = registry.agents().map(SavedAgent::from_agent).collect();
+        Self::new(name, history.to_vec(), conductor_id, agents)
+    }
+}
+
+/// What `/session list` shows about a saved session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub name: String,
+    pub saved_at: u64,
+    pub messages: usize,
+    pub agents: usize,
+}
+
+impl std::fmt::Display for SessionSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let age = unix_now().saturating_sub(self.saved_at);
+        write!(
+            f,
+            "{}: {} messages, {} agents, saved {} ago",
+            self.name,
+            self.messages,
+            self.agents,
+            crate::workspace::format_duration(age)
+        )
+    }
+}
+
+/// Where a workspace's sessions are kept
+pub fn sessions_dir(root: &Path) -> PathBuf {
+    root.join(".axiom").join("sessions")
+}
+
+/// Write `session` under `root`, replacing any session of the same name
+pub fn save(root: &Path, session: &Session) -> Result<PathBuf> {
+    let path = session_path(root, &session.name)?;
+    std::fs::create_dir_all(sessions_dir(root))?;
+    let json = serde_json::to_string_pretty(session)
+        .map_err(|e| AxiomError::Serialization(e.to_string()))?;
+    std::fs::write(&path, json)?;
+    Ok(path)
+}
+
+/// Read the session called `name`, or the most recently saved one
+pub fn load(root: &Path, name: Option<&str>) -> Result<Session> {
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => list(root)?
+            .into_iter()
+            .next()
+            .map(|summary| summary.name)
+            .ok_or_else(|| AxiomError::not_found("no saved sessions"))?,
+    };
+    let path = session_path(root, &name)?;
+    let json = std::fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => AxiomError::not_found(format!("session '{}'", name)),
+        _ => AxiomError::Io(e),
+    })?;
+    serde_json::from_str(&json)
+        .map_err(|e| AxiomError::Serialization(format!("{}: {}", path.display(), e)))
+}
+
+/// Saved sessions, newest first; unreadable files are skipped
+pub fn list(root: &Path) -> Result<Vec<SessionSummary>> {
+    let entries = match std::fs::read_dir(sessions_dir(root)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut sessions: Vec<SessionSummary> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+        .filter_map(|json| serde_json::from_str::<Session>(&json).ok())
+        .map(|session| SessionSummary {
+            name: session.name,
+            saved_at: session.saved_at,
+            messages: session.history.len(),
+            agents: session.agents.len(),
+        })
+        .collect();
+    sessions.sort_by(|a, b| {
+        b.saved_at
+            .cmp(&a.saved_at)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(sessions)
+}
+
+/// File for session `name`; names are limited so they can't leave the directory
+fn session_path(root: &Path, name: &str) -> Result<PathBuf> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(AxiomError::InvalidOperation(format!(
+            "invalid session name '{}' (use letters, digits, '-', '_' and '.')",
+            name
+        )));
+    }
+    Ok(sessions_dir(root).join(format!("{}.json", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{MessageContent, Role};
+    use crate::types::AgentSpawnRequest;
+
+    fn spawn(
+        registry: &mut AgentRegistry,
+        agent_type: AgentType,
+        parent: Option<AgentId>,
+    ) -> AgentId {
+        registry.spawn(AgentSpawnRequest {
+            agent_type,
+            name: "agent".to_string(),
+            description: "task".to_string(),
+            parameters: None,
+            parent_id: parent,
+        })
+    }
+
+    #[test]
+    fn test_save_and_restore_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = AgentRegistry::new();
+        let conductor = spawn(&mut registry, AgentType::Conductor, None);
+        let shell = spawn(&mut registry, AgentType::Shell, Some(conductor));
+        let coder = spawn(&mut registry, AgentType::Coder, Some(conductor));
+        registry.start(conductor);
+        registry.append_output(shell, "$ ls\nsrc\n");
+        registry.complete(shell);
+        registry.start(coder);
+        let history = vec![ChatMessage {
+            role: Role::User,
+            content: MessageContent::Text("list files".to_string()),
+        }];
+
+        let session = Session::capture("work", &history, Some(conductor), &registry);
+        save(dir.path(), &session).unwrap();
+        let loaded = load(dir.path(), None).unwrap();
+        assert_eq!(loaded.agents, session.agents);
+        assert_eq!(loaded.history[0].text(), "list files");
+        assert_eq!(loaded.conductor_id, Some(conductor));
+
+        let mut restored = AgentRegistry::new();
+        restored.restore(
+            loaded
+                .agents
+                .into_iter()
+                .map(SavedAgent::into_agent)
+                .collect(),
+        );
+        assert_eq!(restored.get(shell).unwrap().output, "$ ls\nsrc\n");
+        assert_eq!(restored.get(shell).unwrap().status, AgentStatus::Completed);
+        assert_eq!(restored.get(coder).unwrap().status, AgentStatus::Cancelled);
+        assert_eq!(restored.get(conductor).unwrap().status, AgentStatus::Idle);
+        assert_eq!(restored.children(conductor).len(), 2);
+        // New agents don't reuse restored ids
+        assert_eq!(spawn(&mut restored, AgentType::Shell, None).0, coder.0 + 1);
+    }
+
+    #[test]
+    fn test_list_and_names() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(list(dir.path()).unwrap().is_empty());
+        assert!(matches!(
+            load(dir.path(), None),
+            Err(AxiomError::NotFound(_))
+        ));
+
+        let registry = AgentRegistry::new();
+        let mut older = Session::capture("older", &[], None, &registry);
+        older.saved_at -= 60;
+        save(dir.path(), &older).unwrap();
+        save(dir.path(), &Session::capture("newer", &[], None, &registry)).unwrap();
+        let names: Vec<String> = list(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, ["newer", "older"]);
+        assert_eq!(load(dir.path(), None).unwrap().name, "newer");
+
+        let bad = Session::capture("../escape", &[], None, &registry);
+        assert!(matches!(
+            save(dir.path(), &bad),
+            Err(AxiomError::InvalidOperation(_))
+        ));
+        assert!(matches!(
+            load(dir.path(), Some("missing")),
+            Err(AxiomError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,491 @@
+//! Project templates for `/init`
+//!
+//! A template is a TOML file naming the files a new project starts with and
+//! the commands run once they are written:
+//!
+//! ```toml
+//! name = "rust-bin"
+//! description = "Rust binary crate"
+//! commands = ["git init -q"]
+//!
+//! [[files]]
+//! path = "src/main.rs"
+//! content = '''
+//! fn main() {
+//!     println!("Hello from {{name}}!");
+//! }
+//! '''
+//! ```
+//!
+//! `{{name}}` in paths and contents becomes the project name and
+//! `{{module}}` the same name with underscores. Templates in
+//! `~/.axiom/templates/*.toml` are offered alongside the built-in ones and
+//! replace a built-in template of the same name.
+
+use crate::error::{AxiomError, Result};
+use crate::workspace::{Workspace, WorkspaceManager};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Templates that ship with Axiom
+const BUILTIN: &[&str] = &[
+    include_str!("builtin/next-app.toml"),
+    include_str!("builtin/python.toml"),
+    include_str!("builtin/rust-bin.toml"),
+    include_str!("builtin/rust-lib.toml"),
+];
+
+/// Characters of a failed command's output kept in the report
+const OUTPUT_TAIL: usize = 2000;
+
+/// A project template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Template {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub files: Vec<TemplateFile>,
+    /// Run in order in the new project once its files are written
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+/// A file a template creates
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateFile {
+    /// Relative to the project
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// A post-create command and how it went
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandRun {
+    pub command: String,
+    pub success: bool,
+    /// The end of its combined stdout and stderr
+    pub output: String,
+}
+
+/// What scaffolding a project did
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScaffoldReport {
+    pub template: String,
+    pub root: PathBuf,
+    /// Files created, relative to `root`
+    pub created: Vec<PathBuf>,
+    /// Commands run; a failed command is the last one
+    pub commands: Vec<CommandRun>,
+}
+
+impl ScaffoldReport {
+    /// Whether every command succeeded
+    pub fn success(&self) -> bool {
+        self.commands.iter().all(|run| run.success)
+    }
+
+    /// What `/init` shows
+    pub fn summary(&self) -> String {
+        let mut text = format!(
+            "Created {} project in {} ({} files)",
+            self.template,
+            self.root.display(),
+            self.created.len()
+        );
+        for run in &self.commands {
+            if run.success {
+                text.push_str(&format!("\n  ran `{}`", run.command));
+            } else {
+                text.push_str(&format!("\n  `{}` failed:\n{}", run.command, run.output));
+            }
+        }
+        text
+    }
+}
+
+impl Template {
+    /// Parse a template definition
+    pub fn parse(text: &str) -> Result<Self> {
+        let template: Self = toml::from_str(text)
+            .map_err(|e| AxiomError::config(format!("Invalid template: {}", e)))?;
+        if template.name.trim().is_empty() {
+            return Err(AxiomError::config("Invalid template: missing name"));
+        }
+        Ok(template)
+    }
+
+    /// Create the project `name` in `root`, then run the template's
+    /// commands there, stopping at the first that fails
+    ///
+    /// `root` is created if needed. Nothing is written if any file the
+    /// template creates already exists, or would land outside `root`. An
+    /// `AXIOM.md` is added unless the template has its own.
+    pub fn scaffold(&self, root: &Path, name: &str) -> Result<ScaffoldReport> {
+        let mut files: Vec<(PathBuf, String)> = self
+            .files
+            .iter()
+            .map(|file| {
+                let path = fill(&file.path.to_string_lossy(), name);
+                (PathBuf::from(path), fill(&file.content, name))
+            })
+            .collect();
+        if !files.iter().any(|(path, _)| path == Path::new("AXIOM.md")) {
+            files.push((PathBuf::from("AXIOM.md"), axiom_md(name, root)));
+        }
+
+        for (path, _) in &files {
+            let inside = path
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_)));
+            if !inside {
+                return Err(AxiomError::InvalidOperation(format!(
+                    "template file '{}' is outside the project",
+                    path.display()
+                )));
+            }
+        }
+        let existing: Vec<String> = files
+            .iter()
+            .filter(|(path, _)| root.join(path).exists())
+            .map(|(path, _)| path.display().to_string())
+            .collect();
+        if !existing.is_empty() {
+            return Err(AxiomError::InvalidOperation(format!(
+                "{} already has {}",
+                root.display(),
+                existing.join(", ")
+            )));
+        }
+
+        std::fs::create_dir_all(root)?;
+        let mut created = Vec::new();
+        for (path, content) in files {
+            let target = root.join(&path);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&target, content)?;
+            created.push(path);
+        }
+
+        let mut commands = Vec::new();
+        for command in &self.commands {
+            let run = run_command(root, command);
+            let failed = !run.success;
+            commands.push(run);
+            if failed {
+                break;
+            }
+        }
+        Ok(ScaffoldReport {
+            template: self.name.clone(),
+            root: root.to_path_buf(),
+            created,
+            commands,
+        })
+    }
+}
+
+/// Where user templates are kept (`~/.axiom/templates`)
+pub fn templates_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".axiom").join("templates"))
+}
+
+/// The built-in templates with those in `user_dir`, sorted by name
+///
+/// A user template replaces a built-in one of the same name; files that
+/// don't parse are skipped.
+pub fn load_templates(user_dir: Option<&Path>) -> Vec<Template> {
+    let mut templates: Vec<Template> = BUILTIN
+        .iter()
+        .filter_map(|text| Template::parse(text).ok())
+        .collect();
+    let entries = user_dir.and_then(|dir| std::fs::read_dir(dir).ok());
+    for entry in entries.into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+            continue;
+        }
+        let Some(template) = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| Template::parse(&text).ok())
+        else {
+            continue;
+        };
+        templates.retain(|t| t.name != template.name);
+        templates.push(template);
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    templates
+}
+
+/// Template `name`, built in or in `user_dir`
+pub fn find_template(name: &str, user_dir: Option<&Path>) -> Result<Template> {
+    let templates = load_templates(user_dir);
+    let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+    let available = names.join(", ");
+    templates
+        .iter()
+        .find(|t| t.name == name)
+        .cloned()
+        .ok_or_else(|| {
+            AxiomError::not_found(format!("template '{}' (available: {})", name, available))
+        })
+}
+
+/// `/init <template> [path]`: create a project from template `name` (built
+/// in or in `user_dir`) at `root`, and register it as a workspace unless it
+/// already is one
+pub fn init_project(
+    manager: &WorkspaceManager,
+    name: &str,
+    root: &Path,
+    user_dir: Option<&Path>,
+) -> Result<(ScaffoldReport, Workspace)> {
+    let template = find_template(name, user_dir)?;
+    let report = template.scaffold(root, &project_name(root))?;
+    let workspace = match manager.find_by_path(root) {
+        Some(workspace) => workspace,
+        None => {
+            let name = root
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| root.display().to_string());
+            manager.create_workspace(&name, root.to_path_buf())?
+        }
+    };
+    Ok((report, workspace))
+}
+
+/// One template per line, as `/templates` lists them
+pub fn templates_list(templates: &[Template]) -> String {
+    let lines: Vec<String> = templates
+        .iter()
+        .map(|t| format!("  {:<12} {}", t.name, t.description))
+        .collect();
+    format!("Templates (/init <template> [path]):\n{}", lines.join("\n"))
+}
+
+/// Project name for a directory: its name in lowercase words joined by
+/// dashes
+pub fn project_name(root: &Path) -> String {
+    let name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let name = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if name.is_empty() {
+        "project".to_string()
+    } else {
+        name
+    }
+}
+
+/// Starter `AXIOM.md` for the project `name` at `root`
+pub fn axiom_md(name: &str, root: &Path) -> String {
+    format!(
+        r#"# AXIOM.md
+
+This file provides guidance to Axiom agents when working with code in this repository.
+
+## Project Overview
+
+**Name**: {}
+**Path**: {}
+
+[Describe your project here - what it does, its main purpose, and key features]
+
+## Build & Development Commands
+
+```bash
+# Build the project
+# [Add your build command here]
+
+# Run the project
+# [Add your run command here]
+
+# Run tests
+# [Add your test command here]
+
+# Format code
+# [Add your format command here]
+
+# Lint code
+# [Add your lint command here]
+```
+
+## Architecture
+
+[Describe your project's architecture here:
+- Main components and their responsibilities
+- Data flow between components
+- Key design patterns used
+- Directory structure overview]
+
+## Development Guidelines
+
+[Add any coding standards, patterns, or practices that agents should follow:
+- Naming conventions
+- Error handling approach
+- Testing requirements
+- Documentation standards]
+
+## Dependencies
+
+[List key dependencies and their purposes:
+- Core frameworks
+- Important libraries
+- Development tools]
+
+---
+
+*This file was generated by Axiom. Update it to help AI agents understand your codebase.*
+"#,
+        name,
+        root.display()
+    )
+}
+
+/// `text` with the template variables for project `name` filled in
+fn fill(text: &str, name: &str) -> String {
+    text.replace("{{name}}", name)
+        .replace("{{module}}", &name.replace('-', "_"))
+}
+
+/// Run a post-create command in `root`
+fn run_command(root: &Path, command: &str) -> CommandRun {
+    let (success, output) = match Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(root)
+        .output()
+    {
+        Ok(output) => (
+            output.status.success(),
+            format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        ),
+        Err(e) => (false, e.to_string()),
+    };
+    let output = output.trim();
+    let skip = output.chars().count().saturating_sub(OUTPUT_TAIL);
+    CommandRun {
+        command: command.to_string(),
+        success,
+        output: output.chars().skip(skip).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_builtin_templates() {
+        let templates = load_templates(None);
+        let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["next-app", "python", "rust-bin", "rust-lib"]);
+        assert!(templates.iter().all(|t| !t.files.is_empty()));
+        assert!(templates_list(&templates).contains("rust-bin     Rust binary crate"));
+
+        let err = find_template("cobol", None).unwrap_err();
+        assert!(err.to_string().contains("available: next-app, python"));
+    }
+
+    #[test]
+    fn test_scaffold_and_user_templates() {
+        let home = tempfile::tempdir().unwrap();
+        fs::write(
+            home.path().join("rust-bin.toml"),
+            r#"
+name = "rust-bin"
+description = "Mine"
+commands = ["echo ran > ran.txt", "false", "echo never > never.txt"]
+
+[[files]]
+path = "src/{{module}}.rs"
+content = "// {{name}}\n"
+"#,
+        )
+        .unwrap();
+        fs::write(home.path().join("broken.toml"), "name = ").unwrap();
+
+        let templates = load_templates(Some(home.path()));
+        assert_eq!(templates.len(), 4);
+        let template = find_template("rust-bin", Some(home.path())).unwrap();
+        assert_eq!(template.description, "Mine");
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("my-app");
+        let report = template.scaffold(&root, &project_name(&root)).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("src/my_app.rs")).unwrap(),
+            "// my-app\n"
+        );
+        assert!(fs::read_to_string(root.join("AXIOM.md"))
+            .unwrap()
+            .contains("**Name**: my-app"));
+        assert_eq!(report.created.len(), 2);
+        assert!(root.join("ran.txt").exists());
+        assert!(!root.join("never.txt").exists());
+        assert_eq!(report.commands.len(), 2);
+        assert!(!report.success());
+        assert!(report.summary().contains("`false` failed"));
+
+        // Scaffolding again would overwrite the files
+        let err = template.scaffold(&root, "my-app").unwrap_err();
+        assert!(err.to_string().contains("src/my_app.rs, AXIOM.md"));
+    }
+
+    #[test]
+    fn test_init_registers_workspace() {
+        let storage_dir = tempfile::tempdir().unwrap();
+        let storage =
+            crate::workspace::WorkspaceStorage::with_config_dir(storage_dir.path().to_path_buf());
+        let manager =
+            WorkspaceManager::with_storage(crate::config::AxiomConfig::default(), storage).unwrap();
+        let templates = tempfile::tempdir().unwrap();
+        fs::write(
+            templates.path().join("plain.toml"),
+            "name = \"plain\"\n[[files]]\npath = \"notes.txt\"\ncontent = \"hi\"\n",
+        )
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("demo");
+        let (report, workspace) =
+            init_project(&manager, "plain", &root, Some(templates.path())).unwrap();
+        assert!(report.success());
+        assert_eq!(workspace.name, "demo");
+        assert_eq!(manager.find_by_path(&root).unwrap().id, workspace.id);
+        assert!(init_project(&manager, "plain", &root, Some(templates.path())).is_err());
+    }
+
+    #[test]
+    fn test_files_stay_in_project() {
+        let template = Template::parse(
+            r#"
+name = "escape"
+[[files]]
+path = "../outside.txt"
+content = ""
+"#,
+        )
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        assert!(template.scaffold(&dir.path().join("p"), "p").is_err());
+        assert!(!dir.path().join("outside.txt").exists());
+        assert!(!dir.path().join("p").exists());
+        assert_eq!(project_name(Path::new("/tmp/My App")), "my-app");
+    }
+}
@@ -0,0 +1,30 @@
+//! Test harness for driving agent flows without a UI
+//!
+//! Enabled with the `testing` feature. [`FakeProvider`] stands in for an
+//! LLM, answering from a script, and [`TestWorkspace`] runs an
+//! [`AxiomService`](crate::AxiomService) on a temporary directory with it,
+//! keeping the notifications the service sends so tests can wait for them
+//! and assert on what happened.
+//!
+//! ```ignore
+//! use axiom_core::testing::{FakeProvider, TestWorkspace};
+//! use serde_json::json;
+//!
+//! let provider = FakeProvider::new().tool_call(
+//!     "coder",
+//!     json!({ "description": "Add greet", "path": "src/lib.rs", "content": "pub fn greet() {}\n" }),
+//! );
+//! let mut workspace = TestWorkspace::new(provider);
+//! workspace.prompt("Add a greet function");
+//! workspace.wait_for_kind("FileModified");
+//! workspace.assert_file("src/lib.rs", "pub fn greet() {}\n");
+//! ```
+//!
+//! `crates/axiom-core/tests` runs the Conductor and orchestration flows this
+//! way.
+
+mod provider;
+mod workspace;
+
+pub use provider::FakeProvider;
+pub use workspace::{TestWorkspace, WAIT_TIMEOUT};
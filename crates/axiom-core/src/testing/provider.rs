@@ -0,0 +1,173 @@
+//! An LLM provider that answers from a script
+
+use crate::events::Event;
+use crate::llm::{
+    ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus, ToolCall, ToolReply,
+    ToolSpec,
+};
+use crossbeam_channel::Sender;
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::VecDeque;
+
+/// Provider replying with scripted answers, in order, and keeping the
+/// messages it was sent
+///
+/// ```ignore
+/// let provider = FakeProvider::new()
+///     .tool_call("coder", json!({ "description": "Add greet", "path": "src/lib.rs", "content": "..." }))
+///     .reply("Done");
+/// ```
+///
+/// A call after the script runs out fails with [`LlmError::Internal`].
+pub struct FakeProvider {
+    id: String,
+    model: Mutex<String>,
+    script: Mutex<VecDeque<Result<ToolReply, LlmError>>>,
+    requests: Mutex<Vec<Vec<ChatMessage>>>,
+}
+
+impl FakeProvider {
+    /// A provider with ID `fake` and nothing scripted yet
+    pub fn new() -> Self {
+        Self::with_id("fake")
+    }
+
+    /// A provider with ID `id`, e.g. to stand in for `claude`
+    pub fn with_id(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            model: Mutex::new("fake-1".to_string()),
+            script: Mutex::new(VecDeque::new()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Answer the next call with `text`
+    pub fn reply(self, text: impl Into<String>) -> Self {
+        self.push(Ok(ToolReply {
+            text: text.into(),
+            calls: Vec::new(),
+        }));
+        self
+    }
+
+    /// Answer the next call by calling tool `name` with `arguments`
+    pub fn tool_call(self, name: &str, arguments: Value) -> Self {
+        let call = ToolCall {
+            id: "call_0".to_string(),
+            name: name.to_string(),
+            arguments,
+        };
+        self.push(Ok(ToolReply {
+            text: String::new(),
+            calls: vec![call],
+        }));
+        self
+    }
+
+    /// Fail the next call with `error`
+    pub fn fail(self, error: LlmError) -> Self {
+        self.push(Err(error));
+        self
+    }
+
+    /// Add an answer to the end of the script, e.g. once the provider is
+    /// shared
+    pub fn push(&self, answer: Result<ToolReply, LlmError>) {
+        self.script.lock().push_back(answer);
+    }
+
+    /// Answers not given yet
+    pub fn remaining(&self) -> usize {
+        self.script.lock().len()
+    }
+
+    /// The messages of each call so far, in order
+    pub fn requests(&self) -> Vec<Vec<ChatMessage>> {
+        self.requests.lock().clone()
+    }
+
+    /// Whether any message sent so far contains `text`
+    pub fn saw(&self, text: &str) -> bool {
+        self.requests
+            .lock()
+            .iter()
+            .flatten()
+            .any(|message| message.content.as_text().contains(text))
+    }
+
+    /// Note `messages` and take the next answer
+    fn answer(&self, messages: Vec<ChatMessage>) -> Result<ToolReply, LlmError> {
+        self.requests.lock().push(messages);
+        self.script.lock().pop_front().unwrap_or_else(|| {
+            Err(LlmError::Internal(format!(
+                "{} has no scripted answer left",
+                self.id
+            )))
+        })
+    }
+}
+
+impl Default for FakeProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LlmProvider for FakeProvider {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        "Fake"
+    }
+
+    fn model(&self) -> String {
+        self.model.lock().clone()
+    }
+
+    fn set_model(&self, model: &str) -> Result<(), LlmError> {
+        *self.model.lock() = model.to_string();
+        Ok(())
+    }
+
+    fn list_models(&self) -> Result<Vec<String>, LlmError> {
+        Ok(vec![self.model()])
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            function_calling: true,
+            max_context: 128_000,
+            max_output: 8_192,
+            ..Default::default()
+        }
+    }
+
+    fn status(&self) -> ProviderStatus {
+        ProviderStatus::Ready
+    }
+
+    /// Stream the text of the next answer; its tool calls are dropped
+    fn send_message(&self, messages: Vec<ChatMessage>, event_tx: Sender<Event>) {
+        match self.answer(messages) {
+            Ok(reply) => {
+                let _ = event_tx.send(Event::LlmChunk(reply.text));
+                let _ = event_tx.send(Event::LlmDone);
+            }
+            Err(e) => {
+                let _ = event_tx.send(Event::LlmError(e.to_string()));
+            }
+        }
+    }
+
+    fn chat_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        _tools: &[ToolSpec],
+    ) -> Result<ToolReply, LlmError> {
+        self.answer(messages)
+    }
+}
@@ -0,0 +1,199 @@
+//! A workspace on a temporary directory, served by a fake provider
+
+use super::FakeProvider;
+use crate::orchestration::{apply_operation, DeveloperResponse, OrchestrationService};
+use crate::{AxiomConfig, AxiomService, Command, Notification};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// How long [`TestWorkspace::wait_for`] waits for a notification
+pub const WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An [`AxiomService`] on a temporary directory whose LLM calls go to a
+/// [`FakeProvider`]
+///
+/// The notifications the service sends are kept. Each wait picks up after
+/// the notification the last one returned, so waiting for one kind and
+/// then another checks they came in that order. The directory is removed
+/// when the workspace is dropped.
+pub struct TestWorkspace {
+    dir: TempDir,
+    service: AxiomService,
+    provider: Arc<FakeProvider>,
+    /// Notifications sent so far
+    seen: Vec<Notification>,
+    /// Index in `seen` after the last notification waited for
+    cursor: usize,
+}
+
+impl TestWorkspace {
+    /// An empty workspace with the default config
+    pub fn new(provider: FakeProvider) -> Self {
+        Self::with_config(AxiomConfig::default(), provider)
+    }
+
+    /// An empty workspace with `config`, trusted so agents may run commands
+    ///
+    /// Panics if the directory or service can't be created.
+    pub fn with_config(config: AxiomConfig, provider: FakeProvider) -> Self {
+        let dir = tempfile::tempdir().expect("create workspace directory");
+        let mut service =
+            AxiomService::new(config, dir.path().to_path_buf()).expect("start service");
+        service.set_trusted(true);
+        let provider = Arc::new(provider);
+        service
+            .use_provider(provider.clone())
+            .expect("register fake provider");
+        Self {
+            dir,
+            service,
+            provider,
+            seen: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    /// Root of the workspace
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// `relative` resolved against the root
+    pub fn join(&self, relative: impl AsRef<Path>) -> PathBuf {
+        self.dir.path().join(relative)
+    }
+
+    /// Write `content` to `relative`, creating its directories
+    pub fn write(&self, relative: impl AsRef<Path>, content: &str) -> &Self {
+        let path = self.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create directories");
+        }
+        std::fs::write(&path, content).expect("write file");
+        self
+    }
+
+    /// Content of `relative`; panics if it can't be read
+    pub fn read(&self, relative: impl AsRef<Path>) -> String {
+        let path = self.join(relative);
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {}: {}", path.display(), e))
+    }
+
+    /// The provider answering the service's calls
+    pub fn provider(&self) -> &Arc<FakeProvider> {
+        &self.provider
+    }
+
+    /// The service
+    pub fn service(&mut self) -> &mut AxiomService {
+        &mut self.service
+    }
+
+    /// Send `command`; panics if the service rejects it
+    pub fn send(&mut self, command: Command) {
+        self.service.send(command).expect("send command");
+    }
+
+    /// Ask the Conductor `text`, as typed in the input box
+    pub fn prompt(&mut self, text: &str) {
+        self.send(Command::ProcessInput {
+            text: text.to_string(),
+        });
+    }
+
+    /// An orchestration service for the workspace, each agent answered by
+    /// the provider
+    pub fn orchestration(&self) -> OrchestrationService {
+        OrchestrationService::new(self.path().to_path_buf()).with_provider(self.provider.clone())
+    }
+
+    /// Carry out the file operations of a Developer `response`; panics at
+    /// the first that fails
+    pub fn apply(&self, response: &DeveloperResponse) {
+        for op in &response.operations {
+            apply_operation(self.path(), op).unwrap_or_else(|e| panic!("apply {:?}: {}", op, e));
+        }
+    }
+
+    /// Process the service's events for up to `timeout`, keeping the
+    /// notifications they send; returns the first after the last one
+    /// waited for that `matches`
+    pub fn wait_for_within(
+        &mut self,
+        timeout: Duration,
+        mut matches: impl FnMut(&Notification) -> bool,
+    ) -> Option<Notification> {
+        let deadline = Instant::now() + timeout;
+        let mut checked = self.cursor;
+        loop {
+            if let Some(i) = (checked..self.seen.len()).find(|&i| matches(&self.seen[i])) {
+                self.cursor = i + 1;
+                return Some(self.seen[i].clone());
+            }
+            checked = self.seen.len();
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return None;
+            }
+            self.service
+                .process_events_timeout(left.min(Duration::from_millis(50)))
+                .expect("process events");
+            self.seen.extend(self.service.notifications().try_iter());
+        }
+    }
+
+    /// Like [`Self::wait_for_within`] for up to [`WAIT_TIMEOUT`]; panics
+    /// listing what was sent if nothing matches
+    pub fn wait_for(&mut self, matches: impl FnMut(&Notification) -> bool) -> Notification {
+        let from = self.cursor;
+        self.wait_for_within(WAIT_TIMEOUT, matches)
+            .unwrap_or_else(|| {
+                let kinds: Vec<&str> = self.seen[from..].iter().map(Notification::kind).collect();
+                panic!("no matching notification; got {:?}", kinds)
+            })
+    }
+
+    /// The next notification of `kind` (see [`Notification::kind`])
+    pub fn wait_for_kind(&mut self, kind: &str) -> Notification {
+        self.wait_for(|n| n.kind() == kind)
+    }
+
+    /// Output of all agents so far, in the order it arrived
+    pub fn output(&self) -> String {
+        self.seen
+            .iter()
+            .filter_map(|n| match n {
+                Notification::AgentOutput { chunk, .. } => Some(chunk.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Every notification kept so far
+    pub fn notifications(&self) -> &[Notification] {
+        &self.seen
+    }
+
+    /// Panic unless `relative` holds `expected`
+    pub fn assert_file(&self, relative: impl AsRef<Path>, expected: &str) {
+        let relative = relative.as_ref();
+        assert_eq!(
+            self.read(relative),
+            expected,
+            "content of {}",
+            relative.display()
+        );
+    }
+
+    /// Panic if any notification so far is an error
+    pub fn assert_no_errors(&self) {
+        let errors: Vec<&Notification> = self
+            .seen
+            .iter()
+            .filter(|n| matches!(n, Notification::Error { .. }))
+            .collect();
+        assert!(errors.is_empty(), "errors: {:?}", errors);
+    }
+}
@@ -109,6 +109,9 @@ pub enum AgentType {
     /// Search/retrieval agent
     Search,
 
+    /// Long-running process (dev server, watcher) kept alive and health-checked
+    Service,
+
     /// Custom tool agent with a specified name
     Custom { name: String },
 
@@ -128,6 +131,7 @@ impl AgentType {
             AgentType::Shell => "Shell",
             AgentType::FileOps => "FileOps",
             AgentType::Search => "Search",
+            AgentType::Service => "Service",
             AgentType::Custom { name } => name,
             AgentType::CliAgent { config_id } => config_id,
         }
@@ -141,6 +145,7 @@ impl AgentType {
             AgentType::Shell => "🖥️",
             AgentType::FileOps => "📁",
             AgentType::Search => "🔍",
+            AgentType::Service => "🔁",
             AgentType::Custom { .. } => "🔧",
             AgentType::CliAgent { .. } => "🤖",
         }
@@ -151,6 +156,20 @@ impl AgentType {
         matches!(self, AgentType::CliAgent { .. })
     }
 
+    /// Check if this agent runs shell commands
+    ///
+    /// Restricted workspaces don't let agents start these on their own.
+    pub fn runs_commands(&self) -> bool {
+        matches!(self, AgentType::Shell | AgentType::Service)
+    }
+
+    /// Check if this agent keeps running until cancelled
+    ///
+    /// Long-running agents don't count toward "all done" for a Conductor run.
+    pub fn is_long_running(&self) -> bool {
+        matches!(self, AgentType::Service)
+    }
+
     /// Get the CLI agent config ID if this is a CLI agent
     pub fn cli_agent_config_id(&self) -> Option<&str> {
         match self {
@@ -192,6 +211,43 @@ pub struct AgentView {
 
     /// Token count (for LLM agents)
     pub token_count: usize,
+
+    /// Health, uptime and restarts (service agents only)
+    #[serde(default)]
+    pub service: Option<ServiceInfo>,
+}
+
+/// Health of a service agent's process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceHealth {
+    /// No check configured, or not checked yet
+    Unknown,
+    /// Health check passing
+    Healthy,
+    /// Health check failing or process down
+    Unhealthy,
+}
+
+impl std::fmt::Display for ServiceHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServiceHealth::Unknown => write!(f, "unknown"),
+            ServiceHealth::Healthy => write!(f, "healthy"),
+            ServiceHealth::Unhealthy => write!(f, "unhealthy"),
+        }
+    }
+}
+
+/// Service agent status for UI display
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceInfo {
+    pub health: ServiceHealth,
+    /// Times the process has been restarted
+    pub restarts: u32,
+    /// Seconds since the current process started
+    pub uptime_secs: f64,
+    /// What the health check targets (port or URL)
+    pub check: Option<String>,
 }
 
 /// Represents what is currently displayed in the output area
@@ -248,7 +304,7 @@ impl OutputContext {
 }
 
 /// Terminal screen in UI-agnostic format (for PTY rendering)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TerminalScreen {
     /// Lines of terminal content
     pub lines: Vec<TerminalLine>,
@@ -262,14 +318,14 @@ pub struct TerminalScreen {
 }
 
 /// A single line of terminal content
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TerminalLine {
     /// Cells in this line
     pub cells: Vec<TerminalCell>,
 }
 
 /// A single cell in the terminal
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TerminalCell {
     /// Character in this cell
     pub char: char,
@@ -291,6 +347,85 @@ pub struct TerminalCell {
 
     /// Inverse/reverse video style
     pub inverse: bool,
+
+    /// Right half of a double-width character in the cell before; renderers
+    /// skip it so the row keeps its width
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub continuation: bool,
+}
+
+/// Something a CLI agent reported in its output, as found by its parsers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AgentEvent {
+    /// The agent wrote a file (path as printed, usually workspace-relative)
+    FileChanged { path: PathBuf },
+
+    /// The agent ran a command
+    CommandRun { command: String },
+
+    /// Where the agent is in its work
+    Progress {
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        percent: Option<f32>,
+    },
+
+    /// What the agent's LLM calls cost
+    Cost {
+        /// Cost in USD, if the agent reports it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        usd: Option<f64>,
+        #[serde(default)]
+        input_tokens: u64,
+        #[serde(default)]
+        output_tokens: u64,
+    },
+}
+
+/// The rows of a terminal screen that changed since the previous diff
+///
+/// Diffs for an agent are numbered consecutively. A client applies each to
+/// its copy of the screen; on a gap in `seq` it asks for a resync and
+/// ignores diffs until the keyframe arrives.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenDiff {
+    /// Sequence number, one more than the previous diff's
+    pub seq: u64,
+
+    /// Whether this carries every row and replaces the screen outright
+    pub keyframe: bool,
+
+    /// Terminal dimensions
+    pub cols: u16,
+    pub rows: u16,
+
+    /// Cursor position (col, row)
+    pub cursor: Option<(u16, u16)>,
+
+    /// Changed rows as (row index, content)
+    pub lines: Vec<(u16, TerminalLine)>,
+}
+
+impl ScreenDiff {
+    /// Apply the diff to `screen`; false if it needs a keyframe first
+    pub fn apply(&self, screen: &mut TerminalScreen) -> bool {
+        if !self.keyframe && (screen.cols, screen.rows) != (self.cols, self.rows) {
+            return false;
+        }
+        screen.cols = self.cols;
+        screen.rows = self.rows;
+        screen.cursor = self.cursor;
+        screen
+            .lines
+            .resize_with(self.rows as usize, || TerminalLine { cells: Vec::new() });
+        for (row, line) in &self.lines {
+            if let Some(slot) = screen.lines.get_mut(*row as usize) {
+                *slot = line.clone();
+            }
+        }
+        true
+    }
 }
 
 impl Default for TerminalCell {
@@ -303,6 +438,7 @@ impl Default for TerminalCell {
             underline: false,
             italic: false,
             inverse: false,
+            continuation: false,
         }
     }
 }
@@ -0,0 +1,406 @@
+//! LLM usage and cost accounting
+//!
+//! Every LLM call is appended as a [`UsageRecord`] to `~/.axiom/usage.jsonl`.
+//! Responses are streamed without usage metadata, so token counts are
+//! estimated from text length. Reports group records per day, workspace,
+//! provider and model, priced with [`model_price`].
+
+use crate::error::Result;
+use crate::workspace::{utc_date, WorkspaceId};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Token usage of one LLM call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// When the call finished (Unix epoch seconds)
+    pub timestamp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<WorkspaceId>,
+    /// Workspace name at the time of the call (empty outside a workspace)
+    #[serde(default)]
+    pub workspace: String,
+    pub provider: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Cost in USD as reported by the caller, instead of estimated from
+    /// the model's price
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl UsageRecord {
+    /// Create a record timestamped now, not tied to a workspace
+    pub fn new(
+        provider: impl Into<String>,
+        model: impl Into<String>,
+        input_tokens: u64,
+        output_tokens: u64,
+    ) -> Self {
+        Self {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64,
+            workspace_id: None,
+            workspace: String::new(),
+            provider: provider.into(),
+            model: model.into(),
+            input_tokens,
+            output_tokens,
+            cost_usd: None,
+        }
+    }
+
+    /// Set the cost reported for the call
+    pub fn with_cost(mut self, usd: f64) -> Self {
+        self.cost_usd = Some(usd);
+        self
+    }
+
+    /// Cost in USD: as reported, or estimated with [`model_price`]
+    pub fn cost(&self) -> f64 {
+        self.cost_usd.unwrap_or_else(|| {
+            let (input_price, output_price) = model_price(&self.provider, &self.model);
+            (self.input_tokens as f64 * input_price + self.output_tokens as f64 * output_price)
+                / 1_000_000.0
+        })
+    }
+}
+
+/// Rough token count of `text` (~4 characters per token)
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// List price in USD per million (input, output) tokens
+///
+/// Matched on model name prefixes; local providers and unknown models are
+/// free so the report never overstates spend.
+pub fn model_price(provider: &str, model: &str) -> (f64, f64) {
+    const PRICES: &[(&str, f64, f64)] = &[
+        ("claude-3-haiku", 0.25, 1.25),
+        ("claude-3-5-haiku", 0.8, 4.0),
+        ("claude-haiku", 1.0, 5.0),
+        ("claude-3-opus", 15.0, 75.0),
+        ("claude-opus", 15.0, 75.0),
+        ("claude", 3.0, 15.0),
+        ("gpt-4o-mini", 0.15, 0.6),
+        ("gpt-4o", 2.5, 10.0),
+        ("gpt-4.1-mini", 0.4, 1.6),
+        ("gpt-4.1", 2.0, 8.0),
+        ("gpt-4", 30.0, 60.0),
+        ("gpt-3.5", 0.5, 1.5),
+        ("gemini-1.5-flash", 0.075, 0.3),
+        ("gemini-2.0-flash", 0.1, 0.4),
+        ("gemini-1.5-pro", 1.25, 5.0),
+        ("gemini", 1.25, 10.0),
+    ];
+    if provider == "ollama" {
+        return (0.0, 0.0);
+    }
+    let model = model.to_lowercase();
+    PRICES
+        .iter()
+        .find(|(prefix, _, _)| model.starts_with(prefix))
+        .map(|&(_, input, output)| (input, output))
+        .unwrap_or((0.0, 0.0))
+}
+
+/// Append-only usage log shared by all workspaces
+pub struct UsageLog {
+    path: PathBuf,
+}
+
+impl UsageLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Append a record
+    pub fn append(&self, record: &UsageRecord) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        Ok(())
+    }
+
+    /// All records, oldest first (unreadable lines are skipped)
+    pub fn read(&self) -> Vec<UsageRecord> {
+        fs::read_to_string(&self.path)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Drop every record of workspace `id`; returns how many were dropped
+    pub fn remove_workspace(&self, id: WorkspaceId) -> Result<usize> {
+        let records = self.read();
+        let kept: Vec<&UsageRecord> = records
+            .iter()
+            .filter(|record| record.workspace_id != Some(id))
+            .collect();
+        let dropped = records.len() - kept.len();
+        if dropped > 0 {
+            let mut content = String::new();
+            for record in kept {
+                content.push_str(&serde_json::to_string(record)?);
+                content.push('\n');
+            }
+            fs::write(&self.path, content)?;
+        }
+        Ok(dropped)
+    }
+}
+
+/// Which records a report covers
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UsageFilter {
+    /// First day included (`YYYY-MM-DD`, UTC)
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Last day included (`YYYY-MM-DD`, UTC)
+    #[serde(default)]
+    pub to: Option<String>,
+    /// Workspace ID or name
+    #[serde(default)]
+    pub workspace: Option<String>,
+}
+
+impl UsageFilter {
+    /// Check that `from` and `to` are `YYYY-MM-DD` dates
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        for date in [&self.from, &self.to].into_iter().flatten() {
+            if !is_date(date) {
+                return Err(format!("Invalid date '{}', expected YYYY-MM-DD", date));
+            }
+        }
+        Ok(())
+    }
+
+    fn matches(&self, record: &UsageRecord, date: &str) -> bool {
+        self.from.as_deref().is_none_or(|from| date >= from)
+            && self.to.as_deref().is_none_or(|to| date <= to)
+            && self.workspace.as_deref().is_none_or(|ws| {
+                record.workspace == ws
+                    || record.workspace_id.is_some_and(|id| id.to_string() == ws)
+            })
+    }
+}
+
+fn is_date(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() == 10
+        && b[4] == b'-'
+        && b[7] == b'-'
+        && b.iter()
+            .enumerate()
+            .all(|(i, c)| i == 4 || i == 7 || c.is_ascii_digit())
+}
+
+/// One line of a cost report
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageRow {
+    pub date: String,
+    pub workspace: String,
+    pub provider: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub tokens: u64,
+    /// Estimated cost in USD
+    pub cost: f64,
+}
+
+/// Day, workspace, provider and model of a usage row
+type RowKey = (String, String, String, String);
+
+/// Group records matching `filter` per day, workspace, provider and model
+pub fn usage_rows(records: &[UsageRecord], filter: &UsageFilter) -> Vec<UsageRow> {
+    let mut groups: BTreeMap<RowKey, (u64, u64, f64)> = BTreeMap::new();
+    for record in records {
+        let date = utc_date(record.timestamp);
+        if !filter.matches(record, &date) {
+            continue;
+        }
+        let key = (
+            date,
+            record.workspace.clone(),
+            record.provider.clone(),
+            record.model.clone(),
+        );
+        let entry = groups.entry(key).or_default();
+        entry.0 += record.input_tokens;
+        entry.1 += record.output_tokens;
+        entry.2 += record.cost();
+    }
+
+    groups
+        .into_iter()
+        .map(|((date, workspace, provider, model), (input, output, cost))| UsageRow {
+            date,
+            workspace,
+            provider,
+            model,
+            input_tokens: input,
+            output_tokens: output,
+            tokens: input + output,
+            cost,
+        })
+        .collect()
+}
+
+/// Plain-text totals per provider and model for `/usage`
+pub fn usage_summary(rows: &[UsageRow]) -> String {
+    if rows.is_empty() {
+        return "No LLM usage recorded yet".to_string();
+    }
+    let mut totals: BTreeMap<(&str, &str), (u64, f64)> = BTreeMap::new();
+    for row in rows {
+        let entry = totals.entry((&row.provider, &row.model)).or_default();
+        entry.0 += row.tokens;
+        entry.1 += row.cost;
+    }
+    let tokens: u64 = rows.iter().map(|r| r.tokens).sum();
+    let cost: f64 = rows.iter().map(|r| r.cost).sum();
+
+    let mut text = format!("Usage: ~{} tokens · ${:.2} estimated\n\n", tokens, cost);
+    for ((provider, model), (tokens, cost)) in totals {
+        text.push_str(&format!(
+            "  {:<32} {:>10} tokens  ${:.2}\n",
+            format!("{}:{}", provider, model),
+            tokens,
+            cost
+        ));
+    }
+    text
+}
+
+/// Render rows as CSV with a header line
+///
+/// Costs keep their full precision, so sub-cent rows don't export as zero.
+pub fn rows_to_csv(rows: &[UsageRow]) -> String {
+    let mut csv =
+        String::from("date,workspace,provider,model,input_tokens,output_tokens,tokens,cost_usd\n");
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            row.date,
+            csv_field(&row.workspace),
+            csv_field(&row.provider),
+            csv_field(&row.model),
+            row.input_tokens,
+            row.output_tokens,
+            row.tokens,
+            row.cost
+        ));
+    }
+    csv
+}
+
+/// Quote a CSV field if it contains a separator, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn record(day: i64, workspace: &str, model: &str, input: u64, output: u64) -> UsageRecord {
+        UsageRecord {
+            timestamp: 1_767_225_600 + day * 86_400,
+            workspace_id: None,
+            workspace: workspace.to_string(),
+            provider: "claude".to_string(),
+            model: model.to_string(),
+            input_tokens: input,
+            output_tokens: output,
+            cost_usd: None,
+        }
+    }
+
+    #[test]
+    fn test_rows_group_filter_and_price() {
+        let dir = TempDir::new().unwrap();
+        let log = UsageLog::new(dir.path().join("usage.jsonl"));
+        for r in [
+            record(0, "api", "claude-sonnet-4", 1_000_000, 0),
+            record(0, "api", "claude-sonnet-4", 0, 100_000),
+            record(1, "web, ui", "claude-sonnet-4", 10, 10),
+            record(2, "api", "llama3", 50, 50),
+        ] {
+            log.append(&r).unwrap();
+        }
+        let records = log.read();
+
+        let rows = usage_rows(&records, &UsageFilter::default());
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].date, "2026-01-01");
+        assert_eq!(rows[0].tokens, 1_100_000);
+        assert!((rows[0].cost - 4.5).abs() < 1e-9);
+        assert_eq!(rows[2].cost, 0.0);
+
+        let filter = UsageFilter {
+            from: Some("2026-01-02".to_string()),
+            to: Some("2026-01-02".to_string()),
+            workspace: None,
+        };
+        let rows = usage_rows(&records, &filter);
+        assert_eq!(rows.len(), 1);
+        let csv = rows_to_csv(&rows);
+        assert!(csv.starts_with("date,workspace,provider"));
+        assert!(csv.contains("2026-01-02,\"web, ui\",claude,claude-sonnet-4,10,10,20,0.00018"));
+    }
+
+    #[test]
+    fn test_reported_cost() {
+        let records = [
+            record(0, "api", "cli", 1_000, 100).with_cost(0.25),
+            record(0, "api", "cli", 0, 0).with_cost(0.5),
+        ];
+        let rows = usage_rows(&records, &UsageFilter::default());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].tokens, 1_100);
+        assert!((rows[0].cost - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_csv_keeps_sub_cent_cost() {
+        let records = [record(0, "api", "cli", 10, 0).with_cost(0.00003)];
+        let csv = rows_to_csv(&usage_rows(&records, &UsageFilter::default()));
+        let cost: f64 = csv.trim_end().rsplit(',').next().unwrap().parse().unwrap();
+        assert!(cost > 0.0);
+        assert!((cost - 0.00003).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_filter_validation() {
+        let mut filter = UsageFilter {
+            from: Some("2026-01-01".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.validate().is_ok());
+        filter.to = Some("yesterday".to_string());
+        assert!(filter.validate().is_err());
+        assert_eq!(estimate_tokens("abcdefgh!"), 3);
+    }
+}
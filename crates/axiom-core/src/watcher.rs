@@ -0,0 +1,394 @@
+//! File watcher for a workspace root
+//!
+//! Each [`AxiomService`](crate::AxiomService) watches its workspace
+//! directory and reports changed files as
+//! [`Notification::FileChanged`](crate::Notification::FileChanged).
+//!
+//! Ignored paths come from three places, in increasing precedence:
+//! built-in defaults (build output, VCS and cache directories, dotfiles),
+//! the root `.gitignore`, and `ignore_patterns` in the workspace's
+//! `.axiom/config.toml`. Ignored directories aren't watched at all, so large
+//! `target/` or `node_modules/` trees cost nothing.
+//!
+//! Changes are debounced: a file is reported once it has been quiet for
+//! [`DEFAULT_DEBOUNCE`], however many writes led up to that.
+//!
+//! Config files are watched too, though they are usually ignored dotfiles or
+//! outside the root, and reported separately by
+//! [`FileWatcher::poll_config`].
+
+use crate::error::{AxiomError, Result};
+use crate::workspace::glob_match;
+use crossbeam_channel::{unbounded, Receiver};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Quiet period before a changed file is reported
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Paths ignored in every workspace (gitignore syntax)
+const DEFAULT_IGNORES: &[&str] = &[
+    ".*",
+    "target/",
+    "node_modules/",
+    "__pycache__/",
+    "venv/",
+    "dist/",
+    "build/",
+    "*.pyc",
+    "*.pyo",
+    "*.class",
+    "*.o",
+    "*.a",
+    "*.so",
+    "*.dylib",
+    "*.lock",
+    "*.log",
+    "*.tmp",
+    "*.swp",
+    "*.swo",
+    "*~",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IgnoreRule {
+    pattern: String,
+    /// `!pattern`: re-include what an earlier rule ignored
+    negated: bool,
+    /// Contains a `/` before its end: matches from the root only
+    anchored: bool,
+    /// Ends in `/`: matches directories only
+    dir_only: bool,
+}
+
+impl IgnoreRule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let dir_only = line.ends_with('/');
+        let line = line.trim_end_matches('/');
+        let anchored = line.contains('/');
+        let pattern = line.trim_start_matches('/').to_string();
+        if pattern.is_empty() {
+            return None;
+        }
+        Some(Self {
+            pattern,
+            negated,
+            anchored,
+            dir_only,
+        })
+    }
+
+    /// Whether the rule matches `path` (relative, `/`-separated)
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if self.anchored {
+            glob_match(&self.pattern, path)
+        } else {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            glob_match(&self.pattern, name)
+        }
+    }
+}
+
+/// Gitignore-style rules deciding which paths aren't watched
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IgnoreRules {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreRules {
+    /// Rules from gitignore-syntax lines; later lines take precedence
+    pub fn new<I, S>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            rules: lines
+                .into_iter()
+                .filter_map(|l| IgnoreRule::parse(l.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// Defaults, then `root/.gitignore`, then the workspace's `extra` patterns
+    pub fn load(root: &Path, extra: &[String]) -> Self {
+        let gitignore = std::fs::read_to_string(root.join(".gitignore")).unwrap_or_default();
+        let lines = DEFAULT_IGNORES
+            .iter()
+            .copied()
+            .chain(gitignore.lines())
+            .chain(extra.iter().map(String::as_str));
+        Self::new(lines)
+    }
+
+    /// Whether `path` (relative to the root, `/`-separated) is ignored
+    ///
+    /// As with git, nothing below an ignored directory can be re-included.
+    pub fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+        let path = path.trim_matches('/');
+        if path.is_empty() {
+            return false;
+        }
+        let ends = path
+            .match_indices('/')
+            .map(|(i, _)| i)
+            .chain(std::iter::once(path.len()));
+        for end in ends {
+            let prefix = &path[..end];
+            let prefix_is_dir = end < path.len() || is_dir;
+            let last = self
+                .rules
+                .iter()
+                .rev()
+                .find(|rule| rule.matches(prefix, prefix_is_dir));
+            if last.is_some_and(|rule| !rule.negated) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Changed paths waiting out the debounce
+#[derive(Debug, Default)]
+struct Debouncer {
+    pending: HashMap<PathBuf, Instant>,
+}
+
+impl Debouncer {
+    fn record(&mut self, path: PathBuf, now: Instant) {
+        self.pending.insert(path, now);
+    }
+
+    /// Paths quiet for at least `debounce`, sorted
+    fn due(&mut self, now: Instant, debounce: Duration) -> Vec<PathBuf> {
+        let mut due: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, &last)| now.duration_since(last) >= debounce)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in &due {
+            self.pending.remove(path);
+        }
+        due.sort();
+        due
+    }
+
+    fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// Watches a workspace root, skipping ignored directories
+pub struct FileWatcher {
+    root: PathBuf,
+    rules: IgnoreRules,
+    watcher: RecommendedWatcher,
+    raw_rx: Receiver<notify::Result<notify::Event>>,
+    pending: Debouncer,
+    /// Config files watched with [`FileWatcher::watch_config`]
+    config_files: HashSet<PathBuf>,
+    config_pending: Debouncer,
+    debounce: Duration,
+    enabled: bool,
+}
+
+impl FileWatcher {
+    /// Start watching `root` with the workspace's extra ignore patterns
+    pub fn new(root: &Path, extra_ignores: &[String]) -> Result<Self> {
+        let (raw_tx, raw_rx) = unbounded();
+        let watcher = notify::recommended_watcher(move |res| {
+            let _ = raw_tx.send(res);
+        })
+        .map_err(watch_error)?;
+
+        let mut this = Self {
+            root: root.to_path_buf(),
+            rules: IgnoreRules::load(root, extra_ignores),
+            watcher,
+            raw_rx,
+            pending: Debouncer::default(),
+            config_files: HashSet::new(),
+            config_pending: Debouncer::default(),
+            debounce: DEFAULT_DEBOUNCE,
+            enabled: true,
+        };
+        this.watch_tree(root)?;
+        Ok(this)
+    }
+
+    /// Use a different quiet period
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Pause or resume reporting; changes made while paused are dropped
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.pending.clear();
+    }
+
+    /// Files whose changes have settled at `now`
+    ///
+    /// Call this regularly; it also starts watching new directories.
+    pub fn poll(&mut self, now: Instant) -> Vec<PathBuf> {
+        while let Ok(res) = self.raw_rx.try_recv() {
+            let Ok(event) = res else { continue };
+            if !self.enabled || !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                continue;
+            }
+            for path in event.paths {
+                if self.config_files.contains(&path) {
+                    self.config_pending.record(path, now);
+                    continue;
+                }
+                let Some(relative) = self.relative(&path) else {
+                    continue;
+                };
+                let is_dir = path.is_dir();
+                if self.rules.is_ignored(&relative, is_dir) {
+                    continue;
+                }
+                if is_dir {
+                    // New directories aren't covered by the non-recursive watches
+                    let _ = self.watch_tree(&path);
+                } else if path.is_file() {
+                    self.pending.record(path, now);
+                }
+            }
+        }
+        self.pending.due(now, self.debounce)
+    }
+
+    /// Also watch `files`, reported by [`FileWatcher::poll_config`]; a file
+    /// whose directory doesn't exist yet isn't watched
+    pub fn watch_config(&mut self, files: &[PathBuf]) {
+        for file in files {
+            if let Some(dir) = file.parent().filter(|dir| dir.is_dir()) {
+                if self.watcher.watch(dir, RecursiveMode::NonRecursive).is_ok() {
+                    self.config_files.insert(file.clone());
+                }
+            }
+        }
+    }
+
+    /// Config files whose changes have settled, after [`FileWatcher::poll`]
+    pub fn poll_config(&mut self, now: Instant) -> Vec<PathBuf> {
+        self.config_pending.due(now, self.debounce)
+    }
+
+    fn relative(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        Some(relative.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// Watch `dir` and every directory below it that isn't ignored
+    fn watch_tree(&mut self, dir: &Path) -> Result<()> {
+        self.watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Ok(());
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+            if !is_dir {
+                continue;
+            }
+            match self.relative(&path) {
+                Some(relative) if !self.rules.is_ignored(&relative, true) => {
+                    // Unreadable subdirectories don't stop the rest
+                    let _ = self.watch_tree(&path);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn watch_error(e: notify::Error) -> AxiomError {
+    AxiomError::Config(format!("File watcher error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_rules() {
+        let rules = IgnoreRules::new([
+            "target/",
+            "*.log",
+            "/docs/build",
+            "!keep.log",
+            "src/**/gen_*.rs",
+        ]);
+        assert!(rules.is_ignored("target", true));
+        assert!(rules.is_ignored("crates/core/target/debug/app", false));
+        assert!(!rules.is_ignored("target", false));
+        assert!(rules.is_ignored("logs/app.log", false));
+        assert!(!rules.is_ignored("logs/keep.log", false));
+        assert!(rules.is_ignored("docs/build/index.html", false));
+        assert!(!rules.is_ignored("web/docs/build/index.html", false));
+        assert!(rules.is_ignored("src/a/b/gen_api.rs", false));
+        assert!(!rules.is_ignored("src/main.rs", false));
+        assert!(!rules.is_ignored("", true));
+
+        // Nothing below an ignored directory comes back
+        let rules = IgnoreRules::new(["out/", "!out/keep.txt"]);
+        assert!(rules.is_ignored("out/keep.txt", false));
+    }
+
+    #[test]
+    fn test_load_uses_gitignore_and_extra_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "# comment\ncoverage/\n").unwrap();
+        let rules = IgnoreRules::load(dir.path(), &["*.snap".to_string()]);
+
+        assert!(rules.is_ignored("coverage/lcov.info", false));
+        assert!(rules.is_ignored("tests/ui.snap", false));
+        assert!(rules.is_ignored(".env", false));
+        assert!(rules.is_ignored("node_modules/x/index.js", false));
+        assert!(!rules.is_ignored("src/lib.rs", false));
+    }
+
+    #[test]
+    fn test_debouncer_reports_quiet_files_once() {
+        let mut pending = Debouncer::default();
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let debounce = Duration::from_millis(300);
+
+        pending.record(PathBuf::from("b.rs"), start);
+        pending.record(PathBuf::from("a.rs"), start);
+        pending.record(PathBuf::from("a.rs"), ms(200));
+        assert_eq!(pending.due(ms(300), debounce), [PathBuf::from("b.rs")]);
+        assert_eq!(pending.due(ms(500), debounce), [PathBuf::from("a.rs")]);
+        assert!(pending.due(ms(900), debounce).is_empty());
+    }
+}
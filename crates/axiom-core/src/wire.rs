@@ -0,0 +1,275 @@
+//! Compact WebSocket framing for notifications
+//!
+//! Clients choose a format in the `Sec-WebSocket-Protocol` handshake. With
+//! [`PROTOCOL_BINARY`] the streams that dominate traffic (PTY output,
+//! screens and screen diffs, agent output) arrive as binary frames, and other notifications
+//! large enough to be worth it arrive deflated. With [`PROTOCOL_JSON`], or
+//! no protocol at all, every notification is a JSON text frame.
+//!
+//! A binary frame is laid out as
+//!
+//! | Bytes | Field                                                        |
+//! |-------|--------------------------------------------------------------|
+//! | 1     | Kind: 0 JSON, 1 `PtyOutput`, 2 `AgentOutput`, 3 `PtyScreen`, 4 `PtyScreenDiff` |
+//! | 1     | Flags: bit 0 is set when the payload is deflated (RFC 1951)  |
+//! | 8     | Agent ID, big-endian; absent for kind 0                      |
+//! | rest  | PTY bytes, UTF-8 text, the screen or diff as JSON, or (kind 0) the whole notification as JSON |
+//!
+//! The WebSocket library has no permessage-deflate, so payloads of at least
+//! [`COMPRESS_MIN`] bytes are deflated frame by frame instead, whenever that
+//! makes them smaller.
+
+use crate::error::{AxiomError, Result};
+use crate::notifications::Notification;
+use crate::types::AgentId;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::borrow::Cow;
+use std::io::{Read, Write};
+
+/// Subprotocol for binary frames
+pub const PROTOCOL_BINARY: &str = "axiom.binary.v1";
+
+/// Subprotocol for JSON text frames only
+pub const PROTOCOL_JSON: &str = "axiom.json.v1";
+
+/// Smallest payload worth deflating
+pub const COMPRESS_MIN: usize = 512;
+
+const KIND_JSON: u8 = 0;
+const KIND_PTY_OUTPUT: u8 = 1;
+const KIND_AGENT_OUTPUT: u8 = 2;
+const KIND_PTY_SCREEN: u8 = 3;
+const KIND_PTY_SCREEN_DIFF: u8 = 4;
+
+const FLAG_DEFLATE: u8 = 1;
+
+/// An encoded WebSocket message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+/// How notifications are sent on a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireFormat {
+    #[default]
+    Json,
+    Binary,
+}
+
+impl WireFormat {
+    /// Subprotocols the server accepts, most preferred first
+    pub const PROTOCOLS: [&'static str; 2] = [PROTOCOL_BINARY, PROTOCOL_JSON];
+
+    /// The format for the subprotocol agreed in the handshake
+    pub fn from_protocol(protocol: Option<&str>) -> Self {
+        match protocol {
+            Some(PROTOCOL_BINARY) => Self::Binary,
+            _ => Self::Json,
+        }
+    }
+
+    /// Encode `notification` for the wire
+    pub fn encode(self, notification: &Notification) -> Result<Frame> {
+        if self == Self::Json {
+            return Ok(Frame::Text(serde_json::to_string(notification)?));
+        }
+        let (kind, id, payload): (u8, _, Cow<[u8]>) = match notification {
+            Notification::PtyOutput { id, data } => (KIND_PTY_OUTPUT, *id, data.into()),
+            Notification::AgentOutput { id, chunk } => {
+                (KIND_AGENT_OUTPUT, *id, chunk.as_bytes().into())
+            }
+            Notification::PtyScreen { id, screen } => {
+                (KIND_PTY_SCREEN, *id, serde_json::to_vec(screen)?.into())
+            }
+            Notification::PtyScreenDiff { id, diff } => {
+                (KIND_PTY_SCREEN_DIFF, *id, serde_json::to_vec(diff)?.into())
+            }
+            other => {
+                let json = serde_json::to_string(other)?;
+                return Ok(match deflate(json.as_bytes()) {
+                    Some(deflated) => {
+                        Frame::Binary(frame(KIND_JSON, None, FLAG_DEFLATE, &deflated))
+                    }
+                    None => Frame::Text(json),
+                });
+            }
+        };
+        Ok(Frame::Binary(match deflate(&payload) {
+            Some(deflated) => frame(kind, Some(id), FLAG_DEFLATE, &deflated),
+            None => frame(kind, Some(id), 0, &payload),
+        }))
+    }
+}
+
+fn frame(kind: u8, id: Option<AgentId>, flags: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(10 + payload.len());
+    out.push(kind);
+    out.push(flags);
+    if let Some(id) = id {
+        out.extend_from_slice(&id.value().to_be_bytes());
+    }
+    out.extend_from_slice(payload);
+    out
+}
+
+/// `data` deflated, if it's long enough and that makes it smaller
+fn deflate(data: &[u8]) -> Option<Vec<u8>> {
+    if data.len() < COMPRESS_MIN {
+        return None;
+    }
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+    encoder.write_all(data).ok()?;
+    encoder.finish().ok().filter(|out| out.len() < data.len())
+}
+
+/// Decode a binary frame
+pub fn decode(frame: &[u8]) -> Result<Notification> {
+    let malformed = || AxiomError::Serialization("Malformed binary frame".to_string());
+    let [kind, flags, rest @ ..] = frame else {
+        return Err(malformed());
+    };
+    let inflate = |body: &[u8]| -> Result<Vec<u8>> {
+        if flags & FLAG_DEFLATE == 0 {
+            return Ok(body.to_vec());
+        }
+        let mut out = Vec::new();
+        DeflateDecoder::new(body).read_to_end(&mut out)?;
+        Ok(out)
+    };
+    if *kind == KIND_JSON {
+        return Ok(serde_json::from_slice(&inflate(rest)?)?);
+    }
+    let (id, body) = rest.split_first_chunk::<8>().ok_or_else(malformed)?;
+    let id = AgentId::new(u64::from_be_bytes(*id));
+    let payload = inflate(body)?;
+
+    Ok(match *kind {
+        KIND_PTY_OUTPUT => Notification::PtyOutput { id, data: payload },
+        KIND_AGENT_OUTPUT => Notification::AgentOutput {
+            id,
+            chunk: String::from_utf8(payload).map_err(|_| malformed())?,
+        },
+        KIND_PTY_SCREEN => Notification::PtyScreen {
+            id,
+            screen: serde_json::from_slice(&payload)?,
+        },
+        KIND_PTY_SCREEN_DIFF => Notification::PtyScreenDiff {
+            id,
+            diff: serde_json::from_slice(&payload)?,
+        },
+        _ => return Err(malformed()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ScreenDiff, TerminalScreen};
+
+    #[test]
+    fn test_negotiation_and_json_fallback() {
+        assert_eq!(
+            WireFormat::from_protocol(Some(PROTOCOL_BINARY)),
+            WireFormat::Binary
+        );
+        assert_eq!(WireFormat::from_protocol(Some("other")), WireFormat::Json);
+        assert_eq!(WireFormat::from_protocol(None), WireFormat::Json);
+
+        let output = Notification::AgentOutput {
+            id: AgentId::new(3),
+            chunk: "hi".to_string(),
+        };
+        let Frame::Text(json) = WireFormat::Json.encode(&output).unwrap() else {
+            panic!("expected text");
+        };
+        assert!(json.contains("\"chunk\":\"hi\""));
+
+        // Small notifications that aren't streams stay text
+        let info = Notification::info("saved");
+        assert!(matches!(
+            WireFormat::Binary.encode(&info).unwrap(),
+            Frame::Text(_)
+        ));
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let id = AgentId::new(0x0102);
+        let small = Notification::PtyOutput {
+            id,
+            data: b"\x1b[1mok".to_vec(),
+        };
+        let Frame::Binary(bytes) = WireFormat::Binary.encode(&small).unwrap() else {
+            panic!("expected binary");
+        };
+        assert_eq!(&bytes[..10], &[1, 0, 0, 0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(&bytes[10..], b"\x1b[1mok");
+        assert!(matches!(decode(&bytes).unwrap(),
+            Notification::PtyOutput { id: got, data } if got == id && data == b"\x1b[1mok"));
+
+        // Repetitive output is deflated
+        let chunk = "compiling axiom-core\n".repeat(100);
+        let large = Notification::AgentOutput {
+            id,
+            chunk: chunk.clone(),
+        };
+        let Frame::Binary(bytes) = WireFormat::Binary.encode(&large).unwrap() else {
+            panic!("expected binary");
+        };
+        assert_eq!(bytes[1], FLAG_DEFLATE);
+        assert!(bytes.len() < chunk.len() / 4);
+        assert!(matches!(decode(&bytes).unwrap(),
+            Notification::AgentOutput { chunk: got, .. } if got == chunk));
+
+        let screen = Notification::PtyScreen {
+            id,
+            screen: TerminalScreen {
+                lines: Vec::new(),
+                cursor: Some((4, 2)),
+                cols: 80,
+                rows: 24,
+            },
+        };
+        let Frame::Binary(bytes) = WireFormat::Binary.encode(&screen).unwrap() else {
+            panic!("expected binary");
+        };
+        assert!(matches!(decode(&bytes).unwrap(),
+            Notification::PtyScreen { screen, .. } if screen.cols == 80 && screen.cursor == Some((4, 2))));
+
+        let diff = Notification::PtyScreenDiff {
+            id,
+            diff: ScreenDiff {
+                seq: 7,
+                keyframe: false,
+                cols: 80,
+                rows: 24,
+                cursor: None,
+                lines: Vec::new(),
+            },
+        };
+        let Frame::Binary(bytes) = WireFormat::Binary.encode(&diff).unwrap() else {
+            panic!("expected binary");
+        };
+        assert_eq!(bytes[0], KIND_PTY_SCREEN_DIFF);
+        assert!(matches!(decode(&bytes).unwrap(),
+            Notification::PtyScreenDiff { diff, .. } if diff.seq == 7));
+
+        assert!(decode(&[2, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_large_json_is_deflated() {
+        let warning = Notification::warning("dropped ".repeat(200));
+        let Frame::Binary(bytes) = WireFormat::Binary.encode(&warning).unwrap() else {
+            panic!("expected binary");
+        };
+        assert_eq!(&bytes[..2], &[KIND_JSON, FLAG_DEFLATE]);
+        assert!(
+            matches!(decode(&bytes).unwrap(), Notification::Warning { message } if message.len() == 1600)
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Per-workspace activity feed
+//!
+//! Agent runs, file changes and notifications are appended to a JSON-lines
+//! log (`~/.axiom/activity/<workspace-id>.jsonl`) by whichever UI did the
+//! work. Git commits are read from the repository when the feed is queried,
+//! so the timeline also covers work done outside Axiom.
+
+use super::audit::{AuditKey, ChainLink};
+use super::worktree::{commit_count, recent_commits, CommitInfo};
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// What an activity entry is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    /// An agent (or shell command) started, finished or failed
+    AgentRun,
+    /// A file was written
+    FileChange,
+    /// A git commit (read from the repository)
+    GitCommit,
+    /// An error or warning shown to the user
+    Notification,
+}
+
+impl ActivityKind {
+    /// Short label for display
+    pub fn label(self) -> &'static str {
+        match self {
+            ActivityKind::AgentRun => "agent",
+            ActivityKind::FileChange => "file",
+            ActivityKind::GitCommit => "commit",
+            ActivityKind::Notification => "notice",
+        }
+    }
+}
+
+/// One entry in the activity feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    /// When it happened (Unix epoch seconds)
+    pub timestamp: i64,
+    pub kind: ActivityKind,
+    /// Who did it: "user", an agent name, or a commit author
+    pub actor: String,
+    pub summary: String,
+    /// File the entry refers to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Longer explanation, e.g. an agent's reasoning for a change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Hash-chain link when the workspace signs its log
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<ChainLink>,
+}
+
+impl ActivityEvent {
+    /// Create an entry timestamped now
+    pub fn new(kind: ActivityKind, actor: impl Into<String>, summary: impl Into<String>) -> Self {
+        Self {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64,
+            kind,
+            actor: actor.into(),
+            summary: summary.into(),
+            path: None,
+            detail: None,
+            chain: None,
+        }
+    }
+
+    /// Attach the file this entry refers to
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Attach a longer explanation
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+impl From<CommitInfo> for ActivityEvent {
+    fn from(commit: CommitInfo) -> Self {
+        Self {
+            timestamp: commit.timestamp,
+            kind: ActivityKind::GitCommit,
+            actor: commit.author,
+            summary: format!("{} {}", commit.hash, commit.subject),
+            path: None,
+            detail: None,
+            chain: None,
+        }
+    }
+}
+
+/// One page of the feed, newest first
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityPage {
+    pub events: Vec<ActivityEvent>,
+    /// Entries in the whole feed
+    pub total: usize,
+    /// Offset of the next page, if there is one
+    pub next_offset: Option<usize>,
+    /// MAC of the newest signed entry, if the log is signed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_head: Option<String>,
+}
+
+/// Append-only activity log of one workspace
+pub struct ActivityLog {
+    path: PathBuf,
+}
+
+impl ActivityLog {
+    /// Entries kept when the log is compacted
+    const MAX_EVENTS: usize = 5000;
+
+    /// Log size that triggers compaction
+    const COMPACT_BYTES: u64 = 2 * 1024 * 1024;
+
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// File the log is kept in
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry linked to the end of the signed chain
+    pub fn append_signed(&self, event: &ActivityEvent, key: &AuditKey) -> Result<()> {
+        let mut event = event.clone();
+        event.chain = Some(key.link(self.read().last(), &event));
+        self.append(&event)
+    }
+
+    /// Append an entry, dropping the oldest ones once the log grows large
+    pub fn append(&self, event: &ActivityEvent) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
+
+        if file.metadata()?.len() > Self::COMPACT_BYTES {
+            let events = self.read();
+            let keep = &events[events.len().saturating_sub(Self::MAX_EVENTS)..];
+            let mut content = String::new();
+            for event in keep {
+                content.push_str(&serde_json::to_string(event)?);
+                content.push('\n');
+            }
+            fs::write(&self.path, content)?;
+        }
+        Ok(())
+    }
+
+    /// All entries, oldest first (unreadable lines are skipped)
+    pub fn read(&self) -> Vec<ActivityEvent> {
+        fs::read_to_string(&self.path)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Delete the log file
+    pub fn remove(&self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Merge logged entries with the git history of `repo` and return one page,
+/// newest first
+pub fn timeline(logged: Vec<ActivityEvent>, repo: &Path, offset: usize, limit: usize) -> ActivityPage {
+    let total = logged.len() + commit_count(repo);
+    let chain_head = logged
+        .iter()
+        .rev()
+        .find_map(|e| e.chain.as_ref().map(|link| link.mac.clone()));
+
+    // Commits beyond offset + limit can't land on this page
+    let mut events = logged;
+    events.extend(
+        recent_commits(repo, offset + limit)
+            .into_iter()
+            .map(ActivityEvent::from),
+    );
+    events.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+
+    let events: Vec<ActivityEvent> = events.into_iter().skip(offset).take(limit).collect();
+    let end = offset + events.len();
+    ActivityPage {
+        events,
+        total,
+        next_offset: (end < total).then_some(end),
+        chain_head,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_log_and_timeline() {
+        let dir = TempDir::new().unwrap();
+        let log = ActivityLog::new(dir.path().join("activity").join("ws.jsonl"));
+        assert!(log.read().is_empty());
+
+        for i in 0..3 {
+            let mut event = ActivityEvent::new(ActivityKind::FileChange, "user", format!("edit {}", i))
+                .with_path("src/main.rs");
+            event.timestamp = i;
+            log.append(&event).unwrap();
+        }
+        let events = log.read();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].path.as_deref(), Some(Path::new("src/main.rs")));
+
+        // Not a git repo: only logged entries, newest first, paged
+        let page = timeline(events, dir.path(), 0, 2);
+        assert_eq!(page.total, 3);
+        assert_eq!(page.events[0].summary, "edit 2");
+        assert_eq!(page.next_offset, Some(2));
+
+        let page = timeline(log.read(), dir.path(), 2, 2);
+        assert_eq!(page.events.len(), 1);
+        assert_eq!(page.next_offset, None);
+    }
+}
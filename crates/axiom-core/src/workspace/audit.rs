@@ -0,0 +1,310 @@
+//! Tamper-evident activity logs
+//!
+//! With `[audit] sign = true` in a workspace's `.axiom/config.toml`, every
+//! activity entry is appended with a [`ChainLink`]: a sequence number, the
+//! MAC of the previous entry and an HMAC-SHA256 over both plus the entry
+//! itself, keyed with a per-machine secret in `~/.axiom/audit.key`. Editing,
+//! reordering or removing an entry breaks the chain, which
+//! [`verify_chain`] reports.
+//!
+//! Compaction drops the oldest entries, so a chain may start at a sequence
+//! number above zero; that is reported, not flagged.
+
+use super::activity::ActivityEvent;
+use crate::error::{AxiomError, Result};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// `prev` of the first entry in a chain
+pub const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Audit settings of a workspace
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// Hash-chain and sign activity entries
+    #[serde(default)]
+    pub sign: bool,
+}
+
+/// Position of an entry in the signed chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainLink {
+    pub seq: u64,
+    /// MAC of the previous entry ([`GENESIS`] for the first)
+    pub prev: String,
+    /// HMAC-SHA256 of this entry, hex encoded
+    pub mac: String,
+}
+
+/// Local secret used to sign activity entries
+pub struct AuditKey {
+    key: hmac::Key,
+}
+
+impl AuditKey {
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret),
+        }
+    }
+
+    /// Load the key at `path`, generating it on first use
+    pub fn load_or_create(path: &Path) -> Result<Self> {
+        if let Ok(hex) = fs::read_to_string(path) {
+            let secret = from_hex(hex.trim()).ok_or_else(|| {
+                AxiomError::Config(format!("Invalid audit key in {}", path.display()))
+            })?;
+            return Ok(Self::new(&secret));
+        }
+
+        let mut secret = [0u8; 32];
+        SystemRandom::new()
+            .fill(&mut secret)
+            .map_err(|_| AxiomError::Config("Failed to generate audit key".to_string()))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, to_hex(&secret))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(Self::new(&secret))
+    }
+
+    /// Load the key at `path` if it exists
+    pub fn load(path: &Path) -> Option<Self> {
+        let hex = fs::read_to_string(path).ok()?;
+        Some(Self::new(&from_hex(hex.trim())?))
+    }
+
+    fn mac(&self, seq: u64, prev: &str, event: &ActivityEvent) -> String {
+        let mut unsigned = event.clone();
+        unsigned.chain = None;
+        let body = serde_json::to_string(&unsigned).unwrap_or_default();
+        let tag = hmac::sign(&self.key, format!("{}\n{}\n{}", seq, prev, body).as_bytes());
+        to_hex(tag.as_ref())
+    }
+
+    /// Link `event` after `last`, the entry currently at the end of the log
+    pub fn link(&self, last: Option<&ActivityEvent>, event: &ActivityEvent) -> ChainLink {
+        let (seq, prev) = match last.and_then(|e| e.chain.as_ref()) {
+            Some(link) => (link.seq + 1, link.mac.clone()),
+            None => (0, GENESIS.to_string()),
+        };
+        let mac = self.mac(seq, &prev, event);
+        ChainLink { seq, prev, mac }
+    }
+}
+
+/// Result of checking a workspace's activity log
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AuditReport {
+    /// Entries in the log
+    pub entries: usize,
+    /// Entries carrying a chain link
+    pub signed: usize,
+    /// Sequence number the chain starts at (above 0 after compaction)
+    pub first_seq: Option<u64>,
+    /// MAC of the last signed entry
+    pub chain_head: Option<String>,
+    /// Whether MACs were checked (false without the local key)
+    pub macs_checked: bool,
+    /// Tampering or gaps found, by 1-based entry number
+    pub problems: Vec<String>,
+}
+
+impl AuditReport {
+    /// Whether no tampering or gaps were found
+    pub fn is_intact(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Plain-text report for `/verify-audit`
+    pub fn summary(&self) -> String {
+        if self.signed == 0 {
+            return format!(
+                "Audit log has {} unsigned entries (enable [audit] sign = true in .axiom/config.toml)",
+                self.entries
+            );
+        }
+        let mut text = if self.is_intact() {
+            format!("Audit log intact: {} signed entries", self.signed)
+        } else {
+            format!("Audit log TAMPERED: {} problem(s)", self.problems.len())
+        };
+        if let Some(head) = &self.chain_head {
+            text.push_str(&format!("\nChain head: {}", head));
+        }
+        if let Some(seq) = self.first_seq.filter(|&seq| seq > 0) {
+            text.push_str(&format!(
+                "\nChain starts at #{} (older entries compacted)",
+                seq
+            ));
+        }
+        if !self.macs_checked {
+            text.push_str("\nAudit key not found: only the chain links were checked");
+        }
+        for problem in &self.problems {
+            text.push_str(&format!("\n  {}", problem));
+        }
+        text
+    }
+}
+
+/// Check the links (and, with `key`, the MACs) of a log's entries
+///
+/// Unsigned entries are fine before the chain starts; after it they mean
+/// an entry was inserted or stripped of its link.
+pub fn verify_chain(events: &[ActivityEvent], key: Option<&AuditKey>) -> AuditReport {
+    let mut report = AuditReport {
+        entries: events.len(),
+        macs_checked: key.is_some(),
+        ..Default::default()
+    };
+    let mut last: Option<&ChainLink> = None;
+
+    for (i, event) in events.iter().enumerate() {
+        let n = i + 1;
+        let Some(link) = &event.chain else {
+            if last.is_some() {
+                report
+                    .problems
+                    .push(format!("entry {}: unsigned but inside the chain", n));
+            }
+            continue;
+        };
+        report.signed += 1;
+
+        match last {
+            None => {
+                report.first_seq = Some(link.seq);
+                if link.seq == 0 && link.prev != GENESIS {
+                    report
+                        .problems
+                        .push(format!("entry {}: first entry doesn't start the chain", n));
+                }
+            }
+            Some(prev) => {
+                if link.seq != prev.seq + 1 {
+                    report.problems.push(format!(
+                        "entry {}: expected #{}, found #{} (entries missing or reordered)",
+                        n,
+                        prev.seq + 1,
+                        link.seq
+                    ));
+                } else if link.prev != prev.mac {
+                    report
+                        .problems
+                        .push(format!("entry {}: doesn't link to the previous entry", n));
+                }
+            }
+        }
+        if let Some(key) = key {
+            if key.mac(link.seq, &link.prev, event) != link.mac {
+                report
+                    .problems
+                    .push(format!("entry {}: contents modified (#{})", n, link.seq));
+            }
+        }
+        last = Some(link);
+    }
+
+    report.chain_head = last.map(|link| link.mac.clone());
+    report
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::ActivityKind;
+
+    fn signed_log(key: &AuditKey, n: usize) -> Vec<ActivityEvent> {
+        let mut events: Vec<ActivityEvent> = Vec::new();
+        for i in 0..n {
+            let mut event =
+                ActivityEvent::new(ActivityKind::AgentRun, "coder", format!("run {}", i));
+            event.chain = Some(key.link(events.last(), &event));
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn test_intact_chain() {
+        let key = AuditKey::new(b"secret");
+        let mut events = vec![ActivityEvent::new(
+            ActivityKind::Notification,
+            "axiom",
+            "old",
+        )];
+        events.extend(signed_log(&key, 3));
+
+        let report = verify_chain(&events, Some(&key));
+        assert!(report.is_intact(), "{:?}", report.problems);
+        assert_eq!(report.signed, 3);
+        assert_eq!(report.first_seq, Some(0));
+        assert_eq!(
+            report.chain_head,
+            events[3].chain.as_ref().map(|l| l.mac.clone())
+        );
+    }
+
+    #[test]
+    fn test_detects_edits_and_gaps() {
+        let key = AuditKey::new(b"secret");
+
+        let mut edited = signed_log(&key, 3);
+        edited[1].summary = "run nothing".to_string();
+        let report = verify_chain(&edited, Some(&key));
+        assert_eq!(report.problems, vec!["entry 2: contents modified (#1)"]);
+        // Without the key the edit goes unnoticed, but gaps don't
+        assert!(verify_chain(&edited, None).is_intact());
+
+        let mut gap = signed_log(&key, 3);
+        gap.remove(1);
+        assert!(!verify_chain(&gap, None).is_intact());
+
+        let mut inserted = signed_log(&key, 2);
+        inserted.insert(1, ActivityEvent::new(ActivityKind::FileChange, "user", "x"));
+        let report = verify_chain(&inserted, Some(&key));
+        assert_eq!(
+            report.problems,
+            vec!["entry 2: unsigned but inside the chain"]
+        );
+
+        let forged = signed_log(&AuditKey::new(b"other"), 2);
+        assert_eq!(verify_chain(&forged, Some(&key)).problems.len(), 2);
+    }
+
+    #[test]
+    fn test_key_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("audit.key");
+        assert!(AuditKey::load(&path).is_none());
+
+        let created = AuditKey::load_or_create(&path).unwrap();
+        let loaded = AuditKey::load(&path).unwrap();
+        let event = ActivityEvent::new(ActivityKind::AgentRun, "coder", "run");
+        assert_eq!(created.link(None, &event), loaded.link(None, &event));
+        assert_eq!(fs::read_to_string(&path).unwrap().len(), 64);
+    }
+}
@@ -0,0 +1,227 @@
+//! Moving a workspace's Axiom data to another machine
+//!
+//! A [`WorkspaceBundle`] carries a workspace's name and tags and, when
+//! exported with state, the files in its `.axiom/` directory: settings,
+//! prompt templates, saved sessions, the plan and whatever else Axiom keeps
+//! there. The project's own files are never included, and neither are
+//! caches and logs that only mean something on the machine that made them.
+//! Importing unpacks the files under a directory on the new machine and
+//! rewrites the old root path inside them to the new one.
+
+use super::types::Workspace;
+use crate::error::{AxiomError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bundle layout version; bundles from a newer layout are refused
+pub const BUNDLE_FORMAT: u32 = 1;
+
+/// `.axiom/` entries that stay on the machine they were made on
+const LOCAL_ONLY: [&str; 4] = ["index", "logs", "recordings", "inspect"];
+
+/// A workspace's Axiom data, ready to move
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceBundle {
+    /// Layout version, [`BUNDLE_FORMAT`] when written
+    pub format: u32,
+
+    /// Axiom version that wrote the bundle
+    pub axiom_version: String,
+
+    pub name: String,
+
+    /// Root of the workspace on the machine it was exported from
+    pub root: PathBuf,
+
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Files from `.axiom/`, empty unless exported with state
+    #[serde(default)]
+    pub files: Vec<BundleFile>,
+}
+
+/// One file from a workspace's `.axiom/` directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// Path inside `.axiom/`, `/`-separated
+    pub path: String,
+
+    pub content: String,
+}
+
+impl WorkspaceBundle {
+    /// A bundle of `workspace` without any state
+    pub fn new(workspace: &Workspace) -> Self {
+        Self {
+            format: BUNDLE_FORMAT,
+            axiom_version: env!("CARGO_PKG_VERSION").to_string(),
+            name: workspace.name.clone(),
+            root: workspace.path.clone(),
+            tags: workspace.tags.clone(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Add the files in the workspace's `.axiom/` directory
+    ///
+    /// Machine-local caches and logs are skipped, as are files that aren't
+    /// text. Returns the paths skipped for not being text.
+    pub fn collect_state(&mut self) -> Result<Vec<String>> {
+        let dir = self.root.join(".axiom");
+        let mut skipped = Vec::new();
+        if dir.is_dir() {
+            collect(&dir, "", &mut self.files, &mut skipped)?;
+        }
+        self.files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(skipped)
+    }
+
+    /// Refuse bundles written in a layout this version doesn't know
+    pub fn check_format(&self) -> Result<()> {
+        if self.format > BUNDLE_FORMAT {
+            return Err(AxiomError::Config(format!(
+                "Bundle '{}' was written by Axiom {} in format {}; this Axiom reads up to \
+                 format {}, so upgrade it to import the bundle",
+                self.name, self.axiom_version, self.format, BUNDLE_FORMAT
+            )));
+        }
+        Ok(())
+    }
+
+    /// Write the bundled files under `root/.axiom/`, rewriting the old root
+    /// path in them to `root`
+    ///
+    /// Nothing is written if any of the files already exists there.
+    pub fn unpack(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        self.check_format()?;
+        let dir = root.join(".axiom");
+        let mut targets = Vec::with_capacity(self.files.len());
+        for file in &self.files {
+            let relative = Path::new(&file.path);
+            let safe = relative
+                .components()
+                .all(|part| matches!(part, std::path::Component::Normal(_)));
+            if !safe {
+                return Err(AxiomError::Config(format!(
+                    "Bundle file '{}' points outside .axiom/",
+                    file.path
+                )));
+            }
+            let target = dir.join(relative);
+            if target.exists() {
+                return Err(AxiomError::Config(format!(
+                    "{} already exists; move it aside to import the bundle",
+                    target.display()
+                )));
+            }
+            targets.push(target);
+        }
+
+        for (file, target) in self.files.iter().zip(&targets) {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(target, remap_paths(&file.content, &self.root, root))?;
+        }
+        Ok(targets)
+    }
+}
+
+/// `text` with `from` replaced by `to`, as written plainly and as escaped
+/// in JSON strings
+pub fn remap_paths(text: &str, from: &Path, to: &Path) -> String {
+    let (from, to) = (from.display().to_string(), to.display().to_string());
+    if from.is_empty() || from == to {
+        return text.to_string();
+    }
+    let text = text.replace(&from, &to);
+    let (from_json, to_json) = (json_escaped(&from), json_escaped(&to));
+    if from_json == from {
+        text
+    } else {
+        text.replace(&from_json, &to_json)
+    }
+}
+
+/// `text` as it appears inside a JSON string
+fn json_escaped(text: &str) -> String {
+    let quoted = serde_json::to_string(text).unwrap_or_default();
+    quoted.trim_matches('"').to_string()
+}
+
+/// Add the text files under `dir` (at `prefix` inside `.axiom/`) to `files`
+fn collect(
+    dir: &Path,
+    prefix: &str,
+    files: &mut Vec<BundleFile>,
+    skipped: &mut Vec<String>,
+) -> Result<()> {
+    for entry in fs::read_dir(dir)?.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if prefix.is_empty() && LOCAL_ONLY.contains(&name.as_str()) {
+            continue;
+        }
+        let path = format!("{}{}", prefix, name);
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect(&entry.path(), &format!("{}/", path), files, skipped)?;
+        } else if file_type.is_file() {
+            match fs::read_to_string(entry.path()) {
+                Ok(content) => files.push(BundleFile { path, content }),
+                Err(_) => skipped.push(path),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_moves_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let (old, new) = (dir.path().join("old"), dir.path().join("new"));
+        let axiom = old.join(".axiom");
+        fs::create_dir_all(axiom.join("sessions")).unwrap();
+        fs::create_dir_all(axiom.join("index")).unwrap();
+        fs::write(axiom.join("config.toml"), "[prompts]\n").unwrap();
+        let session = format!("{{\"cwd\":\"{}/src\"}}", old.display());
+        fs::write(axiom.join("sessions").join("a.json"), &session).unwrap();
+        fs::write(axiom.join("index").join("chunks.json"), "{}").unwrap();
+        fs::write(axiom.join("blob"), [0xff, 0xfe]).unwrap();
+        fs::write(old.join("main.rs"), "fn main() {}").unwrap();
+
+        let mut bundle = WorkspaceBundle::new(&Workspace::new_local("shop", &old));
+        assert_eq!(bundle.collect_state().unwrap(), vec!["blob"]);
+        let paths: Vec<&str> = bundle.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["config.toml", "sessions/a.json"]);
+
+        // Unpacked under the new root, with the old root rewritten
+        fs::create_dir_all(&new).unwrap();
+        assert_eq!(bundle.unpack(&new).unwrap().len(), 2);
+        let moved = fs::read_to_string(new.join(".axiom/sessions/a.json")).unwrap();
+        assert_eq!(moved, format!("{{\"cwd\":\"{}/src\"}}", new.display()));
+        assert!(!new.join("main.rs").exists());
+
+        // Existing files are never overwritten, nor are newer formats read
+        assert!(bundle.unpack(&new).is_err());
+        bundle.format = BUNDLE_FORMAT + 1;
+        assert!(bundle.check_format().is_err());
+    }
+
+    #[test]
+    fn test_unpack_stays_inside_axiom_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut bundle = WorkspaceBundle::new(&Workspace::new_local("shop", "/old"));
+        bundle.files.push(BundleFile {
+            path: "../escape.txt".to_string(),
+            content: String::new(),
+        });
+        assert!(bundle.unpack(dir.path()).is_err());
+        assert!(!dir.path().join("escape.txt").exists());
+    }
+}
@@ -0,0 +1,128 @@
+//! Workspace auto-discovery
+//!
+//! Scans configured root directories (e.g. `~/code`) for git repos and
+//! project manifests so existing projects can be registered in one step.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Files or directories that mark a project root, with the project kind
+pub const PROJECT_MARKERS: &[(&str, &str)] = &[
+    (".git", "git"),
+    ("Cargo.toml", "rust"),
+    ("package.json", "node"),
+    ("pyproject.toml", "python"),
+    ("setup.py", "python"),
+    ("go.mod", "go"),
+    ("pom.xml", "java"),
+    ("build.gradle", "java"),
+    ("Gemfile", "ruby"),
+    ("composer.json", "php"),
+    ("mix.exs", "elixir"),
+    ("CMakeLists.txt", "cpp"),
+];
+
+/// Directories never descended into while scanning
+const SKIP_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build", "venv"];
+
+/// A project found on disk
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiscoveredProject {
+    /// Folder name
+    pub name: String,
+    pub path: PathBuf,
+    /// Kind from the first matching marker (e.g. "git", "rust")
+    pub kind: &'static str,
+}
+
+/// Find projects under `roots`, looking at most `max_depth` levels down
+///
+/// A directory with a project marker is reported and not descended into,
+/// so nested packages of a monorepo don't show up separately. Hidden and
+/// build/dependency directories are skipped. Results are sorted by name.
+pub fn discover_projects(roots: &[PathBuf], max_depth: usize) -> Vec<DiscoveredProject> {
+    let mut found = Vec::new();
+    for root in roots {
+        scan(&expand_home(root), max_depth, &mut found);
+    }
+    found.sort_by_key(|p| (p.name.to_lowercase(), p.path.clone()));
+    found.dedup_by(|a, b| a.path == b.path);
+    found
+}
+
+fn scan(dir: &Path, depth_left: usize, found: &mut Vec<DiscoveredProject>) {
+    if let Some(kind) = project_kind(dir) {
+        let name = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| dir.display().to_string());
+        found.push(DiscoveredProject {
+            name,
+            path: dir.to_path_buf(),
+            kind,
+        });
+        return;
+    }
+    if depth_left == 0 {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || SKIP_DIRS.contains(&name.as_ref()) {
+            continue;
+        }
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            scan(&entry.path(), depth_left - 1, found);
+        }
+    }
+}
+
+/// Kind of project rooted at `dir`, if any
+fn project_kind(dir: &Path) -> Option<&'static str> {
+    PROJECT_MARKERS
+        .iter()
+        .find(|(marker, _)| dir.join(marker).exists())
+        .map(|(_, kind)| *kind)
+}
+
+/// Expand a leading `~` to the home directory
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_discover_projects() {
+        let root = TempDir::new().unwrap();
+        let mkdir = |rel: &str| std::fs::create_dir_all(root.path().join(rel)).unwrap();
+        let touch = |rel: &str| std::fs::write(root.path().join(rel), "").unwrap();
+
+        mkdir("app/.git");
+        mkdir("app/packages/inner");
+        touch("app/packages/inner/package.json");
+        mkdir("group/lib");
+        touch("group/lib/Cargo.toml");
+        mkdir("group/deep/er/tool");
+        touch("group/deep/er/tool/go.mod");
+        mkdir("node_modules/dep");
+        touch("node_modules/dep/package.json");
+        mkdir("notes");
+
+        let found = discover_projects(&[root.path().to_path_buf()], 2);
+        let names: Vec<_> = found.iter().map(|p| (p.name.as_str(), p.kind)).collect();
+        // Nested package, too-deep and node_modules projects are skipped
+        assert_eq!(names, vec![("app", "git"), ("lib", "rust")]);
+    }
+}
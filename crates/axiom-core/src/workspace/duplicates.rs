@@ -0,0 +1,169 @@
+//! Path normalization and duplicate workspaces
+//!
+//! Local workspace paths are stored canonical (absolute, symlinks resolved)
+//! so `.`, `../project` and `~/code/project` all name the same workspace.
+//! Registries written before that can hold several entries for one
+//! directory; [`WorkspaceManager::merge_duplicate_workspaces`] folds each
+//! group into one entry, keeping its settings, time and activity.
+//!
+//! [`WorkspaceManager::merge_duplicate_workspaces`]: super::WorkspaceManager::merge_duplicate_workspaces
+
+use super::types::{Workspace, WorkspaceConfig, WorkspaceId, WorkspaceType};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Canonical form of a local workspace path
+///
+/// Paths that can't be canonicalized (e.g. they don't exist) are made
+/// absolute and cleaned of `.` and `..` instead.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    match std::fs::canonicalize(path) {
+        Ok(canonical) => strip_verbatim(canonical),
+        Err(_) => {
+            let absolute = if path.is_absolute() {
+                path.to_path_buf()
+            } else {
+                std::env::current_dir().unwrap_or_default().join(path)
+            };
+            let mut clean = PathBuf::new();
+            for component in absolute.components() {
+                match component {
+                    Component::CurDir => {}
+                    Component::ParentDir => {
+                        clean.pop();
+                    }
+                    other => clean.push(other),
+                }
+            }
+            clean
+        }
+    }
+}
+
+/// Drop the `\\?\` prefix Windows adds to canonical paths, which the rest of
+/// the UI would show and which breaks comparisons with typed paths
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    match path.to_str().and_then(|s| s.strip_prefix(r"\\?\")) {
+        Some(rest) if !rest.starts_with("UNC") => PathBuf::from(rest),
+        _ => path,
+    }
+}
+
+/// A group of duplicate entries folded into one
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergedWorkspaces {
+    /// The entry that was kept
+    pub kept: WorkspaceId,
+    pub name: String,
+    /// Normalized path of the group
+    pub path: PathBuf,
+    /// Names of the entries merged into it
+    pub merged: Vec<String>,
+}
+
+/// Local workspaces sharing a directory, grouped by normalized path
+///
+/// Each group lists the entry to keep first: the active one, otherwise the
+/// oldest.
+pub(super) fn duplicate_groups<'a>(
+    workspaces: impl IntoIterator<Item = &'a Workspace>,
+) -> Vec<(PathBuf, Vec<Workspace>)> {
+    let mut groups: BTreeMap<PathBuf, Vec<Workspace>> = BTreeMap::new();
+    for workspace in workspaces {
+        if workspace.workspace_type == WorkspaceType::Local {
+            groups
+                .entry(normalize_path(&workspace.path))
+                .or_default()
+                .push(workspace.clone());
+        }
+    }
+    groups
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .map(|(path, mut group)| {
+            group.sort_by_key(|ws| (!ws.is_active, ws.created_at, ws.id.to_string()));
+            (path, group)
+        })
+        .collect()
+}
+
+/// Fold `other`'s metadata into `keep`
+pub(super) fn merge_metadata(keep: &mut Workspace, other: &Workspace) {
+    keep.created_at = keep.created_at.min(other.created_at);
+    keep.last_accessed = keep.last_accessed.max(other.last_accessed);
+    for tag in &other.tags {
+        if !keep.tags.contains(tag) {
+            keep.tags.push(tag.clone());
+        }
+    }
+    if keep.config_path.is_none() {
+        keep.config_path = other.config_path.clone();
+    }
+    keep.archived &= other.archived;
+}
+
+/// Add `other`'s settings to `keep` where `keep` has none of its own
+///
+/// Lists (watch rules, CLI agents, ignore patterns) are combined; for
+/// single values `keep` wins.
+pub(super) fn merge_config(keep: &mut WorkspaceConfig, other: WorkspaceConfig) {
+    if keep.llm.is_none() {
+        keep.llm = other.llm;
+    }
+    for agent in other.cli_agents {
+        if !keep.cli_agents.iter().any(|a| a.id == agent.id) {
+            keep.cli_agents.push(agent);
+        }
+    }
+    for (key, value) in other.env {
+        keep.env.entry(key).or_insert(value);
+    }
+    for pattern in other.ignore_patterns {
+        if !keep.ignore_patterns.contains(&pattern) {
+            keep.ignore_patterns.push(pattern);
+        }
+    }
+    for rule in other.watch {
+        if !keep.watch.contains(&rule) {
+            keep.watch.push(rule);
+        }
+    }
+    keep.completion |= other.completion;
+}
+
+/// Plain-text result of a merge
+pub fn merge_summary(merged: &[MergedWorkspaces]) -> String {
+    if merged.is_empty() {
+        return "No duplicate workspaces".to_string();
+    }
+    let mut text = format!("Merged {} group(s) of duplicate workspaces\n\n", merged.len());
+    for group in merged {
+        text.push_str(&format!(
+            "  {} ({}) ← {}\n",
+            group.name,
+            group.path.display(),
+            group.merged.join(", ")
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        let canonical = normalize_path(&project);
+        assert_eq!(normalize_path(&project.join(".")), canonical);
+        assert_eq!(normalize_path(&project.join("src").join("..")), canonical);
+
+        // Missing paths are cleaned lexically
+        let missing = dir.path().join("gone").join("..").join("other");
+        assert_eq!(normalize_path(&missing), dir.path().join("other"));
+    }
+}
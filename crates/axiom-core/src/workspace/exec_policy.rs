@@ -0,0 +1,543 @@
+//! Limits on the shell commands agents and the API run
+//!
+//! The Shell agent and `POST /api/workspaces/:id/command` hand a string to
+//! `sh -c`. Before it runs, every simple command in it is checked against
+//! the workspace's binary allow and deny lists, and path arguments are kept
+//! inside the workspace. While it runs it is stopped after a timeout or once
+//! it has printed too much, and it only sees a scrubbed environment.
+//!
+//! The checks read the command the way the shell would split it, but they
+//! are a guardrail for agent mistakes, not a sandbox: a command that builds
+//! its arguments at run time (variables, `eval`) can still reach outside.
+//! Command substitution is refused while an allowlist is set, since the
+//! commands inside it can't be checked.
+//!
+//! Workspaces configure this under `[exec_policy]` in `.axiom/config.toml`.
+
+use super::write_policy::glob_match;
+use super::WorkspaceConfig;
+use crate::config::default_true;
+use crate::files::normalize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// Binaries refused unless the workspace allows them by name
+const DENIED_BINARIES: &[&str] = &[
+    "sudo", "su", "doas", "pkexec", "chroot", "shutdown", "reboot", "halt", "poweroff", "mkfs*",
+];
+
+/// Environment variables kept when the environment is scrubbed
+const PASSED_ENV: &[&str] = &[
+    "PATH", "HOME", "USER", "LOGNAME", "SHELL", "LANG", "LC_ALL", "LC_CTYPE", "TERM", "TMPDIR",
+    "TZ",
+];
+
+/// Commands that run the command after them
+const WRAPPERS: &[&str] = &[
+    "env", "nohup", "nice", "timeout", "time", "exec", "command", "builtin", "xargs", "stdbuf",
+];
+
+/// Shell words that aren't commands
+const KEYWORDS: &[&str] = &[
+    "!", "{", "}", "if", "then", "else", "elif", "fi", "do", "done", "while", "until",
+];
+
+/// Paths outside the workspace that commands may always use
+const DEVICES: &[&str] = &["/dev/null", "/dev/stdin", "/dev/stdout", "/dev/stderr"];
+
+/// Command execution limits of a workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecPolicy {
+    /// Apply the limits at all
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Binaries that may run; empty allows any that isn't denied
+    #[serde(default)]
+    pub allow: Vec<String>,
+
+    /// Binaries that may never run, on top of the built-in list
+    #[serde(default)]
+    pub deny: Vec<String>,
+
+    /// Refuse path arguments outside the workspace
+    #[serde(default = "default_true")]
+    pub confine_paths: bool,
+
+    /// Seconds before a command is stopped; 0 for no limit
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+
+    /// Bytes of output before a command is stopped; 0 for no limit
+    #[serde(default = "default_max_output")]
+    pub max_output: usize,
+
+    /// Run commands with only a few well-known environment variables
+    #[serde(default = "default_true")]
+    pub scrub_env: bool,
+
+    /// More variables to keep in a scrubbed environment
+    #[serde(default)]
+    pub pass_env: Vec<String>,
+}
+
+fn default_timeout() -> u64 {
+    300
+}
+
+fn default_max_output() -> usize {
+    1024 * 1024
+}
+
+impl Default for ExecPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allow: Vec::new(),
+            deny: Vec::new(),
+            confine_paths: true,
+            timeout_secs: default_timeout(),
+            max_output: default_max_output(),
+            scrub_env: true,
+            pass_env: Vec::new(),
+        }
+    }
+}
+
+/// Why a command was refused or stopped
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum PolicyViolation {
+    /// An allowlist is set and the binary isn't on it
+    NotAllowed { binary: String },
+    /// The binary is on the denylist
+    Denied { binary: String },
+    /// A path argument resolves outside the workspace
+    OutsideWorkspace { path: String },
+    /// `$(...)` or backticks while an allowlist is set
+    Substitution,
+    /// The command ran longer than the timeout
+    Timeout { secs: u64 },
+    /// The command printed more than the output limit
+    OutputLimit { bytes: usize },
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAllowed { binary } => {
+                write!(
+                    f,
+                    "'{}' is not on the workspace's command allowlist",
+                    binary
+                )
+            }
+            Self::Denied { binary } => write!(f, "'{}' is denied by the command policy", binary),
+            Self::OutsideWorkspace { path } => {
+                write!(f, "'{}' is outside the workspace", path)
+            }
+            Self::Substitution => {
+                write!(
+                    f,
+                    "command substitution can't be checked against the allowlist"
+                )
+            }
+            Self::Timeout { secs } => write!(f, "command stopped after {}s", secs),
+            Self::OutputLimit { bytes } => {
+                write!(
+                    f,
+                    "command stopped after printing more than {} bytes",
+                    bytes
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolicyViolation {}
+
+impl ExecPolicy {
+    /// The policy of the workspace at `root`, or the default one
+    pub fn load(root: &Path) -> Self {
+        std::fs::read_to_string(root.join(".axiom").join("config.toml"))
+            .ok()
+            .and_then(|content| toml::from_str::<WorkspaceConfig>(&content).ok())
+            .map(|config| config.exec_policy)
+            .unwrap_or_default()
+    }
+
+    /// Check `command` before running it in the workspace at `root`
+    pub fn check(&self, root: &Path, command: &str) -> Result<(), PolicyViolation> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let parsed = parse(command);
+        if parsed.substitution && !self.allow.is_empty() {
+            return Err(PolicyViolation::Substitution);
+        }
+        let mut args = Vec::new();
+        for words in &parsed.commands {
+            let found = binaries(words);
+            for &i in &found {
+                self.check_binary(&words[i])?;
+            }
+            args.extend(
+                (0..words.len())
+                    .filter(|i| !found.contains(i))
+                    .map(|i| &words[i]),
+            );
+        }
+        if self.confine_paths {
+            for word in args.into_iter().chain(&parsed.redirects) {
+                if let Some(path) = outside(root, word) {
+                    return Err(PolicyViolation::OutsideWorkspace { path });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_binary(&self, binary: &str) -> Result<(), PolicyViolation> {
+        let name = binary.rsplit('/').next().unwrap_or(binary);
+        let matches = |patterns: &[String]| patterns.iter().any(|p| glob_match(p, name));
+        let allowed = matches(&self.allow);
+        let built_in = DENIED_BINARIES.iter().any(|p| glob_match(p, name));
+        if matches(&self.deny) || (built_in && !allowed) {
+            return Err(PolicyViolation::Denied {
+                binary: name.to_string(),
+            });
+        }
+        if !self.allow.is_empty() && !allowed {
+            return Err(PolicyViolation::NotAllowed {
+                binary: name.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// `sh -c command` in `root` with the environment scrubbed, once the
+    /// command passes [`check`](Self::check)
+    pub fn command(&self, root: &Path, command: &str) -> Result<Command, PolicyViolation> {
+        self.check(root, command)?;
+        let mut process = Command::new("sh");
+        process.arg("-c").arg(command).current_dir(root);
+        if self.enabled && self.scrub_env {
+            process.env_clear();
+            let names = PASSED_ENV
+                .iter()
+                .copied()
+                .chain(self.pass_env.iter().map(String::as_str));
+            for name in names {
+                if let Some(value) = std::env::var_os(name) {
+                    process.env(name, value);
+                }
+            }
+        }
+        Ok(process)
+    }
+
+    /// How long a command may run
+    pub fn timeout(&self) -> Option<Duration> {
+        (self.enabled && self.timeout_secs > 0).then(|| Duration::from_secs(self.timeout_secs))
+    }
+
+    /// How many bytes of output a command may print
+    pub fn max_output(&self) -> Option<usize> {
+        (self.enabled && self.max_output > 0).then_some(self.max_output)
+    }
+}
+
+/// A command line split the way `sh` would split it
+#[derive(Debug, Default)]
+struct Parsed {
+    /// Words of each simple command
+    commands: Vec<Vec<String>>,
+    /// Targets of `<` and `>` redirections
+    redirects: Vec<String>,
+    /// Whether `$(...)` or backticks appear outside single quotes
+    substitution: bool,
+}
+
+/// Word and command being built while splitting
+#[derive(Default)]
+struct Splitter {
+    parsed: Parsed,
+    words: Vec<String>,
+    word: String,
+    /// Whether `word` has started; `""` is a word too
+    started: bool,
+    /// Whether `word` follows `<` or `>`
+    redirect: bool,
+}
+
+impl Splitter {
+    fn end_word(&mut self) {
+        if self.started {
+            let word = std::mem::take(&mut self.word);
+            if self.redirect {
+                self.parsed.redirects.push(word);
+            } else {
+                self.words.push(word);
+            }
+        }
+        self.started = false;
+        self.redirect = false;
+    }
+
+    fn end_command(&mut self) {
+        self.end_word();
+        if !self.words.is_empty() {
+            self.parsed.commands.push(std::mem::take(&mut self.words));
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        self.word.push(c);
+        self.started = true;
+    }
+}
+
+fn parse(command: &str) -> Parsed {
+    let mut split = Splitter::default();
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let substitution = c == '`' || (c == '$' && chars.peek() == Some(&'('));
+        if substitution && quote != Some('\'') {
+            split.parsed.substitution = true;
+        }
+        match (quote, c) {
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (Some('"'), '\\') => {
+                if let Some(next) = chars.next() {
+                    split.push(next);
+                }
+            }
+            (Some(_), _) => split.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                split.started = true;
+            }
+            (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    split.push(next);
+                }
+            }
+            (None, '<' | '>') => {
+                // A descriptor number like the 2 in 2> isn't a word
+                if split.started
+                    && !split.redirect
+                    && split.word.chars().all(|c| c.is_ascii_digit())
+                {
+                    split.word.clear();
+                    split.started = false;
+                }
+                split.end_word();
+                // >& duplicates a descriptor rather than naming a file
+                if chars.peek() == Some(&'&') {
+                    chars.next();
+                } else {
+                    split.redirect = true;
+                }
+            }
+            (None, ';' | '&' | '|' | '\n' | '(' | ')' | '`') => split.end_command(),
+            (None, '$') if substitution => split.end_command(),
+            (None, c) if c.is_whitespace() => {
+                // Keep the target of a redirection followed by a space
+                if split.started {
+                    split.end_word();
+                }
+            }
+            (None, c) => split.push(c),
+        }
+    }
+    split.end_command();
+    split.parsed
+}
+
+/// Positions of the binaries a simple command runs, including ones behind
+/// wrappers like `env`
+fn binaries(words: &[String]) -> Vec<usize> {
+    if matches!(
+        words.first().map(String::as_str),
+        Some("for" | "case" | "esac" | "select" | "in")
+    ) {
+        return Vec::new();
+    }
+    let mut found = Vec::new();
+    let mut wrapped = false;
+    for (i, word) in words.iter().enumerate() {
+        let skip = KEYWORDS.contains(&word.as_str())
+            || is_assignment(word)
+            || (wrapped && (word.starts_with('-') || is_duration(word)));
+        if skip {
+            continue;
+        }
+        found.push(i);
+        let name = word.rsplit('/').next().unwrap_or(word);
+        if !WRAPPERS.contains(&name) {
+            break;
+        }
+        wrapped = true;
+    }
+    found
+}
+
+/// Whether `word` sets a variable, like `RUST_LOG=debug`
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Arguments of `timeout` and `nice`, like `10` or `1.5m`
+fn is_duration(word: &str) -> bool {
+    let digits = word.trim_end_matches(['s', 'm', 'h', 'd']);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+/// The path `word` names, if it names one outside the workspace at `root`
+fn outside(root: &Path, word: &str) -> Option<String> {
+    // --output=/etc/passwd names a path too
+    let value = match word.split_once('=') {
+        Some((flag, value)) if flag.starts_with('-') => value,
+        _ => word,
+    };
+    if value.contains("://") || DEVICES.contains(&value) {
+        return None;
+    }
+    let home = || std::env::var_os("HOME").map(PathBuf::from);
+    let path = if value == "~" || value == "$HOME" || value == "${HOME}" {
+        home()?
+    } else if let Some(rest) = value
+        .strip_prefix("~/")
+        .or_else(|| value.strip_prefix("$HOME/"))
+        .or_else(|| value.strip_prefix("${HOME}/"))
+    {
+        home()?.join(rest)
+    } else if value.starts_with('/') || value.split('/').any(|part| part == "..") {
+        root.join(value)
+    } else {
+        return None;
+    };
+    (!normalize(&path).starts_with(normalize(root))).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(policy: &ExecPolicy, command: &str) -> Result<(), PolicyViolation> {
+        policy.check(Path::new("/work/project"), command)
+    }
+
+    #[test]
+    fn test_binaries_are_checked_in_every_command() {
+        let policy = ExecPolicy::default();
+        assert_eq!(
+            check(&policy, "cargo test -- --nocapture 2>&1 | tail -n 20"),
+            Ok(())
+        );
+        assert_eq!(
+            check(&policy, "make && sudo make install"),
+            Err(PolicyViolation::Denied {
+                binary: "sudo".to_string()
+            })
+        );
+        assert_eq!(
+            check(&policy, "FOO=1 env -i BAR=2 nice -n 5 /sbin/mkfs.ext4 disk"),
+            Err(PolicyViolation::Denied {
+                binary: "mkfs.ext4".to_string()
+            })
+        );
+        // Quoted separators are part of an argument
+        assert_eq!(check(&policy, "echo 'a; sudo b' \"|doas\""), Ok(()));
+
+        let policy = ExecPolicy {
+            allow: vec!["cargo".to_string(), "git".to_string(), "rg".to_string()],
+            ..ExecPolicy::default()
+        };
+        assert_eq!(check(&policy, "git status; cargo build"), Ok(()));
+        assert_eq!(
+            check(&policy, "git log | head"),
+            Err(PolicyViolation::NotAllowed {
+                binary: "head".to_string()
+            })
+        );
+        assert_eq!(
+            check(&policy, "git commit -m \"$(date)\""),
+            Err(PolicyViolation::Substitution)
+        );
+        assert_eq!(check(&policy, "rg '$(not run)'"), Ok(()));
+    }
+
+    #[test]
+    fn test_paths_stay_in_the_workspace() {
+        let policy = ExecPolicy::default();
+        assert_eq!(
+            check(&policy, "cat src/main.rs > out.txt 2>/dev/null"),
+            Ok(())
+        );
+        assert_eq!(
+            check(&policy, "ls /work/project/src ../project/src"),
+            Ok(())
+        );
+        assert_eq!(
+            check(&policy, "cat ../other/secrets"),
+            Err(PolicyViolation::OutsideWorkspace {
+                path: "../other/secrets".to_string()
+            })
+        );
+        assert_eq!(
+            check(&policy, "echo hi >/etc/motd"),
+            Err(PolicyViolation::OutsideWorkspace {
+                path: "/etc/motd".to_string()
+            })
+        );
+        assert_eq!(
+            check(&policy, "tar czf --file=/tmp/x.tgz ."),
+            Err(PolicyViolation::OutsideWorkspace {
+                path: "/tmp/x.tgz".to_string()
+            })
+        );
+        assert!(check(&policy, "cat ~/.ssh/id_rsa").is_err());
+
+        let relaxed = ExecPolicy {
+            confine_paths: false,
+            ..ExecPolicy::default()
+        };
+        assert_eq!(check(&relaxed, "cat /etc/hosts"), Ok(()));
+    }
+
+    #[test]
+    fn test_environment_is_scrubbed() {
+        let policy = ExecPolicy {
+            pass_env: vec!["AXIOM_TEST_KEPT".to_string()],
+            ..ExecPolicy::default()
+        };
+        std::env::set_var("AXIOM_TEST_KEPT", "kept");
+        std::env::set_var("AXIOM_TEST_SECRET", "secret");
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let output = policy
+            .command(dir.path(), "echo \"$AXIOM_TEST_KEPT:$AXIOM_TEST_SECRET\"")
+            .unwrap()
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "kept:");
+
+        let violation = PolicyViolation::Denied {
+            binary: "sudo".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&violation).unwrap(),
+            serde_json::json!({ "rule": "denied", "binary": "sudo" })
+        );
+    }
+}
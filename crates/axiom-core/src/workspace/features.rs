@@ -0,0 +1,168 @@
+//! Feature flags for experimental subsystems
+//!
+//! Each workspace can switch subsystems on or off under `[features]` in its
+//! `.axiom/config.toml`:
+//!
+//! ```toml
+//! [features]
+//! watch = false
+//! ```
+//!
+//! Flags not set keep the subsystem's default. Most flags are read whenever
+//! the subsystem runs, so changing them takes effect straight away; those
+//! read only when a workspace is opened say so via [`Feature::needs_restart`].
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A subsystem that can be switched off per workspace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Feature {
+    /// Watch rules that run commands or agents on file changes
+    Watch,
+    /// Watching the workspace directory for changed files
+    FileWatcher,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 2] = [Feature::Watch, Feature::FileWatcher];
+
+    /// Key under `[features]`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feature::Watch => "watch",
+            Feature::FileWatcher => "file_watcher",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Feature::Watch => "run /watch rules when matching files change",
+            Feature::FileWatcher => "reload files changed outside Axiom",
+        }
+    }
+
+    /// Whether the subsystem runs when the workspace doesn't set the flag
+    pub fn default_enabled(&self) -> bool {
+        match self {
+            Feature::Watch | Feature::FileWatcher => true,
+        }
+    }
+
+    /// Whether the flag is only read when the workspace is opened
+    pub fn needs_restart(&self) -> bool {
+        matches!(self, Feature::FileWatcher)
+    }
+
+    pub fn parse(name: &str) -> Option<Feature> {
+        let name = name.replace('-', "_").to_lowercase();
+        Feature::ALL.into_iter().find(|f| f.name() == name)
+    }
+}
+
+/// The `[features]` table of a workspace config
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FeatureFlags(BTreeMap<String, bool>);
+
+impl FeatureFlags {
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.get(feature).unwrap_or_else(|| feature.default_enabled())
+    }
+
+    /// The value set in the config, if any
+    pub fn get(&self, feature: Feature) -> Option<bool> {
+        self.0.get(feature.name()).copied()
+    }
+
+    pub fn set(&mut self, feature: Feature, enabled: bool) {
+        self.0.insert(feature.name().to_string(), enabled);
+    }
+
+    /// Keys that don't name a known feature (typos, or flags of a newer version)
+    pub fn unknown(&self) -> Vec<&str> {
+        self.0
+            .keys()
+            .filter(|name| Feature::parse(name).is_none())
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Confirmation for `/features <name> on|off`
+pub fn feature_switched(feature: Feature, enabled: bool) -> String {
+    let state = if enabled { "on" } else { "off" };
+    if feature.needs_restart() {
+        format!(
+            "Feature {} switched {}; takes effect when the workspace is next opened",
+            feature.name(),
+            state
+        )
+    } else {
+        format!("Feature {} switched {}", feature.name(), state)
+    }
+}
+
+/// Plain-text flag list for `/features`
+pub fn features_summary(flags: &FeatureFlags) -> String {
+    let mut text = String::from("Features (set under [features] in .axiom/config.toml)\n\n");
+    for feature in Feature::ALL {
+        let state = if flags.is_enabled(feature) { "on" } else { "off" };
+        let source = if flags.get(feature).is_some() {
+            "set"
+        } else {
+            "default"
+        };
+        let restart = if feature.needs_restart() {
+            ", needs restart"
+        } else {
+            ""
+        };
+        text.push_str(&format!(
+            "  {:<13} {:<3} ({}{}) {}\n",
+            feature.name(),
+            state,
+            source,
+            restart,
+            feature.description()
+        ));
+    }
+    for name in flags.unknown() {
+        text.push_str(&format!("  {:<13} unknown flag, ignored\n", name));
+    }
+    text.push_str("\nChange with /features <name> on|off");
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::WorkspaceConfig;
+
+    #[test]
+    fn test_flags_from_config() {
+        let config: WorkspaceConfig =
+            toml::from_str("[features]\nwatch = false\nteleport = true\n").unwrap();
+        let flags = &config.features;
+        assert!(!flags.is_enabled(Feature::Watch));
+        assert!(flags.is_enabled(Feature::FileWatcher));
+        assert_eq!(flags.get(Feature::FileWatcher), None);
+        assert_eq!(flags.unknown(), ["teleport"]);
+
+        let summary = features_summary(flags);
+        assert!(summary.contains("watch         off (set)"));
+        assert!(summary.contains("file_watcher  on  (default, needs restart)"));
+        assert!(summary.contains("teleport      unknown flag"));
+    }
+
+    #[test]
+    fn test_parse_and_set() {
+        assert_eq!(Feature::parse("File-Watcher"), Some(Feature::FileWatcher));
+        assert_eq!(Feature::parse("rag"), None);
+
+        let mut flags = FeatureFlags::default();
+        flags.set(Feature::Watch, false);
+        let toml = toml::to_string(&flags).unwrap();
+        assert_eq!(toml.trim(), "watch = false");
+    }
+}
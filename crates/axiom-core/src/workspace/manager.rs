@@ -2,14 +2,37 @@
 //!
 //! Manages workspace lifecycle, configuration, and AxiomService instances.
 
+use super::activity::{timeline, ActivityEvent, ActivityKind, ActivityPage};
+use super::audit::{verify_chain, AuditKey, AuditReport};
+use super::bundle::WorkspaceBundle;
+use super::discovery::{discover_projects, DiscoveredProject};
+use super::duplicates::{
+    duplicate_groups, merge_config, merge_metadata, normalize_path, MergedWorkspaces,
+};
+use super::features::{Feature, FeatureFlags};
+use super::purge::{PurgeItem, PurgePlan};
+use super::report::agent_changelog;
+use super::review::{sort_queue, MergeConflict, ReviewItem};
+use super::search::filter_workspaces;
 use super::storage::{WorkspaceRegistry, WorkspaceStorage};
-use super::types::{Workspace, WorkspaceConfig, WorkspaceId, WorkspaceView};
+use super::time_tracking::{today, utc_date, TimeReport};
+use super::types::{
+    Workspace, WorkspaceConfig, WorkspaceDefaults, WorkspaceId, WorkspaceType, WorkspaceView,
+};
+use super::watch::WatchRule;
+use super::worktree::{self, MergeOutcome, TASK_BRANCH_PREFIX};
+use super::write_policy::{ApprovalQueue, PendingWrite};
+use crate::clock::unix_now;
 use crate::config::AxiomConfig;
 use crate::error::{AxiomError, Result};
-use crate::service::AxiomService;
-use parking_lot::RwLock;
+use crate::llm::{RouteCheck, RoutingPolicy};
+use crate::orchestration::{AgentOperation, AgentRole, RefactorSession};
+use crate::service::{AxiomService, SharedService};
+use crate::types::{AgentEvent, AgentStatus};
+use crate::usage::{rows_to_csv, usage_rows, UsageFilter, UsageRecord, UsageRow};
+use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Manages multiple workspaces and their associated services
@@ -21,13 +44,25 @@ pub struct WorkspaceManager {
     storage: WorkspaceStorage,
 
     /// Active AxiomService instances (lazy-loaded)
-    services: RwLock<HashMap<WorkspaceId, Arc<AxiomService>>>,
+    services: RwLock<HashMap<WorkspaceId, SharedService>>,
 
     /// Global configuration
     global_config: AxiomConfig,
 
     /// Workspace-specific configs (cached)
     workspace_configs: RwLock<HashMap<WorkspaceId, WorkspaceConfig>>,
+
+    /// Agent writes held for approval, shared with each workspace's service
+    pending_writes: RwLock<HashMap<WorkspaceId, ApprovalQueue>>,
+
+    /// Providers allowed despite the routing policy, for this session
+    routing_overrides: RwLock<HashMap<WorkspaceId, Vec<String>>>,
+
+    /// Rename previews and undo history, for this session
+    refactors: RwLock<HashMap<WorkspaceId, RefactorSession>>,
+
+    /// Task merges stopped by conflicts, by parent workspace
+    conflicts: RwLock<HashMap<WorkspaceId, Vec<MergeConflict>>>,
 }
 
 impl WorkspaceManager {
@@ -42,6 +77,10 @@ impl WorkspaceManager {
             services: RwLock::new(HashMap::new()),
             global_config,
             workspace_configs: RwLock::new(HashMap::new()),
+            pending_writes: RwLock::new(HashMap::new()),
+            routing_overrides: RwLock::new(HashMap::new()),
+            refactors: RwLock::new(HashMap::new()),
+            conflicts: RwLock::new(HashMap::new()),
         })
     }
 
@@ -55,18 +94,74 @@ impl WorkspaceManager {
             services: RwLock::new(HashMap::new()),
             global_config,
             workspace_configs: RwLock::new(HashMap::new()),
+            pending_writes: RwLock::new(HashMap::new()),
+            routing_overrides: RwLock::new(HashMap::new()),
+            refactors: RwLock::new(HashMap::new()),
+            conflicts: RwLock::new(HashMap::new()),
         })
     }
 
     // ========== Workspace CRUD ==========
 
-    /// List all workspaces
+    /// List all workspaces except archived ones, most recently accessed first
+    ///
+    /// Each view carries health indicators: whether the path still exists
+    /// (`broken` when it doesn't), whether its git repo has uncommitted
+    /// changes, and how many agents are still running in its service.
     pub fn list_workspaces(&self) -> Vec<WorkspaceView> {
-        self.registry
+        self.views(false)
+    }
+
+    /// List archived workspaces, most recently accessed first
+    pub fn archived_workspaces(&self) -> Vec<WorkspaceView> {
+        self.views(true)
+    }
+
+    /// Workspaces whose directory is missing on disk
+    pub fn broken_workspaces(&self) -> Vec<WorkspaceView> {
+        self.list_workspaces().into_iter().filter(|ws| ws.broken).collect()
+    }
+
+    fn views(&self, archived: bool) -> Vec<WorkspaceView> {
+        let mut views: Vec<WorkspaceView> = self
+            .registry
             .read()
             .list()
             .into_iter()
+            .filter(|ws| ws.archived == archived)
             .map(WorkspaceView::from)
+            .collect();
+
+        let services = self.services.read();
+        for view in &mut views {
+            view.git_dirty = view.exists && worktree::is_dirty(&view.path);
+            view.running_agents = services
+                .get(&view.id)
+                .map(|service| {
+                    service
+                        .lock()
+                        .agents()
+                        .iter()
+                        .filter(|agent| agent.status == AgentStatus::Running)
+                        .count()
+                })
+                .unwrap_or(0);
+        }
+        views
+    }
+
+    /// Workspaces fuzzy-matching `query` on name or path, best match first
+    pub fn search_workspaces(&self, query: &str) -> Vec<WorkspaceView> {
+        filter_workspaces(self.list_workspaces(), query)
+    }
+
+    /// Projects under `roots` that aren't registered yet (archived ones count
+    /// as registered)
+    pub fn discover_workspaces(&self, roots: &[PathBuf], max_depth: usize) -> Vec<DiscoveredProject> {
+        let registry = self.registry.read();
+        discover_projects(roots, max_depth)
+            .into_iter()
+            .filter(|project| registry.find_by_path(&project.path).is_none())
             .collect()
     }
 
@@ -75,6 +170,26 @@ impl WorkspaceManager {
         self.registry.read().get(id).cloned()
     }
 
+    /// Look up a workspace, archived or not, by ID or by (case-insensitive) name
+    pub fn resolve_workspace(&self, key: &str) -> Result<Workspace> {
+        if let Some(ws) = key.parse().ok().and_then(|id| self.get_workspace(id)) {
+            return Ok(ws);
+        }
+        let registry = self.registry.read();
+        let mut named = registry
+            .workspaces
+            .values()
+            .filter(|ws| ws.name.eq_ignore_ascii_case(key));
+        match (named.next(), named.next()) {
+            (Some(ws), None) => Ok(ws.clone()),
+            (Some(_), Some(_)) => Err(AxiomError::invalid_operation(format!(
+                "Several workspaces are named '{}'; use the ID",
+                key
+            ))),
+            (None, _) => Err(AxiomError::Config(format!("Workspace not found: {}", key))),
+        }
+    }
+
     /// Get the currently active workspace
     pub fn active_workspace(&self) -> Option<Workspace> {
         let registry = self.registry.read();
@@ -89,6 +204,10 @@ impl WorkspaceManager {
     }
 
     /// Create a new local workspace
+    ///
+    /// The path is stored normalized; a path that names the directory of an
+    /// existing workspace, however it is spelled, is refused (see
+    /// [`Self::find_by_path`] to offer the existing one instead).
     pub fn create_workspace(&self, name: &str, path: PathBuf) -> Result<Workspace> {
         // Validate path exists
         if !path.exists() {
@@ -97,13 +216,15 @@ impl WorkspaceManager {
                 path.display()
             )));
         }
+        let path = normalize_path(&path);
 
         // Check for duplicates
         {
             let registry = self.registry.read();
-            if registry.find_by_path(&path).is_some() {
+            if let Some(existing) = registry.find_by_path(&path) {
                 return Err(AxiomError::Config(format!(
-                    "Workspace already exists for path: {}",
+                    "Workspace '{}' already exists for path: {}",
+                    existing.name,
                     path.display()
                 )));
             }
@@ -143,53 +264,1211 @@ impl WorkspaceManager {
         Ok(workspace)
     }
 
-    /// Delete a workspace (does not delete files)
-    pub fn delete_workspace(&self, id: WorkspaceId) -> Result<Option<Workspace>> {
-        // Stop any running service
-        self.stop_service(id);
+    /// Delete a workspace (does not delete files)
+    pub fn delete_workspace(&self, id: WorkspaceId) -> Result<Option<Workspace>> {
+        // Stop any running service
+        self.stop_service(id);
+
+        let workspace = {
+            let mut registry = self.registry.write();
+            registry.remove(id)
+        };
+
+        if workspace.is_some() {
+            self.save()?;
+            self.storage.activity_log(id).remove();
+            self.storage.time_ledger(id).remove();
+        }
+
+        // Clean up cached config, held writes, routing overrides and refactors
+        self.workspace_configs.write().remove(&id);
+        self.pending_writes.write().remove(&id);
+        self.routing_overrides.write().remove(&id);
+        self.refactors.write().remove(&id);
+
+        Ok(workspace)
+    }
+
+    /// Update workspace metadata
+    pub fn update_workspace(&self, id: WorkspaceId, name: Option<&str>) -> Result<Workspace> {
+        let workspace = {
+            let mut registry = self.registry.write();
+            let ws = registry
+                .get_mut(id)
+                .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+
+            if let Some(n) = name {
+                ws.name = n.to_string();
+            }
+            ws.clone()
+        };
+
+        self.save()?;
+        Ok(workspace)
+    }
+
+    /// Point a workspace at a new directory, e.g. after it was moved on disk
+    ///
+    /// Also unarchives it. The workspace's service is stopped since it is
+    /// bound to the old path.
+    pub fn relocate_workspace(&self, id: WorkspaceId, path: PathBuf) -> Result<Workspace> {
+        if !path.is_dir() {
+            return Err(AxiomError::Config(format!(
+                "Workspace path does not exist: {}",
+                path.display()
+            )));
+        }
+        let path = normalize_path(&path);
+
+        let workspace = {
+            let mut registry = self.registry.write();
+            if let Some(other) = registry.find_by_path(&path).filter(|ws| ws.id != id) {
+                return Err(AxiomError::Config(format!(
+                    "Workspace already exists for path: {}",
+                    other.name
+                )));
+            }
+            let ws = registry
+                .get_mut(id)
+                .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+            ws.path = path;
+            ws.archived = false;
+            ws.clone()
+        };
+
+        self.stop_service(id);
+        self.workspace_configs.write().remove(&id);
+        self.save()?;
+        Ok(workspace)
+    }
+
+    /// Register a workspace moved from another machine at `path`
+    ///
+    /// The project's files must already be at `path`. The bundle's files are
+    /// unpacked into its `.axiom/` directory with the old root rewritten to
+    /// `path`, and the workspace takes the bundle's name and tags.
+    pub fn import_workspace(&self, bundle: &WorkspaceBundle, path: PathBuf) -> Result<Workspace> {
+        bundle.check_format()?;
+        if !path.is_dir() {
+            return Err(AxiomError::Config(format!(
+                "Workspace path does not exist: {}",
+                path.display()
+            )));
+        }
+        let path = normalize_path(&path);
+        if let Some(existing) = self.registry.read().find_by_path(&path) {
+            return Err(AxiomError::Config(format!(
+                "Workspace '{}' already exists for path: {}",
+                existing.name,
+                path.display()
+            )));
+        }
+
+        // Unpacked before the default config is written, which the bundle's
+        // own would collide with; removed again if registering fails
+        let unpacked = bundle.unpack(&path)?;
+        let mut workspace = Workspace::new_local(&bundle.name, path);
+        workspace.tags = bundle.tags.clone();
+        let registered = self.storage.init_workspace_dir(&workspace).and_then(|()| {
+            self.registry.write().add(workspace.clone());
+            self.save()
+        });
+        if let Err(e) = registered {
+            self.registry.write().remove(workspace.id);
+            for file in unpacked {
+                let _ = std::fs::remove_file(file);
+            }
+            return Err(e);
+        }
+        Ok(workspace)
+    }
+
+    /// Archive or unarchive a workspace (files and registry entry are kept)
+    pub fn set_workspace_archived(&self, id: WorkspaceId, archived: bool) -> Result<Workspace> {
+        let workspace = {
+            let mut registry = self.registry.write();
+            let ws = registry
+                .get_mut(id)
+                .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+            ws.archived = archived;
+            let ws = ws.clone();
+            if archived && registry.active_workspace == Some(id) {
+                registry.set_active(None);
+            }
+            ws
+        };
+
+        if archived {
+            self.stop_service(id);
+        }
+        self.save()?;
+        Ok(workspace)
+    }
+
+    /// What purging a workspace would delete
+    pub fn purge_plan(&self, id: WorkspaceId) -> Result<PurgePlan> {
+        let workspace = self
+            .get_workspace(id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+        let mut plan = PurgePlan::new(&workspace);
+
+        let activity = self.storage.activity_log(id);
+        plan.items
+            .extend(PurgeItem::at("activity log", activity.path().to_path_buf()));
+        let ledger = self.storage.time_ledger(id);
+        plan.items
+            .extend(PurgeItem::at("time ledger", ledger.path().to_path_buf()));
+
+        let sessions = crate::sessions::list(&workspace.path)
+            .map(|list| list.len())
+            .unwrap_or(0);
+        if sessions > 0 {
+            plan.items.extend(PurgeItem::at(
+                format!("{} saved sessions", sessions),
+                crate::sessions::sessions_dir(&workspace.path),
+            ));
+        }
+
+        plan.usage_records = self
+            .storage
+            .usage_log()
+            .read()
+            .iter()
+            .filter(|record| record.workspace_id == Some(id))
+            .count();
+        if plan.usage_records > 0 {
+            plan.items.push(PurgeItem::entry(format!(
+                "{} usage records",
+                plan.usage_records
+            )));
+        }
+
+        if self
+            .storage
+            .trust_store()
+            .load()
+            .contains_key(&workspace.path)
+        {
+            plan.items.push(PurgeItem::entry("trust decision"));
+        }
+
+        for task in self.sub_workspaces(id) {
+            plan.items.extend(PurgeItem::at(
+                format!("task '{}' (worktree and branch)", task.name),
+                task.path.clone(),
+            ));
+            plan.tasks.push(task.id);
+        }
+
+        plan.items.push(PurgeItem::entry("workspace entry"));
+        Ok(plan)
+    }
+
+    /// Permanently delete an archived workspace's Axiom data
+    ///
+    /// Removes everything [`Self::purge_plan`] lists and returns that plan.
+    /// Only archived workspaces can be purged so that data is never lost to
+    /// a single click.
+    pub fn purge_workspace(&self, id: WorkspaceId) -> Result<PurgePlan> {
+        let plan = self.purge_plan(id)?;
+        let archived = self.get_workspace(id).is_some_and(|ws| ws.archived);
+        if !archived {
+            return Err(AxiomError::invalid_operation(format!(
+                "Archive workspace '{}' before purging it",
+                plan.name
+            )));
+        }
+
+        let usage = self.storage.usage_log();
+        for &task in &plan.tasks {
+            if self.discard_task_workspace(task).is_err() {
+                // The worktree is already gone; drop what's left of the entry
+                self.delete_workspace(task)?;
+            }
+            usage.remove_workspace(task)?;
+        }
+
+        match std::fs::remove_dir_all(crate::sessions::sessions_dir(&plan.path)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        usage.remove_workspace(id)?;
+        self.storage.trust_store().forget(&plan.path)?;
+        self.delete_workspace(id)?;
+        Ok(plan)
+    }
+
+    /// Fold workspaces that point at the same directory into one entry
+    ///
+    /// The active (or else oldest) entry of each group is kept under the
+    /// normalized path. It takes over the others' tags, settings, time
+    /// ledger, activity, trust decision and task worktrees. Sessions need no
+    /// merging since they live in the shared directory.
+    pub fn merge_duplicate_workspaces(&self) -> Result<Vec<MergedWorkspaces>> {
+        let groups = duplicate_groups(self.registry.read().workspaces.values());
+        let mut merged = Vec::new();
+        for (path, group) in groups {
+            let Some((keep, others)) = group.split_first() else {
+                continue;
+            };
+            let mut kept = keep.clone();
+            kept.path = path.clone();
+            for other in others {
+                merge_metadata(&mut kept, other);
+            }
+
+            let trust = self.storage.trust_store();
+            if trust.decision(&path).is_none() {
+                let decided = group.iter().find_map(|ws| trust.decision(&ws.path));
+                if let Some(trusted) = decided {
+                    trust.set(&path, trusted)?;
+                }
+            }
+
+            {
+                let mut registry = self.registry.write();
+                let was_active = others
+                    .iter()
+                    .any(|other| registry.active_workspace == Some(other.id));
+                for other in others {
+                    registry.remove(other.id);
+                }
+                for child in registry.workspaces.values_mut() {
+                    if child
+                        .parent_id
+                        .is_some_and(|p| others.iter().any(|o| o.id == p))
+                    {
+                        child.parent_id = Some(kept.id);
+                    }
+                }
+                if let Some(ws) = registry.get_mut(kept.id) {
+                    *ws = kept.clone();
+                }
+                if was_active {
+                    registry.set_active(Some(kept.id));
+                }
+            }
+            self.save()?;
+
+            let mut config = self.get_workspace_config(kept.id)?;
+            let mut config_changed = false;
+            for other in others {
+                self.stop_service(other.id);
+                if other.effective_config_path() != kept.effective_config_path() {
+                    if let Ok(other_config) = self.storage.load_workspace_config(other) {
+                        merge_config(&mut config, other_config);
+                        config_changed = true;
+                    }
+                }
+                self.storage
+                    .time_ledger(kept.id)
+                    .absorb(&self.storage.time_ledger(other.id))?;
+                self.storage.time_ledger(other.id).remove();
+                let log = self.storage.activity_log(other.id);
+                for mut event in log.read() {
+                    event.chain = None;
+                    let _ = self.record_activity(kept.id, &event);
+                }
+                log.remove();
+
+                self.workspace_configs.write().remove(&other.id);
+                self.pending_writes.write().remove(&other.id);
+                self.routing_overrides.write().remove(&other.id);
+                self.refactors.write().remove(&other.id);
+                self.conflicts.write().remove(&other.id);
+            }
+            if config_changed {
+                self.save_workspace_config(kept.id, &config)?;
+            }
+
+            let names: Vec<String> = others.iter().map(|ws| ws.name.clone()).collect();
+            let summary = format!("Merged duplicate workspaces: {}", names.join(", "));
+            let event = ActivityEvent::new(ActivityKind::Notification, "user", summary);
+            let _ = self.record_activity(kept.id, &event);
+            merged.push(MergedWorkspaces {
+                kept: kept.id,
+                name: kept.name,
+                path,
+                merged: names,
+            });
+        }
+        Ok(merged)
+    }
+
+    // ========== Activity Feed ==========
+
+    /// Append an entry to a workspace's activity feed
+    pub fn record_activity(&self, id: WorkspaceId, event: &ActivityEvent) -> Result<()> {
+        if self.registry.read().get(id).is_none() {
+            return Err(AxiomError::Config(format!("Workspace not found: {}", id)));
+        }
+        let log = self.storage.activity_log(id);
+        let sign = self
+            .get_workspace_config(id)
+            .is_ok_and(|config| config.audit.sign);
+        if sign {
+            let key = AuditKey::load_or_create(&self.storage.audit_key_path())?;
+            log.append_signed(event, &key)
+        } else {
+            log.append(event)
+        }
+    }
+
+    /// Check a workspace's activity log for tampering and gaps
+    pub fn verify_audit(&self, id: WorkspaceId) -> Result<AuditReport> {
+        if self.registry.read().get(id).is_none() {
+            return Err(AxiomError::Config(format!("Workspace not found: {}", id)));
+        }
+        let events = self.storage.activity_log(id).read();
+        let key = AuditKey::load(&self.storage.audit_key_path());
+        Ok(verify_chain(&events, key.as_ref()))
+    }
+
+    /// The activity log as JSON lines, headed by its verification result
+    ///
+    /// The first line holds the report, including the chain head, so a copy
+    /// can later be checked against the live log.
+    pub fn audit_jsonl(&self, id: WorkspaceId) -> Result<String> {
+        let workspace = self
+            .get_workspace(id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+        let events = self.storage.activity_log(id).read();
+        let key = AuditKey::load(&self.storage.audit_key_path());
+        let report = verify_chain(&events, key.as_ref());
+
+        let mut content = serde_json::to_string(&serde_json::json!({
+            "workspace": workspace.name,
+            "exported_at": today(),
+            "report": report,
+        }))?;
+        content.push('\n');
+        for event in &events {
+            content.push_str(&serde_json::to_string(event)?);
+            content.push('\n');
+        }
+        Ok(content)
+    }
+
+    /// Write [`Self::audit_jsonl`] into the workspace root
+    /// (`axiom-audit-<date>.jsonl`)
+    pub fn export_audit(&self, id: WorkspaceId) -> Result<PathBuf> {
+        let content = self.audit_jsonl(id)?;
+        let workspace = self
+            .get_workspace(id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+        let path = workspace
+            .path
+            .join(format!("axiom-audit-{}.jsonl", today()));
+        std::fs::write(&path, content)?;
+        Ok(path)
+    }
+
+    /// A page of a workspace's activity feed, newest first
+    ///
+    /// Logged agent runs, file changes and notifications are merged with the
+    /// commits of the workspace's git repo.
+    pub fn activity(&self, id: WorkspaceId, offset: usize, limit: usize) -> Result<ActivityPage> {
+        let workspace = self
+            .get_workspace(id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+        let logged = self.storage.activity_log(id).read();
+        Ok(timeline(logged, &workspace.path, offset, limit))
+    }
+
+    /// Markdown changelog of what agents changed in the last `period_secs`
+    pub fn agent_report(&self, id: WorkspaceId, period_secs: i64) -> Result<String> {
+        let workspace = self
+            .get_workspace(id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        let logged = self.storage.activity_log(id).read();
+        Ok(agent_changelog(
+            &workspace.name,
+            &logged,
+            now - period_secs,
+            now,
+        ))
+    }
+
+    /// File a workspace's activity is logged to, e.g. for tailing it
+    pub fn activity_log_path(&self, id: WorkspaceId) -> PathBuf {
+        self.storage.activity_log(id).path().to_path_buf()
+    }
+
+    // ========== Time Tracking ==========
+
+    /// Add interactive user time to today's entry of a workspace
+    pub fn record_user_time(&self, id: WorkspaceId, secs: u64) -> Result<()> {
+        if self.registry.read().get(id).is_none() {
+            return Err(AxiomError::Config(format!("Workspace not found: {}", id)));
+        }
+        self.storage.time_ledger(id).add_user(&today(), secs)
+    }
+
+    /// Add agent wall-clock time to today's entry of a workspace
+    pub fn record_agent_time(
+        &self,
+        id: WorkspaceId,
+        role: &str,
+        model: Option<&str>,
+        secs: u64,
+    ) -> Result<()> {
+        if self.registry.read().get(id).is_none() {
+            return Err(AxiomError::Config(format!("Workspace not found: {}", id)));
+        }
+        self.storage.time_ledger(id).add_agent(&today(), role, model, secs)
+    }
+
+    /// Record what a CLI agent reported in its output
+    ///
+    /// File changes and commands go to the activity feed, costs to the
+    /// usage log under provider `cli` and the agent's name; progress is only
+    /// shown.
+    pub fn record_agent_event(
+        &self,
+        id: WorkspaceId,
+        agent: &str,
+        event: &AgentEvent,
+    ) -> Result<()> {
+        match event {
+            AgentEvent::FileChanged { path } => {
+                let summary = format!("Edited {}", path.display());
+                let event = ActivityEvent::new(ActivityKind::FileChange, agent, summary);
+                self.record_activity(id, &event.with_path(path.clone()))
+            }
+            AgentEvent::CommandRun { command } => {
+                let event =
+                    ActivityEvent::new(ActivityKind::AgentRun, agent, format!("Ran {}", command));
+                self.record_activity(id, &event)
+            }
+            AgentEvent::Progress { .. } => Ok(()),
+            AgentEvent::Cost {
+                usd,
+                input_tokens,
+                output_tokens,
+            } => {
+                let mut record = UsageRecord::new("cli", agent, *input_tokens, *output_tokens);
+                record.cost_usd = *usd;
+                self.record_usage(Some(id), record)
+            }
+        }
+    }
+
+    /// Agent vs. user time of a workspace, limited to the last `days` days
+    pub fn time_report(&self, id: WorkspaceId, days: Option<u32>) -> Result<TimeReport> {
+        if self.registry.read().get(id).is_none() {
+            return Err(AxiomError::Config(format!("Workspace not found: {}", id)));
+        }
+        let since = days.map(|days| {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64;
+            utc_date(now - i64::from(days.saturating_sub(1)) * 86_400)
+        });
+        Ok(TimeReport::from_days(
+            self.storage.time_ledger(id).load(),
+            since.as_deref(),
+        ))
+    }
+
+    // ========== Usage & Cost ==========
+
+    /// Log an LLM call, attributed to workspace `id` if given
+    pub fn record_usage(&self, id: Option<WorkspaceId>, mut record: UsageRecord) -> Result<()> {
+        if let Some(workspace) = id.and_then(|id| self.get_workspace(id)) {
+            record.workspace_id = Some(workspace.id);
+            record.workspace = workspace.name;
+        }
+        self.storage.usage_log().append(&record)
+    }
+
+    /// Usage grouped per day, workspace, provider and model
+    pub fn usage_rows(&self, filter: &UsageFilter) -> Vec<UsageRow> {
+        usage_rows(&self.storage.usage_log().read(), filter)
+    }
+
+    /// Write a cost report of workspace `id` into its root directory
+    ///
+    /// Returns the path of the written `.csv` (or `.json`) file.
+    pub fn export_usage(
+        &self,
+        id: WorkspaceId,
+        from: Option<String>,
+        to: Option<String>,
+        json: bool,
+    ) -> Result<PathBuf> {
+        let workspace = self
+            .get_workspace(id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+        let filter = UsageFilter {
+            from,
+            to,
+            workspace: Some(id.to_string()),
+        };
+        filter.validate().map_err(AxiomError::Config)?;
+
+        let rows = self.usage_rows(&filter);
+        let (content, ext) = if json {
+            (serde_json::to_string_pretty(&rows)?, "json")
+        } else {
+            (rows_to_csv(&rows), "csv")
+        };
+        let path = workspace
+            .path
+            .join(format!("axiom-usage-{}.{}", today(), ext));
+        std::fs::write(&path, content)?;
+        Ok(path)
+    }
+
+    // ========== Workspace Trust ==========
+
+    /// Whether the user trusts a workspace, or `None` if they haven't decided
+    ///
+    /// Task worktrees follow the decision for their parent's repository.
+    pub fn trust(&self, id: WorkspaceId) -> Result<Option<bool>> {
+        Ok(self.storage.trust_store().decision(&self.trust_dir(id)?))
+    }
+
+    /// Whether a workspace may run commands and CLI agents (undecided
+    /// workspaces are restricted)
+    pub fn is_trusted(&self, id: WorkspaceId) -> bool {
+        matches!(self.trust(id), Ok(Some(true)))
+    }
+
+    /// Trust or restrict a workspace
+    pub fn set_trusted(&self, id: WorkspaceId, trusted: bool) -> Result<()> {
+        let dir = self.trust_dir(id)?;
+        self.storage.trust_store().set(&dir, trusted)?;
+        // Running services of the workspace and its worktrees follow along
+        for (ws, service) in self.services.read().iter() {
+            if self.trust_dir(*ws).is_ok_and(|root| root == dir) {
+                service.lock().set_trusted(trusted);
+            }
+        }
+        let summary = if trusted {
+            "Marked workspace as trusted"
+        } else {
+            "Restricted workspace (untrusted)"
+        };
+        let event = ActivityEvent::new(ActivityKind::Notification, "user", summary).with_path(dir);
+        let _ = self.record_activity(id, &event);
+        Ok(())
+    }
+
+    fn trust_dir(&self, id: WorkspaceId) -> Result<PathBuf> {
+        let workspace = self
+            .get_workspace(id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+        let root = workspace
+            .parent_id
+            .and_then(|parent| self.get_workspace(parent))
+            .unwrap_or(workspace);
+        Ok(root.path)
+    }
+
+    // ========== Data Routing ==========
+
+    /// Routing policy of a workspace (the default if its config can't be read)
+    pub fn routing_policy(&self, id: WorkspaceId) -> RoutingPolicy {
+        self.get_workspace_config(id)
+            .map(|config| config.routing)
+            .unwrap_or_default()
+    }
+
+    /// Providers the user allowed despite the routing policy
+    pub fn routing_overrides(&self, id: WorkspaceId) -> Vec<String> {
+        self.routing_overrides
+            .read()
+            .get(&id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Let a workspace's content go to `provider` despite its routing policy
+    ///
+    /// Overrides last for the session and are recorded, with the reason, in
+    /// the activity log.
+    pub fn allow_routing(&self, id: WorkspaceId, provider: &str, reason: &str) -> Result<()> {
+        if self.registry.read().get(id).is_none() {
+            return Err(AxiomError::Config(format!("Workspace not found: {}", id)));
+        }
+        if reason.trim().is_empty() {
+            return Err(AxiomError::Config(
+                "A reason is required to override routing".to_string(),
+            ));
+        }
+        let mut overrides = self.routing_overrides.write();
+        let allowed = overrides.entry(id).or_default();
+        if !allowed.iter().any(|p| p == provider) {
+            allowed.push(provider.to_string());
+        }
+        drop(overrides);
+
+        let summary = format!(
+            "Routing override: {} allowed for {} content ({})",
+            provider,
+            self.routing_policy(id).sensitivity.label(),
+            reason.trim()
+        );
+        let event = ActivityEvent::new(ActivityKind::Notification, "user", summary);
+        let _ = self.record_activity(id, &event);
+        Ok(())
+    }
+
+    /// Withdraw a routing override; returns whether there was one
+    pub fn revoke_routing(&self, id: WorkspaceId, provider: &str) -> Result<bool> {
+        if self.registry.read().get(id).is_none() {
+            return Err(AxiomError::Config(format!("Workspace not found: {}", id)));
+        }
+        let removed = match self.routing_overrides.write().get_mut(&id) {
+            Some(allowed) => {
+                let before = allowed.len();
+                allowed.retain(|p| p != provider);
+                allowed.len() < before
+            }
+            None => false,
+        };
+        if removed {
+            let summary = format!("Routing override revoked: {}", provider);
+            let event = ActivityEvent::new(ActivityKind::Notification, "user", summary);
+            let _ = self.record_activity(id, &event);
+        }
+        Ok(removed)
+    }
+
+    /// Apply overrides to a provider's route check and record exceptions
+    ///
+    /// A blocked route goes ahead, as a warning, when the user overrode it
+    /// for `provider`. Warnings, overridden routes and refusals all end up
+    /// in the activity log.
+    pub fn audit_route(&self, id: WorkspaceId, provider: &str, check: RouteCheck) -> RouteCheck {
+        let (check, summary) = match check {
+            RouteCheck::Allowed => return RouteCheck::Allowed,
+            RouteCheck::Warn(reason) => {
+                let summary = format!("Routing warning: {}", reason);
+                (RouteCheck::Warn(reason), summary)
+            }
+            RouteCheck::Blocked(reason)
+                if self.routing_overrides(id).iter().any(|p| p == provider) =>
+            {
+                let summary = format!("Sent to {} under override: {}", provider, reason);
+                let warning = format!("{} (overridden)", reason);
+                (RouteCheck::Warn(warning), summary)
+            }
+            RouteCheck::Blocked(reason) => {
+                let summary = format!("Routing blocked: {}", reason);
+                (RouteCheck::Blocked(reason), summary)
+            }
+        };
+        let event = ActivityEvent::new(ActivityKind::Notification, "routing", summary);
+        let _ = self.record_activity(id, &event);
+        check
+    }
+
+    // ========== Watch Rules ==========
+
+    /// Watch rules of a workspace
+    pub fn watch_rules(&self, id: WorkspaceId) -> Result<Vec<WatchRule>> {
+        Ok(self.get_workspace_config(id)?.watch)
+    }
+
+    /// Add a watch rule, returning its 1-based number
+    pub fn add_watch_rule(&self, id: WorkspaceId, rule: WatchRule) -> Result<usize> {
+        let mut config = self.get_workspace_config(id)?;
+        let summary = format!(
+            "Watch rule added: {} → {}",
+            rule.pattern,
+            rule.action.describe()
+        );
+        config.watch.push(rule);
+        self.save_workspace_config(id, &config)?;
+
+        let event = ActivityEvent::new(ActivityKind::Notification, "user", summary);
+        let _ = self.record_activity(id, &event);
+        Ok(config.watch.len())
+    }
+
+    /// Remove watch rule `number` (1-based, as listed by `/watch list`)
+    pub fn remove_watch_rule(&self, id: WorkspaceId, number: usize) -> Result<WatchRule> {
+        let mut config = self.get_workspace_config(id)?;
+        if number == 0 || number > config.watch.len() {
+            return Err(AxiomError::Config(format!(
+                "No watch rule {} ({} defined)",
+                number,
+                config.watch.len()
+            )));
+        }
+        let rule = config.watch.remove(number - 1);
+        self.save_workspace_config(id, &config)?;
+
+        let summary = format!("Watch rule removed: {}", rule.pattern);
+        let event = ActivityEvent::new(ActivityKind::Notification, "user", summary);
+        let _ = self.record_activity(id, &event);
+        Ok(rule)
+    }
+
+    // ========== Defaults ==========
+
+    /// The provider, model and CLI agent a workspace uses over the global ones
+    pub fn workspace_defaults(&self, id: WorkspaceId) -> Result<WorkspaceDefaults> {
+        Ok(self.get_workspace_config(id)?.defaults())
+    }
+
+    /// Replace a workspace's provider, model and CLI agent choices; a running
+    /// service picks them up when it's next started
+    pub fn set_workspace_defaults(
+        &self,
+        id: WorkspaceId,
+        defaults: WorkspaceDefaults,
+    ) -> Result<()> {
+        let mut config = self.get_workspace_config(id)?;
+        let providers = &self.global_config.llm.providers;
+        let unknown_provider = defaults
+            .default_provider
+            .iter()
+            .chain(defaults.agents.values().map(|model| &model.provider))
+            .find(|provider| !providers.contains_key(provider.as_str()));
+        if let Some(provider) = unknown_provider {
+            return Err(AxiomError::Config(format!(
+                "Unknown provider: {}",
+                provider
+            )));
+        }
+        for (role, model) in &defaults.agents {
+            role.parse::<AgentRole>().map_err(AxiomError::Config)?;
+            if model.model.trim().is_empty() {
+                return Err(AxiomError::Config(format!("No model given for {}", role)));
+            }
+        }
+        if let Some(ref agent) = defaults.default_cli_agent {
+            if !self.global_config.cli_agents.agents.contains_key(agent) {
+                return Err(AxiomError::Config(format!("Unknown CLI agent: {}", agent)));
+            }
+        }
+
+        let summary = format!(
+            "Workspace defaults set: provider {}, CLI agent {}",
+            defaults.default_provider.as_deref().unwrap_or("global"),
+            defaults.default_cli_agent.as_deref().unwrap_or("global")
+        );
+        config.set_defaults(defaults);
+        self.save_workspace_config(id, &config)?;
+
+        let event = ActivityEvent::new(ActivityKind::Notification, "user", summary);
+        let _ = self.record_activity(id, &event);
+        Ok(())
+    }
+
+    // ========== Feature Flags ==========
+
+    /// `[features]` of a workspace
+    pub fn features(&self, id: WorkspaceId) -> Result<FeatureFlags> {
+        Ok(self.get_workspace_config(id)?.features)
+    }
+
+    /// Switch `feature` on or off for a workspace
+    pub fn set_feature(&self, id: WorkspaceId, feature: Feature, enabled: bool) -> Result<()> {
+        let mut config = self.get_workspace_config(id)?;
+        config.features.set(feature, enabled);
+        self.save_workspace_config(id, &config)?;
+
+        let state = if enabled { "on" } else { "off" };
+        let summary = format!("Feature {} switched {}", feature.name(), state);
+        let event = ActivityEvent::new(ActivityKind::Notification, "user", summary);
+        let _ = self.record_activity(id, &event);
+        Ok(())
+    }
+
+    // ========== Write Approvals ==========
+
+    /// Hold `op` for approval if it touches a path protected by the
+    /// workspace's write policy
+    ///
+    /// Returns the held write, or `None` when the operation may go ahead.
+    pub fn check_write(
+        &self,
+        id: WorkspaceId,
+        agent: &str,
+        op: &AgentOperation,
+    ) -> Result<Option<PendingWrite>> {
+        let workspace = self
+            .get_workspace(id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+        let policy = self.get_workspace_config(id)?.write_policy;
+        let Some((path, reason)) = policy.check_operation(&workspace.path, op) else {
+            return Ok(None);
+        };
+        let pending = PendingWrite::new(agent, path, reason, op.clone());
+        self.hold_write(id, pending.clone())?;
+        Ok(Some(pending))
+    }
+
+    /// Hold a developer operation for approval unless
+    /// `orchestration.auto_approve` is set
+    ///
+    /// With auto-approve only the write policy can hold it, as for
+    /// [`Self::check_write`].
+    pub fn review_operation(
+        &self,
+        id: WorkspaceId,
+        agent: &str,
+        op: &AgentOperation,
+    ) -> Result<Option<PendingWrite>> {
+        let held = self.check_write(id, agent, op)?;
+        if held.is_some() || self.global_config.orchestration.auto_approve {
+            return Ok(held);
+        }
+        let path = op.path().map(Path::to_path_buf).unwrap_or_default();
+        let pending = PendingWrite::new(agent, path, "approval mode", op.clone());
+        self.hold_write(id, pending.clone())?;
+        Ok(Some(pending))
+    }
+
+    /// Queue a write that needs approval and note it in the activity feed
+    pub fn hold_write(&self, id: WorkspaceId, pending: PendingWrite) -> Result<()> {
+        let event = ActivityEvent::new(
+            ActivityKind::Notification,
+            pending.agent.clone(),
+            format!("Needs approval ({}) [{}]", pending.reason, pending.id),
+        )
+        .with_path(pending.path.clone());
+        self.record_activity(id, &event)?;
+        self.approvals(id).push(pending);
+        Ok(())
+    }
+
+    /// Writes of a workspace waiting for approval, oldest first
+    pub fn pending_writes(&self, id: WorkspaceId) -> Vec<PendingWrite> {
+        self.pending_writes
+            .read()
+            .get(&id)
+            .map(ApprovalQueue::list)
+            .unwrap_or_default()
+    }
+
+    /// The queue of a workspace's held writes, shared with its service
+    pub fn approvals(&self, id: WorkspaceId) -> ApprovalQueue {
+        self.pending_writes.write().entry(id).or_default().clone()
+    }
+
+    /// Apply (`approve`) or discard a held write
+    ///
+    /// Without an `approval_id` the oldest pending write is resolved. A write
+    /// that fails to apply is dropped from the queue and its error returned.
+    pub fn resolve_write(
+        &self,
+        id: WorkspaceId,
+        approval_id: Option<&str>,
+        approve: bool,
+    ) -> Result<PendingWrite> {
+        let workspace = self
+            .get_workspace(id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+        let pending = self
+            .approvals(id)
+            .resolve(&workspace.path, approval_id, approve)?;
+        self.record_resolved(id, &pending, approve);
+        Ok(pending)
+    }
+
+    /// Apply (`approve`) or discard each of the held writes `ids`, or all of
+    /// them when it's empty; returns how many were resolved
+    ///
+    /// A write that fails to apply doesn't stop the rest; their errors are
+    /// returned together.
+    pub fn resolve_writes(&self, id: WorkspaceId, ids: &[String], approve: bool) -> Result<usize> {
+        let workspace = self
+            .get_workspace(id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+        let (resolved, errors) = self
+            .approvals(id)
+            .resolve_each(&workspace.path, ids, approve)?;
+        for pending in &resolved {
+            self.record_resolved(id, pending, approve);
+        }
+        if !errors.is_empty() {
+            return Err(AxiomError::invalid_operation(errors.join("\n")));
+        }
+        Ok(resolved.len())
+    }
+
+    fn record_resolved(&self, id: WorkspaceId, pending: &PendingWrite, approve: bool) {
+        let event = if approve {
+            ActivityEvent::new(ActivityKind::FileChange, "user", "Approved agent write")
+        } else {
+            ActivityEvent::new(ActivityKind::Notification, "user", "Rejected agent write")
+        };
+        let _ = self.record_activity(id, &event.with_path(pending.path.clone()));
+    }
+
+    // ========== Refactoring ==========
+
+    /// Plan renaming `symbol` across a workspace; returns the preview
+    ///
+    /// Replaces any rename still waiting to be applied.
+    pub fn plan_rename(&self, id: WorkspaceId, symbol: &str, new_name: &str) -> Result<String> {
+        let root = self.workspace_path(id)?;
+        let mut refactors = self.refactors.write();
+        let session = refactors.entry(id).or_default();
+        Ok(session.plan(&root, symbol, new_name)?.preview())
+    }
+
+    /// Preview of the rename waiting to be applied
+    pub fn refactor_preview(&self, id: WorkspaceId) -> Option<String> {
+        let refactors = self.refactors.read();
+        Some(refactors.get(&id)?.preview()?.preview())
+    }
+
+    /// Include or leave out a file of the pending rename; returns the preview
+    pub fn toggle_refactor_file(&self, id: WorkspaceId, path: &str) -> Result<String> {
+        let mut refactors = self.refactors.write();
+        let session = refactors.entry(id).or_default();
+        Ok(session.toggle(path)?.preview())
+    }
+
+    /// Apply the pending rename and note each changed file in the activity feed
+    pub fn apply_refactor(&self, id: WorkspaceId) -> Result<String> {
+        let root = self.workspace_path(id)?;
+        let mut refactors = self.refactors.write();
+        let session = refactors.entry(id).or_default();
+        let summary = session.apply(&root)?;
+        if let Some(undo) = session.last_applied() {
+            for path in undo.paths() {
+                let event = ActivityEvent::new(
+                    ActivityKind::FileChange,
+                    "user",
+                    format!("Refactor: {}", undo.description),
+                )
+                .with_path(path);
+                let _ = self.record_activity(id, &event);
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Revert the most recent applied refactor
+    pub fn undo_refactor(&self, id: WorkspaceId) -> Result<String> {
+        let root = self.workspace_path(id)?;
+        let mut refactors = self.refactors.write();
+        let summary = refactors.entry(id).or_default().undo(&root)?;
+        let event = ActivityEvent::new(ActivityKind::FileChange, "user", summary.clone());
+        let _ = self.record_activity(id, &event);
+        Ok(summary)
+    }
+
+    /// Drop the pending rename; false if there wasn't one
+    pub fn discard_refactor(&self, id: WorkspaceId) -> bool {
+        self.refactors
+            .write()
+            .get_mut(&id)
+            .is_some_and(|session| session.discard())
+    }
+
+    fn workspace_path(&self, id: WorkspaceId) -> Result<PathBuf> {
+        self.get_workspace(id)
+            .map(|workspace| workspace.path)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))
+    }
+
+    // ========== Task Worktrees ==========
+
+    /// Create a sub-workspace backed by a new git worktree of `parent_id`
+    ///
+    /// Each sub-workspace gets its own branch, service, agents and file tree,
+    /// so several tasks can run against the same repository in parallel.
+    pub fn create_task_workspace(&self, parent_id: WorkspaceId, task: &str) -> Result<Workspace> {
+        let parent = self
+            .get_workspace(parent_id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", parent_id)))?;
+
+        if parent.workspace_type != WorkspaceType::Local {
+            return Err(AxiomError::invalid_operation(
+                "Task worktrees require a local workspace",
+            ));
+        }
+        if parent.is_sub_workspace() {
+            return Err(AxiomError::invalid_operation(
+                "Cannot create a task worktree from another task worktree",
+            ));
+        }
+        if !worktree::is_git_repo(&parent.path) {
+            return Err(AxiomError::invalid_operation(format!(
+                "Not a git repository: {}",
+                parent.path.display()
+            )));
+        }
+
+        // Suffix keeps parallel tasks with the same name apart
+        let id = WorkspaceId::new();
+        let slug = format!("{}-{}", worktree::task_slug(task), &id.to_string()[..8]);
+        let branch = format!("{}{}", TASK_BRANCH_PREFIX, slug);
+        let path = self
+            .storage
+            .worktrees_dir()
+            .join(parent_id.to_string())
+            .join(&slug);
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        worktree::add_worktree(&parent.path, &path, &branch)?;
+
+        let mut workspace = Workspace::new_worktree(
+            format!("{} / {}", parent.name, task.trim()),
+            path,
+            parent_id,
+            branch,
+        );
+        workspace.id = id;
+
+        self.storage.init_workspace_dir(&workspace)?;
+        {
+            let mut registry = self.registry.write();
+            registry.add(workspace.clone());
+        }
+        self.save()?;
+
+        Ok(workspace)
+    }
+
+    /// List task worktrees of a workspace
+    pub fn sub_workspaces(&self, parent_id: WorkspaceId) -> Vec<WorkspaceView> {
+        self.registry
+            .read()
+            .list()
+            .into_iter()
+            .filter(|ws| ws.parent_id == Some(parent_id))
+            .map(WorkspaceView::from)
+            .collect()
+    }
 
-        let workspace = {
-            let mut registry = self.registry.write();
-            registry.remove(id)
-        };
+    /// Commit a task's changes and merge its branch back into the parent
+    ///
+    /// On success the worktree and sub-workspace are removed. On conflict the
+    /// merge is aborted and the sub-workspace is kept so it can be fixed up.
+    pub fn merge_task_workspace(&self, id: WorkspaceId) -> Result<MergeOutcome> {
+        let (task, parent, branch) = self.task_parts(id)?;
 
-        if workspace.is_some() {
-            self.save()?;
+        // Stop agents before touching the tree
+        self.stop_service(id);
+
+        worktree::commit_all(&task.path, &format!("Axiom task: {}", task.name))?;
+        let outcome = worktree::merge_branch(&parent.path, &branch)?;
+
+        self.clear_conflict(parent.id, id);
+        match &outcome {
+            MergeOutcome::Conflict { branch, files } => {
+                let conflict = MergeConflict {
+                    task_id: id,
+                    task_name: task.name.clone(),
+                    task_path: task.path.clone(),
+                    branch: branch.clone(),
+                    files: files.clone(),
+                    created_at: unix_now() as i64,
+                };
+                self.conflicts
+                    .write()
+                    .entry(parent.id)
+                    .or_default()
+                    .push(conflict);
+            }
+            _ => self.remove_task(&task, &parent, &branch)?,
         }
 
-        // Clean up cached config
-        self.workspace_configs.write().remove(&id);
+        Ok(outcome)
+    }
 
-        Ok(workspace)
+    /// Throw away a task worktree and its branch without merging
+    pub fn discard_task_workspace(&self, id: WorkspaceId) -> Result<()> {
+        let (task, parent, branch) = self.task_parts(id)?;
+        self.stop_service(id);
+        self.clear_conflict(parent.id, id);
+        self.remove_task(&task, &parent, &branch)
     }
 
-    /// Update workspace metadata
-    pub fn update_workspace(&self, id: WorkspaceId, name: Option<&str>) -> Result<Workspace> {
-        let workspace = {
-            let mut registry = self.registry.write();
-            let ws = registry
-                .get_mut(id)
-                .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+    /// Task merges into `parent_id` that stopped on conflicts, oldest first
+    pub fn merge_conflicts(&self, parent_id: WorkspaceId) -> Vec<MergeConflict> {
+        self.conflicts
+            .read()
+            .get(&parent_id)
+            .cloned()
+            .unwrap_or_default()
+    }
 
-            if let Some(n) = name {
-                ws.name = n.to_string();
+    fn clear_conflict(&self, parent_id: WorkspaceId, task_id: WorkspaceId) {
+        if let Some(conflicts) = self.conflicts.write().get_mut(&parent_id) {
+            conflicts.retain(|c| c.task_id != task_id);
+        }
+    }
+
+    /// Everything in a workspace waiting on the user, most urgent first
+    ///
+    /// Stalled agents are those of the workspace's service; UIs running
+    /// their own agents add theirs with [`ReviewItem::stalled_agent`].
+    pub fn review_queue(&self, id: WorkspaceId) -> Vec<ReviewItem> {
+        let mut items: Vec<ReviewItem> = self
+            .pending_writes(id)
+            .iter()
+            .map(ReviewItem::approval)
+            .chain(self.merge_conflicts(id).iter().map(ReviewItem::conflict))
+            .collect();
+        if let Some(service) = self.get_service(id) {
+            items.extend(service.lock().stalled_agents());
+        }
+        sort_queue(&mut items);
+        items
+    }
+
+    /// Look up a task workspace together with its parent and branch
+    fn task_parts(&self, id: WorkspaceId) -> Result<(Workspace, Workspace, String)> {
+        let task = self
+            .get_workspace(id)
+            .ok_or_else(|| AxiomError::Config(format!("Workspace not found: {}", id)))?;
+
+        let (parent_id, branch) = match (task.parent_id, task.branch.clone()) {
+            (Some(parent_id), Some(branch)) => (parent_id, branch),
+            _ => {
+                return Err(AxiomError::invalid_operation(format!(
+                    "Workspace is not a task worktree: {}",
+                    task.name
+                )))
             }
-            ws.clone()
         };
 
-        self.save()?;
-        Ok(workspace)
+        let parent = self.get_workspace(parent_id).ok_or_else(|| {
+            AxiomError::Config(format!("Parent workspace not found: {}", parent_id))
+        })?;
+
+        Ok((task, parent, branch))
+    }
+
+    /// Remove a task's worktree, branch and registry entry
+    fn remove_task(&self, task: &Workspace, parent: &Workspace, branch: &str) -> Result<()> {
+        worktree::remove_worktree(&parent.path, &task.path, branch)?;
+        self.delete_workspace(task.id)?;
+        Ok(())
     }
 
     // ========== Workspace Activation ==========
 
     /// Activate a workspace (make it the current workspace)
-    pub fn activate_workspace(&self, id: WorkspaceId) -> Result<Arc<AxiomService>> {
-        // Verify workspace exists
+    pub fn activate_workspace(&self, id: WorkspaceId) -> Result<SharedService> {
+        // Verify workspace exists and is usable
         {
             let registry = self.registry.read();
-            if registry.get(id).is_none() {
+            let Some(workspace) = registry.get(id) else {
                 return Err(AxiomError::Config(format!("Workspace not found: {}", id)));
+            };
+            if workspace.is_broken() {
+                return Err(AxiomError::Config(format!(
+                    "Workspace '{}' is broken: {} no longer exists (relocate, archive or remove it)",
+                    workspace.name,
+                    workspace.path.display()
+                )));
             }
         }
 
@@ -224,7 +1503,7 @@ impl WorkspaceManager {
     // ========== Service Management ==========
 
     /// Get the AxiomService for a workspace (creates if needed)
-    pub fn get_or_create_service(&self, id: WorkspaceId) -> Result<Arc<AxiomService>> {
+    pub fn get_or_create_service(&self, id: WorkspaceId) -> Result<SharedService> {
         // Check if already exists
         if let Some(service) = self.services.read().get(&id) {
             return Ok(Arc::clone(service));
@@ -242,11 +1521,19 @@ impl WorkspaceManager {
         let ws_config = self.get_workspace_config(id)?;
 
         // Build effective config (global + workspace overrides)
-        let effective_config = self.build_effective_config(&ws_config);
+        let trusted = self.is_trusted(id);
+        let mut effective_config = self.build_effective_config(&ws_config, trusted);
+
+        // Language servers run the project's build scripts and macros
+        if !trusted {
+            effective_config.lsp.enabled = false;
+        }
 
         // Create service
-        let service = AxiomService::new(effective_config, workspace.path.clone())?;
-        let service = Arc::new(service);
+        let mut service = AxiomService::new(effective_config, workspace.path.clone())?;
+        service.set_trusted(trusted);
+        service.set_approvals(self.approvals(id));
+        let service = Arc::new(Mutex::new(service));
 
         // Store service
         self.services.write().insert(id, Arc::clone(&service));
@@ -255,7 +1542,7 @@ impl WorkspaceManager {
     }
 
     /// Get existing service without creating
-    pub fn get_service(&self, id: WorkspaceId) -> Option<Arc<AxiomService>> {
+    pub fn get_service(&self, id: WorkspaceId) -> Option<SharedService> {
         self.services.read().get(&id).cloned()
     }
 
@@ -314,19 +1601,15 @@ impl WorkspaceManager {
         Ok(())
     }
 
+    /// Replace the global config new services are built from
+    pub fn set_global_config(&mut self, config: AxiomConfig) {
+        self.global_config = config;
+    }
+
     /// Build effective config by merging global and workspace configs
-    fn build_effective_config(&self, ws_config: &WorkspaceConfig) -> AxiomConfig {
+    fn build_effective_config(&self, ws_config: &WorkspaceConfig, trusted: bool) -> AxiomConfig {
         let mut config = self.global_config.clone();
-
-        // Apply workspace LLM overrides
-        if let Some(ref llm) = ws_config.llm {
-            if let Some(ref provider) = llm.default_provider {
-                // Would set default provider here
-                let _ = provider;
-            }
-            // Apply other overrides...
-        }
-
+        ws_config.apply_overrides(&mut config, trusted);
         config
     }
 
@@ -379,6 +1662,8 @@ impl Drop for WorkspaceManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::Command;
+    use crate::notifications::Notification;
     use tempfile::TempDir;
 
     fn test_manager() -> (WorkspaceManager, TempDir, TempDir) {
@@ -392,6 +1677,49 @@ mod tests {
         (manager, config_dir, workspace_dir)
     }
 
+    #[test]
+    fn test_import_workspace() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let old = Workspace::new_local("shop", "/home/old/shop");
+        let mut bundle = WorkspaceBundle::new(&old);
+        bundle.tags = vec!["client".to_string()];
+        bundle.files.push(crate::workspace::BundleFile {
+            path: "plan.json".to_string(),
+            content: "{\"root\":\"/home/old/shop\"}".to_string(),
+        });
+
+        let path = workspace_dir.path().to_path_buf();
+        let ws = manager.import_workspace(&bundle, path.clone()).unwrap();
+        assert_eq!((ws.name.as_str(), ws.tags.len()), ("shop", 1));
+        let plan = std::fs::read_to_string(path.join(".axiom/plan.json")).unwrap();
+        assert_eq!(plan, format!("{{\"root\":\"{}\"}}", path.display()));
+        assert!(path.join(".axiom/config.toml").exists());
+
+        // Imported once per directory
+        assert!(manager.import_workspace(&bundle, path).is_err());
+    }
+
+    #[test]
+    fn test_failed_import_leaves_no_files() {
+        let config_dir = TempDir::new().unwrap();
+        let workspace_dir = TempDir::new().unwrap();
+        // The registry can't be saved under a plain file
+        let blocked = config_dir.path().join("blocked");
+        std::fs::write(&blocked, "").unwrap();
+        let storage = WorkspaceStorage::with_config_dir(blocked);
+        let manager = WorkspaceManager::with_storage(AxiomConfig::default(), storage).unwrap();
+        let mut bundle = WorkspaceBundle::new(&Workspace::new_local("shop", "/home/old/shop"));
+        bundle.files.push(crate::workspace::BundleFile {
+            path: "plan.json".to_string(),
+            content: "{}".to_string(),
+        });
+
+        let path = workspace_dir.path().to_path_buf();
+        assert!(manager.import_workspace(&bundle, path.clone()).is_err());
+        assert!(!path.join(".axiom/plan.json").exists());
+        assert!(manager.list_workspaces().is_empty());
+    }
+
     #[test]
     fn test_create_and_list_workspace() {
         let (manager, _config_dir, workspace_dir) = test_manager();
@@ -407,6 +1735,553 @@ mod tests {
         assert_eq!(list[0].name, "test");
     }
 
+    #[test]
+    fn test_duplicate_paths_are_refused_and_merged() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let project = workspace_dir.path().join("project");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        let ws = manager
+            .create_workspace("project", project.join("src/.."))
+            .unwrap();
+        assert_eq!(ws.path, normalize_path(&project));
+        let err = manager
+            .create_workspace("again", project.join("."))
+            .unwrap_err();
+        assert!(err.to_string().contains("'project' already exists"));
+        assert_eq!(manager.find_by_path(&project.join(".")).unwrap().id, ws.id);
+
+        // Entries created before paths were normalized
+        let mut older = Workspace::new_local("older", project.join("."));
+        older.created_at = ws.created_at - 100;
+        older.tags = vec!["rust".to_string()];
+        let newer = Workspace::new_local("newer", project.join("src/.."));
+        for dup in [&older, &newer] {
+            manager.registry.write().add(dup.clone());
+        }
+        manager.record_user_time(ws.id, 60).unwrap();
+        manager.record_user_time(newer.id, 30).unwrap();
+        manager
+            .storage
+            .trust_store()
+            .set(&newer.path, true)
+            .unwrap();
+
+        let merged = manager.merge_duplicate_workspaces().unwrap();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].kept, older.id);
+        assert_eq!(merged[0].merged.len(), 2);
+        let list = manager.list_workspaces();
+        assert_eq!(list.len(), 1);
+        let kept = manager.get_workspace(older.id).unwrap();
+        assert_eq!(kept.path, normalize_path(&project));
+        assert_eq!(kept.tags, ["rust"]);
+        assert_eq!(manager.time_report(older.id, None).unwrap().user_secs, 90);
+        assert!(manager.is_trusted(older.id));
+        assert!(manager.merge_duplicate_workspaces().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_time_report() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("timed", workspace_dir.path().to_path_buf())
+            .unwrap();
+
+        manager.record_user_time(ws.id, 120).unwrap();
+        manager
+            .record_agent_time(ws.id, "Coder", Some("claude"), 360)
+            .unwrap();
+
+        let report = manager.time_report(ws.id, Some(1)).unwrap();
+        assert_eq!(report.user_secs, 120);
+        assert_eq!(report.agent_secs, 360);
+        assert_eq!(report.days.len(), 1);
+        assert!(manager.time_report(WorkspaceId::new(), None).is_err());
+    }
+
+    #[test]
+    fn test_export_usage() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("billing", workspace_dir.path().to_path_buf())
+            .unwrap();
+        manager
+            .record_usage(Some(ws.id), UsageRecord::new("claude", "claude-sonnet-4", 1000, 200))
+            .unwrap();
+        manager
+            .record_usage(None, UsageRecord::new("ollama", "llama3", 10, 10))
+            .unwrap();
+
+        let rows = manager.usage_rows(&UsageFilter::default());
+        assert_eq!(rows.len(), 2);
+
+        let path = manager.export_usage(ws.id, None, None, false).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert!(path.starts_with(workspace_dir.path()));
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.contains("billing,claude,claude-sonnet-4,1000,200,1200"));
+
+        assert!(manager
+            .export_usage(ws.id, Some("last week".into()), None, true)
+            .is_err());
+    }
+
+    #[test]
+    fn test_sensitive_writes_wait_for_approval() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("ci", workspace_dir.path().to_path_buf())
+            .unwrap();
+
+        let plain = AgentOperation::Write {
+            path: PathBuf::from("src/lib.rs"),
+            content: String::new(),
+        };
+        assert!(manager.check_write(ws.id, "developer", &plain).unwrap().is_none());
+
+        let workflow = AgentOperation::Write {
+            path: PathBuf::from(".github/workflows/ci.yml"),
+            content: "on: push\n".to_string(),
+        };
+        let held = manager
+            .check_write(ws.id, "developer", &workflow)
+            .unwrap()
+            .unwrap();
+        assert_eq!(held.reason, "CI workflow");
+        assert!(!workspace_dir.path().join(".github").exists());
+
+        let env = AgentOperation::Delete {
+            path: PathBuf::from(".env"),
+        };
+        manager.check_write(ws.id, "developer", &env).unwrap().unwrap();
+        assert_eq!(manager.pending_writes(ws.id).len(), 2);
+
+        let approved = manager.resolve_write(ws.id, Some(&held.id), true).unwrap();
+        assert_eq!(approved.id, held.id);
+        let written = workspace_dir.path().join(".github/workflows/ci.yml");
+        assert_eq!(std::fs::read_to_string(written).unwrap(), "on: push\n");
+
+        manager.resolve_write(ws.id, None, false).unwrap();
+        assert!(manager.pending_writes(ws.id).is_empty());
+        assert!(manager.resolve_write(ws.id, None, true).is_err());
+    }
+
+    #[test]
+    fn test_service_approves_and_rejects_held_operations() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("app", workspace_dir.path().to_path_buf())
+            .unwrap();
+        let service = manager.get_or_create_service(ws.id).unwrap();
+        let mut service = service.lock();
+        while service.poll_notification().is_some() {}
+
+        let write = |path: &str| AgentOperation::Write {
+            path: PathBuf::from(path),
+            content: "SECRET=1\n".to_string(),
+        };
+        let kept = manager
+            .check_write(ws.id, "developer", &write(".env"))
+            .unwrap()
+            .unwrap();
+        let dropped = manager
+            .check_write(ws.id, "developer", &write(".env.local"))
+            .unwrap()
+            .unwrap();
+
+        service
+            .send(Command::approve_operations(vec![kept.id.clone()]))
+            .unwrap();
+        assert!(workspace_dir.path().join(".env").is_file());
+        match service.poll_notification() {
+            Some(Notification::PendingOperations { operations }) => {
+                assert_eq!(operations.len(), 1);
+                assert_eq!(operations[0].id, dropped.id);
+            }
+            other => panic!("expected PendingOperations, got {:?}", other),
+        }
+
+        service
+            .send(Command::reject_operations(Vec::new()))
+            .unwrap();
+        assert!(!workspace_dir.path().join(".env.local").exists());
+        assert!(manager.pending_writes(ws.id).is_empty());
+        assert!(service
+            .send(Command::reject_operations(Vec::new()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_approval_mode_holds_every_operation() {
+        let (mut manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("app", workspace_dir.path().to_path_buf())
+            .unwrap();
+        let write = AgentOperation::Write {
+            path: PathBuf::from("src/lib.rs"),
+            content: "pub fn one() {}\n".to_string(),
+        };
+
+        let held = manager
+            .review_operation(ws.id, "developer", &write)
+            .unwrap()
+            .unwrap();
+        assert_eq!(held.reason, "approval mode");
+        assert!(!workspace_dir.path().join("src").exists());
+        manager.resolve_write(ws.id, Some(&held.id), true).unwrap();
+        assert!(workspace_dir.path().join("src/lib.rs").is_file());
+
+        let mut config = AxiomConfig::default();
+        config.orchestration.auto_approve = true;
+        manager.set_global_config(config);
+        assert!(manager
+            .review_operation(ws.id, "developer", &write)
+            .unwrap()
+            .is_none());
+        // The write policy still applies
+        let env = AgentOperation::Delete {
+            path: PathBuf::from(".env"),
+        };
+        let held = manager.review_operation(ws.id, "developer", &env).unwrap();
+        assert_eq!(held.unwrap().reason, "environment file");
+    }
+
+    #[test]
+    fn test_workspace_trust() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("cloned", workspace_dir.path().to_path_buf())
+            .unwrap();
+
+        assert_eq!(manager.trust(ws.id).unwrap(), None);
+        assert!(!manager.is_trusted(ws.id));
+
+        manager.set_trusted(ws.id, true).unwrap();
+        assert_eq!(manager.trust(ws.id).unwrap(), Some(true));
+        assert!(manager.is_trusted(ws.id));
+
+        manager.set_trusted(ws.id, false).unwrap();
+        assert!(!manager.is_trusted(ws.id));
+        assert!(manager.trust(WorkspaceId::new()).is_err());
+    }
+
+    #[test]
+    fn test_watch_rules_persist() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("api", workspace_dir.path().to_path_buf())
+            .unwrap();
+        let rule = WatchRule::new(
+            "src/**/*.rs",
+            crate::workspace::WatchAction::Run {
+                command: "cargo test".to_string(),
+            },
+        );
+
+        assert_eq!(manager.add_watch_rule(ws.id, rule.clone()).unwrap(), 1);
+        assert_eq!(manager.watch_rules(ws.id).unwrap(), vec![rule.clone()]);
+        let saved =
+            std::fs::read_to_string(workspace_dir.path().join(".axiom/config.toml")).unwrap();
+        assert!(saved.contains("[[watch]]"), "{}", saved);
+
+        assert!(manager.remove_watch_rule(ws.id, 2).is_err());
+        assert_eq!(manager.remove_watch_rule(ws.id, 1).unwrap(), rule);
+        assert!(manager.watch_rules(ws.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_workspace_defaults_override_global_config() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("hobby", workspace_dir.path().to_path_buf())
+            .unwrap();
+        let unknown = WorkspaceDefaults {
+            default_provider: Some("nope".to_string()),
+            ..Default::default()
+        };
+        assert!(manager.set_workspace_defaults(ws.id, unknown).is_err());
+
+        let defaults = WorkspaceDefaults {
+            default_provider: Some("claude".to_string()),
+            default_cli_agent: Some("gemini".to_string()),
+            ..Default::default()
+        };
+        manager
+            .set_workspace_defaults(ws.id, defaults.clone())
+            .unwrap();
+        assert_eq!(manager.workspace_defaults(ws.id).unwrap(), defaults);
+
+        let ws_config = manager.get_workspace_config(ws.id).unwrap();
+        let untrusted = manager.build_effective_config(&ws_config, manager.is_trusted(ws.id));
+        assert_eq!(untrusted.llm.default_provider, "ollama");
+        let config = manager.build_effective_config(&ws_config, true);
+        assert_eq!(config.llm.default_provider, "claude");
+        assert_eq!(config.cli_agents.default.as_deref(), Some("gemini"));
+    }
+
+    #[test]
+    fn test_routing_override_audit_trail() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("clinic", workspace_dir.path().to_path_buf())
+            .unwrap();
+        let blocked = RouteCheck::Blocked("claude processes data in us".to_string());
+
+        assert_eq!(
+            manager.audit_route(ws.id, "claude", blocked.clone()),
+            blocked
+        );
+        assert!(manager.allow_routing(ws.id, "claude", " ").is_err());
+
+        manager
+            .allow_routing(ws.id, "claude", "de-identified sample")
+            .unwrap();
+        assert_eq!(manager.routing_overrides(ws.id), vec!["claude"]);
+        assert!(matches!(
+            manager.audit_route(ws.id, "claude", blocked.clone()),
+            RouteCheck::Warn(_)
+        ));
+        assert_eq!(
+            manager.audit_route(ws.id, "gemini", blocked.clone()),
+            blocked
+        );
+
+        assert!(manager.revoke_routing(ws.id, "claude").unwrap());
+        assert!(!manager.revoke_routing(ws.id, "claude").unwrap());
+        assert_eq!(
+            manager.audit_route(ws.id, "claude", blocked.clone()),
+            blocked
+        );
+
+        let trail: Vec<String> = manager
+            .activity(ws.id, 0, 20)
+            .unwrap()
+            .events
+            .into_iter()
+            .map(|e| e.summary)
+            .collect();
+        assert_eq!(trail.len(), 6);
+        assert!(trail.iter().any(|s| s.contains("(de-identified sample)")));
+        assert!(trail
+            .iter()
+            .any(|s| s.starts_with("Sent to claude under override")));
+    }
+
+    #[test]
+    fn test_signed_activity_detects_tampering() {
+        let (manager, config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("audited", workspace_dir.path().to_path_buf())
+            .unwrap();
+        let mut config = manager.get_workspace_config(ws.id).unwrap();
+        config.audit.sign = true;
+        manager.save_workspace_config(ws.id, &config).unwrap();
+
+        for summary in ["Started", "Wrote src/main.rs", "Finished"] {
+            let event = ActivityEvent::new(ActivityKind::AgentRun, "coder", summary);
+            manager.record_activity(ws.id, &event).unwrap();
+        }
+        let report = manager.verify_audit(ws.id).unwrap();
+        assert!(report.is_intact(), "{:?}", report.problems);
+        assert_eq!(report.signed, 3);
+        let page = manager.activity(ws.id, 0, 10).unwrap();
+        assert_eq!(page.chain_head, report.chain_head);
+
+        let path = config_dir
+            .path()
+            .join("activity")
+            .join(format!("{}.jsonl", ws.id));
+        let log = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, log.replace("Wrote src/main.rs", "Read src/main.rs")).unwrap();
+        assert!(!manager.verify_audit(ws.id).unwrap().is_intact());
+
+        let exported = manager.export_audit(ws.id).unwrap();
+        let header = std::fs::read_to_string(exported).unwrap();
+        assert!(header.lines().next().unwrap().contains("chain_head"));
+    }
+
+    #[test]
+    fn test_record_agent_event() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("cli", workspace_dir.path().to_path_buf())
+            .unwrap();
+
+        let edit = AgentEvent::FileChanged {
+            path: PathBuf::from("src/app.py"),
+        };
+        manager.record_agent_event(ws.id, "Aider", &edit).unwrap();
+        let cost = AgentEvent::Cost {
+            usd: Some(0.02),
+            input_tokens: 2_000,
+            output_tokens: 100,
+        };
+        manager.record_agent_event(ws.id, "Aider", &cost).unwrap();
+
+        let page = manager.activity(ws.id, 0, 10).unwrap();
+        let entry = page.events.iter().find(|e| e.actor == "Aider").unwrap();
+        assert_eq!(entry.summary, "Edited src/app.py");
+        assert_eq!(entry.path.as_deref(), Some(Path::new("src/app.py")));
+
+        let rows = manager.usage_rows(&UsageFilter::default());
+        let row = rows.iter().find(|r| r.provider == "cli").unwrap();
+        assert_eq!((row.model.as_str(), row.tokens), ("Aider", 2_100));
+        assert!((row.cost - 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_activity_feed_merges_commits() {
+        let (manager, _config_dir, _workspace_dir) = test_manager();
+        let Some(repo) = worktree::tests::init_repo() else {
+            return; // git not available
+        };
+        let ws = manager.create_workspace("repo", repo.path().to_path_buf()).unwrap();
+
+        let event = ActivityEvent::new(ActivityKind::AgentRun, "Conductor", "Finished");
+        manager.record_activity(ws.id, &event).unwrap();
+
+        let page = manager.activity(ws.id, 0, 10).unwrap();
+        assert_eq!(page.total, 2);
+        let kinds: Vec<_> = page.events.iter().map(|e| e.kind.label()).collect();
+        assert!(kinds.contains(&"agent") && kinds.contains(&"commit"));
+
+        manager.delete_workspace(ws.id).unwrap();
+        assert!(manager.storage.activity_log(ws.id).read().is_empty());
+    }
+
+    #[test]
+    fn test_discover_skips_registered() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        for name in ["one", "two"] {
+            std::fs::create_dir_all(workspace_dir.path().join(name).join(".git")).unwrap();
+        }
+        manager
+            .create_workspace("one", workspace_dir.path().join("one"))
+            .unwrap();
+
+        let roots = [workspace_dir.path().to_path_buf()];
+        let found = manager.discover_workspaces(&roots, 1);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "two");
+    }
+
+    #[test]
+    fn test_repair_broken_workspace() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let moved_to = TempDir::new().unwrap();
+        let old_path = workspace_dir.path().join("project");
+        std::fs::create_dir(&old_path).unwrap();
+        let ws = manager.create_workspace("project", old_path.clone()).unwrap();
+
+        std::fs::remove_dir_all(&old_path).unwrap();
+        assert_eq!(manager.broken_workspaces().len(), 1);
+        let err = manager.activate_workspace(ws.id).err().unwrap();
+        assert!(err.to_string().contains("broken"));
+
+        // Archive hides it from the listing but keeps the entry
+        manager.set_workspace_archived(ws.id, true).unwrap();
+        assert!(manager.list_workspaces().is_empty());
+        assert_eq!(manager.archived_workspaces().len(), 1);
+
+        // Relocating repairs and unarchives it
+        assert!(manager.relocate_workspace(ws.id, old_path).is_err());
+        let ws = manager
+            .relocate_workspace(ws.id, moved_to.path().to_path_buf())
+            .unwrap();
+        let list = manager.list_workspaces();
+        assert_eq!(list.len(), 1);
+        assert!(!list[0].broken);
+        assert!(manager.activate_workspace(ws.id).is_ok());
+    }
+
+    #[test]
+    fn test_purge_archived_workspace() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("project", workspace_dir.path().to_path_buf())
+            .unwrap();
+        let other = TempDir::new().unwrap();
+        let kept = manager
+            .create_workspace("kept", other.path().to_path_buf())
+            .unwrap();
+
+        manager.set_trusted(ws.id, true).unwrap();
+        manager
+            .record_activity(
+                ws.id,
+                &ActivityEvent::new(ActivityKind::AgentRun, "shell", "ls"),
+            )
+            .unwrap();
+        for id in [ws.id, ws.id, kept.id] {
+            manager
+                .record_usage(Some(id), UsageRecord::new("claude", "claude-3", 10, 5))
+                .unwrap();
+        }
+        let session = crate::sessions::Session::new("main", Vec::new(), None, Vec::new());
+        crate::sessions::save(&ws.path, &session).unwrap();
+
+        let plan = manager.purge_plan(ws.id).unwrap();
+        assert_eq!(plan.usage_records, 2);
+        let labels: Vec<&str> = plan.items.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "activity log",
+                "1 saved sessions",
+                "2 usage records",
+                "trust decision",
+                "workspace entry"
+            ]
+        );
+
+        assert_eq!(manager.resolve_workspace("PROJECT").unwrap().id, ws.id);
+        assert_eq!(
+            manager.resolve_workspace(&kept.id.to_string()).unwrap().id,
+            kept.id
+        );
+        assert!(manager.resolve_workspace("missing").is_err());
+
+        // Only archived workspaces can be purged; archiving keeps the data
+        assert!(manager.purge_workspace(ws.id).is_err());
+        manager.set_workspace_archived(ws.id, true).unwrap();
+        assert_eq!(manager.purge_plan(ws.id).unwrap(), plan);
+
+        manager.purge_workspace(ws.id).unwrap();
+        assert!(manager.get_workspace(ws.id).is_none());
+        assert!(!crate::sessions::sessions_dir(&ws.path).exists());
+        assert!(ws.path.join(".axiom/config.toml").exists());
+        assert_eq!(manager.trust(kept.id).unwrap(), None);
+        let usage = manager.storage.usage_log().read();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].workspace_id, Some(kept.id));
+    }
+
+    #[test]
+    fn test_list_health_and_search() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let Some(repo) = worktree::tests::init_repo() else {
+            return; // git not available
+        };
+
+        manager
+            .create_workspace("plain", workspace_dir.path().to_path_buf())
+            .unwrap();
+        manager
+            .create_workspace("repo", repo.path().to_path_buf())
+            .unwrap();
+        std::fs::write(repo.path().join("new.txt"), "x").unwrap();
+
+        let list = manager.list_workspaces();
+        let repo_view = list.iter().find(|w| w.name == "repo").unwrap();
+        let plain_view = list.iter().find(|w| w.name == "plain").unwrap();
+        assert!(repo_view.git_dirty);
+        assert!(!plain_view.git_dirty);
+        assert_eq!(repo_view.running_agents, 0);
+
+        let found = manager.search_workspaces("rpo");
+        assert_eq!(found[0].name, "repo");
+    }
+
     #[test]
     fn test_activate_workspace() {
         let (manager, _config_dir, workspace_dir) = test_manager();
@@ -436,4 +2311,48 @@ mod tests {
         assert!(manager.get_workspace(ws.id).is_none());
         assert!(manager.list_workspaces().is_empty());
     }
+
+    #[test]
+    fn test_task_workspace_merge_back() {
+        let Some(repo) = worktree::tests::init_repo() else { return };
+        let (manager, _config_dir, _workspace_dir) = test_manager();
+
+        let parent = manager
+            .create_workspace("repo", repo.path().to_path_buf())
+            .unwrap();
+        let first = manager.create_task_workspace(parent.id, "Add docs").unwrap();
+        let second = manager.create_task_workspace(parent.id, "Add docs").unwrap();
+
+        assert_ne!(first.path, second.path);
+        assert_eq!(first.parent_id, Some(parent.id));
+        assert!(first.branch.as_deref().unwrap().starts_with(TASK_BRANCH_PREFIX));
+        assert_eq!(manager.sub_workspaces(parent.id).len(), 2);
+
+        // Each task gets its own service
+        let a = manager.get_or_create_service(first.id).unwrap();
+        let b = manager.get_or_create_service(second.id).unwrap();
+        assert!(!Arc::ptr_eq(&a, &b));
+        drop((a, b));
+
+        std::fs::write(first.path.join("DOCS.md"), "docs\n").unwrap();
+        let outcome = manager.merge_task_workspace(first.id).unwrap();
+        assert!(matches!(outcome, MergeOutcome::Merged { .. }));
+        assert!(repo.path().join("DOCS.md").exists());
+        assert!(!first.path.exists());
+        assert!(manager.get_workspace(first.id).is_none());
+
+        manager.discard_task_workspace(second.id).unwrap();
+        assert!(manager.sub_workspaces(parent.id).is_empty());
+    }
+
+    #[test]
+    fn test_task_workspace_requires_git() {
+        let (manager, _config_dir, workspace_dir) = test_manager();
+        let ws = manager
+            .create_workspace("plain", workspace_dir.path().to_path_buf())
+            .unwrap();
+
+        assert!(manager.create_task_workspace(ws.id, "task").is_err());
+        assert!(manager.merge_task_workspace(ws.id).is_err());
+    }
 }
@@ -13,10 +13,15 @@
 //!     │
 //!     ├── WorkspaceStorage (disk I/O)
 //!     │   ├── ~/.axiom/workspaces.json (global registry)
-//!     │   └── <workspace>/.axiom/config.toml (per-workspace)
+//!     │   ├── ~/.axiom/trust.json (trusted directories)
+//!     │   ├── ~/.axiom/audit.key (activity log signing key)
+//!     │   └── <workspace>/.axiom/config.toml (per-workspace, incl. watch rules)
 //!     │
-//!     └── Services (lazy-loaded)
-//!         └── HashMap<WorkspaceId, Arc<AxiomService>>
+//!     ├── Services (lazy-loaded)
+//!     │   └── HashMap<WorkspaceId, Arc<AxiomService>>
+//!     │
+//!     └── Task worktrees (sub-workspaces)
+//!         └── ~/.axiom/worktrees/<parent-id>/<task> on branch axiom/<task>
 //! ```
 //!
 //! # Example
@@ -37,13 +42,49 @@
 //! service.send(Command::ProcessInput { text: "hello".into() })?;
 //! ```
 
+mod activity;
+mod audit;
+mod bundle;
+mod discovery;
+mod duplicates;
+mod exec_policy;
+mod features;
 mod manager;
+mod purge;
+mod report;
+mod review;
+mod search;
 mod storage;
+mod time_tracking;
+mod trust;
 mod types;
+mod watch;
+mod worktree;
+mod write_policy;
 
+pub use activity::{timeline, ActivityEvent, ActivityKind, ActivityLog, ActivityPage};
+pub use audit::{verify_chain, AuditConfig, AuditKey, AuditReport, ChainLink, GENESIS};
+pub use bundle::{remap_paths, BundleFile, WorkspaceBundle, BUNDLE_FORMAT};
+pub use discovery::{discover_projects, DiscoveredProject, PROJECT_MARKERS};
+pub use duplicates::{merge_summary, normalize_path, MergedWorkspaces};
+pub use exec_policy::{ExecPolicy, PolicyViolation};
+pub use features::{feature_switched, features_summary, Feature, FeatureFlags};
 pub use manager::WorkspaceManager;
+pub(crate) use purge::format_bytes;
+pub use purge::{purge_summary, PurgeItem, PurgePlan};
+pub use report::{agent_changelog, changesets, parse_since, Changeset, CHANGESET_GAP};
+pub use review::{review_summary, sort_queue, MergeConflict, ReviewItem, ReviewKind, STALL_AFTER};
+pub use search::{filter_workspaces, fuzzy_score};
 pub use storage::{WorkspaceRegistry, WorkspaceStorage};
+pub use time_tracking::{
+    format_duration, today, utc_date, DayReport, DayTime, TimeLedger, TimeReport,
+};
+pub use trust::{trust_status, TrustStore};
 pub use types::{
-    Workspace, WorkspaceCliAgent, WorkspaceConfig, WorkspaceId, WorkspaceLlmConfig,
-    WorkspaceProviderConfig, WorkspaceType, WorkspaceView,
+    Workspace, WorkspaceCliAgent, WorkspaceConfig, WorkspaceDefaults, WorkspaceId,
+    WorkspaceLlmConfig, WorkspaceProviderConfig, WorkspaceType, WorkspaceView,
 };
+pub use watch::{watch_summary, WatchAction, WatchEngine, WatchRule, WatchTrigger, MAX_RUNNING};
+pub use worktree::{is_git_repo, CommitInfo, MergeOutcome, TASK_BRANCH_PREFIX};
+pub use write_policy::{pending_summary, ApprovalQueue, PendingWrite, WritePolicy};
+pub(crate) use write_policy::glob_match;
@@ -0,0 +1,155 @@
+//! Purging archived workspaces
+//!
+//! Deleting a workspace only archives it: it leaves the default list but its
+//! sessions, activity and usage history stay on disk and it can be restored.
+//! Purging an archived workspace removes all of that for good. A
+//! [`PurgePlan`] lists what a purge would delete so UIs can show it before
+//! asking for confirmation. The project's own files, including its
+//! `.axiom/config.toml`, are never touched.
+
+use super::types::{Workspace, WorkspaceId};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One piece of Axiom data a purge deletes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PurgeItem {
+    /// What it is, e.g. "activity log" or "3 saved sessions"
+    pub label: String,
+    /// File or directory removed (None for entries inside shared files)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Size on disk in bytes
+    pub bytes: u64,
+}
+
+impl PurgeItem {
+    /// An item for the file or directory at `path`, or None if it doesn't exist
+    pub fn at(label: impl Into<String>, path: PathBuf) -> Option<Self> {
+        path.exists().then(|| Self {
+            label: label.into(),
+            bytes: disk_usage(&path),
+            path: Some(path),
+        })
+    }
+
+    /// An item with no file of its own
+    pub fn entry(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            path: None,
+            bytes: 0,
+        }
+    }
+}
+
+/// Everything a purge of one workspace would delete
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PurgePlan {
+    pub workspace_id: WorkspaceId,
+    pub name: String,
+    pub path: PathBuf,
+    pub items: Vec<PurgeItem>,
+    /// Task worktrees that go with it
+    pub tasks: Vec<WorkspaceId>,
+    /// Records dropped from the shared usage log
+    pub usage_records: usize,
+}
+
+impl PurgePlan {
+    pub fn new(workspace: &Workspace) -> Self {
+        Self {
+            workspace_id: workspace.id,
+            name: workspace.name.clone(),
+            path: workspace.path.clone(),
+            items: Vec::new(),
+            tasks: Vec::new(),
+            usage_records: 0,
+        }
+    }
+
+    /// Total size of the files removed
+    pub fn total_bytes(&self) -> u64 {
+        self.items.iter().map(|item| item.bytes).sum()
+    }
+}
+
+/// User-facing description of a purge, for confirmation or as its result
+pub fn purge_summary(plan: &PurgePlan, done: bool) -> String {
+    let mut text = if done {
+        format!("Purged workspace '{}':\n", plan.name)
+    } else {
+        format!("Purging workspace '{}' permanently deletes:\n", plan.name)
+    };
+    for item in &plan.items {
+        text.push_str(&format!("  - {}", item.label));
+        if item.bytes > 0 {
+            text.push_str(&format!(" ({})", format_bytes(item.bytes)));
+        }
+        if let Some(path) = &item.path {
+            text.push_str(&format!("  {}", path.display()));
+        }
+        text.push('\n');
+    }
+    text.push_str(&format!(
+        "Total {} on disk. Project files in {} are not touched.",
+        format_bytes(plan.total_bytes()),
+        plan.path.display()
+    ));
+    text
+}
+
+/// Size of a file, or of everything under a directory
+fn disk_usage(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| disk_usage(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        b if b < 1024 => format!("{} B", b),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_lists_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("log.jsonl");
+        fs::write(&log, vec![b'x'; 2048]).unwrap();
+
+        let ws = Workspace::new_local("api", dir.path().to_path_buf());
+        let mut plan = PurgePlan::new(&ws);
+        plan.items
+            .extend(PurgeItem::at("activity log", log.clone()));
+        plan.items
+            .extend(PurgeItem::at("missing", dir.path().join("nope")));
+        plan.items.push(PurgeItem::entry("workspace entry"));
+
+        assert_eq!(plan.items.len(), 2);
+        assert_eq!(plan.total_bytes(), 2048);
+        let summary = purge_summary(&plan, false);
+        assert!(summary.starts_with("Purging workspace 'api' permanently deletes:"));
+        assert!(summary.contains("  - activity log (2.0 KB)"));
+        assert!(summary.contains("  - workspace entry\n"));
+        assert!(purge_summary(&plan, true).starts_with("Purged workspace 'api':"));
+    }
+}
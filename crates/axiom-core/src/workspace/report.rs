@@ -0,0 +1,227 @@
+//! Changelog of agent contributions
+//!
+//! Compiles what agents changed in a workspace over a time range into a
+//! CHANGELOG-style Markdown document (`/report agents --since 7d`).
+//!
+//! The activity log records agent runs and file writes as separate entries.
+//! Consecutive entries by the same agent, no more than [`CHANGESET_GAP`]
+//! seconds apart, are grouped into one changeset; changesets that neither
+//! touched a file nor carry an explanation are left out.
+
+use super::activity::{ActivityEvent, ActivityKind};
+use super::time_tracking::utc_date;
+use std::collections::BTreeSet;
+
+/// Longest pause between entries of one changeset, in seconds
+pub const CHANGESET_GAP: i64 = 600;
+
+/// Longest explanation quoted per changeset
+const MAX_DETAIL_CHARS: usize = 400;
+
+/// Parse a look-back period such as `7d`, `12h` or `2w` into seconds
+///
+/// A bare number counts days.
+pub fn parse_since(text: &str) -> Option<i64> {
+    let text = text.trim().to_lowercase();
+    let (number, unit) = match text.char_indices().last()? {
+        (i, c) if c.is_ascii_alphabetic() => (&text[..i], c),
+        _ => (text.as_str(), 'd'),
+    };
+    let number: i64 = number.parse().ok().filter(|&n| n > 0)?;
+    let unit = match unit {
+        'h' => 3_600,
+        'd' => 86_400,
+        'w' => 7 * 86_400,
+        _ => return None,
+    };
+    number.checked_mul(unit)
+}
+
+/// What one agent changed in one go
+#[derive(Debug, Clone, PartialEq)]
+pub struct Changeset {
+    pub actor: String,
+    /// Time of the first entry (Unix epoch seconds)
+    pub timestamp: i64,
+    /// Agent run summary, e.g. "Completed: add login form (3 operations)"
+    pub title: Option<String>,
+    /// The agent's explanation of the change
+    pub detail: Option<String>,
+    pub files: BTreeSet<String>,
+}
+
+/// Group agent entries at or after `since` into changesets, oldest first
+pub fn changesets(events: &[ActivityEvent], since: i64) -> Vec<Changeset> {
+    let mut events: Vec<&ActivityEvent> = events
+        .iter()
+        .filter(|e| e.timestamp >= since && e.actor != "user")
+        .filter(|e| matches!(e.kind, ActivityKind::AgentRun | ActivityKind::FileChange))
+        .collect();
+    events.sort_by_key(|e| e.timestamp);
+
+    let mut sets: Vec<Changeset> = Vec::new();
+    let mut last_seen = i64::MIN;
+    for event in events {
+        let continues = sets.last().is_some_and(|set| {
+            set.actor == event.actor && event.timestamp - last_seen <= CHANGESET_GAP
+        });
+        if !continues {
+            sets.push(Changeset {
+                actor: event.actor.clone(),
+                timestamp: event.timestamp,
+                title: None,
+                detail: None,
+                files: BTreeSet::new(),
+            });
+        }
+        last_seen = event.timestamp;
+        let set = sets.last_mut().expect("changeset was just pushed");
+
+        if let Some(path) = &event.path {
+            set.files.insert(path.to_string_lossy().into_owned());
+        }
+        if set.detail.is_none() {
+            set.detail = event.detail.clone();
+        }
+        // The finishing entry describes the run better than the start
+        if event.kind == ActivityKind::AgentRun
+            && (set.title.is_none() || !event.summary.starts_with("Started"))
+        {
+            set.title = Some(event.summary.clone());
+        }
+    }
+
+    sets.retain(|set| !set.files.is_empty() || set.detail.is_some());
+    sets
+}
+
+/// Markdown changelog of the agent changes in `events` between `since`
+/// and `now`, newest day first
+pub fn agent_changelog(workspace: &str, events: &[ActivityEvent], since: i64, now: i64) -> String {
+    let sets = changesets(events, since);
+    let files: BTreeSet<&String> = sets.iter().flat_map(|set| &set.files).collect();
+
+    let mut md = format!("# Agent changelog: {}\n\n", workspace);
+    md.push_str(&format!(
+        "{} to {} · {} changeset(s) · {} file(s)\n",
+        utc_date(since),
+        utc_date(now),
+        sets.len(),
+        files.len()
+    ));
+    if sets.is_empty() {
+        md.push_str("\n_No agent changes in this period._\n");
+        return md;
+    }
+
+    let mut day = String::new();
+    for set in sets.iter().rev() {
+        let date = utc_date(set.timestamp);
+        if date != day {
+            md.push_str(&format!("\n## {}\n\n", date));
+            day = date;
+        }
+        let title = set.title.as_deref().unwrap_or("Changed files");
+        md.push_str(&format!(
+            "- **{}** ({} UTC): {}\n",
+            set.actor,
+            time_of_day(set.timestamp),
+            title
+        ));
+        if let Some(detail) = &set.detail {
+            for line in truncate(detail.trim(), MAX_DETAIL_CHARS).lines() {
+                md.push_str(&format!("  > {}\n", line));
+            }
+        }
+        for file in &set.files {
+            md.push_str(&format!("  - `{}`\n", file));
+        }
+    }
+    md
+}
+
+fn time_of_day(timestamp: i64) -> String {
+    let secs = timestamp.rem_euclid(86_400);
+    format!("{:02}:{:02}", secs / 3_600, secs % 3_600 / 60)
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(ts: i64, kind: ActivityKind, actor: &str, summary: &str) -> ActivityEvent {
+        let mut event = ActivityEvent::new(kind, actor, summary);
+        event.timestamp = ts;
+        event
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("7d"), Some(7 * 86_400));
+        assert_eq!(parse_since("12H"), Some(12 * 3_600));
+        assert_eq!(parse_since("2w"), Some(14 * 86_400));
+        assert_eq!(parse_since("3"), Some(3 * 86_400));
+        assert_eq!(parse_since("0d"), None);
+        assert_eq!(parse_since("7y"), None);
+        assert_eq!(parse_since("d"), None);
+    }
+
+    #[test]
+    fn test_changesets_group_by_agent_and_gap() {
+        let day = 1_760_000_000;
+        let events = vec![
+            event(day, ActivityKind::AgentRun, "Coder", "Started Coder: login"),
+            event(day + 5, ActivityKind::FileChange, "Coder", "Wrote file").with_path("a.rs"),
+            event(day + 9, ActivityKind::FileChange, "Coder", "Wrote file").with_path("b.rs"),
+            event(day + 10, ActivityKind::AgentRun, "Coder", "Finished: login"),
+            event(day + 20, ActivityKind::FileChange, "user", "Edited file").with_path("c.rs"),
+            event(
+                day + 30,
+                ActivityKind::AgentRun,
+                "Shell",
+                "Finished: cargo test",
+            ),
+            event(
+                day + 5_000,
+                ActivityKind::AgentRun,
+                "developer",
+                "Completed: fix bug (1 operations)",
+            )
+            .with_detail("Off-by-one in the pager"),
+            event(
+                day + 5_000,
+                ActivityKind::FileChange,
+                "developer",
+                "Patched file",
+            )
+            .with_path("pager.rs"),
+        ];
+
+        let sets = changesets(&events, day);
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].title.as_deref(), Some("Finished: login"));
+        assert_eq!(sets[0].files.len(), 2);
+        assert_eq!(sets[1].detail.as_deref(), Some("Off-by-one in the pager"));
+        assert_eq!(changesets(&events, day + 100).len(), 1);
+
+        let md = agent_changelog("api", &events, day, day + 6_000);
+        assert!(md.starts_with("# Agent changelog: api\n"));
+        assert!(md.contains("2 changeset(s) · 3 file(s)"));
+        assert!(md.contains("  > Off-by-one in the pager\n  - `pager.rs`\n"));
+        // Newest first
+        assert!(md.find("developer").unwrap() < md.find("Coder").unwrap());
+    }
+
+    #[test]
+    fn test_empty_changelog() {
+        let md = agent_changelog("api", &[], 0, 86_400);
+        assert!(md.contains("_No agent changes in this period._"));
+    }
+}
@@ -0,0 +1,190 @@
+//! Review queue: everything in a workspace waiting on the user
+//!
+//! Collects writes held for approval, task merges stopped by conflicts and
+//! agents that have gone quiet into one list, most urgent first, so the TUI
+//! and the web UI show the same queue and offer the same actions.
+
+use super::write_policy::PendingWrite;
+use super::WorkspaceId;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A running agent with no output for this long is considered stalled
+pub const STALL_AFTER: Duration = Duration::from_secs(120);
+
+/// What a review item is about, in order of urgency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewKind {
+    /// A task's merge stopped on conflicts; the parent can't take its work
+    Conflict,
+    /// An agent write is blocked until approved
+    Approval,
+    /// A running agent has produced nothing for [`STALL_AFTER`]
+    StalledAgent,
+}
+
+impl ReviewKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReviewKind::Conflict => "Conflict",
+            ReviewKind::Approval => "Approval",
+            ReviewKind::StalledAgent => "Stalled",
+        }
+    }
+
+    /// What accept, reject and open do for this kind of item
+    pub fn actions(&self) -> [&'static str; 3] {
+        match self {
+            ReviewKind::Conflict => ["retry merge", "discard task", "open file"],
+            ReviewKind::Approval => ["apply write", "discard write", "open file"],
+            ReviewKind::StalledAgent => ["keep waiting", "cancel agent", "show output"],
+        }
+    }
+}
+
+/// A task merge that stopped on conflicts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergeConflict {
+    /// The task worktree's workspace
+    pub task_id: WorkspaceId,
+    pub task_name: String,
+    /// Worktree the conflicting files live in
+    pub task_path: PathBuf,
+    pub branch: String,
+    /// Conflicting files, relative to the worktree
+    pub files: Vec<String>,
+    /// Unix epoch seconds
+    pub created_at: i64,
+}
+
+/// One entry of the review queue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewItem {
+    pub kind: ReviewKind,
+    /// Approval ID, task workspace ID or agent ID, depending on `kind`
+    pub id: String,
+    pub title: String,
+    pub detail: String,
+    /// File that `open` shows, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// When the item started waiting (Unix epoch seconds)
+    pub created_at: i64,
+}
+
+impl ReviewItem {
+    pub fn approval(pending: &PendingWrite) -> Self {
+        Self {
+            kind: ReviewKind::Approval,
+            id: pending.id.clone(),
+            title: format!("{} wants to write {}", pending.agent, pending.path.display()),
+            detail: pending.reason.clone(),
+            path: Some(pending.path.clone()),
+            created_at: pending.created_at,
+        }
+    }
+
+    pub fn conflict(conflict: &MergeConflict) -> Self {
+        Self {
+            kind: ReviewKind::Conflict,
+            id: conflict.task_id.to_string(),
+            title: format!("Merging {} hit conflicts", conflict.task_name),
+            detail: format!("{} on {}", conflict.files.join(", "), conflict.branch),
+            path: conflict.files.first().map(|file| conflict.task_path.join(file)),
+            created_at: conflict.created_at,
+        }
+    }
+
+    /// `agent` (an agent ID) has been quiet for `idle`
+    pub fn stalled_agent(agent: impl ToString, name: &str, idle: Duration, now: i64) -> Self {
+        Self {
+            kind: ReviewKind::StalledAgent,
+            id: agent.to_string(),
+            title: format!("{} has been quiet", name),
+            detail: format!("No output for {}", super::format_duration(idle.as_secs())),
+            path: None,
+            created_at: now - idle.as_secs() as i64,
+        }
+    }
+}
+
+/// Order `items` by urgency, oldest first within a kind
+pub fn sort_queue(items: &mut [ReviewItem]) {
+    items.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then(a.created_at.cmp(&b.created_at))
+    });
+}
+
+/// Plain-text queue for `/review`
+pub fn review_summary(items: &[ReviewItem]) -> String {
+    if items.is_empty() {
+        return "Nothing to review".to_string();
+    }
+    let mut text = format!("{} item(s) to review\n\n", items.len());
+    for item in items {
+        text.push_str(&format!(
+            "  {:<8} [{}] {} ({})\n",
+            item.kind.label(),
+            item.id,
+            item.title,
+            item.detail
+        ));
+    }
+    text.push_str("\nOpen the queue in the TUI to accept, reject or open items");
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestration::AgentOperation;
+
+    #[test]
+    fn test_queue_is_sorted_by_urgency_then_age() {
+        let mut pending = PendingWrite::new(
+            "developer",
+            PathBuf::from(".env"),
+            "secrets",
+            AgentOperation::Execute {
+                command: "true".to_string(),
+            },
+        );
+        pending.created_at = 50;
+        let conflict = MergeConflict {
+            task_id: WorkspaceId::new(),
+            task_name: "login".to_string(),
+            task_path: PathBuf::from("/trees/login"),
+            branch: "axiom/login".to_string(),
+            files: vec!["src/auth.rs".to_string()],
+            created_at: 100,
+        };
+        let mut items = vec![
+            ReviewItem::stalled_agent("agent-3", "Shell", Duration::from_secs(300), 1000),
+            ReviewItem::approval(&pending),
+            ReviewItem::conflict(&conflict),
+        ];
+        let mut older = ReviewItem::approval(&pending);
+        older.created_at = 10;
+        older.id = "older".to_string();
+        items.push(older);
+
+        sort_queue(&mut items);
+        let order: Vec<(ReviewKind, &str)> = items.iter().map(|i| (i.kind, i.id.as_str())).collect();
+        assert_eq!(
+            order,
+            [
+                (ReviewKind::Conflict, conflict.task_id.to_string().as_str()),
+                (ReviewKind::Approval, "older"),
+                (ReviewKind::Approval, pending.id.as_str()),
+                (ReviewKind::StalledAgent, "agent-3"),
+            ]
+        );
+        assert_eq!(items[0].path, Some(PathBuf::from("/trees/login/src/auth.rs")));
+        assert_eq!(items[3].created_at, 700);
+        assert!(review_summary(&items).starts_with("4 item(s) to review"));
+    }
+}
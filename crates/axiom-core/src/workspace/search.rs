@@ -0,0 +1,104 @@
+//! Fuzzy search over workspaces
+//!
+//! Shared by the TUI selector and the `/api/workspaces?q=` endpoint so both
+//! rank results the same way.
+
+use super::types::WorkspaceView;
+
+/// Score how well `query` fuzzy-matches `text`, or `None` if it doesn't
+///
+/// Every query character must appear in order (case-insensitive).
+/// Consecutive matches and matches at word starts score higher; gaps
+/// cost a little.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0i64;
+    let mut qi = 0;
+    let mut prev: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+
+    for (i, c) in text.to_lowercase().chars().enumerate() {
+        if qi < query.len() && c == query[qi] {
+            score += 1;
+            let word_start = prev.is_none_or(|p| !p.is_alphanumeric());
+            if word_start {
+                score += 8;
+            }
+            match last_match {
+                Some(last) if last + 1 == i => score += 5,
+                Some(last) => score -= (i - last - 1).min(5) as i64,
+                None => score -= i.min(5) as i64,
+            }
+            last_match = Some(i);
+            qi += 1;
+        }
+        prev = Some(c);
+    }
+
+    (qi == query.len()).then_some(score)
+}
+
+/// Filter and rank workspaces by `query`
+///
+/// Name matches rank above path-only matches; equal scores keep their
+/// incoming (recent-first) order. An empty query returns the input as is.
+pub fn filter_workspaces(workspaces: Vec<WorkspaceView>, query: &str) -> Vec<WorkspaceView> {
+    let query = query.trim();
+    if query.is_empty() {
+        return workspaces;
+    }
+
+    let mut scored: Vec<(i64, WorkspaceView)> = workspaces
+        .into_iter()
+        .filter_map(|ws| {
+            let name = fuzzy_score(query, &ws.name).map(|s| s + 10);
+            let path = fuzzy_score(query, &ws.path.to_string_lossy());
+            name.max(path).map(|score| (score, ws))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, ws)| ws).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::types::Workspace;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("axm", "axiom").is_some());
+        assert!(fuzzy_score("xa", "axiom").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+
+        // Consecutive and word-start matches beat scattered ones
+        let tight = fuzzy_score("web", "my-web-app").unwrap();
+        let loose = fuzzy_score("web", "wide-enough-box").unwrap();
+        assert!(tight > loose);
+        assert!(fuzzy_score("WEB", "my-web-app").is_some());
+    }
+
+    #[test]
+    fn test_filter_workspaces_ranks_names_first() {
+        let views: Vec<WorkspaceView> = [
+            Workspace::new_local("notes", "/home/me/api-notes"),
+            Workspace::new_local("api", "/home/me/services/api"),
+            Workspace::new_local("website", "/home/me/site"),
+        ]
+        .iter()
+        .map(WorkspaceView::from)
+        .collect();
+
+        let names: Vec<String> = filter_workspaces(views.clone(), "api")
+            .into_iter()
+            .map(|ws| ws.name)
+            .collect();
+        assert_eq!(names, ["api", "notes"]);
+
+        assert_eq!(filter_workspaces(views, "  ").len(), 3);
+    }
+}
@@ -2,8 +2,13 @@
 //!
 //! Handles loading and saving workspace data to disk.
 
-use super::types::{Workspace, WorkspaceConfig, WorkspaceId};
+use super::activity::ActivityLog;
+use super::duplicates::normalize_path;
+use super::time_tracking::TimeLedger;
+use super::trust::TrustStore;
+use super::types::{Workspace, WorkspaceConfig, WorkspaceId, WorkspaceType};
 use crate::error::{AxiomError, Result};
+use crate::usage::UsageLog;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -88,8 +93,16 @@ impl WorkspaceRegistry {
     }
 
     /// Find workspace by path
+    ///
+    /// Local workspaces match however the path is spelled (relative, with
+    /// `..`, through a symlink).
     pub fn find_by_path(&self, path: &Path) -> Option<&Workspace> {
-        self.workspaces.values().find(|ws| ws.path == path)
+        let normalized = normalize_path(path);
+        self.workspaces.values().find(|ws| {
+            ws.path == path
+                || (ws.workspace_type == WorkspaceType::Local
+                    && normalize_path(&ws.path) == normalized)
+        })
     }
 }
 
@@ -123,6 +136,36 @@ impl WorkspaceStorage {
         }
     }
 
+    /// Directory holding task worktrees (~/.axiom/worktrees)
+    pub fn worktrees_dir(&self) -> PathBuf {
+        self.config_dir.join("worktrees")
+    }
+
+    /// Activity log of a workspace (~/.axiom/activity/<id>.jsonl)
+    pub fn activity_log(&self, id: WorkspaceId) -> ActivityLog {
+        ActivityLog::new(self.config_dir.join("activity").join(format!("{}.jsonl", id)))
+    }
+
+    /// LLM usage log shared by all workspaces (~/.axiom/usage.jsonl)
+    pub fn usage_log(&self) -> UsageLog {
+        UsageLog::new(self.config_dir.join("usage.jsonl"))
+    }
+
+    /// Time ledger of a workspace (~/.axiom/time/<id>.json)
+    pub fn time_ledger(&self, id: WorkspaceId) -> TimeLedger {
+        TimeLedger::new(self.config_dir.join("time").join(format!("{}.json", id)))
+    }
+
+    /// Key signing activity logs (~/.axiom/audit.key)
+    pub fn audit_key_path(&self) -> PathBuf {
+        self.config_dir.join("audit.key")
+    }
+
+    /// Workspace trust decisions (~/.axiom/trust.json)
+    pub fn trust_store(&self) -> TrustStore {
+        TrustStore::new(self.config_dir.join("trust.json"))
+    }
+
     /// Ensure config directory exists
     pub fn ensure_config_dir(&self) -> Result<()> {
         if !self.config_dir.exists() {
@@ -0,0 +1,249 @@
+//! Agent vs. user time accounting
+//!
+//! Each workspace keeps a small JSON ledger (`~/.axiom/time/<id>.json`) of
+//! seconds spent per UTC day: interactive user time, and agent wall-clock
+//! time keyed by `role/model`. UIs measure the durations and add them here.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Seconds spent on one day
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DayTime {
+    /// Interactive user time
+    #[serde(default)]
+    pub user_secs: u64,
+    /// Agent wall-clock time keyed by `role/model` (e.g. "Coder/claude")
+    #[serde(default)]
+    pub agent_secs: BTreeMap<String, u64>,
+}
+
+impl DayTime {
+    /// Agent time over all roles and models
+    pub fn total_agent_secs(&self) -> u64 {
+        self.agent_secs.values().sum()
+    }
+}
+
+/// Per-day time ledger of one workspace
+pub struct TimeLedger {
+    path: PathBuf,
+}
+
+impl TimeLedger {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// File the ledger is kept in
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All recorded days keyed by date (`YYYY-MM-DD`)
+    pub fn load(&self) -> BTreeMap<String, DayTime> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Add interactive user time to `date`
+    pub fn add_user(&self, date: &str, secs: u64) -> Result<()> {
+        self.update(date, |day| day.user_secs += secs)
+    }
+
+    /// Add agent time for `role` (and `model`, if it uses one) to `date`
+    pub fn add_agent(&self, date: &str, role: &str, model: Option<&str>, secs: u64) -> Result<()> {
+        let key = match model {
+            Some(model) => format!("{}/{}", role, model),
+            None => role.to_string(),
+        };
+        self.update(date, |day| *day.agent_secs.entry(key).or_default() += secs)
+    }
+
+    /// Add every day recorded in `other` to this ledger
+    pub fn absorb(&self, other: &TimeLedger) -> Result<()> {
+        let mut days = self.load();
+        for (date, time) in other.load() {
+            let day = days.entry(date).or_default();
+            day.user_secs += time.user_secs;
+            for (key, secs) in time.agent_secs {
+                *day.agent_secs.entry(key).or_default() += secs;
+            }
+        }
+        self.write(&days)
+    }
+
+    /// Delete the ledger file
+    pub fn remove(&self) {
+        let _ = fs::remove_file(&self.path);
+    }
+
+    fn update(&self, date: &str, apply: impl FnOnce(&mut DayTime)) -> Result<()> {
+        let mut days = self.load();
+        apply(days.entry(date.to_string()).or_default());
+        self.write(&days)
+    }
+
+    fn write(&self, days: &BTreeMap<String, DayTime>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(days)?)?;
+        Ok(())
+    }
+}
+
+/// Time summary over a range of days
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TimeReport {
+    /// Days with recorded time, oldest first
+    pub days: Vec<DayReport>,
+    pub user_secs: u64,
+    pub agent_secs: u64,
+    /// Agent time keyed by `role/model`
+    pub by_agent: BTreeMap<String, u64>,
+}
+
+/// Totals of one day in a [`TimeReport`]
+#[derive(Debug, Clone, Serialize)]
+pub struct DayReport {
+    pub date: String,
+    pub user_secs: u64,
+    pub agent_secs: u64,
+}
+
+impl TimeReport {
+    /// Build a report from ledger days, keeping only dates from `since` on
+    pub fn from_days(days: BTreeMap<String, DayTime>, since: Option<&str>) -> Self {
+        let mut report = TimeReport::default();
+        for (date, day) in days {
+            if since.is_some_and(|since| date.as_str() < since) {
+                continue;
+            }
+            let agent_secs = day.total_agent_secs();
+            report.user_secs += day.user_secs;
+            report.agent_secs += agent_secs;
+            for (key, secs) in day.agent_secs {
+                *report.by_agent.entry(key).or_default() += secs;
+            }
+            report.days.push(DayReport {
+                date,
+                user_secs: day.user_secs,
+                agent_secs,
+            });
+        }
+        report
+    }
+
+    /// Share of the tracked time spent by agents (0.0 - 1.0)
+    pub fn automated_share(&self) -> f64 {
+        let total = self.user_secs + self.agent_secs;
+        if total == 0 {
+            0.0
+        } else {
+            self.agent_secs as f64 / total as f64
+        }
+    }
+
+    /// Plain-text summary for `/stats time`
+    pub fn summary(&self) -> String {
+        if self.days.is_empty() {
+            return "No time recorded yet".to_string();
+        }
+        let mut text = format!(
+            "Time: agents {} · you {} · {:.0}% automated\n",
+            format_duration(self.agent_secs),
+            format_duration(self.user_secs),
+            self.automated_share() * 100.0
+        );
+        text.push_str("\nBy agent:\n");
+        for (key, secs) in &self.by_agent {
+            text.push_str(&format!("  {:<24} {}\n", key, format_duration(*secs)));
+        }
+        text.push_str("\nBy day:\n");
+        for day in &self.days {
+            text.push_str(&format!(
+                "  {}  agents {:>8}  you {:>8}\n",
+                day.date,
+                format_duration(day.agent_secs),
+                format_duration(day.user_secs)
+            ));
+        }
+        text
+    }
+}
+
+/// Short duration such as "1h 05m", "12m 30s" or "45s"
+pub fn format_duration(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// UTC calendar date (`YYYY-MM-DD`) of a Unix timestamp
+pub fn utc_date(timestamp: i64) -> String {
+    // Days-to-civil conversion from Howard Hinnant's date algorithms
+    let z = timestamp.div_euclid(86_400) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Today's UTC date
+pub fn today() -> String {
+    utc_date(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_utc_date() {
+        assert_eq!(utc_date(0), "1970-01-01");
+        assert_eq!(utc_date(951_782_400), "2000-02-29");
+        assert_eq!(utc_date(1_767_225_599), "2025-12-31");
+    }
+
+    #[test]
+    fn test_ledger_report() {
+        let dir = TempDir::new().unwrap();
+        let ledger = TimeLedger::new(dir.path().join("time").join("ws.json"));
+
+        ledger.add_user("2026-01-01", 600).unwrap();
+        ledger.add_agent("2026-01-01", "Coder", Some("claude"), 300).unwrap();
+        ledger.add_agent("2026-01-02", "Coder", Some("claude"), 900).unwrap();
+        ledger.add_agent("2026-01-02", "Shell", None, 60).unwrap();
+
+        let report = TimeReport::from_days(ledger.load(), None);
+        assert_eq!(report.days.len(), 2);
+        assert_eq!(report.user_secs, 600);
+        assert_eq!(report.agent_secs, 1260);
+        assert_eq!(report.by_agent["Coder/claude"], 1200);
+        assert!((report.automated_share() - 0.677).abs() < 0.01);
+
+        let recent = TimeReport::from_days(ledger.load(), Some("2026-01-02"));
+        assert_eq!(recent.days.len(), 1);
+        assert_eq!(recent.user_secs, 0);
+        assert!(recent.summary().contains("Shell"));
+    }
+}
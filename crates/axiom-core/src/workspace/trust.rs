@@ -0,0 +1,113 @@
+//! Workspace trust decisions
+//!
+//! A freshly cloned project can ship commands that run as soon as an agent
+//! or hook executes them. Until the user trusts a workspace, Axiom keeps it
+//! in restricted mode: agents don't run shell commands on their own and CLI
+//! agents can't be started. Decisions are kept globally in
+//! `~/.axiom/trust.json` keyed by directory, and apply to everything below
+//! it; the most specific decision wins.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Persisted trust decisions
+pub struct TrustStore {
+    path: PathBuf,
+}
+
+impl TrustStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// All decisions keyed by directory (`true` = trusted)
+    pub fn load(&self) -> BTreeMap<PathBuf, bool> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<TrustFile>(&content).ok())
+            .map(|file| file.folders)
+            .unwrap_or_default()
+    }
+
+    /// Decision covering `dir`, or `None` if the user hasn't decided yet
+    pub fn decision(&self, dir: &Path) -> Option<bool> {
+        let folders = self.load();
+        dir.ancestors()
+            .find_map(|ancestor| folders.get(ancestor).copied())
+    }
+
+    /// Remember whether `dir` is trusted
+    pub fn set(&self, dir: &Path, trusted: bool) -> Result<()> {
+        let mut folders = self.load();
+        folders.insert(dir.to_path_buf(), trusted);
+        self.write(folders)
+    }
+
+    /// Drop the decision made for exactly `dir`; returns whether there was one
+    pub fn forget(&self, dir: &Path) -> Result<bool> {
+        let mut folders = self.load();
+        if folders.remove(dir).is_none() {
+            return Ok(false);
+        }
+        self.write(folders)?;
+        Ok(true)
+    }
+
+    fn write(&self, folders: BTreeMap<PathBuf, bool>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(
+            &self.path,
+            serde_json::to_string_pretty(&TrustFile { folders })?,
+        )?;
+        Ok(())
+    }
+}
+
+/// User-facing description of a trust decision for `/trust`
+pub fn trust_status(decision: Option<bool>) -> &'static str {
+    match decision {
+        Some(true) => "Workspace is trusted: agents may run commands and CLI agents",
+        Some(false) => {
+            "Workspace is restricted: commands and CLI agents are disabled (/trust on to allow)"
+        }
+        None => "Workspace trust not decided yet, running restricted (/trust on to allow)",
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct TrustFile {
+    #[serde(default)]
+    folders: BTreeMap<PathBuf, bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_most_specific_decision_wins() {
+        let dir = TempDir::new().unwrap();
+        let store = TrustStore::new(dir.path().join("trust.json"));
+        assert_eq!(store.decision(Path::new("/src/app")), None);
+
+        store.set(Path::new("/src"), true).unwrap();
+        store.set(Path::new("/src/vendor"), false).unwrap();
+
+        assert_eq!(store.decision(Path::new("/src/app")), Some(true));
+        assert_eq!(store.decision(Path::new("/src/vendor/lib")), Some(false));
+        assert_eq!(store.decision(Path::new("/tmp/other")), None);
+
+        store.set(Path::new("/src/vendor"), true).unwrap();
+        assert_eq!(store.decision(Path::new("/src/vendor/lib")), Some(true));
+
+        assert!(store.forget(Path::new("/src/vendor")).unwrap());
+        assert!(!store.forget(Path::new("/src/vendor")).unwrap());
+        assert_eq!(store.decision(Path::new("/src/vendor/lib")), Some(true));
+    }
+}
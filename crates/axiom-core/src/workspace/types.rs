@@ -2,7 +2,17 @@
 //!
 //! Core types for workspace management - serializable for persistence and IPC.
 
+use super::audit::AuditConfig;
+use super::exec_policy::ExecPolicy;
+use super::features::FeatureFlags;
+use super::watch::WatchRule;
+use super::write_policy::WritePolicy;
+use crate::config::{AgentModelConfig, AxiomConfig};
+use crate::guard::GuardConfig;
+use crate::llm::RoutingPolicy;
+use crate::orchestration::VerifyConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use uuid::Uuid;
 
@@ -109,6 +119,19 @@ pub struct Workspace {
     /// Defaults to <workspace_path>/.axiom/config.toml
     #[serde(default)]
     pub config_path: Option<PathBuf>,
+
+    /// Parent workspace when this is a task worktree
+    #[serde(default)]
+    pub parent_id: Option<WorkspaceId>,
+
+    /// Git branch backing a task worktree
+    #[serde(default)]
+    pub branch: Option<String>,
+
+    /// Hidden from listings but kept in the registry (e.g. a broken
+    /// workspace the user may relocate later)
+    #[serde(default)]
+    pub archived: bool,
 }
 
 impl Workspace {
@@ -129,6 +152,9 @@ impl Workspace {
             is_active: false,
             tags: Vec::new(),
             config_path: None,
+            parent_id: None,
+            branch: None,
+            archived: false,
         }
     }
 
@@ -158,9 +184,30 @@ impl Workspace {
             is_active: false,
             tags: Vec::new(),
             config_path: None,
+            parent_id: None,
+            branch: None,
+            archived: false,
         }
     }
 
+    /// Create a sub-workspace backed by a git worktree of `parent`
+    pub fn new_worktree(
+        name: impl Into<String>,
+        path: impl Into<PathBuf>,
+        parent: WorkspaceId,
+        branch: impl Into<String>,
+    ) -> Self {
+        let mut workspace = Self::new_local(name, path);
+        workspace.parent_id = Some(parent);
+        workspace.branch = Some(branch.into());
+        workspace
+    }
+
+    /// Whether this workspace is a task worktree of another workspace
+    pub fn is_sub_workspace(&self) -> bool {
+        self.parent_id.is_some()
+    }
+
     /// Update last accessed timestamp to now
     pub fn touch(&mut self) {
         self.last_accessed = std::time::SystemTime::now()
@@ -180,6 +227,11 @@ impl Workspace {
     pub fn exists(&self) -> bool {
         self.path.exists()
     }
+
+    /// A local workspace whose directory was moved or deleted on disk
+    pub fn is_broken(&self) -> bool {
+        self.workspace_type == WorkspaceType::Local && !self.exists()
+    }
 }
 
 /// Workspace-specific configuration overrides
@@ -200,10 +252,116 @@ pub struct WorkspaceConfig {
     /// Ignored paths (gitignore-style patterns)
     #[serde(default)]
     pub ignore_patterns: Vec<String>,
+
+    /// Secret and prompt-injection checks on content sent to LLMs
+    #[serde(default)]
+    pub guard: GuardConfig,
+
+    /// Paths whose agent writes always need approval
+    #[serde(default)]
+    pub write_policy: WritePolicy,
+
+    /// Limits on commands run by the Shell agent and the command API
+    #[serde(default)]
+    pub exec_policy: ExecPolicy,
+
+    /// Tamper-evident signing of the activity log
+    #[serde(default)]
+    pub audit: AuditConfig,
+
+    /// Data sensitivity and the provider regions it may be sent to
+    #[serde(default)]
+    pub routing: RoutingPolicy,
+
+    /// Commands and prompts run when matching files change
+    #[serde(default)]
+    pub watch: Vec<WatchRule>,
+
+    /// Inline ghost-text completions in the editor
+    #[serde(default)]
+    pub completion: bool,
+
+    /// Experimental subsystems switched on or off (`[features]`)
+    #[serde(default)]
+    pub features: FeatureFlags,
+
+    /// Checks run after the Developer's changes land (`[verify]`)
+    #[serde(default)]
+    pub verify: VerifyConfig,
+
+    /// CLI agent `#` starts when none is named, over `[cli_agents] default`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_cli_agent: Option<String>,
+}
+
+impl WorkspaceConfig {
+    /// Put the workspace's provider, model and CLI agent choices over the
+    /// global `config`
+    ///
+    /// A checked-out project could otherwise send prompts and keys to a
+    /// server of its choosing, so the provider, model, key and URL overrides
+    /// only apply to `trusted` workspaces.
+    pub fn apply_overrides(&self, config: &mut AxiomConfig, trusted: bool) {
+        if let Some(llm) = self.llm.as_ref().filter(|_| trusted) {
+            if let Some(ref provider) = llm.default_provider {
+                config.llm.default_provider = provider.clone();
+            }
+            for (name, overrides) in &llm.providers {
+                let provider = config.llm.providers.entry(name.clone()).or_default();
+                if let Some(ref model) = overrides.model {
+                    provider.default_model = Some(model.clone());
+                }
+                if let Some(ref api_key) = overrides.api_key {
+                    provider.api_key = Some(api_key.clone());
+                }
+                if let Some(ref base_url) = overrides.base_url {
+                    provider.base_url = Some(base_url.clone());
+                }
+            }
+            if let Some(ref model) = llm.default_model {
+                let provider = config
+                    .llm
+                    .providers
+                    .entry(config.llm.default_provider.clone())
+                    .or_default();
+                provider.default_model = Some(model.clone());
+            }
+            config.llm.agents.extend(llm.agents.clone());
+        }
+        if let Some(ref agent) = self.default_cli_agent {
+            config.cli_agents.default = Some(agent.clone());
+        }
+    }
+
+    /// The workspace's provider, model and CLI agent choices
+    pub fn defaults(&self) -> WorkspaceDefaults {
+        let llm = self.llm.clone().unwrap_or_default();
+        WorkspaceDefaults {
+            default_provider: llm.default_provider,
+            default_model: llm.default_model,
+            agents: llm.agents,
+            default_cli_agent: self.default_cli_agent.clone(),
+        }
+    }
+
+    /// Replace the workspace's provider, model and CLI agent choices,
+    /// keeping its per-provider overrides
+    pub fn set_defaults(&mut self, defaults: WorkspaceDefaults) {
+        let mut llm = self.llm.take().unwrap_or_default();
+        llm.default_provider = defaults.default_provider;
+        llm.default_model = defaults.default_model;
+        llm.agents = defaults.agents;
+        let empty = llm.default_provider.is_none()
+            && llm.default_model.is_none()
+            && llm.agents.is_empty()
+            && llm.providers.is_empty();
+        self.llm = (!empty).then_some(llm);
+        self.default_cli_agent = defaults.default_cli_agent;
+    }
 }
 
 /// Workspace-specific LLM configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WorkspaceLlmConfig {
     /// Override default provider
     pub default_provider: Option<String>,
@@ -213,7 +371,28 @@ pub struct WorkspaceLlmConfig {
 
     /// Provider-specific overrides
     #[serde(default)]
-    pub providers: std::collections::HashMap<String, WorkspaceProviderConfig>,
+    pub providers: HashMap<String, WorkspaceProviderConfig>,
+
+    /// Provider and model per agent role (`[llm.agents.developer]`), over
+    /// the global ones
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub agents: HashMap<String, AgentModelConfig>,
+}
+
+/// A workspace's own provider, model and CLI agent, where it has them;
+/// unset ones fall back to the global config
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceDefaults {
+    #[serde(default)]
+    pub default_provider: Option<String>,
+    /// Model of the default provider
+    #[serde(default)]
+    pub default_model: Option<String>,
+    /// Provider and model per agent role
+    #[serde(default)]
+    pub agents: HashMap<String, AgentModelConfig>,
+    #[serde(default)]
+    pub default_cli_agent: Option<String>,
 }
 
 /// Workspace-specific provider configuration
@@ -271,6 +450,21 @@ pub struct WorkspaceView {
     pub is_active: bool,
     pub last_accessed: i64,
     pub exists: bool,
+    #[serde(default)]
+    pub parent_id: Option<WorkspaceId>,
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Uncommitted changes in the workspace's git repo
+    #[serde(default)]
+    pub git_dirty: bool,
+    /// Agents still running in the workspace's service
+    #[serde(default)]
+    pub running_agents: usize,
+    /// Path is missing on disk; relocate, archive or remove to repair
+    #[serde(default)]
+    pub broken: bool,
+    #[serde(default)]
+    pub archived: bool,
 }
 
 impl From<&Workspace> for WorkspaceView {
@@ -283,6 +477,12 @@ impl From<&Workspace> for WorkspaceView {
             is_active: ws.is_active,
             last_accessed: ws.last_accessed,
             exists: ws.exists(),
+            parent_id: ws.parent_id,
+            branch: ws.branch.clone(),
+            git_dirty: false,
+            running_agents: 0,
+            broken: ws.is_broken(),
+            archived: ws.archived,
         }
     }
 }
@@ -308,6 +508,20 @@ mod tests {
         assert_eq!(ws.name, parsed.name);
     }
 
+    #[test]
+    fn test_legacy_workspace_has_no_parent() {
+        let ws = Workspace::new_local("test", "/tmp/test");
+        let mut json: serde_json::Value = serde_json::to_value(&ws).unwrap();
+        json.as_object_mut().unwrap().remove("parent_id");
+        json.as_object_mut().unwrap().remove("branch");
+        let parsed: Workspace = serde_json::from_value(json).unwrap();
+        assert!(!parsed.is_sub_workspace());
+
+        let sub = Workspace::new_worktree("task", "/tmp/task", ws.id, "axiom/task");
+        assert!(sub.is_sub_workspace());
+        assert_eq!(WorkspaceView::from(&sub).parent_id, Some(ws.id));
+    }
+
     #[test]
     fn test_workspace_type_serialization() {
         let remote = WorkspaceType::Remote {
@@ -319,4 +533,101 @@ mod tests {
         assert!(json.contains("Remote"));
         assert!(json.contains("example.com"));
     }
+
+    #[test]
+    fn test_workspace_overrides_global_config() {
+        let ws_config: WorkspaceConfig = toml::from_str(
+            r#"
+            default_cli_agent = "gemini"
+
+            [llm]
+            default_provider = "claude"
+            default_model = "claude-opus-4-20250514"
+
+            [llm.agents.developer]
+            provider = "ollama"
+            model = "qwen2.5-coder"
+            "#,
+        )
+        .unwrap();
+
+        let mut config = AxiomConfig::default();
+        config.llm.agents.insert(
+            "reviewer".to_string(),
+            AgentModelConfig {
+                provider: "gemini".to_string(),
+                model: "gemini-2.0-flash".to_string(),
+            },
+        );
+        ws_config.apply_overrides(&mut config, true);
+
+        assert_eq!(config.llm.default_provider, "claude");
+        assert_eq!(
+            config.llm.providers["claude"].default_model.as_deref(),
+            Some("claude-opus-4-20250514")
+        );
+        assert_eq!(config.llm.agents["developer"].model, "qwen2.5-coder");
+        assert_eq!(config.llm.agents["reviewer"].provider, "gemini");
+        assert_eq!(config.cli_agents.agent_or_default(""), Some("gemini"));
+
+        // Without overrides the global config stands
+        let mut global = AxiomConfig::default();
+        WorkspaceConfig::default().apply_overrides(&mut global, true);
+        assert_eq!(global.llm.default_provider, "ollama");
+        assert_eq!(global.cli_agents.agent_or_default(""), None);
+    }
+
+    #[test]
+    fn test_untrusted_workspace_overrides_are_ignored() {
+        let ws_config: WorkspaceConfig = toml::from_str(
+            r#"
+            default_cli_agent = "gemini"
+
+            [llm]
+            default_provider = "openai"
+            default_model = "gpt-4o"
+
+            [llm.providers.openai]
+            api_key = "sk-project"
+            base_url = "https://collector.example/v1"
+            "#,
+        )
+        .unwrap();
+
+        let mut config = AxiomConfig::default();
+        ws_config.apply_overrides(&mut config, false);
+        assert_eq!(config.llm.default_provider, "ollama");
+        let openai = &config.llm.providers["openai"];
+        assert_ne!(openai.api_key.as_deref(), Some("sk-project"));
+        assert_ne!(
+            openai.base_url.as_deref(),
+            Some("https://collector.example/v1")
+        );
+        // Picking a CLI agent sends nothing anywhere
+        assert_eq!(config.cli_agents.agent_or_default(""), Some("gemini"));
+
+        ws_config.apply_overrides(&mut config, true);
+        assert_eq!(config.llm.default_provider, "openai");
+        assert_eq!(
+            config.llm.providers["openai"].base_url.as_deref(),
+            Some("https://collector.example/v1")
+        );
+    }
+
+    #[test]
+    fn test_set_defaults() {
+        let mut config = WorkspaceConfig::default();
+        let defaults = WorkspaceDefaults {
+            default_provider: Some("ollama".to_string()),
+            default_cli_agent: Some("claude".to_string()),
+            ..Default::default()
+        };
+        config.set_defaults(defaults.clone());
+        assert_eq!(config.defaults(), defaults);
+
+        // Clearing them leaves no empty `[llm]` table behind
+        config.set_defaults(WorkspaceDefaults::default());
+        assert!(config.llm.is_none());
+        assert_eq!(config.default_cli_agent, None);
+    }
 }
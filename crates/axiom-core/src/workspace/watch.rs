@@ -0,0 +1,286 @@
+//! Watch mode: run commands or prompt agents when files change
+//!
+//! Rules live under `[[watch]]` in a workspace's `.axiom/config.toml`:
+//!
+//! ```toml
+//! [[watch]]
+//! pattern = "src/**/*.rs"
+//! action = "run"
+//! command = "cargo test"
+//!
+//! [[watch]]
+//! pattern = "*.py"
+//! action = "agent"
+//! prompt = "Lint {files} and fix any warnings"
+//! debounce_ms = 2000
+//! ```
+//!
+//! Patterns use the same globs as the write policy. A rule fires once its
+//! files have been quiet for `debounce_ms`. Each rule runs at most once at a
+//! time: changes made while it runs (including its own edits) are collected
+//! into a single follow-up run. At most [`MAX_RUNNING`] rules run at once.
+
+use super::write_policy::matches_rule;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+/// Rules that may run at the same time
+pub const MAX_RUNNING: usize = 2;
+
+/// What a watch rule does when it fires
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum WatchAction {
+    /// Run a shell command in the workspace root
+    Run { command: String },
+    /// Send a prompt to the conductor
+    Agent { prompt: String },
+}
+
+impl WatchAction {
+    /// Command or prompt with `{files}` filled in
+    ///
+    /// Commands get shell-quoted paths. Prompts without the placeholder get
+    /// the list appended.
+    pub fn expand(&self, files: &[String]) -> String {
+        match self {
+            WatchAction::Run { command } => {
+                let quoted: Vec<String> = files.iter().map(|f| shell_quote(f)).collect();
+                command.replace("{files}", &quoted.join(" "))
+            }
+            WatchAction::Agent { prompt } if prompt.contains("{files}") => {
+                prompt.replace("{files}", &files.join(", "))
+            }
+            WatchAction::Agent { prompt } => {
+                format!("{}\n\nChanged files:\n- {}", prompt, files.join("\n- "))
+            }
+        }
+    }
+
+    /// One-line description for `/watch list`
+    pub fn describe(&self) -> String {
+        match self {
+            WatchAction::Run { command } => format!("run `{}`", command),
+            WatchAction::Agent { prompt } => format!("agent \"{}\"", prompt),
+        }
+    }
+}
+
+/// A `[[watch]]` rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchRule {
+    /// Glob relative to the workspace root
+    pub pattern: String,
+
+    #[serde(flatten)]
+    pub action: WatchAction,
+
+    /// Quiet period before the rule fires
+    #[serde(default = "default_debounce")]
+    pub debounce_ms: u64,
+}
+
+fn default_debounce() -> u64 {
+    500
+}
+
+impl WatchRule {
+    pub fn new(pattern: impl Into<String>, action: WatchAction) -> Self {
+        Self {
+            pattern: pattern.into(),
+            action,
+            debounce_ms: default_debounce(),
+        }
+    }
+
+    /// Whether a change to `path` (relative, `/`-separated) triggers the rule
+    pub fn matches(&self, path: &str) -> bool {
+        matches_rule(&self.pattern, path)
+    }
+}
+
+/// Plain-text rule list for `/watch list`
+pub fn watch_summary(rules: &[WatchRule]) -> String {
+    if rules.is_empty() {
+        return "No watch rules (/watch add <glob> run <command>)".to_string();
+    }
+    let mut text = format!("{} watch rule(s):", rules.len());
+    for (i, rule) in rules.iter().enumerate() {
+        text.push_str(&format!(
+            "\n  {}. {} → {}",
+            i + 1,
+            rule.pattern,
+            rule.action.describe()
+        ));
+    }
+    text
+}
+
+/// A rule that is due to run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchTrigger {
+    /// Index of the rule
+    pub rule: usize,
+    pub action: WatchAction,
+    /// Changed files, relative to the workspace root
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct RuleState {
+    changed: BTreeSet<String>,
+    /// Last change, for the debounce
+    last_change: Option<Instant>,
+    running: bool,
+}
+
+/// Debounces file changes into rule runs
+#[derive(Debug, Default)]
+pub struct WatchEngine {
+    rules: Vec<WatchRule>,
+    states: Vec<RuleState>,
+}
+
+impl WatchEngine {
+    pub fn new(rules: Vec<WatchRule>) -> Self {
+        let states = rules.iter().map(|_| RuleState::default()).collect();
+        Self { rules, states }
+    }
+
+    pub fn rules(&self) -> &[WatchRule] {
+        &self.rules
+    }
+
+    /// Replace the rules, dropping pending changes
+    ///
+    /// Runs already in progress aren't tracked any more; their
+    /// [`Self::finished`] calls are ignored.
+    pub fn set_rules(&mut self, rules: Vec<WatchRule>) {
+        *self = Self::new(rules);
+    }
+
+    /// Note a change to `path`; returns whether any rule matched
+    pub fn file_changed(&mut self, path: &str, now: Instant) -> bool {
+        let mut matched = false;
+        for (rule, state) in self.rules.iter().zip(&mut self.states) {
+            if rule.matches(path) {
+                state.changed.insert(path.to_string());
+                state.last_change = Some(now);
+                matched = true;
+            }
+        }
+        matched
+    }
+
+    /// Rules whose changes have settled, marked as running
+    pub fn due(&mut self, now: Instant) -> Vec<WatchTrigger> {
+        let mut running = self.states.iter().filter(|s| s.running).count();
+        let mut triggers = Vec::new();
+        for (i, (rule, state)) in self.rules.iter().zip(&mut self.states).enumerate() {
+            if running >= MAX_RUNNING {
+                break;
+            }
+            let settled = state.last_change.is_some_and(|last| {
+                now.duration_since(last) >= Duration::from_millis(rule.debounce_ms)
+            });
+            if state.running || !settled {
+                continue;
+            }
+            state.running = true;
+            state.last_change = None;
+            running += 1;
+            triggers.push(WatchTrigger {
+                rule: i,
+                action: rule.action.clone(),
+                files: std::mem::take(&mut state.changed).into_iter().collect(),
+            });
+        }
+        triggers
+    }
+
+    /// Mark a rule's run as done
+    pub fn finished(&mut self, rule: usize) {
+        if let Some(state) = self.states.get_mut(rule) {
+            state.running = false;
+        }
+    }
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(pattern: &str, command: &str) -> WatchRule {
+        WatchRule::new(
+            pattern,
+            WatchAction::Run {
+                command: command.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_debounce_and_single_flight() {
+        let mut engine = WatchEngine::new(vec![run("src/**/*.rs", "cargo test")]);
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+
+        assert!(!engine.file_changed("README.md", start));
+        assert!(engine.file_changed("src/main.rs", start));
+        assert!(engine.file_changed("src/lib.rs", ms(300)));
+        assert!(engine.due(ms(600)).is_empty());
+
+        let triggers = engine.due(ms(800));
+        assert_eq!(triggers.len(), 1);
+        assert_eq!(triggers[0].files, ["src/lib.rs", "src/main.rs"]);
+
+        // Changes while running wait for the run to finish
+        engine.file_changed("src/main.rs", ms(900));
+        assert!(engine.due(ms(2000)).is_empty());
+        engine.finished(0);
+        assert_eq!(engine.due(ms(2000))[0].files, ["src/main.rs"]);
+        engine.finished(0);
+        assert!(engine.due(ms(5000)).is_empty());
+    }
+
+    #[test]
+    fn test_max_running() {
+        let rules = (0..3).map(|i| run("*.rs", &format!("job {}", i))).collect();
+        let mut engine = WatchEngine::new(rules);
+        let start = Instant::now();
+        engine.file_changed("a.rs", start);
+
+        let later = start + Duration::from_secs(1);
+        assert_eq!(engine.due(later).len(), MAX_RUNNING);
+        engine.finished(0);
+        assert_eq!(engine.due(later)[0].rule, 2);
+    }
+
+    #[test]
+    fn test_expand_and_toml() {
+        let files = vec!["a b.rs".to_string(), "c.rs".to_string()];
+        let cmd = run("*.rs", "rustfmt {files}").action;
+        assert_eq!(cmd.expand(&files), "rustfmt 'a b.rs' 'c.rs'");
+        let prompt = WatchAction::Agent {
+            prompt: "Lint these".to_string(),
+        };
+        assert_eq!(
+            prompt.expand(&files),
+            "Lint these\n\nChanged files:\n- a b.rs\n- c.rs"
+        );
+
+        let rule: WatchRule = toml::from_str(
+            "pattern = \"*.py\"\naction = \"agent\"\nprompt = \"Lint {files}\"\ndebounce_ms = 2000",
+        )
+        .unwrap();
+        assert_eq!(rule.debounce_ms, 2000);
+        assert_eq!(rule.action.expand(&files[1..]), "Lint c.rs");
+        let round: WatchRule = toml::from_str(&toml::to_string(&rule).unwrap()).unwrap();
+        assert_eq!(round, rule);
+    }
+}
@@ -0,0 +1,271 @@
+//! Git worktree support for parallel task execution
+//!
+//! Each task gets its own worktree (and branch) of the parent repository so
+//! several Conductor runs can edit files concurrently without stepping on each
+//! other. When a task is done its branch is merged back into the parent.
+
+use crate::error::{AxiomError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// Branch prefix used for task worktrees
+pub const TASK_BRANCH_PREFIX: &str = "axiom/";
+
+/// Result of merging a task worktree back into its parent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum MergeOutcome {
+    /// Branch was merged into the parent
+    Merged { branch: String },
+
+    /// Task made no changes, nothing to merge
+    UpToDate { branch: String },
+
+    /// Merge was aborted because of conflicts
+    Conflict { branch: String, files: Vec<String> },
+}
+
+impl MergeOutcome {
+    /// Whether the task's changes are now part of the parent
+    pub fn is_success(&self) -> bool {
+        !matches!(self, MergeOutcome::Conflict { .. })
+    }
+}
+
+/// Run a git command in `dir` and return trimmed stdout
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| AxiomError::invalid_operation(format!("Failed to run git: {}", e)))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(AxiomError::invalid_operation(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Check whether the git work tree at `path` has uncommitted changes
+///
+/// Returns false for directories that aren't git repos.
+pub fn is_dirty(path: &Path) -> bool {
+    git(path, &["status", "--porcelain"])
+        .map(|out| !out.is_empty())
+        .unwrap_or(false)
+}
+
+/// Check whether `path` is inside a git work tree
+pub fn is_git_repo(path: &Path) -> bool {
+    git(path, &["rev-parse", "--is-inside-work-tree"])
+        .map(|out| out == "true")
+        .unwrap_or(false)
+}
+
+/// A commit from `git log`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitInfo {
+    pub hash: String,
+    /// Commit time (Unix epoch seconds)
+    pub timestamp: i64,
+    pub author: String,
+    pub subject: String,
+}
+
+/// The `limit` most recent commits on HEAD, newest first
+///
+/// Returns an empty list for directories that aren't git repos.
+pub fn recent_commits(repo: &Path, limit: usize) -> Vec<CommitInfo> {
+    let count = format!("-{}", limit);
+    let Ok(out) = git(repo, &["log", &count, "--pretty=format:%h%x1f%ct%x1f%an%x1f%s"]) else {
+        return Vec::new();
+    };
+    out.lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(4, '\x1f');
+            Some(CommitInfo {
+                hash: parts.next()?.to_string(),
+                timestamp: parts.next()?.parse().ok()?,
+                author: parts.next()?.to_string(),
+                subject: parts.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Number of commits reachable from HEAD (0 outside a git repo)
+pub fn commit_count(repo: &Path) -> usize {
+    git(repo, &["rev-list", "--count", "HEAD"])
+        .ok()
+        .and_then(|out| out.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Turn a task name into a branch/directory friendly slug
+pub fn task_slug(name: &str) -> String {
+    let slug: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+
+    let slug = slug
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() {
+        "task".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Create a worktree at `worktree_path` on a new branch forked from HEAD
+pub fn add_worktree(repo: &Path, worktree_path: &Path, branch: &str) -> Result<()> {
+    let path = worktree_path.to_string_lossy();
+    git(repo, &["worktree", "add", "-b", branch, &path, "HEAD"]).map(|_| ())
+}
+
+/// Remove a worktree and delete its branch
+pub fn remove_worktree(repo: &Path, worktree_path: &Path, branch: &str) -> Result<()> {
+    let path = worktree_path.to_string_lossy();
+    git(repo, &["worktree", "remove", "--force", &path])?;
+    // Branch may be unmerged when a task is discarded
+    git(repo, &["branch", "-D", branch]).map(|_| ())
+}
+
+/// Commit any pending changes in a worktree
+///
+/// Returns `true` if a commit was created. Axiom's own `.axiom` directory
+/// is never committed.
+pub fn commit_all(worktree_path: &Path, message: &str) -> Result<bool> {
+    git(worktree_path, &["add", "-A", "--", ".", ":(exclude).axiom"])?;
+    let staged = git(worktree_path, &["diff", "--cached", "--name-only"])?;
+    if staged.is_empty() {
+        return Ok(false);
+    }
+    git(worktree_path, &["commit", "-q", "-m", message])?;
+    Ok(true)
+}
+
+/// Merge `branch` into the branch checked out in `repo`
+///
+/// On conflicts the merge is aborted so the parent is left untouched.
+pub fn merge_branch(repo: &Path, branch: &str) -> Result<MergeOutcome> {
+    let ahead = git(repo, &["rev-list", "--count", &format!("HEAD..{}", branch)])?;
+    if ahead == "0" {
+        return Ok(MergeOutcome::UpToDate {
+            branch: branch.to_string(),
+        });
+    }
+
+    let message = format!("Merge task branch '{}'", branch);
+    match git(repo, &["merge", "--no-ff", "-m", &message, branch]) {
+        Ok(_) => Ok(MergeOutcome::Merged {
+            branch: branch.to_string(),
+        }),
+        Err(err) => {
+            let conflicts = git(repo, &["diff", "--name-only", "--diff-filter=U"])?;
+            if conflicts.is_empty() {
+                return Err(err);
+            }
+            git(repo, &["merge", "--abort"])?;
+            Ok(MergeOutcome::Conflict {
+                branch: branch.to_string(),
+                files: conflicts.lines().map(String::from).collect(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Create a repo with one commit, or `None` if git is unavailable
+    pub(crate) fn init_repo() -> Option<TempDir> {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path();
+        git(path, &["init", "-q"]).ok()?;
+        git(path, &["config", "user.name", "Axiom Test"]).ok()?;
+        git(path, &["config", "user.email", "test@axiom.local"]).ok()?;
+        fs::write(path.join("README.md"), "hello\n").unwrap();
+        git(path, &["add", "."]).ok()?;
+        git(path, &["commit", "-q", "-m", "init"]).ok()?;
+        Some(dir)
+    }
+
+    #[test]
+    fn test_task_slug() {
+        assert_eq!(task_slug("Fix Login Bug"), "fix-login-bug");
+        assert_eq!(task_slug("  add: API/v2!  "), "add-api-v2");
+        assert_eq!(task_slug("???"), "task");
+    }
+
+    #[test]
+    fn test_worktree_merge_roundtrip() {
+        let Some(repo) = init_repo() else { return };
+        let trees = TempDir::new().unwrap();
+        let wt = trees.path().join("feature");
+        let branch = "axiom/feature";
+
+        assert!(is_git_repo(repo.path()));
+        add_worktree(repo.path(), &wt, branch).unwrap();
+        assert!(wt.join("README.md").exists());
+
+        fs::write(wt.join("new.txt"), "from task\n").unwrap();
+        assert!(commit_all(&wt, "task work").unwrap());
+        assert!(!commit_all(&wt, "nothing").unwrap());
+
+        let outcome = merge_branch(repo.path(), branch).unwrap();
+        assert_eq!(
+            outcome,
+            MergeOutcome::Merged {
+                branch: branch.into()
+            }
+        );
+        assert!(repo.path().join("new.txt").exists());
+
+        remove_worktree(repo.path(), &wt, branch).unwrap();
+        assert!(!wt.exists());
+    }
+
+    #[test]
+    fn test_merge_conflict_is_aborted() {
+        let Some(repo) = init_repo() else { return };
+        let trees = TempDir::new().unwrap();
+        let wt = trees.path().join("conflict");
+        let branch = "axiom/conflict";
+
+        add_worktree(repo.path(), &wt, branch).unwrap();
+        fs::write(wt.join("README.md"), "task version\n").unwrap();
+        commit_all(&wt, "task edit").unwrap();
+
+        fs::write(repo.path().join("README.md"), "parent version\n").unwrap();
+        commit_all(repo.path(), "parent edit").unwrap();
+
+        let outcome = merge_branch(repo.path(), branch).unwrap();
+        assert!(!outcome.is_success());
+        assert_eq!(
+            outcome,
+            MergeOutcome::Conflict {
+                branch: branch.into(),
+                files: vec!["README.md".into()]
+            }
+        );
+        let content = fs::read_to_string(repo.path().join("README.md")).unwrap();
+        assert_eq!(content, "parent version\n");
+    }
+}
@@ -0,0 +1,389 @@
+//! Approval rules for agent file writes
+//!
+//! Some files are too sensitive for an agent to change unattended: CI
+//! workflows run with repository secrets, shell profiles run on every login,
+//! `.env` files hold credentials. Writes, patches, renames and deletes that
+//! touch such a path, or anything outside the workspace, are held as a
+//! [`PendingWrite`] until the user approves them.
+//!
+//! Workspaces can extend or relax the built-in rules under `[write_policy]`
+//! in `.axiom/config.toml`; patterns are gitignore-like globs relative to
+//! the workspace root, and patterns without a `/` match at any depth.
+
+use crate::config::default_true;
+use crate::error::{AxiomError, Result};
+use crate::files::normalize;
+use crate::orchestration::{apply_operation, plan_rename, AgentOperation};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Built-in protected patterns with the reason shown to the user
+const PROTECTED_PATHS: &[(&str, &str)] = &[
+    (".github/workflows/**", "CI workflow"),
+    (".github/actions/**", "CI workflow"),
+    (".gitlab-ci.yml", "CI config"),
+    (".circleci/**", "CI config"),
+    ("Jenkinsfile", "CI config"),
+    (".env", "environment file"),
+    (".env.*", "environment file"),
+    (".bashrc", "shell profile"),
+    (".bash_profile", "shell profile"),
+    (".zshrc", "shell profile"),
+    (".zprofile", "shell profile"),
+    (".profile", "shell profile"),
+    ("config.fish", "shell profile"),
+    (".ssh/**", "SSH config"),
+    (".git/**", "git internals"),
+    (".npmrc", "registry credentials"),
+    (".pypirc", "registry credentials"),
+];
+
+/// Write approval settings of a workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WritePolicy {
+    /// Hold sensitive writes for approval
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Extra patterns that need approval
+    #[serde(default)]
+    pub protected: Vec<String>,
+
+    /// Patterns exempt from the built-in rules (e.g. ".env.example")
+    #[serde(default)]
+    pub allowed: Vec<String>,
+}
+
+impl Default for WritePolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            protected: Vec::new(),
+            allowed: Vec::new(),
+        }
+    }
+}
+
+impl WritePolicy {
+    /// Reason a write to `path` needs approval, if it does
+    ///
+    /// Relative paths are taken relative to `root`.
+    pub fn check(&self, root: &Path, path: &Path) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        let full = normalize(&root.join(path));
+        let Ok(relative) = full.strip_prefix(normalize(root)) else {
+            return Some("outside the workspace".to_string());
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+
+        if self.allowed.iter().any(|p| matches_rule(p, &relative)) {
+            return None;
+        }
+        if let Some((_, reason)) = PROTECTED_PATHS
+            .iter()
+            .find(|(pattern, _)| matches_rule(pattern, &relative))
+        {
+            return Some(reason.to_string());
+        }
+        self.protected
+            .iter()
+            .any(|p| matches_rule(p, &relative))
+            .then(|| "protected by workspace policy".to_string())
+    }
+
+    /// First path touched by `op` that needs approval, with the reason
+    pub fn check_operation(&self, root: &Path, op: &AgentOperation) -> Option<(PathBuf, String)> {
+        let paths: Vec<PathBuf> = match op {
+            AgentOperation::Write { path, .. }
+            | AgentOperation::Patch { path, .. }
+            | AgentOperation::Delete { path } => vec![path.clone()],
+            AgentOperation::Rename { from, to } => vec![from.clone(), to.clone()],
+            // Every file the rename would rewrite
+            AgentOperation::RenameSymbol { symbol, new_name } => {
+                plan_rename(root, symbol, new_name)
+                    .map(|changeset| changeset.files.into_iter().map(|f| f.path).collect())
+                    .unwrap_or_default()
+            }
+            AgentOperation::Execute { .. } => Vec::new(),
+        };
+        paths
+            .into_iter()
+            .find_map(|path| Some((path.clone(), self.check(root, &path)?)))
+    }
+}
+
+/// An agent operation waiting for the user's approval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingWrite {
+    /// Short ID used by `/approve` and the API
+    pub id: String,
+    /// Agent that asked for the write
+    pub agent: String,
+    /// Sensitive path that triggered the hold
+    pub path: PathBuf,
+    /// Why the path is sensitive
+    pub reason: String,
+    pub operation: AgentOperation,
+    /// When the write was held (Unix epoch seconds)
+    pub created_at: i64,
+}
+
+impl PendingWrite {
+    pub fn new(
+        agent: impl Into<String>,
+        path: PathBuf,
+        reason: impl Into<String>,
+        operation: AgentOperation,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            agent: agent.into(),
+            path,
+            reason: reason.into(),
+            operation,
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64,
+        }
+    }
+}
+
+/// A workspace's writes waiting for approval, oldest first
+///
+/// Clones share the queue, so the workspace manager and the workspace's
+/// service hold and resolve the same writes.
+#[derive(Debug, Clone, Default)]
+pub struct ApprovalQueue(Arc<RwLock<Vec<PendingWrite>>>);
+
+impl ApprovalQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hold `pending` until it's approved or rejected
+    pub fn push(&self, pending: PendingWrite) {
+        self.0.write().push(pending);
+    }
+
+    /// The held writes, oldest first
+    pub fn list(&self) -> Vec<PendingWrite> {
+        self.0.read().clone()
+    }
+
+    /// Take the write `approval_id`, or the oldest, off the queue and apply
+    /// it under `root` if `approve`
+    ///
+    /// A write that fails to apply is dropped from the queue and its error
+    /// returned.
+    pub fn resolve(
+        &self,
+        root: &Path,
+        approval_id: Option<&str>,
+        approve: bool,
+    ) -> Result<PendingWrite> {
+        let pending = {
+            let mut queue = self.0.write();
+            let index = match approval_id {
+                Some(approval_id) => queue.iter().position(|p| p.id == approval_id),
+                None => (!queue.is_empty()).then_some(0),
+            };
+            let index = index.ok_or_else(|| {
+                AxiomError::Config(match approval_id {
+                    Some(approval_id) => format!("No pending write with ID {}", approval_id),
+                    None => "No writes are waiting for approval".to_string(),
+                })
+            })?;
+            queue.remove(index)
+        };
+        if approve {
+            apply_operation(root, &pending.operation).map_err(|e| {
+                AxiomError::Config(format!("Failed to apply {}: {}", pending.path.display(), e))
+            })?;
+        }
+        Ok(pending)
+    }
+
+    /// [`Self::resolve`] each of `ids`, or every held write when it's empty
+    ///
+    /// A write that fails to apply doesn't stop the rest. Returns the writes
+    /// resolved and the errors of the others.
+    pub fn resolve_each(
+        &self,
+        root: &Path,
+        ids: &[String],
+        approve: bool,
+    ) -> Result<(Vec<PendingWrite>, Vec<String>)> {
+        let ids: Vec<String> = if ids.is_empty() {
+            self.0
+                .read()
+                .iter()
+                .map(|pending| pending.id.clone())
+                .collect()
+        } else {
+            ids.to_vec()
+        };
+        if ids.is_empty() {
+            return Err(AxiomError::invalid_operation(
+                "No operations are waiting for approval",
+            ));
+        }
+        let mut resolved = Vec::new();
+        let mut errors = Vec::new();
+        for id in &ids {
+            match self.resolve(root, Some(id), approve) {
+                Ok(pending) => resolved.push(pending),
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+        Ok((resolved, errors))
+    }
+}
+
+/// Plain-text list of held writes for `/approvals`
+pub fn pending_summary(writes: &[PendingWrite]) -> String {
+    if writes.is_empty() {
+        return "No writes are waiting for approval".to_string();
+    }
+    let mut text = format!("{} write(s) waiting for approval\n\n", writes.len());
+    for write in writes {
+        text.push_str(&format!(
+            "  [{}] {}: {} ({})\n",
+            write.id,
+            write.agent,
+            write.path.display(),
+            write.reason
+        ));
+    }
+    text.push_str("\n/approve <id> applies a write, /reject <id> discards it");
+    text
+}
+
+/// Match a rule against a `/`-separated relative path
+pub(crate) fn matches_rule(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./").trim_start_matches('/');
+    if pattern.contains('/') {
+        glob_match(pattern, path)
+    } else {
+        path.split('/').any(|segment| glob_match(pattern, segment))
+    }
+}
+
+/// Glob match where `**` spans segments and `*`/`?` stay within one
+pub(crate) fn glob_match(pattern: &str, path: &str) -> bool {
+    fn segments(pattern: &[&str], path: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some((&"**", rest)) => (0..=path.len()).any(|skip| segments(rest, &path[skip..])),
+            Some((first, rest)) => path
+                .split_first()
+                .is_some_and(|(segment, tail)| wildcard(first, segment) && segments(rest, tail)),
+        }
+    }
+
+    fn wildcard(pattern: &str, text: &str) -> bool {
+        let (p, t): (Vec<char>, Vec<char>) = (pattern.chars().collect(), text.chars().collect());
+        let (mut pi, mut ti) = (0, 0);
+        let mut star: Option<(usize, usize)> = None;
+        while ti < t.len() {
+            if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+                pi += 1;
+                ti += 1;
+            } else if pi < p.len() && p[pi] == '*' {
+                star = Some((pi, ti));
+                pi += 1;
+            } else if let Some((sp, st)) = star {
+                pi = sp + 1;
+                ti = st + 1;
+                star = Some((sp, st + 1));
+            } else {
+                return false;
+            }
+        }
+        p[pi..].iter().all(|&c| c == '*')
+    }
+
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    segments(&pattern, &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_rules() {
+        let root = Path::new("/work/app");
+        let policy = WritePolicy::default();
+
+        assert_eq!(
+            policy
+                .check(root, Path::new(".github/workflows/ci.yml"))
+                .as_deref(),
+            Some("CI workflow")
+        );
+        assert_eq!(
+            policy
+                .check(root, Path::new("/work/app/services/api/.env"))
+                .as_deref(),
+            Some("environment file")
+        );
+        assert_eq!(
+            policy
+                .check(root, Path::new("../../home/me/.bashrc"))
+                .as_deref(),
+            Some("outside the workspace")
+        );
+        assert_eq!(policy.check(root, Path::new("src/main.rs")), None);
+        assert_eq!(policy.check(root, Path::new("docs/environment.md")), None);
+    }
+
+    #[test]
+    fn test_workspace_overrides() {
+        let root = Path::new("/work/app");
+        let policy = WritePolicy {
+            protected: vec!["migrations/**".to_string()],
+            allowed: vec![".env.example".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(policy.check(root, Path::new(".env.example")), None);
+        assert!(policy.check(root, Path::new(".env.local")).is_some());
+        assert_eq!(
+            policy
+                .check(root, Path::new("migrations/001_init.sql"))
+                .as_deref(),
+            Some("protected by workspace policy")
+        );
+
+        let rename = AgentOperation::Rename {
+            from: PathBuf::from("ci.yml"),
+            to: PathBuf::from(".github/workflows/ci.yml"),
+        };
+        let (path, _) = policy.check_operation(root, &rename).unwrap();
+        assert_eq!(path, PathBuf::from(".github/workflows/ci.yml"));
+
+        let disabled = WritePolicy {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(disabled.check(root, Path::new(".env")), None);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match(
+            ".github/workflows/**",
+            ".github/workflows/a/b.yml"
+        ));
+        assert!(glob_match("**/*.pem", "certs/dev/key.pem"));
+        assert!(glob_match("*.pem", "key.pem"));
+        assert!(!glob_match("*.pem", "certs/key.pem"));
+        assert!(glob_match("Dockerfile.?", "Dockerfile.1"));
+    }
+}
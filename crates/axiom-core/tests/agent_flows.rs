@@ -0,0 +1,207 @@
+//! Agent flows driven end to end through the public API, with a scripted
+//! provider in place of an LLM
+//!
+//! Each test doubles as an example: the Conductor answering a prompt by
+//! starting agents, and the orchestrator handing a task to the Developer
+//! whose changes are reviewed, applied and verified.
+
+use axiom_core::orchestration::{verify_and_repair, ChatMessage, VerifyCheck, VerifyConfig};
+use axiom_core::testing::{FakeProvider, TestWorkspace};
+use axiom_core::{AgentType, LlmError, NextAgent, Notification};
+use serde_json::json;
+
+/// A Developer reply carrying `operations`
+fn developer_reply(operations: serde_json::Value) -> String {
+    json!({
+        "reasoning": "Change the code",
+        "operations": operations,
+        "message": "Done",
+    })
+    .to_string()
+}
+
+/// Checks that `src/lib.rs` greets with "Hello"
+fn greeting_check() -> VerifyConfig {
+    VerifyConfig {
+        checks: vec![VerifyCheck {
+            name: "test".to_string(),
+            command: "grep -q Hello src/lib.rs || { echo 'greet must say Hello' >&2; exit 1; }"
+                .to_string(),
+        }],
+        max_repairs: 2,
+        timeout_secs: 30,
+    }
+}
+
+#[test]
+fn conductor_writes_a_file_with_the_coder() {
+    let provider = FakeProvider::new().tool_call(
+        "coder",
+        json!({
+            "description": "Add greet",
+            "path": "src/lib.rs",
+            "content": "pub fn greet() {}\n",
+        }),
+    );
+    let mut workspace = TestWorkspace::new(provider);
+    workspace.prompt("Add a greet function");
+
+    workspace.wait_for(|n| {
+        matches!(n, Notification::AgentSpawned { agent_type, .. } if *agent_type == AgentType::Coder)
+    });
+    workspace.wait_for_kind("FileModified");
+    workspace.assert_file("src/lib.rs", "pub fn greet() {}\n");
+    assert!(workspace.provider().saw("Add a greet function"));
+    assert!(workspace.output().contains("→ coder: Add greet"));
+    workspace.assert_no_errors();
+}
+
+#[test]
+fn conductor_runs_shell_commands() {
+    let provider =
+        FakeProvider::new().tool_call("shell", json!({ "command": "echo hello from shell" }));
+    let mut workspace = TestWorkspace::new(provider);
+    workspace.prompt("Say hello");
+
+    workspace.wait_for(|n| {
+        matches!(n, Notification::AgentOutput { chunk, .. } if chunk.starts_with("hello from shell"))
+    });
+}
+
+#[test]
+fn conductor_keeps_the_conversation() {
+    let provider = FakeProvider::new()
+        .reply("The project is empty.")
+        .reply("Still empty.");
+    let mut workspace = TestWorkspace::new(provider);
+    let answered = |text: &'static str| move |n: &Notification| matches!(n, Notification::AgentOutput { chunk, .. } if chunk.contains(text));
+
+    workspace.prompt("What's in the project?");
+    workspace.wait_for(answered("The project is empty."));
+    workspace.prompt("And now?");
+    workspace.wait_for(answered("Still empty."));
+
+    // The second call carries the first exchange
+    let requests = workspace.provider().requests();
+    assert_eq!(requests.len(), 2);
+    let second: Vec<String> = requests[1].iter().map(|m| m.content.as_text()).collect();
+    assert!(second.iter().any(|m| m.contains("What's in the project?")));
+    assert!(second.iter().any(|m| m.contains("The project is empty.")));
+    assert_eq!(workspace.provider().remaining(), 0);
+}
+
+#[test]
+fn conductor_reports_a_rejected_key() {
+    let provider = FakeProvider::new().fail(LlmError::Api {
+        status: 401,
+        message: "invalid x-api-key".to_string(),
+    });
+    let mut workspace = TestWorkspace::new(provider);
+    workspace.prompt("Hello");
+
+    match workspace.wait_for_kind("ProviderAuthFailed") {
+        Notification::ProviderAuthFailed { provider_id, .. } => assert_eq!(provider_id, "fake"),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn orchestrator_hands_a_task_to_the_developer() {
+    let provider = FakeProvider::new()
+        .tool_call(
+            "route",
+            json!({
+                "next_agent": "developer",
+                "reasoning": "A code change",
+                "task": "Add a greet function to src/lib.rs",
+            }),
+        )
+        .reply(developer_reply(json!([{
+            "type": "write",
+            "path": "src/lib.rs",
+            "content": "pub fn greet() -> &'static str {\n    \"Hello\"\n}\n",
+        }])));
+    let workspace = TestWorkspace::new(provider);
+    let service = workspace.orchestration();
+
+    let decision = service
+        .orchestrate(&[ChatMessage::user("Add a greet function")])
+        .unwrap();
+    assert_eq!(decision.next_agent, NextAgent::Developer);
+    let task = decision.task.unwrap();
+
+    let response = service.run_developer(&task).unwrap();
+    workspace.apply(&response);
+    assert!(workspace.read("src/lib.rs").contains("\"Hello\""));
+
+    let verification = verify_and_repair(workspace.path(), &greeting_check(), &task, |_| {
+        panic!("nothing to repair")
+    });
+    assert!(verification.passed);
+    assert_eq!(verification.repairs, 0);
+    assert_eq!(service.take_usage().len(), 2);
+}
+
+#[test]
+fn failed_check_goes_back_to_the_developer() {
+    let provider = FakeProvider::new()
+        .reply(developer_reply(json!([{
+            "type": "write",
+            "path": "src/lib.rs",
+            "content": "pub fn greet() -> &'static str {\n    \"Hi\"\n}\n",
+        }])))
+        .reply(developer_reply(json!([{
+            "type": "patch",
+            "path": "src/lib.rs",
+            "edits": [{ "find": "\"Hi\"", "replace": "\"Hello\"" }],
+        }])));
+    let workspace = TestWorkspace::new(provider);
+    let service = workspace.orchestration();
+    let task = "Add a greet function to src/lib.rs";
+
+    workspace.apply(&service.run_developer(task).unwrap());
+    let verification = verify_and_repair(workspace.path(), &greeting_check(), task, |repair| {
+        let response = service.run_developer(repair).unwrap();
+        workspace.apply(&response);
+        !response.operations.is_empty()
+    });
+
+    assert!(verification.passed);
+    assert_eq!(verification.repairs, 1);
+    assert_eq!(verification.attempts.len(), 2);
+    // The repair was told what failed
+    assert!(workspace.provider().saw("greet must say Hello"));
+    workspace.assert_file(
+        "src/lib.rs",
+        "pub fn greet() -> &'static str {\n    \"Hello\"\n}\n",
+    );
+}
+
+#[test]
+fn reviewer_sends_changes_back_once() {
+    let provider = FakeProvider::new()
+        .reply(developer_reply(json!([{
+            "type": "write",
+            "path": "src/lib.rs",
+            "content": "pub fn greet() -> &'static str {\n    \"Hi\"\n}\n",
+        }])))
+        .reply("REVISE\n- greet must say Hello")
+        .reply(developer_reply(json!([{
+            "type": "write",
+            "path": "src/lib.rs",
+            "content": "pub fn greet() -> &'static str {\n    \"Hello\"\n}\n",
+        }])));
+    let workspace = TestWorkspace::new(provider);
+    let service = workspace.orchestration().with_review(true);
+
+    workspace.apply(&service.run_developer("Add a greet function").unwrap());
+
+    // The rejected attempt was never applied; the revision was
+    workspace.assert_file(
+        "src/lib.rs",
+        "pub fn greet() -> &'static str {\n    \"Hello\"\n}\n",
+    );
+    // The Developer was told what the Reviewer objected to
+    assert_eq!(workspace.provider().requests().len(), 3);
+    assert!(workspace.provider().saw("rejected your previous attempt"));
+}
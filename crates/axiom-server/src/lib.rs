@@ -38,32 +38,88 @@ fn init_tracing() {
 }
 
 /// Build the Axum router with all routes
-fn build_router(state: AppState) -> Router {
+pub fn build_router(state: AppState) -> Router {
     Router::new()
         // API routes
         .route("/api/health", get(health_check))
         .route("/api/workspaces", get(routes::list_workspaces))
         .route("/api/workspaces", axum::routing::post(routes::create_workspace))
+        .route(
+            "/api/workspaces/dedupe",
+            axum::routing::post(routes::dedupe_workspaces),
+        )
         .route("/api/workspaces/:id", get(routes::get_workspace))
         .route(
             "/api/workspaces/:id",
-            axum::routing::delete(routes::delete_workspace),
+            axum::routing::delete(routes::delete_workspace).patch(routes::update_workspace),
+        )
+        .route("/api/workspaces/:id/activity", get(routes::get_activity))
+        .route("/api/workspaces/:id/audit", get(routes::verify_audit))
+        .route("/api/workspaces/:id/audit/export", get(routes::export_audit))
+        .route("/api/workspaces/:id/recordings", get(routes::list_recordings))
+        .route(
+            "/api/workspaces/:id/recordings/:name",
+            get(routes::download_recording),
+        )
+        .route("/api/workspaces/:id/runs", get(routes::list_runs))
+        .route("/api/workspaces/:id/plan", get(routes::get_plan))
+        .route(
+            "/api/workspaces/:id/defaults",
+            get(routes::get_workspace_defaults).put(routes::set_workspace_defaults),
         )
+        .route(
+            "/api/workspaces/:id/report/agents",
+            get(routes::get_agent_report),
+        )
+        .route("/api/workspaces/:id/stats/time", get(routes::get_time_stats))
         .route(
             "/api/workspaces/:id/activate",
             axum::routing::post(routes::activate_workspace),
         )
+        .route(
+            "/api/workspaces/:id/trust",
+            get(routes::get_trust).put(routes::set_trust),
+        )
+        .route(
+            "/api/workspaces/:id/purge",
+            get(routes::get_purge_plan).post(routes::purge_workspace),
+        )
+        // Task worktree routes
+        .route("/api/workspaces/:id/tasks", get(routes::list_tasks))
+        .route(
+            "/api/workspaces/:id/tasks",
+            axum::routing::post(routes::create_task),
+        )
+        .route(
+            "/api/workspaces/:id/merge",
+            axum::routing::post(routes::merge_task),
+        )
+        .route(
+            "/api/workspaces/:id/discard",
+            axum::routing::post(routes::discard_task),
+        )
         .route("/api/workspaces/:id/files", get(routes::list_files))
         .route("/api/workspaces/:id/file", get(routes::read_file))
+        .route("/api/workspaces/:id/search", get(routes::search_workspace))
         .route(
             "/api/workspaces/:id/file",
-            axum::routing::put(routes::write_file),
+            axum::routing::put(routes::write_file)
+                .post(routes::create_file)
+                .delete(routes::delete_file),
+        )
+        .route(
+            "/api/workspaces/:id/file/rename",
+            axum::routing::post(routes::rename_file),
         )
         .route(
             "/api/workspaces/:id/command",
             axum::routing::post(routes::run_command),
         )
         .route("/api/workspaces/:id/ws", get(routes::websocket_handler))
+        .route(
+            "/api/workspaces/:id/terminal",
+            get(routes::terminal_handler),
+        )
         // Orchestration routes
         .route(
             "/api/workspaces/:id/orchestrate",
@@ -73,6 +129,52 @@ fn build_router(state: AppState) -> Router {
             "/api/workspaces/:id/agents/developer",
             axum::routing::post(routes::run_developer),
         )
+        .route(
+            "/api/workspaces/:id/orchestrate/stream",
+            axum::routing::post(routes::orchestrate_stream),
+        )
+        .route(
+            "/api/workspaces/:id/agents/developer/stream",
+            axum::routing::post(routes::run_developer_stream),
+        )
+        .route(
+            "/api/workspaces/:id/agents/developer/tasks",
+            axum::routing::post(routes::run_developer_tasks),
+        )
+        .route(
+            "/api/workspaces/:id/agents/developer/tasks/stream",
+            axum::routing::post(routes::run_developer_tasks_stream),
+        )
+        .route(
+            "/api/workspaces/:id/agents/cli-delegate",
+            axum::routing::post(routes::run_cli_delegate),
+        )
+        // Agent cancellation
+        .route(
+            "/api/workspaces/:id/agents",
+            axum::routing::delete(routes::cancel_all_agents),
+        )
+        .route(
+            "/api/workspaces/:id/agents/:agent_id",
+            axum::routing::delete(routes::cancel_agent),
+        )
+        .route(
+            "/api/workspaces/:id/agents/:agent_id/transcript",
+            get(routes::get_agent_transcript),
+        )
+        // Write approval routes
+        .route(
+            "/api/workspaces/:id/approvals",
+            get(routes::list_approvals),
+        )
+        .route(
+            "/api/workspaces/:id/approvals/:approval_id",
+            axum::routing::post(routes::resolve_approval),
+        )
+        .route(
+            "/api/workspaces/:id/review",
+            get(routes::list_review_queue),
+        )
         .route(
             "/api/workspaces/:id/llm/settings",
             get(routes::get_llm_settings),
@@ -85,6 +187,9 @@ fn build_router(state: AppState) -> Router {
             "/api/workspaces/:id/llm/mappings/:agent_id",
             axum::routing::put(routes::update_agent_mapping),
         )
+        .route("/api/llm/providers", get(routes::list_llm_providers))
+        // Usage export
+        .route("/api/usage/export", get(routes::export_usage))
         // Slash command route
         .route(
             "/api/workspaces/:id/slash",
@@ -113,12 +218,14 @@ pub async fn run_server(port: u16) -> anyhow::Result<()> {
     // Load configuration
     let cwd = std::env::current_dir()?;
     let config = axiom_core::config::load_config(&cwd).unwrap_or_default();
+    axiom_core::llm::configure_rate_limits(&config.llm.rate_limits);
 
     // Create workspace manager
     let workspace_manager = WorkspaceManager::new(config.clone())?;
 
-    // Create app state
-    let state = AppState::new(config, workspace_manager);
+    // Create app state; settings changes go back to the file they came from
+    let config_path = axiom_core::config::loaded_config_path(&cwd);
+    let state = AppState::new(config, config_path, workspace_manager);
 
     // Build router
     let app = build_router(state);
@@ -191,13 +298,17 @@ async fn index_html() -> impl IntoResponse {
                 <li><code>GET /api/workspaces</code> - List workspaces</li>
                 <li><code>POST /api/workspaces</code> - Create workspace</li>
                 <li><code>GET /api/workspaces/:id</code> - Get workspace</li>
-                <li><code>DELETE /api/workspaces/:id</code> - Delete workspace</li>
+                <li><code>DELETE /api/workspaces/:id</code> - Archive workspace</li>
+                <li><code>POST /api/workspaces/:id/purge</code> - Permanently delete archived workspace data</li>
                 <li><code>POST /api/workspaces/:id/activate</code> - Activate workspace</li>
                 <li><code>GET /api/workspaces/:id/files</code> - List files</li>
                 <li><code>GET /api/workspaces/:id/file?path=...</code> - Read file</li>
                 <li><code>PUT /api/workspaces/:id/file</code> - Write file</li>
+                <li><code>POST /api/workspaces/:id/file</code> - Create file or folder</li>
+                <li><code>DELETE /api/workspaces/:id/file?path=...</code> - Delete file or folder</li>
+                <li><code>POST /api/workspaces/:id/file/rename</code> - Rename or move file</li>
                 <li><code>POST /api/workspaces/:id/command</code> - Run command</li>
-                <li><code>WS /api/workspaces/:id/ws</code> - WebSocket stream</li>
+                <li><code>WS /api/workspaces/:id/ws</code> - WebSocket stream (subprotocol <code>axiom.binary.v1</code> or <code>axiom.json.v1</code>)</li>
             </ul>
         </div>
         <p style="margin-top: 2rem;">
@@ -5,28 +5,58 @@ use axum::{
         ws::{Message, WebSocket, WebSocketUpgrade},
         Path, Query, State,
     },
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
     Json,
 };
+use axiom_core::agents::{TerminalEvent, TerminalSession};
+use axiom_core::llm::{blocked_message, routing_summary, ProviderRegistry, RouteCheck};
+use axiom_core::orchestration::{summarize_outcomes, SubTask, TaskProgress, TaskQueue};
+use axiom_core::wire::{Frame, WireFormat};
 use axiom_core::{
-    Command, SlashCommand, SlashCommandData, SlashCommandParser, SlashCommandResult, UiAction,
-    WorkspaceId,
+    guard_content, ActivityEvent, ActivityKind, AgentId, AgentRole, ApprovalSubcommand, Command, ExecPolicy,
+    Feature, FeaturesSubcommand, Notification, PendingWrite, PlanSubcommand, PolicyViolation, RefactorSubcommand, ReportSubcommand, RoutingSubcommand,
+    SessionSubcommand, SharedService, SlashCommand, SlashCommandData, SlashCommandParser,
+    SlashCommandResult, StatsSubcommand, UiAction, UsageFilter, UsageSubcommand, WatchRule,
+    WatchSubcommand, WorkspaceId,
 };
+use futures_util::stream::SplitSink;
 use futures_util::{SinkExt, StreamExt};
 use serde::Deserialize;
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::sync::Arc;
 use tokio::process::Command as TokioCommand;
+use tokio::sync::broadcast;
 
 use crate::state::AppState;
 
 // ========== Workspace Routes ==========
 
-/// List all workspaces
-pub async fn list_workspaces(State(state): State<AppState>) -> impl IntoResponse {
+#[derive(Deserialize)]
+pub struct ListWorkspacesQuery {
+    /// Fuzzy search on name and path
+    #[serde(default)]
+    q: Option<String>,
+    /// List archived workspaces instead
+    #[serde(default)]
+    archived: bool,
+}
+
+/// List workspaces, most recent first (or best match first with `?q=`)
+pub async fn list_workspaces(
+    State(state): State<AppState>,
+    Query(query): Query<ListWorkspacesQuery>,
+) -> impl IntoResponse {
     let manager = state.workspace_manager.read().await;
-    let workspaces = manager.list_workspaces();
+    let workspaces = match query.q.as_deref() {
+        _ if query.archived => manager.archived_workspaces(),
+        Some(q) => manager.search_workspaces(q),
+        None => manager.list_workspaces(),
+    };
     let active_id = manager.active_workspace_id();
 
     Json(serde_json::json!({
@@ -42,12 +72,30 @@ pub struct CreateWorkspaceRequest {
 }
 
 /// Create a new workspace
+///
+/// A path already registered (however it is spelled) gets 409 with the
+/// existing workspace, so the client can offer to open it instead.
 pub async fn create_workspace(
     State(state): State<AppState>,
     Json(req): Json<CreateWorkspaceRequest>,
 ) -> impl IntoResponse {
     let manager = state.workspace_manager.read().await;
 
+    if let Some(existing) = manager.find_by_path(&PathBuf::from(&req.path)) {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "success": false,
+                "error": format!("Workspace '{}' already exists for this path", existing.name),
+                "existing": {
+                    "id": existing.id,
+                    "name": existing.name,
+                    "path": existing.path
+                }
+            })),
+        );
+    }
+
     match manager.create_workspace(&req.name, PathBuf::from(&req.path)) {
         Ok(workspace) => (
             StatusCode::CREATED,
@@ -60,13 +108,19 @@ pub async fn create_workspace(
                 }
             })),
         ),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "success": false,
-                "error": e.to_string()
-            })),
+        Err(e) => (StatusCode::BAD_REQUEST, failure_body(&e)),
+    }
+}
+
+/// Merge workspaces registered more than once for the same directory
+pub async fn dedupe_workspaces(State(state): State<AppState>) -> impl IntoResponse {
+    let manager = state.workspace_manager.read().await;
+    match manager.merge_duplicate_workspaces() {
+        Ok(merged) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "success": true, "merged": merged })),
         ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, failure_body(&e)),
     }
 }
 
@@ -109,10 +163,23 @@ pub async fn get_workspace(
     }
 }
 
-/// Activate a workspace
-pub async fn activate_workspace(
+fn default_activity_limit() -> usize {
+    50
+}
+
+#[derive(Deserialize)]
+pub struct ActivityQuery {
+    #[serde(default)]
+    offset: usize,
+    #[serde(default = "default_activity_limit")]
+    limit: usize,
+}
+
+/// Get a page of a workspace's activity feed, newest first
+pub async fn get_activity(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    Query(query): Query<ActivityQuery>,
 ) -> impl IntoResponse {
     let workspace_id: WorkspaceId = match id.parse() {
         Ok(id) => id,
@@ -126,20 +193,22 @@ pub async fn activate_workspace(
 
     let manager = state.workspace_manager.read().await;
 
-    match manager.activate_workspace(workspace_id) {
-        Ok(_) => (
+    match manager.activity(workspace_id, query.offset, query.limit.min(500)) {
+        Ok(page) => (
             StatusCode::OK,
-            Json(serde_json::json!({ "success": true })),
-        ),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": e.to_string() })),
+            Json(serde_json::json!({
+                "events": page.events,
+                "total": page.total,
+                "next_offset": page.next_offset,
+                "chain_head": page.chain_head
+            })),
         ),
+        Err(e) => (StatusCode::NOT_FOUND, error_body(&e)),
     }
 }
 
-/// Delete a workspace
-pub async fn delete_workspace(
+/// Check a workspace's signed activity log for tampering and gaps
+pub async fn verify_audit(
     State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> impl IntoResponse {
@@ -148,40 +217,30 @@ pub async fn delete_workspace(
         Err(_) => {
             return (
                 StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({ "success": false, "error": "Invalid workspace ID" })),
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
             )
         }
     };
 
     let manager = state.workspace_manager.read().await;
 
-    match manager.delete_workspace(workspace_id) {
-        Ok(_) => (
+    match manager.verify_audit(workspace_id) {
+        Ok(report) => (
             StatusCode::OK,
-            Json(serde_json::json!({ "success": true })),
-        ),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "success": false, "error": e.to_string() })),
+            Json(serde_json::json!({
+                "intact": report.is_intact(),
+                "report": report
+            })),
         ),
+        Err(e) => (StatusCode::NOT_FOUND, error_body(&e)),
     }
 }
 
-// ========== File Routes ==========
-
-#[derive(Deserialize)]
-pub struct ListFilesQuery {
-    path: Option<String>,
-    #[serde(default)]
-    include_hidden: bool,
-}
-
-/// List files in a workspace
-pub async fn list_files(
+/// Download a workspace's activity log as JSON lines, headed by its chain head
+pub async fn export_audit(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    Query(query): Query<ListFilesQuery>,
-) -> impl IntoResponse {
+) -> axum::response::Response {
     let workspace_id: WorkspaceId = match id.parse() {
         Ok(id) => id,
         Err(_) => {
@@ -189,79 +248,76 @@ pub async fn list_files(
                 StatusCode::BAD_REQUEST,
                 Json(serde_json::json!({ "error": "Invalid workspace ID" })),
             )
+                .into_response()
         }
     };
 
     let manager = state.workspace_manager.read().await;
 
-    let workspace = match manager.get_workspace(workspace_id) {
-        Some(ws) => ws,
-        None => {
+    match manager.audit_jsonl(workspace_id) {
+        Ok(content) => (
+            StatusCode::OK,
+            [
+                (axum::http::header::CONTENT_TYPE, "application/x-ndjson"),
+                (
+                    axum::http::header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"axiom-audit.jsonl\"",
+                ),
+            ],
+            content,
+        )
+            .into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, error_body(&e)).into_response(),
+    }
+}
+
+/// Asciicast recordings of the workspace's CLI agent sessions, newest first
+pub async fn list_recordings(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
             return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({ "error": "Workspace not found" })),
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
             )
         }
     };
 
-    let base_path = workspace.path.clone();
-    let target_path = match &query.path {
-        Some(p) => base_path.join(p),
-        None => base_path,
+    let manager = state.workspace_manager.read().await;
+    let Some(workspace) = manager.get_workspace(workspace_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "Workspace not found" })),
+        );
     };
 
-    let mut entries = Vec::new();
-
-    if let Ok(read_dir) = std::fs::read_dir(&target_path) {
-        for entry in read_dir.flatten() {
-            let file_name = entry.file_name().to_string_lossy().to_string();
-
-            // Skip hidden files if not requested
-            if !query.include_hidden && file_name.starts_with('.') {
-                continue;
-            }
-
-            let metadata = entry.metadata().ok();
-            let is_directory = metadata.as_ref().map_or(false, |m| m.is_dir());
-            let size = metadata.as_ref().map_or(0, |m| m.len());
-
-            entries.push(serde_json::json!({
-                "name": file_name,
-                "path": entry.path().strip_prefix(&workspace.path).unwrap_or(entry.path().as_path()),
-                "is_directory": is_directory,
-                "size": size
-            }));
-        }
+    match axiom_core::recordings::list(&workspace.path) {
+        Ok(recordings) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "recordings": recordings })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, error_body(&e)),
     }
+}
 
-    // Sort: directories first, then alphabetically
-    entries.sort_by(|a, b| {
-        let a_is_dir = a["is_directory"].as_bool().unwrap_or(false);
-        let b_is_dir = b["is_directory"].as_bool().unwrap_or(false);
-        match (a_is_dir, b_is_dir) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => {
-                let a_name = a["name"].as_str().unwrap_or("");
-                let b_name = b["name"].as_str().unwrap_or("");
-                a_name.to_lowercase().cmp(&b_name.to_lowercase())
-            }
-        }
-    });
-
-    (StatusCode::OK, Json(serde_json::json!({ "entries": entries })))
+fn default_runs_limit() -> usize {
+    50
 }
 
 #[derive(Deserialize)]
-pub struct ReadFileQuery {
-    path: String,
+pub struct RunsQuery {
+    #[serde(default = "default_runs_limit")]
+    limit: usize,
 }
 
-/// Read a file from a workspace
-pub async fn read_file(
+/// Logged agent runs of the workspace, newest first
+pub async fn list_runs(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    Query(query): Query<ReadFileQuery>,
+    Query(query): Query<RunsQuery>,
 ) -> impl IntoResponse {
     let workspace_id: WorkspaceId = match id.parse() {
         Ok(id) => id,
@@ -274,278 +330,2567 @@ pub async fn read_file(
     };
 
     let manager = state.workspace_manager.read().await;
+    let Some(workspace) = manager.get_workspace(workspace_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "Workspace not found" })),
+        );
+    };
 
-    let workspace = match manager.get_workspace(workspace_id) {
-        Some(ws) => ws,
-        None => {
+    match axiom_core::run_log::list(&workspace.path, query.limit.min(500)) {
+        Ok(runs) => (StatusCode::OK, Json(serde_json::json!({ "runs": runs }))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, error_body(&e)),
+    }
+}
+
+/// The workspace's latest Conductor plan and its step progress
+pub async fn get_plan(State(state): State<AppState>, Path(id): Path<String>) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
             return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({ "error": "Workspace not found" })),
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
             )
         }
     };
 
-    let file_path = workspace.path.join(&query.path);
+    let manager = state.workspace_manager.read().await;
+    let Some(workspace) = manager.get_workspace(workspace_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "Workspace not found" })),
+        );
+    };
 
-    match tokio::fs::read_to_string(&file_path).await {
+    match axiom_core::plan::load(&workspace.path) {
+        Ok(plan) => (StatusCode::OK, Json(serde_json::json!({ "plan": plan }))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, error_body(&e)),
+    }
+}
+
+/// Download a CLI agent session recording as an asciicast file
+pub async fn download_recording(
+    State(state): State<AppState>,
+    Path((id, name)): Path<(String, String)>,
+) -> axum::response::Response {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+                .into_response()
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+    let Some(workspace) = manager.get_workspace(workspace_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "Workspace not found" })),
+        )
+            .into_response();
+    };
+
+    let content = axiom_core::recordings::recording_path(&workspace.path, &name)
+        .and_then(|path| Ok(std::fs::read(path)?));
+    match content {
         Ok(content) => (
             StatusCode::OK,
-            Json(serde_json::json!({ "content": content })),
-        ),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": format!("Failed to read file: {}", e) })),
-        ),
+            [
+                (
+                    axum::http::header::CONTENT_TYPE,
+                    "application/x-asciicast".to_string(),
+                ),
+                (
+                    axum::http::header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", name),
+                ),
+            ],
+            content,
+        )
+            .into_response(),
+        Err(e @ axiom_core::AxiomError::NotFound(_)) => {
+            (StatusCode::NOT_FOUND, error_body(&e)).into_response()
+        }
+        Err(e @ axiom_core::AxiomError::InvalidOperation(_)) => {
+            (StatusCode::BAD_REQUEST, error_body(&e)).into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, error_body(&e)).into_response(),
     }
 }
 
 #[derive(Deserialize)]
-pub struct WriteFileRequest {
-    path: String,
-    content: String,
+pub struct AgentReportQuery {
+    /// Look-back period such as "7d", "12h" or "2w"
+    #[serde(default)]
+    since: Option<String>,
 }
 
-/// Write a file to a workspace
-pub async fn write_file(
+/// Markdown changelog of what agents changed in a workspace
+pub async fn get_agent_report(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    Json(req): Json<WriteFileRequest>,
-) -> impl IntoResponse {
+    Query(query): Query<AgentReportQuery>,
+) -> axum::response::Response {
     let workspace_id: WorkspaceId = match id.parse() {
         Ok(id) => id,
         Err(_) => {
             return (
                 StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({ "success": false, "error": "Invalid workspace ID" })),
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
             )
+                .into_response()
         }
     };
+    let since = query.since.as_deref().unwrap_or("7d");
+    let Some(period) = axiom_core::workspace::parse_since(since) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("Invalid period: {}", since) })),
+        )
+            .into_response();
+    };
 
     let manager = state.workspace_manager.read().await;
 
-    let workspace = match manager.get_workspace(workspace_id) {
-        Some(ws) => ws,
-        None => {
+    match manager.agent_report(workspace_id, period) {
+        Ok(report) => (
+            StatusCode::OK,
+            [(
+                axum::http::header::CONTENT_TYPE,
+                "text/markdown; charset=utf-8",
+            )],
+            report,
+        )
+            .into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, error_body(&e)).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TimeStatsQuery {
+    /// Only count the last N days
+    #[serde(default)]
+    days: Option<u32>,
+}
+
+/// Get agent vs. user time spent in a workspace, per day and per agent
+pub async fn get_time_stats(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<TimeStatsQuery>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
             return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({ "success": false, "error": "Workspace not found" })),
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
             )
         }
     };
 
-    let file_path = workspace.path.join(&req.path);
-
-    // Ensure parent directory exists
-    if let Some(parent) = file_path.parent() {
-        if let Err(e) = tokio::fs::create_dir_all(parent).await {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "success": false, "error": format!("Failed to create directory: {}", e) })),
-            );
-        }
-    }
+    let manager = state.workspace_manager.read().await;
 
-    match tokio::fs::write(&file_path, &req.content).await {
-        Ok(_) => (
+    match manager.time_report(workspace_id, query.days) {
+        Ok(report) => (
             StatusCode::OK,
-            Json(serde_json::json!({ "success": true })),
-        ),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "success": false, "error": format!("Failed to write file: {}", e) })),
+            Json(serde_json::json!({
+                "user_secs": report.user_secs,
+                "agent_secs": report.agent_secs,
+                "automated_share": report.automated_share(),
+                "by_agent": report.by_agent,
+                "days": report.days
+            })),
         ),
+        Err(e) => (StatusCode::NOT_FOUND, error_body(&e)),
     }
 }
 
-#[derive(Deserialize)]
-pub struct RunCommandRequest {
-    command: String,
-}
-
-/// Run a command in a workspace
-pub async fn run_command(
+/// Activate a workspace
+pub async fn activate_workspace(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    Json(req): Json<RunCommandRequest>,
 ) -> impl IntoResponse {
     let workspace_id: WorkspaceId = match id.parse() {
         Ok(id) => id,
         Err(_) => {
             return (
                 StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({
-                    "stdout": "",
-                    "stderr": "Invalid workspace ID",
-                    "exit_code": 1
-                })),
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
             )
         }
     };
 
     let manager = state.workspace_manager.read().await;
 
-    let workspace = match manager.get_workspace(workspace_id) {
+    match manager.activate_workspace(workspace_id) {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "success": true })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, error_body(&e)),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateWorkspaceRequest {
+    #[serde(default)]
+    name: Option<String>,
+    /// New location for a moved or deleted workspace directory
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    archived: Option<bool>,
+}
+
+/// Update a workspace: rename, relocate or (un)archive it
+pub async fn update_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<UpdateWorkspaceRequest>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "success": false, "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+    if manager.get_workspace(workspace_id).is_none() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "success": false, "error": "Workspace not found" })),
+        );
+    }
+
+    let result = manager
+        .update_workspace(workspace_id, req.name.as_deref())
+        .and_then(|ws| match req.path {
+            Some(path) => manager.relocate_workspace(workspace_id, PathBuf::from(path)),
+            None => Ok(ws),
+        })
+        .and_then(|ws| match req.archived {
+            Some(archived) => manager.set_workspace_archived(workspace_id, archived),
+            None => Ok(ws),
+        });
+
+    match result {
+        Ok(workspace) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "success": true,
+                "workspace": axiom_core::WorkspaceView::from(&workspace)
+            })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, failure_body(&e)),
+    }
+}
+
+/// Delete a workspace
+///
+/// This archives it: its data is kept until `POST .../purge`.
+pub async fn delete_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "success": false, "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    match manager.set_workspace_archived(workspace_id, true) {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "success": true, "archived": true })),
+        ),
+        Err(e) => (StatusCode::NOT_FOUND, failure_body(&e)),
+    }
+}
+
+/// What purging a workspace would delete
+pub async fn get_purge_plan(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    match manager.purge_plan(workspace_id) {
+        Ok(plan) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "summary": axiom_core::purge_summary(&plan, false),
+                "plan": plan,
+            })),
+        ),
+        Err(e) => (StatusCode::NOT_FOUND, error_body(&e)),
+    }
+}
+
+/// Permanently delete an archived workspace's Axiom data
+pub async fn purge_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "success": false, "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    let status = match manager.get_workspace(workspace_id) {
+        None => StatusCode::NOT_FOUND,
+        Some(ws) if !ws.archived => StatusCode::CONFLICT,
+        Some(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    match manager.purge_workspace(workspace_id) {
+        Ok(plan) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "success": true,
+                "summary": axiom_core::purge_summary(&plan, true),
+                "plan": plan,
+            })),
+        ),
+        Err(e) => (status, failure_body(&e)),
+    }
+}
+
+// ========== Task Worktree Routes ==========
+
+/// List task worktrees (sub-workspaces) of a workspace
+pub async fn list_tasks(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "tasks": manager.sub_workspaces(workspace_id) })),
+    )
+}
+
+#[derive(Deserialize)]
+pub struct CreateTaskRequest {
+    name: String,
+}
+
+/// Start a task in a new git worktree of the workspace
+pub async fn create_task(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateTaskRequest>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "success": false, "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    match manager.create_task_workspace(workspace_id, &req.name) {
+        Ok(workspace) => (
+            StatusCode::CREATED,
+            Json(serde_json::json!({
+                "success": true,
+                "workspace": {
+                    "id": workspace.id,
+                    "name": workspace.name,
+                    "path": workspace.path,
+                    "parent_id": workspace.parent_id,
+                    "branch": workspace.branch
+                }
+            })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, failure_body(&e)),
+    }
+}
+
+/// Merge a task worktree back into its parent workspace
+pub async fn merge_task(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "success": false, "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    match manager.merge_task_workspace(workspace_id) {
+        Ok(outcome) => {
+            let status = if outcome.is_success() {
+                StatusCode::OK
+            } else {
+                StatusCode::CONFLICT
+            };
+            (
+                status,
+                Json(serde_json::json!({
+                    "success": outcome.is_success(),
+                    "outcome": outcome
+                })),
+            )
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, failure_body(&e)),
+    }
+}
+
+/// Discard a task worktree without merging
+pub async fn discard_task(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "success": false, "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    match manager.discard_task_workspace(workspace_id) {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "success": true })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, failure_body(&e)),
+    }
+}
+
+// ========== File Routes ==========
+
+#[derive(Deserialize)]
+pub struct ListFilesQuery {
+    path: Option<String>,
+    #[serde(default)]
+    include_hidden: bool,
+}
+
+/// List files in a workspace
+pub async fn list_files(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<ListFilesQuery>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    let workspace = match manager.get_workspace(workspace_id) {
+        Some(ws) => ws,
+        None => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": "Workspace not found" })),
+            )
+        }
+    };
+
+    let base_path = workspace.path.clone();
+    let target_path = match &query.path {
+        Some(p) => base_path.join(p),
+        None => base_path,
+    };
+
+    let mut entries = Vec::new();
+
+    if let Ok(read_dir) = std::fs::read_dir(&target_path) {
+        for entry in read_dir.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            // Skip hidden files if not requested
+            if !query.include_hidden && file_name.starts_with('.') {
+                continue;
+            }
+
+            let metadata = entry.metadata().ok();
+            let is_directory = metadata.as_ref().map_or(false, |m| m.is_dir());
+            let size = metadata.as_ref().map_or(0, |m| m.len());
+
+            entries.push(serde_json::json!({
+                "name": file_name,
+                "path": entry.path().strip_prefix(&workspace.path).unwrap_or(entry.path().as_path()),
+                "is_directory": is_directory,
+                "size": size
+            }));
+        }
+    }
+
+    // Sort: directories first, then alphabetically
+    entries.sort_by(|a, b| {
+        let a_is_dir = a["is_directory"].as_bool().unwrap_or(false);
+        let b_is_dir = b["is_directory"].as_bool().unwrap_or(false);
+        match (a_is_dir, b_is_dir) {
+            (true, false) => std::cmp::Ordering::Less,
+            (false, true) => std::cmp::Ordering::Greater,
+            _ => {
+                let a_name = a["name"].as_str().unwrap_or("");
+                let b_name = b["name"].as_str().unwrap_or("");
+                a_name.to_lowercase().cmp(&b_name.to_lowercase())
+            }
+        }
+    });
+
+    (StatusCode::OK, Json(serde_json::json!({ "entries": entries })))
+}
+
+#[derive(Deserialize)]
+pub struct ReadFileQuery {
+    path: String,
+}
+
+/// Read a file from a workspace
+pub async fn read_file(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<ReadFileQuery>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    let workspace = match manager.get_workspace(workspace_id) {
+        Some(ws) => ws,
+        None => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": "Workspace not found" })),
+            )
+        }
+    };
+
+    let file_path = workspace.path.join(&query.path);
+
+    match tokio::fs::read_to_string(&file_path).await {
+        Ok(content) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "content": content })),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to read file: {}", e) })),
+        ),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    q: String,
+    /// Treat `q` as a regular expression
+    #[serde(default)]
+    regex: bool,
+    /// Comma-separated globs, e.g. `*.rs,!tests/**`
+    #[serde(default)]
+    glob: Option<String>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+/// Search the files of a workspace
+pub async fn search_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<SearchQuery>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let (root, ignores) = {
+        let manager = state.workspace_manager.read().await;
+        let Some(workspace) = manager.get_workspace(workspace_id) else {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": "Workspace not found" })),
+            );
+        };
+        let ignores = manager
+            .get_workspace_config(workspace_id)
+            .map(|config| config.ignore_patterns)
+            .unwrap_or_default();
+        (workspace.path, ignores)
+    };
+
+    let search = axiom_core::SearchQuery {
+        regex: query.regex,
+        globs: query
+            .glob
+            .iter()
+            .flat_map(|globs| globs.split(','))
+            .map(str::trim)
+            .filter(|glob| !glob.is_empty())
+            .map(String::from)
+            .collect(),
+        limit: query.limit.unwrap_or(axiom_core::search::DEFAULT_LIMIT),
+        ..axiom_core::SearchQuery::new(query.q)
+    };
+    let result = tokio::task::spawn_blocking(move || {
+        axiom_core::search::search_collect(&root, &search, &ignores)
+    })
+    .await;
+
+    match result {
+        Ok(Ok((hits, summary))) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "results": hits,
+                "matches": summary.matches,
+                "files": summary.files,
+                "truncated": summary.truncated,
+            })),
+        ),
+        Ok(Err(e)) => (StatusCode::BAD_REQUEST, error_body(&e)),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Search failed: {}", e) })),
+        ),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct WriteFileRequest {
+    path: String,
+    content: String,
+}
+
+/// Write a file to a workspace
+pub async fn write_file(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<WriteFileRequest>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "success": false, "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    let workspace = match manager.get_workspace(workspace_id) {
+        Some(ws) => ws,
+        None => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "success": false, "error": "Workspace not found" })),
+            )
+        }
+    };
+
+    let file_path = workspace.path.join(&req.path);
+
+    // Ensure parent directory exists
+    if let Some(parent) = file_path.parent() {
+        if let Err(e) = tokio::fs::create_dir_all(parent).await {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "success": false, "error": format!("Failed to create directory: {}", e) })),
+            );
+        }
+    }
+
+    match tokio::fs::write(&file_path, &req.content).await {
+        Ok(_) => {
+            let event = ActivityEvent::new(ActivityKind::FileChange, "user", "Edited file")
+                .with_path(&req.path);
+            let _ = manager.record_activity(workspace_id, &event);
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({ "success": true })),
+            )
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "success": false, "error": format!("Failed to write file: {}", e) })),
+        ),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CreateFileRequest {
+    path: String,
+    /// Create a directory instead of an empty file
+    #[serde(default)]
+    directory: bool,
+}
+
+/// Create an empty file or a directory in a workspace
+pub async fn create_file(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateFileRequest>,
+) -> impl IntoResponse {
+    let manager = state.workspace_manager.read().await;
+    let (workspace_id, root) = match workspace_root(&manager, &id) {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    let result = axiom_core::files::create_path(&root, req.path.as_ref(), req.directory);
+    let summary = if req.directory {
+        "Created folder"
+    } else {
+        "Created file"
+    };
+    file_change_response(&manager, workspace_id, &root, result, summary)
+}
+
+/// Delete a file, or a directory and its contents, from a workspace
+pub async fn delete_file(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<ReadFileQuery>,
+) -> impl IntoResponse {
+    let manager = state.workspace_manager.read().await;
+    let (workspace_id, root) = match workspace_root(&manager, &id) {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    let result = axiom_core::files::delete_path(&root, query.path.as_ref());
+    file_change_response(&manager, workspace_id, &root, result, "Deleted")
+}
+
+#[derive(Deserialize)]
+pub struct RenameFileRequest {
+    from: String,
+    to: String,
+}
+
+/// Rename or move a file or directory within a workspace
+pub async fn rename_file(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<RenameFileRequest>,
+) -> impl IntoResponse {
+    let manager = state.workspace_manager.read().await;
+    let (workspace_id, root) = match workspace_root(&manager, &id) {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    let result = axiom_core::files::move_path(&root, req.from.as_ref(), req.to.as_ref());
+    let summary = format!("Moved from {}", req.from);
+    file_change_response(&manager, workspace_id, &root, result, &summary)
+}
+
+/// The ID and root directory of workspace `id`
+fn workspace_root(
+    manager: &axiom_core::WorkspaceManager,
+    id: &str,
+) -> Result<(WorkspaceId, PathBuf), ApiResponse> {
+    let workspace_id: WorkspaceId = id.parse().map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "success": false, "error": "Invalid workspace ID" })),
+        )
+    })?;
+    match manager.get_workspace(workspace_id) {
+        Some(workspace) => Ok((workspace_id, workspace.path)),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "success": false, "error": "Workspace not found" })),
+        )),
+    }
+}
+
+/// Record a file created, moved or deleted by the user, and answer with its
+/// path relative to the workspace
+fn file_change_response(
+    manager: &axiom_core::WorkspaceManager,
+    workspace_id: WorkspaceId,
+    root: &std::path::Path,
+    result: axiom_core::Result<PathBuf>,
+    summary: &str,
+) -> ApiResponse {
+    match result {
+        Ok(path) => {
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            let event =
+                ActivityEvent::new(ActivityKind::FileChange, "user", summary).with_path(&relative);
+            let _ = manager.record_activity(workspace_id, &event);
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({ "success": true, "path": relative })),
+            )
+        }
+        Err(e) => {
+            let status = match e {
+                axiom_core::AxiomError::NotFound(_) => StatusCode::NOT_FOUND,
+                axiom_core::AxiomError::InvalidOperation(_) => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, failure_body(&e))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RunCommandRequest {
+    command: String,
+}
+
+/// Run a command in a workspace
+pub async fn run_command(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<RunCommandRequest>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "stdout": "",
+                    "stderr": "Invalid workspace ID",
+                    "exit_code": 1
+                })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    let workspace = match manager.get_workspace(workspace_id) {
+        Some(ws) => ws,
+        None => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "stdout": "",
+                    "stderr": "Workspace not found",
+                    "exit_code": 1
+                })),
+            )
+        }
+    };
+
+    // The workspace's policy may refuse the command before it runs
+    let policy = manager
+        .get_workspace_config(workspace_id)
+        .map(|config| config.exec_policy)
+        .unwrap_or_default();
+    let command = match policy.command(&workspace.path, &req.command) {
+        Ok(command) => command,
+        Err(violation) => return command_blocked(violation),
+    };
+
+    // Execute command using shell
+    let mut command = TokioCommand::from(command);
+    command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(unix)]
+    command.process_group(0);
+
+    match run_limited(command, &policy).await {
+        Ok(Err(violation)) => {
+            let event = ActivityEvent::new(
+                ActivityKind::AgentRun,
+                "user",
+                format!("Stopped `{}`: {}", req.command, violation),
+            );
+            let _ = manager.record_activity(workspace_id, &event);
+            command_blocked(violation)
+        }
+        Ok(Ok(output)) => {
+            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            let exit_code = output.status.code().unwrap_or(1);
+
+            let event = ActivityEvent::new(
+                ActivityKind::AgentRun,
+                "user",
+                format!("Ran `{}` (exit {})", req.command, exit_code),
+            );
+            let _ = manager.record_activity(workspace_id, &event);
+
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "stdout": stdout,
+                    "stderr": stderr,
+                    "exit_code": exit_code
+                })),
+            )
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "stdout": "",
+                "stderr": format!("Failed to execute command: {}", e),
+                "exit_code": 1
+            })),
+        ),
+    }
+}
+
+/// Response for a command the workspace's execution policy refused or stopped
+fn command_blocked(violation: PolicyViolation) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::FORBIDDEN,
+        Json(serde_json::json!({
+            "stdout": "",
+            "stderr": format!("Command blocked: {}", violation),
+            "exit_code": 1,
+            "violation": violation
+        })),
+    )
+}
+
+/// Run `command` within the policy's time and output limits
+async fn run_limited(
+    mut command: TokioCommand,
+    policy: &ExecPolicy,
+) -> std::io::Result<Result<std::process::Output, PolicyViolation>> {
+    use tokio::io::AsyncReadExt;
+
+    let mut child = command.spawn()?;
+    let pid = child.id();
+    let limit = policy.max_output().unwrap_or(usize::MAX);
+    let cap = (limit as u64).saturating_add(1);
+    let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+    let (out, err) = (child.stdout.take(), child.stderr.take());
+
+    let run = async {
+        let read_out = async {
+            match out {
+                Some(pipe) => pipe.take(cap).read_to_end(&mut stdout).await.map(drop),
+                None => Ok(()),
+            }
+        };
+        let read_err = async {
+            match err {
+                Some(pipe) => pipe.take(cap).read_to_end(&mut stderr).await.map(drop),
+                None => Ok(()),
+            }
+        };
+        let (read_out, read_err) = tokio::join!(read_out, read_err);
+        read_out?;
+        read_err?;
+        if stdout.len().saturating_add(stderr.len()) > limit {
+            return Ok(Err(PolicyViolation::OutputLimit { bytes: limit }));
+        }
+        child.wait().await.map(Ok)
+    };
+    let outcome =
+        match policy.timeout() {
+            Some(timeout) => tokio::time::timeout(timeout, run).await.unwrap_or(Ok(Err(
+                PolicyViolation::Timeout {
+                    secs: policy.timeout_secs,
+                },
+            ))),
+            None => run.await,
+        };
+
+    match outcome? {
+        Ok(status) => Ok(Ok(std::process::Output {
+            status,
+            stdout,
+            stderr,
+        })),
+        Err(violation) => {
+            // Stop everything the command started, not only the shell
+            if let Some(pid) = pid {
+                tokio::task::spawn_blocking(move || axiom_core::agents::terminate(pid, || false));
+            }
+            Ok(Err(violation))
+        }
+    }
+}
+
+// ========== Terminal ==========
+
+#[derive(Deserialize)]
+pub struct TerminalQuery {
+    /// Initial screen size, until the client sends a resize
+    cols: Option<u16>,
+    rows: Option<u16>,
+}
+
+/// Text message from a terminal client; raw input may also come as binary
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum TerminalMessage {
+    Input { data: String },
+    Resize { cols: u16, rows: u16 },
+}
+
+/// Whether a request comes from a page served by this server
+///
+/// Browsers always send `Origin` on WebSocket upgrades, so a missing one
+/// means a non-browser client. Any other site's page is refused, since
+/// cross-site WebSockets aren't held back by CORS.
+fn same_origin(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let host = headers.get(header::HOST).and_then(|h| h.to_str().ok());
+    let origin_host = origin.to_str().ok().and_then(|origin| {
+        origin
+            .strip_prefix("http://")
+            .or_else(|| origin.strip_prefix("https://"))
+    });
+    host.is_some() && origin_host == host
+}
+
+/// Terminal over WebSocket: a shell in a PTY in the workspace directory
+///
+/// Output goes out as binary frames of raw bytes for xterm.js, and the
+/// shell's exit as a final `{"type": "exit", "exit_code": n}` text frame.
+/// Only the served UI may connect from a browser.
+pub async fn terminal_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(size): Query<TerminalQuery>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, "Invalid workspace ID").into_response();
+        }
+    };
+    if !same_origin(&headers) {
+        return (
+            StatusCode::FORBIDDEN,
+            "Terminal connections must come from the Axiom UI",
+        )
+            .into_response();
+    }
+
+    let cwd = {
+        let manager = state.workspace_manager.read().await;
+        let Some(workspace) = manager.get_workspace(workspace_id) else {
+            return (StatusCode::NOT_FOUND, "Workspace not found").into_response();
+        };
+        // A shell runs anything, past the command policy
+        if !manager.is_trusted(workspace_id) {
+            return (
+                StatusCode::FORBIDDEN,
+                "Workspace is not trusted (/trust on to allow)",
+            )
+                .into_response();
+        }
+        workspace.path.clone()
+    };
+
+    let cols = size.cols.unwrap_or(80);
+    let rows = size.rows.unwrap_or(24);
+    ws.on_upgrade(move |socket| handle_terminal(socket, cwd, cols, rows))
+}
+
+async fn handle_terminal(socket: WebSocket, cwd: PathBuf, cols: u16, rows: u16) {
+    let (mut sender, mut receiver) = socket.split();
+
+    let (mut session, output) = match TerminalSession::start(&cwd, cols, rows) {
+        Ok(started) => started,
+        Err(e) => {
+            let error = serde_json::json!({ "type": "error", "message": e.to_string() });
+            let _ = sender.send(Message::Text(error.to_string())).await;
+            return;
+        }
+    };
+    tracing::info!("Terminal opened in {}", cwd.display());
+
+    // Output is read on a blocking thread
+    let (output_tx, mut output_rx) = tokio::sync::mpsc::channel(64);
+    tokio::task::spawn_blocking(move || {
+        for event in output {
+            if output_tx.blocking_send(event).is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        tokio::select! {
+            msg = receiver.next() => match msg {
+                Some(Ok(Message::Binary(data))) => {
+                    if session.write(&data).is_err() {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Text(text))) => match serde_json::from_str(&text) {
+                    Ok(TerminalMessage::Input { data }) => {
+                        if session.write(data.as_bytes()).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(TerminalMessage::Resize { cols, rows }) => {
+                        if let Err(e) = session.resize(cols, rows) {
+                            tracing::warn!("Terminal resize failed: {}", e);
+                        }
+                    }
+                    Err(e) => tracing::debug!("Ignoring terminal message: {}", e),
+                },
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Err(e)) => {
+                    tracing::error!("Terminal WebSocket error: {}", e);
+                    break;
+                }
+                Some(Ok(_)) => {}
+            },
+            event = output_rx.recv() => match event {
+                Some(TerminalEvent::Output(data)) => {
+                    if sender.send(Message::Binary(data)).await.is_err() {
+                        break;
+                    }
+                }
+                Some(TerminalEvent::Exit(exit_code)) => {
+                    let exit = serde_json::json!({ "type": "exit", "exit_code": exit_code });
+                    let _ = sender.send(Message::Text(exit.to_string())).await;
+                    let _ = sender.send(Message::Close(None)).await;
+                    break;
+                }
+                None => break,
+            },
+        }
+    }
+
+    // The shell may still be running if the client left first
+    tracing::info!("Terminal closed in {}", cwd.display());
+    tokio::task::spawn_blocking(move || session.terminate());
+}
+
+// ========== WebSocket Handler ==========
+
+/// WebSocket handler for real-time communication
+///
+/// Commands run shells and touch files, so like the terminal only the
+/// served UI may connect from a browser.
+pub async fn websocket_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, "Invalid workspace ID").into_response();
+        }
+    };
+    if !same_origin(&headers) {
+        return (
+            StatusCode::FORBIDDEN,
+            "WebSocket connections must come from the Axiom UI",
+        )
+            .into_response();
+    }
+
+    ws.protocols(WireFormat::PROTOCOLS)
+        .on_upgrade(move |socket| handle_websocket(socket, state, workspace_id))
+}
+
+async fn handle_websocket(socket: WebSocket, state: AppState, workspace_id: WorkspaceId) {
+    let format = WireFormat::from_protocol(socket.protocol().and_then(|p| p.to_str().ok()));
+    let (mut sender, mut receiver) = socket.split();
+
+    tracing::info!("WebSocket connected for workspace: {}", workspace_id);
+
+    // Get or create service for this workspace
+    let service = {
+        let manager = state.workspace_manager.read().await;
+        match manager.get_or_create_service(workspace_id) {
+            Ok(s) => s,
+            Err(e) => {
+                let _ = sender
+                    .send(Message::Text(
+                        serde_json::json!({
+                            "type": "Error",
+                            "message": e.to_string()
+                        })
+                        .to_string(),
+                    ))
+                    .await;
+                return;
+            }
+        }
+    };
+
+    // Send initial state
+    let cwd = service.lock().cwd().clone();
+    let _ = sender
+        .send(Message::Text(
+            serde_json::json!({
+                "type": "Connected",
+                "workspace_id": workspace_id.to_string(),
+                "cwd": cwd
+            })
+            .to_string(),
+        ))
+        .await;
+
+    let mut notifications = state.subscribe(workspace_id, service.clone());
+
+    loop {
+        tokio::select! {
+            msg = receiver.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    tracing::debug!("Received: {}", text);
+
+                    // Failures come back as Error notifications; the stream
+                    // delivers the command's own output
+                    let reply = match dispatch_command(&state, workspace_id, &service, &text).await {
+                        Ok(reply) => reply,
+                        Err(error) => Some(error),
+                    };
+                    if let Some(reply) = reply {
+                        if !send_notification(&mut sender, format, &reply).await {
+                            break;
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    tracing::info!("WebSocket closed for workspace: {}", workspace_id);
+                    break;
+                }
+                Some(Err(e)) => {
+                    tracing::error!("WebSocket error: {}", e);
+                    break;
+                }
+                Some(Ok(_)) => {}
+            },
+            notification = notifications.recv() => match notification {
+                Ok(notification) => {
+                    if !send_notification(&mut sender, format, &notification).await {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    let warning = Notification::warning(format!(
+                        "Connection fell behind; {} notification(s) were dropped",
+                        missed
+                    ));
+                    if !send_notification(&mut sender, format, &warning).await {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        }
+    }
+}
+
+/// Parse a WebSocket message as a [`Command`] and send it to the service
+///
+/// History queries are answered here, for this socket alone, rather than
+/// broadcast to every client of the workspace. Approvals go through the
+/// workspace manager, which notes them in the activity feed.
+async fn dispatch_command(
+    state: &AppState,
+    workspace_id: WorkspaceId,
+    service: &SharedService,
+    text: &str,
+) -> Result<Option<Notification>, Notification> {
+    let command = serde_json::from_str::<Command>(text)
+        .map_err(|e| Notification::error(format!("Invalid command: {}", e)))?;
+    match &command {
+        Command::QueryHistory { filter } => {
+            let entries = service.lock().history(filter);
+            return Ok(Some(Notification::History { entries }));
+        }
+        // Copying or opening files is up to the client that asked
+        Command::GetLastMessage { agent_id } => {
+            let agent_id = *agent_id;
+            let text = service
+                .lock()
+                .last_message(agent_id)
+                .map_err(|e| Notification::from_error(&e))?;
+            return Ok(Some(Notification::AgentLastMessage { agent_id, text }));
+        }
+        Command::GetTouchedFiles { agent_id } => {
+            let agent_id = *agent_id;
+            let paths = service
+                .lock()
+                .touched_files(agent_id)
+                .map_err(|e| Notification::from_error(&e))?;
+            return Ok(Some(Notification::AgentTouchedFiles { agent_id, paths }));
+        }
+        Command::ApproveOperations { ids } => {
+            return resolve_operations(state, workspace_id, ids, true)
+                .await
+                .map(Some);
+        }
+        Command::RejectOperations { ids } => {
+            return resolve_operations(state, workspace_id, ids, false)
+                .await
+                .map(Some);
+        }
+        _ => {}
+    }
+    let service = Arc::clone(service);
+    // Commands may touch the filesystem or spawn processes
+    tokio::task::spawn_blocking(move || service.lock().send(command))
+        .await
+        .map_err(|e| Notification::error(format!("Command failed: {}", e)))?
+        .map(|()| None)
+        .map_err(|e| Notification::from_error(&e))
+}
+
+/// Apply or discard held operations (all of them when `ids` is empty)
+///
+/// Every client of the workspace gets the updated queue; the reply sums up
+/// what happened, and an operation that fails to apply doesn't stop the rest.
+async fn resolve_operations(
+    state: &AppState,
+    workspace_id: WorkspaceId,
+    ids: &[String],
+    approve: bool,
+) -> Result<Notification, Notification> {
+    let manager = state.workspace_manager.read().await;
+    let resolved = manager.resolve_writes(workspace_id, ids, approve);
+    announce_pending(state, &manager, workspace_id);
+    let count = resolved.map_err(|e| Notification::from_error(&e))?;
+    Ok(Notification::info(format!(
+        "{} {} operation(s)",
+        if approve { "Applied" } else { "Discarded" },
+        count
+    )))
+}
+
+/// Tell a workspace's UIs which operations are waiting for approval
+fn announce_pending(
+    state: &AppState,
+    manager: &axiom_core::WorkspaceManager,
+    workspace_id: WorkspaceId,
+) {
+    state.notify(
+        workspace_id,
+        Notification::PendingOperations {
+            operations: manager.pending_writes(workspace_id),
+        },
+    );
+}
+
+/// Forward a notification to the socket; false once the client is gone
+async fn send_notification(
+    sender: &mut SplitSink<WebSocket, Message>,
+    format: WireFormat,
+    notification: &Notification,
+) -> bool {
+    let message = match format.encode(notification) {
+        Ok(Frame::Text(text)) => Message::Text(text),
+        Ok(Frame::Binary(bytes)) => Message::Binary(bytes),
+        Err(e) => {
+            tracing::error!("Failed to serialize notification: {}", e);
+            return true;
+        }
+    };
+    sender.send(message).await.is_ok()
+}
+
+// ========== Orchestration Routes ==========
+
+#[derive(Deserialize)]
+pub struct OrchestrationRequest {
+    messages: Vec<OrchestrationMessage>,
+    /// Files open in the editor, for the agent's repository context
+    #[serde(default)]
+    open_tabs: Vec<PathBuf>,
+}
+
+#[derive(Deserialize)]
+pub struct OrchestrationMessage {
+    role: String,
+    content: String,
+}
+
+/// Run the orchestrator to decide next action
+pub async fn orchestrate(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<OrchestrationRequest>,
+) -> impl IntoResponse {
+    let manager = state.workspace_manager.read().await;
+    let run = match prepare_orchestration(&state, &manager, &id, &req) {
+        Ok(run) => run,
+        Err(response) => return response,
+    };
+    let decision = run.service.orchestrate(&run.messages);
+    finish_orchestration(&manager, run, decision)
+}
+
+/// Run the orchestrator, streaming its output as server-sent events
+///
+/// Emits `token` events with each chunk of text, then one `done` event with
+/// the same body `orchestrate` returns, or an `error` event.
+pub async fn orchestrate_stream(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<OrchestrationRequest>,
+) -> axum::response::Response {
+    let manager = state.workspace_manager.read().await;
+    let run = match prepare_orchestration(&state, &manager, &id, &req) {
+        Ok(run) => run,
+        Err(response) => return response.into_response(),
+    };
+    drop(manager);
+
+    stream_agent(move |on_token| {
+        let decision = run.service.orchestrate_streaming(&run.messages, on_token);
+        let manager = state.workspace_manager.blocking_read();
+        finish_orchestration(&manager, run, decision)
+    })
+    .into_response()
+}
+
+type ApiResponse = (StatusCode, Json<serde_json::Value>);
+
+/// The body of a failed request: the error's message and its code
+fn error_body(error: &axiom_core::AxiomError) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "error": error.to_string(), "code": error.code() }))
+}
+
+/// [`error_body`] with `"success": false`, for routes whose replies say
+/// whether they succeeded
+fn failure_body(error: &axiom_core::AxiomError) -> Json<serde_json::Value> {
+    let Json(mut body) = error_body(error);
+    body["success"] = false.into();
+    Json(body)
+}
+
+/// An orchestrator call that passed the workspace's guard and routing checks
+struct OrchestrationRun {
+    workspace_id: WorkspaceId,
+    service: axiom_core::OrchestrationService,
+    messages: Vec<axiom_core::orchestration::ChatMessage>,
+    guard_warnings: Vec<String>,
+    routing_warning: Option<String>,
+}
+
+fn prepare_orchestration(
+    state: &AppState,
+    manager: &axiom_core::WorkspaceManager,
+    id: &str,
+    req: &OrchestrationRequest,
+) -> Result<OrchestrationRun, ApiResponse> {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            ))
+        }
+    };
+
+    let workspace = match manager.get_workspace(workspace_id) {
+        Some(ws) => ws,
+        None => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": "Workspace not found" })),
+            ))
+        }
+    };
+
+    // Guard everything the model didn't write itself (pasted output, file contents)
+    let guard = manager
+        .get_workspace_config(workspace_id)
+        .map(|config| config.guard)
+        .unwrap_or_default();
+    let mut guard_warnings = Vec::new();
+
+    // Convert messages to orchestration ChatMessage format
+    let chat_messages: Vec<axiom_core::orchestration::ChatMessage> = req
+        .messages
+        .iter()
+        .map(|m| {
+            if m.role == "assistant" {
+                return axiom_core::orchestration::ChatMessage::assistant(&m.content);
+            }
+            let guarded = guard_content(&m.content, &guard);
+            if let Some(warning) = guarded.summary(&format!("{} message", m.role)) {
+                guard_warnings.push(warning);
+            }
+            match m.role.as_str() {
+                "system" => axiom_core::orchestration::ChatMessage::system(&guarded.text),
+                _ => axiom_core::orchestration::ChatMessage::user(&guarded.text),
+            }
+        })
+        .collect();
+    for warning in &guard_warnings {
+        let event = ActivityEvent::new(ActivityKind::Notification, "guard", warning.clone());
+        let _ = manager.record_activity(workspace_id, &event);
+    }
+    let routing = check_routing(state, manager, workspace_id, AgentRole::Orchestrator);
+    let routing_warning = match routing {
+        Ok(warning) => warning,
+        Err(error) => {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({ "error": error })),
+            ))
+        }
+    };
+
+    // Create orchestration service with shared config settings
+    let llm_settings = axiom_core::LlmSettings::from_axiom_config(&state.config());
+    let service = axiom_core::OrchestrationService::with_settings(workspace.path.clone(), llm_settings)
+        .with_context(agent_context(state, &workspace.path, req.open_tabs.clone()))
+        .with_prompts(axiom_core::PromptLibrary::new(
+            &workspace.path,
+            &state.config().prompts,
+        ));
+    // A CLI agent runs anything, past the command policy
+    let service = if manager.is_trusted(workspace_id) {
+        service.with_delegates(cli_delegates(state))
+    } else {
+        service
+    };
+
+    Ok(OrchestrationRun {
+        workspace_id,
+        service,
+        messages: chat_messages,
+        guard_warnings,
+        routing_warning,
+    })
+}
+
+/// Repository context for agent prompts, with the workspace's
+/// `[orchestration.context]` sources
+fn agent_context(
+    state: &AppState,
+    workspace_path: &std::path::Path,
+    open_tabs: Vec<PathBuf>,
+) -> axiom_core::orchestration::ContextBuilder {
+    let roles = state.config().orchestration.context.clone();
+    axiom_core::orchestration::ContextBuilder::new(workspace_path, roles).with_open_tabs(open_tabs)
+}
+
+/// The enabled CLI agents, for the orchestrator to hand tasks to
+fn cli_delegates(state: &AppState) -> Vec<axiom_core::orchestration::Delegate> {
+    let mut delegates: Vec<_> = state
+        .config()
+        .cli_agents
+        .agents
+        .iter()
+        .filter(|(_, agent)| agent.enabled)
+        .map(|(id, agent)| axiom_core::orchestration::Delegate {
+            id: id.clone(),
+            name: agent.name.clone(),
+        })
+        .collect();
+    delegates.sort_by(|a, b| a.id.cmp(&b.id));
+    delegates
+}
+
+fn finish_orchestration(
+    manager: &axiom_core::WorkspaceManager,
+    run: OrchestrationRun,
+    decision: axiom_core::Result<axiom_core::OrchestratorDecision>,
+) -> ApiResponse {
+    for usage in run.service.take_usage() {
+        let _ = manager.record_usage(Some(run.workspace_id), usage);
+    }
+
+    match decision {
+        Ok(decision) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "next_agent": decision.next_agent.as_str(),
+                "cli_agent": decision.next_agent.cli_agent(),
+                "reasoning": decision.reasoning,
+                "task": decision.task,
+                "guard_warnings": run.guard_warnings,
+                "routing_warning": run.routing_warning
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, error_body(&e)),
+    }
+}
+
+/// Stream an agent call as server-sent events
+///
+/// `run` blocks on the LLM, passing each chunk of text to its callback, and
+/// returns the response the non-streaming route would have sent.
+fn stream_agent<F>(
+    run: F,
+) -> Sse<impl futures_util::Stream<Item = Result<Event, std::convert::Infallible>>>
+where
+    F: FnOnce(&mut dyn FnMut(&str)) -> ApiResponse + Send + 'static,
+{
+    stream_events(move |send| run(&mut |text| send("token", serde_json::json!({ "text": text }))))
+}
+
+/// Stream a blocking call's events as server-sent events
+///
+/// `run` passes named JSON events to its callback; the response it returns
+/// ends the stream as a `done` or `error` event.
+fn stream_events<F>(
+    run: F,
+) -> Sse<impl futures_util::Stream<Item = Result<Event, std::convert::Infallible>>>
+where
+    F: FnOnce(&mut dyn FnMut(&str, serde_json::Value)) -> ApiResponse + Send + 'static,
+{
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Event>();
+    tokio::task::spawn_blocking(move || {
+        let (status, Json(body)) = run(&mut |name, data| {
+            if let Ok(event) = Event::default().event(name).json_data(data) {
+                let _ = tx.send(event);
+            }
+        });
+        let name = if status.is_success() { "done" } else { "error" };
+        if let Ok(event) = Event::default().event(name).json_data(body) {
+            let _ = tx.send(event);
+        }
+    });
+
+    let events = futures_util::stream::unfold(rx, |mut rx| async move {
+        let event = rx.recv().await?;
+        Some((Ok(event), rx))
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Check the provider `role` is mapped to (the default provider if it isn't)
+/// against a workspace's routing policy
+///
+/// Returns a warning to pass on, or the error to refuse the request with.
+fn check_routing(
+    state: &AppState,
+    manager: &axiom_core::WorkspaceManager,
+    workspace_id: WorkspaceId,
+    role: AgentRole,
+) -> Result<Option<String>, String> {
+    let config = state.config();
+    let provider = config
+        .llm
+        .agents
+        .get(role.as_str())
+        .map_or(&config.llm.default_provider, |agent| &agent.provider);
+    let policy = manager.routing_policy(workspace_id);
+    let check = ProviderRegistry::from_config(&config).check_route(provider, &policy);
+    match manager.audit_route(workspace_id, provider, check) {
+        RouteCheck::Allowed => Ok(None),
+        RouteCheck::Warn(warning) => Ok(Some(warning)),
+        RouteCheck::Blocked(reason) => Err(blocked_message(provider, &reason)),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DeveloperRequest {
+    task: String,
+    #[serde(default)]
+    open_tabs: Vec<PathBuf>,
+}
+
+/// Run the developer agent
+pub async fn run_developer(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<DeveloperRequest>,
+) -> impl IntoResponse {
+    let manager = state.workspace_manager.read().await;
+    let run = match prepare_developer(&state, &manager, &id, req.open_tabs.clone()) {
+        Ok(run) => run,
+        Err(response) => return response,
+    };
+    let result = run.service.run_developer(&req.task);
+    finish_developer(&state, &manager, run, &req.task, result)
+}
+
+/// Run the developer agent, streaming its output as server-sent events
+///
+/// Events are as for [`orchestrate_stream`]; operations are applied once the
+/// response is complete.
+pub async fn run_developer_stream(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<DeveloperRequest>,
+) -> axum::response::Response {
+    let manager = state.workspace_manager.read().await;
+    let run = match prepare_developer(&state, &manager, &id, req.open_tabs.clone()) {
+        Ok(run) => run,
+        Err(response) => return response.into_response(),
+    };
+    drop(manager);
+
+    stream_agent(move |on_token| {
+        let result = run.service.run_developer_streaming(&req.task, on_token);
+        let manager = state.workspace_manager.blocking_read();
+        finish_developer(&state, &manager, run, &req.task, result)
+    })
+    .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct CliDelegateRequest {
+    /// ID in `[cli_agents]`
+    agent: String,
+    task: String,
+}
+
+/// Run a CLI agent on a task the orchestrator handed it, returning the
+/// files it changed and a summary for the conversation
+///
+/// The agent runs until it exits or goes quiet (`[orchestration.delegate]`),
+/// so this can take minutes.
+pub async fn run_cli_delegate(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<CliDelegateRequest>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let workspace_path = {
+        let manager = state.workspace_manager.read().await;
+        let Some(workspace) = manager.get_workspace(workspace_id) else {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": "Workspace not found" })),
+            );
+        };
+        // A CLI agent runs anything, past the command policy
+        if !manager.is_trusted(workspace_id) {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({ "error": "Workspace is not trusted (/trust on to allow)" })),
+            );
+        }
+        workspace.path.clone()
+    };
+
+    let config = state.config();
+    let Some(agent) = config
+        .cli_agents
+        .agents
+        .get(&req.agent)
+        .filter(|agent| agent.enabled)
+        .cloned()
+    else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("No enabled CLI agent '{}'", req.agent) })),
+        );
+    };
+    let limits = config.orchestration.delegate.clone();
+    let task = req.task.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        axiom_core::orchestration::run_cli_delegate(&agent, &task, &workspace_path, &limits)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(report)) => {
+            let changed = report.created.len() + report.modified.len() + report.deleted.len();
+            let event = ActivityEvent::new(
+                ActivityKind::AgentRun,
+                req.agent.as_str(),
+                format!("Completed: {} ({} files changed)", req.task, changed),
+            );
+            let manager = state.workspace_manager.read().await;
+            let _ = manager.record_activity(workspace_id, &event);
+
+            let summary = report.summary();
+            let mut body = serde_json::to_value(&report).unwrap_or_default();
+            body["summary"] = summary.into();
+            (StatusCode::OK, Json(body))
+        }
+        Ok(Err(e)) => (StatusCode::INTERNAL_SERVER_ERROR, error_body(&e)),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("CLI agent failed: {}", e) })),
+        ),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DeveloperTasksRequest {
+    tasks: Vec<SubTask>,
+    #[serde(default)]
+    open_tabs: Vec<PathBuf>,
+}
+
+/// Run developer agents on a set of subtasks, several at once where their
+/// dependencies allow
+pub async fn run_developer_tasks(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<DeveloperTasksRequest>,
+) -> impl IntoResponse {
+    let manager = state.workspace_manager.read().await;
+    let (run, queue) = match prepare_developer_tasks(&state, &manager, &id, req) {
+        Ok(prepared) => prepared,
+        Err(response) => return response,
+    };
+    let max_parallel = state.config().llm.max_parallel_tasks;
+    finish_developer_tasks(&state, &manager, run, &queue, max_parallel, |_| {})
+}
+
+/// Run developer agents on a set of subtasks, streaming a `progress` event
+/// each time a task changes state
+///
+/// The stream ends with a `done` event carrying what
+/// [`run_developer_tasks`] would have returned.
+pub async fn run_developer_tasks_stream(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<DeveloperTasksRequest>,
+) -> axum::response::Response {
+    let manager = state.workspace_manager.read().await;
+    let (run, queue) = match prepare_developer_tasks(&state, &manager, &id, req) {
+        Ok(prepared) => prepared,
+        Err(response) => return response.into_response(),
+    };
+    drop(manager);
+
+    stream_events(move |send| {
+        let max_parallel = state.config().llm.max_parallel_tasks;
+        let manager = state.workspace_manager.blocking_read();
+        finish_developer_tasks(&state, &manager, run, &queue, max_parallel, |progress| {
+            if let Ok(data) = serde_json::to_value(&progress) {
+                send("progress", data);
+            }
+        })
+    })
+    .into_response()
+}
+
+fn prepare_developer_tasks(
+    state: &AppState,
+    manager: &axiom_core::WorkspaceManager,
+    id: &str,
+    req: DeveloperTasksRequest,
+) -> Result<(DeveloperRun, TaskQueue), ApiResponse> {
+    let queue = TaskQueue::new(req.tasks).map_err(|e| (StatusCode::BAD_REQUEST, error_body(&e)))?;
+    Ok((prepare_developer(state, manager, id, req.open_tabs)?, queue))
+}
+
+fn finish_developer_tasks(
+    state: &AppState,
+    manager: &axiom_core::WorkspaceManager,
+    run: DeveloperRun,
+    queue: &TaskQueue,
+    max_parallel: usize,
+    on_progress: impl FnMut(TaskProgress),
+) -> ApiResponse {
+    let workspace_id = run.workspace_id;
+    // Each task's changes land before the tasks that depend on it start
+    let applied = std::sync::Mutex::new(std::collections::HashMap::new());
+    let outcomes = run.service.run_developer_tasks(
+        queue,
+        max_parallel,
+        |task, response| {
+            let operations =
+                apply_developer_operations(manager, workspace_id, &run.workspace_path, response);
+            if let Ok(mut applied) = applied.lock() {
+                applied.insert(task.id.clone(), operations);
+            }
+            Ok(())
+        },
+        on_progress,
+    );
+    for usage in run.service.take_usage() {
+        let _ = manager.record_usage(Some(workspace_id), usage);
+    }
+
+    let mut applied = applied.into_inner().unwrap_or_default();
+    // The checks run once every task is done, on their combined changes
+    let task = queue
+        .tasks()
+        .iter()
+        .map(|task| task.task.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let all: Vec<serde_json::Value> = applied.values().flatten().cloned().collect();
+    let verification =
+        verify_developer_run(manager, &run, &task, &all).map(|(mut verification, repairs)| {
+            if !repairs.is_empty() {
+                verification["operations"] = repairs.into();
+            }
+            verification
+        });
+    let repairs_held = verification
+        .as_ref()
+        .and_then(|v| v["operations"].as_array())
+        .is_some_and(|ops| ops.iter().any(is_held));
+    if applied.values().flatten().any(is_held) || repairs_held {
+        announce_pending(state, manager, workspace_id);
+    }
+    let tasks: Vec<serde_json::Value> = outcomes
+        .iter()
+        .map(|outcome| {
+            let mut value = serde_json::to_value(&outcome.state).unwrap_or_default();
+            value["id"] = outcome.id.clone().into();
+            if let Some(response) = &outcome.output {
+                let operations = applied.remove(&outcome.id).unwrap_or_default();
+                let event = ActivityEvent::new(
+                    ActivityKind::AgentRun,
+                    "developer",
+                    format!(
+                        "Completed task {} ({} operations)",
+                        outcome.id,
+                        operations.len()
+                    ),
+                );
+                let _ = manager.record_activity(workspace_id, &event);
+                value["reasoning"] = response.reasoning.clone().into();
+                value["message"] = response.message.clone().into();
+                value["diagnostics"] =
+                    check_developer_edits(manager, workspace_id, &run.workspace_path, &operations)
+                        .into();
+                value["operations"] = operations.into();
+            }
+            value
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "tasks": tasks,
+            "summary": summarize_outcomes(&outcomes),
+            "verification": verification,
+            "routing_warning": run.routing_warning
+        })),
+    )
+}
+
+/// A developer call that passed the workspace's routing check
+struct DeveloperRun {
+    workspace_id: WorkspaceId,
+    workspace_path: PathBuf,
+    service: axiom_core::OrchestrationService,
+    routing_warning: Option<String>,
+}
+
+fn prepare_developer(
+    state: &AppState,
+    manager: &axiom_core::WorkspaceManager,
+    id: &str,
+    open_tabs: Vec<PathBuf>,
+) -> Result<DeveloperRun, ApiResponse> {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            ))
+        }
+    };
+
+    let workspace = match manager.get_workspace(workspace_id) {
         Some(ws) => ws,
         None => {
-            return (
+            return Err((
                 StatusCode::NOT_FOUND,
-                Json(serde_json::json!({
-                    "stdout": "",
-                    "stderr": "Workspace not found",
-                    "exit_code": 1
-                })),
-            )
+                Json(serde_json::json!({ "error": "Workspace not found" })),
+            ))
+        }
+    };
+    let routing = check_routing(state, manager, workspace_id, AgentRole::Developer);
+    let routing_warning = match routing {
+        Ok(warning) => warning,
+        Err(error) => {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({ "error": error })),
+            ))
         }
     };
 
-    // Execute command using shell
-    let output = TokioCommand::new("sh")
-        .arg("-c")
-        .arg(&req.command)
-        .current_dir(&workspace.path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .await;
+    // Create orchestration service with shared config settings
+    let llm_settings = axiom_core::LlmSettings::from_axiom_config(&state.config());
+    let service = axiom_core::OrchestrationService::with_settings(workspace.path.clone(), llm_settings)
+        .with_context(agent_context(state, &workspace.path, open_tabs))
+        .with_prompts(axiom_core::PromptLibrary::new(
+            &workspace.path,
+            &state.config().prompts,
+        ))
+        .with_review(state.config().orchestration.review);
+
+    Ok(DeveloperRun {
+        workspace_id,
+        workspace_path: workspace.path.clone(),
+        service,
+        routing_warning,
+    })
+}
 
-    match output {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            let exit_code = output.status.code().unwrap_or(1);
+fn finish_developer(
+    state: &AppState,
+    manager: &axiom_core::WorkspaceManager,
+    run: DeveloperRun,
+    task: &str,
+    result: axiom_core::Result<axiom_core::DeveloperResponse>,
+) -> ApiResponse {
+    let workspace_id = run.workspace_id;
+    for usage in run.service.take_usage() {
+        let _ = manager.record_usage(Some(workspace_id), usage);
+    }
+
+    match result {
+        Ok(response) => {
+            let mut operations =
+                apply_developer_operations(manager, workspace_id, &run.workspace_path, &response);
+            let verification = verify_developer_run(manager, &run, task, &operations).map(
+                |(verification, repairs)| {
+                    operations.extend(repairs);
+                    verification
+                },
+            );
+            if operations.iter().any(is_held) {
+                announce_pending(state, manager, workspace_id);
+            }
+
+            let mut event = ActivityEvent::new(
+                ActivityKind::AgentRun,
+                "developer",
+                format!("Completed: {} ({} operations)", task, operations.len()),
+            );
+            if !response.reasoning.trim().is_empty() {
+                event = event.with_detail(response.reasoning.trim());
+            }
+            let _ = manager.record_activity(workspace_id, &event);
+            let diagnostics =
+                check_developer_edits(manager, workspace_id, &run.workspace_path, &operations);
 
             (
                 StatusCode::OK,
                 Json(serde_json::json!({
-                    "stdout": stdout,
-                    "stderr": stderr,
-                    "exit_code": exit_code
+                    "reasoning": response.reasoning,
+                    "operations": operations,
+                    "message": response.message,
+                    "diagnostics": diagnostics,
+                    "verification": verification,
+                    "routing_warning": run.routing_warning
                 })),
             )
         }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, error_body(&e)),
+    }
+}
+
+/// Apply a developer response's file operations unless they are held for
+/// approval, describing each one for the client
+///
+/// Operations are held in approval mode (`orchestration.auto_approve` off)
+/// and whenever the write policy protects a path.
+fn apply_developer_operations(
+    manager: &axiom_core::WorkspaceManager,
+    workspace_id: WorkspaceId,
+    workspace_path: &std::path::Path,
+    response: &axiom_core::DeveloperResponse,
+) -> Vec<serde_json::Value> {
+    let trusted = manager.is_trusted(workspace_id);
+    response
+        .operations
+        .iter()
+        .map(|op| {
+            use axiom_core::orchestration::AgentOperation;
+
+            let mut value = match op {
+                AgentOperation::Write { path, .. } => {
+                    serde_json::json!({ "type": "write", "path": path.to_string_lossy() })
+                }
+                AgentOperation::Patch { path, .. } => {
+                    serde_json::json!({ "type": "patch", "path": path.to_string_lossy() })
+                }
+                AgentOperation::Rename { from, to } => serde_json::json!({
+                    "type": "rename",
+                    "from": from.to_string_lossy(),
+                    "to": to.to_string_lossy()
+                }),
+                AgentOperation::Delete { path } => {
+                    serde_json::json!({ "type": "delete", "path": path.to_string_lossy() })
+                }
+                AgentOperation::RenameSymbol { symbol, new_name } => serde_json::json!({
+                    "type": "rename_symbol",
+                    "symbol": symbol,
+                    "new_name": new_name
+                }),
+                AgentOperation::Execute { command } if !trusted => {
+                    return serde_json::json!({
+                        "type": "execute",
+                        "command": command,
+                        "blocked": true,
+                        "note": "Workspace is not trusted"
+                    });
+                }
+                AgentOperation::Execute { command } => {
+                    return serde_json::json!({
+                        "type": "execute",
+                        "command": command,
+                        "note": "Execute operations returned to client"
+                    });
+                }
+            };
+
+            match manager.review_operation(workspace_id, "developer", op) {
+                Ok(Some(pending)) => {
+                    value["success"] = false.into();
+                    value["approval_required"] = true.into();
+                    value["approval_id"] = pending.id.into();
+                    value["reason"] = pending.reason.into();
+                }
+                Ok(None) => {
+                    let result = axiom_core::orchestration::apply_operation(workspace_path, op);
+                    if result.is_ok() {
+                        // Kept for the agent changelog
+                        let kind = value["type"].as_str().unwrap_or("change");
+                        let path = value["path"].as_str().or(value["to"].as_str());
+                        let event = ActivityEvent::new(
+                            ActivityKind::FileChange,
+                            "developer",
+                            format!("Applied {}", kind),
+                        )
+                        .with_path(path.unwrap_or_default());
+                        let _ = manager.record_activity(workspace_id, &event);
+                    }
+                    value["success"] = result.is_ok().into();
+                    value["error"] = result.err().into();
+                }
+                Err(e) => {
+                    value["success"] = false.into();
+                    value["error"] = e.to_string().into();
+                }
+            }
+            value
+        })
+        .collect()
+}
+
+/// Language server diagnostics for the files the developer's applied
+/// operations wrote, so a caller can see whether the edits compile
+///
+/// None when no written file has a language server (or the workspace isn't
+/// trusted, which keeps its servers off).
+fn check_developer_edits(
+    manager: &axiom_core::WorkspaceManager,
+    workspace_id: WorkspaceId,
+    workspace_path: &std::path::Path,
+    operations: &[serde_json::Value],
+) -> Option<serde_json::Value> {
+    let paths: Vec<PathBuf> = operations
+        .iter()
+        .filter(|op| op["success"] == true && op["type"] != "delete")
+        .filter_map(|op| op["path"].as_str().or(op["to"].as_str()))
+        .map(|path| workspace_path.join(path))
+        .collect();
+    if paths.is_empty() {
+        return None;
+    }
+    let service = manager.get_or_create_service(workspace_id).ok()?;
+    let (lsp, timeout) = {
+        let service = service.lock();
+        let timeout = service.config().lsp.check_timeout_secs;
+        (
+            service.lsp().clone(),
+            std::time::Duration::from_secs(timeout),
+        )
+    };
+    let files = lsp.check(&paths, timeout);
+    if files.is_empty() {
+        return None;
+    }
+    let by_path: serde_json::Map<String, serde_json::Value> = files
+        .iter()
+        .map(|(path, diagnostics)| {
+            let path = path.strip_prefix(workspace_path).unwrap_or(path);
+            (
+                path.to_string_lossy().into_owned(),
+                serde_json::to_value(diagnostics).unwrap_or_default(),
+            )
+        })
+        .collect();
+    Some(serde_json::json!({
+        "files": by_path,
+        "summary": axiom_core::lsp::diagnostics_report(workspace_path, &files),
+    }))
+}
+
+/// Run the workspace's `[verify]` checks on a developer run's applied
+/// changes, handing failures back to the developer for a bounded number of
+/// repairs
+///
+/// Returns the verification, with a `summary` for the conversation, and the
+/// repairs' operations as [`apply_developer_operations`] describes them.
+/// None when the workspace has no checks or isn't trusted (the checks run
+/// its build), or when nothing was applied.
+fn verify_developer_run(
+    manager: &axiom_core::WorkspaceManager,
+    run: &DeveloperRun,
+    task: &str,
+    operations: &[serde_json::Value],
+) -> Option<(serde_json::Value, Vec<serde_json::Value>)> {
+    let workspace_id = run.workspace_id;
+    if !manager.is_trusted(workspace_id) || !operations.iter().any(|op| op["success"] == true) {
+        return None;
+    }
+    let config = manager.get_workspace_config(workspace_id).ok()?.verify;
+    if !config.is_enabled() {
+        return None;
+    }
+
+    let mut repairs = Vec::new();
+    let verification = axiom_core::orchestration::verify_and_repair(
+        &run.workspace_path,
+        &config,
+        task,
+        |repair| match run.service.run_developer(repair) {
+            Ok(response) => {
+                let applied = apply_developer_operations(
+                    manager,
+                    workspace_id,
+                    &run.workspace_path,
+                    &response,
+                );
+                let changed = applied.iter().any(|op| op["success"] == true);
+                repairs.extend(applied);
+                changed
+            }
+            Err(_) => false,
+        },
+    );
+    for usage in run.service.take_usage() {
+        let _ = manager.record_usage(Some(workspace_id), usage);
+    }
+
+    let summary = verification.summary();
+    let event = ActivityEvent::new(
+        ActivityKind::AgentRun,
+        "verify",
+        summary.lines().next().unwrap_or_default(),
+    );
+    let _ = manager.record_activity(workspace_id, &event.with_detail(&summary));
+
+    let mut value = serde_json::to_value(&verification).unwrap_or_default();
+    value["summary"] = summary.into();
+    Some((value, repairs))
+}
+
+/// Whether an operation described by [`apply_developer_operations`] was held
+fn is_held(operation: &serde_json::Value) -> bool {
+    operation["approval_required"] == true
+}
+
+/// List agent writes waiting for approval
+pub async fn list_approvals(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "approvals": manager.pending_writes(workspace_id) })),
+    )
+}
+
+/// List the review queue: approvals, merge conflicts and stalled agents,
+/// most urgent first
+pub async fn list_review_queue(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "items": manager.review_queue(workspace_id) })),
+    )
+}
+
+#[derive(Deserialize)]
+pub struct ResolveApprovalRequest {
+    /// Apply the write (`false` discards it)
+    approve: bool,
+}
+
+/// Approve or reject a held agent write
+pub async fn resolve_approval(
+    State(state): State<AppState>,
+    Path((id, approval_id)): Path<(String, String)>,
+    Json(req): Json<ResolveApprovalRequest>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+
+    let manager = state.workspace_manager.read().await;
+
+    let result = manager.resolve_write(workspace_id, Some(&approval_id), req.approve);
+    announce_pending(&state, &manager, workspace_id);
+    match result {
+        Ok(write) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "id": write.id,
+                "path": write.path,
+                "applied": req.approve
+            })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, error_body(&e)),
+    }
+}
+
+/// Cancel a pending or running agent, stopping its process or LLM request
+///
+/// `agent_id` is the number or the `agent-N` form.
+pub async fn cancel_agent(
+    State(state): State<AppState>,
+    Path((id, agent_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+    let raw = agent_id.strip_prefix("agent-").unwrap_or(&agent_id);
+    let Ok(agent_id) = raw.parse().map(AgentId) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Invalid agent ID" })),
+        );
+    };
+
+    let manager = state.workspace_manager.read().await;
+    let Some(service) = manager.get_service(workspace_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "Workspace has no running service" })),
+        );
+    };
+    let result = service.lock().cancel_agent(agent_id);
+    match result {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "id": agent_id, "cancelled": true })),
+        ),
+        Err(e) => (StatusCode::NOT_FOUND, error_body(&e)),
+    }
+}
+
+/// Everything a CLI agent printed, escape sequences stripped, including
+/// what scrolled off its screen
+pub async fn get_agent_transcript(
+    State(state): State<AppState>,
+    Path((id, agent_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
+        }
+    };
+    let raw = agent_id.strip_prefix("agent-").unwrap_or(&agent_id);
+    let Ok(agent_id) = raw.parse().map(AgentId) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Invalid agent ID" })),
+        );
+    };
+
+    let manager = state.workspace_manager.read().await;
+    let Some(service) = manager.get_service(workspace_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "Workspace has no running service" })),
+        );
+    };
+    let transcript = service.lock().pty_transcript(agent_id);
+    match transcript {
+        Some(transcript) => (
+            StatusCode::OK,
             Json(serde_json::json!({
-                "stdout": "",
-                "stderr": format!("Failed to execute command: {}", e),
-                "exit_code": 1
+                "id": agent_id,
+                "lines": transcript.lines(),
+                "dropped": transcript.dropped(),
             })),
         ),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "No CLI agent with that ID" })),
+        ),
     }
 }
 
-// ========== WebSocket Handler ==========
-
-/// WebSocket handler for real-time communication
-pub async fn websocket_handler(
+/// Cancel every pending or running agent in a workspace
+pub async fn cancel_all_agents(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
     let workspace_id: WorkspaceId = match id.parse() {
         Ok(id) => id,
         Err(_) => {
-            return (StatusCode::BAD_REQUEST, "Invalid workspace ID").into_response();
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
         }
     };
 
-    ws.on_upgrade(move |socket| handle_websocket(socket, state, workspace_id))
+    let manager = state.workspace_manager.read().await;
+    let cancelled = manager
+        .get_service(workspace_id)
+        .map(|service| service.lock().cancel_all())
+        .unwrap_or_default();
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "cancelled": cancelled })),
+    )
 }
 
-async fn handle_websocket(socket: WebSocket, state: AppState, workspace_id: WorkspaceId) {
-    let (mut sender, mut receiver) = socket.split();
-
-    tracing::info!("WebSocket connected for workspace: {}", workspace_id);
-
-    // Get or create service for this workspace
-    let service = {
-        let manager = state.workspace_manager.read().await;
-        match manager.get_or_create_service(workspace_id) {
-            Ok(s) => s,
-            Err(e) => {
-                let _ = sender
-                    .send(Message::Text(
-                        serde_json::json!({
-                            "type": "Error",
-                            "message": e.to_string()
-                        })
-                        .to_string(),
-                    ))
-                    .await;
-                return;
-            }
+/// Get whether a workspace is trusted (`null` until the user decides)
+pub async fn get_trust(State(state): State<AppState>, Path(id): Path<String>) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
+            )
         }
     };
 
-    // Send initial state
-    let _ = sender
-        .send(Message::Text(
-            serde_json::json!({
-                "type": "Connected",
-                "workspace_id": workspace_id.to_string(),
-                "cwd": service.cwd()
-            })
-            .to_string(),
-        ))
-        .await;
+    let manager = state.workspace_manager.read().await;
 
-    // Handle incoming messages
-    while let Some(msg) = receiver.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                tracing::debug!("Received: {}", text);
-
-                // Parse command
-                match serde_json::from_str::<Command>(&text) {
-                    Ok(command) => {
-                        // Note: In a real implementation, we'd need mutable access to service
-                        // For now, just echo back
-                        let response = serde_json::json!({
-                            "type": "CommandReceived",
-                            "command": text
-                        });
-                        let _ = sender.send(Message::Text(response.to_string())).await;
-                    }
-                    Err(e) => {
-                        let error = serde_json::json!({
-                            "type": "Error",
-                            "message": format!("Invalid command: {}", e)
-                        });
-                        let _ = sender.send(Message::Text(error.to_string())).await;
-                    }
-                }
-            }
-            Ok(Message::Close(_)) => {
-                tracing::info!("WebSocket closed for workspace: {}", workspace_id);
-                break;
-            }
-            Err(e) => {
-                tracing::error!("WebSocket error: {}", e);
-                break;
-            }
-            _ => {}
-        }
+    match manager.trust(workspace_id) {
+        Ok(trusted) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "trusted": trusted })),
+        ),
+        Err(e) => (StatusCode::NOT_FOUND, error_body(&e)),
     }
 }
 
-// ========== Orchestration Routes ==========
-
-#[derive(Deserialize)]
-pub struct OrchestrationRequest {
-    messages: Vec<OrchestrationMessage>,
-}
-
 #[derive(Deserialize)]
-pub struct OrchestrationMessage {
-    role: String,
-    content: String,
+pub struct SetTrustRequest {
+    trusted: bool,
 }
 
-/// Run the orchestrator to decide next action
-pub async fn orchestrate(
+/// Trust or restrict a workspace
+pub async fn set_trust(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    Json(req): Json<OrchestrationRequest>,
+    Json(req): Json<SetTrustRequest>,
 ) -> impl IntoResponse {
     let workspace_id: WorkspaceId = match id.parse() {
         Ok(id) => id,
@@ -559,57 +2904,20 @@ pub async fn orchestrate(
 
     let manager = state.workspace_manager.read().await;
 
-    let workspace = match manager.get_workspace(workspace_id) {
-        Some(ws) => ws,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({ "error": "Workspace not found" })),
-            )
-        }
-    };
-
-    // Convert messages to orchestration ChatMessage format
-    let chat_messages: Vec<axiom_core::orchestration::ChatMessage> = req
-        .messages
-        .iter()
-        .map(|m| match m.role.as_str() {
-            "system" => axiom_core::orchestration::ChatMessage::system(&m.content),
-            "assistant" => axiom_core::orchestration::ChatMessage::assistant(&m.content),
-            _ => axiom_core::orchestration::ChatMessage::user(&m.content),
-        })
-        .collect();
-
-    // Create orchestration service with shared config settings
-    let llm_settings = axiom_core::LlmSettings::from_axiom_config(&state.config);
-    let service = axiom_core::OrchestrationService::with_settings(workspace.path.clone(), llm_settings);
-
-    match service.orchestrate(&chat_messages) {
-        Ok(decision) => (
+    match manager.set_trusted(workspace_id, req.trusted) {
+        Ok(()) => (
             StatusCode::OK,
-            Json(serde_json::json!({
-                "next_agent": format!("{:?}", decision.next_agent).to_lowercase(),
-                "reasoning": decision.reasoning,
-                "task": decision.task
-            })),
-        ),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": e.to_string() })),
+            Json(serde_json::json!({ "trusted": req.trusted })),
         ),
+        Err(e) => (StatusCode::NOT_FOUND, error_body(&e)),
     }
 }
 
-#[derive(Deserialize)]
-pub struct DeveloperRequest {
-    task: String,
-}
-
-/// Run the developer agent
-pub async fn run_developer(
+/// Get the provider, models and CLI agent a workspace uses over the global
+/// ones
+pub async fn get_workspace_defaults(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    Json(req): Json<DeveloperRequest>,
 ) -> impl IntoResponse {
     let workspace_id: WorkspaceId = match id.parse() {
         Ok(id) => id,
@@ -623,73 +2931,70 @@ pub async fn run_developer(
 
     let manager = state.workspace_manager.read().await;
 
-    let workspace = match manager.get_workspace(workspace_id) {
-        Some(ws) => ws,
-        None => {
+    match manager.workspace_defaults(workspace_id) {
+        Ok(defaults) => (StatusCode::OK, Json(serde_json::json!(defaults))),
+        Err(e) => (StatusCode::NOT_FOUND, error_body(&e)),
+    }
+}
+
+/// Replace a workspace's provider, models and CLI agent; unset ones fall
+/// back to the global config
+///
+/// Saved to the workspace's `.axiom/config.toml`. A running service keeps
+/// its providers until it's next started.
+pub async fn set_workspace_defaults(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(defaults): Json<axiom_core::WorkspaceDefaults>,
+) -> impl IntoResponse {
+    let workspace_id: WorkspaceId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => {
             return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({ "error": "Workspace not found" })),
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid workspace ID" })),
             )
         }
     };
 
-    // Create orchestration service with shared config settings
-    let llm_settings = axiom_core::LlmSettings::from_axiom_config(&state.config);
-    let service = axiom_core::OrchestrationService::with_settings(workspace.path.clone(), llm_settings);
+    let manager = state.workspace_manager.read().await;
 
-    match service.run_developer(&req.task) {
-        Ok(response) => {
-            // Execute write operations immediately
-            let operations: Vec<serde_json::Value> = response
-                .operations
-                .iter()
-                .map(|op| match op {
-                    axiom_core::orchestration::AgentOperation::Write { path, content } => {
-                        // Execute write
-                        if let Some(parent) = path.parent() {
-                            let _ = std::fs::create_dir_all(parent);
-                        }
-                        let result = std::fs::write(path, content);
-                        serde_json::json!({
-                            "type": "write",
-                            "path": path.to_string_lossy(),
-                            "success": result.is_ok(),
-                            "error": result.err().map(|e| e.to_string())
-                        })
-                    }
-                    axiom_core::orchestration::AgentOperation::Delete { path } => {
-                        let result = std::fs::remove_file(path);
-                        serde_json::json!({
-                            "type": "delete",
-                            "path": path.to_string_lossy(),
-                            "success": result.is_ok(),
-                            "error": result.err().map(|e| e.to_string())
-                        })
-                    }
-                    axiom_core::orchestration::AgentOperation::Execute { command } => {
-                        serde_json::json!({
-                            "type": "execute",
-                            "command": command,
-                            "note": "Execute operations returned to client"
-                        })
-                    }
-                })
-                .collect();
+    match manager.set_workspace_defaults(workspace_id, defaults.clone()) {
+        Ok(()) => (StatusCode::OK, Json(serde_json::json!(defaults))),
+        Err(e) => (StatusCode::BAD_REQUEST, error_body(&e)),
+    }
+}
 
-            (
-                StatusCode::OK,
-                Json(serde_json::json!({
-                    "reasoning": response.reasoning,
-                    "operations": operations,
-                    "message": response.message
-                })),
-            )
-        }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        ),
+#[derive(Deserialize)]
+pub struct ProvidersQuery {
+    /// Leave out providers the health check couldn't reach
+    #[serde(default)]
+    reachable: bool,
+}
+
+/// Health-check the configured LLM providers and list their models
+///
+/// Every connected UI gets a `ProviderStatusChanged` for each provider whose
+/// reachability changed since the last check.
+pub async fn list_llm_providers(
+    State(state): State<AppState>,
+    Query(query): Query<ProvidersQuery>,
+) -> impl IntoResponse {
+    let registry = state.llm_registry();
+    let probes = registry.probe_all().await;
+    for probe in probes.iter().filter(|probe| probe.changed) {
+        state.broadcast(probe.notification());
     }
+
+    let providers: Vec<_> = probes
+        .iter()
+        .filter(|probe| !query.reachable || probe.status.is_ready())
+        .map(|probe| probe.info())
+        .collect();
+    Json(serde_json::json!({
+        "providers": providers,
+        "active": registry.active_id()
+    }))
 }
 
 /// Get LLM settings
@@ -698,7 +3003,7 @@ pub async fn get_llm_settings(
     Path(_id): Path<String>,
 ) -> impl IntoResponse {
     // Use shared config settings (same as TUI)
-    let settings = axiom_core::LlmSettings::from_axiom_config(&state.config);
+    let settings = axiom_core::LlmSettings::from_axiom_config(&state.config());
 
     let providers: Vec<serde_json::Value> = settings
         .providers
@@ -721,7 +3026,7 @@ pub async fn get_llm_settings(
         .iter()
         .map(|m| {
             serde_json::json!({
-                "agent_id": format!("{:?}", m.agent_id).to_lowercase(),
+                "agent_id": m.agent_id.as_str(),
                 "provider_id": m.provider_id,
                 "model_id": m.model_id
             })
@@ -743,17 +3048,67 @@ pub struct UpdateProviderRequest {
 }
 
 /// Update a provider configuration
+///
+/// Settings are global: the change is saved to the config file and applies
+/// to every workspace.
 pub async fn update_provider(
-    State(_state): State<AppState>,
-    Path((_workspace_id, _provider_id)): Path<(String, String)>,
-    Json(_req): Json<UpdateProviderRequest>,
+    State(state): State<AppState>,
+    Path((_workspace_id, provider_id)): Path<(String, String)>,
+    Json(req): Json<UpdateProviderRequest>,
 ) -> impl IntoResponse {
-    // In a full implementation, this would persist the settings
-    // For now, return success as a placeholder
-    Json(serde_json::json!({
-        "success": true,
-        "message": "Provider settings update not yet implemented - settings are in-memory only"
-    }))
+    if !state.config().llm.providers.contains_key(&provider_id) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Unknown provider: {}", provider_id) })),
+        );
+    }
+
+    let section = format!("llm.providers.{}", provider_id);
+    let result = state
+        .update_config(&section, |config| {
+            let Some(provider) = config.llm.providers.get_mut(&provider_id) else {
+                return Err(format!("Unknown provider: {}", provider_id));
+            };
+            // Empty strings clear optional settings
+            let optional = |value: String| (!value.trim().is_empty()).then_some(value);
+            if let Some(api_key) = req.api_key {
+                provider.api_key = optional(api_key);
+            }
+            if let Some(base_url) = req.base_url {
+                provider.base_url = optional(base_url);
+            }
+            if let Some(default_model) = req.default_model {
+                provider.default_model = optional(default_model);
+            }
+            if let Some(enabled) = req.enabled {
+                provider.enabled = enabled;
+            }
+            Ok(())
+        })
+        .await;
+
+    match result {
+        Ok(config) => {
+            let provider = &config.llm.providers[&provider_id];
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "success": true,
+                    "provider": {
+                        "id": provider_id,
+                        "base_url": provider.base_url,
+                        "default_model": provider.default_model,
+                        "enabled": provider.enabled,
+                        "has_api_key": provider.api_key.as_ref().is_some_and(|k| !k.is_empty())
+                    }
+                })),
+            )
+        }
+        Err(error) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": error })),
+        ),
+    }
 }
 
 #[derive(Deserialize)]
@@ -762,17 +3117,130 @@ pub struct UpdateMappingRequest {
     model_id: String,
 }
 
-/// Update an agent mapping
-pub async fn update_agent_mapping(
-    State(_state): State<AppState>,
-    Path((_workspace_id, _agent_id)): Path<(String, String)>,
-    Json(_req): Json<UpdateMappingRequest>,
-) -> impl IntoResponse {
-    // In a full implementation, this would persist the settings
-    Json(serde_json::json!({
-        "success": true,
-        "message": "Agent mapping update not yet implemented - settings are in-memory only"
-    }))
+/// Update an agent mapping
+///
+/// Saved to the config file as `[llm.agents.<agent>]`, for every workspace.
+pub async fn update_agent_mapping(
+    State(state): State<AppState>,
+    Path((_workspace_id, agent_id)): Path<(String, String)>,
+    Json(req): Json<UpdateMappingRequest>,
+) -> impl IntoResponse {
+    let role: AgentRole = match agent_id.parse() {
+        Ok(role) => role,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": error })),
+            )
+        }
+    };
+    match save_agent_mapping(&state, role, &req.provider_id, &req.model_id).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "success": true,
+                "mapping": {
+                    "agent_id": role.as_str(),
+                    "provider_id": req.provider_id,
+                    "model_id": req.model_id.trim()
+                }
+            })),
+        ),
+        Err((status, error)) => (status, Json(serde_json::json!({ "error": error }))),
+    }
+}
+
+/// Save `[llm.agents.<role>]`, mapping `role` to `model` on `provider`
+async fn save_agent_mapping(
+    state: &AppState,
+    role: AgentRole,
+    provider: &str,
+    model: &str,
+) -> Result<(), (StatusCode, String)> {
+    if !state.config().llm.providers.contains_key(provider) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Unknown provider: {}", provider),
+        ));
+    }
+    if model.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "model_id is required".to_string()));
+    }
+
+    let section = format!("llm.agents.{}", role.as_str());
+    let mapping = axiom_core::config::AgentModelConfig {
+        provider: provider.to_string(),
+        model: model.trim().to_string(),
+    };
+    state
+        .update_config(&section, |config| {
+            config.llm.agents.insert(role.as_str().to_string(), mapping);
+            Ok(())
+        })
+        .await
+        .map(|_| ())
+        .map_err(|error| (StatusCode::INTERNAL_SERVER_ERROR, error))
+}
+
+// ========== Usage Routes ==========
+
+#[derive(Deserialize)]
+pub struct UsageExportQuery {
+    /// First day included (YYYY-MM-DD)
+    #[serde(default)]
+    from: Option<String>,
+    /// Last day included (YYYY-MM-DD)
+    #[serde(default)]
+    to: Option<String>,
+    /// Workspace ID or name
+    #[serde(default)]
+    workspace: Option<String>,
+    /// "csv" (default) or "json"
+    #[serde(default)]
+    format: Option<String>,
+}
+
+/// Export LLM usage and estimated cost per day, workspace, provider and model
+pub async fn export_usage(
+    State(state): State<AppState>,
+    Query(query): Query<UsageExportQuery>,
+) -> axum::response::Response {
+    let filter = UsageFilter {
+        from: query.from,
+        to: query.to,
+        workspace: query.workspace,
+    };
+    if let Err(e) = filter.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response();
+    }
+
+    let manager = state.workspace_manager.read().await;
+    let rows = manager.usage_rows(&filter);
+
+    match query.format.as_deref() {
+        Some("json") => (StatusCode::OK, Json(serde_json::json!({ "rows": rows }))).into_response(),
+        None | Some("csv") => (
+            StatusCode::OK,
+            [
+                (axum::http::header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (
+                    axum::http::header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"axiom-usage.csv\"",
+                ),
+            ],
+            axiom_core::usage::rows_to_csv(&rows),
+        )
+            .into_response(),
+        Some(other) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("Unknown format: {}", other) })),
+        )
+            .into_response(),
+    }
 }
 
 // ========== Slash Command Routes ==========
@@ -854,14 +3322,38 @@ async fn execute_slash_command_impl(
             commit: None,
         }),
 
-        SlashCommand::Init { path } => {
+        SlashCommand::Init { template: None, path } => {
             execute_init_command(state, workspace_id, path).await
         }
 
+        SlashCommand::Init { template: Some(template), path } => {
+            execute_template_command(state, workspace_id, template, path).await
+        }
+
+        SlashCommand::Templates => {
+            let user_dir = axiom_core::templates::templates_dir();
+            let templates = axiom_core::templates::load_templates(user_dir.as_deref());
+            SlashCommandResult::data(SlashCommandData::Text(
+                axiom_core::templates::templates_list(&templates),
+            ))
+        }
+
+        SlashCommand::Keys => {
+            // The terminal UIs' bindings; the web UI has none of its own
+            let keymap = axiom_core::config::Keymap::new(&state.config().keys).unwrap_or_default();
+            SlashCommandResult::data(SlashCommandData::Text(keymap.list()))
+        }
+
+        SlashCommand::LogLevel { .. } => SlashCommandResult::error(
+            "/loglevel sets the terminal UI's log; the server logs by RUST_LOG",
+        ),
+
         SlashCommand::Workspace(sub) => {
             execute_workspace_subcommand(state, sub).await
         }
 
+        SlashCommand::Activity => SlashCommandResult::action(UiAction::OpenActivityFeed),
+
         SlashCommand::Model(sub) => {
             execute_model_subcommand(state, workspace_id, sub).await
         }
@@ -870,6 +3362,374 @@ async fn execute_slash_command_impl(
             execute_theme_subcommand(sub)
         }
 
+        SlashCommand::Stats(StatsSubcommand::Time { days }) => {
+            let manager = state.workspace_manager.read().await;
+            match manager.time_report(workspace_id, days) {
+                Ok(report) => SlashCommandResult::data(SlashCommandData::Text(report.summary())),
+                Err(e) => SlashCommandResult::error(e.to_string()),
+            }
+        }
+
+        SlashCommand::Usage(UsageSubcommand::Summary) => {
+            let manager = state.workspace_manager.read().await;
+            let rows = manager.usage_rows(&UsageFilter {
+                workspace: Some(workspace_id.to_string()),
+                ..Default::default()
+            });
+            SlashCommandResult::data(SlashCommandData::Text(axiom_core::usage::usage_summary(&rows)))
+        }
+
+        SlashCommand::Usage(UsageSubcommand::Export { from, to, json }) => {
+            let manager = state.workspace_manager.read().await;
+            match manager.export_usage(workspace_id, from, to, json) {
+                Ok(path) => SlashCommandResult::success(format!("Usage report written to {}", path.display())),
+                Err(e) => SlashCommandResult::error(e.to_string()),
+            }
+        }
+
+        SlashCommand::Approvals(sub) => {
+            let manager = state.workspace_manager.read().await;
+            let (approval_id, approve) = match sub {
+                ApprovalSubcommand::List => {
+                    let pending = manager.pending_writes(workspace_id);
+                    return SlashCommandResult::data(SlashCommandData::Text(
+                        axiom_core::workspace::pending_summary(&pending),
+                    ));
+                }
+                ApprovalSubcommand::Approve { id } => (id, true),
+                ApprovalSubcommand::Reject { id } => (id, false),
+            };
+            let result = manager.resolve_write(workspace_id, approval_id.as_deref(), approve);
+            announce_pending(state, &manager, workspace_id);
+            match result {
+                Ok(write) => SlashCommandResult::success(format!(
+                    "{} write to {}",
+                    if approve { "Applied" } else { "Discarded" },
+                    write.path.display()
+                )),
+                Err(e) => SlashCommandResult::error(e.to_string()),
+            }
+        }
+
+        SlashCommand::Trust { trusted } => {
+            let manager = state.workspace_manager.read().await;
+            if let Some(trusted) = trusted {
+                if let Err(e) = manager.set_trusted(workspace_id, trusted) {
+                    return SlashCommandResult::error(e.to_string());
+                }
+            }
+            match manager.trust(workspace_id) {
+                Ok(decision) => {
+                    SlashCommandResult::success(axiom_core::workspace::trust_status(decision))
+                }
+                Err(e) => SlashCommandResult::error(e.to_string()),
+            }
+        }
+
+        SlashCommand::Routing(sub) => {
+            let manager = state.workspace_manager.read().await;
+            match sub {
+                RoutingSubcommand::Show => {
+                    let policy = manager.routing_policy(workspace_id);
+                    let config = state.config();
+                    let registry = ProviderRegistry::from_config(&config);
+                    let mut providers: Vec<&String> = config
+                        .llm
+                        .providers
+                        .iter()
+                        .filter(|(_, p)| p.enabled)
+                        .map(|(id, _)| id)
+                        .collect();
+                    providers.sort();
+                    let routes: Vec<_> = providers
+                        .into_iter()
+                        .map(|p| (p.clone(), registry.check_route(p, &policy)))
+                        .collect();
+                    let overrides = manager.routing_overrides(workspace_id);
+                    SlashCommandResult::data(SlashCommandData::Text(routing_summary(
+                        &policy, &routes, &overrides,
+                    )))
+                }
+                RoutingSubcommand::Allow { provider, reason } => {
+                    match manager.allow_routing(workspace_id, &provider, &reason) {
+                        Ok(()) => SlashCommandResult::success(format!(
+                            "Routing override: {} allowed for this session",
+                            provider
+                        )),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+                RoutingSubcommand::Revoke { provider } => {
+                    match manager.revoke_routing(workspace_id, &provider) {
+                        Ok(true) => SlashCommandResult::success(format!(
+                            "Revoked routing override for {}",
+                            provider
+                        )),
+                        Ok(false) => SlashCommandResult::error(format!(
+                            "No routing override for {}",
+                            provider
+                        )),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+            }
+        }
+
+        SlashCommand::VerifyAudit { export } => {
+            let manager = state.workspace_manager.read().await;
+            let mut text = match manager.verify_audit(workspace_id) {
+                Ok(report) => report.summary(),
+                Err(e) => return SlashCommandResult::error(e.to_string()),
+            };
+            if export {
+                match manager.export_audit(workspace_id) {
+                    Ok(path) => {
+                        text.push_str(&format!("\nAudit log written to {}", path.display()))
+                    }
+                    Err(e) => return SlashCommandResult::error(e.to_string()),
+                }
+            }
+            SlashCommandResult::data(SlashCommandData::Text(text))
+        }
+
+        SlashCommand::Watch(sub) => {
+            let manager = state.workspace_manager.read().await;
+            match sub {
+                WatchSubcommand::List => match manager.watch_rules(workspace_id) {
+                    // Rules are run by the TUI's file watcher
+                    Ok(rules) => SlashCommandResult::data(SlashCommandData::Text(
+                        axiom_core::workspace::watch_summary(&rules),
+                    )),
+                    Err(e) => SlashCommandResult::error(e.to_string()),
+                },
+                WatchSubcommand::Add { pattern, action } => {
+                    let description = action.describe();
+                    match manager.add_watch_rule(workspace_id, WatchRule::new(&pattern, action)) {
+                        Ok(n) => SlashCommandResult::success(format!(
+                            "Watch rule {}: {} → {}",
+                            n, pattern, description
+                        )),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+                WatchSubcommand::Remove { number } => {
+                    match manager.remove_watch_rule(workspace_id, number) {
+                        Ok(rule) => SlashCommandResult::success(format!(
+                            "Removed watch rule {}: {}",
+                            number, rule.pattern
+                        )),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+            }
+        }
+
+        SlashCommand::Report(ReportSubcommand::Agents { since }) => {
+            let period = axiom_core::workspace::parse_since(&since).unwrap_or(7 * 86_400);
+            let manager = state.workspace_manager.read().await;
+            match manager.agent_report(workspace_id, period) {
+                Ok(report) => SlashCommandResult::data(SlashCommandData::Text(report)),
+                Err(e) => SlashCommandResult::error(e.to_string()),
+            }
+        }
+
+        SlashCommand::GenTests { target, attempts } => {
+            execute_gen_tests_command(state, workspace_id, &target, attempts).await
+        }
+        SlashCommand::Fix { problem, attempts } => {
+            execute_fix_command(state, workspace_id, problem, attempts).await
+        }
+        SlashCommand::Session(sub) => execute_session_command(state, workspace_id, sub).await,
+        SlashCommand::Recordings => {
+            // Each one downloads from GET /api/workspaces/:id/recordings/:name
+            let manager = state.workspace_manager.read().await;
+            let Some(workspace) = manager.get_workspace(workspace_id) else {
+                return SlashCommandResult::error("Workspace not found");
+            };
+            match axiom_core::recordings::list(&workspace.path) {
+                Ok(recordings) => SlashCommandResult::data(SlashCommandData::Text(
+                    axiom_core::recordings::summary(&recordings),
+                )),
+                Err(e) => SlashCommandResult::error(e.to_string()),
+            }
+        }
+        SlashCommand::RunDoc { .. } => {
+            SlashCommandResult::error("Run documents are written from the TUI Conductor")
+        }
+        SlashCommand::Plan(PlanSubcommand::Show) => {
+            // The web UI renders the steps from GET /api/workspaces/:id/plan
+            let manager = state.workspace_manager.read().await;
+            let Some(workspace) = manager.get_workspace(workspace_id) else {
+                return SlashCommandResult::error("Workspace not found");
+            };
+            match axiom_core::plan::load(&workspace.path) {
+                Ok(Some(plan)) => {
+                    let mut text = format!("Plan: {}\n", plan.goal);
+                    for (i, step) in plan.steps.iter().enumerate() {
+                        text.push_str(&format!("  {}. [{}] {}\n", i + 1, step.status, step.title));
+                    }
+                    SlashCommandResult::data(SlashCommandData::Text(text))
+                }
+                Ok(None) => SlashCommandResult::success("No plan yet"),
+                Err(e) => SlashCommandResult::error(e.to_string()),
+            }
+        }
+        SlashCommand::Plan(_) => SlashCommandResult::error("Plans are run from the TUI Conductor"),
+
+        SlashCommand::Review => {
+            // The web UI renders the queue from GET /api/workspaces/:id/review
+            let manager = state.workspace_manager.read().await;
+            let items = manager.review_queue(workspace_id);
+            SlashCommandResult::data(SlashCommandData::Text(
+                axiom_core::workspace::review_summary(&items),
+            ))
+        }
+        SlashCommand::Inspect(_) => SlashCommandResult::error(
+            "/inspect captures the terminal UI's LLM requests; the server has none to show",
+        ),
+
+        SlashCommand::Find { query: None, .. } => {
+            SlashCommandResult::error("Usage: /find [--regex] [--glob <glob>]... <query>")
+        }
+
+        SlashCommand::Find {
+            query: Some(query),
+            regex,
+            globs,
+        } => {
+            let (root, ignores) = {
+                let manager = state.workspace_manager.read().await;
+                let Some(workspace) = manager.get_workspace(workspace_id) else {
+                    return SlashCommandResult::error("Workspace not found");
+                };
+                let ignores = manager
+                    .get_workspace_config(workspace_id)
+                    .map(|config| config.ignore_patterns)
+                    .unwrap_or_default();
+                (workspace.path, ignores)
+            };
+            let search = axiom_core::SearchQuery {
+                regex,
+                globs,
+                ..axiom_core::SearchQuery::new(query)
+            };
+            let result = tokio::task::spawn_blocking(move || {
+                axiom_core::search::search_collect(&root, &search, &ignores)
+            })
+            .await;
+            match result {
+                Ok(Ok((hits, summary))) => SlashCommandResult::data(SlashCommandData::Text(
+                    axiom_core::search::search_summary(&hits, &summary),
+                )),
+                Ok(Err(e)) => SlashCommandResult::error(e.to_string()),
+                Err(e) => SlashCommandResult::error(format!("Search failed: {}", e)),
+            }
+        }
+
+        SlashCommand::Replace {
+            query,
+            replacement,
+            regex,
+            globs,
+            apply,
+        } => {
+            let (root, ignores) = {
+                let manager = state.workspace_manager.read().await;
+                let Some(workspace) = manager.get_workspace(workspace_id) else {
+                    return SlashCommandResult::error("Workspace not found");
+                };
+                let ignores = manager
+                    .get_workspace_config(workspace_id)
+                    .map(|config| config.ignore_patterns)
+                    .unwrap_or_default();
+                (workspace.path, ignores)
+            };
+            let search = axiom_core::SearchQuery {
+                regex,
+                globs,
+                ..axiom_core::SearchQuery::new(query)
+            };
+            let result = tokio::task::spawn_blocking(move || {
+                let files =
+                    axiom_core::replace::preview_replace(&root, &search, &replacement, &ignores)?;
+                if !apply {
+                    let mut text = axiom_core::replace_summary(&files);
+                    if !files.is_empty() {
+                        text.push_str("\nRun again with --yes to apply");
+                    }
+                    return Ok(text);
+                }
+                axiom_core::replace::apply_replace(&root, &search, &replacement, &files)
+                    .map(|outcome| outcome.summary())
+            })
+            .await;
+            match result {
+                Ok(Ok(text)) => SlashCommandResult::data(SlashCommandData::Text(text)),
+                Ok(Err(e)) => SlashCommandResult::error(e.to_string()),
+                Err(e) => SlashCommandResult::error(format!("Replace failed: {}", e)),
+            }
+        }
+
+        SlashCommand::Features(sub) => {
+            let manager = state.workspace_manager.read().await;
+            match sub {
+                FeaturesSubcommand::List => match manager.features(workspace_id) {
+                    Ok(flags) => SlashCommandResult::data(SlashCommandData::Text(
+                        axiom_core::workspace::features_summary(&flags),
+                    )),
+                    Err(e) => SlashCommandResult::error(e.to_string()),
+                },
+                FeaturesSubcommand::Set { name, enabled } => {
+                    let Some(feature) = Feature::parse(&name) else {
+                        return SlashCommandResult::error(format!("Unknown feature: {}", name));
+                    };
+                    match manager.set_feature(workspace_id, feature, enabled) {
+                        Ok(()) => SlashCommandResult::success(
+                            axiom_core::workspace::feature_switched(feature, enabled),
+                        ),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+            }
+        }
+
+        SlashCommand::GenDocs { path, check } => {
+            execute_gen_docs_command(state, workspace_id, &path, check).await
+        }
+
+        SlashCommand::Refactor(sub) => {
+            let manager = state.workspace_manager.read().await;
+            let preview = |preview| SlashCommandResult::data(SlashCommandData::Text(preview));
+            let result = match sub {
+                RefactorSubcommand::Show => Ok(match manager.refactor_preview(workspace_id) {
+                    Some(text) => preview(text),
+                    None => SlashCommandResult::success(
+                        "No rename pending. Start one with /refactor rename <symbol> <new_name>",
+                    ),
+                }),
+                RefactorSubcommand::Rename { symbol, new_name } => manager
+                    .plan_rename(workspace_id, &symbol, &new_name)
+                    .map(preview),
+                RefactorSubcommand::Toggle { path } => manager
+                    .toggle_refactor_file(workspace_id, &path)
+                    .map(preview),
+                RefactorSubcommand::Apply => manager
+                    .apply_refactor(workspace_id)
+                    .map(SlashCommandResult::success),
+                RefactorSubcommand::Undo => manager
+                    .undo_refactor(workspace_id)
+                    .map(SlashCommandResult::success),
+                RefactorSubcommand::Cancel => Ok(SlashCommandResult::success(
+                    if manager.discard_refactor(workspace_id) {
+                        "Rename discarded"
+                    } else {
+                        "No rename pending"
+                    },
+                )),
+            };
+            result.unwrap_or_else(|e| SlashCommandResult::error(e.to_string()))
+        }
+
         SlashCommand::Custom { name, args: _ } => {
             SlashCommandResult::error(format!(
                 "Unknown command: /{}. Type /help for available commands.",
@@ -905,76 +3765,280 @@ async fn execute_init_command(
         return SlashCommandResult::error("AXIOM.md already exists in this directory");
     }
 
-    // Create the AXIOM.md template
-    let template = format!(
-        r#"# AXIOM.md
-
-This file provides guidance to Axiom agents when working with code in this repository.
+    let template = axiom_core::templates::axiom_md(&workspace.name, &workspace.path);
 
-## Project Overview
-
-**Name**: {}
-**Path**: {}
+    // Write the file
+    match tokio::fs::write(&axiom_md_path, &template).await {
+        Ok(_) => SlashCommandResult::success(format!(
+            "Created AXIOM.md in {}",
+            target_dir.display()
+        )),
+        Err(e) => SlashCommandResult::error(format!("Failed to create AXIOM.md: {}", e)),
+    }
+}
 
-[Describe your project here - what it does, its main purpose, and key features]
+/// Execute `/init <template> [path]` - creates a project from a template,
+/// relative to the workspace, and registers it as a workspace
+async fn execute_template_command(
+    state: &AppState,
+    workspace_id: WorkspaceId,
+    template: String,
+    path: Option<PathBuf>,
+) -> SlashCommandResult {
+    let root = {
+        let manager = state.workspace_manager.read().await;
+        let Some(workspace) = manager.get_workspace(workspace_id) else {
+            return SlashCommandResult::error("Workspace not found");
+        };
+        match path {
+            Some(path) => workspace.path.join(path),
+            None => workspace.path.clone(),
+        }
+    };
 
-## Build & Development Commands
+    // Post-create commands can take a while (npm install)
+    let manager = state.workspace_manager.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let manager = manager.blocking_read();
+        let user_dir = axiom_core::templates::templates_dir();
+        axiom_core::templates::init_project(&manager, &template, &root, user_dir.as_deref())
+    })
+    .await;
+
+    match result {
+        Ok(Ok((report, workspace))) => {
+            let summary = format!("{}\nWorkspace: {}", report.summary(), workspace.name);
+            if report.success() {
+                SlashCommandResult::success(summary)
+            } else {
+                SlashCommandResult::error(summary)
+            }
+        }
+        Ok(Err(e)) => SlashCommandResult::error(format!("Failed to create project: {}", e)),
+        Err(e) => SlashCommandResult::error(format!("Failed to create project: {}", e)),
+    }
+}
 
-```bash
-# Build the project
-# [Add your build command here]
+/// Execute the /gen-docs command - drafts doc comments and holds each file's
+/// changes for review
+async fn execute_gen_docs_command(
+    state: &AppState,
+    workspace_id: WorkspaceId,
+    path: &std::path::Path,
+    check: bool,
+) -> SlashCommandResult {
+    let manager = state.workspace_manager.read().await;
 
-# Run the project
-# [Add your run command here]
+    let workspace = match manager.get_workspace(workspace_id) {
+        Some(ws) => ws,
+        None => return SlashCommandResult::error("Workspace not found"),
+    };
+    // `cargo doc` runs build scripts
+    if check && !manager.is_trusted(workspace_id) {
+        return SlashCommandResult::error("Workspace is not trusted (/trust on to allow --check)");
+    }
+    if let Err(error) = check_routing(state, &manager, workspace_id, AgentRole::Developer) {
+        return SlashCommandResult::error(error);
+    }
 
-# Run tests
-# [Add your test command here]
+    let llm_settings = axiom_core::LlmSettings::from_axiom_config(&state.config());
+    let service =
+        axiom_core::OrchestrationService::with_settings(workspace.path.clone(), llm_settings);
+    let result = service.generate_docs(
+        path,
+        check,
+        |changeset| {
+            let pending = PendingWrite::new(
+                "docs",
+                changeset.file.clone(),
+                format!("{} doc comment(s) for review", changeset.documented.len()),
+                changeset.operation.clone(),
+            );
+            let id = pending.id.clone();
+            manager
+                .hold_write(workspace_id, pending)
+                .map(|()| id)
+                .map_err(|e| e.to_string())
+        },
+        |_| {},
+    );
+    for usage in service.take_usage() {
+        let _ = manager.record_usage(Some(workspace_id), usage);
+    }
 
-# Format code
-# [Add your format command here]
+    match result {
+        Ok(report) => SlashCommandResult::data(SlashCommandData::Text(report.summary())),
+        Err(e) => SlashCommandResult::error(e.to_string()),
+    }
+}
 
-# Lint code
-# [Add your lint command here]
-```
+/// Execute the /gen-tests command - writes tests with the developer agent and
+/// reruns them until they pass
+async fn execute_gen_tests_command(
+    state: &AppState,
+    workspace_id: WorkspaceId,
+    target: &str,
+    attempts: usize,
+) -> SlashCommandResult {
+    let manager = state.workspace_manager.read().await;
 
-## Architecture
+    let workspace = match manager.get_workspace(workspace_id) {
+        Some(ws) => ws,
+        None => return SlashCommandResult::error("Workspace not found"),
+    };
+    // Running the test suite executes project code
+    if !manager.is_trusted(workspace_id) {
+        return SlashCommandResult::error("Workspace is not trusted (/trust on to allow)");
+    }
+    if let Err(error) = check_routing(state, &manager, workspace_id, AgentRole::Developer) {
+        return SlashCommandResult::error(error);
+    }
 
-[Describe your project's architecture here:
-- Main components and their responsibilities
-- Data flow between components
-- Key design patterns used
-- Directory structure overview]
+    let llm_settings = axiom_core::LlmSettings::from_axiom_config(&state.config());
+    let service =
+        axiom_core::OrchestrationService::with_settings(workspace.path.clone(), llm_settings)
+            .with_review(state.config().orchestration.review);
+    let result = service.generate_tests(
+        target,
+        attempts,
+        |op| match manager.check_write(workspace_id, "developer", op) {
+            Ok(Some(pending)) => Err(format!("held for approval ({})", pending.id)),
+            Ok(None) => {
+                axiom_core::orchestration::apply_operation(&workspace.path, op)?;
+                let mut event =
+                    ActivityEvent::new(ActivityKind::FileChange, "developer", "Wrote tests");
+                if let Some(path) = op.path() {
+                    event = event.with_path(path);
+                }
+                let _ = manager.record_activity(workspace_id, &event);
+                Ok(())
+            }
+            Err(e) => Err(e.to_string()),
+        },
+        |_| {},
+    );
+    for usage in service.take_usage() {
+        let _ = manager.record_usage(Some(workspace_id), usage);
+    }
 
-## Development Guidelines
+    match result {
+        Ok(report) => {
+            let event = ActivityEvent::new(
+                ActivityKind::AgentRun,
+                "developer",
+                format!("Completed: tests for {}", report.target),
+            )
+            .with_detail(report.summary());
+            let _ = manager.record_activity(workspace_id, &event);
+            SlashCommandResult::data(SlashCommandData::Text(report.summary()))
+        }
+        Err(e) => SlashCommandResult::error(e.to_string()),
+    }
+}
 
-[Add any coding standards, patterns, or practices that agents should follow:
-- Naming conventions
-- Error handling approach
-- Testing requirements
-- Documentation standards]
+/// Execute the /fix command - lists the build's compiler errors, or has the
+/// developer agent fix one and rebuilds until it's gone
+/// `/session`: list saved sessions, or save/restore the workspace service's
+async fn execute_session_command(
+    state: &AppState,
+    workspace_id: WorkspaceId,
+    sub: SessionSubcommand,
+) -> SlashCommandResult {
+    let manager = state.workspace_manager.read().await;
+    let result = match sub {
+        SessionSubcommand::List => {
+            let Some(workspace) = manager.get_workspace(workspace_id) else {
+                return SlashCommandResult::error("Workspace not found");
+            };
+            axiom_core::sessions::list(&workspace.path).map(|sessions| {
+                if sessions.is_empty() {
+                    return "No saved sessions (/session save [name])".to_string();
+                }
+                let lines: Vec<String> = sessions.iter().map(ToString::to_string).collect();
+                lines.join("\n")
+            })
+        }
+        SessionSubcommand::Save { name } => manager
+            .get_or_create_service(workspace_id)
+            .and_then(|service| service.lock().save_session(name.as_deref()))
+            .map(|path| format!("Session saved to {}", path.display())),
+        SessionSubcommand::Restore { name } => manager
+            .get_or_create_service(workspace_id)
+            .and_then(|service| service.lock().restore_session(name.as_deref()))
+            .map(|name| format!("Restored session '{}'", name)),
+    };
+    match result {
+        Ok(text) => SlashCommandResult::data(SlashCommandData::Text(text)),
+        Err(e) => SlashCommandResult::error(e.to_string()),
+    }
+}
 
-## Dependencies
+async fn execute_fix_command(
+    state: &AppState,
+    workspace_id: WorkspaceId,
+    problem: Option<usize>,
+    attempts: usize,
+) -> SlashCommandResult {
+    let manager = state.workspace_manager.read().await;
 
-[List key dependencies and their purposes:
-- Core frameworks
-- Important libraries
-- Development tools]
+    let workspace = match manager.get_workspace(workspace_id) {
+        Some(ws) => ws,
+        None => return SlashCommandResult::error("Workspace not found"),
+    };
+    // Building runs build scripts and compiler plugins
+    if !manager.is_trusted(workspace_id) {
+        return SlashCommandResult::error("Workspace is not trusted (/trust on to allow)");
+    }
 
----
+    let llm_settings = axiom_core::LlmSettings::from_axiom_config(&state.config());
+    let service =
+        axiom_core::OrchestrationService::with_settings(workspace.path.clone(), llm_settings)
+            .with_review(state.config().orchestration.review);
+    let Some(problem) = problem else {
+        return match service.list_problems() {
+            Ok(list) => SlashCommandResult::data(SlashCommandData::Text(list)),
+            Err(e) => SlashCommandResult::error(e.to_string()),
+        };
+    };
+    if let Err(error) = check_routing(state, &manager, workspace_id, AgentRole::Developer) {
+        return SlashCommandResult::error(error);
+    }
 
-*This file was generated by Axiom. Update it to help AI agents understand your codebase.*
-"#,
-        workspace.name,
-        workspace.path.display()
+    let result = service.fix_problem(
+        problem,
+        attempts,
+        |op| match manager.check_write(workspace_id, "developer", op) {
+            Ok(Some(pending)) => Err(format!("held for approval ({})", pending.id)),
+            Ok(None) => {
+                axiom_core::orchestration::apply_operation(&workspace.path, op)?;
+                let mut event =
+                    ActivityEvent::new(ActivityKind::FileChange, "developer", "Fixed error");
+                if let Some(path) = op.path() {
+                    event = event.with_path(path);
+                }
+                let _ = manager.record_activity(workspace_id, &event);
+                Ok(())
+            }
+            Err(e) => Err(e.to_string()),
+        },
+        |_| {},
     );
+    for usage in service.take_usage() {
+        let _ = manager.record_usage(Some(workspace_id), usage);
+    }
 
-    // Write the file
-    match tokio::fs::write(&axiom_md_path, &template).await {
-        Ok(_) => SlashCommandResult::success(format!(
-            "Created AXIOM.md in {}",
-            target_dir.display()
-        )),
-        Err(e) => SlashCommandResult::error(format!("Failed to create AXIOM.md: {}", e)),
+    match result {
+        Ok(report) => {
+            let event = ActivityEvent::new(
+                ActivityKind::AgentRun,
+                "developer",
+                format!("Completed: fix {}", report.problem),
+            )
+            .with_detail(report.summary());
+            let _ = manager.record_activity(workspace_id, &event);
+            SlashCommandResult::data(SlashCommandData::Text(report.summary()))
+        }
+        Err(e) => SlashCommandResult::error(e.to_string()),
     }
 }
 
@@ -1023,6 +4087,12 @@ async fn execute_workspace_subcommand(
 
         WorkspaceSubcommand::Create { name, path } => {
             let manager = state.workspace_manager.read().await;
+            if let Some(existing) = manager.find_by_path(&path) {
+                return SlashCommandResult::action(UiAction::OfferExistingWorkspace {
+                    id: existing.id.to_string(),
+                    name: existing.name,
+                });
+            }
             match manager.create_workspace(&name, path) {
                 Ok(ws) => SlashCommandResult::success(format!(
                     "Created workspace '{}' with ID {}",
@@ -1031,6 +4101,65 @@ async fn execute_workspace_subcommand(
                 Err(e) => SlashCommandResult::error(e.to_string()),
             }
         }
+
+        WorkspaceSubcommand::Dedupe => {
+            let manager = state.workspace_manager.read().await;
+            match manager.merge_duplicate_workspaces() {
+                Ok(merged) => SlashCommandResult::success(axiom_core::merge_summary(&merged)),
+                Err(e) => SlashCommandResult::error(e.to_string()),
+            }
+        }
+
+        WorkspaceSubcommand::Archived => {
+            let manager = state.workspace_manager.read().await;
+            let info: Vec<axiom_core::WorkspaceInfo> = manager
+                .archived_workspaces()
+                .iter()
+                .map(|ws| axiom_core::WorkspaceInfo {
+                    id: ws.id.to_string(),
+                    name: ws.name.clone(),
+                    path: ws.path.to_string_lossy().to_string(),
+                    is_active: false,
+                })
+                .collect();
+            SlashCommandResult::data(SlashCommandData::WorkspaceList(info))
+        }
+
+        WorkspaceSubcommand::Archive { id } => set_archived_by_key(state, &id, true).await,
+        WorkspaceSubcommand::Restore { id } => set_archived_by_key(state, &id, false).await,
+
+        WorkspaceSubcommand::Purge { id, confirm } => {
+            let manager = state.workspace_manager.read().await;
+            let result = manager.resolve_workspace(&id).and_then(|ws| {
+                if confirm {
+                    manager.purge_workspace(ws.id)
+                } else {
+                    manager.purge_plan(ws.id)
+                }
+            });
+            match result {
+                Ok(plan) => SlashCommandResult::data(SlashCommandData::Text(
+                    axiom_core::purge_summary(&plan, confirm),
+                )),
+                Err(e) => SlashCommandResult::error(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Archive or restore the workspace named by `key` (`/workspace archive|restore`)
+async fn set_archived_by_key(state: &AppState, key: &str, archived: bool) -> SlashCommandResult {
+    let manager = state.workspace_manager.read().await;
+    match manager
+        .resolve_workspace(key)
+        .and_then(|ws| manager.set_workspace_archived(ws.id, archived))
+    {
+        Ok(ws) if archived => SlashCommandResult::success(format!(
+            "Archived workspace '{}' (/workspace restore brings it back)",
+            ws.name
+        )),
+        Ok(ws) => SlashCommandResult::success(format!("Restored workspace '{}'", ws.name)),
+        Err(e) => SlashCommandResult::error(e.to_string()),
     }
 }
 
@@ -1045,7 +4174,7 @@ async fn execute_model_subcommand(
     match sub {
         ModelSubcommand::List => {
             // Get LLM settings from config
-            let settings = axiom_core::LlmSettings::from_axiom_config(&state.config);
+            let settings = axiom_core::LlmSettings::from_axiom_config(&state.config());
 
             // Find the first enabled provider with models
             for provider in &settings.providers {
@@ -1066,8 +4195,22 @@ async fn execute_model_subcommand(
             SlashCommandResult::success(format!("Model set to: {}", model))
         }
 
+        ModelSubcommand::Assign {
+            role,
+            provider,
+            model,
+        } => match save_agent_mapping(state, role, &provider, &model).await {
+            Ok(()) => SlashCommandResult::success(format!(
+                "{} now uses {}/{}",
+                role.display_name(),
+                provider,
+                model
+            )),
+            Err((_, error)) => SlashCommandResult::error(error),
+        },
+
         ModelSubcommand::Current => {
-            let settings = axiom_core::LlmSettings::from_axiom_config(&state.config);
+            let settings = axiom_core::LlmSettings::from_axiom_config(&state.config());
 
             for provider in &settings.providers {
                 if provider.enabled {
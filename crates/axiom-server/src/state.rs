@@ -1,21 +1,221 @@
 //! Server state management
 
-use axiom_core::{AxiomConfig, WorkspaceManager};
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use axiom_core::llm::ProviderRegistry;
+use axiom_core::{AxiomConfig, Notification, SharedService, WorkspaceId, WorkspaceManager};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+
+/// How often a streamed workspace's service is polled for events
+const PUMP_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Notifications a slow WebSocket client can fall behind by before it misses some
+const STREAM_CAPACITY: usize = 1024;
+
+/// Notification fan-out for one workspace's service
+struct NotificationStream {
+    service: SharedService,
+    tx: broadcast::Sender<Notification>,
+}
+
+type Streams = Arc<Mutex<HashMap<WorkspaceId, NotificationStream>>>;
 
 /// Shared application state
 #[derive(Clone)]
 pub struct AppState {
-    pub config: AxiomConfig,
+    config: Arc<std::sync::RwLock<AxiomConfig>>,
+    /// Where settings changes are saved
+    config_path: PathBuf,
     pub workspace_manager: Arc<RwLock<WorkspaceManager>>,
+    streams: Streams,
+    /// Configured LLM providers, rebuilt when settings change
+    llm_registry: Arc<std::sync::RwLock<Arc<ProviderRegistry>>>,
 }
 
 impl AppState {
-    pub fn new(config: AxiomConfig, workspace_manager: WorkspaceManager) -> Self {
+    pub fn new(
+        config: AxiomConfig,
+        config_path: PathBuf,
+        workspace_manager: WorkspaceManager,
+    ) -> Self {
+        let llm_registry = Arc::new(std::sync::RwLock::new(build_registry(&config)));
         Self {
-            config,
+            config: Arc::new(std::sync::RwLock::new(config)),
+            config_path,
             workspace_manager: Arc::new(RwLock::new(workspace_manager)),
+            streams: Arc::default(),
+            llm_registry,
+        }
+    }
+
+    /// The current configuration
+    pub fn config(&self) -> AxiomConfig {
+        self.config
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// The configured LLM providers
+    pub fn llm_registry(&self) -> Arc<ProviderRegistry> {
+        Arc::clone(
+            &self
+                .llm_registry
+                .read()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    /// Change the configuration and save it
+    ///
+    /// Nothing changes if `update` fails or the file can't be written. New
+    /// services pick up the change, and every connected UI is told that
+    /// `section` changed.
+    pub async fn update_config(
+        &self,
+        section: &str,
+        update: impl FnOnce(&mut AxiomConfig) -> Result<(), String>,
+    ) -> Result<AxiomConfig, String> {
+        let updated = {
+            let mut config = self.config.write().unwrap_or_else(PoisonError::into_inner);
+            let mut updated = config.clone();
+            update(&mut updated)?;
+            axiom_core::config::save_config(&updated, &self.config_path)
+                .map_err(|e| format!("Failed to save {}: {}", self.config_path.display(), e))?;
+            *config = updated.clone();
+            updated
+        };
+        *self
+            .llm_registry
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = build_registry(&updated);
+        self.workspace_manager
+            .write()
+            .await
+            .set_global_config(updated.clone());
+        self.broadcast(Notification::SettingsChanged {
+            section: section.to_string(),
+        });
+        Ok(updated)
+    }
+
+    /// Send `notification` to the UIs of every streamed workspace
+    pub fn broadcast(&self, notification: Notification) {
+        let streams = self.streams.lock().unwrap_or_else(PoisonError::into_inner);
+        for stream in streams.values() {
+            let _ = stream.tx.send(notification.clone());
+        }
+    }
+
+    /// Send `notification` to the UIs of one workspace, if any are connected
+    pub fn notify(&self, workspace_id: WorkspaceId, notification: Notification) {
+        let streams = self.streams.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(stream) = streams.get(&workspace_id) {
+            let _ = stream.tx.send(notification);
+        }
+    }
+
+    /// Subscribe to the notifications of a workspace's service
+    ///
+    /// The first subscriber starts a task that processes the service's events
+    /// and broadcasts its notifications; it stops once every subscriber is
+    /// gone, or when the workspace gets a new service.
+    pub fn subscribe(
+        &self,
+        workspace_id: WorkspaceId,
+        service: SharedService,
+    ) -> broadcast::Receiver<Notification> {
+        let mut streams = self.streams.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(stream) = streams.get(&workspace_id) {
+            if Arc::ptr_eq(&stream.service, &service) {
+                return stream.tx.subscribe();
+            }
+        }
+
+        let (tx, rx) = broadcast::channel(STREAM_CAPACITY);
+        streams.insert(
+            workspace_id,
+            NotificationStream {
+                service: Arc::clone(&service),
+                tx: tx.clone(),
+            },
+        );
+        tokio::spawn(pump_notifications(
+            Arc::clone(&self.streams),
+            Arc::clone(&self.workspace_manager),
+            workspace_id,
+            service,
+            tx,
+        ));
+        rx
+    }
+}
+
+/// A registry of the providers configured in `config`
+fn build_registry(config: &AxiomConfig) -> Arc<ProviderRegistry> {
+    let mut registry = ProviderRegistry::from_config(config);
+    registry.register_configured(config);
+    Arc::new(registry)
+}
+
+/// Process `service`'s events and broadcast its notifications until nobody listens
+///
+/// Events parsed from CLI agent output are also recorded in the workspace's
+/// activity feed and usage log.
+async fn pump_notifications(
+    streams: Streams,
+    workspace_manager: Arc<RwLock<WorkspaceManager>>,
+    workspace_id: WorkspaceId,
+    service: SharedService,
+    tx: broadcast::Sender<Notification>,
+) {
+    let mut interval = tokio::time::interval(PUMP_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        {
+            // Checked under the lock so a concurrent subscribe can't attach to
+            // a stream that is shutting down
+            let mut streams = streams.lock().unwrap_or_else(PoisonError::into_inner);
+            if tx.receiver_count() == 0 {
+                if streams
+                    .get(&workspace_id)
+                    .is_some_and(|stream| stream.tx.same_channel(&tx))
+                {
+                    streams.remove(&workspace_id);
+                }
+                break;
+            }
+        }
+
+        // The service lock is held while commands run, so it's taken off the
+        // async workers
+        let service = Arc::clone(&service);
+        let notifications = tokio::task::spawn_blocking(move || {
+            let mut service = service.lock();
+            if let Err(e) = service.process_events() {
+                tracing::warn!("Event processing failed for {}: {}", workspace_id, e);
+            }
+            std::iter::from_fn(|| service.poll_notification()).collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default();
+        for notification in notifications {
+            match &notification {
+                Notification::CliAgentEvent { agent, event, .. } => {
+                    let manager = workspace_manager.read().await;
+                    let _ = manager.record_agent_event(workspace_id, agent, event);
+                }
+                Notification::UsageUpdate { record, .. } => {
+                    let manager = workspace_manager.read().await;
+                    let _ = manager.record_usage(Some(workspace_id), record.clone());
+                }
+                _ => {}
+            }
+            let _ = tx.send(notification);
         }
     }
+    tracing::debug!("Notification stream closed for workspace: {}", workspace_id);
 }
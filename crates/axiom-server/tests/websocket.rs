@@ -0,0 +1,249 @@
+//! The WebSocket API served over a real socket
+//!
+//! A client connects to a workspace, sends a command, and reads the
+//! notification the workspace's service streams back. Neither socket opens
+//! for other sites' pages, and the terminal only for trusted workspaces.
+
+use axiom_core::workspace::WorkspaceStorage;
+use axiom_core::{AxiomConfig, Notification, WorkspaceId, WorkspaceManager};
+use axiom_server::{build_router, AppState};
+use futures_util::{SinkExt, StreamExt};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::{Error, Message};
+
+/// A server on a free port with one workspace
+struct TestServer {
+    addr: SocketAddr,
+    state: AppState,
+    workspace_id: WorkspaceId,
+    workspace_dir: PathBuf,
+    _dirs: (TempDir, TempDir),
+}
+
+impl TestServer {
+    async fn start() -> Self {
+        let config_dir = TempDir::new().unwrap();
+        let workspace_dir = TempDir::new().unwrap();
+
+        let storage = WorkspaceStorage::with_config_dir(config_dir.path().to_path_buf());
+        let manager = WorkspaceManager::with_storage(AxiomConfig::default(), storage).unwrap();
+        let workspace = manager
+            .create_workspace("notes", workspace_dir.path().to_path_buf())
+            .unwrap();
+        let state = AppState::new(
+            AxiomConfig::default(),
+            config_dir.path().join("config.toml"),
+            manager,
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = build_router(state.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        Self {
+            addr,
+            state,
+            workspace_id: workspace.id,
+            workspace_dir: workspace_dir.path().to_path_buf(),
+            _dirs: (config_dir, workspace_dir),
+        }
+    }
+
+    fn url(&self, route: &str) -> String {
+        format!(
+            "ws://{}/api/workspaces/{}/{}",
+            self.addr, self.workspace_id, route
+        )
+    }
+
+    async fn trust(&self) {
+        let manager = self.state.workspace_manager.read().await;
+        manager.set_trusted(self.workspace_id, true).unwrap();
+    }
+
+    /// The HTTP status an upgrade of `route` gets, sent from `origin` if given
+    async fn upgrade_status(&self, route: &str, origin: Option<&str>) -> u16 {
+        let mut request = self.url(route).into_client_request().unwrap();
+        if let Some(origin) = origin {
+            request
+                .headers_mut()
+                .insert("Origin", origin.parse().unwrap());
+        }
+        match tokio_tungstenite::connect_async(request).await {
+            Ok((_, response)) => response.status().as_u16(),
+            Err(Error::Http(response)) => response.status().as_u16(),
+            Err(e) => panic!("{} connection failed: {}", route, e),
+        }
+    }
+
+    /// The HTTP status a terminal upgrade gets, sent from `origin` if given
+    async fn terminal_status(&self, origin: Option<&str>) -> u16 {
+        self.upgrade_status("terminal", origin).await
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn command_streams_its_notification_back() {
+    let server = TestServer::start().await;
+    let notes = server.workspace_dir.join("notes.txt");
+    std::fs::write(&notes, "hello\n").unwrap();
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(server.url("ws"))
+        .await
+        .unwrap();
+    let command = serde_json::json!({ "type": "ReadFile", "path": notes });
+    socket
+        .send(Message::Text(command.to_string()))
+        .await
+        .unwrap();
+
+    let loaded = tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(message) = socket.next().await {
+            let Message::Text(text) = message.unwrap() else {
+                continue;
+            };
+            if let Ok(Notification::FileLoaded { path, content }) = serde_json::from_str(&text) {
+                return Some((path, content));
+            }
+        }
+        None
+    })
+    .await
+    .expect("no FileLoaded notification within 10s");
+
+    assert_eq!(loaded, Some((notes, "hello\n".to_string())));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn commands_stay_inside_the_workspace() {
+    let server = TestServer::start().await;
+    let outside = TempDir::new().unwrap();
+    let secret = outside.path().join("secret.txt");
+    std::fs::write(&secret, "secret\n").unwrap();
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(server.url("ws"))
+        .await
+        .unwrap();
+    let command = serde_json::json!({ "type": "ReadFile", "path": secret });
+    socket
+        .send(Message::Text(command.to_string()))
+        .await
+        .unwrap();
+
+    let error = tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(message) = socket.next().await {
+            let Message::Text(text) = message.unwrap() else {
+                continue;
+            };
+            match serde_json::from_str(&text) {
+                Ok(Notification::FileLoaded { .. }) => return None,
+                Ok(Notification::Error { message, .. }) => return Some(message),
+                _ => {}
+            }
+        }
+        None
+    })
+    .await
+    .expect("no reply within 10s");
+
+    assert!(error.is_some_and(|message| message.contains("outside the workspace")));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn listing_and_lookups_stay_inside_the_workspace() {
+    let server = TestServer::start().await;
+    let (mut socket, _) = tokio_tungstenite::connect_async(server.url("ws"))
+        .await
+        .unwrap();
+
+    let commands = [
+        serde_json::json!({ "type": "ListFiles", "path": "/etc" }),
+        serde_json::json!({ "type": "ListFiles", "path": "../.." }),
+        serde_json::json!({ "type": "Hover", "path": "/etc/passwd", "line": 1, "column": 1 }),
+        serde_json::json!({ "type": "GoToDefinition", "path": "../../x.rs", "line": 1, "column": 1 }),
+        serde_json::json!({ "type": "ListFiles", "path": "." }),
+    ];
+    let mut replies = Vec::new();
+    for command in commands {
+        socket
+            .send(Message::Text(command.to_string()))
+            .await
+            .unwrap();
+        let reply = tokio::time::timeout(Duration::from_secs(10), async {
+            while let Some(message) = socket.next().await {
+                let Message::Text(text) = message.unwrap() else {
+                    continue;
+                };
+                match serde_json::from_str(&text) {
+                    Ok(Notification::FileList { path, .. }) => return Ok(path),
+                    Ok(Notification::Hover { path, .. })
+                    | Ok(Notification::Definition { path, .. }) => return Ok(path),
+                    Ok(Notification::Error { message, .. }) => return Err(message),
+                    _ => {}
+                }
+            }
+            Err("socket closed".to_string())
+        })
+        .await
+        .expect("no reply within 10s");
+        replies.push(reply);
+    }
+
+    // Only the workspace root itself is listed
+    let (root, refused) = replies.split_last().unwrap();
+    assert_eq!(root, &Ok(PathBuf::from(".")));
+    for reply in refused {
+        assert!(
+            reply
+                .as_ref()
+                .is_err_and(|message| message.contains("outside the workspace")),
+            "{:?}",
+            reply
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn websocket_refuses_foreign_origins() {
+    let server = TestServer::start().await;
+    assert_eq!(
+        server.upgrade_status("ws", Some("https://evil.example")).await,
+        403
+    );
+    let ui = format!("http://{}", server.addr);
+    assert_eq!(server.upgrade_status("ws", Some(&ui)).await, 101);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn terminal_refuses_untrusted_workspaces() {
+    let server = TestServer::start().await;
+    let ui = format!("http://{}", server.addr);
+    assert_eq!(server.terminal_status(Some(&ui)).await, 403);
+    assert_eq!(server.terminal_status(None).await, 403);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn terminal_refuses_foreign_origins() {
+    let server = TestServer::start().await;
+    server.trust().await;
+    assert_eq!(
+        server.terminal_status(Some("https://evil.example")).await,
+        403
+    );
+    let other_port = format!("http://127.0.0.1:{}", server.addr.port() + 1);
+    assert_eq!(server.terminal_status(Some(&other_port)).await, 403);
+    assert_eq!(server.terminal_status(Some("null")).await, 403);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn terminal_opens_for_the_served_ui() {
+    let server = TestServer::start().await;
+    server.trust().await;
+    let ui = format!("http://{}", server.addr);
+    assert_eq!(server.terminal_status(Some(&ui)).await, 101);
+}
@@ -3,27 +3,32 @@
 //! This is the bridge between the TUI layer and the backend.
 //! It handles terminal events, sends Commands, and reacts to Notifications.
 
-use axiom_core::{AxiomConfig, AxiomService, Command, Notification, OutputContext, Result};
+use axiom_core::config::{Action, Keymap};
+use axiom_core::{AxiomConfig, Command, Notification, OutputContext, Result, WorkspaceManager};
 use crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyModifiers};
 use ratatui::prelude::*;
 use ratatui::widgets::{Paragraph};
 use std::path::PathBuf;
 use std::time::Duration;
 
-use crate::events::TuiEvent;
+use crate::events::{key_chord, TuiEvent};
 use crate::panels::{AgentsPanel, FileTreePanel, InputPanel, OutputPanel, Panel};
+use crate::sessions::{WorkspaceSessions, MAX_SLOTS};
 use crate::state::{AppState, MessageLevel, PanelId};
 
 /// Main TUI application
 ///
-/// Owns the AxiomService and manages the TUI event loop.
+/// Owns an AxiomService per open workspace and manages the TUI event loop.
 pub struct TuiApp {
-    /// Backend service
-    service: AxiomService,
+    /// Backend services, one per open workspace
+    sessions: WorkspaceSessions,
 
     /// TUI-specific state
     state: AppState,
 
+    /// Key bindings for the global actions
+    keymap: Keymap,
+
     /// Panel instances
     file_tree: FileTreePanel,
     output: OutputPanel,
@@ -34,11 +39,14 @@ pub struct TuiApp {
 impl TuiApp {
     /// Create a new TuiApp
     pub fn new(config: AxiomConfig, cwd: PathBuf) -> Result<Self> {
-        let service = AxiomService::new(config, cwd.clone())?;
+        let keymap = Keymap::new(&config.keys).unwrap_or_default();
+        let manager = WorkspaceManager::new(config.clone()).ok();
+        let sessions = WorkspaceSessions::new(config, cwd.clone(), manager)?;
 
         Ok(Self {
-            service,
+            sessions,
             state: AppState::new(),
+            keymap,
             file_tree: FileTreePanel::new(&cwd),
             output: OutputPanel::new(),
             input: InputPanel::new(),
@@ -49,18 +57,25 @@ impl TuiApp {
     /// Run the main event loop
     pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
         loop {
-            // Process backend events
-            self.service.process_events()?;
+            // Process backend events, including background workspaces'
+            self.sessions.service().process_events()?;
+            self.sessions.process_background()?;
 
             // Update panels from service
-            self.output.update_from_service(&self.service);
-            self.agents.update_from_service(&self.service);
+            self.output.update_from_service(&self.sessions.service());
+            self.agents.update_from_service(&self.sessions.service());
+            self.agents.set_workspaces(self.sessions.badges());
 
             // Process pending actions from panels
             self.process_panel_actions()?;
 
             // Poll notifications from backend
-            while let Some(notification) = self.service.poll_notification() {
+            loop {
+                // The service isn't held while a notification is handled
+                let notification = self.sessions.service().poll_notification();
+                let Some(notification) = notification else {
+                    break;
+                };
                 self.handle_notification(notification);
             }
 
@@ -103,7 +118,7 @@ impl TuiApp {
 
         // Check for input panel commands
         if let Some(command) = self.input.take_pending_command() {
-            self.service.send(command)?;
+            self.sessions.service().send(command)?;
         }
 
         // Check for agents panel context switch
@@ -116,30 +131,44 @@ impl TuiApp {
 
     /// Handle a key event
     fn handle_key(&mut self, key: event::KeyEvent) -> Result<bool> {
-        // Global quit: 'q' in normal mode or Ctrl+C
-        if (key.code == KeyCode::Char('q') && !self.state.input_mode.is_editing())
-            || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
-        {
-            self.service.send(Command::Shutdown)?;
+        // Ctrl+C always quits
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.sessions.shutdown()?;
             return Ok(true);
         }
 
-        // Tab to cycle focus
-        if key.code == KeyCode::Tab && !self.state.input_mode.is_editing() {
-            self.state.focus.next();
-            return Ok(false);
-        }
-
-        // Shift+Tab to cycle focus backward
-        if key.code == KeyCode::BackTab {
-            self.state.focus.prev();
+        // Ctrl+1..9 jumps to an open workspace
+        if let (KeyCode::Char(c @ '1'..='9'), true) =
+            (key.code, key.modifiers.contains(KeyModifiers::CONTROL))
+        {
+            let index = c as usize - '1' as usize;
+            if index < MAX_SLOTS {
+                self.switch_workspace(index)?;
+            }
             return Ok(false);
         }
 
-        // Escape: return to normal mode
-        if key.code == KeyCode::Esc {
-            self.state.input_mode.to_normal();
-            return Ok(false);
+        // Global actions, bound in `[keys]`
+        let editing = self.state.input_mode.is_editing();
+        let scope = self.state.focus.current().keymap_scope();
+        match key_chord(&key).and_then(|chord| self.keymap.action(scope, &chord)) {
+            Some(Action::Quit) if !editing => {
+                self.sessions.shutdown()?;
+                return Ok(true);
+            }
+            Some(Action::FocusNext) if !editing => {
+                self.state.focus.next();
+                return Ok(false);
+            }
+            Some(Action::FocusPrev) => {
+                self.state.focus.prev();
+                return Ok(false);
+            }
+            Some(Action::NormalMode) => {
+                self.state.input_mode.to_normal();
+                return Ok(false);
+            }
+            _ => {}
         }
 
         // Create TuiEvent for panel handling
@@ -151,27 +180,27 @@ impl TuiApp {
             PanelId::OUTPUT => {
                 // Check for PTY input forwarding
                 if let Some(id) = self.output.context().agent_id() {
-                    let agents = self.service.agents();
+                    let agents = self.sessions.service().agents();
                     if let Some(agent) = agents.iter().find(|a| a.id == id) {
                         if agent.agent_type.is_cli_agent() {
                             // Forward key to PTY
                             match key.code {
                                 KeyCode::Char(c) => {
-                                    self.service.send(Command::SendPtyInput {
+                                    self.sessions.service().send(Command::SendPtyInput {
                                         agent_id: id,
                                         data: vec![c as u8],
                                     })?;
                                     return Ok(false);
                                 }
                                 KeyCode::Enter => {
-                                    self.service.send(Command::SendPtyInput {
+                                    self.sessions.service().send(Command::SendPtyInput {
                                         agent_id: id,
                                         data: vec![b'\r'],
                                     })?;
                                     return Ok(false);
                                 }
                                 KeyCode::Backspace => {
-                                    self.service.send(Command::SendPtyInput {
+                                    self.sessions.service().send(Command::SendPtyInput {
                                         agent_id: id,
                                         data: vec![0x7f], // DEL
                                     })?;
@@ -193,6 +222,17 @@ impl TuiApp {
         Ok(false)
     }
 
+    /// Show the workspace in slot `index`; the one left keeps running
+    fn switch_workspace(&mut self, index: usize) -> Result<()> {
+        if self.sessions.switch(index)? {
+            self.file_tree = FileTreePanel::new(self.sessions.path());
+            self.output.set_context(OutputContext::Empty);
+            let name = &self.sessions.badges()[index].name;
+            self.state.info(format!("Workspace: {}", name));
+        }
+        Ok(())
+    }
+
     /// Handle a mouse event
     fn handle_mouse(&mut self, mouse: event::MouseEvent) -> Result<()> {
         let tui_event = TuiEvent::Mouse(mouse);
@@ -228,7 +268,9 @@ impl TuiApp {
             }
             Notification::AgentStatusChanged { id, status } => {
                 if status.is_terminal() {
-                    if let Some(agent) = self.service.agents().iter().find(|a| a.id == id) {
+                    if let Some(agent) =
+                        self.sessions.service().agents().iter().find(|a| a.id == id)
+                    {
                         self.state.info(format!("Completed: {}", agent.name));
                     }
                 }
@@ -248,15 +290,17 @@ impl TuiApp {
                 }
                 let _ = id; // Silence warning
             }
-            Notification::FileModified { path } => {
+            Notification::FileModified { path } | Notification::FileChanged { path } => {
                 self.state.info(format!(
                     "Modified: {}",
                     path.file_name().unwrap_or_default().to_string_lossy()
                 ));
             }
-            Notification::Error { message } => {
-                self.state.error(message);
-            }
+            Notification::Error { message, code } => match code {
+                // The code is what `axiom explain` looks up
+                Some(code) => self.state.error(format!("{} ({})", message, code)),
+                None => self.state.error(message),
+            },
             Notification::Info { message } => {
                 self.state.info(message);
             }
@@ -3,9 +3,10 @@
 //! Handles UI-specific events (keyboard, mouse, resize) and bridges
 //! to the axiom-core backend via Commands and Notifications.
 
+use axiom_core::config::{Chord, Key};
 use axiom_core::{AgentId, OutputContext};
 use crossbeam_channel::{bounded, Receiver, Sender};
-use crossterm::event::{KeyEvent, MouseEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -131,6 +132,45 @@ impl TuiEventBus {
     }
 }
 
+/// The chord for a key event, to look up in a [`Keymap`]; `None` for keys
+/// no binding can name
+///
+/// Terminals report Shift+Tab as BackTab, which is `shift+tab` here.
+///
+/// [`Keymap`]: axiom_core::config::Keymap
+pub fn key_chord(key: &KeyEvent) -> Option<Chord> {
+    let mut shift = key.modifiers.contains(KeyModifiers::SHIFT);
+    let code = match key.code {
+        KeyCode::Char(c) => Key::Char(c),
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Esc => Key::Esc,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::BackTab => {
+            shift = true;
+            Key::Tab
+        }
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::Insert => Key::Insert,
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::F(n) => Key::F(n),
+        _ => return None,
+    };
+    Some(Chord::new(
+        code,
+        key.modifiers.contains(KeyModifiers::CONTROL),
+        key.modifiers.contains(KeyModifiers::ALT),
+        shift,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
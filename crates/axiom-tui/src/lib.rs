@@ -17,12 +17,14 @@
 //!
 //! - `events` - TUI-specific events (keyboard, mouse, resize)
 //! - `state` - TUI state (focus, input mode, app state)
+//! - `sessions` - Open workspaces, each with its own AxiomService
 //! - (Future) `panels` - Panel implementations
 //! - (Future) `ui` - Layout and rendering
 
 pub mod app;
 pub mod events;
 pub mod panels;
+pub mod sessions;
 pub mod state;
 pub mod ui;
 
@@ -2,8 +2,11 @@
 
 use super::Panel;
 use crate::events::TuiEvent;
+use crate::sessions::WorkspaceBadge;
 use crate::state::{AppState, OutputContext, PanelId};
-use axiom_core::{AgentId, AgentStatus, AgentType, AgentView, AxiomService, Result};
+use axiom_core::{
+    AgentId, AgentStatus, AgentType, AgentView, AxiomService, Result, ServiceHealth,
+};
 use crossterm::event::{KeyCode, MouseButton, MouseEventKind};
 use ratatui::{
     layout::Rect,
@@ -30,6 +33,9 @@ pub struct AgentsPanel {
     /// Cached agent views
     agents: Vec<AgentView>,
 
+    /// Open workspaces, shown as badges above the list when there are several
+    workspaces: Vec<WorkspaceBadge>,
+
     /// Pending context switch
     pub pending_context: Option<OutputContext>,
 }
@@ -42,6 +48,7 @@ impl AgentsPanel {
             visible_height: 10,
             list_area: Rect::default(),
             agents: Vec::new(),
+            workspaces: Vec::new(),
             pending_context: None,
         }
     }
@@ -60,6 +67,45 @@ impl AgentsPanel {
         }
     }
 
+    /// Update the open workspaces' badges
+    pub fn set_workspaces(&mut self, workspaces: Vec<WorkspaceBadge>) {
+        self.workspaces = workspaces;
+    }
+
+    /// One badge per workspace: its Ctrl number, name, running agents and
+    /// unseen activity, with the one on screen highlighted
+    fn workspace_line(&self) -> Line<'static> {
+        let mut spans = Vec::new();
+        for badge in &self.workspaces {
+            let style = if badge.active {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            spans.push(Span::styled(
+                format!("{}:{}", badge.number, badge.name),
+                style,
+            ));
+            if badge.running > 0 {
+                spans.push(Span::styled(
+                    format!(" ▶{}", badge.running),
+                    Style::default().fg(Color::Cyan),
+                ));
+            }
+            if badge.unseen > 0 {
+                spans.push(Span::styled(
+                    format!(" ●{}", badge.unseen),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            spans.push(Span::raw(" "));
+        }
+        Line::from(spans)
+    }
+
     /// Move selection up
     fn select_prev(&mut self) {
         if self.agents.is_empty() {
@@ -242,7 +288,11 @@ impl Panel for AgentsPanel {
             Style::default().fg(Color::DarkGray)
         };
 
-        let running = self.agents.iter().filter(|a| a.status.is_running()).count();
+        let running = self
+            .agents
+            .iter()
+            .filter(|a| a.status.is_running() && !a.agent_type.is_long_running())
+            .count();
         let total = self.agents.len();
 
         let title = if running > 0 {
@@ -256,8 +306,16 @@ impl Panel for AgentsPanel {
             .borders(Borders::ALL)
             .border_style(border_style);
 
-        let inner = block.inner(area);
+        let mut inner = block.inner(area);
         frame.render_widget(block, area);
+
+        // Workspace badges take the top line when several are open
+        if self.workspaces.len() > 1 && inner.height > 1 {
+            let badges = Rect { height: 1, ..inner };
+            frame.render_widget(Paragraph::new(self.workspace_line()), badges);
+            inner.y += 1;
+            inner.height -= 1;
+        }
         self.list_area = inner;
 
         // Update visible height
@@ -326,18 +384,40 @@ impl Panel for AgentsPanel {
                     String::new()
                 };
 
-                let stats_parts = if agent.status.is_running() {
-                    let bar = Self::animated_bar(elapsed_ms, 8);
-                    format!("  ⏱{} {} {}", duration, lines, bar)
+                // Services show health, uptime and restarts instead of a progress bar
+                let line2 = if let (Some(info), true) = (&agent.service, agent.status.is_running()) {
+                    let (dot, color) = match info.health {
+                        ServiceHealth::Healthy => ("●", Color::Green),
+                        ServiceHealth::Unhealthy => ("●", Color::Red),
+                        ServiceHealth::Unknown => ("○", Color::DarkGray),
+                    };
+                    let mut spans = vec![
+                        Span::styled(format!("  {} ", dot), Style::default().fg(color)),
+                        Span::styled(
+                            format!("up {}", Self::format_duration(info.uptime_secs)),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    ];
+                    if info.restarts > 0 {
+                        spans.push(Span::styled(
+                            format!(" ↻{}", info.restarts),
+                            Style::default().fg(Color::Yellow),
+                        ));
+                    }
+                    Line::from(spans)
                 } else {
-                    format!("  ⏱{} {}", duration, lines)
+                    let stats_parts = if agent.status.is_running() {
+                        let bar = Self::animated_bar(elapsed_ms, 8);
+                        format!("  ⏱{} {} {}", duration, lines, bar)
+                    } else {
+                        format!("  ⏱{} {}", duration, lines)
+                    };
+                    Line::from(Span::styled(
+                        stats_parts,
+                        Style::default().fg(Color::DarkGray),
+                    ))
                 };
 
-                let line2 = Line::from(Span::styled(
-                    stats_parts,
-                    Style::default().fg(Color::DarkGray),
-                ));
-
                 let item_style = if is_selected {
                     Style::default().bg(if focused {
                         Color::DarkGray
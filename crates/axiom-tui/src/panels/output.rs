@@ -71,7 +71,13 @@ impl OutputPanel {
                     self.content = screen
                         .lines
                         .iter()
-                        .map(|line| line.cells.iter().map(|c| c.char).collect())
+                        .map(|line| {
+                            line.cells
+                                .iter()
+                                .filter(|c| !c.continuation)
+                                .map(|c| c.char)
+                                .collect()
+                        })
                         .collect();
                 } else if let Some(output) = service.agent_output(*agent_id) {
                     // Regular agent output
@@ -0,0 +1,308 @@
+//! Workspaces kept open side by side
+//!
+//! Each open workspace has its own [`AxiomService`], so switching away from
+//! a workspace doesn't stop its agents. Slots are the starting directory
+//! followed by the registered workspaces, up to nine, one per Ctrl+1..9. A
+//! slot's service starts the first time it is shown and then stays warm;
+//! the services in the background are still driven every tick, and what
+//! happens in them is counted for the agents panel's workspace badges.
+//!
+//! Registered workspaces get their services from the [`WorkspaceManager`],
+//! so they run with the workspace's trust, approvals and config overrides.
+//! A starting directory that isn't registered gets an untrusted service.
+
+use axiom_core::{
+    AxiomConfig, AxiomService, Command, Notification, Result, SharedService, WorkspaceId,
+    WorkspaceManager,
+};
+use parking_lot::{Mutex, MutexGuard};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Slots reachable with Ctrl+1..9
+pub const MAX_SLOTS: usize = 9;
+
+/// One workspace that can be switched to
+struct Slot {
+    /// Display name
+    name: String,
+
+    /// Workspace root
+    path: PathBuf,
+
+    /// Its registered workspace, if any
+    id: Option<WorkspaceId>,
+
+    /// Its service, once started
+    service: Option<SharedService>,
+
+    /// Agent starts, finishes and errors since it was last shown
+    unseen: usize,
+}
+
+/// What the agents panel shows for one workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceBadge {
+    /// Ctrl+<number> switches to it
+    pub number: usize,
+
+    /// Display name
+    pub name: String,
+
+    /// Whether it is the workspace on screen
+    pub active: bool,
+
+    /// Agents still running in it
+    pub running: usize,
+
+    /// Agent starts, finishes and errors since it was last shown
+    pub unseen: usize,
+}
+
+/// The open workspaces and which one is on screen
+pub struct WorkspaceSessions {
+    /// Config of an unregistered starting directory
+    config: AxiomConfig,
+
+    /// Registered workspaces, when their registry could be read
+    manager: Option<WorkspaceManager>,
+
+    slots: Vec<Slot>,
+
+    /// Index of the slot on screen
+    active: usize,
+}
+
+impl WorkspaceSessions {
+    /// Sessions starting in `cwd`, with the workspaces `manager` knows in
+    /// the later slots
+    ///
+    /// The service for `cwd` starts now; the others start when first shown.
+    pub fn new(
+        config: AxiomConfig,
+        cwd: PathBuf,
+        manager: Option<WorkspaceManager>,
+    ) -> Result<Self> {
+        let workspaces = manager
+            .as_ref()
+            .map(WorkspaceManager::list_workspaces)
+            .unwrap_or_default();
+        let mut slots = vec![Slot {
+            name: dir_name(&cwd),
+            id: workspaces
+                .iter()
+                .find(|workspace| workspace.path == cwd)
+                .map(|workspace| workspace.id),
+            path: cwd,
+            service: None,
+            unseen: 0,
+        }];
+        for workspace in &workspaces {
+            if slots.len() == MAX_SLOTS {
+                break;
+            }
+            if workspace.broken || slots.iter().any(|slot| slot.path == workspace.path) {
+                continue;
+            }
+            slots.push(Slot {
+                name: workspace.name.clone(),
+                path: workspace.path.clone(),
+                id: Some(workspace.id),
+                service: None,
+                unseen: 0,
+            });
+        }
+        let mut sessions = Self {
+            config,
+            manager,
+            slots,
+            active: 0,
+        };
+        sessions.start(0)?;
+        Ok(sessions)
+    }
+
+    /// Start the service of slot `index` if it isn't running
+    fn start(&mut self, index: usize) -> Result<()> {
+        let slot = &self.slots[index];
+        if slot.service.is_some() {
+            return Ok(());
+        }
+        let service = match (&self.manager, slot.id) {
+            (Some(manager), Some(id)) => manager.get_or_create_service(id)?,
+            _ => Arc::new(Mutex::new(AxiomService::new(
+                self.config.clone(),
+                slot.path.clone(),
+            )?)),
+        };
+        self.slots[index].service = Some(service);
+        Ok(())
+    }
+
+    /// Service of the workspace on screen
+    ///
+    /// Drop the guard before asking for it again.
+    pub fn service(&self) -> MutexGuard<'_, AxiomService> {
+        self.slots[self.active]
+            .service
+            .as_ref()
+            .expect("the active workspace's service is started")
+            .lock()
+    }
+
+    /// Root of the workspace on screen
+    pub fn path(&self) -> &Path {
+        &self.slots[self.active].path
+    }
+
+    /// Index of the slot on screen
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Show the workspace in slot `index`, starting its service if needed
+    ///
+    /// Returns false when there is no such slot or it is already shown.
+    pub fn switch(&mut self, index: usize) -> Result<bool> {
+        if index >= self.slots.len() || index == self.active {
+            return Ok(false);
+        }
+        self.start(index)?;
+        self.slots[index].unseen = 0;
+        self.active = index;
+        Ok(true)
+    }
+
+    /// Drive the services in the background and count what happened in them
+    ///
+    /// Their notifications are drained so the channels never fill up.
+    pub fn process_background(&mut self) -> Result<()> {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if index == self.active {
+                continue;
+            }
+            let Some(service) = slot.service.as_ref() else {
+                continue;
+            };
+            let mut service = service.lock();
+            service.process_events()?;
+            while let Some(notification) = service.poll_notification() {
+                if is_activity(&notification) {
+                    slot.unseen += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A badge for every slot, in slot order
+    pub fn badges(&self) -> Vec<WorkspaceBadge> {
+        self.slots
+            .iter()
+            .enumerate()
+            .map(|(index, slot)| WorkspaceBadge {
+                number: index + 1,
+                name: slot.name.clone(),
+                active: index == self.active,
+                running: slot.service.as_ref().map_or(0, |service| {
+                    service
+                        .lock()
+                        .agents()
+                        .iter()
+                        .filter(|agent| agent.status.is_running())
+                        .count()
+                }),
+                unseen: slot.unseen,
+            })
+            .collect()
+    }
+
+    /// Shut down every started service
+    pub fn shutdown(&mut self) -> Result<()> {
+        for service in self.slots.iter().filter_map(|slot| slot.service.as_ref()) {
+            service.lock().send(Command::Shutdown)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a background workspace's badge should count `notification`
+fn is_activity(notification: &Notification) -> bool {
+    match notification {
+        Notification::AgentSpawned { .. } | Notification::Error { .. } => true,
+        Notification::AgentStatusChanged { status, .. } => status.is_terminal(),
+        _ => false,
+    }
+}
+
+/// Last component of `path`, or the whole path for a root
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axiom_core::workspace::WorkspaceStorage;
+
+    #[test]
+    fn test_switching_keeps_services() {
+        let dir = tempfile::tempdir().unwrap();
+        let (home, shop) = (dir.path().join("home"), dir.path().join("shop"));
+        std::fs::create_dir_all(&home).unwrap();
+        std::fs::create_dir_all(&shop).unwrap();
+        let storage = WorkspaceStorage::with_config_dir(dir.path().join("config"));
+        let manager = WorkspaceManager::with_storage(AxiomConfig::default(), storage).unwrap();
+        manager.create_workspace("home", home.clone()).unwrap();
+        let shop_id = manager.create_workspace("shop", shop.clone()).unwrap().id;
+        manager.set_trusted(shop_id, true).unwrap();
+
+        // The starting directory isn't listed twice
+        let mut sessions =
+            WorkspaceSessions::new(AxiomConfig::default(), home.clone(), Some(manager)).unwrap();
+        let mut names: Vec<String> = sessions.badges().into_iter().map(|b| b.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["home", "shop"]);
+        assert!(sessions.badges()[0].active);
+        let shop_slot = sessions.slots.iter().position(|slot| slot.path == shop).unwrap();
+
+        assert!(!sessions.switch(5).unwrap());
+        assert!(!sessions.switch(0).unwrap());
+        assert!(sessions.switch(shop_slot).unwrap());
+        assert_eq!(sessions.path(), shop.as_path());
+
+        // The workspace's service comes from its manager, with its trust
+        let manager = sessions.manager.as_ref().unwrap();
+        let shared = manager.get_service(shop_id).unwrap();
+        assert!(Arc::ptr_eq(
+            sessions.slots[shop_slot].service.as_ref().unwrap(),
+            &shared
+        ));
+
+        // Back to the first workspace: both services are still there
+        assert!(sessions.switch(0).unwrap());
+        assert_eq!(sessions.service().cwd(), home.as_path());
+        assert!(sessions.slots.iter().all(|slot| slot.service.is_some()));
+
+        sessions.slots[shop_slot].unseen = 2;
+        sessions.process_background().unwrap();
+        assert_eq!(sessions.badges()[shop_slot].unseen, 2);
+        sessions.switch(shop_slot).unwrap();
+        assert_eq!(sessions.badges()[shop_slot].unseen, 0);
+        sessions.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_unregistered_directory_is_untrusted() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions =
+            WorkspaceSessions::new(AxiomConfig::default(), dir.path().to_path_buf(), None)
+                .unwrap();
+        assert_eq!(sessions.badges().len(), 1);
+        assert!(sessions.slots[0].id.is_none());
+        let refused = sessions.service().send(Command::invoke_cli_agent("claude", "hi"));
+        assert!(refused.unwrap_err().to_string().contains("untrusted"));
+    }
+}
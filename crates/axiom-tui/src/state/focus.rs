@@ -18,6 +18,17 @@ impl PanelId {
 
     /// Agents panel (right sidebar, shows spawned agents)
     pub const AGENTS: PanelId = PanelId(3);
+
+    /// Name of the panel's table of key bindings (`[keys.panels.<name>]`)
+    pub fn keymap_scope(self) -> &'static str {
+        match self {
+            PanelId::FILE_TREE => "file_tree",
+            PanelId::OUTPUT => "output",
+            PanelId::INPUT => "input",
+            PanelId::AGENTS => "agents",
+            _ => "",
+        }
+    }
 }
 
 /// Focus state management
@@ -308,9 +308,12 @@ impl SettingsModal {
             ProviderConfig {
                 enabled: true,
                 api_key: None,
+                api_key_command: None,
+                api_key_expires_at: None,
                 base_url: Some(self.ollama_url.clone()),
                 default_model: Some("gemma3:4b".to_string()),
                 models: Vec::new(),
+                extra_headers: HashMap::new(),
             },
         );
 
@@ -321,12 +324,15 @@ impl SettingsModal {
             ProviderConfig {
                 enabled: claude_key.is_some(),
                 api_key: claude_key,
+                api_key_command: None,
+                api_key_expires_at: None,
                 base_url: Some("https://api.anthropic.com".to_string()),
                 default_model: Some("claude-sonnet-4-20250514".to_string()),
                 models: vec![
                     "claude-sonnet-4-20250514".to_string(),
                     "claude-opus-4-20250514".to_string(),
                 ],
+                extra_headers: HashMap::new(),
             },
         );
 
@@ -337,6 +343,8 @@ impl SettingsModal {
             ProviderConfig {
                 enabled: gemini_key.is_some(),
                 api_key: gemini_key,
+                api_key_command: None,
+                api_key_expires_at: None,
                 base_url: Some("https://generativelanguage.googleapis.com".to_string()),
                 default_model: Some("gemini-2.0-flash".to_string()),
                 models: vec![
@@ -344,6 +352,7 @@ impl SettingsModal {
                     "gemini-1.5-pro".to_string(),
                     "gemini-1.5-flash".to_string(),
                 ],
+                extra_headers: HashMap::new(),
             },
         );
 
@@ -354,6 +363,8 @@ impl SettingsModal {
             ProviderConfig {
                 enabled: openai_key.is_some(),
                 api_key: openai_key,
+                api_key_command: None,
+                api_key_expires_at: None,
                 base_url: Some("https://api.openai.com".to_string()),
                 default_model: Some("gpt-4o".to_string()),
                 models: vec![
@@ -361,6 +372,7 @@ impl SettingsModal {
                     "gpt-4o-mini".to_string(),
                     "gpt-4-turbo".to_string(),
                 ],
+                extra_headers: HashMap::new(),
             },
         );
 
@@ -370,8 +382,18 @@ impl SettingsModal {
                 timeout: 120,
                 max_retries: 3,
                 providers,
+                rate_limits: Default::default(),
+                routing: Default::default(),
+                agents: Default::default(),
+                context: Default::default(),
+                max_parallel_tasks: 4,
             },
             cli_agents: Default::default(),
+            orchestration: Default::default(),
+            index: Default::default(),
+            lsp: Default::default(),
+            keys: Default::default(),
+            prompts: Default::default(),
         }
     }
 
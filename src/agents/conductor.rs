@@ -3,13 +3,25 @@
 //! The conductor receives user prompts and decides what agents to spawn.
 //! It uses the active LLM provider to analyze requests and plan agent execution.
 
+use crate::agents::inspect::{spawned_outcome, Exchange};
+use crate::agents::session::{core_message, tui_message};
 use crate::agents::{AgentSpawnRequest, AgentStatus, AgentType};
 use crate::events::Event;
-use crate::llm::{ChatMessage, MessageContent, ProviderRegistry, Role};
+use crate::llm::{ChatMessage, LlmError, LlmProvider, MessageContent, ProviderRegistry, Role};
 use crate::state::AgentId;
+use axiom_core::agents::{CancelToken, ContextManager, CANCEL_POLL};
+use axiom_core::config::ContextConfig;
+use axiom_core::llm::{ToolCall, ToolSpec};
+use axiom_core::prompts::CONDUCTOR;
+use axiom_core::usage::estimate_tokens;
+use axiom_core::{AgentRole, ErrorCode, PromptLibrary};
 use crossbeam_channel::Sender;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long a request waits for providers still starting up
+const PROVIDER_WAIT: Duration = Duration::from_secs(10);
 
 /// The conductor service
 ///
@@ -21,14 +33,27 @@ pub struct Conductor {
     /// Event sender for emitting events
     event_tx: Sender<Event>,
 
-    /// Conversation history for context
-    history: Vec<ChatMessage>,
-
-    /// Maximum history length to retain
-    max_history: usize,
+    /// Conversation history, fitted to the provider's window before each
+    /// request
+    context: Arc<Mutex<ContextManager>>,
 
     /// Persistent conductor agent ID (reused across inputs)
     agent_id: Option<AgentId>,
+
+    /// Stops the LLM request in flight; replaced after each cancel
+    cancel_token: CancelToken,
+
+    /// Context added to the next prompt, such as the output of a CLI agent
+    pending_context: Vec<String>,
+
+    /// The latest prompt, for sending it again
+    last_input: Option<String>,
+
+    /// Workspace templates replacing the built-in system prompt
+    prompts: PromptLibrary,
+
+    /// Whether each LLM request and its reply are captured for `/inspect`
+    inspect: bool,
 }
 
 impl Conductor {
@@ -37,12 +62,37 @@ impl Conductor {
         Self {
             llm_registry,
             event_tx,
-            history: Vec::new(),
-            max_history: 20,
+            context: Arc::new(Mutex::new(ContextManager::default())),
             agent_id: None,
+            cancel_token: CancelToken::new(),
+            pending_context: Vec::new(),
+            last_input: None,
+            prompts: PromptLibrary::default(),
+            inspect: false,
         }
     }
 
+    /// Build the system prompt from `prompts`, e.g. the current workspace's
+    /// templates after a switch or a `[prompts]` change
+    pub fn set_prompts(&mut self, prompts: PromptLibrary) {
+        self.prompts = prompts;
+    }
+
+    /// Use `config` for the history's token budget and summarization
+    pub fn set_context_config(&mut self, config: ContextConfig) {
+        self.context.lock().set_config(config);
+    }
+
+    /// Capture each LLM request and its reply for `/inspect`, or stop
+    pub fn set_inspect(&mut self, inspect: bool) {
+        self.inspect = inspect;
+    }
+
+    /// Whether LLM requests are being captured
+    pub fn inspecting(&self) -> bool {
+        self.inspect
+    }
+
     /// Set the persistent conductor agent ID
     pub fn set_agent_id(&mut self, id: AgentId) {
         self.agent_id = Some(id);
@@ -58,16 +108,15 @@ impl Conductor {
     /// Analyzes the request and spawns appropriate agents.
     /// Reuses the existing conductor agent if available.
     pub fn process(&mut self, input: String) {
-        // Add user message to history
-        self.history.push(ChatMessage {
+        // Add user message to history, led by any context added since the
+        // last one
+        let mut message = std::mem::take(&mut self.pending_context);
+        message.push(input.clone());
+        self.push(ChatMessage {
             role: Role::User,
-            content: MessageContent::Text(input.clone()),
+            content: MessageContent::Text(message.join("\n\n")),
         });
-
-        // Trim history if needed
-        while self.history.len() > self.max_history {
-            self.history.remove(0);
-        }
+        self.last_input = Some(input.clone());
 
         // Check if we have a persistent conductor agent
         if let Some(id) = self.agent_id {
@@ -107,61 +156,117 @@ impl Conductor {
 
         let event_tx = self.event_tx.clone();
         let llm_registry = self.llm_registry.clone();
-        let history = self.history.clone();
+        let context = self.context.clone();
         let task = task.to_string();
+        let cancel_token = self.cancel_token.clone();
+        let prompts = self.prompts.clone();
+        let inspect = self.inspect;
 
         // Run in background thread to not block UI
         std::thread::spawn(move || {
-            execute_conductor(agent_id, &task, history, llm_registry, event_tx);
+            execute_conductor(
+                agent_id,
+                prompts.render(CONDUCTOR, &build_system_prompt(), &task),
+                context,
+                llm_registry,
+                event_tx,
+                cancel_token,
+                inspect,
+            );
         });
     }
 
-    /// Get the conversation history
-    pub fn history(&self) -> &[ChatMessage] {
-        &self.history
+    /// Send the latest prompt again, if it got no reply, e.g. after the
+    /// provider rejected its key; returns whether it was sent
+    pub fn retry(&mut self) -> bool {
+        let unanswered = self
+            .context
+            .lock()
+            .messages()
+            .last()
+            .is_some_and(|message| message.role == axiom_core::llm::Role::User);
+        match (self.agent_id, self.last_input.clone()) {
+            (Some(id), Some(input)) if unanswered => {
+                let _ = self.event_tx.send(Event::AgentWake(id));
+                self.execute(id, &input);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Stop the LLM response being streamed, if any; the conductor goes
+    /// back to idle
+    pub fn cancel(&mut self) {
+        self.cancel_token.cancel();
+        self.cancel_token = CancelToken::new();
+    }
+
+    /// Get the conversation history, led by the summary of turns folded
+    /// away, if any
+    pub fn history(&self) -> Vec<ChatMessage> {
+        self.context
+            .lock()
+            .messages()
+            .iter()
+            .map(tui_message)
+            .collect()
     }
 
     /// Clear conversation history
     pub fn clear_history(&mut self) {
-        self.history.clear();
+        self.context.lock().clear();
+    }
+
+    /// Continue a saved conversation, reusing its conductor agent
+    pub fn restore(&mut self, history: Vec<ChatMessage>, agent_id: Option<AgentId>) {
+        self.context
+            .lock()
+            .restore(history.iter().map(core_message).collect());
+        self.agent_id = agent_id;
     }
 
     /// Add assistant response to history
     pub fn add_response(&mut self, response: String) {
-        self.history.push(ChatMessage {
+        self.push(ChatMessage {
             role: Role::Assistant,
             content: MessageContent::Text(response),
         });
+    }
 
-        while self.history.len() > self.max_history {
-            self.history.remove(0);
-        }
+    fn push(&self, message: ChatMessage) {
+        self.context.lock().push(core_message(&message));
+    }
+
+    /// Give the conductor `context` along with the next prompt
+    ///
+    /// It goes into the history ahead of the prompt, but isn't shown as
+    /// part of it.
+    pub fn add_context(&mut self, context: String) {
+        self.pending_context.push(context);
     }
 }
 
 /// Execute the conductor agent
 fn execute_conductor(
     agent_id: AgentId,
-    task: &str,
-    history: Vec<ChatMessage>,
+    system_prompt: String,
+    context: Arc<Mutex<ContextManager>>,
     llm_registry: Arc<RwLock<ProviderRegistry>>,
     event_tx: Sender<Event>,
+    cancel_token: CancelToken,
+    inspect: bool,
 ) {
-    // Build the system prompt for the conductor
-    let system_prompt = build_system_prompt();
-
-    // Build messages for the LLM
-    let mut messages = vec![ChatMessage {
-        role: Role::System,
-        content: MessageContent::Text(system_prompt),
-    }];
-
-    // Add conversation history
-    messages.extend(history);
+    // Providers are set up in the background at startup; a request made
+    // before they're ready waits for them
+    let deadline = Instant::now() + PROVIDER_WAIT;
+    while llm_registry.read().is_loading() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(20));
+    }
 
-    // Get the active provider
+    // Get the orchestrator's provider
     let registry = llm_registry.read();
-    let provider = match registry.active() {
+    let provider = match registry.for_role(AgentRole::Orchestrator) {
         Some(p) => p.clone(),
         None => {
             let _ = event_tx.send(Event::AgentOutput {
@@ -178,11 +283,69 @@ fn execute_conductor(
     };
     drop(registry);
 
-    // Create a channel for LLM responses
-    let (llm_tx, llm_rx) = crossbeam_channel::unbounded();
+    let tools = conductor_tools();
+
+    // Make room for the prompt and tools, then add as much of the
+    // conversation as fits the provider's window
+    let overhead = estimate_tokens(&system_prompt)
+        + tools
+            .iter()
+            .map(|tool| {
+                estimate_tokens(&tool.description) + estimate_tokens(&tool.parameters.to_string())
+            })
+            .sum::<u64>();
+    fit_context(
+        &context,
+        provider.as_ref(),
+        overhead as usize,
+        &cancel_token,
+    );
+    let mut messages = vec![ChatMessage {
+        role: Role::System,
+        content: MessageContent::Text(system_prompt),
+    }];
+    messages.extend(context.lock().messages().iter().map(tui_message));
+
+    let provider_id = provider.id().to_string();
+    let _ = event_tx.send(Event::AgentStreamStart {
+        id: agent_id,
+        provider: provider_id.clone(),
+        model: provider.model(),
+    });
+
+    let mut exchange = inspect.then(|| {
+        let capabilities = provider.capabilities();
+        let parameters = [
+            ("provider", provider_id.clone()),
+            ("model", provider.model()),
+            ("streaming", capabilities.streaming.to_string()),
+            ("max_context", capabilities.max_context.to_string()),
+            ("max_output", capabilities.max_output.to_string()),
+        ];
+        let parameters = parameters
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        Exchange::new(parameters, &messages)
+    });
+    let sent_at = Instant::now();
+    // Send the captured exchange, if any, with its reply and how it ended
+    let mut report = |response: &str, outcome: String| {
+        if let Some(mut exchange) = exchange.take() {
+            exchange.finish(response, outcome, sent_at.elapsed());
+            let _ = event_tx.send(Event::LlmExchange {
+                id: agent_id,
+                exchange: Box::new(exchange),
+            });
+        }
+    };
 
-    // Send to LLM
-    provider.send_message(messages, llm_tx);
+    // The call blocks until the whole reply is in; wait for it off-thread so
+    // a cancel doesn't have to
+    let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+    std::thread::spawn(move || {
+        let _ = reply_tx.send(provider.chat_with_tools(messages, &tools));
+    });
 
     // Start Axiom response box (chat interface style)
     let _ = event_tx.send(Event::AgentOutput {
@@ -190,27 +353,50 @@ fn execute_conductor(
         chunk: ">>>axiom\n".to_string(),
     });
 
-    // Stream responses to agent output
-    let mut full_response = String::new();
     loop {
-        match llm_rx.recv() {
-            Ok(Event::LlmChunk(chunk)) => {
-                full_response.push_str(&chunk);
-                let _ = event_tx.send(Event::AgentOutput {
-                    id: agent_id,
-                    chunk,
-                });
-            }
-            Ok(Event::LlmDone) => {
+        if cancel_token.is_cancelled() {
+            // The reply is dropped when it arrives
+            let _ = event_tx.send(Event::AgentOutput {
+                id: agent_id,
+                chunk: "\n[cancelled]\n<<<\n\n".to_string(),
+            });
+            report("", "Cancelled".to_string());
+            let _ = event_tx.send(Event::AgentUpdate {
+                id: agent_id,
+                status: AgentStatus::Idle,
+            });
+            break;
+        }
+        match reply_rx.recv_timeout(CANCEL_POLL) {
+            Ok(Ok(reply)) => {
+                // Show the answer and the agents it starts
+                let mut response = reply.text.trim().to_string();
+                for call in &reply.calls {
+                    if !response.is_empty() {
+                        response.push('\n');
+                    }
+                    response.push_str(&format!("→ {}", call_summary(call)));
+                }
                 // Close Axiom response box with separator for next Q&A pair
                 let _ = event_tx.send(Event::AgentOutput {
                     id: agent_id,
-                    chunk: "\n<<<\n\n".to_string(),
+                    chunk: format!("{}\n<<<\n\n", response),
                 });
                 // Send response to add to conductor history (for LLM context)
-                let _ = event_tx.send(Event::ConductorResponse(full_response.clone()));
-                // Parse response for agent spawn commands (pass conductor_id as parent)
-                parse_and_spawn_agents(&full_response, &event_tx, agent_id);
+                let _ = event_tx.send(Event::ConductorResponse {
+                    id: agent_id,
+                    response: response.clone(),
+                });
+                // Spawn the requested agents (conductor_id as parent)
+                let spawned: Vec<AgentSpawnRequest> = reply
+                    .calls
+                    .iter()
+                    .filter_map(|call| spawn_request(call, agent_id))
+                    .collect();
+                for request in &spawned {
+                    let _ = event_tx.send(Event::AgentSpawn(request.clone()));
+                }
+                report(&response, spawned_outcome(&spawned));
                 // Set to Idle so conductor can be reused for next input
                 let _ = event_tx.send(Event::AgentUpdate {
                     id: agent_id,
@@ -218,12 +404,27 @@ fn execute_conductor(
                 });
                 break;
             }
-            Ok(Event::LlmError(e)) => {
+            Ok(Err(e)) => {
+                // An error streamed back by the provider arrives wrapped;
+                // its own message says what went wrong
+                let e = match e {
+                    LlmError::Internal(message) => message,
+                    e => e.to_string(),
+                };
                 // Close box and show error
                 let _ = event_tx.send(Event::AgentOutput {
                     id: agent_id,
                     chunk: format!("\nError: {}\n<<<\n", e),
                 });
+                report("", format!("Error: {}", e));
+                // A rejected key gets its own event, so the user is asked
+                // for another and the prompt is sent again
+                if ErrorCode::for_llm_message(&e) == ErrorCode::ProviderAuthFailed {
+                    let _ = event_tx.send(Event::ProviderAuthFailed {
+                        provider_id,
+                        message: e,
+                    });
+                }
                 // Set to Idle so conductor can be reused
                 let _ = event_tx.send(Event::AgentUpdate {
                     id: agent_id,
@@ -231,12 +432,15 @@ fn execute_conductor(
                 });
                 break;
             }
-            Err(_) => {
+            Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
                 // Close box on error
                 let _ = event_tx.send(Event::AgentOutput {
                     id: agent_id,
                     chunk: "\n<<<\n".to_string(),
                 });
+                let outcome = "The provider stopped without finishing".to_string();
+                report("", outcome);
                 // Set to Idle so conductor can be reused
                 let _ = event_tx.send(Event::AgentUpdate {
                     id: agent_id,
@@ -244,136 +448,197 @@ fn execute_conductor(
                 });
                 break;
             }
+        }
+    }
+}
+
+/// Drop or summarize the oldest turns of `context` until it fits
+/// `provider`'s window alongside `overhead` tokens of system prompt
+fn fit_context(
+    context: &Mutex<ContextManager>,
+    provider: &dyn LlmProvider,
+    overhead: usize,
+    cancel_token: &CancelToken,
+) {
+    let (count, request) = {
+        let context = context.lock();
+        let budget = context
+            .budget(provider.id(), provider.capabilities().max_context)
+            .saturating_sub(overhead);
+        let count = context.overflow(budget);
+        if count == 0 {
+            return;
+        }
+        let request = context
+            .config()
+            .summarize
+            .then(|| context.summary_request(count));
+        (count, request)
+    };
+
+    // Summarize without holding the lock; the request can take a while
+    let summary = request
+        .and_then(|messages| summarize(provider, messages, cancel_token))
+        .filter(|text| !text.trim().is_empty());
+
+    let mut context = context.lock();
+    match summary {
+        Some(summary) => context.fold(count, &summary),
+        None => context.truncate(count),
+    }
+}
+
+/// The provider's whole reply to `messages`, or None if it fails or the
+/// request is cancelled first
+fn summarize(
+    provider: &dyn LlmProvider,
+    messages: Vec<axiom_core::ChatMessage>,
+    cancel_token: &CancelToken,
+) -> Option<String> {
+    let (tx, rx) = crossbeam_channel::unbounded();
+    provider.send_message(messages.iter().map(tui_message).collect(), tx);
+    let mut reply = String::new();
+    while !cancel_token.is_cancelled() {
+        match rx.recv_timeout(CANCEL_POLL) {
+            Ok(Event::LlmChunk(chunk)) => reply.push_str(&chunk),
+            Ok(Event::LlmDone) => return Some(reply),
+            Ok(Event::LlmError(_)) => return None,
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => return None,
             _ => {}
         }
     }
+    None
 }
 
 /// Build the system prompt for the conductor
 fn build_system_prompt() -> String {
     r#"You are an AI assistant integrated into Axiom, a terminal-based IDE. You help users with code, shell commands, and file operations.
 
-When you need to perform actions, you can spawn specialized agents:
-
-1. **Shell Agent**: Execute shell commands
-   Format: `@shell <command>`
-   Example: `@shell ls -la`
+When you need to perform actions, call the tools for Axiom's specialized agents:
 
-2. **Coder Agent**: Modify or create code files
-   Format: `@coder <description>` followed by code blocks
-   Example:
-   @coder Update the main function
-   ```rust:src/main.rs
-   fn main() {
-       println!("Hello!");
-   }
-   ```
+1. **shell**: Execute a shell command, e.g. `ls -la`
+2. **coder**: Modify or create a code file; pass the file's full new content with its path
+3. **search**: Search files or content, e.g. `TODO`
+4. **fileops**: Read, write, or manage files, e.g. `read src/main.rs`.
+   Read only what you need: `read src/main.rs:40-60` or `read src/main.rs#run`.
+5. **service**: Keep a long-running process alive (dev servers, watchers), e.g.
+   `--port 3000 npm run dev`. Use this instead of shell for anything that doesn't exit on its own.
 
-3. **Search Agent**: Search files or content
-   Format: `@search <query>`
-   Example: `@search TODO`
-
-4. **FileOps Agent**: Read, write, or manage files
-   Format: `@fileops <operation> <path>`
-   Example: `@fileops read src/main.rs`
-
-You can spawn multiple agents in a single response. Always explain what you're doing before spawning agents.
+You can call several tools in a single response. Always explain what you're doing alongside the calls.
 
 If the user's request doesn't require any agent actions, just respond conversationally."#.to_string()
 }
 
-/// Parse LLM response and spawn any requested agents
-fn parse_and_spawn_agents(response: &str, event_tx: &Sender<Event>, parent_id: AgentId) {
-    let mut lines = response.lines().peekable();
+/// The agents the conductor can start, as tools
+fn conductor_tools() -> Vec<ToolSpec> {
+    vec![
+        ToolSpec::with_string_args(
+            "shell",
+            "Run a shell command that exits on its own",
+            &[("command", "Command line to run")],
+            &["command"],
+        ),
+        ToolSpec::with_string_args(
+            "coder",
+            "Create or change a code file",
+            &[
+                ("description", "What the change does"),
+                ("path", "File to write, relative to the workspace"),
+                ("content", "Full new content of the file"),
+            ],
+            &["description"],
+        ),
+        ToolSpec::with_string_args(
+            "search",
+            "Search file names and contents",
+            &[("query", "Text to search for")],
+            &["query"],
+        ),
+        ToolSpec::with_string_args(
+            "fileops",
+            "Read, write or manage files",
+            &[(
+                "operation",
+                "Operation and path, e.g. `read src/main.rs:40-60`",
+            )],
+            &["operation"],
+        ),
+        ToolSpec::with_string_args(
+            "service",
+            "Keep a long-running process alive (dev servers, watchers)",
+            &[(
+                "spec",
+                "`[--port N | --health URL] [--no-restart] <command>`",
+            )],
+            &["spec"],
+        ),
+    ]
+}
 
-    while let Some(line) = lines.next() {
-        let trimmed = line.trim();
+/// One-line description of a tool call for the conductor's output
+fn call_summary(call: &ToolCall) -> String {
+    let detail = ["command", "description", "query", "operation", "spec"]
+        .iter()
+        .find_map(|key| call.arg(key))
+        .unwrap_or_default();
+    format!("{}: {}", call.name, detail)
+}
 
-        // Check for shell agent
-        if let Some(cmd) = trimmed.strip_prefix("@shell ") {
-            let request = AgentSpawnRequest {
-                agent_type: AgentType::Shell,
-                name: "Shell".to_string(),
-                description: truncate(cmd, 50),
-                parameters: Some(cmd.to_string()),
-                parent_id: Some(parent_id),
-            };
-            let _ = event_tx.send(Event::AgentSpawn(request));
+/// The agent a conductor tool call asks for, if the call is well-formed
+fn spawn_request(call: &ToolCall, parent_id: AgentId) -> Option<AgentSpawnRequest> {
+    let (agent_type, name, description, parameters) = match call.name.as_str() {
+        "shell" => {
+            let cmd = call.arg("command")?;
+            (
+                AgentType::Shell,
+                "Shell",
+                truncate(cmd, 50),
+                cmd.to_string(),
+            )
         }
-
-        // Check for search agent
-        if let Some(query) = trimmed.strip_prefix("@search ") {
-            let request = AgentSpawnRequest {
-                agent_type: AgentType::Search,
-                name: "Search".to_string(),
-                description: format!("Searching: {}", truncate(query, 40)),
-                parameters: Some(query.to_string()),
-                parent_id: Some(parent_id),
-            };
-            let _ = event_tx.send(Event::AgentSpawn(request));
+        "search" => {
+            let query = call.arg("query")?;
+            let description = format!("Searching: {}", truncate(query, 40));
+            (AgentType::Search, "Search", description, query.to_string())
         }
-
-        // Check for fileops agent
-        if let Some(op) = trimmed.strip_prefix("@fileops ") {
-            let request = AgentSpawnRequest {
-                agent_type: AgentType::FileOps,
-                name: "FileOps".to_string(),
-                description: truncate(op, 50),
-                parameters: Some(op.to_string()),
-                parent_id: Some(parent_id),
-            };
-            let _ = event_tx.send(Event::AgentSpawn(request));
-        }
-
-        // Check for coder agent (may span multiple lines with code blocks)
-        if let Some(desc) = trimmed.strip_prefix("@coder ") {
-            let mut code_content = String::new();
-            let mut in_code_block = false;
-            let mut current_path = String::new();
-
-            // Collect following code blocks
-            while let Some(&next_line) = lines.peek() {
-                if next_line.trim().starts_with("```") && !in_code_block {
-                    in_code_block = true;
-                    // Extract path from code block header
-                    let header = next_line.trim().trim_start_matches('`');
-                    if let Some(path_part) = header.split(':').nth(1) {
-                        current_path = path_part.to_string();
-                    } else if header.contains('/') {
-                        current_path = header.to_string();
-                    }
-                    lines.next();
-                } else if next_line.trim() == "```" && in_code_block {
-                    in_code_block = false;
-                    lines.next();
-                    break;
-                } else if in_code_block {
-                    code_content.push_str(next_line);
-                    code_content.push('\n');
-                    lines.next();
-                } else if next_line.trim().starts_with('@') {
-                    break;
-                } else {
-                    lines.next();
+        "fileops" => {
+            let op = call.arg("operation")?;
+            (
+                AgentType::FileOps,
+                "FileOps",
+                truncate(op, 50),
+                op.to_string(),
+            )
+        }
+        "service" => {
+            let spec = call.arg("spec")?;
+            (
+                AgentType::Service,
+                "Service",
+                truncate(spec, 50),
+                spec.to_string(),
+            )
+        }
+        "coder" => {
+            let desc = call.arg("description")?;
+            // The coder takes `path|content` when given the new file
+            let params = match (call.arg("path"), call.arg("content")) {
+                (Some(path), Some(content)) if !path.is_empty() => {
+                    format!("{}|{}", path, content)
                 }
-            }
-
-            let params = if !current_path.is_empty() {
-                format!("{}|{}", current_path, code_content)
-            } else {
-                desc.to_string()
-            };
-
-            let request = AgentSpawnRequest {
-                agent_type: AgentType::Coder,
-                name: "Coder".to_string(),
-                description: truncate(desc, 50),
-                parameters: Some(params),
-                parent_id: Some(parent_id),
+                _ => desc.to_string(),
             };
-            let _ = event_tx.send(Event::AgentSpawn(request));
+            (AgentType::Coder, "Coder", truncate(desc, 50), params)
         }
-    }
+        _ => return None,
+    };
+    Some(AgentSpawnRequest {
+        agent_type,
+        name: name.to_string(),
+        description,
+        parameters: Some(parameters),
+        parent_id: Some(parent_id),
+    })
 }
 
 /// Truncate a string to the specified length
@@ -388,6 +653,7 @@ fn truncate(s: &str, max_len: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_truncate() {
@@ -405,78 +671,71 @@ mod tests {
         assert_eq!(truncate("", 5), "");
     }
 
-    #[test]
-    fn test_parse_shell_command() {
-        let (tx, rx) = crossbeam_channel::unbounded();
-        let response = "@shell ls -la";
-        let parent_id = AgentId::new(1);
-        parse_and_spawn_agents(response, &tx, parent_id);
-
-        if let Ok(Event::AgentSpawn(req)) = rx.try_recv() {
-            assert_eq!(req.agent_type, AgentType::Shell);
-            assert_eq!(req.parameters, Some("ls -la".to_string()));
-            assert_eq!(req.parent_id, Some(parent_id));
-        } else {
-            panic!("Expected AgentSpawn event");
+    fn call(name: &str, arguments: serde_json::Value) -> ToolCall {
+        ToolCall {
+            id: "call_0".to_string(),
+            name: name.to_string(),
+            arguments,
         }
     }
 
     #[test]
-    fn test_parse_search_command() {
-        let (tx, rx) = crossbeam_channel::unbounded();
-        let response = "@search TODO comments";
+    fn test_shell_call() {
         let parent_id = AgentId::new(1);
-        parse_and_spawn_agents(response, &tx, parent_id);
-
-        if let Ok(Event::AgentSpawn(req)) = rx.try_recv() {
-            assert_eq!(req.agent_type, AgentType::Search);
-            assert_eq!(req.parameters, Some("TODO comments".to_string()));
-        } else {
-            panic!("Expected AgentSpawn event");
-        }
+        let req = spawn_request(&call("shell", json!({ "command": "ls -la" })), parent_id).unwrap();
+        assert_eq!(req.agent_type, AgentType::Shell);
+        assert_eq!(req.parameters, Some("ls -la".to_string()));
+        assert_eq!(req.parent_id, Some(parent_id));
+        assert_eq!(
+            call_summary(&call("shell", json!({ "command": "ls -la" }))),
+            "shell: ls -la"
+        );
     }
 
     #[test]
-    fn test_parse_fileops_command() {
-        let (tx, rx) = crossbeam_channel::unbounded();
-        let response = "@fileops read src/main.rs";
-        let parent_id = AgentId::new(1);
-        parse_and_spawn_agents(response, &tx, parent_id);
-
-        if let Ok(Event::AgentSpawn(req)) = rx.try_recv() {
-            assert_eq!(req.agent_type, AgentType::FileOps);
-            assert_eq!(req.parameters, Some("read src/main.rs".to_string()));
-        } else {
-            panic!("Expected AgentSpawn event");
-        }
+    fn test_search_and_fileops_calls() {
+        let req = spawn_request(
+            &call("search", json!({ "query": "TODO comments" })),
+            AgentId::new(1),
+        )
+        .unwrap();
+        assert_eq!(req.agent_type, AgentType::Search);
+        assert_eq!(req.parameters, Some("TODO comments".to_string()));
+
+        let op = json!({ "operation": "read src/main.rs" });
+        let req = spawn_request(&call("fileops", op), AgentId::new(1)).unwrap();
+        assert_eq!(req.agent_type, AgentType::FileOps);
+        assert_eq!(req.parameters, Some("read src/main.rs".to_string()));
     }
 
     #[test]
-    fn test_parse_multiple_agents() {
-        let (tx, rx) = crossbeam_channel::unbounded();
-        let response = r#"Let me help you with that.
-
-@shell git status
-@search TODO comments"#;
-        let parent_id = AgentId::new(1);
-        parse_and_spawn_agents(response, &tx, parent_id);
-
-        let mut count = 0;
-        while rx.try_recv().is_ok() {
-            count += 1;
-        }
-        assert_eq!(count, 2);
+    fn test_service_call() {
+        let spec = json!({ "spec": "--port 3000 npm run dev" });
+        let req = spawn_request(&call("service", spec), AgentId::new(1)).unwrap();
+        assert_eq!(req.agent_type, AgentType::Service);
+        assert_eq!(req.parameters, Some("--port 3000 npm run dev".to_string()));
     }
 
     #[test]
-    fn test_parse_no_agents() {
-        let (tx, rx) = crossbeam_channel::unbounded();
-        let response = "Here's a simple explanation without any agent commands.";
-        let parent_id = AgentId::new(1);
-        parse_and_spawn_agents(response, &tx, parent_id);
-
-        // Should not spawn any agents
-        assert!(rx.try_recv().is_err());
+    fn test_coder_and_bad_calls() {
+        let args = json!({
+            "description": "Update main",
+            "path": "src/main.rs",
+            "content": "fn main() {}\n"
+        });
+        let req = spawn_request(&call("coder", args), AgentId::new(1)).unwrap();
+        assert_eq!(req.agent_type, AgentType::Coder);
+        assert_eq!(
+            req.parameters,
+            Some("src/main.rs|fn main() {}\n".to_string())
+        );
+
+        // Missing arguments and unknown tools start nothing
+        assert!(spawn_request(&call("shell", json!({})), AgentId::new(1)).is_none());
+        assert!(spawn_request(&call("deploy", json!({})), AgentId::new(1)).is_none());
+
+        let names: Vec<String> = conductor_tools().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, ["shell", "coder", "search", "fileops", "service"]);
     }
 
     #[test]
@@ -527,19 +786,177 @@ mod tests {
         assert!(conductor.history().is_empty());
     }
 
+    /// Replies to every request with the same summary
+    struct SummaryProvider;
+
+    impl LlmProvider for SummaryProvider {
+        fn id(&self) -> &str {
+            "mock"
+        }
+
+        fn name(&self) -> &str {
+            "Mock"
+        }
+
+        fn model(&self) -> String {
+            "mock-model".to_string()
+        }
+
+        fn set_model(&self, _model: &str) -> Result<(), crate::llm::LlmError> {
+            Ok(())
+        }
+
+        fn list_models(&self) -> Result<Vec<String>, crate::llm::LlmError> {
+            Ok(Vec::new())
+        }
+
+        fn capabilities(&self) -> crate::llm::ProviderCapabilities {
+            crate::llm::ProviderCapabilities::default()
+        }
+
+        fn status(&self) -> crate::llm::ProviderStatus {
+            crate::llm::ProviderStatus::Ready
+        }
+
+        fn send_message(&self, _messages: Vec<ChatMessage>, event_tx: Sender<Event>) {
+            let _ = event_tx.send(Event::LlmChunk("The user renamed foo.".to_string()));
+            let _ = event_tx.send(Event::LlmDone);
+        }
+    }
+
+    /// Rejects its key on the first request and answers the rest
+    #[derive(Default)]
+    struct RejectingProvider {
+        requests: std::sync::atomic::AtomicUsize,
+    }
+
+    impl LlmProvider for RejectingProvider {
+        fn id(&self) -> &str {
+            "openai"
+        }
+
+        fn name(&self) -> &str {
+            "Rejecting"
+        }
+
+        fn model(&self) -> String {
+            "mock-model".to_string()
+        }
+
+        fn set_model(&self, _model: &str) -> Result<(), crate::llm::LlmError> {
+            Ok(())
+        }
+
+        fn list_models(&self) -> Result<Vec<String>, crate::llm::LlmError> {
+            Ok(Vec::new())
+        }
+
+        fn capabilities(&self) -> crate::llm::ProviderCapabilities {
+            crate::llm::ProviderCapabilities::default()
+        }
+
+        fn status(&self) -> crate::llm::ProviderStatus {
+            crate::llm::ProviderStatus::Ready
+        }
+
+        fn send_message(&self, _messages: Vec<ChatMessage>, event_tx: Sender<Event>) {
+            let request = self
+                .requests
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if request == 0 {
+                let _ = event_tx.send(Event::LlmError("API error (401): bad key".to_string()));
+            } else {
+                let _ = event_tx.send(Event::LlmChunk("Hello!".to_string()));
+                let _ = event_tx.send(Event::LlmDone);
+            }
+        }
+    }
+
+    /// The first event `rx` gets that `pick` accepts
+    fn next_matching<T>(
+        rx: &crossbeam_channel::Receiver<Event>,
+        pick: impl Fn(Event) -> Option<T>,
+    ) -> T {
+        loop {
+            let event = rx
+                .recv_timeout(Duration::from_secs(10))
+                .expect("no matching event");
+            if let Some(found) = pick(event) {
+                return found;
+            }
+        }
+    }
+
     #[test]
-    fn test_conductor_history_trimming() {
+    fn test_conductor_retries_rejected_prompt_once() {
+        let provider = Arc::new(RejectingProvider::default());
+        let mut registry = ProviderRegistry::new();
+        registry.register(provider.clone());
+        registry.set_active("openai").unwrap();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut conductor = Conductor::new(Arc::new(RwLock::new(registry)), tx);
+        conductor.set_agent_id(AgentId::new(1));
+
+        // Nothing was asked yet
+        assert!(!conductor.retry());
+
+        conductor.process("Say hello".to_string());
+        let provider_id = next_matching(&rx, |event| match event {
+            Event::ProviderAuthFailed { provider_id, .. } => Some(provider_id),
+            _ => None,
+        });
+        assert_eq!(provider_id, "openai");
+
+        assert!(conductor.retry());
+        let reply = next_matching(&rx, |event| match event {
+            Event::ConductorResponse { response, .. } => Some(response),
+            _ => None,
+        });
+        assert_eq!(reply, "Hello!");
+        conductor.add_response(reply);
+
+        // The prompt got its answer, so it isn't sent a third time
+        assert!(!conductor.retry());
+        let requests = provider.requests.load(std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(requests, 2);
+        let history = conductor.history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].text(), "Say hello");
+    }
+
+    /// A conductor with five 300-token replies and a 1000-token window
+    fn long_conversation(summarize: bool) -> Conductor {
         let registry = Arc::new(RwLock::new(ProviderRegistry::new()));
         let (tx, _rx) = crossbeam_channel::unbounded();
         let mut conductor = Conductor::new(registry, tx);
-
-        // Add more than max_history responses
-        for i in 0..25 {
-            conductor.add_response(format!("Response {}", i));
+        let mut config = ContextConfig {
+            reserve_tokens: 0,
+            summarize,
+            ..Default::default()
+        };
+        config.max_tokens.insert("mock".to_string(), 1000);
+        conductor.set_context_config(config);
+        for _ in 0..5 {
+            conductor.add_response("abcd".repeat(300));
         }
+        conductor
+    }
 
-        // History should be trimmed to max_history (20)
-        assert!(conductor.history().len() <= conductor.max_history);
+    #[test]
+    fn test_conductor_history_fits_window() {
+        let conductor = long_conversation(false);
+        fit_context(&conductor.context, &SummaryProvider, 0, &CancelToken::new());
+        assert_eq!(conductor.history().len(), 3);
+    }
+
+    #[test]
+    fn test_conductor_history_summarized() {
+        let conductor = long_conversation(true);
+        fit_context(&conductor.context, &SummaryProvider, 0, &CancelToken::new());
+        let history = conductor.history();
+        assert_eq!(history[0].role, Role::System);
+        assert!(history[0].text().ends_with("The user renamed foo."));
+        assert_eq!(history.len(), 2);
     }
 
     #[test]
@@ -548,9 +965,8 @@ mod tests {
 
         // Should contain key instructions
         assert!(prompt.contains("Axiom"));
-        assert!(prompt.contains("@shell"));
-        assert!(prompt.contains("@coder"));
-        assert!(prompt.contains("@search"));
-        assert!(prompt.contains("@fileops"));
+        for tool in conductor_tools() {
+            assert!(prompt.contains(&format!("**{}**", tool.name)));
+        }
     }
 }
@@ -1,14 +1,26 @@
 //! Agent executor for running spawned agents
 //!
-//! Handles the actual execution of different agent types (Shell, Coder, Search, FileOps).
+//! Handles the actual execution of different agent types (Shell, Coder, Search, FileOps,
+//! TestGen, DocGen, Fixer, Verifier, Service).
 
 use crate::agents::{AgentRegistry, AgentSpawnRequest, AgentStatus, AgentType};
 use crate::events::Event;
 use crate::state::AgentId;
+use axiom_core::agents::{cancel_after, kill_on_cancel, CancelToken};
+use axiom_core::context::{self, ContextBudget, ContextTarget};
+use axiom_core::files::write_atomic;
+use axiom_core::orchestration::{
+    apply_llm_change, apply_operation, failure_report, unified_diff, verify_and_repair,
+    AgentOperation,
+};
+use axiom_core::{
+    guard_content, ExecPolicy, GuardConfig, LlmSettings, OrchestrationService, PendingWrite,
+    PolicyViolation, PromptLibrary, WorkspaceConfig,
+};
 use crossbeam_channel::Sender;
 use parking_lot::RwLock;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::Arc;
 
@@ -48,10 +60,14 @@ impl Executor {
         let request = request.clone();
 
         // Mark agent as running
-        {
+        let cancel_token = {
             let mut registry = agent_registry.write();
             registry.start(agent_id);
-        }
+            registry
+                .get(agent_id)
+                .map(|agent| agent.cancel_token.clone())
+                .unwrap_or_default()
+        };
 
         // Update status
         let _ = event_tx.send(Event::AgentUpdate {
@@ -62,10 +78,24 @@ impl Executor {
         // Execute based on type
         std::thread::spawn(move || {
             let result = match request.agent_type {
-                AgentType::Shell => execute_shell(agent_id, &request, &cwd, &event_tx),
+                AgentType::Shell => {
+                    execute_shell(agent_id, &request, &cwd, &event_tx, cancel_token)
+                }
                 AgentType::Coder => execute_coder(agent_id, &request, &cwd, &event_tx),
                 AgentType::Search => execute_search(agent_id, &request, &cwd, &event_tx),
                 AgentType::FileOps => execute_fileops(agent_id, &request, &cwd, &event_tx),
+                AgentType::TestGen => execute_gen_tests(agent_id, &request, &cwd, &event_tx),
+                AgentType::DocGen => execute_gen_docs(agent_id, &request, &cwd, &event_tx),
+                AgentType::Fixer => execute_fix(agent_id, &request, &cwd, &event_tx),
+                AgentType::Verifier => execute_verify(agent_id, &request, &cwd, &event_tx),
+                AgentType::Service => super::service_agent::run_service(
+                    agent_id,
+                    request.parameters.as_deref().unwrap_or(""),
+                    &cwd,
+                    &agent_registry,
+                    &event_tx,
+                    cancel_token,
+                ),
                 AgentType::Conductor => {
                     // Conductor is handled by the Conductor service
                     Ok(())
@@ -83,8 +113,15 @@ impl Executor {
                 }
             };
 
-            // Update agent status based on result
+            // Update agent status based on result, unless it was cancelled
             let mut registry = agent_registry.write();
+            let cancelled = registry
+                .get(agent_id)
+                .map(|a| a.status == AgentStatus::Cancelled)
+                .unwrap_or(true);
+            if cancelled {
+                return;
+            }
             match result {
                 Ok(()) => registry.complete(agent_id),
                 Err(e) => registry.error(agent_id, e),
@@ -101,6 +138,7 @@ fn execute_shell(
     request: &AgentSpawnRequest,
     cwd: &PathBuf,
     event_tx: &Sender<Event>,
+    cancel_token: CancelToken,
 ) -> Result<(), String> {
     let cmd = request.parameters.as_deref().unwrap_or("");
     if cmd.is_empty() {
@@ -112,53 +150,66 @@ fn execute_shell(
         chunk: format!("$ {}\n", cmd),
     });
 
-    // Execute the command
-    let output = Command::new("sh")
-        .arg("-c")
-        .arg(cmd)
-        .current_dir(cwd)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn();
+    // The workspace's policy may refuse the command outright
+    let policy = ExecPolicy::load(cwd);
+    let mut command = policy
+        .command(cwd, cmd)
+        .map_err(|violation| format!("Command blocked: {}", violation))?;
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
 
-    match output {
-        Ok(mut child) => {
-            // Stream stdout
-            if let Some(stdout) = child.stdout.take() {
-                let reader = BufReader::new(stdout);
-                for line in reader.lines() {
-                    if let Ok(line) = line {
-                        let _ = event_tx.send(Event::AgentOutput {
-                            id: agent_id,
-                            chunk: format!("{}\n", line),
-                        });
-                    }
-                }
-            }
+    // Execute the command in its own process group, so cancelling stops
+    // everything it started
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
 
-            // Collect stderr
-            if let Some(stderr) = child.stderr.take() {
-                let reader = BufReader::new(stderr);
-                for line in reader.lines() {
-                    if let Ok(line) = line {
-                        let _ = event_tx.send(Event::AgentOutput {
-                            id: agent_id,
-                            chunk: format!("{}\n", line),
-                        });
-                    }
+    match command.spawn() {
+        Ok(mut child) => {
+            let _guard = kill_on_cancel(child.id(), cancel_token);
+
+            // Stopped by the policy when it runs too long or prints too much
+            let stop = CancelToken::new();
+            let _stop_guard = kill_on_cancel(child.id(), stop.clone());
+            let _timer = policy.timeout().map(|t| cancel_after(stop.clone(), t));
+            let limit = policy.max_output().unwrap_or(usize::MAX);
+            let mut printed = 0usize;
+
+            // Stream stdout, then collect stderr
+            let stdout = child.stdout.take().map(|out| BufReader::new(out).lines());
+            let stderr = child.stderr.take().map(|err| BufReader::new(err).lines());
+            for line in stdout
+                .into_iter()
+                .flatten()
+                .chain(stderr.into_iter().flatten())
+            {
+                let Ok(line) = line else { continue };
+                printed = printed.saturating_add(line.len() + 1);
+                if printed > limit {
+                    stop.cancel();
+                    continue;
                 }
+                let _ = event_tx.send(Event::AgentOutput {
+                    id: agent_id,
+                    chunk: format!("{}\n", line),
+                });
             }
 
             // Wait for completion
-            match child.wait() {
-                Ok(status) => {
-                    if status.success() {
-                        Ok(())
-                    } else {
-                        Err(format!("Command exited with status: {}", status))
+            let status = child
+                .wait()
+                .map_err(|e| format!("Failed to wait for command: {}", e))?;
+            if stop.is_cancelled() {
+                let violation = if printed > limit {
+                    PolicyViolation::OutputLimit { bytes: limit }
+                } else {
+                    PolicyViolation::Timeout {
+                        secs: policy.timeout_secs,
                     }
-                }
-                Err(e) => Err(format!("Failed to wait for command: {}", e)),
+                };
+                Err(format!("Command blocked: {}", violation))
+            } else if status.success() {
+                Ok(())
+            } else {
+                Err(format!("Command exited with status: {}", status))
             }
         }
         Err(e) => Err(format!("Failed to execute command: {}", e)),
@@ -187,22 +238,33 @@ fn execute_coder(
             chunk: format!("Writing to: {}\n", file_path.display()),
         });
 
-        // Create parent directories if needed
-        if let Some(parent) = file_path.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent)
-                    .map_err(|e| format!("Failed to create directory: {}", e))?;
-            }
+        // The content may be a patch against what's there, kept for the diff
+        let before = std::fs::read_to_string(&file_path).unwrap_or_default();
+        let content = &apply_llm_change(&before, content).map_err(|f| failure_report(&f))?;
+
+        let op = AgentOperation::Write {
+            path: file_path.clone(),
+            content: content.to_string(),
+        };
+        if held_for_approval(agent_id, "coder", op, cwd, event_tx) {
+            return Ok(());
         }
 
-        // Write the file
-        std::fs::write(&file_path, content)
+        write_atomic(&file_path, content.as_bytes())
             .map_err(|e| format!("Failed to write file: {}", e))?;
 
         let _ = event_tx.send(Event::AgentOutput {
             id: agent_id,
             chunk: format!("File written successfully ({} bytes)\n", content.len()),
         });
+        let shown = file_path.strip_prefix(cwd).unwrap_or(&file_path);
+        let diff = unified_diff(&shown.to_string_lossy(), &before, content);
+        if !diff.is_empty() {
+            let _ = event_tx.send(Event::AgentOutput {
+                id: agent_id,
+                chunk: format!("```diff\n{}```\n", diff),
+            });
+        }
 
         // Emit file modification event for output panel to update
         let _ = event_tx.send(Event::FileModification {
@@ -220,6 +282,239 @@ fn execute_coder(
     }
 }
 
+/// Hand `op` to the app for approval if the write policy of the workspace
+/// at `cwd` protects its path
+///
+/// Returns whether the operation was held.
+fn held_for_approval(
+    agent_id: AgentId,
+    agent: &str,
+    op: AgentOperation,
+    cwd: &Path,
+    event_tx: &Sender<Event>,
+) -> bool {
+    let policy = std::fs::read_to_string(cwd.join(".axiom").join("config.toml"))
+        .ok()
+        .and_then(|content| toml::from_str::<WorkspaceConfig>(&content).ok())
+        .map(|config| config.write_policy)
+        .unwrap_or_default();
+    let Some((path, reason)) = policy.check_operation(cwd, &op) else {
+        return false;
+    };
+
+    let _ = event_tx.send(Event::AgentOutput {
+        id: agent_id,
+        chunk: format!("Held for approval: {} ({})\n", path.display(), reason),
+    });
+    let _ = event_tx.send(Event::WriteHeld(PendingWrite::new(agent, path, reason, op)));
+    true
+}
+
+/// Generate tests with the developer agent and rerun them until they pass
+///
+/// Parameters are `attempts|target`.
+fn execute_gen_tests(
+    agent_id: AgentId,
+    request: &AgentSpawnRequest,
+    cwd: &Path,
+    event_tx: &Sender<Event>,
+) -> Result<(), String> {
+    let params = request.parameters.as_deref().unwrap_or("");
+    let (attempts, target) = params
+        .split_once('|')
+        .and_then(|(n, target)| Some((n.parse::<usize>().ok()?, target)))
+        .ok_or_else(|| "No test target provided".to_string())?;
+
+    let config = axiom_core::config::load_config(cwd).map_err(|e| e.to_string())?;
+    let service =
+        OrchestrationService::with_settings(cwd.into(), LlmSettings::from_axiom_config(&config))
+            .with_prompts(PromptLibrary::new(cwd, &config.prompts))
+            .with_review(config.orchestration.review);
+    let output = |chunk: String| {
+        let _ = event_tx.send(Event::AgentOutput {
+            id: agent_id,
+            chunk,
+        });
+    };
+
+    let report = service
+        .generate_tests(
+            target,
+            attempts,
+            |op| {
+                if held_for_approval(agent_id, "developer", op.clone(), cwd, event_tx) {
+                    return Err("held for approval".to_string());
+                }
+                apply_operation(cwd, op)
+            },
+            |line| output(format!("{}\n", line)),
+        )
+        .map_err(|e| e.to_string())?;
+    output(format!("\n{}\n", report.summary()));
+    Ok(())
+}
+
+/// List the build's compiler errors, or fix one and rebuild until it's gone
+///
+/// Parameters are `attempts|problem`, where `problem` is `0` to list them.
+fn execute_fix(
+    agent_id: AgentId,
+    request: &AgentSpawnRequest,
+    cwd: &Path,
+    event_tx: &Sender<Event>,
+) -> Result<(), String> {
+    let params = request.parameters.as_deref().unwrap_or("");
+    let (attempts, problem) = params
+        .split_once('|')
+        .and_then(|(n, problem)| Some((n.parse::<usize>().ok()?, problem.parse::<usize>().ok()?)))
+        .ok_or_else(|| "Invalid fix parameters".to_string())?;
+
+    let config = axiom_core::config::load_config(cwd).map_err(|e| e.to_string())?;
+    let service =
+        OrchestrationService::with_settings(cwd.into(), LlmSettings::from_axiom_config(&config))
+            .with_prompts(PromptLibrary::new(cwd, &config.prompts))
+            .with_review(config.orchestration.review);
+    let output = |chunk: String| {
+        let _ = event_tx.send(Event::AgentOutput {
+            id: agent_id,
+            chunk,
+        });
+    };
+
+    if problem == 0 {
+        let list = service.list_problems().map_err(|e| e.to_string())?;
+        output(format!("{}\n", list));
+        return Ok(());
+    }
+    let report = service
+        .fix_problem(
+            problem,
+            attempts,
+            |op| {
+                if held_for_approval(agent_id, "developer", op.clone(), cwd, event_tx) {
+                    return Err("held for approval".to_string());
+                }
+                apply_operation(cwd, op)
+            },
+            |line| output(format!("{}\n", line)),
+        )
+        .map_err(|e| e.to_string())?;
+    output(format!("\n{}\n", report.summary()));
+    Ok(())
+}
+
+/// Run the workspace's `[verify]` checks on what a run wrote, handing
+/// failures to the developer agent for repair
+///
+/// Parameters are the run's request.
+fn execute_verify(
+    agent_id: AgentId,
+    request: &AgentSpawnRequest,
+    cwd: &Path,
+    event_tx: &Sender<Event>,
+) -> Result<(), String> {
+    let task = request.parameters.as_deref().unwrap_or("");
+    let checks = std::fs::read_to_string(cwd.join(".axiom").join("config.toml"))
+        .ok()
+        .and_then(|content| toml::from_str::<WorkspaceConfig>(&content).ok())
+        .map(|config| config.verify)
+        .unwrap_or_default();
+    if !checks.is_enabled() {
+        return Ok(());
+    }
+
+    let config = axiom_core::config::load_config(cwd).map_err(|e| e.to_string())?;
+    let service =
+        OrchestrationService::with_settings(cwd.into(), LlmSettings::from_axiom_config(&config))
+            .with_prompts(PromptLibrary::new(cwd, &config.prompts))
+            .with_review(config.orchestration.review);
+    let output = |chunk: String| {
+        let _ = event_tx.send(Event::AgentOutput {
+            id: agent_id,
+            chunk,
+        });
+    };
+
+    let verification = verify_and_repair(cwd, &checks, task, |repair| {
+        output("Repairing...\n".to_string());
+        let Ok(response) = service.run_developer(repair) else {
+            return false;
+        };
+        let mut changed = false;
+        for op in response.operations {
+            if held_for_approval(agent_id, "developer", op.clone(), cwd, event_tx) {
+                continue;
+            }
+            let path = op.path().map(|path| path.display().to_string());
+            match apply_operation(cwd, &op) {
+                Ok(()) => {
+                    output(format!("Modified: {}\n", path.unwrap_or_default()));
+                    changed = true;
+                }
+                Err(e) => output(format!("{}\n", e)),
+            }
+        }
+        changed
+    });
+    output(format!("{}\n", verification.summary()));
+    if verification.passed {
+        Ok(())
+    } else {
+        Err("Verification failed".to_string())
+    }
+}
+
+/// Draft doc comments and hold each file's changes for review
+///
+/// Parameters are `check|path`, where `check` is `1` to build the docs with
+/// the changes first.
+fn execute_gen_docs(
+    agent_id: AgentId,
+    request: &AgentSpawnRequest,
+    cwd: &Path,
+    event_tx: &Sender<Event>,
+) -> Result<(), String> {
+    let params = request.parameters.as_deref().unwrap_or("");
+    let (check, path) = params
+        .split_once('|')
+        .ok_or_else(|| "No path provided".to_string())?;
+
+    let config = axiom_core::config::load_config(cwd).map_err(|e| e.to_string())?;
+    let service = OrchestrationService::with_settings(
+        cwd.to_path_buf(),
+        LlmSettings::from_axiom_config(&config),
+    )
+    .with_prompts(PromptLibrary::new(cwd, &config.prompts));
+    let report = service
+        .generate_docs(
+            Path::new(path),
+            check == "1",
+            |changeset| {
+                let pending = PendingWrite::new(
+                    "docs",
+                    changeset.file.clone(),
+                    format!("{} doc comment(s) for review", changeset.documented.len()),
+                    changeset.operation.clone(),
+                );
+                let id = pending.id.clone();
+                let _ = event_tx.send(Event::WriteHeld(pending));
+                Ok(id)
+            },
+            |line| {
+                let _ = event_tx.send(Event::AgentOutput {
+                    id: agent_id,
+                    chunk: format!("{}\n", line),
+                });
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    let _ = event_tx.send(Event::AgentOutput {
+        id: agent_id,
+        chunk: format!("\n{}\n", report.summary()),
+    });
+    Ok(())
+}
+
 /// Execute a search agent
 fn execute_search(
     agent_id: AgentId,
@@ -251,7 +546,14 @@ fn execute_search(
 
     match output {
         Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
+            // Matching lines can hold secrets too, so they're guarded like a file
+            let stdout = guarded(
+                agent_id,
+                &String::from_utf8_lossy(&output.stdout),
+                "search results",
+                cwd,
+                event_tx,
+            );
             let stderr = String::from_utf8_lossy(&output.stderr);
 
             if !stdout.is_empty() {
@@ -295,6 +597,25 @@ fn execute_search(
     }
 }
 
+/// `text` from `source` after the guard of the workspace at `cwd`, with
+/// what it found noted in the agent's output
+fn guarded(
+    agent_id: AgentId,
+    text: &str,
+    source: &str,
+    cwd: &Path,
+    event_tx: &Sender<Event>,
+) -> String {
+    let guarded = guard_content(text, &GuardConfig::load(cwd));
+    if let Some(summary) = guarded.summary(source) {
+        let _ = event_tx.send(Event::AgentOutput {
+            id: agent_id,
+            chunk: format!("{}\n", summary),
+        });
+    }
+    guarded.text
+}
+
 /// Execute a file operations agent
 fn execute_fileops(
     agent_id: AgentId,
@@ -314,10 +635,12 @@ fn execute_fileops(
 
     match operation {
         "read" => {
-            let file_path = if path.starts_with('/') {
-                PathBuf::from(path)
+            // Accepts `path`, `path:10-20` or `path#symbol`
+            let spec = context::parse_file_spec(path);
+            let file_path = if spec.path.starts_with('/') {
+                PathBuf::from(&spec.path)
             } else {
-                cwd.join(path)
+                cwd.join(&spec.path)
             };
 
             let _ = event_tx.send(Event::AgentOutput {
@@ -325,30 +648,69 @@ fn execute_fileops(
                 chunk: format!("Reading: {}\n\n", file_path.display()),
             });
 
-            match std::fs::read_to_string(&file_path) {
-                Ok(content) => {
-                    // Limit output to first 100 lines
-                    let lines: Vec<&str> = content.lines().take(100).collect();
-                    let total_lines = content.lines().count();
+            let content = std::fs::read_to_string(&file_path)
+                .map_err(|e| format!("Failed to read file: {}", e))?;
+            let content = guarded(agent_id, &content, &spec.path, cwd, event_tx);
+            let budget = ContextBudget::default();
 
-                    for (i, line) in lines.iter().enumerate() {
-                        let _ = event_tx.send(Event::AgentOutput {
-                            id: agent_id,
-                            chunk: format!("{:4} | {}\n", i + 1, line),
-                        });
-                    }
+            let snippet = match &spec.target {
+                ContextTarget::File => None,
+                ContextTarget::Lines(start, end) => Some(
+                    context::snippet_for_range(&content, *start, *end, budget.context_lines)
+                        .ok_or_else(|| format!("Line {} is past the end of the file", start))?,
+                ),
+                ContextTarget::Symbol(name) => Some(
+                    context::snippet_for_symbol(&spec.path, &content, name, budget.context_lines)
+                        .ok_or_else(|| format!("Symbol not found: {}", name))?,
+                ),
+            };
 
-                    if total_lines > 100 {
-                        let _ = event_tx.send(Event::AgentOutput {
-                            id: agent_id,
-                            chunk: format!("\n... {} more lines\n", total_lines - 100),
-                        });
-                    }
+            if let Some(snippet) = snippet {
+                let last = snippet.end;
+                let (snippet, _) = context::fit_snippet(snippet, budget.max_bytes);
+                for (i, line) in snippet.text.lines().enumerate() {
+                    let _ = event_tx.send(Event::AgentOutput {
+                        id: agent_id,
+                        chunk: format!("{:4} | {}\n", snippet.start + i, line),
+                    });
+                }
+                if last > snippet.end {
+                    let _ = event_tx.send(Event::AgentOutput {
+                        id: agent_id,
+                        chunk: format!("\n... {} more lines\n", last - snippet.end),
+                    });
+                }
+                return Ok(());
+            }
 
-                    Ok(())
+            // Limit output to first 100 lines
+            let lines: Vec<&str> = content.lines().take(100).collect();
+            let total_lines = content.lines().count();
+
+            for (i, line) in lines.iter().enumerate() {
+                let _ = event_tx.send(Event::AgentOutput {
+                    id: agent_id,
+                    chunk: format!("{:4} | {}\n", i + 1, line),
+                });
+            }
+
+            if total_lines > 100 {
+                let _ = event_tx.send(Event::AgentOutput {
+                    id: agent_id,
+                    chunk: format!("\n... {} more lines\n", total_lines - 100),
+                });
+
+                // Point at the rest of the file instead of dumping it
+                let items = context::outline(&spec.path, &content);
+                if !items.is_empty() {
+                    let _ = event_tx.send(Event::AgentOutput {
+                        id: agent_id,
+                        chunk: format!("\nOutline:\n{}\n", context::format_outline(&items)),
+                    });
                 }
-                Err(e) => Err(format!("Failed to read file: {}", e)),
             }
+
+            Ok(())
         }
         "list" | "ls" => {
             let dir_path = if path.is_empty() {
@@ -418,6 +780,13 @@ fn execute_fileops(
                 chunk: format!("Deleting: {}\n", file_path.display()),
             });
 
+            let op = AgentOperation::Delete {
+                path: file_path.clone(),
+            };
+            if held_for_approval(agent_id, "fileops", op, cwd, event_tx) {
+                return Ok(());
+            }
+
             if file_path.is_dir() {
                 std::fs::remove_dir_all(&file_path)
                     .map_err(|e| format!("Failed to delete directory: {}", e))?;
@@ -455,7 +824,7 @@ mod tests {
             parent_id: None,
         };
 
-        let result = execute_shell(AgentId::new(1), &request, &cwd, &tx);
+        let result = execute_shell(AgentId::new(1), &request, &cwd, &tx, CancelToken::new());
         assert!(result.is_ok());
 
         // Check output contains "hello"
@@ -486,4 +855,128 @@ mod tests {
         let result = execute_search(AgentId::new(1), &request, &cwd, &tx);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_coder_holds_protected_write() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let dir = tempfile::TempDir::new().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let request = AgentSpawnRequest {
+            agent_type: AgentType::Coder,
+            name: "Coder".to_string(),
+            description: "Test".to_string(),
+            parameters: Some(".env|API_KEY=changed".to_string()),
+            parent_id: None,
+        };
+
+        assert!(execute_coder(AgentId::new(1), &request, &cwd, &tx).is_ok());
+        assert!(!cwd.join(".env").exists());
+
+        let held = rx
+            .try_iter()
+            .find_map(|event| match event {
+                Event::WriteHeld(pending) => Some((pending.path, pending.reason)),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(held, (cwd.join(".env"), "environment file".to_string()));
+    }
+
+    #[test]
+    fn test_verify_runs_workspace_checks() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let dir = tempfile::TempDir::new().unwrap();
+        let cwd = dir.path().to_path_buf();
+        std::fs::create_dir(cwd.join(".axiom")).unwrap();
+        let config = |command: &str| {
+            let content = format!(
+                "[verify]\nmax_repairs = 0\n\n[[verify.checks]]\nname = \"test\"\ncommand = \"{}\"\n",
+                command
+            );
+            std::fs::write(cwd.join(".axiom").join("config.toml"), content).unwrap();
+        };
+        let request = AgentSpawnRequest {
+            agent_type: AgentType::Verifier,
+            name: "Verify".to_string(),
+            description: "Test".to_string(),
+            parameters: Some("Add a test".to_string()),
+            parent_id: None,
+        };
+
+        config("touch checked");
+        assert!(execute_verify(AgentId::new(1), &request, &cwd, &tx).is_ok());
+        assert!(cwd.join("checked").exists());
+
+        config("exit 1");
+        assert!(execute_verify(AgentId::new(1), &request, &cwd, &tx).is_err());
+        let output: String = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                Event::AgentOutput { chunk, .. } => Some(chunk),
+                _ => None,
+            })
+            .collect();
+        assert!(output.contains("test: failed with exit code 1"));
+    }
+
+    #[test]
+    fn test_fileops_read_is_guarded() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let dir = tempfile::TempDir::new().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let key = "sk-proj-abcdefghijklmnopqrstuvwxyz012345";
+        std::fs::write(cwd.join(".env"), format!("OPENAI_API_KEY={}\n", key)).unwrap();
+
+        let request = AgentSpawnRequest {
+            agent_type: AgentType::FileOps,
+            name: "Read".to_string(),
+            description: "Test".to_string(),
+            parameters: Some("read .env".to_string()),
+            parent_id: None,
+        };
+        execute_fileops(AgentId::new(1), &request, &cwd, &tx).unwrap();
+
+        let output: String = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                Event::AgentOutput { chunk, .. } => Some(chunk),
+                _ => None,
+            })
+            .collect();
+        assert!(!output.contains(key));
+        assert!(output.contains("[REDACTED:"));
+        assert!(output.contains("Guard: redacted 1 secret in .env"));
+    }
+
+    #[test]
+    fn test_fileops_read_symbol() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let dir = tempfile::TempDir::new().unwrap();
+        let cwd = dir.path().to_path_buf();
+        std::fs::write(
+            cwd.join("lib.rs"),
+            "fn first() {\n    one();\n}\n\nfn second() {\n    two();\n}\n",
+        )
+        .unwrap();
+
+        let request = AgentSpawnRequest {
+            agent_type: AgentType::FileOps,
+            name: "Read".to_string(),
+            description: "Test".to_string(),
+            parameters: Some("read lib.rs#second".to_string()),
+            parent_id: None,
+        };
+        execute_fileops(AgentId::new(1), &request, &cwd, &tx).unwrap();
+
+        let output: String = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                Event::AgentOutput { chunk, .. } => Some(chunk),
+                _ => None,
+            })
+            .collect();
+        assert!(output.contains("   5 | fn second() {"));
+        assert!(!output.contains("fn first()"));
+    }
 }
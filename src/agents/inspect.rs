@@ -0,0 +1,173 @@
+//! Captured LLM exchanges, for `/inspect`
+//!
+//! While the inspector is on, each request an agent sends to a provider is
+//! kept with its reply: the system prompt and messages as sent, the
+//! request's parameters, the reply as it streamed in, and what was parsed
+//! out of it. Anything that looks like a secret is redacted before it is
+//! kept, so a capture can be shared when a prompt or parser misbehaves.
+
+use super::AgentSpawnRequest;
+use crate::llm::{ChatMessage, Role};
+use axiom_core::{guard_content, GuardConfig};
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Exchanges kept per agent; older ones are dropped
+pub const KEEP_EXCHANGES: usize = 20;
+
+/// One request to a provider and how it ended
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exchange {
+    /// Request parameters (provider, model, limits), in order
+    pub parameters: Vec<(String, String)>,
+
+    /// System prompt
+    pub system: String,
+
+    /// The other messages, as (role, text)
+    pub messages: Vec<(String, String)>,
+
+    /// Reply text as streamed
+    pub response: String,
+
+    /// What came of the reply: the agents parsed out of it, or the error
+    pub outcome: String,
+
+    /// Time from sending the request to its end
+    pub duration: Duration,
+}
+
+impl Exchange {
+    /// A request sending `messages` with `parameters`, not yet answered
+    pub fn new(parameters: Vec<(String, String)>, messages: &[ChatMessage]) -> Self {
+        let system: Vec<String> = messages
+            .iter()
+            .filter(|m| m.role == Role::System)
+            .map(|m| redact(&m.text()))
+            .collect();
+        Self {
+            parameters,
+            system: system.join("\n\n"),
+            messages: messages
+                .iter()
+                .filter(|m| m.role != Role::System)
+                .map(|m| (m.role_str().to_string(), redact(&m.text())))
+                .collect(),
+            response: String::new(),
+            outcome: String::new(),
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// Record the reply, how it ended and how long it took
+    pub fn finish(&mut self, response: &str, outcome: String, duration: Duration) {
+        self.response = redact(response);
+        self.outcome = redact(&outcome);
+        self.duration = duration;
+    }
+
+    /// The exchange as a Markdown section numbered `number`
+    pub fn to_markdown(&self, number: usize) -> String {
+        let mut md = format!("## Request {}\n\n", number);
+        for (name, value) in &self.parameters {
+            let _ = writeln!(md, "- **{}**: {}", name, value);
+        }
+        let _ = writeln!(md, "- **duration**: {} ms", self.duration.as_millis());
+        let _ = write!(md, "\n### System prompt\n\n{}\n", fenced(&self.system));
+        for (role, text) in &self.messages {
+            let _ = write!(md, "\n### {}\n\n{}\n", role, fenced(text));
+        }
+        let _ = write!(md, "\n### Response\n\n{}\n", fenced(&self.response));
+        let _ = write!(md, "\n### Parse result\n\n{}\n", self.outcome);
+        md
+    }
+}
+
+/// An agent's exchanges as one Markdown document, oldest first
+pub fn to_markdown(agent_name: &str, exchanges: &[Exchange]) -> String {
+    let mut md = format!("# LLM exchanges: {}\n", agent_name);
+    for (i, exchange) in exchanges.iter().enumerate() {
+        md.push('\n');
+        md.push_str(&exchange.to_markdown(i + 1));
+    }
+    md
+}
+
+/// Parse result of a reply that spawned `requests`
+pub fn spawned_outcome(requests: &[AgentSpawnRequest]) -> String {
+    if requests.is_empty() {
+        return "No agents spawned".to_string();
+    }
+    let lines: Vec<String> = requests
+        .iter()
+        .map(|r| format!("- {}: {}", r.agent_type, r.description))
+        .collect();
+    lines.join("\n")
+}
+
+/// `text` with secrets replaced by `[REDACTED:<kind>]`
+fn redact(text: &str) -> String {
+    guard_content(text, &GuardConfig::default()).text
+}
+
+/// `text` in a code fence longer than any backtick run inside it
+fn fenced(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{}\n{}\n{}", fence, text.trim_end(), fence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::AgentType;
+    use crate::llm::MessageContent;
+
+    fn message(role: Role, text: &str) -> ChatMessage {
+        ChatMessage {
+            role,
+            content: MessageContent::Text(text.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_exchange_is_redacted() {
+        let messages = [
+            message(Role::System, "You are Axiom."),
+            message(
+                Role::User,
+                "key is sk-ant-REDACTED",
+            ),
+        ];
+        let parameters = vec![("model".to_string(), "llama3.2".to_string())];
+        let mut exchange = Exchange::new(parameters, &messages);
+        assert_eq!(exchange.system, "You are Axiom.");
+        assert_eq!(exchange.messages.len(), 1);
+        assert!(exchange.messages[0].1.contains("[REDACTED:"));
+        assert!(!exchange.messages[0].1.contains("abcdefghijklmnop"));
+
+        let spawned = [AgentSpawnRequest {
+            agent_type: AgentType::Shell,
+            name: "Shell".to_string(),
+            description: "ls".to_string(),
+            parameters: Some("ls".to_string()),
+            parent_id: None,
+        }];
+        exchange.finish(
+            "Listing:\n→ shell: ls",
+            spawned_outcome(&spawned),
+            Duration::from_millis(1500),
+        );
+        let md = to_markdown("Conductor", &[exchange]);
+        assert!(md.starts_with("# LLM exchanges: Conductor\n\n## Request 1\n"));
+        assert!(md.contains("- **model**: llama3.2\n- **duration**: 1500 ms\n"));
+        assert!(md.contains("### Response\n\n```\nListing:\n→ shell: ls\n```\n"));
+        assert!(md.contains("### Parse result\n\n- Shell: ls\n"));
+        assert_eq!(spawned_outcome(&[]), "No agents spawned");
+    }
+
+    #[test]
+    fn test_fence_outlasts_backticks() {
+        assert_eq!(fenced("a ```b``` c"), "````\na ```b``` c\n````");
+    }
+}
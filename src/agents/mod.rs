@@ -5,19 +5,31 @@
 
 mod conductor;
 mod executor;
+pub mod inspect;
 mod pty_agent;
 mod pty_manager;
+mod run_document;
+mod service_agent;
+pub mod session;
+mod stream;
 mod types;
+mod workspaces;
 
 pub use conductor::Conductor;
 pub use executor::Executor;
+pub use inspect::Exchange;
 pub use pty_agent::PtyAgent;
 pub use pty_manager::PtyAgentManager;
+pub use run_document::{RunDocument, RunEntry};
+pub use stream::StreamStats;
 pub use types::{AgentStatus, AgentType};
+pub use workspaces::{ParkedWorkspace, WorkspaceSessions};
 
 use crate::state::AgentId;
+use axiom_core::agents::{CancelToken, ServiceState};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Instant;
 
 /// A spawned agent instance
@@ -35,6 +47,9 @@ pub struct Agent {
     /// Description of what this agent is doing
     pub description: String,
 
+    /// What the agent was spawned with, kept for re-running it
+    pub parameters: Option<String>,
+
     /// Current execution status
     pub status: AgentStatus,
 
@@ -58,6 +73,21 @@ pub struct Agent {
 
     /// Parent agent that spawned this agent (for aggregated output)
     pub parent_id: Option<AgentId>,
+
+    /// Which of the parent's runs spawned this agent
+    pub run: usize,
+
+    /// When the agent last started or produced output
+    pub last_activity: Instant,
+
+    /// Live stats of the run it is streaming, once one starts
+    pub stream: Option<StreamStats>,
+
+    /// Stops the agent's running work when cancelled
+    pub cancel_token: CancelToken,
+
+    /// Process health and restarts (service agents only)
+    pub service: Option<ServiceState>,
 }
 
 impl Agent {
@@ -74,25 +104,37 @@ impl Agent {
             agent_type,
             name,
             description,
+            parameters: None,
             status: AgentStatus::Pending,
             output: String::new(),
             created_at: Instant::now(),
             completed_at: None,
+            last_activity: Instant::now(),
             token_count: 0,
             line_count: 0,
             progress: None,
             parent_id,
+            run: 0,
+            cancel_token: CancelToken::new(),
+            stream: None,
+            service: None,
         }
     }
 
     /// Start the agent (set status to Running)
     pub fn start(&mut self) {
         self.status = AgentStatus::Running;
+        self.last_activity = Instant::now();
+        self.stream = Some(StreamStats::new(self.last_activity));
     }
 
     /// Append output to the agent
     pub fn append_output(&mut self, chunk: &str) {
         self.output.push_str(chunk);
+        self.last_activity = Instant::now();
+        if let Some(stream) = self.stream.as_mut().filter(|_| self.status.is_running()) {
+            stream.record(chunk);
+        }
         // Estimate tokens (~4 chars per token on average)
         self.token_count = self.output.len() / 4;
         // Count lines
@@ -120,6 +162,7 @@ impl Agent {
     pub fn cancel(&mut self) {
         self.status = AgentStatus::Cancelled;
         self.completed_at = Some(Instant::now());
+        self.cancel_token.cancel();
     }
 
     /// Get the duration since creation
@@ -157,14 +200,21 @@ pub struct AgentRegistry {
     /// All agents indexed by ID
     agents: HashMap<AgentId, Agent>,
 
-    /// Counter for generating unique IDs
-    next_id: AtomicU64,
+    /// Counter for generating unique IDs, shared with the registries of
+    /// other workspaces
+    next_id: Arc<AtomicU64>,
 
     /// Currently selected agent (for display in output area)
     selected: Option<AgentId>,
 
     /// Order of agents for display (most recent first)
     order: Vec<AgentId>,
+
+    /// The run each parent is on, once it has started a second one
+    runs: HashMap<AgentId, usize>,
+
+    /// LLM exchanges captured for `/inspect`, oldest first
+    exchanges: HashMap<AgentId, Vec<Exchange>>,
 }
 
 impl AgentRegistry {
@@ -172,22 +222,37 @@ impl AgentRegistry {
     pub fn new() -> Self {
         Self {
             agents: HashMap::new(),
-            next_id: AtomicU64::new(1),
+            next_id: Arc::new(AtomicU64::new(1)),
             selected: None,
             order: Vec::new(),
+            runs: HashMap::new(),
+            exchanges: HashMap::new(),
+        }
+    }
+
+    /// Create an empty registry numbering its agents from the same counter
+    /// as this one, so an id names one agent across both
+    pub fn sharing_ids(&self) -> Self {
+        Self {
+            next_id: self.next_id.clone(),
+            ..Self::new()
         }
     }
 
     /// Spawn a new agent and return its ID
     pub fn spawn(&mut self, request: AgentSpawnRequest) -> AgentId {
         let id = AgentId::new(self.next_id.fetch_add(1, Ordering::SeqCst));
-        let agent = Agent::new(
+        let mut agent = Agent::new(
             id,
             request.agent_type,
             request.name,
             request.description,
             request.parent_id,
         );
+        agent.parameters = request.parameters;
+        agent.run = request
+            .parent_id
+            .map_or(0, |parent| self.current_run(parent));
 
         self.order.insert(0, id); // Add to front (most recent)
         self.agents.insert(id, agent);
@@ -237,14 +302,48 @@ impl AgentRegistry {
         self.order.iter().filter_map(|id| self.agents.get(id))
     }
 
-    /// Get all children of a parent agent (for aggregated output)
+    /// Get the children of a parent agent's current run (for aggregated
+    /// output)
     pub fn children(&self, parent_id: AgentId) -> Vec<&Agent> {
+        let run = self.current_run(parent_id);
         self.agents
             .values()
-            .filter(|a| a.parent_id == Some(parent_id))
+            .filter(|a| a.parent_id == Some(parent_id) && a.run == run)
             .collect()
     }
 
+    /// The run of a parent agent that new children belong to
+    pub fn current_run(&self, parent_id: AgentId) -> usize {
+        self.runs.get(&parent_id).copied().unwrap_or(0)
+    }
+
+    /// Start a new run of a parent agent, removing the children of all but
+    /// the last `keep_runs` runs before it
+    pub fn start_run(&mut self, parent_id: AgentId, keep_runs: usize) {
+        let run = self.current_run(parent_id) + 1;
+        self.runs.insert(parent_id, run);
+        let oldest_kept = run.saturating_sub(keep_runs);
+        let to_remove: Vec<AgentId> = self
+            .agents
+            .values()
+            .filter(|a| a.parent_id == Some(parent_id) && a.run < oldest_kept)
+            .map(|a| a.id)
+            .collect();
+        for id in to_remove {
+            self.remove(id, parent_id);
+        }
+    }
+
+    /// Remove an agent, selecting `fallback` if it was selected
+    fn remove(&mut self, id: AgentId, fallback: AgentId) {
+        self.agents.remove(&id);
+        self.exchanges.remove(&id);
+        self.order.retain(|&i| i != id);
+        if self.selected == Some(id) {
+            self.selected = Some(fallback);
+        }
+    }
+
     /// Get the number of agents
     pub fn len(&self) -> usize {
         self.agents.len()
@@ -255,9 +354,49 @@ impl AgentRegistry {
         self.agents.is_empty()
     }
 
+    /// Running agents with no output for at least `after`, quietest first
+    ///
+    /// A quiet service is just serving, so services are left out.
+    pub fn stalled(&self, after: std::time::Duration) -> Vec<&Agent> {
+        let mut stalled: Vec<&Agent> = self
+            .agents
+            .values()
+            .filter(|a| {
+                a.status.is_running()
+                    && !a.agent_type.is_long_running()
+                    && a.last_activity.elapsed() >= after
+            })
+            .collect();
+        stalled.sort_by_key(|a| a.last_activity);
+        stalled
+    }
+
+    /// Count an agent as active now, e.g. when the user chooses to keep waiting
+    pub fn touch(&mut self, id: AgentId) {
+        if let Some(agent) = self.agents.get_mut(&id) {
+            agent.last_activity = Instant::now();
+        }
+    }
+
     /// Get the number of running agents
+    ///
+    /// Long-running services are not counted; they never "finish".
     pub fn running_count(&self) -> usize {
-        self.agents.values().filter(|a| a.status.is_running()).count()
+        self.agents
+            .values()
+            .filter(|a| a.status.is_running() && !a.agent_type.is_long_running())
+            .count()
+    }
+
+    /// Check whether all children of a parent's current run have finished
+    ///
+    /// Service agents are ignored so a running dev server doesn't hold a
+    /// Conductor run open.
+    pub fn children_done(&self, parent_id: AgentId) -> bool {
+        self.children(parent_id)
+            .iter()
+            .filter(|a| !a.agent_type.is_long_running())
+            .all(|a| a.status.is_terminal())
     }
 
     /// Start an agent
@@ -267,6 +406,28 @@ impl AgentRegistry {
         }
     }
 
+    /// Start counting a new run streamed by `provider`'s `model`
+    pub fn start_stream(&mut self, id: AgentId, provider: &str, model: &str) {
+        if let Some(agent) = self.agents.get_mut(&id) {
+            agent.stream = Some(StreamStats::new(Instant::now()).with_source(provider, model));
+        }
+    }
+
+    /// Keep an agent's captured LLM exchange, dropping its oldest beyond
+    /// [`inspect::KEEP_EXCHANGES`]
+    pub fn record_exchange(&mut self, id: AgentId, exchange: Exchange) {
+        let exchanges = self.exchanges.entry(id).or_default();
+        exchanges.push(exchange);
+        if exchanges.len() > inspect::KEEP_EXCHANGES {
+            exchanges.remove(0);
+        }
+    }
+
+    /// An agent's captured LLM exchanges, oldest first
+    pub fn exchanges(&self, id: AgentId) -> &[Exchange] {
+        self.exchanges.get(&id).map_or(&[], Vec::as_slice)
+    }
+
     /// Append output to an agent
     pub fn append_output(&mut self, id: AgentId, chunk: &str) {
         if let Some(agent) = self.agents.get_mut(&id) {
@@ -300,6 +461,8 @@ impl AgentRegistry {
         self.agents.clear();
         self.order.clear();
         self.selected = None;
+        self.runs.clear();
+        self.exchanges.clear();
     }
 
     /// Remove completed/cancelled agents older than the specified age
@@ -319,6 +482,7 @@ impl AgentRegistry {
 
         for id in to_remove {
             self.agents.remove(&id);
+            self.exchanges.remove(&id);
             self.order.retain(|&i| i != id);
             if self.selected == Some(id) {
                 self.selected = self.order.first().copied();
@@ -326,6 +490,20 @@ impl AgentRegistry {
         }
     }
 
+    /// Replace every agent with `agents`, e.g. from a saved session
+    ///
+    /// Ids are kept, and new agents are numbered after the highest of them.
+    pub fn restore(&mut self, agents: Vec<Agent>) {
+        self.clear();
+        let next = agents.iter().map(|a| a.id.value() + 1).max().unwrap_or(1);
+        self.next_id.fetch_max(next, Ordering::SeqCst);
+        for agent in agents {
+            self.order.insert(0, agent.id);
+            self.agents.insert(agent.id, agent);
+        }
+        self.selected = self.order.first().copied();
+    }
+
     /// Remove all children of a parent agent (for new interaction cleanup)
     pub fn remove_children(&mut self, parent_id: AgentId) {
         let to_remove: Vec<AgentId> = self
@@ -336,11 +514,7 @@ impl AgentRegistry {
             .collect();
 
         for id in to_remove {
-            self.agents.remove(&id);
-            self.order.retain(|&i| i != id);
-            if self.selected == Some(id) {
-                self.selected = Some(parent_id); // Select parent instead
-            }
+            self.remove(id, parent_id); // Select parent instead
         }
     }
 }
@@ -426,4 +600,97 @@ mod tests {
         registry.select(id2);
         assert_eq!(registry.selected_id(), Some(id2));
     }
+
+    #[test]
+    fn test_runs_keep_recent_children() {
+        let mut registry = AgentRegistry::new();
+        let conductor = registry.spawn(AgentSpawnRequest {
+            agent_type: AgentType::Conductor,
+            name: "Conductor".to_string(),
+            description: "".to_string(),
+            parameters: None,
+            parent_id: None,
+        });
+        let spawn_child = |registry: &mut AgentRegistry| {
+            registry.spawn(AgentSpawnRequest {
+                agent_type: AgentType::Shell,
+                name: "Shell".to_string(),
+                description: "".to_string(),
+                parameters: None,
+                parent_id: Some(conductor),
+            })
+        };
+
+        let first = spawn_child(&mut registry);
+        registry.start_run(conductor, 1);
+        let second = spawn_child(&mut registry);
+        assert_eq!(registry.get(second).unwrap().run, 1);
+        // Only the current run's children are aggregated
+        let children: Vec<AgentId> = registry.children(conductor).iter().map(|a| a.id).collect();
+        assert_eq!(children, [second]);
+
+        // The run before is kept, older ones go
+        registry.start_run(conductor, 1);
+        assert!(registry.get(first).is_none());
+        assert!(registry.get(second).is_some());
+        registry.start_run(conductor, 0);
+        assert!(registry.get(second).is_none());
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn test_exchanges_keep_latest() {
+        let mut registry = AgentRegistry::new();
+        let id = registry.spawn(AgentSpawnRequest {
+            agent_type: AgentType::Conductor,
+            name: "Conductor".to_string(),
+            description: "".to_string(),
+            parameters: None,
+            parent_id: None,
+        });
+        for n in 0..inspect::KEEP_EXCHANGES + 2 {
+            let mut exchange = Exchange::new(Vec::new(), &[]);
+            exchange.outcome = n.to_string();
+            registry.record_exchange(id, exchange);
+        }
+        let exchanges = registry.exchanges(id);
+        assert_eq!(exchanges.len(), inspect::KEEP_EXCHANGES);
+        assert_eq!(exchanges[0].outcome, "2");
+
+        registry.clear();
+        assert!(registry.exchanges(id).is_empty());
+    }
+
+    #[test]
+    fn test_services_excluded_from_completion() {
+        let mut registry = AgentRegistry::new();
+        let parent = registry.spawn(AgentSpawnRequest {
+            agent_type: AgentType::Conductor,
+            name: "Conductor".to_string(),
+            description: "".to_string(),
+            parameters: None,
+            parent_id: None,
+        });
+        let mut spawn_child = |agent_type: AgentType| {
+            registry.spawn(AgentSpawnRequest {
+                name: agent_type.label().to_string(),
+                agent_type,
+                description: "".to_string(),
+                parameters: None,
+                parent_id: Some(parent),
+            })
+        };
+        let shell = spawn_child(AgentType::Shell);
+        let service = spawn_child(AgentType::Service);
+        registry.start(shell);
+        registry.start(service);
+        assert_eq!(registry.running_count(), 1);
+        assert!(!registry.children_done(parent));
+
+        registry.complete(shell);
+        assert_eq!(registry.running_count(), 0);
+        assert!(registry.children_done(parent));
+        assert!(registry.get(service).unwrap().status.is_running());
+        assert!(registry.stalled(std::time::Duration::ZERO).is_empty());
+    }
 }
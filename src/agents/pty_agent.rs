@@ -7,9 +7,12 @@ use crate::config::CliAgentConfig;
 use crate::core::{PtyError, Result};
 use crate::events::Event;
 use crate::state::AgentId;
+use axiom_core::agents::{terminate, PromptInjector};
+use axiom_core::Recorder;
 use crossbeam_channel::Sender;
 use parking_lot::RwLock;
-use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use std::ffi::OsString;
 use std::io::{Read, Write};
 use std::path::Path;
 use std::sync::Arc;
@@ -33,6 +36,12 @@ pub struct PtyAgent {
 
     /// Whether the PTY process has exited
     pub exited: bool,
+
+    /// The CLI process, kept so it can be stopped
+    child: Box<dyn Child + Send + Sync>,
+
+    /// Recording of the session, if the agent is configured to record
+    recorder: Option<Arc<parking_lot::Mutex<Recorder>>>,
 }
 
 impl PtyAgent {
@@ -68,22 +77,24 @@ impl PtyAgent {
             .openpty(size)
             .map_err(|e| PtyError::Create(e.to_string()))?;
 
-        // Build the command
-        let mut cmd = CommandBuilder::new(&config.command);
-
-        // Add default arguments
-        for arg in &config.default_args {
+        // Build the command, under bash if activation hooks run first
+        let (program, args) = config.launch(prompt);
+        let mut cmd = CommandBuilder::new(program);
+        for arg in args {
             cmd.arg(arg);
         }
 
-        // Add the user's prompt as the final argument
-        if !prompt.is_empty() {
-            cmd.arg(prompt);
-        }
-
-        // Set working directory if configured
-        if config.use_cwd {
-            cmd.cwd(cwd);
+        // Start in the workspace, or the configured subdirectory of it
+        let dir = config.start_dir(cwd).map_err(PtyError::Spawn)?;
+        if let Some(dir) = &dir {
+            if !dir.is_dir() {
+                return Err(PtyError::Spawn(format!(
+                    "Working directory not found: {}",
+                    dir.display()
+                ))
+                .into());
+            }
+            cmd.cwd(dir);
         }
 
         // Set any custom environment variables
@@ -91,8 +102,36 @@ impl PtyAgent {
             cmd.env(key, value);
         }
 
+        // Extra PATH entries go in front of the configured or inherited PATH
+        let inherited = match config.env.get("PATH") {
+            Some(path) => Some(OsString::from(path)),
+            None => std::env::var_os("PATH"),
+        };
+        let path_base = dir.as_deref().unwrap_or(cwd);
+        if let Some(path) = config.search_path(path_base, inherited.as_deref()) {
+            cmd.env("PATH", path);
+        }
+
+        // A prompt left off the command line is typed in once it's ready
+        let injector = match &config.ready {
+            Some(ready) if !prompt.is_empty() => Some(
+                PromptInjector::new(ready, prompt).map_err(|e| PtyError::Spawn(e.to_string()))?,
+            ),
+            _ => None,
+        };
+
+        // Record the session from the first byte of output
+        let recorder = match &config.record {
+            Some(record) => Some(
+                Recorder::start(cwd, &config.name, axiom_core::AgentId(id.0), record, cols, rows)
+                    .map_err(|e| PtyError::Spawn(format!("Failed to start recording: {}", e)))?,
+            ),
+            None => None,
+        };
+
         // Spawn the CLI agent process
-        pair.slave
+        let child = pair
+            .slave
             .spawn_command(cmd)
             .map_err(|e| PtyError::Spawn(e.to_string()))?;
 
@@ -114,23 +153,66 @@ impl PtyAgent {
         // Create vt100 parser
         let parser = Arc::new(RwLock::new(vt100::Parser::new(rows, cols, 1000)));
 
-        // Clone parser for reader thread
+        // Clone parser and recorder for reader thread
         let parser_clone = parser.clone();
+        let recorder = recorder.map(|recorder| Arc::new(parking_lot::Mutex::new(recorder)));
+        let recorder_clone = recorder.clone();
 
         // Spawn reader thread
         let agent_id = id;
+        let reader_tx = event_tx.clone();
         std::thread::spawn(move || {
-            Self::read_loop(agent_id, reader, parser_clone, event_tx);
+            Self::read_loop(agent_id, reader, parser_clone, recorder_clone, reader_tx);
         });
 
-        Ok(Self {
+        let agent = Self {
             id,
             master,
             writer,
             parser,
             size,
             exited: false,
-        })
+            child,
+            recorder,
+        };
+        if let Some(injector) = injector {
+            agent.inject_prompt(injector, event_tx);
+        }
+        Ok(agent)
+    }
+
+    /// Type a prompt in on a background thread once the agent is ready
+    ///
+    /// Sends [`Event::CliAgentNotReady`] if it never is.
+    fn inject_prompt(&self, injector: PromptInjector, event_tx: Sender<Event>) {
+        let id = self.id;
+        // Weak, so a removed agent's screen and input go away
+        let parser = Arc::downgrade(&self.parser);
+        let writer = Arc::downgrade(&self.writer);
+        std::thread::spawn(move || {
+            let screen = || parser.upgrade().map(|p| p.read().screen().contents());
+            let write = |data: &[u8]| {
+                writer.upgrade().is_some_and(|writer| {
+                    let mut writer = writer.lock();
+                    writer.write_all(data).and_then(|_| writer.flush()).is_ok()
+                })
+            };
+            if let Err(e) = injector.run(screen, write) {
+                let _ = event_tx.send(Event::CliAgentNotReady {
+                    id,
+                    error: e.to_string(),
+                });
+            }
+        });
+    }
+
+    /// Stop the CLI process: SIGTERM, then SIGKILL if it lingers
+    ///
+    /// Blocks for up to [`axiom_core::agents::KILL_GRACE`].
+    pub fn terminate(mut self) {
+        if let Some(pid) = self.child.process_id() {
+            terminate(pid, || matches!(self.child.try_wait(), Ok(Some(_))));
+        }
     }
 
     /// Resize the PTY
@@ -159,6 +241,9 @@ impl PtyAgent {
 
         // Resize the parser
         self.parser.write().set_size(rows, cols);
+        if let Some(recorder) = &self.recorder {
+            recorder.lock().resize(cols, rows);
+        }
 
         Ok(())
     }
@@ -188,6 +273,10 @@ impl PtyAgent {
 
             for col in 0..cols {
                 if let Some(cell) = screen.cell(row, col) {
+                    // The wide character before it already covers this cell
+                    if cell.is_wide_continuation() {
+                        continue;
+                    }
                     let text = cell.contents();
                     if text.is_empty() {
                         spans.push(Span::raw(" "));
@@ -237,6 +326,9 @@ impl PtyAgent {
         for row in 0..rows {
             for col in 0..cols {
                 if let Some(cell) = screen.cell(row, col) {
+                    if cell.is_wide_continuation() {
+                        continue;
+                    }
                     let text = cell.contents();
                     if text.is_empty() {
                         output.push(' ');
@@ -253,6 +345,11 @@ impl PtyAgent {
         output.trim_end().to_string()
     }
 
+    /// Whether the program has asked for bracketed paste (`ESC[?2004h`)
+    pub fn bracketed_paste(&self) -> bool {
+        self.parser.read().screen().bracketed_paste()
+    }
+
     /// Get current size
     pub fn size(&self) -> (u16, u16) {
         (self.size.cols, self.size.rows)
@@ -263,6 +360,7 @@ impl PtyAgent {
         agent_id: AgentId,
         mut reader: Box<dyn Read + Send>,
         parser: Arc<RwLock<vt100::Parser>>,
+        recorder: Option<Arc<parking_lot::Mutex<Recorder>>>,
         tx: Sender<Event>,
     ) {
         let mut buf = [0u8; 4096];
@@ -282,6 +380,9 @@ impl PtyAgent {
 
                     // Feed data to vt100 parser
                     parser.write().process(data);
+                    if let Some(recorder) = &recorder {
+                        recorder.lock().output(data);
+                    }
 
                     // Send output event
                     if tx
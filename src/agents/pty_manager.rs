@@ -4,8 +4,10 @@
 //! Handles spawning, input routing, resize events, and cleanup.
 
 use crate::agents::pty_agent::PtyAgent;
+use axiom_core::agents::{OutputParsers, Transcript, DEFAULT_SCROLLBACK_LINES};
+use axiom_core::AgentEvent;
 use crate::config::CliAgentConfig;
-use crate::core::Result;
+use crate::core::{PtyError, Result};
 use crate::events::Event;
 use crate::state::AgentId;
 use crossbeam_channel::Sender;
@@ -17,6 +19,15 @@ pub struct PtyAgentManager {
     /// Active PTY agents
     agents: HashMap<AgentId, PtyAgent>,
 
+    /// Output parsers of agents that have any configured
+    parsers: HashMap<AgentId, OutputParsers>,
+
+    /// Plain-text output per agent, kept until the agent is removed
+    transcripts: HashMap<AgentId, Transcript>,
+
+    /// Lines kept in each transcript
+    scrollback: usize,
+
     /// Event sender
     event_tx: Sender<Event>,
 
@@ -30,6 +41,9 @@ impl PtyAgentManager {
     pub fn new(event_tx: Sender<Event>) -> Self {
         Self {
             agents: HashMap::new(),
+            parsers: HashMap::new(),
+            transcripts: HashMap::new(),
+            scrollback: DEFAULT_SCROLLBACK_LINES,
             event_tx,
             default_cols: 80,
             default_rows: 24,
@@ -42,6 +56,11 @@ impl PtyAgentManager {
         self.default_rows = rows.max(5);
     }
 
+    /// Set the lines kept in the transcripts of new agents
+    pub fn set_scrollback(&mut self, lines: usize) {
+        self.scrollback = lines;
+    }
+
     /// Get the default PTY dimensions
     pub fn default_size(&self) -> (u16, u16) {
         (self.default_cols, self.default_rows)
@@ -61,6 +80,9 @@ impl PtyAgentManager {
         prompt: &str,
         cwd: &Path,
     ) -> Result<()> {
+        let dir = config.start_dir(cwd).ok().flatten();
+        let parsers = OutputParsers::new(&config.parsers, dir.as_deref().unwrap_or(cwd))
+            .map_err(|e| PtyError::Spawn(e.to_string()))?;
         let agent = PtyAgent::new(
             id,
             config,
@@ -72,9 +94,44 @@ impl PtyAgentManager {
         )?;
 
         self.agents.insert(id, agent);
+        self.transcripts
+            .insert(id, Transcript::new(self.scrollback));
+        if !parsers.is_empty() {
+            self.parsers.insert(id, parsers);
+        }
         Ok(())
     }
 
+    /// Run an agent's output through its parsers; returns the events found
+    pub fn parse_output(&mut self, id: AgentId, data: &[u8]) -> Vec<AgentEvent> {
+        self.parsers
+            .get_mut(&id)
+            .map(|parsers| parsers.feed(data))
+            .unwrap_or_default()
+    }
+
+    /// Add output to an agent's transcript
+    pub fn capture(&mut self, id: AgentId, data: &[u8]) {
+        if let Some(transcript) = self.transcripts.get_mut(&id) {
+            transcript.feed(data);
+        }
+    }
+
+    /// Everything an agent printed, including what scrolled off its screen
+    pub fn transcript(&self, id: AgentId) -> Option<&Transcript> {
+        self.transcripts.get(&id)
+    }
+
+    /// Events from the end of an exited agent's output
+    ///
+    /// Stops its parser scripts, waiting for them to finish reading.
+    pub fn finish_output(&mut self, id: AgentId) -> Vec<AgentEvent> {
+        self.parsers
+            .remove(&id)
+            .map(OutputParsers::finish)
+            .unwrap_or_default()
+    }
+
     /// Write input data to a CLI agent
     pub fn write(&mut self, id: AgentId, data: &[u8]) -> Result<()> {
         if let Some(agent) = self.agents.get(&id) {
@@ -112,6 +169,13 @@ impl PtyAgentManager {
         self.agents.get(&id).map(|agent| agent.get_output_text())
     }
 
+    /// Whether a CLI agent wants pastes wrapped in bracketed paste markers
+    pub fn bracketed_paste(&self, id: AgentId) -> bool {
+        self.agents
+            .get(&id)
+            .is_some_and(|agent| agent.bracketed_paste())
+    }
+
     /// Check if an agent ID is a CLI agent managed by this manager
     pub fn contains(&self, id: AgentId) -> bool {
         self.agents.contains_key(&id)
@@ -124,14 +188,19 @@ impl PtyAgentManager {
         }
     }
 
-    /// Remove a CLI agent session
+    /// Remove a CLI agent session, stopping its process in the background
     pub fn remove(&mut self, id: AgentId) {
-        self.agents.remove(&id);
+        self.parsers.remove(&id);
+        self.transcripts.remove(&id);
+        if let Some(agent) = self.agents.remove(&id) {
+            std::thread::spawn(move || agent.terminate());
+        }
     }
 
-    /// Remove all exited agents
+    /// Remove all exited agents, keeping their transcripts
     pub fn cleanup_exited(&mut self) {
         self.agents.retain(|_, agent| !agent.exited);
+        self.parsers.retain(|id, _| self.agents.contains_key(id));
     }
 
     /// Get all active CLI agent IDs
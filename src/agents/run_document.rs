@@ -0,0 +1,320 @@
+//! Run documents: a readable record of one Conductor run
+//!
+//! Interleaves the user's prompt, a summary of the Conductor's reasoning,
+//! the commands its agents ran (with exit codes) and the diffs they applied,
+//! rendered as markdown so it can be read in the output panel or shared.
+
+use crate::agents::{Agent, AgentStatus, AgentType};
+use crate::llm::{ChatMessage, Role};
+
+/// Longest reasoning summary kept, in characters
+const MAX_REASONING_CHARS: usize = 1200;
+
+/// Most output lines kept per agent
+const MAX_OUTPUT_LINES: usize = 200;
+
+/// One section of a run document
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunEntry {
+    /// What the user asked for
+    Prompt(String),
+    /// The Conductor's prose, without agent directives or code
+    Reasoning(String),
+    /// A shell command and how it ended
+    Command {
+        command: String,
+        /// `None` while running, or when it failed to start
+        exit_code: Option<i32>,
+        output: String,
+    },
+    /// A file change applied by a Coder agent
+    Diff { path: String, diff: String },
+    /// Any other agent
+    Agent {
+        name: String,
+        description: String,
+        status: String,
+        output: String,
+    },
+}
+
+/// The prompt, reasoning and agent activity of a Conductor run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunDocument {
+    /// Heading, `Conductor run` unless set
+    title: Option<String>,
+    entries: Vec<RunEntry>,
+}
+
+impl RunDocument {
+    /// Document the latest run in `history`, with the agents it spawned
+    ///
+    /// Returns `None` when the Conductor hasn't been asked anything yet.
+    pub fn build(history: &[ChatMessage], children: &[&Agent]) -> Option<Self> {
+        let start = history.iter().rposition(|m| m.role == Role::User)?;
+        let mut entries = vec![RunEntry::Prompt(history[start].text())];
+        let response = history[start + 1..]
+            .iter()
+            .find(|m| m.role == Role::Assistant)
+            .map(|m| reasoning_summary(&m.text()))
+            .filter(|summary| !summary.is_empty());
+        entries.extend(response.map(RunEntry::Reasoning));
+
+        let mut children = children.to_vec();
+        children.sort_by_key(|agent| (agent.created_at, agent.id.value()));
+        entries.extend(children.into_iter().map(agent_entry));
+        Some(Self {
+            title: None,
+            entries,
+        })
+    }
+
+    /// Document one agent's run on its own
+    pub fn of_agent(agent: &Agent) -> Self {
+        Self {
+            title: Some(format!("{} run", agent.name)),
+            entries: vec![agent_entry(agent)],
+        }
+    }
+
+    pub fn entries(&self) -> &[RunEntry] {
+        &self.entries
+    }
+
+    /// Markdown with one `##` section per entry
+    pub fn to_markdown(&self) -> String {
+        let title = self.title.as_deref().unwrap_or("Conductor run");
+        let mut out = format!("# {}\n", title);
+        for entry in &self.entries {
+            out.push('\n');
+            match entry {
+                RunEntry::Prompt(text) => {
+                    out.push_str(&format!("## Prompt\n\n{}\n", text.trim()));
+                }
+                RunEntry::Reasoning(text) => {
+                    out.push_str(&format!("## Reasoning\n\n{}\n", text));
+                }
+                RunEntry::Command {
+                    command,
+                    exit_code,
+                    output,
+                } => {
+                    let exit = match exit_code {
+                        Some(code) => format!("exit code {}", code),
+                        None => "no exit code".to_string(),
+                    };
+                    out.push_str(&format!("## Command: `{}` ({})\n", command, exit));
+                    if !output.trim().is_empty() {
+                        out.push_str(&format!("\n{}", fenced("text", output)));
+                    }
+                }
+                RunEntry::Diff { path, diff } => {
+                    out.push_str(&format!("## Diff: {}\n\n{}", path, fenced("diff", diff)));
+                }
+                RunEntry::Agent {
+                    name,
+                    description,
+                    status,
+                    output,
+                } => {
+                    out.push_str(&format!("## {}: {} ({})\n", name, description, status));
+                    if !output.trim().is_empty() {
+                        out.push_str(&format!("\n{}", fenced("text", output)));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn agent_entry(agent: &Agent) -> RunEntry {
+    match agent.agent_type {
+        AgentType::Shell => {
+            // The executor echoes the command as the first line
+            let (first, rest) = agent.output.split_once('\n').unwrap_or((&agent.output, ""));
+            let command = match first.strip_prefix("$ ") {
+                Some(command) => command.to_string(),
+                None => agent.description.clone(),
+            };
+            let output = if first.starts_with("$ ") {
+                rest
+            } else {
+                &agent.output
+            };
+            RunEntry::Command {
+                command,
+                exit_code: exit_code(&agent.status),
+                output: clip_output(output),
+            }
+        }
+        AgentType::Coder => match extract_diff(&agent.output) {
+            Some((path, diff)) => RunEntry::Diff { path, diff },
+            None => other_entry(agent),
+        },
+        _ => other_entry(agent),
+    }
+}
+
+fn other_entry(agent: &Agent) -> RunEntry {
+    RunEntry::Agent {
+        name: agent.name.clone(),
+        description: agent.description.clone(),
+        status: agent.status.to_string(),
+        output: clip_output(&agent.output),
+    }
+}
+
+/// Exit code of a finished shell agent
+///
+/// Failures carry the process status in their message, e.g.
+/// `Command exited with status: exit status: 2`.
+fn exit_code(status: &AgentStatus) -> Option<i32> {
+    match status {
+        AgentStatus::Completed => Some(0),
+        AgentStatus::Error(message) => {
+            let rest = message.strip_prefix("Command exited with status:")?;
+            rest.rsplit(' ').next()?.parse().ok()
+        }
+        _ => None,
+    }
+}
+
+/// Path and body of the diff a Coder agent reported after writing
+fn extract_diff(output: &str) -> Option<(String, String)> {
+    let start = output.find("```diff\n")? + "```diff\n".len();
+    let body = &output[start..];
+    let diff = &body[..body.find("```").unwrap_or(body.len())];
+    let path = diff
+        .lines()
+        .find_map(|line| line.strip_prefix("+++ b/"))?
+        .to_string();
+    Some((path, diff.to_string()))
+}
+
+/// The Conductor's prose: the agents it started (and the `@agent`
+/// directives of older conversations) and code blocks dropped,
+/// blank runs collapsed, and capped at [`MAX_REASONING_CHARS`]
+fn reasoning_summary(response: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_code = false;
+    for line in response.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+        } else if in_code || trimmed.starts_with(['→', '@']) {
+            continue;
+        } else if !trimmed.is_empty() || lines.last().is_some_and(|l| !l.is_empty()) {
+            lines.push(trimmed);
+        }
+    }
+    let summary = lines.join("\n");
+    let summary = summary.trim();
+    match summary.char_indices().nth(MAX_REASONING_CHARS) {
+        Some((end, _)) => format!("{}…", &summary[..end]),
+        None => summary.to_string(),
+    }
+}
+
+/// The last [`MAX_OUTPUT_LINES`] lines of `output`
+fn clip_output(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    if lines.len() <= MAX_OUTPUT_LINES {
+        return output.to_string();
+    }
+    let dropped = lines.len() - MAX_OUTPUT_LINES;
+    format!(
+        "[{} earlier lines omitted]\n{}\n",
+        dropped,
+        lines[dropped..].join("\n")
+    )
+}
+
+/// `body` in a code fence longer than any backtick run inside it
+fn fenced(lang: &str, body: &str) -> String {
+    let longest = body.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    let newline = if body.ends_with('\n') { "" } else { "\n" };
+    format!("{}{}\n{}{}{}\n", fence, lang, body, newline, fence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MessageContent;
+    use crate::state::AgentId;
+
+    fn message(role: Role, text: &str) -> ChatMessage {
+        ChatMessage {
+            role,
+            content: MessageContent::Text(text.to_string()),
+        }
+    }
+
+    fn agent(id: u64, agent_type: AgentType, output: &str, status: AgentStatus) -> Agent {
+        let mut agent = Agent::new(
+            AgentId::new(id),
+            agent_type,
+            "Agent".to_string(),
+            "task".to_string(),
+            Some(AgentId::new(1)),
+        );
+        agent.append_output(output);
+        agent.status = status;
+        agent
+    }
+
+    #[test]
+    fn test_build_interleaves_run() {
+        let history = [
+            message(Role::User, "old question"),
+            message(Role::Assistant, "old answer"),
+            message(Role::User, "Fix the tests"),
+            message(
+                Role::Assistant,
+                "The build is broken.\n\n→ shell: cargo test\n→ coder: Fix it\n```rust\nfn a() {}\n```\nThen rerun.",
+            ),
+        ];
+        let shell = agent(
+            2,
+            AgentType::Shell,
+            "$ cargo test\nerror: 1 failed\n",
+            AgentStatus::Error("Command exited with status: exit status: 101".to_string()),
+        );
+        let coder = agent(
+            3,
+            AgentType::Coder,
+            "Writing to: /p/src/lib.rs\n```diff\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,1 +1,1 @@\n-fn a() { }\n+fn a() {}\n```\n",
+            AgentStatus::Completed,
+        );
+
+        let doc = RunDocument::build(&history, &[&coder, &shell]).unwrap();
+        assert_eq!(
+            doc.entries()[..3],
+            [
+                RunEntry::Prompt("Fix the tests".to_string()),
+                RunEntry::Reasoning("The build is broken.\n\nThen rerun.".to_string()),
+                RunEntry::Command {
+                    command: "cargo test".to_string(),
+                    exit_code: Some(101),
+                    output: "error: 1 failed\n".to_string(),
+                },
+            ]
+        );
+        assert!(matches!(&doc.entries()[3], RunEntry::Diff { path, .. } if path == "src/lib.rs"));
+
+        let markdown = doc.to_markdown();
+        assert!(markdown.contains("## Command: `cargo test` (exit code 101)\n"));
+        assert!(markdown.contains("## Diff: src/lib.rs\n\n```diff\n--- a/src/lib.rs\n"));
+        assert!(RunDocument::build(&[], &[]).is_none());
+
+        let markdown = RunDocument::of_agent(&shell).to_markdown();
+        assert!(markdown.starts_with("# Agent run\n\n## Command: `cargo test`"));
+    }
+
+    #[test]
+    fn test_fenced_outlasts_backticks() {
+        assert_eq!(fenced("text", "a\n"), "```text\na\n```\n");
+        assert_eq!(fenced("text", "````x"), "`````text\n````x\n`````\n");
+    }
+}
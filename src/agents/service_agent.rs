@@ -0,0 +1,141 @@
+//! Long-running service agents (dev servers, watchers)
+//!
+//! Unlike shell agents, a service keeps its process alive, is health-checked
+//! against a port or URL, and is restarted when it crashes. The supervising
+//! loop is shared with the core service agent; this only hosts it in the
+//! TUI's registry.
+
+use crate::agents::AgentRegistry;
+use crate::events::Event;
+use crate::state::AgentId;
+use axiom_core::agents::{
+    supervise, CancelToken, HealthCheck, ServiceHost, ServiceSpec, ServiceState,
+};
+use crossbeam_channel::Sender;
+use parking_lot::RwLock;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A service agent of the TUI's registry
+#[derive(Clone)]
+struct AgentHost {
+    agent_id: AgentId,
+    registry: Arc<RwLock<AgentRegistry>>,
+    event_tx: Sender<Event>,
+    cancel_token: CancelToken,
+}
+
+impl ServiceHost for AgentHost {
+    fn stopped(&self) -> bool {
+        self.cancel_token.is_cancelled() || self.registry.read().get(self.agent_id).is_none()
+    }
+
+    fn update(&self, change: impl FnOnce(&mut ServiceState)) -> bool {
+        let mut registry = self.registry.write();
+        let state = registry
+            .get_mut(self.agent_id)
+            .and_then(|a| a.service.as_mut());
+        state.map(change).is_some()
+    }
+
+    fn output(&self, chunk: String) -> bool {
+        self.event_tx
+            .send(Event::AgentOutput {
+                id: self.agent_id,
+                chunk,
+            })
+            .is_ok()
+    }
+}
+
+/// Run a service agent until it is cancelled or gives up restarting
+pub(super) fn run_service(
+    agent_id: AgentId,
+    params: &str,
+    cwd: &Path,
+    registry: &Arc<RwLock<AgentRegistry>>,
+    event_tx: &Sender<Event>,
+    cancel_token: CancelToken,
+) -> Result<(), String> {
+    let spec = ServiceSpec::parse(params)?;
+    let check = spec.health.as_ref().map(HealthCheck::describe);
+
+    if let Some(agent) = registry.write().get_mut(agent_id) {
+        agent.service = Some(ServiceState::new(check));
+    }
+
+    let host = AgentHost {
+        agent_id,
+        registry: registry.clone(),
+        event_tx: event_tx.clone(),
+        cancel_token,
+    };
+    supervise(&spec, cwd, &host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{AgentSpawnRequest, AgentType};
+    use std::time::Duration;
+
+    fn service(registry: &Arc<RwLock<AgentRegistry>>) -> (AgentId, CancelToken) {
+        let mut registry = registry.write();
+        let id = registry.spawn(AgentSpawnRequest {
+            agent_type: AgentType::Service,
+            name: "Service".to_string(),
+            description: String::new(),
+            parameters: None,
+            parent_id: None,
+        });
+        registry.start(id);
+        (id, registry.get(id).unwrap().cancel_token.clone())
+    }
+
+    #[test]
+    fn test_service_restarts_then_gives_up() {
+        let registry = Arc::new(RwLock::new(AgentRegistry::new()));
+        let (id, token) = service(&registry);
+        let (tx, rx) = crossbeam_channel::unbounded();
+
+        let result = run_service(
+            id,
+            "--max-restarts 1 exit 3",
+            &std::env::temp_dir(),
+            &registry,
+            &tx,
+            token,
+        );
+        assert_eq!(result, Err("Service exited with code 3".to_string()));
+
+        let output: String = rx
+            .try_iter()
+            .filter_map(|e| match e {
+                Event::AgentOutput { chunk, .. } => Some(chunk),
+                _ => None,
+            })
+            .collect();
+        assert!(output.contains("restarting (1/1)"));
+        let registry = registry.read();
+        assert_eq!(
+            registry.get(id).unwrap().service.as_ref().unwrap().restarts,
+            1
+        );
+    }
+
+    #[test]
+    fn test_cancel_stops_service() {
+        let registry = Arc::new(RwLock::new(AgentRegistry::new()));
+        let (id, token) = service(&registry);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+
+        let shared = registry.clone();
+        let handle = std::thread::spawn(move || {
+            run_service(id, "sleep 30", &std::env::temp_dir(), &shared, &tx, token)
+        });
+        std::thread::sleep(Duration::from_millis(300));
+        registry.write().cancel(id);
+
+        assert_eq!(handle.join().unwrap(), Ok(()));
+    }
+}
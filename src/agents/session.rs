@@ -0,0 +1,155 @@
+//! Saving and restoring the TUI's conversation as an axiom-core session
+//!
+//! The TUI keeps its own agent and message types, so this converts them to
+//! and from [`axiom_core::Session`] and leaves the file format to
+//! [`axiom_core::sessions`].
+
+use crate::agents::{Agent, AgentRegistry, AgentStatus, AgentType, Conductor};
+use crate::llm::{ChatMessage, MessageContent, Role};
+use crate::state::AgentId;
+use axiom_core::sessions;
+use axiom_core::{SavedAgent, Session};
+use std::path::{Path, PathBuf};
+
+/// Save the conductor's conversation and every agent as session `name`
+pub fn save(
+    root: &Path,
+    name: Option<&str>,
+    conductor: &Conductor,
+    registry: &AgentRegistry,
+) -> axiom_core::Result<PathBuf> {
+    let session = Session::new(
+        name.unwrap_or(sessions::DEFAULT_SESSION),
+        conductor.history().iter().map(core_message).collect(),
+        conductor
+            .agent_id()
+            .map(|id| axiom_core::AgentId(id.value())),
+        registry.agents().map(saved_agent).collect(),
+    );
+    sessions::save(root, &session)
+}
+
+/// Replace the conversation and agents with session `name`, or the newest;
+/// returns the restored session's name
+pub fn restore(
+    root: &Path,
+    name: Option<&str>,
+    conductor: &mut Conductor,
+    registry: &mut AgentRegistry,
+) -> axiom_core::Result<String> {
+    let session = sessions::load(root, name)?;
+    let agents = session
+        .agents
+        .into_iter()
+        .map(|saved| tui_agent(saved.into_agent()))
+        .collect();
+    registry.restore(agents);
+    conductor.restore(
+        session.history.iter().map(tui_message).collect(),
+        session.conductor_id.map(|id| AgentId::new(id.0)),
+    );
+    Ok(session.name)
+}
+
+fn saved_agent(agent: &Agent) -> SavedAgent {
+    use axiom_core::AgentType as Core;
+    let agent_type = match &agent.agent_type {
+        AgentType::Conductor => Core::Conductor,
+        AgentType::Coder => Core::Coder,
+        AgentType::Shell => Core::Shell,
+        AgentType::FileOps => Core::FileOps,
+        AgentType::Search => Core::Search,
+        AgentType::Service => Core::Service,
+        AgentType::CliAgent { config_id } => Core::CliAgent {
+            config_id: config_id.clone(),
+        },
+        // TUI-only agents come back as custom agents with the same label
+        other => Core::Custom {
+            name: other.label().to_string(),
+        },
+    };
+    SavedAgent {
+        id: axiom_core::AgentId(agent.id.value()),
+        agent_type,
+        name: agent.name.clone(),
+        description: agent.description.clone(),
+        status: core_status(&agent.status),
+        output: agent.output.clone(),
+        parent_id: agent.parent_id.map(|id| axiom_core::AgentId(id.value())),
+    }
+}
+
+fn tui_agent(agent: axiom_core::Agent) -> Agent {
+    use axiom_core::AgentType as Core;
+    let agent_type = match agent.agent_type {
+        Core::Conductor => AgentType::Conductor,
+        Core::Coder => AgentType::Coder,
+        Core::Shell => AgentType::Shell,
+        Core::FileOps => AgentType::FileOps,
+        Core::Search => AgentType::Search,
+        Core::Service => AgentType::Service,
+        Core::Custom { name } => AgentType::Custom(name),
+        Core::CliAgent { config_id } => AgentType::CliAgent { config_id },
+    };
+    let mut restored = Agent::new(
+        AgentId::new(agent.id.0),
+        agent_type,
+        agent.name,
+        agent.description,
+        agent.parent_id.map(|id| AgentId::new(id.0)),
+    );
+    restored.append_output(&agent.output);
+    restored.status = tui_status(agent.status);
+    restored.completed_at = agent.completed_at;
+    restored
+}
+
+fn core_status(status: &AgentStatus) -> axiom_core::AgentStatus {
+    use axiom_core::AgentStatus as Core;
+    match status {
+        AgentStatus::Pending => Core::Pending,
+        AgentStatus::Running => Core::Running,
+        AgentStatus::Completed => Core::Completed,
+        AgentStatus::Error(message) => Core::Error(message.clone()),
+        AgentStatus::Cancelled => Core::Cancelled,
+        AgentStatus::Idle => Core::Idle,
+    }
+}
+
+fn tui_status(status: axiom_core::AgentStatus) -> AgentStatus {
+    use axiom_core::AgentStatus as Core;
+    match status {
+        Core::Pending => AgentStatus::Pending,
+        Core::Running => AgentStatus::Running,
+        Core::Completed => AgentStatus::Completed,
+        Core::Error(message) => AgentStatus::Error(message),
+        Core::Cancelled => AgentStatus::Cancelled,
+        Core::Idle => AgentStatus::Idle,
+    }
+}
+
+pub(crate) fn core_message(message: &ChatMessage) -> axiom_core::ChatMessage {
+    use axiom_core::llm::{MessageContent as Content, Role as CoreRole};
+    let role = match message.role {
+        Role::System => CoreRole::System,
+        Role::User => CoreRole::User,
+        Role::Assistant => CoreRole::Assistant,
+    };
+    axiom_core::ChatMessage {
+        role,
+        content: Content::Text(message.text()),
+    }
+}
+
+pub(crate) fn tui_message(message: &axiom_core::ChatMessage) -> ChatMessage {
+    use axiom_core::llm::Role as CoreRole;
+    let role = match message.role {
+        CoreRole::System => Role::System,
+        CoreRole::User => Role::User,
+        CoreRole::Assistant => Role::Assistant,
+    };
+    ChatMessage {
+        role,
+        content: MessageContent::Text(message.text()),
+    }
+}
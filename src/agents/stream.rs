@@ -0,0 +1,95 @@
+//! Live stats of an agent's current run
+//!
+//! While an agent streams, its output header shows how long the run has
+//! taken, how many tokens have arrived and how fast, and which provider and
+//! model answer, so a stalled or misrouted request stands out.
+
+use axiom_core::format_duration;
+use axiom_core::usage::estimate_tokens;
+use std::time::Instant;
+
+/// Stats of the run an agent is streaming
+#[derive(Debug, Clone)]
+pub struct StreamStats {
+    /// When the run started
+    pub started: Instant,
+
+    /// Tokens streamed so far (estimated from their text)
+    pub tokens: u64,
+
+    /// Provider and model answering, for LLM-backed runs
+    pub source: Option<(String, String)>,
+}
+
+impl StreamStats {
+    /// Stats of a run starting at `now`
+    pub fn new(now: Instant) -> Self {
+        Self {
+            started: now,
+            tokens: 0,
+            source: None,
+        }
+    }
+
+    /// Record that `provider`'s `model` answers the run
+    pub fn with_source(mut self, provider: &str, model: &str) -> Self {
+        self.source = Some((provider.to_string(), model.to_string()));
+        self
+    }
+
+    /// Count a streamed chunk
+    pub fn record(&mut self, chunk: &str) {
+        self.tokens += estimate_tokens(chunk);
+    }
+
+    /// Tokens per second over the run so far
+    pub fn tokens_per_sec(&self, now: Instant) -> f64 {
+        let secs = now.saturating_duration_since(self.started).as_secs_f64();
+        if secs < 1.0 {
+            0.0
+        } else {
+            self.tokens as f64 / secs
+        }
+    }
+
+    /// Header badges: elapsed time, tokens, tokens/sec and provider/model
+    pub fn badges(&self, now: Instant) -> Vec<String> {
+        let elapsed = now.saturating_duration_since(self.started);
+        let mut badges = vec![
+            format_duration(elapsed.as_secs()),
+            format!("{} tok", self.tokens),
+            format!("{:.1} tok/s", self.tokens_per_sec(now)),
+        ];
+        if let Some((provider, model)) = &self.source {
+            badges.push(format!("{}/{}", provider, model));
+        }
+        badges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_badges() {
+        let start = Instant::now();
+        let mut stats = StreamStats::new(start).with_source("ollama", "llama3.2");
+        stats.record("abcdefgh");
+        stats.record("ijkl");
+        assert_eq!(stats.tokens, 3);
+
+        let later = start + Duration::from_secs(2);
+        assert_eq!(
+            stats.badges(later),
+            vec!["2s", "3 tok", "1.5 tok/s", "ollama/llama3.2"]
+        );
+
+        // No rate until a second has passed, and no source for plain agents
+        assert_eq!(
+            StreamStats::new(start).badges(start),
+            vec!["0s", "0 tok", "0.0 tok/s"]
+        );
+    }
+}
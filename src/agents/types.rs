@@ -82,6 +82,22 @@ pub enum AgentType {
     /// Search/retrieval agent
     Search,
 
+    /// Writes tests for a target and reruns them until they pass (`/gen-tests`)
+    TestGen,
+
+    /// Drafts doc comments for review (`/gen-docs`)
+    DocGen,
+
+    /// Lists compiler errors or fixes one and rebuilds (`/fix`)
+    Fixer,
+
+    /// Runs the workspace's `[verify]` checks after a run wrote files and
+    /// has failures repaired
+    Verifier,
+
+    /// Long-running process (dev server, watcher) kept alive and health-checked
+    Service,
+
     /// Custom tool agent with a specified name
     Custom(String),
 
@@ -101,6 +117,11 @@ impl AgentType {
             AgentType::Shell => "Shell",
             AgentType::FileOps => "FileOps",
             AgentType::Search => "Search",
+            AgentType::TestGen => "Tests",
+            AgentType::DocGen => "Docs",
+            AgentType::Fixer => "Fix",
+            AgentType::Verifier => "Verify",
+            AgentType::Service => "Service",
             AgentType::Custom(name) => name,
             AgentType::CliAgent { config_id } => config_id,
         }
@@ -114,6 +135,11 @@ impl AgentType {
             AgentType::Shell => "🖥️",
             AgentType::FileOps => "📁",
             AgentType::Search => "🔍",
+            AgentType::TestGen => "🧪",
+            AgentType::DocGen => "📝",
+            AgentType::Fixer => "🩹",
+            AgentType::Verifier => "✅",
+            AgentType::Service => "🔁",
             AgentType::Custom(_) => "🔧",
             AgentType::CliAgent { .. } => "🤖",
         }
@@ -124,6 +150,27 @@ impl AgentType {
         matches!(self, AgentType::CliAgent { .. })
     }
 
+    /// Check if this agent runs shell commands
+    ///
+    /// Restricted workspaces don't let agents start these on their own.
+    pub fn runs_commands(&self) -> bool {
+        matches!(
+            self,
+            AgentType::Shell
+                | AgentType::TestGen
+                | AgentType::Fixer
+                | AgentType::Verifier
+                | AgentType::Service
+        )
+    }
+
+    /// Check if this agent keeps running until cancelled
+    ///
+    /// Long-running agents don't count toward "all done" for a Conductor run.
+    pub fn is_long_running(&self) -> bool {
+        matches!(self, AgentType::Service)
+    }
+
     /// Get the CLI agent config ID if this is a CLI agent
     pub fn cli_agent_config_id(&self) -> Option<&str> {
         match self {
@@ -204,6 +251,11 @@ mod tests {
         assert_eq!(AgentType::Coder.label(), "Coder");
         assert_eq!(AgentType::FileOps.label(), "FileOps");
         assert_eq!(AgentType::Search.label(), "Search");
+        assert_eq!(AgentType::TestGen.label(), "Tests");
+        assert_eq!(AgentType::Fixer.label(), "Fix");
+        assert_eq!(AgentType::Verifier.label(), "Verify");
+        assert_eq!(AgentType::Service.label(), "Service");
+        assert_eq!(AgentType::DocGen.label(), "Docs");
         assert_eq!(
             AgentType::CliAgent { config_id: "claude".to_string() }.label(),
             "claude"
@@ -217,6 +269,8 @@ mod tests {
         assert_eq!(AgentType::Shell.icon(), "🖥️");
         assert_eq!(AgentType::FileOps.icon(), "📁");
         assert_eq!(AgentType::Search.icon(), "🔍");
+        assert_eq!(AgentType::TestGen.icon(), "🧪");
+        assert_eq!(AgentType::DocGen.icon(), "📝");
         assert_eq!(AgentType::Custom("any".to_string()).icon(), "🔧");
         assert_eq!(AgentType::CliAgent { config_id: "test".to_string() }.icon(), "🤖");
     }
@@ -0,0 +1,285 @@
+//! Workspaces kept warm while another one is on screen
+//!
+//! Switching workspaces used to cancel every running agent. Now the agents
+//! of the workspace left behind are parked with its conductor and executor
+//! and keep running; their events are applied to the parked registry, and
+//! switching back brings them all on screen again. Agent ids come from one
+//! counter shared by every workspace's registry, so an event's id names the
+//! workspace it belongs to.
+
+use crate::agents::{AgentRegistry, AgentStatus, AgentType, Conductor, Executor, PtyAgentManager};
+use crate::events::Event;
+use crate::state::AgentId;
+use axiom_core::orchestration::RunOutcome;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The agents of a workspace that isn't on screen
+pub struct ParkedWorkspace {
+    /// Display name, for notes about its agents
+    pub name: String,
+
+    /// Whether its agents may run commands they decided on by themselves
+    pub trusted: bool,
+
+    /// Its conductor, with the conversation so far
+    pub conductor: Conductor,
+
+    /// Runs the agents it spawns, in its root
+    pub executor: Executor,
+
+    /// Its agents
+    pub registry: Arc<RwLock<AgentRegistry>>,
+
+    /// The request it was working on
+    pub run: Option<RunOutcome>,
+}
+
+impl ParkedWorkspace {
+    /// Whether `id` is one of its agents
+    fn owns(&self, id: AgentId) -> bool {
+        self.registry.read().get(id).is_some()
+    }
+
+    /// Apply an event of one of its agents; returns a note for the user
+    /// when an agent finished
+    fn handle(
+        &mut self,
+        event: &Event,
+        pty_manager: &Arc<RwLock<PtyAgentManager>>,
+    ) -> Option<String> {
+        let finished = match event {
+            Event::AgentSpawn(request) => {
+                let id = self.registry.write().spawn(request.clone());
+                // Restricted workspaces don't run commands an agent decided
+                // on by itself, on screen or not
+                if request.agent_type.runs_commands() && !self.trusted {
+                    self.registry
+                        .write()
+                        .error(id, "Workspace is not trusted".to_string());
+                } else if request.agent_type != AgentType::Conductor {
+                    self.executor.execute(id, request);
+                }
+                None
+            }
+            Event::AgentUpdate { id, status } => {
+                if let Some(agent) = self.registry.write().get_mut(*id) {
+                    agent.status = status.clone();
+                }
+                status.is_terminal().then_some(*id)
+            }
+            Event::AgentOutput { id, chunk } => {
+                self.registry.write().append_output(*id, chunk);
+                None
+            }
+            Event::AgentComplete { id } => {
+                self.registry.write().complete(*id);
+                Some(*id)
+            }
+            Event::AgentStreamStart {
+                id,
+                provider,
+                model,
+            } => {
+                self.registry.write().start_stream(*id, provider, model);
+                None
+            }
+            Event::LlmExchange { id, exchange } => {
+                self.registry
+                    .write()
+                    .record_exchange(*id, (**exchange).clone());
+                None
+            }
+            Event::ConductorResponse { response, .. } => {
+                self.conductor.add_response(response.clone());
+                None
+            }
+            Event::CliAgentOutput { id, data } => {
+                {
+                    let mut manager = pty_manager.write();
+                    manager.capture(*id, data);
+                    manager.parse_output(*id, data);
+                }
+                if let Some(agent) = self.registry.write().get_mut(*id) {
+                    agent.line_count += data.iter().filter(|&&b| b == b'\n').count();
+                }
+                None
+            }
+            Event::CliAgentExit { id, exit_code } => {
+                pty_manager.write().mark_exited(*id);
+                let mut registry = self.registry.write();
+                if *exit_code == 0 {
+                    registry.complete(*id);
+                } else {
+                    registry.error(*id, format!("Exited with code {}", exit_code));
+                }
+                Some(*id)
+            }
+            _ => None,
+        }?;
+
+        let registry = self.registry.read();
+        let agent = registry.get(finished)?;
+        let outcome = match agent.status {
+            AgentStatus::Completed => "finished",
+            AgentStatus::Error(_) => "failed",
+            _ => return None,
+        };
+        Some(format!("{} {} in {}", agent.name, outcome, self.name))
+    }
+}
+
+/// The workspace on screen and the ones parked in the background
+pub struct WorkspaceSessions {
+    /// Root of the workspace on screen
+    active: PathBuf,
+
+    /// Parked workspaces by root
+    parked: HashMap<PathBuf, ParkedWorkspace>,
+}
+
+impl WorkspaceSessions {
+    /// Sessions with the workspace at `cwd` on screen
+    pub fn new(cwd: impl Into<PathBuf>) -> Self {
+        Self {
+            active: cwd.into(),
+            parked: HashMap::new(),
+        }
+    }
+
+    /// Root of the workspace on screen
+    pub fn active(&self) -> &Path {
+        &self.active
+    }
+
+    /// Park the workspace on screen and bring back the one at `cwd`, if it
+    /// was parked
+    pub fn switch(&mut self, parked: ParkedWorkspace, cwd: &Path) -> Option<ParkedWorkspace> {
+        let left = std::mem::replace(&mut self.active, cwd.to_path_buf());
+        self.parked.insert(left, parked);
+        self.parked.remove(cwd)
+    }
+
+    /// Apply `event` to the parked workspace it belongs to
+    ///
+    /// Returns None when it belongs to the workspace on screen, else a
+    /// note for the user, if any.
+    pub fn handle_background(
+        &mut self,
+        event: &Event,
+        pty_manager: &Arc<RwLock<PtyAgentManager>>,
+    ) -> Option<Option<String>> {
+        let id = match event {
+            Event::AgentSpawn(request) => request.parent_id?,
+            Event::AgentUpdate { id, .. }
+            | Event::AgentOutput { id, .. }
+            | Event::AgentComplete { id }
+            | Event::AgentStreamStart { id, .. }
+            | Event::LlmExchange { id, .. }
+            | Event::ConductorResponse { id, .. }
+            | Event::CliAgentOutput { id, .. }
+            | Event::CliAgentExit { id, .. } => *id,
+            // Its coder already wrote the file; its run notes it
+            Event::FileModification { path, .. } => {
+                let path = Path::new(path);
+                if path.starts_with(&self.active) {
+                    return None;
+                }
+                let (root, parked) = self
+                    .parked
+                    .iter_mut()
+                    .find(|(root, _)| path.starts_with(root))?;
+                if let (Some(run), Ok(relative)) = (&mut parked.run, path.strip_prefix(root)) {
+                    run.file_written(relative);
+                }
+                return Some(None);
+            }
+            _ => return None,
+        };
+        let parked = self.parked.values_mut().find(|parked| parked.owns(id))?;
+        Some(parked.handle(event, pty_manager))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::AgentSpawnRequest;
+    use crate::llm::ProviderRegistry;
+
+    fn parked(name: &str, registry: AgentRegistry, trusted: bool) -> ParkedWorkspace {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let registry = Arc::new(RwLock::new(registry));
+        ParkedWorkspace {
+            name: name.to_string(),
+            trusted,
+            conductor: Conductor::new(Arc::new(RwLock::new(ProviderRegistry::new())), tx.clone()),
+            executor: Executor::new(tx, registry.clone(), std::env::temp_dir()),
+            registry,
+            run: None,
+        }
+    }
+
+    #[test]
+    fn test_parked_agents_keep_their_events() {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let pty_manager = Arc::new(RwLock::new(PtyAgentManager::new(tx)));
+        let home = AgentRegistry::new();
+        let shop = home.sharing_ids();
+        let mut sessions = WorkspaceSessions::new("/home");
+
+        // Ids stay unique across the workspaces' registries
+        let mut home = parked("home", home, false);
+        let conductor = home.registry.write().spawn(AgentSpawnRequest {
+            agent_type: AgentType::Conductor,
+            name: "Conductor".to_string(),
+            description: String::new(),
+            parameters: None,
+            parent_id: None,
+        });
+        home.conductor.set_agent_id(conductor);
+        let shop = parked("shop", shop, true);
+        let other = shop.registry.write().spawn(AgentSpawnRequest {
+            agent_type: AgentType::Coder,
+            name: "Coder".to_string(),
+            description: String::new(),
+            parameters: None,
+            parent_id: None,
+        });
+        assert_ne!(conductor, other);
+
+        assert!(sessions.switch(home, Path::new("/shop")).is_none());
+        assert_eq!(sessions.active(), Path::new("/shop"));
+
+        // Events of the workspace on screen aren't taken
+        let output = |id| Event::AgentOutput {
+            id,
+            chunk: "hi".to_string(),
+        };
+        assert!(sessions.handle_background(&output(other), &pty_manager).is_none());
+        assert_eq!(
+            sessions.handle_background(&output(conductor), &pty_manager),
+            Some(None)
+        );
+
+        // A command the parked conductor started is still refused there
+        let spawn = Event::AgentSpawn(AgentSpawnRequest {
+            agent_type: AgentType::Shell,
+            name: "Shell".to_string(),
+            description: "ls".to_string(),
+            parameters: Some("ls".to_string()),
+            parent_id: Some(conductor),
+        });
+        assert!(sessions.handle_background(&spawn, &pty_manager).is_some());
+        let home = sessions.switch(shop, Path::new("/home")).unwrap();
+        let registry = home.registry.read();
+        assert_eq!(registry.get(conductor).unwrap().output, "hi");
+        let shell = registry.children(conductor)[0].id;
+        assert!(matches!(
+            registry.get(shell).unwrap().status,
+            AgentStatus::Error(_)
+        ));
+    }
+}
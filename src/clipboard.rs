@@ -1,9 +1,13 @@
 //! System clipboard wrapper for Axiom
 //!
 //! Provides a simple interface to the system clipboard using arboard.
+//! When no system clipboard is reachable (e.g. over SSH), copies fall back to
+//! the OSC 52 escape sequence on terminals that support it.
 
+use crate::ui::capabilities;
 use arboard::Clipboard;
 use parking_lot::Mutex;
+use std::io::Write;
 use std::sync::OnceLock;
 
 /// Global clipboard instance (lazily initialized)
@@ -16,12 +20,52 @@ pub fn init() {
 
 /// Copy text to clipboard
 pub fn copy(text: &str) -> Result<(), String> {
+    let result = copy_system(text);
+    if result.is_err() && capabilities().osc52 {
+        return copy_osc52(text);
+    }
+    result
+}
+
+fn copy_system(text: &str) -> Result<(), String> {
     let guard = CLIPBOARD.get().ok_or("Clipboard not initialized")?;
     let mut lock = guard.lock();
     let clipboard = lock.as_mut().ok_or("Clipboard unavailable")?;
     clipboard.set_text(text).map_err(|e| e.to_string())
 }
 
+/// Ask the terminal to set the clipboard via OSC 52
+fn copy_osc52(text: &str) -> Result<(), String> {
+    let mut stdout = std::io::stdout();
+    write!(stdout, "{}", osc52_sequence(text))
+        .and_then(|_| stdout.flush())
+        .map_err(|e| e.to_string())
+}
+
+/// Build the OSC 52 "set clipboard" sequence
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))
+}
+
+/// Standard base64 encoding (with padding)
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
 /// Get text from clipboard
 pub fn paste() -> Result<String, String> {
     let guard = CLIPBOARD.get().ok_or("Clipboard not initialized")?;
@@ -40,4 +84,13 @@ mod tests {
         // Should not panic on double init
         init();
     }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
 }
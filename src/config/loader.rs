@@ -3,6 +3,7 @@
 //! Loads configuration from `.axiom.toml` in project root or user config directory.
 
 use super::types::{AxiomConfig, ProviderConfig};
+use axiom_core::WorkspaceConfig;
 use regex::Regex;
 use std::path::{Path, PathBuf};
 
@@ -43,6 +44,45 @@ pub fn load_config(project_dir: &Path) -> Result<AxiomConfig, ConfigError> {
     Ok(apply_env_overrides(AxiomConfig::default()))
 }
 
+/// `config` with the project's own provider, model and CLI agent from its
+/// workspace config (`.axiom/config.toml`) put over it
+pub fn with_workspace_overrides(config: &AxiomConfig, project_dir: &Path) -> AxiomConfig {
+    let mut config = config.clone();
+    let Some(workspace) = std::fs::read_to_string(project_dir.join(".axiom").join("config.toml"))
+        .ok()
+        .and_then(|content| toml::from_str::<WorkspaceConfig>(&content).ok())
+    else {
+        return config;
+    };
+
+    if let Some(llm) = workspace.llm {
+        if let Some(provider) = llm.default_provider {
+            config.llm.default_provider = provider;
+        }
+        for (name, overrides) in llm.providers {
+            let provider = config.llm.providers.entry(name).or_default();
+            if overrides.model.is_some() {
+                provider.default_model = overrides.model;
+            }
+            if overrides.api_key.is_some() {
+                provider.api_key = overrides.api_key;
+            }
+            if overrides.base_url.is_some() {
+                provider.base_url = overrides.base_url;
+            }
+        }
+        if let Some(model) = llm.default_model {
+            let default_provider = config.llm.default_provider.clone();
+            let provider = config.llm.providers.entry(default_provider).or_default();
+            provider.default_model = Some(model);
+        }
+    }
+    if let Some(agent) = workspace.default_cli_agent {
+        config.cli_agents.default = Some(agent);
+    }
+    config
+}
+
 /// Get user config directory path
 fn get_user_config_path() -> Option<PathBuf> {
     dirs::config_dir().map(|d| d.join("axiom").join("config.toml"))
@@ -73,6 +113,9 @@ fn expand_env_vars(config: &mut AxiomConfig) {
         if let Some(ref base_url) = provider.base_url {
             provider.base_url = Some(expand_string(base_url, &env_regex));
         }
+        for value in provider.extra_headers.values_mut() {
+            *value = expand_string(value, &env_regex);
+        }
     }
 }
 
@@ -193,10 +236,22 @@ default_model = "gemma3:4b"
 enabled = false
 api_key = "${OPENAI_API_KEY}"
 default_model = "gpt-4o"
+# Tokens that expire can be fetched by a command instead, printing the
+# token or JSON with access_token and expires_in
+# api_key_command = "az account get-access-token --query accessToken -o tsv"
+# Or, for a key rotated by hand, a reminder before it expires (Unix seconds)
+# api_key_expires_at = 1767225600
 
 # CLI Coding Agents
 # Invoke with #agent syntax, e.g., "#claude explain this code"
 
+[cli_agents]
+# Lines of output kept per agent, beyond its screen
+scrollback_lines = 5000
+# Agent started by "# prompt", without a name (a workspace's
+# default_cli_agent wins over it)
+# default = "claude"
+
 [cli_agents.agents.claude]
 enabled = true
 name = "Claude Code"
@@ -223,6 +278,27 @@ icon = "🐙"
 # command = "/path/to/agent"
 # default_args = ["--interactive"]
 # icon = "⚙️"
+
+[agents]
+# Seconds before a finished agent folds under its Conductor run (Space on
+# the Conductor in the Agents panel shows them)
+collapse_after_secs = 30
+# Earlier Conductor runs whose agents are kept
+keep_runs = 3
+
+[prompts]
+# System prompts come from .axiom/prompts/<role>.md when the workspace has
+# one (conductor, orchestrator, developer), else the built-in ones; name
+# another template for a role here. Templates may use {{workspace_name}},
+# {{file_tree}} and {{task}}, and edits apply to the next request.
+# developer = "careful-developer"
+
+[keys]
+# Chords for global actions (`/keys` lists them); an empty list unbinds
+# model_selector = "ctrl+o"
+# search = ["ctrl+f", "alt+f"]
+# [keys.panels.file_tree]
+# quit = []
 "##
 }
 
@@ -237,6 +313,28 @@ mod tests {
         assert!(config.llm.providers.contains_key("ollama"));
     }
 
+    #[test]
+    fn test_workspace_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AxiomConfig::default();
+        let effective = with_workspace_overrides(&config, dir.path());
+        assert_eq!(effective.llm.default_provider, "ollama");
+
+        std::fs::create_dir(dir.path().join(".axiom")).unwrap();
+        std::fs::write(
+            dir.path().join(".axiom").join("config.toml"),
+            "default_cli_agent = \"gemini\"\n\n[llm]\ndefault_provider = \"claude\"\ndefault_model = \"claude-opus-4-20250514\"\n",
+        )
+        .unwrap();
+        let effective = with_workspace_overrides(&config, dir.path());
+        assert_eq!(effective.llm.default_provider, "claude");
+        assert_eq!(
+            effective.llm.providers["claude"].default_model.as_deref(),
+            Some("claude-opus-4-20250514")
+        );
+        assert_eq!(effective.cli_agents.default.as_deref(), Some("gemini"));
+    }
+
     #[test]
     fn test_expand_env_var() {
         let regex = Regex::new(r"\$\{([^}]+)\}").unwrap();
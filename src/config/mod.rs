@@ -9,6 +9,9 @@ mod types;
 mod writer;
 
 pub use cli_agents::{CliAgentConfig, CliAgentsConfig};
-pub use loader::{load_config, sample_config, ConfigError};
-pub use types::{AxiomConfig, LlmConfig, ProviderConfig};
-pub use writer::{config_path, save_config, user_config_path, WriteError};
+pub use loader::{load_config, sample_config, with_workspace_overrides, ConfigError};
+pub use types::{
+    AgentsConfig, AxiomConfig, CompletionConfig, DirSort, DirectoryPickerConfig, DiscoveryConfig,
+    LlmConfig, ProviderConfig, SoundConfig, SuggestionsConfig, TerminalConfig,
+};
+pub use writer::{config_path, loaded_config_path, save_config, user_config_path, WriteError};
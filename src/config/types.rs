@@ -3,8 +3,11 @@
 //! Defines the structure of `.axiom.toml` configuration.
 
 use super::cli_agents::CliAgentsConfig;
+use axiom_core::config::{AgentModelConfig, ContextConfig, BUILTIN_PROVIDERS};
+use axiom_core::llm::{ProviderRouting, RateLimits};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// Root configuration structure
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -16,6 +19,305 @@ pub struct AxiomConfig {
     /// CLI agent configurations
     #[serde(default)]
     pub cli_agents: CliAgentsConfig,
+
+    /// Directory picker preferences (bookmarks, hidden, sorting)
+    #[serde(default)]
+    pub directory_picker: DirectoryPickerConfig,
+
+    /// Workspace auto-discovery roots
+    #[serde(default)]
+    pub discovery: DiscoveryConfig,
+
+    /// Window title and progress reporting
+    #[serde(default)]
+    pub terminal: TerminalConfig,
+
+    /// Inline editor completions (enabled per workspace)
+    #[serde(default)]
+    pub completion: CompletionConfig,
+
+    /// Audio cues for agent completions, approvals and errors
+    #[serde(default)]
+    pub sounds: SoundConfig,
+
+    /// Follow-up prompts offered above the input after a run
+    #[serde(default)]
+    pub suggestions: SuggestionsConfig,
+
+    /// How long the agents of Conductor runs stay in the Agents panel
+    #[serde(default)]
+    pub agents: AgentsConfig,
+
+    /// Language servers for the editor's diagnostics (trusted workspaces)
+    #[serde(default)]
+    pub lsp: axiom_core::config::LspConfig,
+
+    /// Key bindings for global actions, overridable per panel
+    #[serde(default)]
+    pub keys: axiom_core::config::KeymapConfig,
+
+    /// Template in `.axiom/prompts/` each agent role's system prompt uses
+    #[serde(default)]
+    pub prompts: axiom_core::config::PromptsConfig,
+}
+
+/// Audio cue settings
+///
+/// Off unless `enabled`; each event then sounds unless switched off. Without
+/// a `command` the cue is the terminal bell.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoundConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Shell command that plays a sound, e.g. `paplay ~/done.oga`; the event
+    /// (`agent_done`, `approval` or `error`) is in `AXIOM_EVENT`
+    #[serde(default)]
+    pub command: Option<String>,
+
+    /// An agent finished
+    #[serde(default = "default_enabled")]
+    pub agent_done: bool,
+
+    /// A write is waiting for approval
+    #[serde(default = "default_enabled")]
+    pub approval: bool,
+
+    /// An error was reported
+    #[serde(default = "default_enabled")]
+    pub error: bool,
+
+    /// Local time range without sounds, e.g. `"22:00-07:00"`
+    #[serde(default)]
+    pub quiet_hours: Option<String>,
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command: None,
+            agent_done: true,
+            approval: true,
+            error: true,
+            quiet_hours: None,
+        }
+    }
+}
+
+/// Follow-up suggestions
+///
+/// After the conductor and the agents it started finish, a few likely next
+/// prompts are shown as chips on the input; Alt+1 to Alt+3 (or a click)
+/// puts one in the input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuggestionsConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+impl Default for SuggestionsConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Retention of the agents a Conductor run starts
+///
+/// A finished agent folds under the run's Conductor after
+/// `collapse_after_secs`; Space on the Conductor in the Agents panel shows
+/// them again. The agents of the last `keep_runs` runs are kept besides the
+/// current run's, and older ones are removed when a new run starts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentsConfig {
+    #[serde(default = "default_collapse_after")]
+    pub collapse_after_secs: u64,
+
+    #[serde(default = "default_keep_runs")]
+    pub keep_runs: usize,
+}
+
+impl Default for AgentsConfig {
+    fn default() -> Self {
+        Self {
+            collapse_after_secs: default_collapse_after(),
+            keep_runs: default_keep_runs(),
+        }
+    }
+}
+
+fn default_collapse_after() -> u64 {
+    30
+}
+
+fn default_keep_runs() -> usize {
+    3
+}
+
+/// Fill-in-the-middle completion model for editor ghost text
+///
+/// Completions only run in workspaces with `completion = true` in their
+/// `.axiom/config.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompletionConfig {
+    /// Ollama server
+    #[serde(default = "default_completion_url")]
+    pub base_url: String,
+
+    /// A fast model with a fill-in-the-middle template, e.g. `codellama:7b-code`
+    #[serde(default = "default_completion_model")]
+    pub model: String,
+
+    /// Idle time after the last keystroke before asking for a completion
+    #[serde(default = "default_completion_debounce")]
+    pub debounce_ms: u64,
+
+    /// Longest completion, in tokens
+    #[serde(default = "default_completion_tokens")]
+    pub max_tokens: u32,
+}
+
+fn default_completion_url() -> String {
+    "http://localhost:11434".to_string()
+}
+
+fn default_completion_model() -> String {
+    "codellama:7b-code".to_string()
+}
+
+fn default_completion_debounce() -> u64 {
+    400
+}
+
+fn default_completion_tokens() -> u32 {
+    48
+}
+
+impl Default for CompletionConfig {
+    fn default() -> Self {
+        Self {
+            base_url: default_completion_url(),
+            model: default_completion_model(),
+            debounce_ms: default_completion_debounce(),
+            max_tokens: default_completion_tokens(),
+        }
+    }
+}
+
+/// Terminal integration settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerminalConfig {
+    /// Show the workspace and running agents in the window (or tmux pane) title
+    #[serde(default = "default_enabled")]
+    pub title: bool,
+
+    /// Report long agent runs as OSC 9 progress and notify when they finish
+    #[serde(default)]
+    pub osc_progress: bool,
+
+    /// Seconds agents must run before progress is reported
+    #[serde(default = "default_progress_after")]
+    pub progress_after_secs: u64,
+}
+
+fn default_progress_after() -> u64 {
+    10
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self {
+            title: true,
+            osc_progress: false,
+            progress_after_secs: default_progress_after(),
+        }
+    }
+}
+
+/// Workspace auto-discovery settings
+///
+/// Unregistered git repos and projects found under `roots` are offered in
+/// the workspace selector. Discovery is off while `roots` is empty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscoveryConfig {
+    /// Directories to scan, e.g. `~/code`
+    #[serde(default)]
+    pub roots: Vec<PathBuf>,
+
+    /// How many directory levels below each root to look
+    #[serde(default = "default_discovery_depth")]
+    pub max_depth: usize,
+}
+
+fn default_discovery_depth() -> usize {
+    2
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            max_depth: default_discovery_depth(),
+        }
+    }
+}
+
+/// Directory picker preferences
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DirectoryPickerConfig {
+    /// Bookmarked directories, shown at the top of the picker
+    #[serde(default)]
+    pub bookmarks: Vec<PathBuf>,
+
+    /// Show hidden (dot) directories
+    #[serde(default)]
+    pub show_hidden: bool,
+
+    /// Sort order for directory listings
+    #[serde(default)]
+    pub sort: DirSort,
+}
+
+/// Sort order for directory listings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DirSort {
+    /// Alphabetical, A-Z
+    #[default]
+    Name,
+    /// Alphabetical, Z-A
+    NameDesc,
+    /// Most recently modified first
+    Modified,
+}
+
+impl DirSort {
+    /// Next sort order (for cycling)
+    pub fn next(self) -> Self {
+        match self {
+            DirSort::Name => DirSort::NameDesc,
+            DirSort::NameDesc => DirSort::Modified,
+            DirSort::Modified => DirSort::Name,
+        }
+    }
+
+    /// Short label for display
+    pub fn label(self) -> &'static str {
+        match self {
+            DirSort::Name => "name",
+            DirSort::NameDesc => "name desc",
+            DirSort::Modified => "modified",
+        }
+    }
+
+    /// Config value
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DirSort::Name => "name",
+            DirSort::NameDesc => "name_desc",
+            DirSort::Modified => "modified",
+        }
+    }
 }
 
 /// LLM configuration section
@@ -36,6 +338,22 @@ pub struct LlmConfig {
     /// Provider configurations
     #[serde(default)]
     pub providers: HashMap<String, ProviderConfig>,
+
+    /// Request budgets per provider (`[llm.rate_limits.claude]`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rate_limits: HashMap<String, RateLimits>,
+
+    /// Data residency marks per provider (`[llm.routing.claude]`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub routing: HashMap<String, ProviderRouting>,
+
+    /// Provider and model per agent role (`[llm.agents.reviewer]`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub agents: HashMap<String, AgentModelConfig>,
+
+    /// How much conversation the Conductor sends (`[llm.context]`)
+    #[serde(default)]
+    pub context: ContextConfig,
 }
 
 fn default_provider() -> String {
@@ -57,6 +375,10 @@ impl Default for LlmConfig {
             timeout: default_timeout(),
             max_retries: default_max_retries(),
             providers: default_providers(),
+            rate_limits: HashMap::new(),
+            routing: HashMap::new(),
+            agents: HashMap::new(),
+            context: ContextConfig::default(),
         }
     }
 }
@@ -72,6 +394,16 @@ pub struct ProviderConfig {
     #[serde(default)]
     pub api_key: Option<String>,
 
+    /// Command printing a fresh API key, for tokens that expire (e.g.
+    /// `az account get-access-token --query accessToken -o tsv`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_command: Option<String>,
+
+    /// When the API key expires, in Unix seconds; reminded about a week
+    /// ahead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_expires_at: Option<u64>,
+
     /// Base URL for the API
     #[serde(default)]
     pub base_url: Option<String>,
@@ -83,6 +415,11 @@ pub struct ProviderConfig {
     /// Available models (if empty, will be fetched from API)
     #[serde(default)]
     pub models: Vec<String>,
+
+    /// Headers sent with every request, e.g. OpenRouter's `HTTP-Referer`
+    /// (values support ${ENV_VAR} syntax)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra_headers: HashMap<String, String>,
 }
 
 fn default_enabled() -> bool {
@@ -94,9 +431,12 @@ impl Default for ProviderConfig {
         Self {
             enabled: true,
             api_key: None,
+            api_key_command: None,
+            api_key_expires_at: None,
             base_url: None,
             default_model: None,
             models: Vec::new(),
+            extra_headers: HashMap::new(),
         }
     }
 }
@@ -111,9 +451,12 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
         ProviderConfig {
             enabled: true,
             api_key: None,
+            api_key_command: None,
+            api_key_expires_at: None,
             base_url: Some("http://localhost:11434".to_string()),
             default_model: Some("gemma3:4b".to_string()),
             models: Vec::new(),
+            extra_headers: HashMap::new(),
         },
     );
 
@@ -123,12 +466,15 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
         ProviderConfig {
             enabled: false, // Disabled by default until API key is set
             api_key: None,
+            api_key_command: None,
+            api_key_expires_at: None,
             base_url: Some("https://api.anthropic.com".to_string()),
             default_model: Some("claude-sonnet-4-20250514".to_string()),
             models: vec![
                 "claude-sonnet-4-20250514".to_string(),
                 "claude-opus-4-20250514".to_string(),
             ],
+            extra_headers: HashMap::new(),
         },
     );
 
@@ -138,6 +484,8 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
         ProviderConfig {
             enabled: false, // Disabled by default until API key is set
             api_key: None,
+            api_key_command: None,
+            api_key_expires_at: None,
             base_url: Some("https://generativelanguage.googleapis.com".to_string()),
             default_model: Some("gemini-2.0-flash".to_string()),
             models: vec![
@@ -145,6 +493,7 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
                 "gemini-1.5-pro".to_string(),
                 "gemini-1.5-flash".to_string(),
             ],
+            extra_headers: HashMap::new(),
         },
     );
 
@@ -154,6 +503,8 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
         ProviderConfig {
             enabled: false,
             api_key: None,
+            api_key_command: None,
+            api_key_expires_at: None,
             base_url: Some("https://api.openai.com".to_string()),
             default_model: Some("gpt-4o".to_string()),
             models: vec![
@@ -161,6 +512,7 @@ fn default_providers() -> HashMap<String, ProviderConfig> {
                 "gpt-4o-mini".to_string(),
                 "gpt-4-turbo".to_string(),
             ],
+            extra_headers: HashMap::new(),
         },
     );
 
@@ -186,6 +538,36 @@ impl AxiomConfig {
             .filter(|(_, config)| config.enabled && config.api_key.is_some())
             .collect()
     }
+    /// Check that names refer to things that are configured: the default
+    /// provider and the default CLI agent, that custom providers have a
+    /// base URL, and that the key bindings parse
+    pub fn validate(&self) -> Result<(), String> {
+        let mut custom: Vec<_> = self
+            .llm
+            .providers
+            .iter()
+            .filter(|(id, _)| !BUILTIN_PROVIDERS.contains(&id.as_str()))
+            .collect();
+        custom.sort_by_key(|(id, _)| id.as_str());
+        if let Some((id, _)) = custom.iter().find(|(_, cfg)| cfg.base_url.is_none()) {
+            return Err(format!(
+                "[llm.providers.{}] needs a base_url for its OpenAI-compatible endpoint",
+                id
+            ));
+        }
+        if !self.llm.providers.contains_key(&self.llm.default_provider) {
+            return Err(format!(
+                "default_provider '{}' isn't in [llm.providers]",
+                self.llm.default_provider
+            ));
+        }
+        if let Some(ref agent) = self.cli_agents.default {
+            if !self.cli_agents.agents.contains_key(agent) {
+                return Err(format!("cli_agents.default '{}' isn't configured", agent));
+            }
+        }
+        self.keys.validate()
+    }
 }
 
 #[cfg(test)]
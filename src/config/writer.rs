@@ -3,6 +3,7 @@
 //! Saves configuration to `.axiom.toml` files.
 
 use super::types::AxiomConfig;
+use axiom_core::config::BUILTIN_PROVIDERS;
 use std::path::{Path, PathBuf};
 
 /// Configuration writing error
@@ -32,6 +33,17 @@ pub fn user_config_path() -> Option<PathBuf> {
     dirs::config_dir().map(|d| d.join("axiom").join("config.toml"))
 }
 
+/// Get the path of the config file `load_config` reads for a project
+///
+/// The project's `.axiom.toml` if it exists, otherwise the user config.
+pub fn loaded_config_path(project_dir: &Path) -> PathBuf {
+    let project = config_path(project_dir);
+    if project.exists() {
+        return project;
+    }
+    user_config_path().unwrap_or(project)
+}
+
 /// Save configuration to a TOML file
 ///
 /// # Arguments
@@ -85,16 +97,39 @@ fn serialize_config(config: &AxiomConfig) -> Result<String, toml::ser::Error> {
         config.llm.max_retries
     ));
 
-    // Write each provider in a consistent order
-    let provider_order = ["claude", "gemini", "ollama", "openai"];
+    // Write the built-in providers in a consistent order, then custom
+    // endpoints by name
+    let mut custom: Vec<&str> = config
+        .llm
+        .providers
+        .keys()
+        .map(String::as_str)
+        .filter(|id| !BUILTIN_PROVIDERS.contains(id))
+        .collect();
+    custom.sort_unstable();
 
-    for provider_name in provider_order {
+    for provider_name in BUILTIN_PROVIDERS.iter().copied().chain(custom) {
         if let Some(provider) = config.llm.providers.get(provider_name) {
             output.push_str(&format!("\n[llm.providers.{}]\n", provider_name));
             output.push_str(&format!("enabled = {}\n", provider.enabled));
 
+            // A key fetched by a command is never saved, only the command
+            if let Some(ref command) = provider.api_key_command {
+                output.push_str(&format!(
+                    "api_key_command = {}\n",
+                    toml::Value::String(command.clone())
+                ));
+            }
+            if let Some(expires_at) = provider.api_key_expires_at {
+                output.push_str(&format!("api_key_expires_at = {}\n", expires_at));
+            }
+
             // Write API key if present (mask in comments but save actual value)
-            if let Some(ref api_key) = provider.api_key {
+            if let Some(api_key) = provider
+                .api_key
+                .as_ref()
+                .filter(|_| provider.api_key_command.is_none())
+            {
                 if !api_key.is_empty() && !api_key.starts_with("${") {
                     // Direct key value
                     output.push_str(&format!("api_key = \"{}\"\n", api_key));
@@ -120,6 +155,191 @@ fn serialize_config(config: &AxiomConfig) -> Result<String, toml::ser::Error> {
                     provider.models.iter().map(|m| format!("\"{}\"", m)).collect();
                 output.push_str(&format!("models = [{}]\n", models_str.join(", ")));
             }
+
+            // Write extra headers as an inline table
+            if !provider.extra_headers.is_empty() {
+                let mut headers: Vec<_> = provider.extra_headers.iter().collect();
+                headers.sort();
+                let pairs: Vec<String> = headers
+                    .into_iter()
+                    .map(|(name, value)| {
+                        format!(
+                            "{} = {}",
+                            toml::Value::String(name.clone()),
+                            toml::Value::String(value.clone())
+                        )
+                    })
+                    .collect();
+                output.push_str(&format!("extra_headers = {{ {} }}\n", pairs.join(", ")));
+            }
+        }
+    }
+
+    let mut rate_limited: Vec<_> = config.llm.rate_limits.iter().collect();
+    rate_limited.sort_by_key(|(name, _)| name.as_str());
+    for (provider_name, limits) in rate_limited {
+        output.push_str(&format!("\n[llm.rate_limits.{}]\n", provider_name));
+        if let Some(rpm) = limits.requests_per_minute {
+            output.push_str(&format!("requests_per_minute = {}\n", rpm));
+        }
+        if let Some(tpm) = limits.tokens_per_minute {
+            output.push_str(&format!("tokens_per_minute = {}\n", tpm));
+        }
+    }
+
+    let mut routed: Vec<_> = config.llm.routing.iter().collect();
+    routed.sort_by_key(|(name, _)| name.as_str());
+    for (provider_name, routing) in routed {
+        output.push_str(&format!("\n[llm.routing.{}]\n", provider_name));
+        if let Some(ref region) = routing.region {
+            output.push_str(&format!("region = \"{}\"\n", region));
+        }
+        if let Some(classification) = routing.classification {
+            output.push_str(&format!(
+                "classification = \"{}\"\n",
+                classification.label()
+            ));
+        }
+    }
+
+    let mut mapped: Vec<_> = config.llm.agents.iter().collect();
+    mapped.sort_by_key(|(role, _)| role.as_str());
+    for (role, agent) in mapped {
+        output.push_str(&format!("\n[llm.agents.{}]\n", role));
+        output.push_str(&format!("provider = \"{}\"\n", agent.provider));
+        output.push_str(&format!("model = \"{}\"\n", agent.model));
+    }
+
+    let context = &config.llm.context;
+    if context != &Default::default() {
+        output.push_str("\n[llm.context]\n");
+        output.push_str(&format!("reserve_tokens = {}\n", context.reserve_tokens));
+        output.push_str(&format!("summarize = {}\n", context.summarize));
+
+        let mut windows: Vec<_> = context.max_tokens.iter().collect();
+        windows.sort_by_key(|(provider_name, _)| provider_name.as_str());
+        if !windows.is_empty() {
+            output.push_str("\n[llm.context.max_tokens]\n");
+        }
+        for (provider_name, tokens) in windows {
+            output.push_str(&format!("{} = {}\n", provider_name, tokens));
+        }
+    }
+
+    let picker = &config.directory_picker;
+    if picker != &Default::default() {
+        output.push_str("\n[directory_picker]\n");
+        output.push_str(&format!("show_hidden = {}\n", picker.show_hidden));
+        output.push_str(&format!("sort = \"{}\"\n", picker.sort.as_str()));
+        let bookmarks: Vec<String> = picker
+            .bookmarks
+            .iter()
+            .map(|b| toml::Value::String(b.to_string_lossy().to_string()).to_string())
+            .collect();
+        output.push_str(&format!("bookmarks = [{}]\n", bookmarks.join(", ")));
+    }
+
+    let discovery = &config.discovery;
+    if discovery != &Default::default() {
+        output.push_str("\n[discovery]\n");
+        let roots: Vec<String> = discovery
+            .roots
+            .iter()
+            .map(|r| toml::Value::String(r.to_string_lossy().to_string()).to_string())
+            .collect();
+        output.push_str(&format!("roots = [{}]\n", roots.join(", ")));
+        output.push_str(&format!("max_depth = {}\n", discovery.max_depth));
+    }
+
+    let terminal = &config.terminal;
+    if terminal != &Default::default() {
+        output.push_str("\n[terminal]\n");
+        output.push_str(&format!("title = {}\n", terminal.title));
+        output.push_str(&format!("osc_progress = {}\n", terminal.osc_progress));
+        output.push_str(&format!(
+            "progress_after_secs = {}\n",
+            terminal.progress_after_secs
+        ));
+    }
+
+    let completion = &config.completion;
+    if completion != &Default::default() {
+        output.push_str("\n[completion]\n");
+        output.push_str(&format!(
+            "base_url = {}\n",
+            toml::Value::String(completion.base_url.clone())
+        ));
+        output.push_str(&format!(
+            "model = {}\n",
+            toml::Value::String(completion.model.clone())
+        ));
+        output.push_str(&format!("debounce_ms = {}\n", completion.debounce_ms));
+        output.push_str(&format!("max_tokens = {}\n", completion.max_tokens));
+    }
+
+    let sounds = &config.sounds;
+    if sounds != &Default::default() {
+        output.push_str("\n[sounds]\n");
+        output.push_str(&format!("enabled = {}\n", sounds.enabled));
+        if let Some(command) = &sounds.command {
+            output.push_str(&format!(
+                "command = {}\n",
+                toml::Value::String(command.clone())
+            ));
+        }
+        output.push_str(&format!("agent_done = {}\n", sounds.agent_done));
+        output.push_str(&format!("approval = {}\n", sounds.approval));
+        output.push_str(&format!("error = {}\n", sounds.error));
+        if let Some(quiet_hours) = &sounds.quiet_hours {
+            output.push_str(&format!(
+                "quiet_hours = {}\n",
+                toml::Value::String(quiet_hours.clone())
+            ));
+        }
+    }
+
+    if config.suggestions != Default::default() {
+        output.push_str("\n[suggestions]\n");
+        output.push_str(&format!("enabled = {}\n", config.suggestions.enabled));
+    }
+
+    let agents = &config.agents;
+    if agents != &Default::default() {
+        output.push_str("\n[agents]\n");
+        output.push_str(&format!(
+            "collapse_after_secs = {}\n",
+            agents.collapse_after_secs
+        ));
+        output.push_str(&format!("keep_runs = {}\n", agents.keep_runs));
+    }
+
+    if config.lsp != Default::default() {
+        // Servers are tables of their own, so serde writes the section
+        #[derive(serde::Serialize)]
+        struct Lsp<'a> {
+            lsp: &'a axiom_core::config::LspConfig,
+        }
+        output.push('\n');
+        output.push_str(&toml::to_string(&Lsp { lsp: &config.lsp })?);
+    }
+
+    if config.keys != Default::default() {
+        #[derive(serde::Serialize)]
+        struct Keys<'a> {
+            keys: &'a axiom_core::config::KeymapConfig,
+        }
+        output.push('\n');
+        output.push_str(&toml::to_string(&Keys { keys: &config.keys })?);
+    }
+
+    if config.prompts != Default::default() {
+        output.push_str("\n[prompts]\n");
+        for (role, template) in &config.prompts.roles {
+            output.push_str(&format!(
+                "{} = {}\n",
+                role,
+                toml::Value::String(template.clone())
+            ));
         }
     }
 
@@ -137,6 +357,28 @@ mod tests {
         assert_eq!(path, PathBuf::from("/home/user/project/.axiom.toml"));
     }
 
+    #[test]
+    fn test_serialize_key_command_without_key() {
+        let mut config = AxiomConfig::default();
+        let openai = config.llm.providers.get_mut("openai").unwrap();
+        openai.api_key_command = Some("az account get-access-token -o \"json\"".to_string());
+        openai.api_key = Some("fetched-token".to_string());
+        let claude = config.llm.providers.get_mut("claude").unwrap();
+        claude.api_key_expires_at = Some(1_900_000_000);
+
+        let content = serialize_config(&config).unwrap();
+        assert!(!content.contains("fetched-token"));
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(
+            parsed.llm.providers["openai"].api_key_command,
+            config.llm.providers["openai"].api_key_command
+        );
+        assert_eq!(
+            parsed.llm.providers["claude"].api_key_expires_at,
+            Some(1_900_000_000)
+        );
+    }
+
     #[test]
     fn test_serialize_default_config() {
         let config = AxiomConfig::default();
@@ -149,6 +391,194 @@ mod tests {
         assert!(content.contains("[llm.providers.ollama]"));
     }
 
+    #[test]
+    fn test_serialize_rate_limits_roundtrip() {
+        let mut config = AxiomConfig::default();
+        config.llm.rate_limits.insert(
+            "claude".to_string(),
+            axiom_core::llm::RateLimits {
+                requests_per_minute: Some(50),
+                tokens_per_minute: None,
+            },
+        );
+        let content = serialize_config(&config).unwrap();
+        assert!(content.contains("[llm.rate_limits.claude]\nrequests_per_minute = 50\n"));
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.llm.rate_limits, config.llm.rate_limits);
+    }
+
+    #[test]
+    fn test_serialize_routing_roundtrip() {
+        let mut config = AxiomConfig::default();
+        config.llm.routing.insert(
+            "ollama".to_string(),
+            axiom_core::llm::ProviderRouting {
+                region: Some("local".to_string()),
+                classification: Some(axiom_core::llm::Sensitivity::Restricted),
+            },
+        );
+        let content = serialize_config(&config).unwrap();
+        assert!(content.contains("[llm.routing.ollama]\nregion = \"local\"\n"));
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.llm.routing, config.llm.routing);
+    }
+
+    #[test]
+    fn test_serialize_agent_models_roundtrip() {
+        let mut config = AxiomConfig::default();
+        config.llm.agents.insert(
+            "shell-summarizer".to_string(),
+            axiom_core::config::AgentModelConfig {
+                provider: "ollama".to_string(),
+                model: "gemma3:4b".to_string(),
+            },
+        );
+        let content = serialize_config(&config).unwrap();
+        assert!(content.contains("[llm.agents.shell-summarizer]\nprovider = \"ollama\"\n"));
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.llm.agents, config.llm.agents);
+    }
+
+    #[test]
+    fn test_serialize_directory_picker_roundtrip() {
+        let mut config = AxiomConfig::default();
+        assert!(!serialize_config(&config).unwrap().contains("[directory_picker]"));
+
+        config.directory_picker.bookmarks = vec![PathBuf::from("/home/user/my \"code\"")];
+        config.directory_picker.sort = crate::config::DirSort::Modified;
+        let content = serialize_config(&config).unwrap();
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.directory_picker, config.directory_picker);
+    }
+
+    #[test]
+    fn test_serialize_discovery_roundtrip() {
+        let mut config = AxiomConfig::default();
+        assert!(!serialize_config(&config).unwrap().contains("[discovery]"));
+
+        config.discovery.roots = vec![PathBuf::from("~/code")];
+        config.discovery.max_depth = 3;
+        let content = serialize_config(&config).unwrap();
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.discovery, config.discovery);
+    }
+
+    #[test]
+    fn test_serialize_terminal_roundtrip() {
+        let mut config = AxiomConfig::default();
+        assert!(!serialize_config(&config).unwrap().contains("[terminal]"));
+
+        config.terminal.osc_progress = true;
+        config.terminal.progress_after_secs = 30;
+        let content = serialize_config(&config).unwrap();
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.terminal, config.terminal);
+    }
+
+    #[test]
+    fn test_serialize_completion_roundtrip() {
+        let mut config = AxiomConfig::default();
+        assert!(!serialize_config(&config).unwrap().contains("[completion]"));
+
+        config.completion.model = "qwen2.5-coder:1.5b".to_string();
+        config.completion.debounce_ms = 250;
+        let content = serialize_config(&config).unwrap();
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.completion, config.completion);
+    }
+
+    #[test]
+    fn test_serialize_agents_roundtrip() {
+        let mut config = AxiomConfig::default();
+        assert!(!serialize_config(&config).unwrap().contains("[agents]"));
+
+        config.agents.collapse_after_secs = 0;
+        config.agents.keep_runs = 1;
+        let content = serialize_config(&config).unwrap();
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.agents, config.agents);
+    }
+
+    #[test]
+    fn test_serialize_llm_context_roundtrip() {
+        let mut config = AxiomConfig::default();
+        assert!(!serialize_config(&config).unwrap().contains("[llm.context]"));
+
+        config.llm.context.summarize = true;
+        config
+            .llm
+            .context
+            .max_tokens
+            .insert("ollama".to_string(), 32_768);
+        let content = serialize_config(&config).unwrap();
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.llm.context, config.llm.context);
+    }
+
+    #[test]
+    fn test_serialize_prompts_roundtrip() {
+        let mut config = AxiomConfig::default();
+        assert!(!serialize_config(&config).unwrap().contains("[prompts]"));
+
+        config
+            .prompts
+            .roles
+            .insert("developer".to_string(), "careful-developer".to_string());
+        let content = serialize_config(&config).unwrap();
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.prompts, config.prompts);
+    }
+
+    #[test]
+    fn test_serialize_suggestions_roundtrip() {
+        let mut config = AxiomConfig::default();
+        assert!(!serialize_config(&config).unwrap().contains("[suggestions]"));
+
+        config.suggestions.enabled = false;
+        let content = serialize_config(&config).unwrap();
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.suggestions, config.suggestions);
+    }
+
+    #[test]
+    fn test_serialize_sounds_roundtrip() {
+        let mut config = AxiomConfig::default();
+        assert!(!serialize_config(&config).unwrap().contains("[sounds]"));
+
+        config.sounds.enabled = true;
+        config.sounds.approval = false;
+        config.sounds.command = Some("paplay \"done.oga\"".to_string());
+        config.sounds.quiet_hours = Some("22:00-07:00".to_string());
+        let content = serialize_config(&config).unwrap();
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.sounds, config.sounds);
+    }
+
+    #[test]
+    fn test_serialize_lsp_roundtrip() {
+        let mut config = AxiomConfig::default();
+        assert!(!serialize_config(&config).unwrap().contains("[lsp]"));
+
+        config.lsp.check_timeout_secs = 30;
+        config.lsp.servers.remove("python");
+        let content = serialize_config(&config).unwrap();
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.lsp, config.lsp);
+    }
+
+    #[test]
+    fn test_serialize_keys_roundtrip() {
+        let mut config = AxiomConfig::default();
+        assert!(!serialize_config(&config).unwrap().contains("[keys]"));
+
+        config.keys =
+            toml::from_str("search = [\"ctrl+f\", \"alt+f\"]\n[panels.file_tree]\nquit = []\n")
+                .unwrap();
+        let content = serialize_config(&config).unwrap();
+        let parsed: AxiomConfig = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.keys, config.keys);
+    }
+
     #[test]
     fn test_save_config_creates_dirs() {
         use std::fs;
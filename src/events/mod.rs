@@ -2,13 +2,15 @@
 //!
 //! Uses crossbeam bounded channels for backpressure to prevent memory bloat.
 
-use axiom_core::SlashCommand;
+use axiom_core::config::{Chord, Key};
+use axiom_core::llm::FreshKey;
+use axiom_core::{AgentAction, Diagnostic, PendingWrite, SlashCommand};
 use crossbeam_channel::{bounded, Receiver, Sender, TryRecvError};
-use crossterm::event::{KeyEvent, MouseEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
 use std::path::PathBuf;
 use std::time::Duration;
 
-use crate::agents::{AgentSpawnRequest, AgentStatus};
+use crate::agents::{AgentSpawnRequest, AgentStatus, Exchange};
 use crate::state::{AgentId, OutputContext, PanelId, WorkspaceId};
 
 /// Application events - unified event type
@@ -20,6 +22,10 @@ pub enum Event {
     /// Mouse input event
     Mouse(MouseEvent),
 
+    /// Text pasted into the terminal, delivered whole (bracketed paste)
+    /// instead of as a key event per character
+    Paste(String),
+
     /// Terminal resize event with new dimensions (width, height)
     Resize(u16, u16),
 
@@ -38,6 +44,25 @@ pub enum Event {
     /// LLM error occurred with description
     LlmError(String),
 
+    /// A provider rejected its API key (401/403)
+    ProviderAuthFailed {
+        /// The provider's ID, e.g. "claude"
+        provider_id: String,
+        /// The provider's error
+        message: String,
+    },
+
+    /// A provider's `api_key_command` finished
+    ProviderKeyFetched {
+        /// The provider's ID, e.g. "claude"
+        provider_id: String,
+        /// The new key, or why there is none
+        result: Result<FreshKey, String>,
+    },
+
+    /// LLM providers set up in the background at startup are ready
+    ProvidersReady,
+
     /// File modification request from LLM
     ///
     /// Contains the file path and the new content to be applied.
@@ -48,9 +73,33 @@ pub enum Event {
     /// Contains the path to the file that was created or modified.
     FileChanged(std::path::PathBuf),
 
+    /// A language server's latest diagnostics for a file
+    Diagnostics {
+        path: PathBuf,
+        diagnostics: Vec<Diagnostic>,
+    },
+
+    /// File or directory created from the file tree
+    PathCreated(PathBuf),
+
+    /// File or directory renamed or moved from the file tree
+    PathMoved { from: PathBuf, to: PathBuf },
+
+    /// File or directory deleted from the file tree
+    PathDeleted(PathBuf),
+
+    /// An agent write waiting for the user's approval (held by the write
+    /// policy, or drafted for review)
+    WriteHeld(PendingWrite),
+
     /// Tick event for periodic updates (e.g., UI animations, polling)
     Tick,
 
+    /// Editor completion for the request numbered `generation`
+    ///
+    /// Empty when the model had nothing to add or the request failed.
+    GhostCompletion { generation: u64, text: String },
+
     /// Request to focus a specific panel (smart focus based on context)
     FocusPanel(PanelId),
 
@@ -90,8 +139,37 @@ pub enum Event {
     /// Wake an idle agent (used for persistent Conductor)
     AgentWake(AgentId),
 
+    /// An agent's run started streaming from `provider`'s `model`
+    AgentStreamStart {
+        id: AgentId,
+        provider: String,
+        model: String,
+    },
+
+    /// An agent's LLM request and its reply, captured for `/inspect`
+    LlmExchange {
+        id: AgentId,
+        exchange: Box<Exchange>,
+    },
+
+    /// Cancel an agent's running work, or every agent's with `None`
+    AgentCancel(Option<AgentId>),
+
+    /// An action chosen from an agent's menu in the Agents panel
+    AgentAction {
+        /// The agent ID
+        id: AgentId,
+        /// What to do with it
+        action: AgentAction,
+    },
+
     /// Conductor response complete - add to history for context
-    ConductorResponse(String),
+    ConductorResponse {
+        /// The conductor's agent ID
+        id: AgentId,
+        /// The whole reply
+        response: String,
+    },
 
     /// Switch output context (what's displayed in output area)
     SwitchContext(OutputContext),
@@ -125,6 +203,14 @@ pub enum Event {
         exit_code: i32,
     },
 
+    /// CLI agent never became ready for its prompt
+    CliAgentNotReady {
+        /// The agent's runtime ID
+        id: AgentId,
+        /// Why the prompt wasn't sent
+        error: String,
+    },
+
     /// Send input to a CLI agent
     CliAgentInput {
         /// The agent's runtime ID
@@ -245,6 +331,45 @@ impl EventBus {
     }
 }
 
+/// The chord for a key event, to look up in a [`Keymap`]; `None` for keys
+/// no binding can name
+///
+/// Terminals report Shift+Tab as BackTab, which is `shift+tab` here.
+///
+/// [`Keymap`]: axiom_core::config::Keymap
+pub fn key_chord(key: &KeyEvent) -> Option<Chord> {
+    let mut shift = key.modifiers.contains(KeyModifiers::SHIFT);
+    let code = match key.code {
+        KeyCode::Char(c) => Key::Char(c),
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Esc => Key::Esc,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::BackTab => {
+            shift = true;
+            Key::Tab
+        }
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::Insert => Key::Insert,
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::F(n) => Key::F(n),
+        _ => return None,
+    };
+    Some(Chord::new(
+        code,
+        key.modifiers.contains(KeyModifiers::CONTROL),
+        key.modifiers.contains(KeyModifiers::ALT),
+        shift,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -461,4 +586,19 @@ mod tests {
         let settings = Event::SlashCommand(SlashCommand::Settings);
         assert!(matches!(settings, Event::SlashCommand(SlashCommand::Settings)));
     }
+
+    #[test]
+    fn test_key_chord() {
+        let chord = |code, modifiers| {
+            key_chord(&KeyEvent::new(code, modifiers)).map(|chord| chord.to_string())
+        };
+        let ctrl = KeyModifiers::CONTROL;
+        let (none, shift) = (KeyModifiers::NONE, KeyModifiers::SHIFT);
+        assert_eq!(chord(KeyCode::Char('m'), ctrl).as_deref(), Some("ctrl+m"));
+        assert_eq!(chord(KeyCode::BackTab, shift).as_deref(), Some("shift+tab"));
+        assert_eq!(chord(KeyCode::BackTab, none).as_deref(), Some("shift+tab"));
+        assert_eq!(chord(KeyCode::Char('T'), shift).as_deref(), Some("shift+t"));
+        assert_eq!(chord(KeyCode::Char('?'), shift).as_deref(), Some("?"));
+        assert_eq!(chord(KeyCode::Null, none), None);
+    }
 }
@@ -0,0 +1,303 @@
+//! Terminal multiplexer layouts (`axiom layout tmux|zellij`)
+//!
+//! Opens the TUI next to a plain shell in the workspace and a pane tailing
+//! the workspace's activity log, inside a new tmux session or zellij session
+//! (or a new window/tab when already running in one).
+//!
+//! Layouts are templates in the multiplexer's own format: tmux commands
+//! (`source-file` syntax) or a zellij KDL layout. `{axiom}`, `{workspace}`,
+//! `{log}` and `{name}` are replaced with the executable, the workspace
+//! directory, the log file and the session name, escaped for use inside
+//! double quotes. A template in `~/.config/axiom/layouts/tmux.conf` or
+//! `zellij.kdl` replaces the built-in one, as does `--template <file>`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Built-in tmux layout: TUI on the left, shell and log tail on the right
+const TMUX_TEMPLATE: &str = r#"# Axiom layout for tmux
+respawn-pane -k -c "{workspace}" "{axiom}" "{workspace}"
+split-window -h -l 40% -c "{workspace}"
+split-window -v -l 30% -c "{workspace}" tail -F "{log}"
+select-pane -L
+"#;
+
+/// Built-in zellij layout: TUI on the left, shell and log tail on the right
+const ZELLIJ_TEMPLATE: &str = r#"// Axiom layout for zellij
+layout {
+    cwd "{workspace}"
+    pane size=1 borderless=true {
+        plugin location="zellij:tab-bar"
+    }
+    pane split_direction="vertical" {
+        pane command="{axiom}" size="60%" focus=true {
+            args "{workspace}"
+        }
+        pane split_direction="horizontal" {
+            pane
+            pane command="tail" size="30%" {
+                args "-F" "{log}"
+            }
+        }
+    }
+    pane size=2 borderless=true {
+        plugin location="zellij:status-bar"
+    }
+}
+"#;
+
+/// Supported terminal multiplexers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplexer {
+    Tmux,
+    Zellij,
+}
+
+impl Multiplexer {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "tmux" => Some(Multiplexer::Tmux),
+            "zellij" => Some(Multiplexer::Zellij),
+            _ => None,
+        }
+    }
+
+    pub fn program(self) -> &'static str {
+        match self {
+            Multiplexer::Tmux => "tmux",
+            Multiplexer::Zellij => "zellij",
+        }
+    }
+
+    /// File name of the user template in `~/.config/axiom/layouts`
+    fn template_file(self) -> &'static str {
+        match self {
+            Multiplexer::Tmux => "tmux.conf",
+            Multiplexer::Zellij => "zellij.kdl",
+        }
+    }
+
+    pub fn default_template(self) -> &'static str {
+        match self {
+            Multiplexer::Tmux => TMUX_TEMPLATE,
+            Multiplexer::Zellij => ZELLIJ_TEMPLATE,
+        }
+    }
+
+    /// Whether Axiom is already running inside this multiplexer
+    pub fn is_running(self) -> bool {
+        let var = match self {
+            Multiplexer::Tmux => "TMUX",
+            Multiplexer::Zellij => "ZELLIJ",
+        };
+        std::env::var_os(var).is_some_and(|v| !v.is_empty())
+    }
+}
+
+/// User template for `mux` (`~/.config/axiom/layouts/<file>`)
+pub fn user_template_path(mux: Multiplexer) -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("axiom").join("layouts").join(mux.template_file()))
+}
+
+/// Values substituted into a layout template
+#[derive(Debug, Clone)]
+pub struct LayoutVars {
+    /// Axiom executable
+    pub axiom: PathBuf,
+    /// Workspace directory
+    pub workspace: PathBuf,
+    /// Log file shown in the tail pane
+    pub log: PathBuf,
+    /// Session (or window/tab) name
+    pub name: String,
+}
+
+impl LayoutVars {
+    pub fn new(axiom: PathBuf, workspace: PathBuf, log: PathBuf) -> Self {
+        let name = session_name(&workspace);
+        Self {
+            axiom,
+            workspace,
+            log,
+            name,
+        }
+    }
+}
+
+/// Session name for a workspace, e.g. `axiom-my-app`
+///
+/// tmux doesn't allow `.` or `:` in session names, so anything but
+/// alphanumerics, `-` and `_` becomes `-`.
+pub fn session_name(workspace: &Path) -> String {
+    let dir = workspace
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let dir: String = dir
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if dir.is_empty() {
+        "axiom".to_string()
+    } else {
+        format!("axiom-{}", dir)
+    }
+}
+
+/// Fill in a template's placeholders
+pub fn render(template: &str, mux: Multiplexer, vars: &LayoutVars) -> String {
+    let escape = |value: &str| {
+        let mut out = String::with_capacity(value.len());
+        for c in value.chars() {
+            // tmux expands variables in double quotes, KDL doesn't
+            if c == '\\' || c == '"' || (c == '$' && mux == Multiplexer::Tmux) {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out
+    };
+    template
+        .replace("{axiom}", &escape(&vars.axiom.to_string_lossy()))
+        .replace("{workspace}", &escape(&vars.workspace.to_string_lossy()))
+        .replace("{log}", &escape(&vars.log.to_string_lossy()))
+        .replace("{name}", &escape(&vars.name))
+}
+
+/// Command that opens the rendered layout in `layout_file`
+///
+/// Inside the multiplexer already, the layout opens in a new window (tmux)
+/// or tab (zellij) instead of a nested session. An existing tmux session of
+/// the same name is attached rather than laid out again.
+pub fn launch_command(
+    mux: Multiplexer,
+    layout_file: &Path,
+    vars: &LayoutVars,
+    nested: bool,
+    session_exists: bool,
+) -> Command {
+    let mut cmd = Command::new(mux.program());
+    match mux {
+        Multiplexer::Tmux if session_exists && !nested => {
+            cmd.args(["attach-session", "-t", &vars.name]);
+        }
+        Multiplexer::Tmux => {
+            if nested {
+                cmd.args(["new-window", "-n", &vars.name]);
+            } else {
+                cmd.args(["new-session", "-s", &vars.name]);
+            }
+            cmd.arg("-c")
+                .arg(&vars.workspace)
+                .arg(";")
+                .arg("source-file")
+                .arg(layout_file);
+        }
+        Multiplexer::Zellij if nested => {
+            cmd.args(["action", "new-tab", "--name", &vars.name, "--layout"])
+                .arg(layout_file);
+        }
+        Multiplexer::Zellij => {
+            cmd.args(["--session", &vars.name, "--layout"])
+                .arg(layout_file);
+        }
+    }
+    cmd.current_dir(&vars.workspace);
+    cmd
+}
+
+/// Whether a tmux session called `name` exists
+pub fn tmux_session_exists(name: &str) -> bool {
+    Command::new("tmux")
+        .args(["has-session", "-t", &format!("={}", name)])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> LayoutVars {
+        LayoutVars::new(
+            PathBuf::from("/usr/bin/axiom"),
+            PathBuf::from("/src/my.app"),
+            PathBuf::from("/home/u/.axiom/activity/1.jsonl"),
+        )
+    }
+
+    fn args(cmd: &Command) -> Vec<String> {
+        cmd.get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_session_name() {
+        assert_eq!(session_name(Path::new("/src/my.app")), "axiom-my-app");
+        assert_eq!(session_name(Path::new("/")), "axiom");
+    }
+
+    #[test]
+    fn test_render_escapes_for_format() {
+        let vars = LayoutVars::new(
+            PathBuf::from("/opt/a\"b/axiom"),
+            PathBuf::from("/src/$HOME"),
+            PathBuf::from("/tmp/log"),
+        );
+        let tmux = render(TMUX_TEMPLATE, Multiplexer::Tmux, &vars);
+        assert!(tmux.contains(r#"respawn-pane -k -c "/src/\$HOME" "/opt/a\"b/axiom""#));
+        assert!(tmux.contains(r#"tail -F "/tmp/log""#));
+
+        let zellij = render(ZELLIJ_TEMPLATE, Multiplexer::Zellij, &vars);
+        assert!(zellij.contains(r#"cwd "/src/$HOME""#));
+        assert!(zellij.contains(r#"pane command="/opt/a\"b/axiom""#));
+        assert!(!zellij.contains("{log}"));
+    }
+
+    #[test]
+    fn test_launch_command() {
+        let vars = vars();
+        let file = Path::new("/tmp/layout.conf");
+
+        let tmux = launch_command(Multiplexer::Tmux, file, &vars, false, false);
+        assert_eq!(
+            args(&tmux),
+            [
+                "new-session",
+                "-s",
+                "axiom-my-app",
+                "-c",
+                "/src/my.app",
+                ";",
+                "source-file",
+                "/tmp/layout.conf"
+            ]
+        );
+        let nested = launch_command(Multiplexer::Tmux, file, &vars, true, true);
+        assert_eq!(args(&nested)[..3], ["new-window", "-n", "axiom-my-app"]);
+        let attach = launch_command(Multiplexer::Tmux, file, &vars, false, true);
+        assert_eq!(args(&attach), ["attach-session", "-t", "axiom-my-app"]);
+
+        let zellij = launch_command(Multiplexer::Zellij, file, &vars, true, false);
+        assert_eq!(
+            args(&zellij),
+            [
+                "action",
+                "new-tab",
+                "--name",
+                "axiom-my-app",
+                "--layout",
+                "/tmp/layout.conf"
+            ]
+        );
+    }
+}
@@ -13,7 +13,9 @@ pub mod clipboard;
 pub mod config;
 pub mod core;
 pub mod events;
+pub mod layout;
 pub mod llm;
+pub mod logging;
 pub mod panels;
 pub mod state;
 pub mod terminal;
@@ -4,6 +4,7 @@
 
 use super::{ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus};
 use crate::events::Event;
+use axiom_core::llm::{claude_reply, claude_tools, send_with_quota, ToolReply, ToolSpec};
 use crossbeam_channel::Sender;
 use parking_lot::RwLock;
 use std::io::{BufRead, BufReader};
@@ -99,17 +100,21 @@ impl LlmProvider for ClaudeProvider {
             let _ = event_tx.send(Event::LlmDone);
         });
     }
+
+    fn chat_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: &[ToolSpec],
+    ) -> Result<ToolReply, LlmError> {
+        let mut body = messages_body(&self.model.read(), messages);
+        body["tools"] = claude_tools(tools);
+        let response = post_messages(&self.base_url, &self.api_key, "tools", &body)?;
+        Ok(claude_reply(&response.into_json()?))
+    }
 }
 
-/// Send request to Claude API and stream response
-fn send_claude_request(
-    base_url: &str,
-    api_key: &str,
-    model: &str,
-    messages: Vec<ChatMessage>,
-    event_tx: &Sender<Event>,
-) -> Result<(), LlmError> {
-    // Separate system message from conversation
+/// Messages API request body; system messages go in the `system` field
+fn messages_body(model: &str, messages: Vec<ChatMessage>) -> serde_json::Value {
     let mut system_content = String::new();
     let mut conversation: Vec<serde_json::Value> = Vec::new();
 
@@ -125,26 +130,53 @@ fn send_claude_request(
         }
     }
 
-    // Build request body
     let mut body = serde_json::json!({
         "model": model,
         "max_tokens": 8192,
-        "stream": true,
         "messages": conversation
     });
 
-    // Add system message if present
     if !system_content.is_empty() {
         body["system"] = serde_json::Value::String(system_content.trim().to_string());
     }
+    body
+}
 
+/// POST to the Messages API, queued behind other agents for the provider's budget
+fn post_messages(
+    base_url: &str,
+    api_key: &str,
+    agent: &str,
+    body: &serde_json::Value,
+) -> Result<ureq::Response, LlmError> {
     let url = format!("{}/v1/messages", base_url);
+    let response = send_with_quota(
+        "claude",
+        agent,
+        || {
+            ureq::post(&url)
+                .set("Content-Type", "application/json")
+                .set("x-api-key", api_key)
+                .set("anthropic-version", "2023-06-01")
+        },
+        body,
+    )?;
+    Ok(response)
+}
+
+/// Send request to Claude API and stream response
+fn send_claude_request(
+    base_url: &str,
+    api_key: &str,
+    model: &str,
+    messages: Vec<ChatMessage>,
+    event_tx: &Sender<Event>,
+) -> Result<(), LlmError> {
+    let mut body = messages_body(model, messages);
+    body["stream"] = serde_json::Value::Bool(true);
 
-    let response = ureq::post(&url)
-        .set("Content-Type", "application/json")
-        .set("x-api-key", api_key)
-        .set("anthropic-version", "2023-06-01")
-        .send_json(&body)?;
+    // Chat requests queue behind other agents for the provider's budget
+    let response = post_messages(base_url, api_key, "chat", &body)?;
 
     // Read SSE streaming response
     let reader = BufReader::new(response.into_reader());
@@ -0,0 +1,115 @@
+//! Fill-in-the-middle completions from Ollama
+//!
+//! Sends the code before and after the cursor to `/api/generate` with a
+//! `suffix`, which Ollama turns into the model's FIM prompt (codellama
+//! `-code` models, qwen2.5-coder, starcoder2 and others).
+
+use super::LlmError;
+use std::time::Duration;
+
+/// Lines before the cursor sent as context
+const PREFIX_LINES: usize = 60;
+
+/// Lines after the cursor sent as context
+const SUFFIX_LINES: usize = 30;
+
+/// Completions that take longer are no longer useful
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Code around the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FimRequest {
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl FimRequest {
+    /// Split `lines` at (`line`, `column`), keeping nearby lines only
+    pub fn at(lines: &[String], line: usize, column: usize) -> Self {
+        let current = lines.get(line).map(String::as_str).unwrap_or("");
+        let split = current
+            .char_indices()
+            .nth(column)
+            .map_or(current.len(), |(i, _)| i);
+
+        let first = line.saturating_sub(PREFIX_LINES);
+        let mut prefix: String = lines[first..line.min(lines.len())]
+            .iter()
+            .map(|l| format!("{}\n", l))
+            .collect();
+        prefix.push_str(&current[..split]);
+
+        let mut suffix = current[split..].to_string();
+        for l in lines.iter().skip(line + 1).take(SUFFIX_LINES) {
+            suffix.push('\n');
+            suffix.push_str(l);
+        }
+
+        Self { prefix, suffix }
+    }
+}
+
+/// Ask `model` to fill in between the request's prefix and suffix
+///
+/// Blocks until the model answers; call it off the UI thread.
+pub fn complete(
+    base_url: &str,
+    model: &str,
+    request: &FimRequest,
+    max_tokens: u32,
+) -> Result<String, LlmError> {
+    let body = serde_json::json!({
+        "model": model,
+        "prompt": request.prefix,
+        "suffix": request.suffix,
+        "stream": false,
+        "options": {
+            "num_predict": max_tokens,
+            "temperature": 0
+        }
+    });
+
+    let response = ureq::post(&format!("{}/api/generate", base_url))
+        .timeout(TIMEOUT)
+        .send_json(&body)?;
+    let json: serde_json::Value = response.into_json()?;
+    Ok(json
+        .get("response")
+        .and_then(|r| r.as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// The part of a completion shown as ghost text: its first line, without
+/// end-of-text markers some models leave in
+pub fn ghost_line(completion: &str) -> String {
+    let line = completion.lines().next().unwrap_or("");
+    let line = line.split("<EOT>").next().unwrap_or("");
+    line.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fim_request_splits_at_cursor() {
+        let lines: Vec<String> = ["fn add(a: i32, b: i32) -> i32 {", "    a + ", "}"]
+            .map(String::from)
+            .to_vec();
+        let request = FimRequest::at(&lines, 1, 6);
+        assert_eq!(request.prefix, "fn add(a: i32, b: i32) -> i32 {\n    a ");
+        assert_eq!(request.suffix, "+ \n}");
+
+        let end = FimRequest::at(&lines, 2, 1);
+        assert!(end.prefix.ends_with("    a + \n}"));
+        assert_eq!(end.suffix, "");
+    }
+
+    #[test]
+    fn test_ghost_line() {
+        assert_eq!(ghost_line("b\n}\n"), "b");
+        assert_eq!(ghost_line("total += x; <EOT>"), "total += x;");
+        assert_eq!(ghost_line(""), "");
+    }
+}
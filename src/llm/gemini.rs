@@ -4,6 +4,7 @@
 
 use super::{ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus};
 use crate::events::Event;
+use axiom_core::llm::{gemini_reply, gemini_tools, send_with_quota, ToolReply, ToolSpec};
 use crossbeam_channel::Sender;
 use parking_lot::RwLock;
 use std::io::{BufRead, BufReader};
@@ -99,16 +100,32 @@ impl LlmProvider for GeminiProvider {
             let _ = event_tx.send(Event::LlmDone);
         });
     }
+
+    fn chat_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: &[ToolSpec],
+    ) -> Result<ToolReply, LlmError> {
+        let mut body = contents_body(messages);
+        body["tools"] = gemini_tools(tools);
+        let url = format!(
+            "{}/v1beta/models/{}:generateContent?key={}",
+            self.base_url,
+            self.model.read(),
+            self.api_key
+        );
+        let response = send_with_quota(
+            "gemini",
+            "tools",
+            || ureq::post(&url).set("Content-Type", "application/json"),
+            &body,
+        )?;
+        Ok(gemini_reply(&response.into_json()?))
+    }
 }
 
-/// Send request to Gemini API and stream response
-fn send_gemini_request(
-    base_url: &str,
-    api_key: &str,
-    model: &str,
-    messages: Vec<ChatMessage>,
-    event_tx: &Sender<Event>,
-) -> Result<(), LlmError> {
+/// generateContent request body; system messages become `system_instruction`
+fn contents_body(messages: Vec<ChatMessage>) -> serde_json::Value {
     // Convert messages to Gemini format
     let mut contents: Vec<serde_json::Value> = Vec::new();
     let mut system_instruction: Option<String> = None;
@@ -149,6 +166,18 @@ fn send_gemini_request(
             }]
         });
     }
+    body
+}
+
+/// Send request to Gemini API and stream response
+fn send_gemini_request(
+    base_url: &str,
+    api_key: &str,
+    model: &str,
+    messages: Vec<ChatMessage>,
+    event_tx: &Sender<Event>,
+) -> Result<(), LlmError> {
+    let body = contents_body(messages);
 
     // Use streamGenerateContent endpoint
     let url = format!(
@@ -156,9 +185,16 @@ fn send_gemini_request(
         base_url, model, api_key
     );
 
-    let response = ureq::post(&url)
-        .set("Content-Type", "application/json")
-        .send_json(&body)?;
+    // Chat requests queue behind other agents for the provider's budget
+    let response = send_with_quota(
+        "gemini",
+        "chat",
+        || {
+            ureq::post(&url)
+                .set("Content-Type", "application/json")
+        },
+        &body,
+    )?;
 
     // Read SSE streaming response
     let reader = BufReader::new(response.into_reader());
@@ -7,8 +7,10 @@
 //! - Claude (Anthropic API)
 //! - Gemini (Google AI)
 //! - OpenAI (GPT models)
+//! - Any OpenAI-compatible endpoint (LiteLLM, vLLM, LM Studio, OpenRouter)
 
 mod error;
+pub mod fim;
 mod message;
 mod ollama;
 mod provider;
@@ -17,6 +19,7 @@ mod registry;
 // Provider implementations
 mod claude;
 mod gemini;
+mod openai_compatible;
 
 pub use error::LlmError;
 pub use message::{
@@ -29,3 +32,4 @@ pub use registry::{ProviderInfo, ProviderRegistry};
 // Provider implementations
 pub use claude::ClaudeProvider;
 pub use gemini::GeminiProvider;
+pub use openai_compatible::OpenAICompatibleProvider;
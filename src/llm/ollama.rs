@@ -1,14 +1,29 @@
 //! Ollama LLM provider
 //!
 //! Connects to local Ollama instance for chat completions.
+//!
+//! Whether the server is reachable is checked in the background: `status()`
+//! answers from the last check and starts a new one once it's stale, so
+//! nothing on the UI thread waits on the network.
 
 use super::{
     ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus,
 };
 use crate::events::Event;
 use crossbeam_channel::Sender;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::io::{BufRead, BufReader};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long a reachability check stays fresh
+const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Timeout for a background reachability check
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Timeout for listing models on request
+const MODELS_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Ollama provider for local LLM inference
 pub struct OllamaProvider {
@@ -19,10 +34,13 @@ pub struct OllamaProvider {
     model: RwLock<String>,
 
     /// Cached model list
-    cached_models: RwLock<Option<Vec<String>>>,
+    cached_models: Arc<RwLock<Option<Vec<String>>>>,
 
-    /// Current status
-    status: RwLock<ProviderStatus>,
+    /// Status from the last reachability check (Ready until one fails)
+    status: Arc<RwLock<ProviderStatus>>,
+
+    /// When the last reachability check started
+    last_probe: Mutex<Option<Instant>>,
 }
 
 impl Default for OllamaProvider {
@@ -37,8 +55,9 @@ impl OllamaProvider {
         Self {
             base_url: base_url.to_string(),
             model: RwLock::new(model.to_string()),
-            cached_models: RwLock::new(None),
-            status: RwLock::new(ProviderStatus::Ready),
+            cached_models: Arc::new(RwLock::new(None)),
+            status: Arc::new(RwLock::new(ProviderStatus::Ready)),
+            last_probe: Mutex::new(None),
         }
     }
 
@@ -47,41 +66,51 @@ impl OllamaProvider {
         Self::new("http://localhost:11434", model)
     }
 
-    /// Fetch models from Ollama API
-    fn fetch_models(&self) -> Result<Vec<String>, LlmError> {
-        let url = format!("{}/api/tags", self.base_url);
+    /// Check in the background whether Ollama is running, unless a check
+    /// started within the last `PROBE_INTERVAL`
+    ///
+    /// A successful check also fills the model cache.
+    fn probe_if_stale(&self) {
+        let mut last = self.last_probe.lock();
+        if last.is_some_and(|at| at.elapsed() < PROBE_INTERVAL) {
+            return;
+        }
+        *last = Some(Instant::now());
 
-        let response = ureq::get(&url)
-            .timeout(std::time::Duration::from_secs(10))
-            .call()?;
+        let base_url = self.base_url.clone();
+        let status = self.status.clone();
+        let cached_models = self.cached_models.clone();
+        std::thread::spawn(move || match fetch_models(&base_url, PROBE_TIMEOUT) {
+            Ok(models) => {
+                *cached_models.write() = Some(models);
+                *status.write() = ProviderStatus::Ready;
+            }
+            Err(_) => {
+                *status.write() =
+                    ProviderStatus::Unavailable("Cannot connect to Ollama".to_string());
+            }
+        });
+    }
+}
 
-        let json: serde_json::Value = response.into_json()?;
+/// Fetch the installed models from the Ollama API
+fn fetch_models(base_url: &str, timeout: Duration) -> Result<Vec<String>, LlmError> {
+    let url = format!("{}/api/tags", base_url);
 
-        let models: Vec<String> = json
-            .get("models")
-            .and_then(|m| m.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|m| m.get("name").and_then(|n| n.as_str()))
-                    .map(|s| s.to_string())
-                    .collect()
-            })
-            .unwrap_or_default();
+    let response = ureq::get(&url).timeout(timeout).call()?;
 
-        // Cache the models
-        *self.cached_models.write() = Some(models.clone());
-
-        Ok(models)
-    }
+    let json: serde_json::Value = response.into_json()?;
 
-    /// Check if Ollama is running
-    fn check_connection(&self) -> bool {
-        let url = format!("{}/api/tags", self.base_url);
-        ureq::get(&url)
-            .timeout(std::time::Duration::from_secs(5))
-            .call()
-            .is_ok()
-    }
+    Ok(json
+        .get("models")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|m| m.get("name").and_then(|n| n.as_str()))
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default())
 }
 
 impl LlmProvider for OllamaProvider {
@@ -108,7 +137,10 @@ impl LlmProvider for OllamaProvider {
             return Ok(models.clone());
         }
 
-        self.fetch_models()
+        let models = fetch_models(&self.base_url, MODELS_TIMEOUT)?;
+        *self.cached_models.write() = Some(models.clone());
+        *self.status.write() = ProviderStatus::Ready;
+        Ok(models)
     }
 
     fn capabilities(&self) -> ProviderCapabilities {
@@ -123,12 +155,8 @@ impl LlmProvider for OllamaProvider {
     }
 
     fn status(&self) -> ProviderStatus {
-        // Quick check - could be more sophisticated
-        if self.check_connection() {
-            ProviderStatus::Ready
-        } else {
-            ProviderStatus::Unavailable("Cannot connect to Ollama".to_string())
-        }
+        self.probe_if_stale();
+        self.status.read().clone()
     }
 
     fn send_message(&self, messages: Vec<ChatMessage>, event_tx: Sender<Event>) {
@@ -247,6 +275,20 @@ mod tests {
         assert_eq!(caps.max_output, 4096);
     }
 
+    #[test]
+    fn test_ollama_status_checks_in_background() {
+        // Nothing listens on the discard port, so the check fails
+        let provider = OllamaProvider::new("http://127.0.0.1:9", "llama2");
+        let start = Instant::now();
+        assert_eq!(provider.status(), ProviderStatus::Ready);
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        while start.elapsed() < Duration::from_secs(5) && provider.status().is_ready() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(matches!(provider.status(), ProviderStatus::Unavailable(_)));
+    }
+
     #[test]
     fn test_ollama_model_caching() {
         let provider = OllamaProvider::default();
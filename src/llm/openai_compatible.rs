@@ -0,0 +1,263 @@
+//! OpenAI-compatible LLM provider
+//!
+//! Streams chat from any server speaking OpenAI's Chat Completions API
+//! (LiteLLM, vLLM, LM Studio, OpenRouter, ...). Each custom entry under
+//! `[llm.providers]` becomes one of these, registered under its own name.
+
+use super::{ChatMessage, LlmError, LlmProvider, ProviderCapabilities, ProviderStatus};
+use crate::config::ProviderConfig;
+use crate::events::Event;
+use axiom_core::llm::{openai_reply, openai_tools, send_with_quota, ToolReply, ToolSpec};
+use crossbeam_channel::Sender;
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader};
+
+/// Provider for an OpenAI-compatible endpoint
+pub struct OpenAICompatibleProvider {
+    /// Name it is registered and budgeted under
+    id: String,
+
+    /// Base URL, e.g. `http://localhost:1234/v1`
+    base_url: String,
+
+    /// API key, sent as a bearer token when set
+    api_key: String,
+
+    /// Headers sent with every request
+    headers: BTreeMap<String, String>,
+
+    /// Current model
+    model: RwLock<String>,
+
+    /// Available models
+    models: Vec<String>,
+}
+
+impl OpenAICompatibleProvider {
+    /// Create from the `[llm.providers.<id>]` section, which needs a
+    /// `base_url`
+    ///
+    /// The model is the configured default, else the first listed model;
+    /// with no list the default is the one model offered.
+    pub fn from_config(id: &str, config: &ProviderConfig) -> Option<Self> {
+        let base_url = config.base_url.as_deref()?;
+        let model = config
+            .default_model
+            .as_deref()
+            .or(config.models.first().map(String::as_str))
+            .unwrap_or_default();
+        let models = match &config.models {
+            models if models.is_empty() && !model.is_empty() => vec![model.to_string()],
+            models => models.clone(),
+        };
+        Some(Self {
+            id: id.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: config.api_key.clone().unwrap_or_default(),
+            headers: config
+                .extra_headers
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            model: RwLock::new(model.to_string()),
+            models,
+        })
+    }
+}
+
+impl LlmProvider for OpenAICompatibleProvider {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.id
+    }
+
+    fn model(&self) -> String {
+        self.model.read().clone()
+    }
+
+    fn set_model(&self, model: &str) -> Result<(), LlmError> {
+        *self.model.write() = model.to_string();
+        Ok(())
+    }
+
+    fn list_models(&self) -> Result<Vec<String>, LlmError> {
+        Ok(self.models.clone())
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            streaming: true,
+            function_calling: true,
+            vision: false,
+            file_context: true,
+            max_context: 128_000,
+            max_output: 16384,
+        }
+    }
+
+    fn status(&self) -> ProviderStatus {
+        // Local servers usually take no key, so there is nothing to check
+        ProviderStatus::Ready
+    }
+
+    fn send_message(&self, messages: Vec<ChatMessage>, event_tx: Sender<Event>) {
+        let id = self.id.clone();
+        let url = format!("{}/chat/completions", self.base_url);
+        let api_key = self.api_key.clone();
+        let headers = self.headers.clone();
+        let model = self.model.read().clone();
+
+        std::thread::spawn(move || {
+            let request = || completions_request(&url, &api_key, &headers);
+            if let Err(e) = send_completions_request(&id, request, &model, messages, &event_tx) {
+                let _ = event_tx.send(Event::LlmError(e.to_string()));
+            }
+            let _ = event_tx.send(Event::LlmDone);
+        });
+    }
+
+    fn chat_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: &[ToolSpec],
+    ) -> Result<ToolReply, LlmError> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let mut body = completions_body(&self.model.read(), &messages);
+        body["tools"] = openai_tools(tools);
+        let response = send_with_quota(
+            &self.id,
+            "tools",
+            || completions_request(&url, &self.api_key, &self.headers),
+            &body,
+        )?;
+        Ok(openai_reply(&response.into_json()?))
+    }
+}
+
+/// POST to `url` with the key as a bearer token and the configured headers
+fn completions_request(
+    url: &str,
+    api_key: &str,
+    headers: &BTreeMap<String, String>,
+) -> ureq::Request {
+    let mut request = ureq::post(url).set("Content-Type", "application/json");
+    if !api_key.is_empty() {
+        request = request.set("Authorization", &format!("Bearer {}", api_key));
+    }
+    for (name, value) in headers {
+        request = request.set(name, value);
+    }
+    request
+}
+
+/// Chat Completions request body
+fn completions_body(model: &str, messages: &[ChatMessage]) -> serde_json::Value {
+    let conversation: Vec<serde_json::Value> = messages
+        .iter()
+        .map(|msg| {
+            serde_json::json!({
+                "role": msg.role_str(),
+                "content": msg.text()
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "model": model,
+        "messages": conversation,
+        "temperature": 0.7
+    })
+}
+
+/// Send a Chat Completions request and stream the response
+fn send_completions_request(
+    id: &str,
+    request: impl Fn() -> ureq::Request,
+    model: &str,
+    messages: Vec<ChatMessage>,
+    event_tx: &Sender<Event>,
+) -> Result<(), LlmError> {
+    let mut body = completions_body(model, &messages);
+    body["stream"] = serde_json::Value::Bool(true);
+
+    // Chat requests queue behind other agents for the provider's budget
+    let response = send_with_quota(id, "chat", request, &body)?;
+
+    let reader = BufReader::new(response.into_reader());
+
+    for line in reader.lines() {
+        let line = line?;
+
+        let Some(data) = line.strip_prefix("data: ") else {
+            continue;
+        };
+        if data == "[DONE]" {
+            break;
+        }
+
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
+            // Check for errors
+            if let Some(error) = json.get("error") {
+                let message = error
+                    .get("message")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("Unknown error");
+                return Err(LlmError::Api {
+                    status: 500,
+                    message: message.to_string(),
+                });
+            }
+
+            let choice = json.pointer("/choices/0");
+            if let Some(content) = choice
+                .and_then(|c| c.pointer("/delta/content"))
+                .and_then(|c| c.as_str())
+            {
+                if !content.is_empty()
+                    && event_tx.send(Event::LlmChunk(content.to_string())).is_err()
+                {
+                    break;
+                }
+            }
+            if choice
+                .and_then(|c| c.get("finish_reason"))
+                .is_some_and(|reason| !reason.is_null())
+            {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_config() {
+        let mut config = ProviderConfig {
+            base_url: Some("http://localhost:4000/".to_string()),
+            models: vec!["gpt-4o".to_string(), "claude-3-5-sonnet".to_string()],
+            ..Default::default()
+        };
+        let provider = OpenAICompatibleProvider::from_config("litellm", &config).unwrap();
+        assert_eq!(provider.id(), "litellm");
+        assert_eq!(provider.base_url, "http://localhost:4000");
+        assert_eq!(provider.model(), "gpt-4o");
+        assert!(provider.is_available());
+
+        config.models.clear();
+        config.default_model = Some("mistral-large".to_string());
+        let provider = OpenAICompatibleProvider::from_config("litellm", &config).unwrap();
+        assert_eq!(provider.list_models().unwrap(), vec!["mistral-large"]);
+
+        config.base_url = None;
+        assert!(OpenAICompatibleProvider::from_config("litellm", &config).is_none());
+    }
+}
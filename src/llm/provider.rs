@@ -2,7 +2,8 @@
 
 use super::{ChatMessage, LlmError};
 use crate::events::Event;
-use crossbeam_channel::Sender;
+use axiom_core::llm::{fallback_prompt, parse_fallback_reply, ToolReply, ToolSpec};
+use crossbeam_channel::{Receiver, Sender};
 
 /// Provider capabilities flags
 #[derive(Debug, Clone, Default)]
@@ -84,6 +85,24 @@ pub trait LlmProvider: Send + Sync {
     /// - `LlmError(String)` - An error occurred
     fn send_message(&self, messages: Vec<ChatMessage>, event_tx: Sender<Event>);
 
+    /// Send a message offering `tools` and wait for the reply
+    ///
+    /// Providers with native tool calling override this. The default
+    /// describes the tools in a system prompt, asks for JSON calls and
+    /// parses them out of the streamed reply; a reply that isn't JSON is
+    /// plain text with no calls.
+    fn chat_with_tools(
+        &self,
+        mut messages: Vec<ChatMessage>,
+        tools: &[ToolSpec],
+    ) -> Result<ToolReply, LlmError> {
+        messages.insert(0, ChatMessage::system(fallback_prompt(tools)));
+        let (tx, rx) = crossbeam_channel::unbounded();
+        self.send_message(messages, tx);
+        let reply = collect_reply(&rx)?;
+        Ok(parse_fallback_reply(&reply, tools))
+    }
+
     /// Check if the provider is currently available
     fn is_available(&self) -> bool {
         self.status().is_ready()
@@ -93,6 +112,20 @@ pub trait LlmProvider: Send + Sync {
 /// Wrapper to make Box<dyn LlmProvider> cloneable via Arc
 pub type SharedProvider = std::sync::Arc<dyn LlmProvider>;
 
+/// Wait for a streamed reply on `rx` and return its text
+fn collect_reply(rx: &Receiver<Event>) -> Result<String, LlmError> {
+    let mut text = String::new();
+    for event in rx {
+        match event {
+            Event::LlmChunk(chunk) => text.push_str(&chunk),
+            Event::LlmDone => break,
+            Event::LlmError(e) => return Err(LlmError::Internal(e)),
+            _ => {}
+        }
+    }
+    Ok(text)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,6 +2,8 @@
 
 use super::{LlmError, ProviderStatus, SharedProvider};
 use crate::config::AxiomConfig;
+use axiom_core::llm::{check_route, ProviderRouting, RouteCheck, RoutingPolicy};
+use axiom_core::AgentRole;
 use parking_lot::RwLock;
 use std::collections::HashMap;
 
@@ -15,6 +17,15 @@ pub struct ProviderRegistry {
 
     /// Fallback chain for automatic failover
     fallback_chain: Vec<String>,
+
+    /// Region and data classification per provider
+    routing: HashMap<String, ProviderRouting>,
+
+    /// Providers answering for agent roles mapped in `[llm.agents]`
+    roles: HashMap<String, SharedProvider>,
+
+    /// Providers are still being set up in the background
+    loading: bool,
 }
 
 impl ProviderRegistry {
@@ -24,6 +35,9 @@ impl ProviderRegistry {
             providers: HashMap::new(),
             active_provider: RwLock::new(String::new()),
             fallback_chain: Vec::new(),
+            routing: HashMap::new(),
+            roles: HashMap::new(),
+            loading: false,
         }
     }
 
@@ -42,10 +56,27 @@ impl ProviderRegistry {
             .filter(|(_, cfg)| cfg.enabled)
             .map(|(name, _)| name.clone())
             .collect();
+        registry.routing = config.llm.routing.clone();
 
         registry
     }
 
+    /// Placeholder for a registry being built in the background
+    ///
+    /// It has the configured default provider and fallback chain but no
+    /// providers until it's replaced by the real one.
+    pub fn loading(config: &AxiomConfig) -> Self {
+        Self {
+            loading: true,
+            ..Self::from_config(config)
+        }
+    }
+
+    /// Whether this is a placeholder from [`ProviderRegistry::loading`]
+    pub fn is_loading(&self) -> bool {
+        self.loading
+    }
+
     /// Register a provider
     pub fn register(&mut self, provider: SharedProvider) {
         let id = provider.id().to_string();
@@ -63,6 +94,28 @@ impl ProviderRegistry {
         self.get(&id)
     }
 
+    /// Have `provider` answer for `role` instead of the active provider
+    pub fn set_role(&mut self, role: AgentRole, provider: SharedProvider) {
+        self.roles.insert(role.as_str().to_string(), provider);
+    }
+
+    /// The provider answering for `role`: the one mapped to it, else the
+    /// active one
+    pub fn for_role(&self, role: AgentRole) -> Option<SharedProvider> {
+        self.roles
+            .get(role.as_str())
+            .cloned()
+            .or_else(|| self.active())
+    }
+
+    /// ID of the provider answering for `role`
+    pub fn role_id(&self, role: AgentRole) -> String {
+        match self.roles.get(role.as_str()) {
+            Some(provider) => provider.id().to_string(),
+            None => self.active_id(),
+        }
+    }
+
     /// Set the active provider
     pub fn set_active(&self, id: &str) -> Result<(), LlmError> {
         if !self.providers.contains_key(id) {
@@ -103,6 +156,12 @@ impl ProviderRegistry {
         None
     }
 
+    /// Check whether a workspace with `policy` may send content to a provider
+    pub fn check_route(&self, provider_id: &str, policy: &RoutingPolicy) -> RouteCheck {
+        let routing = self.routing.get(provider_id).cloned().unwrap_or_default();
+        check_route(provider_id, &routing, policy)
+    }
+
     /// Get all registered provider IDs
     pub fn provider_ids(&self) -> Vec<String> {
         self.providers.keys().cloned().collect()
@@ -249,6 +308,17 @@ mod tests {
         let registry = ProviderRegistry::new();
         assert!(registry.provider_ids().is_empty());
         assert!(registry.active().is_none());
+        assert!(!registry.is_loading());
+    }
+
+    #[test]
+    fn test_registry_loading_placeholder() {
+        let config = AxiomConfig::default();
+        let registry = ProviderRegistry::loading(&config);
+        assert!(registry.is_loading());
+        assert!(registry.provider_ids().is_empty());
+        assert_eq!(registry.active_id(), config.llm.default_provider);
+        assert!(!ProviderRegistry::from_config(&config).is_loading());
     }
 
     #[test]
@@ -282,12 +352,59 @@ mod tests {
         assert!(registry.active().is_some());
     }
 
+    #[test]
+    fn test_registry_for_role() {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(MockProvider::new("claude", ProviderStatus::Ready)));
+        registry.set_active("claude").unwrap();
+        let developer = MockProvider::new("ollama", ProviderStatus::Ready);
+        developer.set_model("qwen2.5-coder").unwrap();
+        registry.set_role(AgentRole::Developer, Arc::new(developer));
+
+        let provider = registry.for_role(AgentRole::Developer).unwrap();
+        assert_eq!(provider.id(), "ollama");
+        assert_eq!(provider.model(), "qwen2.5-coder");
+        let orchestrator = registry.for_role(AgentRole::Orchestrator).unwrap();
+        assert_eq!(orchestrator.id(), "claude");
+        assert_eq!(registry.role_id(AgentRole::Developer), "ollama");
+        assert_eq!(registry.role_id(AgentRole::Orchestrator), "claude");
+    }
+
     #[test]
     fn test_registry_set_active_nonexistent() {
         let registry = ProviderRegistry::new();
         assert!(registry.set_active("nonexistent").is_err());
     }
 
+    #[test]
+    fn test_registry_check_route() {
+        use axiom_core::llm::Sensitivity;
+
+        let mut config = AxiomConfig::default();
+        config.llm.routing.insert(
+            "ollama".to_string(),
+            ProviderRouting {
+                region: Some("local".to_string()),
+                classification: Some(Sensitivity::Restricted),
+            },
+        );
+        let registry = ProviderRegistry::from_config(&config);
+        let policy = RoutingPolicy {
+            sensitivity: Sensitivity::Confidential,
+            ..Default::default()
+        };
+
+        assert_eq!(registry.check_route("ollama", &policy), RouteCheck::Allowed);
+        assert!(matches!(
+            registry.check_route("claude", &policy),
+            RouteCheck::Blocked(_)
+        ));
+        assert_eq!(
+            registry.check_route("claude", &RoutingPolicy::default()),
+            RouteCheck::Allowed
+        );
+    }
+
     #[test]
     fn test_registry_fallback_chain() {
         let mut registry = ProviderRegistry::new();
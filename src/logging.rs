@@ -0,0 +1,268 @@
+//! The TUI's log file
+//!
+//! The TUI owns the terminal, so its diagnostics go to
+//! `.axiom/logs/tui.log` in the workspace rather than stderr. Once the file
+//! passes [`MAX_BYTES`] it is rotated: `tui.log` becomes `tui.log.1`, the old
+//! `tui.log.1` becomes `tui.log.2`, and so on up to [`KEEP`] old files.
+//!
+//! The level starts at `AXIOM_LOG`, or `info`, and `/loglevel` changes it
+//! while the TUI runs. If the TUI panics, a crash report ending with the
+//! last of the log is written next to it.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, Registry};
+
+/// Size a log file grows to before it is rotated
+pub const MAX_BYTES: u64 = 1024 * 1024;
+
+/// Rotated files kept besides the current one
+pub const KEEP: usize = 3;
+
+/// How much of the log a crash report ends with
+pub const TAIL_BYTES: u64 = 16 * 1024;
+
+/// Levels `/loglevel` takes
+pub const LEVELS: &[&str] = &["off", "error", "warn", "info", "debug", "trace"];
+
+/// A log file that moves aside once it gets too big
+pub struct RollingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: File,
+    size: u64,
+}
+
+impl RollingFile {
+    /// Append to `path`, creating it and its directory
+    pub fn open(path: impl Into<PathBuf>, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            keep,
+            file,
+            size,
+        })
+    }
+
+    /// `path` with `.n` added, for the `n`th rotated file
+    pub fn rotated(path: &Path, n: usize) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    /// Shift the rotated files up one, dropping the oldest, and start afresh
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.keep == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            for n in (1..self.keep).rev() {
+                let from = Self::rotated(&self.path, n);
+                if from.exists() {
+                    fs::rename(&from, Self::rotated(&self.path, n + 1))?;
+                }
+            }
+            fs::rename(&self.path, Self::rotated(&self.path, 1))?;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+        }
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Up to the last `max_bytes` of the log at `path`, reaching back into the
+/// previous file when the current one is short, from the start of a line
+pub fn tail(path: &Path, max_bytes: u64) -> String {
+    let (mut text, mut cut) = read_end(path, max_bytes);
+    let short = max_bytes.saturating_sub(text.len() as u64);
+    if !cut && short > 0 {
+        let (mut previous, previous_cut) = read_end(&RollingFile::rotated(path, 1), short);
+        if !previous.is_empty() {
+            previous.extend_from_slice(&text);
+            (text, cut) = (previous, previous_cut);
+        }
+    }
+    // A line cut short at the start is dropped
+    let start = match text.iter().position(|&b| b == b'\n') {
+        Some(newline) if cut => newline + 1,
+        _ => 0,
+    };
+    String::from_utf8_lossy(&text[start..]).into_owned()
+}
+
+/// The last `max_bytes` of a file, nothing if it can't be read, and whether
+/// that is less than the whole file
+fn read_end(path: &Path, max_bytes: u64) -> (Vec<u8>, bool) {
+    let mut bytes = Vec::new();
+    let Ok(mut file) = File::open(path) else {
+        return (bytes, false);
+    };
+    let len = file.metadata().map_or(0, |meta| meta.len());
+    let from = len.saturating_sub(max_bytes);
+    if file.seek(SeekFrom::Start(from)).is_err() || file.read_to_end(&mut bytes).is_err() {
+        return (Vec::new(), false);
+    }
+    (bytes, from > 0)
+}
+
+/// The level named `text`, e.g. `debug`
+pub fn parse_level(text: &str) -> Result<LevelFilter, String> {
+    text.parse()
+        .map_err(|_| format!("Unknown log level '{}'; use {}", text, LEVELS.join(", ")))
+}
+
+/// The TUI's logger, once set up
+pub struct Logger {
+    path: PathBuf,
+    level: reload::Handle<LevelFilter, Registry>,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Send tracing events to `.axiom/logs/tui.log` under `workspace`
+///
+/// Only the first call sets up the log; later ones return the same logger.
+pub fn init(workspace: &Path) -> io::Result<&'static Logger> {
+    if let Some(logger) = LOGGER.get() {
+        return Ok(logger);
+    }
+    let path = workspace.join(".axiom").join("logs").join("tui.log");
+    let file = RollingFile::open(&path, MAX_BYTES, KEEP)?;
+    let level = std::env::var("AXIOM_LOG")
+        .ok()
+        .and_then(|text| parse_level(&text).ok())
+        .unwrap_or(LevelFilter::INFO);
+    let (filter, handle) = reload::Layer::new(level);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(Mutex::new(file)),
+        )
+        .try_init()
+        .map_err(io::Error::other)?;
+    Ok(LOGGER.get_or_init(|| Logger {
+        path,
+        level: handle,
+    }))
+}
+
+/// The logger, if [`init`] set one up
+pub fn logger() -> Option<&'static Logger> {
+    LOGGER.get()
+}
+
+impl Logger {
+    /// The current log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Events at this level and above are written
+    pub fn level(&self) -> LevelFilter {
+        self.level.clone_current().unwrap_or(LevelFilter::OFF)
+    }
+
+    /// Write events at `level` and above from now on
+    pub fn set_level(&self, level: &str) -> Result<LevelFilter, String> {
+        let level = parse_level(level)?;
+        self.level
+            .modify(|current| *current = level)
+            .map_err(|e| e.to_string())?;
+        Ok(level)
+    }
+
+    /// Write a crash report for `panic` beside the log, ending with the
+    /// last of the log; returns where it went
+    pub fn crash_report(&self, panic: &str) -> io::Result<PathBuf> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let path = dir.join(format!("crash-{}.log", now));
+        let report = format!(
+            "Axiom {} crashed\n\n{}\n\nBacktrace:\n{}\n\nLast log lines ({}):\n{}",
+            env!("CARGO_PKG_VERSION"),
+            panic,
+            std::backtrace::Backtrace::force_capture(),
+            self.path.display(),
+            tail(&self.path, TAIL_BYTES),
+        );
+        fs::write(&path, report)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_keeps_the_newest_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("tui.log");
+        let mut file = RollingFile::open(&path, 10, 2).unwrap();
+        for line in ["aaaaaaa\n", "bbbbbbb\n", "ccccccc\n", "ddddddd\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "ddddddd\n");
+        let rotated = |n| fs::read_to_string(RollingFile::rotated(&path, n)).unwrap();
+        assert_eq!(rotated(1), "ccccccc\n");
+        assert_eq!(rotated(2), "bbbbbbb\n");
+        assert!(!RollingFile::rotated(&path, 3).exists());
+
+        // The tail reaches into the previous file, from a whole line
+        assert_eq!(tail(&path, 8), "ddddddd\n");
+        assert_eq!(tail(&path, 12), "ddddddd\n");
+        assert_eq!(tail(&path, 16), "ccccccc\nddddddd\n");
+        assert_eq!(tail(&dir.path().join("missing.log"), 16), "");
+
+        // Reopening carries on with the size already written
+        let mut file = RollingFile::open(&path, 10, 2).unwrap();
+        file.write_all(b"eeeeeee\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "eeeeeee\n");
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("debug"), Ok(LevelFilter::DEBUG));
+        assert_eq!(parse_level("WARN"), Ok(LevelFilter::WARN));
+        assert_eq!(parse_level("off"), Ok(LevelFilter::OFF));
+        assert!(parse_level("loud").unwrap_err().contains("trace"));
+    }
+}
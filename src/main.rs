@@ -9,30 +9,62 @@
 //! - `cargo run --web --dev` - Start web server with live reload (development)
 
 use axiom::{
-    agents::{Conductor, Executor, PtyAgentManager},
-    config::{config_path, load_config, save_config, AxiomConfig},
+    agents::{Conductor, Executor, ParkedWorkspace, PtyAgentManager, WorkspaceSessions},
+    config::{
+        config_path, load_config, loaded_config_path, save_config, with_workspace_overrides,
+        AxiomConfig,
+    },
     core::Result,
-    events::{Event, EventBus},
-    llm::{ClaudeProvider, GeminiProvider, OllamaProvider, ProviderRegistry},
-    panels::PanelRegistry,
-    state::{AppState, OutputContext, PanelId, WorkspaceId},
-    ui::{self, settings::SettingsAction, workspace_selector::WorkspaceSelectorAction, SelectorMode, toggle_theme, current_variant},
-    watcher::FileWatcher,
+    events::{key_chord, Event, EventBus},
+    llm::{
+        ClaudeProvider, GeminiProvider, LlmProvider, OllamaProvider, OpenAICompatibleProvider,
+        ProviderRegistry, SharedProvider,
+    },
+    logging,
+    panels::{Highlighter, Panel, PanelRegistry},
+    state::{AgentId, AppState, OutputContext, PanelId, PasteQueue, WorkspaceId, PASTE_CHUNK},
+    ui::{self, settings::SettingsAction, workspace_selector::WorkspaceSelectorAction, SelectorMode, toggle_theme, current_variant, capabilities, Damage, FrameProfiler, PickerAction, RedrawScheduler, WorkTimer, StatusReporter, AppStatus, ReviewAction, TrustPrompt, DuplicatePrompt, Bell, SoundEvent, SearchPanel, ReplacePanel},
+    watcher::{is_config_file, FileWatcher},
 };
+use axiom_core::agents::{last_message, last_prompt, touched_files, transcript_context};
+use axiom_core::clock::unix_now;
+use axiom_core::config::{
+    key_commands_allowed, Action, AgentModelConfig, Keymap, BUILTIN_PROVIDERS,
+};
+use axiom_core::files::{moved_path, write_atomic};
+use axiom_core::llm::{
+    blocked_message, describe_expiry, fetch_key, routing_summary, KeyAction, RouteCheck,
+};
+use axiom_core::orchestration::{
+    apply_llm_change, failure_report, suggest_follow_ups, AgentOperation, RunOutcome,
+};
+use axiom_core::usage::estimate_tokens;
+use axiom_core::workspace::{feature_switched, features_summary, parse_since, watch_summary};
 use axiom_core::{
-    ModelSubcommand, SlashCommand, SlashCommandData, SlashCommandParser, SlashCommandResult,
-    ThemeSubcommand, UiAction, WorkspaceSubcommand,
+    guard_content, ActivityEvent, ActivityKind, AgentAction, AgentEvent, AgentRole,
+    ApprovalSubcommand, ConfigChanges, Feature, FeaturesSubcommand, GuardConfig, InspectSubcommand,
+    MergeOutcome, ModelSubcommand, Plan, PlanSubcommand, PromptLibrary, RefactorSubcommand,
+    ReportSubcommand, ReviewKind, RoutingSubcommand, SearchQuery, SessionSubcommand, SlashCommand,
+    SlashCommandData, SlashCommandParser, SlashCommandResult, StatsSubcommand, ThemeSubcommand,
+    UiAction, UsageFilter, UsageRecord, UsageSubcommand, WatchAction, WatchRule, WatchSubcommand,
+    WorkspaceSubcommand,
 };
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, KeyCode, KeyModifiers},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        KeyCode, KeyModifiers,
+    },
     execute,
-    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{
+        self, BeginSynchronizedUpdate, EndSynchronizedUpdate, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
 };
 use ratatui::prelude::*;
 use std::io;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Command-line arguments
 struct Args {
@@ -46,6 +78,16 @@ struct Args {
     dev: bool,
     /// Open TUI in a new terminal window
     new_window: bool,
+    /// Multiplexer to open a layout in (`axiom layout tmux|zellij`)
+    layout: Option<String>,
+    /// Layout template replacing the built-in one (`--template`)
+    template: Option<PathBuf>,
+    /// Print the layout instead of launching it (`--print`)
+    print: bool,
+    /// Error code to explain (`axiom explain AX2003`); empty lists them all
+    explain: Option<String>,
+    /// Words after `axiom workspace` (`export ...` or `import ...`)
+    workspace_command: Option<Vec<String>>,
 }
 
 impl Args {
@@ -57,9 +99,31 @@ impl Args {
         let mut web = false;
         let mut dev = false;
         let mut new_window = false;
+        let mut layout = None;
+        let mut template = None;
+        let mut print = false;
+        let mut explain = None;
+        let mut workspace_command = None;
+        let mut first = true;
 
         while let Some(arg) = args.next() {
+            let is_first = std::mem::take(&mut first);
             match arg.as_str() {
+                "layout" if is_first => {
+                    layout = Some(args.next().unwrap_or_default());
+                }
+                "explain" if is_first => {
+                    explain = Some(args.next().unwrap_or_default());
+                }
+                "workspace" if is_first => {
+                    workspace_command = Some(args.by_ref().collect());
+                }
+                "--template" => {
+                    template = args.next().map(PathBuf::from);
+                }
+                "--print" => {
+                    print = true;
+                }
                 "--web" | "-W" => {
                     web = true;
                 }
@@ -82,7 +146,18 @@ impl Args {
             }
         }
 
-        Self { path, workspace, web, dev, new_window }
+        Self {
+            path,
+            workspace,
+            web,
+            dev,
+            new_window,
+            layout,
+            template,
+            print,
+            explain,
+            workspace_command,
+        }
     }
 }
 
@@ -91,6 +166,9 @@ impl Args {
 /// Determines the run mode based on arguments:
 /// - `--web` / `-W`: Start web server and open browser
 /// - `-n` / `--new-window`: Spawn TUI in a new terminal window
+/// - `layout tmux|zellij`: Open TUI, shell and log tail in a multiplexer
+/// - `explain <code>`: Print the causes and fixes of an error code
+/// - `workspace export|import`: Move a workspace's Axiom data between machines
 /// - Default: Run TUI in current terminal
 fn main() -> Result<()> {
     // If AXIOM_TUI is set, we were spawned by -n flag - run TUI directly
@@ -100,6 +178,18 @@ fn main() -> Result<()> {
 
     let args = Args::parse();
 
+    if let Some(code) = &args.explain {
+        return run_explain(code);
+    }
+
+    if let Some(words) = &args.workspace_command {
+        return run_workspace(words);
+    }
+
+    if args.layout.is_some() {
+        return run_layout(args);
+    }
+
     if args.web {
         return run_web(args);
     }
@@ -112,6 +202,103 @@ fn main() -> Result<()> {
     run_tui()
 }
 
+/// Prints what causes error `code` and how to fix it, or every code when
+/// none is given.
+fn run_explain(code: &str) -> Result<()> {
+    use axiom::core::AxiomError;
+    use axiom_core::error_codes::{catalog, explain};
+
+    if code.is_empty() {
+        println!("{}", catalog());
+        return Ok(());
+    }
+    let code: axiom_core::ErrorCode = code.parse().map_err(|_| {
+        AxiomError::Config(format!(
+            "Unknown error code '{}' (run `axiom explain` to list them)",
+            code
+        ))
+    })?;
+    print!("{}", explain(code));
+    Ok(())
+}
+
+/// Moves a workspace between machines (`axiom workspace export|import`).
+///
+/// `export <name|id> [--with-state] [-o <file>]` writes a bundle of the
+/// workspace, with its `.axiom/` data when `--with-state` is given, to
+/// `<name>.axiom-workspace.json` unless `-o` names another file. `import
+/// <file> [path]` registers the bundle at `path`, the current directory by
+/// default, where the project's files must already be.
+fn run_workspace(words: &[String]) -> Result<()> {
+    use axiom::core::AxiomError;
+    use axiom_core::{WorkspaceBundle, WorkspaceManager};
+
+    const USAGE: &str =
+        "usage: axiom workspace export <name|id> [--with-state] [-o <file>]\n       \
+                         axiom workspace import <file> [path]";
+    let core_error = |e: axiom_core::AxiomError| AxiomError::Config(e.to_string());
+    let usage = || AxiomError::Config(USAGE.to_string());
+
+    let manager = WorkspaceManager::new(axiom_core::AxiomConfig::default()).map_err(core_error)?;
+    let mut words = words.iter().map(String::as_str);
+    match words.next() {
+        Some("export") => {
+            let (mut key, mut with_state, mut output) = (None, false, None);
+            while let Some(word) = words.next() {
+                match word {
+                    "--with-state" => with_state = true,
+                    "-o" | "--output" => output = words.next().map(PathBuf::from),
+                    _ if key.is_none() && !word.starts_with('-') => key = Some(word),
+                    _ => return Err(usage()),
+                }
+            }
+            let workspace = manager
+                .resolve_workspace(key.ok_or_else(usage)?)
+                .map_err(core_error)?;
+            let mut bundle = WorkspaceBundle::new(&workspace);
+            if with_state {
+                for path in bundle.collect_state().map_err(core_error)? {
+                    eprintln!("Skipped .axiom/{} (not text)", path);
+                }
+            }
+            let output = output.unwrap_or_else(|| {
+                PathBuf::from(format!("{}.axiom-workspace.json", workspace.name))
+            });
+            let json = serde_json::to_string_pretty(&bundle)
+                .map_err(|e| AxiomError::Config(format!("Failed to write bundle: {}", e)))?;
+            std::fs::write(&output, json)?;
+            println!(
+                "Exported '{}' ({} files of state) to {}",
+                workspace.name,
+                bundle.files.len(),
+                output.display()
+            );
+        }
+        Some("import") => {
+            let file = words.next().ok_or_else(usage)?;
+            let cwd = std::env::current_dir()?;
+            let path = words.next().map_or(cwd.clone(), |path| cwd.join(path));
+            let json = std::fs::read_to_string(file)
+                .map_err(|e| AxiomError::Config(format!("Cannot read {}: {}", file, e)))?;
+            let bundle: WorkspaceBundle = serde_json::from_str(&json).map_err(|e| {
+                AxiomError::Config(format!("{} is not a workspace bundle: {}", file, e))
+            })?;
+            let workspace = manager
+                .import_workspace(&bundle, path)
+                .map_err(core_error)?;
+            println!(
+                "Imported '{}' from {} at {} ({} files of state)",
+                workspace.name,
+                bundle.root.display(),
+                workspace.path.display(),
+                bundle.files.len()
+            );
+        }
+        _ => return Err(usage()),
+    }
+    Ok(())
+}
+
 /// Spawns the TUI in a new terminal window (used with `-n` flag).
 ///
 /// Sets `AXIOM_TUI=1` so the spawned process runs TUI directly.
@@ -213,6 +400,83 @@ fn spawn_in_new_terminal() -> Result<()> {
     }
 }
 
+/// Opens the TUI in a tmux or zellij layout (`axiom layout tmux|zellij`).
+///
+/// The tail pane follows the workspace's activity log when the directory is
+/// a registered workspace, and the diagnostics log otherwise.
+fn run_layout(args: Args) -> Result<()> {
+    use axiom::core::AxiomError;
+    use axiom::layout::{self, LayoutVars, Multiplexer};
+
+    let name = args.layout.unwrap_or_default();
+    let mux = Multiplexer::parse(&name).ok_or_else(|| {
+        AxiomError::Config(format!(
+            "Unknown multiplexer '{}' (usage: axiom layout tmux|zellij [path])",
+            name
+        ))
+    })?;
+
+    let cwd = std::env::current_dir()?;
+    let dir = args.path.map(|p| cwd.join(p)).unwrap_or(cwd);
+    let dir = dir
+        .canonicalize()
+        .map_err(|e| AxiomError::Config(format!("Cannot open {}: {}", dir.display(), e)))?;
+
+    let log = axiom_core::WorkspaceManager::new(axiom_core::AxiomConfig::default())
+        .ok()
+        .and_then(|manager| {
+            let workspace = manager.find_by_path(&dir)?;
+            Some(manager.activity_log_path(workspace.id))
+        })
+        .or_else(axiom::ui::profiler::diagnostics_log_path)
+        .unwrap_or_else(|| std::env::temp_dir().join("axiom.log"));
+
+    let template_path = args
+        .template
+        .or_else(|| layout::user_template_path(mux).filter(|p| p.is_file()));
+    let template = match &template_path {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| {
+            AxiomError::Config(format!(
+                "Failed to read layout template {}: {}",
+                path.display(),
+                e
+            ))
+        })?,
+        None => mux.default_template().to_string(),
+    };
+
+    let exe = std::env::current_exe()
+        .map_err(|e| AxiomError::Config(format!("Failed to get executable path: {}", e)))?;
+    let vars = LayoutVars::new(exe, dir, log);
+    let rendered = layout::render(&template, mux, &vars);
+
+    if args.print {
+        print!("{}", rendered);
+        return Ok(());
+    }
+
+    let extension = match mux {
+        Multiplexer::Tmux => "conf",
+        Multiplexer::Zellij => "kdl",
+    };
+    let layout_file = std::env::temp_dir().join(format!("{}.{}", vars.name, extension));
+    std::fs::write(&layout_file, rendered)?;
+
+    let nested = mux.is_running();
+    let exists = mux == Multiplexer::Tmux && !nested && layout::tmux_session_exists(&vars.name);
+    let status = layout::launch_command(mux, &layout_file, &vars, nested, exists)
+        .status()
+        .map_err(|e| AxiomError::Config(format!("Failed to start {}: {}", mux.program(), e)))?;
+    if !status.success() {
+        return Err(AxiomError::Config(format!(
+            "{} exited with {}",
+            mux.program(),
+            status
+        )));
+    }
+    Ok(())
+}
+
 /// Runs both the API server and web UI, then opens the browser.
 ///
 /// Starts:
@@ -398,9 +662,15 @@ fn run_tui() -> Result<()> {
     // Setup terminal
     terminal::enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut term = Terminal::new(backend)?;
+    install_panic_hook();
 
     // Run app with panic recovery
     let result = run_app(&mut term, args);
@@ -410,7 +680,8 @@ fn run_tui() -> Result<()> {
     execute!(
         term.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     term.show_cursor()?;
 
@@ -422,12 +693,42 @@ fn run_tui() -> Result<()> {
     result
 }
 
+/// Logs panics, and for one on the main thread restores the terminal and
+/// writes a crash report with the last of the log before the usual message.
+///
+/// Other threads' panics only go to the log, so the screen stays intact.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        tracing::error!("{}", info);
+        if std::thread::current().name() != Some("main") {
+            return;
+        }
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(
+            io::stdout(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableBracketedPaste
+        );
+        if let Some(logger) = logging::logger() {
+            match logger.crash_report(&info.to_string()) {
+                Ok(path) => eprintln!("Crash report written to {}", path.display()),
+                Err(e) => eprintln!("Failed to write crash report: {}", e),
+            }
+        }
+        default_hook(info);
+    }));
+}
+
 /// Main application loop.
 ///
 /// Initializes the application state, event bus, panels, and file watcher.
 /// Handles the main event loop, rendering the UI and processing events
 /// until a quit signal is received.
 fn run_app<B: Backend>(terminal: &mut Terminal<B>, args: Args) -> Result<()> {
+    let started = Instant::now();
+
     // Initialize clipboard
     axiom::clipboard::init();
 
@@ -456,15 +757,18 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, args: Args) -> Result<()> {
         AppState::new()
     };
 
-    // Load configuration
-    let config = load_config(&state.cwd).unwrap_or_else(|e| {
-        eprintln!("Warning: Failed to load config: {}. Using defaults.", e);
-        AxiomConfig::default()
-    });
+    // The TUI owns the terminal, so diagnostics go to a log file
+    let log_error = logging::init(&state.cwd).err();
+    let version = env!("CARGO_PKG_VERSION");
+    tracing::info!("Axiom {} started in {}", version, state.cwd.display());
+
+    // Load configuration while the workspace manager reads its registry
+    let config_cwd = state.cwd.clone();
+    let config_load = std::thread::spawn(move || load_config(&config_cwd));
 
     // Initialize workspace manager
     if let Err(e) = state.init_workspace_manager(axiom_core::AxiomConfig::default()) {
-        eprintln!("Warning: Failed to initialize workspace manager: {}", e);
+        tracing::warn!("Failed to initialize workspace manager: {}", e);
     }
 
     // If --workspace argument provided, try to switch to it
@@ -473,11 +777,11 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, args: Args) -> Result<()> {
             match state.switch_workspace(ws_id) {
                 Ok(_) => {}
                 Err(e) => {
-                    eprintln!("Warning: Failed to switch to workspace {}: {}", workspace_id_str, e);
+                    tracing::warn!("Failed to switch to workspace {}: {}", workspace_id_str, e);
                 }
             }
         } else {
-            eprintln!("Warning: Invalid workspace ID: {}", workspace_id_str);
+            tracing::warn!("Invalid workspace ID: {}", workspace_id_str);
         }
     } else if let Some(manager) = &state.workspace_manager {
         // Try to find existing workspace for cwd or create/detect one
@@ -490,31 +794,74 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, args: Args) -> Result<()> {
         }
     }
 
-    // Create provider registry from config
-    let llm_registry = create_provider_registry(&config);
+    let mut config = match config_load.join() {
+        Ok(Ok(config)) => config,
+        Ok(Err(e)) => {
+            tracing::warn!("Failed to load config: {}. Using defaults.", e);
+            AxiomConfig::default()
+        }
+        Err(_) => AxiomConfig::default(),
+    };
+    state.keymap = Keymap::new(&config.keys).unwrap_or_else(|e| {
+        state.warn(format!("Key bindings not loaded: {}", e));
+        Keymap::default()
+    });
+    if let Some(e) = log_error {
+        state.warn(format!("Log file not opened: {}", e));
+    }
 
-    // Store config for settings modal (mutable for reloading)
-    let mut config = config;
+    // Providers are set up off the critical path; until then the registry
+    // is a placeholder and the status bar shows a spinner
+    let llm_registry = ProviderRegistry::loading(&config);
 
     // Create panels
     let mut panels = PanelRegistry::new(event_bus.sender(), &state.cwd, llm_registry, &config)?;
 
+    // The workspace's own provider and CLI agent win over the global ones
+    let effective = with_workspace_overrides(&config, &state.cwd);
+    panels.update_cli_agents(effective.cli_agents.clone());
+    spawn_provider_init(panels.llm_registry.clone(), effective, event_bus.sender());
+    let mut defaults_cwd = state.cwd.clone();
+
+    // Syntax definitions load in the background too, ready for the first file
+    std::thread::spawn(Highlighter::warm_up);
+
     // Create conductor and executor
     let mut conductor = Conductor::new(panels.llm_registry.clone(), event_bus.sender());
-    let executor = Executor::new(
+    conductor.set_prompts(PromptLibrary::new(&state.cwd, &config.prompts));
+    conductor.set_context_config(config.llm.context.clone());
+    let mut executor = Executor::new(
         event_bus.sender(),
         panels.agent_registry.clone(),
         state.cwd.clone(),
     );
 
+    // Agents of workspaces switched away from keep running in the background
+    let mut sessions = WorkspaceSessions::new(state.cwd.clone());
+
     // Create PTY agent manager for CLI agents (wrapped in Arc<RwLock> for sharing)
-    let pty_manager = Arc::new(parking_lot::RwLock::new(PtyAgentManager::new(event_bus.sender())));
+    let mut pty_manager = PtyAgentManager::new(event_bus.sender());
+    pty_manager.set_scrollback(config.cli_agents.scrollback_lines);
+    let pty_manager = Arc::new(parking_lot::RwLock::new(pty_manager));
 
     // Give OutputPanel access to PTY manager for CLI agent rendering
     panels.output.set_pty_manager(pty_manager.clone(), event_bus.sender());
-
-    // Start file watcher for the project directory
-    let _file_watcher = FileWatcher::new(&state.cwd, event_bus.sender())
+    panels.output.set_lsp_config(config.lsp.clone());
+    panels.output.set_completion_config(config.completion.clone());
+    panels.agents.set_retention(&config.agents);
+
+    // Start file watcher for the project directory, unless the workspace
+    // switched it off
+    let watch_files = match (&state.workspace_manager, state.active_workspace_id) {
+        (Some(manager), Some(id)) => manager
+            .features(id)
+            .map(|flags| flags.is_enabled(Feature::FileWatcher))
+            .unwrap_or(true),
+        _ => true,
+    };
+    let _file_watcher = watch_files
+        .then(|| FileWatcher::new(&state.cwd, event_bus.sender()))
+        .transpose()
         .map_err(|e| axiom::core::AxiomError::Config(format!("File watcher error: {}", e)))?;
 
     // Spawn input reader thread
@@ -532,31 +879,100 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, args: Args) -> Result<()> {
     // Initialize workspace selector with existing workspaces
     // (it will be shown as main screen until a workspace is selected)
     if let Some(manager) = &state.workspace_manager {
-        let workspaces = manager.list_workspaces();
-        panels.open_workspace_selector(workspaces, state.active_workspace_id);
+        let broken = manager.broken_workspaces().len();
+        panels.open_workspace_selector(manager, state.active_workspace_id);
+        if broken > 0 {
+            state.error(format!(
+                "{} workspace{} no longer found on disk - select to repair",
+                broken,
+                if broken == 1 { "" } else { "s" }
+            ));
+        }
+    }
+
+    // Opt-in render profiler (AXIOM_PROFILE=1 or F12)
+    let mut profiler = FrameProfiler::from_env();
+
+    // Only redraw when something changed (input, coalesced output, heartbeat)
+    let mut redraw = RedrawScheduler::default();
+
+    // Watch rules, key refreshes and completions are polled on their own timer
+    let mut work = WorkTimer::default();
+
+    // Window title and progress follow the workspace and running agents
+    let mut status = StatusReporter::detect(config.terminal.clone());
+
+    // Audio cues for finished agents, approvals and errors
+    let mut bell = Bell::new(config.sounds.clone());
+    if let Some(warning) = bell.warning() {
+        state.warn(warning);
     }
 
     // Main event loop
+    let mut first_paint = true;
     loop {
         // Render
-        terminal.draw(|frame| {
-            ui::render(frame, &state, &mut panels);
-        })?;
-
-        // Process events with timeout (50ms for responsive UI)
-        if let Some(event) = event_bus.recv_timeout(Duration::from_millis(50)) {
-            if handle_event(
-                &event,
+        let discovered = panels.poll_discovery(state.workspace_manager.as_deref());
+        if panels.poll_search() || discovered || panels.needs_redraw() || profiler.is_enabled() {
+            redraw.mark(Damage::Coalesced);
+        }
+        if redraw.should_draw(Instant::now()) {
+            // Synchronized output avoids tearing where the terminal supports it
+            let sync = capabilities().synchronized_output;
+            if sync {
+                execute!(io::stdout(), BeginSynchronizedUpdate)?;
+            }
+            let draw_start = Instant::now();
+            terminal.draw(|frame| {
+                ui::render(frame, &state, &mut panels, &mut profiler);
+            })?;
+            profiler.record_remainder("terminal flush", draw_start.elapsed());
+            if sync {
+                execute!(io::stdout(), EndSynchronizedUpdate)?;
+            }
+            redraw.drawn(Instant::now());
+            if std::mem::take(&mut first_paint) && profiler.is_enabled() {
+                state.info(format!(
+                    "First paint after {} ms",
+                    started.elapsed().as_millis()
+                ));
+            }
+        }
+
+        // Wait for events until the next frame or periodic work is due
+        let now = Instant::now();
+        let wait = redraw.timeout(now).min(work.timeout(now));
+        if let Some(event) = event_bus.recv_timeout(wait) {
+            redraw.mark(Damage::for_event(&event));
+            if toggle_profiler(&event, &mut profiler, &mut state)
+                || background_event(&event, &mut sessions, &mut state, &pty_manager)
+            {
+                continue;
+            }
+            let label = ui::event_label(&event, state.focus.current());
+            if profiler.time(label, || {
+                handle_event(
+                    &event,
+                    &mut state,
+                    &mut panels,
+                    screen_area,
+                    &mut config,
+                    &mut conductor,
+                    &executor,
+                    &pty_manager,
+                )
+            })? {
+                break; // Quit requested
+            }
+            follow_workspace(
+                &mut sessions,
                 &mut state,
                 &mut panels,
-                screen_area,
-                &mut config,
                 &mut conductor,
-                &executor,
-                &pty_manager,
-            )? {
-                break; // Quit requested
-            }
+                &mut executor,
+                &config,
+                &event_bus.sender(),
+            );
 
             // Handle resize events
             if let Event::Resize(w, h) = event {
@@ -568,18 +984,74 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, args: Args) -> Result<()> {
 
         // Drain additional events to prevent lag
         for event in event_bus.drain(50) {
-            if handle_event(
-                &event,
+            redraw.mark(Damage::for_event(&event));
+            if toggle_profiler(&event, &mut profiler, &mut state)
+                || background_event(&event, &mut sessions, &mut state, &pty_manager)
+            {
+                continue;
+            }
+            let label = ui::event_label(&event, state.focus.current());
+            if profiler.time(label, || {
+                handle_event(
+                    &event,
+                    &mut state,
+                    &mut panels,
+                    screen_area,
+                    &mut config,
+                    &mut conductor,
+                    &executor,
+                    &pty_manager,
+                )
+            })? {
+                break;
+            }
+            follow_workspace(
+                &mut sessions,
                 &mut state,
                 &mut panels,
-                screen_area,
-                &mut config,
                 &mut conductor,
-                &executor,
-                &pty_manager,
-            )? {
-                break;
-            }
+                &mut executor,
+                &config,
+                &event_bus.sender(),
+            );
+        }
+
+        // Hand the next piece of a large paste to its panel
+        if deliver_paste(&mut state, &mut panels)? {
+            redraw.mark(Damage::Coalesced);
+        }
+
+
+        // Another workspace brings its own provider and CLI agent
+        if defaults_cwd != state.cwd {
+            defaults_cwd = state.cwd.clone();
+            apply_workspace_defaults(&defaults_cwd, &mut panels, &config);
+            conductor.set_prompts(PromptLibrary::new(&defaults_cwd, &config.prompts));
+        }
+
+
+        // Ask whether to trust a workspace opened for the first time
+        if let Some(workspace) = state.take_trust_prompt() {
+            panels.trust_prompt = Some(TrustPrompt::new(
+                workspace.id,
+                workspace.name,
+                workspace.path,
+            ));
+            state.input_mode.open_modal("trust_prompt");
+        }
+
+        write_terminal(&status.update(&app_status(&state, &panels), Instant::now()))?;
+        write_terminal(&bell.ring(&state.take_cues()))?;
+
+        if work.due(Instant::now()) {
+            // Ask for an editor completion once typing pauses
+            panels.output.editor_event(&Event::Tick, &mut state)?;
+
+            // Fetch provider keys before they expire
+            poll_keys(&mut state, &config, &event_bus.sender());
+
+            // Start watch rules whose files have settled
+            run_due_watches(&mut state, &mut panels, &mut conductor, &executor);
         }
 
         // Check if file tree wants to open a file (auto-open on selection)
@@ -589,14 +1061,209 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, args: Args) -> Result<()> {
             // Don't switch focus - let user keep navigating file tree
         }
 
+        // Persist directory picker bookmarks and listing preferences
+        if let Some(prefs) = panels.take_picker_prefs_change() {
+            config.directory_picker = prefs;
+            if let Err(e) = save_config(&config, &loaded_config_path(&state.cwd)) {
+                state.error(format!("Failed to save bookmarks: {}", e));
+            }
+        }
+
+        // Slow frames are logged to the diagnostics log by the profiler
+        profiler.end_frame();
+
         if state.should_quit {
             break;
         }
     }
 
+    write_terminal(&status.finish())?;
+    state.flush_user_time();
+    Ok(())
+}
+
+/// Apply an event of an agent in a workspace parked in the background;
+/// returns whether it was one
+fn background_event(
+    event: &Event,
+    sessions: &mut WorkspaceSessions,
+    state: &mut AppState,
+    pty_manager: &Arc<parking_lot::RwLock<PtyAgentManager>>,
+) -> bool {
+    let Some(note) = sessions.handle_background(event, pty_manager) else {
+        return false;
+    };
+    if let Some(note) = note {
+        state.info(note);
+    }
+    true
+}
+
+/// Once the workspace on screen changed, park the agents of the one left,
+/// still running, and bring back those of the one switched to
+fn follow_workspace(
+    sessions: &mut WorkspaceSessions,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &mut Conductor,
+    executor: &mut Executor,
+    config: &AxiomConfig,
+    event_tx: &crossbeam_channel::Sender<Event>,
+) {
+    if sessions.active() == state.cwd {
+        return;
+    }
+    let left = state
+        .workspace_manager
+        .as_ref()
+        .and_then(|manager| Some((manager, manager.find_by_path(sessions.active())?)));
+    // Like `AppState::is_trusted`, a directory that isn't a workspace is
+    let trusted = left.as_ref().is_none_or(|(manager, ws)| manager.is_trusted(ws.id));
+    let name = left.map_or_else(|| workspace_name_for(sessions.active()), |(_, ws)| ws.name);
+
+    let registry = Arc::new(parking_lot::RwLock::new(
+        panels.agent_registry.read().sharing_ids(),
+    ));
+    let mut fresh = Conductor::new(panels.llm_registry.clone(), event_tx.clone());
+    fresh.set_context_config(config.llm.context.clone());
+    fresh.set_inspect(conductor.inspecting());
+    let parked = ParkedWorkspace {
+        name,
+        trusted,
+        conductor: std::mem::replace(conductor, fresh),
+        executor: std::mem::replace(
+            executor,
+            Executor::new(event_tx.clone(), registry.clone(), state.cwd.clone()),
+        ),
+        registry: panels.agent_registry(),
+        run: state.run.take(),
+    };
+    match sessions.switch(parked, &state.cwd.clone()) {
+        Some(restored) => {
+            *conductor = restored.conductor;
+            *executor = restored.executor;
+            panels.set_agent_registry(restored.registry);
+            state.run = restored.run;
+        }
+        None => panels.set_agent_registry(registry),
+    }
+}
+
+/// Start the watch rules that are due
+///
+/// Rules come from the workspace's own config, so untrusted workspaces
+/// don't run them.
+fn run_due_watches(
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &mut Conductor,
+    executor: &Executor,
+) {
+    state.watch.sync(
+        state.workspace_manager.as_deref(),
+        state.active_workspace_id,
+    );
+    let triggers = state.watch.due(Instant::now());
+    if triggers.is_empty() {
+        return;
+    }
+    if !state.is_trusted() {
+        for trigger in &triggers {
+            state.watch.skipped(trigger.rule);
+        }
+        state.warn("Watch rules don't run in untrusted workspaces (/trust on to allow)");
+        return;
+    }
+
+    for trigger in triggers {
+        let text = trigger.action.expand(&trigger.files);
+        let changed = match trigger.files.as_slice() {
+            [file] => file.clone(),
+            files => format!("{} files", files.len()),
+        };
+        match trigger.action {
+            WatchAction::Run { .. } => {
+                let request = axiom::agents::AgentSpawnRequest {
+                    agent_type: axiom::agents::AgentType::Shell,
+                    name: "Watch".to_string(),
+                    description: format!("{} changed: {}", changed, truncate_cmd(&text, 40)),
+                    parameters: Some(text),
+                    parent_id: None,
+                };
+                let agent_id = panels.agent_registry.write().spawn(request.clone());
+                state.time.agent_started(agent_id, Instant::now());
+                executor.execute(agent_id, &request);
+                state.watch.started(agent_id, trigger.rule);
+                state.record_activity(ActivityEvent::new(
+                    ActivityKind::AgentRun,
+                    "watch",
+                    format!("Started Watch: {}", request.description),
+                ));
+            }
+            WatchAction::Agent { .. } => {
+                if !route_allowed(state, panels, AgentRole::Orchestrator) {
+                    state.watch.skipped(trigger.rule);
+                    continue;
+                }
+                conductor.process(guard_prompt(state, &text));
+                // The first prompt spawns the conductor, so there's no agent
+                // to wait for yet
+                match conductor.agent_id() {
+                    Some(agent_id) => state.watch.started(agent_id, trigger.rule),
+                    None => state.watch.skipped(trigger.rule),
+                }
+                state.info(format!(
+                    "Watch: {} changed, prompting the conductor",
+                    changed
+                ));
+            }
+        }
+    }
+}
+
+/// What the window title and progress reflect
+fn app_status(state: &AppState, panels: &PanelRegistry) -> AppStatus {
+    AppStatus {
+        workspace: state.active_workspace().map(|ws| ws.name),
+        running: panels
+            .agent_registry
+            .read()
+            .agents()
+            .filter(|agent| agent.status.is_running())
+            .map(|agent| agent.name.clone())
+            .collect(),
+    }
+}
+
+/// Write raw escape sequences outside the ratatui buffer
+fn write_terminal(sequences: &str) -> Result<()> {
+    if !sequences.is_empty() {
+        use std::io::Write;
+        let mut stdout = io::stdout();
+        stdout.write_all(sequences.as_bytes())?;
+        stdout.flush()?;
+    }
     Ok(())
 }
 
+/// Toggle the render profiler on F12
+///
+/// Returns true if the event was consumed.
+fn toggle_profiler(event: &Event, profiler: &mut FrameProfiler, state: &mut AppState) -> bool {
+    let Event::Key(key) = event else {
+        return false;
+    };
+    if key.code != KeyCode::F(12) {
+        return false;
+    }
+    if profiler.toggle() {
+        state.info("Profiler on (slow frames logged to diagnostics.log)");
+    } else {
+        state.info("Profiler off");
+    }
+    true
+}
+
 /// Processes a single application event.
 ///
 /// Handles global keybindings (like quitting or focus switching) and routes
@@ -613,42 +1280,34 @@ fn handle_event(
     executor: &Executor,
     pty_manager: &Arc<parking_lot::RwLock<PtyAgentManager>>,
 ) -> Result<bool> {
+    if matches!(event, Event::Key(_) | Event::Mouse(_) | Event::Paste(_)) {
+        state.note_input();
+    }
+
     // Workspace selection phase: only handle workspace selector events and resize
     if state.active_workspace_id.is_none() {
         match event {
+            Event::Key(key) if state.input_mode.is_modal_open("duplicate_prompt") => {
+                handle_duplicate_prompt_key(key.code, state, panels);
+            }
             Event::Key(key) => {
                 let is_creating = panels.workspace_selector.mode == SelectorMode::CreateNew;
                 let is_browsing = panels.workspace_selector.mode == SelectorMode::BrowseFolders;
+                let is_listing = panels.workspace_selector.mode == SelectorMode::List;
+                let is_repairing = panels.workspace_selector.mode == SelectorMode::Repair;
 
-                // Handle BrowseFolders mode
+                // BrowseFolders mode: keys go to the folder picker
                 if is_browsing {
-                    match key.code {
-                        KeyCode::Esc => {
-                            panels.workspace_selector.cancel_browse();
-                            return Ok(false);
-                        }
-                        KeyCode::Up => {
-                            panels.workspace_selector.up();
-                            return Ok(false);
-                        }
-                        KeyCode::Down => {
-                            panels.workspace_selector.down();
-                            return Ok(false);
-                        }
-                        KeyCode::Enter => {
-                            panels.workspace_selector.folder_enter();
-                            return Ok(false);
-                        }
-                        KeyCode::Backspace => {
-                            panels.workspace_selector.folder_parent();
-                            return Ok(false);
-                        }
-                        _ => return Ok(false),
-                    }
+                    let action = panels.workspace_selector.handle_picker_key(*key);
+                    handle_workspace_selector_action_initial(action, state, panels);
+                    return Ok(false);
                 }
 
                 match key.code {
-                    // No Esc to dismiss - must select a workspace
+                    // No Esc to dismiss - must select a workspace, but Esc still clears the search
+                    KeyCode::Esc if is_listing => {
+                        panels.workspace_selector.escape();
+                    }
                     KeyCode::Up => {
                         panels.workspace_selector.up();
                     }
@@ -664,24 +1323,39 @@ fn handle_event(
                     }
                     KeyCode::Enter => {
                         let action = panels.workspace_selector.enter();
-                        handle_workspace_selector_action_initial(action, state, panels, pty_manager);
+                        handle_workspace_selector_action_initial(action, state, panels);
                     }
                     KeyCode::Delete => {
                         panels.workspace_selector.delete();
                     }
                     KeyCode::Char('y') if panels.workspace_selector.mode == SelectorMode::ConfirmDelete => {
                         let action = panels.workspace_selector.confirm();
-                        handle_workspace_selector_action_initial(action, state, panels, pty_manager);
+                        handle_workspace_selector_action_initial(action, state, panels);
                     }
                     KeyCode::Char('n') if panels.workspace_selector.mode == SelectorMode::ConfirmDelete => {
                         panels.workspace_selector.deny();
                     }
+                    // Repair a broken workspace: relocate or archive it
+                    KeyCode::Char('r') if is_repairing => {
+                        panels.workspace_selector.relocate();
+                    }
+                    KeyCode::Char('a') if is_repairing => {
+                        let action = panels.workspace_selector.archive();
+                        handle_workspace_selector_action_initial(action, state, panels);
+                    }
                     KeyCode::Char(c) if is_creating => {
                         panels.workspace_selector.insert_char(c);
                     }
                     KeyCode::Backspace if is_creating => {
                         panels.workspace_selector.backspace();
                     }
+                    // Typing in the list filters workspaces
+                    KeyCode::Char(c) if is_listing && !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        panels.workspace_selector.search_push(c);
+                    }
+                    KeyCode::Backspace if is_listing => {
+                        panels.workspace_selector.search_pop();
+                    }
                     KeyCode::Left if is_creating => {
                         panels.workspace_selector.cursor_left();
                     }
@@ -692,6 +1366,27 @@ fn handle_event(
                 }
                 return Ok(false);
             }
+            Event::Mouse(mouse) => {
+                let (x, y) = (mouse.column, mouse.row);
+                // No click-outside dismiss - must select a workspace
+                match mouse.kind {
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        let action = panels.workspace_selector.handle_click(x, y);
+                        handle_workspace_selector_action_initial(action, state, panels);
+                    }
+                    event::MouseEventKind::ScrollUp => {
+                        panels.workspace_selector.handle_scroll(false);
+                    }
+                    event::MouseEventKind::ScrollDown => {
+                        panels.workspace_selector.handle_scroll(true);
+                    }
+                    event::MouseEventKind::Moved => {
+                        panels.workspace_selector.handle_hover(x, y);
+                    }
+                    _ => {}
+                }
+                return Ok(false);
+            }
             Event::Resize(w, h) => {
                 // Handle resize during workspace selection
                 let _ = (w, h); // Layout will be recalculated on next render
@@ -702,6 +1397,22 @@ fn handle_event(
     }
 
     match event {
+        // Pastes go to the focused panel as one insert, piece by piece if large
+        Event::Paste(text) => {
+            if state.input_mode.is_modal() {
+                return Ok(false);
+            }
+            let focused = state.focus.current();
+            if focused == PanelId::INPUT && !state.input_mode.is_editing() {
+                state.input_mode.to_insert();
+            }
+            if text.len() > PASTE_CHUNK {
+                state.paste = Some(PasteQueue::new(focused, text.clone()));
+            } else {
+                panels.get_mut(focused).handle_input(event, state)?;
+            }
+        }
+
         // Global key bindings (checked first)
         Event::Key(key) => {
             // Handle settings modal
@@ -711,6 +1422,7 @@ fn handle_event(
                         if panels.settings.editing {
                             panels.settings.cancel_edit();
                         } else {
+                            state.auth_retry.cancel();
                             state.input_mode.to_normal();
                         }
                         return Ok(false);
@@ -734,21 +1446,37 @@ fn handle_event(
                     KeyCode::Enter => {
                         match panels.settings.enter() {
                             SettingsAction::Save => {
-                                if let Some(new_config) = panels.apply_settings() {
+                                let workspace_saved = save_workspace_defaults(state, panels);
+                                if let Some(mut new_config) = panels.apply_settings() {
+                                    new_config.directory_picker = config.directory_picker.clone();
+                                    new_config.discovery = config.discovery.clone();
+                                    new_config.terminal = config.terminal.clone();
+                                    new_config.completion = config.completion.clone();
+                                    new_config.sounds = config.sounds.clone();
+                                    new_config.agents = config.agents.clone();
+                                    new_config.prompts = config.prompts.clone();
                                     // Save to file
                                     let path = config_path(&state.cwd);
                                     if let Err(e) = save_config(&new_config, &path) {
                                         state.error(format!("Failed to save settings: {}", e));
                                     } else {
                                         // Reload providers with new config
-                                        reload_providers(panels, &new_config);
+                                        // and the workspace's over it
                                         *config = new_config;
+                                        apply_workspace_defaults(&state.cwd, panels, config);
                                         state.info("Settings saved");
+                                        if panels.retry_rejected_prompt(state, conductor) {
+                                            state.info("Retrying with the new key");
+                                        }
                                     }
+                                } else if workspace_saved {
+                                    apply_workspace_defaults(&state.cwd, panels, config);
+                                    state.info("Settings saved");
                                 }
                                 state.input_mode.to_normal();
                             }
                             SettingsAction::Cancel => {
+                                state.auth_retry.cancel();
                                 state.input_mode.to_normal();
                             }
                             SettingsAction::StartEdit | SettingsAction::None => {
@@ -799,52 +1527,183 @@ fn handle_event(
                 }
             }
 
-            // Handle workspace selector modal
-            if state.input_mode.is_modal_open("workspace_selector") {
-                let is_creating = panels.workspace_selector.mode == SelectorMode::CreateNew;
-                let is_browsing = panels.workspace_selector.mode == SelectorMode::BrowseFolders;
-
-                // Handle BrowseFolders mode
-                if is_browsing {
+            // Handle activity feed modal
+            if state.input_mode.is_modal_open("activity_feed") {
+                if let Some(feed) = panels.activity_feed.as_mut() {
                     match key.code {
-                        KeyCode::Esc => {
-                            panels.workspace_selector.cancel_browse();
-                            return Ok(false);
-                        }
-                        KeyCode::Up => {
-                            panels.workspace_selector.up();
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            panels.activity_feed = None;
+                            state.input_mode.to_normal();
                             return Ok(false);
                         }
-                        KeyCode::Down => {
-                            panels.workspace_selector.down();
+                        KeyCode::Up | KeyCode::Char('k') => feed.up(1),
+                        KeyCode::Down | KeyCode::Char('j') => feed.down(1),
+                        KeyCode::PageUp => feed.up(10),
+                        KeyCode::PageDown => feed.down(10),
+                        KeyCode::Home => feed.up(usize::MAX),
+                        _ => return Ok(false),
+                    }
+                }
+                if let Some(manager) = &state.workspace_manager {
+                    panels.load_more_activity(manager);
+                }
+                return Ok(false);
+            }
+
+            // Handle plan modal
+            if state.input_mode.is_modal_open("plan") {
+                if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
+                    panels.plan_view = None;
+                    state.input_mode.to_normal();
+                }
+                return Ok(false);
+            }
+
+            // Handle review queue modal
+            if state.input_mode.is_modal_open("review_queue") {
+                if let Some(queue) = panels.review_queue.as_mut() {
+                    let action = match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            panels.review_queue = None;
+                            state.input_mode.to_normal();
                             return Ok(false);
                         }
-                        KeyCode::Enter => {
-                            panels.workspace_selector.folder_enter();
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            queue.up(1);
                             return Ok(false);
                         }
-                        KeyCode::Backspace => {
-                            panels.workspace_selector.folder_parent();
+                        KeyCode::Down | KeyCode::Char('j') => {
+                            queue.down(1);
                             return Ok(false);
                         }
+                        KeyCode::Char('a') => ReviewAction::Accept,
+                        KeyCode::Char('r') => ReviewAction::Reject,
+                        KeyCode::Char('o') | KeyCode::Enter => ReviewAction::Open,
                         _ => return Ok(false),
+                    };
+                    resolve_review_item(action, state, panels, pty_manager);
+                }
+                return Ok(false);
+            }
+
+            // Handle search panel modal
+            if state.input_mode.is_modal_open("search") {
+                let mut open = None;
+                if let Some(search) = panels.search.as_mut() {
+                    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                    match key.code {
+                        // The panel stays around so Ctrl+F returns to its results
+                        KeyCode::Esc => state.input_mode.to_normal(),
+                        KeyCode::Char('r') if ctrl => search.toggle_regex(),
+                        KeyCode::Up => search.up(1),
+                        KeyCode::Down => search.down(1),
+                        KeyCode::PageUp => search.up(10),
+                        KeyCode::PageDown => search.down(10),
+                        KeyCode::Backspace => search.backspace(),
+                        KeyCode::Enter if search.is_dirty() => search.run(),
+                        KeyCode::Enter => open = search.selected_location(),
+                        KeyCode::Char(c) if !ctrl => search.push_char(c),
+                        _ => {}
                     }
                 }
+                if let Some((path, line)) = open {
+                    panels.open_file_at(path, line as usize);
+                    state.input_mode.to_normal();
+                }
+                return Ok(false);
+            }
 
-                match key.code {
-                    KeyCode::Esc => {
-                        let action = panels.workspace_selector.escape();
-                        if action == WorkspaceSelectorAction::Cancel {
+            // Handle replace preview modal
+            if state.input_mode.is_modal_open("replace") {
+                if let Some(replace) = panels.replace.as_mut() {
+                    match key.code {
+                        KeyCode::Esc => {
+                            panels.replace = None;
                             state.input_mode.to_normal();
                         }
-                        return Ok(false);
-                    }
-                    KeyCode::Up => {
-                        panels.workspace_selector.up();
-                        return Ok(false);
+                        KeyCode::Up => replace.up(1),
+                        KeyCode::Down => replace.down(1),
+                        KeyCode::PageUp => replace.scroll_diff(false, 10),
+                        KeyCode::PageDown => replace.scroll_diff(true, 10),
+                        KeyCode::Char(' ') => replace.toggle(),
+                        KeyCode::Char('a') => replace.toggle_all(),
+                        KeyCode::Enter => apply_replace_panel(state, panels),
+                        _ => {}
                     }
-                    KeyCode::Down => {
-                        panels.workspace_selector.down();
+                }
+                return Ok(false);
+            }
+
+            // Handle trust prompt modal
+            if state.input_mode.is_modal_open("trust_prompt") {
+                let trusted = match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => Some(true),
+                    KeyCode::Char('n') | KeyCode::Char('N') => Some(false),
+                    KeyCode::Esc => None,
+                    _ => return Ok(false),
+                };
+                let prompt = panels.trust_prompt.take();
+                state.input_mode.to_normal();
+                match (trusted, prompt, &state.workspace_manager) {
+                    (Some(trusted), Some(prompt), Some(manager)) => {
+                        match manager.set_trusted(prompt.workspace_id, trusted) {
+                            Ok(()) => state.info(axiom_core::workspace::trust_status(Some(trusted))),
+                            Err(e) => state.error(format!("Failed to save trust: {}", e)),
+                        }
+                    }
+                    _ => state.info(axiom_core::workspace::trust_status(None)),
+                }
+                return Ok(false);
+            }
+
+            // Handle duplicate workspace prompt
+            if state.input_mode.is_modal_open("duplicate_prompt") {
+                handle_duplicate_prompt_key(key.code, state, panels);
+                return Ok(false);
+            }
+
+            // File tree name prompt or delete confirmation
+            if state.input_mode.is_modal_open("file_tree_prompt") {
+                panels.file_tree.handle_input(event, state)?;
+                return Ok(false);
+            }
+
+            // Handle directory picker modal (/init)
+            if state.input_mode.is_modal_open("directory_picker") {
+                if let Some(action) = panels.directory_picker.as_mut().map(|p| p.handle_key(*key)) {
+                    handle_directory_picker_action(action, state, panels);
+                }
+                return Ok(false);
+            }
+
+            // Handle workspace selector modal
+            if state.input_mode.is_modal_open("workspace_selector") {
+                let is_creating = panels.workspace_selector.mode == SelectorMode::CreateNew;
+                let is_browsing = panels.workspace_selector.mode == SelectorMode::BrowseFolders;
+                let is_listing = panels.workspace_selector.mode == SelectorMode::List;
+                let is_repairing = panels.workspace_selector.mode == SelectorMode::Repair;
+
+                // BrowseFolders mode: keys go to the folder picker
+                if is_browsing {
+                    let action = panels.workspace_selector.handle_picker_key(*key);
+                    handle_workspace_selector_action(action, state, panels);
+                    return Ok(false);
+                }
+
+                match key.code {
+                    KeyCode::Esc => {
+                        let action = panels.workspace_selector.escape();
+                        if action == WorkspaceSelectorAction::Cancel {
+                            state.input_mode.to_normal();
+                        }
+                        return Ok(false);
+                    }
+                    KeyCode::Up => {
+                        panels.workspace_selector.up();
+                        return Ok(false);
+                    }
+                    KeyCode::Down => {
+                        panels.workspace_selector.down();
                         return Ok(false);
                     }
                     KeyCode::Tab if is_creating => {
@@ -858,7 +1717,7 @@ fn handle_event(
                     }
                     KeyCode::Enter => {
                         let action = panels.workspace_selector.enter();
-                        handle_workspace_selector_action(action, state, panels, pty_manager);
+                        handle_workspace_selector_action(action, state, panels);
                         return Ok(false);
                     }
                     KeyCode::Delete => {
@@ -867,13 +1726,23 @@ fn handle_event(
                     }
                     KeyCode::Char('y') if panels.workspace_selector.mode == SelectorMode::ConfirmDelete => {
                         let action = panels.workspace_selector.confirm();
-                        handle_workspace_selector_action(action, state, panels, pty_manager);
+                        handle_workspace_selector_action(action, state, panels);
                         return Ok(false);
                     }
                     KeyCode::Char('n') if panels.workspace_selector.mode == SelectorMode::ConfirmDelete => {
                         panels.workspace_selector.deny();
                         return Ok(false);
                     }
+                    // Repair a broken workspace: relocate or archive it
+                    KeyCode::Char('r') if is_repairing => {
+                        panels.workspace_selector.relocate();
+                        return Ok(false);
+                    }
+                    KeyCode::Char('a') if is_repairing => {
+                        let action = panels.workspace_selector.archive();
+                        handle_workspace_selector_action(action, state, panels);
+                        return Ok(false);
+                    }
                     KeyCode::Char(c) if is_creating => {
                         panels.workspace_selector.insert_char(c);
                         return Ok(false);
@@ -882,6 +1751,15 @@ fn handle_event(
                         panels.workspace_selector.backspace();
                         return Ok(false);
                     }
+                    // Typing in the list filters workspaces
+                    KeyCode::Char(c) if is_listing && !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        panels.workspace_selector.search_push(c);
+                        return Ok(false);
+                    }
+                    KeyCode::Backspace if is_listing => {
+                        panels.workspace_selector.search_pop();
+                        return Ok(false);
+                    }
                     KeyCode::Left if is_creating => {
                         panels.workspace_selector.cursor_left();
                         return Ok(false);
@@ -894,83 +1772,79 @@ fn handle_event(
                 }
             }
 
-            // 'q' in Normal mode: Quit (vim-style, avoids Ctrl+Q terminal conflict)
-            if key.code == KeyCode::Char('q') && !state.input_mode.is_editing() && !state.input_mode.is_modal() {
-                state.quit();
-                return Ok(true);
-            }
-
-            // Ctrl+M: Open model selector
-            if key.code == KeyCode::Char('m') && key.modifiers.contains(KeyModifiers::CONTROL) {
-                panels.open_model_selector();
-                state.input_mode.open_modal("model_selector");
-                return Ok(false);
-            }
-
-            // Ctrl+,: Open settings modal
-            if key.code == KeyCode::Char(',') && key.modifiers.contains(KeyModifiers::CONTROL) {
-                panels.open_settings(config);
-                state.input_mode.open_modal("settings");
-                return Ok(false);
-            }
-
-            // Ctrl+T: Toggle theme
-            if key.code == KeyCode::Char('t') && key.modifiers.contains(KeyModifiers::CONTROL) {
-                toggle_theme();
-                state.info(format!("Theme: {}", current_variant().as_str()));
-                return Ok(false);
-            }
-
-            // Ctrl+W: Open workspace selector
-            if key.code == KeyCode::Char('w') && key.modifiers.contains(KeyModifiers::CONTROL) {
-                if let Some(manager) = &state.workspace_manager {
-                    let workspaces = manager.list_workspaces();
-                    panels.open_workspace_selector(workspaces, state.active_workspace_id);
-                    state.input_mode.open_modal("workspace_selector");
-                } else {
-                    state.error("Workspace manager not initialized");
+            // Global actions, bound in `[keys]`
+            let focused = state.focus.current();
+            let normal = !state.input_mode.is_editing() && !state.input_mode.is_modal();
+            let action = key_chord(key)
+                .and_then(|chord| state.keymap.action(focused.keymap_scope(), &chord));
+            match action {
+                // Quit in Normal mode (vim-style `q`, avoids Ctrl+Q terminal conflict)
+                Some(Action::Quit) if normal && panels.output.has_unsaved() => {
+                    state.warn("Unsaved changes in the editor: Ctrl+Alt+S saves them all");
+                    return Ok(false);
                 }
-                return Ok(false);
-            }
-
-            // Tab (without modifiers): Cycle focus
-            // Ctrl+Tab is reserved for panel-specific use (e.g., editor tabs)
-            if key.code == KeyCode::Tab
-                && key.modifiers.is_empty()
-                && !state.input_mode.is_editing()
-                && !state.input_mode.is_modal()
-            {
-                state.focus.next();
-                panels.handle_focus_change(state.focus.current(), screen_area);
-                return Ok(false);
-            }
-
-            // Backtab/Shift+Tab (without Ctrl): Cycle focus backwards
-            // Ctrl+Shift+Tab is reserved for panel-specific use (e.g., editor tabs)
-            if key.code == KeyCode::BackTab
-                && !key.modifiers.contains(KeyModifiers::CONTROL)
-                && !state.input_mode.is_editing()
-                && !state.input_mode.is_modal()
-            {
-                state.focus.prev();
-                panels.handle_focus_change(state.focus.current(), screen_area);
-                return Ok(false);
-            }
-
-            // Escape: Return to normal mode
-            if key.code == KeyCode::Esc {
-                state.input_mode.to_normal();
-                return Ok(false);
-            }
-
-            // 'i' in Normal mode: Enter insert mode (but not if Input panel is focused - let it type directly)
-            if key.code == KeyCode::Char('i')
-                && !state.input_mode.is_editing()
-                && !state.input_mode.is_modal()
-                && state.focus.current() != PanelId::INPUT
-            {
-                state.input_mode.to_insert();
-                return Ok(false);
+                Some(Action::Quit) if normal => {
+                    state.quit();
+                    return Ok(true);
+                }
+                Some(Action::ModelSelector) => {
+                    panels.open_model_selector();
+                    state.input_mode.open_modal("model_selector");
+                    return Ok(false);
+                }
+                Some(Action::Settings) => {
+                    panels.open_settings(config, state.workspace_defaults());
+                    state.input_mode.open_modal("settings");
+                    return Ok(false);
+                }
+                // (Ctrl+Shift+T is left to the editor for reopening tabs, and
+                // Ctrl+W for closing them while editing)
+                Some(Action::ToggleTheme) => {
+                    toggle_theme();
+                    state.info(format!("Theme: {}", current_variant().as_str()));
+                    return Ok(false);
+                }
+                Some(Action::WorkspaceSelector)
+                    if !(focused == PanelId::OUTPUT && panels.output.is_editing()) =>
+                {
+                    if let Some(manager) = &state.workspace_manager {
+                        panels.open_workspace_selector(manager, state.active_workspace_id);
+                        state.input_mode.open_modal("workspace_selector");
+                    } else {
+                        state.error("Workspace manager not initialized");
+                    }
+                    return Ok(false);
+                }
+                Some(Action::Search) => {
+                    open_search(state, panels, None, false, Vec::new());
+                    return Ok(false);
+                }
+                // Ctrl+Tab and Ctrl+Shift+Tab are left to panels (e.g., editor tabs)
+                Some(Action::FocusNext) if normal => {
+                    state.focus.next();
+                    panels.handle_focus_change(state.focus.current(), screen_area);
+                    return Ok(false);
+                }
+                Some(Action::FocusPrev) if normal => {
+                    state.focus.prev();
+                    panels.handle_focus_change(state.focus.current(), screen_area);
+                    return Ok(false);
+                }
+                // Copy mode, the editor's search prompt and the agent action
+                // menu take Esc to leave
+                Some(Action::NormalMode)
+                    if !((focused == PanelId::OUTPUT && panels.output.takes_esc())
+                        || (focused == PanelId::AGENTS && panels.agents.takes_esc())) =>
+                {
+                    state.input_mode.to_normal();
+                    return Ok(false);
+                }
+                // Not in the Input panel, which is typed into directly
+                Some(Action::InsertMode) if normal && focused != PanelId::INPUT => {
+                    state.input_mode.to_insert();
+                    return Ok(false);
+                }
+                _ => {}
             }
 
             // Auto-enter insert mode when Input panel is focused and user types
@@ -979,7 +1853,6 @@ fn handle_event(
             }
 
             // Forward to focused panel
-            let focused = state.focus.current();
             panels.get_mut(focused).handle_input(event, state)?;
         }
 
@@ -997,19 +1870,34 @@ fn handle_event(
                             // Handle click on settings items
                             match panels.settings.handle_click(x, y) {
                                 SettingsAction::Save => {
-                                    if let Some(new_config) = panels.apply_settings() {
+                                    let workspace_saved = save_workspace_defaults(state, panels);
+                                    if let Some(mut new_config) = panels.apply_settings() {
+                                        new_config.directory_picker = config.directory_picker.clone();
+                                        new_config.discovery = config.discovery.clone();
+                                        new_config.terminal = config.terminal.clone();
+                                        new_config.completion = config.completion.clone();
+                                        new_config.sounds = config.sounds.clone();
+                                        new_config.agents = config.agents.clone();
+                                        new_config.prompts = config.prompts.clone();
                                         let path = config_path(&state.cwd);
                                         if let Err(e) = save_config(&new_config, &path) {
                                             state.error(format!("Failed to save settings: {}", e));
                                         } else {
-                                            reload_providers(panels, &new_config);
                                             *config = new_config;
+                                            apply_workspace_defaults(&state.cwd, panels, config);
                                             state.info("Settings saved");
+                                            if panels.retry_rejected_prompt(state, conductor) {
+                                                state.info("Retrying with the new key");
+                                            }
                                         }
+                                    } else if workspace_saved {
+                                        apply_workspace_defaults(&state.cwd, panels, config);
+                                        state.info("Settings saved");
                                     }
                                     state.input_mode.to_normal();
                                 }
                                 SettingsAction::Cancel => {
+                                    state.auth_retry.cancel();
                                     state.input_mode.to_normal();
                                 }
                                 SettingsAction::StartEdit | SettingsAction::None => {
@@ -1018,6 +1906,7 @@ fn handle_event(
                             }
                         } else {
                             // Click outside modal - close it
+                            state.auth_retry.cancel();
                             state.input_mode.to_normal();
                         }
                     }
@@ -1070,6 +1959,151 @@ fn handle_event(
                 return Ok(false);
             }
 
+            // Handle activity feed modal mouse events
+            if state.input_mode.is_modal_open("activity_feed") {
+                if let Some(feed) = panels.activity_feed.as_mut() {
+                    match mouse.kind {
+                        event::MouseEventKind::Down(event::MouseButton::Left)
+                            if !feed.contains(x, y) =>
+                        {
+                            panels.activity_feed = None;
+                            state.input_mode.to_normal();
+                        }
+                        event::MouseEventKind::ScrollUp => feed.up(3),
+                        event::MouseEventKind::ScrollDown => {
+                            feed.down(3);
+                            if let Some(manager) = &state.workspace_manager {
+                                panels.load_more_activity(manager);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                return Ok(false);
+            }
+
+            // Handle search panel mouse events
+            if state.input_mode.is_modal_open("search") {
+                if let Some(search) = panels.search.as_mut() {
+                    match mouse.kind {
+                        event::MouseEventKind::Down(event::MouseButton::Left)
+                            if !search.contains(x, y) =>
+                        {
+                            state.input_mode.to_normal();
+                        }
+                        event::MouseEventKind::ScrollUp => search.up(3),
+                        event::MouseEventKind::ScrollDown => search.down(3),
+                        _ => {}
+                    }
+                }
+                return Ok(false);
+            }
+
+            // Handle replace preview mouse events
+            if state.input_mode.is_modal_open("replace") {
+                if let Some(replace) = panels.replace.as_mut() {
+                    match mouse.kind {
+                        event::MouseEventKind::Down(event::MouseButton::Left)
+                            if !replace.contains(x, y) =>
+                        {
+                            panels.replace = None;
+                            state.input_mode.to_normal();
+                        }
+                        event::MouseEventKind::ScrollUp => replace.scroll_diff(false, 3),
+                        event::MouseEventKind::ScrollDown => replace.scroll_diff(true, 3),
+                        _ => {}
+                    }
+                }
+                return Ok(false);
+            }
+
+            // Handle plan modal mouse events
+            if state.input_mode.is_modal_open("plan") {
+                let outside = panels
+                    .plan_view
+                    .as_ref()
+                    .is_none_or(|view| !view.contains(x, y));
+                if outside
+                    && matches!(
+                        mouse.kind,
+                        event::MouseEventKind::Down(event::MouseButton::Left)
+                    )
+                {
+                    panels.plan_view = None;
+                    state.input_mode.to_normal();
+                }
+                return Ok(false);
+            }
+
+            // Handle review queue modal mouse events
+            if state.input_mode.is_modal_open("review_queue") {
+                if let Some(queue) = panels.review_queue.as_mut() {
+                    match mouse.kind {
+                        event::MouseEventKind::Down(event::MouseButton::Left)
+                            if !queue.contains(x, y) =>
+                        {
+                            panels.review_queue = None;
+                            state.input_mode.to_normal();
+                        }
+                        event::MouseEventKind::ScrollUp => queue.up(1),
+                        event::MouseEventKind::ScrollDown => queue.down(1),
+                        _ => {}
+                    }
+                }
+                return Ok(false);
+            }
+
+            // Handle directory picker modal mouse events
+            if state.input_mode.is_modal_open("directory_picker") {
+                let Some(picker) = panels.directory_picker.as_mut() else {
+                    return Ok(false);
+                };
+                match mouse.kind {
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        // Check if click is inside modal
+                        if picker.contains(x, y) {
+                            let action = picker.handle_click(x, y);
+                            handle_directory_picker_action(action, state, panels);
+                        } else {
+                            // Click outside modal - close it
+                            handle_directory_picker_action(PickerAction::Cancelled, state, panels);
+                        }
+                    }
+                    event::MouseEventKind::ScrollUp if picker.contains(x, y) => picker.handle_scroll(false),
+                    event::MouseEventKind::ScrollDown if picker.contains(x, y) => picker.handle_scroll(true),
+                    event::MouseEventKind::Moved => picker.handle_hover(x, y),
+                    _ => {}
+                }
+                return Ok(false);
+            }
+
+            // Handle workspace selector modal mouse events
+            if state.input_mode.is_modal_open("workspace_selector") {
+                match mouse.kind {
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        // Check if click is inside modal
+                        if panels.workspace_selector.contains(x, y) {
+                            let action = panels.workspace_selector.handle_click(x, y);
+                            handle_workspace_selector_action(action, state, panels);
+                        } else {
+                            // Click outside modal - close it
+                            state.input_mode.to_normal();
+                        }
+                    }
+                    event::MouseEventKind::ScrollUp if panels.workspace_selector.contains(x, y) => {
+                        panels.workspace_selector.handle_scroll(false);
+                    }
+                    event::MouseEventKind::ScrollDown if panels.workspace_selector.contains(x, y) => {
+                        panels.workspace_selector.handle_scroll(true);
+                    }
+                    event::MouseEventKind::Moved => {
+                        panels.workspace_selector.handle_hover(x, y);
+                    }
+                    _ => {}
+                }
+                return Ok(false);
+            }
+
             // Handle click on model badge in status bar
             if let event::MouseEventKind::Down(event::MouseButton::Left) = mouse.kind {
                 if panels.is_model_badge_click(x, y) {
@@ -1099,6 +2133,18 @@ fn handle_event(
                 }
             }
 
+            // Right-click on an agent opens its action menu
+            if let event::MouseEventKind::Down(event::MouseButton::Right) = mouse.kind {
+                if layout.panel_at(x, y) == Some(PanelId::AGENTS) {
+                    if state.focus.current() != PanelId::AGENTS {
+                        state.focus.focus(PanelId::AGENTS);
+                        state.input_mode.to_normal();
+                        panels.handle_focus_change(PanelId::AGENTS, screen_area);
+                    }
+                    panels.agents.handle_input(event, state)?;
+                }
+            }
+
             // Handle mouse scroll in panels
             match mouse.kind {
                 event::MouseEventKind::ScrollUp => {
@@ -1138,30 +2184,102 @@ fn handle_event(
             // TODO: Route to conductor when implemented
         }
 
-        // New agent events
-        Event::ConductorRequest(ref text) => {
-            conductor.process(text.clone());
-            // Switch to agent output view
-            let registry = panels.agent_registry.read();
-            if let Some(agent_id) = registry.selected_id() {
-                drop(registry);
-                panels.set_output_context(OutputContext::Agent { agent_id });
+        // A rejected key: ask for a new one, and send the prompt again once
+        // it's saved
+        Event::ProviderAuthFailed {
+            ref provider_id,
+            ref message,
+        } => {
+            // A key with a command is fetched again instead of asked for
+            let refreshable = key_commands_allowed(&state.cwd, state.is_trusted())
+                && config
+                    .get_provider(provider_id)
+                    .is_some_and(|provider| provider.api_key_command.is_some());
+            if refreshable {
+                state.key_watch.force(provider_id);
+                state.auth_retry.rejected(provider_id);
+                state.warn(format!(
+                    "{} rejected its API key; fetching a new one",
+                    provider_id
+                ));
+                return Ok(false);
+            }
+            if panels.ask_for_key(state, config, provider_id) {
+                state.cue(SoundEvent::Error);
+                state.warn(format!(
+                    "{} rejected its API key; enter a new one and save to retry",
+                    provider_id
+                ));
+            } else {
+                state.error(format!("{} rejected its API key: {}", provider_id, message));
+            }
+        }
 
-                // Focus Input panel for typing more prompts to Conductor
-                state.focus.focus(PanelId::INPUT);
-                panels.handle_focus_change(PanelId::INPUT, screen_area);
+        Event::ProviderKeyFetched {
+            ref provider_id,
+            ref result,
+        } => {
+            let report = state.key_watch.finished(provider_id, result.is_ok());
+            let retry = state.auth_retry.key_updated(provider_id);
+            match result {
+                Ok(fresh) => {
+                    if let Some(provider) = config.llm.providers.get_mut(provider_id) {
+                        provider.api_key = Some(fresh.key.clone());
+                        provider.api_key_expires_at = fresh.expires_at;
+                    }
+                    apply_workspace_defaults(&state.cwd, panels, config);
+                    if retry && conductor.retry() {
+                        state.info(format!("Retrying with {}'s new key", provider_id));
+                    } else if let Some(expires_at) = fresh.expires_at {
+                        state.info(format!(
+                            "Refreshed {}'s API key; it expires {}",
+                            provider_id,
+                            describe_expiry(expires_at, unix_now())
+                        ));
+                    }
+                }
+                Err(e) if report || retry => {
+                    state.error(format!("Couldn't refresh {}'s API key: {}", provider_id, e));
+                }
+                Err(_) => {}
             }
         }
 
+        // New agent events
+        Event::ConductorRequest(ref text) => {
+            prompt_conductor(text, state, panels, conductor, screen_area);
+        }
+
         Event::AgentSpawn(ref request) => {
             // Spawn the agent in registry
             let agent_id = {
                 let mut registry = panels.agent_registry.write();
                 registry.spawn(request.clone())
             };
+            state.time.agent_started(agent_id, Instant::now());
+            if request.parent_id.is_some() && request.parent_id == conductor.agent_id() {
+                if let Some(plan) = state.plan.as_mut() {
+                    plan.add_agent(axiom_core::AgentId(agent_id.value()));
+                    save_plan(state);
+                }
+            }
 
-            // Execute non-conductor agents
-            if request.agent_type != axiom::agents::AgentType::Conductor {
+            // Execute non-conductor agents; restricted workspaces don't run
+            // commands an agent decided on by itself
+            if request.agent_type.runs_commands()
+                && request.parent_id.is_some()
+                && !state.is_trusted()
+            {
+                panels
+                    .agent_registry
+                    .write()
+                    .error(agent_id, "Workspace is not trusted".to_string());
+                state.time.agent_stopped(agent_id, Instant::now());
+                state.warn(format!(
+                    "Blocked `{}` in untrusted workspace (/trust on to allow)",
+                    truncate_cmd(request.parameters.as_deref().unwrap_or(""), 50)
+                ));
+            } else if request.agent_type != axiom::agents::AgentType::Conductor {
                 executor.execute(agent_id, request);
             } else {
                 // Store the persistent conductor agent ID
@@ -1170,6 +2288,21 @@ fn handle_event(
                 conductor.execute(agent_id, request.parameters.as_deref().unwrap_or(""));
             }
 
+            let actor = match request.parent_id {
+                Some(parent) => panels
+                    .agent_registry
+                    .read()
+                    .get(parent)
+                    .map(|a| a.name.clone())
+                    .unwrap_or_else(|| "agent".to_string()),
+                None => "user".to_string(),
+            };
+            state.record_activity(ActivityEvent::new(
+                ActivityKind::AgentRun,
+                actor,
+                format!("Started {}: {}", request.name, request.description),
+            ));
+
             // Only switch context for top-level agents (no parent)
             // Child agents are shown in the Conductor's aggregated view
             if request.parent_id.is_none() {
@@ -1178,9 +2311,25 @@ fn handle_event(
         }
 
         Event::AgentUpdate { id, ref status } => {
-            let mut registry = panels.agent_registry.write();
-            if let Some(agent) = registry.get_mut(*id) {
-                agent.status = status.clone();
+            {
+                let mut registry = panels.agent_registry.write();
+                if let Some(agent) = registry.get_mut(*id) {
+                    agent.status = status.clone();
+                }
+            }
+            if status.is_terminal() || *status == axiom::agents::AgentStatus::Idle {
+                record_agent_run(state, panels, *id);
+                state.watch.agent_done(*id);
+                verify_run(state, panels, conductor, executor);
+                offer_follow_ups(state, panels, conductor, config.suggestions.enabled);
+                advance_plan(state, panels, conductor, screen_area);
+            }
+            match status {
+                axiom::agents::AgentStatus::Completed | axiom::agents::AgentStatus::Idle => {
+                    state.cue(SoundEvent::AgentDone)
+                }
+                axiom::agents::AgentStatus::Error(_) => state.cue(SoundEvent::Error),
+                _ => {}
             }
         }
 
@@ -1190,21 +2339,40 @@ fn handle_event(
         }
 
         Event::AgentComplete { id } => {
-            let mut registry = panels.agent_registry.write();
-            registry.complete(*id);
+            {
+                let mut registry = panels.agent_registry.write();
+                registry.complete(*id);
+                if let Some(agent) = registry.get(*id) {
+                    state.record_activity(ActivityEvent::new(
+                        ActivityKind::AgentRun,
+                        agent.name.clone(),
+                        format!("Finished: {}", agent.description),
+                    ));
+                }
+            }
+            record_agent_run(state, panels, *id);
+            state.watch.agent_done(*id);
+            state.cue(SoundEvent::AgentDone);
+            verify_run(state, panels, conductor, executor);
+            offer_follow_ups(state, panels, conductor, config.suggestions.enabled);
+            advance_plan(state, panels, conductor, screen_area);
         }
 
-        Event::ConductorResponse(ref response) => {
+        Event::ConductorResponse { ref response, .. } => {
+            record_conductor_usage(state, panels, &conductor.history(), response);
             // Add assistant response to conductor history for LLM context
             conductor.add_response(response.clone());
+            if let Some(goal) = state.planning.take() {
+                receive_plan(goal, response, state);
+            }
         }
 
         Event::AgentWake(id) => {
             // Wake an idle agent (used for persistent Conductor)
             let mut registry = panels.agent_registry.write();
 
-            // Remove old child agents from previous interaction
-            registry.remove_children(*id);
+            // Start a new interaction, dropping the agents of old ones
+            registry.start_run(*id, config.agents.keep_runs);
 
             if let Some(agent) = registry.get_mut(*id) {
                 // Keep output history - displays as Q&A pairs (ChatGPT style)
@@ -1212,9 +2380,61 @@ fn handle_event(
                 agent.status = axiom::agents::AgentStatus::Running;
             }
             drop(registry);
+            state.time.agent_started(*id, Instant::now());
             panels.set_output_context(OutputContext::Agent { agent_id: *id });
         }
 
+        Event::AgentStreamStart {
+            id,
+            ref provider,
+            ref model,
+        } => {
+            let mut registry = panels.agent_registry.write();
+            registry.start_stream(*id, provider, model);
+        }
+
+        Event::LlmExchange { id, ref exchange } => {
+            let mut registry = panels.agent_registry.write();
+            registry.record_exchange(*id, (**exchange).clone());
+        }
+
+        Event::AgentCancel(target) => {
+            let cancelled = cancel_agents(*target, panels, conductor, pty_manager);
+            for id in &cancelled {
+                state.time.agent_stopped(*id, Instant::now());
+            }
+            match cancelled.as_slice() {
+                [] => state.info("Nothing running to cancel"),
+                [id] => state.info(format!("Cancelled {}", id)),
+                ids => state.info(format!("Cancelled {} agents", ids.len())),
+            }
+            let stops_plan = cancelled.iter().any(|id| {
+                conductor.agent_id() == Some(*id)
+                    || state
+                        .plan
+                        .as_ref()
+                        .is_some_and(|plan| plan.is_running_agent(axiom_core::AgentId(id.value())))
+            });
+            if stops_plan && cancel_plan(state) {
+                state.warn("Plan stopped: its agents were cancelled");
+            }
+        }
+
+        Event::AgentAction { id, action } => {
+            if let Some(next) = run_agent_action(*id, *action, state, panels, conductor) {
+                return handle_event(
+                    &next,
+                    state,
+                    panels,
+                    screen_area,
+                    config,
+                    conductor,
+                    executor,
+                    pty_manager,
+                );
+            }
+        }
+
         Event::SwitchContext(ref context) => {
             panels.set_output_context(context.clone());
 
@@ -1265,11 +2485,24 @@ fn handle_event(
             };
             executor.execute(agent_id, &request);
             panels.set_output_context(OutputContext::Agent { agent_id });
+            state.time.agent_started(agent_id, Instant::now());
+            state.record_activity(ActivityEvent::new(
+                ActivityKind::AgentRun,
+                "user",
+                format!("Ran `{}`", truncate_cmd(cmd, 80)),
+            ));
         }
 
         // ===== CLI Agent Events =====
 
         Event::CliAgentInvoke { ref agent_id, ref prompt } => {
+            if !state.is_trusted() {
+                state.warn(format!(
+                    "CLI agents are disabled in untrusted workspaces (/trust on to allow @{})",
+                    agent_id
+                ));
+                return Ok(false);
+            }
             // Get CLI agent config
             if let Some(cli_config) = config.cli_agents.get(agent_id) {
                 // Spawn agent in registry
@@ -1300,6 +2533,7 @@ fn handle_event(
                     let mut registry = panels.agent_registry.write();
                     registry.start(runtime_id);
                     drop(registry);
+                    state.time.agent_started(runtime_id, Instant::now());
                     panels.set_output_context(OutputContext::Agent { agent_id: runtime_id });
 
                     // Focus Output panel for PTY interaction
@@ -1307,6 +2541,11 @@ fn handle_event(
                     panels.handle_focus_change(PanelId::OUTPUT, screen_area);
 
                     state.info(format!("Started {} agent", cli_config.name));
+                    state.record_activity(ActivityEvent::new(
+                        ActivityKind::AgentRun,
+                        "user",
+                        format!("Started {}: {}", cli_config.name, truncate_cmd(prompt, 80)),
+                    ));
                 }
             } else {
                 state.error(format!("Unknown CLI agent: {}", agent_id));
@@ -1316,22 +2555,63 @@ fn handle_event(
         Event::CliAgentOutput { id, ref data } => {
             // Output is already processed by the PTY parser - no need to store separately
             // The OutputPanel will render directly from the PTY manager's screen
-            // But we still track line count for status
+            // But we still track line count for status, and keep its transcript
+            let events = {
+                let mut manager = pty_manager.write();
+                manager.capture(*id, data);
+                manager.parse_output(*id, data)
+            };
             let mut registry = panels.agent_registry.write();
             if let Some(agent) = registry.get_mut(*id) {
                 agent.line_count += data.iter().filter(|&&b| b == b'\n').count();
+                record_agent_events(state, &agent.name, &events);
             }
         }
 
         Event::CliAgentExit { id, exit_code } => {
             // Mark agent as complete
-            pty_manager.write().mark_exited(*id);
+            let (events, transcript) = {
+                let mut manager = pty_manager.write();
+                manager.mark_exited(*id);
+                (manager.finish_output(*id), manager.transcript(*id).cloned())
+            };
+            if let Some(agent) = panels.agent_registry.read().get(*id) {
+                record_agent_events(state, &agent.name, &events);
+                // The Conductor reads what it did with the next prompt
+                if let Some(context) = transcript
+                    .and_then(|transcript| transcript_context(&agent.name, *exit_code, &transcript))
+                {
+                    conductor.add_context(context);
+                }
+            }
             let mut registry = panels.agent_registry.write();
             if *exit_code == 0 {
                 registry.complete(*id);
             } else {
                 registry.error(*id, format!("Exited with code {}", exit_code));
             }
+            if let Some(agent) = registry.get(*id) {
+                state.record_activity(ActivityEvent::new(
+                    ActivityKind::AgentRun,
+                    agent.name.clone(),
+                    format!("Exited with code {}", exit_code),
+                ));
+            }
+            drop(registry);
+            record_agent_run(state, panels, *id);
+        }
+
+        Event::CliAgentNotReady { id, ref error } => {
+            // Leave it running for the user to take over
+            let running = pty_manager.read().get_active(*id).is_some_and(|agent| !agent.exited);
+            let mut registry = panels.agent_registry.write();
+            if let Some(agent) = registry.get_mut(*id).filter(|_| running) {
+                agent.error(error.clone());
+                state.error(format!(
+                    "{} never became ready, prompt not sent: {}",
+                    agent.name, error
+                ));
+            }
         }
 
         Event::CliAgentInput { id, ref data } => {
@@ -1341,10 +2621,9 @@ fn handle_event(
             }
         }
 
-        // File modification from LLM - for now just log (will be handled by coder agent)
+        // File modification from the LLM: a patch against the file as it is
+        // now, or its whole new content
         Event::FileModification { ref path, ref content } => {
-            // TODO: Route to coder agent when implemented
-            // For now, write the file directly
             let file_path = std::path::PathBuf::from(path);
             let resolved_path = if file_path.is_absolute() {
                 file_path
@@ -1352,17 +2631,62 @@ fn handle_event(
                 state.cwd.join(&file_path)
             };
 
-            if let Err(e) = std::fs::write(&resolved_path, content) {
+            let current = std::fs::read_to_string(&resolved_path).unwrap_or_default();
+            let content = match apply_llm_change(&current, content) {
+                Ok(content) => content,
+                Err(failures) => {
+                    let report = failure_report(&failures);
+                    let summary = report.lines().next().unwrap_or_default();
+                    state.error(format!("{}: {}", path, summary));
+                    return Ok(false);
+                }
+            };
+            let op = AgentOperation::Write {
+                path: resolved_path.clone(),
+                content: content.clone(),
+            };
+            if state.write_held("conductor", &op) {
+                return Ok(false);
+            }
+
+            if let Err(e) = write_atomic(&resolved_path, content.as_bytes()) {
                 state.error(format!("Failed to write {}: {}", path, e));
             } else {
                 state.info(format!("Modified: {}", path));
+                if let Some(run) = &mut state.run {
+                    let relative = resolved_path.strip_prefix(&state.cwd).unwrap_or(&resolved_path);
+                    run.file_written(relative);
+                }
+                state.record_activity(
+                    ActivityEvent::new(ActivityKind::FileChange, "conductor", "Wrote file")
+                        .with_path(path),
+                );
                 // Switch to show the modified file
                 panels.set_output_context(OutputContext::File { path: resolved_path });
             }
         }
 
+        // Agent write to a protected path, waiting for /approve
+        Event::WriteHeld(ref pending) => {
+            state.hold_write(pending.clone());
+        }
+
         // File changed on disk (detected by file watcher)
         Event::FileChanged(ref path) => {
+            // The file tree already reported its own changes
+            if state.is_tree_change(path) {
+                return Ok(false);
+            }
+            // Edited config files apply without a restart
+            if is_config_file(path) && path.starts_with(&state.cwd) {
+                reload_config(state, panels, config, conductor, pty_manager, path);
+                return Ok(false);
+            }
+            let root = state.cwd.clone();
+            state.watch.file_changed(&root, path, Instant::now());
+            // An open tab reloads, or asks before its edits overwrite the change
+            panels.output.editor_event(event, state)?;
+
             // If currently viewing this file, refresh the view
             if let OutputContext::File { path: current_path } = panels.output_context() {
                 if current_path == path {
@@ -1373,33 +2697,63 @@ fn handle_event(
             }
         }
 
+        Event::Diagnostics {
+            ref path,
+            ref diagnostics,
+        } => {
+            panels.output.set_diagnostics(path.clone(), diagnostics.clone());
+        }
+
+        // File tree changes: let watch rules see them and keep the view on
+        // the file
+        Event::PathCreated(ref path) => {
+            let root = state.cwd.clone();
+            state.watch.file_changed(&root, path, Instant::now());
+            if path.is_file() {
+                panels.set_output_context(OutputContext::File { path: path.clone() });
+            }
+        }
+
+        Event::PathMoved { ref from, ref to } => {
+            let root = state.cwd.clone();
+            state.watch.file_changed(&root, to, Instant::now());
+            panels.path_moved(from, to);
+            if let OutputContext::File { path } = panels.output_context() {
+                if let Some(moved) = moved_path(path, from, to) {
+                    panels.set_output_context(OutputContext::File { path: moved });
+                }
+            }
+        }
+
+        Event::PathDeleted(ref path) => {
+            panels.path_deleted(path);
+            if let OutputContext::File { path: current } = panels.output_context() {
+                if current.starts_with(path) {
+                    panels.set_output_context(OutputContext::Empty);
+                }
+            }
+        }
+
         // Resize is handled in main loop
         Event::Resize(_, _) => {}
 
         // Tick - could be used for animations
         Event::Tick => {}
 
+        Event::GhostCompletion { .. } => {
+            panels.output.editor_event(event, state)?;
+        }
+
         // ===== Workspace Events =====
 
         Event::WorkspaceSwitch(id) => {
             // Attempt to switch workspace
             match state.switch_workspace(*id) {
                 Ok(new_path) => {
-                    // Cancel all running agents before switching
-                    cancel_all_agents(panels, pty_manager);
-
-                    // Update panels
+                    // Agents of the workspace left keep running in the
+                    // background; update panels
                     panels.handle_workspace_switch(&new_path);
 
-                    // Reload workspace-specific config if available
-                    if let Some(manager) = &state.workspace_manager {
-                        if let Ok(ws_config) = manager.get_workspace_config(*id) {
-                            // Merge workspace CLI agents with global config
-                            // For now, we just use global config
-                            let _ = ws_config;
-                        }
-                    }
-
                     state.info(format!("Switched to: {}", state.workspace_name()));
                 }
                 Err(e) => {
@@ -1409,6 +2763,9 @@ fn handle_event(
         }
 
         Event::WorkspaceCreate { ref name, ref path } => {
+            if offer_existing_workspace(path, state, panels) {
+                return Ok(false);
+            }
             if let Some(manager) = &state.workspace_manager {
                 match manager.create_workspace(name, path.clone()) {
                     Ok(workspace) => {
@@ -1427,20 +2784,18 @@ fn handle_event(
         }
 
         Event::WorkspaceDelete(id) => {
+            // Deleting archives: the workspace's data stays until purged
             if let Some(manager) = &state.workspace_manager {
-                match manager.delete_workspace(*id) {
-                    Ok(Some(deleted)) => {
-                        state.info(format!("Deleted workspace: {}", deleted.name));
-                        // If we deleted the active workspace, clear the active ID
+                match manager.set_workspace_archived(*id, true) {
+                    Ok(archived) => {
+                        state.info(format!("Archived workspace: {}", archived.name));
+                        // If we archived the active workspace, clear the active ID
                         if state.active_workspace_id == Some(*id) {
                             state.active_workspace_id = None;
                         }
                     }
-                    Ok(None) => {
-                        state.error("Workspace not found");
-                    }
                     Err(e) => {
-                        state.error(format!("Failed to delete workspace: {}", e));
+                        state.error(format!("Failed to archive workspace: {}", e));
                     }
                 }
             } else {
@@ -1456,7 +2811,56 @@ fn handle_event(
         // ===== Slash Command Events =====
 
         Event::SlashCommand(ref cmd) => {
+            if matches!(cmd, SlashCommand::Stats(_)) {
+                // Include the time since the last flush
+                state.flush_user_time();
+            }
+            match cmd {
+                SlashCommand::GenTests { target, attempts } => {
+                    start_gen_tests(target, *attempts, state, panels, executor);
+                    return Ok(false);
+                }
+                SlashCommand::GenDocs { path, check } => {
+                    start_gen_docs(path, *check, state, panels, executor);
+                    return Ok(false);
+                }
+                SlashCommand::Fix { problem, attempts } => {
+                    start_fix(*problem, *attempts, state, panels, executor);
+                    return Ok(false);
+                }
+                SlashCommand::RunDoc { path } => {
+                    write_run_document(path.as_deref(), state, panels, conductor);
+                    return Ok(false);
+                }
+                SlashCommand::Session(sub) => {
+                    session_command(sub, state, panels, conductor);
+                    return Ok(false);
+                }
+                SlashCommand::Inspect(sub) => {
+                    inspect_command(sub, state, panels, conductor);
+                    return Ok(false);
+                }
+                SlashCommand::Plan(sub) => {
+                    plan_command(sub, state, panels, conductor, screen_area);
+                    return Ok(false);
+                }
+                SlashCommand::Model(ModelSubcommand::Assign {
+                    role,
+                    provider,
+                    model,
+                }) => {
+                    assign_role_model(*role, provider, model, state, panels, config);
+                    return Ok(false);
+                }
+                _ => {}
+            }
             let result = execute_slash_command(cmd, state, panels, config, pty_manager);
+            if matches!(cmd, SlashCommand::Watch(_) | SlashCommand::Features(_)) {
+                let manager = state.workspace_manager.clone();
+                state
+                    .watch
+                    .reload(manager.as_deref(), state.active_workspace_id);
+            }
             if handle_slash_result(result, state, panels, config, screen_area, pty_manager)? {
                 return Ok(true); // Exit requested
             }
@@ -1474,14 +2878,12 @@ fn handle_workspace_selector_action(
     action: WorkspaceSelectorAction,
     state: &mut AppState,
     panels: &mut PanelRegistry,
-    pty_manager: &Arc<parking_lot::RwLock<PtyAgentManager>>,
 ) {
     match action {
         WorkspaceSelectorAction::Select(id) => {
             // Switch to selected workspace
             match state.switch_workspace(id) {
                 Ok(new_path) => {
-                    cancel_all_agents(panels, pty_manager);
                     panels.handle_workspace_switch(&new_path);
                     state.info(format!("Switched to: {}", state.workspace_name()));
                 }
@@ -1492,13 +2894,15 @@ fn handle_workspace_selector_action(
             state.input_mode.to_normal();
         }
         WorkspaceSelectorAction::Create { name, path } => {
+            if offer_existing_workspace(&path, state, panels) {
+                return;
+            }
             if let Some(manager) = &state.workspace_manager {
                 match manager.create_workspace(&name, path.clone()) {
                     Ok(workspace) => {
                         state.info(format!("Created: {}", workspace.name));
                         // Switch to the new workspace
                         if let Ok(_) = state.switch_workspace(workspace.id) {
-                            cancel_all_agents(panels, pty_manager);
                             panels.handle_workspace_switch(&workspace.path);
                         }
                     }
@@ -1509,33 +2913,19 @@ fn handle_workspace_selector_action(
             }
             state.input_mode.to_normal();
         }
-        WorkspaceSelectorAction::Delete(id) => {
-            // Use a separate scope for the manager borrow
-            let delete_result = state.workspace_manager.as_ref()
-                .map(|manager| manager.delete_workspace(id));
-
-            match delete_result {
-                Some(Ok(Some(deleted))) => {
-                    let deleted_name = deleted.name.clone();
-                    state.info(format!("Deleted: {}", deleted_name));
-                    if state.active_workspace_id == Some(id) {
-                        state.active_workspace_id = None;
-                    }
-                    // Refresh the workspace list in the selector
-                    if let Some(manager) = &state.workspace_manager {
-                        let workspaces = manager.list_workspaces();
-                        panels.open_workspace_selector(workspaces, state.active_workspace_id);
-                    }
-                }
-                Some(Ok(None)) => {
-                    state.error("Workspace not found");
-                }
-                Some(Err(e)) => {
-                    state.error(format!("Failed to delete: {}", e));
-                }
-                None => {
-                    state.error("Workspace manager not initialized");
-                }
+        WorkspaceSelectorAction::Relocate { id, path } => {
+            match repair_workspace(state, panels, id, Some(path)) {
+                Ok(()) => handle_workspace_selector_action(
+                    WorkspaceSelectorAction::Select(id),
+                    state,
+                    panels,
+                ),
+                Err(e) => state.error(format!("Failed to relocate: {}", e)),
+            }
+        }
+        WorkspaceSelectorAction::Archive(id) => {
+            if let Err(e) = repair_workspace(state, panels, id, None) {
+                state.error(format!("Failed to archive: {}", e));
             }
         }
         WorkspaceSelectorAction::Cancel => {
@@ -1547,20 +2937,132 @@ fn handle_workspace_selector_action(
     }
 }
 
+/// Relocate a broken workspace to `path`, or archive it when `path` is None,
+/// then refresh the selector list
+fn repair_workspace(
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    id: axiom_core::WorkspaceId,
+    path: Option<PathBuf>,
+) -> axiom_core::Result<()> {
+    let manager = state.workspace_manager.as_ref().ok_or_else(|| {
+        axiom_core::AxiomError::Config("Workspace manager not initialized".into())
+    })?;
+    let workspace = match path {
+        Some(path) => manager.relocate_workspace(id, path)?,
+        None => manager.set_workspace_archived(id, true)?,
+    };
+    let active = state
+        .active_workspace_id
+        .filter(|&active| !(workspace.archived && active == id));
+    panels.open_workspace_selector(manager, active);
+    state.active_workspace_id = active;
+    if workspace.archived {
+        state.info(format!("Archived: {}", workspace.name));
+    }
+    Ok(())
+}
+
+/// Handle directory picker (/init) action: create and open a workspace at the chosen folder
+fn handle_directory_picker_action(
+    action: PickerAction,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+) {
+    match action {
+        PickerAction::None => {}
+        PickerAction::Selected(path) => {
+            panels.directory_picker = None;
+            let name = workspace_name_for(&path);
+            handle_workspace_selector_action(
+                WorkspaceSelectorAction::Create { name, path },
+                state,
+                panels,
+            );
+        }
+        PickerAction::Cancelled => {
+            panels.directory_picker = None;
+            state.input_mode.to_normal();
+        }
+    }
+}
+
+/// Offer to open the workspace already registered for `path`, if there is one
+///
+/// Returns whether the prompt was opened (and creating should stop).
+fn offer_existing_workspace(
+    path: &std::path::Path,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+) -> bool {
+    let existing = state
+        .workspace_manager
+        .as_ref()
+        .and_then(|manager| manager.find_by_path(path));
+    match existing {
+        Some(workspace) => {
+            panels.duplicate_prompt = Some(DuplicatePrompt::new(
+                workspace.id,
+                workspace.name,
+                workspace.path,
+            ));
+            state.input_mode.open_modal("duplicate_prompt");
+            true
+        }
+        None => false,
+    }
+}
+
+/// Enter opens the existing workspace, Esc returns to where the user was
+fn handle_duplicate_prompt_key(
+    code: KeyCode,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+) {
+    let open = match code {
+        KeyCode::Enter | KeyCode::Char('o') | KeyCode::Char('y') => true,
+        KeyCode::Esc | KeyCode::Char('n') => false,
+        _ => return,
+    };
+    let Some(prompt) = panels.duplicate_prompt.take() else {
+        return;
+    };
+    state.input_mode.to_normal();
+    if !open {
+        return;
+    }
+    if state.active_workspace_id == Some(prompt.workspace_id) {
+        state.info(format!("Already in: {}", state.workspace_name()));
+        return;
+    }
+    match state.switch_workspace(prompt.workspace_id) {
+        Ok(new_path) => {
+            panels.handle_workspace_switch(&new_path);
+            state.info(format!("Opened: {}", state.workspace_name()));
+        }
+        Err(e) => state.error(format!("Failed to open: {}", e)),
+    }
+}
+
+/// Default workspace name for a directory (its folder name)
+fn workspace_name_for(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
 /// Handle workspace selector action during initial workspace selection phase
 /// (before any workspace is active - not a modal, the main screen)
 fn handle_workspace_selector_action_initial(
     action: WorkspaceSelectorAction,
     state: &mut AppState,
     panels: &mut PanelRegistry,
-    pty_manager: &Arc<parking_lot::RwLock<PtyAgentManager>>,
 ) {
     match action {
         WorkspaceSelectorAction::Select(id) => {
             // Switch to selected workspace
             match state.switch_workspace(id) {
                 Ok(new_path) => {
-                    cancel_all_agents(panels, pty_manager);
                     panels.handle_workspace_switch(&new_path);
                     state.info(format!("Opened: {}", state.workspace_name()));
                 }
@@ -1571,13 +3073,15 @@ fn handle_workspace_selector_action_initial(
             // Don't close modal - workspace is now active, render.rs will show main UI
         }
         WorkspaceSelectorAction::Create { name, path } => {
+            if offer_existing_workspace(&path, state, panels) {
+                return;
+            }
             if let Some(manager) = &state.workspace_manager {
                 match manager.create_workspace(&name, path.clone()) {
                     Ok(workspace) => {
                         state.info(format!("Created: {}", workspace.name));
                         // Switch to the new workspace
                         if let Ok(_) = state.switch_workspace(workspace.id) {
-                            cancel_all_agents(panels, pty_manager);
                             panels.handle_workspace_switch(&workspace.path);
                         }
                     }
@@ -1588,30 +3092,19 @@ fn handle_workspace_selector_action_initial(
             }
             // Don't close modal - workspace is now active, render.rs will show main UI
         }
-        WorkspaceSelectorAction::Delete(id) => {
-            // Use a separate scope for the manager borrow
-            let delete_result = state.workspace_manager.as_ref()
-                .map(|manager| manager.delete_workspace(id));
-
-            match delete_result {
-                Some(Ok(Some(deleted))) => {
-                    let deleted_name = deleted.name.clone();
-                    state.info(format!("Deleted: {}", deleted_name));
-                    // Refresh the workspace list in the selector
-                    if let Some(manager) = &state.workspace_manager {
-                        let workspaces = manager.list_workspaces();
-                        panels.open_workspace_selector(workspaces, state.active_workspace_id);
-                    }
-                }
-                Some(Ok(None)) => {
-                    state.error("Workspace not found");
-                }
-                Some(Err(e)) => {
-                    state.error(format!("Failed to delete: {}", e));
-                }
-                None => {
-                    state.error("Workspace manager not initialized");
-                }
+        WorkspaceSelectorAction::Relocate { id, path } => {
+            match repair_workspace(state, panels, id, Some(path)) {
+                Ok(()) => handle_workspace_selector_action_initial(
+                    WorkspaceSelectorAction::Select(id),
+                    state,
+                    panels,
+                ),
+                Err(e) => state.error(format!("Failed to relocate: {}", e)),
+            }
+        }
+        WorkspaceSelectorAction::Archive(id) => {
+            if let Err(e) = repair_workspace(state, panels, id, None) {
+                state.error(format!("Failed to archive: {}", e));
             }
         }
         WorkspaceSelectorAction::Cancel => {
@@ -1625,38 +3118,238 @@ fn handle_workspace_selector_action_initial(
 }
 
 /// Cancel all running agents when switching workspaces
-fn cancel_all_agents(
+/// Accept, reject or open the selected review queue item, then reload the queue
+fn resolve_review_item(
+    action: ReviewAction,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    pty_manager: &Arc<parking_lot::RwLock<PtyAgentManager>>,
+) {
+    let Some(queue) = panels.review_queue.as_ref() else {
+        return;
+    };
+    let workspace_id = queue.workspace_id;
+    let Some(item) = queue.selected_item().cloned() else {
+        return;
+    };
+    let Some(manager) = state.workspace_manager.clone() else {
+        return;
+    };
+
+    if action == ReviewAction::Open {
+        let context = match item.kind {
+            ReviewKind::StalledAgent => {
+                review_agent_id(&item).map(|agent_id| OutputContext::Agent { agent_id })
+            }
+            _ => item.path.as_ref().map(|path| OutputContext::File {
+                path: state.cwd.join(path),
+            }),
+        };
+        match context {
+            Some(context) => {
+                panels.set_output_context(context);
+                panels.review_queue = None;
+                state.input_mode.to_normal();
+            }
+            None => state.error("Nothing to open for this item"),
+        }
+        return;
+    }
+
+    let accept = action == ReviewAction::Accept;
+    let result = match item.kind {
+        ReviewKind::Approval => manager
+            .resolve_write(workspace_id, Some(&item.id), accept)
+            .map(|write| {
+                format!(
+                    "{} write to {}",
+                    if accept { "Applied" } else { "Discarded" },
+                    write.path.display()
+                )
+            }),
+        ReviewKind::Conflict => match item.id.parse() {
+            Ok(task_id) if accept => {
+                manager
+                    .merge_task_workspace(task_id)
+                    .map(|outcome| match outcome {
+                        MergeOutcome::Conflict { files, .. } => {
+                            format!("Still conflicting: {}", files.join(", "))
+                        }
+                        _ => "Task merged".to_string(),
+                    })
+            }
+            Ok(task_id) => manager
+                .discard_task_workspace(task_id)
+                .map(|()| "Task discarded".to_string()),
+            Err(_) => Err(axiom_core::AxiomError::not_found(&item.id)),
+        },
+        ReviewKind::StalledAgent => match review_agent_id(&item) {
+            Some(id) if accept => {
+                panels.agent_registry.write().touch(id);
+                Ok(format!("Waiting on {}", item.id))
+            }
+            Some(id) => {
+                panels.agent_registry.write().cancel(id);
+                pty_manager.write().remove(id);
+                Ok(format!("Cancelled {}", item.id))
+            }
+            None => Err(axiom_core::AxiomError::not_found(item.id.clone())),
+        },
+    };
+    match result {
+        Ok(message) => state.info(message),
+        Err(e) => state.error(e.to_string()),
+    }
+    panels.refresh_review_queue(&manager);
+}
+
+/// Open the search panel over the working directory
+///
+/// Without a query, a panel left open earlier for the same directory comes
+/// back with its results; with one, the search starts right away.
+fn open_search(
+    state: &mut AppState,
     panels: &mut PanelRegistry,
-    pty_manager: &Arc<parking_lot::RwLock<axiom::agents::PtyAgentManager>>,
+    query: Option<String>,
+    regex: bool,
+    globs: Vec<String>,
 ) {
-    // Get all running agent IDs
-    let running_ids: Vec<_> = {
-        let registry = panels.agent_registry.read();
-        registry
-            .agents()
-            .filter(|a| a.status == axiom::agents::AgentStatus::Running)
-            .map(|a| a.id)
-            .collect()
+    let reusable = panels
+        .search
+        .as_ref()
+        .is_some_and(|search| search.root() == state.cwd);
+    if query.is_some() || !globs.is_empty() || !reusable {
+        let mut search = SearchPanel::new(state.cwd.clone(), workspace_ignores(state));
+        if let Some(query) = query {
+            search.set_query(query, regex, globs);
+            search.run();
+        } else {
+            search.set_query("", regex, globs);
+        }
+        panels.search = Some(search);
+    }
+    state.input_mode.open_modal("search");
+}
+
+/// The active workspace's `ignore_patterns`
+fn workspace_ignores(state: &AppState) -> Vec<String> {
+    match (&state.workspace_manager, state.active_workspace_id) {
+        (Some(manager), Some(id)) => manager
+            .get_workspace_config(id)
+            .map(|config| config.ignore_patterns)
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Preview a replace over the working directory, applying it right away
+/// for `/replace --yes`
+fn open_replace(
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    query: SearchQuery,
+    replacement: String,
+    apply: bool,
+) {
+    let ignores = workspace_ignores(state);
+    match ReplacePanel::preview(state.cwd.clone(), query, replacement, &ignores) {
+        Ok(replace) if replace.files().is_empty() => state.info("No matches to replace"),
+        Ok(replace) => {
+            panels.replace = Some(replace);
+            if apply {
+                apply_replace_panel(state, panels);
+            } else {
+                state.input_mode.open_modal("replace");
+            }
+        }
+        Err(e) => state.error(e.to_string()),
+    }
+}
+
+/// Write the files checked in the replace panel and close it
+fn apply_replace_panel(state: &mut AppState, panels: &mut PanelRegistry) {
+    let Some(replace) = panels.replace.take() else {
+        return;
     };
+    state.input_mode.to_normal();
+    match replace.apply() {
+        Ok((outcome, changed)) => {
+            for (path, before) in &changed {
+                panels.show_file_changes(path, before);
+            }
+            state.info(outcome.summary());
+        }
+        Err(e) => state.error(format!("Replace failed: {}", e)),
+    }
+}
 
-    // Mark agents as cancelled in registry
-    {
+/// The agent a stalled-agent review item is about
+fn review_agent_id(item: &axiom_core::ReviewItem) -> Option<AgentId> {
+    let id = item.id.strip_prefix("agent-")?.parse().ok()?;
+    Some(AgentId::new(id))
+}
+
+/// Cancel `target` (or every agent) if pending or running, stopping its
+/// process, PTY or Conductor LLM request; returns the agents cancelled
+fn cancel_agents(
+    target: Option<AgentId>,
+    panels: &mut PanelRegistry,
+    conductor: &mut Conductor,
+    pty_manager: &Arc<parking_lot::RwLock<PtyAgentManager>>,
+) -> Vec<AgentId> {
+    let cancelled: Vec<AgentId> = {
         let mut registry = panels.agent_registry.write();
-        for id in &running_ids {
+        let ids: Vec<AgentId> = registry
+            .agents()
+            .filter(|a| target.is_none_or(|id| id == a.id))
+            .filter(|a| {
+                matches!(
+                    a.status,
+                    axiom::agents::AgentStatus::Pending | axiom::agents::AgentStatus::Running
+                )
+            })
+            .map(|a| a.id)
+            .collect();
+        for id in &ids {
             registry.cancel(*id);
         }
-    }
+        ids
+    };
 
-    // Remove PTY sessions
+    if conductor
+        .agent_id()
+        .is_some_and(|id| cancelled.contains(&id))
     {
-        let mut manager = pty_manager.write();
-        for id in running_ids {
-            manager.remove(id);
+        conductor.cancel();
+    }
+    let mut manager = pty_manager.write();
+    for id in &cancelled {
+        manager.remove(*id);
+    }
+    cancelled
+}
+
+/// Hands the next piece of a queued paste to the panel it was pasted into.
+///
+/// Returns true while a paste was in progress.
+fn deliver_paste(state: &mut AppState, panels: &mut PanelRegistry) -> Result<bool> {
+    let Some(mut queue) = state.paste.take() else {
+        return Ok(false);
+    };
+    match queue.next_chunk() {
+        Some(chunk) => {
+            panels
+                .get_mut(queue.panel)
+                .handle_input(&Event::Paste(chunk), state)?;
+            state.info(format!("Pasting... {}%", queue.percent()));
+            state.paste = Some(queue);
         }
+        None => state.info(format!("Pasted {} KB", queue.size() / 1024)),
     }
+    Ok(true)
 }
 
-/// Spawns a dedicated thread to read input events (keyboard, mouse, resize).
+/// Spawns a dedicated thread to read input events (keyboard, mouse, resize, paste).
 ///
 /// Events are sent to the main loop via the provided channel.
 /// The thread polls for events with a timeout to allow for clean shutdown.
@@ -1681,6 +3374,11 @@ fn spawn_input_reader(tx: crossbeam_channel::Sender<Event>) {
                             break;
                         }
                     }
+                    Ok(event::Event::Paste(text)) => {
+                        if tx.send(Event::Paste(text)).is_err() {
+                            break;
+                        }
+                    }
                     Ok(_) => {}
                     Err(_) => break,
                 }
@@ -1689,89 +3387,815 @@ fn spawn_input_reader(tx: crossbeam_channel::Sender<Event>) {
     });
 }
 
-/// Creates the LLM provider registry based on configuration.
+/// Runs conductor input through the active workspace's guard.
 ///
-/// Registers all enabled providers (Ollama, Claude, Gemini) and sets
-/// the default active provider.
-fn create_provider_registry(config: &AxiomConfig) -> ProviderRegistry {
-    let mut registry = ProviderRegistry::from_config(config);
-
-    // Register Ollama provider (always available, uses local server)
-    if let Some(ollama_config) = config.get_provider("ollama") {
-        if ollama_config.enabled {
-            let base_url = ollama_config
-                .base_url
-                .as_deref()
-                .unwrap_or("http://localhost:11434");
-            let model = ollama_config
-                .default_model
-                .as_deref()
-                .unwrap_or("gemma3:4b");
-            registry.register(Arc::new(OllamaProvider::new(base_url, model)));
-        }
-    } else {
-        // Register with defaults if not in config
-        registry.register(Arc::new(OllamaProvider::default()));
+/// Input often carries pasted logs or file contents, so secrets are
+/// redacted and injection markers reported before it reaches the provider.
+fn guard_prompt(state: &mut AppState, text: &str) -> String {
+    let config = match (&state.workspace_manager, state.active_workspace_id) {
+        (Some(manager), Some(id)) => manager
+            .get_workspace_config(id)
+            .map(|config| config.guard)
+            .unwrap_or_default(),
+        _ => GuardConfig::default(),
+    };
+    let guarded = guard_content(text, &config);
+    if let Some(warning) = guarded.summary("prompt") {
+        state.warn(warning);
     }
+    guarded.text
+}
 
-    // Register Claude provider if API key is available
-    if let Some(claude_config) = config.get_provider("claude") {
-        if claude_config.enabled {
-            if let Some(ref api_key) = claude_config.api_key {
-                if !api_key.is_empty() && !api_key.starts_with("${") {
-                    let model = claude_config
-                        .default_model
-                        .as_deref()
-                        .unwrap_or("claude-sonnet-4-20250514");
-                    registry.register(Arc::new(ClaudeProvider::new(api_key, model)));
-                }
-            }
+/// Checks the provider answering for `role` against the workspace's routing policy.
+///
+/// Blocked routes are reported and the prompt isn't sent; warn-only and
+/// overridden routes go ahead with a warning.
+fn route_allowed(state: &mut AppState, panels: &PanelRegistry, role: AgentRole) -> bool {
+    let (Some(manager), Some(id)) = (state.workspace_manager.clone(), state.active_workspace_id)
+    else {
+        return true;
+    };
+    let registry = panels.llm_registry.read();
+    let provider = registry.role_id(role);
+    let check = registry.check_route(&provider, &manager.routing_policy(id));
+    drop(registry);
+
+    match manager.audit_route(id, &provider, check) {
+        RouteCheck::Allowed => true,
+        RouteCheck::Warn(warning) => {
+            state.warn(format!("Routing: {}", warning));
+            true
         }
-    }
-
-    // Register Gemini provider if API key is available
-    if let Some(gemini_config) = config.get_provider("gemini") {
-        if gemini_config.enabled {
-            if let Some(ref api_key) = gemini_config.api_key {
-                if !api_key.is_empty() && !api_key.starts_with("${") {
-                    let model = gemini_config
-                        .default_model
-                        .as_deref()
-                        .unwrap_or("gemini-2.0-flash");
-                    registry.register(Arc::new(GeminiProvider::new(api_key, model)));
-                }
-            }
+        RouteCheck::Blocked(reason) => {
+            state.error(blocked_message(&provider, &reason));
+            false
         }
     }
+}
 
-    // Set the default provider as active
-    let _ = registry.set_active(&config.llm.default_provider);
-
-    // Fallback to ollama if default provider is not available
-    if registry.active().is_none() {
-        let _ = registry.set_active("ollama");
+/// Start `/gen-tests` as an agent
+///
+/// The agent runs the project's test suite, so untrusted workspaces can't
+/// use it.
+fn start_gen_tests(
+    target: &str,
+    attempts: usize,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    executor: &Executor,
+) {
+    if !state.is_trusted() {
+        state.warn("/gen-tests runs the test suite, which untrusted workspaces don't allow (/trust on to allow)");
+        return;
+    }
+    if !route_allowed(state, panels, AgentRole::Developer) {
+        return;
     }
 
-    registry
+    let request = axiom::agents::AgentSpawnRequest {
+        agent_type: axiom::agents::AgentType::TestGen,
+        name: "Tests".to_string(),
+        description: truncate_cmd(target, 50),
+        parameters: Some(format!("{}|{}", attempts, target)),
+        parent_id: None,
+    };
+    let agent_id = panels.agent_registry.write().spawn(request.clone());
+    state.time.agent_started(agent_id, Instant::now());
+    executor.execute(agent_id, &request);
+    panels.set_output_context(OutputContext::Agent { agent_id });
+    state.record_activity(ActivityEvent::new(
+        ActivityKind::AgentRun,
+        "user",
+        format!("Started Tests: {}", request.description),
+    ));
 }
 
-/// Reloads LLM providers with a new configuration.
-///
-/// Recreates the provider registry for use by the conductor.
-fn reload_providers(panels: &mut PanelRegistry, config: &AxiomConfig) {
-    // Create new provider registry
-    let new_registry = create_provider_registry(config);
-
-    // Replace the registry
-    *panels.llm_registry.write() = new_registry;
+/// Start `/fix` as an agent: lists the build's errors, or fixes `problem`
+fn start_fix(
+    problem: Option<usize>,
+    attempts: usize,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    executor: &Executor,
+) {
+    if !state.is_trusted() {
+        state.warn(
+            "/fix builds the project, which untrusted workspaces don't allow (/trust on to allow)",
+        );
+        return;
+    }
+    // Listing only builds; the model is involved once a problem is picked
+    if problem.is_some() && !route_allowed(state, panels, AgentRole::Developer) {
+        return;
+    }
 
-    // Provider selection will be handled by conductor when implemented
+    let description = match problem {
+        Some(n) => format!("problem {}", n),
+        None => "list problems".to_string(),
+    };
+    let request = axiom::agents::AgentSpawnRequest {
+        agent_type: axiom::agents::AgentType::Fixer,
+        name: "Fix".to_string(),
+        description,
+        parameters: Some(format!("{}|{}", attempts, problem.unwrap_or(0))),
+        parent_id: None,
+    };
+    let agent_id = panels.agent_registry.write().spawn(request.clone());
+    state.time.agent_started(agent_id, Instant::now());
+    executor.execute(agent_id, &request);
+    panels.set_output_context(OutputContext::Agent { agent_id });
+    state.record_activity(ActivityEvent::new(
+        ActivityKind::AgentRun,
+        "user",
+        format!("Started Fix: {}", request.description),
+    ));
 }
 
-/// Checks if a given path corresponds to a source code file.
-///
-/// Determines if a file should be automatically opened in the editor
-/// based on its file extension or specific filename (e.g., Dockerfile, Makefile).
+/// `/session`: list, save or restore the conversation and agent output
+fn session_command(
+    sub: &SessionSubcommand,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &mut Conductor,
+) {
+    use axiom::agents::session;
+    match sub {
+        SessionSubcommand::List => match axiom_core::sessions::list(&state.cwd) {
+            Ok(sessions) if sessions.is_empty() => {
+                state.info("No saved sessions (/session save [name])")
+            }
+            Ok(sessions) => {
+                let lines: Vec<String> = sessions.iter().map(ToString::to_string).collect();
+                state.info(format!("Sessions: {}", lines.join("; ")));
+            }
+            Err(e) => state.warn(format!("Failed to list sessions: {}", e)),
+        },
+        SessionSubcommand::Save { name } => {
+            let registry = panels.agent_registry.read();
+            let saved = session::save(&state.cwd, name.as_deref(), conductor, &registry);
+            drop(registry);
+            match saved {
+                Ok(path) => state.info(format!("Session saved to {}", path.display())),
+                Err(e) => state.warn(format!("Failed to save session: {}", e)),
+            }
+        }
+        SessionSubcommand::Restore { name } => {
+            if panels.agent_registry.read().running_count() > 0 {
+                state.warn("Agents are still running; cancel them before restoring a session");
+                return;
+            }
+            let mut registry = panels.agent_registry.write();
+            let restored = session::restore(&state.cwd, name.as_deref(), conductor, &mut registry);
+            drop(registry);
+            match restored {
+                Ok(name) => {
+                    if let Some(agent_id) = conductor.agent_id() {
+                        panels.set_output_context(OutputContext::Agent { agent_id });
+                    }
+                    state.info(format!("Restored session '{}'", name));
+                }
+                Err(e) => state.warn(format!("Failed to restore session: {}", e)),
+            }
+        }
+    }
+}
+
+/// `/model set <role> <provider>/<model>`: save the role's mapping to
+/// `[llm.agents]` and rebuild the providers so it takes effect now
+fn assign_role_model(
+    role: AgentRole,
+    provider: &str,
+    model: &str,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    config: &mut AxiomConfig,
+) {
+    if provider != "ollama" && !config.llm.providers.contains_key(provider) {
+        state.error(format!("Unknown provider: {}", provider));
+        return;
+    }
+    let mut new_config = config.clone();
+    new_config.llm.agents.insert(
+        role.as_str().to_string(),
+        AgentModelConfig {
+            provider: provider.to_string(),
+            model: model.to_string(),
+        },
+    );
+    if let Err(e) = save_config(&new_config, &loaded_config_path(&state.cwd)) {
+        state.error(format!("Failed to save model for {}: {}", role.as_str(), e));
+        return;
+    }
+    *config = new_config;
+    apply_workspace_defaults(&state.cwd, panels, config);
+
+    let registry = panels.llm_registry.read();
+    match registry.for_role(role) {
+        Some(mapped) if mapped.id() == provider => state.info(format!(
+            "{} now uses {}/{}",
+            role.display_name(),
+            provider,
+            model
+        )),
+        _ => state.warn(format!(
+            "{} saved as {}/{}, but that provider isn't available; using the active one",
+            role.display_name(),
+            provider,
+            model
+        )),
+    }
+}
+
+/// `/inspect`: capture LLM requests and replies, or show an agent's
+fn inspect_command(
+    sub: &InspectSubcommand,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &mut Conductor,
+) {
+    let (agent, copy) = match sub {
+        InspectSubcommand::On => {
+            conductor.set_inspect(true);
+            state.info("Capturing LLM requests and replies, redacted; /inspect shows them");
+            return;
+        }
+        InspectSubcommand::Off => {
+            conductor.set_inspect(false);
+            state.info("Stopped capturing LLM requests; captured ones are kept");
+            return;
+        }
+        InspectSubcommand::Show { agent, copy } => (agent.as_deref(), *copy),
+    };
+
+    // An ID or a name (the newest agent of that name), else the agent shown
+    let registry = panels.agent_registry.read();
+    let id = match agent {
+        Some(agent) => agent.parse().ok().map(AgentId::new).or_else(|| {
+            registry
+                .agents()
+                .filter(|a| a.name.eq_ignore_ascii_case(agent))
+                .map(|a| a.id)
+                .max_by_key(|id| id.value())
+        }),
+        None => match panels.output_context() {
+            OutputContext::Agent { agent_id } => Some(*agent_id),
+            _ => conductor.agent_id(),
+        },
+    };
+    let Some(agent) = id.and_then(|id| registry.get(id)) else {
+        drop(registry);
+        state.warn(format!("No agent {}", agent.unwrap_or("to inspect")));
+        return;
+    };
+    let exchanges = registry.exchanges(agent.id);
+    if exchanges.is_empty() {
+        let hint = if conductor.inspecting() {
+            ""
+        } else {
+            " (/inspect on captures them)"
+        };
+        let message = format!("No LLM requests captured for {}{}", agent.name, hint);
+        drop(registry);
+        state.info(message);
+        return;
+    }
+    let markdown = axiom::agents::inspect::to_markdown(&agent.name, exchanges);
+    let name = agent
+        .name
+        .to_lowercase()
+        .replace(|c: char| !c.is_alphanumeric(), "-");
+    let (id, count) = (agent.id, exchanges.len());
+    drop(registry);
+
+    if copy {
+        match axiom::clipboard::copy(&markdown) {
+            Ok(()) => state.info(format!("Copied {} captured request(s)", count)),
+            Err(e) => state.warn(format!("Copy failed: {}", e)),
+        }
+        return;
+    }
+    let path = state
+        .cwd
+        .join(".axiom/inspect")
+        .join(format!("{}-{}.md", name, id.value()));
+    let written = std::fs::create_dir_all(state.cwd.join(".axiom/inspect"))
+        .and_then(|()| std::fs::write(&path, markdown));
+    match written {
+        Ok(()) => {
+            state.info(format!("{} request(s) in {}", count, path.display()));
+            panels.set_output_context(OutputContext::File { path });
+        }
+        Err(e) => state.warn(format!("Failed to write {}: {}", path.display(), e)),
+    }
+}
+
+/// Carry out an action picked from an agent's menu; actions that other
+/// events already handle come back as that event
+fn run_agent_action(
+    id: AgentId,
+    action: AgentAction,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &Conductor,
+) -> Option<Event> {
+    let registry = panels.agent_registry.read();
+    let Some(agent) = registry.get(id) else {
+        drop(registry);
+        state.warn(format!("No agent {}", id));
+        return None;
+    };
+    match action {
+        AgentAction::ViewOutput => {
+            Some(Event::SwitchContext(OutputContext::Agent { agent_id: id }))
+        }
+        AgentAction::Cancel => Some(Event::AgentCancel(Some(id))),
+        AgentAction::Rerun => {
+            let task = agent
+                .parameters
+                .clone()
+                .unwrap_or_else(|| agent.description.clone());
+            Some(match &agent.agent_type {
+                axiom::agents::AgentType::Conductor => match last_prompt(&agent.output) {
+                    Some(prompt) => Event::ConductorRequest(prompt.to_string()),
+                    None => {
+                        drop(registry);
+                        state.info("The Conductor hasn't been asked anything yet");
+                        return None;
+                    }
+                },
+                axiom::agents::AgentType::CliAgent { config_id } => Event::CliAgentInvoke {
+                    agent_id: config_id.clone(),
+                    prompt: task,
+                },
+                agent_type => Event::AgentSpawn(axiom::agents::AgentSpawnRequest {
+                    agent_type: agent_type.clone(),
+                    name: agent.name.clone(),
+                    description: agent.description.clone(),
+                    parameters: agent.parameters.clone(),
+                    parent_id: None,
+                }),
+            })
+        }
+        AgentAction::Export => {
+            if conductor.agent_id() == Some(id) {
+                drop(registry);
+                write_run_document(None, state, panels, conductor);
+            } else {
+                let document = axiom::agents::RunDocument::of_agent(agent);
+                drop(registry);
+                let prefix = format!("agent-{}", id.value());
+                save_run_document(&document, None, &prefix, state, panels);
+            }
+            None
+        }
+        AgentAction::CopyLastMessage => {
+            let copied = axiom::clipboard::copy(last_message(&agent.output));
+            let name = agent.name.clone();
+            drop(registry);
+            match copied {
+                Ok(()) => state.info(format!("Copied {}'s last message", name)),
+                Err(e) => state.warn(format!("Copy failed: {}", e)),
+            }
+            None
+        }
+        AgentAction::OpenTouchedFiles => {
+            let name = agent.name.clone();
+            let paths: Vec<std::path::PathBuf> = touched_files(&agent.output)
+                .into_iter()
+                .map(|path| state.cwd.join(path))
+                .filter(|path| path.is_file())
+                .collect();
+            drop(registry);
+            match paths.split_first() {
+                None => state.info(format!("{} hasn't written any files", name)),
+                Some((first, rest)) => {
+                    let shown = |path: &std::path::Path| {
+                        path.strip_prefix(&state.cwd)
+                            .unwrap_or(path)
+                            .display()
+                            .to_string()
+                    };
+                    let mut message = format!("Opened {}", shown(first));
+                    if !rest.is_empty() {
+                        let others: Vec<String> = rest.iter().map(|path| shown(path)).collect();
+                        message.push_str(&format!("; also touched {}", others.join(", ")));
+                    }
+                    state.info(message);
+                    let path = first.clone();
+                    panels.set_output_context(OutputContext::File { path });
+                }
+            }
+            None
+        }
+    }
+}
+
+/// Write the Conductor's last run as markdown (`/run-doc`) and open it
+fn write_run_document(
+    path: Option<&std::path::Path>,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &Conductor,
+) {
+    let registry = panels.agent_registry.read();
+    let children = conductor
+        .agent_id()
+        .map(|id| registry.children(id))
+        .unwrap_or_default();
+    let Some(document) = axiom::agents::RunDocument::build(&conductor.history(), &children) else {
+        drop(registry);
+        state.warn("No Conductor run to document yet");
+        return;
+    };
+    drop(registry);
+    save_run_document(&document, path, "run", state, panels);
+}
+
+/// Write `document` to `path`, or to `.axiom/runs/<prefix>-<time>.md`, and
+/// open it
+fn save_run_document(
+    document: &axiom::agents::RunDocument,
+    path: Option<&std::path::Path>,
+    prefix: &str,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+) {
+    let path = match path {
+        Some(path) => state.cwd.join(path),
+        None => {
+            let secs = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            state
+                .cwd
+                .join(".axiom/runs")
+                .join(format!("{}-{}.md", prefix, secs))
+        }
+    };
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&path, document.to_markdown()));
+    match written {
+        Ok(()) => {
+            state.info(format!(
+                "Run document saved to {} ([ and ] jump between sections)",
+                path.display()
+            ));
+            panels.set_output_context(OutputContext::File { path });
+        }
+        Err(e) => state.warn(format!("Failed to write {}: {}", path.display(), e)),
+    }
+}
+
+/// Start `/gen-docs` as an agent; its drafts land in `/approvals`
+fn start_gen_docs(
+    path: &std::path::Path,
+    check: bool,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    executor: &Executor,
+) {
+    // `cargo doc` runs build scripts
+    if check && !state.is_trusted() {
+        state.warn("/gen-docs --check builds the project, which untrusted workspaces don't allow (/trust on to allow)");
+        return;
+    }
+    if !route_allowed(state, panels, AgentRole::Developer) {
+        return;
+    }
+
+    let path = path.to_string_lossy();
+    let request = axiom::agents::AgentSpawnRequest {
+        agent_type: axiom::agents::AgentType::DocGen,
+        name: "Docs".to_string(),
+        description: truncate_cmd(&path, 50),
+        parameters: Some(format!("{}|{}", if check { 1 } else { 0 }, path)),
+        parent_id: None,
+    };
+    let agent_id = panels.agent_registry.write().spawn(request.clone());
+    state.time.agent_started(agent_id, Instant::now());
+    executor.execute(agent_id, &request);
+    panels.set_output_context(OutputContext::Agent { agent_id });
+    state.record_activity(ActivityEvent::new(
+        ActivityKind::AgentRun,
+        "user",
+        format!("Started Docs: {}", request.description),
+    ));
+}
+
+/// Build the provider registry on a background thread, replacing the
+/// placeholder in `registry` unless settings have replaced it already
+fn spawn_provider_init(
+    registry: Arc<parking_lot::RwLock<ProviderRegistry>>,
+    config: AxiomConfig,
+    event_tx: crossbeam_channel::Sender<Event>,
+) {
+    std::thread::spawn(move || {
+        let ready = create_provider_registry(&config);
+        let mut registry = registry.write();
+        if registry.is_loading() {
+            *registry = ready;
+        }
+        drop(registry);
+        let _ = event_tx.send(Event::ProvidersReady);
+    });
+}
+
+/// Creates the LLM provider registry based on configuration.
+///
+/// Registers all enabled providers (Ollama, Claude, Gemini), applies the
+/// configured rate limits and sets the default active provider.
+fn create_provider_registry(config: &AxiomConfig) -> ProviderRegistry {
+    axiom_core::llm::configure_rate_limits(&config.llm.rate_limits);
+    let mut registry = ProviderRegistry::from_config(config);
+
+    // Ollama first, then the hosted providers that have an API key, then
+    // every enabled custom OpenAI-compatible endpoint
+    let custom = config
+        .llm
+        .providers
+        .keys()
+        .map(String::as_str)
+        .filter(|id| !BUILTIN_PROVIDERS.contains(id));
+    for id in ["ollama", "claude", "gemini"].into_iter().chain(custom) {
+        if let Some(provider) = configured_provider(config, id, None) {
+            registry.register(provider);
+        }
+    }
+
+    // Roles mapped in [llm.agents] get an instance of their own, so picking
+    // a model for the provider doesn't change theirs
+    for (role, agent) in &config.llm.agents {
+        let Ok(role) = role.parse::<AgentRole>() else {
+            continue;
+        };
+        let model = Some(agent.model.as_str()).filter(|model| !model.is_empty());
+        if let Some(provider) = configured_provider(config, &agent.provider, model) {
+            registry.set_role(role, provider);
+        }
+    }
+
+    // Set the default provider as active
+    let _ = registry.set_active(&config.llm.default_provider);
+
+    // Fallback to ollama if default provider is not available
+    if registry.active().is_none() {
+        let _ = registry.set_active("ollama");
+    }
+
+    registry
+}
+
+/// Provider `id` as configured, answering with `model` rather than its
+/// default model when one is given
+///
+/// None when it's disabled, or hosted and without an API key. Ollama is
+/// available with its defaults even when it isn't configured.
+fn configured_provider(
+    config: &AxiomConfig,
+    id: &str,
+    model: Option<&str>,
+) -> Option<SharedProvider> {
+    let Some(provider_config) = config.get_provider(id) else {
+        return match id {
+            "ollama" => {
+                let provider = OllamaProvider::default();
+                if let Some(model) = model {
+                    provider.set_model(model).ok()?;
+                }
+                Some(Arc::new(provider))
+            }
+            _ => None,
+        };
+    };
+    if !provider_config.enabled {
+        return None;
+    }
+    let pick = |default: &str| {
+        model
+            .or(provider_config.default_model.as_deref())
+            .unwrap_or(default)
+            .to_string()
+    };
+    let api_key = provider_config
+        .api_key
+        .as_deref()
+        .filter(|key| !key.is_empty() && !key.starts_with("${"));
+
+    match id {
+        "ollama" => {
+            let base_url = provider_config
+                .base_url
+                .as_deref()
+                .unwrap_or("http://localhost:11434");
+            Some(Arc::new(OllamaProvider::new(base_url, &pick("gemma3:4b"))))
+        }
+        "claude" => Some(Arc::new(ClaudeProvider::new(
+            api_key?,
+            &pick("claude-sonnet-4-20250514"),
+        ))),
+        "gemini" => Some(Arc::new(GeminiProvider::new(
+            api_key?,
+            &pick("gemini-2.0-flash"),
+        ))),
+        _ if BUILTIN_PROVIDERS.contains(&id) => None,
+        _ => {
+            let provider = OpenAICompatibleProvider::from_config(id, provider_config)?;
+            if let Some(model) = model {
+                provider.set_model(model).ok()?;
+            }
+            Some(Arc::new(provider))
+        }
+    }
+}
+
+/// Reloads LLM providers with a new configuration.
+///
+/// Recreates the provider registry for use by the conductor.
+fn reload_providers(panels: &mut PanelRegistry, config: &AxiomConfig) {
+    // Create new provider registry
+    let new_registry = create_provider_registry(config);
+
+    // Replace the registry
+    *panels.llm_registry.write() = new_registry;
+
+    // Provider selection will be handled by conductor when implemented
+}
+
+/// Rebuilds providers and CLI agents from `config` with the workspace at
+/// `cwd`'s own provider, model and CLI agent over it.
+fn apply_workspace_defaults(
+    cwd: &std::path::Path,
+    panels: &mut PanelRegistry,
+    config: &AxiomConfig,
+) {
+    let effective = with_workspace_overrides(config, cwd);
+    panels.update_cli_agents(effective.cli_agents.clone());
+    reload_providers(panels, &effective);
+}
+
+/// Rereads the config after `path` changed on disk and applies what changed;
+/// a config that doesn't load or validate is reported and the current one
+/// kept.
+fn reload_config(
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    config: &mut AxiomConfig,
+    conductor: &mut Conductor,
+    pty_manager: &Arc<parking_lot::RwLock<PtyAgentManager>>,
+    path: &std::path::Path,
+) {
+    let mut new_config = match load_config(&state.cwd) {
+        Ok(new_config) => new_config,
+        Err(e) => {
+            state.error(format!("Config not reloaded: {}", e));
+            return;
+        }
+    };
+    if let Err(e) = with_workspace_overrides(&new_config, &state.cwd).validate() {
+        state.error(format!("Config not reloaded: {}", e));
+        return;
+    }
+
+    // Keys fetched by a command aren't in the file
+    for (id, provider) in &mut new_config.llm.providers {
+        let Some(current) = config.llm.providers.get(id) else {
+            continue;
+        };
+        if provider.api_key.is_none()
+            && provider.api_key_command.is_some()
+            && provider.api_key_command == current.api_key_command
+        {
+            provider.api_key = current.api_key.clone();
+            provider.api_key_expires_at = current.api_key_expires_at;
+        }
+    }
+
+    let mut changes = ConfigChanges::between(&*config, &new_config).needing_restart(&[
+        "completion",
+        "directory_picker",
+        "discovery",
+        "sounds",
+        "terminal",
+    ]);
+    // The workspace's own file only holds overrides of the global config
+    let workspace_file = path.ends_with(".axiom/config.toml");
+    if workspace_file {
+        changes.sections.push("workspace overrides".to_string());
+    } else if changes.is_empty() {
+        return;
+    }
+    *config = new_config;
+
+    apply_workspace_defaults(&state.cwd, panels, config);
+    pty_manager
+        .write()
+        .set_scrollback(config.cli_agents.scrollback_lines);
+    if changes.changed("lsp") {
+        panels.output.set_lsp_config(config.lsp.clone());
+    }
+    if changes.changed("agents") {
+        panels.agents.set_retention(&config.agents);
+    }
+    if changes.changed("keys") {
+        // Validated above
+        state.keymap = Keymap::new(&config.keys).unwrap_or_default();
+    }
+    if changes.changed("prompts") {
+        conductor.set_prompts(PromptLibrary::new(&state.cwd, &config.prompts));
+    }
+    if changes.changed("llm.context") {
+        conductor.set_context_config(config.llm.context.clone());
+    }
+    tracing::info!("Config reloaded from {}: {}", path.display(), changes.summary());
+    state.info(format!("Config reloaded: {}", changes.summary()));
+}
+
+/// Saves the workspace provider and CLI agent picked in settings, if they
+/// changed; false when there was nothing to save or it failed.
+fn save_workspace_defaults(state: &mut AppState, panels: &PanelRegistry) -> bool {
+    let Some(defaults) = panels.settings.workspace_changes() else {
+        return false;
+    };
+    let (Some(manager), Some(id)) = (&state.workspace_manager, state.active_workspace_id) else {
+        return false;
+    };
+    match manager.set_workspace_defaults(id, defaults) {
+        Ok(()) => true,
+        Err(e) => {
+            state.error(format!("Failed to save workspace settings: {}", e));
+            false
+        }
+    }
+}
+
+/// Fetches provider keys that are missing or about to expire, and reminds
+/// about keys without a command as they near their expiry.
+fn poll_keys(
+    state: &mut AppState,
+    config: &AxiomConfig,
+    event_tx: &crossbeam_channel::Sender<Event>,
+) {
+    if !state.key_watch.due(Instant::now()) {
+        return;
+    }
+    let now = unix_now();
+    // An untrusted project's own config doesn't get to run commands
+    let commands_allowed = key_commands_allowed(&state.cwd, state.is_trusted());
+    let mut providers: Vec<_> = config
+        .llm
+        .providers
+        .iter()
+        .filter(|(_, provider)| provider.enabled)
+        .collect();
+    providers.sort_by_key(|(id, _)| id.as_str());
+
+    for (id, provider) in providers {
+        let action = state.key_watch.check(
+            id,
+            provider.api_key_expires_at,
+            provider.api_key_command.is_some() && commands_allowed,
+            provider.api_key.is_some(),
+            now,
+        );
+        let expires = provider
+            .api_key_expires_at
+            .map(|at| describe_expiry(at, now))
+            .unwrap_or_default();
+        match action {
+            Some(KeyAction::Refresh) => {
+                let Some(command) = provider.api_key_command.clone() else {
+                    continue;
+                };
+                let provider_id = id.clone();
+                let event_tx = event_tx.clone();
+                std::thread::spawn(move || {
+                    let result = fetch_key(&command);
+                    let _ = event_tx.send(Event::ProviderKeyFetched {
+                        provider_id,
+                        result,
+                    });
+                });
+            }
+            Some(KeyAction::Remind { .. }) => state.warn(format!(
+                "{}'s API key expires {}; replace it in Settings",
+                id, expires
+            )),
+            Some(KeyAction::Expired) => state.error(format!(
+                "{}'s API key expired {}; replace it in Settings",
+                id, expires
+            )),
+            None => {}
+        }
+    }
+}
+
+/// Checks if a given path corresponds to a source code file.
+///
+/// Determines if a file should be automatically opened in the editor
+/// based on its file extension or specific filename (e.g., Dockerfile, Makefile).
 fn is_source_file(path: &std::path::Path) -> bool {
     let source_extensions = [
         "rs", "py", "js", "ts", "tsx", "jsx", "go", "java", "c", "cpp", "h", "hpp",
@@ -1809,6 +4233,37 @@ fn is_source_file(path: &std::path::Path) -> bool {
 }
 
 /// Truncate a command string for display
+/// Run a `/refactor` subcommand against the active workspace
+fn refactor_command(
+    manager: &axiom_core::WorkspaceManager,
+    id: axiom_core::WorkspaceId,
+    sub: &RefactorSubcommand,
+) -> SlashCommandResult {
+    let preview = |preview| SlashCommandResult::data(SlashCommandData::Text(preview));
+    let result = match sub {
+        RefactorSubcommand::Show => Ok(match manager.refactor_preview(id) {
+            Some(text) => preview(text),
+            None => SlashCommandResult::success(
+                "No rename pending. Start one with /refactor rename <symbol> <new_name>",
+            ),
+        }),
+        RefactorSubcommand::Rename { symbol, new_name } => {
+            manager.plan_rename(id, symbol, new_name).map(preview)
+        }
+        RefactorSubcommand::Toggle { path } => manager.toggle_refactor_file(id, path).map(preview),
+        RefactorSubcommand::Apply => manager.apply_refactor(id).map(SlashCommandResult::success),
+        RefactorSubcommand::Undo => manager.undo_refactor(id).map(SlashCommandResult::success),
+        RefactorSubcommand::Cancel => Ok(SlashCommandResult::success(
+            if manager.discard_refactor(id) {
+                "Rename discarded"
+            } else {
+                "No rename pending"
+            },
+        )),
+    };
+    result.unwrap_or_else(|e| SlashCommandResult::error(e.to_string()))
+}
+
 fn truncate_cmd(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
@@ -1817,6 +4272,352 @@ fn truncate_cmd(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Add a stopped agent's wall-clock time to the workspace time ledger
+///
+/// LLM-backed agents are keyed by the provider of the role they run as;
+/// shell, service and CLI agents by their role alone.
+fn record_agent_run(state: &mut AppState, panels: &PanelRegistry, id: AgentId) {
+    let Some(ran) = state.time.agent_stopped(id, Instant::now()) else {
+        return;
+    };
+    let registry = panels.agent_registry.read();
+    let Some(agent) = registry.get(id) else {
+        return;
+    };
+    use axiom::agents::AgentType;
+    let role = match agent.agent_type {
+        AgentType::Shell | AgentType::Service | AgentType::CliAgent { .. } => None,
+        AgentType::Coder
+        | AgentType::TestGen
+        | AgentType::DocGen
+        | AgentType::Fixer
+        | AgentType::Verifier => Some(AgentRole::Developer),
+        _ => Some(AgentRole::Orchestrator),
+    };
+    let model = role.map(|role| panels.llm_registry.read().role_id(role));
+    state.record_agent_time(agent.agent_type.label(), model.as_deref(), ran);
+}
+
+/// Whether an agent of the conductor's latest request failed, once the
+/// conductor and every agent it started are done
+///
+/// Services it started keep running and don't hold the run open.
+fn settled_run(panels: &PanelRegistry, conductor: &Conductor) -> Option<bool> {
+    let conductor_id = conductor.agent_id()?;
+    let registry = panels.agent_registry.read();
+    if registry.get(conductor_id).is_some_and(|a| a.status.is_running())
+        || !registry.children_done(conductor_id)
+    {
+        return None;
+    }
+    Some(
+        registry
+            .children(conductor_id)
+            .iter()
+            .any(|a| matches!(a.status, axiom::agents::AgentStatus::Error(_))),
+    )
+}
+
+/// Once the conductor and every agent it started are done, run the
+/// workspace's `[verify]` checks on the files they wrote
+///
+/// The checks run its build, so untrusted workspaces skip them. The
+/// verifier joins the run, which settles again when it's done.
+fn verify_run(
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &Conductor,
+    executor: &Executor,
+) {
+    let Some(run) = state.run.as_ref().filter(|run| !run.files.is_empty()) else {
+        return;
+    };
+    let (Some(conductor_id), Some(false)) = (conductor.agent_id(), settled_run(panels, conductor))
+    else {
+        return;
+    };
+    if !state.is_trusted() {
+        return;
+    }
+    let enabled = match (&state.workspace_manager, state.active_workspace_id) {
+        (Some(manager), Some(id)) => manager
+            .get_workspace_config(id)
+            .is_ok_and(|config| config.verify.is_enabled()),
+        _ => false,
+    };
+    let verified = panels
+        .agent_registry
+        .read()
+        .children(conductor_id)
+        .iter()
+        .any(|a| a.agent_type == axiom::agents::AgentType::Verifier);
+    if !enabled || verified {
+        return;
+    }
+
+    let request = axiom::agents::AgentSpawnRequest {
+        agent_type: axiom::agents::AgentType::Verifier,
+        name: "Verify".to_string(),
+        description: truncate_cmd(&run.task, 50),
+        parameters: Some(run.task.clone()),
+        parent_id: Some(conductor_id),
+    };
+    let agent_id = panels.agent_registry.write().spawn(request.clone());
+    state.time.agent_started(agent_id, Instant::now());
+    executor.execute(agent_id, &request);
+    state.record_activity(ActivityEvent::new(
+        ActivityKind::AgentRun,
+        "conductor",
+        format!("Started Verify: {}", request.description),
+    ));
+}
+
+/// Offer follow-up prompts on the input once the conductor and every agent
+/// it started are done
+fn offer_follow_ups(
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &Conductor,
+    enabled: bool,
+) {
+    if state.run.is_none() {
+        return;
+    }
+    let Some(failed) = settled_run(panels, conductor) else {
+        return;
+    };
+    let Some(mut run) = state.run.take() else {
+        return;
+    };
+    run.failed |= failed;
+    if enabled {
+        panels
+            .input
+            .set_suggestions(suggest_follow_ups(&state.cwd, &run));
+    }
+}
+
+/// `/plan`: show the plan, plan a request or stop the running plan
+fn plan_command(
+    sub: &PlanSubcommand,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &mut Conductor,
+    screen_area: ratatui::layout::Rect,
+) {
+    match sub {
+        PlanSubcommand::Show => {
+            if state.plan.is_none() {
+                match axiom_core::plan::load(&state.cwd) {
+                    // A plan still running belonged to an earlier session
+                    Ok(Some(mut plan)) => {
+                        if !plan.is_finished() {
+                            plan.cancel();
+                        }
+                        state.plan = Some(plan);
+                    }
+                    Ok(None) => {
+                        state.info("No plan yet (/plan <request>)");
+                        return;
+                    }
+                    Err(e) => {
+                        state.warn(format!("Failed to load the plan: {}", e));
+                        return;
+                    }
+                }
+            }
+            panels.plan_view = Some(ui::PlanView::new());
+            state.input_mode.open_modal("plan");
+        }
+        PlanSubcommand::Start { request } => {
+            let running = state.plan.as_ref().is_some_and(|plan| !plan.is_finished());
+            if running || state.planning.is_some() {
+                state.warn("A plan is already running (/plan cancel to stop it)");
+                return;
+            }
+            let prompt = axiom_core::plan::planning_prompt(request);
+            if prompt_plan_turn(&prompt, state, panels, conductor, screen_area) {
+                state.planning = Some(request.clone());
+                state.info(format!("Planning: {}", request));
+            }
+        }
+        PlanSubcommand::Cancel => {
+            if cancel_plan(state) {
+                state.info("Plan cancelled");
+            } else {
+                state.info("No plan running");
+            }
+        }
+    }
+}
+
+/// Send `text` to the conductor and show its output, unless the routing
+/// policy blocks it; returns whether it was sent
+fn prompt_conductor(
+    text: &str,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &mut Conductor,
+    screen_area: ratatui::layout::Rect,
+) -> bool {
+    if !route_allowed(state, panels, AgentRole::Orchestrator) {
+        return false;
+    }
+    conductor.process(guard_prompt(state, text));
+    state.run = Some(RunOutcome::new(text));
+    panels.input.set_suggestions(Vec::new());
+    // Switch to agent output view
+    let registry = panels.agent_registry.read();
+    if let Some(agent_id) = registry.selected_id() {
+        drop(registry);
+        panels.set_output_context(OutputContext::Agent { agent_id });
+
+        // Focus Input panel for typing more prompts to Conductor
+        state.focus.focus(PanelId::INPUT);
+        panels.handle_focus_change(PanelId::INPUT, screen_area);
+    }
+    true
+}
+
+/// Send the planning prompt or a plan step to the conductor
+///
+/// The conductor is marked running right away, so agents that finish
+/// before it wakes up don't end its turn early.
+fn prompt_plan_turn(
+    text: &str,
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &mut Conductor,
+    screen_area: ratatui::layout::Rect,
+) -> bool {
+    if !prompt_conductor(text, state, panels, conductor, screen_area) {
+        return false;
+    }
+    if let Some(id) = conductor.agent_id() {
+        if let Some(agent) = panels.agent_registry.write().get_mut(id) {
+            agent.status = axiom::agents::AgentStatus::Running;
+        }
+    }
+    true
+}
+
+/// Take the plan out of the conductor's reply to a planning prompt
+fn receive_plan(goal: String, reply: &str, state: &mut AppState) {
+    match Plan::parse(goal, reply) {
+        Some(plan) => {
+            state.info(format!(
+                "Plan: {} steps (/plan to follow it)",
+                plan.steps.len()
+            ));
+            state.plan = Some(plan);
+            save_plan(state);
+        }
+        None => state.warn("The Conductor answered without a plan"),
+    }
+}
+
+/// Move the plan on once the conductor and the agents of its turn are done:
+/// the running step is marked done or failed, and the next one is started
+fn advance_plan(
+    state: &mut AppState,
+    panels: &mut PanelRegistry,
+    conductor: &mut Conductor,
+    screen_area: ratatui::layout::Rect,
+) {
+    if state.planning.is_none() && state.plan.as_ref().is_none_or(Plan::is_finished) {
+        return;
+    }
+    let Some(failed) = settled_run(panels, conductor) else {
+        return;
+    };
+    if let Some(goal) = state.planning.take() {
+        // The planning request failed before a reply came
+        state.warn(format!("No plan was made for: {}", goal));
+        return;
+    }
+    let Some(plan) = state.plan.as_mut() else {
+        return;
+    };
+    plan.finish_step(failed);
+    let next = plan.start_next().map(|prompt| {
+        let index = plan.current().unwrap_or_default();
+        (prompt, index, plan.steps[index].title.clone())
+    });
+    let total = plan.steps.len();
+    let failed_at = plan
+        .steps
+        .iter()
+        .position(|step| step.status == axiom_core::PlanStepStatus::Failed);
+    save_plan(state);
+
+    match (next, failed_at) {
+        (Some((prompt, index, title)), _) => {
+            state.info(format!("Plan step {}/{}: {}", index + 1, total, title));
+            if !prompt_plan_turn(&prompt, state, panels, conductor, screen_area) {
+                cancel_plan(state);
+            }
+        }
+        (None, Some(index)) => state.warn(format!("Plan stopped: step {} failed", index + 1)),
+        (None, None) => state.info(format!("Plan done: {} steps", total)),
+    }
+}
+
+/// Stop the running plan, if any; returns whether one was running
+fn cancel_plan(state: &mut AppState) -> bool {
+    state.planning = None;
+    let Some(plan) = state.plan.as_mut().filter(|plan| !plan.is_finished()) else {
+        return false;
+    };
+    plan.cancel();
+    save_plan(state);
+    true
+}
+
+/// Keep the plan in `.axiom/plan.json` for the server to read
+fn save_plan(state: &mut AppState) {
+    let Some(plan) = &state.plan else {
+        return;
+    };
+    if let Err(e) = axiom_core::plan::save(&state.cwd, plan) {
+        state.warn(format!("Plan not saved: {}", e));
+    }
+}
+
+/// Record the events a CLI agent's output parsers found; progress goes to
+/// the status bar
+fn record_agent_events(state: &mut AppState, agent: &str, events: &[AgentEvent]) {
+    for event in events {
+        match event {
+            AgentEvent::Progress { message, percent } => match percent {
+                Some(percent) => state.info(format!("{}: {} ({:.0}%)", agent, message, percent)),
+                None => state.info(format!("{}: {}", agent, message)),
+            },
+            event => state.record_agent_event(agent, event),
+        }
+    }
+}
+
+/// Log the estimated token usage of a conductor turn
+///
+/// The request was the conversation history so far; the response is the
+/// streamed answer.
+fn record_conductor_usage(
+    state: &AppState,
+    panels: &PanelRegistry,
+    history: &[axiom::llm::ChatMessage],
+    response: &str,
+) {
+    let Some(manager) = &state.workspace_manager else {
+        return;
+    };
+    let Some(provider) = panels.llm_registry.read().for_role(AgentRole::Orchestrator) else {
+        return;
+    };
+    let input: u64 = history.iter().map(|m| estimate_tokens(&m.content.as_text())).sum();
+    let record = UsageRecord::new(provider.id(), provider.model(), input, estimate_tokens(response));
+    let _ = manager.record_usage(state.active_workspace_id, record);
+}
+
 /// Execute a slash command and return the result
 fn execute_slash_command(
     cmd: &SlashCommand,
@@ -1866,17 +4667,337 @@ fn execute_slash_command(
             })
         }
 
-        SlashCommand::Init { path } => {
-            // Initialize workspace at the given path or current directory
-            let target_path = path.clone().unwrap_or_else(|| state.cwd.clone());
-            if target_path.exists() && target_path.is_dir() {
-                // Could create a workspace here, but for now just show message
-                SlashCommandResult::success(format!(
-                    "Would initialize workspace at: {}",
-                    target_path.display()
-                ))
+        SlashCommand::Init {
+            template: Some(template),
+            path,
+        } => {
+            let Some(manager) = &state.workspace_manager else {
+                return SlashCommandResult::error("Workspace manager not initialized");
+            };
+            let root = match path {
+                Some(path) => state.cwd.join(path),
+                None => state.cwd.clone(),
+            };
+            let user_dir = axiom_core::templates::templates_dir();
+            match axiom_core::templates::init_project(manager, template, &root, user_dir.as_deref())
+            {
+                Ok((report, workspace)) => {
+                    let summary = format!("{}\nWorkspace: {}", report.summary(), workspace.name);
+                    if report.success() {
+                        SlashCommandResult::success(summary)
+                    } else {
+                        SlashCommandResult::error(summary)
+                    }
+                }
+                Err(e) => SlashCommandResult::error(format!("Failed to create project: {}", e)),
+            }
+        }
+
+        SlashCommand::Templates => {
+            let user_dir = axiom_core::templates::templates_dir();
+            let templates = axiom_core::templates::load_templates(user_dir.as_deref());
+            SlashCommandResult::data(SlashCommandData::Text(
+                axiom_core::templates::templates_list(&templates),
+            ))
+        }
+
+        SlashCommand::Keys => {
+            SlashCommandResult::data(SlashCommandData::Text(state.keymap.list()))
+        }
+
+        SlashCommand::LogLevel { level } => {
+            let Some(logger) = logging::logger() else {
+                return SlashCommandResult::error("Logging isn't set up");
+            };
+            match level {
+                None => SlashCommandResult::success(format!(
+                    "Log level: {} ({})",
+                    logger.level(),
+                    logger.path().display()
+                )),
+                Some(level) => match logger.set_level(level) {
+                    Ok(level) => {
+                        tracing::info!("Log level set to {}", level);
+                        SlashCommandResult::success(format!("Log level: {}", level))
+                    }
+                    Err(e) => SlashCommandResult::error(e),
+                },
+            }
+        }
+
+        SlashCommand::Init {
+            template: None,
+            path,
+        } => {
+            // Without a path, pick the directory interactively
+            let Some(target_path) = path.clone() else {
+                return SlashCommandResult::action(UiAction::OpenDirectoryPicker);
+            };
+            if !target_path.is_dir() {
+                return SlashCommandResult::error(format!("Path does not exist: {}", target_path.display()));
+            }
+            if let Some(manager) = &state.workspace_manager {
+                if let Some(existing) = manager.find_by_path(&target_path) {
+                    return SlashCommandResult::action(UiAction::OfferExistingWorkspace {
+                        id: existing.id.to_string(),
+                        name: existing.name,
+                    });
+                }
+                match manager.create_workspace(&workspace_name_for(&target_path), target_path.clone()) {
+                    Ok(ws) => SlashCommandResult::success(format!("Initialized workspace: {}", ws.name)),
+                    Err(e) => SlashCommandResult::error(format!("Failed to create workspace: {}", e)),
+                }
             } else {
-                SlashCommandResult::error(format!("Path does not exist: {}", target_path.display()))
+                SlashCommandResult::error("Workspace manager not initialized")
+            }
+        }
+
+        SlashCommand::Activity => SlashCommandResult::action(UiAction::OpenActivityFeed),
+
+        SlashCommand::Review => SlashCommandResult::action(UiAction::OpenReviewQueue),
+
+        SlashCommand::Recordings => match axiom_core::recordings::list(&state.cwd) {
+            Ok(recordings) => SlashCommandResult::data(SlashCommandData::Text(
+                axiom_core::recordings::summary(&recordings),
+            )),
+            Err(e) => SlashCommandResult::error(format!("Failed to list recordings: {}", e)),
+        },
+
+        SlashCommand::Find {
+            query,
+            regex,
+            globs,
+        } => SlashCommandResult::action(UiAction::OpenSearch {
+            query: query.clone(),
+            regex: *regex,
+            globs: globs.clone(),
+        }),
+
+        SlashCommand::Replace {
+            query,
+            replacement,
+            regex,
+            globs,
+            apply,
+        } => SlashCommandResult::action(UiAction::OpenReplace {
+            query: query.clone(),
+            replacement: replacement.clone(),
+            regex: *regex,
+            globs: globs.clone(),
+            apply: *apply,
+        }),
+
+        SlashCommand::Stats(StatsSubcommand::Time { days }) => {
+            match (&state.workspace_manager, state.active_workspace_id) {
+                (Some(manager), Some(id)) => match manager.time_report(id, *days) {
+                    Ok(report) => SlashCommandResult::data(SlashCommandData::Text(report.summary())),
+                    Err(e) => SlashCommandResult::error(e.to_string()),
+                },
+                _ => SlashCommandResult::error("No active workspace"),
+            }
+        }
+
+        SlashCommand::Usage(sub) => match (&state.workspace_manager, state.active_workspace_id) {
+            (Some(manager), Some(id)) => match sub {
+                UsageSubcommand::Summary => {
+                    let rows = manager.usage_rows(&UsageFilter {
+                        workspace: Some(id.to_string()),
+                        ..Default::default()
+                    });
+                    SlashCommandResult::data(SlashCommandData::Text(
+                        axiom_core::usage::usage_summary(&rows),
+                    ))
+                }
+                UsageSubcommand::Export { from, to, json } => {
+                    match manager.export_usage(id, from.clone(), to.clone(), *json) {
+                        Ok(path) => SlashCommandResult::success(format!(
+                            "Usage report written to {}",
+                            path.display()
+                        )),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+            },
+            _ => SlashCommandResult::error("No active workspace"),
+        },
+
+        SlashCommand::Approvals(sub) => match (&state.workspace_manager, state.active_workspace_id)
+        {
+            (Some(manager), Some(id)) => {
+                let (approval_id, approve) = match sub {
+                    ApprovalSubcommand::List => {
+                        return SlashCommandResult::data(SlashCommandData::Text(
+                            axiom_core::workspace::pending_summary(&manager.pending_writes(id)),
+                        ));
+                    }
+                    ApprovalSubcommand::Approve { id } => (id.as_deref(), true),
+                    ApprovalSubcommand::Reject { id } => (id.as_deref(), false),
+                };
+                match manager.resolve_write(id, approval_id, approve) {
+                    Ok(write) => SlashCommandResult::success(format!(
+                        "{} write to {}",
+                        if approve { "Applied" } else { "Discarded" },
+                        write.path.display()
+                    )),
+                    Err(e) => SlashCommandResult::error(e.to_string()),
+                }
+            }
+            _ => SlashCommandResult::error("No active workspace"),
+        },
+
+        SlashCommand::Trust { trusted } => match (&state.workspace_manager, state.active_workspace_id)
+        {
+            (Some(manager), Some(id)) => {
+                if let Some(trusted) = trusted {
+                    if let Err(e) = manager.set_trusted(id, *trusted) {
+                        return SlashCommandResult::error(e.to_string());
+                    }
+                }
+                match manager.trust(id) {
+                    Ok(decision) => {
+                        SlashCommandResult::success(axiom_core::workspace::trust_status(decision))
+                    }
+                    Err(e) => SlashCommandResult::error(e.to_string()),
+                }
+            }
+            _ => SlashCommandResult::error("No active workspace"),
+        },
+
+        SlashCommand::Routing(sub) => match (&state.workspace_manager, state.active_workspace_id) {
+            (Some(manager), Some(id)) => match sub {
+                RoutingSubcommand::Show => {
+                    let policy = manager.routing_policy(id);
+                    let registry = panels.llm_registry.read();
+                    let mut providers = registry.provider_ids();
+                    providers.sort();
+                    let routes: Vec<_> = providers
+                        .into_iter()
+                        .map(|p| {
+                            let check = registry.check_route(&p, &policy);
+                            (p, check)
+                        })
+                        .collect();
+                    let overrides = manager.routing_overrides(id);
+                    SlashCommandResult::data(SlashCommandData::Text(routing_summary(
+                        &policy, &routes, &overrides,
+                    )))
+                }
+                RoutingSubcommand::Allow { provider, reason } => {
+                    match manager.allow_routing(id, provider, reason) {
+                        Ok(()) => SlashCommandResult::success(format!(
+                            "Routing override: {} allowed for this session",
+                            provider
+                        )),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+                RoutingSubcommand::Revoke { provider } => {
+                    match manager.revoke_routing(id, provider) {
+                        Ok(true) => SlashCommandResult::success(format!(
+                            "Revoked routing override for {}",
+                            provider
+                        )),
+                        Ok(false) => SlashCommandResult::error(format!(
+                            "No routing override for {}",
+                            provider
+                        )),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+            },
+            _ => SlashCommandResult::error("No active workspace"),
+        },
+
+        SlashCommand::VerifyAudit { export } => {
+            match (&state.workspace_manager, state.active_workspace_id) {
+                (Some(manager), Some(id)) => {
+                    let mut text = match manager.verify_audit(id) {
+                        Ok(report) => report.summary(),
+                        Err(e) => return SlashCommandResult::error(e.to_string()),
+                    };
+                    if *export {
+                        match manager.export_audit(id) {
+                            Ok(path) => {
+                                text.push_str(&format!("\nAudit log written to {}", path.display()))
+                            }
+                            Err(e) => return SlashCommandResult::error(e.to_string()),
+                        }
+                    }
+                    SlashCommandResult::data(SlashCommandData::Text(text))
+                }
+                _ => SlashCommandResult::error("No active workspace"),
+            }
+        }
+
+        SlashCommand::Watch(sub) => match (&state.workspace_manager, state.active_workspace_id) {
+            (Some(manager), Some(id)) => match sub {
+                WatchSubcommand::List => match manager.watch_rules(id) {
+                    Ok(rules) => {
+                        SlashCommandResult::data(SlashCommandData::Text(watch_summary(&rules)))
+                    }
+                    Err(e) => SlashCommandResult::error(e.to_string()),
+                },
+                WatchSubcommand::Add { pattern, action } => {
+                    let rule = WatchRule::new(pattern.clone(), action.clone());
+                    match manager.add_watch_rule(id, rule) {
+                        Ok(n) => SlashCommandResult::success(format!(
+                            "Watch rule {}: {} → {}",
+                            n,
+                            pattern,
+                            action.describe()
+                        )),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+                WatchSubcommand::Remove { number } => {
+                    match manager.remove_watch_rule(id, *number) {
+                        Ok(rule) => SlashCommandResult::success(format!(
+                            "Removed watch rule {}: {}",
+                            number, rule.pattern
+                        )),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+            },
+            _ => SlashCommandResult::error("No active workspace"),
+        },
+
+        SlashCommand::Features(sub) => {
+            match (&state.workspace_manager, state.active_workspace_id) {
+                (Some(manager), Some(id)) => match sub {
+                    FeaturesSubcommand::List => match manager.features(id) {
+                        Ok(flags) => SlashCommandResult::data(SlashCommandData::Text(
+                            features_summary(&flags),
+                        )),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    },
+                    FeaturesSubcommand::Set { name, enabled } => {
+                        let Some(feature) = Feature::parse(name) else {
+                            return SlashCommandResult::error(format!("Unknown feature: {}", name));
+                        };
+                        match manager.set_feature(id, feature, *enabled) {
+                            Ok(()) => {
+                                SlashCommandResult::success(feature_switched(feature, *enabled))
+                            }
+                            Err(e) => SlashCommandResult::error(e.to_string()),
+                        }
+                    }
+                },
+                _ => SlashCommandResult::error("No active workspace"),
+            }
+        }
+
+        SlashCommand::Report(ReportSubcommand::Agents { since }) => {
+            match (&state.workspace_manager, state.active_workspace_id) {
+                (Some(manager), Some(id)) => {
+                    // The parser already rejected unparseable periods
+                    let period = parse_since(since).unwrap_or(7 * 86_400);
+                    match manager.agent_report(id, period) {
+                        Ok(report) => SlashCommandResult::data(SlashCommandData::Text(report)),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+                _ => SlashCommandResult::error("No active workspace"),
             }
         }
 
@@ -1909,6 +5030,12 @@ fn execute_slash_command(
             }
             WorkspaceSubcommand::Create { name, path } => {
                 if let Some(manager) = &state.workspace_manager {
+                    if let Some(existing) = manager.find_by_path(path) {
+                        return SlashCommandResult::action(UiAction::OfferExistingWorkspace {
+                            id: existing.id.to_string(),
+                            name: existing.name,
+                        });
+                    }
                     match manager.create_workspace(name, path.clone()) {
                         Ok(ws) => SlashCommandResult::success(format!("Created workspace: {}", ws.name)),
                         Err(e) => SlashCommandResult::error(format!("Failed to create workspace: {}", e)),
@@ -1917,6 +5044,76 @@ fn execute_slash_command(
                     SlashCommandResult::error("Workspace manager not initialized")
                 }
             }
+            WorkspaceSubcommand::Dedupe => match &state.workspace_manager {
+                Some(manager) => match manager.merge_duplicate_workspaces() {
+                    Ok(merged) => SlashCommandResult::data(SlashCommandData::Text(
+                        axiom_core::merge_summary(&merged),
+                    )),
+                    Err(e) => {
+                        SlashCommandResult::error(format!("Failed to merge workspaces: {}", e))
+                    }
+                },
+                None => SlashCommandResult::error("Workspace manager not initialized"),
+            },
+            WorkspaceSubcommand::Archived => match &state.workspace_manager {
+                Some(manager) => {
+                    let workspaces: Vec<axiom_core::WorkspaceInfo> = manager
+                        .archived_workspaces()
+                        .iter()
+                        .map(|w| axiom_core::WorkspaceInfo {
+                            id: w.id.to_string(),
+                            name: w.name.clone(),
+                            path: w.path.to_string_lossy().to_string(),
+                            is_active: false,
+                        })
+                        .collect();
+                    SlashCommandResult::data(SlashCommandData::WorkspaceList(workspaces))
+                }
+                None => SlashCommandResult::error("Workspace manager not initialized"),
+            },
+            WorkspaceSubcommand::Archive { id } | WorkspaceSubcommand::Restore { id } => {
+                let archive = matches!(sub, WorkspaceSubcommand::Archive { .. });
+                let Some(manager) = &state.workspace_manager else {
+                    return SlashCommandResult::error("Workspace manager not initialized");
+                };
+                let workspace = match manager.resolve_workspace(id) {
+                    Ok(ws) => ws,
+                    Err(e) => return SlashCommandResult::error(e.to_string()),
+                };
+                if archive && state.active_workspace_id == Some(workspace.id) {
+                    return SlashCommandResult::error(
+                        "Switch to another workspace before archiving this one",
+                    );
+                }
+                match manager.set_workspace_archived(workspace.id, archive) {
+                    Ok(ws) if archive => SlashCommandResult::success(format!(
+                        "Archived workspace: {} (/workspace restore brings it back)",
+                        ws.name
+                    )),
+                    Ok(ws) => {
+                        SlashCommandResult::success(format!("Restored workspace: {}", ws.name))
+                    }
+                    Err(e) => SlashCommandResult::error(e.to_string()),
+                }
+            }
+            WorkspaceSubcommand::Purge { id, confirm } => match &state.workspace_manager {
+                Some(manager) => {
+                    let result = manager.resolve_workspace(id).and_then(|ws| {
+                        if *confirm {
+                            manager.purge_workspace(ws.id)
+                        } else {
+                            manager.purge_plan(ws.id)
+                        }
+                    });
+                    match result {
+                        Ok(plan) => SlashCommandResult::data(SlashCommandData::Text(
+                            axiom_core::purge_summary(&plan, *confirm),
+                        )),
+                        Err(e) => SlashCommandResult::error(e.to_string()),
+                    }
+                }
+                None => SlashCommandResult::error("Workspace manager not initialized"),
+            },
         },
 
         SlashCommand::Model(sub) => match sub {
@@ -1928,6 +5125,9 @@ fn execute_slash_command(
                 // For now, open the model selector - direct setting requires more work
                 SlashCommandResult::action(UiAction::OpenModelSelector)
             }
+            ModelSubcommand::Assign { .. } => {
+                SlashCommandResult::error("/model set <role> needs the config")
+            }
             ModelSubcommand::Current => {
                 let registry = panels.llm_registry.read();
                 if let Some(provider) = registry.active() {
@@ -1951,6 +5151,24 @@ fn execute_slash_command(
             }
         },
 
+        // Started as agents by `start_gen_tests`, `start_gen_docs` and `start_fix`
+        SlashCommand::GenTests { .. } => SlashCommandResult::error("/gen-tests runs as an agent"),
+        SlashCommand::Fix { .. } => SlashCommandResult::error("/fix runs as an agent"),
+        SlashCommand::GenDocs { .. } => SlashCommandResult::error("/gen-docs runs as an agent"),
+        // Need the Conductor; handled by `write_run_document`, `session_command`,
+        // `plan_command` and `inspect_command`
+        SlashCommand::RunDoc { .. } => SlashCommandResult::error("/run-doc needs the Conductor"),
+        SlashCommand::Session(_) => SlashCommandResult::error("/session needs the Conductor"),
+        SlashCommand::Plan(_) => SlashCommandResult::error("/plan needs the Conductor"),
+        SlashCommand::Inspect(_) => SlashCommandResult::error("/inspect needs the Conductor"),
+
+        SlashCommand::Refactor(sub) => {
+            match (&state.workspace_manager, state.active_workspace_id) {
+                (Some(manager), Some(id)) => refactor_command(manager, id, sub),
+                _ => SlashCommandResult::error("No active workspace"),
+            }
+        }
+
         SlashCommand::Custom { name, args: _ } => {
             // Custom commands are not supported yet
             SlashCommandResult::error(format!("Unknown command: /{}", name))
@@ -1980,7 +5198,7 @@ fn handle_slash_result(
         SlashCommandResult::UiAction(action) => {
             match action {
                 UiAction::OpenSettings => {
-                    panels.open_settings(config);
+                    panels.open_settings(config, state.workspace_defaults());
                     state.input_mode.open_modal("settings");
                 }
                 UiAction::OpenModelSelector => {
@@ -1989,13 +5207,64 @@ fn handle_slash_result(
                 }
                 UiAction::OpenWorkspaceSelector => {
                     if let Some(manager) = &state.workspace_manager {
-                        let workspaces = manager.list_workspaces();
-                        panels.open_workspace_selector(workspaces, state.active_workspace_id);
+                        panels.open_workspace_selector(manager, state.active_workspace_id);
                         state.input_mode.open_modal("workspace_selector");
                     } else {
                         state.error("Workspace manager not initialized");
                     }
                 }
+                UiAction::OpenActivityFeed => {
+                    match (&state.workspace_manager, state.active_workspace()) {
+                        (Some(manager), Some(workspace)) => {
+                            match panels.open_activity_feed(manager, &workspace) {
+                                Ok(()) => state.input_mode.open_modal("activity_feed"),
+                                Err(e) => state.error(format!("Failed to load activity: {}", e)),
+                            }
+                        }
+                        _ => state.error("No active workspace"),
+                    }
+                }
+                UiAction::OpenReviewQueue => {
+                    match (&state.workspace_manager, state.active_workspace()) {
+                        (Some(manager), Some(workspace)) => {
+                            panels.open_review_queue(manager, &workspace);
+                            state.input_mode.open_modal("review_queue");
+                        }
+                        _ => state.error("No active workspace"),
+                    }
+                }
+                UiAction::OpenSearch {
+                    query,
+                    regex,
+                    globs,
+                } => open_search(state, panels, query, regex, globs),
+                UiAction::OpenReplace {
+                    query,
+                    replacement,
+                    regex,
+                    globs,
+                    apply,
+                } => {
+                    let query = SearchQuery {
+                        regex,
+                        globs,
+                        ..SearchQuery::new(query)
+                    };
+                    open_replace(state, panels, query, replacement, apply);
+                }
+                UiAction::OpenDirectoryPicker => {
+                    panels.open_directory_picker(state.cwd.clone(), &config.directory_picker);
+                    state.input_mode.open_modal("directory_picker");
+                }
+                UiAction::OfferExistingWorkspace { id, .. } => {
+                    let existing = id
+                        .parse()
+                        .ok()
+                        .and_then(|id| state.workspace_manager.as_ref()?.get_workspace(id));
+                    if let Some(workspace) = existing {
+                        offer_existing_workspace(&workspace.path, state, panels);
+                    }
+                }
                 UiAction::ClearOutput => {
                     panels.clear_output();
                     state.info("Output cleared");
@@ -1,14 +1,25 @@
 //! Agents panel for displaying active/completed agents
 //!
 //! Shows the list of spawned agents with their status,
-//! allowing users to select and view agent output.
+//! allowing users to select and view agent output, and to cancel the
+//! selected agent (`x`) or every running agent (`X`).
+//!
+//! Enter or a right-click opens the selected agent's action menu: view
+//! output, cancel, re-run, export, copy last message and open touched
+//! files. Each action also runs straight from its key, menu open or not.
+//!
+//! Agents a Conductor run started fold under it a while after they finish
+//! (`[agents] collapse_after_secs`); Space on the Conductor, or on one of its
+//! agents, shows or folds them again.
 
-use crate::agents::AgentRegistry;
+use crate::agents::{Agent, AgentRegistry, AgentStatus};
+use crate::config::AgentsConfig;
 use crate::core::Result;
 use crate::events::Event;
 use crate::panels::Panel;
 use crate::state::{AgentId, AppState, OutputContext, PanelId};
 use crate::ui::theme::theme;
+use axiom_core::{AgentAction, ServiceHealth};
 use crossbeam_channel::Sender;
 use crossterm::event::{KeyCode, MouseButton, MouseEventKind};
 use parking_lot::RwLock;
@@ -16,11 +27,16 @@ use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, Clear, List, ListItem},
     Frame,
 };
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Spinner frame duration for running agents
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
 
 /// Agents panel showing spawned agents
 pub struct AgentsPanel {
@@ -44,6 +60,22 @@ pub struct AgentsPanel {
 
     /// Agent IDs in display order (cached for mouse click handling)
     agent_ids: RefCell<Vec<AgentId>>,
+
+    /// Highlighted row of the open action menu, an index into
+    /// [`AgentAction::ALL`]
+    menu: Option<usize>,
+
+    /// Action menu area for mouse detection
+    menu_area: RefCell<Rect>,
+
+    /// How long after finishing a child agent folds under its parent
+    collapse_after: Duration,
+
+    /// Parents whose folded children are shown anyway
+    expanded: HashSet<AgentId>,
+
+    /// When the panel was last rendered (drives spinner animation)
+    last_render: Instant,
 }
 
 impl AgentsPanel {
@@ -57,12 +89,70 @@ impl AgentsPanel {
             visible_height: 20,
             list_area: RefCell::new(Rect::default()),
             agent_ids: RefCell::new(Vec::new()),
+            menu: None,
+            menu_area: RefCell::new(Rect::default()),
+            collapse_after: Duration::from_secs(AgentsConfig::default().collapse_after_secs),
+            expanded: HashSet::new(),
+            last_render: Instant::now(),
         }
     }
 
-    /// Get the number of agents
+    /// List the agents of `registry` instead
+    pub fn set_registry(&mut self, registry: Arc<RwLock<AgentRegistry>>) {
+        self.registry = registry;
+        self.selected_index = 0;
+        self.scroll_offset = 0;
+        self.menu = None;
+        self.expanded.clear();
+    }
+
+    /// Fold finished child agents after `config.collapse_after_secs`
+    pub fn set_retention(&mut self, config: &AgentsConfig) {
+        self.collapse_after = Duration::from_secs(config.collapse_after_secs);
+    }
+
+    /// Whether `agent` is folded under its parent: a child that finished
+    /// `collapse_after` ago or more, of a parent that isn't expanded
+    fn folded(&self, agent: &Agent, now: Instant) -> bool {
+        agent
+            .parent_id
+            .is_some_and(|parent| !self.expanded.contains(&parent))
+            && Self::foldable(agent, self.collapse_after, now)
+    }
+
+    /// Whether `agent` is a child that finished `after` ago or more
+    fn foldable(agent: &Agent, after: Duration, now: Instant) -> bool {
+        agent.parent_id.is_some()
+            && agent.status.is_terminal()
+            && agent
+                .completed_at
+                .is_none_or(|done| now.duration_since(done) >= after)
+    }
+
+    /// Get the number of agents shown
     fn agent_count(&self) -> usize {
-        self.registry.read().len()
+        let now = Instant::now();
+        self.registry
+            .read()
+            .agents()
+            .filter(|a| !self.folded(a, now))
+            .count()
+    }
+
+    /// Show or fold the finished children of the selected agent, or of its
+    /// parent when it is a child
+    fn toggle_expanded(&mut self) {
+        let Some(id) = self.selected_id() else {
+            return;
+        };
+        let parent = self
+            .registry
+            .read()
+            .get(id)
+            .map_or(id, |agent| agent.parent_id.unwrap_or(id));
+        if !self.expanded.remove(&parent) {
+            self.expanded.insert(parent);
+        }
     }
 
     /// Move selection up
@@ -132,6 +222,149 @@ impl AgentsPanel {
         }
     }
 
+    /// The selected agent, if any
+    fn selected_id(&self) -> Option<AgentId> {
+        self.agent_ids.borrow().get(self.selected_index).copied()
+    }
+
+    /// Whether the selected agent is at work, so can be cancelled but not
+    /// re-run (an idle Conductor can be re-run)
+    fn selected_running(&self) -> bool {
+        self.selected_id().is_some_and(|id| {
+            self.registry.read().get(id).is_some_and(|agent| {
+                matches!(agent.status, AgentStatus::Pending | AgentStatus::Running)
+            })
+        })
+    }
+
+    /// Whether the action menu is open, taking Esc to close
+    pub fn takes_esc(&self) -> bool {
+        self.menu.is_some()
+    }
+
+    /// Open the selected agent's action menu
+    fn open_menu(&mut self) {
+        if self.selected_id().is_some() {
+            self.menu = Some(0);
+        }
+    }
+
+    /// Move the menu highlight by `delta` rows, wrapping around
+    fn move_menu(&mut self, delta: isize) {
+        if let Some(row) = self.menu {
+            let len = AgentAction::ALL.len() as isize;
+            self.menu = Some((row as isize + delta).rem_euclid(len) as usize);
+        }
+    }
+
+    /// Run `action` on the selected agent and close the menu; actions that
+    /// don't apply to it are ignored
+    fn run_action(&mut self, action: AgentAction) {
+        if !action.available(self.selected_running()) {
+            return;
+        }
+        if let Some(id) = self.selected_id() {
+            let _ = self.event_tx.send(Event::AgentAction { id, action });
+        }
+        self.menu = None;
+    }
+
+    /// Keys while the action menu is open
+    fn menu_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.move_menu(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_menu(1),
+            KeyCode::Enter => {
+                if let Some(row) = self.menu {
+                    self.run_action(AgentAction::ALL[row]);
+                }
+            }
+            KeyCode::Esc => self.menu = None,
+            KeyCode::Char(c) => {
+                if let Some(action) = AgentAction::from_key(c) {
+                    self.run_action(action);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Click at `row` inside the action menu; returns whether it was inside
+    fn menu_click(&mut self, column: u16, row: u16) -> bool {
+        let area = *self.menu_area.borrow();
+        let inside = column >= area.x
+            && column < area.x + area.width
+            && row >= area.y
+            && row < area.y + area.height;
+        if !inside {
+            return false;
+        }
+        // Past the border, one row per action
+        let index = (row - area.y).checked_sub(1).map(usize::from);
+        if let Some(action) = index.and_then(|i| AgentAction::ALL.get(i)) {
+            self.menu = Some(index.unwrap_or_default());
+            self.run_action(*action);
+        }
+        true
+    }
+
+    /// Draw the action menu under the selected agent, or above it when it
+    /// doesn't fit
+    fn render_menu(&self, frame: &mut Frame, list: Rect, row: usize, running: bool) {
+        let t = theme();
+        let height = (AgentAction::ALL.len() + 2) as u16;
+        let width = list.width.min(24);
+        if list.height < height || width < 8 {
+            *self.menu_area.borrow_mut() = Rect::default();
+            return;
+        }
+        let entry_y = list.y + (row.saturating_sub(self.scroll_offset) * 2) as u16;
+        let below = entry_y + 2;
+        let y = if below + height <= list.y + list.height {
+            below
+        } else {
+            entry_y.saturating_sub(height).max(list.y)
+        };
+        let area = Rect::new(list.x + list.width - width, y, width, height);
+        *self.menu_area.borrow_mut() = area;
+
+        let items: Vec<ListItem> = AgentAction::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let available = action.available(running);
+                let style = if !available {
+                    Style::default().fg(t.text_muted)
+                } else if self.menu == Some(i) {
+                    Style::default()
+                        .fg(t.text_primary)
+                        .bg(t.bg_selection)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(t.text_primary)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!(" {} ", action.key()),
+                        if available {
+                            style.fg(t.status_info)
+                        } else {
+                            style
+                        },
+                    ),
+                    Span::styled(action.label(), style),
+                ]))
+                .style(style)
+            })
+            .collect();
+        let block = Block::default()
+            .title(" Actions ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_focused));
+        frame.render_widget(Clear, area);
+        frame.render_widget(List::new(items).block(block), area);
+    }
+
     /// Format duration for display
     fn format_duration(secs: u64) -> String {
         if secs < 60 {
@@ -203,6 +436,10 @@ impl Panel for AgentsPanel {
 
     fn handle_input(&mut self, event: &Event, _state: &mut AppState) -> Result<bool> {
         match event {
+            Event::Key(key) if self.menu.is_some() => {
+                self.menu_key(key.code);
+                Ok(true)
+            }
             Event::Key(key) => match key.code {
                 KeyCode::Up | KeyCode::Char('k') => {
                     self.select_prev();
@@ -214,6 +451,15 @@ impl Panel for AgentsPanel {
                 }
                 KeyCode::Enter => {
                     self.notify_selection_change();
+                    self.open_menu();
+                    Ok(true)
+                }
+                KeyCode::Char('X') => {
+                    let _ = self.event_tx.send(Event::AgentCancel(None));
+                    Ok(true)
+                }
+                KeyCode::Char(' ') => {
+                    self.toggle_expanded();
                     Ok(true)
                 }
                 KeyCode::Home => {
@@ -231,12 +477,28 @@ impl Panel for AgentsPanel {
                     }
                     Ok(true)
                 }
+                KeyCode::Char(c) => match AgentAction::from_key(c) {
+                    Some(action) => {
+                        self.run_action(action);
+                        Ok(true)
+                    }
+                    None => Ok(false),
+                },
                 _ => Ok(false),
             },
             Event::Mouse(mouse) => {
                 match mouse.kind {
                     MouseEventKind::Down(MouseButton::Left) => {
+                        if self.menu.is_some() && self.menu_click(mouse.column, mouse.row) {
+                            return Ok(true);
+                        }
+                        self.menu = None;
+                        self.select_at(mouse.row);
+                        Ok(true)
+                    }
+                    MouseEventKind::Down(MouseButton::Right) => {
                         self.select_at(mouse.row);
+                        self.open_menu();
                         Ok(true)
                     }
                     MouseEventKind::ScrollUp => {
@@ -256,6 +518,7 @@ impl Panel for AgentsPanel {
     }
 
     fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool) {
+        self.last_render = Instant::now();
         let t = theme();
         let border_style = if focused {
             Style::default().fg(t.border_focused)
@@ -266,6 +529,8 @@ impl Panel for AgentsPanel {
         let registry = self.registry.read();
         let running = registry.running_count();
         let total = registry.len();
+        let now = Instant::now();
+        let selected = self.agent_ids.borrow().get(self.selected_index).copied();
 
         let title = if running > 0 {
             format!(" Agents ({}/{}) ", running, total)
@@ -286,11 +551,24 @@ impl Panel for AgentsPanel {
         // Build list items and cache IDs
         let mut items: Vec<ListItem> = Vec::new();
         let mut ids: Vec<AgentId> = Vec::new();
+        let mut selected_running = false;
+
+        let shown: Vec<&Agent> = registry.agents().filter(|a| !self.folded(a, now)).collect();
+        // Keep the selected agent selected as others fold away
+        if let Some(index) = selected.and_then(|id| shown.iter().position(|a| a.id == id)) {
+            self.selected_index = index;
+        } else if self.selected_index >= shown.len() {
+            self.selected_index = shown.len().saturating_sub(1);
+        }
 
-        for (idx, agent) in registry.agents().enumerate() {
+        for (idx, agent) in shown.iter().enumerate() {
             ids.push(agent.id);
 
             let is_selected = idx == self.selected_index;
+            if is_selected {
+                selected_running =
+                    matches!(agent.status, AgentStatus::Pending | AgentStatus::Running);
+            }
 
             // For running agents, use live elapsed time; for completed, use fixed run duration
             let (elapsed_ms, display_secs) = if agent.status.is_terminal() {
@@ -359,10 +637,45 @@ impl Panel for AgentsPanel {
                 format!("  ⏱{} ◇{} {}", duration, tokens, lines)
             };
 
-            let line2 = Line::from(Span::styled(
-                stats_parts,
-                Style::default().fg(t.text_muted),
-            ));
+            // Finished children, folded or shown
+            let finished = registry
+                .agents()
+                .filter(|a| {
+                    a.parent_id == Some(agent.id) && Self::foldable(a, self.collapse_after, now)
+                })
+                .count();
+            let stats_parts = match finished {
+                0 => stats_parts,
+                n if self.expanded.contains(&agent.id) => format!("{} ▾{} done", stats_parts, n),
+                n => format!("{} ▸{} done", stats_parts, n),
+            };
+
+            // Services show health, uptime and restarts instead of a progress bar
+            let line2 = match (&agent.service, agent.status.is_running()) {
+                (Some(service), true) => {
+                    let (dot, color) = match service.health {
+                        ServiceHealth::Healthy => ("●", t.status_success),
+                        ServiceHealth::Unhealthy => ("●", t.status_error),
+                        ServiceHealth::Unknown => ("○", t.text_muted),
+                    };
+                    let uptime = Self::format_duration(service.started_at.elapsed().as_secs());
+                    let mut spans = vec![
+                        Span::styled(format!("  {} ", dot), Style::default().fg(color)),
+                        Span::styled(format!("up {}", uptime), Style::default().fg(t.text_muted)),
+                    ];
+                    if service.restarts > 0 {
+                        spans.push(Span::styled(
+                            format!(" ↻{}", service.restarts),
+                            Style::default().fg(t.status_warning),
+                        ));
+                    }
+                    Line::from(spans)
+                }
+                _ => Line::from(Span::styled(
+                    stats_parts,
+                    Style::default().fg(t.text_muted),
+                )),
+            };
 
             let item_style = if is_selected {
                 Style::default().bg(if focused {
@@ -388,6 +701,9 @@ impl Panel for AgentsPanel {
         } else {
             let list = List::new(items);
             frame.render_widget(list, inner);
+            if self.menu.is_some() {
+                self.render_menu(frame, inner, self.selected_index, selected_running);
+            }
         }
     }
 
@@ -395,6 +711,21 @@ impl Panel for AgentsPanel {
         // Each agent takes 2 lines, so divide by 2
         self.visible_height = (rows.saturating_sub(2) / 2) as usize;
     }
+
+    fn needs_redraw(&self) -> bool {
+        // Running agents animate a spinner, and finished ones fold away
+        if self.last_render.elapsed() < SPINNER_INTERVAL {
+            return false;
+        }
+        let now = Instant::now();
+        let registry = self.registry.read();
+        registry.agents().any(|a| a.status.is_running())
+            || self
+                .agent_ids
+                .borrow()
+                .iter()
+                .any(|id| registry.get(*id).is_some_and(|a| self.folded(a, now)))
+    }
 }
 
 #[cfg(test)]
@@ -490,6 +821,95 @@ mod tests {
         assert_eq!(panel.agent_count(), 0);
     }
 
+    fn key(code: KeyCode) -> Event {
+        Event::Key(crossterm::event::KeyEvent::from(code))
+    }
+
+    #[test]
+    fn test_action_menu() {
+        let registry = Arc::new(RwLock::new(AgentRegistry::new()));
+        let id = registry.write().spawn(crate::agents::AgentSpawnRequest {
+            agent_type: crate::agents::AgentType::Shell,
+            name: "Shell".to_string(),
+            description: "ls".to_string(),
+            parameters: Some("ls".to_string()),
+            parent_id: None,
+        });
+        registry.write().complete(id);
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut panel = AgentsPanel::new(registry, tx);
+        panel.agent_ids.borrow_mut().push(id);
+        let mut state = AppState::new();
+
+        panel
+            .handle_input(&key(KeyCode::Enter), &mut state)
+            .unwrap();
+        assert_eq!(panel.menu, Some(0));
+        assert!(panel.takes_esc());
+        panel.handle_input(&key(KeyCode::Up), &mut state).unwrap();
+        assert_eq!(panel.menu, Some(AgentAction::ALL.len() - 1));
+
+        // A finished agent can't be cancelled, so the menu stays open
+        panel
+            .handle_input(&key(KeyCode::Char('x')), &mut state)
+            .unwrap();
+        assert!(panel.menu.is_some());
+        panel
+            .handle_input(&key(KeyCode::Char('r')), &mut state)
+            .unwrap();
+        assert!(panel.menu.is_none());
+        let actions: Vec<AgentAction> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                Event::AgentAction { action, .. } => Some(action),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(actions, [AgentAction::Rerun]);
+
+        panel
+            .handle_input(&key(KeyCode::Enter), &mut state)
+            .unwrap();
+        panel.handle_input(&key(KeyCode::Esc), &mut state).unwrap();
+        assert!(!panel.takes_esc());
+    }
+
+    #[test]
+    fn test_finished_children_fold() {
+        let registry = Arc::new(RwLock::new(AgentRegistry::new()));
+        let spawn = |agent_type, parent_id| {
+            registry.write().spawn(crate::agents::AgentSpawnRequest {
+                agent_type,
+                name: "Agent".to_string(),
+                description: String::new(),
+                parameters: None,
+                parent_id,
+            })
+        };
+        let conductor = spawn(crate::agents::AgentType::Conductor, None);
+        let shell = spawn(crate::agents::AgentType::Shell, Some(conductor));
+        let running = spawn(crate::agents::AgentType::Shell, Some(conductor));
+        registry.write().start(running);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut panel = AgentsPanel::new(registry.clone(), tx);
+        panel.set_retention(&AgentsConfig {
+            collapse_after_secs: 0,
+            keep_runs: 3,
+        });
+        assert_eq!(panel.agent_count(), 3);
+
+        // Finished children fold, running ones stay
+        registry.write().complete(shell);
+        assert_eq!(panel.agent_count(), 2);
+        panel.agent_ids.borrow_mut().push(conductor);
+        let mut state = AppState::new();
+        let space = key(KeyCode::Char(' '));
+        panel.handle_input(&space, &mut state).unwrap();
+        assert_eq!(panel.agent_count(), 3);
+        panel.handle_input(&space, &mut state).unwrap();
+        assert_eq!(panel.agent_count(), 2);
+    }
+
     #[test]
     fn test_agents_panel_ensure_visible() {
         let registry = Arc::new(RwLock::new(AgentRegistry::new()));
@@ -0,0 +1,255 @@
+//! Code actions on the editor selection
+//!
+//! Alt+Enter with a selection opens a small menu ("explain", "refactor",
+//! "add tests"). The chosen action turns the selected code, its location and
+//! the lines around it into a prompt for the Conductor, whose answer streams
+//! into its agent view.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::path::PathBuf;
+
+/// Lines of surrounding code sent on each side of the selection
+pub const CONTEXT_LINES: usize = 15;
+
+/// An action offered for the selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeAction {
+    Explain,
+    Refactor,
+    AddTests,
+}
+
+impl CodeAction {
+    /// Menu order
+    pub const ALL: [CodeAction; 3] = [
+        CodeAction::Explain,
+        CodeAction::Refactor,
+        CodeAction::AddTests,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CodeAction::Explain => "Explain selection",
+            CodeAction::Refactor => "Refactor selection",
+            CodeAction::AddTests => "Add tests for selection",
+        }
+    }
+
+    /// Key that picks the action directly
+    pub fn shortcut(self) -> char {
+        match self {
+            CodeAction::Explain => 'e',
+            CodeAction::Refactor => 'r',
+            CodeAction::AddTests => 't',
+        }
+    }
+
+    /// What the Conductor is asked to do with the code
+    fn instructions(self) -> &'static str {
+        match self {
+            CodeAction::Explain => {
+                "Explain what the selected code does, step by step. Point out \
+                 anything surprising or error-prone. Don't change any files."
+            }
+            CodeAction::Refactor => {
+                "Refactor the selected code for readability and maintainability \
+                 without changing its behaviour. Keep the surrounding code and \
+                 public interfaces intact, apply the change to the file and \
+                 summarize what you changed."
+            }
+            CodeAction::AddTests => {
+                "Write tests for the selected code, covering normal use, edge \
+                 cases and error paths. Follow the test conventions already used \
+                 in this project and put the tests where this project keeps them."
+            }
+        }
+    }
+}
+
+/// The selected code and where it sits
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectionContext {
+    pub path: Option<PathBuf>,
+    /// Syntax name, e.g. "Rust"
+    pub language: String,
+    /// First selected line (1-based)
+    pub start_line: usize,
+    /// Last selected line (1-based)
+    pub end_line: usize,
+    pub code: String,
+    /// Lines just before the selection
+    pub before: Vec<String>,
+    /// Lines just after the selection
+    pub after: Vec<String>,
+}
+
+/// Prompt sent to the Conductor for `action` on the selection
+pub fn action_prompt(action: CodeAction, ctx: &SelectionContext) -> String {
+    let file = ctx
+        .path
+        .as_ref()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "an unsaved buffer".to_string());
+    let fence = |lines: &str| format!("```{}\n{}\n```", ctx.language.to_lowercase(), lines);
+
+    let mut prompt = format!(
+        "{}\n\nSelection from {} (lines {}-{}, {}):\n\n{}",
+        action.instructions(),
+        file,
+        ctx.start_line,
+        ctx.end_line,
+        ctx.language,
+        fence(&ctx.code)
+    );
+    if !ctx.before.is_empty() {
+        prompt.push_str("\n\nCode just before the selection:\n\n");
+        prompt.push_str(&fence(&ctx.before.join("\n")));
+    }
+    if !ctx.after.is_empty() {
+        prompt.push_str("\n\nCode just after the selection:\n\n");
+        prompt.push_str(&fence(&ctx.after.join("\n")));
+    }
+    prompt
+}
+
+/// Result of a key press in the menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    None,
+    Cancelled,
+    Chosen(CodeAction),
+}
+
+/// Code action menu state
+#[derive(Debug, Default)]
+pub struct ActionMenu {
+    selected: usize,
+}
+
+impl ActionMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn selected(&self) -> CodeAction {
+        CodeAction::ALL[self.selected]
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> MenuAction {
+        let count = CodeAction::ALL.len();
+        match key.code {
+            KeyCode::Esc => MenuAction::Cancelled,
+            KeyCode::Enter => MenuAction::Chosen(self.selected()),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = (self.selected + count - 1) % count;
+                MenuAction::None
+            }
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => {
+                self.selected = (self.selected + 1) % count;
+                MenuAction::None
+            }
+            KeyCode::Char(c) => CodeAction::ALL
+                .into_iter()
+                .find(|a| a.shortcut() == c.to_ascii_lowercase())
+                .map_or(MenuAction::None, MenuAction::Chosen),
+            _ => MenuAction::None,
+        }
+    }
+
+    /// Render the menu at (`x`, `y`), kept inside `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, x: u16, y: u16) {
+        let width = (CodeAction::ALL
+            .iter()
+            .map(|a| a.label().len())
+            .max()
+            .unwrap_or(0)
+            + 8) as u16;
+        let width = width.min(area.width);
+        let height = (CodeAction::ALL.len() as u16 + 2).min(area.height);
+        let x = x.min(area.x + area.width.saturating_sub(width));
+        let y = y.min(area.y + area.height.saturating_sub(height));
+        let menu_area = Rect::new(x, y, width, height);
+
+        frame.render_widget(Clear, menu_area);
+
+        let lines: Vec<Line> = CodeAction::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let style = if i == self.selected {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    Span::styled(format!(" {} ", action.shortcut()), style.fg(Color::Yellow)),
+                    Span::styled(format!("{} ", action.label()), style),
+                ])
+            })
+            .collect();
+
+        let block = Block::default()
+            .title(" Ask agent ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        frame.render_widget(Paragraph::new(lines).block(block), menu_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_action_prompt() {
+        let ctx = SelectionContext {
+            path: Some(PathBuf::from("src/pager.rs")),
+            language: "Rust".to_string(),
+            start_line: 3,
+            end_line: 4,
+            code: "let end = start + len;\npage(end)".to_string(),
+            before: vec!["fn show() {".to_string()],
+            after: Vec::new(),
+        };
+        let prompt = action_prompt(CodeAction::AddTests, &ctx);
+        assert!(prompt.starts_with("Write tests for the selected code"));
+        assert!(prompt.contains(
+            "Selection from src/pager.rs (lines 3-4, Rust):\n\n```rust\nlet end = start + len;\npage(end)\n```"
+        ));
+        assert!(prompt.contains("Code just before the selection:\n\n```rust\nfn show() {\n```"));
+        assert!(!prompt.contains("just after"));
+    }
+
+    #[test]
+    fn test_menu_keys() {
+        let mut menu = ActionMenu::new();
+        assert_eq!(menu.handle_key(key(KeyCode::Up)), MenuAction::None);
+        assert_eq!(menu.selected(), CodeAction::AddTests);
+        assert_eq!(menu.handle_key(key(KeyCode::Down)), MenuAction::None);
+        assert_eq!(
+            menu.handle_key(key(KeyCode::Enter)),
+            MenuAction::Chosen(CodeAction::Explain)
+        );
+        assert_eq!(
+            menu.handle_key(key(KeyCode::Char('R'))),
+            MenuAction::Chosen(CodeAction::Refactor)
+        );
+        assert_eq!(menu.handle_key(key(KeyCode::Char('x'))), MenuAction::None);
+        assert_eq!(menu.handle_key(key(KeyCode::Esc)), MenuAction::Cancelled);
+    }
+}
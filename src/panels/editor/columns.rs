@@ -0,0 +1,83 @@
+//! Cursor columns versus screen columns
+//!
+//! The cursor column counts chars into the line, but the cursor steps over
+//! whole grapheme clusters (a flag, an emoji with a skin tone, a letter with
+//! combining accents) and is drawn at the display width of the text before
+//! it, where CJK characters and most emoji take two cells.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Byte offset of char column `col`, or the line's length past its end
+pub fn byte_offset(line: &str, col: usize) -> usize {
+    line.char_indices().nth(col).map_or(line.len(), |(i, _)| i)
+}
+
+/// Cells taken by the first `col` chars of `line`
+pub fn width_before(line: &str, col: usize) -> usize {
+    line[..byte_offset(line, col)].width()
+}
+
+/// Column of the grapheme drawn at screen column `x`, or of the line's end
+///
+/// A position inside a wide character lands on its start.
+pub fn column_at(line: &str, x: usize) -> usize {
+    let (mut col, mut width) = (0, 0);
+    for grapheme in line.graphemes(true) {
+        width += grapheme.width();
+        if width > x {
+            break;
+        }
+        col += grapheme.chars().count();
+    }
+    col
+}
+
+/// Column of the grapheme boundary before `col`
+pub fn prev_boundary(line: &str, col: usize) -> usize {
+    let before = &line[..byte_offset(line, col)];
+    before.graphemes(true).next_back().map_or(0, |grapheme| {
+        before.chars().count() - grapheme.chars().count()
+    })
+}
+
+/// Column of the grapheme boundary after `col`, or `col` at the line's end
+pub fn next_boundary(line: &str, col: usize) -> usize {
+    let byte = byte_offset(line, col);
+    let at = line[..byte].chars().count();
+    line[byte..]
+        .graphemes(true)
+        .next()
+        .map_or(at, |grapheme| at + grapheme.chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wide_and_clustered_text() {
+        // "日本" is two cells each; the family emoji is five chars joined by ZWJs
+        let line = "a日本👨‍👩‍👧b";
+        assert_eq!(width_before(line, 1), 1);
+        assert_eq!(width_before(line, 3), 5);
+        assert_eq!(width_before(line, 8), 7);
+
+        assert_eq!(column_at(line, 2), 1);
+        assert_eq!(column_at(line, 3), 2);
+        assert_eq!(column_at(line, 5), 3);
+        assert_eq!(column_at(line, 7), 8);
+        assert_eq!(column_at(line, 40), 9);
+
+        assert_eq!(next_boundary(line, 3), 8);
+        assert_eq!(prev_boundary(line, 8), 3);
+        assert_eq!(prev_boundary(line, 0), 0);
+        assert_eq!(next_boundary(line, 9), 9);
+
+        // e + combining acute accent is one step
+        let accented = "ne\u{301}e";
+        assert_eq!(next_boundary(accented, 1), 3);
+        assert_eq!(prev_boundary(accented, 3), 1);
+        assert_eq!(width_before(accented, 3), 2);
+    }
+}
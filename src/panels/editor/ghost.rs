@@ -0,0 +1,135 @@
+//! Ghost-text completions in the editor
+//!
+//! Typing arms a debounce timer; once the editor has been idle for the
+//! configured time, a fill-in-the-middle request goes out. Requests are
+//! numbered: any key press bumps the number, so an answer that arrives after
+//! the user moved on is dropped instead of shown.
+
+use std::time::{Duration, Instant};
+
+/// A completion shown after the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ghost {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+/// Debounce and cancellation of completion requests
+#[derive(Debug, Default)]
+pub struct GhostState {
+    /// Number of the current request
+    generation: u64,
+    /// Last edit, while a request is due
+    edited_at: Option<Instant>,
+    /// Cursor position of the request in flight
+    pending: Option<(usize, usize)>,
+    ghost: Option<Ghost>,
+}
+
+impl GhostState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The text changed: restart the debounce
+    pub fn edited(&mut self, now: Instant) {
+        self.cancel();
+        self.edited_at = Some(now);
+    }
+
+    /// Drop the ghost and any request in flight
+    pub fn cancel(&mut self) {
+        self.generation += 1;
+        self.edited_at = None;
+        self.pending = None;
+        self.ghost = None;
+    }
+
+    /// Number of the request to send for `cursor`, once idle for `debounce`
+    pub fn due(&mut self, now: Instant, debounce: Duration, cursor: (usize, usize)) -> Option<u64> {
+        let edited_at = self.edited_at?;
+        if now.duration_since(edited_at) < debounce {
+            return None;
+        }
+        self.edited_at = None;
+        self.pending = Some(cursor);
+        Some(self.generation)
+    }
+
+    /// An answer arrived; returns whether it is shown
+    pub fn receive(&mut self, generation: u64, text: String) -> bool {
+        if generation != self.generation {
+            return false;
+        }
+        let Some((line, column)) = self.pending.take() else {
+            return false;
+        };
+        if text.is_empty() {
+            return false;
+        }
+        self.ghost = Some(Ghost { line, column, text });
+        true
+    }
+
+    /// Whether a request waits for the debounce to run out
+    pub fn is_waiting(&self) -> bool {
+        self.edited_at.is_some()
+    }
+
+    /// Ghost text at `cursor`, if any
+    pub fn visible(&self, cursor: (usize, usize)) -> Option<&str> {
+        self.ghost
+            .as_ref()
+            .filter(|g| (g.line, g.column) == cursor)
+            .map(|g| g.text.as_str())
+    }
+
+    /// Take the ghost text to insert it
+    pub fn accept(&mut self) -> Option<String> {
+        let ghost = self.ghost.take()?;
+        self.cancel();
+        Some(ghost.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounce_and_stale_answers() {
+        let mut ghost = GhostState::new();
+        let start = Instant::now();
+        let debounce = Duration::from_millis(400);
+        let ms = |n| start + Duration::from_millis(n);
+
+        assert_eq!(ghost.due(ms(1000), debounce, (0, 3)), None);
+        ghost.edited(start);
+        assert_eq!(ghost.due(ms(300), debounce, (0, 3)), None);
+        let first = ghost.due(ms(400), debounce, (0, 3)).unwrap();
+        assert_eq!(ghost.due(ms(500), debounce, (0, 3)), None);
+
+        // A keystroke while the request is out makes its answer stale
+        ghost.edited(ms(450));
+        assert!(!ghost.receive(first, "let".to_string()));
+        let second = ghost.due(ms(900), debounce, (0, 4)).unwrap();
+        assert!(ghost.receive(second, "x = 1;".to_string()));
+        assert_eq!(ghost.visible((0, 4)), Some("x = 1;"));
+        assert_eq!(ghost.visible((0, 3)), None);
+
+        assert_eq!(ghost.accept().as_deref(), Some("x = 1;"));
+        assert_eq!(ghost.visible((0, 4)), None);
+        assert_eq!(ghost.accept(), None);
+    }
+
+    #[test]
+    fn test_empty_answer_shows_nothing() {
+        let mut ghost = GhostState::new();
+        let start = Instant::now();
+        ghost.edited(start);
+        let n = ghost.due(start, Duration::ZERO, (2, 0)).unwrap();
+        assert!(!ghost.receive(n, String::new()));
+        assert_eq!(ghost.visible((2, 0)), None);
+    }
+}
@@ -1,24 +1,35 @@
 //! Syntax highlighting with syntect
 //!
 //! Provides efficient syntax highlighting with per-line caching.
+//!
+//! Syntect's syntax and theme sets take tens of milliseconds to load, so
+//! they're loaded once, on first use, and shared by every highlighter.
 
+use crate::ui::capabilities::capabilities;
 use crate::ui::theme::{current_variant, ThemeVariant};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use std::path::Path;
+use std::sync::OnceLock;
 use syntect::easy::HighlightLines;
 use syntect::highlighting::{FontStyle, ThemeSet};
 use syntect::parsing::SyntaxSet;
 
-/// Syntax highlighter with caching
-pub struct Highlighter {
-    /// Syntax definitions
-    syntax_set: SyntaxSet,
+/// Syntax definitions, loaded on first use
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
 
-    /// Color themes
-    theme_set: ThemeSet,
+/// Color themes, loaded on first use
+fn theme_set() -> &'static ThemeSet {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    THEMES.get_or_init(ThemeSet::load_defaults)
 }
 
+/// Syntax highlighter with caching
+pub struct Highlighter;
+
 impl Default for Highlighter {
     fn default() -> Self {
         Self::new()
@@ -28,10 +39,14 @@ impl Default for Highlighter {
 impl Highlighter {
     /// Create a new highlighter
     pub fn new() -> Self {
-        Self {
-            syntax_set: SyntaxSet::load_defaults_newlines(),
-            theme_set: ThemeSet::load_defaults(),
-        }
+        Self
+    }
+
+    /// Load the shared syntax and theme sets, e.g. on a background thread
+    /// so the first file opened doesn't wait for them
+    pub fn warm_up() {
+        syntax_set();
+        theme_set();
     }
 
     /// Get the appropriate syntect theme name based on the app theme
@@ -47,7 +62,7 @@ impl Highlighter {
         path.and_then(|p| p.extension())
             .and_then(|e| e.to_str())
             .and_then(|ext| {
-                self.syntax_set
+                syntax_set()
                     .find_syntax_by_extension(ext)
                     .map(|s| s.name.as_str())
             })
@@ -63,14 +78,13 @@ impl Highlighter {
         let syntax = file_path
             .and_then(|p| p.extension())
             .and_then(|e| e.to_str())
-            .and_then(|ext| self.syntax_set.find_syntax_by_extension(ext))
-            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+            .and_then(|ext| syntax_set().find_syntax_by_extension(ext))
+            .unwrap_or_else(|| syntax_set().find_syntax_plain_text());
 
-        let theme = self
-            .theme_set
+        let theme = theme_set()
             .themes
             .get(self.theme_name())
-            .unwrap_or_else(|| self.theme_set.themes.values().next().unwrap());
+            .unwrap_or_else(|| theme_set().themes.values().next().unwrap());
 
         let mut highlighter = HighlightLines::new(syntax, theme);
         let mut result = Vec::with_capacity(lines.len());
@@ -79,7 +93,7 @@ impl Highlighter {
             // Add newline for syntect's line-based parsing
             let line_with_newline = format!("{}\n", line);
             let highlighted = highlighter
-                .highlight_line(&line_with_newline, &self.syntax_set)
+                .highlight_line(&line_with_newline, syntax_set())
                 .unwrap_or_default();
 
             let styled: Vec<(String, Style)> = highlighted
@@ -110,7 +124,11 @@ impl Highlighter {
 
 /// Convert syntect style to ratatui style
 fn syntect_style_to_ratatui(style: syntect::highlighting::Style) -> Style {
-    let fg = Color::Rgb(style.foreground.r, style.foreground.g, style.foreground.b);
+    let fg = capabilities().adapt_color(Color::Rgb(
+        style.foreground.r,
+        style.foreground.g,
+        style.foreground.b,
+    ));
 
     let mut ratatui_style = Style::default().fg(fg);
 
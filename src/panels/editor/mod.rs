@@ -3,27 +3,53 @@
 //! Features:
 //! - Syntax highlighting via syntect
 //! - Git-style diff tracking for LLM modifications
-//! - Vim-style cursor movement
-//! - Multi-file tabs support
-//! - Text selection with Shift+Arrow keys
+//! - Vim-style cursor movement, `/` search with `n`/`N` and `:42` to go to
+//!   a line
+//! - Multi-file tabs support, with pinning (Alt+P), reopening closed tabs
+//!   (Ctrl+Shift+T) and a list of all tabs when they overflow (Alt+0)
+//! - Text selection with Shift+Arrow keys, double-click to select a word
+//! - Word-wise motions (Ctrl+Left/Right, `w`/`b`/`e`) and deletion
+//!   (Ctrl+Backspace/Delete)
 //! - Clipboard operations (Ctrl+C/X/V)
 //! - Undo/redo (Ctrl+Z/Y)
-
+//! - Code actions on the selection (Alt+Enter)
+//! - Ghost-text completions (Tab to accept, Esc to dismiss)
+//! - Saving (Ctrl+S, Ctrl+Alt+S for all tabs) without clobbering changes
+//!   made on disk since the file was opened
+//! - Language server diagnostics as gutter markers, with the message for
+//!   the cursor's line under the text
+
+mod actions;
+mod columns;
 mod diff;
+mod ghost;
 mod highlight;
+mod search;
 mod selection;
+mod tabs;
 mod undo;
 
+pub use actions::{action_prompt, ActionMenu, CodeAction, MenuAction, SelectionContext};
 pub use diff::DiffTracker;
+use ghost::GhostState;
 pub use highlight::Highlighter;
+use search::{LinePrompt, PromptKind, Seek};
 pub use selection::{Position, Selection};
+use tabs::{visible_tabs, ClosedTab, TabList, TabListAction, CLOSED_TABS_KEPT};
 pub use undo::{EditOp, UndoStack};
 
+use super::words;
+use crate::config::CompletionConfig;
 use crate::core::Result;
 use crate::events::Event;
+use crate::llm::fim::{self, FimRequest};
 use crate::state::{AppState, PanelId};
-use crate::ui::ScrollBar;
-use crossterm::event::{KeyCode, KeyModifiers};
+use crate::ui::controls::{hit, ClickTracker};
+use crate::ui::{capabilities, ScrollBar};
+use axiom_core::files::{modified_time, moved_path, write_atomic};
+use axiom_core::{Diagnostic, DiagnosticSeverity, LspManager};
+use crossbeam_channel::Sender;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Direction as LayoutDirection, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -32,7 +58,11 @@ use ratatui::{
     Frame,
 };
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use unicode_width::UnicodeWidthStr;
 
 /// Single file tab state
 pub struct FileTab {
@@ -56,6 +86,14 @@ pub struct FileTab {
     pub selection: Selection,
     /// Undo history
     pub undo_stack: UndoStack,
+    /// Modification time on disk when last opened, reloaded or saved
+    pub disk_mtime: Option<SystemTime>,
+    /// The file changed on disk while the tab had unsaved edits
+    pub changed_on_disk: bool,
+    /// Whether the file ends with a newline, kept when saving
+    pub trailing_newline: bool,
+    /// Pinned tabs sort first and aren't closed by Ctrl+W
+    pub pinned: bool,
 }
 
 impl FileTab {
@@ -72,6 +110,10 @@ impl FileTab {
             highlight_dirty: true,
             selection: Selection::new(),
             undo_stack: UndoStack::new(),
+            disk_mtime: None,
+            changed_on_disk: false,
+            trailing_newline: true,
+            pinned: false,
         }
     }
 
@@ -88,6 +130,10 @@ impl FileTab {
             highlight_dirty: true,
             selection: Selection::new(),
             undo_stack: UndoStack::new(),
+            disk_mtime: None,
+            changed_on_disk: false,
+            trailing_newline: true,
+            pinned: false,
         }
     }
 
@@ -100,10 +146,35 @@ impl FileTab {
             .unwrap_or_else(|| "[New]".to_string())
     }
 
+    /// Title in the tab bar and tab list
+    fn tab_title(&self) -> String {
+        let pin = if self.pinned { "📌" } else { "" };
+        let modified = if self.modified { "*" } else { "" };
+        format!(" {}{}{} ", pin, self.display_name(), modified)
+    }
+
     /// Get current line content
     fn current_line(&self) -> &str {
         self.lines.get(self.cursor.0).map(|s| s.as_str()).unwrap_or("")
     }
+
+    /// The text to write to disk
+    fn content(&self) -> String {
+        let mut content = self.lines.join("\n");
+        if self.trailing_newline {
+            content.push('\n');
+        }
+        content
+    }
+}
+
+/// A question on the editor's bottom row, taking all keys until answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SavePrompt {
+    /// The tab's file changed on disk since it was opened: overwrite or reload
+    Conflict(usize),
+    /// Closing a tab with unsaved changes: save or discard them
+    Close(usize),
 }
 
 /// Editor panel with multi-file tabs
@@ -118,10 +189,36 @@ pub struct EditorPanel {
     visible_height: usize,
     /// Tab bar area for mouse click detection (interior mutability for render)
     tab_bar_area: RefCell<Option<Rect>>,
-    /// Calculated tab boundaries (start_x, end_x) for each tab
-    tab_boundaries: RefCell<Vec<(u16, u16)>>,
+    /// Calculated boundaries (index, start_x, end_x) of each tab on show
+    tab_boundaries: RefCell<Vec<(usize, u16, u16)>>,
+    /// Button opening the tab list, while tabs overflow the bar
+    overflow_button: RefCell<Option<Rect>>,
+    /// List of all tabs, while open
+    tab_list: Option<TabList>,
+    /// Recently closed tabs, newest last
+    closed: Vec<ClosedTab>,
     /// Content area for scroll bar click detection
     content_area: RefCell<Rect>,
+    /// Code action menu, open after Alt+Enter on a selection
+    action_menu: Option<ActionMenu>,
+    /// Where code action prompts and completions are sent
+    event_tx: Option<Sender<Event>>,
+    /// Completion model, when completions are on for the workspace
+    completion: Option<CompletionConfig>,
+    /// Ghost-text completion state
+    ghost: GhostState,
+    /// Save conflict or unsaved-close question, while one is open
+    prompt: Option<SavePrompt>,
+    /// Search or go-to-line prompt, while one is open
+    line_prompt: Option<LinePrompt>,
+    /// Last searched pattern, whose matches stay highlighted
+    search: Option<String>,
+    /// Double-click detection on text positions
+    clicks: ClickTracker<(usize, usize)>,
+    /// Language servers files are synced to on open and save
+    lsp: Option<Arc<LspManager>>,
+    /// Latest diagnostics per file
+    diagnostics: HashMap<PathBuf, Vec<Diagnostic>>,
 }
 
 impl Default for EditorPanel {
@@ -140,10 +237,103 @@ impl EditorPanel {
             visible_height: 20,
             tab_bar_area: RefCell::new(None),
             tab_boundaries: RefCell::new(Vec::new()),
+            overflow_button: RefCell::new(None),
+            tab_list: None,
+            closed: Vec::new(),
             content_area: RefCell::new(Rect::default()),
+            action_menu: None,
+            event_tx: None,
+            completion: None,
+            ghost: GhostState::new(),
+            prompt: None,
+            line_prompt: None,
+            search: None,
+            clicks: ClickTracker::new(),
+            lsp: None,
+            diagnostics: HashMap::new(),
         }
     }
 
+    /// Send code action prompts to the Conductor through `event_tx`
+    pub fn set_event_tx(&mut self, event_tx: Sender<Event>) {
+        self.event_tx = Some(event_tx);
+    }
+
+    /// Sync files to these language servers, or stop with None; the old
+    /// servers' diagnostics are dropped
+    pub fn set_lsp(&mut self, lsp: Option<Arc<LspManager>>) {
+        self.lsp = lsp;
+        self.diagnostics.clear();
+        let paths: Vec<PathBuf> = self.tabs.iter().filter_map(|t| t.file_path.clone()).collect();
+        for path in paths {
+            self.sync_lsp(&path);
+        }
+    }
+
+    /// The language servers files are synced to
+    pub fn lsp(&self) -> Option<&Arc<LspManager>> {
+        self.lsp.as_ref()
+    }
+
+    /// Show a language server's latest diagnostics for `path`
+    pub fn set_diagnostics(&mut self, path: PathBuf, diagnostics: Vec<Diagnostic>) {
+        if diagnostics.is_empty() {
+            self.diagnostics.remove(&path);
+        } else {
+            self.diagnostics.insert(path, diagnostics);
+        }
+    }
+
+    /// Hand `path` to its language server on a background thread, as
+    /// starting one can take a while
+    fn sync_lsp(&self, path: &std::path::Path) {
+        let Some(lsp) = self.lsp.clone().filter(|lsp| lsp.handles(path)) else {
+            return;
+        };
+        let path = path.to_path_buf();
+        std::thread::spawn(move || {
+            let _ = lsp.sync(&path);
+        });
+    }
+
+    /// The most serious diagnostic on each line (from 0) of the active tab
+    fn line_severities(&self) -> HashMap<usize, DiagnosticSeverity> {
+        let mut lines = HashMap::new();
+        let Some(diagnostics) = self
+            .active_tab()
+            .file_path
+            .as_ref()
+            .and_then(|path| self.diagnostics.get(path))
+        else {
+            return lines;
+        };
+        for diagnostic in diagnostics {
+            let line = diagnostic.line.saturating_sub(1) as usize;
+            let severity = lines.entry(line).or_insert(diagnostic.severity);
+            *severity = (*severity).min(diagnostic.severity);
+        }
+        lines
+    }
+
+    /// The most serious diagnostic on the cursor's line
+    fn cursor_diagnostic(&self) -> Option<&Diagnostic> {
+        let tab = self.active_tab();
+        let line = tab.cursor.0 as u32 + 1;
+        self.diagnostics
+            .get(tab.file_path.as_ref()?)?
+            .iter()
+            .filter(|diagnostic| diagnostic.line == line)
+            .min_by_key(|diagnostic| diagnostic.severity)
+    }
+
+    /// Turn ghost-text completions on (with the model to ask) or off
+    ///
+    /// The caller passes `Some` only for workspaces that enable completions.
+    pub fn set_completion(&mut self, completion: Option<CompletionConfig>) {
+        self.completion = completion;
+        self.ghost.cancel();
+    }
+
     // ==================== Tab Access ====================
 
     /// Get active tab reference
@@ -202,6 +392,9 @@ impl EditorPanel {
 
     /// Close tab by index
     pub fn close_tab(&mut self, index: usize) {
+        if index < self.tabs.len() {
+            self.remember_closed(index);
+        }
         if self.tabs.len() <= 1 {
             // Keep at least one tab (empty)
             self.tabs[0] = FileTab::new();
@@ -224,6 +417,70 @@ impl EditorPanel {
         self.close_tab(self.active_tab);
     }
 
+    /// Remember the tab at `index` for reopening, if it has a file
+    fn remember_closed(&mut self, index: usize) {
+        let tab = &self.tabs[index];
+        let Some(path) = tab.file_path.clone() else {
+            return;
+        };
+        self.closed.retain(|closed| closed.path != path);
+        self.closed.push(ClosedTab {
+            path,
+            cursor: tab.cursor,
+            scroll: tab.scroll,
+            pinned: tab.pinned,
+        });
+        if self.closed.len() > CLOSED_TABS_KEPT {
+            self.closed.remove(0);
+        }
+    }
+
+    /// Reopen the most recently closed tab where its cursor was
+    ///
+    /// Returns whether there was one to reopen.
+    pub fn reopen_closed_tab(&mut self) -> Result<bool> {
+        let Some(closed) = self.closed.pop() else {
+            return Ok(false);
+        };
+        if let Some(index) = self.find_tab_by_path(&closed.path) {
+            self.active_tab = index;
+            return Ok(true);
+        }
+        self.open(&closed.path)?;
+        let tab = self.active_tab_mut();
+        let last = tab.lines.len() - 1;
+        let row = closed.cursor.0.min(last);
+        tab.cursor = (row, closed.cursor.1.min(tab.lines[row].chars().count()));
+        tab.scroll = (closed.scroll.0.min(last), closed.scroll.1);
+        tab.pinned = closed.pinned;
+        self.sort_pinned();
+        Ok(true)
+    }
+
+    /// Pin or unpin the active tab
+    fn toggle_pin(&mut self, state: &mut AppState) {
+        let tab = self.active_tab_mut();
+        tab.pinned = !tab.pinned;
+        let message = if tab.pinned { "Pinned" } else { "Unpinned" };
+        state.info(format!("{} {}", message, tab.display_name()));
+        self.sort_pinned();
+    }
+
+    /// Move pinned tabs ahead of the others, keeping both in order and the
+    /// same tab active
+    fn sort_pinned(&mut self) {
+        let mut tabs: Vec<(usize, FileTab)> = std::mem::take(&mut self.tabs)
+            .into_iter()
+            .enumerate()
+            .collect();
+        tabs.sort_by_key(|(_, tab)| !tab.pinned);
+        self.active_tab = tabs
+            .iter()
+            .position(|&(i, _)| i == self.active_tab)
+            .unwrap_or(0);
+        self.tabs = tabs.into_iter().map(|(_, tab)| tab).collect();
+    }
+
     // ==================== File Operations ====================
 
     /// Open a file in editor (creates new tab or switches to existing)
@@ -247,6 +504,8 @@ impl EditorPanel {
         tab.file_path = Some(path.to_path_buf());
         tab.modified = false;
         tab.highlight_dirty = true;
+        tab.disk_mtime = modified_time(path);
+        tab.trailing_newline = content.ends_with('\n');
 
         // Refresh highlighting for new tab
         tab.highlighted_lines = self.highlighter.highlight_all(&tab.lines, Some(path));
@@ -258,6 +517,7 @@ impl EditorPanel {
             self.tabs.push(tab);
             self.active_tab = self.tabs.len() - 1;
         }
+        self.sync_lsp(path);
 
         Ok(())
     }
@@ -291,6 +551,29 @@ impl EditorPanel {
         self.find_tab_by_path(path).is_some()
     }
 
+    /// Whether the active tab has unsaved changes
+    pub fn is_modified(&self) -> bool {
+        self.active_tab().modified
+    }
+
+    /// First line on screen in the active tab (from 0)
+    pub fn top_line(&self) -> usize {
+        self.active_tab().scroll.0
+    }
+
+    /// Scroll the active tab so `line` (from 0) is at the top, moving the
+    /// cursor there unless it's already on screen
+    pub fn scroll_to_line(&mut self, line: usize) {
+        let visible_height = self.visible_height;
+        let tab = self.active_tab_mut();
+        let top = line.min(tab.lines.len().saturating_sub(1));
+        tab.scroll.0 = top;
+        if !(top..top + visible_height).contains(&tab.cursor.0) {
+            tab.cursor = (top, 0);
+            tab.selection.clear();
+        }
+    }
+
     // ==================== Diff Tracking ====================
 
     /// Start tracking changes for LLM diff display
@@ -338,6 +621,400 @@ impl EditorPanel {
         self.apply_llm_modification(content);
     }
 
+    /// Show a change written to `path` on disk (e.g. by a replace) in its tab
+    ///
+    /// The tab takes the new content with the change diff-tracked, without
+    /// becoming active. Returns false, leaving the tab alone, when the file
+    /// isn't open or has unsaved edits of its own.
+    pub fn track_external_change(&mut self, path: &std::path::Path, content: &str) -> bool {
+        let Some(idx) = self.find_tab_by_path(path) else {
+            return false;
+        };
+        let tab = &mut self.tabs[idx];
+        if tab.modified {
+            return false;
+        }
+        if !tab.diff_tracker.is_tracking() {
+            tab.diff_tracker.start_tracking(&tab.lines);
+        }
+        tab.lines = content.lines().map(String::from).collect();
+        if tab.lines.is_empty() {
+            tab.lines.push(String::new());
+        }
+        let line = tab.cursor.0.min(tab.lines.len() - 1);
+        tab.cursor = (line, tab.cursor.1.min(tab.lines[line].len()));
+        tab.selection = Selection::new();
+        tab.undo_stack.clear();
+        tab.diff_tracker.update_diff(&tab.lines);
+        tab.highlighted_lines = self.highlighter.highlight_all(&tab.lines, Some(path));
+        tab.highlight_dirty = false;
+        tab.disk_mtime = modified_time(path);
+        true
+    }
+
+    /// React to a `FileChanged` event for `path`
+    ///
+    /// A tab without unsaved edits reloads; one with edits is flagged, so
+    /// saving asks before overwriting. Our own saves are recognized by
+    /// their modification time and ignored. Returns whether a tab changed.
+    pub fn file_changed_on_disk(&mut self, path: &std::path::Path, state: &mut AppState) -> bool {
+        let Some(index) = self.find_tab_by_path(path) else {
+            return false;
+        };
+        let tab = &mut self.tabs[index];
+        if modified_time(path) == tab.disk_mtime {
+            return false;
+        }
+        if !tab.modified {
+            let reloaded = self.reload_tab(index).is_ok();
+            self.sync_lsp(path);
+            return reloaded;
+        }
+        if !tab.changed_on_disk {
+            tab.changed_on_disk = true;
+            state.warn(format!(
+                "{} changed on disk; saving will ask before overwriting it",
+                tab.display_name()
+            ));
+        }
+        true
+    }
+
+    /// Follow a file or folder moved from `from` to `to` in the tabs showing it
+    pub fn path_moved(&mut self, from: &std::path::Path, to: &std::path::Path) {
+        for tab in &mut self.tabs {
+            let Some(moved) = tab
+                .file_path
+                .as_deref()
+                .and_then(|p| moved_path(p, from, to))
+            else {
+                continue;
+            };
+            tab.file_path = Some(moved);
+            tab.highlight_dirty = true;
+        }
+        for closed in &mut self.closed {
+            if let Some(moved) = moved_path(&closed.path, from, to) {
+                closed.path = moved;
+            }
+        }
+        self.diagnostics.retain(|path, _| !path.starts_with(from));
+    }
+
+    /// Close the tabs of a deleted file or folder
+    ///
+    /// Tabs with unsaved edits stay open, flagged as changed on disk, so
+    /// the edits can still be saved.
+    pub fn path_deleted(&mut self, path: &std::path::Path) {
+        let mut index = self.tabs.len();
+        while index > 0 {
+            index -= 1;
+            let tab = &mut self.tabs[index];
+            let inside = tab.file_path.as_ref().is_some_and(|p| p.starts_with(path));
+            if !inside {
+                continue;
+            }
+            if tab.modified {
+                tab.changed_on_disk = true;
+            } else {
+                self.close_tab(index);
+            }
+        }
+        self.closed.retain(|closed| !closed.path.starts_with(path));
+        self.diagnostics.retain(|p, _| !p.starts_with(path));
+    }
+
+    /// Replace the tab's content with the file on disk, dropping edits
+    fn reload_tab(&mut self, index: usize) -> Result<()> {
+        let tab = &mut self.tabs[index];
+        let Some(path) = tab.file_path.clone() else {
+            return Ok(());
+        };
+        let content = std::fs::read_to_string(&path).map_err(crate::core::AxiomError::Io)?;
+        tab.lines = content.lines().map(String::from).collect();
+        if tab.lines.is_empty() {
+            tab.lines.push(String::new());
+        }
+        let line = tab.cursor.0.min(tab.lines.len() - 1);
+        tab.cursor = (line, tab.cursor.1.min(tab.lines[line].len()));
+        tab.selection = Selection::new();
+        tab.undo_stack.clear();
+        tab.modified = false;
+        tab.changed_on_disk = false;
+        tab.trailing_newline = content.ends_with('\n');
+        tab.disk_mtime = modified_time(&path);
+        tab.highlighted_lines = self.highlighter.highlight_all(&tab.lines, Some(&path));
+        tab.highlight_dirty = false;
+        Ok(())
+    }
+
+    // ==================== Saving ====================
+
+    /// Save the tab at `index`, or ask first if its file changed on disk
+    ///
+    /// Returns whether the file was written.
+    fn save_tab(&mut self, index: usize, state: &mut AppState) -> bool {
+        let tab = &self.tabs[index];
+        let Some(path) = &tab.file_path else {
+            state.info("This tab has no file to save to");
+            return false;
+        };
+        if modified_time(path) != tab.disk_mtime {
+            self.active_tab = index;
+            self.prompt = Some(SavePrompt::Conflict(index));
+            return false;
+        }
+        self.write_tab(index, state)
+    }
+
+    /// Write the tab at `index` to its file, whatever is there now
+    fn write_tab(&mut self, index: usize, state: &mut AppState) -> bool {
+        let tab = &mut self.tabs[index];
+        let Some(path) = &tab.file_path else {
+            return false;
+        };
+        match write_atomic(path, tab.content().as_bytes()) {
+            Ok(mtime) => {
+                tab.disk_mtime = Some(mtime);
+                tab.modified = false;
+                tab.changed_on_disk = false;
+                state.info(format!("Saved {}", tab.display_name()));
+                let path = path.clone();
+                self.sync_lsp(&path);
+                true
+            }
+            Err(e) => {
+                state.error(format!("Failed to save {}: {}", path.display(), e));
+                false
+            }
+        }
+    }
+
+    /// Save every tab with unsaved changes
+    ///
+    /// Stops at the first file that changed on disk to ask about it.
+    fn save_all(&mut self, state: &mut AppState) {
+        let mut saved = 0;
+        for index in 0..self.tabs.len() {
+            let tab = &self.tabs[index];
+            if !tab.modified || tab.file_path.is_none() {
+                continue;
+            }
+            if self.save_tab(index, state) {
+                saved += 1;
+            } else if self.prompt.is_some() {
+                return;
+            }
+        }
+        if saved != 1 {
+            state.info(format!("Saved {} files", saved));
+        }
+    }
+
+    /// Close the tab at `index`, asking first if it has unsaved changes
+    ///
+    /// Pinned tabs stay open until unpinned.
+    fn request_close(&mut self, index: usize, state: &mut AppState) {
+        if self.tabs[index].pinned {
+            state.warn(format!(
+                "{} is pinned; unpin it (Alt+P) to close it",
+                self.tabs[index].display_name()
+            ));
+        } else if self.tabs[index].modified {
+            self.active_tab = index;
+            self.prompt = Some(SavePrompt::Close(index));
+        } else {
+            self.close_tab(index);
+        }
+    }
+
+    /// Whether any tab has unsaved changes
+    pub fn has_unsaved(&self) -> bool {
+        self.tabs.iter().any(|tab| tab.modified)
+    }
+
+    /// Handle a key while a prompt is open
+    fn prompt_key(&mut self, prompt: SavePrompt, code: KeyCode, state: &mut AppState) {
+        self.prompt = None;
+        match (prompt, code) {
+            (SavePrompt::Conflict(index), KeyCode::Char('o')) => {
+                self.write_tab(index, state);
+            }
+            (SavePrompt::Conflict(index), KeyCode::Char('r')) => {
+                if let Err(e) = self.reload_tab(index) {
+                    state.error(format!("Failed to reload: {}", e));
+                }
+            }
+            (SavePrompt::Close(index), KeyCode::Char('s')) => {
+                if self.save_tab(index, state) {
+                    self.close_tab(index);
+                }
+            }
+            (SavePrompt::Close(index), KeyCode::Char('d')) => self.close_tab(index),
+            (_, KeyCode::Esc | KeyCode::Char('c')) => {}
+            // Anything else leaves the question open
+            _ => self.prompt = Some(prompt),
+        }
+    }
+
+    /// Text of the prompt line
+    fn prompt_line(&self) -> Option<String> {
+        if let (None, Some(line_prompt)) = (self.prompt, &self.line_prompt) {
+            return Some(self.line_prompt_text(line_prompt));
+        }
+        Some(match self.prompt? {
+            SavePrompt::Conflict(index) => format!(
+                " {} changed on disk: (o)verwrite, (r)eload, (c)ancel",
+                self.tabs[index].display_name()
+            ),
+            SavePrompt::Close(index) => format!(
+                " {} has unsaved changes: (s)ave, (d)iscard, (c)ancel",
+                self.tabs[index].display_name()
+            ),
+        })
+    }
+
+    // ==================== Search ====================
+
+    /// Open the `/` search or `:` go-to-line prompt
+    fn open_line_prompt(&mut self, kind: PromptKind, state: &mut AppState) {
+        let tab = self.active_tab();
+        self.line_prompt = Some(LinePrompt::new(kind, tab.cursor, tab.scroll));
+        match kind {
+            PromptKind::Search => state.input_mode.open_search(true),
+            PromptKind::GoToLine => state.input_mode.open_command(),
+        }
+    }
+
+    /// Whether Esc should come here rather than switch to normal mode,
+    /// which it does while the search or go-to-line prompt is open
+    pub fn takes_esc(&self) -> bool {
+        self.line_prompt.is_some()
+    }
+
+    /// Handle a key while the search or go-to-line prompt is open
+    fn line_prompt_key(&mut self, mut prompt: LinePrompt, key: KeyEvent, state: &mut AppState) {
+        let cancel =
+            key.code == KeyCode::Esc || (key.code == KeyCode::Backspace && prompt.text.is_empty());
+        if cancel {
+            self.restore_origin(&prompt);
+            state.input_mode.to_normal();
+            return;
+        }
+        match key.code {
+            KeyCode::Enter => {
+                state.input_mode.to_normal();
+                self.finish_line_prompt(prompt, state);
+                return;
+            }
+            KeyCode::Backspace => {
+                prompt.text.pop();
+            }
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                prompt.text.push(c)
+            }
+            _ => {}
+        }
+        // Searching moves to the first match from where it started as the
+        // pattern is typed
+        if prompt.kind == PromptKind::Search {
+            let (cursor, _) = prompt.origin;
+            let found = search::seek(&self.active_tab().lines, &prompt.text, cursor, Seek::From);
+            self.restore_origin(&prompt);
+            if let Some(found) = found {
+                self.jump_to(found.at);
+            }
+        }
+        self.line_prompt = Some(prompt);
+    }
+
+    /// Search for or go to what was typed in the prompt
+    fn finish_line_prompt(&mut self, prompt: LinePrompt, state: &mut AppState) {
+        let text = prompt.text.trim();
+        match prompt.kind {
+            // An empty search clears the highlighted matches
+            PromptKind::Search if prompt.text.is_empty() => self.search = None,
+            PromptKind::Search => {
+                let (cursor, _) = prompt.origin;
+                if search::seek(&self.active_tab().lines, &prompt.text, cursor, Seek::From)
+                    .is_none()
+                {
+                    self.restore_origin(&prompt);
+                    state.warn(format!("Pattern not found: {}", prompt.text));
+                }
+                self.search = Some(prompt.text);
+            }
+            PromptKind::GoToLine if text.is_empty() => {}
+            PromptKind::GoToLine => {
+                match search::parse_line_number(text, self.active_tab().lines.len()) {
+                    Some(line) => self.jump_to((line, 0)),
+                    None => state.warn(format!("Not a line number: {}", text)),
+                }
+            }
+        }
+    }
+
+    /// Put the cursor and scroll back where they were when `prompt` opened
+    fn restore_origin(&mut self, prompt: &LinePrompt) {
+        let (cursor, scroll) = prompt.origin;
+        let tab = self.active_tab_mut();
+        tab.cursor = cursor;
+        tab.scroll = scroll;
+    }
+
+    /// Move to the next match of the last search, or the previous one
+    fn search_next(&mut self, forward: bool, state: &mut AppState) {
+        let Some(pattern) = self.search.clone() else {
+            state.info("No previous search".to_string());
+            return;
+        };
+        let seek = if forward { Seek::After } else { Seek::Before };
+        let cursor = self.active_tab().cursor;
+        match search::seek(&self.active_tab().lines, &pattern, cursor, seek) {
+            Some(found) => {
+                if found.wrapped {
+                    state.info(if forward {
+                        "Search hit BOTTOM, continuing at TOP".to_string()
+                    } else {
+                        "Search hit TOP, continuing at BOTTOM".to_string()
+                    });
+                }
+                self.jump_to(found.at);
+            }
+            None => state.warn(format!("Pattern not found: {}", pattern)),
+        }
+    }
+
+    /// Pattern whose matches are highlighted: the one being typed, or the
+    /// last one searched for
+    fn highlighted_pattern(&self) -> Option<&str> {
+        match &self.line_prompt {
+            Some(prompt) if prompt.kind == PromptKind::Search && !prompt.text.is_empty() => {
+                Some(&prompt.text)
+            }
+            _ => self.search.as_deref(),
+        }
+    }
+
+    /// Prompt line for `prompt`, e.g. " /foo  (3 of 17)"
+    fn line_prompt_text(&self, prompt: &LinePrompt) -> String {
+        let mut text = format!(" {}{}", prompt.kind.sigil(), prompt.text);
+        if prompt.kind == PromptKind::Search && !prompt.text.is_empty() {
+            let tab = self.active_tab();
+            let count = match search::position(&tab.lines, &prompt.text, tab.cursor) {
+                (_, 0) => "  (no matches)".to_string(),
+                (Some(index), total) => format!("  ({} of {})", index, total),
+                (None, total) => format!("  ({} matches)", total),
+            };
+            text.push_str(&count);
+        }
+        text
+    }
+
     // ==================== Highlighting ====================
 
     /// Refresh syntax highlighting for active tab
@@ -471,6 +1148,107 @@ impl EditorPanel {
         }
     }
 
+    // ==================== Code Actions ====================
+
+    /// Selected code with its location and surrounding lines
+    fn selection_context(&self) -> Option<SelectionContext> {
+        let tab = self.active_tab();
+        let (start, end) = tab.selection.range(self.cursor_pos())?;
+        let code = self.get_selected_text()?;
+        if code.trim().is_empty() {
+            return None;
+        }
+        // A selection ending at the start of a line doesn't include it
+        let last = if end.column == 0 && end.line > start.line {
+            end.line - 1
+        } else {
+            end.line
+        };
+        let before_start = start.line.saturating_sub(actions::CONTEXT_LINES);
+        let after_end = (last + 1 + actions::CONTEXT_LINES).min(tab.lines.len());
+
+        Some(SelectionContext {
+            path: tab.file_path.clone(),
+            language: self
+                .highlighter
+                .detect_syntax(tab.file_path.as_deref())
+                .to_string(),
+            start_line: start.line + 1,
+            end_line: last + 1,
+            code: code.trim_end_matches('\n').to_string(),
+            before: tab.lines[before_start..start.line].to_vec(),
+            after: tab.lines.get(last + 1..after_end).unwrap_or_default().to_vec(),
+        })
+    }
+
+    /// Open the code action menu if there is a selection
+    fn open_action_menu(&mut self, state: &mut AppState) {
+        if self.selection_context().is_some() {
+            self.action_menu = Some(ActionMenu::new());
+        } else {
+            state.info("Select code first (Shift+Arrows)".to_string());
+        }
+    }
+
+    /// Send `action` on the selection to the Conductor
+    fn run_code_action(&mut self, action: CodeAction, state: &mut AppState) {
+        let Some(ctx) = self.selection_context() else {
+            return;
+        };
+        match &self.event_tx {
+            Some(event_tx) => {
+                let _ = event_tx.send(Event::ConductorRequest(action_prompt(action, &ctx)));
+                state.info(format!("{}: asked the Conductor", action.label()));
+            }
+            None => state.warn("Code actions aren't connected to an agent".to_string()),
+        }
+    }
+
+    // ==================== Completions ====================
+
+    /// Send a completion request if the editor has been idle long enough
+    ///
+    /// Only asks at the end of a line, where ghost text can be shown inline.
+    fn poll_completion(&mut self, now: Instant) -> bool {
+        let (Some(config), Some(event_tx)) = (&self.completion, &self.event_tx) else {
+            return false;
+        };
+        let tab = &self.tabs[self.active_tab];
+        let debounce = Duration::from_millis(config.debounce_ms);
+        let Some(generation) = self.ghost.due(now, debounce, tab.cursor) else {
+            return false;
+        };
+        if tab.cursor.1 != tab.current_line().chars().count() {
+            self.ghost.cancel();
+            return false;
+        }
+
+        let request = FimRequest::at(&tab.lines, tab.cursor.0, tab.cursor.1);
+        let config = config.clone();
+        let event_tx = event_tx.clone();
+        std::thread::spawn(move || {
+            let text = fim::complete(&config.base_url, &config.model, &request, config.max_tokens)
+                .map(|completion| fim::ghost_line(&completion))
+                .unwrap_or_default();
+            let _ = event_tx.send(Event::GhostCompletion { generation, text });
+        });
+        true
+    }
+
+    /// Insert the ghost text at the cursor
+    fn accept_ghost(&mut self) -> bool {
+        let Some(text) = self.ghost.accept() else {
+            return false;
+        };
+        let start_pos = self.cursor_pos();
+        self.insert_text(&text);
+        self.active_tab_mut()
+            .undo_stack
+            .push(EditOp::Insert { pos: start_pos, text });
+        self.refresh_highlighting();
+        true
+    }
+
     // ==================== Clipboard Operations ====================
 
     /// Copy selection to clipboard
@@ -496,24 +1274,28 @@ impl EditorPanel {
     /// Paste from clipboard
     fn paste(&mut self) -> bool {
         if let Ok(text) = crate::clipboard::paste() {
-            // Delete selection first if any
-            self.delete_selection();
+            self.paste_text(text);
+            return true;
+        }
+        false
+    }
 
-            let start_pos = self.cursor_pos();
+    /// Insert pasted text over the selection as a single undo step
+    fn paste_text(&mut self, text: String) {
+        // Delete selection first if any
+        self.delete_selection();
 
-            // Insert text
-            self.insert_text(&text);
+        let start_pos = self.cursor_pos();
 
-            // Record for undo
-            let tab = self.active_tab_mut();
-            tab.undo_stack.push(EditOp::Insert {
-                pos: start_pos,
-                text,
-            });
+        // Insert text
+        self.insert_text(&text);
 
-            return true;
-        }
-        false
+        // Record for undo
+        let tab = self.active_tab_mut();
+        tab.undo_stack.push(EditOp::Insert {
+            pos: start_pos,
+            text,
+        });
     }
 
     // ==================== Undo/Redo ====================
@@ -634,10 +1416,10 @@ impl EditorPanel {
             let col = col.min(line.chars().count());
 
             if col > 0 {
-                let byte_pos: usize = line.chars().take(col).map(|ch| ch.len_utf8()).sum();
-                let prev_char_len = line.chars().nth(col - 1).map(|ch| ch.len_utf8()).unwrap_or(1);
-                line.remove(byte_pos - prev_char_len);
-                tab.cursor.1 = col - 1;
+                let prev = columns::prev_boundary(line, col);
+                let range = columns::byte_offset(line, prev)..columns::byte_offset(line, col);
+                line.replace_range(range, "");
+                tab.cursor.1 = prev;
                 tab.modified = true;
                 tab.highlight_dirty = true;
             }
@@ -661,8 +1443,9 @@ impl EditorPanel {
 
         if col < line_len {
             let line = &mut tab.lines[row];
-            let byte_pos: usize = line.chars().take(col).map(|ch| ch.len_utf8()).sum();
-            line.remove(byte_pos);
+            let next = columns::next_boundary(line, col);
+            let range = columns::byte_offset(line, col)..columns::byte_offset(line, next);
+            line.replace_range(range, "");
             tab.modified = true;
             tab.highlight_dirty = true;
         } else if row + 1 < tab.lines.len() {
@@ -699,21 +1482,22 @@ impl EditorPanel {
         match dir {
             Direction::Up => {
                 if tab.cursor.0 > 0 {
+                    // Keep the screen column, not the char count
+                    let x = columns::width_before(tab.current_line(), tab.cursor.1);
                     tab.cursor.0 -= 1;
-                    let line_len = tab.current_line().chars().count();
-                    tab.cursor.1 = tab.cursor.1.min(line_len);
+                    tab.cursor.1 = columns::column_at(tab.current_line(), x);
                 }
             }
             Direction::Down => {
                 if tab.cursor.0 + 1 < tab.lines.len() {
+                    let x = columns::width_before(tab.current_line(), tab.cursor.1);
                     tab.cursor.0 += 1;
-                    let line_len = tab.current_line().chars().count();
-                    tab.cursor.1 = tab.cursor.1.min(line_len);
+                    tab.cursor.1 = columns::column_at(tab.current_line(), x);
                 }
             }
             Direction::Left => {
                 if tab.cursor.1 > 0 {
-                    tab.cursor.1 -= 1;
+                    tab.cursor.1 = columns::prev_boundary(tab.current_line(), tab.cursor.1);
                 } else if tab.cursor.0 > 0 {
                     tab.cursor.0 -= 1;
                     tab.cursor.1 = tab.current_line().chars().count();
@@ -722,7 +1506,7 @@ impl EditorPanel {
             Direction::Right => {
                 let line_len = tab.current_line().chars().count();
                 if tab.cursor.1 < line_len {
-                    tab.cursor.1 += 1;
+                    tab.cursor.1 = columns::next_boundary(tab.current_line(), tab.cursor.1);
                 } else if tab.cursor.0 + 1 < tab.lines.len() {
                     tab.cursor.0 += 1;
                     tab.cursor.1 = 0;
@@ -732,6 +1516,98 @@ impl EditorPanel {
         self.ensure_cursor_visible();
     }
 
+    /// Move the cursor to (line, column) and scroll it into view
+    fn jump_to(&mut self, to: (usize, usize)) {
+        self.active_tab_mut().cursor = to;
+        self.ensure_cursor_visible();
+    }
+
+    /// Start of the word before the cursor, or of the previous line's last
+    /// word from a line's first word
+    fn word_left(&self) -> (usize, usize) {
+        let tab = self.active_tab();
+        let (row, col) = tab.cursor;
+        let line = tab.current_line();
+        if row == 0 || !line.chars().take(col).all(char::is_whitespace) {
+            return (row, words::prev_word_start(line, col));
+        }
+        let above = &tab.lines[row - 1];
+        (
+            row - 1,
+            words::prev_word_start(above, above.chars().count()),
+        )
+    }
+
+    /// Start of the next word, on the next line once past the last one
+    fn word_right(&self) -> (usize, usize) {
+        let tab = self.active_tab();
+        let (row, col) = tab.cursor;
+        let line = tab.current_line();
+        let next = words::next_word_start(line, col);
+        if next < line.chars().count() || row + 1 >= tab.lines.len() {
+            return (row, next);
+        }
+        let below = &tab.lines[row + 1];
+        (
+            row + 1,
+            below.chars().take_while(|c| c.is_whitespace()).count(),
+        )
+    }
+
+    /// Last character of the current or next word (vim `e`)
+    fn word_end(&self) -> (usize, usize) {
+        let tab = self.active_tab();
+        let (row, col) = tab.cursor;
+        if let Some(end) = words::word_end(tab.current_line(), col + 1) {
+            return (row, end);
+        }
+        tab.lines
+            .iter()
+            .enumerate()
+            .skip(row + 1)
+            .find_map(|(i, line)| Some((i, words::word_end(line, 0)?)))
+            .unwrap_or((row, col))
+    }
+
+    /// Delete from the cursor to `to` as a single undo step
+    fn delete_to(&mut self, to: (usize, usize)) {
+        if to == self.active_tab().cursor {
+            return;
+        }
+        self.active_tab_mut()
+            .selection
+            .start(Position::new(to.0, to.1));
+        self.delete_selection();
+        self.refresh_highlighting();
+    }
+
+    /// Line and column of the text under screen cell (x, y)
+    fn position_at(&self, x: u16, y: u16) -> Option<(usize, usize)> {
+        let content = *self.content_area.borrow();
+        let tab = self.active_tab();
+        if !hit(&content, x, y) {
+            return None;
+        }
+        let x = ((x - content.x) as usize).checked_sub(gutter_width(tab.lines.len()))?;
+        let row = tab.scroll.0 + (y - content.y) as usize;
+        let line = tab.lines.get(row)?;
+        Some((row, columns::column_at(line, x)))
+    }
+
+    /// Put the cursor where the text was clicked, selecting the word there
+    /// on a double-click
+    fn click_text(&mut self, row: usize, col: usize) {
+        let double = self.clicks.click((row, col), Instant::now());
+        let tab = self.active_tab_mut();
+        tab.selection.clear();
+        tab.cursor = (row, col);
+        if double {
+            let (start, end) = words::word_at(tab.current_line(), col);
+            tab.selection.start(Position::new(row, start));
+            tab.cursor.1 = end;
+        }
+    }
+
     // ==================== Scrolling ====================
 
     fn scroll_up(&mut self, n: usize) {
@@ -801,7 +1677,13 @@ impl EditorPanel {
             tab.lines.len(),
         );
 
-        let modified_marker = if tab.modified { " *" } else { "" };
+        let modified_marker = if tab.changed_on_disk {
+            " * [changed on disk]"
+        } else if tab.modified {
+            " *"
+        } else {
+            ""
+        };
 
         format!(" {}{} ({}){}{} ", name, modified_marker, syntax, diff_indicator, scroll_info)
     }
@@ -814,17 +1696,99 @@ enum Direction {
     Right,
 }
 
+/// Cells kept at the right of the tab bar for the tab list button
+const OVERFLOW_BUTTON_WIDTH: u16 = 7;
+
+/// Color of a diagnostic's gutter marker and message
+fn severity_style(severity: DiagnosticSeverity) -> Style {
+    let color = match severity {
+        DiagnosticSeverity::Error => Color::Red,
+        DiagnosticSeverity::Warning => Color::Yellow,
+        DiagnosticSeverity::Information | DiagnosticSeverity::Hint => Color::Blue,
+    };
+    Style::default().fg(color)
+}
+
+/// Width of the diff marker and line numbers left of the text
+fn gutter_width(line_count: usize) -> usize {
+    format!("{}", line_count).len() + 2
+}
+
 impl super::Panel for EditorPanel {
     fn id(&self) -> PanelId {
         PanelId::EDITOR
     }
 
+    /// Keeps the loop polling while a completion's debounce runs
+    fn needs_redraw(&self) -> bool {
+        self.ghost.is_waiting()
+    }
+
     fn name(&self) -> &str {
         "Editor"
     }
 
     fn handle_input(&mut self, event: &Event, state: &mut AppState) -> Result<bool> {
         if let Event::Key(key) = event {
+            // The code action menu takes all keys while open
+            if let Some(menu) = &mut self.action_menu {
+                match menu.handle_key(*key) {
+                    MenuAction::None => {}
+                    MenuAction::Cancelled => self.action_menu = None,
+                    MenuAction::Chosen(action) => {
+                        self.action_menu = None;
+                        self.run_code_action(action, state);
+                    }
+                }
+                return Ok(true);
+            }
+
+            // And the tab list
+            if let Some(list) = &mut self.tab_list {
+                match list.handle_key(*key, self.tabs.len()) {
+                    TabListAction::None => {}
+                    TabListAction::Cancelled => self.tab_list = None,
+                    TabListAction::Chosen(index) => {
+                        self.tab_list = None;
+                        self.switch_tab(index);
+                    }
+                }
+                return Ok(true);
+            }
+
+            // So does a save or close prompt
+            if let Some(prompt) = self.prompt {
+                self.prompt_key(prompt, key.code, state);
+                return Ok(true);
+            }
+
+            // And the search or go-to-line prompt, unless something else
+            // left the search or command mode it opened
+            if let Some(prompt) = self.line_prompt.take() {
+                if state.input_mode.query().is_some() {
+                    self.line_prompt_key(prompt, *key, state);
+                    return Ok(true);
+                }
+            }
+
+            // Tab accepts a completion, Esc dismisses it; any other key
+            // drops it along with requests still in flight
+            let cursor = self.active_tab().cursor;
+            if self.ghost.visible(cursor).is_some() && state.input_mode.is_editing() {
+                match key.code {
+                    KeyCode::Tab if key.modifiers.is_empty() => {
+                        self.accept_ghost();
+                        return Ok(true);
+                    }
+                    KeyCode::Esc => {
+                        self.ghost.cancel();
+                        return Ok(true);
+                    }
+                    _ => {}
+                }
+            }
+            self.ghost.cancel();
+
             // Tab navigation keys (work in all modes)
             // Alt+] on Windows/Linux, Cmd+] on Mac (SUPER modifier)
             match (key.code, key.modifiers) {
@@ -842,9 +1806,48 @@ impl super::Panel for EditorPanel {
                     self.prev_tab();
                     return Ok(true);
                 }
-                // Ctrl+W: close current tab
+                // Alt+Enter: code actions on the selection
+                (KeyCode::Enter, m) if m.contains(KeyModifiers::ALT) => {
+                    self.open_action_menu(state);
+                    return Ok(true);
+                }
+                // Ctrl+Alt+S: save all tabs
+                (KeyCode::Char('s'), m)
+                    if m.contains(KeyModifiers::CONTROL) && m.contains(KeyModifiers::ALT) =>
+                {
+                    self.save_all(state);
+                    return Ok(true);
+                }
+                // Ctrl+S: save current tab
+                (KeyCode::Char('s'), m) if m.contains(KeyModifiers::CONTROL) => {
+                    self.save_tab(self.active_tab, state);
+                    return Ok(true);
+                }
+                // Ctrl+W: close current tab, asking about unsaved changes
                 (KeyCode::Char('w'), m) if m.contains(KeyModifiers::CONTROL) => {
-                    self.close_current_tab();
+                    self.request_close(self.active_tab, state);
+                    return Ok(true);
+                }
+                // Ctrl+Shift+T: reopen the last closed tab
+                (KeyCode::Char(c), m)
+                    if m.contains(KeyModifiers::CONTROL)
+                        && (c == 'T' || (c == 't' && m.contains(KeyModifiers::SHIFT))) =>
+                {
+                    match self.reopen_closed_tab() {
+                        Ok(true) => self.refresh_highlighting(),
+                        Ok(false) => state.info("No closed tabs to reopen".to_string()),
+                        Err(e) => state.error(format!("Failed to reopen tab: {}", e)),
+                    }
+                    return Ok(true);
+                }
+                // Alt+P: pin or unpin the current tab
+                (KeyCode::Char('p'), m) if m.contains(KeyModifiers::ALT) => {
+                    self.toggle_pin(state);
+                    return Ok(true);
+                }
+                // Alt+0: list all tabs
+                (KeyCode::Char('0'), m) if m.contains(KeyModifiers::ALT) => {
+                    self.tab_list = Some(TabList::new(self.active_tab));
                     return Ok(true);
                 }
                 // Alt+1-9: switch to tab by number
@@ -951,6 +1954,18 @@ impl super::Panel for EditorPanel {
                         self.move_cursor(Direction::Right);
                         Ok(true)
                     }
+                    KeyCode::Char('w') => {
+                        self.jump_to(self.word_right());
+                        Ok(true)
+                    }
+                    KeyCode::Char('b') => {
+                        self.jump_to(self.word_left());
+                        Ok(true)
+                    }
+                    KeyCode::Char('e') => {
+                        self.jump_to(self.word_end());
+                        Ok(true)
+                    }
                     KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                         self.scroll_half_page_up();
                         Ok(true)
@@ -966,6 +1981,22 @@ impl super::Panel for EditorPanel {
                         tab.scroll.0 = 0;
                         Ok(true)
                     }
+                    KeyCode::Char('/') => {
+                        self.open_line_prompt(PromptKind::Search, state);
+                        Ok(true)
+                    }
+                    KeyCode::Char(':') => {
+                        self.open_line_prompt(PromptKind::GoToLine, state);
+                        Ok(true)
+                    }
+                    KeyCode::Char('n') => {
+                        self.search_next(true, state);
+                        Ok(true)
+                    }
+                    KeyCode::Char('N') => {
+                        self.search_next(false, state);
+                        Ok(true)
+                    }
                     KeyCode::Char('G') => {
                         let tab = self.active_tab_mut();
                         tab.cursor.0 = tab.lines.len().saturating_sub(1);
@@ -992,6 +2023,19 @@ impl super::Panel for EditorPanel {
 
             // Insert mode: editing
             let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+            // Ctrl, or Alt as on macOS, makes arrows and deletes word-wise
+            let by_word = key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+
+            let edits = match key.code {
+                KeyCode::Char(_) => !key.modifiers.contains(KeyModifiers::CONTROL),
+                KeyCode::Backspace | KeyCode::Delete | KeyCode::Enter => true,
+                _ => false,
+            };
+            if edits && self.completion.is_some() {
+                self.ghost.edited(Instant::now());
+            }
 
             match key.code {
                 KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -1002,6 +2046,14 @@ impl super::Panel for EditorPanel {
                     self.insert_char(c);
                     Ok(true)
                 }
+                KeyCode::Backspace if by_word && !self.active_tab().selection.is_active() => {
+                    self.delete_to(self.word_left());
+                    Ok(true)
+                }
+                KeyCode::Delete if by_word && !self.active_tab().selection.is_active() => {
+                    self.delete_to(self.word_right());
+                    Ok(true)
+                }
                 KeyCode::Backspace => {
                     // Delete selection if active, otherwise normal backspace
                     if self.active_tab().selection.is_active() {
@@ -1040,6 +2092,16 @@ impl super::Panel for EditorPanel {
                     self.move_cursor(Direction::Down);
                     Ok(true)
                 }
+                KeyCode::Left if by_word => {
+                    self.handle_selection(shift);
+                    self.jump_to(self.word_left());
+                    Ok(true)
+                }
+                KeyCode::Right if by_word => {
+                    self.handle_selection(shift);
+                    self.jump_to(self.word_right());
+                    Ok(true)
+                }
                 KeyCode::Left => {
                     self.handle_selection(shift);
                     self.move_cursor(Direction::Left);
@@ -1075,6 +2137,14 @@ impl super::Panel for EditorPanel {
                     let x = mouse.column;
                     let y = mouse.row;
 
+                    // An open tab list takes the click, closing if it missed
+                    if let Some(list) = self.tab_list.take() {
+                        if let Some(index) = list.tab_at(x, y, self.tabs.len()) {
+                            self.switch_tab(index);
+                        }
+                        return Ok(true);
+                    }
+
                     // Check for scroll bar clicks first
                     let content = *self.content_area.borrow();
                     let tab = self.active_tab();
@@ -1099,9 +2169,10 @@ impl super::Panel for EditorPanel {
                         if y == area.y && x >= area.x && x < area.x + area.width {
                             // Find which tab was clicked
                             let boundaries = self.tab_boundaries.borrow();
-                            boundaries.iter().enumerate()
-                                .find(|(_, &(start_x, end_x))| x >= start_x && x < end_x)
-                                .map(|(idx, _)| idx)
+                            boundaries
+                                .iter()
+                                .find(|&&(_, start_x, end_x)| x >= start_x && x < end_x)
+                                .map(|&(idx, _, _)| idx)
                         } else {
                             None
                         }
@@ -1113,6 +2184,15 @@ impl super::Panel for EditorPanel {
                         self.switch_tab(idx);
                         return Ok(true);
                     }
+                    let overflow_button = *self.overflow_button.borrow();
+                    if overflow_button.is_some_and(|button| hit(&button, x, y)) {
+                        self.tab_list = Some(TabList::new(self.active_tab));
+                        return Ok(true);
+                    }
+                    if let Some((row, col)) = self.position_at(x, y) {
+                        self.click_text(row, col);
+                        return Ok(true);
+                    }
                     Ok(false)
                 }
                 // Handle mouse scroll
@@ -1126,6 +2206,25 @@ impl super::Panel for EditorPanel {
                 }
                 _ => Ok(false),
             }
+        } else if let Event::Paste(text) = event {
+            // Pasting into an open menu or prompt would edit behind it
+            if !self.has_tabs()
+                || self.action_menu.is_some()
+                || self.tab_list.is_some()
+                || self.prompt.is_some()
+            {
+                return Ok(false);
+            }
+            self.ghost.cancel();
+            self.paste_text(text.clone());
+            self.refresh_highlighting();
+            Ok(true)
+        } else if let Event::FileChanged(path) = event {
+            Ok(self.file_changed_on_disk(path, state))
+        } else if let Event::GhostCompletion { generation, text } = event {
+            Ok(self.ghost.receive(*generation, text.clone()))
+        } else if let Event::Tick = event {
+            Ok(self.poll_completion(Instant::now()))
         } else {
             Ok(false)
         }
@@ -1139,10 +2238,17 @@ impl super::Panel for EditorPanel {
         };
 
         // Create outer block with title
-        let block = Block::default()
+        let mut block = Block::default()
             .title(self.title())
             .borders(Borders::ALL)
             .border_style(border_style);
+        if let Some(diagnostic) = self.cursor_diagnostic() {
+            let message = diagnostic.message.lines().next().unwrap_or_default();
+            block = block.title_bottom(Span::styled(
+                format!(" {}: {} ", diagnostic.severity.as_str(), message),
+                severity_style(diagnostic.severity),
+            ));
+        }
 
         let inner = block.inner(area);
 
@@ -1156,7 +2262,12 @@ impl super::Panel for EditorPanel {
             .split(inner);
 
         let tab_bar_area = chunks[0];
-        let content_area = chunks[1];
+        let mut content_area = chunks[1];
+        // The bottom row holds the prompt while one is open
+        let prompt = self.prompt_line().filter(|_| content_area.height > 1);
+        if prompt.is_some() {
+            content_area.height -= 1;
+        }
         let visible_height = content_area.height as usize;
 
         // Store content area for scroll bar click detection
@@ -1165,18 +2276,36 @@ impl super::Panel for EditorPanel {
         // Store tab bar area for mouse click detection
         *self.tab_bar_area.borrow_mut() = Some(tab_bar_area);
 
+        // Tabs that fit in the bar, leaving room for the overflow button
+        // when some don't
+        let titles: Vec<String> = self.tabs.iter().map(FileTab::tab_title).collect();
+        // +1 for the divider
+        let widths: Vec<u16> = titles
+            .iter()
+            .map(|title| title.width() as u16 + 1)
+            .collect();
+        let mut bar = tab_bar_area;
+        let mut window = visible_tabs(&widths, self.active_tab, bar.width);
+        let mut overflow_button = None;
+        if window.len() < self.tabs.len() {
+            bar.width = bar.width.saturating_sub(OVERFLOW_BUTTON_WIDTH);
+            window = visible_tabs(&widths, self.active_tab, bar.width);
+            overflow_button = Some(Rect {
+                x: bar.x + bar.width,
+                width: tab_bar_area.width - bar.width,
+                ..bar
+            });
+        }
+        *self.overflow_button.borrow_mut() = overflow_button;
+
         // Calculate and store tab boundaries for click detection
         {
             let mut boundaries = self.tab_boundaries.borrow_mut();
             boundaries.clear();
-            let mut current_x = tab_bar_area.x;
-            for tab in &self.tabs {
-                let name = tab.display_name();
-                let modified = if tab.modified { "*" } else { "" };
-                let title = format!(" {}{} ", name, modified);
-                let tab_width = title.len() as u16 + 1; // +1 for divider
-                boundaries.push((current_x, current_x + tab_width));
-                current_x += tab_width;
+            let mut current_x = bar.x;
+            for idx in window.clone() {
+                boundaries.push((idx, current_x, current_x + widths[idx]));
+                current_x += widths[idx];
             }
         }
 
@@ -1184,46 +2313,60 @@ impl super::Panel for EditorPanel {
         frame.render_widget(block, area);
 
         // Render tab bar
-        let tab_titles: Vec<Line> = self.tabs.iter().enumerate().map(|(idx, tab)| {
-            let name = tab.display_name();
-            let modified = if tab.modified { "*" } else { "" };
-            let title = format!(" {}{} ", name, modified);
-
-            if idx == self.active_tab {
-                Line::from(Span::styled(
-                    title,
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                ))
-            } else {
-                Line::from(Span::styled(
-                    title,
-                    Style::default().fg(Color::DarkGray),
-                ))
-            }
-        }).collect();
+        let tab_titles: Vec<Line> = window
+            .clone()
+            .map(|idx| {
+                let title = titles[idx].clone();
+
+                if idx == self.active_tab {
+                    Line::from(Span::styled(
+                        title,
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    ))
+                } else {
+                    Line::from(Span::styled(title, Style::default().fg(Color::DarkGray)))
+                }
+            })
+            .collect();
 
         let tabs_widget = Tabs::new(tab_titles)
             .style(Style::default().fg(Color::DarkGray))
             .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
-            .select(self.active_tab)
+            .select(self.active_tab.saturating_sub(window.start))
             .divider(Span::raw("|"));
 
-        frame.render_widget(tabs_widget, tab_bar_area);
+        frame.render_widget(tabs_widget, bar);
+        if let Some(button) = overflow_button {
+            let hidden = self.tabs.len() - window.len();
+            let label =
+                Paragraph::new(format!(" ▾ +{}", hidden)).style(Style::default().fg(Color::Cyan));
+            frame.render_widget(label, button);
+        }
 
         // Get active tab data for rendering content
         let tab = self.active_tab();
 
         // Calculate line number width
         let line_count = tab.lines.len();
-        let gutter_width = format!("{}", line_count).len() + 2;
+        let gutter_width = gutter_width(line_count);
 
         let scroll_y = tab.scroll.0;
 
         // Get selection range for highlighting
         let cursor_pos = Position::new(tab.cursor.0, tab.cursor.1);
-        let selection_bg = Color::Rgb(60, 80, 120); // Selection background color
+        let selection_bg = capabilities().adapt_color(Color::Rgb(60, 80, 120)); // Selection background color
+
+        // Diagnostics take the diff marker's place in the gutter
+        let severities = self.line_severities();
+
+        // Search matches, the one at the cursor brighter
+        let pattern = self.highlighted_pattern();
+        let match_style = Style::default().bg(capabilities().adapt_color(Color::Rgb(90, 75, 20)));
+        let current_match_style = Style::default()
+            .fg(Color::Black)
+            .bg(capabilities().adapt_color(Color::Rgb(230, 180, 50)));
 
         // Build lines with syntax highlighting
         let lines: Vec<Line> = tab
@@ -1235,10 +2378,13 @@ impl super::Panel for EditorPanel {
             .map(|(idx, line)| {
                 let change = tab.diff_tracker.get_line_change(idx);
 
-                let diff_marker = Span::styled(
-                    format!("{}", change.gutter_char()),
-                    change.gutter_style(),
-                );
+                let diff_marker = match severities.get(&idx) {
+                    Some(&severity) => Span::styled("●", severity_style(severity)),
+                    None => Span::styled(
+                        format!("{}", change.gutter_char()),
+                        change.gutter_style(),
+                    ),
+                };
 
                 let line_num = format!("{:>width$} ", idx + 1, width = gutter_width - 2);
                 let line_num_style = Style::default().fg(Color::DarkGray);
@@ -1246,13 +2392,27 @@ impl super::Panel for EditorPanel {
                 // Get selection range on this line if any
                 let line_char_count = line.chars().count();
                 let selection_range = tab.selection.line_range(cursor_pos, idx, line_char_count);
+                let matches: Vec<(usize, usize, Style)> = pattern
+                    .map(|pattern| search::find_in_line(line, pattern))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(start, end)| {
+                        let style = if (idx, start) == tab.cursor {
+                            current_match_style
+                        } else {
+                            match_style
+                        };
+                        (start, end, style)
+                    })
+                    .collect();
 
                 let content_spans: Vec<Span> = if idx < tab.highlighted_lines.len() {
                     // Apply selection highlighting to syntax-highlighted spans
                     let mut result_spans = Vec::new();
                     let mut char_pos = 0;
 
-                    for (text, style) in &tab.highlighted_lines[idx] {
+                    let spans = search::overlay(line, &tab.highlighted_lines[idx], &matches);
+                    for (text, style) in &spans {
                         let text_char_count = text.chars().count();
                         let span_start = char_pos;
                         let span_end = char_pos + text_char_count;
@@ -1268,7 +2428,9 @@ impl super::Panel for EditorPanel {
                                     let before_end = (sel_start - span_start).min(text_chars.len());
                                     let before: String = text_chars[..before_end].iter().collect();
                                     let mut s = *style;
-                                    if let Some(bg_style) = change.line_bg_style() {
+                                    if let Some(bg_style) =
+                                        change.line_bg_style().filter(|_| style.bg.is_none())
+                                    {
                                         s = s.bg(bg_style.bg.unwrap_or(Color::Reset));
                                     }
                                     result_spans.push(Span::styled(before, s));
@@ -1289,7 +2451,9 @@ impl super::Panel for EditorPanel {
                                     if after_start < text_chars.len() {
                                         let after: String = text_chars[after_start..].iter().collect();
                                         let mut s = *style;
-                                        if let Some(bg_style) = change.line_bg_style() {
+                                        if let Some(bg_style) =
+                                            change.line_bg_style().filter(|_| style.bg.is_none())
+                                        {
                                             s = s.bg(bg_style.bg.unwrap_or(Color::Reset));
                                         }
                                         result_spans.push(Span::styled(after, s));
@@ -1298,7 +2462,9 @@ impl super::Panel for EditorPanel {
                             } else {
                                 // No overlap with selection
                                 let mut s = *style;
-                                if let Some(bg_style) = change.line_bg_style() {
+                                if let Some(bg_style) =
+                                    change.line_bg_style().filter(|_| style.bg.is_none())
+                                {
                                     s = s.bg(bg_style.bg.unwrap_or(Color::Reset));
                                 }
                                 result_spans.push(Span::styled(text.clone(), s));
@@ -1306,7 +2472,9 @@ impl super::Panel for EditorPanel {
                         } else {
                             // No selection - normal rendering
                             let mut s = *style;
-                            if let Some(bg_style) = change.line_bg_style() {
+                            if let Some(bg_style) =
+                                change.line_bg_style().filter(|_| style.bg.is_none())
+                            {
                                 s = s.bg(bg_style.bg.unwrap_or(Color::Reset));
                             }
                             result_spans.push(Span::styled(text.clone(), s));
@@ -1316,7 +2484,10 @@ impl super::Panel for EditorPanel {
                     }
                     result_spans
                 } else {
-                    vec![Span::raw(line.clone())]
+                    search::overlay(line, &[], &matches)
+                        .into_iter()
+                        .map(|(text, style)| Span::styled(text, style))
+                        .collect()
                 };
 
                 let mut spans = vec![
@@ -1324,6 +2495,16 @@ impl super::Panel for EditorPanel {
                     Span::styled(line_num, line_num_style),
                 ];
                 spans.extend(content_spans);
+                if idx == tab.cursor.0 {
+                    if let Some(ghost) = self.ghost.visible(tab.cursor) {
+                        spans.push(Span::styled(
+                            ghost.to_string(),
+                            Style::default()
+                                .fg(Color::DarkGray)
+                                .add_modifier(Modifier::ITALIC),
+                        ));
+                    }
+                }
 
                 Line::from(spans)
             })
@@ -1339,7 +2520,8 @@ impl super::Panel for EditorPanel {
         // Show cursor if focused and in view
         if focused {
             if tab.cursor.0 >= scroll_y && tab.cursor.0 < scroll_y + visible_height {
-                let cursor_x = content_area.x + gutter_width as u16 + tab.cursor.1 as u16;
+                let column = columns::width_before(tab.current_line(), tab.cursor.1);
+                let cursor_x = content_area.x + gutter_width as u16 + column as u16;
                 let cursor_y = content_area.y + (tab.cursor.0 - scroll_y) as u16;
 
                 if cursor_x < content_area.x + content_area.width
@@ -1349,6 +2531,34 @@ impl super::Panel for EditorPanel {
                 }
             }
         }
+
+        // Code action menu just below the cursor
+        if let Some(menu) = &self.action_menu {
+            let cursor_row = tab.cursor.0.saturating_sub(scroll_y) as u16;
+            let column = columns::width_before(tab.current_line(), tab.cursor.1);
+            let x = content_area.x + gutter_width as u16 + column as u16;
+            let y = content_area.y + cursor_row.saturating_add(1);
+            menu.render(frame, content_area, x, y);
+        }
+
+        // Tab list hanging from the right end of the tab bar
+        if let Some(list) = &self.tab_list {
+            let right = tab_bar_area.x + tab_bar_area.width;
+            list.render(frame, inner, right, tab_bar_area.y + 1, &titles);
+        }
+
+        if let Some(prompt) = prompt {
+            let row = Rect {
+                y: content_area.y + content_area.height,
+                height: 1,
+                ..content_area
+            };
+            let style = Style::default().fg(Color::Yellow);
+            if focused && self.prompt.is_none() && self.line_prompt.is_some() {
+                frame.set_cursor_position((row.x + prompt.width() as u16, row.y));
+            }
+            frame.render_widget(Paragraph::new(prompt).style(style), row);
+        }
     }
 
     fn on_resize(&mut self, _cols: u16, rows: u16) {
@@ -1359,6 +2569,9 @@ impl super::Panel for EditorPanel {
     fn on_blur(&mut self) {
         // Clear selection when losing focus
         self.active_tab_mut().selection.clear();
+        self.action_menu = None;
+        self.tab_list = None;
+        self.line_prompt = None;
     }
 }
 
@@ -1374,6 +2587,345 @@ mod tests {
         assert_eq!(editor.tab_count(), 1);
     }
 
+    #[test]
+    fn test_code_action_sends_selection_to_conductor() {
+        use super::super::Panel;
+        use crossterm::event::KeyEvent;
+
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut editor = EditorPanel::new();
+        editor.set_event_tx(tx);
+        let mut state = AppState::new();
+        let alt_enter = Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT));
+
+        // Nothing selected
+        editor.handle_input(&alt_enter, &mut state).unwrap();
+        assert!(editor.action_menu.is_none());
+
+        let tab = editor.active_tab_mut();
+        tab.file_path = Some(PathBuf::from("src/calc.rs"));
+        tab.lines = ["fn main() {", "    let x = 1;", "    dbg!(x);", "}"]
+            .map(String::from)
+            .to_vec();
+        tab.selection.start(Position::new(1, 0));
+        tab.cursor = (3, 0);
+
+        editor.handle_input(&alt_enter, &mut state).unwrap();
+        assert!(editor.action_menu.is_some());
+        let explain = Event::Key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+        editor.handle_input(&explain, &mut state).unwrap();
+        assert!(editor.action_menu.is_none());
+
+        let Ok(Event::ConductorRequest(prompt)) = rx.try_recv() else {
+            panic!("expected a conductor request");
+        };
+        assert!(prompt.starts_with("Explain what the selected code does"));
+        assert!(prompt.contains("src/calc.rs (lines 2-3, Rust)"));
+        assert!(prompt.contains("```rust\n    let x = 1;\n    dbg!(x);\n```"));
+        assert!(prompt.contains("just before the selection:\n\n```rust\nfn main() {\n```"));
+    }
+
+    #[test]
+    fn test_ghost_completion_accept_and_cancel() {
+        use super::super::Panel;
+        use crossterm::event::KeyEvent;
+
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut editor = EditorPanel::new();
+        editor.set_event_tx(tx);
+        editor.set_completion(Some(CompletionConfig::default()));
+        let mut state = AppState::new();
+        state.input_mode.to_insert();
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+        editor.handle_input(&key(KeyCode::Char('x')), &mut state).unwrap();
+        let now = Instant::now() + Duration::from_secs(1);
+        let generation = editor.ghost.due(now, Duration::ZERO, (0, 1)).unwrap();
+        let answer = Event::GhostCompletion {
+            generation,
+            text: " = 42;".to_string(),
+        };
+        assert!(editor.handle_input(&answer, &mut state).unwrap());
+
+        editor.handle_input(&key(KeyCode::Tab), &mut state).unwrap();
+        assert_eq!(editor.active_tab().lines[0], "x = 42;");
+        assert_eq!(editor.active_tab().cursor, (0, 7));
+
+        // An answer to a request the user typed past is dropped
+        editor.handle_input(&key(KeyCode::Char(' ')), &mut state).unwrap();
+        let stale = editor.ghost.due(now, Duration::ZERO, (0, 8)).unwrap();
+        editor.handle_input(&key(KeyCode::Char('/')), &mut state).unwrap();
+        let answer = Event::GhostCompletion {
+            generation: stale,
+            text: "/ answer".to_string(),
+        };
+        assert!(!editor.handle_input(&answer, &mut state).unwrap());
+        editor.handle_input(&key(KeyCode::Tab), &mut state).unwrap();
+        assert_eq!(editor.active_tab().lines[0], "x = 42; /");
+    }
+
+    #[test]
+    fn test_save_and_conflict() {
+        use super::super::Panel;
+        use crossterm::event::KeyEvent;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "one\n").unwrap();
+        let mut editor = EditorPanel::new();
+        editor.open(&path).unwrap();
+        let mut state = AppState::new();
+        state.input_mode.to_insert();
+        let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+        let ctrl_s = key(KeyCode::Char('s'), KeyModifiers::CONTROL);
+
+        editor
+            .handle_input(&key(KeyCode::Char('1'), KeyModifiers::NONE), &mut state)
+            .unwrap();
+        editor.handle_input(&ctrl_s, &mut state).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1one\n");
+        assert!(!editor.active_tab().modified);
+
+        // Our own save isn't news
+        assert!(!editor.file_changed_on_disk(&path, &mut state));
+
+        // Someone else writes while there are unsaved edits
+        editor
+            .handle_input(&key(KeyCode::Char('2'), KeyModifiers::NONE), &mut state)
+            .unwrap();
+        let past = SystemTime::now() - Duration::from_secs(60);
+        std::fs::write(&path, "theirs\n").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(past)
+            .unwrap();
+        assert!(editor
+            .handle_input(&Event::FileChanged(path.clone()), &mut state)
+            .unwrap());
+        assert!(editor.active_tab().changed_on_disk);
+
+        editor.handle_input(&ctrl_s, &mut state).unwrap();
+        assert_eq!(editor.prompt, Some(SavePrompt::Conflict(0)));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "theirs\n");
+        editor
+            .handle_input(&key(KeyCode::Char('r'), KeyModifiers::NONE), &mut state)
+            .unwrap();
+        assert!(editor.prompt.is_none());
+        assert_eq!(editor.active_tab().lines, vec!["theirs"]);
+        assert!(!editor.active_tab().modified);
+
+        // Without unsaved edits a change on disk just reloads
+        std::fs::write(&path, "again").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(past - Duration::from_secs(60))
+            .unwrap();
+        assert!(editor.file_changed_on_disk(&path, &mut state));
+        assert_eq!(editor.active_tab().lines, vec!["again"]);
+        assert!(!editor.active_tab().trailing_newline);
+    }
+
+    #[test]
+    fn test_close_with_unsaved_changes() {
+        use super::super::Panel;
+        use crossterm::event::KeyEvent;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b) = (dir.path().join("a.txt"), dir.path().join("b.txt"));
+        std::fs::write(&a, "a\n").unwrap();
+        std::fs::write(&b, "b\n").unwrap();
+        let mut editor = EditorPanel::new();
+        editor.open(&a).unwrap();
+        editor.open(&b).unwrap();
+        let mut state = AppState::new();
+        state.input_mode.to_insert();
+        let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+        let ctrl_w = key(KeyCode::Char('w'), KeyModifiers::CONTROL);
+
+        for index in 0..2 {
+            editor.switch_tab(index);
+            editor
+                .handle_input(&key(KeyCode::Char('x'), KeyModifiers::NONE), &mut state)
+                .unwrap();
+        }
+        assert!(editor.has_unsaved());
+
+        editor.handle_input(&ctrl_w, &mut state).unwrap();
+        assert_eq!(editor.prompt, Some(SavePrompt::Close(1)));
+        // Other keys leave the question open
+        editor
+            .handle_input(&key(KeyCode::Char('q'), KeyModifiers::NONE), &mut state)
+            .unwrap();
+        assert!(editor.prompt.is_some());
+        editor
+            .handle_input(&key(KeyCode::Char('d'), KeyModifiers::NONE), &mut state)
+            .unwrap();
+        assert_eq!(editor.tab_count(), 1);
+        assert_eq!(std::fs::read_to_string(&b).unwrap(), "b\n");
+
+        let ctrl_alt_s = key(
+            KeyCode::Char('s'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+        );
+        editor.handle_input(&ctrl_alt_s, &mut state).unwrap();
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "xa\n");
+        assert!(!editor.has_unsaved());
+        editor.handle_input(&ctrl_w, &mut state).unwrap();
+        assert!(editor.prompt.is_none());
+    }
+
+    #[test]
+    fn test_tabs_follow_moved_and_deleted_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        let (a, b) = (src.join("a.rs"), src.join("b.rs"));
+        std::fs::write(&a, "a\n").unwrap();
+        std::fs::write(&b, "b\n").unwrap();
+        let mut editor = EditorPanel::new();
+        editor.open(&a).unwrap();
+        editor.open(&b).unwrap();
+
+        let lib = dir.path().join("lib");
+        std::fs::rename(&src, &lib).unwrap();
+        editor.path_moved(&src, &lib);
+        assert_eq!(editor.find_tab_by_path(&lib.join("a.rs")), Some(0));
+        assert_eq!(editor.current_file(), Some(lib.join("b.rs").as_path()));
+        assert!(!editor.has_file_open(&a));
+
+        editor.active_tab_mut().modified = true;
+        std::fs::remove_dir_all(&lib).unwrap();
+        editor.path_deleted(&lib);
+        assert_eq!(editor.tab_count(), 1);
+        assert_eq!(editor.current_file(), Some(lib.join("b.rs").as_path()));
+        assert!(editor.active_tab().changed_on_disk);
+        assert!(!editor.reopen_closed_tab().unwrap());
+    }
+
+    #[test]
+    fn test_word_motions_and_deletion() {
+        use super::super::Panel;
+        use crossterm::event::{KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+
+        let mut editor = EditorPanel::new();
+        editor.active_tab_mut().lines =
+            ["let x = foo.bar;", "    baz()"].map(String::from).to_vec();
+        *editor.content_area.borrow_mut() = Rect::new(0, 1, 40, 10);
+        let mut state = AppState::new();
+        let mut press = |editor: &mut EditorPanel, code, modifiers| {
+            let event = Event::Key(KeyEvent::new(code, modifiers));
+            editor.handle_input(&event, &mut state).unwrap();
+            editor.active_tab().cursor
+        };
+
+        // Normal mode: w, e and b, crossing line breaks
+        let none = KeyModifiers::NONE;
+        assert_eq!(press(&mut editor, KeyCode::Char('w'), none), (0, 4));
+        assert_eq!(press(&mut editor, KeyCode::Char('e'), none), (0, 6));
+        assert_eq!(press(&mut editor, KeyCode::Char('e'), none), (0, 10));
+        assert_eq!(press(&mut editor, KeyCode::Char('w'), none), (0, 11));
+        editor.active_tab_mut().cursor = (0, 15);
+        assert_eq!(press(&mut editor, KeyCode::Char('w'), none), (1, 4));
+        assert_eq!(press(&mut editor, KeyCode::Char('b'), none), (0, 15));
+        assert_eq!(press(&mut editor, KeyCode::Char('b'), none), (0, 12));
+
+        // Double-click selects the word under the pointer
+        let click = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 3 + 9,
+            row: 1,
+            modifiers: none,
+        });
+        editor.handle_input(&click, &mut AppState::new()).unwrap();
+        assert!(!editor.active_tab().selection.is_active());
+        editor.handle_input(&click, &mut AppState::new()).unwrap();
+        assert_eq!(editor.get_selected_text().as_deref(), Some("foo"));
+
+        // Insert mode: Ctrl+Backspace/Delete remove a word as one undo step
+        let mut state = AppState::new();
+        state.input_mode.to_insert();
+        let mut press = |editor: &mut EditorPanel, code, modifiers| {
+            let event = Event::Key(KeyEvent::new(code, modifiers));
+            editor.handle_input(&event, &mut state).unwrap();
+        };
+        press(&mut editor, KeyCode::Esc, none);
+        editor.active_tab_mut().cursor = (0, 15);
+        press(&mut editor, KeyCode::Backspace, KeyModifiers::CONTROL);
+        assert_eq!(editor.active_tab().lines[0], "let x = foo.;");
+        assert_eq!(editor.active_tab().cursor, (0, 12));
+        press(&mut editor, KeyCode::Char('z'), KeyModifiers::CONTROL);
+        assert_eq!(editor.active_tab().lines[0], "let x = foo.bar;");
+
+        editor.active_tab_mut().cursor = (0, 0);
+        press(&mut editor, KeyCode::Delete, KeyModifiers::CONTROL);
+        assert_eq!(editor.active_tab().lines[0], "x = foo.bar;");
+        press(
+            &mut editor,
+            KeyCode::Right,
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        );
+        assert_eq!(editor.get_selected_text().as_deref(), Some("x "));
+    }
+
+    #[test]
+    fn test_search_and_go_to_line() {
+        use super::super::Panel;
+        use crossterm::event::KeyEvent;
+
+        let mut editor = EditorPanel::new();
+        editor.active_tab_mut().lines = ["let foo = 1;", "bar(foo);", "", "Foo::new()"]
+            .map(String::from)
+            .to_vec();
+        let mut state = AppState::new();
+        let type_keys = |editor: &mut EditorPanel, state: &mut AppState, keys: &[KeyCode]| {
+            for &code in keys {
+                let event = Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+                editor.handle_input(&event, state).unwrap();
+            }
+            editor.active_tab().cursor
+        };
+        let chars = |text: &str| text.chars().map(KeyCode::Char).collect::<Vec<_>>();
+        let enter = [KeyCode::Enter];
+
+        // Typing moves to the first match; Esc goes back
+        assert_eq!(type_keys(&mut editor, &mut state, &chars("/fo")), (0, 4));
+        assert_eq!(state.input_mode.query(), Some(""));
+        assert_eq!(editor.prompt_line().as_deref(), Some(" /fo  (1 of 3)"));
+        assert_eq!(type_keys(&mut editor, &mut state, &[KeyCode::Esc]), (0, 0));
+        assert!(!editor.takes_esc());
+        assert!(state.input_mode.query().is_none());
+
+        // n and N step through matches, wrapping around
+        type_keys(&mut editor, &mut state, &chars("/foo"));
+        type_keys(&mut editor, &mut state, &enter);
+        assert_eq!(editor.search.as_deref(), Some("foo"));
+        assert_eq!(type_keys(&mut editor, &mut state, &chars("n")), (1, 4));
+        assert_eq!(type_keys(&mut editor, &mut state, &chars("n")), (3, 0));
+        assert_eq!(type_keys(&mut editor, &mut state, &chars("n")), (0, 4));
+        let message = state.status_message.as_ref().unwrap();
+        assert_eq!(message.text, "Search hit BOTTOM, continuing at TOP");
+        assert_eq!(type_keys(&mut editor, &mut state, &chars("N")), (3, 0));
+
+        // A capital makes the search match case
+        type_keys(&mut editor, &mut state, &chars("/Foo"));
+        assert_eq!(type_keys(&mut editor, &mut state, &enter), (3, 0));
+        type_keys(&mut editor, &mut state, &chars("/nope"));
+        assert_eq!(type_keys(&mut editor, &mut state, &enter), (3, 0));
+        let message = state.status_message.as_ref().unwrap();
+        assert_eq!(message.text, "Pattern not found: nope");
+
+        // :N goes to line N, clamped to the file
+        type_keys(&mut editor, &mut state, &chars(":2"));
+        assert_eq!(editor.prompt_line().as_deref(), Some(" :2"));
+        assert_eq!(type_keys(&mut editor, &mut state, &enter), (1, 0));
+        type_keys(&mut editor, &mut state, &chars(":99"));
+        assert_eq!(type_keys(&mut editor, &mut state, &enter), (3, 0));
+    }
+
     #[test]
     fn test_editor_panel_default() {
         let editor = EditorPanel::default();
@@ -1508,6 +3060,75 @@ mod tests {
         assert_eq!(editor.tab_count(), 1);
     }
 
+    #[test]
+    fn test_pinned_and_reopened_tabs() {
+        use super::super::Panel;
+        use crossterm::event::KeyEvent;
+
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<PathBuf> = ["a.rs", "b.rs", "c.rs"]
+            .iter()
+            .map(|name| dir.path().join(name))
+            .collect();
+        for path in &paths {
+            std::fs::write(path, "one\ntwo\nthree\n").unwrap();
+        }
+        let mut editor = EditorPanel::new();
+        for path in &paths {
+            editor.open(path).unwrap();
+        }
+        let mut state = AppState::new();
+        let mut press = |editor: &mut EditorPanel, code, modifiers| {
+            let event = Event::Key(KeyEvent::new(code, modifiers));
+            editor.handle_input(&event, &mut state).unwrap();
+        };
+        let names = |editor: &EditorPanel| -> Vec<String> {
+            editor.tabs.iter().map(FileTab::display_name).collect()
+        };
+
+        // Pinning c.rs moves it first and keeps it active; Ctrl+W leaves it
+        press(&mut editor, KeyCode::Char('p'), KeyModifiers::ALT);
+        assert_eq!(names(&editor), ["c.rs", "a.rs", "b.rs"]);
+        assert_eq!(editor.current_file(), Some(paths[2].as_path()));
+        press(&mut editor, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(editor.tab_count(), 3);
+
+        // Closed tabs come back where their cursor was, newest first
+        editor.switch_tab(2);
+        editor.active_tab_mut().cursor = (2, 3);
+        press(&mut editor, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        editor.switch_tab(0);
+        editor.tabs[0].pinned = false;
+        press(&mut editor, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(names(&editor), ["a.rs"]);
+
+        let ctrl_shift = KeyModifiers::CONTROL | KeyModifiers::SHIFT;
+        press(&mut editor, KeyCode::Char('T'), ctrl_shift);
+        assert_eq!(editor.current_file(), Some(paths[2].as_path()));
+        press(&mut editor, KeyCode::Char('t'), ctrl_shift);
+        assert_eq!(editor.current_file(), Some(paths[1].as_path()));
+        assert_eq!(editor.active_tab().cursor, (2, 3));
+        press(&mut editor, KeyCode::Char('T'), KeyModifiers::CONTROL);
+        assert_eq!(editor.tab_count(), 3);
+
+        // Tabs that don't fit leave a button opening the list of all tabs
+        let backend = ratatui::backend::TestBackend::new(18, 10);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|f| editor.render(f, f.area(), true)).unwrap();
+        let button = editor.overflow_button.borrow().expect("tabs overflow");
+        assert!(editor
+            .tab_boundaries
+            .borrow()
+            .iter()
+            .any(|&(idx, _, _)| idx == editor.active_tab));
+        press(&mut editor, KeyCode::Char('0'), KeyModifiers::ALT);
+        press(&mut editor, KeyCode::Down, KeyModifiers::NONE);
+        press(&mut editor, KeyCode::Enter, KeyModifiers::NONE);
+        assert!(editor.tab_list.is_none());
+        assert_eq!(editor.active_tab, 0);
+        assert_eq!(button.x + button.width, 17);
+    }
+
     #[test]
     fn test_editor_find_tab_by_path() {
         let mut editor = EditorPanel::new();
@@ -1621,6 +3242,33 @@ mod tests {
         assert!(editor.active_tab().modified);
     }
 
+    #[test]
+    fn test_editor_track_external_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = dir.path().join("lib.rs");
+        let main = dir.path().join("main.rs");
+        std::fs::write(&lib, "fn old() {}\nfn keep() {}\n").unwrap();
+        std::fs::write(&main, "fn main() {}\n").unwrap();
+
+        let mut editor = EditorPanel::new();
+        editor.open(&lib).unwrap();
+        editor.open(&main).unwrap();
+        let active = editor.active_tab;
+
+        assert!(editor.track_external_change(&lib, "fn new() {}\nfn keep() {}\n"));
+        assert_eq!(editor.active_tab, active);
+        let tab = &editor.tabs[editor.find_tab_by_path(&lib).unwrap()];
+        assert_eq!(tab.lines[0], "fn new() {}");
+        assert!(!tab.modified);
+        assert_eq!(tab.diff_tracker.get_line_change(1), diff::LineChange::Unchanged);
+        assert_ne!(tab.diff_tracker.get_line_change(0), diff::LineChange::Unchanged);
+
+        // Unsaved edits and files that aren't open are left alone
+        editor.active_tab_mut().modified = true;
+        assert!(!editor.track_external_change(&main, "fn main() { run(); }\n"));
+        assert!(!editor.track_external_change(&dir.path().join("x.rs"), ""));
+    }
+
     #[test]
     fn test_editor_diff_tracking() {
         let mut editor = EditorPanel::new();
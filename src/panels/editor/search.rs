@@ -0,0 +1,256 @@
+//! Searching within a file and going to a line
+//!
+//! `/` opens a search prompt on the editor's bottom row that moves the
+//! cursor to the first match as the pattern is typed, and `:` one that takes
+//! a line number. Matching is smart-case: a pattern with no capitals ignores
+//! case. Matches are char ranges, like cursor columns, and are laid over the
+//! cached syntax highlighting when drawn.
+
+use ratatui::style::Style;
+
+/// Which question the bottom-row prompt asks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    /// `/pattern`
+    Search,
+    /// `:42`
+    GoToLine,
+}
+
+impl PromptKind {
+    /// Character shown before the typed text
+    pub fn sigil(self) -> char {
+        match self {
+            PromptKind::Search => '/',
+            PromptKind::GoToLine => ':',
+        }
+    }
+}
+
+/// A search or go-to-line prompt, while open
+#[derive(Debug, Clone)]
+pub struct LinePrompt {
+    pub kind: PromptKind,
+    pub text: String,
+    /// Cursor and scroll when the prompt opened, restored on Esc
+    pub origin: ((usize, usize), (usize, usize)),
+}
+
+impl LinePrompt {
+    pub fn new(kind: PromptKind, cursor: (usize, usize), scroll: (usize, usize)) -> Self {
+        Self {
+            kind,
+            text: String::new(),
+            origin: (cursor, scroll),
+        }
+    }
+}
+
+/// Where to look for a match relative to the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seek {
+    /// The first match starting at the cursor or after it
+    From,
+    /// The first match starting after the cursor
+    After,
+    /// The last match starting before the cursor
+    Before,
+}
+
+/// A match found by [`seek`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Found {
+    /// Line and char column the match starts at
+    pub at: (usize, usize),
+    /// Whether the search went past one end of the file to the other
+    pub wrapped: bool,
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Char ranges of `pattern` in `line`, not overlapping
+pub fn find_in_line(line: &str, pattern: &str) -> Vec<(usize, usize)> {
+    let ignore_case = !pattern.chars().any(char::is_uppercase);
+    let norm = |c: char| if ignore_case { fold(c) } else { c };
+    let needle: Vec<char> = pattern.chars().map(norm).collect();
+    let hay: Vec<char> = line.chars().map(norm).collect();
+    let mut ranges = Vec::new();
+    if needle.is_empty() {
+        return ranges;
+    }
+    let mut start = 0;
+    while start + needle.len() <= hay.len() {
+        if hay[start..start + needle.len()] == needle[..] {
+            ranges.push((start, start + needle.len()));
+            start += needle.len();
+        } else {
+            start += 1;
+        }
+    }
+    ranges
+}
+
+/// Start of every match of `pattern` in `lines`, in order
+pub fn find_all(lines: &[String], pattern: &str) -> Vec<(usize, usize)> {
+    lines
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, line)| {
+            find_in_line(line, pattern)
+                .into_iter()
+                .map(move |(start, _)| (idx, start))
+        })
+        .collect()
+}
+
+/// The match of `pattern` nearest `cursor` in the direction of `seek`,
+/// wrapping around the file
+pub fn seek(lines: &[String], pattern: &str, cursor: (usize, usize), seek: Seek) -> Option<Found> {
+    let matches = find_all(lines, pattern);
+    let found = match seek {
+        Seek::From => matches.iter().find(|&&at| at >= cursor),
+        Seek::After => matches.iter().find(|&&at| at > cursor),
+        Seek::Before => matches.iter().rev().find(|&&at| at < cursor),
+    };
+    match found {
+        Some(&at) => Some(Found { at, wrapped: false }),
+        None => {
+            let at = match seek {
+                Seek::Before => matches.last(),
+                Seek::From | Seek::After => matches.first(),
+            };
+            at.map(|&at| Found { at, wrapped: true })
+        }
+    }
+}
+
+/// Which match starts at `cursor`, counting from 1, and how many there are
+pub fn position(lines: &[String], pattern: &str, cursor: (usize, usize)) -> (Option<usize>, usize) {
+    let matches = find_all(lines, pattern);
+    let index = matches.iter().position(|&at| at == cursor).map(|i| i + 1);
+    (index, matches.len())
+}
+
+/// Line index for a typed line number, counting from 1 and clamped to the
+/// `line_count` lines of the file
+pub fn parse_line_number(text: &str, line_count: usize) -> Option<usize> {
+    let number: usize = text.trim().parse().ok()?;
+    Some(number.saturating_sub(1).min(line_count.saturating_sub(1)))
+}
+
+/// `spans` with each char range in `ranges` patched with its style
+///
+/// The spans are the cached highlighting of `line`; while they are behind
+/// an edit, the plain line is used instead so matches land on the right
+/// text.
+pub fn overlay(
+    line: &str,
+    spans: &[(String, Style)],
+    ranges: &[(usize, usize, Style)],
+) -> Vec<(String, Style)> {
+    let in_sync = spans
+        .iter()
+        .flat_map(|(text, _)| text.chars())
+        .eq(line.chars());
+    let plain = [(line.to_string(), Style::default())];
+    let spans = if in_sync { spans } else { &plain[..] };
+    if ranges.is_empty() {
+        return spans.to_vec();
+    }
+
+    let mut result = Vec::new();
+    let mut pos = 0;
+    for (text, style) in spans {
+        let chars: Vec<char> = text.chars().collect();
+        let (span_start, span_end) = (pos, pos + chars.len());
+        // Cut the span at each range boundary inside it
+        let mut cuts = vec![span_start, span_end];
+        for &(start, end, _) in ranges {
+            cuts.extend(
+                [start, end]
+                    .into_iter()
+                    .filter(|&c| c > span_start && c < span_end),
+            );
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+        for piece in cuts.windows(2) {
+            let (from, to) = (piece[0], piece[1]);
+            let text: String = chars[from - span_start..to - span_start].iter().collect();
+            let style = ranges
+                .iter()
+                .find(|&&(start, end, _)| start <= from && to <= end)
+                .map_or(*style, |&(_, _, patch)| style.patch(patch));
+            result.push((text, style));
+        }
+        pos = span_end;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_smart_case_and_wrapping() {
+        assert_eq!(
+            find_in_line("Foo foo fOO", "foo"),
+            vec![(0, 3), (4, 7), (8, 11)]
+        );
+        assert_eq!(find_in_line("Foo foo fOO", "Foo"), vec![(0, 3)]);
+        assert_eq!(find_in_line("aaaa", "aa"), vec![(0, 2), (2, 4)]);
+        assert_eq!(find_in_line("日本語 本", "本"), vec![(1, 2), (4, 5)]);
+
+        let file = lines(&["let x = 1;", "x += 1;", "print(x)"]);
+        let found = |cursor, how| seek(&file, "x", cursor, how).map(|f| (f.at, f.wrapped));
+        assert_eq!(found((0, 4), Seek::From), Some(((0, 4), false)));
+        assert_eq!(found((0, 4), Seek::After), Some(((1, 0), false)));
+        assert_eq!(found((2, 6), Seek::After), Some(((0, 4), true)));
+        assert_eq!(found((0, 4), Seek::Before), Some(((2, 6), true)));
+        assert_eq!(seek(&file, "y", (0, 0), Seek::From), None);
+        assert_eq!(position(&file, "x", (1, 0)), (Some(2), 3));
+
+        assert_eq!(parse_line_number("2", 3), Some(1));
+        assert_eq!(parse_line_number("99", 3), Some(2));
+        assert_eq!(parse_line_number("0", 3), Some(0));
+        assert_eq!(parse_line_number("x", 3), None);
+    }
+
+    #[test]
+    fn test_overlay_splits_highlighted_spans() {
+        let keyword = Style::default().fg(Color::Magenta);
+        let mark = Style::default().bg(Color::Yellow);
+        let spans = vec![
+            ("let".to_string(), keyword),
+            (" xyz = 1;".to_string(), Style::default()),
+        ];
+        let overlaid = overlay("let xyz = 1;", &spans, &[(2, 5, mark)]);
+        assert_eq!(
+            overlaid,
+            vec![
+                ("le".to_string(), keyword),
+                ("t".to_string(), keyword.bg(Color::Yellow)),
+                (" x".to_string(), mark),
+                ("yz = 1;".to_string(), Style::default()),
+            ]
+        );
+
+        // Spans behind an edit give way to the line itself
+        let overlaid = overlay("let y", &spans, &[(4, 5, mark)]);
+        assert_eq!(
+            overlaid,
+            vec![
+                ("let ".to_string(), Style::default()),
+                ("y".to_string(), mark)
+            ]
+        );
+    }
+}
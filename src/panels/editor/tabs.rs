@@ -0,0 +1,190 @@
+//! Tab bar helpers: recently closed tabs, overflow and the tab list
+//!
+//! Closed tabs with a file are remembered (newest last) so Ctrl+Shift+T can
+//! reopen them where the cursor was. When the tabs don't fit in the bar,
+//! only a window around the active tab is drawn, with a `▾ +N` button at
+//! the bar's end that opens a list of every tab (also on Alt+0).
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::cell::Cell;
+use std::ops::Range;
+use std::path::PathBuf;
+use unicode_width::UnicodeWidthStr;
+
+/// Closed tabs remembered for reopening
+pub const CLOSED_TABS_KEPT: usize = 20;
+
+/// A closed tab that can be reopened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosedTab {
+    pub path: PathBuf,
+    pub cursor: (usize, usize),
+    pub scroll: (usize, usize),
+    pub pinned: bool,
+}
+
+/// Tabs drawn in a bar `width` cells wide, given each tab's width
+///
+/// Starts at the first tab when the active one fits from there, and
+/// otherwise ends the window at the active tab, filling leftwards.
+pub fn visible_tabs(widths: &[u16], active: usize, width: u16) -> Range<usize> {
+    let fits =
+        |range: Range<usize>| widths[range].iter().map(|&w| w as u32).sum::<u32>() <= width as u32;
+    let active = active.min(widths.len().saturating_sub(1));
+    let mut start = 0;
+    while start < active && !fits(start..active + 1) {
+        start += 1;
+    }
+    let mut end = active + 1;
+    while end < widths.len() && fits(start..end + 1) {
+        end += 1;
+    }
+    start..end.min(widths.len())
+}
+
+/// Result of a key press in the tab list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabListAction {
+    None,
+    Cancelled,
+    Chosen(usize),
+}
+
+/// Dropdown listing every tab, for when they overflow the bar
+#[derive(Debug, Default)]
+pub struct TabList {
+    selected: usize,
+    /// First row shown, when the list is taller than its area
+    top: Cell<usize>,
+    /// Where the list was last drawn, for clicks
+    area: Cell<Rect>,
+}
+
+impl TabList {
+    /// Open the list on the active tab
+    pub fn new(active: usize) -> Self {
+        Self {
+            selected: active,
+            ..Self::default()
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, count: usize) -> TabListAction {
+        if count == 0 {
+            return TabListAction::Cancelled;
+        }
+        match key.code {
+            KeyCode::Esc => TabListAction::Cancelled,
+            KeyCode::Char('0') if key.modifiers.contains(KeyModifiers::ALT) => {
+                TabListAction::Cancelled
+            }
+            KeyCode::Enter => TabListAction::Chosen(self.selected.min(count - 1)),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = (self.selected + count - 1) % count;
+                TabListAction::None
+            }
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => {
+                self.selected = (self.selected + 1) % count;
+                TabListAction::None
+            }
+            _ => TabListAction::None,
+        }
+    }
+
+    /// Tab under (`x`, `y`) as last drawn
+    pub fn tab_at(&self, x: u16, y: u16, count: usize) -> Option<usize> {
+        let area = self.area.get();
+        let inside =
+            x > area.x && x + 1 < area.x + area.width && y > area.y && y + 1 < area.y + area.height;
+        let index = self.top.get() + (y.saturating_sub(area.y + 1)) as usize;
+        (inside && index < count).then_some(index)
+    }
+
+    /// Render the list of tab `titles` with its top right corner at
+    /// (`right`, `y`), kept inside `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, right: u16, y: u16, titles: &[String]) {
+        let width = titles.iter().map(|title| title.width()).max().unwrap_or(0) as u16 + 2;
+        let width = width.min(area.width);
+        let height = (titles.len() as u16 + 2).min(area.height);
+        let x = right.saturating_sub(width).max(area.x);
+        let y = y.min(area.y + area.height.saturating_sub(height));
+        let list_area = Rect::new(x, y, width, height);
+        self.area.set(list_area);
+
+        let rows = height.saturating_sub(2) as usize;
+        let mut top = self.top.get().min(self.selected);
+        if rows > 0 && self.selected >= top + rows {
+            top = self.selected + 1 - rows;
+        }
+        self.top.set(top);
+
+        frame.render_widget(Clear, list_area);
+        let lines: Vec<Line> = titles
+            .iter()
+            .enumerate()
+            .skip(top)
+            .take(rows)
+            .map(|(i, title)| {
+                let style = if i == self.selected {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                Line::from(Span::styled(title.as_str(), style))
+            })
+            .collect();
+
+        let block = Block::default()
+            .title(format!(" Tabs ({}) ", titles.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        frame.render_widget(Paragraph::new(lines).block(block), list_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_tabs() {
+        let widths = [10, 10, 10, 10, 10];
+        assert_eq!(visible_tabs(&widths, 0, 100), 0..5);
+        assert_eq!(visible_tabs(&widths, 1, 35), 0..3);
+        assert_eq!(visible_tabs(&widths, 4, 35), 2..5);
+        assert_eq!(visible_tabs(&widths, 3, 20), 2..4);
+        // An active tab wider than the bar is still the one shown
+        assert_eq!(visible_tabs(&[10, 50, 10], 1, 20), 1..2);
+        assert_eq!(visible_tabs(&[], 0, 20), 0..0);
+    }
+
+    #[test]
+    fn test_tab_list_keys() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut list = TabList::new(1);
+        assert_eq!(list.handle_key(key(KeyCode::Down), 3), TabListAction::None);
+        assert_eq!(
+            list.handle_key(key(KeyCode::Enter), 3),
+            TabListAction::Chosen(2)
+        );
+        list.handle_key(key(KeyCode::Down), 3);
+        assert_eq!(
+            list.handle_key(key(KeyCode::Enter), 3),
+            TabListAction::Chosen(0)
+        );
+        assert_eq!(
+            list.handle_key(key(KeyCode::Esc), 3),
+            TabListAction::Cancelled
+        );
+    }
+}
@@ -1,10 +1,17 @@
-//! File tree panel for directory navigation
+//! File tree panel for directory navigation and file management
+//!
+//! `n`/`N` create a file or folder next to the selection (inside it if it's
+//! a folder), `r` renames, `d` deletes after a y/n confirmation, and `x`
+//! then `p` moves an entry. Each change is sent as an event so the output
+//! view can follow it.
 
 use crate::core::Result;
 use crate::events::Event;
 use crate::state::{AppState, PanelId};
 use crate::ui::theme::theme;
 use crate::ui::ScrollBar;
+use axiom_core::files::{create_path, delete_path, move_path};
+use crossbeam_channel::Sender;
 use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::{
     layout::Rect,
@@ -14,6 +21,7 @@ use ratatui::{
     Frame,
 };
 use std::cell::Cell;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 /// File or directory entry in the tree
@@ -26,6 +34,23 @@ pub struct FileEntry {
     pub depth: usize,
 }
 
+/// Input the file tree is waiting for
+#[derive(Debug, Clone, PartialEq)]
+enum Prompt {
+    /// Name of a new file or folder in `dir`
+    Create {
+        dir: PathBuf,
+        is_dir: bool,
+        name: String,
+    },
+
+    /// New name for `path`
+    Rename { path: PathBuf, name: String },
+
+    /// Confirmation to delete `path`
+    Delete { path: PathBuf },
+}
+
 /// File tree panel
 pub struct FileTreePanel {
     /// Root directory
@@ -48,6 +73,15 @@ pub struct FileTreePanel {
 
     /// Current scroll offset for mouse click detection (updated during render)
     render_scroll: Cell<usize>,
+
+    /// Name or confirmation being asked for
+    prompt: Option<Prompt>,
+
+    /// Entry cut with `x`, moved on `p`
+    cut: Option<PathBuf>,
+
+    /// Where file changes are reported
+    event_tx: Option<Sender<Event>>,
 }
 
 impl FileTreePanel {
@@ -61,11 +95,19 @@ impl FileTreePanel {
             pending_open: None,
             inner_area: Cell::new(Rect::default()),
             render_scroll: Cell::new(0),
+            prompt: None,
+            cut: None,
+            event_tx: None,
         };
         panel.refresh();
         panel
     }
 
+    /// Set the channel file changes are reported on
+    pub fn set_event_tx(&mut self, event_tx: Sender<Event>) {
+        self.event_tx = Some(event_tx);
+    }
+
     /// Take pending file to open (returns and clears it)
     pub fn take_pending_open(&mut self) -> Option<PathBuf> {
         self.pending_open.take()
@@ -81,6 +123,8 @@ impl FileTreePanel {
         self.selected = 0;
         self.scroll = 0;
         self.pending_open = None;
+        self.prompt = None;
+        self.cut = None;
         self.refresh();
     }
 
@@ -95,6 +139,200 @@ impl FileTreePanel {
         self.load_dir(&self.root.clone(), 0, true);
     }
 
+    /// Reload from disk, keeping open directories open and selecting
+    /// `select` (opening its parents) if given
+    fn reload(&mut self, select: Option<&Path>) {
+        let mut open: HashSet<PathBuf> = self
+            .entries
+            .iter()
+            .filter(|e| e.is_dir && e.expanded)
+            .map(|e| e.path.clone())
+            .collect();
+        if let Some(path) = select {
+            open.extend(path.ancestors().skip(1).map(Path::to_path_buf));
+        }
+        let previous = self.selected;
+
+        self.refresh();
+        // Expanding inserts children after the entry, so they're visited too
+        let mut i = 0;
+        while i < self.entries.len() {
+            if self.entries[i].is_dir && open.contains(&self.entries[i].path) {
+                self.selected = i;
+                self.toggle_selected();
+            }
+            i += 1;
+        }
+
+        let last = self.entries.len().saturating_sub(1);
+        self.selected = select
+            .and_then(|path| self.entries.iter().position(|e| e.path == path))
+            .unwrap_or(previous.min(last));
+    }
+
+    /// Directory new entries go in: the selection if it's a directory,
+    /// otherwise the one it's in
+    fn target_dir(&self) -> PathBuf {
+        match self.entries.get(self.selected) {
+            Some(entry) if entry.is_dir => entry.path.clone(),
+            Some(entry) => entry
+                .path
+                .parent()
+                .map_or_else(|| self.root.clone(), Path::to_path_buf),
+            None => self.root.clone(),
+        }
+    }
+
+    /// Path relative to the root, for messages
+    fn display_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+
+    fn emit(&self, event: Event) {
+        if let Some(tx) = &self.event_tx {
+            let _ = tx.send(event);
+        }
+    }
+
+    fn open_prompt(&mut self, prompt: Prompt, state: &mut AppState) {
+        self.prompt = Some(prompt);
+        state.input_mode.open_modal("file_tree_prompt");
+    }
+
+    fn close_prompt(&mut self, state: &mut AppState) {
+        self.prompt = None;
+        state.input_mode.to_normal();
+    }
+
+    /// Handle a key while a prompt is open
+    fn prompt_key(&mut self, key: &crossterm::event::KeyEvent, state: &mut AppState) {
+        let Some(prompt) = self.prompt.as_mut() else {
+            return;
+        };
+        if let Prompt::Delete { path } = prompt {
+            let path = path.clone();
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.delete(&path, state);
+            }
+            self.close_prompt(state);
+            return;
+        }
+
+        let (Prompt::Create { name, .. } | Prompt::Rename { name, .. }) = prompt else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.close_prompt(state),
+            KeyCode::Enter => {
+                if let Some(prompt) = self.prompt.take() {
+                    self.apply_prompt(prompt, state);
+                }
+                if self.prompt.is_none() {
+                    state.input_mode.to_normal();
+                }
+            }
+            KeyCode::Backspace => {
+                name.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => name.push(c),
+            _ => {}
+        }
+    }
+
+    /// Carry out a create or rename
+    fn apply_prompt(&mut self, prompt: Prompt, state: &mut AppState) {
+        match prompt {
+            Prompt::Create { dir, is_dir, name } if !name.trim().is_empty() => {
+                match create_path(&self.root, &dir.join(name.trim()), is_dir) {
+                    Ok(path) => {
+                        state.note_tree_change(&path);
+                        state.info(format!("Created: {}", self.display_path(&path)));
+                        self.reload(Some(&path));
+                        self.emit(Event::PathCreated(path));
+                    }
+                    Err(e) => state.error(format!("Create failed: {}", e)),
+                }
+            }
+            Prompt::Rename { path, name } if !name.trim().is_empty() => {
+                let to = path.with_file_name(name.trim());
+                if to != path {
+                    self.move_entry(&path, &to, state);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn move_entry(&mut self, from: &Path, to: &Path, state: &mut AppState) {
+        match move_path(&self.root, from, to) {
+            Ok(to) => {
+                state.note_tree_change(&to);
+                state.info(format!(
+                    "Moved {} to {}",
+                    self.display_path(from),
+                    self.display_path(&to)
+                ));
+                self.reload(Some(&to));
+                self.emit(Event::PathMoved {
+                    from: from.to_path_buf(),
+                    to,
+                });
+            }
+            Err(e) => state.error(format!("Move failed: {}", e)),
+        }
+    }
+
+    fn delete(&mut self, path: &Path, state: &mut AppState) {
+        match delete_path(&self.root, path) {
+            Ok(path) => {
+                state.note_tree_change(&path);
+                state.info(format!("Deleted: {}", self.display_path(&path)));
+                if self
+                    .cut
+                    .as_deref()
+                    .is_some_and(|cut| cut.starts_with(&path))
+                {
+                    self.cut = None;
+                }
+                self.reload(None);
+                self.emit(Event::PathDeleted(path));
+            }
+            Err(e) => state.error(format!("Delete failed: {}", e)),
+        }
+    }
+
+    /// Move the cut entry into the target directory
+    fn paste(&mut self, state: &mut AppState) {
+        let Some(from) = self.cut.take() else {
+            state.info("Nothing to paste (x cuts the selection)");
+            return;
+        };
+        let Some(name) = from.file_name() else {
+            return;
+        };
+        let to = self.target_dir().join(name);
+        if to != from {
+            self.move_entry(&from, &to, state);
+        }
+    }
+
+    /// Text of the prompt line
+    fn prompt_line(&self) -> Option<String> {
+        Some(match self.prompt.as_ref()? {
+            Prompt::Create { is_dir, name, .. } => {
+                let kind = if *is_dir { "New folder" } else { "New file" };
+                format!("{}: {}█", kind, name)
+            }
+            Prompt::Rename { name, .. } => format!("Rename: {}█", name),
+            Prompt::Delete { path } => {
+                format!("Delete {}? (y/n)", self.display_path(path))
+            }
+        })
+    }
+
     /// Load directory entries
     fn load_dir(&mut self, path: &Path, depth: usize, expanded: bool) {
         if !expanded {
@@ -255,6 +493,10 @@ impl super::Panel for FileTreePanel {
 
     fn handle_input(&mut self, event: &Event, state: &mut AppState) -> Result<bool> {
         if let Event::Key(key) = event {
+            if self.prompt.is_some() {
+                self.prompt_key(key, state);
+                return Ok(true);
+            }
             match key.code {
                 KeyCode::Up | KeyCode::Char('k') => {
                     self.move_up();
@@ -298,6 +540,48 @@ impl super::Panel for FileTreePanel {
                     self.refresh();
                     Ok(true)
                 }
+                KeyCode::Char(c @ ('n' | 'N')) => {
+                    let prompt = Prompt::Create {
+                        dir: self.target_dir(),
+                        is_dir: c == 'N',
+                        name: String::new(),
+                    };
+                    self.open_prompt(prompt, state);
+                    Ok(true)
+                }
+                KeyCode::Char('r') => {
+                    if let Some(entry) = self.entries.get(self.selected) {
+                        let prompt = Prompt::Rename {
+                            path: entry.path.clone(),
+                            name: entry.name.clone(),
+                        };
+                        self.open_prompt(prompt, state);
+                    }
+                    Ok(true)
+                }
+                KeyCode::Char('d') | KeyCode::Delete => {
+                    if let Some(entry) = self.entries.get(self.selected) {
+                        let prompt = Prompt::Delete {
+                            path: entry.path.clone(),
+                        };
+                        self.open_prompt(prompt, state);
+                    }
+                    Ok(true)
+                }
+                KeyCode::Char('x') => {
+                    if let Some(entry) = self.entries.get(self.selected) {
+                        state.info(format!(
+                            "Cut {}; select a folder and press p to move it there",
+                            entry.name
+                        ));
+                        self.cut = Some(entry.path.clone());
+                    }
+                    Ok(true)
+                }
+                KeyCode::Char('p') => {
+                    self.paste(state);
+                    Ok(true)
+                }
                 // Ctrl+C: Copy selected file/directory path to clipboard
                 KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                     if let Some(entry) = self.entries.get(self.selected) {
@@ -395,7 +679,12 @@ impl super::Panel for FileTreePanel {
 
         // Calculate inner area first to get visible height
         let temp_block = Block::default().borders(Borders::ALL);
-        let inner = temp_block.inner(area);
+        let mut inner = temp_block.inner(area);
+        // The bottom row holds the prompt while one is open
+        let prompt = self.prompt_line().filter(|_| inner.height > 0);
+        if prompt.is_some() {
+            inner.height -= 1;
+        }
         let visible_height = inner.height as usize;
 
         // Adjust scroll to keep selected visible
@@ -454,6 +743,10 @@ impl super::Panel for FileTreePanel {
                         .fg(t.text_primary)
                         .bg(t.bg_selection)
                         .add_modifier(Modifier::BOLD)
+                } else if self.cut.as_ref() == Some(&entry.path) {
+                    Style::default()
+                        .fg(t.text_muted)
+                        .add_modifier(Modifier::ITALIC)
                 } else {
                     Style::default().fg(base_color)
                 };
@@ -478,6 +771,16 @@ impl super::Panel for FileTreePanel {
         // Render scroll bar
         let scrollbar = ScrollBar::new(scroll, visible_height, self.entries.len());
         scrollbar.render(frame, inner, focused);
+
+        if let Some(prompt) = prompt {
+            let row = Rect {
+                y: inner.y + inner.height,
+                height: 1,
+                ..inner
+            };
+            let style = Style::default().fg(t.accent_primary);
+            frame.render_widget(Paragraph::new(prompt).style(style), row);
+        }
     }
 }
 
@@ -742,6 +1045,80 @@ mod tests {
         assert_eq!(panel.selected, panel.entries.len().saturating_sub(1));
     }
 
+    fn press(panel: &mut FileTreePanel, state: &mut AppState, keys: &str) {
+        use crate::panels::Panel;
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\x08' => KeyCode::Backspace,
+                c => KeyCode::Char(c),
+            };
+            let key = crossterm::event::KeyEvent::new(code, KeyModifiers::NONE);
+            panel.handle_input(&Event::Key(key), state).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_create_rename_and_delete() {
+        let (path, _guard) = create_temp_dir();
+        fs::create_dir(path.join("src")).unwrap();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut panel = FileTreePanel::new(&path);
+        panel.set_event_tx(tx);
+        let mut state = AppState::new();
+
+        // With the folder selected, the new file goes inside it
+        press(&mut panel, &mut state, "nlib.rs");
+        assert!(state.input_mode.is_modal_open("file_tree_prompt"));
+        press(&mut panel, &mut state, "\n");
+        let created = path.join("src/lib.rs");
+        assert!(created.is_file());
+        assert!(!state.input_mode.is_modal());
+        assert!(matches!(rx.try_recv(), Ok(Event::PathCreated(p)) if p == created));
+        assert_eq!(panel.selected_file(), Some(created.as_path()));
+        assert!(state.is_tree_change(&created));
+
+        // Rename starts from the current name
+        press(&mut panel, &mut state, "r\x08\x08\x08\x08\x08\x08main.rs\n");
+        let renamed = path.join("src/main.rs");
+        assert!(renamed.is_file() && !created.exists());
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(Event::PathMoved { from, to }) if from == created && to == renamed
+        ));
+
+        // Anything but y keeps the file
+        press(&mut panel, &mut state, "dn");
+        assert!(renamed.exists());
+        press(&mut panel, &mut state, "dy");
+        assert!(!renamed.exists());
+        assert!(matches!(rx.try_recv(), Ok(Event::PathDeleted(p)) if p == renamed));
+    }
+
+    #[test]
+    fn test_cut_and_paste_moves_into_folder() {
+        let (path, _guard) = create_temp_dir();
+        fs::create_dir(path.join("docs")).unwrap();
+        fs::write(path.join("notes.md"), "hi").unwrap();
+        let mut panel = FileTreePanel::new(&path);
+        let mut state = AppState::new();
+
+        panel.selected = panel.entries.iter().position(|e| !e.is_dir).unwrap();
+        press(&mut panel, &mut state, "x");
+        panel.selected = panel.entries.iter().position(|e| e.is_dir).unwrap();
+        press(&mut panel, &mut state, "p");
+        assert_eq!(
+            fs::read_to_string(path.join("docs/notes.md")).unwrap(),
+            "hi"
+        );
+        assert!(panel.cut.is_none());
+        // The folder is opened to show the moved file
+        assert!(panel
+            .entries
+            .iter()
+            .any(|e| e.name == "notes.md" && e.depth == 1));
+    }
+
     #[test]
     fn test_get_file_icon_and_color_rust() {
         let t = crate::ui::theme::Theme::dark();
@@ -5,14 +5,20 @@
 //! - Plain text → LLM conductor
 //! - `!command` or `:command` → Shell execution
 //! - `#agent prompt` → CLI agent invocation (e.g., #claude, #gemini)
+//!
+//! Follow-up suggestions from the last run sit on the top border as chips;
+//! Alt+1 to Alt+3 or a click puts one in the input.
 
-use axiom_core::{SlashCommand, SlashCommandParser};
+use axiom_core::orchestration::FollowUp;
+use axiom_core::{SlashCommand, SlashCommandParser, SlashError, SlashParseError};
 use crate::clipboard;
 use crate::config::CliAgentsConfig;
 use crate::core::Result;
 use crate::events::Event;
+use crate::panels::words;
 use crate::panels::Panel;
 use crate::state::{AppState, PanelId};
+use crate::ui::controls::ClickTracker;
 use crate::ui::theme::theme;
 use crossbeam_channel::Sender;
 use crossterm::event::{KeyCode, KeyModifiers, MouseEventKind};
@@ -25,6 +31,7 @@ use ratatui::{
 };
 use std::cell::RefCell;
 use std::sync::Arc;
+use std::time::Instant;
 
 /// Parsed input command
 #[derive(Debug, Clone)]
@@ -32,6 +39,10 @@ pub enum InputCommand {
     /// Slash command (prefixed with /)
     Slash(SlashCommand),
 
+    /// Slash command that doesn't parse, e.g. a mistyped name or a missing
+    /// argument
+    Invalid(SlashError),
+
     /// Chat message to conductor
     Chat(String),
 
@@ -63,12 +74,13 @@ impl InputCommand {
         }
 
         // Check for slash command FIRST (highest priority)
-        if let Some(result) = SlashCommandParser::parse(trimmed) {
+        // (spans of errors point into `input`)
+        if let Some(result) = SlashCommandParser::parse_spanned(input) {
             match result {
                 Ok(cmd) => return InputCommand::Slash(cmd),
+                Err(e) if e.error != SlashParseError::Empty => return InputCommand::Invalid(e),
                 Err(_) => {
-                    // Parse error - fall through to treat as chat
-                    // This allows users to type things like "/ is a path separator"
+                    // A lone "/" falls through to chat
                 }
             }
         }
@@ -82,10 +94,14 @@ impl InputCommand {
                 None => (rest.to_string(), String::new()),
             };
 
-            // Validate agent ID if config is provided
+            // Validate agent ID if config is provided; `# prompt` goes to
+            // the default agent
             if let Some(config) = cli_agents {
-                if config.is_available(&agent_id) {
-                    return InputCommand::CliAgent { agent_id, prompt };
+                if let Some(agent_id) = config.agent_or_default(&agent_id) {
+                    if config.is_available(agent_id) {
+                        let agent_id = agent_id.to_string();
+                        return InputCommand::CliAgent { agent_id, prompt };
+                    }
                 }
             } else {
                 // No config validation, allow any agent ID
@@ -140,6 +156,18 @@ pub struct InputPanel {
 
     /// CLI agents configuration
     cli_agents: Arc<CliAgentsConfig>,
+
+    /// Double-click detection on text positions
+    clicks: ClickTracker<usize>,
+
+    /// Follow-up prompts offered after the last run
+    suggestions: Vec<FollowUp>,
+
+    /// Columns each rendered suggestion chip covers on the top border
+    chip_areas: RefCell<Vec<(u16, u16)>>,
+
+    /// Changed by the app (not by input) since the last render
+    changed: bool,
 }
 
 impl InputPanel {
@@ -156,6 +184,10 @@ impl InputPanel {
             input_area: RefCell::new(Rect::default()),
             is_processing: false,
             cli_agents,
+            clicks: ClickTracker::new(),
+            suggestions: Vec::new(),
+            chip_areas: RefCell::new(Vec::new()),
+            changed: false,
         }
     }
 
@@ -166,6 +198,7 @@ impl InputPanel {
 
     /// Set processing state
     pub fn set_processing(&mut self, processing: bool) {
+        self.changed |= self.is_processing != processing;
         self.is_processing = processing;
     }
 
@@ -174,6 +207,67 @@ impl InputPanel {
         self.is_processing
     }
 
+    // ==================== Suggestions ====================
+
+    /// Offer follow-up prompts; an empty list removes them
+    pub fn set_suggestions(&mut self, suggestions: Vec<FollowUp>) {
+        self.changed |= self.suggestions != suggestions;
+        self.suggestions = suggestions;
+    }
+
+    /// Follow-up prompts on offer
+    pub fn suggestions(&self) -> &[FollowUp] {
+        &self.suggestions
+    }
+
+    /// Replace the input with suggestion `index`, ready to edit or submit
+    fn accept_suggestion(&mut self, index: usize) -> bool {
+        let Some(suggestion) = self.suggestions.get(index) else {
+            return false;
+        };
+        self.input = suggestion.input.clone();
+        self.cursor = self.input.chars().count();
+        self.history_index = None;
+        self.clear_selection();
+        self.suggestions.clear();
+        true
+    }
+
+    /// Chips for the suggestions that fit in `width` columns, right-aligned
+    /// on the top border, recording the columns each covers
+    fn suggestion_chips(&self, area: Rect, width: usize) -> Line<'static> {
+        let t = theme();
+        let mut chips: Vec<String> = self
+            .suggestions
+            .iter()
+            .enumerate()
+            .map(|(i, s)| format!(" {} {} ", i + 1, s.label))
+            .collect();
+        let line_width = |chips: &[String]| {
+            chips.iter().map(|c| c.chars().count() + 1).sum::<usize>()
+        };
+        while !chips.is_empty() && line_width(&chips) > width {
+            chips.pop();
+        }
+
+        // Right-aligned within the borders
+        let mut x = (area.x + area.width).saturating_sub(1 + line_width(&chips) as u16);
+        let mut areas = self.chip_areas.borrow_mut();
+        areas.clear();
+        let mut spans = Vec::new();
+        for chip in chips {
+            let len = chip.chars().count() as u16;
+            areas.push((x, x + len));
+            x += len + 1;
+            spans.push(Span::styled(
+                chip,
+                Style::default().fg(t.text_primary).bg(t.bg_selection),
+            ));
+            spans.push(Span::raw(" "));
+        }
+        Line::from(spans).right_aligned()
+    }
+
     // ==================== Selection ====================
 
     /// Get selected text range (start, end)
@@ -237,6 +331,26 @@ impl InputPanel {
         }
     }
 
+    /// Move cursor to the start of the previous word
+    fn move_word_left(&mut self, extend_selection: bool) {
+        if extend_selection && self.selection_anchor.is_none() {
+            self.selection_anchor = Some(self.cursor);
+        } else if !extend_selection {
+            self.clear_selection();
+        }
+        self.cursor = words::prev_word_start(&self.input, self.cursor);
+    }
+
+    /// Move cursor to the start of the next word
+    fn move_word_right(&mut self, extend_selection: bool) {
+        if extend_selection && self.selection_anchor.is_none() {
+            self.selection_anchor = Some(self.cursor);
+        } else if !extend_selection {
+            self.clear_selection();
+        }
+        self.cursor = words::next_word_start(&self.input, self.cursor);
+    }
+
     /// Move to start of line
     fn move_home(&mut self, extend_selection: bool) {
         if extend_selection && self.selection_anchor.is_none() {
@@ -315,6 +429,22 @@ impl InputPanel {
         }
     }
 
+    /// Delete the word before the cursor
+    fn delete_word_back(&mut self) {
+        if self.selection_anchor.is_none() {
+            self.selection_anchor = Some(words::prev_word_start(&self.input, self.cursor));
+        }
+        self.delete_selection();
+    }
+
+    /// Delete up to the start of the next word
+    fn delete_word_forward(&mut self) {
+        if self.selection_anchor.is_none() {
+            self.selection_anchor = Some(words::next_word_start(&self.input, self.cursor));
+        }
+        self.delete_selection();
+    }
+
     // ==================== History Navigation ====================
 
     /// Navigate to previous history entry
@@ -364,13 +494,24 @@ impl InputPanel {
     // ==================== Submit ====================
 
     /// Submit current input
-    fn submit(&mut self) {
+    fn submit(&mut self, state: &mut AppState) {
         if self.input.trim().is_empty() || self.is_processing {
             return;
         }
 
         let command = InputCommand::parse(&self.input, Some(&self.cli_agents));
 
+        // A slash command that doesn't parse stays, with the part at fault
+        // selected
+        if let InputCommand::Invalid(e) = command {
+            state.error(e.to_string());
+            let column = |byte: usize| self.input[..byte].chars().count();
+            let (start, end) = (column(e.span.start), column(e.span.end));
+            self.selection_anchor = (start < end).then_some(start);
+            self.cursor = end;
+            return;
+        }
+
         // Add to history
         self.history.push(self.input.clone());
         if self.history.len() > 100 {
@@ -391,7 +532,7 @@ impl InputPanel {
             InputCommand::CliAgent { agent_id, prompt } => {
                 let _ = self.event_tx.send(Event::CliAgentInvoke { agent_id, prompt });
             }
-            InputCommand::Empty => {}
+            InputCommand::Invalid(_) | InputCommand::Empty => {}
         }
 
         // Clear input
@@ -399,6 +540,7 @@ impl InputPanel {
         self.cursor = 0;
         self.history_index = None;
         self.clear_selection();
+        self.suggestions.clear();
     }
 
     // ==================== Clipboard ====================
@@ -421,12 +563,16 @@ impl InputPanel {
     /// Paste from clipboard
     fn paste_from_clipboard(&mut self) {
         if let Ok(text) = clipboard::paste() {
-            // Remove newlines for single-line input
-            let clean_text: String = text.replace('\n', " ").replace('\r', "");
-            self.insert_str(&clean_text);
+            self.insert_paste(&text);
         }
     }
 
+    /// Insert pasted text, flattened onto one line
+    fn insert_paste(&mut self, text: &str) {
+        let clean_text: String = text.replace('\n', " ").replace('\r', "");
+        self.insert_str(&clean_text);
+    }
+
     /// Select all text
     fn select_all(&mut self) {
         self.selection_anchor = Some(0);
@@ -460,16 +606,22 @@ impl Panel for InputPanel {
         "Input"
     }
 
+    /// Suggestions offered when a run settles arrive without an event
+    fn needs_redraw(&self) -> bool {
+        self.changed
+    }
+
     fn handle_input(&mut self, event: &Event, state: &mut AppState) -> Result<bool> {
         match event {
             Event::Key(key) => {
                 let shift = key.modifiers.contains(KeyModifiers::SHIFT);
                 let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                let alt = key.modifiers.contains(KeyModifiers::ALT);
 
                 match key.code {
                     // Submit
                     KeyCode::Enter if !shift => {
-                        self.submit();
+                        self.submit(state);
                         Ok(true)
                     }
 
@@ -479,7 +631,15 @@ impl Panel for InputPanel {
                         Ok(true)
                     }
 
-                    // Navigation
+                    // Navigation; Ctrl, or Alt as on macOS, moves by word
+                    KeyCode::Left if ctrl || alt => {
+                        self.move_word_left(shift);
+                        Ok(true)
+                    }
+                    KeyCode::Right if ctrl || alt => {
+                        self.move_word_right(shift);
+                        Ok(true)
+                    }
                     KeyCode::Left => {
                         self.move_left(shift);
                         Ok(true)
@@ -508,6 +668,14 @@ impl Panel for InputPanel {
                     }
 
                     // Editing
+                    KeyCode::Backspace if ctrl || alt => {
+                        self.delete_word_back();
+                        Ok(true)
+                    }
+                    KeyCode::Delete if ctrl || alt => {
+                        self.delete_word_forward();
+                        Ok(true)
+                    }
                     KeyCode::Backspace => {
                         self.delete_back();
                         Ok(true)
@@ -535,6 +703,12 @@ impl Panel for InputPanel {
                         Ok(true)
                     }
 
+                    // Alt+1..3 takes a follow-up suggestion
+                    KeyCode::Char(c @ '1'..='9') if alt && !self.suggestions.is_empty() => {
+                        let index = c as usize - '1' as usize;
+                        Ok(self.accept_suggestion(index))
+                    }
+
                     // Clear input
                     KeyCode::Char('u') if ctrl => {
                         self.input.clear();
@@ -551,11 +725,14 @@ impl Panel for InputPanel {
                         Ok(true)
                     }
 
-                    // Escape clears selection
+                    // Escape clears selection, then dismisses suggestions
                     KeyCode::Esc => {
                         if self.selection_anchor.is_some() {
                             self.clear_selection();
                             Ok(true)
+                        } else if !self.suggestions.is_empty() {
+                            self.suggestions.clear();
+                            Ok(true)
                         } else {
                             Ok(false)
                         }
@@ -568,6 +745,17 @@ impl Panel for InputPanel {
                 // Handle click to position cursor
                 if let MouseEventKind::Down(_) = mouse.kind {
                     let area = *self.input_area.borrow();
+                    // A suggestion chip on the top border
+                    if mouse.row == area.y {
+                        let chip = self
+                            .chip_areas
+                            .borrow()
+                            .iter()
+                            .position(|&(start, end)| (start..end).contains(&mouse.column));
+                        if let Some(index) = chip {
+                            return Ok(self.accept_suggestion(index));
+                        }
+                    }
                     if mouse.row >= area.y
                         && mouse.row < area.y + area.height
                         && mouse.column >= area.x
@@ -582,18 +770,30 @@ impl Panel for InputPanel {
                             let text_pos = click_col - prompt_len;
                             self.cursor = text_pos.min(self.input.chars().count());
                             self.clear_selection();
+
+                            // Double-click selects the word under the pointer
+                            if self.clicks.click(self.cursor, Instant::now()) {
+                                let (start, end) = words::word_at(&self.input, self.cursor);
+                                self.selection_anchor = Some(start);
+                                self.cursor = end;
+                            }
                         }
                         return Ok(true);
                     }
                 }
                 Ok(false)
             }
+            Event::Paste(text) => {
+                self.insert_paste(text);
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
 
     fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool) {
         *self.input_area.borrow_mut() = area;
+        self.changed = false;
 
         let t = theme();
         let border_style = if focused {
@@ -611,10 +811,15 @@ impl Panel for InputPanel {
             " > "
         };
 
-        let block = Block::default()
+        let mut block = Block::default()
             .title(title)
             .borders(Borders::ALL)
             .border_style(border_style);
+        self.chip_areas.borrow_mut().clear();
+        if !self.suggestions.is_empty() {
+            let width = (area.width as usize).saturating_sub(title.len() + 3);
+            block = block.title_top(self.suggestion_chips(area, width));
+        }
 
         let inner = block.inner(area);
         frame.render_widget(block, area);
@@ -766,6 +971,25 @@ mod tests {
         assert!(matches!(cmd, InputCommand::Chat(_)));
     }
 
+    #[test]
+    fn test_parse_cli_agent_default() {
+        let mut config = CliAgentsConfig::default();
+        // Without a default, `#` alone is chat
+        assert!(matches!(
+            InputCommand::parse("# fix the build", Some(&config)),
+            InputCommand::Chat(_)
+        ));
+
+        config.default = Some("gemini".to_string());
+        match InputCommand::parse("# fix the build", Some(&config)) {
+            InputCommand::CliAgent { agent_id, prompt } => {
+                assert_eq!(agent_id, "gemini");
+                assert_eq!(prompt, "fix the build");
+            }
+            _ => panic!("Expected CliAgent command"),
+        }
+    }
+
     #[test]
     fn test_parse_cli_agent_no_config() {
         // Without config validation, any agent ID is accepted
@@ -917,6 +1141,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_invalid_slash_command_keeps_the_input() {
+        let cmd = InputCommand::parse_simple("/wrkspace list");
+        assert!(matches!(cmd, InputCommand::Invalid(e) if e.span == (1..9)));
+
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut panel = InputPanel::new(tx, Arc::new(CliAgentsConfig::default()));
+        let mut state = AppState::new();
+        panel.input = "/trust onn".to_string();
+        panel.submit(&mut state);
+        // The mistake is selected to fix, and nothing is sent
+        assert_eq!(panel.input, "/trust onn");
+        assert_eq!(panel.selected_text().as_deref(), Some("onn"));
+        let message = state.status_message.unwrap().text;
+        assert!(message.ends_with("(did you mean on?)"));
+        assert!(rx.try_recv().is_err());
+    }
+
+    // ==================== Suggestions ====================
+
+    #[test]
+    fn test_accept_suggestion() {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut panel = InputPanel::new(tx, Arc::new(CliAgentsConfig::default()));
+        panel.insert_str("draft");
+        panel.set_suggestions(vec![FollowUp {
+            label: "run the tests".to_string(),
+            input: "!cargo test".to_string(),
+        }]);
+
+        assert!(!panel.accept_suggestion(1));
+        assert!(panel.accept_suggestion(0));
+        assert_eq!(panel.input, "!cargo test");
+        assert_eq!(panel.cursor, 11);
+        assert!(panel.suggestions().is_empty());
+    }
+
+    #[test]
+    fn test_new_suggestions_need_redraw() {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut panel = InputPanel::new(tx, Arc::new(CliAgentsConfig::default()));
+        assert!(!panel.needs_redraw());
+
+        let suggestions = vec![FollowUp {
+            label: "run the tests".to_string(),
+            input: "!cargo test".to_string(),
+        }];
+        panel.set_suggestions(suggestions.clone());
+        assert!(panel.needs_redraw());
+
+        let backend = ratatui::backend::TestBackend::new(60, 3);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|f| panel.render(f, f.area(), true)).unwrap();
+        assert!(!panel.needs_redraw());
+        panel.set_suggestions(suggestions);
+        assert!(!panel.needs_redraw());
+    }
+
     #[test]
     fn test_slash_takes_priority_over_chat() {
         // Slash commands should be parsed before chat
@@ -13,6 +13,7 @@ mod editor;
 mod file_tree;
 mod input;
 mod output;
+mod words;
 
 // Legacy panels (kept for potential reuse of components)
 mod chat;
@@ -27,13 +28,19 @@ pub use output::OutputPanel;
 // Re-export editor components for file viewer
 pub use editor::{DiffTracker, Highlighter, Position, Selection};
 
-use crate::agents::AgentRegistry;
-use crate::config::{AxiomConfig, CliAgentsConfig};
+use crate::agents::{AgentRegistry, Conductor};
+use crate::config::{AxiomConfig, CliAgentsConfig, DirectoryPickerConfig, DiscoveryConfig};
 use crate::core::Result;
 use crate::events::Event;
 use crate::llm::ProviderRegistry;
-use crate::state::{AppState, OutputContext, PanelId, WorkspaceId, WorkspaceView};
-use crate::ui::{ModelSelector, SettingsModal, WorkspaceSelectorModal};
+use crate::state::{AppState, OutputContext, PanelId, Workspace, WorkspaceId, WorkspaceManager};
+use crate::ui::activity_feed::ACTIVITY_PAGE_SIZE;
+use crate::ui::{
+    ActivityFeed, DirectoryPicker, DuplicatePrompt, ModelSelector, PlanView, ReplacePanel,
+    ReviewQueue, SearchPanel, SettingsModal, TrustPrompt, WorkspaceSelectorModal,
+};
+use axiom_core::workspace::{discover_projects, sort_queue, DiscoveredProject, STALL_AFTER};
+use axiom_core::{ReviewItem, WorkspaceDefaults};
 use parking_lot::RwLock;
 use ratatui::layout::Rect;
 use ratatui::Frame;
@@ -67,6 +74,14 @@ pub trait Panel: Send {
     /// Called when the panel area is resized
     fn on_resize(&mut self, _cols: u16, _rows: u16) {}
 
+    /// Whether the panel changed since its last render without an event
+    ///
+    /// Panels with animations (spinners, progress bars) return true when their
+    /// next frame is due, so the main loop can otherwise skip redrawing.
+    fn needs_redraw(&self) -> bool {
+        false
+    }
+
     /// Check if the panel can be closed
     fn can_close(&self) -> bool {
         true
@@ -102,6 +117,36 @@ pub struct PanelRegistry {
     /// Workspace selector modal
     pub workspace_selector: WorkspaceSelectorModal,
 
+    /// Directory picker modal (open for `/init`)
+    pub directory_picker: Option<DirectoryPicker>,
+
+    /// Activity feed modal (open for `/activity`)
+    pub activity_feed: Option<ActivityFeed>,
+
+    /// Review queue modal (open for `/review`)
+    pub review_queue: Option<ReviewQueue>,
+
+    /// Plan modal (open for `/plan`)
+    pub plan_view: Option<PlanView>,
+
+    /// Search panel modal (open for `/find` or Ctrl+F)
+    pub search: Option<SearchPanel>,
+
+    /// Replace preview modal (open for `/replace`)
+    pub replace: Option<ReplacePanel>,
+
+    /// Trust prompt for a workspace opened for the first time
+    pub trust_prompt: Option<TrustPrompt>,
+
+    /// Offer to open the workspace already registered for a directory
+    pub duplicate_prompt: Option<DuplicatePrompt>,
+
+    /// Roots scanned for unregistered projects when the selector opens
+    discovery: DiscoveryConfig,
+
+    /// Discovery scan still running for the workspace selector
+    discovery_scan: Option<crossbeam_channel::Receiver<Vec<DiscoveredProject>>>,
+
     /// Cached model badge area for click detection
     pub model_badge_area: Option<Rect>,
 
@@ -120,24 +165,113 @@ impl PanelRegistry {
         let llm_registry = Arc::new(RwLock::new(llm_registry));
         let agent_registry = Arc::new(RwLock::new(AgentRegistry::new()));
         let cli_agents = Arc::new(config.cli_agents.clone());
+        let mut workspace_selector = WorkspaceSelectorModal::new();
+        workspace_selector.set_picker_prefs(config.directory_picker.clone());
+        let mut file_tree = FileTreePanel::new(cwd);
+        file_tree.set_event_tx(event_tx.clone());
 
         Ok(Self {
-            file_tree: FileTreePanel::new(cwd),
+            file_tree,
             output: OutputPanel::new(agent_registry.clone()),
             input: InputPanel::new(event_tx.clone(), cli_agents),
             agents: AgentsPanel::new(agent_registry.clone(), event_tx),
             agent_registry,
             model_selector: ModelSelector::new(),
             settings: SettingsModal::new(config),
-            workspace_selector: WorkspaceSelectorModal::new(),
+            workspace_selector,
+            directory_picker: None,
+            activity_feed: None,
+            review_queue: None,
+            plan_view: None,
+            search: None,
+            replace: None,
+            trust_prompt: None,
+            duplicate_prompt: None,
+            discovery: config.discovery.clone(),
+            discovery_scan: None,
             model_badge_area: None,
             llm_registry,
         })
     }
 
-    /// Open the workspace selector modal with workspace list
-    pub fn open_workspace_selector(&mut self, workspaces: Vec<WorkspaceView>, active_id: Option<WorkspaceId>) {
-        self.workspace_selector.set_workspaces(workspaces, active_id);
+    /// Whether any panel has pending changes that no event reported
+    pub fn needs_redraw(&self) -> bool {
+        self.output.needs_redraw()
+            || self.input.needs_redraw()
+            || self.agents.needs_redraw()
+            || self.search.as_ref().is_some_and(SearchPanel::is_running)
+            || self.discovery_scan.is_some()
+            || self.llm_registry.read().is_loading()
+    }
+
+    /// Take in new hits of a running search; returns whether any arrived
+    pub fn poll_search(&mut self) -> bool {
+        self.search.as_mut().is_some_and(SearchPanel::poll)
+    }
+
+    /// Diff-track changes written to `path` wherever the file is on show
+    pub fn show_file_changes(&mut self, path: &std::path::Path, before: &str) {
+        self.output.show_file_changes(path, before);
+    }
+
+    /// Show `path` in the output panel scrolled to `line` (from 1)
+    pub fn open_file_at(&mut self, path: std::path::PathBuf, line: usize) {
+        self.set_output_context(OutputContext::File { path });
+        self.output.reveal_line(line);
+    }
+
+    /// Open the workspace selector modal with the registered workspaces and
+    /// any unregistered projects found under the discovery roots
+    pub fn open_workspace_selector(&mut self, manager: &WorkspaceManager, active_id: Option<WorkspaceId>) {
+        self.workspace_selector.set_workspaces(manager.list_workspaces(), active_id);
+
+        // Scanning the discovery roots can take a while; the selector shows
+        // registered workspaces straight away and gains the rest when done
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let roots = self.discovery.roots.clone();
+        let max_depth = self.discovery.max_depth;
+        std::thread::spawn(move || {
+            let _ = tx.send(discover_projects(&roots, max_depth));
+        });
+        self.discovery_scan = Some(rx);
+    }
+
+    /// Take in the result of the selector's discovery scan once it's done;
+    /// returns whether it arrived
+    pub fn poll_discovery(&mut self, manager: Option<&WorkspaceManager>) -> bool {
+        let Some(scan) = &self.discovery_scan else {
+            return false;
+        };
+        let found = match scan.try_recv() {
+            Ok(found) => found,
+            Err(crossbeam_channel::TryRecvError::Empty) => return false,
+            Err(crossbeam_channel::TryRecvError::Disconnected) => Vec::new(),
+        };
+        self.discovery_scan = None;
+        // Skip projects registered since the scan started
+        let found = found
+            .into_iter()
+            .filter(|project| manager.is_none_or(|m| m.find_by_path(&project.path).is_none()))
+            .collect();
+        self.workspace_selector.set_discovered(found);
+        true
+    }
+
+    /// Open the directory picker modal
+    pub fn open_directory_picker(&mut self, start: std::path::PathBuf, prefs: &DirectoryPickerConfig) {
+        self.directory_picker = Some(DirectoryPicker::new(start, prefs));
+    }
+
+    /// Directory picker preferences changed by an open picker, for persisting
+    pub fn take_picker_prefs_change(&mut self) -> Option<DirectoryPickerConfig> {
+        let from_modal = self
+            .directory_picker
+            .as_mut()
+            .and_then(|p| p.take_prefs_change());
+        if let Some(prefs) = &from_modal {
+            self.workspace_selector.set_picker_prefs(prefs.clone());
+        }
+        self.workspace_selector.take_picker_prefs_change().or(from_modal)
     }
 
     /// Get the agent registry
@@ -145,6 +279,23 @@ impl PanelRegistry {
         self.agent_registry.clone()
     }
 
+    /// Show the agents of `registry`, e.g. another workspace's
+    pub fn set_agent_registry(&mut self, registry: Arc<RwLock<AgentRegistry>>) {
+        self.output.set_agent_registry(registry.clone());
+        self.agents.set_registry(registry.clone());
+        self.agent_registry = registry;
+    }
+
+    /// Follow a moved file or folder in the editor's tabs
+    pub fn path_moved(&mut self, from: &std::path::Path, to: &std::path::Path) {
+        self.output.path_moved(from, to);
+    }
+
+    /// Close the editor's tabs for a deleted file or folder
+    pub fn path_deleted(&mut self, path: &std::path::Path) {
+        self.output.path_deleted(path);
+    }
+
     /// Set the output context (what's displayed in output panel)
     pub fn set_output_context(&mut self, context: OutputContext) {
         self.output.set_context(context);
@@ -160,14 +311,52 @@ impl PanelRegistry {
         self.output.clear();
     }
 
-    /// Open the settings modal with current configuration
-    pub fn open_settings(&mut self, config: &AxiomConfig) {
+    /// Open the settings modal with current configuration and the active
+    /// workspace's own choices
+    pub fn open_settings(&mut self, config: &AxiomConfig, workspace: Option<WorkspaceDefaults>) {
         self.settings = SettingsModal::new(config);
+        self.settings.set_workspace(workspace);
+    }
+
+    /// Open settings on `provider`'s API key after the provider rejected it
+    ///
+    /// Once a new key is saved, [`Self::retry_rejected_prompt`] sends the
+    /// prompt again. Returns false, opening nothing, while another modal is
+    /// open or if the provider has no key in settings.
+    pub fn ask_for_key(
+        &mut self,
+        state: &mut AppState,
+        config: &AxiomConfig,
+        provider: &str,
+    ) -> bool {
+        if state.input_mode.is_modal() {
+            return false;
+        }
+        self.open_settings(config, state.workspace_defaults());
+        if !self.settings.edit_api_key(provider) {
+            return false;
+        }
+        state.auth_retry.rejected(provider);
+        state.input_mode.open_modal("settings");
+        true
+    }
+
+    /// Send the Conductor's rejected prompt again if its provider got a new
+    /// key; returns whether it was sent
+    ///
+    /// Only the first save after the rejection counts.
+    pub fn retry_rejected_prompt(&self, state: &mut AppState, conductor: &mut Conductor) -> bool {
+        let changed = state
+            .auth_retry
+            .pending()
+            .is_some_and(|provider| self.settings.key_changed(provider));
+        state.auth_retry.cancel();
+        changed && conductor.retry()
     }
 
     /// Apply settings and return updated config if there are changes
     pub fn apply_settings(&self) -> Option<AxiomConfig> {
-        if self.settings.has_changes() {
+        if self.settings.config_changed() {
             Some(self.settings.to_config())
         } else {
             None
@@ -208,11 +397,74 @@ impl PanelRegistry {
         self.model_selector.set_models(all_models, &current);
     }
 
-    /// Apply the selected model
+    /// Open the activity feed of `workspace` at its newest entries
+    pub fn open_activity_feed(
+        &mut self,
+        manager: &WorkspaceManager,
+        workspace: &Workspace,
+    ) -> axiom_core::Result<()> {
+        let page = manager.activity(workspace.id, 0, ACTIVITY_PAGE_SIZE)?;
+        self.activity_feed = Some(ActivityFeed::new(workspace.id, workspace.name.clone(), page));
+        Ok(())
+    }
+
+    /// Fetch the next activity page once the selection nears the end
+    pub fn load_more_activity(&mut self, manager: &WorkspaceManager) {
+        let Some(feed) = self.activity_feed.as_mut() else {
+            return;
+        };
+        if let Some(offset) = feed.wants_more() {
+            if let Ok(page) = manager.activity(feed.workspace_id, offset, ACTIVITY_PAGE_SIZE) {
+                feed.extend(page);
+            }
+        }
+    }
+
+    /// Review items of `workspace_id`, including this TUI's stalled agents
+    pub fn review_items(&self, manager: &WorkspaceManager, workspace_id: WorkspaceId) -> Vec<ReviewItem> {
+        let mut items = manager.review_queue(workspace_id);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        let registry = self.agent_registry.read();
+        items.extend(registry.stalled(STALL_AFTER).into_iter().map(|agent| {
+            ReviewItem::stalled_agent(agent.id, &agent.name, agent.last_activity.elapsed(), now)
+        }));
+        sort_queue(&mut items);
+        items
+    }
+
+    /// Open the review queue of `workspace`
+    pub fn open_review_queue(&mut self, manager: &WorkspaceManager, workspace: &Workspace) {
+        let items = self.review_items(manager, workspace.id);
+        self.review_queue = Some(ReviewQueue::new(workspace.id, workspace.name.clone(), items));
+    }
+
+    /// Reload the open review queue after an item was handled
+    pub fn refresh_review_queue(&mut self, manager: &WorkspaceManager) {
+        let Some(workspace_id) = self.review_queue.as_ref().map(|queue| queue.workspace_id) else {
+            return;
+        };
+        let items = self.review_items(manager, workspace_id);
+        if let Some(queue) = self.review_queue.as_mut() {
+            queue.set_items(items);
+        }
+    }
+
+    /// Apply the selected model, making its provider the active one
+    ///
+    /// Entries read `provider:model`; models may have colons of their own.
     pub fn apply_selected_model(&mut self) -> Option<String> {
-        self.model_selector
-            .selected_model()
-            .map(|s| s.to_string())
+        let selected = self.model_selector.selected_model()?.to_string();
+        if let Some((id, model)) = selected.split_once(':') {
+            let registry = self.llm_registry.read();
+            if let Some(provider) = registry.get(id) {
+                let _ = provider.set_model(model);
+                let _ = registry.set_active(id);
+            }
+        }
+        Some(selected)
     }
 
     /// Get panel by ID
@@ -284,3 +536,47 @@ impl PanelRegistry {
         self.input.set_cli_agents(Arc::new(cli_agents));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejected_key_is_asked_for_and_retried_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let config = AxiomConfig::default();
+        let mut panels =
+            PanelRegistry::new(tx.clone(), dir.path(), ProviderRegistry::new(), &config).unwrap();
+        let mut state = AppState::new();
+        let mut conductor = Conductor::new(Arc::new(RwLock::new(ProviderRegistry::new())), tx);
+        conductor.process("Say hello".to_string());
+        conductor.set_agent_id(crate::state::AgentId::new(1));
+
+        // Providers without a key in settings aren't asked about
+        assert!(!panels.ask_for_key(&mut state, &config, "ollama"));
+        assert!(state.auth_retry.pending().is_none());
+
+        assert!(panels.ask_for_key(&mut state, &config, "openai"));
+        assert_eq!(state.auth_retry.pending(), Some("openai"));
+        assert!(state.input_mode.is_modal_open("settings"));
+        assert!(panels.settings.editing);
+        // Not asked twice over an open prompt
+        assert!(!panels.ask_for_key(&mut state, &config, "claude"));
+
+        // Saving without a new key doesn't retry
+        assert!(!panels.retry_rejected_prompt(&mut state, &mut conductor));
+        assert!(state.auth_retry.pending().is_none());
+
+        state.input_mode.to_normal();
+        assert!(panels.ask_for_key(&mut state, &config, "openai"));
+        for c in "sk-new".chars() {
+            panels.settings.insert_char(c);
+        }
+        panels.settings.enter();
+        assert!(panels.settings.key_changed("openai"));
+        assert!(panels.retry_rejected_prompt(&mut state, &mut conductor));
+        // A later save doesn't send the prompt again
+        assert!(!panels.retry_rejected_prompt(&mut state, &mut conductor));
+    }
+}
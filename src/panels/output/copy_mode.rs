@@ -0,0 +1,272 @@
+//! Copy mode for CLI agents, like tmux's
+//!
+//! PageUp on a CLI agent freezes its transcript and shows it with a cursor
+//! line: j/k, Ctrl+u/Ctrl+d and g/G move, `v` starts a selection and `y`
+//! copies it (or the cursor line) to the clipboard. Esc or q goes back to
+//! the live screen.
+
+use crate::ui::theme::theme;
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+/// What a key did in copy mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyAction {
+    /// Moved or selected; stay in copy mode
+    None,
+    /// Copy this text and leave
+    Yank(String),
+    /// Leave copy mode
+    Exit,
+}
+
+/// A frozen transcript with a cursor and an optional selection
+pub struct CopyMode {
+    lines: Vec<String>,
+    /// Line the cursor is on
+    cursor: usize,
+    /// First line shown
+    offset: usize,
+    /// Where `v` started the selection
+    anchor: Option<usize>,
+    /// Lines shown at once
+    height: usize,
+}
+
+impl CopyMode {
+    /// Copy mode over `lines`, starting a page up from the end
+    pub fn new(lines: Vec<String>, height: usize) -> Self {
+        let height = height.max(1);
+        let last = lines.len().saturating_sub(1);
+        let mut mode = Self {
+            lines,
+            cursor: last,
+            offset: 0,
+            anchor: None,
+            height,
+        };
+        mode.move_up(height.saturating_sub(2));
+        mode
+    }
+
+    /// Move the cursor up `n` lines
+    pub fn move_up(&mut self, n: usize) {
+        self.cursor = self.cursor.saturating_sub(n);
+        self.follow_cursor();
+    }
+
+    /// Move the cursor down `n` lines
+    pub fn move_down(&mut self, n: usize) {
+        self.cursor = (self.cursor + n).min(self.lines.len().saturating_sub(1));
+        self.follow_cursor();
+    }
+
+    /// Move a page up
+    pub fn page_up(&mut self) {
+        self.move_up(self.height.saturating_sub(2).max(1));
+    }
+
+    /// Move a page down
+    pub fn page_down(&mut self) {
+        self.move_down(self.height.saturating_sub(2).max(1));
+    }
+
+    /// Move half a page up (Ctrl+u)
+    pub fn half_page_up(&mut self) {
+        self.move_up((self.height / 2).max(1));
+    }
+
+    /// Move half a page down (Ctrl+d)
+    pub fn half_page_down(&mut self) {
+        self.move_down((self.height / 2).max(1));
+    }
+
+    /// Go to the first line
+    pub fn top(&mut self) {
+        self.move_up(self.cursor);
+    }
+
+    /// Go to the last line
+    pub fn bottom(&mut self) {
+        self.move_down(self.lines.len());
+    }
+
+    /// Start a selection at the cursor, or drop the one there is
+    pub fn toggle_selection(&mut self) {
+        self.anchor = match self.anchor {
+            Some(_) => None,
+            None => Some(self.cursor),
+        };
+    }
+
+    /// Whether a selection is being made
+    pub fn is_selecting(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    /// First and last line selected, or the cursor line without a selection
+    fn selection(&self) -> (usize, usize) {
+        let anchor = self.anchor.unwrap_or(self.cursor);
+        (anchor.min(self.cursor), anchor.max(self.cursor))
+    }
+
+    /// The selected lines as text
+    pub fn selected_text(&self) -> String {
+        if self.lines.is_empty() {
+            return String::new();
+        }
+        let (start, end) = self.selection();
+        self.lines[start..=end].join("\n")
+    }
+
+    /// Where the cursor is, from 1, and how many lines there are
+    pub fn position(&self) -> (usize, usize) {
+        (self.cursor + 1, self.lines.len())
+    }
+
+    /// Change the lines shown at once
+    pub fn set_height(&mut self, height: usize) {
+        self.height = height.max(1);
+        self.follow_cursor();
+    }
+
+    /// Handle a vim-style key
+    pub fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> CopyAction {
+        match (code, modifiers) {
+            (KeyCode::Char('k'), KeyModifiers::NONE) | (KeyCode::Up, _) => self.move_up(1),
+            (KeyCode::Char('j'), KeyModifiers::NONE) | (KeyCode::Down, _) => self.move_down(1),
+            (KeyCode::Char('u'), KeyModifiers::CONTROL) => self.half_page_up(),
+            (KeyCode::Char('d'), KeyModifiers::CONTROL) => self.half_page_down(),
+            (KeyCode::Char('b'), KeyModifiers::CONTROL) | (KeyCode::PageUp, _) => self.page_up(),
+            (KeyCode::Char('f'), KeyModifiers::CONTROL) | (KeyCode::PageDown, _) => {
+                self.page_down()
+            }
+            (KeyCode::Char('g'), KeyModifiers::NONE) | (KeyCode::Home, _) => self.top(),
+            (KeyCode::Char('G'), _) | (KeyCode::End, _) => self.bottom(),
+            (KeyCode::Char('v'), KeyModifiers::NONE) | (KeyCode::Char('V'), _) => {
+                self.toggle_selection()
+            }
+            (KeyCode::Char('y'), KeyModifiers::NONE) | (KeyCode::Enter, _) => {
+                return CopyAction::Yank(self.selected_text());
+            }
+            (KeyCode::Esc, _) if self.is_selecting() => self.anchor = None,
+            (KeyCode::Esc, _) | (KeyCode::Char('q'), KeyModifiers::NONE) => {
+                return CopyAction::Exit;
+            }
+            _ => {}
+        }
+        CopyAction::None
+    }
+
+    /// Keep the cursor on screen
+    fn follow_cursor(&mut self) {
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.cursor >= self.offset + self.height {
+            self.offset = self.cursor + 1 - self.height;
+        }
+    }
+
+    /// Render the lines in view, the cursor and selection highlighted
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        self.set_height(area.height as usize);
+        let t = theme();
+        let (start, end) = match self.anchor {
+            Some(_) => self.selection(),
+            None => (usize::MAX, 0),
+        };
+        let lines: Vec<Line> = self
+            .lines
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(self.height)
+            .map(|(i, text)| {
+                let style = if i == self.cursor {
+                    Style::default()
+                        .bg(t.bg_selection)
+                        .add_modifier(Modifier::BOLD)
+                } else if (start..=end).contains(&i) {
+                    Style::default().bg(t.bg_selection)
+                } else {
+                    Style::default().fg(t.text_primary)
+                };
+                Line::from(Span::styled(text.clone(), style))
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(n: usize) -> Vec<String> {
+        (1..=n).map(|i| format!("line {}", i)).collect()
+    }
+
+    #[test]
+    fn test_copy_mode_starts_a_page_up() {
+        let mode = CopyMode::new(lines(50), 10);
+        assert_eq!(mode.position(), (42, 50));
+
+        // Short transcripts start at the top
+        let mode = CopyMode::new(lines(3), 10);
+        assert_eq!(mode.position(), (1, 3));
+        assert_eq!(CopyMode::new(Vec::new(), 10).selected_text(), "");
+    }
+
+    #[test]
+    fn test_copy_mode_navigation() {
+        let mut mode = CopyMode::new(lines(50), 10);
+        mode.handle_key(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(mode.position(), (50, 50));
+        assert_eq!(mode.offset, 40);
+        mode.handle_key(KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!(mode.position(), (45, 50));
+        mode.handle_key(KeyCode::Char('k'), KeyModifiers::NONE);
+        assert_eq!(mode.position(), (44, 50));
+        mode.handle_key(KeyCode::Char('g'), KeyModifiers::NONE);
+        assert_eq!(mode.position(), (1, 50));
+        assert_eq!(mode.offset, 0);
+        mode.handle_key(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(mode.position(), (2, 50));
+    }
+
+    #[test]
+    fn test_copy_mode_yank() {
+        let mut mode = CopyMode::new(lines(5), 10);
+        // Without a selection the cursor line is copied
+        assert_eq!(
+            mode.handle_key(KeyCode::Char('y'), KeyModifiers::NONE),
+            CopyAction::Yank("line 1".to_string())
+        );
+
+        mode.handle_key(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        mode.handle_key(KeyCode::Char('v'), KeyModifiers::NONE);
+        mode.handle_key(KeyCode::Char('k'), KeyModifiers::NONE);
+        mode.handle_key(KeyCode::Char('k'), KeyModifiers::NONE);
+        assert_eq!(
+            mode.handle_key(KeyCode::Char('y'), KeyModifiers::NONE),
+            CopyAction::Yank("line 3\nline 4\nline 5".to_string())
+        );
+
+        // Esc drops the selection first, then leaves
+        assert_eq!(
+            mode.handle_key(KeyCode::Esc, KeyModifiers::NONE),
+            CopyAction::None
+        );
+        assert!(!mode.is_selecting());
+        assert_eq!(
+            mode.handle_key(KeyCode::Esc, KeyModifiers::NONE),
+            CopyAction::Exit
+        );
+    }
+}
@@ -1,8 +1,10 @@
 //! File viewer component for the output panel
 //!
 //! Displays file content with syntax highlighting in read-only mode.
+//! Changes written to the open file by Axiom itself (e.g. a replace) can be
+//! diff-tracked so they show in the gutter until another file is opened.
 
-use crate::panels::editor::Highlighter;
+use crate::panels::editor::{DiffTracker, Highlighter};
 use crate::ui::theme::theme;
 use ratatui::{
     layout::Rect,
@@ -32,6 +34,9 @@ pub struct FileViewer {
 
     /// Currently loaded file path
     current_path: Option<std::path::PathBuf>,
+
+    /// Changes to the current file since `show_changes`
+    diff_tracker: DiffTracker,
 }
 
 impl FileViewer {
@@ -44,9 +49,15 @@ impl FileViewer {
             visible_height: 20,
             highlighter: Highlighter::new(),
             current_path: None,
+            diff_tracker: DiffTracker::new(),
         }
     }
 
+    /// Currently loaded file path
+    pub fn current_path(&self) -> Option<&Path> {
+        self.current_path.as_deref()
+    }
+
     /// Load file content
     pub fn load_file(&mut self, path: &Path) {
         // Don't reload if same file
@@ -56,7 +67,12 @@ impl FileViewer {
 
         self.current_path = Some(path.to_path_buf());
         self.scroll_offset = 0;
+        self.diff_tracker.stop_tracking();
+        self.read(path);
+    }
 
+    /// Read `path` into the viewer, keeping the scroll position
+    fn read(&mut self, path: &Path) {
         match std::fs::read_to_string(path) {
             Ok(content) => {
                 self.lines = content.lines().map(String::from).collect();
@@ -75,16 +91,29 @@ impl FileViewer {
                 )]];
             }
         }
+        if self.diff_tracker.is_tracking() {
+            self.diff_tracker.update_diff(&self.lines);
+        }
+        self.scroll_offset = self.scroll_offset.min(self.max_scroll());
     }
 
     /// Reload current file
     pub fn reload(&mut self) {
         if let Some(path) = self.current_path.clone() {
-            self.current_path = None; // Force reload
-            self.load_file(&path);
+            self.read(&path);
         }
     }
 
+    /// Reload the current file, marking how it differs from `before`
+    pub fn show_changes(&mut self, before: &str) {
+        let Some(path) = self.current_path.clone() else {
+            return;
+        };
+        let before: Vec<String> = before.lines().map(String::from).collect();
+        self.diff_tracker.start_tracking(&before);
+        self.read(&path);
+    }
+
     /// Get scroll offset
     pub fn scroll_offset(&self) -> usize {
         self.scroll_offset
@@ -115,6 +144,38 @@ impl FileViewer {
         self.scroll_offset = (self.scroll_offset + lines).min(self.max_scroll());
     }
 
+    /// Scroll to the next markdown heading below the top line
+    pub fn next_heading(&mut self) {
+        let offset = self.scroll_offset;
+        if let Some(line) = self.headings().into_iter().find(|&i| i > offset) {
+            self.set_scroll_offset(line);
+        }
+    }
+
+    /// Scroll to the previous markdown heading above the top line
+    pub fn prev_heading(&mut self) {
+        let offset = self.scroll_offset;
+        let line = self.headings().into_iter().rev().find(|&i| i < offset);
+        self.scroll_offset = line.unwrap_or(0);
+    }
+
+    /// Lines starting a markdown heading, skipping fenced code blocks
+    fn headings(&self) -> Vec<usize> {
+        let mut in_fence = false;
+        let mut headings = Vec::new();
+        for (i, line) in self.lines.iter().enumerate() {
+            if line.starts_with("```") {
+                in_fence = !in_fence;
+            } else if !in_fence
+                && line.starts_with('#')
+                && line.trim_start_matches('#').starts_with(' ')
+            {
+                headings.push(i);
+            }
+        }
+        headings
+    }
+
     /// Get total line count
     pub fn line_count(&self) -> usize {
         self.lines.len()
@@ -126,6 +187,7 @@ impl FileViewer {
         self.highlighted.clear();
         self.scroll_offset = 0;
         self.current_path = None;
+        self.diff_tracker.stop_tracking();
     }
 
     /// Render the file content
@@ -154,13 +216,22 @@ impl FileViewer {
                     Style::default().fg(t.text_secondary),
                 )];
 
+                let change = self.diff_tracker.get_line_change(i);
+                if self.diff_tracker.is_tracking() {
+                    spans.push(Span::styled(
+                        format!("{} ", change.gutter_char()),
+                        change.gutter_style(),
+                    ));
+                }
+                let bg = change.line_bg_style().unwrap_or_default();
+
                 // Add highlighted content
                 if i < self.highlighted.len() {
                     for (text, style) in &self.highlighted[i] {
-                        spans.push(Span::styled(text.clone(), *style));
+                        spans.push(Span::styled(text.clone(), style.patch(bg)));
                     }
                 } else if i < self.lines.len() {
-                    spans.push(Span::raw(self.lines[i].clone()));
+                    spans.push(Span::styled(self.lines[i].clone(), bg));
                 }
 
                 Line::from(spans)
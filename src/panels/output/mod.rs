@@ -2,20 +2,30 @@
 //!
 //! Displays either file content or agent output based on the current OutputContext.
 //! Delegates rendering to specialized sub-viewers.
-//! For CLI agents, renders an interactive terminal with full vt100 support.
+//! For CLI agents, renders an interactive terminal with full vt100 support;
+//! PageUp enters copy mode over the agent's transcript.
+//! A viewed file can be handed to an editor in place (`e`) and previewed
+//! again from it (`p` in normal mode), keeping the scroll position.
+//! Editing a file in a trusted workspace starts its language servers, whose
+//! diagnostics the editor marks in its gutter.
 
 mod agent_viewer;
+mod copy_mode;
 mod file_viewer;
 
 pub use agent_viewer::AgentViewer;
+pub use copy_mode::{CopyAction, CopyMode};
 pub use file_viewer::FileViewer;
 
 use crate::agents::{Agent, AgentRegistry, AgentType, PtyAgentManager};
+use crate::config::CompletionConfig;
 use crate::core::Result;
 use crate::events::Event;
-use crate::panels::Panel;
+use crate::panels::{EditorPanel, Panel};
 use crate::state::{AgentId, AppState, OutputContext, PanelId};
 use crate::ui::theme::theme;
+use axiom_core::config::LspConfig;
+use axiom_core::{Diagnostic, LspManager};
 use crossbeam_channel::Sender;
 use crossterm::event::{KeyCode, KeyModifiers, MouseEventKind};
 use parking_lot::RwLock;
@@ -29,6 +39,13 @@ use ratatui::{
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often a running agent's spinner and stream stats advance
+const ANIMATION_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Lines kept above a line revealed with [`OutputPanel::reveal_line`]
+const REVEAL_CONTEXT: usize = 3;
 
 /// Scroll state for a context
 #[derive(Debug, Clone, Default)]
@@ -47,6 +64,15 @@ pub struct OutputPanel {
     /// Agent viewer for displaying agent output
     agent_viewer: AgentViewer,
 
+    /// Editor for files handed over from the file viewer
+    editor: EditorPanel,
+
+    /// Whether the editor is shown in place of the file viewer
+    editing: bool,
+
+    /// Copy mode over the viewed CLI agent's transcript, if entered
+    copy_mode: Option<CopyMode>,
+
     /// Scroll states preserved per context
     scroll_states: HashMap<String, ScrollState>,
 
@@ -68,6 +94,15 @@ pub struct OutputPanel {
 
     /// Content area for scroll detection
     content_area: RefCell<Rect>,
+
+    /// Language servers to run for the editor (None when off)
+    lsp_config: Option<LspConfig>,
+
+    /// Completion model for workspaces that turn completions on
+    completion_config: Option<CompletionConfig>,
+
+    /// When the panel was last rendered
+    last_render: Instant,
 }
 
 impl OutputPanel {
@@ -77,6 +112,9 @@ impl OutputPanel {
             context: OutputContext::Empty,
             file_viewer: FileViewer::new(),
             agent_viewer: AgentViewer::new(),
+            editor: EditorPanel::new(),
+            editing: false,
+            copy_mode: None,
             scroll_states: HashMap::new(),
             agent_registry,
             pty_manager: None,
@@ -85,9 +123,17 @@ impl OutputPanel {
             panel_cols: 80,
             panel_rows: 24,
             content_area: RefCell::new(Rect::default()),
+            lsp_config: None,
+            completion_config: None,
+            last_render: Instant::now(),
         }
     }
 
+    /// Show agents from `registry` instead
+    pub fn set_agent_registry(&mut self, registry: Arc<RwLock<AgentRegistry>>) {
+        self.agent_registry = registry;
+    }
+
     /// Set the PTY agent manager for CLI agent rendering
     pub fn set_pty_manager(&mut self, manager: Arc<RwLock<PtyAgentManager>>, event_tx: Sender<Event>) {
         // Set the default PTY size based on current panel dimensions
@@ -95,10 +141,65 @@ impl OutputPanel {
         let pty_rows = self.panel_rows.saturating_sub(2);
         manager.write().set_default_size(pty_cols, pty_rows);
 
+        // The editor sends code action prompts through the same channel
+        self.editor.set_event_tx(event_tx.clone());
         self.pty_manager = Some(manager);
         self.event_tx = Some(event_tx);
     }
 
+    /// Language servers to run for edited files
+    pub fn set_lsp_config(&mut self, config: LspConfig) {
+        self.lsp_config = Some(config).filter(|config| config.enabled);
+    }
+
+    /// Completion model for edited files, in workspaces that enable completions
+    pub fn set_completion_config(&mut self, config: CompletionConfig) {
+        self.completion_config = Some(config);
+    }
+
+    /// Show a language server's latest diagnostics for `path` in the editor
+    pub fn set_diagnostics(&mut self, path: std::path::PathBuf, diagnostics: Vec<Diagnostic>) {
+        self.editor.set_diagnostics(path, diagnostics);
+    }
+
+    /// Give the editor language servers for the active workspace, starting
+    /// them on first use and again after a switch
+    ///
+    /// Servers run the project's build scripts and macros, so untrusted
+    /// workspaces get none.
+    fn update_lsp(&mut self, state: &AppState) {
+        let wanted = match (&self.lsp_config, &self.event_tx) {
+            (Some(config), Some(event_tx)) if state.is_trusted() => Some((config, event_tx)),
+            _ => None,
+        };
+        let current = self.editor.lsp().map(|lsp| lsp.root().to_path_buf());
+        match wanted {
+            Some(_) if current.as_ref() == Some(&state.cwd) => {}
+            Some((config, event_tx)) => {
+                let event_tx = event_tx.clone();
+                let lsp = LspManager::new(&state.cwd, config.clone(), move |path, diagnostics| {
+                    let _ = event_tx.send(Event::Diagnostics { path, diagnostics });
+                });
+                self.editor.set_lsp(Some(Arc::new(lsp)));
+            }
+            None if current.is_some() => self.editor.set_lsp(None),
+            None => {}
+        }
+    }
+
+    /// Give the editor the completion model if the active workspace has
+    /// `completion = true`
+    fn update_completion(&mut self, state: &AppState) {
+        let enabled = match (&state.workspace_manager, state.active_workspace_id) {
+            (Some(manager), Some(id)) => manager
+                .get_workspace_config(id)
+                .is_ok_and(|config| config.completion),
+            _ => false,
+        };
+        let completion = self.completion_config.clone().filter(|_| enabled);
+        self.editor.set_completion(completion);
+    }
+
     /// Check if the current context is a CLI agent
     fn is_cli_agent(&self) -> bool {
         if let OutputContext::Agent { agent_id } = &self.context {
@@ -123,6 +224,55 @@ impl OutputPanel {
         None
     }
 
+    /// Whether copy mode is on
+    pub fn is_copy_mode(&self) -> bool {
+        self.copy_mode.is_some()
+    }
+
+    /// Whether Esc should come here rather than switch to normal mode: in
+    /// copy mode, or while the editor's search or go-to-line prompt is open
+    pub fn takes_esc(&self) -> bool {
+        self.is_copy_mode() || (self.editing && self.editor.takes_esc())
+    }
+
+    /// Freeze the agent's transcript and enter copy mode over it
+    fn enter_copy_mode(&mut self, agent_id: AgentId, state: &mut AppState) {
+        let lines = self.pty_manager.as_ref().and_then(|manager| {
+            manager
+                .read()
+                .transcript(agent_id)
+                .filter(|transcript| !transcript.is_empty())
+                .map(|transcript| transcript.lines())
+        });
+        match lines {
+            Some(lines) => {
+                self.copy_mode = Some(CopyMode::new(lines, self.visible_height));
+                state.info("Copy mode: j/k to move, v to select, y to copy, q to leave");
+            }
+            None => state.info("No output to scroll back through yet"),
+        }
+    }
+
+    /// Handle a key in copy mode
+    fn copy_mode_key(&mut self, code: KeyCode, modifiers: KeyModifiers, state: &mut AppState) {
+        let Some(copy_mode) = self.copy_mode.as_mut() else {
+            return;
+        };
+        match copy_mode.handle_key(code, modifiers) {
+            CopyAction::None => {}
+            CopyAction::Yank(text) => {
+                self.copy_mode = None;
+                let count = text.lines().count();
+                match crate::clipboard::copy(&text) {
+                    Ok(()) if count == 1 => state.info("Copied 1 line"),
+                    Ok(()) => state.info(format!("Copied {} lines", count)),
+                    Err(e) => state.error(format!("Failed to copy: {}", e)),
+                }
+            }
+            CopyAction::Exit => self.copy_mode = None,
+        }
+    }
+
     /// Get the current context
     pub fn context(&self) -> &OutputContext {
         &self.context
@@ -136,6 +286,14 @@ impl OutputPanel {
             self.scroll_states.insert(key, state);
         }
 
+        // Showing anything but the edited file goes back to viewing
+        let edited = self.editor.current_file();
+        if !matches!(&context, OutputContext::File { path } if edited == Some(path.as_path())) {
+            self.editing = false;
+        }
+        if self.context != context {
+            self.copy_mode = None;
+        }
         self.context = context;
 
         // Load file content if switching to file context
@@ -150,9 +308,98 @@ impl OutputPanel {
         }
     }
 
+    /// Scroll the open file so `line` (from 1) is near the top
+    pub fn reveal_line(&mut self, line: usize) {
+        if let OutputContext::File { .. } = self.context {
+            let top = line.saturating_sub(1 + REVEAL_CONTEXT);
+            self.file_viewer.set_scroll_offset(top);
+            if self.editing {
+                self.editor.scroll_to_line(top);
+            }
+        }
+    }
+
+    /// Whether the viewed file is open in the editor
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    /// Whether any editor tab has unsaved changes, shown or not
+    pub fn has_unsaved(&self) -> bool {
+        self.editor.has_unsaved()
+    }
+
+    /// Pass the editor an event it takes wherever focus is: a file changed
+    /// on disk, a completion arriving, or a tick
+    pub fn editor_event(&mut self, event: &Event, state: &mut AppState) -> Result<bool> {
+        self.editor.handle_input(event, state)
+    }
+
+    /// Open the viewed file in the editor at the viewer's scroll position
+    fn edit_file(&mut self, state: &mut AppState) {
+        self.update_lsp(state);
+        self.update_completion(state);
+        let OutputContext::File { path } = &self.context else {
+            return;
+        };
+        if let Err(e) = self.editor.open(path) {
+            state.error(format!(
+                "Failed to open {} for editing: {}",
+                path.display(),
+                e
+            ));
+            return;
+        }
+        self.editor.scroll_to_line(self.file_viewer.scroll_offset());
+        self.editing = true;
+        state.info("Editing: i to insert, p in normal mode to preview");
+    }
+
+    /// Show the editor's file in the viewer at the editor's scroll position
+    fn preview_file(&mut self, state: &mut AppState) {
+        let Some(path) = self.editor.current_file().map(std::path::Path::to_path_buf) else {
+            return;
+        };
+        let top = self.editor.top_line();
+        self.editing = false;
+        if self.file_viewer.current_path() == Some(path.as_path()) {
+            self.file_viewer.reload();
+        }
+        if self.editor.is_modified() {
+            state.warn(format!(
+                "Previewing {} as saved; unsaved edits aren't shown",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ));
+        }
+        self.set_context(OutputContext::File { path });
+        self.file_viewer.set_scroll_offset(top);
+    }
+
+    /// Mark changes written to `path` if it's the file on show
+    pub fn show_file_changes(&mut self, path: &std::path::Path, before: &str) {
+        if self.file_viewer.current_path() == Some(path) {
+            self.file_viewer.show_changes(before);
+        }
+    }
+
+    /// Point the editor's tabs for a moved file or folder at its new path
+    pub fn path_moved(&mut self, from: &std::path::Path, to: &std::path::Path) {
+        self.editor.path_moved(from, to);
+    }
+
+    /// Close the editor's tabs for a deleted file or folder
+    pub fn path_deleted(&mut self, path: &std::path::Path) {
+        self.editor.path_deleted(path);
+        if self.editor.current_file().is_none() {
+            self.editing = false;
+        }
+    }
+
     /// Clear the output panel (reset to empty state)
     pub fn clear(&mut self) {
         self.context = OutputContext::Empty;
+        self.editing = false;
+        self.copy_mode = None;
         self.scroll_states.clear();
         self.file_viewer.clear();
         self.agent_viewer.clear();
@@ -220,7 +467,27 @@ impl OutputPanel {
             OutputContext::Agent { agent_id } => {
                 let registry = self.agent_registry.read();
                 if let Some(agent) = registry.get(*agent_id) {
-                    format!(" {} {} ", agent.agent_type.icon(), agent.name)
+                    // Live stats while the agent streams
+                    let stats = agent
+                        .stream
+                        .as_ref()
+                        .filter(|_| agent.status.is_running())
+                        .map(|stream| format!("· {} ", stream.badges(Instant::now()).join(" · ")))
+                        .unwrap_or_default();
+                    match &self.copy_mode {
+                        Some(copy_mode) => {
+                            let (line, total) = copy_mode.position();
+                            format!(
+                                " {} {} · copy {}/{} {}",
+                                agent.agent_type.icon(),
+                                agent.name,
+                                line,
+                                total,
+                                stats
+                            )
+                        }
+                        None => format!(" {} {} {}", agent.agent_type.icon(), agent.name, stats),
+                    }
                 } else {
                     format!(" Agent {} ", agent_id)
                 }
@@ -235,14 +502,54 @@ impl Panel for OutputPanel {
         PanelId::OUTPUT
     }
 
+    fn needs_redraw(&self) -> bool {
+        if self.editing {
+            return self.editor.needs_redraw();
+        }
+        // A running agent's spinner and stream stats move on their own
+        let OutputContext::Agent { agent_id } = self.context else {
+            return false;
+        };
+        self.last_render.elapsed() >= ANIMATION_INTERVAL
+            && self
+                .agent_registry
+                .read()
+                .get(agent_id)
+                .is_some_and(|agent| agent.status.is_running())
+    }
+
     fn name(&self) -> &str {
         "Output"
     }
 
-    fn handle_input(&mut self, event: &Event, _state: &mut AppState) -> Result<bool> {
+    fn handle_input(&mut self, event: &Event, state: &mut AppState) -> Result<bool> {
+        if self.editing {
+            if self.editor.handle_input(event, state)? {
+                return Ok(true);
+            }
+            if let Event::Key(key) = event {
+                if key.code == KeyCode::Char('p')
+                    && key.modifiers.is_empty()
+                    && !state.input_mode.is_editing()
+                {
+                    self.preview_file(state);
+                    return Ok(true);
+                }
+            }
+            return Ok(false);
+        }
+
         // If viewing a CLI agent, route keyboard input to the PTY
         if let Some(agent_id) = self.current_cli_agent_id() {
             if let Event::Key(key) = event {
+                if self.copy_mode.is_some() {
+                    self.copy_mode_key(key.code, key.modifiers, state);
+                    return Ok(true);
+                }
+                if key.code == KeyCode::PageUp && key.modifiers.is_empty() {
+                    self.enter_copy_mode(agent_id, state);
+                    return Ok(true);
+                }
                 // Convert key to bytes and send to PTY
                 let bytes = key_to_bytes(key.code, key.modifiers);
                 if !bytes.is_empty() {
@@ -255,6 +562,32 @@ impl Panel for OutputPanel {
                     return Ok(true);
                 }
             }
+            if let (Event::Mouse(mouse), Some(copy_mode)) = (event, self.copy_mode.as_mut()) {
+                match mouse.kind {
+                    MouseEventKind::ScrollUp => copy_mode.move_up(3),
+                    MouseEventKind::ScrollDown => copy_mode.move_down(3),
+                    _ => return Ok(false),
+                }
+                return Ok(true);
+            }
+            if let Event::Paste(text) = event {
+                let bracketed = self
+                    .pty_manager
+                    .as_ref()
+                    .is_some_and(|manager| manager.read().bracketed_paste(agent_id));
+                let data = if bracketed {
+                    format!("\x1b[200~{}\x1b[201~", text)
+                } else {
+                    text.clone()
+                };
+                if let Some(ref event_tx) = self.event_tx {
+                    let _ = event_tx.send(Event::CliAgentInput {
+                        id: agent_id,
+                        data: data.into_bytes(),
+                    });
+                }
+                return Ok(true);
+            }
         }
 
         match event {
@@ -283,6 +616,26 @@ impl Panel for OutputPanel {
                         self.apply_scroll(ScrollState { offset: 0 });
                         Ok(true)
                     }
+                    // Hand the file to the editor
+                    (KeyCode::Char('e'), KeyModifiers::NONE)
+                        if matches!(self.context, OutputContext::File { .. }) =>
+                    {
+                        self.edit_file(state);
+                        Ok(true)
+                    }
+                    // Jump between markdown headings, e.g. run document sections
+                    (KeyCode::Char(']'), KeyModifiers::NONE)
+                        if matches!(self.context, OutputContext::File { .. }) =>
+                    {
+                        self.file_viewer.next_heading();
+                        Ok(true)
+                    }
+                    (KeyCode::Char('['), KeyModifiers::NONE)
+                        if matches!(self.context, OutputContext::File { .. }) =>
+                    {
+                        self.file_viewer.prev_heading();
+                        Ok(true)
+                    }
                     _ => Ok(false),
                 }
             }
@@ -304,6 +657,12 @@ impl Panel for OutputPanel {
     fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool) {
         // Store content area for input handling
         *self.content_area.borrow_mut() = area;
+        self.last_render = Instant::now();
+
+        if self.editing {
+            self.editor.render(frame, area, focused);
+            return;
+        }
 
         let t = theme();
         let border_style = if focused {
@@ -336,6 +695,11 @@ impl Panel for OutputPanel {
                         let agent_status = agent.status.clone();
                         drop(registry);
 
+                        if let Some(ref mut copy_mode) = self.copy_mode {
+                            copy_mode.render(frame, inner);
+                            return;
+                        }
+
                         // Get screen lines from PTY manager
                         if let Some(ref pty_manager) = self.pty_manager {
                             let manager = pty_manager.read();
@@ -369,6 +733,7 @@ impl Panel for OutputPanel {
                             agent_type: agent.agent_type.clone(),
                             name: agent.name.clone(),
                             description: agent.description.clone(),
+                            parameters: agent.parameters.clone(),
                             status: agent.status.clone(),
                             output: agent.output.clone(),
                             created_at: agent.created_at,
@@ -377,6 +742,11 @@ impl Panel for OutputPanel {
                             line_count: agent.line_count,
                             progress: agent.progress,
                             parent_id: agent.parent_id,
+                            run: agent.run,
+                            last_activity: agent.last_activity,
+                            stream: agent.stream.clone(),
+                            cancel_token: agent.cancel_token.clone(),
+                            service: agent.service.clone(),
                         };
 
                         // Get children for aggregated output (only for Conductor)
@@ -388,6 +758,7 @@ impl Panel for OutputPanel {
                                 agent_type: c.agent_type.clone(),
                                 name: c.name.clone(),
                                 description: c.description.clone(),
+                                parameters: c.parameters.clone(),
                                 status: c.status.clone(),
                                 output: c.output.clone(),
                                 created_at: c.created_at,
@@ -396,6 +767,11 @@ impl Panel for OutputPanel {
                                 line_count: c.line_count,
                                 progress: c.progress,
                                 parent_id: c.parent_id,
+                                run: c.run,
+                                last_activity: c.last_activity,
+                                stream: c.stream.clone(),
+                                cancel_token: c.cancel_token.clone(),
+                                service: c.service.clone(),
                             })
                             .collect();
 
@@ -427,6 +803,12 @@ impl Panel for OutputPanel {
         }
     }
 
+    fn on_blur(&mut self) {
+        if self.editing {
+            self.editor.on_blur();
+        }
+    }
+
     fn on_resize(&mut self, cols: u16, rows: u16) {
         // Store panel dimensions for future PTY creation
         self.panel_cols = cols;
@@ -435,6 +817,7 @@ impl Panel for OutputPanel {
         self.visible_height = rows.saturating_sub(2) as usize;
         self.file_viewer.set_visible_height(self.visible_height);
         self.agent_viewer.set_visible_height(self.visible_height);
+        self.editor.on_resize(cols, rows);
 
         // Resize PTY agents to match panel size (account for borders)
         let pty_cols = cols.saturating_sub(2);
@@ -639,6 +1022,55 @@ mod tests {
         assert!(matches!(panel.context(), OutputContext::Agent { .. }));
     }
 
+    #[test]
+    fn test_edit_and_preview_keep_position() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        let content: String = (1..=100).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(&path, content).unwrap();
+        let other = dir.path().join("other.txt");
+        std::fs::write(&other, "x\n").unwrap();
+
+        let agent_registry = Arc::new(RwLock::new(AgentRegistry::new()));
+        let mut panel = OutputPanel::new(agent_registry);
+        panel.on_resize(80, 22);
+        let mut state = AppState::new();
+        let press = |panel: &mut OutputPanel, state: &mut AppState, c: char| {
+            let key = crossterm::event::KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+            panel.handle_input(&Event::Key(key), state).unwrap()
+        };
+
+        panel.set_context(OutputContext::File { path: path.clone() });
+        panel.reveal_line(44);
+        assert_eq!(panel.file_viewer.scroll_offset(), 40);
+
+        assert!(press(&mut panel, &mut state, 'e'));
+        assert!(panel.is_editing());
+        assert_eq!(panel.editor.current_file(), Some(path.as_path()));
+        assert_eq!(panel.editor.top_line(), 40);
+
+        // The editor takes keys now: j moves its cursor, not the viewer
+        for _ in 0..25 {
+            press(&mut panel, &mut state, 'j');
+        }
+        let top = panel.editor.top_line();
+        assert!(top > 40);
+        assert_eq!(panel.file_viewer.scroll_offset(), 40);
+
+        assert!(press(&mut panel, &mut state, 'p'));
+        assert!(!panel.is_editing());
+        assert_eq!(panel.file_viewer.scroll_offset(), top);
+
+        // Editing again picks up where the viewer is
+        panel.file_viewer.set_scroll_offset(10);
+        press(&mut panel, &mut state, 'e');
+        assert_eq!(panel.editor.top_line(), 10);
+
+        // Showing another file leaves the editor
+        panel.set_context(OutputContext::File { path: other });
+        assert!(!panel.is_editing());
+    }
+
     #[test]
     fn test_output_panel_title_empty() {
         let agent_registry = Arc::new(RwLock::new(AgentRegistry::new()));
@@ -647,4 +1079,70 @@ mod tests {
         let title = panel.title();
         assert_eq!(title, " Output ");
     }
+
+    #[test]
+    fn test_mounted_editor_actions_and_disk_changes() {
+        use crossterm::event::KeyEvent;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("calc.rs");
+        std::fs::write(&path, "fn main() {\n    dbg!(1);\n}\n").unwrap();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut panel = OutputPanel::new(Arc::new(RwLock::new(AgentRegistry::new())));
+        panel.editor.set_event_tx(tx);
+        let mut state = AppState::new();
+        let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+
+        panel.set_context(OutputContext::File { path: path.clone() });
+        panel.edit_file(&mut state);
+        assert!(panel.is_editing());
+
+        // Alt+Enter on a selection reaches the editor's code actions
+        state.input_mode.to_insert();
+        panel
+            .handle_input(&key(KeyCode::Down, KeyModifiers::SHIFT), &mut state)
+            .unwrap();
+        panel
+            .handle_input(&key(KeyCode::Enter, KeyModifiers::ALT), &mut state)
+            .unwrap();
+        panel
+            .handle_input(&key(KeyCode::Char('e'), KeyModifiers::NONE), &mut state)
+            .unwrap();
+        assert!(matches!(rx.try_recv(), Ok(Event::ConductorRequest(_))));
+
+        // A change on disk reaches the editor without focus
+        panel
+            .handle_input(&key(KeyCode::Char('x'), KeyModifiers::NONE), &mut state)
+            .unwrap();
+        assert!(panel.has_unsaved());
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        std::fs::write(&path, "fn main() {}\n").unwrap();
+        assert!(panel
+            .editor_event(&Event::FileChanged(path.clone()), &mut state)
+            .unwrap());
+        assert!(panel.has_unsaved());
+    }
+
+    #[test]
+    fn test_running_agent_needs_redraw() {
+        let registry = Arc::new(RwLock::new(AgentRegistry::new()));
+        let agent_id = registry.write().spawn(crate::agents::AgentSpawnRequest {
+            agent_type: crate::agents::AgentType::Shell,
+            name: "Shell".to_string(),
+            description: String::new(),
+            parameters: None,
+            parent_id: None,
+        });
+        registry.write().start(agent_id);
+        let mut panel = OutputPanel::new(registry.clone());
+        assert!(!panel.needs_redraw());
+
+        // The spinner advances once its interval has passed
+        panel.set_context(OutputContext::Agent { agent_id });
+        panel.last_render = Instant::now() - ANIMATION_INTERVAL;
+        assert!(panel.needs_redraw());
+
+        registry.write().complete(agent_id);
+        assert!(!panel.needs_redraw());
+    }
 }
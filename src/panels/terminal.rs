@@ -82,6 +82,10 @@ impl TerminalPanel {
             for col in 0..cols {
                 let cell = screen.cell(row, col);
                 if let Some(cell) = cell {
+                    // The wide character before it already covers this cell
+                    if cell.is_wide_continuation() {
+                        continue;
+                    }
                     let text = cell.contents();
                     if text.is_empty() {
                         spans.push(Span::raw(" "));
@@ -348,7 +352,7 @@ impl super::Panel for TerminalPanel {
         let inner_rows = inner_rows.max(1);
 
         if let Err(e) = self.pty.resize(inner_cols, inner_rows) {
-            eprintln!("PTY resize failed: {}", e);
+            tracing::warn!("PTY resize failed: {}", e);
         }
 
         // Also resize the parser to match PTY size
@@ -0,0 +1,143 @@
+//! Word boundaries for word-wise motions and double-click selection
+//!
+//! As in vim, a word is a run of letters, digits and `_`, or a run of other
+//! non-blank characters, so `foo.bar()` holds the words `foo`, `.`, `bar`
+//! and `()`. Columns count chars, and a grapheme cluster is never split.
+//! Everything here works on one line; callers handle line breaks.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Blank,
+    Word,
+    Punct,
+}
+
+/// Start column and class of each grapheme in `line`
+fn graphemes(line: &str) -> Vec<(usize, Class)> {
+    let mut col = 0;
+    line.graphemes(true)
+        .map(|grapheme| {
+            let c = grapheme.chars().next().unwrap_or(' ');
+            let class = if c.is_whitespace() {
+                Class::Blank
+            } else if c.is_alphanumeric() || c == '_' {
+                Class::Word
+            } else {
+                Class::Punct
+            };
+            let item = (col, class);
+            col += grapheme.chars().count();
+            item
+        })
+        .collect()
+}
+
+/// Index of the first grapheme starting at or after `col`
+fn index_at(items: &[(usize, Class)], col: usize) -> usize {
+    items.partition_point(|&(start, _)| start < col)
+}
+
+/// Column of grapheme `i`, or the line's length past the last one
+fn column_of(line: &str, items: &[(usize, Class)], i: usize) -> usize {
+    items
+        .get(i)
+        .map_or_else(|| line.chars().count(), |&(col, _)| col)
+}
+
+/// Start of the word after the one at `col` (vim `w`), or the line's end
+pub fn next_word_start(line: &str, col: usize) -> usize {
+    let items = graphemes(line);
+    let mut i = index_at(&items, col);
+    if let Some(&(_, class)) = items.get(i) {
+        while class != Class::Blank && items.get(i).is_some_and(|&(_, c)| c == class) {
+            i += 1;
+        }
+    }
+    while items.get(i).is_some_and(|&(_, c)| c == Class::Blank) {
+        i += 1;
+    }
+    column_of(line, &items, i)
+}
+
+/// Start of the word before `col` (vim `b`), or 0
+pub fn prev_word_start(line: &str, col: usize) -> usize {
+    let items = graphemes(line);
+    let mut i = index_at(&items, col);
+    while i > 0 && items[i - 1].1 == Class::Blank {
+        i -= 1;
+    }
+    if let Some(&(_, class)) = i.checked_sub(1).and_then(|j| items.get(j)) {
+        while i > 0 && items[i - 1].1 == class {
+            i -= 1;
+        }
+    }
+    column_of(line, &items, i)
+}
+
+/// Last column of the first word ending at or after `from`, if the line has
+/// one (vim `e` searches from the column after the cursor)
+pub fn word_end(line: &str, from: usize) -> Option<usize> {
+    let items = graphemes(line);
+    let mut i = index_at(&items, from);
+    while items.get(i).is_some_and(|&(_, c)| c == Class::Blank) {
+        i += 1;
+    }
+    let &(_, class) = items.get(i)?;
+    while items.get(i + 1).is_some_and(|&(_, c)| c == class) {
+        i += 1;
+    }
+    Some(items[i].0)
+}
+
+/// Columns `start..end` of the word, or run of blanks, at `col`
+pub fn word_at(line: &str, col: usize) -> (usize, usize) {
+    let items = graphemes(line);
+    if items.is_empty() {
+        return (0, 0);
+    }
+    let i = index_at(&items, col + 1).saturating_sub(1);
+    let class = items[i].1;
+    let mut start = i;
+    while start > 0 && items[start - 1].1 == class {
+        start -= 1;
+    }
+    let mut end = i;
+    while items.get(end + 1).is_some_and(|&(_, c)| c == class) {
+        end += 1;
+    }
+    (items[start].0, column_of(line, &items, end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_boundaries() {
+        let line = "let x = foo.bar(1);  // done";
+        assert_eq!(next_word_start(line, 0), 4);
+        assert_eq!(next_word_start(line, 8), 11);
+        assert_eq!(next_word_start(line, 11), 12);
+        assert_eq!(next_word_start(line, 24), 28);
+        assert_eq!(prev_word_start(line, 12), 11);
+        assert_eq!(prev_word_start(line, 11), 8);
+        assert_eq!(prev_word_start(line, 21), 17);
+        assert_eq!(prev_word_start(line, 2), 0);
+        assert_eq!(word_end(line, 1), Some(2));
+        assert_eq!(word_end(line, 9), Some(10));
+        assert_eq!(word_end(line, 27), Some(27));
+        assert_eq!(word_end("  ", 0), None);
+        assert_eq!(word_at(line, 9), (8, 11));
+        assert_eq!(word_at(line, 19), (19, 21));
+        assert_eq!(word_at(line, 99), (24, 28));
+
+        // Accents and emoji stay with their word
+        let line = "cafe\u{301} 👍🏽 ok";
+        assert_eq!(next_word_start(line, 0), 6);
+        assert_eq!(word_at(line, 2), (0, 5));
+        assert_eq!(word_end(line, 6), Some(6));
+        assert_eq!(prev_word_start(line, 9), 6);
+    }
+}
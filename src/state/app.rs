@@ -2,9 +2,20 @@
 //!
 //! Uses composition instead of a god object with 40+ fields.
 
-use super::{FocusState, InputMode};
-use axiom_core::{Workspace, WorkspaceId, WorkspaceManager};
+use super::time::{self, TimeTracker};
+use super::{FocusState, InputMode, PasteQueue, WatchState};
+use crate::ui::SoundEvent;
+use axiom_core::agents::AuthRetry;
+use axiom_core::config::Keymap;
+use axiom_core::llm::KeyWatch;
+use axiom_core::orchestration::{AgentOperation, RunOutcome};
+use axiom_core::{
+    ActivityEvent, ActivityKind, AgentEvent, PendingWrite, Plan, Workspace, WorkspaceId,
+    WorkspaceManager,
+};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Central application state
 ///
@@ -30,8 +41,48 @@ pub struct AppState {
 
     /// Active workspace ID (if any)
     pub active_workspace_id: Option<WorkspaceId>,
+
+    /// Agent and user clocks for the workspace time ledger
+    pub time: TimeTracker,
+
+    /// Watch rules of the active workspace
+    pub watch: WatchState,
+
+    /// Large paste still being handed to a panel
+    pub paste: Option<PasteQueue>,
+
+    /// The conductor request being worked on, for follow-up suggestions
+    pub run: Option<RunOutcome>,
+
+    /// The Conductor's latest multi-step plan (`/plan`)
+    pub plan: Option<Plan>,
+
+    /// Request the Conductor was asked to plan, until its reply arrives
+    pub planning: Option<String>,
+
+    /// Provider whose rejected key is being replaced; the Conductor's
+    /// prompt is sent again once it is
+    pub auth_retry: AuthRetry,
+
+    /// Provider keys being refreshed or reminded about
+    pub key_watch: KeyWatch,
+
+    /// Key bindings for the global actions, from `[keys]`
+    pub keymap: Keymap,
+
+    /// Last workspace the trust prompt was shown for
+    trust_asked: Option<WorkspaceId>,
+
+    /// Events waiting to be played by the bell
+    cues: Vec<SoundEvent>,
+
+    /// Paths just changed from the file tree, and when
+    tree_changes: Vec<(PathBuf, Instant)>,
 }
 
+/// How long the file watcher's report of a file tree change is dropped for
+const TREE_CHANGE_WINDOW: Duration = Duration::from_secs(2);
+
 /// Status bar message with optional timeout
 pub struct StatusMessage {
     pub text: String,
@@ -67,6 +118,18 @@ impl AppState {
             cwd,
             workspace_manager: None,
             active_workspace_id: None,
+            time: TimeTracker::new(),
+            watch: WatchState::new(),
+            paste: None,
+            run: None,
+            plan: None,
+            planning: None,
+            auth_retry: AuthRetry::default(),
+            key_watch: KeyWatch::new(),
+            keymap: Keymap::default(),
+            trust_asked: None,
+            cues: Vec::new(),
+            tree_changes: Vec::new(),
         }
     }
 
@@ -80,6 +143,18 @@ impl AppState {
             cwd,
             workspace_manager: None,
             active_workspace_id: None,
+            time: TimeTracker::new(),
+            watch: WatchState::new(),
+            paste: None,
+            run: None,
+            plan: None,
+            planning: None,
+            auth_retry: AuthRetry::default(),
+            key_watch: KeyWatch::new(),
+            keymap: Keymap::default(),
+            trust_asked: None,
+            cues: Vec::new(),
+            tree_changes: Vec::new(),
         }
     }
 
@@ -123,8 +198,17 @@ impl AppState {
             })
     }
 
+    /// The active workspace's own provider, models and CLI agent
+    pub fn workspace_defaults(&self) -> Option<axiom_core::WorkspaceDefaults> {
+        let manager = self.workspace_manager.as_ref()?;
+        manager.workspace_defaults(self.active_workspace_id?).ok()
+    }
+
     /// Switch to a different workspace
     pub fn switch_workspace(&mut self, id: WorkspaceId) -> crate::core::Result<std::path::PathBuf> {
+        // Time so far belongs to the workspace being left
+        self.flush_user_time();
+
         let manager = self.workspace_manager.as_ref().ok_or_else(|| {
             crate::core::AxiomError::Config("Workspace manager not initialized".into())
         })?;
@@ -176,10 +260,177 @@ impl AppState {
         self.set_status(text, MessageLevel::Info);
     }
 
-    /// Set error status
+    /// Set warning status (also logged, and to the workspace activity feed)
+    pub fn warn(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        tracing::warn!("{}", text);
+        self.record_activity(ActivityEvent::new(
+            ActivityKind::Notification,
+            "axiom",
+            text.clone(),
+        ));
+        self.set_status(text, MessageLevel::Warning);
+    }
+
+    /// Set error status (also logged, and to the workspace activity feed)
     pub fn error(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        tracing::error!("{}", text);
+        self.record_activity(ActivityEvent::new(
+            ActivityKind::Notification,
+            "axiom",
+            text.clone(),
+        ));
         self.set_status(text, MessageLevel::Error);
+        self.cue(SoundEvent::Error);
+    }
+
+    /// Ask for an audio cue; the main loop plays it if `[sounds]` allows
+    pub fn cue(&mut self, event: SoundEvent) {
+        self.cues.push(event);
+    }
+
+    /// Cues queued since the last call
+    pub fn take_cues(&mut self) -> Vec<SoundEvent> {
+        std::mem::take(&mut self.cues)
+    }
+
+    /// Note a change made from the file tree, which reports it itself
+    pub fn note_tree_change(&mut self, path: &Path) {
+        self.tree_changes.push((path.to_path_buf(), Instant::now()));
+    }
+
+    /// Whether the file watcher's report of `path` is for a change the file
+    /// tree just made
+    pub fn is_tree_change(&mut self, path: &Path) -> bool {
+        let now = Instant::now();
+        self.tree_changes
+            .retain(|(_, at)| now.duration_since(*at) < TREE_CHANGE_WINDOW);
+        self.tree_changes
+            .iter()
+            .any(|(changed, _)| path.starts_with(changed))
+    }
+
+    /// Note a key or mouse event, writing user time out every minute or so
+    pub fn note_input(&mut self) {
+        self.time.input(Instant::now());
+        if let Some(spent) = self.time.take_user_time(time::FLUSH_AFTER) {
+            self.record_user_time(spent);
+        }
+    }
+
+    /// Write out all user time accumulated so far
+    pub fn flush_user_time(&mut self) {
+        if let Some(spent) = self.time.take_user_time(Duration::ZERO) {
+            self.record_user_time(spent);
+        }
+    }
+
+    fn record_user_time(&self, spent: Duration) {
+        if let (Some(manager), Some(id)) = (&self.workspace_manager, self.active_workspace_id) {
+            let _ = manager.record_user_time(id, spent.as_secs());
+        }
+    }
+
+    /// Add an agent run to the active workspace's time ledger
+    pub fn record_agent_time(&self, role: &str, model: Option<&str>, ran: Duration) {
+        if let (Some(manager), Some(id)) = (&self.workspace_manager, self.active_workspace_id) {
+            let _ = manager.record_agent_time(id, role, model, ran.as_secs());
+        }
+    }
+
+    /// Append an entry to the active workspace's activity feed
+    ///
+    /// Does nothing without an active workspace; write failures are ignored
+    /// so bookkeeping never interrupts the user.
+    pub fn record_activity(&self, event: ActivityEvent) {
+        if let (Some(manager), Some(id)) = (&self.workspace_manager, self.active_workspace_id) {
+            let _ = manager.record_activity(id, &event);
+        }
+    }
+
+    /// Record what a CLI agent reported in its output in the active
+    /// workspace's activity feed and usage log
+    pub fn record_agent_event(&self, agent: &str, event: &AgentEvent) {
+        if let (Some(manager), Some(id)) = (&self.workspace_manager, self.active_workspace_id) {
+            let _ = manager.record_agent_event(id, agent, event);
+        }
+    }
+
+    /// Whether the active workspace may run agent commands and CLI agents
+    ///
+    /// Without a workspace there is nothing to restrict.
+    pub fn is_trusted(&self) -> bool {
+        match (&self.workspace_manager, self.active_workspace_id) {
+            (Some(manager), Some(id)) => manager.is_trusted(id),
+            _ => true,
+        }
     }
+
+    /// The active workspace, if it was just opened and its trust hasn't been
+    /// decided yet (asked once per switch)
+    pub fn take_trust_prompt(&mut self) -> Option<Workspace> {
+        let id = self.active_workspace_id?;
+        if self.trust_asked == Some(id) || self.input_mode.is_modal() {
+            return None;
+        }
+        self.trust_asked = Some(id);
+        let manager = self.workspace_manager.as_ref()?;
+        match manager.trust(id) {
+            Ok(None) => manager.get_workspace(id),
+            _ => None,
+        }
+    }
+
+    /// Hold `op` if the active workspace's write policy protects its path
+    ///
+    /// Returns whether the operation was held; it must not be applied then.
+    pub fn write_held(&mut self, agent: &str, op: &AgentOperation) -> bool {
+        let (Some(manager), Some(id)) = (&self.workspace_manager, self.active_workspace_id) else {
+            return false;
+        };
+        match manager.check_write(id, agent, op) {
+            Ok(Some(pending)) => {
+                self.set_status(approval_hint(&pending), MessageLevel::Warning);
+                self.cue(SoundEvent::Approval);
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                self.error(format!("Write policy check failed: {}", e));
+                true
+            }
+        }
+    }
+
+    /// Queue a write an agent held back for approval
+    pub fn hold_write(&mut self, pending: PendingWrite) {
+        let (Some(manager), Some(id)) = (&self.workspace_manager, self.active_workspace_id) else {
+            self.error(format!(
+                "Dropped write to {}: no workspace open to approve it in",
+                pending.path.display()
+            ));
+            return;
+        };
+        let hint = approval_hint(&pending);
+        match manager.hold_write(id, pending) {
+            Ok(()) => {
+                self.set_status(hint, MessageLevel::Warning);
+                self.cue(SoundEvent::Approval);
+            }
+            Err(e) => self.error(format!("Failed to hold write: {}", e)),
+        }
+    }
+}
+
+fn approval_hint(pending: &PendingWrite) -> String {
+    format!(
+        "{} wants to change {} ({}): /approve {}",
+        pending.agent,
+        pending.path.display(),
+        pending.reason,
+        pending.id
+    )
 }
 
 #[cfg(test)]
@@ -236,6 +487,8 @@ mod tests {
         let msg = state.status_message.as_ref().unwrap();
         assert_eq!(msg.text, "Error message");
         assert_eq!(msg.level, MessageLevel::Error);
+        assert_eq!(state.take_cues(), [SoundEvent::Error]);
+        assert!(state.take_cues().is_empty());
     }
 
     #[test]
@@ -28,6 +28,17 @@ impl PanelId {
 
     #[deprecated(note = "Use AGENTS instead")]
     pub const CHAT: PanelId = PanelId(3);
+
+    /// Name of the panel's table of key bindings (`[keys.panels.<name>]`)
+    pub fn keymap_scope(self) -> &'static str {
+        match self {
+            PanelId::FILE_TREE => "file_tree",
+            PanelId::OUTPUT => "output",
+            PanelId::INPUT => "input",
+            PanelId::AGENTS => "agents",
+            _ => "",
+        }
+    }
 }
 
 /// Focus state management
@@ -6,11 +6,17 @@ mod app;
 mod context;
 mod focus;
 mod input_mode;
+mod paste;
+mod time;
+mod watch;
 
 pub use app::AppState;
 pub use context::{AgentId, OutputContext};
 pub use focus::{FocusState, PanelId};
 pub use input_mode::InputMode;
+pub use paste::{PasteQueue, PASTE_CHUNK};
+pub use time::TimeTracker;
+pub use watch::WatchState;
 
 // Re-export workspace types from axiom-core
 pub use axiom_core::{Workspace, WorkspaceId, WorkspaceManager, WorkspaceType, WorkspaceView};
@@ -0,0 +1,89 @@
+//! Large pastes delivered in pieces
+//!
+//! A bracketed paste arrives as one `Event::Paste`, which panels insert in
+//! one go. A paste of several megabytes would hold up the UI for as long as
+//! that takes, so anything over [`PASTE_CHUNK`] bytes is queued and handed
+//! to the panel a chunk per frame, with progress in the status bar.
+
+use super::PanelId;
+
+/// Largest piece of a paste handed to a panel at once
+pub const PASTE_CHUNK: usize = 64 * 1024;
+
+/// A large paste still being delivered
+#[derive(Debug)]
+pub struct PasteQueue {
+    /// Panel that had focus when the text was pasted
+    pub panel: PanelId,
+    text: String,
+    /// Bytes of `text` delivered so far
+    sent: usize,
+}
+
+impl PasteQueue {
+    pub fn new(panel: PanelId, text: String) -> Self {
+        Self {
+            panel,
+            text,
+            sent: 0,
+        }
+    }
+
+    /// The next piece, ending after a line break when there is one in it
+    pub fn next_chunk(&mut self) -> Option<String> {
+        let rest = &self.text[self.sent..];
+        if rest.is_empty() {
+            return None;
+        }
+        let mut end = rest.len().min(PASTE_CHUNK);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end < rest.len() {
+            if let Some(newline) = rest[..end].rfind('\n') {
+                end = newline + 1;
+            }
+        }
+        self.sent += end;
+        Some(rest[..end].to_string())
+    }
+
+    /// Percentage delivered
+    pub fn percent(&self) -> usize {
+        self.sent * 100 / self.text.len().max(1)
+    }
+
+    /// Size of the whole paste in bytes
+    pub fn size(&self) -> usize {
+        self.text.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_end_at_line_breaks() {
+        let line = format!("{}\n", "é".repeat(999));
+        let text = line.repeat(100);
+        let mut queue = PasteQueue::new(PanelId::INPUT, text.clone());
+
+        let first = queue.next_chunk().unwrap();
+        assert!(first.len() <= PASTE_CHUNK);
+        assert!(first.ends_with('\n'));
+        assert!(queue.percent() > 0 && queue.percent() < 100);
+
+        let mut joined = first;
+        while let Some(chunk) = queue.next_chunk() {
+            joined.push_str(&chunk);
+        }
+        assert_eq!(joined, text);
+        assert_eq!(queue.percent(), 100);
+
+        // A single long line is split at a char boundary
+        let mut queue = PasteQueue::new(PanelId::INPUT, "日".repeat(PASTE_CHUNK));
+        let chunk = queue.next_chunk().unwrap();
+        assert!(chunk.len() <= PASTE_CHUNK && chunk.chars().all(|c| c == '日'));
+    }
+}
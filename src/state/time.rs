@@ -0,0 +1,91 @@
+//! Measuring agent and user time for the workspace time ledger
+//!
+//! User time is the sum of gaps between consecutive key/mouse events, with
+//! gaps longer than [`IDLE_GAP`] treated as time away. Agent time is wall
+//! clock from start (or wake) to completion.
+
+use super::AgentId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Longest pause between inputs still counted as interactive time
+pub const IDLE_GAP: Duration = Duration::from_secs(120);
+
+/// Accumulated user time is written out once it reaches this much
+pub const FLUSH_AFTER: Duration = Duration::from_secs(60);
+
+/// Running clocks for the active workspace
+#[derive(Debug, Default)]
+pub struct TimeTracker {
+    last_input: Option<Instant>,
+    user: Duration,
+    runs: HashMap<AgentId, Instant>,
+}
+
+impl TimeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note a user input event at `now`
+    pub fn input(&mut self, now: Instant) {
+        if let Some(last) = self.last_input {
+            let gap = now.saturating_duration_since(last);
+            if gap <= IDLE_GAP {
+                self.user += gap;
+            }
+        }
+        self.last_input = Some(now);
+    }
+
+    /// Take the accumulated user time once it reaches `min`
+    pub fn take_user_time(&mut self, min: Duration) -> Option<Duration> {
+        if self.user.is_zero() || self.user < min {
+            return None;
+        }
+        Some(std::mem::take(&mut self.user))
+    }
+
+    /// Start (or restart) the clock of an agent
+    pub fn agent_started(&mut self, id: AgentId, now: Instant) {
+        self.runs.insert(id, now);
+    }
+
+    /// Stop the clock of an agent, returning how long it ran
+    pub fn agent_stopped(&mut self, id: AgentId, now: Instant) -> Option<Duration> {
+        self.runs
+            .remove(&id)
+            .map(|started| now.saturating_duration_since(started))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_time_skips_idle_gaps() {
+        let start = Instant::now();
+        let mut tracker = TimeTracker::new();
+        tracker.input(start);
+        tracker.input(start + Duration::from_secs(30));
+        tracker.input(start + Duration::from_secs(60));
+        // Away for ten minutes
+        tracker.input(start + Duration::from_secs(660));
+        tracker.input(start + Duration::from_secs(670));
+
+        assert_eq!(tracker.take_user_time(FLUSH_AFTER), Some(Duration::from_secs(70)));
+        assert_eq!(tracker.take_user_time(Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_agent_runs() {
+        let start = Instant::now();
+        let mut tracker = TimeTracker::new();
+        tracker.agent_started(AgentId::new(1), start);
+
+        let ran = tracker.agent_stopped(AgentId::new(1), start + Duration::from_secs(42));
+        assert_eq!(ran, Some(Duration::from_secs(42)));
+        assert_eq!(tracker.agent_stopped(AgentId::new(1), start), None);
+    }
+}
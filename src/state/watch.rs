@@ -0,0 +1,117 @@
+//! Watch rules of the active workspace and the runs they started
+//!
+//! The file watcher reports changes as absolute paths; rules match paths
+//! relative to the workspace root. A rule counts as running until the agent
+//! it started completes (shell commands) or goes idle (the conductor).
+
+use super::AgentId;
+use axiom_core::{Feature, WatchEngine, WatchTrigger, WorkspaceId, WorkspaceManager};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+
+/// Watch engine kept in sync with the active workspace's rules
+#[derive(Debug, Default)]
+pub struct WatchState {
+    engine: WatchEngine,
+    /// Workspace the rules were loaded from
+    loaded_for: Option<WorkspaceId>,
+    /// Rule each running agent was started for
+    runs: HashMap<AgentId, usize>,
+}
+
+impl WatchState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the rules of workspace `id` unless they already are
+    pub fn sync(&mut self, manager: Option<&WorkspaceManager>, id: Option<WorkspaceId>) {
+        if self.loaded_for != id {
+            self.reload(manager, id);
+        }
+    }
+
+    /// Load the rules of workspace `id`, e.g. after `/watch add`
+    ///
+    /// No rules are loaded while the workspace's `watch` feature is off.
+    pub fn reload(&mut self, manager: Option<&WorkspaceManager>, id: Option<WorkspaceId>) {
+        let rules = match (manager, id) {
+            (Some(manager), Some(id)) => manager
+                .get_workspace_config(id)
+                .ok()
+                .filter(|config| config.features.is_enabled(Feature::Watch))
+                .map(|config| config.watch)
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        self.engine.set_rules(rules);
+        self.loaded_for = id;
+        self.runs.clear();
+    }
+
+    /// Note a change to `path` below `root`; returns whether a rule matched
+    pub fn file_changed(&mut self, root: &Path, path: &Path, now: Instant) -> bool {
+        let Ok(relative) = path.strip_prefix(root) else {
+            return false;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        self.engine.file_changed(&relative, now)
+    }
+
+    /// Rules due to run at `now`
+    pub fn due(&mut self, now: Instant) -> Vec<WatchTrigger> {
+        self.engine.due(now)
+    }
+
+    /// Remember that `agent` runs `rule`
+    pub fn started(&mut self, agent: AgentId, rule: usize) {
+        self.runs.insert(agent, rule);
+    }
+
+    /// Mark a rule done without an agent (skipped or failed to start)
+    pub fn skipped(&mut self, rule: usize) {
+        self.engine.finished(rule);
+    }
+
+    /// An agent completed or went idle
+    pub fn agent_done(&mut self, agent: AgentId) {
+        if let Some(rule) = self.runs.remove(&agent) {
+            self.engine.finished(rule);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axiom_core::{WatchAction, WatchRule};
+    use std::time::Duration;
+
+    #[test]
+    fn test_rule_runs_until_agent_done() {
+        let mut watch = WatchState::new();
+        watch.engine.set_rules(vec![WatchRule::new(
+            "src/*.rs",
+            WatchAction::Run {
+                command: "cargo check".to_string(),
+            },
+        )]);
+        let root = Path::new("/work/app");
+        let start = Instant::now();
+        let later = start + Duration::from_secs(1);
+
+        assert!(!watch.file_changed(root, Path::new("/elsewhere/src/a.rs"), start));
+        assert!(watch.file_changed(root, Path::new("/work/app/src/a.rs"), start));
+        let trigger = watch.due(later).pop().unwrap();
+        assert_eq!(trigger.files, ["src/a.rs"]);
+        watch.started(AgentId(7), trigger.rule);
+
+        watch.file_changed(root, Path::new("/work/app/src/b.rs"), start);
+        assert!(watch.due(later).is_empty());
+        watch.agent_done(AgentId(3));
+        assert!(watch.due(later).is_empty());
+        watch.agent_done(AgentId(7));
+        assert_eq!(watch.due(later)[0].files, ["src/b.rs"]);
+    }
+}
@@ -0,0 +1,250 @@
+//! Activity feed modal showing what happened in the active workspace
+//!
+//! Entries come from `WorkspaceManager::activity` one page at a time; the
+//! next page is requested when the selection nears the end of what's loaded.
+
+use crate::state::WorkspaceId;
+use crate::ui::theme::theme;
+use axiom_core::{ActivityEvent, ActivityKind, ActivityPage};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Entries requested per page
+pub const ACTIVITY_PAGE_SIZE: usize = 50;
+
+/// Rows from the end of the loaded entries at which the next page is fetched
+const PREFETCH_ROWS: usize = 5;
+
+/// Activity feed modal state
+pub struct ActivityFeed {
+    /// Workspace the feed belongs to
+    pub workspace_id: WorkspaceId,
+
+    /// Workspace name for the title
+    workspace_name: String,
+
+    /// Loaded entries, newest first
+    events: Vec<ActivityEvent>,
+
+    /// Entries in the whole feed
+    total: usize,
+
+    /// Offset of the next page, if there is one
+    next_offset: Option<usize>,
+
+    /// Currently selected index
+    selected: usize,
+
+    /// Cached modal area for hit testing
+    modal_area: Option<Rect>,
+}
+
+impl ActivityFeed {
+    /// Create a feed from its first page
+    pub fn new(workspace_id: WorkspaceId, workspace_name: impl Into<String>, page: ActivityPage) -> Self {
+        Self {
+            workspace_id,
+            workspace_name: workspace_name.into(),
+            events: page.events,
+            total: page.total,
+            next_offset: page.next_offset,
+            selected: 0,
+            modal_area: None,
+        }
+    }
+
+    /// Append the next page
+    pub fn extend(&mut self, page: ActivityPage) {
+        self.events.extend(page.events);
+        self.total = page.total;
+        self.next_offset = page.next_offset;
+    }
+
+    /// Offset of the page to load, once the selection is close to the end
+    pub fn wants_more(&self) -> Option<usize> {
+        let offset = self.next_offset?;
+        (self.selected + PREFETCH_ROWS >= self.events.len()).then_some(offset)
+    }
+
+    /// Loaded entries, newest first
+    pub fn events(&self) -> &[ActivityEvent] {
+        &self.events
+    }
+
+    /// Move selection up by `rows`
+    pub fn up(&mut self, rows: usize) {
+        self.selected = self.selected.saturating_sub(rows);
+    }
+
+    /// Move selection down by `rows`
+    pub fn down(&mut self, rows: usize) {
+        self.selected = (self.selected + rows).min(self.events.len().saturating_sub(1));
+    }
+
+    /// Check if a point is inside the modal
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        self.modal_area
+            .map(|r| x >= r.x && x < r.x + r.width && y >= r.y && y < r.y + r.height)
+            .unwrap_or(false)
+    }
+
+    /// Render the activity feed modal
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let modal_width = (area.width as f32 * 0.7).max(50.0) as u16;
+        let modal_height = (area.height as f32 * 0.7).max(12.0) as u16;
+        let modal_area = Rect::new(
+            area.width.saturating_sub(modal_width) / 2,
+            area.height.saturating_sub(modal_height) / 2,
+            modal_width.min(area.width),
+            modal_height.min(area.height),
+        );
+        self.modal_area = Some(modal_area);
+
+        frame.render_widget(Clear, modal_area);
+
+        let t = theme();
+        let block = Block::default()
+            .title(format!(" Activity · {} (↑↓ PgUp PgDn Esc) ", self.workspace_name))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_focused))
+            .style(Style::default().bg(t.bg_modal));
+        let inner = block.inner(modal_area);
+        frame.render_widget(block, modal_area);
+
+        if self.events.is_empty() {
+            let empty = Paragraph::new("No activity yet")
+                .style(Style::default().fg(t.text_muted))
+                .alignment(Alignment::Center);
+            frame.render_widget(empty, inner);
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(inner);
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        let items: Vec<ListItem> = self
+            .events
+            .iter()
+            .enumerate()
+            .map(|(i, event)| {
+                let kind_color = match event.kind {
+                    ActivityKind::AgentRun => t.accent_primary,
+                    ActivityKind::FileChange => t.status_success,
+                    ActivityKind::GitCommit => t.status_warning,
+                    ActivityKind::Notification => t.status_error,
+                };
+                let mut summary = event.summary.clone();
+                if let Some(path) = &event.path {
+                    summary = format!("{} {}", summary, path.display());
+                }
+
+                let mut line = Line::from(vec![
+                    Span::styled(
+                        format!("{:>8}  ", time_ago(event.timestamp, now)),
+                        Style::default().fg(t.text_muted),
+                    ),
+                    Span::styled(format!("{:<7}", event.kind.label()), Style::default().fg(kind_color)),
+                    Span::styled(
+                        format!("{:<14} ", truncate(&event.actor, 14)),
+                        Style::default().fg(t.text_secondary),
+                    ),
+                    Span::styled(summary, Style::default().fg(t.text_primary)),
+                ]);
+                if i == self.selected {
+                    line = line.style(
+                        Style::default()
+                            .bg(t.accent_primary)
+                            .fg(t.text_inverse)
+                            .add_modifier(Modifier::BOLD),
+                    );
+                }
+                ListItem::new(line)
+            })
+            .collect();
+
+        let mut list_state = ListState::default();
+        list_state.select(Some(self.selected));
+        frame.render_stateful_widget(List::new(items), chunks[0], &mut list_state);
+
+        let footer = Paragraph::new(format!("{} of {} entries", self.events.len(), self.total))
+            .style(Style::default().fg(t.text_muted))
+            .alignment(Alignment::Center);
+        frame.render_widget(footer, chunks[1]);
+    }
+}
+
+/// Compact age of a timestamp, e.g. "5m ago"
+fn time_ago(timestamp: i64, now: i64) -> String {
+    let secs = (now - timestamp).max(0);
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86_399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
+
+/// Cut `text` to at most `max` characters, marking the cut with '…'
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        let cut: String = text.chars().take(max.saturating_sub(1)).collect();
+        format!("{}…", cut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(range: std::ops::Range<usize>, total: usize) -> ActivityPage {
+        let events = range
+            .clone()
+            .map(|i| ActivityEvent::new(ActivityKind::AgentRun, "Shell", format!("run {}", i)))
+            .collect();
+        ActivityPage {
+            events,
+            total,
+            next_offset: (range.end < total).then_some(range.end),
+            chain_head: None,
+        }
+    }
+
+    #[test]
+    fn test_pages_load_near_the_end() {
+        let mut feed = ActivityFeed::new(WorkspaceId::new(), "demo", page(0..20, 30));
+        assert_eq!(feed.wants_more(), None);
+
+        feed.down(14);
+        assert_eq!(feed.wants_more(), None);
+        feed.down(1);
+        assert_eq!(feed.wants_more(), Some(20));
+
+        feed.extend(page(20..30, 30));
+        assert_eq!(feed.events().len(), 30);
+        feed.down(100);
+        assert_eq!(feed.events()[feed.selected].summary, "run 29");
+        assert_eq!(feed.wants_more(), None);
+    }
+
+    #[test]
+    fn test_time_ago() {
+        assert_eq!(time_ago(100, 130), "just now");
+        assert_eq!(time_ago(0, 300), "5m ago");
+        assert_eq!(time_ago(0, 7200), "2h ago");
+        assert_eq!(time_ago(0, 3 * 86_400), "3d ago");
+    }
+}
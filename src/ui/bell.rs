@@ -0,0 +1,260 @@
+//! Audio cues for events that need the user's attention
+//!
+//! Code that notices such an event calls [`AppState::cue`](crate::state::AppState::cue);
+//! errors cue themselves through `AppState::error`. The main loop hands the
+//! queued events to a [`Bell`], which rings the terminal bell or runs the
+//! `[sounds] command` for those enabled in the config, outside quiet hours.
+//! Cues closer together than [`MIN_GAP`] are dropped so a burst of agents
+//! finishing rings once.
+
+use crate::config::SoundConfig;
+use std::time::{Duration, Instant};
+
+/// Shortest time between two cues
+pub const MIN_GAP: Duration = Duration::from_secs(1);
+
+/// Something that may deserve a sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEvent {
+    /// An agent finished its work
+    AgentDone,
+    /// A write is waiting for approval
+    Approval,
+    /// An error was reported
+    Error,
+}
+
+impl SoundEvent {
+    /// Name passed to the sound command in `AXIOM_EVENT`
+    pub fn name(&self) -> &'static str {
+        match self {
+            SoundEvent::AgentDone => "agent_done",
+            SoundEvent::Approval => "approval",
+            SoundEvent::Error => "error",
+        }
+    }
+}
+
+/// A daily time range in minutes after midnight; may wrap past midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    start: u32,
+    end: u32,
+}
+
+impl QuietHours {
+    /// Parse `"HH:MM-HH:MM"`, e.g. `"22:00-07:00"`
+    pub fn parse(range: &str) -> Option<Self> {
+        let (start, end) = range.split_once('-')?;
+        Some(Self {
+            start: parse_clock(start)?,
+            end: parse_clock(end)?,
+        })
+    }
+
+    pub fn contains(&self, minute: u32) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+fn parse_clock(clock: &str) -> Option<u32> {
+    let (hours, minutes) = clock.trim().split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// How a cue is played
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cue {
+    /// Write BEL to the terminal
+    Bell,
+    /// Run the configured sound command
+    Command(String),
+}
+
+/// Decides which events make a sound and plays them
+#[derive(Debug)]
+pub struct Bell {
+    config: SoundConfig,
+    quiet: Option<QuietHours>,
+    last: Option<Instant>,
+}
+
+impl Bell {
+    pub fn new(config: SoundConfig) -> Self {
+        let quiet = config.quiet_hours.as_deref().and_then(QuietHours::parse);
+        Self {
+            config,
+            quiet,
+            last: None,
+        }
+    }
+
+    /// Problem with the config worth telling the user about
+    pub fn warning(&self) -> Option<String> {
+        let range = self.config.quiet_hours.as_ref()?;
+        self.quiet.is_none().then(|| {
+            format!(
+                "Ignoring [sounds] quiet_hours = \"{}\" (expected e.g. \"22:00-07:00\")",
+                range
+            )
+        })
+    }
+
+    /// The cue for `event` at `now` (`minute` of the local day), if any
+    pub fn cue(&mut self, event: SoundEvent, now: Instant, minute: u32) -> Option<Cue> {
+        let wanted = self.config.enabled
+            && match event {
+                SoundEvent::AgentDone => self.config.agent_done,
+                SoundEvent::Approval => self.config.approval,
+                SoundEvent::Error => self.config.error,
+            };
+        if !wanted || self.quiet.is_some_and(|quiet| quiet.contains(minute)) {
+            return None;
+        }
+        if self
+            .last
+            .is_some_and(|last| now.duration_since(last) < MIN_GAP)
+        {
+            return None;
+        }
+        self.last = Some(now);
+        Some(match &self.config.command {
+            Some(command) => Cue::Command(command.clone()),
+            None => Cue::Bell,
+        })
+    }
+
+    /// Play the cues for `events`; returns the sequence to write to the terminal
+    pub fn ring(&mut self, events: &[SoundEvent]) -> String {
+        let mut out = String::new();
+        for &event in events {
+            match self.cue(event, Instant::now(), local_minute()) {
+                Some(Cue::Bell) => out.push('\x07'),
+                Some(Cue::Command(command)) => spawn_sound(&command, event),
+                None => {}
+            }
+        }
+        out
+    }
+}
+
+/// Run `command` in the background; a broken command only costs the sound
+fn spawn_sound(command: &str, event: SoundEvent) {
+    let mut process = if cfg!(windows) {
+        let mut cmd = std::process::Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = std::process::Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    let spawned = process
+        .env("AXIOM_EVENT", event.name())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+    if let Ok(mut child) = spawned {
+        // Reap it so it doesn't linger as a zombie
+        std::thread::spawn(move || child.wait());
+    }
+}
+
+/// Minutes since local midnight
+#[cfg(unix)]
+fn local_minute() -> u32 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as libc::time_t)
+        .unwrap_or(0);
+    // SAFETY: localtime_r only writes to the tm we pass in
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&now, &mut tm) }.is_null() {
+        return utc_minute();
+    }
+    (tm.tm_hour * 60 + tm.tm_min) as u32
+}
+
+/// Minutes since midnight UTC (no local time zone lookup off unix)
+#[cfg(not(unix))]
+fn local_minute() -> u32 {
+    utc_minute()
+}
+
+fn utc_minute() -> u32 {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    ((secs / 60) % (24 * 60)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SoundConfig {
+        SoundConfig {
+            enabled: true,
+            ..SoundConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_quiet_hours() {
+        let night = QuietHours::parse("22:00-07:30").unwrap();
+        assert!(night.contains(23 * 60));
+        assert!(night.contains(7 * 60 + 29));
+        assert!(!night.contains(7 * 60 + 30));
+        assert!(!night.contains(12 * 60));
+
+        let lunch = QuietHours::parse("12:00 - 13:00").unwrap();
+        assert!(lunch.contains(12 * 60 + 30));
+        assert!(!lunch.contains(13 * 60));
+
+        assert_eq!(QuietHours::parse("25:00-07:00"), None);
+        assert_eq!(QuietHours::parse("late"), None);
+    }
+
+    #[test]
+    fn test_cues_follow_flags_gap_and_quiet_hours() {
+        let now = Instant::now();
+        let noon = 12 * 60;
+
+        let mut off = Bell::new(SoundConfig::default());
+        assert_eq!(off.cue(SoundEvent::Error, now, noon), None);
+
+        let mut bell = Bell::new(SoundConfig {
+            approval: false,
+            quiet_hours: Some("22:00-07:00".to_string()),
+            ..config()
+        });
+        assert_eq!(bell.warning(), None);
+        assert_eq!(bell.cue(SoundEvent::Approval, now, noon), None);
+        assert_eq!(bell.cue(SoundEvent::AgentDone, now, 23 * 60), None);
+        assert_eq!(bell.cue(SoundEvent::AgentDone, now, noon), Some(Cue::Bell));
+        // A burst rings once
+        assert_eq!(bell.cue(SoundEvent::Error, now, noon), None);
+        assert_eq!(
+            bell.cue(SoundEvent::Error, now + MIN_GAP, noon),
+            Some(Cue::Bell)
+        );
+
+        let mut custom = Bell::new(SoundConfig {
+            command: Some("paplay done.oga".to_string()),
+            quiet_hours: Some("soon".to_string()),
+            ..config()
+        });
+        assert!(custom.warning().is_some());
+        assert_eq!(
+            custom.cue(SoundEvent::AgentDone, now, noon),
+            Some(Cue::Command("paplay done.oga".to_string()))
+        );
+    }
+}
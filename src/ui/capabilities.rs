@@ -0,0 +1,275 @@
+//! Terminal capability detection
+//!
+//! Probed once at startup from the environment (`TERM`, `COLORTERM`,
+//! `TERM_PROGRAM`, multiplexer variables) and consulted by the theme,
+//! syntax highlighter, clipboard and render loop so features degrade
+//! gracefully on basic terminals. Set `AXIOM_BASIC_TERMINAL=1` to force the
+//! most conservative profile.
+
+use ratatui::style::Color;
+use std::sync::OnceLock;
+
+/// What the host terminal can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// 24-bit RGB colors
+    pub truecolor: bool,
+    /// 256-color palette
+    pub colors_256: bool,
+    /// Kitty graphics protocol (inline images)
+    pub kitty_graphics: bool,
+    /// Synchronized output (DEC mode 2026), avoids tearing
+    pub synchronized_output: bool,
+    /// OSC 52 clipboard writes (works over SSH)
+    pub osc52: bool,
+    /// Bracketed paste mode
+    pub bracketed_paste: bool,
+}
+
+impl Capabilities {
+    /// The most conservative profile: 16 colors, no extensions
+    pub fn basic() -> Self {
+        Self {
+            truecolor: false,
+            colors_256: false,
+            kitty_graphics: false,
+            synchronized_output: false,
+            osc52: false,
+            bracketed_paste: false,
+        }
+    }
+
+    /// Detect capabilities from the process environment
+    pub fn detect() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Detect capabilities from an environment lookup function
+    pub fn from_lookup(env: impl Fn(&str) -> Option<String>) -> Self {
+        let is_set = |key: &str| env(key).is_some_and(|v| !v.is_empty());
+        if env("AXIOM_BASIC_TERMINAL").is_some_and(|v| v != "0" && !v.is_empty()) {
+            return Self::basic();
+        }
+
+        let term = env("TERM").unwrap_or_default().to_lowercase();
+        if term.is_empty() || term == "dumb" {
+            return Self::basic();
+        }
+        let colorterm = env("COLORTERM").unwrap_or_default().to_lowercase();
+        let program = env("TERM_PROGRAM").unwrap_or_default();
+        let in_tmux = is_set("TMUX");
+        let linux_console = term == "linux";
+
+        let kitty = term.contains("kitty") || is_set("KITTY_WINDOW_ID");
+        let wezterm = program == "WezTerm" || is_set("WEZTERM_EXECUTABLE");
+        let ghostty = term.contains("ghostty") || program == "ghostty";
+        let modern = kitty
+            || wezterm
+            || ghostty
+            || term.contains("alacritty")
+            || term.starts_with("foot")
+            || term.contains("contour")
+            || matches!(program.as_str(), "iTerm.app" | "vscode")
+            || is_set("WT_SESSION");
+
+        let truecolor =
+            colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") || modern;
+        let colors_256 = truecolor || term.contains("256color");
+
+        Self {
+            truecolor,
+            colors_256,
+            // tmux doesn't pass graphics through
+            kitty_graphics: (kitty || wezterm || ghostty) && !in_tmux,
+            synchronized_output: modern,
+            osc52: !linux_console && (modern || in_tmux || term.starts_with("xterm")),
+            bracketed_paste: !linux_console,
+        }
+    }
+
+    /// Map a color to something this terminal can display
+    pub fn adapt_color(&self, color: Color) -> Color {
+        match color {
+            Color::Rgb(r, g, b) if !self.truecolor => {
+                if self.colors_256 {
+                    Color::Indexed(nearest_256(r, g, b))
+                } else {
+                    nearest_16(r, g, b)
+                }
+            }
+            Color::Indexed(i) if !self.colors_256 && i >= 16 => {
+                let (r, g, b) = indexed_rgb(i);
+                nearest_16(r, g, b)
+            }
+            other => other,
+        }
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::basic()
+    }
+}
+
+static CAPABILITIES: OnceLock<Capabilities> = OnceLock::new();
+
+/// Get the terminal capabilities (detected on first use)
+pub fn capabilities() -> &'static Capabilities {
+    CAPABILITIES.get_or_init(Capabilities::detect)
+}
+
+/// Levels of the xterm 6x6x6 color cube
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Approximate RGB values of the 16 ANSI colors
+const ANSI_16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (128, 0, 0)),
+    (Color::Green, (0, 128, 0)),
+    (Color::Yellow, (128, 128, 0)),
+    (Color::Blue, (0, 0, 128)),
+    (Color::Magenta, (128, 0, 128)),
+    (Color::Cyan, (0, 128, 128)),
+    (Color::Gray, (192, 192, 192)),
+    (Color::DarkGray, (128, 128, 128)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (0, 0, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn nearest_cube_level(v: u8) -> usize {
+    CUBE_LEVELS
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, level)| (v as i32 - **level as i32).abs())
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// RGB value of a 256-palette entry
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..=15 => ANSI_16[i as usize].1,
+        16..=231 => {
+            let i = i - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[((i / 6) % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let v = 8 + (i - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+/// Nearest entry in the 256-color palette (cube or grayscale ramp)
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let (ri, gi, bi) = (
+        nearest_cube_level(r),
+        nearest_cube_level(g),
+        nearest_cube_level(b),
+    );
+    let cube = 16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8;
+
+    let avg = ((r as u16 + g as u16 + b as u16) / 3) as u8;
+    let gray = 232 + (avg.saturating_sub(3) / 10).min(23);
+
+    if distance((r, g, b), indexed_rgb(gray)) < distance((r, g, b), indexed_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// Nearest of the 16 ANSI colors
+fn nearest_16(r: u8, g: u8, b: u8) -> Color {
+    ANSI_16
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn detect(vars: &[(&str, &str)]) -> Capabilities {
+        let env: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Capabilities::from_lookup(|key| env.get(key).cloned())
+    }
+
+    #[test]
+    fn test_detect_basic_terminals() {
+        assert_eq!(detect(&[]), Capabilities::basic());
+        assert_eq!(detect(&[("TERM", "dumb")]), Capabilities::basic());
+
+        let linux = detect(&[("TERM", "linux")]);
+        assert!(!linux.colors_256);
+        assert!(!linux.osc52);
+        assert!(!linux.bracketed_paste);
+    }
+
+    #[test]
+    fn test_detect_modern_terminals() {
+        let kitty = detect(&[("TERM", "xterm-kitty"), ("COLORTERM", "truecolor")]);
+        assert!(kitty.truecolor && kitty.kitty_graphics && kitty.synchronized_output);
+
+        let tmux = detect(&[
+            ("TERM", "tmux-256color"),
+            ("TMUX", "/tmp/tmux-1000/default,1,0"),
+            ("KITTY_WINDOW_ID", "1"),
+        ]);
+        assert!(tmux.colors_256);
+        assert!(!tmux.kitty_graphics);
+        assert!(tmux.osc52);
+
+        let xterm = detect(&[("TERM", "xterm-256color")]);
+        assert!(xterm.colors_256 && !xterm.truecolor && !xterm.synchronized_output);
+
+        let forced = detect(&[("TERM", "xterm-kitty"), ("AXIOM_BASIC_TERMINAL", "1")]);
+        assert_eq!(forced, Capabilities::basic());
+    }
+
+    #[test]
+    fn test_adapt_color() {
+        let truecolor = detect(&[("TERM", "xterm-kitty")]);
+        assert_eq!(
+            truecolor.adapt_color(Color::Rgb(1, 2, 3)),
+            Color::Rgb(1, 2, 3)
+        );
+
+        let palette = detect(&[("TERM", "xterm-256color")]);
+        assert_eq!(
+            palette.adapt_color(Color::Rgb(255, 0, 0)),
+            Color::Indexed(196)
+        );
+        assert_eq!(
+            palette.adapt_color(Color::Rgb(30, 30, 30)),
+            Color::Indexed(234)
+        );
+        assert_eq!(palette.adapt_color(Color::Cyan), Color::Cyan);
+
+        let basic = Capabilities::basic();
+        assert_eq!(basic.adapt_color(Color::Rgb(250, 10, 10)), Color::LightRed);
+        assert_eq!(basic.adapt_color(Color::Rgb(20, 20, 25)), Color::Black);
+        assert_eq!(basic.adapt_color(Color::Indexed(196)), Color::LightRed);
+    }
+}
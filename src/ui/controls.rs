@@ -0,0 +1,130 @@
+//! Mouse-aware building blocks shared by modals
+//!
+//! Hit testing, double-click detection and a clickable `key: label` button
+//! row, as used by the workspace selector and directory picker.
+
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use std::time::{Duration, Instant};
+
+/// Two clicks on the same target within this window count as a double-click
+pub const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Check whether (x, y) is inside `area`
+pub fn hit(area: &Rect, x: u16, y: u16) -> bool {
+    x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height
+}
+
+/// Last `width` characters of `s`, prefixed with "..." when truncated
+pub fn tail(s: &str, width: usize) -> String {
+    let count = s.chars().count();
+    if count <= width {
+        return s.to_string();
+    }
+    let keep = width.saturating_sub(3);
+    format!("...{}", s.chars().skip(count - keep).collect::<String>())
+}
+
+/// Detects double-clicks on the same target
+#[derive(Debug)]
+pub struct ClickTracker<K> {
+    last: Option<(Instant, K)>,
+}
+
+impl<K: Copy + PartialEq> ClickTracker<K> {
+    pub fn new() -> Self {
+        Self { last: None }
+    }
+
+    /// Record a click on `target` and report whether it completes a double-click
+    pub fn click(&mut self, target: K, now: Instant) -> bool {
+        let double = self
+            .last
+            .is_some_and(|(at, last)| last == target && now.duration_since(at) <= DOUBLE_CLICK);
+        self.last = if double { None } else { Some((now, target)) };
+        double
+    }
+
+    /// Forget the last click
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+impl<K: Copy + PartialEq> Default for ClickTracker<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A clickable `key: label` hint
+pub struct Button<B> {
+    pub id: B,
+    pub key: &'static str,
+    pub label: &'static str,
+    pub style: Style,
+}
+
+impl<B> Button<B> {
+    pub fn new(id: B, key: &'static str, label: &'static str, style: Style) -> Self {
+        Self {
+            id,
+            key,
+            label,
+            style,
+        }
+    }
+}
+
+/// Render buttons centered in `area`, recording their hit areas in `areas`
+pub fn render_buttons<B: Copy>(
+    frame: &mut Frame,
+    area: Rect,
+    buttons: &[Button<B>],
+    areas: &mut Vec<(B, Rect)>,
+) {
+    let widths: Vec<u16> = buttons
+        .iter()
+        .map(|b| (b.key.len() + 2 + b.label.len()) as u16)
+        .collect();
+    let total = widths.iter().sum::<u16>() + 2 * buttons.len().saturating_sub(1) as u16;
+    let mut x = area.x + area.width.saturating_sub(total) / 2;
+
+    let mut spans = Vec::new();
+    for (i, (button, width)) in buttons.iter().zip(&widths).enumerate() {
+        if i > 0 {
+            spans.push(Span::raw("  "));
+            x += 2;
+        }
+        spans.push(Span::styled(button.key, button.style));
+        spans.push(Span::raw(format!(": {}", button.label)));
+        areas.push((button.id, Rect::new(x, area.y, *width, 1)));
+        x += width;
+    }
+    frame.render_widget(
+        Paragraph::new(Line::from(spans)).alignment(Alignment::Center),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_click_tracker() {
+        let mut clicks = ClickTracker::new();
+        let now = Instant::now();
+        assert!(!clicks.click(1, now));
+        assert!(!clicks.click(2, now));
+        assert!(clicks.click(2, now + Duration::from_millis(100)));
+        // A double-click consumes the pair
+        assert!(!clicks.click(2, now + Duration::from_millis(200)));
+        assert!(!clicks.click(2, now + Duration::from_secs(1)));
+    }
+}
@@ -0,0 +1,911 @@
+//! Reusable directory picker
+//!
+//! Browses directories with the keyboard or mouse, accepts a typed path with
+//! tab-completion, and keeps bookmarks, the hidden-directory toggle and sort
+//! order in [`DirectoryPickerConfig`] so they persist across sessions. Used
+//! by the workspace selector's folder browser and `/init`; in save-as mode it
+//! also asks for a file name.
+
+use super::controls::{hit, render_buttons, tail, Button, ClickTracker};
+use super::theme::theme;
+use crate::config::{DirSort, DirectoryPickerConfig};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Kind of row in the picker
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryKind {
+    /// A bookmarked directory
+    Bookmark,
+    /// The parent directory
+    Parent,
+    /// A subdirectory of the current directory
+    Directory,
+}
+
+/// Entry in the directory list
+#[derive(Debug, Clone)]
+pub struct FolderEntry {
+    /// Display name
+    pub name: String,
+    /// Full path
+    pub path: PathBuf,
+    pub kind: EntryKind,
+}
+
+/// Result of a picker interaction
+#[derive(Debug, Clone, PartialEq)]
+pub enum PickerAction {
+    /// Keep interacting
+    None,
+    /// A directory (or, in save-as mode, a file path) was chosen
+    Selected(PathBuf),
+    /// The picker was dismissed
+    Cancelled,
+}
+
+/// Which part of the picker receives typed keys
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PickerFocus {
+    List,
+    PathInput,
+    FileName,
+}
+
+/// Clickable controls
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PickerButton {
+    Select,
+    Parent,
+    Cancel,
+    TypePath,
+    Hidden,
+    Sort,
+    Bookmark,
+}
+
+/// Directory picker state
+pub struct DirectoryPicker {
+    /// Directory being browsed
+    path: PathBuf,
+    /// Bookmarks, parent and subdirectories; the row after the last entry is
+    /// "Select This Folder"
+    entries: Vec<FolderEntry>,
+    selected: usize,
+    scroll: usize,
+
+    show_hidden: bool,
+    sort: DirSort,
+    bookmarks: Vec<PathBuf>,
+    /// Preferences changed since the last `take_prefs_change`
+    prefs_changed: bool,
+
+    pub focus: PickerFocus,
+    /// Typed path (when focus is `PathInput`)
+    path_input: String,
+    /// File name (save-as mode only)
+    file_name: Option<String>,
+    /// Feedback shown under the path (errors, completion candidates)
+    message: Option<String>,
+
+    /// Hit testing
+    area: Option<Rect>,
+    row_areas: Vec<(usize, Rect)>,
+    button_areas: Vec<(PickerButton, Rect)>,
+    path_area: Option<Rect>,
+    file_area: Option<Rect>,
+    hover: Option<usize>,
+    clicks: ClickTracker<usize>,
+}
+
+impl DirectoryPicker {
+    /// Create a picker starting at `start`
+    pub fn new(start: PathBuf, prefs: &DirectoryPickerConfig) -> Self {
+        let path = if start.is_dir() {
+            start
+        } else {
+            dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"))
+        };
+        let mut picker = Self {
+            path,
+            entries: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            show_hidden: prefs.show_hidden,
+            sort: prefs.sort,
+            bookmarks: prefs.bookmarks.clone(),
+            prefs_changed: false,
+            focus: PickerFocus::List,
+            path_input: String::new(),
+            file_name: None,
+            message: None,
+            area: None,
+            row_areas: Vec::new(),
+            button_areas: Vec::new(),
+            path_area: None,
+            file_area: None,
+            hover: None,
+            clicks: ClickTracker::new(),
+        };
+        picker.refresh();
+        picker
+    }
+
+    /// Create a save-as picker that also asks for a file name
+    pub fn save_as(start: PathBuf, file_name: &str, prefs: &DirectoryPickerConfig) -> Self {
+        let mut picker = Self::new(start, prefs);
+        picker.file_name = Some(file_name.to_string());
+        picker
+    }
+
+    /// Directory being browsed
+    pub fn current_dir(&self) -> &Path {
+        &self.path
+    }
+
+    /// Current preferences
+    pub fn prefs(&self) -> DirectoryPickerConfig {
+        DirectoryPickerConfig {
+            bookmarks: self.bookmarks.clone(),
+            show_hidden: self.show_hidden,
+            sort: self.sort,
+        }
+    }
+
+    /// Preferences, if they changed since the last call (for persisting)
+    pub fn take_prefs_change(&mut self) -> Option<DirectoryPickerConfig> {
+        std::mem::take(&mut self.prefs_changed).then(|| self.prefs())
+    }
+
+    // ========== Listing ==========
+
+    /// Reload entries for the current directory, keeping the selected entry
+    fn refresh(&mut self) {
+        let previous = self
+            .entries
+            .get(self.selected)
+            .map(|e| (e.kind, e.path.clone()));
+        self.entries.clear();
+
+        for bookmark in &self.bookmarks {
+            self.entries.push(FolderEntry {
+                name: bookmark
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| bookmark.to_string_lossy().to_string()),
+                path: bookmark.clone(),
+                kind: EntryKind::Bookmark,
+            });
+        }
+
+        if let Some(parent) = self.path.parent() {
+            self.entries.push(FolderEntry {
+                name: "..".to_string(),
+                path: parent.to_path_buf(),
+                kind: EntryKind::Parent,
+            });
+        }
+
+        for (name, path) in list_dirs(&self.path, self.show_hidden, self.sort) {
+            self.entries.push(FolderEntry {
+                name,
+                path,
+                kind: EntryKind::Directory,
+            });
+        }
+
+        self.selected = previous
+            .and_then(|(kind, path)| {
+                self.entries
+                    .iter()
+                    .position(|e| e.kind == kind && e.path == path)
+            })
+            .unwrap_or(self.selected.min(self.entries.len()));
+    }
+
+    /// Index of the "Select This Folder" row
+    fn select_row(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Browse into `path`
+    fn navigate(&mut self, path: PathBuf) {
+        if !path.is_dir() {
+            self.message = Some(format!("Not a directory: {}", path.display()));
+            return;
+        }
+        self.path = path;
+        self.message = None;
+        self.scroll = 0;
+        self.hover = None;
+        self.refresh();
+        // Start on the first real directory, past bookmarks and ".."
+        self.selected = self
+            .entries
+            .iter()
+            .position(|e| e.kind == EntryKind::Directory)
+            .unwrap_or(self.select_row());
+    }
+
+    pub fn up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        if self.selected < self.select_row() {
+            self.selected += 1;
+        }
+    }
+
+    /// Go to the parent directory
+    pub fn parent(&mut self) {
+        if let Some(parent) = self.path.parent().map(Path::to_path_buf) {
+            self.navigate(parent);
+        }
+    }
+
+    /// Activate the selected row
+    pub fn enter(&mut self) -> PickerAction {
+        match self.entries.get(self.selected) {
+            Some(entry) => {
+                let path = entry.path.clone();
+                self.navigate(path);
+                PickerAction::None
+            }
+            None => self.choose(),
+        }
+    }
+
+    /// Choose the current directory (joined with the file name in save-as mode)
+    fn choose(&mut self) -> PickerAction {
+        match &self.file_name {
+            None => PickerAction::Selected(self.path.clone()),
+            Some(name) if name.is_empty() || name.contains('/') => {
+                self.message = Some("Enter a file name".to_string());
+                self.focus = PickerFocus::FileName;
+                PickerAction::None
+            }
+            Some(name) => PickerAction::Selected(self.path.join(name)),
+        }
+    }
+
+    /// Show or hide dot-directories
+    pub fn toggle_hidden(&mut self) {
+        self.show_hidden = !self.show_hidden;
+        self.prefs_changed = true;
+        self.refresh();
+    }
+
+    /// Cycle the sort order
+    pub fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.prefs_changed = true;
+        self.refresh();
+    }
+
+    /// Bookmark (or un-bookmark) the selected directory, or the current one
+    pub fn toggle_bookmark(&mut self) {
+        let target = match self.entries.get(self.selected) {
+            Some(entry) if entry.kind != EntryKind::Parent => entry.path.clone(),
+            _ => self.path.clone(),
+        };
+        if let Some(pos) = self.bookmarks.iter().position(|b| *b == target) {
+            self.bookmarks.remove(pos);
+            self.message = Some(format!("Removed bookmark {}", display_path(&target)));
+        } else {
+            self.message = Some(format!("Bookmarked {}", display_path(&target)));
+            self.bookmarks.push(target);
+        }
+        self.prefs_changed = true;
+        self.refresh();
+    }
+
+    // ========== Path Input ==========
+
+    /// Start typing a path, prefilled with the current directory
+    pub fn start_path_input(&mut self) {
+        let mut input = display_path(&self.path);
+        if !input.ends_with('/') {
+            input.push('/');
+        }
+        self.path_input = input;
+        self.focus = PickerFocus::PathInput;
+        self.message = None;
+    }
+
+    /// Resolve typed text to a path (`~` expands to home, relative paths
+    /// are relative to the current directory)
+    fn resolve(&self, input: &str) -> PathBuf {
+        let expanded = match input.strip_prefix('~') {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => dirs::home_dir()
+                .map(|home| home.join(rest.trim_start_matches('/')))
+                .unwrap_or_else(|| PathBuf::from(input)),
+            _ => PathBuf::from(input),
+        };
+        if expanded.is_absolute() {
+            expanded
+        } else {
+            self.path.join(expanded)
+        }
+    }
+
+    /// Complete the last component of the typed path
+    ///
+    /// A unique match is completed with a trailing `/`; several matches are
+    /// completed to their common prefix and listed in the message line.
+    pub fn complete_path(&mut self) {
+        let split = self.path_input.rfind('/').map(|i| i + 1).unwrap_or(0);
+        let (dir_part, prefix) = self.path_input.split_at(split);
+        let dir = if dir_part.is_empty() {
+            self.path.clone()
+        } else {
+            self.resolve(dir_part)
+        };
+
+        let show_hidden = self.show_hidden || prefix.starts_with('.');
+        let matches: Vec<String> = list_dirs(&dir, show_hidden, DirSort::Name)
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| name.starts_with(prefix))
+            .collect();
+
+        let completed = match matches.as_slice() {
+            [] => {
+                self.message = Some("No matches".to_string());
+                return;
+            }
+            [only] => {
+                self.message = None;
+                format!("{}/", only)
+            }
+            many => {
+                self.message = Some(many.join("  "));
+                common_prefix(many)
+            }
+        };
+        self.path_input = format!("{}{}", dir_part, completed);
+    }
+
+    /// Browse to the typed path
+    fn submit_path(&mut self) {
+        let path = self.resolve(self.path_input.trim());
+        self.navigate(path);
+        if self.message.is_none() {
+            self.focus = PickerFocus::List;
+        }
+    }
+
+    // ========== Input Handling ==========
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, key: KeyEvent) -> PickerAction {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match self.focus {
+            PickerFocus::PathInput => match key.code {
+                KeyCode::Esc => self.focus = PickerFocus::List,
+                KeyCode::Enter => self.submit_path(),
+                KeyCode::Tab => self.complete_path(),
+                KeyCode::Backspace => {
+                    self.path_input.pop();
+                }
+                KeyCode::Char(c) if !ctrl => self.path_input.push(c),
+                _ => {}
+            },
+            PickerFocus::FileName => {
+                let name = self.file_name.get_or_insert_with(String::new);
+                match key.code {
+                    KeyCode::Esc | KeyCode::Tab => self.focus = PickerFocus::List,
+                    KeyCode::Enter => return self.choose(),
+                    KeyCode::Backspace => {
+                        name.pop();
+                    }
+                    KeyCode::Char(c) if !ctrl => name.push(c),
+                    _ => {}
+                }
+            }
+            PickerFocus::List => match key.code {
+                KeyCode::Esc => return PickerAction::Cancelled,
+                KeyCode::Up => self.up(),
+                KeyCode::Down => self.down(),
+                KeyCode::Home => self.selected = 0,
+                KeyCode::End => self.selected = self.select_row(),
+                KeyCode::Enter => return self.enter(),
+                KeyCode::Right if self.selected < self.select_row() => return self.enter(),
+                KeyCode::Backspace | KeyCode::Left => self.parent(),
+                KeyCode::Tab if self.file_name.is_some() => self.focus = PickerFocus::FileName,
+                KeyCode::Char('/') | KeyCode::Tab => self.start_path_input(),
+                KeyCode::Char('l') if ctrl => self.start_path_input(),
+                KeyCode::Char('~') => {
+                    if let Some(home) = dirs::home_dir() {
+                        self.navigate(home);
+                    }
+                }
+                KeyCode::Char('.') => self.toggle_hidden(),
+                KeyCode::Char('s') => self.cycle_sort(),
+                KeyCode::Char('b') if !ctrl => self.toggle_bookmark(),
+                _ => {}
+            },
+        }
+        PickerAction::None
+    }
+
+    /// Check if point is inside the picker
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        self.area.is_some_and(|area| hit(&area, x, y))
+    }
+
+    /// Handle mouse click: select a row, double-click to open it
+    pub fn handle_click(&mut self, x: u16, y: u16) -> PickerAction {
+        self.click_at(x, y, Instant::now())
+    }
+
+    fn click_at(&mut self, x: u16, y: u16, now: Instant) -> PickerAction {
+        if let Some(button) = self
+            .button_areas
+            .iter()
+            .find(|(_, area)| hit(area, x, y))
+            .map(|(button, _)| *button)
+        {
+            self.clicks.reset();
+            return self.press(button);
+        }
+        if self.path_area.is_some_and(|area| hit(&area, x, y)) {
+            if self.focus != PickerFocus::PathInput {
+                self.start_path_input();
+            }
+            return PickerAction::None;
+        }
+        if self.file_area.is_some_and(|area| hit(&area, x, y)) {
+            self.focus = PickerFocus::FileName;
+            return PickerAction::None;
+        }
+
+        let Some(row) = self
+            .row_areas
+            .iter()
+            .find(|(_, area)| hit(area, x, y))
+            .map(|(row, _)| *row)
+        else {
+            return PickerAction::None;
+        };
+        self.focus = PickerFocus::List;
+        self.selected = row;
+        if self.clicks.click(row, now) {
+            return self.enter();
+        }
+        PickerAction::None
+    }
+
+    fn press(&mut self, button: PickerButton) -> PickerAction {
+        match button {
+            PickerButton::Select => return self.choose(),
+            PickerButton::Parent => self.parent(),
+            PickerButton::Cancel => return PickerAction::Cancelled,
+            PickerButton::TypePath => self.start_path_input(),
+            PickerButton::Hidden => self.toggle_hidden(),
+            PickerButton::Sort => self.cycle_sort(),
+            PickerButton::Bookmark => self.toggle_bookmark(),
+        }
+        PickerAction::None
+    }
+
+    /// Handle mouse wheel
+    pub fn handle_scroll(&mut self, down: bool) {
+        if down {
+            self.down();
+        } else {
+            self.up();
+        }
+    }
+
+    /// Track the row under the mouse pointer
+    pub fn handle_hover(&mut self, x: u16, y: u16) {
+        self.hover = self
+            .row_areas
+            .iter()
+            .find(|(_, area)| hit(area, x, y))
+            .map(|(row, _)| *row);
+    }
+
+    // ========== Rendering ==========
+
+    /// Render as a standalone centered modal
+    pub fn render_modal(&mut self, frame: &mut Frame, area: Rect, title: &str) {
+        let width = (area.width as f32 * 0.7).clamp(50.0, 80.0) as u16;
+        let height = (area.height as f32 * 0.6).clamp(15.0, 25.0) as u16;
+        let modal = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width.min(area.width),
+            height.min(area.height),
+        );
+
+        let t = theme();
+        let block = Block::default()
+            .title(format!(" {} ", title))
+            .title_style(
+                Style::default()
+                    .fg(t.accent_highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_focused))
+            .style(Style::default().bg(t.bg_modal));
+        drop(t);
+
+        frame.render_widget(Clear, modal);
+        let inner = block.inner(modal);
+        frame.render_widget(block, modal);
+        self.render(frame, inner);
+        self.area = Some(modal);
+    }
+
+    /// Render into `area` (no border)
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        self.area = Some(area);
+        self.row_areas.clear();
+        self.button_areas.clear();
+        self.file_area = None;
+
+        let t = theme();
+        let field_width = area.width.saturating_sub(8) as usize;
+
+        // Path field
+        let (path_text, path_style) = if self.focus == PickerFocus::PathInput {
+            (
+                format!("{}█", tail(&self.path_input, field_width.saturating_sub(1))),
+                Style::default().fg(t.accent_highlight).bg(t.bg_selection),
+            )
+        } else {
+            (
+                tail(&display_path(&self.path), field_width),
+                Style::default().fg(t.accent_primary),
+            )
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("Path: ", Style::default().fg(t.text_primary)),
+                Span::styled(path_text, path_style),
+            ])),
+            Rect::new(area.x, area.y, area.width, 1),
+        );
+        self.path_area = Some(Rect::new(area.x, area.y, area.width, 1));
+
+        // Status or message
+        let status = self.message.clone().unwrap_or_else(|| {
+            format!(
+                "sort: {}  hidden: {}  bookmarks: {}",
+                self.sort.label(),
+                if self.show_hidden { "shown" } else { "off" },
+                self.bookmarks.len()
+            )
+        });
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                tail(&status, area.width as usize),
+                Style::default().fg(t.text_muted),
+            )),
+            Rect::new(area.x, area.y + 1, area.width, 1),
+        );
+
+        // Directory list, keeping the selection visible
+        let footer = if self.file_name.is_some() { 3 } else { 2 };
+        let list_y = area.y + 2;
+        let list_height = area.height.saturating_sub(2 + footer) as usize;
+        let visible = list_height.max(1);
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + visible {
+            self.scroll = self.selected + 1 - visible;
+        }
+
+        for (offset, row) in (self.scroll..=self.select_row())
+            .take(list_height)
+            .enumerate()
+        {
+            let row_area = Rect::new(area.x, list_y + offset as u16, area.width, 1);
+            self.row_areas.push((row, row_area));
+
+            let is_selected = row == self.selected;
+            let bg = if is_selected {
+                t.bg_selection
+            } else if self.hover == Some(row) {
+                t.bg_hover
+            } else {
+                t.bg_modal
+            };
+            let name_style = if is_selected {
+                Style::default()
+                    .fg(t.text_primary)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(t.text_secondary)
+            };
+
+            let line = match self.entries.get(row) {
+                Some(entry) => match entry.kind {
+                    EntryKind::Bookmark => Line::from(vec![
+                        Span::styled(" ★ ", Style::default().fg(t.accent_highlight)),
+                        Span::styled(format!("{}/", entry.name), name_style),
+                        Span::styled(
+                            format!("  {}", display_path(&entry.path)),
+                            Style::default().fg(t.text_muted),
+                        ),
+                    ]),
+                    EntryKind::Parent => Line::from(vec![
+                        Span::raw(" .. "),
+                        Span::styled("(parent directory)", name_style),
+                    ]),
+                    EntryKind::Directory => Line::from(vec![
+                        Span::raw(" 📁 "),
+                        Span::styled(format!("{}/", entry.name), name_style),
+                    ]),
+                },
+                None => {
+                    let label = if self.file_name.is_some() {
+                        "  [ Save Here ]"
+                    } else {
+                        "  [ Select This Folder ]"
+                    };
+                    Line::from(Span::styled(
+                        label,
+                        Style::default()
+                            .fg(t.status_success)
+                            .add_modifier(if is_selected {
+                                Modifier::BOLD
+                            } else {
+                                Modifier::empty()
+                            }),
+                    ))
+                }
+            };
+            frame.render_widget(
+                Paragraph::new(line).style(Style::default().bg(bg)),
+                row_area,
+            );
+        }
+
+        // File name field (save-as)
+        let bottom = area.y + area.height;
+        if let Some(name) = &self.file_name {
+            let focused = self.focus == PickerFocus::FileName;
+            let text = if focused {
+                format!("{}█", tail(name, field_width.saturating_sub(1)))
+            } else {
+                tail(name, field_width)
+            };
+            let style = if focused {
+                Style::default().fg(t.accent_highlight).bg(t.bg_selection)
+            } else {
+                Style::default().fg(t.text_secondary)
+            };
+            let file_area = Rect::new(area.x, bottom.saturating_sub(3), area.width, 1);
+            frame.render_widget(
+                Paragraph::new(Line::from(vec![
+                    Span::styled("File: ", Style::default().fg(t.text_primary)),
+                    Span::styled(text, style),
+                ])),
+                file_area,
+            );
+            self.file_area = Some(file_area);
+        }
+
+        // Help (clickable)
+        let key = Style::default().fg(t.text_secondary);
+        let actions = [
+            Button::new(
+                PickerButton::Select,
+                "Enter",
+                "open/select",
+                Style::default().fg(t.accent_primary),
+            ),
+            Button::new(PickerButton::Parent, "Backspace", "parent", key),
+            Button::new(PickerButton::Cancel, "Esc", "cancel", key),
+        ];
+        let options = [
+            Button::new(PickerButton::TypePath, "/", "path", key),
+            Button::new(PickerButton::Hidden, ".", "hidden", key),
+            Button::new(PickerButton::Sort, "s", "sort", key),
+            Button::new(
+                PickerButton::Bookmark,
+                "b",
+                "bookmark",
+                Style::default().fg(t.accent_highlight),
+            ),
+        ];
+        drop(t);
+        if area.height >= 4 {
+            let rows = [
+                Rect::new(area.x, bottom - 2, area.width, 1),
+                Rect::new(area.x, bottom - 1, area.width, 1),
+            ];
+            render_buttons(frame, rows[0], &actions, &mut self.button_areas);
+            render_buttons(frame, rows[1], &options, &mut self.button_areas);
+        } else {
+            frame.render_widget(
+                Paragraph::new("Enter: select  Esc: cancel").alignment(Alignment::Center),
+                Rect::new(area.x, bottom.saturating_sub(1), area.width, 1),
+            );
+        }
+    }
+}
+
+/// Subdirectories of `dir` as (name, path), sorted
+fn list_dirs(dir: &Path, show_hidden: bool, sort: DirSort) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<_> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+        .filter(|(name, _)| show_hidden || !name.starts_with('.'))
+        .collect();
+
+    match sort {
+        DirSort::Name => dirs.sort_by(|a, b| a.0.cmp(&b.0)),
+        DirSort::NameDesc => dirs.sort_by(|a, b| b.0.cmp(&a.0)),
+        DirSort::Modified => dirs.sort_by_cached_key(|(_, path)| {
+            std::cmp::Reverse(path.metadata().and_then(|m| m.modified()).ok())
+        }),
+    }
+    dirs
+}
+
+/// Longest common prefix of non-empty `names`
+fn common_prefix(names: &[String]) -> String {
+    let first = &names[0];
+    let mut len = first.len();
+    for name in &names[1..] {
+        len = first
+            .char_indices()
+            .zip(name.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map(|((i, c), _)| i + c.len_utf8())
+            .unwrap_or(0)
+            .min(len);
+    }
+    first[..len].to_string()
+}
+
+/// Path with the home directory shown as `~`
+fn display_path(path: &Path) -> String {
+    if let Some(home) = dirs::home_dir() {
+        if let Ok(rest) = path.strip_prefix(&home) {
+            return if rest.as_os_str().is_empty() {
+                "~".to_string()
+            } else {
+                format!("~/{}", rest.display())
+            };
+        }
+    }
+    path.display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_tree(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("axiom_picker_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["alpha", "alpine", "beta", ".hidden"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        root
+    }
+
+    fn names(picker: &DirectoryPicker) -> Vec<&str> {
+        picker
+            .entries
+            .iter()
+            .filter(|e| e.kind == EntryKind::Directory)
+            .map(|e| e.name.as_str())
+            .collect()
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_hidden_and_sort() {
+        let root = temp_tree("sort");
+        let mut picker = DirectoryPicker::new(root.clone(), &DirectoryPickerConfig::default());
+        assert_eq!(names(&picker), ["alpha", "alpine", "beta"]);
+
+        picker.handle_key(key(KeyCode::Char('.')));
+        assert_eq!(names(&picker), [".hidden", "alpha", "alpine", "beta"]);
+
+        picker.handle_key(key(KeyCode::Char('s')));
+        assert_eq!(names(&picker), ["beta", "alpine", "alpha", ".hidden"]);
+
+        let prefs = picker.take_prefs_change().unwrap();
+        assert!(prefs.show_hidden);
+        assert_eq!(prefs.sort, DirSort::NameDesc);
+        assert!(picker.take_prefs_change().is_none());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_path_completion() {
+        let root = temp_tree("complete");
+        let mut picker = DirectoryPicker::new(root.clone(), &DirectoryPickerConfig::default());
+        picker.start_path_input();
+        picker.path_input = format!("{}/al", root.display());
+
+        picker.complete_path();
+        assert_eq!(picker.path_input, format!("{}/alp", root.display()));
+        assert_eq!(picker.message.as_deref(), Some("alpha  alpine"));
+
+        picker.handle_key(key(KeyCode::Char('i')));
+        picker.handle_key(key(KeyCode::Tab));
+        assert_eq!(picker.path_input, format!("{}/alpine/", root.display()));
+
+        picker.handle_key(key(KeyCode::Enter));
+        assert_eq!(picker.focus, PickerFocus::List);
+        assert_eq!(picker.current_dir(), root.join("alpine"));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_bookmarks_and_selection() {
+        let root = temp_tree("bookmark");
+        let mut picker = DirectoryPicker::new(root.clone(), &DirectoryPickerConfig::default());
+
+        // First directory is selected after navigating; bookmark it
+        picker.navigate(root.clone());
+        picker.handle_key(key(KeyCode::Char('b')));
+        assert_eq!(picker.prefs().bookmarks, vec![root.join("alpha")]);
+        assert_eq!(picker.entries[0].kind, EntryKind::Bookmark);
+
+        // Bookmarks are rows too: Enter jumps to it
+        picker.selected = 0;
+        assert_eq!(picker.handle_key(key(KeyCode::Enter)), PickerAction::None);
+        assert_eq!(picker.current_dir(), root.join("alpha"));
+
+        picker.handle_key(key(KeyCode::End));
+        assert_eq!(
+            picker.handle_key(key(KeyCode::Enter)),
+            PickerAction::Selected(root.join("alpha"))
+        );
+        assert_eq!(
+            picker.handle_key(key(KeyCode::Esc)),
+            PickerAction::Cancelled
+        );
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_save_as_needs_file_name() {
+        let root = temp_tree("save");
+        let mut picker =
+            DirectoryPicker::save_as(root.clone(), "", &DirectoryPickerConfig::default());
+        picker.handle_key(key(KeyCode::End));
+        assert_eq!(picker.handle_key(key(KeyCode::Enter)), PickerAction::None);
+        assert_eq!(picker.focus, PickerFocus::FileName);
+
+        for c in "notes.md".chars() {
+            picker.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(
+            picker.handle_key(key(KeyCode::Enter)),
+            PickerAction::Selected(root.join("notes.md"))
+        );
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
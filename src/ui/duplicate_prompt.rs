@@ -0,0 +1,95 @@
+//! Prompt shown when creating a workspace for a directory that already has one
+
+use crate::state::WorkspaceId;
+use crate::ui::theme::theme;
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::path::PathBuf;
+
+/// Duplicate workspace prompt state
+pub struct DuplicatePrompt {
+    /// The workspace already registered for the directory
+    pub workspace_id: WorkspaceId,
+
+    /// Its name
+    workspace_name: String,
+
+    /// Its (normalized) root
+    path: PathBuf,
+}
+
+impl DuplicatePrompt {
+    pub fn new(
+        workspace_id: WorkspaceId,
+        workspace_name: impl Into<String>,
+        path: PathBuf,
+    ) -> Self {
+        Self {
+            workspace_id,
+            workspace_name: workspace_name.into(),
+            path,
+        }
+    }
+
+    /// Render the prompt centered in `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let modal_width = 64.min(area.width);
+        let modal_height = 9.min(area.height);
+        let modal_area = Rect::new(
+            area.width.saturating_sub(modal_width) / 2,
+            area.height.saturating_sub(modal_height) / 2,
+            modal_width,
+            modal_height,
+        );
+
+        frame.render_widget(Clear, modal_area);
+
+        let t = theme();
+        let block = Block::default()
+            .title(" Workspace already exists ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.status_warning))
+            .style(Style::default().bg(t.bg_modal));
+
+        let key = |k: &'static str| {
+            Span::styled(
+                k,
+                Style::default()
+                    .fg(t.accent_primary)
+                    .add_modifier(Modifier::BOLD),
+            )
+        };
+        let text = vec![
+            Line::from(Span::styled(
+                self.path.display().to_string(),
+                Style::default().fg(t.text_secondary),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!(
+                    "This directory is already the workspace '{}'.",
+                    self.workspace_name
+                ),
+                Style::default().fg(t.text_primary),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                key("Enter"),
+                Span::raw(" open it   "),
+                key("Esc"),
+                Span::raw(" cancel"),
+            ]),
+        ];
+
+        let paragraph = Paragraph::new(text)
+            .block(block)
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Left);
+        frame.render_widget(paragraph, modal_area);
+    }
+}
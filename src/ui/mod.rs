@@ -1,19 +1,46 @@
 //! UI rendering module
 
+pub mod activity_feed;
+pub mod bell;
+pub mod capabilities;
+pub mod controls;
+pub mod directory_picker;
+pub mod duplicate_prompt;
 mod layout;
 pub mod markdown;
 pub mod model_selector;
+pub mod plan_view;
+pub mod profiler;
+pub mod redraw;
 mod render;
+pub mod replace_panel;
+pub mod review_queue;
 pub mod scroll;
+pub mod search_panel;
 pub mod settings;
 pub mod theme;
+pub mod title;
+pub mod trust_prompt;
 pub mod workspace_selector;
 
+pub use activity_feed::ActivityFeed;
+pub use bell::{Bell, SoundEvent};
+pub use capabilities::{capabilities, Capabilities};
+pub use directory_picker::{DirectoryPicker, PickerAction};
+pub use duplicate_prompt::DuplicatePrompt;
 pub use layout::{get_layout, get_layout_with_focus, AppLayout};
 pub use markdown::render_markdown;
 pub use model_selector::ModelSelector;
-pub use render::render;
+pub use plan_view::PlanView;
+pub use profiler::FrameProfiler;
+pub use redraw::{Damage, RedrawScheduler, WorkTimer};
+pub use render::{event_label, render};
+pub use replace_panel::ReplacePanel;
+pub use review_queue::{ReviewAction, ReviewQueue};
 pub use scroll::ScrollBar;
+pub use search_panel::SearchPanel;
 pub use settings::SettingsModal;
+pub use title::{AppStatus, StatusReporter};
+pub use trust_prompt::TrustPrompt;
 pub use theme::{theme, set_theme, toggle_theme, cycle_theme, current_variant, Theme, ThemeVariant};
 pub use workspace_selector::{WorkspaceSelectorModal, WorkspaceSelectorAction, SelectorMode};
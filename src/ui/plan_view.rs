@@ -0,0 +1,159 @@
+//! Plan modal showing the Conductor's multi-step plan and its progress
+//!
+//! The plan itself lives in `AppState` and is updated as its steps run, so
+//! the modal only keeps what it needs for hit testing.
+
+use crate::ui::theme::theme;
+use axiom_core::{Plan, PlanStepStatus};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Plan modal state
+#[derive(Default)]
+pub struct PlanView {
+    /// Cached modal area for hit testing
+    modal_area: Option<Rect>,
+}
+
+impl PlanView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if a point is inside the modal
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        self.modal_area
+            .map(|r| x >= r.x && x < r.x + r.width && y >= r.y && y < r.y + r.height)
+            .unwrap_or(false)
+    }
+
+    /// Render the plan modal
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, plan: &Plan) {
+        let modal_width = (area.width as f32 * 0.6).max(50.0) as u16;
+        let modal_height = (plan.steps.len() as u16 + 8).max(10);
+        let modal_area = Rect::new(
+            area.width.saturating_sub(modal_width) / 2,
+            area.height.saturating_sub(modal_height) / 2,
+            modal_width.min(area.width),
+            modal_height.min(area.height),
+        );
+        self.modal_area = Some(modal_area);
+
+        frame.render_widget(Clear, modal_area);
+
+        let t = theme();
+        let (done, total) = plan.progress();
+        let block = Block::default()
+            .title(format!(" Plan · {}/{} steps (Esc) ", done, total))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_focused))
+            .style(Style::default().bg(t.bg_modal));
+        let inner = block.inner(modal_area);
+        frame.render_widget(block, modal_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let goal = Paragraph::new(plan.goal.as_str())
+            .style(
+                Style::default()
+                    .fg(t.text_primary)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .wrap(Wrap { trim: true });
+        frame.render_widget(goal, chunks[0]);
+
+        let steps: Vec<Line> = plan
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let (icon, color) = match step.status {
+                    PlanStepStatus::Pending => ("○", t.text_muted),
+                    PlanStepStatus::Running => ("◐", t.accent_primary),
+                    PlanStepStatus::Done => ("✓", t.status_success),
+                    PlanStepStatus::Failed => ("✗", t.status_error),
+                    PlanStepStatus::Skipped => ("–", t.text_muted),
+                };
+                let mut spans = vec![
+                    Span::styled(format!(" {} ", icon), Style::default().fg(color)),
+                    Span::styled(
+                        format!("{}. {}", i + 1, step.title),
+                        Style::default().fg(if step.status == PlanStepStatus::Skipped {
+                            t.text_muted
+                        } else {
+                            t.text_primary
+                        }),
+                    ),
+                ];
+                if !step.agents.is_empty() {
+                    spans.push(Span::styled(
+                        format!("  ({} agents)", step.agents.len()),
+                        Style::default().fg(t.text_secondary),
+                    ));
+                }
+                Line::from(spans)
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(steps), chunks[1]);
+
+        let footer = Paragraph::new(summary(plan))
+            .style(Style::default().fg(t.text_muted))
+            .alignment(Alignment::Center);
+        frame.render_widget(footer, chunks[2]);
+    }
+}
+
+/// Where the plan stands, e.g. "Running step 2 of 4"
+fn summary(plan: &Plan) -> String {
+    let total = plan.steps.len();
+    if let Some(index) = plan.current() {
+        return format!(
+            "Running step {} of {} (/plan cancel to stop)",
+            index + 1,
+            total
+        );
+    }
+    match plan
+        .steps
+        .iter()
+        .position(|step| step.status == PlanStepStatus::Failed)
+    {
+        Some(index) => format!("Stopped at step {} of {}", index + 1, total),
+        None if plan.is_finished() => "All steps done".to_string(),
+        None => "Waiting for the next step".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let mut plan = Plan::new("Ship it", ["Build", "Release"]);
+        assert_eq!(summary(&plan), "Waiting for the next step");
+        plan.start_next();
+        assert_eq!(summary(&plan), "Running step 1 of 2 (/plan cancel to stop)");
+        plan.finish_step(false);
+        plan.start_next();
+        plan.finish_step(true);
+        assert_eq!(summary(&plan), "Stopped at step 2 of 2");
+
+        let mut plan = Plan::new("Ship it", ["Build"]);
+        plan.start_next();
+        plan.finish_step(false);
+        assert_eq!(summary(&plan), "All steps done");
+    }
+}
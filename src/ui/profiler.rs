@@ -0,0 +1,322 @@
+//! Opt-in frame profiler
+//!
+//! Measures per-panel render and event-handling time each frame, shows the
+//! slowest components in a small HUD, and appends a breakdown of slow frames
+//! to the diagnostics log. Enable with `AXIOM_PROFILE=1` or toggle with F12.
+
+use super::theme::theme;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Frames slower than this are logged (about 30 FPS)
+pub const DEFAULT_SLOW_FRAME_MS: u64 = 33;
+
+/// Weight of the newest sample in the moving averages
+const SMOOTHING: f64 = 0.2;
+
+/// Number of components listed in the HUD
+const HUD_ROWS: usize = 5;
+
+/// Path of the diagnostics log (`~/.config/axiom/diagnostics.log`)
+pub fn diagnostics_log_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("axiom").join("diagnostics.log"))
+}
+
+/// A frame that exceeded the slow-frame threshold
+#[derive(Debug, Clone)]
+pub struct SlowFrame {
+    /// Total measured time
+    pub total: Duration,
+    /// Time per component, slowest first
+    pub breakdown: Vec<(&'static str, Duration)>,
+}
+
+impl std::fmt::Display for SlowFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "slow frame {:.1}ms:", ms(self.total))?;
+        for (i, (label, time)) in self.breakdown.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(f, "{}{} {:.1}ms", sep, label, ms(*time))?;
+        }
+        Ok(())
+    }
+}
+
+/// Per-frame timing of panels and event handling
+pub struct FrameProfiler {
+    enabled: bool,
+    threshold: Duration,
+    /// Samples recorded during the current frame
+    samples: Vec<(&'static str, Duration)>,
+    /// Moving average per component, in milliseconds
+    averages: HashMap<&'static str, f64>,
+    /// Total time of the last completed frame
+    last_frame: Duration,
+    /// Number of frames over the threshold since enabled
+    slow_frames: u64,
+    /// Where slow frames are logged
+    log_path: Option<PathBuf>,
+}
+
+impl FrameProfiler {
+    /// Create a profiler (no logging)
+    pub fn new(enabled: bool, threshold: Duration) -> Self {
+        Self {
+            enabled,
+            threshold,
+            samples: Vec::new(),
+            averages: HashMap::new(),
+            last_frame: Duration::ZERO,
+            slow_frames: 0,
+            log_path: None,
+        }
+    }
+
+    /// Create from `AXIOM_PROFILE` / `AXIOM_PROFILE_THRESHOLD_MS`, logging
+    /// slow frames to the diagnostics log
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("AXIOM_PROFILE")
+            .map(|v| !v.is_empty() && v != "0")
+            .unwrap_or(false);
+        let threshold_ms = std::env::var("AXIOM_PROFILE_THRESHOLD_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_SLOW_FRAME_MS);
+
+        let mut profiler = Self::new(enabled, Duration::from_millis(threshold_ms));
+        profiler.log_path = diagnostics_log_path();
+        profiler
+    }
+
+    /// Whether profiling is active
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Toggle profiling, returning the new state
+    pub fn toggle(&mut self) -> bool {
+        self.enabled = !self.enabled;
+        self.samples.clear();
+        self.averages.clear();
+        self.slow_frames = 0;
+        self.enabled
+    }
+
+    /// Run `f`, recording its duration under `label`
+    pub fn time<R>(&mut self, label: &'static str, f: impl FnOnce() -> R) -> R {
+        if !self.enabled {
+            return f();
+        }
+        let start = Instant::now();
+        let result = f();
+        self.record(label, start.elapsed());
+        result
+    }
+
+    /// Record a duration for `label` in the current frame
+    pub fn record(&mut self, label: &'static str, elapsed: Duration) {
+        if !self.enabled {
+            return;
+        }
+        match self.samples.iter_mut().find(|(l, _)| *l == label) {
+            Some((_, total)) => *total += elapsed,
+            None => self.samples.push((label, elapsed)),
+        }
+    }
+
+    /// Record whatever part of `total` wasn't covered by samples so far
+    ///
+    /// Used for the terminal flush after panels have rendered.
+    pub fn record_remainder(&mut self, label: &'static str, total: Duration) {
+        let measured: Duration = self.samples.iter().map(|(_, d)| *d).sum();
+        self.record(label, total.saturating_sub(measured));
+    }
+
+    /// Finish the current frame
+    ///
+    /// Updates the moving averages and returns (and logs) the frame if it was
+    /// slower than the threshold.
+    pub fn end_frame(&mut self) -> Option<SlowFrame> {
+        if !self.enabled {
+            return None;
+        }
+        let samples = std::mem::take(&mut self.samples);
+        let total: Duration = samples.iter().map(|(_, d)| *d).sum();
+        self.last_frame = total;
+
+        for (label, elapsed) in &samples {
+            let sample = ms(*elapsed);
+            self.averages
+                .entry(label)
+                .and_modify(|avg| *avg += SMOOTHING * (sample - *avg))
+                .or_insert(sample);
+        }
+
+        if total <= self.threshold {
+            return None;
+        }
+
+        let mut breakdown = samples;
+        breakdown.sort_by_key(|(_, elapsed)| std::cmp::Reverse(*elapsed));
+        let slow = SlowFrame { total, breakdown };
+        self.slow_frames += 1;
+        self.log(&slow);
+        Some(slow)
+    }
+
+    /// Components with the highest average time, slowest first
+    pub fn slowest(&self, n: usize) -> Vec<(&'static str, f64)> {
+        let mut entries: Vec<_> = self.averages.iter().map(|(l, avg)| (*l, *avg)).collect();
+        entries.sort_by(|a, b| b.1.total_cmp(&a.1));
+        entries.truncate(n);
+        entries
+    }
+
+    /// Append a slow frame to the diagnostics log
+    fn log(&self, slow: &SlowFrame) {
+        let Some(path) = &self.log_path else { return };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+        {
+            let _ = writeln!(file, "[{}] {}", timestamp, slow);
+        }
+    }
+
+    /// Render the HUD in the top-right corner of `area`
+    pub fn render_hud(&self, frame: &mut Frame, area: Rect) {
+        if !self.enabled {
+            return;
+        }
+        let t = theme();
+        let rows = self.slowest(HUD_ROWS);
+        let width = 30.min(area.width);
+        let height = (rows.len() as u16 + 3).min(area.height);
+        let hud = Rect::new(area.x + area.width - width, area.y, width, height);
+
+        let frame_style = if self.last_frame > self.threshold {
+            Style::default().fg(t.status_error)
+        } else {
+            Style::default().fg(t.status_success)
+        };
+        let mut lines = vec![Line::from(vec![
+            Span::styled(format!("frame {:>6.1}ms", ms(self.last_frame)), frame_style),
+            Span::styled(
+                format!("  slow {}", self.slow_frames),
+                Style::default().fg(t.text_muted),
+            ),
+        ])];
+        for (label, avg) in rows {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:<18}", label),
+                    Style::default().fg(t.text_secondary),
+                ),
+                Span::styled(
+                    format!("{:>6.1}ms", avg),
+                    Style::default().fg(t.text_primary),
+                ),
+            ]));
+        }
+
+        let block = Block::default()
+            .title(Span::styled(
+                " Profiler ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_focused));
+
+        frame.render_widget(Clear, hud);
+        frame.render_widget(Paragraph::new(lines).block(block), hud);
+    }
+}
+
+impl Default for FrameProfiler {
+    fn default() -> Self {
+        Self::new(false, Duration::from_millis(DEFAULT_SLOW_FRAME_MS))
+    }
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_records_nothing() {
+        let mut profiler = FrameProfiler::default();
+        assert_eq!(profiler.time("Output", || 42), 42);
+        assert!(profiler.end_frame().is_none());
+        assert!(profiler.slowest(5).is_empty());
+    }
+
+    #[test]
+    fn test_slow_frame_breakdown() {
+        let mut profiler = FrameProfiler::new(true, Duration::from_millis(10));
+        profiler.record("Files", Duration::from_millis(2));
+        profiler.record("Output", Duration::from_millis(12));
+        profiler.record("Files", Duration::from_millis(1));
+
+        let slow = profiler.end_frame().expect("frame should be slow");
+        assert_eq!(slow.total, Duration::from_millis(15));
+        assert_eq!(slow.breakdown[0], ("Output", Duration::from_millis(12)));
+        assert_eq!(slow.breakdown[1], ("Files", Duration::from_millis(3)));
+        assert_eq!(
+            slow.to_string(),
+            "slow frame 15.0ms: Output 12.0ms, Files 3.0ms"
+        );
+
+        profiler.record("Files", Duration::from_millis(1));
+        assert!(profiler.end_frame().is_none());
+    }
+
+    #[test]
+    fn test_slowest_uses_moving_average() {
+        let mut profiler = FrameProfiler::new(true, Duration::from_secs(1));
+        profiler.record("Agents", Duration::from_millis(1));
+        profiler.record("Output", Duration::from_millis(4));
+        profiler.end_frame();
+        profiler.record("Agents", Duration::from_millis(11));
+        profiler.end_frame();
+
+        let slowest = profiler.slowest(1);
+        assert_eq!(slowest.len(), 1);
+        assert_eq!(slowest[0].0, "Output");
+        assert!((profiler.slowest(2)[1].1 - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_record_remainder() {
+        let mut profiler = FrameProfiler::new(true, Duration::from_secs(1));
+        profiler.record("Output", Duration::from_millis(3));
+        profiler.record_remainder("flush", Duration::from_millis(5));
+        profiler.end_frame();
+        let flush = profiler
+            .slowest(2)
+            .into_iter()
+            .find(|(l, _)| *l == "flush")
+            .unwrap();
+        assert!((flush.1 - 2.0).abs() < 1e-9);
+    }
+}
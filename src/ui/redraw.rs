@@ -0,0 +1,246 @@
+//! Damage tracking for the main loop
+//!
+//! The loop only redraws when something changed: user input redraws
+//! immediately, streaming output (PTY, agents, LLM) is coalesced to a capped
+//! frame rate, and a low-frequency heartbeat keeps clocks and elapsed times
+//! fresh while idle. Periodic work (watch rules, key refreshes) runs on its
+//! own timer, so it isn't held up by an idle loop waiting for the heartbeat.
+
+use crate::events::Event;
+use std::time::{Duration, Instant};
+
+/// Frame-rate cap for coalesced output updates
+pub const MAX_OUTPUT_FPS: u32 = 30;
+
+/// Redraw at least this often, even when idle
+pub const HEARTBEAT: Duration = Duration::from_secs(1);
+
+/// How often the main loop's periodic work runs
+pub const WORK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How an event affects the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Damage {
+    /// Nothing visible changed
+    None,
+    /// Redraw on the next loop iteration (user input, resize)
+    Immediate,
+    /// Redraw, but no faster than the output frame-rate cap
+    Coalesced,
+}
+
+impl Damage {
+    /// Classify an event
+    pub fn for_event(event: &Event) -> Self {
+        match event {
+            Event::Tick => Damage::None,
+            Event::PtyOutput(_)
+            | Event::CliAgentOutput { .. }
+            | Event::AgentOutput { .. }
+            | Event::LlmChunk(_)
+            | Event::FileChanged(_) => Damage::Coalesced,
+            _ => Damage::Immediate,
+        }
+    }
+}
+
+/// Decides when the main loop should draw
+#[derive(Debug)]
+pub struct RedrawScheduler {
+    /// Something changed since the last draw
+    dirty: bool,
+    /// The change should be drawn without waiting for the frame cap
+    urgent: bool,
+    last_draw: Option<Instant>,
+    /// Minimum time between coalesced draws
+    min_interval: Duration,
+    heartbeat: Duration,
+}
+
+impl RedrawScheduler {
+    /// Create a scheduler with an output FPS cap and idle heartbeat
+    pub fn new(max_fps: u32, heartbeat: Duration) -> Self {
+        Self {
+            dirty: true,
+            urgent: true,
+            last_draw: None,
+            min_interval: Duration::from_secs(1) / max_fps.max(1),
+            heartbeat,
+        }
+    }
+
+    /// Record damage
+    pub fn mark(&mut self, damage: Damage) {
+        match damage {
+            Damage::None => {}
+            Damage::Immediate => {
+                self.dirty = true;
+                self.urgent = true;
+            }
+            Damage::Coalesced => self.dirty = true,
+        }
+    }
+
+    /// Whether a frame should be drawn at `now`
+    pub fn should_draw(&self, now: Instant) -> bool {
+        let Some(last) = self.last_draw else {
+            return true;
+        };
+        let since = now.saturating_duration_since(last);
+        self.urgent || (self.dirty && since >= self.min_interval) || since >= self.heartbeat
+    }
+
+    /// Record that a frame was drawn at `now`
+    pub fn drawn(&mut self, now: Instant) {
+        self.dirty = false;
+        self.urgent = false;
+        self.last_draw = Some(now);
+    }
+
+    /// How long the loop may block waiting for events before the next draw is due
+    pub fn timeout(&self, now: Instant) -> Duration {
+        let Some(last) = self.last_draw else {
+            return Duration::ZERO;
+        };
+        if self.urgent {
+            return Duration::ZERO;
+        }
+        let since = now.saturating_duration_since(last);
+        let due = if self.dirty {
+            self.min_interval
+        } else {
+            self.heartbeat
+        };
+        due.saturating_sub(since)
+    }
+}
+
+impl Default for RedrawScheduler {
+    fn default() -> Self {
+        Self::new(MAX_OUTPUT_FPS, HEARTBEAT)
+    }
+}
+
+/// Paces the main loop's periodic work independently of drawing
+#[derive(Debug)]
+pub struct WorkTimer {
+    interval: Duration,
+    last_run: Option<Instant>,
+}
+
+impl WorkTimer {
+    /// Create a timer firing every `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_run: None,
+        }
+    }
+
+    /// Whether the work is due at `now`; restarts the interval when it is
+    pub fn due(&mut self, now: Instant) -> bool {
+        let due = self
+            .last_run
+            .is_none_or(|last| now.saturating_duration_since(last) >= self.interval);
+        if due {
+            self.last_run = Some(now);
+        }
+        due
+    }
+
+    /// How long the loop may block waiting for events before the work is due
+    pub fn timeout(&self, now: Instant) -> Duration {
+        self.last_run.map_or(Duration::ZERO, |last| {
+            self.interval
+                .saturating_sub(now.saturating_duration_since(last))
+        })
+    }
+}
+
+impl Default for WorkTimer {
+    fn default() -> Self {
+        Self::new(WORK_INTERVAL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drawn_scheduler(start: Instant) -> RedrawScheduler {
+        let mut scheduler = RedrawScheduler::new(10, Duration::from_secs(1));
+        assert!(scheduler.should_draw(start));
+        scheduler.drawn(start);
+        scheduler
+    }
+
+    #[test]
+    fn test_idle_waits_for_heartbeat() {
+        let start = Instant::now();
+        let scheduler = drawn_scheduler(start);
+
+        assert!(!scheduler.should_draw(start + Duration::from_millis(500)));
+        assert_eq!(
+            scheduler.timeout(start + Duration::from_millis(400)),
+            Duration::from_millis(600)
+        );
+        assert!(scheduler.should_draw(start + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_input_draws_immediately() {
+        let start = Instant::now();
+        let mut scheduler = drawn_scheduler(start);
+
+        scheduler.mark(Damage::Immediate);
+        assert!(scheduler.should_draw(start + Duration::from_millis(1)));
+        assert_eq!(scheduler.timeout(start), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_output_is_coalesced() {
+        let start = Instant::now();
+        let mut scheduler = drawn_scheduler(start);
+
+        scheduler.mark(Damage::Coalesced);
+        scheduler.mark(Damage::Coalesced);
+        assert!(!scheduler.should_draw(start + Duration::from_millis(50)));
+        assert_eq!(
+            scheduler.timeout(start + Duration::from_millis(30)),
+            Duration::from_millis(70)
+        );
+        assert!(scheduler.should_draw(start + Duration::from_millis(100)));
+
+        scheduler.drawn(start + Duration::from_millis(100));
+        assert!(!scheduler.should_draw(start + Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn test_event_damage() {
+        assert_eq!(Damage::for_event(&Event::Tick), Damage::None);
+        assert_eq!(
+            Damage::for_event(&Event::PtyOutput(vec![b'x'])),
+            Damage::Coalesced
+        );
+        assert_eq!(Damage::for_event(&Event::Resize(80, 24)), Damage::Immediate);
+    }
+
+    #[test]
+    fn test_work_runs_on_its_own_interval() {
+        let start = Instant::now();
+        let mut work = WorkTimer::new(Duration::from_millis(100));
+        assert_eq!(work.timeout(start), Duration::ZERO);
+        assert!(work.due(start));
+
+        // An idle scheduler would wait a full second; the work doesn't
+        let scheduler = drawn_scheduler(start);
+        let wait = scheduler
+            .timeout(start)
+            .min(work.timeout(start + Duration::from_millis(40)));
+        assert_eq!(wait, Duration::from_millis(60));
+
+        assert!(!work.due(start + Duration::from_millis(50)));
+        assert!(work.due(start + Duration::from_millis(100)));
+        assert!(!work.due(start + Duration::from_millis(150)));
+    }
+}
@@ -1,9 +1,12 @@
 //! Main render function
 
 use super::layout::get_layout_with_focus;
+use super::profiler::FrameProfiler;
 use super::theme::theme;
+use crate::events::Event;
 use crate::panels::{Panel, PanelRegistry};
 use crate::state::{AppState, PanelId};
+use axiom_core::config::Action;
 use ratatui::{
     layout::Rect,
     style::Style,
@@ -13,12 +16,21 @@ use ratatui::{
 };
 
 /// Render the entire application
-pub fn render(frame: &mut Frame, state: &AppState, panels: &mut PanelRegistry) {
+///
+/// Each panel's render time is recorded in `profiler` (a no-op unless enabled).
+pub fn render(
+    frame: &mut Frame,
+    state: &AppState,
+    panels: &mut PanelRegistry,
+    profiler: &mut FrameProfiler,
+) {
     let area = frame.area();
 
     // Workspace selection phase: full-screen selector only
     if state.active_workspace_id.is_none() {
-        panels.workspace_selector.render(frame, area);
+        profiler.time("Workspaces", || panels.workspace_selector.render(frame, area));
+        render_duplicate_prompt(frame, state, panels, area, profiler);
+        profiler.render_hud(frame, area);
         return; // Don't render main UI until workspace is selected
     }
 
@@ -28,36 +40,116 @@ pub fn render(frame: &mut Frame, state: &AppState, panels: &mut PanelRegistry) {
     let layout = get_layout_with_focus(area, Some(focused));
 
     // Render all panels
-    panels
-        .file_tree
-        .render(frame, layout.file_tree, focused == PanelId::FILE_TREE);
-    panels
-        .output
-        .render(frame, layout.output, focused == PanelId::OUTPUT);
-    panels
-        .input
-        .render(frame, layout.input, focused == PanelId::INPUT);
-    panels
-        .agents
-        .render(frame, layout.agents, focused == PanelId::AGENTS);
+    profiler.time("Files", || {
+        panels
+            .file_tree
+            .render(frame, layout.file_tree, focused == PanelId::FILE_TREE)
+    });
+    profiler.time("Output", || {
+        panels
+            .output
+            .render(frame, layout.output, focused == PanelId::OUTPUT)
+    });
+    profiler.time("Input", || {
+        panels
+            .input
+            .render(frame, layout.input, focused == PanelId::INPUT)
+    });
+    profiler.time("Agents", || {
+        panels
+            .agents
+            .render(frame, layout.agents, focused == PanelId::AGENTS)
+    });
 
     // Render status bar and get model badge area
-    let model_badge_area = render_status_bar(frame, layout.status, state, panels);
+    let model_badge_area =
+        profiler.time("Status bar", || render_status_bar(frame, layout.status, state, panels));
     panels.model_badge_area = Some(model_badge_area);
 
     // Render model selector modal if open
     if state.input_mode.is_modal_open("model_selector") {
-        panels.model_selector.render(frame, area);
+        profiler.time("Model selector", || panels.model_selector.render(frame, area));
     }
 
     // Render settings modal if open
     if state.input_mode.is_modal_open("settings") {
-        panels.settings.render(frame, area);
+        profiler.time("Settings", || panels.settings.render(frame, area));
     }
 
     // Render workspace selector modal if open
     if state.input_mode.is_modal_open("workspace_selector") {
-        panels.workspace_selector.render(frame, area);
+        profiler.time("Workspaces", || panels.workspace_selector.render(frame, area));
+    }
+
+    // Render activity feed modal if open
+    if state.input_mode.is_modal_open("activity_feed") {
+        if let Some(feed) = panels.activity_feed.as_mut() {
+            profiler.time("Activity", || feed.render(frame, area));
+        }
+    }
+
+    // Render review queue modal if open
+    if state.input_mode.is_modal_open("review_queue") {
+        if let Some(queue) = panels.review_queue.as_mut() {
+            profiler.time("Review", || queue.render(frame, area));
+        }
+    }
+
+    // Render plan modal if open
+    if state.input_mode.is_modal_open("plan") {
+        if let (Some(view), Some(plan)) = (panels.plan_view.as_mut(), state.plan.as_ref()) {
+            profiler.time("Plan", || view.render(frame, area, plan));
+        }
+    }
+
+    // Render search panel if open
+    if state.input_mode.is_modal_open("search") {
+        if let Some(search) = panels.search.as_mut() {
+            profiler.time("Search", || search.render(frame, area));
+        }
+    }
+
+    // Render replace preview if open
+    if state.input_mode.is_modal_open("replace") {
+        if let Some(replace) = panels.replace.as_mut() {
+            profiler.time("Replace", || replace.render(frame, area));
+        }
+    }
+
+    // Render trust prompt if open
+    if state.input_mode.is_modal_open("trust_prompt") {
+        if let Some(prompt) = panels.trust_prompt.as_ref() {
+            profiler.time("Trust prompt", || prompt.render(frame, area));
+        }
+    }
+
+    render_duplicate_prompt(frame, state, panels, area, profiler);
+
+    // Render directory picker modal if open
+    if state.input_mode.is_modal_open("directory_picker") {
+        if let Some(picker) = panels.directory_picker.as_mut() {
+            profiler.time("Directory picker", || {
+                picker.render_modal(frame, area, "Initialize Workspace")
+            });
+        }
+    }
+
+    // Profiler HUD goes on top of everything
+    profiler.render_hud(frame, area);
+}
+
+/// Render the duplicate workspace prompt if open (also over the initial selector)
+fn render_duplicate_prompt(
+    frame: &mut Frame,
+    state: &AppState,
+    panels: &PanelRegistry,
+    area: Rect,
+    profiler: &mut FrameProfiler,
+) {
+    if state.input_mode.is_modal_open("duplicate_prompt") {
+        if let Some(prompt) = panels.duplicate_prompt.as_ref() {
+            profiler.time("Duplicate prompt", || prompt.render(frame, area));
+        }
     }
 }
 
@@ -90,6 +182,12 @@ fn render_status_bar(
         " 🤖 ".to_string()
     };
 
+    // Items waiting in the review queue
+    let review_count = match (&state.workspace_manager, state.active_workspace_id) {
+        (Some(manager), Some(id)) => panels.review_items(manager, id).len(),
+        _ => 0,
+    };
+
     let status_text = if let Some(msg) = &state.status_message {
         msg.text.clone()
     } else {
@@ -107,7 +205,7 @@ fn render_status_bar(
 
     // Build status line with theme colors
     let t = theme();
-    let spans = vec![
+    let mut spans = vec![
         Span::styled(mode, Style::default().bg(t.statusbar_mode_bg).fg(t.statusbar_mode_fg)),
         Span::raw(" "),
         Span::styled(
@@ -125,13 +223,33 @@ fn render_status_bar(
             Style::default().bg(t.statusbar_agents_bg).fg(t.statusbar_agents_fg),
         ),
         Span::raw(" "),
+    ];
+    if review_count > 0 {
+        spans.push(Span::styled(
+            format!(" ⚑ {} ", review_count),
+            Style::default().bg(t.status_warning).fg(t.text_inverse),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if panels.llm_registry.read().is_loading() {
+        spans.push(Span::styled(
+            format!("{} Starting providers ", startup_spinner()),
+            Style::default().fg(t.text_muted),
+        ));
+    }
+    let keys = &state.keymap;
+    let hints = format!(
+        " {}: Find  {}: Theme  {}: Workspaces  {}: Quit ",
+        keys.hint(Action::Search),
+        keys.hint(Action::ToggleTheme),
+        keys.hint(Action::WorkspaceSelector),
+        keys.hint(Action::Quit),
+    );
+    spans.extend([
         Span::styled(status_text, Style::default().fg(t.text_secondary)),
         Span::raw("  "),
-        Span::styled(
-            " Ctrl+T: Theme  Ctrl+W: Workspaces  q: Quit ",
-            Style::default().fg(t.text_muted),
-        ),
-    ];
+        Span::styled(hints, Style::default().fg(t.text_muted)),
+    ]);
 
     let status = Paragraph::new(Line::from(spans)).style(Style::default().bg(t.statusbar_bg));
 
@@ -140,6 +258,37 @@ fn render_status_bar(
     model_badge_area
 }
 
+/// Profiler label for handling `event` while `focused` has focus
+pub fn event_label(event: &Event, focused: PanelId) -> &'static str {
+    match event {
+        Event::Key(_) => match focused {
+            PanelId::FILE_TREE => "Files (keys)",
+            PanelId::OUTPUT => "Output (keys)",
+            PanelId::INPUT => "Input (keys)",
+            PanelId::AGENTS => "Agents (keys)",
+            _ => "keys",
+        },
+        Event::Mouse(_) => "mouse",
+        Event::Paste(_) => "paste",
+        Event::Resize(..) => "resize",
+        Event::PtyOutput(_) | Event::CliAgentOutput { .. } => "pty output",
+        Event::AgentOutput { .. } => "agent output",
+        Event::LlmChunk(_) => "llm stream",
+        Event::FileChanged(_) | Event::FileModification { .. } => "file events",
+        _ => "other events",
+    }
+}
+
+/// Spinner frame for work still running at startup
+fn startup_spinner() -> char {
+    const FRAMES: [char; 8] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧'];
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    FRAMES[(millis / 100) as usize % FRAMES.len()]
+}
+
 /// Get panel display name
 fn panel_name(id: PanelId) -> &'static str {
     match id {
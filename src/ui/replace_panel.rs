@@ -0,0 +1,337 @@
+//! Replace panel: workspace-wide find and replace (`/replace`)
+//!
+//! Shows every file the replace would change, each with its diff. Files are
+//! all checked to begin with; Space unchecks the selected one and Enter
+//! writes the checked ones.
+
+use crate::ui::theme::theme;
+use axiom_core::replace::{apply_replace, preview_replace};
+use axiom_core::{FileReplace, ReplaceOutcome, Result, SearchQuery};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use std::path::PathBuf;
+
+/// Replace panel modal state
+pub struct ReplacePanel {
+    /// Workspace root the replace runs in
+    root: PathBuf,
+
+    /// What is replaced
+    query: SearchQuery,
+
+    /// What it's replaced with
+    replacement: String,
+
+    /// Files the preview would change
+    files: Vec<FileReplace>,
+
+    /// Whether each file is to be written
+    checked: Vec<bool>,
+
+    /// Currently selected file
+    selected: usize,
+
+    /// First diff line shown
+    diff_scroll: usize,
+
+    /// Cached modal area for hit testing
+    modal_area: Option<Rect>,
+}
+
+impl ReplacePanel {
+    /// Preview replacing `query` with `replacement` under `root`
+    pub fn preview(
+        root: PathBuf,
+        query: SearchQuery,
+        replacement: String,
+        ignores: &[String],
+    ) -> Result<Self> {
+        let files = preview_replace(&root, &query, &replacement, ignores)?;
+        Ok(Self {
+            root,
+            query,
+            replacement,
+            checked: vec![true; files.len()],
+            files,
+            selected: 0,
+            diff_scroll: 0,
+            modal_area: None,
+        })
+    }
+
+    /// Files the replace would change
+    pub fn files(&self) -> &[FileReplace] {
+        &self.files
+    }
+
+    /// Check or uncheck the selected file
+    pub fn toggle(&mut self) {
+        if let Some(checked) = self.checked.get_mut(self.selected) {
+            *checked = !*checked;
+        }
+    }
+
+    /// Check every file, or uncheck them all if they already are
+    pub fn toggle_all(&mut self) {
+        let all = self.checked.iter().all(|&checked| checked);
+        self.checked.iter_mut().for_each(|checked| *checked = !all);
+    }
+
+    /// Move selection up by `rows`
+    pub fn up(&mut self, rows: usize) {
+        self.selected = self.selected.saturating_sub(rows);
+        self.diff_scroll = 0;
+    }
+
+    /// Move selection down by `rows`
+    pub fn down(&mut self, rows: usize) {
+        self.selected = (self.selected + rows).min(self.files.len().saturating_sub(1));
+        self.diff_scroll = 0;
+    }
+
+    /// Scroll the selected file's diff
+    pub fn scroll_diff(&mut self, down: bool, rows: usize) {
+        let len = self
+            .files
+            .get(self.selected)
+            .map_or(0, |file| file.diff.lines().count());
+        self.diff_scroll = if down {
+            (self.diff_scroll + rows).min(len.saturating_sub(1))
+        } else {
+            self.diff_scroll.saturating_sub(rows)
+        };
+    }
+
+    /// Write the checked files
+    ///
+    /// Returns the outcome along with the absolute path and previous content
+    /// of each file written, so views of those files can mark the change.
+    pub fn apply(&self) -> Result<(ReplaceOutcome, Vec<(PathBuf, String)>)> {
+        let files: Vec<FileReplace> = self
+            .files
+            .iter()
+            .zip(&self.checked)
+            .filter(|(_, &checked)| checked)
+            .map(|(file, _)| file.clone())
+            .collect();
+        let before: Vec<(PathBuf, String)> = files
+            .iter()
+            .filter_map(|file| {
+                let path = self.root.join(&file.path);
+                let text = std::fs::read_to_string(&path).ok()?;
+                Some((path, text))
+            })
+            .collect();
+        let outcome = apply_replace(&self.root, &self.query, &self.replacement, &files)?;
+        let changed = before
+            .into_iter()
+            .filter(|(path, _)| {
+                outcome
+                    .applied
+                    .iter()
+                    .any(|applied| self.root.join(applied) == *path)
+            })
+            .collect();
+        Ok((outcome, changed))
+    }
+
+    /// Check if a point is inside the modal
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        self.modal_area
+            .map(|r| x >= r.x && x < r.x + r.width && y >= r.y && y < r.y + r.height)
+            .unwrap_or(false)
+    }
+
+    fn status(&self) -> String {
+        let files = self.checked.iter().filter(|&&checked| checked).count();
+        let matches: usize = self
+            .files
+            .iter()
+            .zip(&self.checked)
+            .filter(|(_, &checked)| checked)
+            .map(|(file, _)| file.replacements)
+            .sum();
+        format!(
+            "{} match(es) in {} of {} file(s) · Enter: apply",
+            matches,
+            files,
+            self.files.len()
+        )
+    }
+
+    /// Render the replace panel modal
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let modal_width = (area.width as f32 * 0.85).max(60.0) as u16;
+        let modal_height = (area.height as f32 * 0.8).max(12.0) as u16;
+        let modal_area = Rect::new(
+            area.width.saturating_sub(modal_width) / 2,
+            area.height.saturating_sub(modal_height) / 2,
+            modal_width.min(area.width),
+            modal_height.min(area.height),
+        );
+        self.modal_area = Some(modal_area);
+
+        frame.render_widget(Clear, modal_area);
+
+        let t = theme();
+        let block = Block::default()
+            .title(" Replace in workspace (Space toggle · a all · Enter apply · Esc) ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_focused))
+            .style(Style::default().bg(t.bg_modal));
+        let inner = block.inner(modal_area);
+        frame.render_widget(block, modal_area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let mut header = vec![
+            Span::styled(
+                self.query.query.clone(),
+                Style::default().fg(t.diff_removed_fg),
+            ),
+            Span::styled(" → ", Style::default().fg(t.text_muted)),
+            Span::styled(
+                self.replacement.clone(),
+                Style::default().fg(t.diff_added_fg),
+            ),
+        ];
+        if self.query.regex {
+            header.push(Span::styled(
+                "  [regex]",
+                Style::default().fg(t.status_warning),
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(header)), rows[0]);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(rows[1]);
+
+        let items: Vec<ListItem> = self
+            .files
+            .iter()
+            .zip(&self.checked)
+            .enumerate()
+            .map(|(i, (file, &checked))| {
+                let mark = if checked { "[x] " } else { "[ ] " };
+                let mut line = Line::from(vec![
+                    Span::styled(mark, Style::default().fg(t.accent_primary)),
+                    Span::styled(
+                        file.path.display().to_string(),
+                        Style::default().fg(t.text_primary),
+                    ),
+                    Span::styled(
+                        format!(" ({})", file.replacements),
+                        Style::default().fg(t.text_muted),
+                    ),
+                ]);
+                if i == self.selected {
+                    line = line.style(
+                        Style::default()
+                            .bg(t.accent_primary)
+                            .fg(t.text_inverse)
+                            .add_modifier(Modifier::BOLD),
+                    );
+                }
+                ListItem::new(line)
+            })
+            .collect();
+        let mut list_state = ListState::default();
+        list_state.select((!self.files.is_empty()).then_some(self.selected));
+        frame.render_stateful_widget(
+            List::new(items).block(Block::default().borders(Borders::RIGHT)),
+            columns[0],
+            &mut list_state,
+        );
+
+        let diff: Vec<Line> = self
+            .files
+            .get(self.selected)
+            .map(|file| {
+                file.diff
+                    .lines()
+                    .skip(self.diff_scroll)
+                    .map(|line| {
+                        let color = match line.chars().next() {
+                            Some('+') if !line.starts_with("+++") => t.diff_added_fg,
+                            Some('-') if !line.starts_with("---") => t.diff_removed_fg,
+                            Some('@') => t.accent_primary,
+                            _ => t.text_secondary,
+                        };
+                        Line::styled(line.to_string(), Style::default().fg(color))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let diff_area = Rect {
+            x: columns[1].x + 1,
+            width: columns[1].width.saturating_sub(1),
+            ..columns[1]
+        };
+        frame.render_widget(Paragraph::new(diff), diff_area);
+
+        let footer = Paragraph::new(self.status())
+            .style(Style::default().fg(t.text_muted))
+            .alignment(Alignment::Center);
+        frame.render_widget(footer, rows[2]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_uncheck_then_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "let colour = 1;\n").unwrap();
+        std::fs::write(dir.path().join("b.rs"), "// colour colour\n").unwrap();
+
+        let mut panel = ReplacePanel::preview(
+            dir.path().to_path_buf(),
+            SearchQuery::new("colour"),
+            "color".to_string(),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(panel.files().len(), 2);
+        assert_eq!(
+            panel.status(),
+            "3 match(es) in 2 of 2 file(s) · Enter: apply"
+        );
+
+        // Leave b.rs alone
+        panel.down(1);
+        panel.toggle();
+        assert_eq!(
+            panel.status(),
+            "1 match(es) in 1 of 2 file(s) · Enter: apply"
+        );
+
+        let (outcome, changed) = panel.apply().unwrap();
+        assert_eq!(outcome.applied, [PathBuf::from("a.rs")]);
+        assert_eq!(
+            changed,
+            [(dir.path().join("a.rs"), "let colour = 1;\n".to_string())]
+        );
+        let b = std::fs::read_to_string(dir.path().join("b.rs")).unwrap();
+        assert_eq!(b, "// colour colour\n");
+
+        panel.toggle_all();
+        panel.toggle_all();
+        assert!(panel.checked.iter().all(|&checked| !checked));
+    }
+}
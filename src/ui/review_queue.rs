@@ -0,0 +1,203 @@
+//! Review queue modal: everything in the active workspace waiting on the user
+//!
+//! Shows pending approvals, merge conflicts and stalled agents most urgent
+//! first. The selected item is accepted with `a`, rejected with `r` and
+//! opened with `o`/Enter; what each does depends on the item's kind.
+
+use crate::state::WorkspaceId;
+use crate::ui::theme::theme;
+use axiom_core::{ReviewItem, ReviewKind};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// What to do with the selected review item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewAction {
+    Accept,
+    Reject,
+    Open,
+}
+
+/// Review queue modal state
+pub struct ReviewQueue {
+    /// Workspace the queue belongs to
+    pub workspace_id: WorkspaceId,
+
+    /// Workspace name for the title
+    workspace_name: String,
+
+    /// Items, most urgent first
+    items: Vec<ReviewItem>,
+
+    /// Currently selected index
+    selected: usize,
+
+    /// Cached modal area for hit testing
+    modal_area: Option<Rect>,
+}
+
+impl ReviewQueue {
+    pub fn new(
+        workspace_id: WorkspaceId,
+        workspace_name: impl Into<String>,
+        items: Vec<ReviewItem>,
+    ) -> Self {
+        Self {
+            workspace_id,
+            workspace_name: workspace_name.into(),
+            items,
+            selected: 0,
+            modal_area: None,
+        }
+    }
+
+    /// Replace the items after one was handled, keeping the selection in range
+    pub fn set_items(&mut self, items: Vec<ReviewItem>) {
+        self.items = items;
+        self.selected = self.selected.min(self.items.len().saturating_sub(1));
+    }
+
+    pub fn items(&self) -> &[ReviewItem] {
+        &self.items
+    }
+
+    pub fn selected_item(&self) -> Option<&ReviewItem> {
+        self.items.get(self.selected)
+    }
+
+    /// Move selection up by `rows`
+    pub fn up(&mut self, rows: usize) {
+        self.selected = self.selected.saturating_sub(rows);
+    }
+
+    /// Move selection down by `rows`
+    pub fn down(&mut self, rows: usize) {
+        self.selected = (self.selected + rows).min(self.items.len().saturating_sub(1));
+    }
+
+    /// Check if a point is inside the modal
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        self.modal_area
+            .map(|r| x >= r.x && x < r.x + r.width && y >= r.y && y < r.y + r.height)
+            .unwrap_or(false)
+    }
+
+    /// Render the review queue modal
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let modal_width = (area.width as f32 * 0.7).max(50.0) as u16;
+        let modal_height = (area.height as f32 * 0.6).max(10.0) as u16;
+        let modal_area = Rect::new(
+            area.width.saturating_sub(modal_width) / 2,
+            area.height.saturating_sub(modal_height) / 2,
+            modal_width.min(area.width),
+            modal_height.min(area.height),
+        );
+        self.modal_area = Some(modal_area);
+
+        frame.render_widget(Clear, modal_area);
+
+        let t = theme();
+        let block = Block::default()
+            .title(format!(" Review · {} (↑↓ a r o Esc) ", self.workspace_name))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_focused))
+            .style(Style::default().bg(t.bg_modal));
+        let inner = block.inner(modal_area);
+        frame.render_widget(block, modal_area);
+
+        if self.items.is_empty() {
+            let empty = Paragraph::new("Nothing to review")
+                .style(Style::default().fg(t.text_muted))
+                .alignment(Alignment::Center);
+            frame.render_widget(empty, inner);
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(inner);
+
+        let items: Vec<ListItem> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let kind_color = match item.kind {
+                    ReviewKind::Conflict => t.status_error,
+                    ReviewKind::Approval => t.status_warning,
+                    ReviewKind::StalledAgent => t.text_secondary,
+                };
+                let mut title = Line::from(vec![
+                    Span::styled(
+                        format!("{:<9}", item.kind.label()),
+                        Style::default().fg(kind_color),
+                    ),
+                    Span::styled(item.title.clone(), Style::default().fg(t.text_primary)),
+                ]);
+                if i == self.selected {
+                    title = title.style(
+                        Style::default()
+                            .bg(t.accent_primary)
+                            .fg(t.text_inverse)
+                            .add_modifier(Modifier::BOLD),
+                    );
+                }
+                let detail = Line::from(Span::styled(
+                    format!("{:9}{}", "", item.detail),
+                    Style::default().fg(t.text_muted),
+                ));
+                ListItem::new(vec![title, detail])
+            })
+            .collect();
+
+        let mut list_state = ListState::default();
+        list_state.select(Some(self.selected));
+        frame.render_stateful_widget(List::new(items), chunks[0], &mut list_state);
+
+        let hint = self
+            .selected_item()
+            .map(|item| {
+                let [accept, reject, open] = item.kind.actions();
+                format!("a: {}  r: {}  o: {}", accept, reject, open)
+            })
+            .unwrap_or_default();
+        let footer = Paragraph::new(hint)
+            .style(Style::default().fg(t.text_muted))
+            .alignment(Alignment::Center);
+        frame.render_widget(footer, chunks[1]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_selection_stays_in_range_as_items_resolve() {
+        let items: Vec<ReviewItem> = (0..3)
+            .map(|i| {
+                ReviewItem::stalled_agent(
+                    format!("agent-{}", i),
+                    "Shell",
+                    Duration::from_secs(200),
+                    1000,
+                )
+            })
+            .collect();
+        let mut queue = ReviewQueue::new(WorkspaceId::new(), "demo", items.clone());
+        queue.down(10);
+        assert_eq!(queue.selected_item().unwrap().id, "agent-2");
+
+        queue.set_items(items[..1].to_vec());
+        assert_eq!(queue.selected_item().unwrap().id, "agent-0");
+        queue.set_items(Vec::new());
+        assert!(queue.selected_item().is_none());
+    }
+}
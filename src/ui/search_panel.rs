@@ -0,0 +1,340 @@
+//! Search panel: workspace-wide text search (`/find`, Ctrl+F)
+//!
+//! Type a query and press Enter to search; hits stream in from a background
+//! search and the selected one opens with Enter once the query is unchanged.
+//! Ctrl+R switches between plain text and regex.
+
+use crate::ui::theme::theme;
+use axiom_core::{SearchHandle, SearchHit, SearchQuery, SearchSummary, SearchUpdate};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use std::path::PathBuf;
+
+/// Search panel modal state
+pub struct SearchPanel {
+    /// Workspace root searched
+    root: PathBuf,
+
+    /// The workspace's `ignore_patterns`
+    ignores: Vec<String>,
+
+    /// Query being typed
+    query: String,
+
+    /// Treat the query as a regex
+    regex: bool,
+
+    /// Globs limiting the files searched (set by `/find --glob`)
+    globs: Vec<String>,
+
+    /// The query or regex flag changed since the last search
+    dirty: bool,
+
+    /// Hits so far, in arrival order
+    hits: Vec<SearchHit>,
+
+    /// Currently selected hit
+    selected: usize,
+
+    /// Running search, if any
+    search: Option<SearchHandle>,
+
+    /// How the last search ended
+    summary: Option<SearchSummary>,
+
+    /// Why the last search couldn't run
+    error: Option<String>,
+
+    /// Cached modal area for hit testing
+    modal_area: Option<Rect>,
+}
+
+impl SearchPanel {
+    pub fn new(root: PathBuf, ignores: Vec<String>) -> Self {
+        Self {
+            root,
+            ignores,
+            query: String::new(),
+            regex: false,
+            globs: Vec::new(),
+            dirty: false,
+            hits: Vec::new(),
+            selected: 0,
+            search: None,
+            summary: None,
+            error: None,
+            modal_area: None,
+        }
+    }
+
+    /// Workspace root searched
+    pub fn root(&self) -> &std::path::Path {
+        &self.root
+    }
+
+    /// Fill in the query and flags, e.g. from `/find`
+    pub fn set_query(&mut self, query: impl Into<String>, regex: bool, globs: Vec<String>) {
+        self.query = query.into();
+        self.regex = regex;
+        self.globs = globs;
+        self.dirty = true;
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.query.push(c);
+        self.dirty = true;
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.dirty = true;
+    }
+
+    pub fn toggle_regex(&mut self) {
+        self.regex = !self.regex;
+        self.dirty = true;
+    }
+
+    /// Whether Enter should search rather than open the selected hit
+    pub fn is_dirty(&self) -> bool {
+        self.dirty || (self.search.is_none() && self.summary.is_none() && self.error.is_none())
+    }
+
+    /// Start searching for the current query, cancelling any running search
+    pub fn run(&mut self) {
+        self.search = None;
+        self.hits.clear();
+        self.selected = 0;
+        self.summary = None;
+        self.error = None;
+        self.dirty = false;
+        if self.query.is_empty() {
+            return;
+        }
+        let query = SearchQuery {
+            regex: self.regex,
+            globs: self.globs.clone(),
+            ..SearchQuery::new(self.query.clone())
+        };
+        self.search = Some(SearchHandle::spawn(
+            self.root.clone(),
+            query,
+            self.ignores.clone(),
+        ));
+    }
+
+    /// Take in hits found since the last call; returns whether anything changed
+    pub fn poll(&mut self) -> bool {
+        let Some(search) = &self.search else {
+            return false;
+        };
+        let updates = search.poll();
+        let changed = !updates.is_empty();
+        for update in updates {
+            match update {
+                SearchUpdate::Hit(hit) => self.hits.push(hit),
+                SearchUpdate::Done(summary) => {
+                    self.summary = Some(summary);
+                    self.search = None;
+                }
+                SearchUpdate::Failed(e) => {
+                    self.error = Some(e);
+                    self.search = None;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Whether a search is still running
+    pub fn is_running(&self) -> bool {
+        self.search.is_some()
+    }
+
+    pub fn selected_hit(&self) -> Option<&SearchHit> {
+        self.hits.get(self.selected)
+    }
+
+    /// Absolute path of the selected hit and its line
+    pub fn selected_location(&self) -> Option<(PathBuf, u64)> {
+        self.selected_hit()
+            .map(|hit| (self.root.join(&hit.path), hit.line))
+    }
+
+    /// Move selection up by `rows`
+    pub fn up(&mut self, rows: usize) {
+        self.selected = self.selected.saturating_sub(rows);
+    }
+
+    /// Move selection down by `rows`
+    pub fn down(&mut self, rows: usize) {
+        self.selected = (self.selected + rows).min(self.hits.len().saturating_sub(1));
+    }
+
+    /// Check if a point is inside the modal
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        self.modal_area
+            .map(|r| x >= r.x && x < r.x + r.width && y >= r.y && y < r.y + r.height)
+            .unwrap_or(false)
+    }
+
+    fn status(&self) -> String {
+        if let Some(error) = &self.error {
+            return error.clone();
+        }
+        if self.is_running() {
+            return format!("Searching… {} found", self.hits.len());
+        }
+        match &self.summary {
+            Some(summary) if summary.truncated => format!(
+                "First {} matches in {} files (limit reached)",
+                summary.matches, summary.files
+            ),
+            Some(summary) => format!("{} matches in {} files", summary.matches, summary.files),
+            None => "Enter: search".to_string(),
+        }
+    }
+
+    /// Render the search panel modal
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let modal_width = (area.width as f32 * 0.8).max(50.0) as u16;
+        let modal_height = (area.height as f32 * 0.7).max(10.0) as u16;
+        let modal_area = Rect::new(
+            area.width.saturating_sub(modal_width) / 2,
+            area.height.saturating_sub(modal_height) / 2,
+            modal_width.min(area.width),
+            modal_height.min(area.height),
+        );
+        self.modal_area = Some(modal_area);
+
+        frame.render_widget(Clear, modal_area);
+
+        let t = theme();
+        let block = Block::default()
+            .title(" Find in workspace (Enter search/open · Ctrl+R regex · Esc) ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_focused))
+            .style(Style::default().bg(t.bg_modal));
+        let inner = block.inner(modal_area);
+        frame.render_widget(block, modal_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let mut input = vec![
+            Span::styled("> ", Style::default().fg(t.accent_primary)),
+            Span::styled(self.query.clone(), Style::default().fg(t.text_primary)),
+            Span::styled("▏", Style::default().fg(t.accent_primary)),
+        ];
+        if self.regex {
+            input.push(Span::styled(
+                "  [regex]",
+                Style::default().fg(t.status_warning),
+            ));
+        }
+        if !self.globs.is_empty() {
+            input.push(Span::styled(
+                format!("  [{}]", self.globs.join(" ")),
+                Style::default().fg(t.text_secondary),
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(input)), chunks[0]);
+
+        let items: Vec<ListItem> = self
+            .hits
+            .iter()
+            .enumerate()
+            .map(|(i, hit)| {
+                let mut line = Line::from(vec![
+                    Span::styled(
+                        format!("{}:{} ", hit.path.display(), hit.line),
+                        Style::default().fg(t.accent_primary),
+                    ),
+                    Span::styled(
+                        hit.text.trim().to_string(),
+                        Style::default().fg(t.text_secondary),
+                    ),
+                ]);
+                if i == self.selected {
+                    line = line.style(
+                        Style::default()
+                            .bg(t.accent_primary)
+                            .fg(t.text_inverse)
+                            .add_modifier(Modifier::BOLD),
+                    );
+                }
+                ListItem::new(line)
+            })
+            .collect();
+        let mut list_state = ListState::default();
+        list_state.select((!self.hits.is_empty()).then_some(self.selected));
+        frame.render_stateful_widget(List::new(items), chunks[2], &mut list_state);
+
+        let status_color = if self.error.is_some() {
+            t.status_error
+        } else {
+            t.text_muted
+        };
+        let footer = Paragraph::new(self.status())
+            .style(Style::default().fg(status_color))
+            .alignment(Alignment::Center);
+        frame.render_widget(footer, chunks[3]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn finish(panel: &mut SearchPanel) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while panel.is_running() && Instant::now() < deadline {
+            panel.poll();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_search_then_open_selected_hit() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn main() {}\n// TODO one\n").unwrap();
+        std::fs::write(dir.path().join("b.rs"), "// TODO two\n").unwrap();
+
+        let mut panel = SearchPanel::new(dir.path().to_path_buf(), Vec::new());
+        assert!(panel.is_dirty());
+        for c in "todo".chars() {
+            panel.push_char(c);
+        }
+        panel.run();
+        finish(&mut panel);
+        assert!(!panel.is_dirty());
+        assert_eq!(panel.status(), "2 matches in 2 files");
+
+        panel.down(10);
+        let (path, line) = panel.selected_location().unwrap();
+        assert!(path.starts_with(dir.path()));
+        assert!(line >= 1);
+
+        // Editing the query means Enter searches again
+        panel.toggle_regex();
+        assert!(panel.is_dirty());
+        panel.set_query("fn (", true, Vec::new());
+        panel.run();
+        finish(&mut panel);
+        assert!(panel.status().contains("Search pattern doesn't parse"));
+        assert!(panel.selected_hit().is_none());
+    }
+}
@@ -1,6 +1,12 @@
 //! Settings modal for configuring API keys and providers
-
-use crate::config::{AxiomConfig, LlmConfig, ProviderConfig};
+//!
+//! The last two rows pick the active workspace's own provider and CLI agent,
+//! which are saved to its `.axiom/config.toml` and win over the global ones.
+
+use crate::config::{AxiomConfig, CliAgentsConfig, LlmConfig, ProviderConfig};
+use axiom_core::llm::{ProviderRouting, RateLimits};
+use axiom_core::config::{AgentModelConfig, ContextConfig};
+use axiom_core::WorkspaceDefaults;
 use crate::ui::theme::{theme, current_variant, set_theme, ThemeVariant};
 use ratatui::{
     layout::{Alignment, Rect},
@@ -23,6 +29,8 @@ pub enum SettingsRow {
     GeminiApiKey,
     OpenAiApiKey,
     OllamaUrl,
+    WorkspaceProvider,
+    WorkspaceCliAgent,
     CancelButton,
     SaveButton,
 }
@@ -36,6 +44,8 @@ impl SettingsRow {
             SettingsRow::GeminiApiKey,
             SettingsRow::OpenAiApiKey,
             SettingsRow::OllamaUrl,
+            SettingsRow::WorkspaceProvider,
+            SettingsRow::WorkspaceCliAgent,
             SettingsRow::CancelButton,
             SettingsRow::SaveButton,
         ]
@@ -45,6 +55,16 @@ impl SettingsRow {
         Self::all().get(idx).copied()
     }
 
+    /// The API key row of `provider`, if it has one
+    fn api_key(provider: &str) -> Option<SettingsRow> {
+        match provider {
+            "claude" => Some(SettingsRow::ClaudeApiKey),
+            "gemini" => Some(SettingsRow::GeminiApiKey),
+            "openai" => Some(SettingsRow::OpenAiApiKey),
+            _ => None,
+        }
+    }
+
     fn is_text_field(&self) -> bool {
         matches!(
             self,
@@ -56,7 +76,13 @@ impl SettingsRow {
     }
 
     fn is_dropdown(&self) -> bool {
-        matches!(self, SettingsRow::DefaultProvider | SettingsRow::Theme)
+        matches!(
+            self,
+            SettingsRow::DefaultProvider
+                | SettingsRow::Theme
+                | SettingsRow::WorkspaceProvider
+                | SettingsRow::WorkspaceCliAgent
+        )
     }
 
     fn is_button(&self) -> bool {
@@ -71,6 +97,8 @@ impl SettingsRow {
             SettingsRow::GeminiApiKey => "Google API Key",
             SettingsRow::OpenAiApiKey => "OpenAI API Key",
             SettingsRow::OllamaUrl => "Ollama Base URL",
+            SettingsRow::WorkspaceProvider => "Workspace LLM",
+            SettingsRow::WorkspaceCliAgent => "Workspace Agent",
             SettingsRow::CancelButton => "Cancel",
             SettingsRow::SaveButton => "Save",
         }
@@ -98,6 +126,18 @@ pub struct SettingsModal {
     original_keys: HashMap<String, String>,
     original_url: String,
 
+    // The active workspace's own choices (None without a workspace)
+    pub workspace: Option<WorkspaceDefaults>,
+    original_workspace: Option<WorkspaceDefaults>,
+
+    // Not editable here, kept so saving doesn't drop them
+    rate_limits: HashMap<String, RateLimits>,
+    routing: HashMap<String, ProviderRouting>,
+    agent_models: HashMap<String, AgentModelConfig>,
+    context: ContextConfig,
+    cli_agents: CliAgentsConfig,
+    providers: HashMap<String, ProviderConfig>,
+
     // UI state
     pub selected_row: usize,
     pub editing: bool,
@@ -135,6 +175,14 @@ impl SettingsModal {
             original_keys: api_keys,
             ollama_url: ollama_url.clone(),
             original_url: ollama_url,
+            workspace: None,
+            original_workspace: None,
+            rate_limits: config.llm.rate_limits.clone(),
+            routing: config.llm.routing.clone(),
+            agent_models: config.llm.agents.clone(),
+            context: config.llm.context.clone(),
+            cli_agents: config.cli_agents.clone(),
+            providers: config.llm.providers.clone(),
             selected_row: 0,
             editing: false,
             edit_buffer: String::new(),
@@ -144,13 +192,95 @@ impl SettingsModal {
         }
     }
 
+    /// Show the active workspace's own choices, or none without a workspace
+    pub fn set_workspace(&mut self, defaults: Option<WorkspaceDefaults>) {
+        self.workspace = defaults.clone();
+        self.original_workspace = defaults;
+    }
+
     /// Check if there are unsaved changes
     pub fn has_changes(&self) -> bool {
+        self.config_changed() || self.workspace_changes().is_some()
+    }
+
+    /// Whether the global config was changed
+    pub fn config_changed(&self) -> bool {
         self.default_provider != self.original_provider
             || self.api_keys != self.original_keys
             || self.ollama_url != self.original_url
     }
 
+    /// The workspace's new choices, if they were changed
+    pub fn workspace_changes(&self) -> Option<WorkspaceDefaults> {
+        self.workspace
+            .clone()
+            .filter(|_| self.workspace != self.original_workspace)
+    }
+
+    /// Pick the previous or next workspace provider or CLI agent
+    fn cycle_workspace(&mut self, row: SettingsRow, forward: bool) {
+        let agents: Vec<&str> = {
+            let mut agents: Vec<&str> = self
+                .cli_agents
+                .enabled_agents()
+                .map(|(id, _)| id.as_str())
+                .collect();
+            agents.sort_unstable();
+            agents
+        };
+        let Some(ref mut workspace) = self.workspace else {
+            return;
+        };
+        let (options, current): (&[&str], _) = match row {
+            SettingsRow::WorkspaceProvider => (&PROVIDERS, &mut workspace.default_provider),
+            SettingsRow::WorkspaceCliAgent => (&agents, &mut workspace.default_cli_agent),
+            _ => return,
+        };
+        *current = cycle(options, current.as_deref(), forward);
+    }
+
+    /// Select `provider`'s API key and start editing it; false if the
+    /// provider has no key here
+    pub fn edit_api_key(&mut self, provider: &str) -> bool {
+        let Some(row) = SettingsRow::api_key(provider) else {
+            return false;
+        };
+        self.selected_row = SettingsRow::all()
+            .iter()
+            .position(|r| *r == row)
+            .unwrap_or_default();
+        // The rejected key is replaced, not amended
+        self.start_edit();
+        self.edit_buffer.clear();
+        self.cursor_pos = 0;
+        true
+    }
+
+    /// Whether `provider`'s API key was changed
+    pub fn key_changed(&self, provider: &str) -> bool {
+        self.api_keys.get(provider) != self.original_keys.get(provider)
+    }
+
+    /// `provider`'s key command and, unless its key was replaced here, the
+    /// key's expiry
+    fn key_lifetime(&self, provider: &str) -> (Option<String>, Option<u64>) {
+        let Some(config) = self.providers.get(provider) else {
+            return (None, None);
+        };
+        let expires_at = config
+            .api_key_expires_at
+            .filter(|_| !self.key_changed(provider));
+        (config.api_key_command.clone(), expires_at)
+    }
+
+    /// Headers configured for `provider`, which aren't edited here
+    fn extra_headers(&self, provider: &str) -> HashMap<String, String> {
+        self.providers
+            .get(provider)
+            .map(|config| config.extra_headers.clone())
+            .unwrap_or_default()
+    }
+
     /// Navigate up
     pub fn up(&mut self) {
         if !self.editing && self.selected_row > 0 {
@@ -191,6 +321,9 @@ impl SettingsModal {
                     };
                     set_theme(new_variant);
                 }
+                Some(row @ (SettingsRow::WorkspaceProvider | SettingsRow::WorkspaceCliAgent)) => {
+                    self.cycle_workspace(row, false);
+                }
                 _ => {}
             }
         }
@@ -213,6 +346,9 @@ impl SettingsModal {
                     let current = current_variant();
                     set_theme(current.cycle());
                 }
+                Some(row @ (SettingsRow::WorkspaceProvider | SettingsRow::WorkspaceCliAgent)) => {
+                    self.cycle_workspace(row, true);
+                }
                 _ => {}
             }
         }
@@ -359,35 +495,45 @@ impl SettingsModal {
             ProviderConfig {
                 enabled: true,
                 api_key: None,
+                api_key_command: None,
+                api_key_expires_at: None,
                 base_url: Some(self.ollama_url.clone()),
                 default_model: Some("gemma3:4b".to_string()),
                 models: Vec::new(),
+                extra_headers: self.extra_headers("ollama"),
             },
         );
 
         // Claude
         let claude_key = self.api_keys.get("claude").cloned();
+        let (claude_command, claude_expiry) = self.key_lifetime("claude");
         providers.insert(
             "claude".to_string(),
             ProviderConfig {
-                enabled: claude_key.is_some(),
+                enabled: claude_key.is_some() || claude_command.is_some(),
                 api_key: claude_key,
+                api_key_command: claude_command,
+                api_key_expires_at: claude_expiry,
                 base_url: Some("https://api.anthropic.com".to_string()),
                 default_model: Some("claude-sonnet-4-20250514".to_string()),
                 models: vec![
                     "claude-sonnet-4-20250514".to_string(),
                     "claude-opus-4-20250514".to_string(),
                 ],
+                extra_headers: self.extra_headers("claude"),
             },
         );
 
         // Gemini
         let gemini_key = self.api_keys.get("gemini").cloned();
+        let (gemini_command, gemini_expiry) = self.key_lifetime("gemini");
         providers.insert(
             "gemini".to_string(),
             ProviderConfig {
-                enabled: gemini_key.is_some(),
+                enabled: gemini_key.is_some() || gemini_command.is_some(),
                 api_key: gemini_key,
+                api_key_command: gemini_command,
+                api_key_expires_at: gemini_expiry,
                 base_url: Some("https://generativelanguage.googleapis.com".to_string()),
                 default_model: Some("gemini-2.0-flash".to_string()),
                 models: vec![
@@ -395,16 +541,20 @@ impl SettingsModal {
                     "gemini-1.5-pro".to_string(),
                     "gemini-1.5-flash".to_string(),
                 ],
+                extra_headers: self.extra_headers("gemini"),
             },
         );
 
         // OpenAI
         let openai_key = self.api_keys.get("openai").cloned();
+        let (openai_command, openai_expiry) = self.key_lifetime("openai");
         providers.insert(
             "openai".to_string(),
             ProviderConfig {
-                enabled: openai_key.is_some(),
+                enabled: openai_key.is_some() || openai_command.is_some(),
                 api_key: openai_key,
+                api_key_command: openai_command,
+                api_key_expires_at: openai_expiry,
                 base_url: Some("https://api.openai.com".to_string()),
                 default_model: Some("gpt-4o".to_string()),
                 models: vec![
@@ -412,17 +562,39 @@ impl SettingsModal {
                     "gpt-4o-mini".to_string(),
                     "gpt-4-turbo".to_string(),
                 ],
+                extra_headers: self.extra_headers("openai"),
             },
         );
 
+        // Custom OpenAI-compatible endpoints aren't edited here
+        for (id, provider) in &self.providers {
+            providers
+                .entry(id.clone())
+                .or_insert_with(|| provider.clone());
+        }
+
         AxiomConfig {
             llm: LlmConfig {
                 default_provider: self.default_provider.clone(),
                 timeout: 120,
                 max_retries: 3,
                 providers,
+                rate_limits: self.rate_limits.clone(),
+                routing: self.routing.clone(),
+                agents: self.agent_models.clone(),
+                context: self.context.clone(),
             },
-            cli_agents: Default::default(),
+            cli_agents: self.cli_agents.clone(),
+            directory_picker: Default::default(),
+            discovery: Default::default(),
+            terminal: Default::default(),
+            completion: Default::default(),
+            sounds: Default::default(),
+            suggestions: Default::default(),
+            agents: Default::default(),
+            lsp: Default::default(),
+            keys: Default::default(),
+            prompts: Default::default(),
         }
     }
 
@@ -473,7 +645,7 @@ impl SettingsModal {
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
         // Calculate modal size (60% width, 50% height)
         let modal_width = (area.width as f32 * 0.6).max(50.0).min(70.0) as u16;
-        let modal_height = (area.height as f32 * 0.5).max(15.0).min(20.0) as u16;
+        let modal_height = (area.height as f32 * 0.5).clamp(22.0, 24.0) as u16;
 
         let x = (area.width.saturating_sub(modal_width)) / 2;
         let y = (area.height.saturating_sub(modal_height)) / 2;
@@ -631,6 +803,38 @@ impl SettingsModal {
                 frame.render_widget(Paragraph::new(line), area);
             }
 
+            SettingsRow::WorkspaceProvider | SettingsRow::WorkspaceCliAgent => {
+                let (display, value_style) = match &self.workspace {
+                    Some(workspace) => {
+                        let value = match row {
+                            SettingsRow::WorkspaceProvider => &workspace.default_provider,
+                            _ => &workspace.default_cli_agent,
+                        };
+                        let style = if selected {
+                            Style::default().fg(t.accent_highlight)
+                        } else if value.is_some() {
+                            Style::default().fg(t.text_secondary)
+                        } else {
+                            Style::default().fg(t.text_muted)
+                        };
+                        (
+                            format!("< {} >", value.as_deref().unwrap_or("global")),
+                            style,
+                        )
+                    }
+                    None => (
+                        "(no workspace open)".to_string(),
+                        Style::default().fg(t.text_muted),
+                    ),
+                };
+                let line = Line::from(vec![
+                    Span::styled(format!("{:>16}: ", row.label()), label_style),
+                    Span::styled(display, value_style),
+                ]);
+
+                frame.render_widget(Paragraph::new(line), area);
+            }
+
             SettingsRow::OllamaUrl => {
                 let value = if is_editing_this {
                     self.edit_buffer.clone()
@@ -682,6 +886,19 @@ impl SettingsModal {
     }
 }
 
+/// The option before or after `current` in `options`, going through "none"
+/// (the global choice) between the last and the first
+fn cycle(options: &[&str], current: Option<&str>, forward: bool) -> Option<String> {
+    let index = current.and_then(|current| options.iter().position(|o| *o == current));
+    let next = match (index, forward) {
+        (None, true) => options.first(),
+        (None, false) => options.last(),
+        (Some(i), true) => options.get(i + 1),
+        (Some(i), false) => i.checked_sub(1).and_then(|i| options.get(i)),
+    };
+    next.map(|option| option.to_string())
+}
+
 impl Default for SettingsModal {
     fn default() -> Self {
         Self::new(&AxiomConfig::default())
@@ -1,5 +1,6 @@
 //! Theme system for light/dark mode support
 
+use super::capabilities::{capabilities, Capabilities};
 use parking_lot::RwLock;
 use ratatui::style::Color;
 use std::sync::LazyLock;
@@ -257,6 +258,58 @@ impl Theme {
             agent_pending: Color::Rgb(90, 90, 110),
         }
     }
+    /// Convert all colors to ones the terminal can display
+    pub fn adapted(&self, caps: &Capabilities) -> Self {
+        let adapt = |c| caps.adapt_color(c);
+        Self {
+            bg_primary: adapt(self.bg_primary),
+            bg_secondary: adapt(self.bg_secondary),
+            bg_modal: adapt(self.bg_modal),
+            bg_selection: adapt(self.bg_selection),
+            bg_hover: adapt(self.bg_hover),
+            text_primary: adapt(self.text_primary),
+            text_secondary: adapt(self.text_secondary),
+            text_muted: adapt(self.text_muted),
+            text_inverse: adapt(self.text_inverse),
+            border_focused: adapt(self.border_focused),
+            border_unfocused: adapt(self.border_unfocused),
+            accent_primary: adapt(self.accent_primary),
+            accent_secondary: adapt(self.accent_secondary),
+            accent_highlight: adapt(self.accent_highlight),
+            status_success: adapt(self.status_success),
+            status_error: adapt(self.status_error),
+            status_warning: adapt(self.status_warning),
+            status_info: adapt(self.status_info),
+            diff_added_bg: adapt(self.diff_added_bg),
+            diff_added_fg: adapt(self.diff_added_fg),
+            diff_removed_bg: adapt(self.diff_removed_bg),
+            diff_removed_fg: adapt(self.diff_removed_fg),
+            diff_modified_bg: adapt(self.diff_modified_bg),
+            diff_modified_fg: adapt(self.diff_modified_fg),
+            code_bg: adapt(self.code_bg),
+            code_border: adapt(self.code_border),
+            code_text: adapt(self.code_text),
+            scrollbar_track: adapt(self.scrollbar_track),
+            scrollbar_thumb: adapt(self.scrollbar_thumb),
+            scrollbar_thumb_focused: adapt(self.scrollbar_thumb_focused),
+            statusbar_bg: adapt(self.statusbar_bg),
+            statusbar_mode_bg: adapt(self.statusbar_mode_bg),
+            statusbar_mode_fg: adapt(self.statusbar_mode_fg),
+            statusbar_focus_bg: adapt(self.statusbar_focus_bg),
+            statusbar_focus_fg: adapt(self.statusbar_focus_fg),
+            statusbar_workspace_bg: adapt(self.statusbar_workspace_bg),
+            statusbar_workspace_fg: adapt(self.statusbar_workspace_fg),
+            statusbar_agents_bg: adapt(self.statusbar_agents_bg),
+            statusbar_agents_fg: adapt(self.statusbar_agents_fg),
+            file_tree_directory: adapt(self.file_tree_directory),
+            file_tree_file: adapt(self.file_tree_file),
+            file_tree_symlink: adapt(self.file_tree_symlink),
+            agent_running: adapt(self.agent_running),
+            agent_completed: adapt(self.agent_completed),
+            agent_failed: adapt(self.agent_failed),
+            agent_pending: adapt(self.agent_pending),
+        }
+    }
 }
 
 // Global theme state
@@ -267,7 +320,7 @@ struct ThemeState {
 
 static THEME_STATE: LazyLock<RwLock<ThemeState>> = LazyLock::new(|| {
     RwLock::new(ThemeState {
-        theme: Theme::dark(),
+        theme: Theme::dark().adapted(capabilities()),
         variant: ThemeVariant::Dark,
     })
 });
@@ -286,7 +339,7 @@ pub fn current_variant() -> ThemeVariant {
 pub fn set_theme(variant: ThemeVariant) {
     let mut state = THEME_STATE.write();
     state.variant = variant;
-    state.theme = match variant {
+    let theme = match variant {
         ThemeVariant::Dark => Theme::dark(),
         ThemeVariant::Light => Theme::light(),
         ThemeVariant::System => {
@@ -294,6 +347,7 @@ pub fn set_theme(variant: ThemeVariant) {
             Theme::dark()
         }
     };
+    state.theme = theme.adapted(capabilities());
 }
 
 /// Toggle between light and dark themes
@@ -0,0 +1,228 @@
+//! Window title, tmux pane title and OSC 9 progress
+//!
+//! The main loop hands a [`StatusReporter`] the current [`AppStatus`] once
+//! per iteration; it writes escape sequences only when something changed.
+//! The title reads "axiom — {workspace} — {running agents}". Inside tmux it
+//! becomes the pane title, and progress sequences are wrapped for tmux
+//! passthrough so they reach the outer terminal.
+//!
+//! With `[terminal] osc_progress = true`, agent runs that last longer than
+//! `progress_after_secs` show as indeterminate progress (OSC 9;4, supported
+//! by Windows Terminal, ConEmu, Ghostty and others) and end with an OSC 9
+//! notification.
+
+use crate::config::TerminalConfig;
+use std::time::{Duration, Instant};
+
+/// What the title and progress reflect
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppStatus {
+    /// Active workspace name
+    pub workspace: Option<String>,
+    /// Names of running agents
+    pub running: Vec<String>,
+}
+
+impl AppStatus {
+    /// Window title for this status
+    pub fn title(&self) -> String {
+        let mut parts = vec!["axiom".to_string()];
+        if let Some(workspace) = &self.workspace {
+            parts.push(workspace.clone());
+        }
+        match self.running.len() {
+            0 => {}
+            1..=3 => parts.push(self.running.join(", ")),
+            n => parts.push(format!("{} agents running", n)),
+        }
+        // Control characters would end the sequence early
+        parts
+            .join(" — ")
+            .chars()
+            .filter(|c| !c.is_control())
+            .collect()
+    }
+}
+
+/// Emits title and progress sequences when the app status changes
+#[derive(Debug)]
+pub struct StatusReporter {
+    config: TerminalConfig,
+    in_tmux: bool,
+    /// Status the current title was written for
+    last: Option<AppStatus>,
+    /// When agents started running (continuously)
+    busy_since: Option<Instant>,
+    /// Progress is currently shown
+    progress: bool,
+}
+
+impl StatusReporter {
+    pub fn new(config: TerminalConfig, in_tmux: bool) -> Self {
+        Self {
+            config,
+            in_tmux,
+            last: None,
+            busy_since: None,
+            progress: false,
+        }
+    }
+
+    /// Create a reporter for the current terminal
+    pub fn detect(config: TerminalConfig) -> Self {
+        Self::new(
+            config,
+            std::env::var_os("TMUX").is_some_and(|v| !v.is_empty()),
+        )
+    }
+
+    /// Sequences to write for `status` at `now` (empty when nothing changed)
+    pub fn update(&mut self, status: &AppStatus, now: Instant) -> String {
+        let mut out = String::new();
+
+        if self.config.title && self.last.as_ref() != Some(status) {
+            if self.last.is_none() && !self.in_tmux {
+                // Save the user's title so it can be restored on exit
+                out.push_str("\x1b[22;0t");
+            }
+            let osc = if self.in_tmux { 2 } else { 0 };
+            out.push_str(&format!("\x1b]{};{}\x07", osc, status.title()));
+        }
+        self.last = Some(status.clone());
+
+        if status.running.is_empty() {
+            if let Some(since) = self.busy_since.take() {
+                if self.progress {
+                    self.progress = false;
+                    out.push_str(&self.osc9("4;0;0"));
+                    out.push_str(&self.osc9(&format!(
+                        "axiom: agents finished after {}",
+                        format_elapsed(now.duration_since(since))
+                    )));
+                }
+            }
+        } else {
+            let since = *self.busy_since.get_or_insert(now);
+            let threshold = Duration::from_secs(self.config.progress_after_secs);
+            if self.config.osc_progress && !self.progress && now.duration_since(since) >= threshold
+            {
+                self.progress = true;
+                out.push_str(&self.osc9("4;3;0"));
+            }
+        }
+        out
+    }
+
+    /// Sequences that undo the title and progress on exit
+    pub fn finish(&mut self) -> String {
+        let mut out = String::new();
+        if self.progress {
+            self.progress = false;
+            out.push_str(&self.osc9("4;0;0"));
+        }
+        if self.config.title && self.last.take().is_some() && !self.in_tmux {
+            out.push_str("\x1b[23;0t");
+        }
+        out
+    }
+
+    /// An OSC 9 sequence, wrapped for tmux passthrough when needed
+    fn osc9(&self, payload: &str) -> String {
+        let payload: String = payload.chars().filter(|c| !c.is_control()).collect();
+        if self.in_tmux {
+            format!("\x1bPtmux;\x1b\x1b]9;{}\x07\x1b\\", payload)
+        } else {
+            format!("\x1b]9;{}\x07", payload)
+        }
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m {}s", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(workspace: &str, running: &[&str]) -> AppStatus {
+        AppStatus {
+            workspace: Some(workspace.to_string()),
+            running: running.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_title() {
+        assert_eq!(AppStatus::default().title(), "axiom");
+        assert_eq!(status("api", &[]).title(), "axiom — api");
+        assert_eq!(
+            status("api", &["Coder", "Shell"]).title(),
+            "axiom — api — Coder, Shell"
+        );
+        assert_eq!(
+            status("api\x07", &["a", "b", "c", "d"]).title(),
+            "axiom — api — 4 agents running"
+        );
+    }
+
+    #[test]
+    fn test_title_written_on_change_only() {
+        let mut reporter = StatusReporter::new(TerminalConfig::default(), false);
+        let now = Instant::now();
+
+        let first = reporter.update(&status("api", &[]), now);
+        assert_eq!(first, "\x1b[22;0t\x1b]0;axiom — api\x07");
+        assert_eq!(reporter.update(&status("api", &[]), now), "");
+        assert_eq!(
+            reporter.update(&status("api", &["Coder"]), now),
+            "\x1b]0;axiom — api — Coder\x07"
+        );
+        assert_eq!(reporter.finish(), "\x1b[23;0t");
+
+        let mut tmux = StatusReporter::new(TerminalConfig::default(), true);
+        assert_eq!(
+            tmux.update(&status("api", &[]), now),
+            "\x1b]2;axiom — api\x07"
+        );
+    }
+
+    #[test]
+    fn test_progress_for_long_runs() {
+        let config = TerminalConfig {
+            title: false,
+            osc_progress: true,
+            progress_after_secs: 10,
+        };
+        let mut reporter = StatusReporter::new(config, false);
+        let start = Instant::now();
+        let busy = status("api", &["Coder"]);
+
+        assert_eq!(reporter.update(&busy, start), "");
+        assert_eq!(reporter.update(&busy, start + Duration::from_secs(5)), "");
+        assert_eq!(
+            reporter.update(&busy, start + Duration::from_secs(10)),
+            "\x1b]9;4;3;0\x07"
+        );
+        assert_eq!(reporter.update(&busy, start + Duration::from_secs(20)), "");
+
+        let done = reporter.update(&status("api", &[]), start + Duration::from_secs(75));
+        assert_eq!(
+            done,
+            "\x1b]9;4;0;0\x07\x1b]9;axiom: agents finished after 1m 15s\x07"
+        );
+
+        // Short runs stay quiet
+        let start = start + Duration::from_secs(100);
+        reporter.update(&busy, start);
+        assert_eq!(
+            reporter.update(&status("api", &[]), start + Duration::from_secs(3)),
+            ""
+        );
+    }
+}
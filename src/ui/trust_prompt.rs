@@ -0,0 +1,99 @@
+//! Trust prompt shown when a workspace is opened for the first time
+//!
+//! Until the user answers, the workspace runs restricted: agents can't run
+//! shell commands on their own and CLI agents can't be started.
+
+use crate::state::WorkspaceId;
+use crate::ui::theme::theme;
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::path::PathBuf;
+
+/// Trust prompt modal state
+pub struct TrustPrompt {
+    /// Workspace being asked about
+    pub workspace_id: WorkspaceId,
+
+    /// Workspace name for the title
+    workspace_name: String,
+
+    /// Workspace root
+    path: PathBuf,
+}
+
+impl TrustPrompt {
+    pub fn new(
+        workspace_id: WorkspaceId,
+        workspace_name: impl Into<String>,
+        path: PathBuf,
+    ) -> Self {
+        Self {
+            workspace_id,
+            workspace_name: workspace_name.into(),
+            path,
+        }
+    }
+
+    /// Render the prompt centered in `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let modal_width = 64.min(area.width);
+        let modal_height = 12.min(area.height);
+        let modal_area = Rect::new(
+            area.width.saturating_sub(modal_width) / 2,
+            area.height.saturating_sub(modal_height) / 2,
+            modal_width,
+            modal_height,
+        );
+
+        frame.render_widget(Clear, modal_area);
+
+        let t = theme();
+        let block = Block::default()
+            .title(format!(" Trust {}? ", self.workspace_name))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.status_warning))
+            .style(Style::default().bg(t.bg_modal));
+
+        let key = |k: &'static str| {
+            Span::styled(
+                k,
+                Style::default()
+                    .fg(t.accent_primary)
+                    .add_modifier(Modifier::BOLD),
+            )
+        };
+        let text = vec![
+            Line::from(Span::styled(
+                self.path.display().to_string(),
+                Style::default().fg(t.text_secondary),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Files in this workspace may make agents run arbitrary commands. \
+                 Until you trust it, agents won't run shell commands on their own \
+                 and CLI agents are disabled.",
+                Style::default().fg(t.text_primary),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                key("y"),
+                Span::raw(" trust   "),
+                key("n"),
+                Span::raw(" keep restricted   "),
+                key("Esc"),
+                Span::raw(" decide later"),
+            ]),
+        ];
+
+        let paragraph = Paragraph::new(text)
+            .block(block)
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Left);
+        frame.render_widget(paragraph, modal_area);
+    }
+}
@@ -1,7 +1,12 @@
 //! Workspace selector modal for managing and switching workspaces
 
+use crate::config::DirectoryPickerConfig;
 use crate::state::{WorkspaceId, WorkspaceView, WorkspaceType};
+use axiom_core::{filter_workspaces, fuzzy_score, DiscoveredProject};
+use crate::ui::controls::{hit, render_buttons, tail, Button, ClickTracker};
+use crate::ui::directory_picker::{DirectoryPicker, PickerAction};
 use crate::ui::theme::theme;
+use crossterm::event::KeyEvent;
 use ratatui::{
     layout::{Alignment, Rect},
     style::{Modifier, Style},
@@ -10,6 +15,7 @@ use ratatui::{
     Frame,
 };
 use std::path::PathBuf;
+use std::time::Instant;
 
 /// Mode of the workspace selector
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -18,21 +24,12 @@ pub enum SelectorMode {
     List,
     /// Creating a new workspace
     CreateNew,
-    /// Confirming deletion
+    /// Confirming deletion (which archives the workspace)
     ConfirmDelete,
     /// Browsing folders to select a path
     BrowseFolders,
-}
-
-/// Entry in the folder browser
-#[derive(Debug, Clone)]
-pub struct FolderEntry {
-    /// Display name
-    pub name: String,
-    /// Full path
-    pub path: PathBuf,
-    /// Whether this is a directory (always true for folder browser)
-    pub is_parent: bool,
+    /// Repairing a workspace whose directory went missing
+    Repair,
 }
 
 /// Action returned from workspace selector interactions
@@ -42,22 +39,54 @@ pub enum WorkspaceSelectorAction {
     Select(WorkspaceId),
     /// Create a new workspace
     Create { name: String, path: std::path::PathBuf },
-    /// Delete a workspace
-    Delete(WorkspaceId),
+    /// Point a broken workspace at a new directory
+    Relocate { id: WorkspaceId, path: PathBuf },
+    /// Hide a workspace from the list, keeping its entry and data
+    Archive(WorkspaceId),
     /// Cancel/close the modal
     Cancel,
     /// No action (continue interaction)
     None,
 }
 
+/// Clickable buttons in the modal's help line
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SelectorButton {
+    Select,
+    Delete,
+    Close,
+    Create,
+    Browse,
+    Back,
+    ConfirmYes,
+    ConfirmNo,
+    Relocate,
+    Archive,
+}
+
 /// Workspace selector modal state
 pub struct WorkspaceSelectorModal {
-    /// List of workspaces to display
+    /// All workspaces, most recently accessed first
     workspaces: Vec<WorkspaceView>,
 
-    /// Currently selected index
+    /// Workspaces matching the search query, best match first
+    filtered: Vec<WorkspaceView>,
+
+    /// Unregistered projects found under the discovery roots
+    discovered: Vec<DiscoveredProject>,
+
+    /// Discovered projects matching the search query
+    filtered_discovered: Vec<DiscoveredProject>,
+
+    /// Search-as-you-type query
+    query: String,
+
+    /// Currently selected index (into `filtered`)
     selected_index: usize,
 
+    /// First visible row of the list
+    list_scroll: usize,
+
     /// Current mode (public for external mode checks)
     pub mode: SelectorMode,
 
@@ -78,17 +107,23 @@ pub struct WorkspaceSelectorModal {
 
     /// Hit testing
     modal_area: Option<Rect>,
-    row_areas: Vec<Rect>,
-
-    // Folder browser state
-    /// Current directory being browsed
-    folder_browser_path: PathBuf,
-    /// Directories in current path
-    folder_entries: Vec<FolderEntry>,
-    /// Selected folder index
-    folder_selected: usize,
-    /// Scroll offset for folder list
-    folder_scroll: usize,
+    /// Visible list rows with the index they stand for
+    row_areas: Vec<(usize, Rect)>,
+    button_areas: Vec<(SelectorButton, Rect)>,
+    /// Name and path fields of the create form
+    field_areas: [Option<Rect>; 2],
+
+    /// Row under the mouse pointer, and the mode it was hovered in
+    hover: Option<(SelectorMode, usize)>,
+    /// Double-click detection on workspace rows
+    clicks: ClickTracker<usize>,
+
+    /// Folder browser (in `BrowseFolders` mode)
+    folder_picker: Option<DirectoryPicker>,
+    /// Mode the folder browser returns to (CreateNew or Repair)
+    browse_return: SelectorMode,
+    /// Bookmarks and listing preferences for the folder browser
+    picker_prefs: DirectoryPickerConfig,
 }
 
 impl WorkspaceSelectorModal {
@@ -96,7 +131,12 @@ impl WorkspaceSelectorModal {
     pub fn new() -> Self {
         Self {
             workspaces: Vec::new(),
+            filtered: Vec::new(),
+            discovered: Vec::new(),
+            filtered_discovered: Vec::new(),
+            query: String::new(),
             selected_index: 0,
+            list_scroll: 0,
             mode: SelectorMode::List,
             new_workspace_name: String::new(),
             new_workspace_path: String::new(),
@@ -105,39 +145,120 @@ impl WorkspaceSelectorModal {
             active_workspace_id: None,
             modal_area: None,
             row_areas: Vec::new(),
-            folder_browser_path: std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")),
-            folder_entries: Vec::new(),
-            folder_selected: 0,
-            folder_scroll: 0,
+            button_areas: Vec::new(),
+            field_areas: [None, None],
+            hover: None,
+            clicks: ClickTracker::new(),
+            folder_picker: None,
+            browse_return: SelectorMode::CreateNew,
+            picker_prefs: DirectoryPickerConfig::default(),
         }
     }
 
+    /// Set folder browser preferences (from config)
+    pub fn set_picker_prefs(&mut self, prefs: DirectoryPickerConfig) {
+        self.picker_prefs = prefs;
+    }
+
+    /// Folder browser preferences, if changed since the last call (for persisting)
+    pub fn take_picker_prefs_change(&mut self) -> Option<DirectoryPickerConfig> {
+        let prefs = self.folder_picker.as_mut()?.take_prefs_change()?;
+        self.picker_prefs = prefs.clone();
+        Some(prefs)
+    }
+
     /// Set the list of workspaces
     pub fn set_workspaces(&mut self, workspaces: Vec<WorkspaceView>, active_id: Option<WorkspaceId>) {
         self.workspaces = workspaces;
         self.active_workspace_id = active_id;
+        self.query.clear();
+        self.filtered = self.workspaces.clone();
+        self.discovered.clear();
+        self.filtered_discovered.clear();
         self.selected_index = 0;
+        self.list_scroll = 0;
         self.mode = SelectorMode::List;
 
         // Try to select the active workspace
         if let Some(active) = active_id {
-            if let Some(idx) = self.workspaces.iter().position(|w| w.id == active) {
+            if let Some(idx) = self.filtered.iter().position(|w| w.id == active) {
                 self.selected_index = idx;
             }
         }
     }
 
+    /// Set the unregistered projects listed under "Discovered"
+    pub fn set_discovered(&mut self, projects: Vec<DiscoveredProject>) {
+        self.discovered = projects;
+        self.filtered_discovered = Self::filter_discovered(&self.discovered, &self.query);
+    }
+
     /// Get the currently selected workspace
     pub fn selected_workspace(&self) -> Option<&WorkspaceView> {
-        self.workspaces.get(self.selected_index)
+        self.filtered.get(self.selected_index)
+    }
+
+    /// Discovered project at list index `idx` (they follow the workspaces)
+    fn discovered_at(&self, idx: usize) -> Option<&DiscoveredProject> {
+        idx.checked_sub(self.filtered.len())
+            .and_then(|i| self.filtered_discovered.get(i))
+    }
+
+    /// Rows in the list: workspaces, discovered projects and "Create New"
+    fn row_count(&self) -> usize {
+        self.filtered.len() + self.filtered_discovered.len() + 1
+    }
+
+    /// Current search query
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Type a character into the search query
+    pub fn search_push(&mut self, c: char) {
+        self.query.push(c);
+        self.refilter();
+    }
+
+    /// Delete the last character of the search query
+    pub fn search_pop(&mut self) {
+        if self.query.pop().is_some() {
+            self.refilter();
+        }
+    }
+
+    /// Re-rank workspaces for the query and select the best match
+    fn refilter(&mut self) {
+        self.filtered = filter_workspaces(self.workspaces.clone(), &self.query);
+        self.filtered_discovered = Self::filter_discovered(&self.discovered, &self.query);
+        self.selected_index = 0;
+        self.list_scroll = 0;
+        self.hover = None;
+    }
+
+    /// Discovered projects matching `query`, ranked like workspaces
+    fn filter_discovered(projects: &[DiscoveredProject], query: &str) -> Vec<DiscoveredProject> {
+        let query = query.trim();
+        if query.is_empty() {
+            return projects.to_vec();
+        }
+        let mut scored: Vec<(i64, &DiscoveredProject)> = projects
+            .iter()
+            .filter_map(|p| {
+                let name = fuzzy_score(query, &p.name).map(|s| s + 10);
+                let path = fuzzy_score(query, &p.path.to_string_lossy());
+                name.max(path).map(|score| (score, p))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, p)| p.clone()).collect()
     }
 
     /// Navigate up
     pub fn up(&mut self) {
         match self.mode {
             SelectorMode::List => {
-                // +1 for "Create New" option at the end
-                let total = self.workspaces.len() + 1;
+                let total = self.row_count();
                 if self.selected_index > 0 {
                     self.selected_index -= 1;
                 } else {
@@ -149,14 +270,10 @@ impl WorkspaceSelectorModal {
                 self.edit_field = if self.edit_field == 0 { 1 } else { 0 };
                 self.update_cursor_for_field();
             }
-            SelectorMode::ConfirmDelete => {}
+            SelectorMode::ConfirmDelete | SelectorMode::Repair => {}
             SelectorMode::BrowseFolders => {
-                // +1 for "Select This Folder" option at the end
-                let total = self.folder_entries.len() + 1;
-                if self.folder_selected > 0 {
-                    self.folder_selected -= 1;
-                } else {
-                    self.folder_selected = total.saturating_sub(1);
+                if let Some(picker) = &mut self.folder_picker {
+                    picker.up();
                 }
             }
         }
@@ -166,7 +283,7 @@ impl WorkspaceSelectorModal {
     pub fn down(&mut self) {
         match self.mode {
             SelectorMode::List => {
-                let total = self.workspaces.len() + 1;
+                let total = self.row_count();
                 if self.selected_index < total.saturating_sub(1) {
                     self.selected_index += 1;
                 } else {
@@ -177,13 +294,10 @@ impl WorkspaceSelectorModal {
                 self.edit_field = if self.edit_field == 0 { 1 } else { 0 };
                 self.update_cursor_for_field();
             }
-            SelectorMode::ConfirmDelete => {}
+            SelectorMode::ConfirmDelete | SelectorMode::Repair => {}
             SelectorMode::BrowseFolders => {
-                let total = self.folder_entries.len() + 1;
-                if self.folder_selected < total.saturating_sub(1) {
-                    self.folder_selected += 1;
-                } else {
-                    self.folder_selected = 0;
+                if let Some(picker) = &mut self.folder_picker {
+                    picker.down();
                 }
             }
         }
@@ -202,10 +316,21 @@ impl WorkspaceSelectorModal {
     pub fn enter(&mut self) -> WorkspaceSelectorAction {
         match self.mode {
             SelectorMode::List => {
-                if self.selected_index < self.workspaces.len() {
-                    // Select workspace
-                    let ws = &self.workspaces[self.selected_index];
-                    WorkspaceSelectorAction::Select(ws.id)
+                if self.selected_index < self.filtered.len() {
+                    // Select workspace, or offer repair if its directory is gone
+                    let ws = &self.filtered[self.selected_index];
+                    if ws.broken {
+                        self.mode = SelectorMode::Repair;
+                        WorkspaceSelectorAction::None
+                    } else {
+                        WorkspaceSelectorAction::Select(ws.id)
+                    }
+                } else if let Some(project) = self.discovered_at(self.selected_index) {
+                    // Register a discovered project in one keystroke
+                    WorkspaceSelectorAction::Create {
+                        name: project.name.clone(),
+                        path: project.path.clone(),
+                    }
                 } else {
                     // "Create New" option
                     self.mode = SelectorMode::CreateNew;
@@ -228,17 +353,21 @@ impl WorkspaceSelectorModal {
                 }
             }
             SelectorMode::ConfirmDelete => {
-                if let Some(ws) = self.workspaces.get(self.selected_index) {
+                if let Some(ws) = self.filtered.get(self.selected_index) {
                     let id = ws.id;
                     self.mode = SelectorMode::List;
-                    WorkspaceSelectorAction::Delete(id)
+                    WorkspaceSelectorAction::Archive(id)
                 } else {
                     self.mode = SelectorMode::List;
                     WorkspaceSelectorAction::None
                 }
             }
             SelectorMode::BrowseFolders => {
-                // Handled by folder_enter() instead
+                // Handled by the folder picker instead
+                WorkspaceSelectorAction::None
+            }
+            SelectorMode::Repair => {
+                self.relocate();
                 WorkspaceSelectorAction::None
             }
         }
@@ -247,14 +376,20 @@ impl WorkspaceSelectorModal {
     /// Handle Escape key
     pub fn escape(&mut self) -> WorkspaceSelectorAction {
         match self.mode {
+            // First Esc clears the search, the next one closes
+            SelectorMode::List if !self.query.is_empty() => {
+                self.query.clear();
+                self.refilter();
+                WorkspaceSelectorAction::None
+            }
             SelectorMode::List => WorkspaceSelectorAction::Cancel,
-            SelectorMode::CreateNew | SelectorMode::ConfirmDelete => {
+            SelectorMode::CreateNew | SelectorMode::ConfirmDelete | SelectorMode::Repair => {
                 self.mode = SelectorMode::List;
                 WorkspaceSelectorAction::None
             }
             SelectorMode::BrowseFolders => {
-                // Return to CreateNew mode without selecting
-                self.mode = SelectorMode::CreateNew;
+                // Return to the form without selecting
+                self.cancel_browse();
                 WorkspaceSelectorAction::None
             }
         }
@@ -262,11 +397,39 @@ impl WorkspaceSelectorModal {
 
     /// Handle Delete key
     pub fn delete(&mut self) {
-        if self.mode == SelectorMode::List && self.selected_index < self.workspaces.len() {
+        if self.mode == SelectorMode::List && self.selected_index < self.filtered.len() {
             self.mode = SelectorMode::ConfirmDelete;
         }
     }
 
+    /// Repair: browse for the workspace's new location
+    pub fn relocate(&mut self) {
+        if self.mode != SelectorMode::Repair {
+            return;
+        }
+        // Start from the closest ancestor of the old path that still exists
+        let start = self
+            .selected_workspace()
+            .and_then(|ws| ws.path.ancestors().find(|p| p.is_dir()).map(PathBuf::from))
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("/"));
+        self.folder_picker = Some(DirectoryPicker::new(start, &self.picker_prefs));
+        self.browse_return = SelectorMode::Repair;
+        self.mode = SelectorMode::BrowseFolders;
+    }
+
+    /// Repair: archive the broken workspace
+    pub fn archive(&mut self) -> WorkspaceSelectorAction {
+        if self.mode != SelectorMode::Repair {
+            return WorkspaceSelectorAction::None;
+        }
+        self.mode = SelectorMode::List;
+        match self.selected_workspace() {
+            Some(ws) => WorkspaceSelectorAction::Archive(ws.id),
+            None => WorkspaceSelectorAction::None,
+        }
+    }
+
     /// Handle 'y' key (confirm delete)
     pub fn confirm(&mut self) -> WorkspaceSelectorAction {
         if self.mode == SelectorMode::ConfirmDelete {
@@ -335,95 +498,53 @@ impl WorkspaceSelectorModal {
 
     /// Start folder browsing mode
     pub fn start_folder_browse(&mut self) {
-        // If path field has content, try to use it as starting point
-        if !self.new_workspace_path.is_empty() {
-            let path = PathBuf::from(&self.new_workspace_path);
-            if path.is_dir() {
-                self.folder_browser_path = path;
-            }
-        }
+        // Start from the path field if it names a directory
+        let start = Some(PathBuf::from(&self.new_workspace_path))
+            .filter(|p| !self.new_workspace_path.is_empty() && p.is_dir())
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("/"));
+        self.folder_picker = Some(DirectoryPicker::new(start, &self.picker_prefs));
+        self.browse_return = SelectorMode::CreateNew;
         self.mode = SelectorMode::BrowseFolders;
-        self.folder_selected = 0;
-        self.folder_scroll = 0;
-        self.refresh_folder_entries();
-    }
-
-    /// Refresh the folder entries list
-    fn refresh_folder_entries(&mut self) {
-        self.folder_entries.clear();
-
-        // Add parent directory entry if not at root
-        if let Some(parent) = self.folder_browser_path.parent() {
-            self.folder_entries.push(FolderEntry {
-                name: "..".to_string(),
-                path: parent.to_path_buf(),
-                is_parent: true,
-            });
-        }
-
-        // List subdirectories (sorted, no hidden files)
-        if let Ok(entries) = std::fs::read_dir(&self.folder_browser_path) {
-            let mut dirs: Vec<_> = entries
-                .filter_map(|e| e.ok())
-                .filter(|e| e.path().is_dir())
-                .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
-                .collect();
-            dirs.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
-
-            for entry in dirs {
-                self.folder_entries.push(FolderEntry {
-                    name: entry.file_name().to_string_lossy().to_string(),
-                    path: entry.path(),
-                    is_parent: false,
-                });
-            }
-        }
-
-        // Ensure selected index is valid
-        let total = self.folder_entries.len() + 1; // +1 for "Select This Folder"
-        if self.folder_selected >= total {
-            self.folder_selected = 0;
-        }
     }
 
-    /// Handle Enter key in folder browser
-    /// Returns true if a folder was selected (should return to CreateNew)
-    pub fn folder_enter(&mut self) -> bool {
-        // Check if "Select This Folder" is selected (last option)
-        if self.folder_selected == self.folder_entries.len() {
-            // Select current folder
-            self.new_workspace_path = self.folder_browser_path.to_string_lossy().to_string();
-            self.cursor_pos = self.new_workspace_path.len();
-            self.mode = SelectorMode::CreateNew;
-            self.edit_field = 1; // Stay on path field
-            return true;
-        }
-
-        // Navigate into selected folder
-        if let Some(entry) = self.folder_entries.get(self.folder_selected) {
-            if entry.path.is_dir() {
-                self.folder_browser_path = entry.path.clone();
-                self.folder_selected = 0;
-                self.folder_scroll = 0;
-                self.refresh_folder_entries();
-            }
+    /// Forward a key to the folder browser
+    pub fn handle_picker_key(&mut self, key: KeyEvent) -> WorkspaceSelectorAction {
+        match self.folder_picker.as_mut().map(|p| p.handle_key(key)) {
+            Some(action) => self.apply_picker_action(action),
+            None => WorkspaceSelectorAction::None,
         }
-        false
     }
 
-    /// Go to parent directory
-    pub fn folder_parent(&mut self) {
-        if let Some(parent) = self.folder_browser_path.parent() {
-            self.folder_browser_path = parent.to_path_buf();
-            self.folder_selected = 0;
-            self.folder_scroll = 0;
-            self.refresh_folder_entries();
+    /// Leave the folder browser when a folder was chosen or browsing cancelled
+    fn apply_picker_action(&mut self, action: PickerAction) -> WorkspaceSelectorAction {
+        match action {
+            PickerAction::None => {}
+            PickerAction::Selected(path) if self.browse_return == SelectorMode::Repair => {
+                self.mode = SelectorMode::List;
+                if let Some(ws) = self.selected_workspace() {
+                    return WorkspaceSelectorAction::Relocate { id: ws.id, path };
+                }
+            }
+            PickerAction::Selected(path) => {
+                self.new_workspace_path = path.to_string_lossy().to_string();
+                self.cursor_pos = self.new_workspace_path.len();
+                if self.new_workspace_name.is_empty() {
+                    if let Some(name) = path.file_name() {
+                        self.new_workspace_name = name.to_string_lossy().to_string();
+                    }
+                }
+                self.edit_field = 1; // Stay on path field
+                self.mode = SelectorMode::CreateNew;
+            }
+            PickerAction::Cancelled => self.cancel_browse(),
         }
+        WorkspaceSelectorAction::None
     }
 
-    /// Cancel folder browsing and return to CreateNew
+    /// Cancel folder browsing and return to the form that opened it
     pub fn cancel_browse(&mut self) {
-        self.mode = SelectorMode::CreateNew;
+        self.mode = self.browse_return;
     }
 
     /// Check if point is inside modal
@@ -434,20 +555,124 @@ impl WorkspaceSelectorModal {
     }
 
     /// Handle mouse click
+    ///
+    /// A single click selects a row, a double-click opens it. Help-line
+    /// buttons and create-form fields are clickable too.
     pub fn handle_click(&mut self, x: u16, y: u16) -> WorkspaceSelectorAction {
-        if self.mode != SelectorMode::List {
-            return WorkspaceSelectorAction::None;
+        self.click_at(x, y, Instant::now())
+    }
+
+    fn click_at(&mut self, x: u16, y: u16, now: Instant) -> WorkspaceSelectorAction {
+        if let Some(button) = self
+            .button_areas
+            .iter()
+            .find(|(_, area)| hit(area, x, y))
+            .map(|(button, _)| *button)
+        {
+            self.clicks.reset();
+            return self.press(button);
         }
 
-        for (idx, area) in self.row_areas.iter().enumerate() {
-            if x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height {
+        match self.mode {
+            SelectorMode::List => {
+                let Some(idx) = self.row_index_at(x, y) else {
+                    return WorkspaceSelectorAction::None;
+                };
                 self.selected_index = idx;
-                return self.enter();
+                if self.clicks.click(idx, now) {
+                    return self.enter();
+                }
+            }
+            SelectorMode::BrowseFolders => {
+                if let Some(action) = self.folder_picker.as_mut().map(|p| p.handle_click(x, y)) {
+                    return self.apply_picker_action(action);
+                }
+            }
+            SelectorMode::CreateNew => {
+                if let Some(field) = self.field_areas.iter().position(|a| a.is_some_and(|a| hit(&a, x, y))) {
+                    self.edit_field = field;
+                    let text = if field == 0 {
+                        &self.new_workspace_name
+                    } else {
+                        &self.new_workspace_path
+                    };
+                    // Field text starts after "Name: [" / "Path: ["
+                    let area = self.field_areas[field].unwrap_or_default();
+                    let mut pos = (x.saturating_sub(area.x + 1) as usize).min(text.len());
+                    while !text.is_char_boundary(pos) {
+                        pos -= 1;
+                    }
+                    self.cursor_pos = pos;
+                }
             }
+            SelectorMode::ConfirmDelete | SelectorMode::Repair => {}
         }
         WorkspaceSelectorAction::None
     }
 
+    /// Activate a help-line button
+    fn press(&mut self, button: SelectorButton) -> WorkspaceSelectorAction {
+        match button {
+            SelectorButton::Select | SelectorButton::Create => self.enter(),
+            SelectorButton::Delete => {
+                self.delete();
+                WorkspaceSelectorAction::None
+            }
+            SelectorButton::Close | SelectorButton::Back => self.escape(),
+            SelectorButton::Browse => {
+                self.start_folder_browse();
+                WorkspaceSelectorAction::None
+            }
+            SelectorButton::ConfirmYes => self.confirm(),
+            SelectorButton::ConfirmNo => {
+                self.deny();
+                WorkspaceSelectorAction::None
+            }
+            SelectorButton::Relocate => {
+                self.relocate();
+                WorkspaceSelectorAction::None
+            }
+            SelectorButton::Archive => self.archive(),
+        }
+    }
+
+    /// Handle mouse wheel
+    pub fn handle_scroll(&mut self, down: bool) {
+        if !matches!(self.mode, SelectorMode::List | SelectorMode::BrowseFolders) {
+            return;
+        }
+        if down {
+            self.down();
+        } else {
+            self.up();
+        }
+    }
+
+    /// Track the row under the mouse pointer for hover highlighting
+    pub fn handle_hover(&mut self, x: u16, y: u16) {
+        if let (SelectorMode::BrowseFolders, Some(picker)) = (self.mode, &mut self.folder_picker) {
+            picker.handle_hover(x, y);
+        }
+        let row = match self.mode {
+            SelectorMode::List => self.row_index_at(x, y),
+            _ => None,
+        };
+        self.hover = row.map(|row| (self.mode, row));
+    }
+
+    /// List index of the row at (x, y)
+    fn row_index_at(&self, x: u16, y: u16) -> Option<usize> {
+        self.row_areas
+            .iter()
+            .find(|(_, area)| hit(area, x, y))
+            .map(|(idx, _)| *idx)
+    }
+
+    /// Whether `row` of the current mode's list is under the mouse pointer
+    fn is_hovered(&self, row: usize) -> bool {
+        self.hover == Some((self.mode, row))
+    }
+
     /// Get type icon for workspace
     fn type_icon(ws_type: &WorkspaceType) -> &'static str {
         match ws_type {
@@ -468,6 +693,8 @@ impl WorkspaceSelectorModal {
 
         let modal_area = Rect::new(x, y, modal_width, modal_height);
         self.modal_area = Some(modal_area);
+        self.button_areas.clear();
+        self.field_areas = [None, None];
 
         // Clear background
         frame.render_widget(Clear, modal_area);
@@ -476,8 +703,9 @@ impl WorkspaceSelectorModal {
         let title = match self.mode {
             SelectorMode::List => " Workspaces ",
             SelectorMode::CreateNew => " Create Workspace ",
-            SelectorMode::ConfirmDelete => " Delete Workspace? ",
+            SelectorMode::ConfirmDelete => " Archive Workspace? ",
             SelectorMode::BrowseFolders => " Select Folder ",
+            SelectorMode::Repair => " Repair Workspace ",
         };
 
         let block = Block::default()
@@ -494,6 +722,7 @@ impl WorkspaceSelectorModal {
             SelectorMode::CreateNew => self.render_create_form(frame, inner),
             SelectorMode::ConfirmDelete => self.render_delete_confirm(frame, inner),
             SelectorMode::BrowseFolders => self.render_folder_browser(frame, inner),
+            SelectorMode::Repair => self.render_repair(frame, inner),
         }
     }
 
@@ -501,46 +730,76 @@ impl WorkspaceSelectorModal {
     fn render_list(&mut self, frame: &mut Frame, area: Rect) {
         self.row_areas.clear();
 
-        let row_height = 2u16;
-        let mut current_y = area.y;
+        // Search line
+        let t = theme();
+        let matches = self.filtered.len() + self.filtered_discovered.len();
+        let search = if self.query.is_empty() {
+            Line::from(Span::styled("  Type to search...", Style::default().fg(t.text_muted)))
+        } else {
+            Line::from(vec![
+                Span::styled("  Search: ", Style::default().fg(t.text_secondary)),
+                Span::styled(format!("{}█", self.query), Style::default().fg(t.text_primary)),
+                Span::styled(
+                    format!("  ({} match{})", matches, if matches == 1 { "" } else { "es" }),
+                    Style::default().fg(t.text_muted),
+                ),
+            ])
+        };
+        drop(t);
+        frame.render_widget(Paragraph::new(search), Rect::new(area.x, area.y, area.width, 1));
+
+        // Rows between the search line and the help line; the first
+        // discovered project carries a section header above it
+        let list_top = area.y + 2;
+        let list_height = area.height.saturating_sub(4).max(2);
+        let total = self.row_count();
+        let first_discovered = (!self.filtered_discovered.is_empty()).then_some(self.filtered.len());
+        let row_height = |idx: usize| if Some(idx) == first_discovered { 3u16 } else { 2 };
+
+        // Keep the selection in view
+        self.list_scroll = self.list_scroll.min(self.selected_index);
+        while self.list_scroll < self.selected_index
+            && (self.list_scroll..=self.selected_index).map(row_height).sum::<u16>() > list_height
+        {
+            self.list_scroll += 1;
+        }
 
-        // Render workspaces
-        for (idx, ws) in self.workspaces.iter().enumerate() {
-            if current_y + row_height > area.y + area.height - 2 {
+        let mut y = list_top;
+        for idx in self.list_scroll..total {
+            let height = row_height(idx);
+            if y + height > list_top + list_height {
                 break;
             }
-
-            let row_area = Rect::new(area.x, current_y, area.width, row_height);
-            self.row_areas.push(row_area);
+            if height == 3 {
+                self.render_discovered_header(frame, Rect::new(area.x, y, area.width, 1));
+            }
+            let row_area = Rect::new(area.x, y + height - 2, area.width, 2);
+            self.row_areas.push((idx, row_area));
+            y += height;
 
             let is_selected = idx == self.selected_index;
-            let is_active = Some(ws.id) == self.active_workspace_id;
-
-            self.render_workspace_row(frame, row_area, ws, is_selected, is_active);
-            current_y += row_height;
-        }
-
-        // "Create New" option
-        if current_y + row_height <= area.y + area.height {
-            let row_area = Rect::new(area.x, current_y, area.width, row_height);
-            self.row_areas.push(row_area);
-
-            let is_selected = self.selected_index == self.workspaces.len();
-            self.render_create_new_option(frame, row_area, is_selected);
+            let is_hovered = self.is_hovered(idx);
+            if let Some(ws) = self.filtered.get(idx) {
+                let is_active = Some(ws.id) == self.active_workspace_id;
+                self.render_workspace_row(frame, row_area, ws, is_selected, is_hovered, is_active);
+            } else if let Some(project) = self.discovered_at(idx) {
+                self.render_discovered_row(frame, row_area, project, is_selected, is_hovered);
+            } else {
+                self.render_create_new_option(frame, row_area, is_selected, is_hovered);
+            }
         }
 
-        // Help text at bottom
+        // Help text at bottom (clickable)
         let t = theme();
-        let help = Line::from(vec![
-            Span::styled("Enter", Style::default().fg(t.accent_primary)),
-            Span::raw(": select  "),
-            Span::styled("Del", Style::default().fg(t.status_error)),
-            Span::raw(": delete  "),
-            Span::styled("Esc", Style::default().fg(t.text_secondary)),
-            Span::raw(": close"),
-        ]);
+        let select_label = if self.discovered_at(self.selected_index).is_some() { "register" } else { "select" };
+        let buttons = [
+            Button::new(SelectorButton::Select, "Enter", select_label, Style::default().fg(t.accent_primary)),
+            Button::new(SelectorButton::Delete, "Del", "archive", Style::default().fg(t.status_error)),
+            Button::new(SelectorButton::Close, "Esc", "close", Style::default().fg(t.text_secondary)),
+        ];
+        drop(t);
         let help_area = Rect::new(area.x, area.y + area.height - 1, area.width, 1);
-        frame.render_widget(Paragraph::new(help).alignment(Alignment::Center), help_area);
+        render_buttons(frame, help_area, &buttons, &mut self.button_areas);
     }
 
     /// Render a single workspace row
@@ -550,22 +809,31 @@ impl WorkspaceSelectorModal {
         area: Rect,
         ws: &WorkspaceView,
         selected: bool,
+        hovered: bool,
         active: bool,
     ) {
         let t = theme();
         let bg_color = if selected {
             t.bg_selection
+        } else if hovered {
+            t.bg_hover
         } else {
             t.bg_modal
         };
 
         let icon = Self::type_icon(&ws.workspace_type);
         let active_marker = if active { " *" } else { "  " };
-        let exists_marker = if ws.exists { "" } else { " (missing)" };
+        let broken_marker = if ws.broken { " ⚠ broken" } else { "" };
+        let dirty_marker = if ws.git_dirty { " ● dirty" } else { "" };
+        let agents_marker = if ws.running_agents > 0 {
+            format!(" ⚙ {} running", ws.running_agents)
+        } else {
+            String::new()
+        };
 
         let name_style = if selected {
             Style::default().fg(t.text_primary).add_modifier(Modifier::BOLD)
-        } else if !ws.exists {
+        } else if ws.broken {
             Style::default().fg(t.text_muted)
         } else {
             Style::default().fg(t.text_secondary)
@@ -578,7 +846,9 @@ impl WorkspaceSelectorModal {
             Span::raw(format!("  {} ", icon)),
             Span::styled(&ws.name, name_style),
             Span::styled(active_marker, active_style),
-            Span::styled(exists_marker, Style::default().fg(t.status_error)),
+            Span::styled(broken_marker, Style::default().fg(t.status_error)),
+            Span::styled(dirty_marker, Style::default().fg(t.status_warning)),
+            Span::styled(agents_marker, Style::default().fg(t.accent_primary)),
         ]);
 
         let path_display = ws.path.to_string_lossy();
@@ -598,14 +868,68 @@ impl WorkspaceSelectorModal {
         frame.render_widget(paragraph, area);
     }
 
+    /// Render the "Discovered" section header
+    fn render_discovered_header(&self, frame: &mut Frame, area: Rect) {
+        let t = theme();
+        let line = Line::from(vec![
+            Span::styled("  Discovered ", Style::default().fg(t.text_secondary).add_modifier(Modifier::BOLD)),
+            Span::styled("(Enter to register)", Style::default().fg(t.text_muted)),
+        ]);
+        frame.render_widget(Paragraph::new(line).style(Style::default().bg(t.bg_modal)), area);
+    }
+
+    /// Render an unregistered project found by discovery
+    fn render_discovered_row(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        project: &DiscoveredProject,
+        selected: bool,
+        hovered: bool,
+    ) {
+        let t = theme();
+        let bg_color = if selected {
+            t.bg_selection
+        } else if hovered {
+            t.bg_hover
+        } else {
+            t.bg_modal
+        };
+        let name_style = if selected {
+            Style::default().fg(t.text_primary).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(t.text_secondary)
+        };
+
+        let line1 = Line::from(vec![
+            Span::styled("  + ", Style::default().fg(t.accent_primary)),
+            Span::styled(&project.name, name_style),
+            Span::styled(format!(" [{}]", project.kind), Style::default().fg(t.text_muted)),
+        ]);
+        let line2 = Line::from(vec![
+            Span::raw("     "),
+            Span::styled(
+                tail(&project.path.to_string_lossy(), area.width.saturating_sub(6) as usize),
+                Style::default().fg(t.text_muted),
+            ),
+        ]);
+
+        frame.render_widget(
+            Paragraph::new(vec![line1, line2]).style(Style::default().bg(bg_color)),
+            area,
+        );
+    }
+
     /// Render "Create New" option
-    fn render_create_new_option(&self, frame: &mut Frame, area: Rect, selected: bool) {
+    fn render_create_new_option(&self, frame: &mut Frame, area: Rect, selected: bool, hovered: bool) {
         let t = theme();
         let style = if selected {
             Style::default()
                 .fg(t.accent_primary)
                 .bg(t.bg_selection)
                 .add_modifier(Modifier::BOLD)
+        } else if hovered {
+            Style::default().fg(t.accent_primary).bg(t.bg_hover)
         } else {
             Style::default().fg(t.text_secondary).bg(t.bg_modal)
         };
@@ -653,20 +977,31 @@ impl WorkspaceSelectorModal {
                 Span::styled("Path: ", Style::default().fg(t.text_primary)),
                 Span::styled(path_field, path_style),
             ]),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("Enter", Style::default().fg(t.accent_primary)),
-                Span::raw(": create  "),
-                Span::styled("Ctrl+B", Style::default().fg(t.status_success)),
-                Span::raw(": browse  "),
-                Span::styled("Tab", Style::default().fg(t.text_secondary)),
-                Span::raw(": switch  "),
-                Span::styled("Esc", Style::default().fg(t.text_secondary)),
-                Span::raw(": back"),
-            ]),
         ];
 
         frame.render_widget(Paragraph::new(lines), area);
+        // "[" of each field sits right after "Name: " / "Path: "
+        self.field_areas = [
+            Some(Rect::new(area.x + 6, area.y, 32, 1)),
+            Some(Rect::new(area.x + 6, area.y + 2, 32, 1)),
+        ];
+
+        let buttons = [
+            Button::new(SelectorButton::Create, "Enter", "create", Style::default().fg(t.accent_primary)),
+            Button::new(SelectorButton::Browse, "Ctrl+B", "browse", Style::default().fg(t.status_success)),
+            Button::new(SelectorButton::Back, "Esc", "back", Style::default().fg(t.text_secondary)),
+        ];
+        let tab_hint = Line::from(vec![
+            Span::styled("Tab", Style::default().fg(t.text_secondary)),
+            Span::raw(": switch field"),
+        ]);
+        drop(t);
+        if area.height > 5 {
+            let buttons_area = Rect::new(area.x, area.y + 4, area.width, 1);
+            render_buttons(frame, buttons_area, &buttons, &mut self.button_areas);
+            let hint_area = Rect::new(area.x, area.y + 5, area.width, 1);
+            frame.render_widget(Paragraph::new(tab_hint).alignment(Alignment::Center), hint_area);
+        }
 
         // Show cursor
         let cursor_x = area.x + 7 + self.cursor_pos as u16;
@@ -679,9 +1014,9 @@ impl WorkspaceSelectorModal {
     }
 
     /// Render delete confirmation
-    fn render_delete_confirm(&self, frame: &mut Frame, area: Rect) {
+    fn render_delete_confirm(&mut self, frame: &mut Frame, area: Rect) {
         let t = theme();
-        let ws_name = self.workspaces
+        let ws_name = self.filtered
             .get(self.selected_index)
             .map(|w| w.name.as_str())
             .unwrap_or("Unknown");
@@ -689,131 +1024,81 @@ impl WorkspaceSelectorModal {
         let lines = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("Delete workspace: ", Style::default().fg(t.text_primary)),
+                Span::styled("Archive workspace: ", Style::default().fg(t.text_primary)),
                 Span::styled(ws_name, Style::default().fg(t.accent_highlight).add_modifier(Modifier::BOLD)),
                 Span::raw("?"),
             ]),
             Line::from(""),
             Line::from(Span::styled(
-                "(This will not delete any files, only the workspace entry)",
+                "(Sessions and history are kept: /workspace restore, /workspace purge)",
                 Style::default().fg(t.text_muted),
             )),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("y", Style::default().fg(t.status_success)),
-                Span::raw(": yes  "),
-                Span::styled("n", Style::default().fg(t.status_error)),
-                Span::raw(": no  "),
-                Span::styled("Esc", Style::default().fg(t.text_secondary)),
-                Span::raw(": cancel"),
-            ]),
         ];
 
         frame.render_widget(
             Paragraph::new(lines).alignment(Alignment::Center),
             area,
         );
+
+        let buttons = [
+            Button::new(SelectorButton::ConfirmYes, "y", "yes", Style::default().fg(t.status_success)),
+            Button::new(SelectorButton::ConfirmNo, "n", "no", Style::default().fg(t.status_error)),
+            Button::new(SelectorButton::Back, "Esc", "cancel", Style::default().fg(t.text_secondary)),
+        ];
+        drop(t);
+        if area.height > 5 {
+            let buttons_area = Rect::new(area.x, area.y + 5, area.width, 1);
+            render_buttons(frame, buttons_area, &buttons, &mut self.button_areas);
+        }
     }
 
-    /// Render folder browser
-    fn render_folder_browser(&self, frame: &mut Frame, area: Rect) {
+    /// Render the repair options for a broken workspace
+    fn render_repair(&mut self, frame: &mut Frame, area: Rect) {
         let t = theme();
+        let (name, path) = self
+            .selected_workspace()
+            .map(|w| (w.name.clone(), w.path.display().to_string()))
+            .unwrap_or_default();
 
-        // Current path display at top
-        let path_display = self.folder_browser_path.to_string_lossy();
-        let path_truncated: String = if path_display.len() > area.width as usize - 4 {
-            format!("...{}", &path_display[path_display.len().saturating_sub(area.width as usize - 7)..])
-        } else {
-            path_display.to_string()
-        };
-
-        let path_line = Line::from(vec![
-            Span::styled(&path_truncated, Style::default().fg(t.accent_primary)),
-        ]);
-        let path_area = Rect::new(area.x, area.y, area.width, 1);
-        frame.render_widget(Paragraph::new(path_line), path_area);
-
-        // Folder list
-        let list_start_y = area.y + 2;
-        let list_height = area.height.saturating_sub(4); // Leave room for path, separator, help
-
-        let mut current_y = list_start_y;
-
-        // Calculate visible range with scroll
-        let visible_count = list_height as usize;
-        let total_items = self.folder_entries.len() + 1; // +1 for "Select This Folder"
-
-        // Render folder entries
-        for (idx, entry) in self.folder_entries.iter().enumerate() {
-            if current_y >= list_start_y + list_height {
-                break;
-            }
-            if idx < self.folder_scroll {
-                continue;
-            }
-
-            let is_selected = idx == self.folder_selected;
-            let bg_color = if is_selected {
-                t.bg_selection
-            } else {
-                t.bg_modal
-            };
-
-            let icon = if entry.is_parent { " .." } else { " 📁" };
-            let name_style = if is_selected {
-                Style::default().fg(t.text_primary).add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(t.text_secondary)
-            };
-
-            let display_name = if entry.is_parent {
-                "(parent directory)".to_string()
-            } else {
-                format!("{}/", entry.name)
-            };
-
-            let line = Line::from(vec![
-                Span::raw(icon),
-                Span::raw(" "),
-                Span::styled(display_name, name_style),
-            ]);
-
-            let row_area = Rect::new(area.x, current_y, area.width, 1);
-            frame.render_widget(Paragraph::new(line).style(Style::default().bg(bg_color)), row_area);
-            current_y += 1;
-        }
-
-        // "Select This Folder" option
-        if current_y < list_start_y + list_height {
-            let is_selected = self.folder_selected == self.folder_entries.len();
-            let style = if is_selected {
-                Style::default()
-                    .fg(t.status_success)
-                    .bg(t.bg_selection)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(t.status_success).bg(t.bg_modal)
-            };
+        let lines = vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(name, Style::default().fg(t.accent_highlight).add_modifier(Modifier::BOLD)),
+                Span::styled(" can't be opened", Style::default().fg(t.text_primary)),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{} no longer exists", path),
+                Style::default().fg(t.status_error),
+            )),
+            Line::from(Span::styled(
+                "Relocate it to where the folder moved, or archive it",
+                Style::default().fg(t.text_muted),
+            )),
+        ];
 
-            let line = Line::from(vec![
-                Span::styled("  [ Select This Folder ]", style),
-            ]);
+        frame.render_widget(
+            Paragraph::new(lines).alignment(Alignment::Center),
+            area,
+        );
 
-            let row_area = Rect::new(area.x, current_y, area.width, 1);
-            frame.render_widget(Paragraph::new(line).style(style), row_area);
+        let buttons = [
+            Button::new(SelectorButton::Relocate, "r", "relocate", Style::default().fg(t.accent_primary)),
+            Button::new(SelectorButton::Archive, "a", "archive", Style::default().fg(t.status_warning)),
+            Button::new(SelectorButton::Back, "Esc", "back", Style::default().fg(t.text_secondary)),
+        ];
+        drop(t);
+        if area.height > 6 {
+            let buttons_area = Rect::new(area.x, area.y + 6, area.width, 1);
+            render_buttons(frame, buttons_area, &buttons, &mut self.button_areas);
         }
+    }
 
-        // Help text at bottom
-        let help = Line::from(vec![
-            Span::styled("Enter", Style::default().fg(t.accent_primary)),
-            Span::raw(": open/select  "),
-            Span::styled("Backspace", Style::default().fg(t.text_secondary)),
-            Span::raw(": parent  "),
-            Span::styled("Esc", Style::default().fg(t.text_secondary)),
-            Span::raw(": cancel"),
-        ]);
-        let help_area = Rect::new(area.x, area.y + area.height - 1, area.width, 1);
-        frame.render_widget(Paragraph::new(help).alignment(Alignment::Center), help_area);
+    /// Render folder browser
+    fn render_folder_browser(&mut self, frame: &mut Frame, area: Rect) {
+        if let Some(picker) = &mut self.folder_picker {
+            picker.render(frame, area);
+        }
     }
 }
 
@@ -826,6 +1111,7 @@ impl Default for WorkspaceSelectorModal {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
 
     #[test]
     fn test_workspace_selector_new() {
@@ -834,6 +1120,88 @@ mod tests {
         assert_eq!(selector.mode, SelectorMode::List);
     }
 
+    fn views(names: &[&str]) -> Vec<WorkspaceView> {
+        names
+            .iter()
+            .map(|name| {
+                let ws = crate::state::Workspace::new_local(*name, std::path::PathBuf::from("/tmp"));
+                WorkspaceView::from(&ws)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_search_filters_and_esc_clears() {
+        let mut selector = WorkspaceSelectorModal::new();
+        selector.set_workspaces(views(&["backend", "frontend", "docs"]), None);
+
+        for c in "fe".chars() {
+            selector.search_push(c);
+        }
+        assert_eq!(selector.selected_workspace().unwrap().name, "frontend");
+        // "Create New" stays reachable below the matches
+        selector.down();
+        assert!(selector.selected_workspace().is_none());
+
+        selector.search_pop();
+        selector.search_push('x');
+        assert_eq!(selector.filtered.len(), 0);
+
+        assert_eq!(selector.escape(), WorkspaceSelectorAction::None);
+        assert_eq!(selector.query(), "");
+        assert_eq!(selector.filtered.len(), 3);
+        assert_eq!(selector.escape(), WorkspaceSelectorAction::Cancel);
+    }
+
+    #[test]
+    fn test_register_discovered_project() {
+        let mut selector = WorkspaceSelectorModal::new();
+        selector.set_workspaces(views(&["backend"]), None);
+        selector.set_discovered(vec![DiscoveredProject {
+            name: "scratch".into(),
+            path: PathBuf::from("/code/scratch"),
+            kind: "git",
+        }]);
+
+        // Discovered projects sit between the workspaces and "Create New"
+        selector.down();
+        assert_eq!(
+            selector.enter(),
+            WorkspaceSelectorAction::Create {
+                name: "scratch".into(),
+                path: PathBuf::from("/code/scratch"),
+            }
+        );
+        draw(&mut selector);
+        assert_eq!(selector.row_areas.len(), 3);
+
+        selector.search_push('b');
+        assert!(selector.filtered_discovered.is_empty());
+        selector.search_pop();
+        assert_eq!(selector.filtered_discovered.len(), 1);
+    }
+
+    #[test]
+    fn test_broken_workspace_opens_repair() {
+        let mut selector = WorkspaceSelectorModal::new();
+        let mut workspaces = views(&["gone"]);
+        workspaces[0].broken = true;
+        let id = workspaces[0].id;
+        selector.set_workspaces(workspaces, None);
+
+        assert_eq!(selector.enter(), WorkspaceSelectorAction::None);
+        assert_eq!(selector.mode, SelectorMode::Repair);
+
+        // Relocating browses for a folder and comes back to repair on cancel
+        selector.relocate();
+        assert_eq!(selector.mode, SelectorMode::BrowseFolders);
+        selector.escape();
+        assert_eq!(selector.mode, SelectorMode::Repair);
+
+        assert_eq!(selector.archive(), WorkspaceSelectorAction::Archive(id));
+        assert_eq!(selector.mode, SelectorMode::List);
+    }
+
     #[test]
     fn test_escape_cancels() {
         let mut selector = WorkspaceSelectorModal::new();
@@ -849,4 +1217,62 @@ mod tests {
         assert_eq!(action, WorkspaceSelectorAction::None);
         assert_eq!(selector.mode, SelectorMode::List);
     }
+
+    fn rendered() -> WorkspaceSelectorModal {
+        let mut selector = WorkspaceSelectorModal::new();
+        draw(&mut selector);
+        selector
+    }
+
+    fn draw(selector: &mut WorkspaceSelectorModal) {
+        let backend = ratatui::backend::TestBackend::new(100, 40);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|f| selector.render(f, f.area())).unwrap();
+    }
+
+    #[test]
+    fn test_double_click_opens_row() {
+        let mut selector = rendered();
+        let (_, row) = selector.row_areas[0];
+        let now = Instant::now();
+
+        selector.click_at(row.x + 1, row.y, now);
+        assert_eq!(selector.mode, SelectorMode::List);
+
+        // Too slow for a double-click
+        selector.click_at(row.x + 1, row.y, now + Duration::from_secs(1));
+        assert_eq!(selector.mode, SelectorMode::List);
+
+        selector.click_at(row.x + 1, row.y, now + Duration::from_millis(1100));
+        assert_eq!(selector.mode, SelectorMode::CreateNew);
+    }
+
+    #[test]
+    fn test_click_buttons_and_hover() {
+        let mut selector = rendered();
+        let (_, row) = selector.row_areas[0];
+        selector.handle_hover(row.x, row.y);
+        assert!(selector.is_hovered(0));
+
+        let (_, close) = *selector
+            .button_areas
+            .iter()
+            .find(|(b, _)| *b == SelectorButton::Close)
+            .unwrap();
+        assert_eq!(
+            selector.handle_click(close.x, close.y),
+            WorkspaceSelectorAction::Cancel
+        );
+
+        // Hover doesn't carry over to another mode
+        selector.enter();
+        assert_eq!(selector.mode, SelectorMode::CreateNew);
+        assert!(!selector.is_hovered(0));
+
+        draw(&mut selector);
+        let path = selector.field_areas[1].unwrap();
+        selector.handle_click(path.x + 1, path.y);
+        assert_eq!(selector.edit_field, 1);
+        assert_eq!(selector.cursor_pos, 0);
+    }
 }
@@ -52,8 +52,17 @@ impl FileWatcher {
     }
 }
 
+/// Whether `path` is a project config file, reloaded when it changes
+pub fn is_config_file(path: &Path) -> bool {
+    path.ends_with(".axiom.toml") || path.ends_with(".axiom/config.toml")
+}
+
 /// Check if a path should be ignored by the watcher
 fn should_ignore(path: &Path) -> bool {
+    if is_config_file(path) {
+        return false;
+    }
+
     let path_str = path.to_string_lossy();
 
     // Ignore hidden files and directories
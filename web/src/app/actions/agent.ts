@@ -6,9 +6,13 @@ import { axiomApi } from '@/lib/api';
 export type AgentRole = 'orchestrator' | 'po' | 'architect' | 'developer';
 
 export interface OrchestratorDecision {
-  nextAgent: AgentRole | 'user';
+  nextAgent: AgentRole | 'user' | 'cli';
+  /** The CLI agent to run, when nextAgent is 'cli' */
+  cliAgent?: string;
   reasoning: string;
   task?: string;
+  guardWarnings?: string[];
+  routingWarning?: string;
 }
 
 export interface AgentOperation {
@@ -18,12 +22,17 @@ export interface AgentOperation {
   command?: string;
   success?: boolean;
   error?: string;
+  blocked?: boolean;
 }
 
 export interface DeveloperResponse {
   reasoning: string;
   operations: AgentOperation[];
   message: string;
+  /** Language server problems in the files written */
+  diagnostics?: string;
+  /** Outcome of the workspace's checks */
+  verification?: string;
 }
 
 export interface LLMMessage {
@@ -47,9 +56,12 @@ export async function orchestrateAction(
 
     // Convert backend format to frontend format
     return {
-      nextAgent: response.next_agent as AgentRole | 'user',
+      nextAgent: response.next_agent as AgentRole | 'user' | 'cli',
+      cliAgent: response.cli_agent ?? undefined,
       reasoning: response.reasoning,
       task: response.task,
+      guardWarnings: response.guard_warnings,
+      routingWarning: response.routing_warning ?? undefined,
     };
   } catch (error: any) {
     console.error('Orchestration error:', error);
@@ -85,8 +97,11 @@ export async function developerAction(
         command: op.command,
         success: op.success,
         error: op.error,
+        blocked: op.blocked,
       })),
       message: response.message,
+      diagnostics: response.diagnostics?.summary,
+      verification: response.verification?.summary,
     };
   } catch (error: any) {
     console.error('Developer agent error:', error);
@@ -98,6 +113,33 @@ export async function developerAction(
   }
 }
 
+/**
+ * Run a CLI agent on the orchestrator's task and summarize what it changed
+ */
+export async function cliDelegateAction(
+  workspaceId: string,
+  agent: string,
+  task: string
+): Promise<{ summary: string; changed: boolean }> {
+  try {
+    const response = await axiomApi.runCliDelegate(workspaceId, agent, task);
+
+    if (response.error) {
+      throw new Error(response.error);
+    }
+
+    const changed =
+      response.created.length + response.modified.length + response.deleted.length > 0;
+    return { summary: response.summary, changed };
+  } catch (error: any) {
+    console.error('CLI agent error:', error);
+    return {
+      summary: error.message || 'The CLI agent failed. Please try again.',
+      changed: false,
+    };
+  }
+}
+
 /**
  * Get LLM settings for the workspace
  */
@@ -88,21 +88,25 @@ export async function getWorkspaceByIdAction(id: string): Promise<WorkspaceConfi
   }
 }
 
+/** Create a workspace; `existing` is set instead when the path already has one */
 export async function addWorkspaceAction(
   title: string,
   path: string,
   type: 'local' | 'remote' = 'local'
-): Promise<WorkspaceConfig> {
+): Promise<{ workspace?: WorkspaceConfig; existing?: WorkspaceConfig }> {
   const response = await axiomApi.createWorkspace({
     name: title,
     path: path,
   });
 
+  if (response.existing) {
+    return { existing: toWorkspaceConfig(response.existing) };
+  }
   if (!response.success || !response.workspace) {
     throw new Error(response.error || 'Failed to create workspace');
   }
 
-  return toWorkspaceConfig(response.workspace);
+  return { workspace: toWorkspaceConfig(response.workspace) };
 }
 
 export async function removeWorkspaceAction(id: string): Promise<void> {
@@ -112,6 +116,21 @@ export async function removeWorkspaceAction(id: string): Promise<void> {
   }
 }
 
+/** Whether the workspace is trusted, or null if the user hasn't decided */
+export async function getTrustAction(id: string): Promise<boolean | null> {
+  try {
+    const response = await axiomApi.getTrust(id);
+    return response.trusted;
+  } catch (error) {
+    console.error('Failed to get workspace trust:', error);
+    return false;
+  }
+}
+
+export async function setTrustAction(id: string, trusted: boolean): Promise<void> {
+  await axiomApi.setTrust(id, trusted);
+}
+
 export async function listFilesAction(workspaceId: string, path?: string): Promise<FileEntry[]> {
   try {
     const response = await axiomApi.listFiles(workspaceId, path);
@@ -4,6 +4,7 @@ import React, { useState, useEffect } from 'react';
 import { Plus, HelpCircle, Shield, FileText, Home, MessageSquare, Menu, PanelLeft, Settings, X } from 'lucide-react';
 import { cn } from '@/lib/utils';
 import Link from 'next/link';
+import { useRouter } from 'next/navigation';
 import { ThemeToggle } from '@/components/ThemeToggle';
 import { getWorkspacesAction, addWorkspaceAction } from '@/app/actions/workspace';
 import { WorkspaceConfig } from '@/lib/types';
@@ -17,6 +18,7 @@ export default function Dashboard() {
   const [isLoading, setIsLoading] = useState(true);
   const [isPickerOpen, setIsPickerOpen] = useState(false);
   const [isSettingsOpen, setIsSettingsOpen] = useState(false);
+  const router = useRouter();
 
   const loadWorkspaces = async () => {
     try {
@@ -43,7 +45,13 @@ export default function Dashboard() {
     const title = path.split('/').pop() || 'Untitled Workspace';
     
     try {
-      await addWorkspaceAction(title, path, 'local');
+      const { existing } = await addWorkspaceAction(title, path, 'local');
+      if (existing) {
+        if (confirm(`${path} is already the workspace '${existing.title}'. Open it instead?`)) {
+          router.push(`/workspace/${existing.id}`);
+        }
+        return;
+      }
       await loadWorkspaces();
     } catch (error) {
       console.error('Failed to create workspace:', error);
@@ -25,8 +25,14 @@ import { cn } from '@/lib/utils';
 import LinkNext from 'next/link';
 import { ThemeToggle } from '@/components/ThemeToggle';
 import { useParams, useRouter } from 'next/navigation';
-import { getWorkspaceByIdAction, listFilesAction, readFileAction } from '@/app/actions/workspace';
-import { orchestrateAction, developerAction } from '@/app/actions/agent';
+import {
+  getWorkspaceByIdAction,
+  getTrustAction,
+  listFilesAction,
+  readFileAction,
+  setTrustAction,
+} from '@/app/actions/workspace';
+import { orchestrateAction, developerAction, cliDelegateAction } from '@/app/actions/agent';
 import { slashCommandAction } from '@/app/actions/slash';
 import type { SlashCommandResult, SlashCommandData, UiAction, CommandHelp } from '@/lib/api/types';
 import { runCommandAction } from '@/app/actions/terminal';
@@ -91,6 +97,14 @@ export default function WorkspaceStudio() {
             return a.isDirectory ? -1 : 1;
           }));
           setIsLoadingFiles(false);
+
+          // First visit: ask before agents may run commands here
+          if ((await getTrustAction(ws.id)) === null) {
+            const trusted = window.confirm(
+              `Trust "${ws.title}"?\n\nUntrusted workspaces don't run agent commands or CLI agents.`
+            );
+            await setTrustAction(ws.id, trusted);
+          }
         }
       } catch (error) {
         console.error('Failed to load workspace data:', error);
@@ -198,6 +212,12 @@ export default function WorkspaceStudio() {
         router.push('/');
         break;
 
+      case 'OfferExistingWorkspace':
+        if (confirm(`That directory is already the workspace '${action.name}'. Open it instead?`)) {
+          router.push(`/workspace/${action.id}`);
+        }
+        break;
+
       case 'OpenModelSelector':
         setShowSettingsModal(true);
         break;
@@ -286,10 +306,30 @@ export default function WorkspaceStudio() {
     try {
       updateAgentStatus('orchestrator', 'thinking', 'Analyzing request...');
       const decision = await orchestrateAction(workspaceId, [...messages, userMsg]);
-      updateAgentStatus('orchestrator', 'idle', decision.reasoning);
+      const notes = [...(decision.guardWarnings ?? []), ...(decision.routingWarning ? [decision.routingWarning] : [])];
+      const guardNote = notes.length ? `${notes.join('; ')}. ` : '';
+      updateAgentStatus('orchestrator', 'idle', guardNote + decision.reasoning);
 
       if (decision.nextAgent === 'user') {
         setMessages(prev => [...prev, { role: 'assistant', content: decision.task || "I'm not sure how to help." }]);
+      } else if (decision.nextAgent === 'cli' && decision.cliAgent) {
+        setMessages(prev => [...prev, {
+          role: 'assistant',
+          content: `**Orchestrator**: Delegating to CLI agent **${decision.cliAgent}**.\n\n*Reasoning*: ${decision.reasoning}\n\n*Task*: ${decision.task}`
+        }]);
+        updateAgentStatus('developer', 'working', `Waiting on ${decision.cliAgent}...`);
+
+        const result = await cliDelegateAction(workspaceId, decision.cliAgent, decision.task || '');
+        if (result.changed) {
+          const fsEntries = await listFilesAction(workspaceId);
+          setFiles(fsEntries.sort((a, b) => {
+            if (a.isDirectory === b.isDirectory) return a.name.localeCompare(b.name);
+            return a.isDirectory ? -1 : 1;
+          }));
+        }
+
+        updateAgentStatus('developer', 'idle', 'Task complete');
+        setMessages(prev => [...prev, { role: 'assistant', content: result.summary }]);
       } else {
         const agentRole = decision.nextAgent as AgentRole;
         const agentName = agentStates[agentRole].name;
@@ -304,7 +344,10 @@ export default function WorkspaceStudio() {
         if (agentRole === 'developer') {
            if (!workspace?.path) throw new Error("Workspace path not found");
            const devResult = await developerAction(workspaceId, decision.task || '');
-           const executeOps = devResult.operations.filter(op => op.type === 'execute');
+           const executeOps = devResult.operations.filter(op => op.type === 'execute' && !op.blocked);
+           if (devResult.operations.some(op => op.blocked)) {
+             updateAgentStatus('developer', 'working', 'Skipped commands: workspace is not trusted (/trust on)');
+           }
            
            if (executeOps.length > 0) {
              setRightPanelMode('terminal');
@@ -327,7 +370,9 @@ export default function WorkspaceStudio() {
            }));
 
            updateAgentStatus(agentRole, 'idle', 'Task complete');
-           setMessages(prev => [...prev, { role: 'assistant', content: `**${agentName}**: ${devResult.message}` }]);
+           const diagnostics = devResult.diagnostics ? `\n\nDiagnostics:\n${devResult.diagnostics}` : '';
+           const verification = devResult.verification ? `\n\n${devResult.verification}` : '';
+           setMessages(prev => [...prev, { role: 'assistant', content: `**${agentName}**: ${devResult.message}${diagnostics}${verification}` }]);
         } else {
           setTimeout(() => {
              updateAgentStatus(agentRole, 'idle', 'Task complete');
@@ -208,7 +208,9 @@ function AgentMappingRow({ mapping, providers, onUpdate }: { mapping: AgentMappi
     orchestrator: 'Orchestrator',
     po: 'Product Owner',
     architect: 'Architect',
-    developer: 'Developer'
+    developer: 'Developer',
+    reviewer: 'Reviewer',
+    'shell-summarizer': 'Shell Summarizer'
   };
 
   return (
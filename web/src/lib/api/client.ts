@@ -6,15 +6,56 @@
 import type {
   Workspace,
   WorkspaceView,
+  UpdateWorkspaceRequest,
+  PurgePlan,
+  ActivityPage,
+  AuditReport,
+  TimeStats,
+  PendingWrite,
+  UsageRow,
+  UsageExportParams,
   CreateWorkspaceRequest,
   FileEntry,
+  SearchResults,
   CommandResult,
+  RunRecord,
+  Plan,
+  WorkspaceDefaults,
   ApiResponse,
+  SubTask,
+  TaskState,
+  ProviderHealth,
+  Diagnostic,
+  Verification,
+  ErrorCode,
 } from './types';
 
 // Default to localhost in development, can be configured for production
 const API_BASE_URL = process.env.NEXT_PUBLIC_API_URL || 'http://localhost:8080';
 
+// A failed request, with the error's code when the server sent one
+export class ApiError extends Error {
+  constructor(
+    public status: number,
+    message: string,
+    public code?: ErrorCode
+  ) {
+    super(message);
+  }
+}
+
+// The ApiError for a response that isn't ok
+async function apiError(response: Response): Promise<ApiError> {
+  const errorBody = await response.text();
+  let code: ErrorCode | undefined;
+  try {
+    code = JSON.parse(errorBody).code;
+  } catch {
+    // Not a JSON error body
+  }
+  return new ApiError(response.status, `API error (${response.status}): ${errorBody}`, code);
+}
+
 class AxiomApiClient {
   private baseUrl: string;
 
@@ -37,8 +78,7 @@ class AxiomApiClient {
     });
 
     if (!response.ok) {
-      const errorBody = await response.text();
-      throw new Error(`API error (${response.status}): ${errorBody}`);
+      throw await apiError(response);
     }
 
     return response.json();
@@ -59,13 +99,23 @@ class AxiomApiClient {
     return this.fetch('/api/workspaces');
   }
 
+  /** Resolves with `existing` (409) when the path already has a workspace */
   async createWorkspace(
     request: CreateWorkspaceRequest
-  ): Promise<{ success: boolean; workspace?: Workspace; error?: string }> {
-    return this.fetch('/api/workspaces', {
+  ): Promise<{ success: boolean; workspace?: Workspace; existing?: Workspace; error?: string }> {
+    const response = await fetch(`${this.baseUrl}/api/workspaces`, {
       method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(request),
     });
+    if (!response.ok && response.status !== 409) {
+      throw await apiError(response);
+    }
+    return response.json();
+  }
+
+  async dedupeWorkspaces(): Promise<{ success: boolean; merged?: unknown[]; error?: string }> {
+    return this.fetch('/api/workspaces/dedupe', { method: 'POST' });
   }
 
   async getWorkspace(
@@ -82,14 +132,94 @@ class AxiomApiClient {
     });
   }
 
+  async updateWorkspace(
+    id: string,
+    request: UpdateWorkspaceRequest
+  ): Promise<{ success: boolean; workspace?: WorkspaceView; error?: string }> {
+    return this.fetch(`/api/workspaces/${id}`, {
+      method: 'PATCH',
+      body: JSON.stringify(request),
+    });
+  }
+
+  async getActivity(id: string, offset = 0, limit = 50): Promise<ActivityPage> {
+    return this.fetch(`/api/workspaces/${id}/activity?offset=${offset}&limit=${limit}`);
+  }
+
+  async getTimeStats(id: string, days?: number): Promise<TimeStats> {
+    const query = days ? `?days=${days}` : '';
+    return this.fetch(`/api/workspaces/${id}/stats/time${query}`);
+  }
+
+  async verifyAudit(id: string): Promise<{ intact: boolean; report: AuditReport }> {
+    return this.fetch(`/api/workspaces/${id}/audit`);
+  }
+
+  /** Markdown changelog of agent changes; `since` like "7d", "12h" or "2w" */
+  async agentReport(id: string, since = '7d'): Promise<string> {
+    const response = await fetch(
+      `${this.baseUrl}/api/workspaces/${id}/report/agents?since=${encodeURIComponent(since)}`
+    );
+    if (!response.ok) {
+      throw await apiError(response);
+    }
+    return response.text();
+  }
+
+  /** `trusted` is null until the user decides */
+  async getTrust(id: string): Promise<{ trusted: boolean | null }> {
+    return this.fetch(`/api/workspaces/${id}/trust`);
+  }
+
+  async setTrust(id: string, trusted: boolean): Promise<{ trusted: boolean }> {
+    return this.fetch(`/api/workspaces/${id}/trust`, {
+      method: 'PUT',
+      body: JSON.stringify({ trusted }),
+    });
+  }
+
+  async getWorkspaceDefaults(id: string): Promise<WorkspaceDefaults> {
+    return this.fetch(`/api/workspaces/${id}/defaults`);
+  }
+
+  /** Set the workspace's provider, models and CLI agent over the global ones */
+  async setWorkspaceDefaults(
+    id: string,
+    defaults: WorkspaceDefaults
+  ): Promise<WorkspaceDefaults> {
+    return this.fetch(`/api/workspaces/${id}/defaults`, {
+      method: 'PUT',
+      body: JSON.stringify(defaults),
+    });
+  }
+
+  /** Archive a workspace; its data is kept until purged */
   async deleteWorkspace(
     id: string
-  ): Promise<{ success: boolean; error?: string }> {
+  ): Promise<{ success: boolean; archived?: boolean; error?: string }> {
     return this.fetch(`/api/workspaces/${id}`, {
       method: 'DELETE',
     });
   }
 
+  async restoreWorkspace(
+    id: string
+  ): Promise<{ success: boolean; workspace?: WorkspaceView; error?: string }> {
+    return this.updateWorkspace(id, { archived: false });
+  }
+
+  async getPurgePlan(id: string): Promise<{ plan: PurgePlan; summary: string }> {
+    return this.fetch(`/api/workspaces/${id}/purge`);
+  }
+
+  async purgeWorkspace(
+    id: string
+  ): Promise<{ success: boolean; plan?: PurgePlan; summary?: string; error?: string }> {
+    return this.fetch(`/api/workspaces/${id}/purge`, {
+      method: 'POST',
+    });
+  }
+
   // ========== File Operations ==========
 
   async listFiles(
@@ -115,6 +245,17 @@ class AxiomApiClient {
     return this.fetch(`/api/workspaces/${workspaceId}/file?${params}`);
   }
 
+  async searchWorkspace(
+    workspaceId: string,
+    q: string,
+    options: { regex?: boolean; globs?: string[] } = {}
+  ): Promise<SearchResults> {
+    const params = new URLSearchParams({ q });
+    if (options.regex) params.set('regex', 'true');
+    if (options.globs?.length) params.set('glob', options.globs.join(','));
+    return this.fetch(`/api/workspaces/${workspaceId}/search?${params}`);
+  }
+
   async writeFile(
     workspaceId: string,
     path: string,
@@ -126,6 +267,41 @@ class AxiomApiClient {
     });
   }
 
+  /** Create an empty file, or a folder with `directory` */
+  async createFile(
+    workspaceId: string,
+    path: string,
+    directory = false
+  ): Promise<{ success: boolean; path?: string; error?: string }> {
+    return this.fetch(`/api/workspaces/${workspaceId}/file`, {
+      method: 'POST',
+      body: JSON.stringify({ path, directory }),
+    });
+  }
+
+  /** Delete a file, or a folder and everything in it */
+  async deleteFile(
+    workspaceId: string,
+    path: string
+  ): Promise<{ success: boolean; path?: string; error?: string }> {
+    const params = new URLSearchParams({ path });
+    return this.fetch(`/api/workspaces/${workspaceId}/file?${params}`, {
+      method: 'DELETE',
+    });
+  }
+
+  /** Rename or move a file or folder */
+  async renameFile(
+    workspaceId: string,
+    from: string,
+    to: string
+  ): Promise<{ success: boolean; path?: string; error?: string }> {
+    return this.fetch(`/api/workspaces/${workspaceId}/file/rename`, {
+      method: 'POST',
+      body: JSON.stringify({ from, to }),
+    });
+  }
+
   // ========== Terminal/Command Operations ==========
 
   async runCommand(
@@ -166,6 +342,14 @@ class AxiomApiClient {
     return this.fetch(`/api/workspaces/${workspaceId}/agents`);
   }
 
+  // A CLI agent's output as plain text, including what scrolled off its screen
+  async getAgentTranscript(
+    workspaceId: string,
+    agentId: string
+  ): Promise<{ id?: number; lines?: string[]; dropped?: number; error?: string }> {
+    return this.fetch(`/api/workspaces/${workspaceId}/agents/${agentId}/transcript`);
+  }
+
   // ========== WebSocket URL ==========
 
   getWebSocketUrl(workspaceId: string): string {
@@ -173,26 +357,65 @@ class AxiomApiClient {
     return `${wsBase}/api/workspaces/${workspaceId}/ws`;
   }
 
+  // A shell in the workspace directory, for xterm.js
+  getTerminalUrl(workspaceId: string, cols: number, rows: number): string {
+    const wsBase = this.baseUrl.replace(/^http/, 'ws');
+    return `${wsBase}/api/workspaces/${workspaceId}/terminal?cols=${cols}&rows=${rows}`;
+  }
+
+  // Asciicast recordings of CLI agent sessions, newest first
+  async listRecordings(workspaceId: string): Promise<{
+    recordings?: Array<{ name: string; agent: string; started_at: number; size: number }>;
+    error?: string;
+  }> {
+    return this.fetch(`/api/workspaces/${workspaceId}/recordings`);
+  }
+
+  // Download link for a recording, playable with `asciinema play`
+  getRecordingUrl(workspaceId: string, name: string): string {
+    return `${this.baseUrl}/api/workspaces/${workspaceId}/recordings/${encodeURIComponent(name)}`;
+  }
+
+  // Logged agent runs, newest first
+  async listRuns(workspaceId: string, limit?: number): Promise<{ runs?: RunRecord[]; error?: string }> {
+    const query = limit ? `?limit=${limit}` : '';
+    return this.fetch(`/api/workspaces/${workspaceId}/runs${query}`);
+  }
+
+  // The Conductor's latest plan; null until it has made one
+  async getPlan(workspaceId: string): Promise<{ plan?: Plan | null; error?: string }> {
+    return this.fetch(`/api/workspaces/${workspaceId}/plan`);
+  }
+
   // ========== Orchestration Operations ==========
 
   async orchestrate(
     workspaceId: string,
-    messages: Array<{ role: string; content: string }>
+    messages: Array<{ role: string; content: string }>,
+    /** Files open in the editor, added to the agent's repository context */
+    openTabs: string[] = []
   ): Promise<{
     next_agent: string;
+    /** The CLI agent to run, when next_agent is "cli" */
+    cli_agent?: string | null;
     reasoning: string;
     task?: string;
+    /** Secrets redacted or injection markers flagged in the messages */
+    guard_warnings?: string[];
+    /** Provider allowed despite the workspace's routing policy */
+    routing_warning?: string | null;
     error?: string;
   }> {
     return this.fetch(`/api/workspaces/${workspaceId}/orchestrate`, {
       method: 'POST',
-      body: JSON.stringify({ messages }),
+      body: JSON.stringify({ messages, open_tabs: openTabs }),
     });
   }
 
   async runDeveloper(
     workspaceId: string,
-    task: string
+    task: string,
+    openTabs: string[] = []
   ): Promise<{
     reasoning: string;
     operations: Array<{
@@ -201,13 +424,76 @@ class AxiomApiClient {
       command?: string;
       success?: boolean;
       error?: string;
+      /** Held by the workspace write policy until approved */
+      approval_required?: boolean;
+      approval_id?: string;
+      reason?: string;
+      /** Command not run because the workspace is untrusted */
+      blocked?: boolean;
     }>;
     message: string;
+    /** Language server problems in the files written, when any has a server */
+    diagnostics?: { files: Record<string, Diagnostic[]>; summary: string } | null;
+    /** The workspace's checks, when it has any; repairs' operations are in `operations` */
+    verification?: Verification | null;
+    routing_warning?: string | null;
     error?: string;
   }> {
     return this.fetch(`/api/workspaces/${workspaceId}/agents/developer`, {
       method: 'POST',
-      body: JSON.stringify({ task }),
+      body: JSON.stringify({ task, open_tabs: openTabs }),
+    });
+  }
+
+  /** Run a CLI agent on a task until it exits or goes idle */
+  async runCliDelegate(
+    workspaceId: string,
+    agent: string,
+    task: string
+  ): Promise<{
+    agent: string;
+    end: { kind: 'exited'; code: number } | { kind: 'idle' } | { kind: 'timed_out' };
+    duration_secs: number;
+    created: string[];
+    modified: string[];
+    deleted: string[];
+    output: string;
+    /** The run and its file changes, for the conversation */
+    summary: string;
+    error?: string;
+  }> {
+    return this.fetch(`/api/workspaces/${workspaceId}/agents/cli-delegate`, {
+      method: 'POST',
+      body: JSON.stringify({ agent, task }),
+    });
+  }
+
+  async runDeveloperTasks(
+    workspaceId: string,
+    tasks: SubTask[]
+  ): Promise<{
+    tasks: Array<
+      TaskState & {
+        id: string;
+        reasoning?: string;
+        message?: string;
+        operations?: Array<{ type: string; path?: string; success?: boolean; error?: string }>;
+      }
+    >;
+    /** What the developers did, one line per task */
+    summary: string;
+    /** The workspace's checks on all tasks' changes, with the repairs' operations */
+    verification?:
+      | (Verification & {
+          operations?: Array<{ type: string; path?: string; success?: boolean; error?: string }>;
+        })
+      | null;
+    routing_warning?: string | null;
+    error?: string;
+  }> {
+    return this.fetch(`/api/workspaces/${workspaceId}/agents/developer/tasks`, {
+      method: 'POST',
+      body: JSON.stringify({ tasks }),
     });
   }
 
@@ -229,6 +515,13 @@ class AxiomApiClient {
     return this.fetch(`/api/workspaces/${workspaceId}/llm/settings`);
   }
 
+  // Health-checks every configured provider; `reachable` leaves out the ones that failed
+  async listLlmProviders(
+    reachable = false
+  ): Promise<{ providers: ProviderHealth[]; active: string }> {
+    return this.fetch(`/api/llm/providers${reachable ? '?reachable=true' : ''}`);
+  }
+
   async updateProvider(
     workspaceId: string,
     providerId: string,
@@ -263,6 +556,42 @@ class AxiomApiClient {
     );
   }
 
+  // ========== Write Approvals ==========
+
+  async getApprovals(workspaceId: string): Promise<{ approvals: PendingWrite[] }> {
+    return this.fetch(`/api/workspaces/${workspaceId}/approvals`);
+  }
+
+  async resolveApproval(
+    workspaceId: string,
+    approvalId: string,
+    approve: boolean
+  ): Promise<{ id: string; path: string; applied: boolean }> {
+    return this.fetch(`/api/workspaces/${workspaceId}/approvals/${approvalId}`, {
+      method: 'POST',
+      body: JSON.stringify({ approve }),
+    });
+  }
+
+  // ========== Usage ==========
+
+  private usageQuery(params: UsageExportParams, format: 'csv' | 'json'): string {
+    const query = new URLSearchParams({ format });
+    for (const [key, value] of Object.entries(params)) {
+      if (value) query.set(key, value);
+    }
+    return `/api/usage/export?${query}`;
+  }
+
+  async getUsage(params: UsageExportParams = {}): Promise<{ rows: UsageRow[] }> {
+    return this.fetch(this.usageQuery(params, 'json'));
+  }
+
+  /** URL of the CSV cost report, for download links */
+  usageCsvUrl(params: UsageExportParams = {}): string {
+    return `${this.baseUrl}${this.usageQuery(params, 'csv')}`;
+  }
+
   // ========== Slash Command Operations ==========
 
   async executeSlashCommand(
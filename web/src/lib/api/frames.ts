@@ -0,0 +1,68 @@
+/**
+ * Binary WebSocket frames
+ * Decoder for the compact format the server sends under the
+ * `axiom.binary.v1` subprotocol (see `axiom_core::wire`)
+ */
+
+import type { Notification } from './types';
+
+export const PROTOCOL_BINARY = 'axiom.binary.v1';
+export const PROTOCOL_JSON = 'axiom.json.v1';
+
+const KIND_JSON = 0;
+const KIND_PTY_OUTPUT = 1;
+const KIND_AGENT_OUTPUT = 2;
+const KIND_PTY_SCREEN = 3;
+const KIND_PTY_SCREEN_DIFF = 4;
+
+const FLAG_DEFLATE = 1;
+
+/** Whether this browser can inflate deflated frames */
+export function supportsBinaryFrames(): boolean {
+  return typeof DecompressionStream !== 'undefined';
+}
+
+async function inflate(data: Uint8Array): Promise<Uint8Array> {
+  const stream = new Blob([data]).stream().pipeThrough(new DecompressionStream('deflate-raw'));
+  return new Uint8Array(await new Response(stream).arrayBuffer());
+}
+
+/** Decode a binary frame into the notification it carries */
+export async function decodeFrame(buffer: ArrayBuffer): Promise<Notification> {
+  const bytes = new Uint8Array(buffer);
+  if (bytes.length < 2) {
+    throw new Error('Malformed binary frame');
+  }
+  const [kind, flags] = bytes;
+  const unpack = (body: Uint8Array) => (flags & FLAG_DEFLATE ? inflate(body) : Promise.resolve(body));
+
+  if (kind === KIND_JSON) {
+    const payload = await unpack(bytes.subarray(2));
+    return JSON.parse(new TextDecoder().decode(payload)) as Notification;
+  }
+  if (bytes.length < 10) {
+    throw new Error('Malformed binary frame');
+  }
+  // IDs are u64 on the wire but small in practice; JSON sends them as numbers too
+  const id = Number(new DataView(buffer, 2, 8).getBigUint64(0));
+  const payload = await unpack(bytes.subarray(10));
+
+  let fields: Record<string, unknown>;
+  switch (kind) {
+    case KIND_PTY_OUTPUT:
+      fields = { type: 'PtyOutput', id, data: Array.from(payload) };
+      break;
+    case KIND_AGENT_OUTPUT:
+      fields = { type: 'AgentOutput', id, chunk: new TextDecoder().decode(payload) };
+      break;
+    case KIND_PTY_SCREEN:
+      fields = { type: 'PtyScreen', id, screen: JSON.parse(new TextDecoder().decode(payload)) };
+      break;
+    case KIND_PTY_SCREEN_DIFF:
+      fields = { type: 'PtyScreenDiff', id, diff: JSON.parse(new TextDecoder().decode(payload)) };
+      break;
+    default:
+      throw new Error(`Unknown frame kind ${kind}`);
+  }
+  return fields as Notification;
+}
@@ -28,6 +28,12 @@ export interface WorkspaceView {
   path: string;
   workspace_type: WorkspaceTypeValue;
   is_active: boolean;
+  last_accessed?: number;
+  exists?: boolean;
+  git_dirty?: boolean;
+  running_agents?: number;
+  broken?: boolean;
+  archived?: boolean;
 }
 
 export interface CreateWorkspaceRequest {
@@ -35,6 +41,127 @@ export interface CreateWorkspaceRequest {
   path: string;
 }
 
+export interface UpdateWorkspaceRequest {
+  name?: string;
+  path?: string;
+  archived?: boolean;
+}
+
+/** One piece of Axiom data a purge deletes */
+export interface PurgeItem {
+  label: string;
+  path?: string;
+  bytes: number;
+}
+
+/** What purging an archived workspace deletes */
+export interface PurgePlan {
+  workspace_id: string;
+  name: string;
+  path: string;
+  items: PurgeItem[];
+  tasks: string[];
+  usage_records: number;
+}
+
+// Activity Feed Types
+export type ActivityKind = 'agent_run' | 'file_change' | 'git_commit' | 'notification';
+
+export interface ActivityEvent {
+  timestamp: number;
+  kind: ActivityKind;
+  actor: string;
+  summary: string;
+  path?: string;
+  /** Longer explanation, e.g. an agent's reasoning */
+  detail?: string;
+}
+
+export interface ActivityPage {
+  events: ActivityEvent[];
+  total: number;
+  next_offset: number | null;
+  /** MAC of the last signed entry, when audit signing is on */
+  chain_head?: string;
+}
+
+export interface AuditReport {
+  entries: number;
+  signed: number;
+  first_seq: number | null;
+  chain_head: string | null;
+  macs_checked: boolean;
+  problems: string[];
+}
+
+// Usage Types
+export interface UsageRow {
+  date: string;
+  workspace: string;
+  provider: string;
+  model: string;
+  input_tokens: number;
+  output_tokens: number;
+  tokens: number;
+  cost: number;
+}
+
+// One LLM call's token usage; cost_usd when the caller reported it
+export interface UsageRecord {
+  timestamp: number;
+  workspace_id?: string;
+  workspace: string;
+  provider: string;
+  model: string;
+  input_tokens: number;
+  output_tokens: number;
+  cost_usd?: number;
+}
+
+export interface UsageExportParams {
+  from?: string;
+  to?: string;
+  workspace?: string;
+}
+
+// Time Tracking Types
+export interface TimeStats {
+  user_secs: number;
+  agent_secs: number;
+  automated_share: number;
+  by_agent: Record<string, number>;
+  days: { date: string; user_secs: number; agent_secs: number }[];
+}
+
+// Write Approval Types
+export interface PendingWrite {
+  id: string;
+  agent: string;
+  path: string;
+  reason: string;
+  operation: { type: string; path?: string; from?: string; to?: string };
+  created_at: number;
+}
+
+// Developer Task Types
+export interface SubTask {
+  id: string;
+  task: string;
+  /** IDs of tasks that must be done first */
+  depends_on?: string[];
+}
+
+export type TaskState =
+  | { state: 'queued' | 'running' | 'done' }
+  | { state: 'failed' | 'skipped'; reason: string };
+
+/** Streamed as a `progress` event each time a task changes state */
+export type TaskProgress = TaskState & {
+  id: string;
+  finished: number;
+  total: number;
+};
+
 // File Types
 export interface FileEntry {
   name: string;
@@ -46,6 +173,145 @@ export interface FileEntry {
   is_hidden?: boolean;
 }
 
+// Search Types
+export interface SearchHit {
+  path: string;
+  line: number;
+  column: number;
+  text: string;
+}
+
+export interface SearchResults {
+  results: SearchHit[];
+  matches: number;
+  files: number;
+  truncated: boolean;
+}
+
+// A chunk of a file close in meaning to a semantic search
+export interface SemanticHit {
+  path: string;
+  start_line: number;
+  end_line: number;
+  score: number;
+  text: string;
+}
+
+// A problem a language server found in a file
+export interface Diagnostic {
+  line: number;
+  column: number;
+  end_line: number;
+  end_column: number;
+  severity: 'error' | 'warning' | 'information' | 'hint';
+  message: string;
+  source?: string;
+}
+
+// Where go-to-definition found a symbol
+export interface SourceLocation {
+  path: string;
+  line: number;
+  column: number;
+}
+
+// One `[verify]` check run after a developer's changes
+export interface CheckResult {
+  name: string;
+  command: string;
+  success: boolean;
+  exit_code: number | null;
+  timed_out: boolean;
+  duration_secs: number;
+  output: string;
+}
+
+// Checks run on a developer run, with the repairs they took
+export interface Verification {
+  /** One pass over the checks each; the last is the outcome */
+  attempts: Array<{ checks: CheckResult[] }>;
+  repairs: number;
+  passed: boolean;
+  summary: string;
+}
+
+// Run Log Types
+export type RunLogFormat = 'json' | 'markdown';
+
+// One agent invocation from `.axiom/logs/runs.jsonl`
+export interface RunRecord {
+  agent_id: string;
+  agent: string;
+  agent_type: string;
+  parent_id?: string;
+  prompt: string;
+  provider?: string;
+  model?: string;
+  tool_calls: Array<{ tool: string; input: string; agent_id?: string }>;
+  operations: Array<{ kind: string; path: string; success: boolean }>;
+  /** Unix seconds */
+  started_at: number;
+  duration_ms: number;
+  status: 'completed' | 'failed' | 'cancelled';
+  exit_code?: number;
+  error?: string;
+  output: string;
+}
+
+// Plan Types
+export type PlanStepStatus = 'pending' | 'running' | 'done' | 'failed' | 'skipped';
+
+export interface PlanStep {
+  title: string;
+  status: PlanStepStatus;
+  /** Agents started for the step */
+  agents: number[];
+  /** Unix seconds */
+  started_at?: number;
+  finished_at?: number;
+}
+
+// A Conductor request laid out as steps, from `.axiom/plan.json`
+export interface Plan {
+  goal: string;
+  steps: PlanStep[];
+  created_at: number;
+  updated_at: number;
+}
+
+// Workspace defaults: unset ones fall back to the global config
+export interface WorkspaceDefaults {
+  default_provider?: string | null;
+  /** Model of the default provider */
+  default_model?: string | null;
+  /** Provider and model per agent role */
+  agents?: Record<string, { provider: string; model: string }>;
+  default_cli_agent?: string | null;
+}
+
+// Replace Types
+export interface FileReplace {
+  path: string;
+  replacements: number;
+  diff: string;
+  fingerprint: string;
+}
+
+// Notification History Types
+export interface HistoryFilter {
+  after?: number;
+  since_ms?: number;
+  kinds?: Notification['type'][];
+  agent_id?: string;
+  limit?: number;
+}
+
+export interface HistoryEntry {
+  seq: number;
+  timestamp_ms: number;
+  notification: Notification;
+}
+
 // Agent Types
 export type AgentType = 'llm' | 'cli' | 'shell' | 'conductor';
 export type AgentStatus = 'pending' | 'running' | 'completed' | 'failed' | 'cancelled';
@@ -67,14 +333,28 @@ export type Command =
   | { type: 'InvokeCliAgent'; agent_id: string; prompt: string }
   | { type: 'SendPtyInput'; agent_id: string; data: number[] }
   | { type: 'ResizePty'; agent_id: string; cols: number; rows: number }
+  | { type: 'ResyncPtyScreen'; agent_id: string }
   | { type: 'ReadFile'; path: string }
   | { type: 'WriteFile'; path: string; content: string }
   | { type: 'CancelAgent'; agent_id: string }
+  | { type: 'RerunAgent'; agent_id: string }
+  | { type: 'GetLastMessage'; agent_id: string }
+  | { type: 'GetTouchedFiles'; agent_id: string }
+  | { type: 'ApproveOperations'; ids?: string[] }
+  | { type: 'RejectOperations'; ids?: string[] }
   | { type: 'ListWorkspaces' }
   | { type: 'CreateWorkspace'; name: string; path: string }
   | { type: 'DeleteWorkspace'; workspace_id: string }
   | { type: 'ActivateWorkspace'; workspace_id: string }
   | { type: 'ListFiles'; path: string; include_hidden: boolean }
+  | { type: 'SearchWorkspace'; query: string; regex?: boolean; globs?: string[] }
+  | { type: 'SemanticSearch'; query: string }
+  | { type: 'Hover'; path: string; line: number; column: number }
+  | { type: 'GoToDefinition'; path: string; line: number; column: number }
+  | { type: 'ReplaceInWorkspace'; query: string; replacement: string; regex?: boolean; globs?: string[] }
+  | { type: 'ApplyReplace'; replace_id: number; paths?: string[] }
+  | { type: 'QueryHistory'; filter?: HistoryFilter }
+  | { type: 'ExportRunLog'; agent_id: string; format?: RunLogFormat }
   | { type: 'SlashCommand'; command: SlashCommand };
 
 // Notification Types (received from backend via WebSocket)
@@ -83,18 +363,50 @@ export type Notification =
   | { type: 'AgentStatusChanged'; id: string; status: AgentStatus }
   | { type: 'AgentOutput'; id: string; chunk: string }
   | { type: 'PtyOutput'; id: string; data: number[] }
+  | { type: 'PtyScreen'; id: string; screen: TerminalScreen }
+  | { type: 'PtyScreenDiff'; id: string; diff: ScreenDiff }
   | { type: 'PtyExited'; id: string; exit_code: number }
+  | { type: 'CliAgentEvent'; id: string; agent: string; event: AgentEvent }
+  | { type: 'OperationApplied'; id: string; path: string; kind: string }
+  | { type: 'PlanUpdated'; plan: Plan }
+  | { type: 'UsageUpdate'; id?: string; record: UsageRecord }
   | { type: 'FileModified'; path: string }
+  | { type: 'FileChanged'; path: string }
   | { type: 'FileLoaded'; path: string; content: string }
-  | { type: 'Error'; message: string }
+  | { type: 'Error'; message: string; code?: ErrorCode }
   | { type: 'Info'; message: string }
   | { type: 'WorkspaceList'; workspaces: WorkspaceView[]; active_id?: string }
   | { type: 'WorkspaceCreated'; workspace: Workspace }
   | { type: 'WorkspaceDeleted'; workspace_id: string }
   | { type: 'WorkspaceActivated'; workspace: Workspace }
   | { type: 'FileList'; path: string; entries: FileEntry[] }
+  | { type: 'SearchResult'; search_id: number; path: string; line: number; column: number; text: string }
+  | { type: 'SearchFinished'; search_id: number; query: string; matches: number; truncated: boolean }
+  | { type: 'SemanticSearchResults'; query: string; hits: SemanticHit[] }
+  | { type: 'Diagnostics'; path: string; diagnostics: Diagnostic[] }
+  | { type: 'Hover'; path: string; line: number; column: number; contents: string | null }
+  | { type: 'Definition'; path: string; line: number; column: number; locations: SourceLocation[] }
+  | { type: 'ReplacePreview'; replace_id: number; query: string; replacement: string; files: FileReplace[] }
+  | { type: 'ReplaceApplied'; replace_id: number; applied: string[]; replacements: number; skipped: string[] }
+  | { type: 'PendingOperations'; operations: PendingWrite[] }
+  | { type: 'ProviderStatusChanged'; provider_id: string; status: ProviderStatus; models: string[] }
+  | { type: 'ProviderAuthFailed'; provider_id: string; message: string }
+  | { type: 'ProviderKeyExpiring'; provider_id: string; expires_at: number; expired: boolean }
+  | { type: 'ProviderKeyRefreshed'; provider_id: string; expires_at?: number }
+  | { type: 'ConfigReloaded'; paths: string[]; changes: ConfigChanges }
+  | { type: 'History'; entries: HistoryEntry[] }
+  | { type: 'RunLogExport'; agent_id: string; format: RunLogFormat; content: string }
+  | { type: 'AgentLastMessage'; agent_id: string; text: string }
+  | { type: 'AgentTouchedFiles'; agent_id: string; paths: string[] }
   | { type: 'SlashCommandResult'; result: SlashCommandResult };
 
+// What a CLI agent reported in its output, as found by its parsers
+export type AgentEvent =
+  | { kind: 'file_changed'; path: string }
+  | { kind: 'command_run'; command: string }
+  | { kind: 'progress'; message: string; percent?: number }
+  | { kind: 'cost'; usd?: number; input_tokens: number; output_tokens: number };
+
 // Terminal Types
 export interface TerminalLine {
   text: string;
@@ -108,13 +420,43 @@ export interface TerminalScreen {
   rows: number;
 }
 
+// Changed rows since the previous diff; on a gap in seq, send ResyncPtyScreen
+export interface ScreenDiff {
+  seq: number;
+  keyframe: boolean;
+  cols: number;
+  rows: number;
+  cursor?: [number, number];
+  lines: [number, TerminalLine][];
+}
+
+// Why the workspace's execution policy refused or stopped a command
+export type PolicyViolation =
+  | { rule: 'not_allowed'; binary: string }
+  | { rule: 'denied'; binary: string }
+  | { rule: 'outside_workspace'; path: string }
+  | { rule: 'substitution' }
+  | { rule: 'timeout'; secs: number }
+  | { rule: 'output_limit'; bytes: number };
+
 // Command execution result
 export interface CommandResult {
   stdout: string;
   stderr: string;
   exit_code: number;
+  violation?: PolicyViolation;
 }
 
+// Text frames sent on a terminal socket; raw input may also go as binary
+export type TerminalClientMessage =
+  | { type: 'input'; data: string }
+  | { type: 'resize'; cols: number; rows: number };
+
+// Text frames received on a terminal socket; output arrives as binary frames
+export type TerminalServerMessage =
+  | { type: 'exit'; exit_code: number }
+  | { type: 'error'; message: string };
+
 // LLM Types
 export interface LlmInfo {
   provider_id: string;
@@ -129,11 +471,35 @@ export interface LlmProvider {
   models: string[];
 }
 
+// A provider as found by the health check of GET /api/llm/providers
+export type ProviderStatus = 'Available' | 'Connecting' | 'Unavailable' | { Error: string };
+
+export interface ProviderHealth {
+  id: string;
+  name: string;
+  status: ProviderStatus;
+  current_model?: string;
+  models: string[];
+}
+
+// What changed when an edited config file was reloaded
+export interface ConfigChanges {
+  providers: string[];
+  default_provider?: string;
+  cli_agents: string[];
+  sections: string[];
+  restart_needed: string[];
+}
+
+// Catalog code of an error, e.g. `AX2003`; `axiom explain <code>` describes it
+export type ErrorCode = string;
+
 // API Response wrapper
 export interface ApiResponse<T> {
   success: boolean;
   data?: T;
   error?: string;
+  code?: ErrorCode;
 }
 
 // ========== Slash Command Types ==========
@@ -168,6 +534,9 @@ export type UiAction =
   | { action: 'OpenSettings' }
   | { action: 'OpenModelSelector' }
   | { action: 'OpenWorkspaceSelector' }
+  | { action: 'OpenActivityFeed' }
+  | { action: 'OpenDirectoryPicker' }
+  | { action: 'OfferExistingWorkspace'; id: string; name: string }
   | { action: 'ClearOutput' }
   | { action: 'ToggleTheme' }
   | { action: 'SetTheme'; variant: string }
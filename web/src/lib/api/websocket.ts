@@ -3,6 +3,7 @@
  * Real-time streaming connection to the Rust backend
  */
 
+import { decodeFrame, PROTOCOL_BINARY, PROTOCOL_JSON, supportsBinaryFrames } from './frames';
 import type { Command, Notification } from './types';
 
 export type ConnectionState = 'connecting' | 'connected' | 'disconnected' | 'error';
@@ -30,6 +31,10 @@ export class AxiomWebSocket {
   private reconnectAttempts = 0;
   private reconnectTimer: ReturnType<typeof setTimeout> | null = null;
   private messageQueue: Command[] = [];
+  // When the connection dropped, to catch up on what was missed once back
+  private disconnectedAt: number | null = null;
+  // Binary frames decode asynchronously; chaining keeps messages in order
+  private incoming: Promise<void> = Promise.resolve();
 
   constructor(url: string, options: WebSocketClientOptions = {}) {
     this.url = url;
@@ -44,7 +49,9 @@ export class AxiomWebSocket {
     this.setState('connecting');
 
     try {
-      this.ws = new WebSocket(this.url);
+      const protocols = supportsBinaryFrames() ? [PROTOCOL_BINARY, PROTOCOL_JSON] : [PROTOCOL_JSON];
+      this.ws = new WebSocket(this.url, protocols);
+      this.ws.binaryType = 'arraybuffer';
       this.setupEventHandlers();
     } catch (error) {
       this.handleError(error as Error);
@@ -91,6 +98,10 @@ export class AxiomWebSocket {
     this.ws.onopen = () => {
       this.setState('connected');
       this.reconnectAttempts = 0;
+      if (this.disconnectedAt !== null) {
+        this.send({ type: 'QueryHistory', filter: { since_ms: this.disconnectedAt } });
+        this.disconnectedAt = null;
+      }
       this.flushMessageQueue();
     };
 
@@ -98,6 +109,9 @@ export class AxiomWebSocket {
       this.setState('disconnected');
 
       if (this.options.reconnect && !event.wasClean) {
+        if (this.disconnectedAt === null) {
+          this.disconnectedAt = Date.now();
+        }
         this.scheduleReconnect();
       }
     };
@@ -107,15 +121,25 @@ export class AxiomWebSocket {
     };
 
     this.ws.onmessage = (event) => {
-      try {
-        const notification = JSON.parse(event.data) as Notification;
-        this.options.onNotification?.(notification);
-      } catch (error) {
-        console.error('Failed to parse WebSocket message:', error);
-      }
+      const data = event.data as string | ArrayBuffer;
+      this.incoming = this.incoming
+        .then(() => (typeof data === 'string' ? (JSON.parse(data) as Notification) : decodeFrame(data)))
+        .then((notification) => this.dispatch(notification))
+        .catch((error) => console.error('Failed to parse WebSocket message:', error));
     };
   }
 
+  private dispatch(notification: Notification): void {
+    if (notification.type === 'History') {
+      // Replay the backfill as if it had arrived live
+      for (const entry of notification.entries) {
+        this.options.onNotification?.(entry.notification);
+      }
+    } else {
+      this.options.onNotification?.(notification);
+    }
+  }
+
   private setState(state: ConnectionState): void {
     this.state = state;
     this.options.onStateChange?.(state);
@@ -21,7 +21,7 @@ export interface LLMProvider {
 }
 
 export interface AgentMapping {
-  agentId: string; // orchestrator, po, architect, developer
+  agentId: string; // orchestrator, po, architect, developer, reviewer, shell-summarizer
   providerId: string;
   modelId: string;
 }